@@ -0,0 +1,3 @@
+-- Demo mode: synthetic data across modules for onboarding and tutorials
+INSERT OR IGNORE INTO feature_flags (id, feature_name, enabled, description) VALUES
+('demo_mode', 'demo_mode', 0, 'Serve realistic synthetic prices, portfolios, alerts, and social data instead of live sources');
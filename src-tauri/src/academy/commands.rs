@@ -1,4 +1,5 @@
 use super::*;
+use chrono::{DateTime, Utc};
 use tauri::State;
 
 // Course commands
@@ -501,41 +502,150 @@ pub async fn get_challenge_submissions(
         .map_err(|e| e.to_string())
 }
 
+/// Self-reported attendance is kept for webinars run outside the app. It no
+/// longer earns XP; rewards come from check-in verified attendance.
 #[tauri::command]
 pub async fn record_webinar_attendance(
     academy: State<'_, SharedAcademyEngine>,
     attendance: progress::WebinarAttendance,
 ) -> Result<progress::WebinarAttendance, String> {
-    let progress_tracker = academy.read().await.progress_tracker();
-    let result = progress_tracker
+    let engine = academy.read().await;
+    let webinar = engine
+        .content_service()
         .read()
         .await
-        .record_webinar_attendance(attendance.clone())
+        .get_webinar(&attendance.webinar_id)
         .await
         .map_err(|e| e.to_string())?;
+    if webinar.started_at.is_some() {
+        return Err("Attendance for live webinars is recorded from check-ins".to_string());
+    }
 
-    // Award XP for attendance
-    if result.duration_minutes >= 30 {
-        let content_service = academy.read().await.content_service();
-        let webinars = content_service
-            .read()
-            .await
-            .list_webinars(None)
-            .await
-            .map_err(|e| e.to_string())?;
+    engine
+        .progress_tracker()
+        .read()
+        .await
+        .record_webinar_attendance(attendance)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-        if let Some(webinar) = webinars.iter().find(|w| w.id == result.webinar_id) {
-            let xp = webinar.xp_reward;
-            progress_tracker
-                .read()
-                .await
-                .add_xp(&result.wallet_address, xp)
-                .await
-                .map_err(|e| e.to_string())?;
-        }
-    }
+#[tauri::command]
+pub async fn join_webinar(
+    academy: State<'_, SharedAcademyEngine>,
+    webinar_id: String,
+    wallet_address: String,
+) -> Result<webinar_live::WebinarJoinLink, String> {
+    webinar_live::open_join_link(
+        &academy.read().await,
+        &webinar_id,
+        &wallet_address,
+        Utc::now(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
 
-    Ok(result)
+#[tauri::command]
+pub async fn webinar_check_in(
+    academy: State<'_, SharedAcademyEngine>,
+    webinar_id: String,
+    wallet_address: String,
+) -> Result<webinar_live::WebinarCheckIn, String> {
+    webinar_live::record_check_in(
+        &academy.read().await,
+        &webinar_id,
+        &wallet_address,
+        Utc::now(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn start_webinar(
+    app: tauri::AppHandle,
+    academy: State<'_, SharedAcademyEngine>,
+    webinar_id: String,
+) -> Result<content::Webinar, String> {
+    let webinar = webinar_live::go_live(&academy.read().await, &webinar_id, Utc::now())
+        .await
+        .map_err(|e| e.to_string())?;
+    webinar_live::emit_live_update(
+        &app,
+        &webinar_live::WebinarLiveUpdate {
+            event: webinar_live::WebinarLiveEvent::Started,
+            webinar: webinar.clone(),
+            settlement: None,
+        },
+    );
+    Ok(webinar)
+}
+
+#[tauri::command]
+pub async fn end_webinar(
+    app: tauri::AppHandle,
+    academy: State<'_, SharedAcademyEngine>,
+    webinar_id: String,
+    recording_url: Option<String>,
+) -> Result<webinar_live::WebinarSettlement, String> {
+    let (webinar, settlement) = webinar_live::finish_webinar(
+        &academy.read().await,
+        &webinar_id,
+        Utc::now(),
+        recording_url.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    webinar_live::emit_live_update(
+        &app,
+        &webinar_live::WebinarLiveUpdate {
+            event: webinar_live::WebinarLiveEvent::Ended,
+            webinar,
+            settlement: Some(settlement.clone()),
+        },
+    );
+    Ok(settlement)
+}
+
+#[tauri::command]
+pub async fn handle_webinar_webhook(
+    app: tauri::AppHandle,
+    academy: State<'_, SharedAcademyEngine>,
+    payload: String,
+    signature: String,
+) -> Result<webinar_live::WebinarLiveUpdate, String> {
+    let update = webinar_live::apply_webhook(&academy.read().await, &payload, &signature)
+        .await
+        .map_err(|e| e.to_string())?;
+    webinar_live::emit_live_update(&app, &update);
+    Ok(update)
+}
+
+#[tauri::command]
+pub async fn get_webinar_webhook_secret(
+    academy: State<'_, SharedAcademyEngine>,
+    webinar_id: String,
+) -> Result<String, String> {
+    webinar_live::ensure_webhook_secret(&academy.read().await, &webinar_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_webinar_attendees(
+    academy: State<'_, SharedAcademyEngine>,
+    webinar_id: String,
+) -> Result<Vec<progress::WebinarAttendance>, String> {
+    academy
+        .read()
+        .await
+        .progress_tracker()
+        .read()
+        .await
+        .list_webinar_attendance(&webinar_id)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -570,6 +680,139 @@ pub async fn get_user_mentor_sessions(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn set_mentor_availability(
+    academy: State<'_, SharedAcademyEngine>,
+    mentor_id: String,
+    availability: mentoring::MentorAvailability,
+) -> Result<mentoring::MentorAvailability, String> {
+    mentoring::set_availability(&academy.read().await, &mentor_id, availability)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_mentor_availability(
+    academy: State<'_, SharedAcademyEngine>,
+    mentor_id: String,
+) -> Result<mentoring::MentorAvailability, String> {
+    let mentor = academy
+        .read()
+        .await
+        .content_service()
+        .read()
+        .await
+        .get_mentor(&mentor_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(mentoring::MentorAvailability::from_mentor(&mentor))
+}
+
+#[tauri::command]
+pub async fn get_mentor_open_slots(
+    academy: State<'_, SharedAcademyEngine>,
+    mentor_id: String,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    duration_minutes: i64,
+    timezone: Option<String>,
+) -> Result<Vec<mentoring::BookableSlot>, String> {
+    mentoring::list_open_slots(
+        &academy.read().await,
+        &mentor_id,
+        from,
+        to,
+        duration_minutes,
+        timezone.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn book_mentor_session(
+    academy: State<'_, SharedAcademyEngine>,
+    request: mentoring::BookMentorSessionRequest,
+) -> Result<progress::MentorSession, String> {
+    mentoring::book_session(&academy.read().await, request)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn cancel_mentor_session(
+    academy: State<'_, SharedAcademyEngine>,
+    session_id: String,
+    wallet_address: String,
+    reason: Option<String>,
+) -> Result<progress::MentorSession, String> {
+    mentoring::update_session_status(
+        &academy.read().await,
+        &session_id,
+        &wallet_address,
+        "cancelled",
+        reason.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn complete_mentor_session(
+    academy: State<'_, SharedAcademyEngine>,
+    session_id: String,
+    wallet_address: String,
+    notes: Option<String>,
+) -> Result<progress::MentorSession, String> {
+    mentoring::update_session_status(
+        &academy.read().await,
+        &session_id,
+        &wallet_address,
+        "completed",
+        notes.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rate_mentor_session(
+    academy: State<'_, SharedAcademyEngine>,
+    session_id: String,
+    wallet_address: String,
+    rating: f64,
+) -> Result<progress::MentorSession, String> {
+    mentoring::rate_session(&academy.read().await, &session_id, &wallet_address, rating)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_mentor_sessions(
+    academy: State<'_, SharedAcademyEngine>,
+    mentor_id: String,
+) -> Result<Vec<progress::MentorSession>, String> {
+    academy
+        .read()
+        .await
+        .progress_tracker()
+        .read()
+        .await
+        .get_mentor_sessions(&mentor_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_mentor_leaderboard(
+    academy: State<'_, SharedAcademyEngine>,
+    limit: Option<usize>,
+) -> Result<Vec<mentoring::MentorLeaderboardEntry>, String> {
+    mentoring::mentor_leaderboard(&academy.read().await, limit.unwrap_or(20))
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_user_stats(
     academy: State<'_, SharedAcademyEngine>,
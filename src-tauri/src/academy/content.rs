@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use std::path::PathBuf;
@@ -119,6 +120,12 @@ pub struct Webinar {
     pub xp_reward: i64,
     pub status: String, // scheduled, live, completed, cancelled
     pub created_at: DateTime<Utc>,
+    /// Set by the live start/end events; attendance is only verified
+    /// against webinars that went live in-app.
+    #[serde(default)]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub ended_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -168,7 +175,7 @@ pub struct ContentService {
 
 impl ContentService {
     pub async fn new(app_handle: &AppHandle) -> Result<Self, ContentError> {
-        let app_dir = app_handle.path().app_data_dir().map_err(|err| {
+        let app_dir = app_handle.path().profile_data_dir().map_err(|err| {
             ContentError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 format!("Unable to resolve app data directory: {err}"),
@@ -344,6 +351,14 @@ impl ContentService {
             .execute(pool)
             .await?;
 
+        // Live tracking columns were added after webinars shipped; the
+        // ALTERs fail harmlessly once they exist.
+        for column in ["started_at TEXT", "ended_at TEXT", "webhook_secret TEXT"] {
+            let _ = sqlx::query(&format!("ALTER TABLE webinars ADD COLUMN {column}"))
+                .execute(pool)
+                .await;
+        }
+
         Ok(())
     }
 
@@ -565,8 +580,9 @@ impl ContentService {
             r#"
             INSERT INTO webinars (
                 id, title, description, instructor, scheduled_at, duration_minutes,
-                max_participants, meeting_url, recording_url, xp_reward, status, created_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                max_participants, meeting_url, recording_url, xp_reward, status, created_at,
+                started_at, ended_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&webinar.id)
@@ -581,12 +597,107 @@ impl ContentService {
         .bind(webinar.xp_reward)
         .bind(&webinar.status)
         .bind(webinar.created_at.to_rfc3339())
+        .bind(webinar.started_at.map(|d| d.to_rfc3339()))
+        .bind(webinar.ended_at.map(|d| d.to_rfc3339()))
         .execute(&self.pool)
         .await?;
 
         Ok(webinar)
     }
 
+    pub async fn get_webinar(&self, id: &str) -> Result<Webinar, ContentError> {
+        let row = sqlx::query("SELECT * FROM webinars WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| ContentError::NotFound(format!("webinar {}", id)))?;
+
+        Self::webinar_from_row(&row)
+    }
+
+    pub async fn mark_webinar_live(
+        &self,
+        id: &str,
+        started_at: DateTime<Utc>,
+    ) -> Result<(), ContentError> {
+        let result = sqlx::query(
+            "UPDATE webinars SET status = 'live', started_at = COALESCE(started_at, ?) WHERE id = ?",
+        )
+        .bind(started_at.to_rfc3339())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ContentError::NotFound(format!("webinar {}", id)));
+        }
+        Ok(())
+    }
+
+    pub async fn mark_webinar_ended(
+        &self,
+        id: &str,
+        ended_at: DateTime<Utc>,
+    ) -> Result<(), ContentError> {
+        let result = sqlx::query(
+            "UPDATE webinars SET status = 'completed', ended_at = COALESCE(ended_at, ?) WHERE id = ?",
+        )
+        .bind(ended_at.to_rfc3339())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ContentError::NotFound(format!("webinar {}", id)));
+        }
+        Ok(())
+    }
+
+    pub async fn set_webinar_recording(
+        &self,
+        id: &str,
+        recording_url: &str,
+    ) -> Result<(), ContentError> {
+        let result = sqlx::query("UPDATE webinars SET recording_url = ? WHERE id = ?")
+            .bind(recording_url)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ContentError::NotFound(format!("webinar {}", id)));
+        }
+        Ok(())
+    }
+
+    /// Shared secret the meeting provider signs start/end events with.
+    pub async fn webinar_webhook_secret(&self, id: &str) -> Result<Option<String>, ContentError> {
+        let secret: Option<Option<String>> =
+            sqlx::query_scalar("SELECT webhook_secret FROM webinars WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        secret.ok_or_else(|| ContentError::NotFound(format!("webinar {}", id)))
+    }
+
+    pub async fn set_webinar_webhook_secret(
+        &self,
+        id: &str,
+        secret: &str,
+    ) -> Result<(), ContentError> {
+        let result = sqlx::query("UPDATE webinars SET webhook_secret = ? WHERE id = ?")
+            .bind(secret)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ContentError::NotFound(format!("webinar {}", id)));
+        }
+        Ok(())
+    }
+
     pub async fn list_webinars(
         &self,
         status: Option<String>,
@@ -663,6 +774,50 @@ impl ContentService {
         Ok(mentors)
     }
 
+    pub async fn get_mentor(&self, id: &str) -> Result<Mentor, ContentError> {
+        let row = sqlx::query("SELECT * FROM mentors WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| ContentError::NotFound(format!("mentor {}", id)))?;
+
+        Self::mentor_from_row(&row)
+    }
+
+    pub async fn update_mentor_availability(
+        &self,
+        id: &str,
+        availability: &str,
+    ) -> Result<(), ContentError> {
+        let result = sqlx::query("UPDATE mentors SET availability = ? WHERE id = ?")
+            .bind(availability)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ContentError::NotFound(format!("mentor {}", id)));
+        }
+
+        Ok(())
+    }
+
+    pub async fn update_mentor_stats(
+        &self,
+        id: &str,
+        rating: f64,
+        total_sessions: i64,
+    ) -> Result<(), ContentError> {
+        sqlx::query("UPDATE mentors SET rating = ?, total_sessions = ? WHERE id = ?")
+            .bind(rating)
+            .bind(total_sessions)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn get_stats(&self) -> Result<ContentStats, ContentError> {
         let total_courses: i64 =
             sqlx::query_scalar("SELECT COUNT(*) FROM courses WHERE is_published = 1")
@@ -833,6 +988,8 @@ impl ContentService {
     fn webinar_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Webinar, ContentError> {
         let scheduled_str: String = row.try_get("scheduled_at")?;
         let created_str: String = row.try_get("created_at")?;
+        let started_str: Option<String> = row.try_get("started_at")?;
+        let ended_str: Option<String> = row.try_get("ended_at")?;
 
         Ok(Webinar {
             id: row.try_get("id")?,
@@ -851,6 +1008,16 @@ impl ContentService {
             created_at: DateTime::parse_from_rfc3339(&created_str)
                 .map_err(|e| ContentError::InvalidData(e.to_string()))?
                 .with_timezone(&Utc),
+            started_at: started_str.and_then(|s| {
+                DateTime::parse_from_rfc3339(&s)
+                    .ok()
+                    .map(|d| d.with_timezone(&Utc))
+            }),
+            ended_at: ended_str.and_then(|s| {
+                DateTime::parse_from_rfc3339(&s)
+                    .ok()
+                    .map(|d| d.with_timezone(&Utc))
+            }),
         })
     }
 
@@ -0,0 +1,427 @@
+use super::SharedAcademyEngine;
+use crate::profiles::ProfilePaths;
+use crate::wallet::multi_wallet::MultiWalletManager;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::RwLock;
+
+const GATES_FILE: &str = "academy_gates.json";
+
+/// High-risk tools that can be locked behind academy progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GatedFeature {
+    AutoTrading,
+    /// Auto-trading executions started with `live` set; checked on top of
+    /// `AutoTrading`. The engine still only tracks paper capital, so for now
+    /// this gates the flag that order routing will act on, not a separate
+    /// execution path.
+    AutoTradingLive,
+    CopyTrading,
+    /// There is no sniper execution path yet; this gates marking new coins
+    /// as snipe candidates in triage, the closest step to one.
+    Sniper,
+}
+
+impl GatedFeature {
+    pub const ALL: [GatedFeature; 4] = [
+        GatedFeature::AutoTrading,
+        GatedFeature::AutoTradingLive,
+        GatedFeature::CopyTrading,
+        GatedFeature::Sniper,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GatedFeature::AutoTrading => "auto_trading",
+            GatedFeature::AutoTradingLive => "auto_trading_live",
+            GatedFeature::CopyTrading => "copy_trading",
+            GatedFeature::Sniper => "sniper",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GateRequirement {
+    pub feature: GatedFeature,
+    pub required_courses: Vec<String>,
+    pub required_quizzes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureGateSettings {
+    /// "Learn before you leverage" mode. Off by default so existing users
+    /// keep access to every tool until they opt in.
+    pub enabled: bool,
+    /// Empty by default: the academy ships without seeded courses, so each
+    /// requirement must name content that exists, which is checked when the
+    /// settings are saved.
+    pub requirements: Vec<GateRequirement>,
+    /// Features unlocked manually regardless of academy progress.
+    #[serde(default)]
+    pub overrides: Vec<GatedFeature>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureUnlockStatus {
+    pub feature: GatedFeature,
+    pub unlocked: bool,
+    pub overridden: bool,
+    pub missing_courses: Vec<String>,
+    pub missing_quizzes: Vec<String>,
+    pub reason: Option<String>,
+}
+
+pub struct FeatureGate {
+    settings: FeatureGateSettings,
+    settings_path: Option<PathBuf>,
+}
+
+pub type SharedFeatureGate = Arc<RwLock<FeatureGate>>;
+
+impl FeatureGate {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        let settings_path = app_handle
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(GATES_FILE));
+
+        let settings = settings_path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        Self {
+            settings,
+            settings_path,
+        }
+    }
+
+    pub fn settings(&self) -> FeatureGateSettings {
+        self.settings.clone()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.settings.enabled
+    }
+
+    pub fn update_settings(&mut self, settings: FeatureGateSettings) -> Result<(), String> {
+        self.settings = settings;
+        self.save()
+    }
+
+    pub fn set_override(&mut self, feature: GatedFeature, enabled: bool) -> Result<(), String> {
+        self.settings.overrides.retain(|f| *f != feature);
+        if enabled {
+            self.settings.overrides.push(feature);
+        }
+        self.save()
+    }
+
+    /// Evaluates `feature` against the completed courses and passed quizzes
+    /// of a wallet.
+    pub fn evaluate(
+        &self,
+        feature: GatedFeature,
+        completed_courses: &HashSet<String>,
+        passed_quizzes: &HashSet<String>,
+    ) -> FeatureUnlockStatus {
+        let overridden = self.settings.overrides.contains(&feature);
+        let (missing_courses, missing_quizzes) = self
+            .settings
+            .requirements
+            .iter()
+            .find(|req| req.feature == feature)
+            .map(|req| {
+                let courses = req
+                    .required_courses
+                    .iter()
+                    .filter(|id| !completed_courses.contains(*id))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let quizzes = req
+                    .required_quizzes
+                    .iter()
+                    .filter(|id| !passed_quizzes.contains(*id))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                (courses, quizzes)
+            })
+            .unwrap_or_default();
+
+        let requirements_met = missing_courses.is_empty() && missing_quizzes.is_empty();
+        let unlocked = !self.settings.enabled || overridden || requirements_met;
+        let reason = if unlocked {
+            None
+        } else {
+            Some(format!(
+                "{} is locked until the required academy lessons are finished ({} course(s), {} quiz(zes) remaining)",
+                feature.as_str(),
+                missing_courses.len(),
+                missing_quizzes.len()
+            ))
+        };
+
+        FeatureUnlockStatus {
+            feature,
+            unlocked,
+            overridden,
+            missing_courses,
+            missing_quizzes,
+            reason,
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(path) = &self.settings_path {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create academy gate directory: {e}"))?;
+            }
+            let contents = serde_json::to_string_pretty(&self.settings)
+                .map_err(|e| format!("Failed to serialize academy gates: {e}"))?;
+            fs::write(path, contents)
+                .map_err(|e| format!("Failed to persist academy gates: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Lists every required course and quiz id that is not in the academy.
+fn unknown_requirements(
+    settings: &FeatureGateSettings,
+    courses: &HashSet<String>,
+    quizzes: &HashSet<String>,
+) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for req in &settings.requirements {
+        for id in req
+            .required_courses
+            .iter()
+            .filter(|id| !courses.contains(*id))
+        {
+            unknown.push(format!("course '{}' ({})", id, req.feature.as_str()));
+        }
+        for id in req
+            .required_quizzes
+            .iter()
+            .filter(|id| !quizzes.contains(*id))
+        {
+            unknown.push(format!("quiz '{}' ({})", id, req.feature.as_str()));
+        }
+    }
+    unknown
+}
+
+/// Rejects requirements naming content the academy does not have, which
+/// would otherwise lock the feature for good once gating is on.
+async fn validate_requirements(
+    settings: &FeatureGateSettings,
+    academy: &SharedAcademyEngine,
+) -> Result<(), String> {
+    let content = academy.read().await.content_service();
+    let content = content.read().await;
+    let mut courses = HashSet::new();
+    let mut quizzes = HashSet::new();
+    for req in &settings.requirements {
+        for id in &req.required_courses {
+            if content.get_course(id).await.is_ok() {
+                courses.insert(id.clone());
+            }
+        }
+        for id in &req.required_quizzes {
+            if content.get_quiz(id).await.is_ok() {
+                quizzes.insert(id.clone());
+            }
+        }
+    }
+
+    let unknown = unknown_requirements(settings, &courses, &quizzes);
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Academy gates reference content that does not exist: {}",
+            unknown.join(", ")
+        ))
+    }
+}
+
+async fn load_unlock_status(
+    feature: GatedFeature,
+    wallet_address: &str,
+    gate: &FeatureGate,
+    academy: &SharedAcademyEngine,
+) -> Result<FeatureUnlockStatus, String> {
+    let tracker = academy.read().await.progress_tracker();
+    let tracker = tracker.read().await;
+    let completed: HashSet<String> = tracker
+        .completed_course_ids(wallet_address)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+    let passed: HashSet<String> = tracker
+        .passed_quiz_ids(wallet_address)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+
+    Ok(gate.evaluate(feature, &completed, &passed))
+}
+
+/// Command-layer guard for gated tools. Progress is read for the wallet
+/// that is active in the app, never one named by the caller, so a locked
+/// user cannot borrow another wallet's progress. Returns an error
+/// describing what is still missing when the feature is locked.
+pub async fn enforce_feature_gate(
+    feature: GatedFeature,
+    app: &AppHandle,
+    gate: &SharedFeatureGate,
+    academy: &SharedAcademyEngine,
+) -> Result<(), String> {
+    let gate = gate.read().await;
+    if !gate.is_enabled() || gate.settings.overrides.contains(&feature) {
+        return Ok(());
+    }
+
+    let wallet_address = app
+        .try_state::<MultiWalletManager>()
+        .and_then(|manager| manager.get_active_wallet().ok().flatten())
+        .map(|wallet| wallet.public_key)
+        .ok_or_else(|| {
+            format!(
+                "{} requires an active wallet while academy gating is enabled",
+                feature.as_str()
+            )
+        })?;
+
+    let status = load_unlock_status(feature, &wallet_address, &gate, academy).await?;
+    match status.reason {
+        Some(reason) => Err(reason),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub async fn academy_get_feature_gates(
+    gate: State<'_, SharedFeatureGate>,
+) -> Result<FeatureGateSettings, String> {
+    Ok(gate.read().await.settings())
+}
+
+#[tauri::command]
+pub async fn academy_update_feature_gates(
+    settings: FeatureGateSettings,
+    gate: State<'_, SharedFeatureGate>,
+    academy: State<'_, SharedAcademyEngine>,
+) -> Result<FeatureGateSettings, String> {
+    validate_requirements(&settings, &academy).await?;
+    let mut gate = gate.write().await;
+    gate.update_settings(settings)?;
+    Ok(gate.settings())
+}
+
+#[tauri::command]
+pub async fn academy_set_gate_override(
+    feature: GatedFeature,
+    enabled: bool,
+    gate: State<'_, SharedFeatureGate>,
+) -> Result<FeatureGateSettings, String> {
+    let mut gate = gate.write().await;
+    gate.set_override(feature, enabled)?;
+    Ok(gate.settings())
+}
+
+#[tauri::command]
+pub async fn academy_get_unlock_status(
+    wallet_address: String,
+    gate: State<'_, SharedFeatureGate>,
+    academy: State<'_, SharedAcademyEngine>,
+) -> Result<Vec<FeatureUnlockStatus>, String> {
+    let gate = gate.read().await;
+    let mut statuses = Vec::with_capacity(GatedFeature::ALL.len());
+    for feature in GatedFeature::ALL {
+        statuses.push(load_unlock_status(feature, &wallet_address, &gate, &academy).await?);
+    }
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gate(settings: FeatureGateSettings) -> FeatureGate {
+        FeatureGate {
+            settings,
+            settings_path: None,
+        }
+    }
+
+    fn set(ids: &[&str]) -> HashSet<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn auto_trading_settings(enabled: bool) -> FeatureGateSettings {
+        FeatureGateSettings {
+            enabled,
+            requirements: vec![GateRequirement {
+                feature: GatedFeature::AutoTrading,
+                required_courses: vec!["risk-course".to_string()],
+                required_quizzes: vec!["bots-quiz".to_string()],
+            }],
+            ..FeatureGateSettings::default()
+        }
+    }
+
+    #[test]
+    fn disabled_gating_unlocks_everything() {
+        let gate = gate(auto_trading_settings(false));
+        let status = gate.evaluate(GatedFeature::AutoTrading, &set(&[]), &set(&[]));
+        assert!(status.unlocked);
+        assert_eq!(status.missing_courses, vec!["risk-course"]);
+    }
+
+    #[test]
+    fn enabled_gating_requires_courses_and_quizzes() {
+        let mut gate = gate(auto_trading_settings(true));
+
+        let locked = gate.evaluate(GatedFeature::AutoTrading, &set(&["risk-course"]), &set(&[]));
+        assert!(!locked.unlocked);
+        assert_eq!(locked.missing_quizzes, vec!["bots-quiz"]);
+        assert!(locked.reason.is_some());
+
+        let unlocked = gate.evaluate(
+            GatedFeature::AutoTrading,
+            &set(&["risk-course"]),
+            &set(&["bots-quiz"]),
+        );
+        assert!(unlocked.unlocked);
+
+        gate.set_override(GatedFeature::CopyTrading, true).unwrap();
+        let overridden = gate.evaluate(GatedFeature::CopyTrading, &set(&[]), &set(&[]));
+        assert!(overridden.unlocked && overridden.overridden);
+    }
+
+    #[test]
+    fn requirements_must_reference_existing_content() {
+        let settings = auto_trading_settings(true);
+        assert!(
+            unknown_requirements(&settings, &set(&["risk-course"]), &set(&["bots-quiz"]))
+                .is_empty()
+        );
+
+        let unknown = unknown_requirements(&settings, &set(&["risk-course"]), &set(&[]));
+        assert_eq!(unknown, vec!["quiz 'bots-quiz' (auto_trading)"]);
+    }
+}
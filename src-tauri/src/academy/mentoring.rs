@@ -0,0 +1,956 @@
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::Duration as StdDuration;
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+use super::content::{ContentError, Mentor};
+use super::progress::{MentorSession, ProgressError};
+use super::{AcademyEngine, SharedAcademyEngine};
+use crate::notifications::router::SharedNotificationRouter;
+
+const MIN_SESSION_MINUTES: i64 = 15;
+const MAX_SESSION_MINUTES: i64 = 240;
+const MAX_BUFFER_MINUTES: i64 = 120;
+const SLOT_STEP_MINUTES: i64 = 30;
+const MAX_SLOT_RANGE_DAYS: i64 = 31;
+const REMINDER_INTERVAL: StdDuration = StdDuration::from_secs(300);
+
+/// Reminder kinds and how long before the session each one goes out,
+/// longest lead first.
+pub const MENTOR_REMINDER_LEADS: [(&str, i64); 2] = [("24h", 24 * 60), ("1h", 60)];
+
+/// Mentors with few ratings are pulled toward this score on the leaderboard
+/// so a single five-star session does not outrank an established record.
+const LEADERBOARD_PRIOR_RATING: f64 = 4.0;
+const LEADERBOARD_PRIOR_WEIGHT: f64 = 5.0;
+
+/// A recurring weekly window in the mentor's own timezone. Windows whose end
+/// is not after their start run past midnight into the next day.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailabilityWindow {
+    /// 0 = Sunday, matching the DND scheduler.
+    pub day_of_week: u8,
+    /// "HH:MM"
+    pub start_time: String,
+    /// "HH:MM"
+    pub end_time: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlackoutPeriod {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Booking calendar stored as JSON in `Mentor::availability`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MentorAvailability {
+    /// Fixed UTC offset such as "UTC", "+05:30" or "-08:00". Offsets do not
+    /// follow daylight saving, so mentors update them when their clocks
+    /// change.
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    #[serde(default)]
+    pub weekly: Vec<AvailabilityWindow>,
+    #[serde(default)]
+    pub blackouts: Vec<BlackoutPeriod>,
+    /// Gap kept free between consecutive sessions.
+    #[serde(default)]
+    pub buffer_minutes: i64,
+    #[serde(default = "default_min_notice_minutes")]
+    pub min_notice_minutes: i64,
+    #[serde(default = "default_max_advance_days")]
+    pub max_advance_days: i64,
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+fn default_min_notice_minutes() -> i64 {
+    120
+}
+
+fn default_max_advance_days() -> i64 {
+    60
+}
+
+impl Default for MentorAvailability {
+    fn default() -> Self {
+        Self {
+            timezone: default_timezone(),
+            weekly: Vec::new(),
+            blackouts: Vec::new(),
+            buffer_minutes: 0,
+            min_notice_minutes: default_min_notice_minutes(),
+            max_advance_days: default_max_advance_days(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookableSlot {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub mentor_local_time: String,
+    /// Present when the caller supplied their timezone.
+    pub student_local_time: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookMentorSessionRequest {
+    pub student_address: String,
+    pub mentor_id: String,
+    pub topic: String,
+    pub scheduled_at: DateTime<Utc>,
+    pub duration_minutes: i64,
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MentorLeaderboardEntry {
+    pub rank: usize,
+    pub mentor_id: String,
+    pub name: String,
+    pub expertise_areas: Vec<String>,
+    pub average_rating: f64,
+    pub rated_sessions: i64,
+    pub completed_sessions: i64,
+    /// Rating weighted by sample size, used for ordering.
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MentorSessionReminder {
+    pub session_id: String,
+    pub kind: String,
+    pub student_address: String,
+    pub mentor_id: String,
+    pub topic: String,
+    pub scheduled_at: DateTime<Utc>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum BookingError {
+    #[error("mentor is not accepting bookings")]
+    MentorInactive,
+    #[error("sessions must last between 15 and 240 minutes")]
+    InvalidDuration,
+    #[error("sessions must be booked at least {0} minutes in advance")]
+    TooSoon(i64),
+    #[error("sessions can be booked at most {0} days ahead")]
+    TooFarAhead(i64),
+    #[error("requested time is outside the mentor's availability")]
+    OutsideAvailability,
+    #[error("mentor is unavailable at that time: {0}")]
+    Blackout(String),
+    #[error("mentor already has a session at {0}")]
+    MentorConflict(DateTime<Utc>),
+    #[error("student already has a session at {0}")]
+    StudentConflict(DateTime<Utc>),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MentoringError {
+    #[error(transparent)]
+    Content(#[from] ContentError),
+    #[error(transparent)]
+    Progress(#[from] ProgressError),
+    #[error(transparent)]
+    Booking(#[from] BookingError),
+    #[error("invalid availability: {0}")]
+    InvalidAvailability(String),
+    #[error("invalid timezone: {0}")]
+    InvalidTimezone(String),
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("{0}")]
+    NotAllowed(String),
+}
+
+/// Parses "UTC", "Z", "+05:30", "-0800", "+2" or "UTC+02:00".
+pub fn parse_utc_offset(value: &str) -> Result<FixedOffset, MentoringError> {
+    let invalid = || MentoringError::InvalidTimezone(value.to_string());
+    let trimmed = value.trim();
+    let rest = trimmed
+        .strip_prefix("UTC")
+        .or_else(|| trimmed.strip_prefix("GMT"))
+        .unwrap_or(trimmed);
+    if rest.is_empty() || rest == "Z" {
+        return Ok(FixedOffset::east_opt(0).expect("zero offset"));
+    }
+
+    let (sign, digits) = match rest.as_bytes()[0] {
+        b'+' => (1, &rest[1..]),
+        b'-' => (-1, &rest[1..]),
+        _ => return Err(invalid()),
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if digits.len() > 2 => digits.split_at(digits.len() - 2),
+        None => (digits, "0"),
+    };
+    let hours: i32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 14 || minutes >= 60 {
+        return Err(invalid());
+    }
+
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(invalid)
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, MentoringError> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .map_err(|_| MentoringError::InvalidAvailability(format!("invalid time '{}'", value)))
+}
+
+pub fn format_local(at: DateTime<Utc>, offset: FixedOffset) -> String {
+    at.with_timezone(&offset)
+        .format("%a %Y-%m-%d %H:%M (UTC%:z)")
+        .to_string()
+}
+
+fn overlaps(
+    a_start: DateTime<Utc>,
+    a_end: DateTime<Utc>,
+    b_start: DateTime<Utc>,
+    b_end: DateTime<Utc>,
+) -> bool {
+    a_start < b_end && b_start < a_end
+}
+
+fn session_end(session: &MentorSession) -> DateTime<Utc> {
+    session.scheduled_at + Duration::minutes(session.duration_minutes)
+}
+
+impl MentorAvailability {
+    /// Reads a mentor's stored calendar. Mentors created before calendars
+    /// existed carry free-form text here and are treated as having no
+    /// published windows.
+    pub fn from_mentor(mentor: &Mentor) -> Self {
+        serde_json::from_str(&mentor.availability).unwrap_or_default()
+    }
+
+    pub fn validate(&self) -> Result<(), MentoringError> {
+        parse_utc_offset(&self.timezone)?;
+        for window in &self.weekly {
+            if window.day_of_week > 6 {
+                return Err(MentoringError::InvalidAvailability(format!(
+                    "day_of_week must be 0-6, got {}",
+                    window.day_of_week
+                )));
+            }
+            if parse_time(&window.start_time)? == parse_time(&window.end_time)? {
+                return Err(MentoringError::InvalidAvailability(format!(
+                    "window starting {} has no length",
+                    window.start_time
+                )));
+            }
+        }
+        if let Some(blackout) = self.blackouts.iter().find(|b| b.end <= b.start) {
+            return Err(MentoringError::InvalidAvailability(format!(
+                "blackout starting {} ends before it starts",
+                blackout.start
+            )));
+        }
+        if !(0..=MAX_BUFFER_MINUTES).contains(&self.buffer_minutes) {
+            return Err(MentoringError::InvalidAvailability(format!(
+                "buffer must be between 0 and {} minutes",
+                MAX_BUFFER_MINUTES
+            )));
+        }
+        if self.min_notice_minutes < 0 || self.max_advance_days < 1 {
+            return Err(MentoringError::InvalidAvailability(
+                "notice and booking horizon must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Concrete availability intervals in UTC that intersect `[from, to)`,
+    /// in chronological order.
+    pub fn windows_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>, MentoringError> {
+        let offset = parse_utc_offset(&self.timezone)?;
+        let windows = self
+            .weekly
+            .iter()
+            .map(|w| {
+                Ok((
+                    w.day_of_week,
+                    parse_time(&w.start_time)?,
+                    parse_time(&w.end_time)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, MentoringError>>()?;
+
+        // Start a day early so overnight windows from the previous local day
+        // are included.
+        let mut day = from.with_timezone(&offset).date_naive() - Duration::days(1);
+        let last_day = to.with_timezone(&offset).date_naive();
+        let mut intervals = Vec::new();
+        while day <= last_day {
+            let weekday = day.weekday().num_days_from_sunday() as u8;
+            for (window_day, start_time, end_time) in &windows {
+                if *window_day != weekday {
+                    continue;
+                }
+                let local_start = day.and_time(*start_time);
+                let mut local_end = day.and_time(*end_time);
+                if end_time <= start_time {
+                    local_end += Duration::days(1);
+                }
+                let (Some(start), Some(end)) = (
+                    offset.from_local_datetime(&local_start).single(),
+                    offset.from_local_datetime(&local_end).single(),
+                ) else {
+                    continue;
+                };
+                let (start, end) = (start.with_timezone(&Utc), end.with_timezone(&Utc));
+                if overlaps(start, end, from, to) {
+                    intervals.push((start, end));
+                }
+            }
+            day += Duration::days(1);
+        }
+
+        intervals.sort();
+        Ok(intervals)
+    }
+
+    fn covers(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<bool, MentoringError> {
+        Ok(self
+            .windows_between(start, end)?
+            .iter()
+            .any(|(window_start, window_end)| *window_start <= start && end <= *window_end))
+    }
+}
+
+/// Checks a proposed session against the mentor's calendar and both
+/// parties' existing bookings. Only sessions still `scheduled` block time.
+pub fn check_booking(
+    availability: &MentorAvailability,
+    start: DateTime<Utc>,
+    duration_minutes: i64,
+    mentor_sessions: &[MentorSession],
+    student_sessions: &[MentorSession],
+    now: DateTime<Utc>,
+) -> Result<(), MentoringError> {
+    if !(MIN_SESSION_MINUTES..=MAX_SESSION_MINUTES).contains(&duration_minutes) {
+        return Err(BookingError::InvalidDuration.into());
+    }
+    if start < now + Duration::minutes(availability.min_notice_minutes) {
+        return Err(BookingError::TooSoon(availability.min_notice_minutes).into());
+    }
+    if start > now + Duration::days(availability.max_advance_days) {
+        return Err(BookingError::TooFarAhead(availability.max_advance_days).into());
+    }
+
+    let end = start + Duration::minutes(duration_minutes);
+    if !availability.covers(start, end)? {
+        return Err(BookingError::OutsideAvailability.into());
+    }
+    if let Some(blackout) = availability
+        .blackouts
+        .iter()
+        .find(|b| overlaps(start, end, b.start, b.end))
+    {
+        let reason = blackout
+            .reason
+            .clone()
+            .unwrap_or_else(|| format!("blocked until {}", blackout.end));
+        return Err(BookingError::Blackout(reason).into());
+    }
+
+    let buffer = Duration::minutes(availability.buffer_minutes);
+    if let Some(taken) = mentor_sessions.iter().find(|s| {
+        s.status == "scheduled"
+            && overlaps(start - buffer, end + buffer, s.scheduled_at, session_end(s))
+    }) {
+        return Err(BookingError::MentorConflict(taken.scheduled_at).into());
+    }
+    if let Some(taken) = student_sessions
+        .iter()
+        .find(|s| s.status == "scheduled" && overlaps(start, end, s.scheduled_at, session_end(s)))
+    {
+        return Err(BookingError::StudentConflict(taken.scheduled_at).into());
+    }
+
+    Ok(())
+}
+
+/// Free slots of `duration_minutes` on a half-hour grid within `[from, to)`.
+pub fn open_slots(
+    availability: &MentorAvailability,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    duration_minutes: i64,
+    mentor_sessions: &[MentorSession],
+    student_offset: Option<FixedOffset>,
+    now: DateTime<Utc>,
+) -> Result<Vec<BookableSlot>, MentoringError> {
+    let mentor_offset = parse_utc_offset(&availability.timezone)?;
+    let duration = Duration::minutes(duration_minutes);
+    let step = Duration::minutes(SLOT_STEP_MINUTES);
+    let mut slots = Vec::new();
+
+    for (window_start, window_end) in availability.windows_between(from, to)? {
+        let mut start = window_start.max(from);
+        // Keep slots on the grid of the window itself rather than of `from`.
+        let misalignment = (start - window_start).num_minutes() % SLOT_STEP_MINUTES;
+        if misalignment != 0 {
+            start += Duration::minutes(SLOT_STEP_MINUTES - misalignment);
+        }
+        while start + duration <= window_end && start < to {
+            if check_booking(
+                availability,
+                start,
+                duration_minutes,
+                mentor_sessions,
+                &[],
+                now,
+            )
+            .is_ok()
+            {
+                slots.push(BookableSlot {
+                    start,
+                    end: start + duration,
+                    mentor_local_time: format_local(start, mentor_offset),
+                    student_local_time: student_offset.map(|offset| format_local(start, offset)),
+                });
+            }
+            start += step;
+        }
+    }
+
+    // Overlapping weekly windows can offer the same start twice.
+    slots.sort_by_key(|slot| slot.start);
+    slots.dedup_by_key(|slot| slot.start);
+    Ok(slots)
+}
+
+/// Picks the reminders to send now. A session entering its one-hour window
+/// without having had the day-before reminder only gets the closer one.
+pub fn due_reminders<'a>(
+    sessions: &'a [MentorSession],
+    sent: &HashSet<(String, String)>,
+    now: DateTime<Utc>,
+) -> Vec<(&'a MentorSession, &'static str)> {
+    sessions
+        .iter()
+        .filter(|s| s.status == "scheduled" && s.scheduled_at > now)
+        .filter_map(|session| {
+            let lead = MENTOR_REMINDER_LEADS
+                .iter()
+                .rev()
+                .find(|(_, minutes)| session.scheduled_at - now <= Duration::minutes(*minutes))?;
+            let already_sent = MENTOR_REMINDER_LEADS
+                .iter()
+                .filter(|(_, minutes)| *minutes <= lead.1)
+                .any(|(kind, _)| sent.contains(&(session.id.clone(), kind.to_string())));
+            (!already_sent).then_some((session, lead.0))
+        })
+        .collect()
+}
+
+pub fn leaderboard_score(average_rating: f64, rated_sessions: i64) -> f64 {
+    let n = rated_sessions as f64;
+    (LEADERBOARD_PRIOR_RATING * LEADERBOARD_PRIOR_WEIGHT + average_rating * n)
+        / (LEADERBOARD_PRIOR_WEIGHT + n)
+}
+
+pub async fn set_availability(
+    engine: &AcademyEngine,
+    mentor_id: &str,
+    availability: MentorAvailability,
+) -> Result<MentorAvailability, MentoringError> {
+    availability.validate()?;
+    let json = serde_json::to_string(&availability).map_err(ContentError::from)?;
+    engine
+        .content_service()
+        .read()
+        .await
+        .update_mentor_availability(mentor_id, &json)
+        .await?;
+    Ok(availability)
+}
+
+pub async fn list_open_slots(
+    engine: &AcademyEngine,
+    mentor_id: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    duration_minutes: i64,
+    timezone: Option<&str>,
+) -> Result<Vec<BookableSlot>, MentoringError> {
+    if to <= from || to - from > Duration::days(MAX_SLOT_RANGE_DAYS) {
+        return Err(MentoringError::InvalidRequest(format!(
+            "slot range must be positive and at most {} days",
+            MAX_SLOT_RANGE_DAYS
+        )));
+    }
+    let student_offset = timezone.map(parse_utc_offset).transpose()?;
+    let mentor = engine
+        .content_service()
+        .read()
+        .await
+        .get_mentor(mentor_id)
+        .await?;
+    if !mentor.is_active {
+        return Ok(Vec::new());
+    }
+    let sessions = engine
+        .progress_tracker()
+        .read()
+        .await
+        .get_mentor_sessions(mentor_id)
+        .await?;
+
+    open_slots(
+        &MentorAvailability::from_mentor(&mentor),
+        from,
+        to,
+        duration_minutes,
+        &sessions,
+        student_offset,
+        Utc::now(),
+    )
+}
+
+pub async fn book_session(
+    engine: &AcademyEngine,
+    request: BookMentorSessionRequest,
+) -> Result<MentorSession, MentoringError> {
+    if let Some(timezone) = &request.timezone {
+        parse_utc_offset(timezone)?;
+    }
+    let mentor = engine
+        .content_service()
+        .read()
+        .await
+        .get_mentor(&request.mentor_id)
+        .await?;
+    if !mentor.is_active {
+        return Err(BookingError::MentorInactive.into());
+    }
+    if mentor.wallet_address == request.student_address {
+        return Err(MentoringError::NotAllowed(
+            "mentors cannot book sessions with themselves".to_string(),
+        ));
+    }
+
+    // Conflict checks and the insert happen under one write lock so two
+    // bookings for the same slot cannot both pass.
+    let tracker = engine.progress_tracker();
+    let tracker = tracker.write().await;
+    let mentor_sessions = tracker.get_mentor_sessions(&mentor.id).await?;
+    let student_sessions = tracker
+        .get_user_mentor_sessions(&request.student_address)
+        .await?;
+    check_booking(
+        &MentorAvailability::from_mentor(&mentor),
+        request.scheduled_at,
+        request.duration_minutes,
+        &mentor_sessions,
+        &student_sessions,
+        Utc::now(),
+    )?;
+
+    let session = MentorSession {
+        id: Uuid::new_v4().to_string(),
+        student_address: request.student_address,
+        mentor_id: mentor.id,
+        topic: request.topic,
+        scheduled_at: request.scheduled_at,
+        duration_minutes: request.duration_minutes,
+        status: "scheduled".to_string(),
+        notes: None,
+        student_rating: None,
+        mentor_rating: None,
+        created_at: Utc::now(),
+        completed_at: None,
+        timezone: request.timezone,
+    };
+    Ok(tracker.create_mentor_session(session).await?)
+}
+
+/// Which side of a session `wallet_address` is on; errors if neither.
+async fn participant_role(
+    engine: &AcademyEngine,
+    session: &MentorSession,
+    wallet_address: &str,
+) -> Result<bool, MentoringError> {
+    if session.student_address == wallet_address {
+        return Ok(false);
+    }
+    let mentor = engine
+        .content_service()
+        .read()
+        .await
+        .get_mentor(&session.mentor_id)
+        .await?;
+    if mentor.wallet_address == wallet_address {
+        Ok(true)
+    } else {
+        Err(MentoringError::NotAllowed(
+            "only the student or mentor can change this session".to_string(),
+        ))
+    }
+}
+
+pub async fn update_session_status(
+    engine: &AcademyEngine,
+    session_id: &str,
+    wallet_address: &str,
+    status: &str,
+    notes: Option<&str>,
+) -> Result<MentorSession, MentoringError> {
+    let session = engine
+        .progress_tracker()
+        .read()
+        .await
+        .get_mentor_session(session_id)
+        .await?;
+    participant_role(engine, &session, wallet_address).await?;
+    if session.status != "scheduled" {
+        return Err(MentoringError::NotAllowed(format!(
+            "session is already {}",
+            session.status
+        )));
+    }
+    if status == "completed" && Utc::now() < session.scheduled_at {
+        return Err(MentoringError::NotAllowed(
+            "session cannot be completed before it starts".to_string(),
+        ));
+    }
+
+    let tracker = engine.progress_tracker();
+    let tracker = tracker.read().await;
+    tracker
+        .update_mentor_session_status(session_id, status, notes)
+        .await?;
+    let updated = tracker.get_mentor_session(session_id).await?;
+    drop(tracker);
+
+    if status == "completed" {
+        refresh_mentor_stats(engine, &updated.mentor_id).await?;
+    }
+    Ok(updated)
+}
+
+/// Records post-session feedback. A student's rating goes to the mentor and
+/// feeds the leaderboard; a mentor's rating goes to the student.
+pub async fn rate_session(
+    engine: &AcademyEngine,
+    session_id: &str,
+    wallet_address: &str,
+    rating: f64,
+) -> Result<MentorSession, MentoringError> {
+    if !(1.0..=5.0).contains(&rating) {
+        return Err(MentoringError::InvalidRequest(
+            "rating must be between 1 and 5".to_string(),
+        ));
+    }
+    let tracker = engine.progress_tracker();
+    let session = tracker.read().await.get_mentor_session(session_id).await?;
+    if session.status != "completed" {
+        return Err(MentoringError::NotAllowed(
+            "only completed sessions can be rated".to_string(),
+        ));
+    }
+
+    let rated_by_mentor = participant_role(engine, &session, wallet_address).await?;
+    let (mentor_rating, student_rating) = if rated_by_mentor {
+        (None, Some(rating))
+    } else {
+        (Some(rating), None)
+    };
+    let tracker = tracker.read().await;
+    tracker
+        .record_mentor_session_rating(session_id, mentor_rating, student_rating)
+        .await?;
+    let updated = tracker.get_mentor_session(session_id).await?;
+    drop(tracker);
+
+    if !rated_by_mentor {
+        refresh_mentor_stats(engine, &updated.mentor_id).await?;
+    }
+    Ok(updated)
+}
+
+async fn refresh_mentor_stats(
+    engine: &AcademyEngine,
+    mentor_id: &str,
+) -> Result<(), MentoringError> {
+    let stats = engine
+        .progress_tracker()
+        .read()
+        .await
+        .mentor_rating_stats()
+        .await?
+        .remove(mentor_id)
+        .unwrap_or_default();
+    engine
+        .content_service()
+        .read()
+        .await
+        .update_mentor_stats(mentor_id, stats.average_rating, stats.completed_sessions)
+        .await?;
+    Ok(())
+}
+
+pub async fn mentor_leaderboard(
+    engine: &AcademyEngine,
+    limit: usize,
+) -> Result<Vec<MentorLeaderboardEntry>, MentoringError> {
+    let mentors = engine
+        .content_service()
+        .read()
+        .await
+        .list_mentors(None)
+        .await?;
+    let mut stats = engine
+        .progress_tracker()
+        .read()
+        .await
+        .mentor_rating_stats()
+        .await?;
+
+    let mut entries: Vec<MentorLeaderboardEntry> = mentors
+        .into_iter()
+        .filter_map(|mentor| {
+            let stats = stats.remove(&mentor.id)?;
+            (stats.rated_sessions > 0).then(|| MentorLeaderboardEntry {
+                rank: 0,
+                score: leaderboard_score(stats.average_rating, stats.rated_sessions),
+                mentor_id: mentor.id,
+                name: mentor.name,
+                expertise_areas: mentor.expertise_areas,
+                average_rating: stats.average_rating,
+                rated_sessions: stats.rated_sessions,
+                completed_sessions: stats.completed_sessions,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.completed_sessions.cmp(&a.completed_sessions))
+    });
+    entries.truncate(limit);
+    for (index, entry) in entries.iter_mut().enumerate() {
+        entry.rank = index + 1;
+    }
+    Ok(entries)
+}
+
+async fn send_due_reminders(
+    app: &AppHandle,
+    academy: &SharedAcademyEngine,
+    router: &SharedNotificationRouter,
+) -> Result<(), MentoringError> {
+    let engine = academy.read().await;
+    let tracker = engine.progress_tracker();
+    let (sessions, sent) = {
+        let tracker = tracker.read().await;
+        (
+            tracker.get_scheduled_mentor_sessions().await?,
+            tracker.sent_mentor_reminders().await?,
+        )
+    };
+
+    for (session, kind) in due_reminders(&sessions, &sent, Utc::now()) {
+        let offset = session
+            .timezone
+            .as_deref()
+            .and_then(|tz| parse_utc_offset(tz).ok())
+            .unwrap_or_else(|| FixedOffset::east_opt(0).expect("zero offset"));
+        let mentor_name = engine
+            .content_service()
+            .read()
+            .await
+            .get_mentor(&session.mentor_id)
+            .await
+            .map(|m| m.name)
+            .unwrap_or_else(|_| "your mentor".to_string());
+        let message = format!(
+            "Reminder: mentor session \"{}\" with {} starts {} ({} minutes).",
+            session.topic,
+            mentor_name,
+            format_local(session.scheduled_at, offset),
+            session.duration_minutes
+        );
+
+        if let Err(e) = router
+            .read()
+            .await
+            .send_text_notification(&session.id, "Mentor session reminder", &message)
+            .await
+        {
+            eprintln!("Failed to send mentor session reminder: {}", e);
+        }
+        let _ = app.emit(
+            "mentor_session_reminder",
+            MentorSessionReminder {
+                session_id: session.id.clone(),
+                kind: kind.to_string(),
+                student_address: session.student_address.clone(),
+                mentor_id: session.mentor_id.clone(),
+                topic: session.topic.clone(),
+                scheduled_at: session.scheduled_at,
+                message,
+            },
+        );
+        tracker
+            .read()
+            .await
+            .mark_mentor_reminder_sent(&session.id, kind)
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub fn start_mentor_reminders(
+    app: AppHandle,
+    academy: SharedAcademyEngine,
+    router: SharedNotificationRouter,
+) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(REMINDER_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = send_due_reminders(&app, &academy, &router).await {
+                eprintln!("Mentor reminder check failed: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monday_nine_to_noon(timezone: &str) -> MentorAvailability {
+        MentorAvailability {
+            timezone: timezone.to_string(),
+            weekly: vec![AvailabilityWindow {
+                day_of_week: 1,
+                start_time: "09:00".into(),
+                end_time: "12:00".into(),
+            }],
+            min_notice_minutes: 0,
+            ..Default::default()
+        }
+    }
+
+    fn session(id: &str, start: DateTime<Utc>, minutes: i64) -> MentorSession {
+        MentorSession {
+            id: id.into(),
+            student_address: "student".into(),
+            mentor_id: "mentor".into(),
+            topic: "Risk".into(),
+            scheduled_at: start,
+            duration_minutes: minutes,
+            status: "scheduled".into(),
+            notes: None,
+            student_rating: None,
+            mentor_rating: None,
+            created_at: start,
+            completed_at: None,
+            timezone: None,
+        }
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_offsets() {
+        assert_eq!(parse_utc_offset("UTC").unwrap().local_minus_utc(), 0);
+        assert_eq!(parse_utc_offset("+05:30").unwrap().local_minus_utc(), 19800);
+        assert_eq!(parse_utc_offset("-0800").unwrap().local_minus_utc(), -28800);
+        assert_eq!(parse_utc_offset("UTC+2").unwrap().local_minus_utc(), 7200);
+        assert!(parse_utc_offset("America/New_York").is_err());
+    }
+
+    #[test]
+    fn availability_is_read_in_mentor_timezone() {
+        // 09:00-12:00 at UTC+2 on Monday 2024-01-08 is 07:00-10:00 UTC.
+        let availability = monday_nine_to_noon("+02:00");
+        let now = at(1, 0, 0);
+
+        assert!(check_booking(&availability, at(8, 7, 0), 60, &[], &[], now).is_ok());
+        assert!(matches!(
+            check_booking(&availability, at(8, 9, 30), 60, &[], &[], now),
+            Err(MentoringError::Booking(BookingError::OutsideAvailability))
+        ));
+
+        let slots =
+            open_slots(&availability, at(8, 0, 0), at(9, 0, 0), 60, &[], None, now).unwrap();
+        assert_eq!(slots.len(), 5);
+        assert_eq!(slots[0].start, at(8, 7, 0));
+    }
+
+    #[test]
+    fn detects_conflicts_with_buffer() {
+        let mut availability = monday_nine_to_noon("UTC");
+        availability.buffer_minutes = 15;
+        let now = at(1, 0, 0);
+        let booked = vec![session("a", at(8, 10, 0), 60)];
+
+        assert!(matches!(
+            check_booking(&availability, at(8, 9, 0), 60, &booked, &[], now),
+            Err(MentoringError::Booking(BookingError::MentorConflict(_)))
+        ));
+        assert!(check_booking(&availability, at(8, 11, 15), 45, &booked, &[], now).is_ok());
+
+        let student = vec![session("b", at(8, 11, 0), 30)];
+        assert!(matches!(
+            check_booking(&availability, at(8, 11, 15), 45, &[], &student, now),
+            Err(MentoringError::Booking(BookingError::StudentConflict(_)))
+        ));
+    }
+
+    #[test]
+    fn sends_only_the_closest_pending_reminder() {
+        let sessions = vec![
+            session("a", at(8, 10, 0), 60),
+            session("b", at(9, 9, 0), 60),
+        ];
+        let mut sent = HashSet::new();
+
+        let due = due_reminders(&sessions, &sent, at(8, 9, 30));
+        assert_eq!(due.len(), 2);
+        assert_eq!((due[0].0.id.as_str(), due[0].1), ("a", "1h"));
+        assert_eq!((due[1].0.id.as_str(), due[1].1), ("b", "24h"));
+
+        sent.insert(("b".to_string(), "24h".to_string()));
+        sent.insert(("a".to_string(), "1h".to_string()));
+        assert!(due_reminders(&sessions, &sent, at(8, 9, 45)).is_empty());
+        assert_eq!(due_reminders(&sessions, &sent, at(9, 8, 30))[0].1, "1h");
+    }
+
+    #[test]
+    fn leaderboard_score_discounts_small_samples() {
+        assert!(leaderboard_score(4.8, 40) > leaderboard_score(5.0, 1));
+    }
+}
@@ -1,12 +1,18 @@
 pub mod commands;
 pub mod content;
+pub mod gating;
+pub mod mentoring;
 pub mod progress;
 pub mod rewards;
+pub mod webinar_live;
 
 pub use commands::*;
 pub use content::*;
+pub use gating::*;
+pub use mentoring::*;
 pub use progress::*;
 pub use rewards::*;
+pub use webinar_live::*;
 
 use std::sync::Arc;
 use tokio::sync::RwLock;
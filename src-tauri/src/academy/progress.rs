@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
+use std::collections::{HashMap, HashSet};
 use tauri::{AppHandle, Manager};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -81,6 +83,9 @@ pub struct WebinarAttendance {
     pub duration_minutes: i64,
     pub engagement_score: f64, // 0-100
     pub certificate_issued: bool,
+    /// Set when check-ins, not the attendee, confirmed the attendance.
+    #[serde(default)]
+    pub verified_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -98,6 +103,18 @@ pub struct MentorSession {
     pub mentor_rating: Option<f64>,
     pub created_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// Student's UTC offset at booking time, used to localize reminders.
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+/// Aggregated feedback a mentor has received from students.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MentorRatingStats {
+    pub completed_sessions: i64,
+    pub rated_sessions: i64,
+    pub average_rating: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -115,6 +132,13 @@ pub struct UserStats {
     pub current_streak_days: i64,
     pub longest_streak_days: i64,
     pub badges_earned: Vec<String>,
+    #[serde(default)]
+    pub predictions_resolved: i64,
+    #[serde(default)]
+    pub predictions_correct: i64,
+    /// Mean Brier score across resolved predictions; lower is better.
+    #[serde(default)]
+    pub average_brier_score: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -148,7 +172,7 @@ pub struct ProgressTracker {
 
 impl ProgressTracker {
     pub async fn new(app_handle: &AppHandle) -> Result<Self, ProgressError> {
-        let app_dir = app_handle.path().app_data_dir().map_err(|err| {
+        let app_dir = app_handle.path().profile_data_dir().map_err(|err| {
             ProgressError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 format!("Unable to resolve app data directory: {err}"),
@@ -327,6 +351,60 @@ impl ProgressTracker {
             .execute(pool)
             .await?;
 
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS mentor_session_reminders (
+                session_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                sent_at TEXT NOT NULL,
+                PRIMARY KEY (session_id, kind)
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_mentor_sessions_mentor ON mentor_sessions(mentor_id, scheduled_at)")
+            .execute(pool)
+            .await?;
+
+        let _ = sqlx::query("ALTER TABLE mentor_sessions ADD COLUMN timezone TEXT")
+            .execute(pool)
+            .await;
+
+        // In-app check-ins backing verified webinar attendance
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS webinar_checkins (
+                webinar_id TEXT NOT NULL,
+                wallet_address TEXT NOT NULL,
+                checked_in_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_webinar_checkins_webinar ON webinar_checkins(webinar_id, wallet_address, checked_in_at)")
+            .execute(pool)
+            .await?;
+
+        let _ = sqlx::query("ALTER TABLE webinar_attendance ADD COLUMN verified_at TEXT")
+            .execute(pool)
+            .await;
+
+        // Forecasting columns were added after user_stats shipped; the
+        // ALTERs fail harmlessly once they exist.
+        for column in [
+            "predictions_resolved INTEGER NOT NULL DEFAULT 0",
+            "predictions_correct INTEGER NOT NULL DEFAULT 0",
+            "prediction_brier_total REAL NOT NULL DEFAULT 0",
+        ] {
+            let _ = sqlx::query(&format!("ALTER TABLE user_stats ADD COLUMN {column}"))
+                .execute(pool)
+                .await;
+        }
+
         Ok(())
     }
 
@@ -667,6 +745,153 @@ impl ProgressTracker {
         Ok(attendance)
     }
 
+    pub async fn get_webinar_attendance(
+        &self,
+        webinar_id: &str,
+        wallet_address: &str,
+    ) -> Result<Option<WebinarAttendance>, ProgressError> {
+        let row = sqlx::query(
+            "SELECT * FROM webinar_attendance WHERE webinar_id = ? AND wallet_address = ?",
+        )
+        .bind(webinar_id)
+        .bind(wallet_address)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| Self::webinar_attendance_from_row(&row))
+            .transpose()
+    }
+
+    pub async fn list_webinar_attendance(
+        &self,
+        webinar_id: &str,
+    ) -> Result<Vec<WebinarAttendance>, ProgressError> {
+        let rows = sqlx::query(
+            "SELECT * FROM webinar_attendance WHERE webinar_id = ? ORDER BY joined_at ASC",
+        )
+        .bind(webinar_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut attendance = Vec::new();
+        for row in rows {
+            attendance.push(Self::webinar_attendance_from_row(&row)?);
+        }
+
+        Ok(attendance)
+    }
+
+    pub async fn count_webinar_attendees(&self, webinar_id: &str) -> Result<i64, ProgressError> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM webinar_attendance WHERE webinar_id = ?")
+                .bind(webinar_id)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(count)
+    }
+
+    /// Records a join without touching an existing row, so rejoining keeps
+    /// the original join time.
+    pub async fn join_webinar(
+        &self,
+        attendance: WebinarAttendance,
+    ) -> Result<WebinarAttendance, ProgressError> {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO webinar_attendance (
+                id, wallet_address, webinar_id, joined_at, left_at,
+                duration_minutes, engagement_score, certificate_issued
+            ) VALUES (?, ?, ?, ?, NULL, 0, 0.0, 0)
+            "#,
+        )
+        .bind(&attendance.id)
+        .bind(&attendance.wallet_address)
+        .bind(&attendance.webinar_id)
+        .bind(attendance.joined_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        self.update_user_activity(&attendance.wallet_address)
+            .await?;
+
+        self.get_webinar_attendance(&attendance.webinar_id, &attendance.wallet_address)
+            .await?
+            .ok_or_else(|| ProgressError::NotFound(format!("attendance {}", attendance.id)))
+    }
+
+    pub async fn record_webinar_checkin(
+        &self,
+        webinar_id: &str,
+        wallet_address: &str,
+        checked_in_at: DateTime<Utc>,
+    ) -> Result<(), ProgressError> {
+        sqlx::query(
+            "INSERT INTO webinar_checkins (webinar_id, wallet_address, checked_in_at) VALUES (?, ?, ?)",
+        )
+        .bind(webinar_id)
+        .bind(wallet_address)
+        .bind(checked_in_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Check-in times for one attendee, oldest first.
+    pub async fn webinar_checkins(
+        &self,
+        webinar_id: &str,
+        wallet_address: &str,
+    ) -> Result<Vec<DateTime<Utc>>, ProgressError> {
+        let rows: Vec<String> = sqlx::query_scalar(
+            "SELECT checked_in_at FROM webinar_checkins WHERE webinar_id = ? AND wallet_address = ? ORDER BY checked_in_at ASC",
+        )
+        .bind(webinar_id)
+        .bind(wallet_address)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|s| {
+                DateTime::parse_from_rfc3339(s)
+                    .map(|d| d.with_timezone(&Utc))
+                    .map_err(|e| ProgressError::InvalidData(e.to_string()))
+            })
+            .collect()
+    }
+
+    /// Stores the check-in derived attendance figures. Rows that were
+    /// already verified are left alone; returns true when this call is the
+    /// one that verified the attendee.
+    pub async fn settle_webinar_attendance(
+        &self,
+        webinar_id: &str,
+        wallet_address: &str,
+        left_at: Option<DateTime<Utc>>,
+        duration_minutes: i64,
+        engagement_score: f64,
+        verified_at: Option<DateTime<Utc>>,
+    ) -> Result<bool, ProgressError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE webinar_attendance
+            SET left_at = ?, duration_minutes = ?, engagement_score = ?, verified_at = ?
+            WHERE webinar_id = ? AND wallet_address = ? AND verified_at IS NULL
+            "#,
+        )
+        .bind(left_at.map(|d| d.to_rfc3339()))
+        .bind(duration_minutes)
+        .bind(engagement_score)
+        .bind(verified_at.map(|d| d.to_rfc3339()))
+        .bind(webinar_id)
+        .bind(wallet_address)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(verified_at.is_some() && result.rows_affected() > 0)
+    }
+
     // Mentor sessions
     pub async fn create_mentor_session(
         &self,
@@ -676,8 +901,8 @@ impl ProgressTracker {
             r#"
             INSERT INTO mentor_sessions (
                 id, student_address, mentor_id, topic, scheduled_at,
-                duration_minutes, status, created_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                duration_minutes, status, created_at, timezone
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&session.id)
@@ -688,12 +913,174 @@ impl ProgressTracker {
         .bind(session.duration_minutes)
         .bind(&session.status)
         .bind(session.created_at.to_rfc3339())
+        .bind(&session.timezone)
         .execute(&self.pool)
         .await?;
 
         Ok(session)
     }
 
+    pub async fn get_mentor_session(&self, id: &str) -> Result<MentorSession, ProgressError> {
+        let row = sqlx::query("SELECT * FROM mentor_sessions WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| ProgressError::NotFound(format!("mentor session {}", id)))?;
+
+        Self::mentor_session_from_row(&row)
+    }
+
+    pub async fn get_mentor_sessions(
+        &self,
+        mentor_id: &str,
+    ) -> Result<Vec<MentorSession>, ProgressError> {
+        let rows = sqlx::query(
+            "SELECT * FROM mentor_sessions WHERE mentor_id = ? ORDER BY scheduled_at DESC",
+        )
+        .bind(mentor_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(Self::mentor_session_from_row(&row)?);
+        }
+
+        Ok(sessions)
+    }
+
+    pub async fn get_scheduled_mentor_sessions(&self) -> Result<Vec<MentorSession>, ProgressError> {
+        let rows = sqlx::query(
+            "SELECT * FROM mentor_sessions WHERE status = 'scheduled' ORDER BY scheduled_at ASC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(Self::mentor_session_from_row(&row)?);
+        }
+
+        Ok(sessions)
+    }
+
+    pub async fn update_mentor_session_status(
+        &self,
+        id: &str,
+        status: &str,
+        notes: Option<&str>,
+    ) -> Result<(), ProgressError> {
+        let completed_at = (status == "completed").then(|| Utc::now().to_rfc3339());
+
+        let result = sqlx::query(
+            r#"
+            UPDATE mentor_sessions
+            SET status = ?, notes = COALESCE(?, notes), completed_at = COALESCE(?, completed_at)
+            WHERE id = ?
+            "#,
+        )
+        .bind(status)
+        .bind(notes)
+        .bind(completed_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ProgressError::NotFound(format!("mentor session {}", id)));
+        }
+
+        Ok(())
+    }
+
+    /// Stores feedback for a session. `mentor_rating` is the student's rating
+    /// of the mentor; `student_rating` is the mentor's rating of the student.
+    pub async fn record_mentor_session_rating(
+        &self,
+        id: &str,
+        mentor_rating: Option<f64>,
+        student_rating: Option<f64>,
+    ) -> Result<(), ProgressError> {
+        sqlx::query(
+            r#"
+            UPDATE mentor_sessions
+            SET mentor_rating = COALESCE(?, mentor_rating),
+                student_rating = COALESCE(?, student_rating)
+            WHERE id = ?
+            "#,
+        )
+        .bind(mentor_rating)
+        .bind(student_rating)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mentor_rating_stats(
+        &self,
+    ) -> Result<HashMap<String, MentorRatingStats>, ProgressError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT mentor_id,
+                   COUNT(*) AS completed_sessions,
+                   COUNT(mentor_rating) AS rated_sessions,
+                   COALESCE(AVG(mentor_rating), 0.0) AS average_rating
+            FROM mentor_sessions
+            WHERE status = 'completed'
+            GROUP BY mentor_id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut stats = HashMap::new();
+        for row in rows {
+            stats.insert(
+                row.try_get::<String, _>("mentor_id")?,
+                MentorRatingStats {
+                    completed_sessions: row.try_get("completed_sessions")?,
+                    rated_sessions: row.try_get("rated_sessions")?,
+                    average_rating: row.try_get("average_rating")?,
+                },
+            );
+        }
+
+        Ok(stats)
+    }
+
+    /// `(session_id, kind)` pairs for reminders that have already gone out.
+    pub async fn sent_mentor_reminders(&self) -> Result<HashSet<(String, String)>, ProgressError> {
+        let rows = sqlx::query("SELECT session_id, kind FROM mentor_session_reminders")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut sent = HashSet::new();
+        for row in rows {
+            sent.insert((row.try_get("session_id")?, row.try_get("kind")?));
+        }
+
+        Ok(sent)
+    }
+
+    pub async fn mark_mentor_reminder_sent(
+        &self,
+        session_id: &str,
+        kind: &str,
+    ) -> Result<(), ProgressError> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO mentor_session_reminders (session_id, kind, sent_at) VALUES (?, ?, ?)",
+        )
+        .bind(session_id)
+        .bind(kind)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn get_user_mentor_sessions(
         &self,
         wallet_address: &str,
@@ -714,6 +1101,31 @@ impl ProgressTracker {
     }
 
     // User stats operations
+    pub async fn completed_course_ids(
+        &self,
+        wallet_address: &str,
+    ) -> Result<Vec<String>, ProgressError> {
+        let rows = sqlx::query(
+            "SELECT course_id FROM user_progress WHERE wallet_address = ? AND status = 'completed'",
+        )
+        .bind(wallet_address)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| row.get("course_id")).collect())
+    }
+
+    pub async fn passed_quiz_ids(&self, wallet_address: &str) -> Result<Vec<String>, ProgressError> {
+        let rows = sqlx::query(
+            "SELECT DISTINCT quiz_id FROM quiz_attempts WHERE wallet_address = ? AND passed = 1",
+        )
+        .bind(wallet_address)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| row.get("quiz_id")).collect())
+    }
+
     pub async fn get_user_stats(&self, wallet_address: &str) -> Result<UserStats, ProgressError> {
         // Initialize stats if not exists
         sqlx::query(
@@ -784,6 +1196,9 @@ impl ProgressTracker {
         let longest_streak_days: i64 = row.try_get("longest_streak_days")?;
         let badges_json: String = row.try_get("badges_earned")?;
         let badges_earned: Vec<String> = serde_json::from_str(&badges_json)?;
+        let predictions_resolved: i64 = row.try_get("predictions_resolved").unwrap_or(0);
+        let predictions_correct: i64 = row.try_get("predictions_correct").unwrap_or(0);
+        let brier_total: f64 = row.try_get("prediction_brier_total").unwrap_or(0.0);
 
         Ok(UserStats {
             wallet_address: wallet_address.to_string(),
@@ -798,6 +1213,10 @@ impl ProgressTracker {
             current_streak_days,
             longest_streak_days,
             badges_earned,
+            predictions_resolved,
+            predictions_correct,
+            average_brier_score: (predictions_resolved > 0)
+                .then(|| brier_total / predictions_resolved as f64),
         })
     }
 
@@ -819,6 +1238,37 @@ impl ProgressTracker {
         Ok(())
     }
 
+    /// Counts a resolved custom prediction towards the user's forecasting
+    /// stats.
+    pub async fn record_prediction_result(
+        &self,
+        wallet_address: &str,
+        correct: bool,
+        brier_score: f64,
+    ) -> Result<(), ProgressError> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_stats (
+                wallet_address, last_activity_date,
+                predictions_resolved, predictions_correct, prediction_brier_total
+            )
+            VALUES (?, ?, 1, ?, ?)
+            ON CONFLICT(wallet_address) DO UPDATE SET
+                predictions_resolved = predictions_resolved + 1,
+                predictions_correct = predictions_correct + excluded.predictions_correct,
+                prediction_brier_total = prediction_brier_total + excluded.prediction_brier_total
+            "#,
+        )
+        .bind(wallet_address)
+        .bind(Utc::now().to_rfc3339())
+        .bind(if correct { 1 } else { 0 })
+        .bind(brier_score)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn add_badge(
         &self,
         wallet_address: &str,
@@ -1104,6 +1554,37 @@ impl ProgressTracker {
                     .ok()
                     .map(|d| d.with_timezone(&Utc))
             }),
+            timezone: row.try_get("timezone")?,
+        })
+    }
+
+    fn webinar_attendance_from_row(
+        row: &sqlx::sqlite::SqliteRow,
+    ) -> Result<WebinarAttendance, ProgressError> {
+        let joined_str: String = row.try_get("joined_at")?;
+        let left_str: Option<String> = row.try_get("left_at")?;
+        let verified_str: Option<String> = row.try_get("verified_at")?;
+
+        Ok(WebinarAttendance {
+            id: row.try_get("id")?,
+            wallet_address: row.try_get("wallet_address")?,
+            webinar_id: row.try_get("webinar_id")?,
+            joined_at: DateTime::parse_from_rfc3339(&joined_str)
+                .map_err(|e| ProgressError::InvalidData(e.to_string()))?
+                .with_timezone(&Utc),
+            left_at: left_str.and_then(|s| {
+                DateTime::parse_from_rfc3339(&s)
+                    .ok()
+                    .map(|d| d.with_timezone(&Utc))
+            }),
+            duration_minutes: row.try_get("duration_minutes")?,
+            engagement_score: row.try_get("engagement_score")?,
+            certificate_issued: row.try_get::<i64, _>("certificate_issued")? != 0,
+            verified_at: verified_str.and_then(|s| {
+                DateTime::parse_from_rfc3339(&s)
+                    .ok()
+                    .map(|d| d.with_timezone(&Utc))
+            }),
         })
     }
 }
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use tauri::{AppHandle, Manager};
@@ -110,7 +111,7 @@ pub struct RewardEngine {
 
 impl RewardEngine {
     pub async fn new(app_handle: &AppHandle) -> Result<Self, RewardError> {
-        let app_dir = app_handle.path().app_data_dir().map_err(|err| {
+        let app_dir = app_handle.path().profile_data_dir().map_err(|err| {
             RewardError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 format!("Unable to resolve app data directory: {err}"),
@@ -642,6 +643,18 @@ impl RewardEngine {
                 is_active: true,
                 created_at: chrono::Utc::now(),
             },
+            Badge {
+                id: "live_learner".to_string(),
+                name: "Live Learner".to_string(),
+                description: "Attend a live webinar, verified by in-app check-ins".to_string(),
+                rarity: BadgeRarity::Uncommon,
+                icon_url: None,
+                xp_reward: 200,
+                reputation_boost: 2.0,
+                requirements: serde_json::json!({"verified_webinars": 1}).to_string(),
+                is_active: true,
+                created_at: chrono::Utc::now(),
+            },
         ];
 
         for badge in badges {
@@ -0,0 +1,540 @@
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tauri::{AppHandle, Emitter};
+
+use super::content::{ContentError, Webinar};
+use super::progress::{ProgressError, WebinarAttendance};
+use super::rewards::RewardError;
+use super::AcademyEngine;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How often the client prompts attendees to check in while a webinar is
+/// live. Each accepted check-in credits at most this much attendance.
+pub const WEBINAR_CHECKIN_INTERVAL_SECONDS: i64 = 300;
+/// Check-ins arriving sooner than this after the previous one are ignored,
+/// leaving some slack for timer jitter on the client.
+const CHECKIN_MIN_GAP_SECONDS: i64 = 240;
+/// Join links open this long before the scheduled start.
+const JOIN_EARLY_MINUTES: i64 = 15;
+/// Share of the live session an attendee must be credited with to count as
+/// having attended.
+const MIN_VERIFIED_RATIO: f64 = 0.6;
+const LIVE_LEARNER_BADGE: &str = "live_learner";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebinarLiveEvent {
+    Started,
+    Ended,
+    RecordingReady,
+}
+
+/// Body of a start/end notification from the meeting provider, signed
+/// with the webinar's webhook secret.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebinarWebhookEvent {
+    pub webinar_id: String,
+    pub event: WebinarLiveEvent,
+    #[serde(default)]
+    pub occurred_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub recording_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebinarJoinLink {
+    pub webinar_id: String,
+    pub join_url: String,
+    pub joined_at: DateTime<Utc>,
+    pub checkin_interval_seconds: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebinarCheckIn {
+    pub webinar_id: String,
+    pub accepted: bool,
+    /// Attendance credited so far, counting this check-in.
+    pub credited_minutes: i64,
+    pub next_check_in_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebinarSettlement {
+    pub webinar_id: String,
+    pub live_minutes: i64,
+    pub verified_attendees: Vec<String>,
+    pub unverified_attendees: Vec<String>,
+    /// Attendees verified by this settlement, and so rewarded by it.
+    pub newly_rewarded: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebinarLiveUpdate {
+    pub event: WebinarLiveEvent,
+    pub webinar: Webinar,
+    pub settlement: Option<WebinarSettlement>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebinarLiveError {
+    #[error(transparent)]
+    Content(#[from] ContentError),
+    #[error(transparent)]
+    Progress(#[from] ProgressError),
+    #[error(transparent)]
+    Reward(#[from] RewardError),
+    #[error("invalid webhook payload: {0}")]
+    InvalidPayload(String),
+    #[error("webhook signature does not match")]
+    InvalidSignature,
+    #[error("{0}")]
+    NotAllowed(String),
+}
+
+/// Checks a hex HMAC-SHA256 of the raw payload, with or without a
+/// `sha256=` prefix.
+pub fn verify_webhook_signature(secret: &str, payload: &str, signature: &str) -> bool {
+    let signature = signature.trim();
+    let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+    let Ok(expected) = hex::decode(signature) else {
+        return false;
+    };
+    let Ok(mut mac) = HmacSha256::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(payload.as_bytes());
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Minutes of attendance backed by check-ins. Each check-in inside the
+/// live window covers the time until the next one or the end of the
+/// window, capped at one check-in interval, so closing the tab stops the
+/// credit from accruing.
+pub fn credited_minutes(
+    checkins: &[DateTime<Utc>],
+    started_at: DateTime<Utc>,
+    ended_at: DateTime<Utc>,
+) -> i64 {
+    let interval = Duration::seconds(WEBINAR_CHECKIN_INTERVAL_SECONDS);
+    let inside: Vec<DateTime<Utc>> = checkins
+        .iter()
+        .copied()
+        .filter(|at| *at >= started_at && *at <= ended_at)
+        .collect();
+
+    let covered: i64 = inside
+        .iter()
+        .enumerate()
+        .map(|(i, at)| {
+            let until = inside.get(i + 1).copied().unwrap_or(ended_at);
+            (until - *at).min(interval).num_seconds()
+        })
+        .sum();
+    covered / 60
+}
+
+/// Engagement score (0-100) and whether the credited minutes are enough to
+/// count as verified attendance.
+pub fn attendance_verdict(credited_minutes: i64, live_minutes: i64) -> (f64, bool) {
+    if live_minutes <= 0 {
+        return (0.0, false);
+    }
+    let share = (credited_minutes as f64 / live_minutes as f64).clamp(0.0, 1.0);
+    (share * 100.0, share >= MIN_VERIFIED_RATIO)
+}
+
+/// Returns the webinar's webhook secret, creating one on first use.
+pub async fn ensure_webhook_secret(
+    engine: &AcademyEngine,
+    webinar_id: &str,
+) -> Result<String, WebinarLiveError> {
+    let content = engine.content_service();
+    let content = content.read().await;
+    if let Some(secret) = content.webinar_webhook_secret(webinar_id).await? {
+        return Ok(secret);
+    }
+    let secret = hex::encode(rand::random::<[u8; 32]>());
+    content
+        .set_webinar_webhook_secret(webinar_id, &secret)
+        .await?;
+    Ok(secret)
+}
+
+pub async fn open_join_link(
+    engine: &AcademyEngine,
+    webinar_id: &str,
+    wallet_address: &str,
+    now: DateTime<Utc>,
+) -> Result<WebinarJoinLink, WebinarLiveError> {
+    let webinar = engine
+        .content_service()
+        .read()
+        .await
+        .get_webinar(webinar_id)
+        .await?;
+    match webinar.status.as_str() {
+        "cancelled" => {
+            return Err(WebinarLiveError::NotAllowed(
+                "webinar was cancelled".to_string(),
+            ))
+        }
+        "completed" => {
+            return Err(WebinarLiveError::NotAllowed(
+                "webinar has ended".to_string(),
+            ))
+        }
+        "live" => {}
+        _ if now < webinar.scheduled_at - Duration::minutes(JOIN_EARLY_MINUTES) => {
+            return Err(WebinarLiveError::NotAllowed(format!(
+                "join link opens {} minutes before the start",
+                JOIN_EARLY_MINUTES
+            )))
+        }
+        _ => {}
+    }
+    let join_url = webinar
+        .meeting_url
+        .clone()
+        .ok_or_else(|| WebinarLiveError::NotAllowed("webinar has no join link yet".to_string()))?;
+
+    let tracker = engine.progress_tracker();
+    let tracker = tracker.read().await;
+    let existing = tracker
+        .get_webinar_attendance(webinar_id, wallet_address)
+        .await?;
+    if existing.is_none() {
+        if let Some(max) = webinar.max_participants {
+            if tracker.count_webinar_attendees(webinar_id).await? >= max {
+                return Err(WebinarLiveError::NotAllowed("webinar is full".to_string()));
+            }
+        }
+    }
+    let attendance = tracker
+        .join_webinar(WebinarAttendance {
+            id: format!("{}_{}", wallet_address, webinar_id),
+            wallet_address: wallet_address.to_string(),
+            webinar_id: webinar_id.to_string(),
+            joined_at: now,
+            left_at: None,
+            duration_minutes: 0,
+            engagement_score: 0.0,
+            certificate_issued: false,
+            verified_at: None,
+        })
+        .await?;
+
+    Ok(WebinarJoinLink {
+        webinar_id: webinar_id.to_string(),
+        join_url,
+        joined_at: attendance.joined_at,
+        checkin_interval_seconds: WEBINAR_CHECKIN_INTERVAL_SECONDS,
+    })
+}
+
+pub async fn record_check_in(
+    engine: &AcademyEngine,
+    webinar_id: &str,
+    wallet_address: &str,
+    now: DateTime<Utc>,
+) -> Result<WebinarCheckIn, WebinarLiveError> {
+    let webinar = engine
+        .content_service()
+        .read()
+        .await
+        .get_webinar(webinar_id)
+        .await?;
+    let started_at = match (webinar.status.as_str(), webinar.started_at) {
+        ("live", Some(started_at)) => started_at,
+        _ => {
+            return Err(WebinarLiveError::NotAllowed(
+                "check-ins are only accepted while the webinar is live".to_string(),
+            ))
+        }
+    };
+
+    let tracker = engine.progress_tracker();
+    let tracker = tracker.read().await;
+    if tracker
+        .get_webinar_attendance(webinar_id, wallet_address)
+        .await?
+        .is_none()
+    {
+        return Err(WebinarLiveError::NotAllowed(
+            "join the webinar before checking in".to_string(),
+        ));
+    }
+
+    let mut checkins = tracker.webinar_checkins(webinar_id, wallet_address).await?;
+    let too_soon = checkins
+        .last()
+        .is_some_and(|last| now - *last < Duration::seconds(CHECKIN_MIN_GAP_SECONDS));
+    if !too_soon {
+        tracker
+            .record_webinar_checkin(webinar_id, wallet_address, now)
+            .await?;
+        checkins.push(now);
+    }
+    let last = checkins.last().copied().unwrap_or(now);
+
+    Ok(WebinarCheckIn {
+        webinar_id: webinar_id.to_string(),
+        accepted: !too_soon,
+        credited_minutes: credited_minutes(&checkins, started_at, now),
+        next_check_in_at: last + Duration::seconds(WEBINAR_CHECKIN_INTERVAL_SECONDS),
+    })
+}
+
+pub async fn go_live(
+    engine: &AcademyEngine,
+    webinar_id: &str,
+    at: DateTime<Utc>,
+) -> Result<Webinar, WebinarLiveError> {
+    let content = engine.content_service();
+    let content = content.read().await;
+    let webinar = content.get_webinar(webinar_id).await?;
+    if matches!(webinar.status.as_str(), "completed" | "cancelled") {
+        return Err(WebinarLiveError::NotAllowed(format!(
+            "webinar is already {}",
+            webinar.status
+        )));
+    }
+    content.mark_webinar_live(webinar_id, at).await?;
+    Ok(content.get_webinar(webinar_id).await?)
+}
+
+/// Ends the webinar, stores the recording link when the provider sent one
+/// and settles attendance from check-ins.
+pub async fn finish_webinar(
+    engine: &AcademyEngine,
+    webinar_id: &str,
+    at: DateTime<Utc>,
+    recording_url: Option<&str>,
+) -> Result<(Webinar, WebinarSettlement), WebinarLiveError> {
+    let content = engine.content_service();
+    let content = content.read().await;
+    let webinar = content.get_webinar(webinar_id).await?;
+    if webinar.started_at.is_none() {
+        return Err(WebinarLiveError::NotAllowed(
+            "webinar never went live".to_string(),
+        ));
+    }
+    content.mark_webinar_ended(webinar_id, at).await?;
+    if let Some(url) = recording_url {
+        content.set_webinar_recording(webinar_id, url).await?;
+    }
+    let webinar = content.get_webinar(webinar_id).await?;
+    drop(content);
+
+    let settlement = settle_attendance(engine, &webinar).await?;
+    Ok((webinar, settlement))
+}
+
+async fn settle_attendance(
+    engine: &AcademyEngine,
+    webinar: &Webinar,
+) -> Result<WebinarSettlement, WebinarLiveError> {
+    let (Some(started_at), Some(ended_at)) = (webinar.started_at, webinar.ended_at) else {
+        return Err(WebinarLiveError::NotAllowed(
+            "webinar has not finished".to_string(),
+        ));
+    };
+    let live_minutes = (ended_at - started_at).num_minutes();
+    let mut settlement = WebinarSettlement {
+        webinar_id: webinar.id.clone(),
+        live_minutes,
+        verified_attendees: Vec::new(),
+        unverified_attendees: Vec::new(),
+        newly_rewarded: Vec::new(),
+    };
+
+    let tracker = engine.progress_tracker();
+    let tracker = tracker.read().await;
+    for attendance in tracker.list_webinar_attendance(&webinar.id).await? {
+        let wallet = attendance.wallet_address;
+        if attendance.verified_at.is_some() {
+            settlement.verified_attendees.push(wallet);
+            continue;
+        }
+
+        let checkins = tracker.webinar_checkins(&webinar.id, &wallet).await?;
+        let minutes = credited_minutes(&checkins, started_at, ended_at);
+        let (engagement, verified) = attendance_verdict(minutes, live_minutes);
+        let left_at = checkins.last().map(|last| {
+            (*last + Duration::seconds(WEBINAR_CHECKIN_INTERVAL_SECONDS)).min(ended_at)
+        });
+        let newly_verified = tracker
+            .settle_webinar_attendance(
+                &webinar.id,
+                &wallet,
+                left_at,
+                minutes,
+                engagement,
+                verified.then_some(ended_at),
+            )
+            .await?;
+
+        if newly_verified {
+            tracker.add_xp(&wallet, webinar.xp_reward).await?;
+            let badge = engine
+                .reward_engine()
+                .read()
+                .await
+                .award_badge(
+                    &wallet,
+                    LIVE_LEARNER_BADGE,
+                    &format!("webinar:{}", webinar.id),
+                )
+                .await;
+            match badge {
+                Ok(_) | Err(RewardError::AlreadyClaimed(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+            settlement.newly_rewarded.push(wallet.clone());
+        }
+        if verified {
+            settlement.verified_attendees.push(wallet);
+        } else {
+            settlement.unverified_attendees.push(wallet);
+        }
+    }
+
+    Ok(settlement)
+}
+
+/// Applies a signed start/end/recording notification from the meeting
+/// provider. The payload must be passed through exactly as received so the
+/// signature matches.
+pub async fn apply_webhook(
+    engine: &AcademyEngine,
+    payload: &str,
+    signature: &str,
+) -> Result<WebinarLiveUpdate, WebinarLiveError> {
+    let event: WebinarWebhookEvent = serde_json::from_str(payload)
+        .map_err(|e| WebinarLiveError::InvalidPayload(e.to_string()))?;
+    let secret = engine
+        .content_service()
+        .read()
+        .await
+        .webinar_webhook_secret(&event.webinar_id)
+        .await?
+        .ok_or_else(|| {
+            WebinarLiveError::NotAllowed("webhooks are not set up for this webinar".to_string())
+        })?;
+    if !verify_webhook_signature(&secret, payload, signature) {
+        return Err(WebinarLiveError::InvalidSignature);
+    }
+
+    let at = event.occurred_at.unwrap_or_else(Utc::now);
+    match event.event {
+        WebinarLiveEvent::Started => Ok(WebinarLiveUpdate {
+            event: event.event,
+            webinar: go_live(engine, &event.webinar_id, at).await?,
+            settlement: None,
+        }),
+        WebinarLiveEvent::Ended => {
+            let (webinar, settlement) = finish_webinar(
+                engine,
+                &event.webinar_id,
+                at,
+                event.recording_url.as_deref(),
+            )
+            .await?;
+            Ok(WebinarLiveUpdate {
+                event: event.event,
+                webinar,
+                settlement: Some(settlement),
+            })
+        }
+        WebinarLiveEvent::RecordingReady => {
+            let url = event.recording_url.as_deref().ok_or_else(|| {
+                WebinarLiveError::InvalidPayload("recordingUrl is required".to_string())
+            })?;
+            let content = engine.content_service();
+            let content = content.read().await;
+            content
+                .set_webinar_recording(&event.webinar_id, url)
+                .await?;
+            Ok(WebinarLiveUpdate {
+                event: event.event,
+                webinar: content.get_webinar(&event.webinar_id).await?,
+                settlement: None,
+            })
+        }
+    }
+}
+
+/// Tells the frontend about a lifecycle change; attendees watching for
+/// `webinar_recording_available` get the link as soon as it is known.
+pub fn emit_live_update(app: &AppHandle, update: &WebinarLiveUpdate) {
+    let event = match update.event {
+        WebinarLiveEvent::Started => "webinar_live_started",
+        WebinarLiveEvent::Ended => "webinar_live_ended",
+        WebinarLiveEvent::RecordingReady => "webinar_recording_available",
+    };
+    let _ = app.emit(event, update);
+    if update.event == WebinarLiveEvent::Ended && update.webinar.recording_url.is_some() {
+        let _ = app.emit("webinar_recording_available", update);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(minute: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 1, 18, 0, 0).unwrap() + Duration::minutes(minute)
+    }
+
+    #[test]
+    fn check_ins_credit_at_most_one_interval_each() {
+        // Steady check-ins for the first 30 minutes, then the tab is closed
+        // for 20 minutes before one last check-in near the end.
+        let mut checkins: Vec<DateTime<Utc>> = (0..6).map(|i| at(i * 5)).collect();
+        checkins.push(at(55));
+        let minutes = credited_minutes(&checkins, at(0), at(60));
+        assert_eq!(minutes, 35);
+
+        let (engagement, verified) = attendance_verdict(minutes, 60);
+        assert!((engagement - 35.0 / 60.0 * 100.0).abs() < 1e-9);
+        assert!(!verified);
+    }
+
+    #[test]
+    fn check_ins_outside_the_live_window_are_ignored() {
+        let checkins = vec![at(-10), at(-5), at(0), at(5), at(10), at(65)];
+        assert_eq!(credited_minutes(&checkins, at(0), at(15)), 15);
+        assert_eq!(attendance_verdict(15, 15), (100.0, true));
+        assert_eq!(attendance_verdict(10, 0), (0.0, false));
+    }
+
+    #[test]
+    fn webhook_signature_must_match_the_exact_payload() {
+        let payload = r#"{"webinarId":"w1","event":"started"}"#;
+        let mut mac = HmacSha256::new_from_slice(b"secret").unwrap();
+        mac.update(payload.as_bytes());
+        let signature = hex::encode(mac.finalize().into_bytes());
+
+        assert!(verify_webhook_signature("secret", payload, &signature));
+        assert!(verify_webhook_signature(
+            "secret",
+            payload,
+            &format!("sha256={signature}")
+        ));
+        assert!(!verify_webhook_signature("other", payload, &signature));
+        assert!(!verify_webhook_signature(
+            "secret",
+            r#"{"webinarId":"w2","event":"started"}"#,
+            &signature
+        ));
+        assert!(!verify_webhook_signature("secret", payload, "not-hex"));
+    }
+}
@@ -9,6 +9,7 @@ pub use model::*;
 pub use training::*;
 
 use chrono::Utc;
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use std::collections::HashMap;
@@ -65,7 +66,7 @@ pub type SharedLaunchPredictor = Arc<RwLock<LaunchPredictor>>;
 
 impl LaunchPredictor {
     pub async fn new(app: &AppHandle) -> Result<Self, sqlx::Error> {
-        let mut db_path = app.path().app_data_dir().map_err(|_| {
+        let mut db_path = app.path().profile_data_dir().map_err(|_| {
             sqlx::Error::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 "App data dir not found",
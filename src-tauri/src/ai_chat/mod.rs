@@ -1,5 +1,9 @@
+pub mod optimization;
+
+pub use optimization::*;
+
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::{AppHandle, Emitter, Manager, State};
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -216,6 +220,7 @@ pub async fn ai_execute_quick_action(
 #[tauri::command]
 pub async fn ai_optimize_portfolio(
     holdings: std::collections::HashMap<String, f64>,
+    store: State<'_, SharedOptimizationStore>,
 ) -> Result<PortfolioOptimization, String> {
     use chrono::Utc;
     use std::collections::HashMap;
@@ -259,7 +264,7 @@ pub async fn ai_optimize_portfolio(
         }
     }
 
-    Ok(PortfolioOptimization {
+    let optimization = PortfolioOptimization {
         id: Uuid::new_v4().to_string(),
         timestamp: Utc::now().to_rfc3339(),
         current_allocation: holdings,
@@ -272,14 +277,9 @@ pub async fn ai_optimize_portfolio(
             "Diversification across major tokens reduces volatility".to_string(),
         ],
         actions,
-    })
-}
-
-#[tauri::command]
-pub async fn ai_apply_optimization(optimization_id: String) -> Result<(), String> {
-    // Mock - in production would execute the optimization actions
-    tracing::info!("Applying optimization: {}", optimization_id);
-    Ok(())
+    };
+    store.write().await.insert(optimization.clone());
+    Ok(optimization)
 }
 
 #[tauri::command]
@@ -0,0 +1,446 @@
+//! Executes AI portfolio optimizations through the rebalancer.
+//!
+//! Each suggested action becomes a rebalance leg against the tracked
+//! portfolio. Every leg goes through token policy and the safety engine
+//! before anything executes; blocked legs are skipped instead of failing the
+//! whole plan. Afterwards the realized allocation is compared with the one
+//! the optimization promised.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::RwLock;
+
+use crate::portfolio::{
+    execute_planned_legs, Position, RebalanceAction, SharedPortfolioData, SharedRebalancerState,
+};
+use crate::trading::token_policy::{check_token_policy, ExecutionPath};
+use crate::trading::{SafetyCheckRequest, SharedSafetyEngine};
+use crate::wallet::multi_wallet::MultiWalletManager;
+
+use super::{OptimizationAction, PortfolioOptimization};
+
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const LEG_SLIPPAGE_BPS: u64 = 50;
+const PROGRESS_EVENT: &str = "ai:optimization:progress";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ActionExecutionStatus {
+    Pending,
+    Executed,
+    Blocked,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionExecution {
+    pub index: usize,
+    pub action_type: String,
+    pub token: String,
+    pub amount_usd: f64,
+    pub status: ActionExecutionStatus,
+    pub detail: Option<String>,
+    /// Safety decision trace for the leg, when it reached the safety engine.
+    pub safety_trace_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationDrift {
+    pub token: String,
+    pub before_percent: f64,
+    pub expected_percent: f64,
+    pub realized_percent: f64,
+    /// Realized minus expected, in percentage points.
+    pub drift_percent: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimizationExecution {
+    pub optimization_id: String,
+    pub wallet_address: String,
+    pub started_at: String,
+    pub completed_at: Option<String>,
+    pub actions: Vec<ActionExecution>,
+    pub rebalance_history_id: Option<String>,
+    pub drift: Vec<AllocationDrift>,
+    pub max_drift_percent: f64,
+}
+
+/// Optimizations handed to the UI, kept so they can be applied by id.
+#[derive(Debug, Default)]
+pub struct OptimizationStore {
+    optimizations: HashMap<String, PortfolioOptimization>,
+    executions: HashMap<String, OptimizationExecution>,
+}
+
+impl OptimizationStore {
+    pub fn insert(&mut self, optimization: PortfolioOptimization) {
+        self.optimizations
+            .insert(optimization.id.clone(), optimization);
+    }
+}
+
+pub type SharedOptimizationStore = Arc<RwLock<OptimizationStore>>;
+
+/// Turns one suggested action into a rebalance leg against the tracked
+/// positions. Sells are capped at the position's current value.
+fn plan_leg(
+    action: &OptimizationAction,
+    positions: &[Position],
+    suggested_allocation: &HashMap<String, f64>,
+) -> Result<RebalanceAction, String> {
+    let side = action.action_type.to_lowercase();
+    if side != "buy" && side != "sell" {
+        return Err(format!("Unsupported action type '{}'", action.action_type));
+    }
+    if action.amount.is_nan() || action.amount <= 0.0 {
+        return Err("Action amount must be positive".to_string());
+    }
+
+    let position = positions
+        .iter()
+        .find(|p| p.symbol.eq_ignore_ascii_case(&action.token))
+        .ok_or_else(|| format!("No tracked position for {}", action.token))?;
+    if position.current_price <= f64::EPSILON {
+        return Err(format!("No price available for {}", position.symbol));
+    }
+
+    let estimated_value = if side == "sell" {
+        action.amount.min(position.total_value)
+    } else {
+        action.amount
+    };
+    if estimated_value <= f64::EPSILON {
+        return Err(format!("No {} left to sell", position.symbol));
+    }
+
+    let target_percent = suggested_allocation
+        .get(&action.token)
+        .or_else(|| suggested_allocation.get(&position.symbol))
+        .copied()
+        .unwrap_or(position.allocation);
+
+    Ok(RebalanceAction {
+        symbol: position.symbol.clone(),
+        mint: position.mint.clone(),
+        current_percent: position.allocation,
+        target_percent,
+        deviation: position.allocation - target_percent,
+        action: side,
+        amount: estimated_value / position.current_price,
+        estimated_value,
+    })
+}
+
+/// Legs are priced against USDC. No quote is fetched here, so price impact
+/// is left to the engine's own simulation.
+fn safety_request(wallet_address: &str, leg: &RebalanceAction) -> SafetyCheckRequest {
+    let (input_mint, input_symbol, output_mint, output_symbol, input_amount) =
+        if leg.action == "buy" {
+            (
+                USDC_MINT,
+                "USDC",
+                leg.mint.as_str(),
+                leg.symbol.as_str(),
+                leg.estimated_value,
+            )
+        } else {
+            (
+                leg.mint.as_str(),
+                leg.symbol.as_str(),
+                USDC_MINT,
+                "USDC",
+                leg.amount,
+            )
+        };
+
+    SafetyCheckRequest {
+        wallet_address: wallet_address.to_string(),
+        input_amount,
+        input_mint: input_mint.to_string(),
+        output_mint: output_mint.to_string(),
+        input_symbol: input_symbol.to_string(),
+        output_symbol: output_symbol.to_string(),
+        amount_usd: leg.estimated_value,
+        slippage_bps: LEG_SLIPPAGE_BPS,
+        price_impact_percent: 0.0,
+        security_score: None,
+        input_extensions: None,
+        output_extensions: None,
+    }
+}
+
+fn allocation_drift(
+    suggested_allocation: &HashMap<String, f64>,
+    before: &[Position],
+    after: &[Position],
+) -> Vec<AllocationDrift> {
+    let allocation = |positions: &[Position], token: &str| {
+        positions
+            .iter()
+            .find(|p| p.symbol.eq_ignore_ascii_case(token))
+            .map_or(0.0, |p| p.allocation)
+    };
+
+    let mut drift: Vec<AllocationDrift> = suggested_allocation
+        .iter()
+        .map(|(token, expected)| {
+            let realized = allocation(after, token);
+            AllocationDrift {
+                token: token.clone(),
+                before_percent: allocation(before, token),
+                expected_percent: *expected,
+                realized_percent: realized,
+                drift_percent: realized - expected,
+            }
+        })
+        .collect();
+    drift.sort_by(|a, b| a.token.cmp(&b.token));
+    drift
+}
+
+fn emit_progress(app: &AppHandle, optimization_id: &str, action: &ActionExecution) {
+    let _ = app.emit(
+        PROGRESS_EVENT,
+        serde_json::json!({
+            "optimizationId": optimization_id,
+            "action": action,
+        }),
+    );
+}
+
+#[tauri::command]
+pub async fn ai_apply_optimization(
+    app: AppHandle,
+    optimization_id: String,
+    store: State<'_, SharedOptimizationStore>,
+    safety_engine: State<'_, SharedSafetyEngine>,
+    rebalancer: State<'_, SharedRebalancerState>,
+    portfolio: State<'_, SharedPortfolioData>,
+) -> Result<OptimizationExecution, String> {
+    let wallet_address = app
+        .try_state::<MultiWalletManager>()
+        .and_then(|manager| manager.get_active_wallet().ok().flatten())
+        .map(|wallet| wallet.public_key)
+        .ok_or("Select an active wallet before applying an optimization")?;
+
+    let positions_before = portfolio
+        .lock()
+        .map_err(|_| "Portfolio data locked".to_string())?
+        .positions();
+
+    // Checked and claimed under one guard so a second apply of the same
+    // optimization cannot slip in between.
+    let mut guard = store.write().await;
+    if guard.executions.contains_key(&optimization_id) {
+        return Err("Optimization has already been applied".to_string());
+    }
+    let optimization = guard
+        .optimizations
+        .get(&optimization_id)
+        .cloned()
+        .ok_or_else(|| format!("Optimization {} not found", optimization_id))?;
+    let mut execution = OptimizationExecution {
+        optimization_id: optimization_id.clone(),
+        wallet_address: wallet_address.clone(),
+        started_at: Utc::now().to_rfc3339(),
+        completed_at: None,
+        actions: optimization
+            .actions
+            .iter()
+            .enumerate()
+            .map(|(index, action)| ActionExecution {
+                index,
+                action_type: action.action_type.clone(),
+                token: action.token.clone(),
+                amount_usd: action.amount,
+                status: ActionExecutionStatus::Pending,
+                detail: None,
+                safety_trace_id: None,
+            })
+            .collect(),
+        rebalance_history_id: None,
+        drift: Vec::new(),
+        max_drift_percent: 0.0,
+    };
+    guard
+        .executions
+        .insert(optimization_id.clone(), execution.clone());
+    drop(guard);
+
+    // Every leg is checked before any of them executes, so one blocked leg
+    // never leaves the others half applied.
+    let mut approved = Vec::new();
+    for (index, action) in optimization.actions.iter().enumerate() {
+        let record = &mut execution.actions[index];
+        match plan_leg(
+            action,
+            &positions_before,
+            &optimization.suggested_allocation,
+        ) {
+            Err(err) => {
+                record.status = ActionExecutionStatus::Failed;
+                record.detail = Some(err);
+            }
+            Ok(leg) => {
+                let policy = if leg.action == "buy" {
+                    check_token_policy(ExecutionPath::Rebalance, &leg.mint).await
+                } else {
+                    Ok(())
+                };
+                if let Err(err) = policy {
+                    record.status = ActionExecutionStatus::Blocked;
+                    record.detail = Some(err);
+                } else {
+                    let request = safety_request(&wallet_address, &leg);
+                    match safety_engine
+                        .write()
+                        .await
+                        .check_trade_safety(request)
+                        .await
+                    {
+                        Err(err) => {
+                            record.status = ActionExecutionStatus::Failed;
+                            record.detail = Some(err);
+                        }
+                        Ok(check) => {
+                            record.safety_trace_id = Some(check.decision_trace.id.clone());
+                            if check.allowed {
+                                approved.push((index, leg));
+                            } else {
+                                record.status = ActionExecutionStatus::Blocked;
+                                record.detail = Some(check.decision_trace.summary);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        if record.status != ActionExecutionStatus::Pending {
+            emit_progress(&app, &optimization_id, record);
+        }
+    }
+
+    if !approved.is_empty() {
+        let history = {
+            let mut rebalancer = rebalancer
+                .lock()
+                .map_err(|_| "Rebalancer unavailable".to_string())?;
+            let mut portfolio = portfolio
+                .lock()
+                .map_err(|_| "Portfolio data locked".to_string())?;
+            execute_planned_legs(
+                &mut rebalancer,
+                &mut portfolio,
+                &format!("ai-optimization-{}", optimization_id),
+                "ai_optimization",
+                approved.iter().map(|(_, leg)| leg.clone()).collect(),
+            )
+        };
+        execution.rebalance_history_id = Some(history.id);
+
+        let mut engine = safety_engine.write().await;
+        for (index, _) in &approved {
+            engine.approve_trade(&wallet_address);
+            let record = &mut execution.actions[*index];
+            record.status = ActionExecutionStatus::Executed;
+            emit_progress(&app, &optimization_id, record);
+        }
+    }
+
+    let positions_after = portfolio
+        .lock()
+        .map_err(|_| "Portfolio data locked".to_string())?
+        .positions();
+    execution.drift = allocation_drift(
+        &optimization.suggested_allocation,
+        &positions_before,
+        &positions_after,
+    );
+    execution.max_drift_percent = execution
+        .drift
+        .iter()
+        .map(|d| d.drift_percent.abs())
+        .fold(0.0, f64::max);
+    execution.completed_at = Some(Utc::now().to_rfc3339());
+
+    store
+        .write()
+        .await
+        .executions
+        .insert(optimization_id, execution.clone());
+    Ok(execution)
+}
+
+#[tauri::command]
+pub async fn ai_get_optimization_execution(
+    optimization_id: String,
+    store: State<'_, SharedOptimizationStore>,
+) -> Result<Option<OptimizationExecution>, String> {
+    Ok(store.read().await.executions.get(&optimization_id).cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(symbol: &str, price: f64, total_value: f64, allocation: f64) -> Position {
+        Position {
+            symbol: symbol.to_string(),
+            mint: format!("{}-mint", symbol),
+            amount: total_value / price,
+            current_price: price,
+            avg_entry_price: price,
+            total_value,
+            unrealized_pnl: 0.0,
+            unrealized_pnl_percent: 0.0,
+            allocation,
+        }
+    }
+
+    fn action(action_type: &str, token: &str, amount: f64) -> OptimizationAction {
+        OptimizationAction {
+            action_type: action_type.to_string(),
+            token: token.to_string(),
+            amount,
+            reason: String::new(),
+        }
+    }
+
+    #[test]
+    fn plans_legs_and_measures_drift() {
+        let positions = vec![
+            position("SOL", 100.0, 6000.0, 60.0),
+            position("USDC", 1.0, 4000.0, 40.0),
+        ];
+        let suggested = HashMap::from([("SOL".to_string(), 50.0), ("USDC".to_string(), 50.0)]);
+
+        let sell = plan_leg(&action("Sell", "sol", 9000.0), &positions, &suggested).unwrap();
+        assert_eq!(sell.action, "sell");
+        assert_eq!(sell.estimated_value, 6000.0);
+        assert_eq!(sell.amount, 60.0);
+        assert_eq!(sell.target_percent, 50.0);
+
+        let buy = plan_leg(&action("buy", "USDC", 1000.0), &positions, &suggested).unwrap();
+        assert_eq!(buy.target_percent, 50.0);
+        assert!(plan_leg(&action("buy", "BONK", 10.0), &positions, &suggested).is_err());
+        assert!(plan_leg(&action("stake", "SOL", 10.0), &positions, &suggested).is_err());
+
+        let after = vec![
+            position("SOL", 100.0, 5500.0, 55.0),
+            position("USDC", 1.0, 4500.0, 45.0),
+        ];
+        let drift = allocation_drift(&suggested, &positions, &after);
+        assert_eq!(drift[0].token, "SOL");
+        assert_eq!(drift[0].before_percent, 60.0);
+        assert_eq!(drift[0].drift_percent, 5.0);
+        assert_eq!(drift[1].drift_percent, -5.0);
+    }
+}
@@ -1,4 +1,5 @@
 use chrono::Utc;
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use std::collections::HashMap;
@@ -94,7 +95,7 @@ pub type SharedLaunchPredictor = Arc<RwLock<LaunchPredictor>>;
 impl LaunchPredictor {
     pub async fn new(app: &AppHandle) -> Result<Self, sqlx::Error> {
         let app_handle = app.clone();
-        let mut db_path = app_handle.path().app_data_dir().map_err(|_| {
+        let mut db_path = app_handle.path().profile_data_dir().map_err(|_| {
             sqlx::Error::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 "App data dir not found",
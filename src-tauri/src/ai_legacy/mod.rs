@@ -1,6 +1,9 @@
 pub mod launch_predictor;
+pub mod portfolio_risk;
 pub use launch_predictor::*;
+pub use portfolio_risk::*;
 
+use crate::profiles::ProfilePaths;
 use crate::security::keystore::Keystore;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -277,7 +280,7 @@ pub type SharedRiskAnalyzer = Arc<RwLock<RiskAnalyzer>>;
 
 impl RiskAnalyzer {
     pub async fn new(app: &AppHandle) -> Result<Self, sqlx::Error> {
-        let mut db_path = app.path().app_data_dir().map_err(|_| {
+        let mut db_path = app.path().profile_data_dir().map_err(|_| {
             sqlx::Error::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 "App data dir not found",
@@ -460,6 +463,40 @@ impl RiskAnalyzer {
         }
     }
 
+    /// Every stored score within the range across all tokens, oldest first.
+    pub async fn get_scores_between(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<RiskScore>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT token_address, score, risk_level, factors, timestamp
+            FROM risk_scores
+            WHERE (?1 IS NULL OR timestamp >= ?1) AND (?2 IS NULL OR timestamp <= ?2)
+            ORDER BY timestamp ASC
+            "#,
+        )
+        .bind(from.map(|t| t.to_rfc3339()))
+        .bind(to.map(|t| t.to_rfc3339()))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let factors_json: String = row.get("factors");
+                RiskScore {
+                    token_address: row.get("token_address"),
+                    score: row.get("score"),
+                    risk_level: row.get("risk_level"),
+                    contributing_factors: serde_json::from_str(&factors_json).unwrap_or_default(),
+                    timestamp: row.get("timestamp"),
+                }
+            })
+            .collect())
+    }
+
     pub async fn save_model(&self, metrics: Option<String>) -> Result<(), sqlx::Error> {
         let model = self.model.read().await;
         let model_json = model.to_json().map_err(|e| {
@@ -548,12 +585,22 @@ pub async fn get_token_risk_score(
     token_address: String,
     risk_analyzer: State<'_, SharedRiskAnalyzer>,
     holder_analyzer: State<'_, crate::market::SharedHolderAnalyzer>,
+) -> Result<RiskScore, String> {
+    score_token_risk(&token_address, &risk_analyzer, &holder_analyzer).await
+}
+
+/// Gathers holder, metadata and verification inputs and scores the token,
+/// storing the result in the risk history.
+pub async fn score_token_risk(
+    token_address: &str,
+    risk_analyzer: &SharedRiskAnalyzer,
+    holder_analyzer: &crate::market::SharedHolderAnalyzer,
 ) -> Result<RiskScore, String> {
     // Gather features from various sources
     let holder_data = {
         let analyzer = holder_analyzer.read().await;
         analyzer
-            .get_holder_distribution(&token_address)
+            .get_holder_distribution(token_address)
             .await
             .map_err(|e| format!("Failed to get holder data: {}", e))?
     };
@@ -561,7 +608,7 @@ pub async fn get_token_risk_score(
     let metadata = {
         let analyzer = holder_analyzer.read().await;
         analyzer
-            .get_token_metadata(&token_address)
+            .get_token_metadata(token_address)
             .await
             .map_err(|e| format!("Failed to get metadata: {}", e))?
     };
@@ -569,11 +616,28 @@ pub async fn get_token_risk_score(
     let verification = {
         let analyzer = holder_analyzer.read().await;
         analyzer
-            .get_verification_status(&token_address)
+            .get_verification_status(token_address)
             .await
             .map_err(|e| format!("Failed to get verification: {}", e))?
     };
 
+    let features = build_risk_features(&holder_data, &metadata, &verification)?;
+
+    let analyzer = risk_analyzer.read().await;
+    let risk_score = analyzer
+        .score_token(token_address, features)
+        .await
+        .map_err(|e| format!("Failed to score token: {}", e))?;
+
+    Ok(risk_score)
+}
+
+/// Model inputs from the holder, metadata and verification lookups.
+pub(crate) fn build_risk_features(
+    holder_data: &crate::market::HolderDistribution,
+    metadata: &crate::market::TokenMetadata,
+    verification: &crate::market::VerificationStatus,
+) -> Result<RiskFeatures, String> {
     // Calculate token age
     let token_age_days = {
         let creation_date = chrono::DateTime::parse_from_rfc3339(&metadata.creation_date)
@@ -583,8 +647,7 @@ pub async fn get_token_risk_score(
         (now - creation_date).num_days() as f64
     };
 
-    // Build features
-    let features = RiskFeatures {
+    Ok(RiskFeatures {
         gini_coefficient: holder_data.gini_coefficient,
         top_10_percentage: holder_data.top_10_percentage,
         total_holders: holder_data.total_holders,
@@ -599,15 +662,7 @@ pub async fn get_token_risk_score(
         token_age_days,
         volume_24h: 50000.0,    // Mock
         price_volatility: 15.0, // Mock
-    };
-
-    let analyzer = risk_analyzer.read().await;
-    let risk_score = analyzer
-        .score_token(&token_address, features)
-        .await
-        .map_err(|e| format!("Failed to score token: {}", e))?;
-
-    Ok(risk_score)
+    })
 }
 
 #[tauri::command]
@@ -1082,7 +1137,7 @@ pub struct ConversationManager {
 
 impl ConversationManager {
     pub async fn new(app: &AppHandle) -> Result<Self, sqlx::Error> {
-        let mut db_path = app.path().app_data_dir().map_err(|_| {
+        let mut db_path = app.path().profile_data_dir().map_err(|_| {
             sqlx::Error::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 "App data dir not found",
@@ -1327,7 +1382,7 @@ impl UsageThrottle {
         max_requests_per_hour: u32,
         max_tokens_per_day: u64,
     ) -> Result<Self, sqlx::Error> {
-        let mut db_path = app.path().app_data_dir().map_err(|_| {
+        let mut db_path = app.path().profile_data_dir().map_err(|_| {
             sqlx::Error::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 "App data dir not found",
@@ -1757,6 +1812,40 @@ impl AIAssistant {
         })
     }
 
+    /// Single prompt outside any conversation, for features that need a
+    /// one-off generation such as summaries. Counts against the same
+    /// throttle as chat.
+    pub async fn complete(
+        &self,
+        user_id: &str,
+        prompt: String,
+        system_prompt: Option<String>,
+    ) -> Result<String, String> {
+        let llm_client = self
+            .llm_client
+            .as_ref()
+            .ok_or_else(|| "AI assistant not configured. Please set API key first.".to_string())?;
+
+        let allowed = self
+            .usage_throttle
+            .check_and_record(user_id, 1000)
+            .await
+            .map_err(|e| format!("Throttle check failed: {}", e))?;
+        if !allowed {
+            return Err("Rate limit exceeded. Please try again later.".to_string());
+        }
+
+        let message = Message {
+            role: "user".to_string(),
+            content: prompt,
+            timestamp: Utc::now().to_rfc3339(),
+        };
+        let response = llm_client
+            .chat(vec![message], system_prompt, Vec::new())
+            .await?;
+        Ok(response.message)
+    }
+
     async fn build_trading_context(&self, _user_id: &str) -> Result<TradingContext, String> {
         // In a real implementation, this would fetch actual portfolio, alerts, etc.
         // For now, return mock context
@@ -0,0 +1,419 @@
+//! Batch risk scoring for every held and watchlisted token.
+//!
+//! Scoring tokens one by one repeats the holder, metadata and verification
+//! lookups for each request. The batch job collects the token set once,
+//! fetches each token's inputs at most once per cache window, reuses scores
+//! that are still fresh, and limits how many lookups run at the same time.
+//! Overlapping runs are coalesced: a run that starts while another is in
+//! progress waits for it and then finds its results cached.
+
+use chrono::{DateTime, Duration, Utc};
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::{Mutex, RwLock};
+
+use super::{build_risk_features, RiskScore, SharedRiskAnalyzer};
+use crate::chains::SharedRpcPool;
+use crate::market::{HolderDistribution, SharedHolderAnalyzer, TokenMetadata, VerificationStatus};
+use crate::portfolio::dust::fetch_holdings;
+use crate::portfolio::SharedWatchlistManager;
+use crate::wallet::flows::own_wallet_addresses;
+
+/// Holder and metadata lookups are shared across runs for this long.
+const INPUT_CACHE_MINUTES: i64 = 30;
+/// Stored scores younger than this are reused unless a run is forced.
+const SCORE_REUSE_MINUTES: i64 = 15;
+const MAX_CONCURRENT_LOOKUPS: usize = 4;
+const RISKIEST_COUNT: usize = 5;
+pub const PORTFOLIO_RISK_DIGEST_EVENT: &str = "portfolio_risk_digest";
+
+#[derive(Debug, Clone)]
+struct CachedInputs {
+    holders: HolderDistribution,
+    metadata: TokenMetadata,
+    verification: VerificationStatus,
+    fetched_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenRiskEntry {
+    pub mint: String,
+    pub symbol: Option<String>,
+    pub held: bool,
+    pub watchlisted: bool,
+    pub score: f64,
+    pub risk_level: String,
+    pub top_factor: Option<String>,
+    /// The stored score was fresh enough to reuse.
+    pub reused: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskScoreFailure {
+    pub mint: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LookupCounts {
+    pub inputs_fetched: usize,
+    pub inputs_cached: usize,
+    pub scores_reused: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioRiskDigest {
+    pub generated_at: DateTime<Utc>,
+    pub tokens_scored: usize,
+    pub average_score: Option<f64>,
+    pub held_average_score: Option<f64>,
+    pub level_counts: BTreeMap<String, usize>,
+    pub riskiest: Vec<TokenRiskEntry>,
+    pub entries: Vec<TokenRiskEntry>,
+    pub failures: Vec<RiskScoreFailure>,
+    pub lookups: LookupCounts,
+}
+
+fn average(scores: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = scores.fold((0.0, 0usize), |(sum, count), s| (sum + s, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+pub fn summarize_portfolio_risk(
+    mut entries: Vec<TokenRiskEntry>,
+    failures: Vec<RiskScoreFailure>,
+    lookups: LookupCounts,
+    now: DateTime<Utc>,
+) -> PortfolioRiskDigest {
+    entries.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.mint.cmp(&b.mint))
+    });
+    let mut level_counts = BTreeMap::new();
+    for entry in &entries {
+        *level_counts.entry(entry.risk_level.clone()).or_insert(0) += 1;
+    }
+    PortfolioRiskDigest {
+        generated_at: now,
+        tokens_scored: entries.len(),
+        average_score: average(entries.iter().map(|e| e.score)),
+        held_average_score: average(entries.iter().filter(|e| e.held).map(|e| e.score)),
+        level_counts,
+        riskiest: entries.iter().take(RISKIEST_COUNT).cloned().collect(),
+        entries,
+        failures,
+        lookups,
+    }
+}
+
+fn top_factor(score: &RiskScore) -> Option<String> {
+    score
+        .contributing_factors
+        .iter()
+        .max_by(|a, b| a.impact.total_cmp(&b.impact))
+        .map(|factor| factor.factor_name.clone())
+}
+
+pub type SharedPortfolioRiskScorer = Arc<PortfolioRiskScorer>;
+
+#[derive(Default)]
+pub struct PortfolioRiskScorer {
+    run_lock: Mutex<()>,
+    inputs: RwLock<HashMap<String, CachedInputs>>,
+    last_digest: RwLock<Option<PortfolioRiskDigest>>,
+}
+
+impl PortfolioRiskScorer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn last_digest(&self) -> Option<PortfolioRiskDigest> {
+        self.last_digest.read().await.clone()
+    }
+
+    /// Cached inputs for `mint`, fetching them if missing or stale. The
+    /// flag says whether a lookup was made.
+    async fn inputs(
+        &self,
+        holder_analyzer: &SharedHolderAnalyzer,
+        mint: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(CachedInputs, bool), String> {
+        let cutoff = now - Duration::minutes(INPUT_CACHE_MINUTES);
+        if let Some(cached) = self
+            .inputs
+            .read()
+            .await
+            .get(mint)
+            .filter(|cached| cached.fetched_at >= cutoff)
+        {
+            return Ok((cached.clone(), false));
+        }
+
+        let analyzer = holder_analyzer.read().await;
+        let fetched = CachedInputs {
+            holders: analyzer
+                .get_holder_distribution(mint)
+                .await
+                .map_err(|e| format!("Failed to get holder data: {}", e))?,
+            metadata: analyzer
+                .get_token_metadata(mint)
+                .await
+                .map_err(|e| format!("Failed to get metadata: {}", e))?,
+            verification: analyzer
+                .get_verification_status(mint)
+                .await
+                .map_err(|e| format!("Failed to get verification: {}", e))?,
+            fetched_at: now,
+        };
+        drop(analyzer);
+        self.inputs
+            .write()
+            .await
+            .insert(mint.to_string(), fetched.clone());
+        Ok((fetched, true))
+    }
+
+    async fn score_one(
+        &self,
+        risk_analyzer: &SharedRiskAnalyzer,
+        holder_analyzer: &SharedHolderAnalyzer,
+        token: &TrackedToken,
+        force: bool,
+        now: DateTime<Utc>,
+    ) -> Result<(TokenRiskEntry, LookupCounts), String> {
+        let mut counts = LookupCounts::default();
+        let entry = |score: &RiskScore, symbol: Option<String>, reused: bool| TokenRiskEntry {
+            mint: token.mint.clone(),
+            symbol,
+            held: token.held,
+            watchlisted: token.watchlisted,
+            score: score.score,
+            risk_level: score.risk_level.clone(),
+            top_factor: top_factor(score),
+            reused,
+        };
+        let cached_symbol = self
+            .inputs
+            .read()
+            .await
+            .get(&token.mint)
+            .map(|cached| cached.metadata.symbol.clone());
+
+        if !force {
+            let latest = risk_analyzer
+                .read()
+                .await
+                .get_latest_risk_score(&token.mint)
+                .await
+                .map_err(|e| format!("Failed to read stored score: {}", e))?;
+            let fresh = latest.filter(|score| {
+                DateTime::parse_from_rfc3339(&score.timestamp).is_ok_and(|at| {
+                    now - at.with_timezone(&Utc) < Duration::minutes(SCORE_REUSE_MINUTES)
+                })
+            });
+            if let Some(score) = fresh {
+                counts.scores_reused = 1;
+                return Ok((entry(&score, cached_symbol, true), counts));
+            }
+        }
+
+        let (inputs, fetched) = self.inputs(holder_analyzer, &token.mint, now).await?;
+        if fetched {
+            counts.inputs_fetched = 1;
+        } else {
+            counts.inputs_cached = 1;
+        }
+        let features =
+            build_risk_features(&inputs.holders, &inputs.metadata, &inputs.verification)?;
+        let score = risk_analyzer
+            .read()
+            .await
+            .score_token(&token.mint, features)
+            .await
+            .map_err(|e| format!("Failed to score token: {}", e))?;
+        Ok((
+            entry(&score, Some(inputs.metadata.symbol.clone()), false),
+            counts,
+        ))
+    }
+
+    pub async fn run(
+        &self,
+        app: &AppHandle,
+        risk_analyzer: &SharedRiskAnalyzer,
+        holder_analyzer: &SharedHolderAnalyzer,
+        force: bool,
+    ) -> Result<PortfolioRiskDigest, String> {
+        let _running = self.run_lock.lock().await;
+        let now = Utc::now();
+        let cutoff = now - Duration::minutes(INPUT_CACHE_MINUTES);
+        self.inputs
+            .write()
+            .await
+            .retain(|_, cached| cached.fetched_at >= cutoff);
+
+        let tokens = tracked_tokens(app).await;
+        let results: Vec<(String, Result<(TokenRiskEntry, LookupCounts), String>)> =
+            stream::iter(tokens.iter())
+                .map(|token| async move {
+                    let result = self
+                        .score_one(risk_analyzer, holder_analyzer, token, force, now)
+                        .await;
+                    (token.mint.clone(), result)
+                })
+                .buffer_unordered(MAX_CONCURRENT_LOOKUPS)
+                .collect()
+                .await;
+
+        let mut entries = Vec::new();
+        let mut failures = Vec::new();
+        let mut lookups = LookupCounts::default();
+        for (mint, result) in results {
+            match result {
+                Ok((entry, counts)) => {
+                    lookups.inputs_fetched += counts.inputs_fetched;
+                    lookups.inputs_cached += counts.inputs_cached;
+                    lookups.scores_reused += counts.scores_reused;
+                    entries.push(entry);
+                }
+                Err(error) => failures.push(RiskScoreFailure { mint, error }),
+            }
+        }
+        failures.sort_by(|a, b| a.mint.cmp(&b.mint));
+
+        let digest = summarize_portfolio_risk(entries, failures, lookups, now);
+        *self.last_digest.write().await = Some(digest.clone());
+        let _ = app.emit(PORTFOLIO_RISK_DIGEST_EVENT, &digest);
+        Ok(digest)
+    }
+}
+
+struct TrackedToken {
+    mint: String,
+    held: bool,
+    watchlisted: bool,
+}
+
+/// Every mint in a watchlist or held by one of the user's wallets, once.
+async fn tracked_tokens(app: &AppHandle) -> Vec<TrackedToken> {
+    let mut tokens: BTreeMap<String, TrackedToken> = BTreeMap::new();
+    if let Some(watchlists) = app.try_state::<SharedWatchlistManager>() {
+        match watchlists.read().await.list_watchlists().await {
+            Ok(lists) => {
+                for item in lists.into_iter().flat_map(|list| list.items) {
+                    tokens
+                        .entry(item.mint.clone())
+                        .or_insert_with(|| TrackedToken {
+                            mint: item.mint,
+                            held: false,
+                            watchlisted: false,
+                        })
+                        .watchlisted = true;
+                }
+            }
+            Err(e) => eprintln!("Failed to load watchlists for risk scoring: {}", e),
+        }
+    }
+    if let Some(pool) = app.try_state::<SharedRpcPool>() {
+        for wallet in own_wallet_addresses(app) {
+            match fetch_holdings(pool.inner(), &wallet).await {
+                Ok(holdings) => {
+                    for holding in holdings.into_iter().filter(|holding| holding.amount > 0) {
+                        tokens
+                            .entry(holding.mint.clone())
+                            .or_insert_with(|| TrackedToken {
+                                mint: holding.mint,
+                                held: false,
+                                watchlisted: false,
+                            })
+                            .held = true;
+                    }
+                }
+                Err(e) => eprintln!(
+                    "Failed to load holdings of {} for risk scoring: {}",
+                    wallet, e
+                ),
+            }
+        }
+    }
+    tokens.into_values().collect()
+}
+
+/// Scores every held and watchlisted token and returns the portfolio
+/// digest. `force` rescores tokens whose stored score is still fresh.
+#[tauri::command]
+pub async fn score_portfolio_risk(
+    app: AppHandle,
+    force: Option<bool>,
+    scorer: State<'_, SharedPortfolioRiskScorer>,
+    risk_analyzer: State<'_, SharedRiskAnalyzer>,
+    holder_analyzer: State<'_, SharedHolderAnalyzer>,
+) -> Result<PortfolioRiskDigest, String> {
+    scorer
+        .run(
+            &app,
+            risk_analyzer.inner(),
+            holder_analyzer.inner(),
+            force.unwrap_or(false),
+        )
+        .await
+}
+
+#[tauri::command]
+pub async fn get_portfolio_risk_digest(
+    scorer: State<'_, SharedPortfolioRiskScorer>,
+) -> Result<Option<PortfolioRiskDigest>, String> {
+    Ok(scorer.last_digest().await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(mint: &str, score: f64, level: &str, held: bool) -> TokenRiskEntry {
+        TokenRiskEntry {
+            mint: mint.into(),
+            symbol: None,
+            held,
+            watchlisted: !held,
+            score,
+            risk_level: level.into(),
+            top_factor: None,
+            reused: false,
+        }
+    }
+
+    #[test]
+    fn digest_ranks_and_counts_levels() {
+        let entries = vec![
+            entry("a", 20.0, "Low", true),
+            entry("b", 85.0, "Critical", false),
+            entry("c", 65.0, "High", true),
+            entry("d", 25.0, "Low", false),
+        ];
+        let digest =
+            summarize_portfolio_risk(entries, Vec::new(), LookupCounts::default(), Utc::now());
+
+        assert_eq!(digest.tokens_scored, 4);
+        assert_eq!(digest.riskiest[0].mint, "b");
+        assert_eq!(digest.level_counts.get("Low"), Some(&2));
+        assert_eq!(digest.level_counts.get("Critical"), Some(&1));
+        assert!((digest.average_score.unwrap() - 48.75).abs() < 1e-9);
+        assert!((digest.held_average_score.unwrap() - 42.5).abs() < 1e-9);
+
+        let empty =
+            summarize_portfolio_risk(Vec::new(), Vec::new(), LookupCounts::default(), Utc::now());
+        assert_eq!(empty.average_score, None);
+        assert!(empty.riskiest.is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 use chrono::Utc;
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use std::path::PathBuf;
@@ -437,7 +438,7 @@ impl AlertHistoryManager {
 fn alert_history_db_path(app: &AppHandle) -> Result<PathBuf, AlertError> {
     let app_data_dir = app
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .ok_or_else(|| AlertError::Internal("Unable to resolve app data directory".to_string()))?;
 
     std::fs::create_dir_all(&app_data_dir)?;
@@ -0,0 +1,1138 @@
+use super::logic::{
+    Action, ActionType, AlertRule, CreateSmartRuleRequest, RuleNode, SharedSmartAlertManager,
+    SmartAlertError, SmartAlertManager, SmartRuleFilter, UpdateSmartRuleRequest,
+};
+use super::price_alerts::{
+    AlertError, AlertManager, CompoundCondition, CreateAlertRequest, NotificationChannel,
+    PriceAlert, SharedAlertManager, UpdateAlertRequest,
+};
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use tauri::State;
+
+pub const BUNDLE_FORMAT_VERSION: &str = "1.0";
+const MAX_BUNDLE_BYTES: usize = 1024 * 1024;
+const MAX_BUNDLE_ENTRIES: usize = 500;
+
+/// Keys whose values are enum tags or identifiers; template variables are
+/// never substituted into (or extracted from) them.
+const STRUCTURAL_KEYS: &[&str] = &[
+    "id",
+    "conditionType",
+    "operator",
+    "actionType",
+    "notificationChannels",
+    "priority",
+    "comparisonOperator",
+    "side",
+    "orderType",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum VariableKind {
+    #[default]
+    Text,
+    Number,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleVariable {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub kind: VariableKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+/// Shareable pack of price alerts and smart-alert rules. Entries are kept as
+/// raw JSON so they can carry `{VARIABLE}` placeholders in numeric fields;
+/// they are only turned into typed requests once values are resolved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertBundle {
+    pub format_version: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub created_at: String,
+    #[serde(default)]
+    pub variables: Vec<BundleVariable>,
+    #[serde(default)]
+    pub price_alerts: Vec<Value>,
+    #[serde(default)]
+    pub smart_rules: Vec<Value>,
+}
+
+/// Portable subset of a [`PriceAlert`]: runtime state and the local
+/// watchlist link are dropped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundledPriceAlert {
+    pub name: String,
+    pub symbol: String,
+    pub mint: String,
+    pub compound_condition: CompoundCondition,
+    pub notification_channels: Vec<NotificationChannel>,
+    pub cooldown_minutes: i32,
+}
+
+/// Portable subset of an [`AlertRule`]: ownership and sharing are dropped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundledSmartRule {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub rule_tree: RuleNode,
+    #[serde(default)]
+    pub actions: Vec<Action>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl From<&PriceAlert> for BundledPriceAlert {
+    fn from(alert: &PriceAlert) -> Self {
+        Self {
+            name: alert.name.clone(),
+            symbol: alert.symbol.clone(),
+            mint: alert.mint.clone(),
+            compound_condition: alert.compound_condition.clone(),
+            notification_channels: alert.notification_channels.clone(),
+            cooldown_minutes: alert.cooldown_minutes,
+        }
+    }
+}
+
+impl From<&AlertRule> for BundledSmartRule {
+    fn from(rule: &AlertRule) -> Self {
+        Self {
+            name: rule.name.clone(),
+            description: rule.description.clone(),
+            rule_tree: rule.rule_tree.clone(),
+            actions: rule.actions.clone(),
+            enabled: rule.enabled,
+            symbol: rule.symbol.clone(),
+            tags: rule.tags.clone(),
+        }
+    }
+}
+
+/// A literal value to replace with a `{NAME}` placeholder when exporting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportVariable {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub kind: Option<VariableKind>,
+    /// Keep the exported value as the variable's default.
+    #[serde(default)]
+    pub keep_default: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportBundleRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    /// `None` exports every price alert.
+    #[serde(default)]
+    pub price_alert_ids: Option<Vec<String>>,
+    /// `None` exports every smart rule, including disabled ones.
+    #[serde(default)]
+    pub smart_rule_ids: Option<Vec<String>>,
+    #[serde(default)]
+    pub variables: Vec<ExportVariable>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BundleConflictMode {
+    /// Leave the existing alert untouched.
+    #[default]
+    Skip,
+    /// Overwrite the existing alert with the bundled definition.
+    Replace,
+    /// Import alongside the existing alert under a unique name.
+    Rename,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BundleItemKind {
+    PriceAlert,
+    SmartRule,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BundleItemOutcome {
+    Created,
+    Replaced,
+    Renamed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImportItem {
+    pub kind: BundleItemKind,
+    pub name: String,
+    pub outcome: BundleItemOutcome,
+    /// Id of the created or replaced alert; `None` for skipped items and dry runs that create.
+    pub id: Option<String>,
+    /// Existing alert the item collided with.
+    pub conflict_with: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImportReport {
+    pub bundle_name: String,
+    pub dry_run: bool,
+    pub created: usize,
+    pub replaced: usize,
+    pub skipped: usize,
+    pub items: Vec<BundleImportItem>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSummary {
+    pub name: String,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub format_version: String,
+    pub variables: Vec<BundleVariable>,
+    pub price_alert_count: usize,
+    pub smart_rule_count: usize,
+    /// Problems found without any variable values supplied; missing values
+    /// for variables without defaults are not reported here.
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    #[error("invalid bundle: {}", .0.join("; "))]
+    Invalid(Vec<String>),
+
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Alert(#[from] AlertError),
+
+    #[error(transparent)]
+    SmartAlert(#[from] SmartAlertError),
+}
+
+/// Bundle entries after variable substitution and validation.
+#[derive(Debug, Clone)]
+pub struct ResolvedBundle {
+    pub price_alerts: Vec<BundledPriceAlert>,
+    pub smart_rules: Vec<BundledSmartRule>,
+    pub warnings: Vec<String>,
+}
+
+fn placeholder_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\{([A-Z][A-Z0-9_]*)\}").unwrap())
+}
+
+fn variable_name_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^[A-Z][A-Z0-9_]{0,31}$").unwrap())
+}
+
+/// Calls `f` on every string and number leaf outside [`STRUCTURAL_KEYS`].
+fn for_each_leaf(value: &mut Value, f: &mut dyn FnMut(&mut Value)) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if !STRUCTURAL_KEYS.contains(&key.as_str()) {
+                    for_each_leaf(child, f);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                for_each_leaf(item, f);
+            }
+        }
+        Value::String(_) | Value::Number(_) => f(value),
+        _ => {}
+    }
+}
+
+/// Replaces literal occurrences of each variable's value with `{NAME}`. Text
+/// values are replaced inside strings; number values only where a leaf is
+/// exactly equal to them.
+pub fn tokenize_entry(entry: &mut Value, variables: &[BundleVariable], literals: &[String]) {
+    for_each_leaf(entry, &mut |leaf| {
+        for (variable, literal) in variables.iter().zip(literals) {
+            let placeholder = format!("{{{}}}", variable.name);
+            match (variable.kind, &*leaf) {
+                (VariableKind::Text, Value::String(s)) if s.contains(literal.as_str()) => {
+                    *leaf = Value::String(s.replace(literal.as_str(), &placeholder));
+                }
+                (VariableKind::Number, Value::Number(n)) => {
+                    let matches = literal
+                        .parse::<f64>()
+                        .ok()
+                        .zip(n.as_f64())
+                        .is_some_and(|(a, b)| a == b);
+                    if matches {
+                        *leaf = Value::String(placeholder);
+                    }
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+/// Substitutes resolved values into an entry. A string that is exactly one
+/// number placeholder becomes a JSON number so typed fields deserialize.
+pub fn substitute_entry(
+    entry: &mut Value,
+    kinds: &HashMap<String, VariableKind>,
+    values: &HashMap<String, String>,
+) {
+    for_each_leaf(entry, &mut |leaf| {
+        let Value::String(s) = &*leaf else {
+            return;
+        };
+        let re = placeholder_regex();
+        let number = re
+            .captures(s)
+            .filter(|caps| caps[0].len() == s.len())
+            .filter(|caps| kinds.get(&caps[1]) == Some(&VariableKind::Number))
+            .and_then(|caps| values.get(&caps[1]))
+            .and_then(|v| v.parse::<f64>().ok())
+            .and_then(serde_json::Number::from_f64);
+        let next = match number {
+            Some(number) => Value::Number(number),
+            None => {
+                let replaced = re.replace_all(s, |caps: &regex::Captures| {
+                    values
+                        .get(&caps[1])
+                        .cloned()
+                        .unwrap_or_else(|| caps[0].to_string())
+                });
+                Value::String(replaced.into_owned())
+            }
+        };
+        *leaf = next;
+    });
+}
+
+fn collect_placeholders(entry: &Value, out: &mut HashSet<String>) {
+    let mut entry = entry.clone();
+    for_each_leaf(&mut entry, &mut |leaf| {
+        if let Value::String(s) = leaf {
+            for caps in placeholder_regex().captures_iter(s) {
+                out.insert(caps[1].to_string());
+            }
+        }
+    });
+}
+
+fn entry_label(entry: &Value, index: usize) -> String {
+    entry
+        .get("name")
+        .and_then(Value::as_str)
+        .map(|name| format!("'{}'", name))
+        .unwrap_or_else(|| format!("#{}", index + 1))
+}
+
+pub fn parse_bundle(json: &str) -> Result<AlertBundle, BundleError> {
+    if json.len() > MAX_BUNDLE_BYTES {
+        return Err(BundleError::Invalid(vec![format!(
+            "bundle exceeds {} bytes",
+            MAX_BUNDLE_BYTES
+        )]));
+    }
+    let bundle: AlertBundle = serde_json::from_str(json)?;
+    Ok(bundle)
+}
+
+/// Checks the bundle header and variable declarations and reports every
+/// placeholder that is not declared.
+fn validate_structure(bundle: &AlertBundle) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    let major = bundle.format_version.split('.').next().unwrap_or_default();
+    if major != BUNDLE_FORMAT_VERSION.split('.').next().unwrap_or_default() {
+        errors.push(format!(
+            "unsupported format version {} (expected {})",
+            bundle.format_version, BUNDLE_FORMAT_VERSION
+        ));
+    }
+    if bundle.name.trim().is_empty() {
+        errors.push("bundle name is empty".to_string());
+    }
+    let entries = bundle.price_alerts.len() + bundle.smart_rules.len();
+    if entries == 0 {
+        errors.push("bundle contains no alerts".to_string());
+    }
+    if entries > MAX_BUNDLE_ENTRIES {
+        errors.push(format!(
+            "bundle contains {} alerts (max {})",
+            entries, MAX_BUNDLE_ENTRIES
+        ));
+    }
+
+    let mut declared = HashSet::new();
+    for variable in &bundle.variables {
+        if !variable_name_regex().is_match(&variable.name) {
+            errors.push(format!(
+                "invalid variable name '{}' (use UPPER_SNAKE_CASE)",
+                variable.name
+            ));
+        }
+        if !declared.insert(variable.name.clone()) {
+            errors.push(format!("variable {} is declared twice", variable.name));
+        }
+        if let (VariableKind::Number, Some(default)) = (variable.kind, &variable.default) {
+            if !default.parse::<f64>().is_ok_and(f64::is_finite) {
+                errors.push(format!(
+                    "default for {} is not a number: {}",
+                    variable.name, default
+                ));
+            }
+        }
+    }
+
+    let mut used = HashSet::new();
+    for entry in bundle.price_alerts.iter().chain(&bundle.smart_rules) {
+        collect_placeholders(entry, &mut used);
+    }
+    let mut undeclared: Vec<_> = used.difference(&declared).cloned().collect();
+    undeclared.sort();
+    for name in undeclared {
+        errors.push(format!(
+            "placeholder {{{}}} has no variable declaration",
+            name
+        ));
+    }
+
+    errors
+}
+
+fn validate_price_alert(alert: &BundledPriceAlert) -> Result<(), String> {
+    if alert.name.trim().is_empty() {
+        return Err("name is empty".to_string());
+    }
+    if alert.symbol.trim().is_empty() || alert.mint.trim().is_empty() {
+        return Err("symbol and mint are required".to_string());
+    }
+    if alert.compound_condition.conditions.is_empty() {
+        return Err("at least one condition is required".to_string());
+    }
+    if alert
+        .compound_condition
+        .conditions
+        .iter()
+        .any(|condition| !condition.value.is_finite())
+    {
+        return Err("condition values must be finite numbers".to_string());
+    }
+    if alert.notification_channels.is_empty() {
+        return Err("at least one notification channel is required".to_string());
+    }
+    if alert.cooldown_minutes < 0 {
+        return Err("cooldown must not be negative".to_string());
+    }
+    Ok(())
+}
+
+fn validate_smart_rule(rule: &BundledSmartRule) -> Result<(), String> {
+    if rule.name.trim().is_empty() {
+        return Err("name is empty".to_string());
+    }
+    if rule.rule_tree.condition.is_none() && rule.rule_tree.group.is_none() {
+        return Err("rule tree has no condition or group".to_string());
+    }
+    for action in &rule.actions {
+        action.validate()?;
+    }
+    Ok(())
+}
+
+/// Resolves variables (supplied values first, then defaults), substitutes
+/// them and validates every entry. All problems are collected so a bundle
+/// author sees the full list in one pass.
+pub fn resolve_bundle(
+    bundle: &AlertBundle,
+    values: &HashMap<String, String>,
+) -> Result<ResolvedBundle, BundleError> {
+    let mut errors = validate_structure(bundle);
+
+    let mut kinds = HashMap::new();
+    let mut resolved = HashMap::new();
+    for variable in &bundle.variables {
+        kinds.insert(variable.name.clone(), variable.kind);
+        let value = values
+            .get(&variable.name)
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .or_else(|| variable.default.clone());
+        match value {
+            Some(value) => {
+                if variable.kind == VariableKind::Number
+                    && !value.parse::<f64>().is_ok_and(f64::is_finite)
+                {
+                    errors.push(format!(
+                        "{} must be a number, got '{}'",
+                        variable.name, value
+                    ));
+                }
+                resolved.insert(variable.name.clone(), value);
+            }
+            None => errors.push(format!("no value supplied for {}", variable.name)),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(BundleError::Invalid(errors));
+    }
+
+    let mut warnings = Vec::new();
+    let mut price_alerts = Vec::new();
+    for (index, entry) in bundle.price_alerts.iter().enumerate() {
+        let mut entry = entry.clone();
+        substitute_entry(&mut entry, &kinds, &resolved);
+        let label = entry_label(&entry, index);
+        match serde_json::from_value::<BundledPriceAlert>(entry) {
+            Ok(alert) => match validate_price_alert(&alert) {
+                Ok(()) => price_alerts.push(alert),
+                Err(err) => errors.push(format!("price alert {}: {}", label, err)),
+            },
+            Err(err) => errors.push(format!("price alert {}: {}", label, err)),
+        }
+    }
+
+    let mut smart_rules = Vec::new();
+    for (index, entry) in bundle.smart_rules.iter().enumerate() {
+        let mut entry = entry.clone();
+        substitute_entry(&mut entry, &kinds, &resolved);
+        let label = entry_label(&entry, index);
+        match serde_json::from_value::<BundledSmartRule>(entry) {
+            Ok(mut rule) => match validate_smart_rule(&rule) {
+                Ok(()) => {
+                    // Never let a shared pack place trades without the user
+                    // opting in on each rule.
+                    for action in rule
+                        .actions
+                        .iter_mut()
+                        .filter(|a| a.action_type == ActionType::ExecuteTrade && a.enabled)
+                    {
+                        action.enabled = false;
+                        warnings.push(format!(
+                            "smart rule {}: trade action imported disabled",
+                            label
+                        ));
+                    }
+                    smart_rules.push(rule);
+                }
+                Err(err) => errors.push(format!("smart rule {}: {}", label, err)),
+            },
+            Err(err) => errors.push(format!("smart rule {}: {}", label, err)),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(BundleError::Invalid(errors));
+    }
+
+    Ok(ResolvedBundle {
+        price_alerts,
+        smart_rules,
+        warnings,
+    })
+}
+
+pub fn summarize_bundle(json: &str) -> Result<BundleSummary, BundleError> {
+    let bundle = parse_bundle(json)?;
+    let errors = match resolve_bundle(&bundle, &HashMap::new()) {
+        Ok(_) => Vec::new(),
+        Err(BundleError::Invalid(errors)) => errors
+            .into_iter()
+            .filter(|e| !e.starts_with("no value supplied"))
+            .collect(),
+        Err(err) => vec![err.to_string()],
+    };
+
+    Ok(BundleSummary {
+        name: bundle.name,
+        description: bundle.description,
+        author: bundle.author,
+        format_version: bundle.format_version,
+        variables: bundle.variables,
+        price_alert_count: bundle.price_alerts.len(),
+        smart_rule_count: bundle.smart_rules.len(),
+        errors,
+    })
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+fn json_eq<T: Serialize>(a: &T, b: &T) -> bool {
+    match (serde_json::to_value(a), serde_json::to_value(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// An existing price alert duplicates a bundled one when it watches the same
+/// mint and either shares its name or has an identical condition set.
+pub fn find_price_conflict<'a>(
+    existing: &'a [BundledPriceAlertRef],
+    candidate: &BundledPriceAlert,
+) -> Option<&'a BundledPriceAlertRef> {
+    existing.iter().find(|e| {
+        e.alert.mint == candidate.mint
+            && (same_name(&e.alert.name, &candidate.name)
+                || json_eq(&e.alert.compound_condition, &candidate.compound_condition))
+    })
+}
+
+/// An existing smart rule duplicates a bundled one when the names match, or
+/// when both target the same symbol with an identical rule tree.
+pub fn find_smart_conflict<'a>(
+    existing: &'a [BundledSmartRuleRef],
+    candidate: &BundledSmartRule,
+) -> Option<&'a BundledSmartRuleRef> {
+    existing.iter().find(|e| {
+        same_name(&e.rule.name, &candidate.name)
+            || (e.rule.symbol == candidate.symbol
+                && json_eq(&e.rule.rule_tree, &candidate.rule_tree))
+    })
+}
+
+/// A stored (or about-to-be stored) alert used for conflict detection. `id`
+/// is `None` for entries planned earlier in the same dry run.
+#[derive(Debug, Clone)]
+pub struct BundledPriceAlertRef {
+    pub id: Option<String>,
+    pub alert: BundledPriceAlert,
+}
+
+#[derive(Debug, Clone)]
+pub struct BundledSmartRuleRef {
+    pub id: Option<String>,
+    pub rule: BundledSmartRule,
+}
+
+fn unique_name<'a>(base: &str, taken: impl Iterator<Item = &'a str> + Clone) -> String {
+    (2..)
+        .map(|n| format!("{} ({})", base, n))
+        .find(|name| !taken.clone().any(|t| same_name(t, name)))
+        .unwrap_or_else(|| base.to_string())
+}
+
+pub async fn export_bundle(
+    alerts: &AlertManager,
+    smart: &SmartAlertManager,
+    req: ExportBundleRequest,
+) -> Result<AlertBundle, BundleError> {
+    let price_alerts: Vec<PriceAlert> = match &req.price_alert_ids {
+        Some(ids) => {
+            let mut selected = Vec::with_capacity(ids.len());
+            for id in ids {
+                selected.push(alerts.get_alert(id).await?);
+            }
+            selected
+        }
+        None => alerts.list_alerts().await?,
+    };
+    let smart_rules: Vec<AlertRule> = match &req.smart_rule_ids {
+        Some(ids) => {
+            let mut selected = Vec::with_capacity(ids.len());
+            for id in ids {
+                selected.push(smart.get_rule(id).await?);
+            }
+            selected
+        }
+        None => {
+            smart
+                .list_rules(Some(SmartRuleFilter {
+                    include_disabled: true,
+                    ..Default::default()
+                }))
+                .await?
+        }
+    };
+
+    let variables: Vec<BundleVariable> = req
+        .variables
+        .iter()
+        .map(|v| BundleVariable {
+            name: v.name.clone(),
+            description: v.description.clone(),
+            kind: v.kind.unwrap_or(if v.value.parse::<f64>().is_ok() {
+                VariableKind::Number
+            } else {
+                VariableKind::Text
+            }),
+            default: v.keep_default.then(|| v.value.clone()),
+        })
+        .collect();
+    let literals: Vec<String> = req.variables.iter().map(|v| v.value.clone()).collect();
+
+    let mut tokenize = |value: Value| {
+        let mut value = value;
+        tokenize_entry(&mut value, &variables, &literals);
+        value
+    };
+    let price_alerts = price_alerts
+        .iter()
+        .map(|alert| serde_json::to_value(BundledPriceAlert::from(alert)).map(&mut tokenize))
+        .collect::<Result<Vec<_>, _>>()?;
+    let smart_rules = smart_rules
+        .iter()
+        .map(|rule| serde_json::to_value(BundledSmartRule::from(rule)).map(&mut tokenize))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let bundle = AlertBundle {
+        format_version: BUNDLE_FORMAT_VERSION.to_string(),
+        name: req.name,
+        description: req.description,
+        author: req.author,
+        created_at: Utc::now().to_rfc3339(),
+        variables,
+        price_alerts,
+        smart_rules,
+    };
+
+    let errors = validate_structure(&bundle);
+    if !errors.is_empty() {
+        return Err(BundleError::Invalid(errors));
+    }
+    Ok(bundle)
+}
+
+pub async fn import_bundle(
+    alerts: &AlertManager,
+    smart: &SmartAlertManager,
+    json: &str,
+    values: &HashMap<String, String>,
+    mode: BundleConflictMode,
+    dry_run: bool,
+) -> Result<BundleImportReport, BundleError> {
+    let bundle = parse_bundle(json)?;
+    let resolved = resolve_bundle(&bundle, values)?;
+
+    let mut report = BundleImportReport {
+        bundle_name: bundle.name.clone(),
+        dry_run,
+        created: 0,
+        replaced: 0,
+        skipped: 0,
+        items: Vec::new(),
+        warnings: resolved.warnings,
+    };
+
+    let mut existing_alerts: Vec<BundledPriceAlertRef> = alerts
+        .list_alerts()
+        .await?
+        .iter()
+        .map(|alert| BundledPriceAlertRef {
+            id: Some(alert.id.clone()),
+            alert: alert.into(),
+        })
+        .collect();
+
+    for mut candidate in resolved.price_alerts {
+        let conflict = find_price_conflict(&existing_alerts, &candidate).cloned();
+        let (outcome, id) = match (&conflict, mode) {
+            (Some(_), BundleConflictMode::Skip) => (BundleItemOutcome::Skipped, None),
+            (Some(existing), BundleConflictMode::Replace) => {
+                let id = match (&existing.id, dry_run) {
+                    (Some(id), false) => {
+                        alerts
+                            .update_alert(
+                                id,
+                                UpdateAlertRequest {
+                                    name: Some(candidate.name.clone()),
+                                    compound_condition: Some(candidate.compound_condition.clone()),
+                                    notification_channels: Some(
+                                        candidate.notification_channels.clone(),
+                                    ),
+                                    cooldown_minutes: Some(candidate.cooldown_minutes),
+                                    state: None,
+                                },
+                            )
+                            .await?;
+                        Some(id.clone())
+                    }
+                    (id, _) => id.clone(),
+                };
+                (BundleItemOutcome::Replaced, id)
+            }
+            (conflict, _) => {
+                if conflict.is_some() {
+                    candidate.name = unique_name(
+                        &candidate.name,
+                        existing_alerts.iter().map(|e| e.alert.name.as_str()),
+                    );
+                }
+                let id = if dry_run {
+                    None
+                } else {
+                    let created = alerts
+                        .create_alert(CreateAlertRequest {
+                            name: candidate.name.clone(),
+                            symbol: candidate.symbol.clone(),
+                            mint: candidate.mint.clone(),
+                            watchlist_id: None,
+                            compound_condition: candidate.compound_condition.clone(),
+                            notification_channels: candidate.notification_channels.clone(),
+                            cooldown_minutes: candidate.cooldown_minutes,
+                        })
+                        .await?;
+                    Some(created.id)
+                };
+                existing_alerts.push(BundledPriceAlertRef {
+                    id: id.clone(),
+                    alert: candidate.clone(),
+                });
+                let outcome = if conflict.is_some() {
+                    BundleItemOutcome::Renamed
+                } else {
+                    BundleItemOutcome::Created
+                };
+                (outcome, id)
+            }
+        };
+        report.record(
+            BundleItemKind::PriceAlert,
+            candidate.name,
+            outcome,
+            id,
+            conflict.map(|c| c.alert.name),
+        );
+    }
+
+    let mut existing_rules: Vec<BundledSmartRuleRef> = smart
+        .list_rules(Some(SmartRuleFilter {
+            include_disabled: true,
+            ..Default::default()
+        }))
+        .await?
+        .iter()
+        .map(|rule| BundledSmartRuleRef {
+            id: Some(rule.id.clone()),
+            rule: rule.into(),
+        })
+        .collect();
+
+    for mut candidate in resolved.smart_rules {
+        let conflict = find_smart_conflict(&existing_rules, &candidate).cloned();
+        let (outcome, id) = match (&conflict, mode) {
+            (Some(_), BundleConflictMode::Skip) => (BundleItemOutcome::Skipped, None),
+            (Some(existing), BundleConflictMode::Replace) => {
+                let id = match (&existing.id, dry_run) {
+                    (Some(id), false) => {
+                        smart
+                            .update_rule(
+                                id,
+                                UpdateSmartRuleRequest {
+                                    name: Some(candidate.name.clone()),
+                                    description: Some(candidate.description.clone()),
+                                    rule_tree: Some(candidate.rule_tree.clone()),
+                                    actions: Some(candidate.actions.clone()),
+                                    enabled: Some(candidate.enabled),
+                                    symbol: Some(candidate.symbol.clone()),
+                                    tags: Some(candidate.tags.clone()),
+                                    ..Default::default()
+                                },
+                            )
+                            .await?;
+                        Some(id.clone())
+                    }
+                    (id, _) => id.clone(),
+                };
+                (BundleItemOutcome::Replaced, id)
+            }
+            (conflict, _) => {
+                if conflict.is_some() {
+                    candidate.name = unique_name(
+                        &candidate.name,
+                        existing_rules.iter().map(|e| e.rule.name.as_str()),
+                    );
+                }
+                let id = if dry_run {
+                    None
+                } else {
+                    let created = smart
+                        .create_rule(CreateSmartRuleRequest {
+                            name: candidate.name.clone(),
+                            description: candidate.description.clone(),
+                            rule_tree: candidate.rule_tree.clone(),
+                            actions: candidate.actions.clone(),
+                            enabled: candidate.enabled,
+                            symbol: candidate.symbol.clone(),
+                            owner_id: None,
+                            team_id: None,
+                            shared_with: Vec::new(),
+                            tags: candidate.tags.clone(),
+                        })
+                        .await?;
+                    Some(created.id)
+                };
+                existing_rules.push(BundledSmartRuleRef {
+                    id: id.clone(),
+                    rule: candidate.clone(),
+                });
+                let outcome = if conflict.is_some() {
+                    BundleItemOutcome::Renamed
+                } else {
+                    BundleItemOutcome::Created
+                };
+                (outcome, id)
+            }
+        };
+        report.record(
+            BundleItemKind::SmartRule,
+            candidate.name,
+            outcome,
+            id,
+            conflict.map(|c| c.rule.name),
+        );
+    }
+
+    Ok(report)
+}
+
+impl BundleImportReport {
+    fn record(
+        &mut self,
+        kind: BundleItemKind,
+        name: String,
+        outcome: BundleItemOutcome,
+        id: Option<String>,
+        conflict_with: Option<String>,
+    ) {
+        match outcome {
+            BundleItemOutcome::Created | BundleItemOutcome::Renamed => self.created += 1,
+            BundleItemOutcome::Replaced => self.replaced += 1,
+            BundleItemOutcome::Skipped => self.skipped += 1,
+        }
+        self.items.push(BundleImportItem {
+            kind,
+            name,
+            outcome,
+            id,
+            conflict_with,
+        });
+    }
+}
+
+// Tauri commands
+
+#[tauri::command]
+pub async fn alert_bundle_export(
+    alerts: State<'_, SharedAlertManager>,
+    smart: State<'_, SharedSmartAlertManager>,
+    req: ExportBundleRequest,
+) -> Result<String, String> {
+    let alerts = alerts.read().await;
+    let smart = smart.read().await;
+    let bundle = export_bundle(&alerts, &smart, req)
+        .await
+        .map_err(|e| e.to_string())?;
+    serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn alert_bundle_inspect(json: String) -> Result<BundleSummary, String> {
+    summarize_bundle(&json).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn alert_bundle_import(
+    alerts: State<'_, SharedAlertManager>,
+    smart: State<'_, SharedSmartAlertManager>,
+    json: String,
+    values: Option<HashMap<String, String>>,
+    conflict_mode: Option<BundleConflictMode>,
+    dry_run: Option<bool>,
+) -> Result<BundleImportReport, String> {
+    let alerts = alerts.read().await;
+    let smart = smart.write().await;
+    import_bundle(
+        &alerts,
+        &smart,
+        &json,
+        &values.unwrap_or_default(),
+        conflict_mode.unwrap_or_default(),
+        dry_run.unwrap_or(false),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_bundle() -> AlertBundle {
+        AlertBundle {
+            format_version: BUNDLE_FORMAT_VERSION.to_string(),
+            name: "Breakout pack".to_string(),
+            description: None,
+            author: None,
+            created_at: Utc::now().to_rfc3339(),
+            variables: vec![
+                BundleVariable {
+                    name: "TOKEN".to_string(),
+                    description: None,
+                    kind: VariableKind::Text,
+                    default: None,
+                },
+                BundleVariable {
+                    name: "TARGET".to_string(),
+                    description: None,
+                    kind: VariableKind::Number,
+                    default: Some("1.5".to_string()),
+                },
+            ],
+            price_alerts: vec![json!({
+                "name": "{TOKEN} breakout",
+                "symbol": "{TOKEN}",
+                "mint": "{TOKEN}",
+                "compoundCondition": {
+                    "conditions": [{ "conditionType": "above", "value": "{TARGET}", "timeframeMinutes": null }],
+                    "operator": "and"
+                },
+                "notificationChannels": ["in_app"],
+                "cooldownMinutes": 30
+            })],
+            smart_rules: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn resolves_text_and_number_variables() {
+        let bundle = sample_bundle();
+        let values = HashMap::from([("TOKEN".to_string(), "BONK".to_string())]);
+        let resolved = resolve_bundle(&bundle, &values).expect("bundle resolves");
+
+        let alert = &resolved.price_alerts[0];
+        assert_eq!(alert.name, "BONK breakout");
+        assert_eq!(alert.symbol, "BONK");
+        assert_eq!(alert.compound_condition.conditions[0].value, 1.5);
+    }
+
+    #[test]
+    fn rejects_missing_and_undeclared_variables() {
+        let mut bundle = sample_bundle();
+        bundle.price_alerts[0]["name"] = json!("{PAIR} breakout");
+
+        let Err(BundleError::Invalid(errors)) = resolve_bundle(&bundle, &HashMap::new()) else {
+            panic!("expected validation errors");
+        };
+        assert!(errors.iter().any(|e| e.contains("{PAIR}")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("no value supplied for TOKEN")));
+    }
+
+    #[test]
+    fn tokenize_round_trips_through_substitution() {
+        let variables = vec![
+            BundleVariable {
+                name: "TOKEN".to_string(),
+                description: None,
+                kind: VariableKind::Text,
+                default: None,
+            },
+            BundleVariable {
+                name: "TARGET".to_string(),
+                description: None,
+                kind: VariableKind::Number,
+                default: None,
+            },
+        ];
+        let original = json!({
+            "name": "SOL above 200",
+            "compoundCondition": { "conditions": [{ "conditionType": "above", "value": 200.0 }], "operator": "and" },
+        });
+        let mut entry = original.clone();
+        tokenize_entry(
+            &mut entry,
+            &variables,
+            &["SOL".to_string(), "200".to_string()],
+        );
+        assert_eq!(entry["name"], json!("{TOKEN} above 200"));
+        assert_eq!(
+            entry["compoundCondition"]["conditions"][0]["value"],
+            json!("{TARGET}")
+        );
+        assert_eq!(entry["compoundCondition"]["operator"], json!("and"));
+
+        let kinds = variables.iter().map(|v| (v.name.clone(), v.kind)).collect();
+        let values = HashMap::from([
+            ("TOKEN".to_string(), "SOL".to_string()),
+            ("TARGET".to_string(), "200".to_string()),
+        ]);
+        substitute_entry(&mut entry, &kinds, &values);
+        assert_eq!(entry, original);
+    }
+
+    #[test]
+    fn detects_duplicates_by_name_or_conditions() {
+        let bundle = sample_bundle();
+        let values = HashMap::from([("TOKEN".to_string(), "BONK".to_string())]);
+        let candidate = resolve_bundle(&bundle, &values)
+            .unwrap()
+            .price_alerts
+            .remove(0);
+
+        let mut renamed = candidate.clone();
+        renamed.name = "Something else".to_string();
+        let existing = vec![BundledPriceAlertRef {
+            id: Some("a1".to_string()),
+            alert: renamed,
+        }];
+        assert!(find_price_conflict(&existing, &candidate).is_some());
+
+        let mut other_mint = candidate.clone();
+        other_mint.mint = "OTHER".to_string();
+        assert!(find_price_conflict(&existing, &other_mint).is_none());
+
+        let taken = ["BONK breakout", "BONK breakout (2)"];
+        assert_eq!(
+            unique_name("BONK breakout", taken.iter().copied()),
+            "BONK breakout (3)"
+        );
+    }
+}
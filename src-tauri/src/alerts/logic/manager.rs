@@ -1,3 +1,4 @@
+use crate::profiles::ProfilePaths;
 use super::actions::Action;
 use super::conditions::{MarketData, WhaleActivity};
 use super::dry_run::{execute_rule_with_dry_run, DryRunResult, DryRunSimulator};
@@ -8,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tauri::{AppHandle, Manager, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 use tokio::sync::RwLock;
 
 const SMART_ALERTS_DB_FILE: &str = "smart_alerts.db";
@@ -418,7 +419,7 @@ impl SmartAlertManager {
 
 fn smart_alerts_db_path(app: &AppHandle) -> Result<PathBuf, SmartAlertError> {
     let app_handle = app.clone();
-    let mut app_data_dir = app_handle.path().app_data_dir().map_err(|err| {
+    let mut app_data_dir = app_handle.path().profile_data_dir().map_err(|err| {
         SmartAlertError::Internal(format!("Unable to resolve app data directory: {err}"))
     })?;
 
@@ -490,6 +491,7 @@ pub async fn smart_alert_dry_run(
 
 #[tauri::command]
 pub async fn smart_alert_execute(
+    app: AppHandle,
     manager: State<'_, SharedSmartAlertManager>,
     id: String,
     market_data: MarketData,
@@ -497,7 +499,12 @@ pub async fn smart_alert_execute(
     dry_run: bool,
 ) -> Result<RuleExecutionResult, String> {
     let mgr = manager.read().await;
-    mgr.execute(&id, market_data, whale_activity, dry_run)
+    let result = mgr
+        .execute(&id, market_data, whale_activity, dry_run)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    if result.triggered && !result.dry_run {
+        let _ = app.emit(crate::trading::SMART_ALERT_TRIGGERED_EVENT, &result);
+    }
+    Ok(result)
 }
@@ -1,6 +1,8 @@
+pub mod bundles;
 pub mod logic;
 pub mod price_alerts;
 
+pub use bundles::*;
 pub use logic::*;
 // Re-export price_alerts items except LogicalOperator (already exported from logic::rule_engine to avoid ambiguity)
 pub use price_alerts::{
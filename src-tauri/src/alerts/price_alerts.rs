@@ -1,4 +1,6 @@
 use chrono::{DateTime, Duration, Utc};
+use crate::demo::SharedDemoModeState;
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use std::collections::HashMap;
@@ -17,6 +19,9 @@ pub enum AlertConditionType {
     Below,
     PercentChange,
     VolumeSpike,
+    /// Perp funding, in percent per hour.
+    FundingRateAbove,
+    FundingRateBelow,
 }
 
 impl AlertConditionType {
@@ -26,6 +31,8 @@ impl AlertConditionType {
             AlertConditionType::Below => "below",
             AlertConditionType::PercentChange => "percent_change",
             AlertConditionType::VolumeSpike => "volume_spike",
+            AlertConditionType::FundingRateAbove => "funding_rate_above",
+            AlertConditionType::FundingRateBelow => "funding_rate_below",
         }
     }
 
@@ -35,6 +42,8 @@ impl AlertConditionType {
             "below" => Some(AlertConditionType::Below),
             "percent_change" => Some(AlertConditionType::PercentChange),
             "volume_spike" => Some(AlertConditionType::VolumeSpike),
+            "funding_rate_above" => Some(AlertConditionType::FundingRateAbove),
+            "funding_rate_below" => Some(AlertConditionType::FundingRateBelow),
             _ => None,
         }
     }
@@ -422,6 +431,41 @@ impl AlertManager {
         Ok(alert)
     }
 
+    /// Writes the alert exactly as given, replacing any stored alert with
+    /// the same id. Used when restoring alerts from a snapshot.
+    pub async fn upsert_alert(&self, alert: &PriceAlert) -> Result<(), AlertError> {
+        let compound_condition_json = serde_json::to_string(&alert.compound_condition)?;
+        let channels_json = serde_json::to_string(&alert.notification_channels)?;
+
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO price_alerts (
+                id, name, symbol, mint, watchlist_id, compound_condition,
+                notification_channels, cooldown_minutes, state,
+                last_triggered_at, cooldown_until, created_at, updated_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+            "#,
+        )
+        .bind(&alert.id)
+        .bind(&alert.name)
+        .bind(&alert.symbol)
+        .bind(&alert.mint)
+        .bind(&alert.watchlist_id)
+        .bind(&compound_condition_json)
+        .bind(&channels_json)
+        .bind(alert.cooldown_minutes)
+        .bind(alert.state.as_str())
+        .bind(&alert.last_triggered_at)
+        .bind(&alert.cooldown_until)
+        .bind(&alert.created_at)
+        .bind(&alert.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn delete_alert(&self, id: &str) -> Result<(), AlertError> {
         let result = sqlx::query("DELETE FROM price_alerts WHERE id = ?1")
             .bind(id)
@@ -441,6 +485,7 @@ impl AlertManager {
         current_price: f64,
         price_24h_ago: Option<f64>,
         volume_24h: Option<f64>,
+        funding_rate: Option<f64>,
     ) -> Result<AlertTestResult, AlertError> {
         let alert = self.get_alert(id).await?;
 
@@ -449,6 +494,7 @@ impl AlertManager {
             current_price,
             price_24h_ago,
             volume_24h,
+            funding_rate,
         );
 
         Ok(AlertTestResult {
@@ -466,6 +512,30 @@ impl AlertManager {
         current_price: f64,
         price_24h_ago: Option<f64>,
         volume_24h: Option<f64>,
+    ) -> Result<Vec<String>, AlertError> {
+        self.trigger_matching_alerts(symbol, current_price, price_24h_ago, volume_24h, None)
+            .await
+    }
+
+    /// Checks alerts on a perp market (e.g. "SOL-PERP") that have a funding
+    /// condition. `funding_rate` is in percent per hour.
+    pub async fn check_funding_alerts(
+        &self,
+        symbol: &str,
+        mark_price: f64,
+        funding_rate: f64,
+    ) -> Result<Vec<String>, AlertError> {
+        self.trigger_matching_alerts(symbol, mark_price, None, None, Some(funding_rate))
+            .await
+    }
+
+    async fn trigger_matching_alerts(
+        &self,
+        symbol: &str,
+        current_price: f64,
+        price_24h_ago: Option<f64>,
+        volume_24h: Option<f64>,
+        funding_rate: Option<f64>,
     ) -> Result<Vec<String>, AlertError> {
         let now = Utc::now();
         let rows = sqlx::query(
@@ -487,6 +557,12 @@ impl AlertManager {
         for row in rows {
             let alert = self.row_to_alert(row)?;
 
+            // Funding updates only carry a mark price, so leave price-only
+            // alerts to the regular price checks.
+            if funding_rate.is_some() && !has_funding_condition(&alert.compound_condition) {
+                continue;
+            }
+
             if let Some(cooldown_until_str) = &alert.cooldown_until {
                 if let Ok(cooldown_until) = DateTime::parse_from_rfc3339(cooldown_until_str) {
                     if now < cooldown_until.with_timezone(&Utc) {
@@ -500,6 +576,7 @@ impl AlertManager {
                 current_price,
                 price_24h_ago,
                 volume_24h,
+                funding_rate,
             );
 
             if would_trigger {
@@ -577,6 +654,7 @@ impl AlertManager {
         current_price: f64,
         price_24h_ago: Option<f64>,
         volume_24h: Option<f64>,
+        funding_rate: Option<f64>,
     ) -> (bool, Vec<bool>, String) {
         let mut results = Vec::new();
         let mut messages = Vec::new();
@@ -630,6 +708,30 @@ impl AlertManager {
                         (false, "Volume data unavailable".to_string())
                     }
                 }
+                AlertConditionType::FundingRateAbove | AlertConditionType::FundingRateBelow => {
+                    if let Some(rate) = funding_rate {
+                        let above = condition.condition_type == AlertConditionType::FundingRateAbove;
+                        let met = if above {
+                            rate > condition.value
+                        } else {
+                            rate < condition.value
+                        };
+                        let msg = format!(
+                            "Funding {:.4}%/hr {} threshold {:.4}%/hr",
+                            rate,
+                            match (above, met) {
+                                (true, true) => "above",
+                                (true, false) => "not above",
+                                (false, true) => "below",
+                                (false, false) => "not below",
+                            },
+                            condition.value
+                        );
+                        (met, msg)
+                    } else {
+                        (false, "Funding rate unavailable".to_string())
+                    }
+                }
             };
 
             results.push(met);
@@ -675,10 +777,19 @@ impl AlertManager {
     }
 }
 
+fn has_funding_condition(compound: &CompoundCondition) -> bool {
+    compound.conditions.iter().any(|c| {
+        matches!(
+            c.condition_type,
+            AlertConditionType::FundingRateAbove | AlertConditionType::FundingRateBelow
+        )
+    })
+}
+
 fn alerts_db_path(app: &AppHandle) -> Result<PathBuf, AlertError> {
     let app_data_dir = app
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|e| AlertError::Internal(format!("Unable to resolve app data directory: {}", e)))?;
 
     std::fs::create_dir_all(&app_data_dir)?;
@@ -696,7 +807,13 @@ pub async fn alert_create(
 }
 
 #[tauri::command]
-pub async fn alert_list(manager: State<'_, SharedAlertManager>) -> Result<Vec<PriceAlert>, String> {
+pub async fn alert_list(
+    manager: State<'_, SharedAlertManager>,
+    demo: State<'_, SharedDemoModeState>,
+) -> Result<Vec<PriceAlert>, String> {
+    if let Some(overlay) = &demo.read().await.overlay {
+        return Ok(overlay.alerts());
+    }
     let mgr = manager.read().await;
     mgr.list_alerts().await.map_err(|e| e.to_string())
 }
@@ -704,8 +821,14 @@ pub async fn alert_list(manager: State<'_, SharedAlertManager>) -> Result<Vec<Pr
 #[tauri::command]
 pub async fn alert_get(
     manager: State<'_, SharedAlertManager>,
+    demo: State<'_, SharedDemoModeState>,
     id: String,
 ) -> Result<PriceAlert, String> {
+    if let Some(overlay) = &demo.read().await.overlay {
+        return overlay
+            .alert(&id)
+            .ok_or_else(|| AlertError::NotFound(id).to_string());
+    }
     let mgr = manager.read().await;
     mgr.get_alert(&id).await.map_err(|e| e.to_string())
 }
@@ -736,9 +859,10 @@ pub async fn alert_test(
     current_price: f64,
     price_24h_ago: Option<f64>,
     volume_24h: Option<f64>,
+    funding_rate: Option<f64>,
 ) -> Result<AlertTestResult, String> {
     let mgr = manager.read().await;
-    mgr.test_alert(&id, current_price, price_24h_ago, volume_24h)
+    mgr.test_alert(&id, current_price, price_24h_ago, volume_24h, funding_rate)
         .await
         .map_err(|e| e.to_string())
 }
@@ -1,3 +1,4 @@
+use crate::demo::SharedDemoModeState;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -46,10 +47,13 @@ pub struct AnomalyDetectionConfig {
 
 pub type SharedAnomalyDetector = Arc<RwLock<AnomalyDetector>>;
 
+const DEFAULT_MAX_ANOMALIES: usize = 200;
+
 pub struct AnomalyDetector {
     price_history: HashMap<String, Vec<PriceData>>,
     transaction_history: HashMap<String, Vec<TransactionData>>,
     anomalies: Vec<Anomaly>,
+    max_anomalies: usize,
     config: AnomalyDetectionConfig,
 }
 
@@ -59,6 +63,7 @@ impl AnomalyDetector {
             price_history: HashMap::new(),
             transaction_history: HashMap::new(),
             anomalies: Vec::new(),
+            max_anomalies: DEFAULT_MAX_ANOMALIES,
             config: AnomalyDetectionConfig {
                 enabled: true,
                 zscore_threshold: 3.0,
@@ -298,9 +303,7 @@ impl AnomalyDetector {
             }
         }
 
-        if self.anomalies.len() > 200 {
-            self.anomalies.drain(0..self.anomalies.len() - 200);
-        }
+        self.trim_to_limit();
     }
 
     fn detect_wash_trading(&mut self, token_address: &str) {
@@ -424,6 +427,44 @@ impl AnomalyDetector {
         }
     }
 
+    fn trim_to_limit(&mut self) {
+        if self.anomalies.len() > self.max_anomalies {
+            self.anomalies.drain(0..self.anomalies.len() - self.max_anomalies);
+        }
+    }
+
+    /// Caps how many anomalies are kept; set from the retention policy.
+    pub fn set_max_anomalies(&mut self, max: usize) {
+        self.max_anomalies = max;
+        self.trim_to_limit();
+    }
+
+    pub fn anomaly_count(&self) -> usize {
+        self.anomalies.len()
+    }
+
+    pub fn count_older_than(&self, max_age_secs: Option<i64>) -> usize {
+        let Some(max_age) = max_age_secs else {
+            return 0;
+        };
+        let cutoff = Utc::now().timestamp() - max_age;
+        self.anomalies.iter().filter(|a| a.timestamp < cutoff).count()
+    }
+
+    /// Drops anomalies older than `max_age_secs`, then the oldest beyond
+    /// `keep`. Returns how many were removed.
+    pub fn prune_anomalies(&mut self, max_age_secs: Option<i64>, keep: usize) -> usize {
+        let before = self.anomalies.len();
+        if let Some(max_age) = max_age_secs {
+            let cutoff = Utc::now().timestamp() - max_age;
+            self.anomalies.retain(|a| a.timestamp >= cutoff);
+        }
+        if self.anomalies.len() > keep {
+            self.anomalies.drain(0..self.anomalies.len() - keep);
+        }
+        before - self.anomalies.len()
+    }
+
     pub fn update_config(&mut self, config: AnomalyDetectionConfig) {
         self.config = config;
     }
@@ -502,7 +543,13 @@ pub async fn get_anomalies(
     token_address: Option<String>,
     anomaly_type: Option<String>,
     detector: tauri::State<'_, SharedAnomalyDetector>,
+    demo: tauri::State<'_, SharedDemoModeState>,
 ) -> Result<Vec<Anomaly>, String> {
+    if let Some(overlay) = &demo.read().await.overlay {
+        return Ok(overlay
+            .anomalies
+            .get_anomalies(token_address.as_deref(), anomaly_type.as_deref()));
+    }
     let det = detector.read().await;
     Ok(det.get_anomalies(token_address.as_deref(), anomaly_type.as_deref()))
 }
@@ -510,7 +557,11 @@ pub async fn get_anomalies(
 #[tauri::command]
 pub async fn get_active_anomalies(
     detector: tauri::State<'_, SharedAnomalyDetector>,
+    demo: tauri::State<'_, SharedDemoModeState>,
 ) -> Result<Vec<Anomaly>, String> {
+    if let Some(overlay) = &demo.read().await.overlay {
+        return Ok(overlay.anomalies.get_active_anomalies());
+    }
     let det = detector.read().await;
     Ok(det.get_active_anomalies())
 }
@@ -519,7 +570,12 @@ pub async fn get_active_anomalies(
 pub async fn dismiss_anomaly(
     anomaly_id: String,
     detector: tauri::State<'_, SharedAnomalyDetector>,
+    demo: tauri::State<'_, SharedDemoModeState>,
 ) -> Result<(), String> {
+    if let Some(overlay) = demo.write().await.overlay.as_mut() {
+        overlay.anomalies.dismiss_anomaly(&anomaly_id);
+        return Ok(());
+    }
     let mut det = detector.write().await;
     det.dismiss_anomaly(&anomaly_id);
     Ok(())
@@ -547,7 +603,11 @@ pub async fn get_anomaly_detection_config(
 pub async fn get_anomaly_statistics(
     token_address: String,
     detector: tauri::State<'_, SharedAnomalyDetector>,
+    demo: tauri::State<'_, SharedDemoModeState>,
 ) -> Result<Option<AnomalyStatistics>, String> {
+    if let Some(overlay) = &demo.read().await.overlay {
+        return Ok(overlay.anomalies.get_statistics(&token_address));
+    }
     let det = detector.read().await;
     Ok(det.get_statistics(&token_address))
 }
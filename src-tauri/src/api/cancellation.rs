@@ -0,0 +1,251 @@
+use crate::errors::AppError;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::Notify;
+
+/// Cooperative cancellation signal shared between a command and whoever
+/// wants to abort it.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<TokenInner>,
+}
+
+#[derive(Debug, Default)]
+struct TokenInner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once `cancel` has been called.
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Races `future` against cancellation, dropping it (and any in-flight
+    /// HTTP request it owns) as soon as the token fires.
+    pub async fn run<F, T>(&self, future: F) -> Result<T, AppError>
+    where
+        F: Future<Output = T>,
+    {
+        if self.is_cancelled() {
+            return Err(AppError::Cancelled(
+                "request cancelled before start".to_string(),
+            ));
+        }
+
+        tokio::select! {
+            output = future => Ok(output),
+            _ = self.cancelled() => Err(AppError::Cancelled("request cancelled".to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InFlightRequest {
+    pub request_id: String,
+    pub command: String,
+    pub started_at: i64,
+}
+
+struct RegisteredRequest {
+    token: CancellationToken,
+    info: InFlightRequest,
+}
+
+/// Tracks cancellable requests by the frontend-supplied request id.
+#[derive(Default)]
+pub struct RequestRegistry {
+    requests: Mutex<HashMap<String, RegisteredRequest>>,
+}
+
+pub type SharedRequestRegistry = Arc<RequestRegistry>;
+
+impl RequestRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a request and returns its token. Re-using an id that is
+    /// still in flight cancels the previous request first.
+    pub fn register(&self, request_id: &str, command: &str) -> CancellationToken {
+        let token = CancellationToken::new();
+        let entry = RegisteredRequest {
+            token: token.clone(),
+            info: InFlightRequest {
+                request_id: request_id.to_string(),
+                command: command.to_string(),
+                started_at: chrono::Utc::now().timestamp_millis(),
+            },
+        };
+
+        if let Some(previous) = self.requests.lock().insert(request_id.to_string(), entry) {
+            previous.token.cancel();
+        }
+        token
+    }
+
+    pub fn complete(&self, request_id: &str, token: &CancellationToken) {
+        let mut requests = self.requests.lock();
+        let same_request = requests
+            .get(request_id)
+            .map(|entry| Arc::ptr_eq(&entry.token.inner, &token.inner))
+            .unwrap_or(false);
+        if same_request {
+            requests.remove(request_id);
+        }
+    }
+
+    pub fn cancel(&self, request_id: &str) -> bool {
+        match self.requests.lock().remove(request_id) {
+            Some(entry) => {
+                entry.token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn cancel_all(&self) -> usize {
+        let drained: Vec<_> = self.requests.lock().drain().collect();
+        for (_, entry) in &drained {
+            entry.token.cancel();
+        }
+        drained.len()
+    }
+
+    pub fn in_flight(&self) -> Vec<InFlightRequest> {
+        self.requests
+            .lock()
+            .values()
+            .map(|entry| entry.info.clone())
+            .collect()
+    }
+
+    /// Runs `future` under a token registered as `request_id`. Requests
+    /// without an id are not tracked and run to completion.
+    pub async fn track<F, T>(
+        &self,
+        request_id: Option<&str>,
+        command: &str,
+        future: F,
+    ) -> Result<T, AppError>
+    where
+        F: Future<Output = T>,
+    {
+        let Some(request_id) = request_id else {
+            return Ok(future.await);
+        };
+
+        let token = self.register(request_id, command);
+        let result = token.run(future).await;
+        self.complete(request_id, &token);
+
+        if result.is_err() {
+            tracing::debug!(request_id, command, "in-flight request cancelled");
+        }
+        result
+    }
+}
+
+#[tauri::command]
+pub async fn cancel_request(
+    request_id: String,
+    registry: State<'_, SharedRequestRegistry>,
+) -> Result<bool, String> {
+    Ok(registry.cancel(&request_id))
+}
+
+#[tauri::command]
+pub async fn cancel_all_requests(
+    registry: State<'_, SharedRequestRegistry>,
+) -> Result<usize, String> {
+    Ok(registry.cancel_all())
+}
+
+#[tauri::command]
+pub async fn list_inflight_requests(
+    registry: State<'_, SharedRequestRegistry>,
+) -> Result<Vec<InFlightRequest>, String> {
+    Ok(registry.in_flight())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn cancel_aborts_pending_future() {
+        let registry = Arc::new(RequestRegistry::new());
+        let worker = registry.clone();
+
+        let handle = tokio::spawn(async move {
+            worker
+                .track(Some("quote-1"), "jupiter_quote", async {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    42
+                })
+                .await
+        });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(registry.cancel("quote-1"));
+
+        let result = handle.await.unwrap();
+        assert!(matches!(result, Err(AppError::Cancelled(_))));
+        assert!(registry.in_flight().is_empty());
+    }
+
+    #[tokio::test]
+    async fn completed_requests_are_removed() {
+        let registry = RequestRegistry::new();
+        let value = registry
+            .track(Some("holders-1"), "get_holder_distribution", async {
+                "done"
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(value, "done");
+        assert!(!registry.cancel("holders-1"));
+    }
+
+    #[tokio::test]
+    async fn reusing_an_id_cancels_the_previous_request() {
+        let registry = RequestRegistry::new();
+        let first = registry.register("search", "search_tokens");
+        let second = registry.register("search", "search_tokens");
+
+        assert!(first.is_cancelled());
+        assert!(!second.is_cancelled());
+
+        registry.complete("search", &first);
+        assert_eq!(registry.in_flight().len(), 1);
+    }
+}
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use std::collections::HashMap;
@@ -111,7 +112,7 @@ impl ApiHealthMonitor {
     }
 
     fn health_db_path(app: &AppHandle) -> Result<std::path::PathBuf, HealthMonitorError> {
-        let mut path = app.path().app_data_dir().map_err(|err| {
+        let mut path = app.path().profile_data_dir().map_err(|err| {
             HealthMonitorError::Internal(format!("Unable to resolve app data directory: {err}"))
         })?;
 
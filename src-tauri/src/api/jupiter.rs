@@ -9,6 +9,10 @@ use std::time::Duration;
 use thiserror::Error;
 use tracing::{debug, instrument, warn};
 
+use super::cancellation::SharedRequestRegistry;
+use crate::token_extensions::SharedTokenExtensionService;
+use crate::trading::token_policy::{check_token_policy, ExecutionPath};
+
 const JUPITER_BASE_URL: &str = "https://quote-api.jup.ag/v6";
 
 #[derive(Debug, Error)]
@@ -192,6 +196,20 @@ pub struct QuoteResult {
     pub context_slot: u64,
     #[serde(default)]
     pub prioritization_fee_lamports: Option<String>,
+    /// Set when either mint charges a Token-2022 transfer fee.
+    #[serde(default)]
+    pub transfer_fee_adjustment: Option<TransferFeeAdjustment>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferFeeAdjustment {
+    pub input_fee: u64,
+    pub output_fee: u64,
+    pub input_fee_bps: Option<u16>,
+    pub output_fee_bps: Option<u16>,
+    /// Output the wallet actually receives after the output mint's fee.
+    pub net_output_amount: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -293,26 +311,87 @@ struct SwapRequestBody<'a> {
     simulate: Option<bool>,
 }
 
-#[tauri::command]
-#[instrument(skip(input), fields(input_mint = %input.input_mint, output_mint = %input.output_mint, amount = input.amount))]
-pub async fn jupiter_quote(input: QuoteCommandInput) -> Result<QuoteResult, String> {
+/// Fetches a quote and parses its route plan. Shared by the `jupiter_quote`
+/// command and internal callers such as the DCA bots.
+pub async fn fetch_quote(input: &QuoteCommandInput) -> Result<QuoteResult, JupiterError> {
     let client = JupiterClient::default();
-    let response = client.quote(&input).await.map_err(String::from)?;
+    let response = client.quote(input).await?;
     let route = parse_route_plan(&response);
     Ok(QuoteResult {
         context_slot: response.context_slot,
         prioritization_fee_lamports: response.prioritization_fee_lamports.clone(),
+        transfer_fee_adjustment: None,
         route,
         quote: response,
     })
 }
 
+/// Computes the transfer fees withheld on both legs of a quote. Returns
+/// `None` when neither mint charges one.
+pub async fn transfer_fee_adjustment(
+    quote: &QuoteResponse,
+    extensions: &SharedTokenExtensionService,
+) -> Option<TransferFeeAdjustment> {
+    let mut service = extensions.write().await;
+    let input = service.try_get_extensions(&quote.input_mint).await;
+    let output = service.try_get_extensions(&quote.output_mint).await;
+    if input
+        .as_ref()
+        .and_then(|e| e.transfer_fee.as_ref())
+        .is_none()
+        && output
+            .as_ref()
+            .and_then(|e| e.transfer_fee.as_ref())
+            .is_none()
+    {
+        return None;
+    }
+
+    let epoch = service.current_epoch().await;
+    let input_amount = quote.input_amount.parse::<u64>().unwrap_or(0);
+    let output_amount = quote.output_amount.parse::<u64>().unwrap_or(0);
+    let input_fee = input
+        .as_ref()
+        .map(|e| e.breakdown(input_amount, epoch).fee_amount)
+        .unwrap_or(0);
+    let output_fee = output
+        .as_ref()
+        .map(|e| e.breakdown(output_amount, epoch).fee_amount)
+        .unwrap_or(0);
+
+    Some(TransferFeeAdjustment {
+        input_fee,
+        output_fee,
+        input_fee_bps: input.as_ref().and_then(|e| e.transfer_fee_bps(epoch)),
+        output_fee_bps: output.as_ref().and_then(|e| e.transfer_fee_bps(epoch)),
+        net_output_amount: output_amount.saturating_sub(output_fee),
+    })
+}
+
+#[tauri::command]
+#[instrument(skip(input, requests, extensions), fields(input_mint = %input.input_mint, output_mint = %input.output_mint, amount = input.amount))]
+pub async fn jupiter_quote(
+    input: QuoteCommandInput,
+    request_id: Option<String>,
+    requests: tauri::State<'_, SharedRequestRegistry>,
+    extensions: tauri::State<'_, SharedTokenExtensionService>,
+) -> Result<QuoteResult, String> {
+    let mut result = requests
+        .track(request_id.as_deref(), "jupiter_quote", fetch_quote(&input))
+        .await?
+        .map_err(String::from)?;
+    result.transfer_fee_adjustment = transfer_fee_adjustment(&result.quote, &extensions).await;
+    Ok(result)
+}
+
 #[tauri::command]
 #[instrument(skip(input), fields(user = %input.user_public_key))]
 pub async fn jupiter_swap(input: SwapCommandInput) -> Result<SwapResult, String> {
+    crate::environment::require_mainnet("Jupiter swaps").map_err(|e| e.to_string())?;
     if input.quote.route_plan.is_empty() {
         return Err(JupiterError::MissingQuote.into());
     }
+    check_token_policy(ExecutionPath::Swap, &input.quote.output_mint).await?;
 
     let client = JupiterClient::default();
     let response = client
@@ -362,7 +441,7 @@ fn parse_route_plan(quote: &QuoteResponse) -> ParsedRoutePlan {
     }
 }
 
-fn decode_versioned_transaction(encoded: &str) -> Result<EncodedTransaction, String> {
+pub(crate) fn decode_versioned_transaction(encoded: &str) -> Result<EncodedTransaction, String> {
     use solana_sdk::{signature::Signature, transaction::VersionedTransaction};
 
     let bytes = general_purpose::STANDARD
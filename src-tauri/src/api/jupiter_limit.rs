@@ -0,0 +1,837 @@
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::RwLock;
+use tracing::{instrument, warn};
+
+use super::jupiter::{decode_versioned_transaction, EncodedTransaction, JupiterError};
+use crate::chains::{RoutingHint, RpcPool, SharedRpcPool};
+use crate::profiles::ProfilePaths;
+
+const JUPITER_LIMIT_BASE_URL: &str = "https://api.jup.ag/limit/v2";
+/// Jupiter Limit Order v2 program; every order is an account owned by it.
+pub const JUPITER_LIMIT_ORDER_PROGRAM_ID: &str = "j1o2qRpjcyUwEvwtcfhEQefh773ZgjxcVRry7LDqg5X";
+const LIMIT_ORDERS_FILE: &str = "jupiter_limit_orders.json";
+/// How long an order may go unseen on chain before its creation is
+/// considered to have failed (the user never signed, or it expired).
+const PENDING_GRACE_MINUTES: i64 = 10;
+const MAX_ACCOUNTS_PER_CALL: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitOrderStatus {
+    /// Transaction built but the order account has not been seen yet.
+    Pending,
+    Open,
+    /// Cancel transaction built; still open until the account closes.
+    Cancelling,
+    Filled,
+    Cancelled,
+    Expired,
+    /// Account closed but history could not tell how.
+    Closed,
+    /// Never appeared on chain within the grace period.
+    Failed,
+}
+
+impl LimitOrderStatus {
+    pub fn is_terminal(&self) -> bool {
+        !matches!(
+            self,
+            LimitOrderStatus::Pending | LimitOrderStatus::Open | LimitOrderStatus::Cancelling
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackedLimitOrder {
+    /// Order account address.
+    pub order: String,
+    pub maker: String,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub making_amount: u64,
+    pub taking_amount: u64,
+    /// Input still unfilled, as last reported by Jupiter.
+    pub remaining_making_amount: u64,
+    #[serde(default)]
+    pub expired_at: Option<i64>,
+    pub status: LimitOrderStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub last_reconciled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateLimitOrderInput {
+    pub maker: String,
+    #[serde(default)]
+    pub payer: Option<String>,
+    pub input_mint: String,
+    pub output_mint: String,
+    /// Input amount in base units.
+    pub making_amount: u64,
+    /// Minimum output amount in base units; sets the limit price.
+    pub taking_amount: u64,
+    /// Unix seconds after which the order can no longer fill.
+    #[serde(default)]
+    pub expired_at: Option<i64>,
+    #[serde(default)]
+    pub compute_unit_price_micro_lamports: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitOrderTransaction {
+    pub order: String,
+    pub transaction: EncodedTransaction,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelLimitOrdersResult {
+    pub orders: Vec<String>,
+    pub transactions: Vec<EncodedTransaction>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitOrderReconcileReport {
+    pub checked: usize,
+    pub opened: usize,
+    pub closed: usize,
+    /// Open orders found on chain that were not tracked locally.
+    pub discovered: usize,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateOrderParams {
+    making_amount: String,
+    taking_amount: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expired_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateOrderBody<'a> {
+    input_mint: &'a str,
+    output_mint: &'a str,
+    maker: &'a str,
+    payer: &'a str,
+    params: CreateOrderParams,
+    compute_unit_price: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateOrderResponse {
+    order: String,
+    tx: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CancelOrdersBody<'a> {
+    maker: &'a str,
+    orders: &'a [String],
+    compute_unit_price: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CancelOrdersResponse {
+    txs: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct OpenOrderAccount {
+    maker: String,
+    input_mint: String,
+    output_mint: String,
+    ori_making_amount: String,
+    ori_taking_amount: String,
+    making_amount: String,
+    #[serde(default)]
+    expired_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct OpenOrder {
+    public_key: String,
+    account: OpenOrderAccount,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct HistoryOrder {
+    order_key: String,
+    status: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OrderHistoryResponse {
+    #[serde(default)]
+    orders: Vec<HistoryOrder>,
+}
+
+#[derive(Debug, Clone)]
+pub struct JupiterLimitClient {
+    http: Client,
+    base_url: String,
+}
+
+impl Default for JupiterLimitClient {
+    fn default() -> Self {
+        let http = Client::builder()
+            .timeout(Duration::from_secs(20))
+            .build()
+            .expect("failed to construct http client");
+        Self {
+            http,
+            base_url: JUPITER_LIMIT_BASE_URL.to_string(),
+        }
+    }
+}
+
+impl JupiterLimitClient {
+    #[cfg(test)]
+    pub fn with_base_url(base_url: impl Into<String>) -> Self {
+        Self {
+            http: Client::new(),
+            base_url: base_url.into(),
+        }
+    }
+
+    async fn send<T: for<'de> Deserialize<'de>>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, JupiterError> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| JupiterError::Network(e.to_string()))?;
+        if !response.status().is_success() {
+            let status: StatusCode = response.status();
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "<unavailable>".into());
+            return Err(JupiterError::Http { status, body });
+        }
+        response
+            .json::<T>()
+            .await
+            .map_err(|e| JupiterError::Serialization(e.to_string()))
+    }
+
+    async fn create_order(
+        &self,
+        input: &CreateLimitOrderInput,
+    ) -> Result<CreateOrderResponse, JupiterError> {
+        let body = CreateOrderBody {
+            input_mint: &input.input_mint,
+            output_mint: &input.output_mint,
+            maker: &input.maker,
+            payer: input.payer.as_deref().unwrap_or(&input.maker),
+            params: CreateOrderParams {
+                making_amount: input.making_amount.to_string(),
+                taking_amount: input.taking_amount.to_string(),
+                expired_at: input.expired_at.map(|ts| ts.to_string()),
+            },
+            compute_unit_price: compute_unit_price(input.compute_unit_price_micro_lamports),
+        };
+        self.send(
+            self.http
+                .post(format!("{}/createOrder", self.base_url))
+                .json(&body),
+        )
+        .await
+    }
+
+    async fn cancel_orders(
+        &self,
+        maker: &str,
+        orders: &[String],
+    ) -> Result<CancelOrdersResponse, JupiterError> {
+        let body = CancelOrdersBody {
+            maker,
+            orders,
+            compute_unit_price: compute_unit_price(None),
+        };
+        self.send(
+            self.http
+                .post(format!("{}/cancelOrders", self.base_url))
+                .json(&body),
+        )
+        .await
+    }
+
+    async fn open_orders(&self, wallet: &str) -> Result<Vec<OpenOrder>, JupiterError> {
+        self.send(
+            self.http
+                .get(format!("{}/openOrders", self.base_url))
+                .query(&[("wallet", wallet)]),
+        )
+        .await
+    }
+
+    async fn order_history(&self, wallet: &str) -> Result<Vec<HistoryOrder>, JupiterError> {
+        let response: OrderHistoryResponse = self
+            .send(
+                self.http
+                    .get(format!("{}/orderHistory", self.base_url))
+                    .query(&[("wallet", wallet), ("page", "1")]),
+            )
+            .await?;
+        Ok(response.orders)
+    }
+}
+
+fn compute_unit_price(micro_lamports: Option<u64>) -> String {
+    micro_lamports
+        .map(|price| price.to_string())
+        .unwrap_or_else(|| "auto".to_string())
+}
+
+fn parse_amount(value: &str) -> u64 {
+    value
+        .parse::<u64>()
+        .or_else(|_| value.parse::<f64>().map(|v| v as u64))
+        .unwrap_or(0)
+}
+
+/// Maps the status string from Jupiter's order history.
+fn history_status(status: &str) -> LimitOrderStatus {
+    let status = status.to_ascii_lowercase();
+    if status.contains("complet") || status.contains("fill") {
+        LimitOrderStatus::Filled
+    } else if status.contains("cancel") {
+        LimitOrderStatus::Cancelled
+    } else if status.contains("expir") {
+        LimitOrderStatus::Expired
+    } else {
+        LimitOrderStatus::Closed
+    }
+}
+
+/// Status for a tracked order whose account no longer exists (or never
+/// did). `history` is what Jupiter reports for it, if anything.
+pub fn resolve_missing_order(
+    order: &TrackedLimitOrder,
+    history: Option<LimitOrderStatus>,
+    now: DateTime<Utc>,
+) -> LimitOrderStatus {
+    if order.status == LimitOrderStatus::Pending {
+        if let Some(status) = history {
+            return status;
+        }
+        return if now - order.created_at > ChronoDuration::minutes(PENDING_GRACE_MINUTES) {
+            LimitOrderStatus::Failed
+        } else {
+            LimitOrderStatus::Pending
+        };
+    }
+    if let Some(status) = history {
+        return status;
+    }
+    if order.status == LimitOrderStatus::Cancelling {
+        return LimitOrderStatus::Cancelled;
+    }
+    if order.expired_at.is_some_and(|ts| ts <= now.timestamp()) {
+        return LimitOrderStatus::Expired;
+    }
+    LimitOrderStatus::Closed
+}
+
+pub type SharedLimitOrderTracker = Arc<RwLock<LimitOrderTracker>>;
+
+/// Locally tracked Jupiter limit orders, persisted per profile. Chain state
+/// is authoritative: [`reconcile_limit_orders`] brings this in line with
+/// which order accounts still exist.
+#[derive(Debug, Default)]
+pub struct LimitOrderTracker {
+    orders: HashMap<String, TrackedLimitOrder>,
+    path: Option<PathBuf>,
+}
+
+impl LimitOrderTracker {
+    pub fn new(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(LIMIT_ORDERS_FILE));
+        let orders: Vec<TrackedLimitOrder> = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            orders: orders
+                .into_iter()
+                .map(|order| (order.order.clone(), order))
+                .collect(),
+            path,
+        }
+    }
+
+    pub fn list(&self, maker: Option<&str>, include_closed: bool) -> Vec<TrackedLimitOrder> {
+        let mut orders: Vec<TrackedLimitOrder> = self
+            .orders
+            .values()
+            .filter(|order| maker.map_or(true, |maker| order.maker == maker))
+            .filter(|order| include_closed || !order.status.is_terminal())
+            .cloned()
+            .collect();
+        orders.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        orders
+    }
+
+    pub fn get(&self, order: &str) -> Option<&TrackedLimitOrder> {
+        self.orders.get(order)
+    }
+
+    pub fn track(&mut self, order: TrackedLimitOrder) {
+        self.orders.insert(order.order.clone(), order);
+        self.save();
+    }
+
+    pub fn set_status(&mut self, order: &str, status: LimitOrderStatus) {
+        if let Some(tracked) = self.orders.get_mut(order) {
+            tracked.status = status;
+            tracked.updated_at = Utc::now();
+        }
+        self.save();
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let orders: Vec<&TrackedLimitOrder> = self.orders.values().collect();
+        match serde_json::to_string_pretty(&orders) {
+            Ok(contents) => {
+                if let Err(err) = fs::write(path, contents) {
+                    warn!("Failed to persist limit orders: {}", err);
+                }
+            }
+            Err(err) => warn!("Failed to serialize limit orders: {}", err),
+        }
+    }
+}
+
+/// Checks every non-terminal tracked order against chain state. Orders
+/// whose account exists are open and get their remaining amounts from
+/// Jupiter; orders whose account is gone are resolved from order history.
+/// Open orders Jupiter reports for known makers but that are missing
+/// locally (placed from another device) are added.
+pub async fn reconcile_limit_orders(
+    tracker: &SharedLimitOrderTracker,
+    rpc_pool: &SharedRpcPool,
+    client: &JupiterLimitClient,
+) -> LimitOrderReconcileReport {
+    let mut report = LimitOrderReconcileReport::default();
+    let (active, makers): (Vec<TrackedLimitOrder>, HashSet<String>) = {
+        let guard = tracker.read().await;
+        let all = guard.list(None, true);
+        let makers = all.iter().map(|order| order.maker.clone()).collect();
+        (
+            all.into_iter()
+                .filter(|order| !order.status.is_terminal())
+                .collect(),
+            makers,
+        )
+    };
+    report.checked = active.len();
+
+    let mut exists: HashMap<String, bool> = HashMap::new();
+    let keys: Vec<(String, Pubkey)> = active
+        .iter()
+        .filter_map(|order| match Pubkey::from_str(&order.order) {
+            Ok(key) => Some((order.order.clone(), key)),
+            Err(_) => {
+                report
+                    .errors
+                    .push(format!("invalid order address {}", order.order));
+                None
+            }
+        })
+        .collect();
+    for chunk in keys.chunks(MAX_ACCOUNTS_PER_CALL) {
+        let pubkeys: Vec<Pubkey> = chunk.iter().map(|(_, key)| *key).collect();
+        let result = RpcPool::call(rpc_pool, RoutingHint::Read, move |client| {
+            client.get_multiple_accounts(&pubkeys)
+        })
+        .await;
+        match result {
+            Ok(accounts) => {
+                for ((order, _), account) in chunk.iter().zip(accounts) {
+                    let owned = account
+                        .map(|account| account.owner.to_string() == JUPITER_LIMIT_ORDER_PROGRAM_ID);
+                    exists.insert(order.clone(), owned.unwrap_or(false));
+                }
+            }
+            // Without chain state nothing in this chunk can be resolved.
+            Err(err) => report.errors.push(format!("account lookup failed: {err}")),
+        }
+    }
+
+    let mut open_by_maker: HashMap<String, Vec<OpenOrder>> = HashMap::new();
+    let mut history_by_maker: HashMap<String, Vec<HistoryOrder>> = HashMap::new();
+    for maker in &makers {
+        match client.open_orders(maker).await {
+            Ok(orders) => {
+                open_by_maker.insert(maker.clone(), orders);
+            }
+            Err(err) => report
+                .errors
+                .push(format!("open orders for {maker}: {err}")),
+        }
+        let needs_history = active
+            .iter()
+            .any(|order| &order.maker == maker && exists.get(&order.order) == Some(&false));
+        if needs_history {
+            match client.order_history(maker).await {
+                Ok(orders) => {
+                    history_by_maker.insert(maker.clone(), orders);
+                }
+                Err(err) => report
+                    .errors
+                    .push(format!("order history for {maker}: {err}")),
+            }
+        }
+    }
+
+    let now = Utc::now();
+    let mut guard = tracker.write().await;
+    for order in &active {
+        let Some(&on_chain) = exists.get(&order.order) else {
+            continue;
+        };
+        let Some(tracked) = guard.orders.get_mut(&order.order) else {
+            continue;
+        };
+        if on_chain {
+            if tracked.status == LimitOrderStatus::Pending {
+                tracked.status = LimitOrderStatus::Open;
+                report.opened += 1;
+            }
+            if let Some(open) = open_by_maker
+                .get(&tracked.maker)
+                .and_then(|orders| orders.iter().find(|o| o.public_key == tracked.order))
+            {
+                tracked.remaining_making_amount = parse_amount(&open.account.making_amount);
+            }
+        } else {
+            let history = history_by_maker
+                .get(&tracked.maker)
+                .and_then(|orders| orders.iter().find(|o| o.order_key == tracked.order))
+                .map(|o| history_status(&o.status));
+            let status = resolve_missing_order(tracked, history, now);
+            if status.is_terminal() {
+                report.closed += 1;
+                if status == LimitOrderStatus::Filled {
+                    tracked.remaining_making_amount = 0;
+                }
+            }
+            if status != tracked.status {
+                tracked.status = status;
+                tracked.updated_at = now;
+            }
+        }
+        tracked.last_reconciled_at = Some(now);
+    }
+
+    for open in open_by_maker.values().flatten() {
+        if guard.orders.contains_key(&open.public_key) {
+            continue;
+        }
+        let account = &open.account;
+        guard.orders.insert(
+            open.public_key.clone(),
+            TrackedLimitOrder {
+                order: open.public_key.clone(),
+                maker: account.maker.clone(),
+                input_mint: account.input_mint.clone(),
+                output_mint: account.output_mint.clone(),
+                making_amount: parse_amount(&account.ori_making_amount),
+                taking_amount: parse_amount(&account.ori_taking_amount),
+                remaining_making_amount: parse_amount(&account.making_amount),
+                expired_at: account
+                    .expired_at
+                    .as_deref()
+                    .and_then(|ts| ts.parse::<i64>().ok()),
+                status: LimitOrderStatus::Open,
+                created_at: now,
+                updated_at: now,
+                last_reconciled_at: Some(now),
+            },
+        );
+        report.discovered += 1;
+    }
+    guard.save();
+
+    report
+}
+
+/// Reconciles once shortly after startup so orders filled or cancelled
+/// while the app was closed are reflected before the user looks.
+pub fn start_limit_order_reconciliation(tracker: SharedLimitOrderTracker, rpc_pool: SharedRpcPool) {
+    tauri::async_runtime::spawn(async move {
+        if tracker.read().await.orders.is_empty() {
+            return;
+        }
+        // Let the RPC pool finish its first probe before ranking endpoints.
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        let report =
+            reconcile_limit_orders(&tracker, &rpc_pool, &JupiterLimitClient::default()).await;
+        if !report.errors.is_empty() {
+            warn!("Limit order reconciliation incomplete: {:?}", report.errors);
+        }
+    });
+}
+
+fn validate_create_input(input: &CreateLimitOrderInput) -> Result<(), String> {
+    for (label, key) in [
+        ("maker", Some(&input.maker)),
+        ("payer", input.payer.as_ref()),
+        ("input mint", Some(&input.input_mint)),
+        ("output mint", Some(&input.output_mint)),
+    ] {
+        if let Some(key) = key {
+            Pubkey::from_str(key).map_err(|_| format!("Invalid {label} address: {key}"))?;
+        }
+    }
+    if input.input_mint == input.output_mint {
+        return Err("Input and output mints must differ".to_string());
+    }
+    if input.making_amount == 0 || input.taking_amount == 0 {
+        return Err("Limit order amounts must be greater than zero".to_string());
+    }
+    if input
+        .expired_at
+        .is_some_and(|ts| ts <= Utc::now().timestamp())
+    {
+        return Err("Expiry must be in the future".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[instrument(skip(input, tracker), fields(maker = %input.maker))]
+pub async fn jupiter_create_limit_order(
+    input: CreateLimitOrderInput,
+    tracker: State<'_, SharedLimitOrderTracker>,
+) -> Result<LimitOrderTransaction, String> {
+    crate::environment::require_mainnet("Jupiter limit orders").map_err(|e| e.to_string())?;
+    validate_create_input(&input)?;
+
+    let response = JupiterLimitClient::default().create_order(&input).await?;
+    let transaction = decode_versioned_transaction(&response.tx)?;
+
+    let now = Utc::now();
+    tracker.write().await.track(TrackedLimitOrder {
+        order: response.order.clone(),
+        maker: input.maker,
+        input_mint: input.input_mint,
+        output_mint: input.output_mint,
+        making_amount: input.making_amount,
+        taking_amount: input.taking_amount,
+        remaining_making_amount: input.making_amount,
+        expired_at: input.expired_at,
+        status: LimitOrderStatus::Pending,
+        created_at: now,
+        updated_at: now,
+        last_reconciled_at: None,
+    });
+
+    Ok(LimitOrderTransaction {
+        order: response.order,
+        transaction,
+    })
+}
+
+#[tauri::command]
+#[instrument(skip(tracker))]
+pub async fn jupiter_cancel_limit_orders(
+    maker: String,
+    orders: Vec<String>,
+    tracker: State<'_, SharedLimitOrderTracker>,
+) -> Result<CancelLimitOrdersResult, String> {
+    crate::environment::require_mainnet("Jupiter limit orders").map_err(|e| e.to_string())?;
+    if orders.is_empty() {
+        return Err("No orders to cancel".to_string());
+    }
+    {
+        let guard = tracker.read().await;
+        for order in &orders {
+            if let Some(tracked) = guard.get(order) {
+                if tracked.maker != maker {
+                    return Err(format!("Order {order} belongs to a different wallet"));
+                }
+                if tracked.status.is_terminal() {
+                    return Err(format!("Order {order} is already closed"));
+                }
+            }
+        }
+    }
+
+    let response = JupiterLimitClient::default()
+        .cancel_orders(&maker, &orders)
+        .await?;
+    let transactions = response
+        .txs
+        .iter()
+        .map(|tx| decode_versioned_transaction(tx.as_str()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut guard = tracker.write().await;
+    for order in &orders {
+        guard.set_status(order, LimitOrderStatus::Cancelling);
+    }
+
+    Ok(CancelLimitOrdersResult {
+        orders,
+        transactions,
+    })
+}
+
+#[tauri::command]
+pub async fn jupiter_list_limit_orders(
+    maker: Option<String>,
+    include_closed: Option<bool>,
+    tracker: State<'_, SharedLimitOrderTracker>,
+) -> Result<Vec<TrackedLimitOrder>, String> {
+    Ok(tracker
+        .read()
+        .await
+        .list(maker.as_deref(), include_closed.unwrap_or(false)))
+}
+
+#[tauri::command]
+pub async fn jupiter_reconcile_limit_orders(
+    tracker: State<'_, SharedLimitOrderTracker>,
+    rpc_pool: State<'_, SharedRpcPool>,
+) -> Result<LimitOrderReconcileReport, String> {
+    Ok(reconcile_limit_orders(&tracker, &rpc_pool, &JupiterLimitClient::default()).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+
+    fn tracked(status: LimitOrderStatus, age_minutes: i64) -> TrackedLimitOrder {
+        let created_at = Utc::now() - ChronoDuration::minutes(age_minutes);
+        TrackedLimitOrder {
+            order: Pubkey::new_unique().to_string(),
+            maker: Pubkey::new_unique().to_string(),
+            input_mint: "So11111111111111111111111111111111111111112".into(),
+            output_mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".into(),
+            making_amount: 1_000_000,
+            taking_amount: 200_000,
+            remaining_making_amount: 1_000_000,
+            expired_at: None,
+            status,
+            created_at,
+            updated_at: created_at,
+            last_reconciled_at: None,
+        }
+    }
+
+    #[test]
+    fn missing_orders_resolve_from_history_then_local_state() {
+        let now = Utc::now();
+
+        let fresh = tracked(LimitOrderStatus::Pending, 1);
+        assert_eq!(
+            resolve_missing_order(&fresh, None, now),
+            LimitOrderStatus::Pending
+        );
+        let stale = tracked(LimitOrderStatus::Pending, 60);
+        assert_eq!(
+            resolve_missing_order(&stale, None, now),
+            LimitOrderStatus::Failed
+        );
+
+        let open = tracked(LimitOrderStatus::Open, 60);
+        assert_eq!(
+            resolve_missing_order(&open, Some(history_status("Completed")), now),
+            LimitOrderStatus::Filled
+        );
+        assert_eq!(
+            resolve_missing_order(&open, None, now),
+            LimitOrderStatus::Closed
+        );
+
+        let cancelling = tracked(LimitOrderStatus::Cancelling, 60);
+        assert_eq!(
+            resolve_missing_order(&cancelling, None, now),
+            LimitOrderStatus::Cancelled
+        );
+
+        let mut expiring = tracked(LimitOrderStatus::Open, 60);
+        expiring.expired_at = Some(now.timestamp() - 5);
+        assert_eq!(
+            resolve_missing_order(&expiring, None, now),
+            LimitOrderStatus::Expired
+        );
+    }
+
+    #[tokio::test]
+    async fn create_order_sends_amounts_as_strings() {
+        let server = MockServer::start();
+        let maker = Pubkey::new_unique().to_string();
+        let order = Pubkey::new_unique().to_string();
+
+        let mock = server.mock(|when, then| {
+            when.method(POST).path("/createOrder").json_body_partial(
+                serde_json::json!({
+                    "maker": maker,
+                    "payer": maker,
+                    "params": { "makingAmount": "1000000", "takingAmount": "200000" },
+                    "computeUnitPrice": "auto"
+                })
+                .to_string(),
+            );
+            then.status(200)
+                .json_body(serde_json::json!({ "order": order, "tx": "AA==" }));
+        });
+
+        let client = JupiterLimitClient::with_base_url(server.base_url());
+        let response = client
+            .create_order(&CreateLimitOrderInput {
+                maker: maker.clone(),
+                payer: None,
+                input_mint: "So11111111111111111111111111111111111111112".into(),
+                output_mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".into(),
+                making_amount: 1_000_000,
+                taking_amount: 200_000,
+                expired_at: None,
+                compute_unit_price_micro_lamports: None,
+            })
+            .await
+            .expect("create order should succeed");
+
+        mock.assert();
+        assert_eq!(response.order, order);
+    }
+}
@@ -1,9 +1,15 @@
+pub mod cancellation;
 pub mod health_commands;
 pub mod health_monitor;
 pub mod jupiter;
+pub mod jupiter_limit;
+pub mod rpc_pool;
 pub mod trading_execution;
 
+pub use cancellation::*;
 pub use health_commands::*;
 pub use health_monitor::*;
 pub use jupiter::*;
+pub use jupiter_limit::*;
+pub use rpc_pool::*;
 pub use trading_execution::*;
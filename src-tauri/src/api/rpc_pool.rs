@@ -0,0 +1,509 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::RwLock;
+
+use super::health_monitor::{HealthCheckRecord, HealthStatus, SharedApiHealthMonitor};
+use crate::chains::{
+    rank_endpoints, validate_endpoint, ChainConfig, ChainId, EndpointHealth, EndpointRole,
+    EndpointSource, RoutingHint, RpcEndpoint, RpcPool, RpcPoolSettings, SharedRpcPool,
+};
+use crate::profiles::ProfilePaths;
+
+const RPC_POOLS_FILE: &str = "rpc_pools.json";
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Health-monitor service name for one pooled endpoint.
+fn service_name(chain: &ChainId, endpoint_id: &str) -> String {
+    format!("rpc:{}:{}", chain.as_str(), endpoint_id)
+}
+
+#[derive(Debug, Default)]
+struct ChainEndpoints {
+    endpoints: Vec<RpcEndpoint>,
+    health: HashMap<String, EndpointHealth>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedChain {
+    chain: ChainId,
+    endpoints: Vec<RpcEndpoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PooledEndpointStatus {
+    pub endpoint: RpcEndpoint,
+    pub health: EndpointHealth,
+    /// Share of successful health checks over the last 24 hours, from the
+    /// API health monitor.
+    pub uptime_percent: Option<f64>,
+    pub monitor_status: Option<HealthStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainRpcStatus {
+    pub chain: ChainId,
+    /// URL reads would currently be routed to.
+    pub active_url: Option<String>,
+    pub endpoints: Vec<PooledEndpointStatus>,
+}
+
+pub type SharedRpcPoolManager = Arc<RwLock<RpcPoolManager>>;
+
+/// Endpoint pools for the EVM chains, ranked and failed over the same way
+/// as the Solana `RpcPool`. Solana keeps its own pool because its calls go
+/// through `RpcClient` and sends are checked against the cluster; the
+/// commands here present both together.
+#[derive(Debug)]
+pub struct RpcPoolManager {
+    chains: HashMap<ChainId, ChainEndpoints>,
+    settings: RpcPoolSettings,
+    path: Option<PathBuf>,
+}
+
+impl RpcPoolManager {
+    pub fn new(app: &AppHandle, configs: &[ChainConfig]) -> Self {
+        let path = app
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(RPC_POOLS_FILE));
+        let persisted: Vec<PersistedChain> = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        let mut manager = Self::from_configs(configs);
+        for chain in persisted {
+            if chain.chain != ChainId::Solana && !chain.endpoints.is_empty() {
+                manager.set_endpoints(chain.chain, chain.endpoints);
+            }
+        }
+        manager.path = path;
+        manager
+    }
+
+    /// One default endpoint per EVM chain, from its chain config.
+    fn from_configs(configs: &[ChainConfig]) -> Self {
+        let mut manager = Self {
+            chains: HashMap::new(),
+            settings: RpcPoolSettings::default(),
+            path: None,
+        };
+        for config in configs.iter().filter(|c| c.chain_id != ChainId::Solana) {
+            let endpoint = RpcEndpoint {
+                id: format!("{}-default", config.chain_id.as_str()),
+                label: format!("{} (public)", config.chain_id.as_str()),
+                url: config.rpc_url.clone(),
+                weight: 1,
+                role: EndpointRole::Any,
+                enabled: true,
+                source: EndpointSource::Default,
+            };
+            manager.set_endpoints(config.chain_id.clone(), vec![endpoint]);
+        }
+        manager
+    }
+
+    fn set_endpoints(&mut self, chain: ChainId, endpoints: Vec<RpcEndpoint>) {
+        let pool = self.chains.entry(chain).or_default();
+        pool.health
+            .retain(|id, _| endpoints.iter().any(|endpoint| &endpoint.id == id));
+        for endpoint in &endpoints {
+            pool.health.entry(endpoint.id.clone()).or_default();
+        }
+        pool.endpoints = endpoints;
+    }
+
+    pub fn update_endpoints(
+        &mut self,
+        chain: ChainId,
+        mut endpoints: Vec<RpcEndpoint>,
+    ) -> Result<(), String> {
+        if chain == ChainId::Solana {
+            return Err("Solana endpoints are managed by the Solana RPC pool".to_string());
+        }
+        for endpoint in endpoints.iter_mut() {
+            validate_endpoint(endpoint)?;
+        }
+        if !endpoints.iter().any(|endpoint| endpoint.enabled) {
+            return Err(format!(
+                "At least one {} RPC endpoint must stay enabled",
+                chain.as_str()
+            ));
+        }
+        self.set_endpoints(chain, endpoints);
+        self.save()
+    }
+
+    /// `(id, url)` pairs in the order a call on `chain` should try them.
+    pub fn ranked(&self, chain: &ChainId, roll: f64) -> Vec<(String, String)> {
+        let Some(pool) = self.chains.get(chain) else {
+            return Vec::new();
+        };
+        rank_endpoints(&pool.endpoints, &pool.health, RoutingHint::Read, roll)
+            .into_iter()
+            .filter_map(|id| {
+                let endpoint = pool.endpoints.iter().find(|e| e.id == id)?;
+                Some((id, endpoint.url.clone()))
+            })
+            .collect()
+    }
+
+    pub fn select(&self, chain: &ChainId) -> Option<String> {
+        self.ranked(chain, rand::random::<f64>())
+            .into_iter()
+            .next()
+            .map(|(_, url)| url)
+    }
+
+    pub fn record_success(&mut self, chain: &ChainId, id: &str, latency_ms: f64) {
+        if let Some(pool) = self.chains.get_mut(chain) {
+            pool.health
+                .entry(id.to_string())
+                .or_default()
+                .record_success(latency_ms);
+        }
+    }
+
+    pub fn record_failure(&mut self, chain: &ChainId, id: &str, error: String) {
+        let threshold = self.settings.failover_threshold;
+        if let Some(pool) = self.chains.get_mut(chain) {
+            pool.health
+                .entry(id.to_string())
+                .or_default()
+                .record_failure(error, threshold);
+        }
+    }
+
+    /// Follows the Solana pool's settings so both pools fail over alike.
+    pub fn sync_settings(&mut self, settings: &RpcPoolSettings) {
+        self.settings = settings.clone();
+    }
+
+    fn endpoints_with_health(&self, chain: &ChainId) -> Vec<(RpcEndpoint, EndpointHealth)> {
+        self.chains
+            .get(chain)
+            .map(|pool| {
+                pool.endpoints
+                    .iter()
+                    .map(|endpoint| {
+                        let health = pool.health.get(&endpoint.id).cloned().unwrap_or_default();
+                        (endpoint.clone(), health)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn chain_ids(&self) -> Vec<ChainId> {
+        let mut chains: Vec<ChainId> = self.chains.keys().cloned().collect();
+        chains.sort_by_key(|chain| chain.as_str());
+        chains
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create RPC pool directory: {e}"))?;
+        }
+        let persisted: Vec<PersistedChain> = self
+            .chain_ids()
+            .into_iter()
+            .map(|chain| PersistedChain {
+                endpoints: self.chains[&chain].endpoints.clone(),
+                chain,
+            })
+            .collect();
+        let contents = serde_json::to_string_pretty(&persisted)
+            .map_err(|e| format!("Failed to serialize RPC pools: {e}"))?;
+        fs::write(path, contents).map_err(|e| format!("Failed to persist RPC pools: {e}"))
+    }
+}
+
+async fn probe_evm_endpoint(client: &reqwest::Client, url: &str) -> Result<f64, String> {
+    let started = Instant::now();
+    let response = client
+        .post(url)
+        .timeout(PROBE_TIMEOUT)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber", "params": [] }))
+        .send()
+        .await
+        .map_err(|e| format!("Probe failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("Probe returned {}", response.status()));
+    }
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid probe response: {e}"))?;
+    if let Some(error) = body.get("error") {
+        return Err(format!("Node unhealthy: {error}"));
+    }
+    Ok(started.elapsed().as_secs_f64() * 1000.0)
+}
+
+async fn record_check(
+    monitor: &SharedApiHealthMonitor,
+    chain: &ChainId,
+    endpoint_id: &str,
+    result: &Result<f64, String>,
+) {
+    let record = HealthCheckRecord {
+        id: uuid::Uuid::new_v4().to_string(),
+        service_name: service_name(chain, endpoint_id),
+        timestamp: Utc::now(),
+        success: result.is_ok(),
+        latency_ms: result.as_ref().map_or(0, |ms| ms.round() as i64),
+        status_code: None,
+        error: result.as_ref().err().cloned(),
+    };
+    if let Err(e) = monitor.read().await.record_check(record).await {
+        eprintln!("Failed to record RPC health check: {}", e);
+    }
+}
+
+/// Health-checks every enabled endpoint of every chain and records the
+/// results in both the pools and the API health monitor.
+pub async fn probe_rpc_pools(
+    solana: &SharedRpcPool,
+    pools: &SharedRpcPoolManager,
+    monitor: &SharedApiHealthMonitor,
+) {
+    RpcPool::probe(solana).await;
+    let solana_results: Vec<(String, Result<f64, String>)> = solana
+        .read()
+        .await
+        .snapshot()
+        .endpoints
+        .into_iter()
+        .filter(|status| status.endpoint.enabled)
+        .map(|status| {
+            let result = match status.health.last_error {
+                Some(error) => Err(error),
+                None => Ok(status.health.latency_ms.unwrap_or_default()),
+            };
+            (status.endpoint.id, result)
+        })
+        .collect();
+    for (id, result) in &solana_results {
+        record_check(monitor, &ChainId::Solana, id, result).await;
+    }
+
+    let targets: Vec<(ChainId, RpcEndpoint)> = {
+        let mut guard = pools.write().await;
+        guard.sync_settings(solana.read().await.settings());
+        guard
+            .chain_ids()
+            .into_iter()
+            .flat_map(|chain| {
+                guard
+                    .endpoints_with_health(&chain)
+                    .into_iter()
+                    .filter(|(endpoint, _)| endpoint.enabled)
+                    .map(move |(endpoint, _)| (chain.clone(), endpoint))
+            })
+            .collect()
+    };
+    let client = reqwest::Client::new();
+    let results = futures_util::future::join_all(
+        targets
+            .iter()
+            .map(|(_, endpoint)| probe_evm_endpoint(&client, &endpoint.url)),
+    )
+    .await;
+
+    {
+        let mut guard = pools.write().await;
+        let now = Utc::now();
+        for ((chain, endpoint), result) in targets.iter().zip(&results) {
+            match result {
+                Ok(latency_ms) => guard.record_success(chain, &endpoint.id, *latency_ms),
+                Err(error) => guard.record_failure(chain, &endpoint.id, error.clone()),
+            }
+            if let Some(health) = guard
+                .chains
+                .get_mut(chain)
+                .and_then(|pool| pool.health.get_mut(&endpoint.id))
+            {
+                health.last_checked = Some(now);
+            }
+        }
+    }
+    for ((chain, endpoint), result) in targets.iter().zip(&results) {
+        record_check(monitor, chain, &endpoint.id, result).await;
+    }
+}
+
+async fn with_monitor_metrics(
+    monitor: &SharedApiHealthMonitor,
+    chain: &ChainId,
+    endpoints: Vec<(RpcEndpoint, EndpointHealth)>,
+) -> Vec<PooledEndpointStatus> {
+    let monitor = monitor.read().await;
+    let mut statuses = Vec::with_capacity(endpoints.len());
+    for (endpoint, health) in endpoints {
+        let metrics = monitor
+            .get_metrics(&service_name(chain, &endpoint.id))
+            .await
+            .ok()
+            .filter(|metrics| metrics.total_requests > 0);
+        statuses.push(PooledEndpointStatus {
+            uptime_percent: metrics.as_ref().map(|m| m.uptime_percent),
+            monitor_status: metrics.map(|m| m.health_status),
+            endpoint,
+            health,
+        });
+    }
+    statuses
+}
+
+#[tauri::command]
+pub async fn rpc_pool_get_status(
+    chain: Option<ChainId>,
+    solana_pool: State<'_, SharedRpcPool>,
+    pools: State<'_, SharedRpcPoolManager>,
+    monitor: State<'_, SharedApiHealthMonitor>,
+) -> Result<Vec<ChainRpcStatus>, String> {
+    let mut statuses = Vec::new();
+
+    if chain.as_ref().map_or(true, |c| *c == ChainId::Solana) {
+        let (active_url, endpoints) = {
+            let pool = solana_pool.read().await;
+            let endpoints = pool
+                .snapshot()
+                .endpoints
+                .into_iter()
+                .map(|status| (status.endpoint, status.health))
+                .collect();
+            (pool.select(RoutingHint::Read), endpoints)
+        };
+        statuses.push(ChainRpcStatus {
+            chain: ChainId::Solana,
+            active_url,
+            endpoints: with_monitor_metrics(&monitor, &ChainId::Solana, endpoints).await,
+        });
+    }
+
+    let evm: Vec<(ChainId, Option<String>, Vec<(RpcEndpoint, EndpointHealth)>)> = {
+        let guard = pools.read().await;
+        guard
+            .chain_ids()
+            .into_iter()
+            .filter(|id| chain.as_ref().map_or(true, |c| c == id))
+            .map(|id| {
+                let endpoints = guard.endpoints_with_health(&id);
+                (id.clone(), guard.select(&id), endpoints)
+            })
+            .collect()
+    };
+    for (id, active_url, endpoints) in evm {
+        statuses.push(ChainRpcStatus {
+            endpoints: with_monitor_metrics(&monitor, &id, endpoints).await,
+            chain: id,
+            active_url,
+        });
+    }
+
+    Ok(statuses)
+}
+
+/// Replaces the endpoint list of one chain.
+#[tauri::command]
+pub async fn rpc_pool_update_endpoints(
+    chain: ChainId,
+    endpoints: Vec<RpcEndpoint>,
+    solana_pool: State<'_, SharedRpcPool>,
+    pools: State<'_, SharedRpcPoolManager>,
+) -> Result<(), String> {
+    match chain {
+        ChainId::Solana => solana_pool.write().await.replace_endpoints(endpoints),
+        other => pools.write().await.update_endpoints(other, endpoints),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(chain_id: ChainId, rpc_url: &str) -> ChainConfig {
+        ChainConfig {
+            chain_id,
+            rpc_url: rpc_url.to_string(),
+            explorer_url: String::new(),
+            native_token: String::new(),
+            enabled: true,
+        }
+    }
+
+    fn endpoint(id: &str, weight: u32) -> RpcEndpoint {
+        RpcEndpoint {
+            id: id.to_string(),
+            label: id.to_string(),
+            url: format!("https://{id}.example.com"),
+            weight,
+            role: EndpointRole::Any,
+            enabled: true,
+            source: EndpointSource::Custom,
+        }
+    }
+
+    #[test]
+    fn evm_chains_fail_over_to_the_next_endpoint() {
+        let mut manager = RpcPoolManager::from_configs(&[
+            config(ChainId::Solana, "https://api.mainnet-beta.solana.com"),
+            config(ChainId::Base, "https://mainnet.base.org"),
+        ]);
+        assert!(manager.chains.get(&ChainId::Solana).is_none());
+        assert_eq!(
+            manager.select(&ChainId::Base).as_deref(),
+            Some("https://mainnet.base.org")
+        );
+
+        manager
+            .update_endpoints(
+                ChainId::Base,
+                vec![endpoint("primary", 10), endpoint("backup", 1)],
+            )
+            .unwrap();
+        let ranked: Vec<String> = manager
+            .ranked(&ChainId::Base, 0.0)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(ranked, vec!["primary", "backup"]);
+
+        for _ in 0..RpcPoolSettings::default().failover_threshold {
+            manager.record_failure(&ChainId::Base, "primary", "timeout".to_string());
+        }
+        assert_eq!(manager.ranked(&ChainId::Base, 0.0)[0].0, "backup");
+    }
+
+    #[test]
+    fn updates_reject_solana_and_all_disabled_lists() {
+        let mut manager =
+            RpcPoolManager::from_configs(&[config(ChainId::Polygon, "https://polygon-rpc.com")]);
+        assert!(manager
+            .update_endpoints(ChainId::Solana, vec![endpoint("a", 1)])
+            .is_err());
+
+        let mut disabled = endpoint("a", 1);
+        disabled.enabled = false;
+        assert!(manager
+            .update_endpoints(ChainId::Polygon, vec![disabled])
+            .is_err());
+    }
+}
@@ -1,7 +1,23 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::time::{SystemTime, UNIX_EPOCH};
+use serde_json::{json, Value};
+use solana_sdk::transaction::VersionedTransaction;
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, State};
 use tracing::{debug, instrument};
 
+use crate::wallet::tx_lifecycle::{SharedTransactionLifecycle, SubmitTransactionRequest};
+
+const DEFAULT_JITO_BLOCK_ENGINE_URL: &str = "https://mainnet.block-engine.jito.wtf";
+const JITO_BLOCK_ENGINE_ENV: &str = "JITO_BLOCK_ENGINE_URL";
+const PRIVATE_RPC_ENV: &str = "MEV_PRIVATE_RPC_URL";
+const RELAY_TIMEOUT: Duration = Duration::from_secs(15);
+// Rough per-swap sandwich losses avoided; these are estimates, not measured.
+const JITO_ESTIMATED_SAVINGS_SOL: f64 = 0.001;
+const PRIVATE_RPC_ESTIMATED_SAVINGS_SOL: f64 = 0.0005;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MEVProtectionConfig {
@@ -43,6 +59,115 @@ pub struct MEVProtectionResult {
     pub method: Option<String>, // "jito", "private_rpc"
     pub bundle_id: Option<String>,
     pub estimated_savings: f64, // in SOL
+    /// Signature of the first broadcast when submitted through the public
+    /// mempool via the transaction lifecycle service.
+    #[serde(default)]
+    pub signature: Option<String>,
+    #[serde(default)]
+    pub lifecycle_id: Option<String>,
+}
+
+/// Endpoints for MEV-protected submission. Jito bundles go to the block
+/// engine; private RPC submissions need an explicitly configured endpoint.
+#[derive(Debug, Clone)]
+pub struct MevRelay {
+    http: Client,
+    jito_url: String,
+    private_rpc_url: Option<String>,
+}
+
+impl MevRelay {
+    pub fn from_env() -> Self {
+        Self::with_endpoints(
+            std::env::var(JITO_BLOCK_ENGINE_ENV)
+                .unwrap_or_else(|_| DEFAULT_JITO_BLOCK_ENGINE_URL.to_string()),
+            std::env::var(PRIVATE_RPC_ENV).ok(),
+        )
+    }
+
+    pub fn with_endpoints(jito_url: impl Into<String>, private_rpc_url: Option<String>) -> Self {
+        let http = Client::builder()
+            .timeout(RELAY_TIMEOUT)
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self {
+            http,
+            jito_url: jito_url.into().trim_end_matches('/').to_string(),
+            private_rpc_url: private_rpc_url
+                .map(|url| url.trim().to_string())
+                .filter(|url| !url.is_empty()),
+        }
+    }
+
+    /// Sends the transaction as a single-transaction Jito bundle. The
+    /// transaction must already carry its tip instruction.
+    async fn send_bundle(&self, transaction_base64: &str) -> Result<String, String> {
+        let url = format!("{}/api/v1/bundles", self.jito_url);
+        let result = self
+            .rpc(
+                &url,
+                "sendBundle",
+                json!([[transaction_base64], { "encoding": "base64" }]),
+            )
+            .await
+            .map_err(|e| format!("Jito bundle submission failed: {}", e))?;
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "Jito returned no bundle id".to_string())
+    }
+
+    async fn send_private(&self, transaction_base64: &str) -> Result<String, String> {
+        let url = self.private_rpc_url.as_deref().ok_or_else(|| {
+            format!(
+                "Private RPC protection is enabled but {} is not set",
+                PRIVATE_RPC_ENV
+            )
+        })?;
+        let result = self
+            .rpc(
+                url,
+                "sendTransaction",
+                json!([transaction_base64, { "encoding": "base64" }]),
+            )
+            .await
+            .map_err(|e| format!("Private RPC submission failed: {}", e))?;
+        result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "Private RPC returned no signature".to_string())
+    }
+
+    async fn rpc(&self, url: &str, method: &str, params: Value) -> Result<Value, String> {
+        let response = self
+            .http
+            .post(url)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status();
+        let body: Value = response.json().await.map_err(|e| e.to_string())?;
+        if let Some(error) = body.get("error") {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| error.to_string());
+            return Err(message);
+        }
+        if !status.is_success() {
+            return Err(format!("HTTP {}", status));
+        }
+        body.get("result")
+            .cloned()
+            .ok_or_else(|| "response has no result".to_string())
+    }
 }
 
 /// Get current network congestion and priority fee recommendations
@@ -125,66 +250,106 @@ pub async fn get_priority_fee_estimates() -> Result<Vec<PriorityFeeEstimate>, St
 
 /// Submit transaction with MEV protection
 #[tauri::command]
-#[instrument(skip(transaction_base64))]
+#[instrument(skip(transaction_base64, app, lifecycle))]
 pub async fn submit_with_mev_protection(
+    app: AppHandle,
     transaction_base64: String,
     config: MEVProtectionConfig,
+    last_valid_block_height: Option<u64>,
+    lifecycle: State<'_, SharedTransactionLifecycle>,
 ) -> Result<MEVProtectionResult, String> {
+    let lifecycle = lifecycle.inner().clone();
+    route_submission(
+        &MevRelay::from_env(),
+        transaction_base64,
+        config,
+        |transaction_base64| async move {
+            // Public mempool: retries, fee escalation and confirmation
+            // tracking are handled by the lifecycle service.
+            let tracked = lifecycle
+                .submit(
+                    app,
+                    SubmitTransactionRequest {
+                        transaction_base64,
+                        last_valid_block_height,
+                        max_attempts: None,
+                        fee_schedule: None,
+                        label: Some("Swap".to_string()),
+                    },
+                )
+                .await?;
+            Ok((tracked.signature, tracked.id))
+        },
+    )
+    .await
+}
+
+/// Sends through Jito or the private RPC when protection is enabled, and
+/// through `submit_public` otherwise. A failed protected submission is
+/// returned as an error rather than silently falling back to the public
+/// mempool, which would expose the trade the user asked to shield.
+async fn route_submission<F, Fut>(
+    relay: &MevRelay,
+    transaction_base64: String,
+    config: MEVProtectionConfig,
+    submit_public: F,
+) -> Result<MEVProtectionResult, String>
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<(String, String), String>>,
+{
     debug!(
         "Submitting transaction with MEV protection: jito={}, private_rpc={}",
         config.use_jito, config.use_private_rpc
     );
 
-    if !config.enabled {
+    if !config.enabled || !(config.use_jito || config.use_private_rpc) {
+        let (signature, lifecycle_id) = submit_public(transaction_base64).await?;
         return Ok(MEVProtectionResult {
             protected: false,
             method: None,
             bundle_id: None,
             estimated_savings: 0.0,
+            signature: Some(signature),
+            lifecycle_id: Some(lifecycle_id),
         });
     }
 
-    // In a real implementation, this would:
-    // 1. If use_jito: Submit to Jito block engine as bundle
-    // 2. If use_private_rpc: Submit via private mempool RPC
-    // 3. Track and estimate MEV savings
-
-    let method = if config.use_jito {
-        Some("jito".to_string())
-    } else if config.use_private_rpc {
-        Some("private_rpc".to_string())
+    let result = if config.use_jito {
+        let bundle_id = relay.send_bundle(&transaction_base64).await?;
+        MEVProtectionResult {
+            protected: true,
+            method: Some("jito".to_string()),
+            bundle_id: Some(bundle_id),
+            estimated_savings: JITO_ESTIMATED_SAVINGS_SOL,
+            signature: transaction_signature(&transaction_base64),
+            lifecycle_id: None,
+        }
     } else {
-        None
-    };
-
-    // Simulate MEV protection result
-    let bundle_id = if config.use_jito {
-        Some(format!("jito_bundle_{}", uuid::Uuid::new_v4()))
-    } else {
-        None
-    };
-
-    // Estimate savings (in a real implementation, this would be calculated based on
-    // the difference between MEV-protected execution and public mempool execution)
-    let estimated_savings = if config.use_jito {
-        0.001 + (rand::random::<f64>() * 0.01) // 0.001-0.011 SOL savings
-    } else if config.use_private_rpc {
-        0.0005 + (rand::random::<f64>() * 0.005) // 0.0005-0.0055 SOL savings
-    } else {
-        0.0
+        let signature = relay.send_private(&transaction_base64).await?;
+        MEVProtectionResult {
+            protected: true,
+            method: Some("private_rpc".to_string()),
+            bundle_id: None,
+            estimated_savings: PRIVATE_RPC_ESTIMATED_SAVINGS_SOL,
+            signature: Some(signature),
+            lifecycle_id: None,
+        }
     };
 
     debug!(
-        "MEV protection result: method={:?}, bundle_id={:?}, savings={}",
-        method, bundle_id, estimated_savings
+        "MEV protection result: method={:?}, bundle_id={:?}, signature={:?}",
+        result.method, result.bundle_id, result.signature
     );
+    Ok(result)
+}
 
-    Ok(MEVProtectionResult {
-        protected: true,
-        method,
-        bundle_id,
-        estimated_savings,
-    })
+/// A bundle id is not a transaction signature; read the signature from the
+/// signed transaction so the caller can track confirmation.
+fn transaction_signature(transaction_base64: &str) -> Option<String> {
+    let bytes = STANDARD.decode(transaction_base64).ok()?;
+    let transaction: VersionedTransaction = bincode::deserialize(&bytes).ok()?;
+    transaction.signatures.first().map(|s| s.to_string())
 }
 
 /// Validate if a trade should be blocked based on slippage/impact thresholds
@@ -212,6 +377,7 @@ pub async fn validate_trade_thresholds(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use httpmock::prelude::*;
 
     #[tokio::test]
     async fn test_get_network_congestion() {
@@ -254,6 +420,21 @@ mod tests {
         assert_eq!(result.unwrap(), true);
     }
 
+    async fn submit_with_mev_protection(
+        relay: &MevRelay,
+        transaction_base64: String,
+        config: MEVProtectionConfig,
+    ) -> Result<MEVProtectionResult, String> {
+        route_submission(relay, transaction_base64, config, |_| async {
+            Ok(("public_signature".to_string(), "lifecycle_id".to_string()))
+        })
+        .await
+    }
+
+    fn jito_relay(server: &MockServer) -> MevRelay {
+        MevRelay::with_endpoints(server.base_url(), None)
+    }
+
     #[tokio::test]
     async fn test_mev_protection_disabled() {
         let config = MEVProtectionConfig {
@@ -261,8 +442,10 @@ mod tests {
             use_jito: false,
             use_private_rpc: false,
         };
+        let server = MockServer::start();
+        let relay = jito_relay(&server);
 
-        let result = submit_with_mev_protection("test_tx".to_string(), config).await;
+        let result = submit_with_mev_protection(&relay, "test_tx".to_string(), config).await;
         assert!(result.is_ok());
 
         let protection = result.unwrap();
@@ -277,8 +460,17 @@ mod tests {
             use_jito: true,
             use_private_rpc: false,
         };
+        let server = MockServer::start();
+        let bundle = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/bundles")
+                .json_body_partial(r#"{"method": "sendBundle", "params": [["test_tx"]]}"#);
+            then.status(200)
+                .json_body(json!({ "jsonrpc": "2.0", "id": 1, "result": "bundle-123" }));
+        });
+        let relay = jito_relay(&server);
 
-        let result = submit_with_mev_protection("test_tx".to_string(), config).await;
+        let result = submit_with_mev_protection(&relay, "test_tx".to_string(), config).await;
         assert!(result.is_ok());
 
         let protection = result.unwrap();
@@ -286,5 +478,64 @@ mod tests {
         assert_eq!(protection.method, Some("jito".to_string()));
         assert!(protection.bundle_id.is_some());
         assert!(protection.estimated_savings > 0.0);
+        bundle.assert();
+    }
+
+    #[tokio::test]
+    async fn rejected_bundles_fail_instead_of_falling_back() {
+        let config = MEVProtectionConfig {
+            enabled: true,
+            use_jito: true,
+            use_private_rpc: false,
+        };
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/api/v1/bundles");
+            then.status(400).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": { "code": -32602, "message": "bundle must tip" }
+            }));
+        });
+        let relay = jito_relay(&server);
+
+        let result = route_submission(&relay, "test_tx".to_string(), config, |_| async {
+            Err("protected trades must not reach the public mempool".to_string())
+        })
+        .await;
+        assert!(result.unwrap_err().contains("bundle must tip"));
+    }
+
+    #[tokio::test]
+    async fn private_rpc_returns_the_broadcast_signature() {
+        let config = MEVProtectionConfig {
+            enabled: true,
+            use_jito: false,
+            use_private_rpc: true,
+        };
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .json_body_partial(r#"{"method": "sendTransaction"}"#);
+            then.status(200)
+                .json_body(json!({ "jsonrpc": "2.0", "id": 1, "result": "sig-456" }));
+        });
+        let relay = MevRelay::with_endpoints("http://unused", Some(server.url("/")));
+
+        let protection = submit_with_mev_protection(&relay, "test_tx".to_string(), config)
+            .await
+            .unwrap();
+        assert_eq!(protection.method, Some("private_rpc".to_string()));
+        assert_eq!(protection.signature, Some("sig-456".to_string()));
+
+        let unconfigured = MevRelay::with_endpoints("http://unused", None);
+        let config = MEVProtectionConfig {
+            enabled: true,
+            use_jito: false,
+            use_private_rpc: true,
+        };
+        let result = submit_with_mev_protection(&unconfigured, "test_tx".to_string(), config).await;
+        assert!(result.unwrap_err().contains(PRIVATE_RPC_ENV));
     }
 }
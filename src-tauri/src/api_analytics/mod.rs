@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -408,7 +409,7 @@ pub async fn get_fair_use_status(
 pub fn initialize_usage_tracker(app: &AppHandle) -> Result<Arc<Mutex<ApiUsageTracker>>, String> {
     let mut data_path = app
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|err| format!("Unable to resolve app data directory: {err}"))?;
 
     data_path.push("api_usage.json");
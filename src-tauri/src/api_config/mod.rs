@@ -4,8 +4,15 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use tauri::{Manager, State};
 
+use crate::auth::command_policy::{CommandPolicyManager, GuardedCommand, VerificationOutcome};
+use crate::auth::passkey::{PasskeyManager, SensitiveCategory};
+use crate::auth::two_factor::TwoFactorManager;
+use crate::chains::SharedRpcPool;
 use crate::security::keystore::{Keystore, KeystoreError};
 
+mod scopes;
+pub use scopes::*;
+
 const KEY_HELIUS_API: &str = "api_key_helius";
 const KEY_BIRDEYE_API: &str = "api_key_birdeye";
 const KEY_JUPITER_API: &str = "api_key_jupiter";
@@ -55,6 +62,8 @@ pub struct ApiKeyMetadata {
     pub rotation_due_at: Option<DateTime<Utc>>,
     #[serde(default)]
     pub reminder_sent_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub scope_report: Option<KeyScopeReport>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -105,6 +114,7 @@ pub struct ServiceStatus {
     pub days_until_rotation_due: Option<i64>,
     pub rotation_overdue: bool,
     pub rotation_history: Vec<RotationRecord>,
+    pub scope_report: Option<KeyScopeReport>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -147,6 +157,7 @@ fn default_metadata(service: &str, use_default: bool) -> ApiKeyMetadata {
         rotation_history: Vec::new(),
         rotation_due_at: Some(now + Duration::days(ROTATION_INTERVAL_DAYS)),
         reminder_sent_at: None,
+        scope_report: None,
     }
 }
 
@@ -203,6 +214,23 @@ impl ApiConfigManager {
     }
 }
 
+/// The user's own Helius key, if one has been saved. The developer
+/// fallback key is not returned.
+pub fn stored_helius_key(keystore: &Keystore) -> Option<String> {
+    let secret = keystore.retrieve_secret(KEY_HELIUS_API).ok()?;
+    String::from_utf8(secret.to_vec())
+        .ok()
+        .filter(|key| !key.is_empty() && key != DEFAULT_HELIUS_KEY)
+}
+
+/// The user's own Birdeye key, if one has been saved.
+pub fn stored_birdeye_key(keystore: &Keystore) -> Option<String> {
+    let secret = keystore.retrieve_secret(KEY_BIRDEYE_API).ok()?;
+    String::from_utf8(secret.to_vec())
+        .ok()
+        .filter(|key| !key.is_empty() && key != DEFAULT_BIRDEYE_KEY)
+}
+
 impl Default for ApiConfigManager {
     fn default() -> Self {
         Self::new()
@@ -216,6 +244,7 @@ pub async fn save_api_key(
     expiry_date: Option<DateTime<Utc>>,
     keystore: State<'_, Keystore>,
     config_manager: State<'_, ApiConfigManager>,
+    rpc_pool: State<'_, SharedRpcPool>,
 ) -> Result<String, String> {
     let key_id = match service.as_str() {
         "helius" => KEY_HELIUS_API,
@@ -263,6 +292,10 @@ pub async fn save_api_key(
         .update_metadata(&service, metadata, &keystore)
         .map_err(|e| format!("Failed to update metadata: {}", e))?;
 
+    if service == "helius" {
+        rpc_pool.write().await.sync_helius(Some(&api_key));
+    }
+
     Ok(format!("API key for {} saved successfully", service))
 }
 
@@ -271,6 +304,7 @@ pub async fn remove_api_key(
     service: String,
     keystore: State<'_, Keystore>,
     config_manager: State<'_, ApiConfigManager>,
+    rpc_pool: State<'_, SharedRpcPool>,
 ) -> Result<String, String> {
     let key_id = match service.as_str() {
         "helius" => KEY_HELIUS_API,
@@ -298,6 +332,10 @@ pub async fn remove_api_key(
         .update_metadata(&service, metadata, &keystore)
         .map_err(|e| format!("Failed to update metadata: {}", e))?;
 
+    if service == "helius" {
+        rpc_pool.write().await.sync_helius(None);
+    }
+
     Ok(format!("API key for {} removed", service))
 }
 
@@ -478,6 +516,7 @@ fn get_service_status(
         .as_ref()
         .map(|m| m.rotation_history.clone())
         .unwrap_or_default();
+    let scope_report = metadata.as_ref().and_then(|m| m.scope_report.clone());
 
     Ok(ServiceStatus {
         configured,
@@ -492,6 +531,7 @@ fn get_service_status(
         days_until_rotation_due,
         rotation_overdue,
         rotation_history,
+        scope_report,
     })
 }
 
@@ -725,8 +765,31 @@ pub struct ApiKeysExport {
 #[tauri::command]
 pub async fn export_api_keys(
     password: String,
+    two_factor_code: Option<String>,
     keystore: State<'_, Keystore>,
+    passkeys: State<'_, PasskeyManager>,
+    policy: State<'_, CommandPolicyManager>,
+    two_factor: State<'_, TwoFactorManager>,
 ) -> Result<ApiKeysExport, String> {
+    passkeys
+        .require_presence(SensitiveCategory::KeyExport)
+        .map_err(|e| e.to_string())?;
+    let outcome = policy
+        .authorize(
+            GuardedCommand::ExportApiKeys,
+            two_factor_code.as_deref(),
+            two_factor.inner(),
+            keystore.inner(),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    // Key export always needs 2FA when it is enrolled, whatever the policy.
+    if outcome != VerificationOutcome::TwoFactor {
+        two_factor
+            .require_code(two_factor_code.as_deref(), keystore.inner())
+            .map_err(|e| e.to_string())?;
+    }
+
     // Export the entire keystore backup which includes API keys
     let backup = keystore
         .export_backup(&password)
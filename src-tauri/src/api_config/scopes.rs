@@ -0,0 +1,372 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use tauri::State;
+
+use super::{
+    ApiConfigManager, DEFAULT_BIRDEYE_KEY, DEFAULT_HELIUS_KEY, DEFAULT_JUPITER_KEY,
+    KEY_BIRDEYE_API, KEY_HELIUS_API, KEY_JUPITER_API, KEY_SOLANA_RPC,
+};
+use crate::security::keystore::Keystore;
+
+const SERVICES: [(&str, &str); 4] = [
+    ("helius", KEY_HELIUS_API),
+    ("birdeye", KEY_BIRDEYE_API),
+    ("jupiter", KEY_JUPITER_API),
+    ("solana_rpc", KEY_SOLANA_RPC),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScopeState {
+    Granted,
+    Denied,
+    /// The provider does not reveal this permission, or the probe failed.
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyScope {
+    pub name: String,
+    pub description: String,
+    /// Whether the app needs this permission for the features it uses.
+    pub required: bool,
+    pub state: ScopeState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScopeWarningKind {
+    OverPrivileged,
+    MissingRequired,
+    SharedKey,
+    DefaultKey,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeWarning {
+    pub kind: ScopeWarningKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyScopeReport {
+    pub service: String,
+    pub checked_at: DateTime<Utc>,
+    /// False when the provider gives no way to inspect a key's permissions.
+    pub scopes_exposed: bool,
+    pub scopes: Vec<KeyScope>,
+    pub warnings: Vec<ScopeWarning>,
+    pub least_privilege: bool,
+}
+
+struct ScopeProbe {
+    name: &'static str,
+    description: &'static str,
+    required: bool,
+    request: fn(&reqwest::Client, &str) -> reqwest::RequestBuilder,
+}
+
+/// Read-only requests that succeed only if the key carries the scope.
+/// Nothing here creates, changes or spends anything.
+fn probes_for(service: &str) -> Vec<ScopeProbe> {
+    match service {
+        "helius" => vec![
+            ScopeProbe {
+                name: "rpc",
+                description: "Standard Solana JSON-RPC",
+                required: true,
+                request: |client, key| {
+                    client
+                        .post(format!("https://mainnet.helius-rpc.com/?api-key={key}"))
+                        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "getHealth" }))
+                },
+            },
+            ScopeProbe {
+                name: "das",
+                description: "Digital Asset Standard API (compressed NFTs)",
+                required: true,
+                request: |client, key| {
+                    client
+                        .post(format!("https://mainnet.helius-rpc.com/?api-key={key}"))
+                        .json(&json!({
+                            "jsonrpc": "2.0",
+                            "id": 1,
+                            "method": "getAssetsByOwner",
+                            "params": {
+                                "ownerAddress": "HeM8ZhRrPA8QUcLt7ycTGy8AyD1q2CqfRvEdBZ99jqZv",
+                                "limit": 1
+                            }
+                        }))
+                },
+            },
+            ScopeProbe {
+                name: "enhanced_api",
+                description: "Enhanced transactions and balances",
+                required: true,
+                request: |client, key| {
+                    client.get(format!(
+                        "https://api.helius.xyz/v0/addresses/HeM8ZhRrPA8QUcLt7ycTGy8AyD1q2CqfRvEdBZ99jqZv/balances?api-key={key}"
+                    ))
+                },
+            },
+            ScopeProbe {
+                name: "webhook_admin",
+                description: "Create, edit and delete account webhooks",
+                required: false,
+                request: |client, key| {
+                    client.get(format!("https://api.helius.xyz/v0/webhooks?api-key={key}"))
+                },
+            },
+        ],
+        "birdeye" => vec![
+            ScopeProbe {
+                name: "public",
+                description: "Public price and token list endpoints",
+                required: true,
+                request: |client, key| {
+                    client
+                        .get("https://public-api.birdeye.so/defi/price?address=So11111111111111111111111111111111111111112")
+                        .header("X-API-KEY", key)
+                },
+            },
+            ScopeProbe {
+                name: "historical",
+                description: "Historical price and OHLCV data",
+                required: true,
+                request: |client, key| {
+                    client
+                        .get("https://public-api.birdeye.so/defi/history_price?address=So11111111111111111111111111111111111111112&address_type=token&type=1H&time_from=1700000000&time_to=1700003600")
+                        .header("X-API-KEY", key)
+                },
+            },
+            ScopeProbe {
+                name: "wallet",
+                description: "Wallet portfolio and trader analytics",
+                required: false,
+                request: |client, key| {
+                    client
+                        .get("https://public-api.birdeye.so/v1/wallet/token_list?wallet=HeM8ZhRrPA8QUcLt7ycTGy8AyD1q2CqfRvEdBZ99jqZv")
+                        .header("X-API-KEY", key)
+                },
+            },
+        ],
+        _ => Vec::new(),
+    }
+}
+
+fn state_for(status: u16) -> ScopeState {
+    match status {
+        200..=299 => ScopeState::Granted,
+        401 | 403 => ScopeState::Denied,
+        _ => ScopeState::Unknown,
+    }
+}
+
+async fn probe_scopes(service: &str, secret: &str) -> Vec<KeyScope> {
+    let client = reqwest::Client::new();
+    let probes = probes_for(service);
+    let results = futures_util::future::join_all(probes.iter().map(|probe| {
+        (probe.request)(&client, secret)
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+    }))
+    .await;
+
+    probes
+        .iter()
+        .zip(results)
+        .map(|(probe, result)| KeyScope {
+            name: probe.name.to_string(),
+            description: probe.description.to_string(),
+            required: probe.required,
+            state: result
+                .map(|response| state_for(response.status().as_u16()))
+                .unwrap_or(ScopeState::Unknown),
+        })
+        .collect()
+}
+
+/// Groups services whose stored secrets contain one another, e.g. the same
+/// key saved twice or a Helius key embedded in the custom RPC URL.
+pub fn find_shared_keys(secrets: &HashMap<String, String>) -> HashMap<String, Vec<String>> {
+    let mut shared: HashMap<String, Vec<String>> = HashMap::new();
+    for (service, secret) in secrets {
+        for (other, other_secret) in secrets {
+            if service == other || secret.len() < 16 || other_secret.len() < 16 {
+                continue;
+            }
+            if secret.contains(other_secret.as_str()) || other_secret.contains(secret.as_str()) {
+                shared
+                    .entry(service.clone())
+                    .or_default()
+                    .push(other.clone());
+            }
+        }
+    }
+    for services in shared.values_mut() {
+        services.sort();
+    }
+    shared
+}
+
+pub fn evaluate_scopes(
+    service: &str,
+    scopes: &[KeyScope],
+    shared_with: &[String],
+    using_default: bool,
+) -> Vec<ScopeWarning> {
+    let mut warnings = Vec::new();
+    if using_default {
+        warnings.push(ScopeWarning {
+            kind: ScopeWarningKind::DefaultKey,
+            message: format!("{service} is using the shared developer key"),
+        });
+    }
+    for scope in scopes {
+        match (scope.required, scope.state) {
+            (false, ScopeState::Granted) => warnings.push(ScopeWarning {
+                kind: ScopeWarningKind::OverPrivileged,
+                message: format!(
+                    "{service} key grants {} ({}), which the app never uses; a restricted key would limit damage if it leaks",
+                    scope.name, scope.description
+                ),
+            }),
+            (true, ScopeState::Denied) => warnings.push(ScopeWarning {
+                kind: ScopeWarningKind::MissingRequired,
+                message: format!("{service} key lacks {} ({})", scope.name, scope.description),
+            }),
+            _ => {}
+        }
+    }
+    if !shared_with.is_empty() {
+        warnings.push(ScopeWarning {
+            kind: ScopeWarningKind::SharedKey,
+            message: format!(
+                "{service} key is also stored for {}; rotating or revoking one affects all",
+                shared_with.join(", ")
+            ),
+        });
+    }
+    warnings
+}
+
+fn stored_secret(keystore: &Keystore, key_id: &str) -> Option<String> {
+    let secret = keystore.retrieve_secret(key_id).ok()?;
+    String::from_utf8(secret.to_vec())
+        .ok()
+        .filter(|secret| !secret.is_empty())
+        .filter(|secret| {
+            ![DEFAULT_HELIUS_KEY, DEFAULT_BIRDEYE_KEY, DEFAULT_JUPITER_KEY]
+                .contains(&secret.as_str())
+        })
+}
+
+/// Probes the permissions of each stored key (or just `service`) and saves
+/// the findings to the key's metadata for the API status dashboard.
+#[tauri::command]
+pub async fn check_api_key_scopes(
+    service: Option<String>,
+    keystore: State<'_, Keystore>,
+    config_manager: State<'_, ApiConfigManager>,
+) -> Result<Vec<KeyScopeReport>, String> {
+    if let Some(service) = &service {
+        if !SERVICES.iter().any(|(name, _)| name == service) {
+            return Err("Unknown service".to_string());
+        }
+    }
+
+    let secrets: HashMap<String, String> = SERVICES
+        .iter()
+        .filter_map(|(name, key_id)| {
+            stored_secret(&keystore, key_id).map(|secret| (name.to_string(), secret))
+        })
+        .collect();
+    let shared = find_shared_keys(&secrets);
+
+    let mut reports = Vec::new();
+    for (name, _) in SERVICES
+        .iter()
+        .filter(|(name, _)| service.as_deref().map_or(true, |s| s == *name))
+    {
+        let mut metadata = config_manager.get_or_create_metadata(name, true);
+        let using_default = metadata.use_default || !secrets.contains_key(*name);
+        let scopes = match secrets.get(*name) {
+            Some(secret) if !using_default => probe_scopes(name, secret).await,
+            _ => Vec::new(),
+        };
+        let shared_with = shared.get(*name).cloned().unwrap_or_default();
+        let warnings = evaluate_scopes(name, &scopes, &shared_with, using_default);
+        let report = KeyScopeReport {
+            service: name.to_string(),
+            checked_at: Utc::now(),
+            scopes_exposed: !scopes.is_empty(),
+            least_privilege: warnings.is_empty(),
+            scopes,
+            warnings,
+        };
+
+        metadata.scope_report = Some(report.clone());
+        if let Err(err) = config_manager.update_metadata(name, metadata, &keystore) {
+            eprintln!("Failed to persist API metadata: {err}");
+        }
+        reports.push(report);
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(name: &str, required: bool, state: ScopeState) -> KeyScope {
+        KeyScope {
+            name: name.to_string(),
+            description: String::new(),
+            required,
+            state,
+        }
+    }
+
+    #[test]
+    fn flags_unused_and_missing_scopes() {
+        let scopes = vec![
+            scope("rpc", true, ScopeState::Granted),
+            scope("das", true, ScopeState::Denied),
+            scope("webhook_admin", false, ScopeState::Granted),
+            scope("wallet", false, ScopeState::Unknown),
+        ];
+        let kinds: Vec<_> = evaluate_scopes("helius", &scopes, &[], false)
+            .into_iter()
+            .map(|w| w.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ScopeWarningKind::MissingRequired,
+                ScopeWarningKind::OverPrivileged
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_keys_embedded_in_other_secrets() {
+        let key = "3f9c2a7e-1b4d-4e8a-9c6f-2d7b8e1a0c55";
+        let secrets = HashMap::from([
+            ("helius".to_string(), key.to_string()),
+            (
+                "solana_rpc".to_string(),
+                format!("https://mainnet.helius-rpc.com/?api-key={key}"),
+            ),
+            ("birdeye".to_string(), "a-different-birdeye-key".to_string()),
+        ]);
+        let shared = find_shared_keys(&secrets);
+        assert_eq!(shared.get("helius"), Some(&vec!["solana_rpc".to_string()]));
+        assert_eq!(shared.get("solana_rpc"), Some(&vec!["helius".to_string()]));
+        assert!(!shared.contains_key("birdeye"));
+    }
+}
@@ -0,0 +1,604 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+use zeroize::Zeroize;
+
+use super::biometric;
+use super::passkey::{PasskeyAssertion, PasskeyError, PasskeyManager, SensitiveCategory};
+use crate::errors::{AppError, CommandResultExt};
+use crate::security::keystore::{Keystore, KeystoreError};
+
+const SETTINGS_KEY: &str = "app-lock-settings";
+const PASSWORD_HASH_KEY: &str = "app-lock-password";
+const MIN_PASSWORD_LEN: usize = 6;
+const FREE_ATTEMPTS: u32 = 5;
+const BASE_LOCKOUT_SECONDS: i64 = 30;
+const MAX_LOCKOUT_SECONDS: i64 = 15 * 60;
+const SLEEP_WATCH_INTERVAL_SECS: u64 = 5;
+/// A tick arriving this much later than scheduled means the machine was
+/// suspended rather than just busy.
+const SLEEP_GAP_SECONDS: i64 = 30;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppLockError {
+    #[error("app lock is not configured")]
+    NotConfigured,
+    #[error("incorrect password")]
+    InvalidPassword,
+    #[error("too many failed attempts, try again in {0} seconds")]
+    LockedOut(i64),
+    #[error("password must be at least {MIN_PASSWORD_LEN} characters")]
+    WeakPassword,
+    #[error("biometric unlock failed: {0}")]
+    Biometric(#[from] biometric::BiometricError),
+    #[error("passkey unlock is not enabled")]
+    PasskeyDisabled,
+    #[error("passkey unlock failed: {0}")]
+    Passkey(#[from] PasskeyError),
+    #[error("invalid boss key: {0}")]
+    InvalidShortcut(String),
+    #[error("keystore error: {0}")]
+    Keystore(#[from] KeystoreError),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("internal error")]
+    Internal,
+}
+
+impl From<AppLockError> for AppError {
+    fn from(err: AppLockError) -> Self {
+        match err {
+            AppLockError::Biometric(e) => e.into(),
+            AppLockError::Passkey(e) => e.into(),
+            AppLockError::Serialization(e) => AppError::Serialization(e),
+            AppLockError::LockedOut(seconds) => AppError::RateLimited {
+                message: err.to_string(),
+                retry_after_ms: u64::try_from(seconds).ok().map(|s| s * 1000),
+            },
+            err @ AppLockError::NotConfigured => AppError::Conflict(err.to_string()),
+            err @ AppLockError::InvalidPassword => AppError::Unauthorized(err.to_string()),
+            err @ AppLockError::PasskeyDisabled => AppError::Forbidden(err.to_string()),
+            err @ (AppLockError::WeakPassword | AppLockError::InvalidShortcut(_)) => {
+                AppError::Validation(err.to_string())
+            }
+            err @ (AppLockError::Keystore(_) | AppLockError::Internal) => {
+                AppError::Generic(err.to_string())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLockSettings {
+    pub enabled: bool,
+    pub lock_on_sleep: bool,
+    pub allow_biometric: bool,
+    #[serde(default)]
+    pub allow_passkey: bool,
+    /// Global shortcut such as `CmdOrCtrl+Shift+H`.
+    pub boss_key: Option<String>,
+}
+
+impl Default for AppLockSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lock_on_sleep: true,
+            allow_biometric: false,
+            allow_passkey: false,
+            boss_key: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLockUpdate {
+    pub settings: AppLockSettings,
+    pub new_password: Option<String>,
+    /// Required to change anything once a password is set.
+    pub current_password: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LockReason {
+    Startup,
+    Manual,
+    SystemSleep,
+    Screensaver,
+    BossKey,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLockStatus {
+    pub settings: AppLockSettings,
+    pub password_configured: bool,
+    pub locked: bool,
+    pub lock_reason: Option<LockReason>,
+    pub locked_at: Option<DateTime<Utc>>,
+    pub windows_hidden: bool,
+    pub notifications_muted: bool,
+    pub failed_attempts: u32,
+    pub retry_after: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+struct LockRuntime {
+    locked: bool,
+    reason: Option<LockReason>,
+    locked_at: Option<DateTime<Utc>>,
+    windows_hidden: bool,
+    muted: bool,
+    failed_attempts: u32,
+    retry_after: Option<DateTime<Utc>>,
+}
+
+/// Backoff after the free attempts are used up: 30s, 60s, 120s, ... capped
+/// at fifteen minutes.
+fn lockout_after(failed_attempts: u32) -> Option<Duration> {
+    let excess = failed_attempts.checked_sub(FREE_ATTEMPTS)?;
+    let seconds = BASE_LOCKOUT_SECONDS
+        .saturating_mul(1i64 << excess.min(10))
+        .min(MAX_LOCKOUT_SECONDS);
+    Some(Duration::seconds(seconds))
+}
+
+fn resumed_from_sleep(last_tick: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    now - last_tick > Duration::seconds(SLEEP_WATCH_INTERVAL_SECS as i64 + SLEEP_GAP_SECONDS)
+}
+
+pub struct AppLockManager {
+    settings: Mutex<AppLockSettings>,
+    password_hash: Mutex<Option<String>>,
+    runtime: Mutex<LockRuntime>,
+}
+
+pub type SharedAppLock = Arc<AppLockManager>;
+
+impl AppLockManager {
+    pub fn new() -> Self {
+        Self {
+            settings: Mutex::new(AppLockSettings::default()),
+            password_hash: Mutex::new(None),
+            runtime: Mutex::new(LockRuntime::default()),
+        }
+    }
+
+    /// Loads the saved configuration. An enabled lock starts locked so the
+    /// app never opens straight onto portfolio data.
+    pub fn hydrate(&self, keystore: &Keystore) -> Result<(), AppLockError> {
+        match keystore.retrieve_secret(SETTINGS_KEY) {
+            Ok(payload) => *lock(&self.settings)? = serde_json::from_slice(payload.as_ref())?,
+            Err(KeystoreError::NotFound) => {}
+            Err(err) => return Err(err.into()),
+        }
+        match keystore.retrieve_secret(PASSWORD_HASH_KEY) {
+            Ok(payload) => {
+                *lock(&self.password_hash)? = String::from_utf8(payload.to_vec()).ok();
+            }
+            Err(KeystoreError::NotFound) => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let enabled = lock(&self.settings)?.enabled && lock(&self.password_hash)?.is_some();
+        if enabled {
+            let mut runtime = lock(&self.runtime)?;
+            runtime.locked = true;
+            runtime.reason = Some(LockReason::Startup);
+            runtime.locked_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+
+    pub fn status(&self) -> Result<AppLockStatus, AppLockError> {
+        let settings = lock(&self.settings)?.clone();
+        let password_configured = lock(&self.password_hash)?.is_some();
+        let runtime = lock(&self.runtime)?;
+        Ok(AppLockStatus {
+            settings,
+            password_configured,
+            locked: runtime.locked,
+            lock_reason: runtime.reason,
+            locked_at: runtime.locked_at,
+            windows_hidden: runtime.windows_hidden,
+            notifications_muted: runtime.muted,
+            failed_attempts: runtime.failed_attempts,
+            retry_after: runtime.retry_after,
+        })
+    }
+
+    pub fn is_active(&self) -> bool {
+        let enabled = lock(&self.settings).map(|s| s.enabled).unwrap_or(false);
+        let configured = lock(&self.password_hash)
+            .map(|h| h.is_some())
+            .unwrap_or(false);
+        enabled && configured
+    }
+
+    pub fn notifications_muted(&self) -> bool {
+        lock(&self.runtime).map(|r| r.muted).unwrap_or(false)
+    }
+
+    pub fn configure(
+        &self,
+        app: &AppHandle,
+        update: AppLockUpdate,
+        keystore: &Keystore,
+    ) -> Result<AppLockStatus, AppLockError> {
+        let AppLockUpdate {
+            settings,
+            mut new_password,
+            current_password,
+        } = update;
+
+        let existing_hash = lock(&self.password_hash)?.clone();
+        if let Some(hash) = &existing_hash {
+            let mut supplied = current_password.unwrap_or_default();
+            let result = self.check_password(&supplied, hash);
+            supplied.zeroize();
+            result?;
+        }
+
+        if let Some(boss_key) = &settings.boss_key {
+            boss_key
+                .parse::<tauri_plugin_global_shortcut::Shortcut>()
+                .map_err(|e| AppLockError::InvalidShortcut(e.to_string()))?;
+        }
+
+        if let Some(password) = new_password.as_mut() {
+            if password.chars().count() < MIN_PASSWORD_LEN {
+                password.zeroize();
+                return Err(AppLockError::WeakPassword);
+            }
+            let salt = SaltString::generate(&mut OsRng);
+            let hash = Argon2::default()
+                .hash_password(password.as_bytes(), &salt)
+                .map_err(|_| AppLockError::Internal)?
+                .to_string();
+            password.zeroize();
+            keystore.store_secret(PASSWORD_HASH_KEY, hash.as_bytes())?;
+            *lock(&self.password_hash)? = Some(hash);
+        } else if settings.enabled && existing_hash.is_none() {
+            return Err(AppLockError::NotConfigured);
+        }
+
+        let previous_boss_key = lock(&self.settings)?.boss_key.clone();
+        keystore.store_secret(SETTINGS_KEY, &serde_json::to_vec(&settings)?)?;
+        *lock(&self.settings)? = settings;
+
+        if !self.is_active() {
+            let mut runtime = lock(&self.runtime)?;
+            runtime.locked = false;
+            runtime.reason = None;
+            runtime.locked_at = None;
+        }
+        if let Err(err) = register_boss_key(app, previous_boss_key.as_deref()) {
+            eprintln!("Failed to register boss key: {err}");
+        }
+
+        self.status()
+    }
+
+    fn check_password(&self, password: &str, hash: &str) -> Result<(), AppLockError> {
+        let parsed = PasswordHash::new(hash).map_err(|_| AppLockError::Internal)?;
+        Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .map_err(|_| AppLockError::InvalidPassword)
+    }
+
+    /// Locks the app. Ignored when no lock is configured, except that the
+    /// boss key still hides windows.
+    pub fn lock_app(&self, app: &AppHandle, reason: LockReason) -> Result<(), AppLockError> {
+        let active = self.is_active();
+        {
+            let mut runtime = lock(&self.runtime)?;
+            if active && !runtime.locked {
+                runtime.locked = true;
+                runtime.reason = Some(reason);
+                runtime.locked_at = Some(Utc::now());
+            }
+            if reason == LockReason::BossKey {
+                runtime.windows_hidden = true;
+                runtime.muted = true;
+            }
+        }
+        if reason == LockReason::BossKey {
+            for window in app.webview_windows().values() {
+                let _ = window.hide();
+            }
+        }
+        if active {
+            if let Some(passkeys) = app.try_state::<PasskeyManager>() {
+                let _ = passkeys.clear_presence();
+            }
+        }
+        self.broadcast(app);
+        Ok(())
+    }
+
+    /// Boss key pressed again: bring the main window back. Notifications
+    /// stay muted until the lock screen is passed, if one is configured.
+    fn reveal(&self, app: &AppHandle) -> Result<(), AppLockError> {
+        {
+            let mut runtime = lock(&self.runtime)?;
+            runtime.windows_hidden = false;
+            runtime.muted = runtime.locked;
+        }
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        self.broadcast(app);
+        Ok(())
+    }
+
+    pub async fn unlock(
+        &self,
+        app: &AppHandle,
+        password: Option<String>,
+        use_biometric: bool,
+        passkey: Option<PasskeyAssertion>,
+    ) -> Result<AppLockStatus, AppLockError> {
+        {
+            let runtime = lock(&self.runtime)?;
+            if let Some(retry_after) = runtime.retry_after {
+                let remaining = (retry_after - Utc::now()).num_seconds();
+                if remaining > 0 {
+                    return Err(AppLockError::LockedOut(remaining));
+                }
+            }
+        }
+
+        let result = if let Some(assertion) = passkey {
+            if !lock(&self.settings)?.allow_passkey {
+                return Err(AppLockError::PasskeyDisabled);
+            }
+            match (
+                app.try_state::<PasskeyManager>(),
+                app.try_state::<Keystore>(),
+            ) {
+                (Some(passkeys), Some(keystore)) => passkeys
+                    .authenticate(&assertion, keystore.inner())
+                    .map(|_| ())
+                    .map_err(AppLockError::from),
+                _ => Err(AppLockError::Internal),
+            }
+        } else if use_biometric {
+            if !lock(&self.settings)?.allow_biometric {
+                return Err(AppLockError::Biometric(
+                    biometric::BiometricError::NotEnrolled,
+                ));
+            }
+            biometric::verify().await.map_err(AppLockError::from)
+        } else {
+            let hash = lock(&self.password_hash)?
+                .clone()
+                .ok_or(AppLockError::NotConfigured)?;
+            let mut password = password.unwrap_or_default();
+            let result = self.check_password(&password, &hash);
+            password.zeroize();
+            result
+        };
+
+        {
+            let mut runtime = lock(&self.runtime)?;
+            match &result {
+                Ok(()) => {
+                    *runtime = LockRuntime::default();
+                }
+                Err(_) => {
+                    runtime.failed_attempts += 1;
+                    runtime.retry_after =
+                        lockout_after(runtime.failed_attempts).map(|wait| Utc::now() + wait);
+                }
+            }
+        }
+        result?;
+
+        for window in app.webview_windows().values() {
+            let _ = window.show();
+        }
+        self.broadcast(app);
+        self.status()
+    }
+
+    fn broadcast(&self, app: &AppHandle) {
+        if let Ok(status) = self.status() {
+            let _ = app.emit("app_lock_changed", status);
+        }
+    }
+}
+
+impl Default for AppLockManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>, AppLockError> {
+    mutex.lock().map_err(|_| AppLockError::Internal)
+}
+
+/// Whether OS and in-app notifications should currently be suppressed.
+pub fn notifications_muted(app: &AppHandle) -> bool {
+    app.try_state::<SharedAppLock>()
+        .map(|manager| manager.notifications_muted())
+        .unwrap_or(false)
+}
+
+/// Swaps the registered boss key for the one in the current settings.
+pub fn register_boss_key(app: &AppHandle, previous: Option<&str>) -> Result<(), String> {
+    let Some(manager) = app.try_state::<SharedAppLock>() else {
+        return Ok(());
+    };
+    let boss_key = lock(&manager.settings)
+        .map_err(|e| e.to_string())?
+        .boss_key
+        .clone();
+
+    let shortcuts = app.global_shortcut();
+    if let Some(previous) = previous {
+        if let Err(err) = shortcuts.unregister(previous) {
+            eprintln!("Failed to unregister previous boss key: {err}");
+        }
+    }
+    let Some(boss_key) = boss_key else {
+        return Ok(());
+    };
+
+    shortcuts
+        .on_shortcut(boss_key.as_str(), |app, _shortcut, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            let manager = app.state::<SharedAppLock>();
+            let hidden = lock(&manager.runtime)
+                .map(|r| r.windows_hidden)
+                .unwrap_or(false);
+            let result = if hidden {
+                manager.reveal(app)
+            } else {
+                manager.lock_app(app, LockReason::BossKey)
+            };
+            if let Err(err) = result {
+                eprintln!("Boss key action failed: {err}");
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+/// Locks the app after the machine wakes from sleep. Timers do not fire
+/// while suspended, so a tick that arrives far later than scheduled by the
+/// wall clock marks a resume.
+pub fn start_sleep_watch(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_tick = Utc::now();
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(SLEEP_WATCH_INTERVAL_SECS)).await;
+            let now = Utc::now();
+            if resumed_from_sleep(last_tick, now) {
+                let manager = app.state::<SharedAppLock>();
+                let lock_on_sleep = lock(&manager.settings)
+                    .map(|s| s.lock_on_sleep)
+                    .unwrap_or(false);
+                if lock_on_sleep {
+                    if let Err(err) = manager.lock_app(&app, LockReason::SystemSleep) {
+                        eprintln!("Failed to lock app after sleep: {err}");
+                    }
+                }
+            }
+            last_tick = now;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn app_lock_status(state: State<'_, SharedAppLock>) -> Result<AppLockStatus, AppError> {
+    state.status().logged("app_lock_status")
+}
+
+#[tauri::command]
+pub async fn app_lock_configure(
+    update: AppLockUpdate,
+    app: AppHandle,
+    state: State<'_, SharedAppLock>,
+    keystore: State<'_, Keystore>,
+    passkeys: State<'_, PasskeyManager>,
+) -> Result<AppLockStatus, AppError> {
+    passkeys
+        .require_presence(SensitiveCategory::SecuritySettings)
+        .logged("app_lock_configure")?;
+    state
+        .configure(&app, update, keystore.inner())
+        .logged("app_lock_configure")
+}
+
+#[tauri::command]
+pub async fn app_lock_lock(
+    app: AppHandle,
+    state: State<'_, SharedAppLock>,
+) -> Result<(), AppError> {
+    state
+        .lock_app(&app, LockReason::Manual)
+        .logged("app_lock_lock")
+}
+
+#[tauri::command]
+pub async fn app_lock_unlock(
+    password: Option<String>,
+    use_biometric: Option<bool>,
+    passkey: Option<PasskeyAssertion>,
+    app: AppHandle,
+    state: State<'_, SharedAppLock>,
+) -> Result<AppLockStatus, AppError> {
+    state
+        .unlock(&app, password, use_biometric.unwrap_or(false), passkey)
+        .await
+        .logged("app_lock_unlock")
+}
+
+/// Forwards OS signals the backend cannot observe itself, such as the
+/// screensaver starting or the session being locked.
+#[tauri::command]
+pub async fn app_lock_report_os_event(
+    event: String,
+    app: AppHandle,
+    state: State<'_, SharedAppLock>,
+) -> Result<(), AppError> {
+    let lock_on_sleep = state
+        .status()
+        .logged("app_lock_report_os_event")?
+        .settings
+        .lock_on_sleep;
+    let reason = match event.as_str() {
+        "sleep" | "suspend" => LockReason::SystemSleep,
+        "screensaver" | "screenLocked" => LockReason::Screensaver,
+        other => {
+            return Err(AppError::Validation(format!("Unknown OS event: {other}"))
+                .logged("app_lock_report_os_event"))
+        }
+    };
+    if lock_on_sleep {
+        state
+            .lock_app(&app, reason)
+            .logged("app_lock_report_os_event")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lockout_starts_after_free_attempts_and_is_capped() {
+        assert!(lockout_after(FREE_ATTEMPTS - 1).is_none());
+        assert_eq!(lockout_after(FREE_ATTEMPTS), Some(Duration::seconds(30)));
+        assert_eq!(
+            lockout_after(FREE_ATTEMPTS + 2),
+            Some(Duration::seconds(120))
+        );
+        assert_eq!(
+            lockout_after(FREE_ATTEMPTS + 40),
+            Some(Duration::seconds(MAX_LOCKOUT_SECONDS))
+        );
+    }
+
+    #[test]
+    fn only_long_gaps_count_as_sleep() {
+        let start = Utc::now();
+        assert!(!resumed_from_sleep(start, start + Duration::seconds(6)));
+        assert!(!resumed_from_sleep(start, start + Duration::seconds(20)));
+        assert!(resumed_from_sleep(start, start + Duration::minutes(10)));
+    }
+}
@@ -14,6 +14,8 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use zeroize::Zeroize;
 
+use crate::errors::AppError;
+
 const KEYRING_SERVICE: &str = "eclipse-market-pro";
 const BIOMETRIC_TOKEN_KEY: &str = "biometric-token";
 const FALLBACK_HASH_KEY: &str = "biometric-fallback";
@@ -58,6 +60,24 @@ impl From<BiometricError> for String {
     }
 }
 
+impl From<BiometricError> for AppError {
+    fn from(err: BiometricError) -> Self {
+        match err {
+            BiometricError::Unavailable(message) => AppError::ExternalService {
+                service: "Biometric hardware".to_string(),
+                message,
+            },
+            err @ (BiometricError::Denied(_) | BiometricError::InvalidFallback) => {
+                AppError::Unauthorized(err.to_string())
+            }
+            err @ BiometricError::NotEnrolled => AppError::Conflict(err.to_string()),
+            err @ (BiometricError::Failed(_) | BiometricError::Storage(_)) => {
+                AppError::Generic(err.to_string())
+            }
+        }
+    }
+}
+
 pub fn current_status() -> Result<BiometricStatus, BiometricError> {
     let platform = platform_kind();
     let available = platform_is_available()?;
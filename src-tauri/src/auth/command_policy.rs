@@ -0,0 +1,423 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::biometric::{self, BiometricError};
+use super::two_factor::{TwoFactorError, TwoFactorManager};
+use crate::errors::{AppError, CommandResultExt};
+use crate::security::keystore::{Keystore, KeystoreError};
+
+const COMMAND_POLICY_KEY: &str = "command-policy";
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommandPolicyError {
+    #[error("{command} requires {method} verification")]
+    VerificationRequired {
+        command: &'static str,
+        method: &'static str,
+    },
+    #[error("{command} requires {method}, which is not set up")]
+    MethodUnavailable {
+        command: &'static str,
+        method: &'static str,
+    },
+    #[error("two-factor authentication is still required by: {0}")]
+    TwoFactorInUse(String),
+    #[error("two-factor error: {0}")]
+    TwoFactor(#[from] TwoFactorError),
+    #[error("biometric error: {0}")]
+    Biometric(#[from] BiometricError),
+    #[error("keystore error: {0}")]
+    Keystore(#[from] KeystoreError),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("internal error")]
+    Internal,
+}
+
+impl From<CommandPolicyError> for AppError {
+    fn from(err: CommandPolicyError) -> Self {
+        match err {
+            CommandPolicyError::TwoFactor(e) => e.into(),
+            CommandPolicyError::Biometric(e) => e.into(),
+            CommandPolicyError::Serialization(e) => AppError::Serialization(e),
+            err @ CommandPolicyError::VerificationRequired { .. } => {
+                AppError::Unauthorized(err.to_string())
+            }
+            err @ CommandPolicyError::MethodUnavailable { .. } => {
+                AppError::Forbidden(err.to_string())
+            }
+            err @ CommandPolicyError::TwoFactorInUse(_) => AppError::Conflict(err.to_string()),
+            err @ (CommandPolicyError::Keystore(_) | CommandPolicyError::Internal) => {
+                AppError::Generic(err.to_string())
+            }
+        }
+    }
+}
+
+/// Commands whose re-verification requirement is configurable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardedCommand {
+    WalletSendTransaction,
+    ExportApiKeys,
+    RollbackUpdate,
+    AutoTradingDeactivateKillSwitch,
+}
+
+impl GuardedCommand {
+    pub const ALL: [GuardedCommand; 4] = [
+        GuardedCommand::WalletSendTransaction,
+        GuardedCommand::ExportApiKeys,
+        GuardedCommand::RollbackUpdate,
+        GuardedCommand::AutoTradingDeactivateKillSwitch,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GuardedCommand::WalletSendTransaction => "wallet_send_transaction",
+            GuardedCommand::ExportApiKeys => "export_api_keys",
+            GuardedCommand::RollbackUpdate => "rollback_update",
+            GuardedCommand::AutoTradingDeactivateKillSwitch => {
+                "auto_trading_deactivate_kill_switch"
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VerificationRequirement {
+    #[default]
+    None,
+    TwoFactor,
+    Biometric,
+    /// A 2FA code when one is supplied, otherwise a biometric prompt.
+    TwoFactorOrBiometric,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandPolicyRule {
+    pub command: GuardedCommand,
+    pub command_name: String,
+    pub requirement: VerificationRequirement,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CommandPolicyConfig {
+    rules: HashMap<GuardedCommand, VerificationRequirement>,
+}
+
+/// Which check `authorize` actually ran. Commands that also demand a 2FA
+/// code on their own skip that when the policy already consumed one, since
+/// the replay guard would reject the same code twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationOutcome {
+    NotRequired,
+    TwoFactor,
+    Biometric,
+}
+
+/// What has to happen before a guarded command may run.
+#[derive(Debug, PartialEq, Eq)]
+enum Check {
+    Allow,
+    TwoFactorCode,
+    BiometricPrompt,
+}
+
+/// Picks the check for a requirement. A method that has since been disabled
+/// denies rather than allows, so turning off 2FA or biometrics cannot be
+/// used to slip past a rule.
+fn plan_check(
+    command: GuardedCommand,
+    requirement: VerificationRequirement,
+    has_code: bool,
+    two_factor_enrolled: bool,
+    biometric_enrolled: bool,
+) -> Result<Check, CommandPolicyError> {
+    let command = command.as_str();
+    match requirement {
+        VerificationRequirement::None => Ok(Check::Allow),
+        VerificationRequirement::TwoFactor if !two_factor_enrolled => {
+            Err(CommandPolicyError::MethodUnavailable {
+                command,
+                method: "two-factor authentication",
+            })
+        }
+        VerificationRequirement::TwoFactor if !has_code => {
+            Err(CommandPolicyError::VerificationRequired {
+                command,
+                method: "two-factor",
+            })
+        }
+        VerificationRequirement::TwoFactor => Ok(Check::TwoFactorCode),
+        VerificationRequirement::Biometric if !biometric_enrolled => {
+            Err(CommandPolicyError::MethodUnavailable {
+                command,
+                method: "biometric unlock",
+            })
+        }
+        VerificationRequirement::Biometric => Ok(Check::BiometricPrompt),
+        VerificationRequirement::TwoFactorOrBiometric => {
+            match (has_code && two_factor_enrolled, biometric_enrolled) {
+                (true, _) => Ok(Check::TwoFactorCode),
+                (false, true) => Ok(Check::BiometricPrompt),
+                (false, false) if two_factor_enrolled => {
+                    Err(CommandPolicyError::VerificationRequired {
+                        command,
+                        method: "two-factor",
+                    })
+                }
+                (false, false) => Err(CommandPolicyError::MethodUnavailable {
+                    command,
+                    method: "two-factor authentication or biometric unlock",
+                }),
+            }
+        }
+    }
+}
+
+/// Commands whose rule could no longer be met if 2FA were switched off.
+fn two_factor_dependents(
+    rules: &HashMap<GuardedCommand, VerificationRequirement>,
+    biometric_enrolled: bool,
+) -> Vec<GuardedCommand> {
+    GuardedCommand::ALL
+        .iter()
+        .copied()
+        .filter(|command| match rules.get(command) {
+            Some(VerificationRequirement::TwoFactor) => true,
+            Some(VerificationRequirement::TwoFactorOrBiometric) => !biometric_enrolled,
+            _ => false,
+        })
+        .collect()
+}
+
+/// Server-side re-verification for high-risk commands. Each guarded command
+/// calls `authorize` before doing any work, so a modified frontend cannot
+/// skip the prompt.
+pub struct CommandPolicyManager {
+    config: Mutex<CommandPolicyConfig>,
+}
+
+impl CommandPolicyManager {
+    pub fn new() -> Self {
+        Self {
+            config: Mutex::new(CommandPolicyConfig::default()),
+        }
+    }
+
+    pub fn hydrate(&self, keystore: &Keystore) -> Result<(), CommandPolicyError> {
+        match keystore.retrieve_secret(COMMAND_POLICY_KEY) {
+            Ok(bytes) => {
+                let config: CommandPolicyConfig = serde_json::from_slice(bytes.as_ref())?;
+                *self.lock_config()? = config;
+            }
+            Err(KeystoreError::NotFound) => {}
+            Err(err) => return Err(CommandPolicyError::Keystore(err)),
+        }
+        Ok(())
+    }
+
+    pub fn requirement(
+        &self,
+        command: GuardedCommand,
+    ) -> Result<VerificationRequirement, CommandPolicyError> {
+        Ok(self
+            .lock_config()?
+            .rules
+            .get(&command)
+            .copied()
+            .unwrap_or_default())
+    }
+
+    pub fn rules(&self) -> Result<Vec<CommandPolicyRule>, CommandPolicyError> {
+        let config = self.lock_config()?;
+        Ok(GuardedCommand::ALL
+            .iter()
+            .map(|command| CommandPolicyRule {
+                command: *command,
+                command_name: command.as_str().to_string(),
+                requirement: config.rules.get(command).copied().unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Runs the verification the policy asks for `command`. A supplied 2FA
+    /// code is consumed; a biometric requirement prompts the OS.
+    pub async fn authorize(
+        &self,
+        command: GuardedCommand,
+        two_factor_code: Option<&str>,
+        two_factor: &TwoFactorManager,
+        keystore: &Keystore,
+    ) -> Result<VerificationOutcome, CommandPolicyError> {
+        let requirement = self.requirement(command)?;
+        if requirement == VerificationRequirement::None {
+            return Ok(VerificationOutcome::NotRequired);
+        }
+
+        let code = two_factor_code.filter(|c| !c.trim().is_empty());
+        let biometric_enrolled = matches!(
+            requirement,
+            VerificationRequirement::Biometric | VerificationRequirement::TwoFactorOrBiometric
+        ) && biometric::current_status()?.enrolled;
+
+        match plan_check(
+            command,
+            requirement,
+            code.is_some(),
+            two_factor.is_enrolled()?,
+            biometric_enrolled,
+        )? {
+            Check::Allow => Ok(VerificationOutcome::NotRequired),
+            Check::TwoFactorCode => {
+                two_factor.require_code(code, keystore)?;
+                Ok(VerificationOutcome::TwoFactor)
+            }
+            Check::BiometricPrompt => {
+                biometric::verify().await?;
+                Ok(VerificationOutcome::Biometric)
+            }
+        }
+    }
+
+    /// Disabling 2FA while a rule still depends on it would lock that
+    /// command for good, so it is refused until the rule is changed.
+    pub fn ensure_two_factor_removable(&self) -> Result<(), CommandPolicyError> {
+        let rules = self.lock_config()?.rules.clone();
+        let biometric_enrolled = rules
+            .values()
+            .any(|r| *r == VerificationRequirement::TwoFactorOrBiometric)
+            && biometric::current_status()?.enrolled;
+
+        let blocked = two_factor_dependents(&rules, biometric_enrolled);
+        if blocked.is_empty() {
+            return Ok(());
+        }
+        let names: Vec<&str> = blocked.iter().map(|command| command.as_str()).collect();
+        Err(CommandPolicyError::TwoFactorInUse(names.join(", ")))
+    }
+
+    /// Changing a rule needs whatever the rule currently demands, so a
+    /// hijacked session cannot simply switch the protection off.
+    pub async fn set_requirement(
+        &self,
+        command: GuardedCommand,
+        requirement: VerificationRequirement,
+        two_factor_code: Option<&str>,
+        two_factor: &TwoFactorManager,
+        keystore: &Keystore,
+    ) -> Result<(), CommandPolicyError> {
+        self.authorize(command, two_factor_code, two_factor, keystore)
+            .await?;
+
+        let mut config = self.lock_config()?;
+        if requirement == VerificationRequirement::None {
+            config.rules.remove(&command);
+        } else {
+            config.rules.insert(command, requirement);
+        }
+        let payload = serde_json::to_vec(&*config)?;
+        keystore.store_secret(COMMAND_POLICY_KEY, &payload)?;
+        Ok(())
+    }
+
+    fn lock_config(&self) -> Result<MutexGuard<'_, CommandPolicyConfig>, CommandPolicyError> {
+        self.config.lock().map_err(|_| CommandPolicyError::Internal)
+    }
+}
+
+#[tauri::command]
+pub async fn command_policy_list(
+    state: State<'_, CommandPolicyManager>,
+) -> Result<Vec<CommandPolicyRule>, AppError> {
+    state.rules().logged("command_policy_list")
+}
+
+#[tauri::command]
+pub async fn command_policy_set(
+    command: GuardedCommand,
+    requirement: VerificationRequirement,
+    two_factor_code: Option<String>,
+    state: State<'_, CommandPolicyManager>,
+    two_factor: State<'_, TwoFactorManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<Vec<CommandPolicyRule>, AppError> {
+    state
+        .set_requirement(
+            command,
+            requirement,
+            two_factor_code.as_deref(),
+            two_factor.inner(),
+            keystore.inner(),
+        )
+        .await
+        .logged("command_policy_set")?;
+    state.rules().logged("command_policy_set")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEND: GuardedCommand = GuardedCommand::WalletSendTransaction;
+
+    #[test]
+    fn disabled_methods_deny_instead_of_allowing() {
+        assert!(matches!(
+            plan_check(SEND, VerificationRequirement::TwoFactor, true, false, true),
+            Err(CommandPolicyError::MethodUnavailable { .. })
+        ));
+        assert!(matches!(
+            plan_check(SEND, VerificationRequirement::Biometric, false, true, false),
+            Err(CommandPolicyError::MethodUnavailable { .. })
+        ));
+        assert_eq!(
+            plan_check(SEND, VerificationRequirement::None, false, false, false).unwrap(),
+            Check::Allow
+        );
+    }
+
+    #[test]
+    fn two_factor_dependents_account_for_biometric_fallback() {
+        let mut rules = HashMap::new();
+        rules.insert(SEND, VerificationRequirement::TwoFactor);
+        rules.insert(
+            GuardedCommand::ExportApiKeys,
+            VerificationRequirement::TwoFactorOrBiometric,
+        );
+        rules.insert(
+            GuardedCommand::RollbackUpdate,
+            VerificationRequirement::Biometric,
+        );
+
+        assert_eq!(two_factor_dependents(&rules, true), vec![SEND]);
+        assert_eq!(
+            two_factor_dependents(&rules, false),
+            vec![SEND, GuardedCommand::ExportApiKeys]
+        );
+    }
+
+    #[test]
+    fn either_method_prefers_a_supplied_code() {
+        let either = VerificationRequirement::TwoFactorOrBiometric;
+        assert_eq!(
+            plan_check(SEND, either, true, true, true).unwrap(),
+            Check::TwoFactorCode
+        );
+        assert_eq!(
+            plan_check(SEND, either, false, true, true).unwrap(),
+            Check::BiometricPrompt
+        );
+        assert!(matches!(
+            plan_check(SEND, either, false, true, false),
+            Err(CommandPolicyError::VerificationRequired { .. })
+        ));
+    }
+}
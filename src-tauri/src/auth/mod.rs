@@ -1,7 +1,11 @@
+pub mod app_lock;
 pub mod biometric;
+pub mod command_policy;
+pub mod passkey;
 pub mod session_manager;
 pub mod two_factor;
 
+use crate::errors::{AppError, CommandResultExt};
 use biometric::BiometricStatus;
 use serde::{Deserialize, Serialize};
 
@@ -12,7 +16,7 @@ pub struct AuthState {
 }
 
 #[tauri::command]
-pub async fn connect_phantom(_app: tauri::AppHandle) -> Result<AuthState, String> {
+pub async fn connect_phantom(_app: tauri::AppHandle) -> Result<AuthState, AppError> {
     Ok(AuthState {
         connected: false,
         wallet_address: None,
@@ -20,28 +24,28 @@ pub async fn connect_phantom(_app: tauri::AppHandle) -> Result<AuthState, String
 }
 
 #[tauri::command]
-pub async fn biometric_get_status() -> Result<BiometricStatus, String> {
-    biometric::current_status().map_err(|e| e.into())
+pub async fn biometric_get_status() -> Result<BiometricStatus, AppError> {
+    biometric::current_status().logged("biometric_get_status")
 }
 
 #[tauri::command]
-pub async fn biometric_enroll(fallback_password: String) -> Result<BiometricStatus, String> {
+pub async fn biometric_enroll(fallback_password: String) -> Result<BiometricStatus, AppError> {
     biometric::enroll(fallback_password)
         .await
-        .map_err(|e| e.into())
+        .logged("biometric_enroll")
 }
 
 #[tauri::command]
-pub async fn biometric_verify() -> Result<(), String> {
-    biometric::verify().await.map_err(|e| e.into())
+pub async fn biometric_verify() -> Result<(), AppError> {
+    biometric::verify().await.logged("biometric_verify")
 }
 
 #[tauri::command]
-pub async fn biometric_disable() -> Result<BiometricStatus, String> {
-    biometric::disable().map_err(|e| e.into())
+pub async fn biometric_disable() -> Result<BiometricStatus, AppError> {
+    biometric::disable().logged("biometric_disable")
 }
 
 #[tauri::command]
-pub async fn biometric_verify_fallback(password: String) -> Result<(), String> {
-    biometric::verify_fallback(password).map_err(|e| e.into())
+pub async fn biometric_verify_fallback(password: String) -> Result<(), AppError> {
+    biometric::verify_fallback(password).logged("biometric_verify_fallback")
 }
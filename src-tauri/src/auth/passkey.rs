@@ -0,0 +1,923 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Duration, Utc};
+use ring::signature::{self, UnparsedPublicKey, VerificationAlgorithm};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tauri::State;
+
+use crate::errors::{AppError, CommandResultExt};
+use crate::security::keystore::{Keystore, KeystoreError};
+
+const PASSKEY_CONFIG_KEY: &str = "passkey-config";
+const RP_NAME: &str = "EclipseMarketPro";
+const CHALLENGE_TTL_SECONDS: i64 = 5 * 60;
+/// How long a successful passkey assertion unlocks gated commands.
+const PRESENCE_WINDOW_SECONDS: i64 = 5 * 60;
+const MAX_CREDENTIALS: usize = 10;
+
+/// Relying party ids the webview can present, depending on platform.
+const ALLOWED_RP_IDS: &[&str] = &["localhost", "tauri.localhost"];
+const ALLOWED_ORIGINS: &[&str] = &[
+    "tauri://localhost",
+    "http://tauri.localhost",
+    "https://tauri.localhost",
+    "http://localhost:1420",
+];
+
+const COSE_ES256: i64 = -7;
+const COSE_EDDSA: i64 = -8;
+const COSE_RS256: i64 = -257;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PasskeyError {
+    #[error("no passkeys registered")]
+    NotRegistered,
+    #[error("passkey not found")]
+    UnknownCredential,
+    #[error("passkey already registered")]
+    DuplicateCredential,
+    #[error("at most {MAX_CREDENTIALS} passkeys can be registered")]
+    TooManyCredentials,
+    #[error("unsupported passkey algorithm {0}")]
+    UnsupportedAlgorithm(i64),
+    #[error("passkey challenge is invalid or expired")]
+    InvalidChallenge,
+    #[error("unexpected origin {0}")]
+    InvalidOrigin(String),
+    #[error("unexpected relying party id {0}")]
+    InvalidRpId(String),
+    #[error("authenticator did not confirm user presence and verification")]
+    UserNotVerified,
+    #[error("passkey signature is invalid")]
+    InvalidSignature,
+    #[error("passkey sign counter went backwards; the authenticator may be cloned")]
+    CounterRegression,
+    #[error("malformed passkey data: {0}")]
+    Malformed(&'static str),
+    #[error("passkey confirmation required for {0}")]
+    PresenceRequired(&'static str),
+    #[error("keystore error: {0}")]
+    Keystore(#[from] KeystoreError),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("internal error")]
+    Internal,
+}
+
+impl From<PasskeyError> for AppError {
+    fn from(err: PasskeyError) -> Self {
+        match err {
+            PasskeyError::Serialization(e) => AppError::Serialization(e),
+            err @ (PasskeyError::NotRegistered | PasskeyError::UnknownCredential) => {
+                AppError::NotFound(err.to_string())
+            }
+            err @ (PasskeyError::DuplicateCredential | PasskeyError::TooManyCredentials) => {
+                AppError::Conflict(err.to_string())
+            }
+            err @ (PasskeyError::UnsupportedAlgorithm(_) | PasskeyError::Malformed(_)) => {
+                AppError::Validation(err.to_string())
+            }
+            err @ (PasskeyError::InvalidChallenge
+            | PasskeyError::InvalidOrigin(_)
+            | PasskeyError::InvalidRpId(_)
+            | PasskeyError::UserNotVerified
+            | PasskeyError::InvalidSignature
+            | PasskeyError::CounterRegression
+            | PasskeyError::PresenceRequired(_)) => AppError::Unauthorized(err.to_string()),
+            err @ (PasskeyError::Keystore(_) | PasskeyError::Internal) => {
+                AppError::Generic(err.to_string())
+            }
+        }
+    }
+}
+
+/// Command groups that can be made to require a recent passkey assertion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SensitiveCategory {
+    Trading,
+    Transfers,
+    KeyExport,
+    SecuritySettings,
+}
+
+impl SensitiveCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SensitiveCategory::Trading => "trading",
+            SensitiveCategory::Transfers => "transfers",
+            SensitiveCategory::KeyExport => "key export",
+            SensitiveCategory::SecuritySettings => "security settings",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyCredential {
+    /// Base64url credential id as reported by the authenticator.
+    pub credential_id: String,
+    pub name: String,
+    /// Base64url SubjectPublicKeyInfo DER from `getPublicKey()`.
+    pub public_key: String,
+    /// COSE algorithm identifier.
+    pub algorithm: i64,
+    pub rp_id: String,
+    pub sign_count: u32,
+    #[serde(default)]
+    pub transports: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyConfig {
+    pub credentials: Vec<PasskeyCredential>,
+    /// Base64url WebAuthn user handle shared by every credential.
+    pub user_handle: String,
+    pub gated_categories: Vec<SensitiveCategory>,
+}
+
+impl Default for PasskeyConfig {
+    fn default() -> Self {
+        Self {
+            credentials: Vec::new(),
+            user_handle: b64url(&rand::random::<[u8; 16]>()),
+            gated_categories: vec![
+                SensitiveCategory::Transfers,
+                SensitiveCategory::KeyExport,
+                SensitiveCategory::SecuritySettings,
+            ],
+        }
+    }
+}
+
+/// Credential metadata safe to hand to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeySummary {
+    pub credential_id: String,
+    pub name: String,
+    pub algorithm: i64,
+    pub transports: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<&PasskeyCredential> for PasskeySummary {
+    fn from(credential: &PasskeyCredential) -> Self {
+        Self {
+            credential_id: credential.credential_id.clone(),
+            name: credential.name.clone(),
+            algorithm: credential.algorithm,
+            transports: credential.transports.clone(),
+            created_at: credential.created_at,
+            last_used_at: credential.last_used_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyStatus {
+    pub registered: usize,
+    pub gated_categories: Vec<SensitiveCategory>,
+    /// Whether gated commands are currently allowed without a new assertion.
+    pub present: bool,
+    pub present_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyRegistrationRequest {
+    pub name: String,
+    pub credential_id: String,
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub public_key: String,
+    pub algorithm: i64,
+    #[serde(default)]
+    pub transports: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyAssertion {
+    pub credential_id: String,
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CeremonyKind {
+    Registration,
+    Authentication,
+}
+
+impl CeremonyKind {
+    fn client_data_type(&self) -> &'static str {
+        match self {
+            CeremonyKind::Registration => "webauthn.create",
+            CeremonyKind::Authentication => "webauthn.get",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PendingChallenge {
+    kind: CeremonyKind,
+    rp_id: String,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+    origin: String,
+}
+
+struct AuthenticatorData {
+    rp_id_hash: [u8; 32],
+    flags: u8,
+    sign_count: u32,
+}
+
+pub struct PasskeyManager {
+    config: Mutex<PasskeyConfig>,
+    challenges: Mutex<HashMap<String, PendingChallenge>>,
+    last_verified_at: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl PasskeyManager {
+    pub fn new() -> Self {
+        Self {
+            config: Mutex::new(PasskeyConfig::default()),
+            challenges: Mutex::new(HashMap::new()),
+            last_verified_at: Mutex::new(None),
+        }
+    }
+
+    pub fn hydrate(&self, keystore: &Keystore) -> Result<(), PasskeyError> {
+        match keystore.retrieve_secret(PASSKEY_CONFIG_KEY) {
+            Ok(bytes) => {
+                let config: PasskeyConfig = serde_json::from_slice(bytes.as_ref())?;
+                *lock(&self.config)? = config;
+            }
+            Err(KeystoreError::NotFound) => {}
+            Err(err) => return Err(PasskeyError::Keystore(err)),
+        }
+        Ok(())
+    }
+
+    /// `PublicKeyCredentialCreationOptions` in the JSON form accepted by
+    /// `PublicKeyCredential.parseCreationOptionsFromJSON`.
+    pub fn registration_options(
+        &self,
+        user_name: &str,
+        rp_id: Option<String>,
+    ) -> Result<serde_json::Value, PasskeyError> {
+        let config = lock(&self.config)?.clone();
+        if !config.credentials.is_empty() {
+            self.require_presence(SensitiveCategory::SecuritySettings)?;
+        }
+        if config.credentials.len() >= MAX_CREDENTIALS {
+            return Err(PasskeyError::TooManyCredentials);
+        }
+
+        let rp_id = resolve_rp_id(rp_id)?;
+        let challenge = self.issue_challenge(CeremonyKind::Registration, &rp_id)?;
+        let exclude: Vec<_> = config
+            .credentials
+            .iter()
+            .map(|c| json!({ "type": "public-key", "id": c.credential_id, "transports": c.transports }))
+            .collect();
+
+        Ok(json!({
+            "challenge": challenge,
+            "rp": { "id": rp_id, "name": RP_NAME },
+            "user": {
+                "id": config.user_handle,
+                "name": user_name,
+                "displayName": user_name,
+            },
+            "pubKeyCredParams": [COSE_ES256, COSE_EDDSA, COSE_RS256]
+                .iter()
+                .map(|alg| json!({ "type": "public-key", "alg": alg }))
+                .collect::<Vec<_>>(),
+            "timeout": CHALLENGE_TTL_SECONDS * 1000,
+            "attestation": "none",
+            "excludeCredentials": exclude,
+            "authenticatorSelection": {
+                "residentKey": "preferred",
+                "userVerification": "required",
+            },
+        }))
+    }
+
+    pub fn register(
+        &self,
+        request: PasskeyRegistrationRequest,
+        keystore: &Keystore,
+    ) -> Result<PasskeySummary, PasskeyError> {
+        if algorithm_for(request.algorithm).is_none() {
+            return Err(PasskeyError::UnsupportedAlgorithm(request.algorithm));
+        }
+
+        let client_data_json = b64url_decode(&request.client_data_json)?;
+        let pending = self.consume_client_data(&client_data_json, CeremonyKind::Registration)?;
+        let auth_data = parse_authenticator_data(&b64url_decode(&request.authenticator_data)?)?;
+        check_authenticator_data(&auth_data, &pending.rp_id)?;
+
+        // Only the key itself is needed later; fail now rather than at first login.
+        spki_public_key(&b64url_decode(&request.public_key)?)?;
+
+        let credential = PasskeyCredential {
+            credential_id: request.credential_id,
+            name: normalize_name(&request.name),
+            public_key: request.public_key,
+            algorithm: request.algorithm,
+            rp_id: pending.rp_id,
+            sign_count: auth_data.sign_count,
+            transports: request.transports,
+            created_at: Utc::now(),
+            last_used_at: None,
+        };
+        let summary = PasskeySummary::from(&credential);
+
+        let mut config = lock(&self.config)?;
+        if config.credentials.len() >= MAX_CREDENTIALS {
+            return Err(PasskeyError::TooManyCredentials);
+        }
+        if config
+            .credentials
+            .iter()
+            .any(|c| c.credential_id == credential.credential_id)
+        {
+            return Err(PasskeyError::DuplicateCredential);
+        }
+        config.credentials.push(credential);
+        persist(&config, keystore)?;
+        drop(config);
+
+        // Registering requires user verification, so it counts as presence.
+        self.mark_present()?;
+        Ok(summary)
+    }
+
+    /// `PublicKeyCredentialRequestOptions` listing every registered key.
+    pub fn authentication_options(
+        &self,
+        rp_id: Option<String>,
+    ) -> Result<serde_json::Value, PasskeyError> {
+        let config = lock(&self.config)?.clone();
+        if config.credentials.is_empty() {
+            return Err(PasskeyError::NotRegistered);
+        }
+
+        let rp_id = resolve_rp_id(rp_id)?;
+        let challenge = self.issue_challenge(CeremonyKind::Authentication, &rp_id)?;
+        let allow: Vec<_> = config
+            .credentials
+            .iter()
+            .filter(|c| c.rp_id == rp_id)
+            .map(|c| json!({ "type": "public-key", "id": c.credential_id, "transports": c.transports }))
+            .collect();
+
+        Ok(json!({
+            "challenge": challenge,
+            "rpId": rp_id,
+            "timeout": CHALLENGE_TTL_SECONDS * 1000,
+            "allowCredentials": allow,
+            "userVerification": "required",
+        }))
+    }
+
+    pub fn authenticate(
+        &self,
+        assertion: &PasskeyAssertion,
+        keystore: &Keystore,
+    ) -> Result<PasskeySummary, PasskeyError> {
+        let client_data_json = b64url_decode(&assertion.client_data_json)?;
+        let raw_auth_data = b64url_decode(&assertion.authenticator_data)?;
+        let signature = b64url_decode(&assertion.signature)?;
+
+        let credential = lock(&self.config)?
+            .credentials
+            .iter()
+            .find(|c| c.credential_id == assertion.credential_id)
+            .cloned()
+            .ok_or(PasskeyError::UnknownCredential)?;
+
+        let pending = self.consume_client_data(&client_data_json, CeremonyKind::Authentication)?;
+        if pending.rp_id != credential.rp_id {
+            return Err(PasskeyError::InvalidRpId(pending.rp_id));
+        }
+        let auth_data = parse_authenticator_data(&raw_auth_data)?;
+        check_authenticator_data(&auth_data, &credential.rp_id)?;
+
+        let algorithm = algorithm_for(credential.algorithm)
+            .ok_or(PasskeyError::UnsupportedAlgorithm(credential.algorithm))?;
+        let spki = b64url_decode(&credential.public_key)?;
+        let public_key = spki_public_key(&spki)?;
+
+        let mut signed = raw_auth_data;
+        signed.extend_from_slice(&Sha256::digest(&client_data_json));
+        UnparsedPublicKey::new(algorithm, public_key)
+            .verify(&signed, &signature)
+            .map_err(|_| PasskeyError::InvalidSignature)?;
+
+        // Authenticators that don't count always report zero.
+        if (auth_data.sign_count != 0 || credential.sign_count != 0)
+            && auth_data.sign_count <= credential.sign_count
+        {
+            return Err(PasskeyError::CounterRegression);
+        }
+
+        let mut config = lock(&self.config)?;
+        let stored = config
+            .credentials
+            .iter_mut()
+            .find(|c| c.credential_id == credential.credential_id)
+            .ok_or(PasskeyError::UnknownCredential)?;
+        stored.sign_count = auth_data.sign_count;
+        stored.last_used_at = Some(Utc::now());
+        let summary = PasskeySummary::from(&*stored);
+        persist(&config, keystore)?;
+        drop(config);
+
+        self.mark_present()?;
+        Ok(summary)
+    }
+
+    pub fn list(&self) -> Result<Vec<PasskeySummary>, PasskeyError> {
+        Ok(lock(&self.config)?
+            .credentials
+            .iter()
+            .map(PasskeySummary::from)
+            .collect())
+    }
+
+    pub fn rename(
+        &self,
+        credential_id: &str,
+        name: &str,
+        keystore: &Keystore,
+    ) -> Result<PasskeySummary, PasskeyError> {
+        let mut config = lock(&self.config)?;
+        let credential = config
+            .credentials
+            .iter_mut()
+            .find(|c| c.credential_id == credential_id)
+            .ok_or(PasskeyError::UnknownCredential)?;
+        credential.name = normalize_name(name);
+        let summary = PasskeySummary::from(&*credential);
+        persist(&config, keystore)?;
+        Ok(summary)
+    }
+
+    pub fn remove(&self, credential_id: &str, keystore: &Keystore) -> Result<(), PasskeyError> {
+        self.require_presence(SensitiveCategory::SecuritySettings)?;
+
+        let mut config = lock(&self.config)?;
+        let before = config.credentials.len();
+        config
+            .credentials
+            .retain(|c| c.credential_id != credential_id);
+        if config.credentials.len() == before {
+            return Err(PasskeyError::UnknownCredential);
+        }
+        persist(&config, keystore)?;
+        if config.credentials.is_empty() {
+            *lock(&self.last_verified_at)? = None;
+        }
+        Ok(())
+    }
+
+    pub fn set_gated_categories(
+        &self,
+        categories: Vec<SensitiveCategory>,
+        keystore: &Keystore,
+    ) -> Result<PasskeyStatus, PasskeyError> {
+        self.require_presence(SensitiveCategory::SecuritySettings)?;
+
+        let mut unique = Vec::new();
+        for category in categories {
+            if !unique.contains(&category) {
+                unique.push(category);
+            }
+        }
+        {
+            let mut config = lock(&self.config)?;
+            config.gated_categories = unique;
+            persist(&config, keystore)?;
+        }
+        self.status()
+    }
+
+    pub fn status(&self) -> Result<PasskeyStatus, PasskeyError> {
+        let config = lock(&self.config)?;
+        let present_until = self.present_until()?;
+        Ok(PasskeyStatus {
+            registered: config.credentials.len(),
+            gated_categories: config.gated_categories.clone(),
+            present: present_until.is_some(),
+            present_until,
+        })
+    }
+
+    /// Passes when no passkey is registered, the category isn't gated, or a
+    /// passkey was verified within the presence window.
+    pub fn require_presence(&self, category: SensitiveCategory) -> Result<(), PasskeyError> {
+        {
+            let config = lock(&self.config)?;
+            if config.credentials.is_empty() || !config.gated_categories.contains(&category) {
+                return Ok(());
+            }
+        }
+        match self.present_until()? {
+            Some(_) => Ok(()),
+            None => Err(PasskeyError::PresenceRequired(category.as_str())),
+        }
+    }
+
+    /// Drops presence early, e.g. when the app is locked.
+    pub fn clear_presence(&self) -> Result<(), PasskeyError> {
+        *lock(&self.last_verified_at)? = None;
+        Ok(())
+    }
+
+    fn present_until(&self) -> Result<Option<DateTime<Utc>>, PasskeyError> {
+        let until =
+            lock(&self.last_verified_at)?.map(|at| at + Duration::seconds(PRESENCE_WINDOW_SECONDS));
+        Ok(until.filter(|until| *until > Utc::now()))
+    }
+
+    fn mark_present(&self) -> Result<(), PasskeyError> {
+        *lock(&self.last_verified_at)? = Some(Utc::now());
+        Ok(())
+    }
+
+    fn issue_challenge(&self, kind: CeremonyKind, rp_id: &str) -> Result<String, PasskeyError> {
+        let now = Utc::now();
+        let challenge = b64url(&rand::random::<[u8; 32]>());
+        let mut challenges = lock(&self.challenges)?;
+        challenges.retain(|_, pending| pending.expires_at > now);
+        challenges.insert(
+            challenge.clone(),
+            PendingChallenge {
+                kind,
+                rp_id: rp_id.to_string(),
+                expires_at: now + Duration::seconds(CHALLENGE_TTL_SECONDS),
+            },
+        );
+        Ok(challenge)
+    }
+
+    /// Checks `clientDataJSON` and burns the challenge it answers.
+    fn consume_client_data(
+        &self,
+        client_data_json: &[u8],
+        kind: CeremonyKind,
+    ) -> Result<PendingChallenge, PasskeyError> {
+        let client_data: ClientData = serde_json::from_slice(client_data_json)
+            .map_err(|_| PasskeyError::Malformed("clientDataJSON"))?;
+        if client_data.kind != kind.client_data_type() {
+            return Err(PasskeyError::Malformed("unexpected ceremony type"));
+        }
+        if !ALLOWED_ORIGINS.contains(&client_data.origin.as_str()) {
+            return Err(PasskeyError::InvalidOrigin(client_data.origin));
+        }
+
+        let pending = lock(&self.challenges)?
+            .remove(&client_data.challenge)
+            .ok_or(PasskeyError::InvalidChallenge)?;
+        if pending.kind != kind || pending.expires_at <= Utc::now() {
+            return Err(PasskeyError::InvalidChallenge);
+        }
+        Ok(pending)
+    }
+}
+
+impl Default for PasskeyManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> Result<MutexGuard<'_, T>, PasskeyError> {
+    mutex.lock().map_err(|_| PasskeyError::Internal)
+}
+
+fn persist(config: &PasskeyConfig, keystore: &Keystore) -> Result<(), PasskeyError> {
+    let bytes = serde_json::to_vec(config)?;
+    keystore.store_secret(PASSKEY_CONFIG_KEY, &bytes)?;
+    Ok(())
+}
+
+fn b64url(bytes: &[u8]) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn b64url_decode(value: &str) -> Result<Vec<u8>, PasskeyError> {
+    general_purpose::URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| PasskeyError::Malformed("base64url"))
+}
+
+fn normalize_name(name: &str) -> String {
+    let name = name.trim();
+    if name.is_empty() {
+        "Passkey".to_string()
+    } else {
+        name.chars().take(64).collect()
+    }
+}
+
+fn resolve_rp_id(rp_id: Option<String>) -> Result<String, PasskeyError> {
+    let rp_id = rp_id.unwrap_or_else(|| ALLOWED_RP_IDS[0].to_string());
+    if ALLOWED_RP_IDS.contains(&rp_id.as_str()) {
+        Ok(rp_id)
+    } else {
+        Err(PasskeyError::InvalidRpId(rp_id))
+    }
+}
+
+fn algorithm_for(cose_alg: i64) -> Option<&'static dyn VerificationAlgorithm> {
+    match cose_alg {
+        COSE_ES256 => Some(&signature::ECDSA_P256_SHA256_ASN1),
+        COSE_EDDSA => Some(&signature::ED25519),
+        COSE_RS256 => Some(&signature::RSA_PKCS1_2048_8192_SHA256),
+        _ => None,
+    }
+}
+
+fn parse_authenticator_data(data: &[u8]) -> Result<AuthenticatorData, PasskeyError> {
+    if data.len() < 37 {
+        return Err(PasskeyError::Malformed("authenticatorData too short"));
+    }
+    let mut rp_id_hash = [0u8; 32];
+    rp_id_hash.copy_from_slice(&data[..32]);
+    Ok(AuthenticatorData {
+        rp_id_hash,
+        flags: data[32],
+        sign_count: u32::from_be_bytes([data[33], data[34], data[35], data[36]]),
+    })
+}
+
+fn check_authenticator_data(data: &AuthenticatorData, rp_id: &str) -> Result<(), PasskeyError> {
+    if data.rp_id_hash[..] != Sha256::digest(rp_id.as_bytes())[..] {
+        return Err(PasskeyError::InvalidRpId(rp_id.to_string()));
+    }
+    let required = FLAG_USER_PRESENT | FLAG_USER_VERIFIED;
+    if data.flags & required != required {
+        return Err(PasskeyError::UserNotVerified);
+    }
+    Ok(())
+}
+
+/// Splits one DER TLV into (tag, contents, remainder).
+fn der_read(input: &[u8]) -> Result<(u8, &[u8], &[u8]), PasskeyError> {
+    let (&tag, rest) = input
+        .split_first()
+        .ok_or(PasskeyError::Malformed("public key DER"))?;
+    let (&first, rest) = rest
+        .split_first()
+        .ok_or(PasskeyError::Malformed("public key DER"))?;
+
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 2 || rest.len() < count {
+            return Err(PasskeyError::Malformed("public key DER"));
+        }
+        let len = rest[..count]
+            .iter()
+            .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        (len, &rest[count..])
+    };
+
+    if rest.len() < len {
+        return Err(PasskeyError::Malformed("public key DER"));
+    }
+    Ok((tag, &rest[..len], &rest[len..]))
+}
+
+/// Returns the key bytes inside a SubjectPublicKeyInfo, which is the form
+/// ring expects for every supported algorithm.
+fn spki_public_key(spki: &[u8]) -> Result<&[u8], PasskeyError> {
+    let (tag, body, _) = der_read(spki)?;
+    if tag != 0x30 {
+        return Err(PasskeyError::Malformed("public key DER"));
+    }
+    let (tag, _, rest) = der_read(body)?;
+    if tag != 0x30 {
+        return Err(PasskeyError::Malformed("public key DER"));
+    }
+    let (tag, bits, _) = der_read(rest)?;
+    match bits.split_first() {
+        Some((0, key)) if tag == 0x03 && !key.is_empty() => Ok(key),
+        _ => Err(PasskeyError::Malformed("public key DER")),
+    }
+}
+
+#[tauri::command]
+pub async fn passkey_registration_options(
+    user_name: String,
+    rp_id: Option<String>,
+    state: State<'_, PasskeyManager>,
+) -> Result<serde_json::Value, AppError> {
+    state
+        .registration_options(&user_name, rp_id)
+        .logged("passkey_registration_options")
+}
+
+#[tauri::command]
+pub async fn passkey_register(
+    request: PasskeyRegistrationRequest,
+    state: State<'_, PasskeyManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<PasskeySummary, AppError> {
+    state
+        .register(request, keystore.inner())
+        .logged("passkey_register")
+}
+
+#[tauri::command]
+pub async fn passkey_authentication_options(
+    rp_id: Option<String>,
+    state: State<'_, PasskeyManager>,
+) -> Result<serde_json::Value, AppError> {
+    state
+        .authentication_options(rp_id)
+        .logged("passkey_authentication_options")
+}
+
+#[tauri::command]
+pub async fn passkey_authenticate(
+    assertion: PasskeyAssertion,
+    state: State<'_, PasskeyManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<PasskeySummary, AppError> {
+    state
+        .authenticate(&assertion, keystore.inner())
+        .logged("passkey_authenticate")
+}
+
+#[tauri::command]
+pub async fn passkey_list(
+    state: State<'_, PasskeyManager>,
+) -> Result<Vec<PasskeySummary>, AppError> {
+    state.list().logged("passkey_list")
+}
+
+#[tauri::command]
+pub async fn passkey_rename(
+    credential_id: String,
+    name: String,
+    state: State<'_, PasskeyManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<PasskeySummary, AppError> {
+    state
+        .rename(&credential_id, &name, keystore.inner())
+        .logged("passkey_rename")
+}
+
+#[tauri::command]
+pub async fn passkey_remove(
+    credential_id: String,
+    state: State<'_, PasskeyManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<(), AppError> {
+    state
+        .remove(&credential_id, keystore.inner())
+        .logged("passkey_remove")
+}
+
+#[tauri::command]
+pub async fn passkey_status(state: State<'_, PasskeyManager>) -> Result<PasskeyStatus, AppError> {
+    state.status().logged("passkey_status")
+}
+
+#[tauri::command]
+pub async fn passkey_set_gated_categories(
+    categories: Vec<SensitiveCategory>,
+    state: State<'_, PasskeyManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<PasskeyStatus, AppError> {
+    state
+        .set_gated_categories(categories, keystore.inner())
+        .logged("passkey_set_gated_categories")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client_data(kind: &str, challenge: &str, origin: &str) -> Vec<u8> {
+        serde_json::to_vec(&json!({ "type": kind, "challenge": challenge, "origin": origin }))
+            .unwrap()
+    }
+
+    #[test]
+    fn extracts_key_from_spki() {
+        // Ed25519 SubjectPublicKeyInfo with a 32-byte key of 0xAB.
+        let mut spki = vec![
+            0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+        ];
+        spki.extend_from_slice(&[0xab; 32]);
+
+        assert_eq!(spki_public_key(&spki).unwrap(), &[0xab; 32][..]);
+        assert!(spki_public_key(&spki[..20]).is_err());
+    }
+
+    #[test]
+    fn authenticator_data_requires_rp_hash_and_verification_flags() {
+        let mut raw = Sha256::digest(b"localhost").to_vec();
+        raw.push(FLAG_USER_PRESENT | FLAG_USER_VERIFIED);
+        raw.extend_from_slice(&7u32.to_be_bytes());
+
+        let data = parse_authenticator_data(&raw).unwrap();
+        assert_eq!(data.sign_count, 7);
+        assert!(check_authenticator_data(&data, "localhost").is_ok());
+        assert!(check_authenticator_data(&data, "tauri.localhost").is_err());
+
+        raw[32] = FLAG_USER_PRESENT;
+        let data = parse_authenticator_data(&raw).unwrap();
+        assert!(matches!(
+            check_authenticator_data(&data, "localhost"),
+            Err(PasskeyError::UserNotVerified)
+        ));
+    }
+
+    #[test]
+    fn challenges_are_single_use_and_bound_to_ceremony() {
+        let manager = PasskeyManager::new();
+        let challenge = manager
+            .issue_challenge(CeremonyKind::Authentication, "localhost")
+            .unwrap();
+
+        let wrong_type = client_data("webauthn.create", &challenge, "tauri://localhost");
+        assert!(manager
+            .consume_client_data(&wrong_type, CeremonyKind::Registration)
+            .is_err());
+
+        let foreign = client_data("webauthn.get", &challenge, "https://evil.example");
+        assert!(matches!(
+            manager.consume_client_data(&foreign, CeremonyKind::Authentication),
+            Err(PasskeyError::InvalidOrigin(_))
+        ));
+
+        let good = client_data("webauthn.get", &challenge, "tauri://localhost");
+        assert!(manager
+            .consume_client_data(&good, CeremonyKind::Authentication)
+            .is_ok());
+        assert!(matches!(
+            manager.consume_client_data(&good, CeremonyKind::Authentication),
+            Err(PasskeyError::InvalidChallenge)
+        ));
+    }
+
+    #[test]
+    fn presence_only_enforced_once_a_passkey_exists() {
+        let manager = PasskeyManager::new();
+        assert!(manager
+            .require_presence(SensitiveCategory::Transfers)
+            .is_ok());
+
+        lock(&manager.config)
+            .unwrap()
+            .credentials
+            .push(PasskeyCredential {
+                credential_id: "cred".to_string(),
+                name: "Key".to_string(),
+                public_key: String::new(),
+                algorithm: COSE_ES256,
+                rp_id: "localhost".to_string(),
+                sign_count: 0,
+                transports: Vec::new(),
+                created_at: Utc::now(),
+                last_used_at: None,
+            });
+        assert!(manager
+            .require_presence(SensitiveCategory::Transfers)
+            .is_err());
+        assert!(manager.require_presence(SensitiveCategory::Trading).is_ok());
+
+        manager.mark_present().unwrap();
+        assert!(manager
+            .require_presence(SensitiveCategory::Transfers)
+            .is_ok());
+    }
+}
@@ -8,11 +8,15 @@ use tauri::State;
 use uuid::Uuid;
 use zeroize::{Zeroize, Zeroizing};
 
+use crate::errors::{AppError, CommandResultExt};
 use crate::security::keystore::{Keystore, KeystoreError};
 
 const JWT_SECRET_KEY: &str = "jwt-signing-key";
 const SESSION_STATE_KEY: &str = "session-state";
+const SESSION_CONFIG_KEY: &str = "session-config";
 const DEFAULT_SESSION_TIMEOUT_MINUTES: u64 = 15;
+const MIN_SESSION_TIMEOUT_MINUTES: u64 = 1;
+const MAX_SESSION_TIMEOUT_MINUTES: u64 = 24 * 60;
 const SESSION_WARNING_SECONDS: u64 = 60;
 
 #[derive(Debug, thiserror::Error)]
@@ -23,6 +27,8 @@ pub enum SessionError {
     InvalidToken,
     #[error("no active session")]
     NoSession,
+    #[error("idle timeout must be between 1 and 1440 minutes, got {0}")]
+    InvalidTimeout(u64),
     #[error("keystore error: {0}")]
     Keystore(#[from] KeystoreError),
     #[error("jwt error: {0}")]
@@ -33,6 +39,22 @@ pub enum SessionError {
     Internal,
 }
 
+impl From<SessionError> for AppError {
+    fn from(err: SessionError) -> Self {
+        match err {
+            SessionError::Serialization(e) => AppError::Serialization(e),
+            err
+            @ (SessionError::Expired | SessionError::InvalidToken | SessionError::NoSession) => {
+                AppError::Unauthorized(err.to_string())
+            }
+            err @ SessionError::InvalidTimeout(_) => AppError::Validation(err.to_string()),
+            err @ (SessionError::Keystore(_) | SessionError::Jwt(_) | SessionError::Internal) => {
+                AppError::Generic(err.to_string())
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionClaims {
@@ -72,18 +94,68 @@ pub struct CreateSessionRequest {
     pub timeout_minutes: Option<u64>,
 }
 
+/// Settings that outlive any single session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionConfig {
+    /// Minutes without activity before a session lapses. Applied to new
+    /// sessions and to the current one when changed.
+    pub idle_timeout_minutes: u64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout_minutes: DEFAULT_SESSION_TIMEOUT_MINUTES,
+        }
+    }
+}
+
+/// The keystore calls the session manager makes, so it can be exercised
+/// against an in-memory store in tests.
+pub trait SessionStore {
+    fn retrieve_secret(&self, key: &str) -> Result<Zeroizing<Vec<u8>>, KeystoreError>;
+    fn store_secret(&self, key: &str, secret: &[u8]) -> Result<(), KeystoreError>;
+    fn remove_secret(&self, key: &str) -> Result<(), KeystoreError>;
+}
+
+impl SessionStore for Keystore {
+    fn retrieve_secret(&self, key: &str) -> Result<Zeroizing<Vec<u8>>, KeystoreError> {
+        Keystore::retrieve_secret(self, key)
+    }
+
+    fn store_secret(&self, key: &str, secret: &[u8]) -> Result<(), KeystoreError> {
+        Keystore::store_secret(self, key, secret)
+    }
+
+    fn remove_secret(&self, key: &str) -> Result<(), KeystoreError> {
+        Keystore::remove_secret(self, key)
+    }
+}
+
 pub struct SessionManager {
     current_session: Mutex<Option<SessionState>>,
+    config: Mutex<SessionConfig>,
 }
 
 impl SessionManager {
     pub fn new() -> Self {
         Self {
             current_session: Mutex::new(None),
+            config: Mutex::new(SessionConfig::default()),
         }
     }
 
-    pub fn hydrate(&self, keystore: &Keystore) -> Result<(), SessionError> {
+    pub fn hydrate(&self, keystore: &impl SessionStore) -> Result<(), SessionError> {
+        match keystore.retrieve_secret(SESSION_CONFIG_KEY) {
+            Ok(payload) => {
+                let config: SessionConfig = serde_json::from_slice(payload.as_ref())?;
+                *self.config.lock().map_err(|_| SessionError::Internal)? = config;
+            }
+            Err(KeystoreError::NotFound) => {}
+            Err(err) => return Err(SessionError::Keystore(err)),
+        }
+
         match keystore.retrieve_secret(SESSION_STATE_KEY) {
             Ok(payload) => {
                 let session: SessionState = serde_json::from_slice(payload.as_ref())?;
@@ -104,9 +176,12 @@ impl SessionManager {
         &self,
         user_id: String,
         timeout_minutes: Option<u64>,
-        keystore: &Keystore,
+        keystore: &impl SessionStore,
     ) -> Result<SessionState, SessionError> {
-        let timeout = timeout_minutes.unwrap_or(DEFAULT_SESSION_TIMEOUT_MINUTES);
+        let timeout = match timeout_minutes {
+            Some(minutes) => Self::validate_timeout(minutes)?,
+            None => self.idle_timeout_minutes()?,
+        };
         let now = Utc::now();
         let session_id = Uuid::new_v4().to_string();
 
@@ -144,13 +219,15 @@ impl SessionManager {
         Ok(session)
     }
 
-    pub fn renew_session(&self, keystore: &Keystore) -> Result<SessionState, SessionError> {
+    pub fn renew_session(
+        &self,
+        keystore: &impl SessionStore,
+    ) -> Result<SessionState, SessionError> {
         let mut guard = self.lock_session()?;
-        let current = guard.as_mut().ok_or(SessionError::NoSession)?;
-
-        if !Self::is_session_valid(current) {
+        if Self::expire_if_lapsed(&mut guard, keystore)? {
             return Err(SessionError::Expired);
         }
+        let current = guard.as_mut().ok_or(SessionError::NoSession)?;
 
         let now = Utc::now();
         let new_expiry = now + chrono::Duration::minutes(current.timeout_minutes as i64);
@@ -183,7 +260,7 @@ impl SessionManager {
         Ok(current.clone())
     }
 
-    pub fn end_session(&self, keystore: &Keystore) -> Result<(), SessionError> {
+    pub fn end_session(&self, keystore: &impl SessionStore) -> Result<(), SessionError> {
         let mut guard = self.lock_session()?;
         *guard = None;
         let _ = keystore.remove_secret(SESSION_STATE_KEY);
@@ -191,6 +268,7 @@ impl SessionManager {
     }
 
     pub fn get_status(&self) -> Result<SessionStatus, SessionError> {
+        let idle_timeout = self.idle_timeout_minutes()?;
         let guard = self.lock_session()?;
         if let Some(session) = guard.as_ref() {
             let active = Self::is_session_valid(session);
@@ -208,35 +286,71 @@ impl SessionManager {
                 session_id: None,
                 expires_at: None,
                 last_activity: None,
-                timeout_minutes: DEFAULT_SESSION_TIMEOUT_MINUTES,
+                timeout_minutes: idle_timeout,
                 warning_threshold_seconds: SESSION_WARNING_SECONDS,
             })
         }
     }
 
-    pub fn verify_session(&self) -> Result<bool, SessionError> {
-        let guard = self.lock_session()?;
-        if let Some(session) = guard.as_ref() {
-            Ok(Self::is_session_valid(session))
-        } else {
-            Ok(false)
+    /// Checks that a session is live and that its token was signed with
+    /// this install's key, so a hand-edited keystore entry is not trusted.
+    pub fn verify_session(&self, keystore: &impl SessionStore) -> Result<bool, SessionError> {
+        let mut guard = self.lock_session()?;
+        Self::expire_if_lapsed(&mut guard, keystore)?;
+        let Some(session) = guard.as_ref() else {
+            return Ok(false);
+        };
+
+        let secret = self.get_jwt_secret(keystore)?;
+        let mut validation = Validation::new(Algorithm::HS256);
+        // Expiry is tracked by `expires_at`, which slides with activity
+        // between renewals; the token only has to be genuine.
+        validation.validate_exp = false;
+        match decode::<SessionClaims>(
+            &session.token,
+            &DecodingKey::from_secret(secret.as_ref()),
+            &validation,
+        ) {
+            Ok(data) => Ok(data.claims.session_id == session.session_id),
+            Err(_) => Ok(false),
         }
     }
 
-    pub fn update_activity(&self, keystore: &Keystore) -> Result<(), SessionError> {
+    /// Records user activity, pushing the idle deadline out by the session's
+    /// timeout.
+    pub fn update_activity(&self, keystore: &impl SessionStore) -> Result<(), SessionError> {
         let mut guard = self.lock_session()?;
+        if Self::expire_if_lapsed(&mut guard, keystore)? {
+            return Err(SessionError::Expired);
+        }
         if let Some(session) = guard.as_mut() {
-            session.last_activity = Utc::now();
+            let now = Utc::now();
+            session.last_activity = now;
+            session.expires_at = now + chrono::Duration::minutes(session.timeout_minutes as i64);
             self.persist_session(keystore, session)?;
         }
         Ok(())
     }
 
+    pub fn config(&self) -> Result<SessionConfig, SessionError> {
+        self.config
+            .lock()
+            .map(|config| config.clone())
+            .map_err(|_| SessionError::Internal)
+    }
+
     pub fn configure_timeout(
         &self,
         timeout_minutes: u64,
-        keystore: &Keystore,
+        keystore: &impl SessionStore,
     ) -> Result<(), SessionError> {
+        let timeout_minutes = Self::validate_timeout(timeout_minutes)?;
+        let config = SessionConfig {
+            idle_timeout_minutes: timeout_minutes,
+        };
+        keystore.store_secret(SESSION_CONFIG_KEY, &serde_json::to_vec(&config)?)?;
+        *self.config.lock().map_err(|_| SessionError::Internal)? = config;
+
         let mut guard = self.lock_session()?;
         if let Some(session) = guard.as_mut() {
             session.timeout_minutes = timeout_minutes;
@@ -247,7 +361,10 @@ impl SessionManager {
         Ok(())
     }
 
-    fn get_jwt_secret(&self, keystore: &Keystore) -> Result<Zeroizing<Vec<u8>>, SessionError> {
+    fn get_jwt_secret(
+        &self,
+        keystore: &impl SessionStore,
+    ) -> Result<Zeroizing<Vec<u8>>, SessionError> {
         match keystore.retrieve_secret(JWT_SECRET_KEY) {
             Ok(secret) => Ok(secret),
             Err(KeystoreError::NotFound) => {
@@ -262,7 +379,7 @@ impl SessionManager {
 
     fn persist_session(
         &self,
-        keystore: &Keystore,
+        keystore: &impl SessionStore,
         session: &SessionState,
     ) -> Result<(), SessionError> {
         let payload = serde_json::to_vec(session)?;
@@ -270,6 +387,32 @@ impl SessionManager {
         Ok(())
     }
 
+    fn idle_timeout_minutes(&self) -> Result<u64, SessionError> {
+        Ok(self.config()?.idle_timeout_minutes)
+    }
+
+    fn validate_timeout(minutes: u64) -> Result<u64, SessionError> {
+        if (MIN_SESSION_TIMEOUT_MINUTES..=MAX_SESSION_TIMEOUT_MINUTES).contains(&minutes) {
+            Ok(minutes)
+        } else {
+            Err(SessionError::InvalidTimeout(minutes))
+        }
+    }
+
+    /// Drops a lapsed session from memory and the keystore. Returns whether
+    /// one was dropped.
+    fn expire_if_lapsed(
+        guard: &mut MutexGuard<'_, Option<SessionState>>,
+        keystore: &impl SessionStore,
+    ) -> Result<bool, SessionError> {
+        if guard.as_ref().is_some_and(|s| !Self::is_session_valid(s)) {
+            **guard = None;
+            let _ = keystore.remove_secret(SESSION_STATE_KEY);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
     fn is_session_valid(session: &SessionState) -> bool {
         let now = Utc::now();
         now < session.expires_at
@@ -287,50 +430,53 @@ pub async fn session_create(
     request: CreateSessionRequest,
     state: State<'_, SessionManager>,
     keystore: State<'_, Keystore>,
-) -> Result<SessionState, String> {
+) -> Result<SessionState, AppError> {
     state
         .create_session(request.user_id, request.timeout_minutes, keystore.inner())
-        .map_err(|e| e.to_string())
+        .logged("session_create")
 }
 
 #[tauri::command]
 pub async fn session_renew(
     state: State<'_, SessionManager>,
     keystore: State<'_, Keystore>,
-) -> Result<SessionState, String> {
+) -> Result<SessionState, AppError> {
     state
         .renew_session(keystore.inner())
-        .map_err(|e| e.to_string())
+        .logged("session_renew")
 }
 
 #[tauri::command]
 pub async fn session_end(
     state: State<'_, SessionManager>,
     keystore: State<'_, Keystore>,
-) -> Result<(), String> {
-    state
-        .end_session(keystore.inner())
-        .map_err(|e| e.to_string())
+) -> Result<(), AppError> {
+    state.end_session(keystore.inner()).logged("session_end")
 }
 
 #[tauri::command]
-pub async fn session_status(state: State<'_, SessionManager>) -> Result<SessionStatus, String> {
-    state.get_status().map_err(|e| e.to_string())
+pub async fn session_status(state: State<'_, SessionManager>) -> Result<SessionStatus, AppError> {
+    state.get_status().logged("session_status")
 }
 
 #[tauri::command]
-pub async fn session_verify(state: State<'_, SessionManager>) -> Result<bool, String> {
-    state.verify_session().map_err(|e| e.to_string())
+pub async fn session_verify(
+    state: State<'_, SessionManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<bool, AppError> {
+    state
+        .verify_session(keystore.inner())
+        .logged("session_verify")
 }
 
 #[tauri::command]
 pub async fn session_update_activity(
     state: State<'_, SessionManager>,
     keystore: State<'_, Keystore>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     state
         .update_activity(keystore.inner())
-        .map_err(|e| e.to_string())
+        .logged("session_update_activity")
 }
 
 #[tauri::command]
@@ -338,8 +484,175 @@ pub async fn session_configure_timeout(
     timeout_minutes: u64,
     state: State<'_, SessionManager>,
     keystore: State<'_, Keystore>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     state
         .configure_timeout(timeout_minutes, keystore.inner())
-        .map_err(|e| e.to_string())
+        .logged("session_configure_timeout")
+}
+
+#[tauri::command]
+pub async fn session_get_config(
+    state: State<'_, SessionManager>,
+) -> Result<SessionConfig, AppError> {
+    state.config().logged("session_get_config")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct MemoryStore(Mutex<HashMap<String, Vec<u8>>>);
+
+    impl SessionStore for MemoryStore {
+        fn retrieve_secret(&self, key: &str) -> Result<Zeroizing<Vec<u8>>, KeystoreError> {
+            self.0
+                .lock()
+                .unwrap()
+                .get(key)
+                .cloned()
+                .map(Zeroizing::new)
+                .ok_or(KeystoreError::NotFound)
+        }
+
+        fn store_secret(&self, key: &str, secret: &[u8]) -> Result<(), KeystoreError> {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(key.to_string(), secret.to_vec());
+            Ok(())
+        }
+
+        fn remove_secret(&self, key: &str) -> Result<(), KeystoreError> {
+            self.0.lock().unwrap().remove(key);
+            Ok(())
+        }
+    }
+
+    fn backdate(manager: &SessionManager, by: chrono::Duration) {
+        let mut guard = manager.lock_session().unwrap();
+        let session = guard.as_mut().unwrap();
+        session.last_activity -= by;
+        session.expires_at -= by;
+    }
+
+    #[test]
+    fn timeout_must_be_within_bounds() {
+        assert!(matches!(
+            SessionManager::validate_timeout(0),
+            Err(SessionError::InvalidTimeout(0))
+        ));
+        assert_eq!(SessionManager::validate_timeout(1).unwrap(), 1);
+        assert_eq!(SessionManager::validate_timeout(1440).unwrap(), 1440);
+        assert!(SessionManager::validate_timeout(1441).is_err());
+
+        let store = MemoryStore::default();
+        let manager = SessionManager::new();
+        assert!(manager
+            .create_session("user".to_string(), Some(0), &store)
+            .is_err());
+        assert!(manager.configure_timeout(1441, &store).is_err());
+        assert_eq!(
+            manager.config().unwrap().idle_timeout_minutes,
+            DEFAULT_SESSION_TIMEOUT_MINUTES
+        );
+    }
+
+    #[test]
+    fn activity_slides_the_deadline_until_it_lapses() {
+        let store = MemoryStore::default();
+        let manager = SessionManager::new();
+        manager
+            .create_session("user".to_string(), Some(5), &store)
+            .unwrap();
+
+        backdate(&manager, chrono::Duration::minutes(3));
+        let before = manager.get_status().unwrap().expires_at.unwrap();
+        manager.update_activity(&store).unwrap();
+        let after = manager.get_status().unwrap().expires_at.unwrap();
+        assert!(after - before >= chrono::Duration::minutes(3));
+        assert!(after <= Utc::now() + chrono::Duration::minutes(5));
+
+        backdate(&manager, chrono::Duration::minutes(6));
+        assert!(matches!(
+            manager.update_activity(&store),
+            Err(SessionError::Expired)
+        ));
+        assert!(manager.get_status().unwrap().session_id.is_none());
+        assert!(store.retrieve_secret(SESSION_STATE_KEY).is_err());
+        assert!(matches!(
+            manager.renew_session(&store),
+            Err(SessionError::NoSession)
+        ));
+    }
+
+    #[test]
+    fn tampered_tokens_fail_verification() {
+        let store = MemoryStore::default();
+        let manager = SessionManager::new();
+        let session = manager
+            .create_session("user".to_string(), None, &store)
+            .unwrap();
+        assert!(manager.verify_session(&store).unwrap());
+
+        let claims = SessionClaims {
+            sub: "user".to_string(),
+            session_id: session.session_id.clone(),
+            exp: session.expires_at.timestamp() as u64,
+            iat: session.created_at.timestamp() as u64,
+            nbf: session.created_at.timestamp() as u64,
+        };
+        let foreign = encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(b"not this install's key"),
+        )
+        .unwrap();
+        manager.lock_session().unwrap().as_mut().unwrap().token = foreign;
+        assert!(!manager.verify_session(&store).unwrap());
+
+        // Genuine signature, but issued for another session.
+        let secret = store.retrieve_secret(JWT_SECRET_KEY).unwrap();
+        let other = encode(
+            &Header::new(Algorithm::HS256),
+            &SessionClaims {
+                session_id: Uuid::new_v4().to_string(),
+                ..claims
+            },
+            &EncodingKey::from_secret(secret.as_ref()),
+        )
+        .unwrap();
+        manager.lock_session().unwrap().as_mut().unwrap().token = other;
+        assert!(!manager.verify_session(&store).unwrap());
+    }
+
+    #[test]
+    fn hydrate_restores_config_and_live_session() {
+        let store = MemoryStore::default();
+        let manager = SessionManager::new();
+        manager.configure_timeout(30, &store).unwrap();
+        let session = manager
+            .create_session("user".to_string(), None, &store)
+            .unwrap();
+        assert_eq!(session.timeout_minutes, 30);
+
+        let restored = SessionManager::new();
+        restored.hydrate(&store).unwrap();
+        assert_eq!(restored.config().unwrap().idle_timeout_minutes, 30);
+        let status = restored.get_status().unwrap();
+        assert!(status.active);
+        assert_eq!(status.session_id, Some(session.session_id));
+        assert!(restored.verify_session(&store).unwrap());
+
+        backdate(&manager, chrono::Duration::minutes(31));
+        let lapsed = manager.lock_session().unwrap().clone().unwrap();
+        manager.persist_session(&store, &lapsed).unwrap();
+
+        let restored = SessionManager::new();
+        restored.hydrate(&store).unwrap();
+        assert!(restored.get_status().unwrap().session_id.is_none());
+        assert!(store.retrieve_secret(SESSION_STATE_KEY).is_err());
+        assert_eq!(restored.config().unwrap().idle_timeout_minutes, 30);
+    }
 }
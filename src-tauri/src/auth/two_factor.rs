@@ -10,17 +10,26 @@ use sha1::Sha1;
 use sha2::{Digest, Sha256};
 use tauri::State;
 
+use super::command_policy::CommandPolicyManager;
+use crate::errors::{AppError, CommandResultExt};
 use crate::security::keystore::{Keystore, KeystoreError};
 
 type HmacSha1 = Hmac<Sha1>;
 
 const TOTP_SECRET_KEY: &str = "totp-secret";
+const TOTP_PENDING_SECRET_KEY: &str = "totp-pending-secret";
 const TOTP_CONFIG_KEY: &str = "totp-config";
 const TOTP_ISSUER: &str = "EclipseMarketPro";
 const TOTP_DIGITS: u32 = 6;
 const TOTP_STEP: u64 = 30;
 const BACKUP_CODE_COUNT: usize = 10;
 const BACKUP_CODE_LENGTH: usize = 10;
+const DEFAULT_DRIFT_STEPS: u8 = 1;
+const MAX_DRIFT_STEPS: u8 = 3;
+/// Native-asset amount at or above which a transfer needs a 2FA code.
+const DEFAULT_LARGE_TRANSFER_THRESHOLD: f64 = 10.0;
+/// An enrollment that is never confirmed is discarded after this long.
+const PENDING_ENROLLMENT_MINUTES: i64 = 10;
 
 #[derive(Debug, thiserror::Error)]
 pub enum TwoFactorError {
@@ -28,6 +37,14 @@ pub enum TwoFactorError {
     NotEnrolled,
     #[error("2FA already enrolled")]
     AlreadyEnrolled,
+    #[error("no pending 2FA enrollment to confirm")]
+    NoPendingEnrollment,
+    #[error("2FA code required")]
+    CodeRequired,
+    #[error("drift window must be at most 3 steps")]
+    InvalidDriftWindow,
+    #[error("large transfer threshold must be a non-negative amount")]
+    InvalidThreshold,
     #[error("invalid TOTP code")]
     InvalidCode,
     #[error("invalid backup code")]
@@ -42,6 +59,26 @@ pub enum TwoFactorError {
     Internal,
 }
 
+impl From<TwoFactorError> for AppError {
+    fn from(err: TwoFactorError) -> Self {
+        match err {
+            TwoFactorError::Serialization(e) => AppError::Serialization(e),
+            err @ (TwoFactorError::NotEnrolled
+            | TwoFactorError::AlreadyEnrolled
+            | TwoFactorError::NoPendingEnrollment) => AppError::Conflict(err.to_string()),
+            err @ (TwoFactorError::InvalidDriftWindow | TwoFactorError::InvalidThreshold) => {
+                AppError::Validation(err.to_string())
+            }
+            err @ (TwoFactorError::CodeRequired
+            | TwoFactorError::InvalidCode
+            | TwoFactorError::InvalidBackupCode) => AppError::Unauthorized(err.to_string()),
+            err @ (TwoFactorError::Keystore(_)
+            | TwoFactorError::QrGeneration
+            | TwoFactorError::Internal) => AppError::Generic(err.to_string()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TwoFactorConfig {
@@ -49,6 +86,27 @@ pub struct TwoFactorConfig {
     pub backup_code_hashes: Vec<String>,
     pub used_backup_code_hashes: Vec<String>,
     pub enrolled_at: Option<DateTime<Utc>>,
+    /// Set between `enroll` and `confirm_enrollment`.
+    #[serde(default)]
+    pub pending_enrollment_at: Option<DateTime<Utc>>,
+    /// Time steps either side of now that still accept a code, to absorb
+    /// clock skew between this machine and the authenticator.
+    #[serde(default = "default_drift_steps")]
+    pub drift_steps: u8,
+    /// Last time step a code was accepted for; codes at or before it are
+    /// rejected so an observed code cannot be replayed.
+    #[serde(default)]
+    pub last_used_step: Option<u64>,
+    #[serde(default = "default_large_transfer_threshold")]
+    pub large_transfer_threshold: f64,
+}
+
+fn default_drift_steps() -> u8 {
+    DEFAULT_DRIFT_STEPS
+}
+
+fn default_large_transfer_threshold() -> f64 {
+    DEFAULT_LARGE_TRANSFER_THRESHOLD
 }
 
 impl Default for TwoFactorConfig {
@@ -58,6 +116,10 @@ impl Default for TwoFactorConfig {
             backup_code_hashes: Vec::new(),
             used_backup_code_hashes: Vec::new(),
             enrolled_at: None,
+            pending_enrollment_at: None,
+            drift_steps: DEFAULT_DRIFT_STEPS,
+            last_used_step: None,
+            large_transfer_threshold: DEFAULT_LARGE_TRANSFER_THRESHOLD,
         }
     }
 }
@@ -66,6 +128,7 @@ impl Default for TwoFactorConfig {
 #[serde(rename_all = "camelCase")]
 pub struct TwoFactorEnrollment {
     pub secret: String,
+    pub provisioning_uri: String,
     pub qr_code: String,
     pub backup_codes: Vec<String>,
     pub manual_entry_key: String,
@@ -77,6 +140,9 @@ pub struct TwoFactorStatus {
     pub enrolled: bool,
     pub enrolled_at: Option<DateTime<Utc>>,
     pub backup_codes_remaining: usize,
+    pub pending_enrollment: bool,
+    pub drift_steps: u8,
+    pub large_transfer_threshold: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -127,43 +193,116 @@ impl TwoFactorManager {
             }
         }
 
+        // The secret stays pending until the user proves their authenticator
+        // produces matching codes; until then 2FA is not enforced.
         let secret = Self::generate_secret();
         let secret_bytes = secret.clone().into_bytes();
-        keystore.store_secret(TOTP_SECRET_KEY, &secret_bytes)?;
+        keystore.store_secret(TOTP_PENDING_SECRET_KEY, &secret_bytes)?;
 
         let backup_codes = Self::generate_backup_codes();
         let backup_hashes: Vec<String> = backup_codes.iter().map(|code| hash_code(code)).collect();
 
-        let qr_code = Self::generate_qr_code(user_id, &secret)?;
+        let provisioning_uri = provisioning_uri(user_id, &secret);
+        let qr_code = Self::generate_qr_code(&provisioning_uri)?;
 
         {
             let mut config = self.lock_config()?;
-            config.enrolled = true;
             config.backup_code_hashes = backup_hashes;
             config.used_backup_code_hashes.clear();
-            config.enrolled_at = Some(Utc::now());
+            config.pending_enrollment_at = Some(Utc::now());
+            config.last_used_step = None;
             self.persist_config(keystore, &config)?;
         }
 
         Ok(TwoFactorEnrollment {
             secret: secret.clone(),
+            provisioning_uri,
             qr_code,
             backup_codes,
             manual_entry_key: secret,
         })
     }
 
+    /// Completes enrollment once the user enters a code from the freshly
+    /// provisioned authenticator.
+    pub fn confirm_enrollment(
+        &self,
+        code: &str,
+        keystore: &Keystore,
+    ) -> Result<(), TwoFactorError> {
+        let mut config = self.lock_config()?;
+        if config.enrolled {
+            return Err(TwoFactorError::AlreadyEnrolled);
+        }
+        let pending_since = config
+            .pending_enrollment_at
+            .ok_or(TwoFactorError::NoPendingEnrollment)?;
+        if Utc::now() - pending_since > chrono::Duration::minutes(PENDING_ENROLLMENT_MINUTES) {
+            *config = TwoFactorConfig::default();
+            self.persist_config(keystore, &config)?;
+            let _ = keystore.remove_secret(TOTP_PENDING_SECRET_KEY);
+            return Err(TwoFactorError::NoPendingEnrollment);
+        }
+
+        let secret = Self::decode_secret(keystore, TOTP_PENDING_SECRET_KEY)?;
+        let step = matching_step(&secret, &normalize_code(code), config.drift_steps, None)?
+            .ok_or(TwoFactorError::InvalidCode)?;
+
+        let secret_bytes = keystore.retrieve_secret(TOTP_PENDING_SECRET_KEY)?;
+        keystore.store_secret(TOTP_SECRET_KEY, secret_bytes.as_ref())?;
+        let _ = keystore.remove_secret(TOTP_PENDING_SECRET_KEY);
+
+        config.enrolled = true;
+        config.enrolled_at = Some(Utc::now());
+        config.pending_enrollment_at = None;
+        config.last_used_step = Some(step);
+        self.persist_config(keystore, &config)?;
+        Ok(())
+    }
+
     pub fn status(&self) -> Result<TwoFactorStatus, TwoFactorError> {
         let config = self.lock_config()?;
         Ok(TwoFactorStatus {
             enrolled: config.enrolled,
             enrolled_at: config.enrolled_at,
             backup_codes_remaining: config.backup_code_hashes.len(),
+            pending_enrollment: config.pending_enrollment_at.is_some(),
+            drift_steps: config.drift_steps,
+            large_transfer_threshold: config.large_transfer_threshold,
         })
     }
 
+    pub fn is_enrolled(&self) -> Result<bool, TwoFactorError> {
+        Ok(self.lock_config()?.enrolled)
+    }
+
+    pub fn is_large_transfer(&self, amount: f64) -> Result<bool, TwoFactorError> {
+        Ok(amount >= self.lock_config()?.large_transfer_threshold)
+    }
+
+    /// Enforcement hook for sensitive commands. Passes when 2FA is not
+    /// enrolled; otherwise the caller must supply a valid TOTP or backup
+    /// code, which is consumed.
+    pub fn require_code(
+        &self,
+        code: Option<&str>,
+        keystore: &Keystore,
+    ) -> Result<(), TwoFactorError> {
+        if !self.is_enrolled()? {
+            return Ok(());
+        }
+        let code = code
+            .filter(|c| !c.trim().is_empty())
+            .ok_or(TwoFactorError::CodeRequired)?;
+        if self.verify(code, keystore)? {
+            Ok(())
+        } else {
+            Err(TwoFactorError::InvalidCode)
+        }
+    }
+
     pub fn verify(&self, code: &str, keystore: &Keystore) -> Result<bool, TwoFactorError> {
-        let trimmed = code.trim().to_uppercase();
+        let trimmed = normalize_code(code);
         if trimmed.is_empty() {
             return Err(TwoFactorError::InvalidCode);
         }
@@ -175,25 +314,73 @@ impl TwoFactorManager {
         self.verify_backup_code(&trimmed, keystore)
     }
 
-    pub fn disable(&self, keystore: &Keystore) -> Result<(), TwoFactorError> {
+    /// Turns 2FA off, or abandons a pending enrollment. Disabling an active
+    /// enrollment needs a current code so a stolen session cannot strip it.
+    pub fn disable(&self, code: Option<&str>, keystore: &Keystore) -> Result<(), TwoFactorError> {
         {
-            let mut config = self.lock_config()?;
-            if !config.enrolled {
+            let config = self.lock_config()?;
+            if !config.enrolled && config.pending_enrollment_at.is_none() {
                 return Err(TwoFactorError::NotEnrolled);
             }
-            *config = TwoFactorConfig::default();
+        }
+        self.require_code(code, keystore)?;
+
+        {
+            let mut config = self.lock_config()?;
+            // Keep the drift and threshold preferences across re-enrollment.
+            *config = TwoFactorConfig {
+                drift_steps: config.drift_steps,
+                large_transfer_threshold: config.large_transfer_threshold,
+                ..TwoFactorConfig::default()
+            };
             self.persist_config(keystore, &config)?;
         }
 
         let _ = keystore.remove_secret(TOTP_SECRET_KEY);
-        let _ = keystore.remove_secret(TOTP_CONFIG_KEY);
+        let _ = keystore.remove_secret(TOTP_PENDING_SECRET_KEY);
         Ok(())
     }
 
+    /// A wider window accepts more codes, so changing it needs a current one.
+    pub fn set_drift_window(
+        &self,
+        steps: u8,
+        code: Option<&str>,
+        keystore: &Keystore,
+    ) -> Result<(), TwoFactorError> {
+        if steps > MAX_DRIFT_STEPS {
+            return Err(TwoFactorError::InvalidDriftWindow);
+        }
+        self.require_code(code, keystore)?;
+
+        let mut config = self.lock_config()?;
+        config.drift_steps = steps;
+        self.persist_config(keystore, &config)
+    }
+
+    pub fn set_large_transfer_threshold(
+        &self,
+        threshold: f64,
+        code: Option<&str>,
+        keystore: &Keystore,
+    ) -> Result<(), TwoFactorError> {
+        if !threshold.is_finite() || threshold < 0.0 {
+            return Err(TwoFactorError::InvalidThreshold);
+        }
+        self.require_code(code, keystore)?;
+
+        let mut config = self.lock_config()?;
+        config.large_transfer_threshold = threshold;
+        self.persist_config(keystore, &config)
+    }
+
     pub fn regenerate_backup_codes(
         &self,
+        code: &str,
         keystore: &Keystore,
     ) -> Result<Vec<String>, TwoFactorError> {
+        self.require_code(Some(code), keystore)?;
+
         let mut config = self.lock_config()?;
         if !config.enrolled {
             return Err(TwoFactorError::NotEnrolled);
@@ -208,38 +395,27 @@ impl TwoFactorManager {
     }
 
     fn verify_totp(&self, code: &str, keystore: &Keystore) -> Result<bool, TwoFactorError> {
-        let config = self.lock_config()?;
+        let mut config = self.lock_config()?;
         if !config.enrolled {
             return Err(TwoFactorError::NotEnrolled);
         }
-        drop(config);
 
-        let secret_bytes = keystore.retrieve_secret(TOTP_SECRET_KEY)?;
-        let secret = BASE32
-            .decode(secret_bytes.as_ref())
-            .map_err(|_| TwoFactorError::Internal)?;
-
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map_err(|_| TwoFactorError::Internal)?
-            .as_secs() as i64;
-
-        let mut valid = false;
-        for offset in -1..=1 {
-            let timestamp = now + offset * TOTP_STEP as i64;
-            if timestamp < 0 {
-                continue;
-            }
-            let counter = (timestamp as u64) / TOTP_STEP;
-            let value = generate_totp(&secret, counter)?;
-            let formatted = format!("{:0width$}", value, width = TOTP_DIGITS as usize);
-            if formatted == code {
-                valid = true;
-                break;
+        let secret = Self::decode_secret(keystore, TOTP_SECRET_KEY)?;
+        match matching_step(&secret, code, config.drift_steps, config.last_used_step)? {
+            Some(step) => {
+                config.last_used_step = Some(step);
+                self.persist_config(keystore, &config)?;
+                Ok(true)
             }
+            None => Ok(false),
         }
+    }
 
-        Ok(valid)
+    fn decode_secret(keystore: &Keystore, key: &str) -> Result<Vec<u8>, TwoFactorError> {
+        let secret_bytes = keystore.retrieve_secret(key)?;
+        BASE32
+            .decode(secret_bytes.as_ref())
+            .map_err(|_| TwoFactorError::Internal)
     }
 
     fn verify_backup_code(&self, code: &str, keystore: &Keystore) -> Result<bool, TwoFactorError> {
@@ -292,17 +468,8 @@ impl TwoFactorManager {
             .collect()
     }
 
-    fn generate_qr_code(user_id: &str, secret: &str) -> Result<String, TwoFactorError> {
-        let uri = format!(
-            "otpauth://totp/{issuer}:{user}?secret={secret}&issuer={issuer}&digits={digits}&period={period}",
-            issuer = TOTP_ISSUER,
-            user = user_id,
-            secret = secret,
-            digits = TOTP_DIGITS,
-            period = TOTP_STEP
-        );
-
-        let qr = QrCode::encode_text(&uri, QrCodeEcc::Medium)
+    fn generate_qr_code(uri: &str) -> Result<String, TwoFactorError> {
+        let qr = QrCode::encode_text(uri, QrCodeEcc::Medium)
             .map_err(|_| TwoFactorError::QrGeneration)?;
 
         let size = qr.size() as usize;
@@ -332,6 +499,76 @@ impl TwoFactorManager {
     }
 }
 
+/// Key URI understood by Google Authenticator and compatible apps.
+fn provisioning_uri(user_id: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{issuer}:{user}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={digits}&period={period}",
+        issuer = percent_encode(TOTP_ISSUER),
+        user = percent_encode(user_id),
+        secret = secret,
+        digits = TOTP_DIGITS,
+        period = TOTP_STEP
+    )
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Codes are accepted with the spaces and dashes authenticators and
+/// printed backup sheets use for readability.
+fn normalize_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// Finds the time step within the drift window whose code matches, skipping
+/// steps at or before `last_used_step`.
+fn matching_step(
+    secret: &[u8],
+    code: &str,
+    drift_steps: u8,
+    last_used_step: Option<u64>,
+) -> Result<Option<u64>, TwoFactorError> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|_| TwoFactorError::Internal)?
+        .as_secs();
+    matching_step_at(secret, code, drift_steps, last_used_step, now)
+}
+
+fn matching_step_at(
+    secret: &[u8],
+    code: &str,
+    drift_steps: u8,
+    last_used_step: Option<u64>,
+    now: u64,
+) -> Result<Option<u64>, TwoFactorError> {
+    let current = now / TOTP_STEP;
+    let drift = u64::from(drift_steps);
+    for step in current.saturating_sub(drift)..=current + drift {
+        if last_used_step.is_some_and(|last| step <= last) {
+            continue;
+        }
+        let value = generate_totp(secret, step)?;
+        let formatted = format!("{:0width$}", value, width = TOTP_DIGITS as usize);
+        if formatted == code {
+            return Ok(Some(step));
+        }
+    }
+    Ok(None)
+}
+
 fn hash_code(code: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(code.as_bytes());
@@ -360,10 +597,22 @@ pub async fn two_factor_enroll(
     user_id: String,
     state: State<'_, TwoFactorManager>,
     keystore: State<'_, Keystore>,
-) -> Result<TwoFactorEnrollment, String> {
+) -> Result<TwoFactorEnrollment, AppError> {
     state
         .enroll(&user_id, keystore.inner())
-        .map_err(|e| e.to_string())
+        .logged("two_factor_enroll")
+}
+
+#[tauri::command]
+pub async fn two_factor_confirm_enrollment(
+    request: VerifyRequest,
+    state: State<'_, TwoFactorManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<TwoFactorStatus, AppError> {
+    state
+        .confirm_enrollment(&request.code, keystore.inner())
+        .logged("two_factor_confirm_enrollment")?;
+    state.status().logged("two_factor_confirm_enrollment")
 }
 
 #[tauri::command]
@@ -371,36 +620,121 @@ pub async fn two_factor_verify(
     request: VerifyRequest,
     state: State<'_, TwoFactorManager>,
     keystore: State<'_, Keystore>,
-) -> Result<bool, String> {
+) -> Result<bool, AppError> {
     state
         .verify(&request.code, keystore.inner())
-        .map_err(|e| e.to_string())
+        .logged("two_factor_verify")
 }
 
 #[tauri::command]
 pub async fn two_factor_disable(
+    code: Option<String>,
     state: State<'_, TwoFactorManager>,
+    policy: State<'_, CommandPolicyManager>,
     keystore: State<'_, Keystore>,
-) -> Result<(), String> {
-    state.disable(keystore.inner()).map_err(|e| e.to_string())
+) -> Result<(), AppError> {
+    if state.is_enrolled().logged("two_factor_disable")? {
+        policy
+            .ensure_two_factor_removable()
+            .logged("two_factor_disable")?;
+    }
+    state
+        .disable(code.as_deref(), keystore.inner())
+        .logged("two_factor_disable")
 }
 
 #[tauri::command]
 pub async fn two_factor_status(
     state: State<'_, TwoFactorManager>,
-) -> Result<TwoFactorStatus, String> {
-    state.status().map_err(|e| e.to_string())
+) -> Result<TwoFactorStatus, AppError> {
+    state.status().logged("two_factor_status")
 }
 
 #[tauri::command]
 pub async fn two_factor_regenerate_backup_codes(
+    request: VerifyRequest,
     state: State<'_, TwoFactorManager>,
     keystore: State<'_, Keystore>,
-) -> Result<RegenerateBackupCodesResponse, String> {
+) -> Result<RegenerateBackupCodesResponse, AppError> {
     let codes = state
-        .regenerate_backup_codes(keystore.inner())
-        .map_err(|e| e.to_string())?;
+        .regenerate_backup_codes(&request.code, keystore.inner())
+        .logged("two_factor_regenerate_backup_codes")?;
     Ok(RegenerateBackupCodesResponse {
         backup_codes: codes,
     })
 }
+
+#[tauri::command]
+pub async fn two_factor_set_drift_window(
+    steps: u8,
+    code: Option<String>,
+    state: State<'_, TwoFactorManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<TwoFactorStatus, AppError> {
+    state
+        .set_drift_window(steps, code.as_deref(), keystore.inner())
+        .logged("two_factor_set_drift_window")?;
+    state.status().logged("two_factor_set_drift_window")
+}
+
+#[tauri::command]
+pub async fn two_factor_set_large_transfer_threshold(
+    threshold: f64,
+    code: Option<String>,
+    state: State<'_, TwoFactorManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<TwoFactorStatus, AppError> {
+    state
+        .set_large_transfer_threshold(threshold, code.as_deref(), keystore.inner())
+        .logged("two_factor_set_large_transfer_threshold")?;
+    state
+        .status()
+        .logged("two_factor_set_large_transfer_threshold")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 appendix B, SHA-1 seed, truncated to six digits.
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn totp_matches_rfc_vectors() {
+        assert_eq!(generate_totp(RFC_SECRET, 59 / TOTP_STEP).unwrap(), 287082);
+        assert_eq!(
+            generate_totp(RFC_SECRET, 1111111109 / TOTP_STEP).unwrap(),
+            81804
+        );
+    }
+
+    #[test]
+    fn drift_window_and_replay() {
+        let now = 1111111109;
+        let code = format!(
+            "{:06}",
+            generate_totp(RFC_SECRET, now / TOTP_STEP - 1).unwrap()
+        );
+
+        assert_eq!(
+            matching_step_at(RFC_SECRET, &code, 0, None, now).unwrap(),
+            None
+        );
+        let step = matching_step_at(RFC_SECRET, &code, 1, None, now)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            matching_step_at(RFC_SECRET, &code, 1, Some(step), now).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn codes_are_normalized_and_uri_encoded() {
+        assert_eq!(normalize_code(" abcde-fghij "), "ABCDEFGHIJ");
+        let uri = provisioning_uri("alice@example.com", "SECRET");
+        assert!(
+            uri.starts_with("otpauth://totp/EclipseMarketPro:alice%40example.com?secret=SECRET")
+        );
+    }
+}
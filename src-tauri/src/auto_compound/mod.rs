@@ -6,3 +6,6 @@ pub use crate::defi::auto_compound::{
     estimate_compound_apy_boost, get_auto_compound_config, get_compound_history,
 };
 pub use crate::defi::types::AutoCompoundSettings;
+pub use crate::defi::reward_claims::{
+    RewardClaimRecord, RewardClaimScheduler, RewardClaimSettings, SharedRewardClaimScheduler,
+};
@@ -1,4 +1,5 @@
 use auto_launch::AutoLaunch;
+use crate::profiles::ProfilePaths;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -52,7 +53,7 @@ impl AutoStartManager {
     }
 
     pub fn initialize(&self, app_handle: &AppHandle) {
-        if let Ok(mut data_dir) = app_handle.path().app_data_dir() {
+        if let Ok(mut data_dir) = app_handle.path().profile_data_dir() {
             if let Err(err) = fs::create_dir_all(&data_dir) {
                 eprintln!("Failed to ensure auto-start settings directory: {err}");
             } else {
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -88,7 +89,7 @@ impl CloudProviderManager {
         let mut path = self
             .app_handle
             .path()
-            .app_data_dir()
+            .profile_data_dir()
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::NotFound, format!("app data dir: {}", e)))?;
         path.push(STORAGE_DIR);
         if !path.exists() {
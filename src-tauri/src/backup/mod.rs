@@ -1,9 +1,13 @@
 pub mod cloud_providers;
+pub mod remote;
 pub mod scheduler;
+pub mod selective;
 pub mod service;
 pub mod settings_manager;
 
 pub use cloud_providers::*;
+pub use remote::*;
 pub use scheduler::*;
+pub use selective::*;
 pub use service::*;
 pub use settings_manager::*;
@@ -0,0 +1,1007 @@
+use crate::profiles::ProfilePaths;
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::{
+    aead::{Aead, KeyInit, OsRng},
+    Aes256Gcm,
+};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64_ENGINE, Engine};
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rand_core::RngCore;
+use reqwest::{Client, Method, RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, State};
+use uuid::Uuid;
+
+use crate::security::keystore::Keystore;
+
+use super::selective::{capture_domains, BackupArchive};
+use super::service::{BackupError, BackupService, EncryptedBackup, SharedBackupService};
+
+const REMOTE_CONFIG_FILE: &str = "backup_remotes.enc";
+/// HKDF context for the key that encrypts remote archives. The derived key
+/// cannot be reversed into the local backup key, so exposing it does not
+/// expose local backups.
+const REMOTE_KEY_INFO: &[u8] = b"backup.remote.archive.v1";
+const REMOTE_BACKUP_VERSION: u32 = 2;
+const OBJECT_PREFIX: &str = "backup_";
+const OBJECT_SUFFIX: &str = ".enc";
+const OBJECT_TIME_FORMAT: &str = "%Y%m%d_%H%M%S";
+const DRIVE_API: &str = "https://www.googleapis.com/drive/v3/files";
+const DRIVE_UPLOAD_API: &str = "https://www.googleapis.com/upload/drive/v3/files";
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+pub enum RemoteTarget {
+    /// Any S3-compatible store (AWS, MinIO, R2, Backblaze B2, Wasabi).
+    S3 {
+        #[serde(default)]
+        endpoint: Option<String>,
+        region: String,
+        bucket: String,
+        access_key_id: String,
+        secret_access_key: String,
+        /// Key prefix inside the bucket, e.g. `backups/desktop/`.
+        #[serde(default)]
+        prefix: String,
+        /// Address the bucket in the path rather than the host name, as
+        /// most self-hosted stores require.
+        #[serde(default)]
+        path_style: bool,
+    },
+    WebDav {
+        /// Collection URL the archives are written into.
+        url: String,
+        username: String,
+        password: String,
+    },
+    GoogleDrive {
+        #[serde(default)]
+        access_token: Option<String>,
+        #[serde(default)]
+        refresh_token: Option<String>,
+        #[serde(default)]
+        client_id: Option<String>,
+        #[serde(default)]
+        client_secret: Option<String>,
+        #[serde(default)]
+        folder_id: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteRetentionPolicy {
+    /// Number of most recent archives to keep. The newest archive is always
+    /// kept, even when it is older than `max_age_days`.
+    pub keep_last: usize,
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+}
+
+impl Default for RemoteRetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_last: 10,
+            max_age_days: None,
+        }
+    }
+}
+
+impl RemoteRetentionPolicy {
+    /// Objects to delete, chosen among those named by this module.
+    /// Anything else in the target is left alone.
+    pub fn expired<'a>(
+        &self,
+        objects: &'a [RemoteObject],
+        now: DateTime<Utc>,
+    ) -> Vec<&'a RemoteObject> {
+        let mut dated: Vec<(DateTime<Utc>, &RemoteObject)> = objects
+            .iter()
+            .filter_map(|object| object_timestamp(&object.name).map(|at| (at, object)))
+            .collect();
+        dated.sort_by(|a, b| b.0.cmp(&a.0));
+
+        let cutoff = self
+            .max_age_days
+            .map(|days| now - Duration::days(i64::from(days)));
+        let keep_last = self.keep_last.max(1);
+        dated
+            .into_iter()
+            .enumerate()
+            .filter(|(index, (at, _))| {
+                *index >= keep_last || (*index > 0 && cutoff.is_some_and(|cutoff| *at < cutoff))
+            })
+            .map(|(_, (_, object))| object)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteBackupConfig {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub target: RemoteTarget,
+    #[serde(default)]
+    pub retention: RemoteRetentionPolicy,
+    pub enabled: bool,
+    /// Settings sections to include; all sections when absent.
+    #[serde(default)]
+    pub sections: Option<Vec<String>>,
+    #[serde(default)]
+    pub last_sync: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteObject {
+    pub name: String,
+    /// Provider handle used to delete the object: the key for S3 and
+    /// WebDAV, the file id for Google Drive.
+    pub handle: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteSyncReport {
+    pub remote_id: String,
+    pub uploaded: Option<String>,
+    pub size_bytes: u64,
+    pub pruned: Vec<String>,
+    pub error: Option<String>,
+    pub synced_at: DateTime<Utc>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteBackupError {
+    #[error("backup error: {0}")]
+    Backup(#[from] BackupError),
+    #[error("http error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("remote returned {status}: {body}")]
+    Status { status: u16, body: String },
+    #[error("invalid configuration: {0}")]
+    InvalidConfig(String),
+    #[error("remote target not found")]
+    NotFound,
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("encryption error")]
+    Encryption,
+    #[error("decryption error")]
+    Decryption,
+}
+
+/// A place encrypted archives can be written to. Implementations only move
+/// opaque bytes; encryption happens before `upload` is called.
+#[async_trait]
+pub trait RemoteBackupTarget: Send + Sync {
+    async fn upload(&self, name: &str, data: Vec<u8>) -> Result<(), RemoteBackupError>;
+    async fn list(&self) -> Result<Vec<RemoteObject>, RemoteBackupError>;
+    async fn delete(&self, object: &RemoteObject) -> Result<(), RemoteBackupError>;
+}
+
+pub async fn connect_remote(
+    target: &RemoteTarget,
+    client: Client,
+) -> Result<Box<dyn RemoteBackupTarget>, RemoteBackupError> {
+    validate_target(target)?;
+    Ok(match target {
+        RemoteTarget::S3 {
+            endpoint,
+            region,
+            bucket,
+            access_key_id,
+            secret_access_key,
+            prefix,
+            path_style,
+        } => Box::new(S3Target {
+            client,
+            endpoint: endpoint
+                .clone()
+                .unwrap_or_else(|| format!("https://s3.{region}.amazonaws.com")),
+            region: region.clone(),
+            bucket: bucket.clone(),
+            access_key_id: access_key_id.clone(),
+            secret_access_key: secret_access_key.clone(),
+            prefix: prefix.clone(),
+            path_style: *path_style,
+        }),
+        RemoteTarget::WebDav {
+            url,
+            username,
+            password,
+        } => Box::new(WebDavTarget {
+            client,
+            url: format!("{}/", url.trim_end_matches('/')),
+            username: username.clone(),
+            password: password.clone(),
+        }),
+        RemoteTarget::GoogleDrive {
+            access_token,
+            refresh_token,
+            client_id,
+            client_secret,
+            folder_id,
+        } => {
+            let access_token = match (refresh_token, client_id, client_secret) {
+                (Some(refresh), Some(id), Some(secret)) => {
+                    refresh_google_token(&client, refresh, id, secret).await?
+                }
+                _ => access_token.clone().unwrap_or_default(),
+            };
+            Box::new(GoogleDriveTarget {
+                client,
+                access_token,
+                folder_id: folder_id.clone(),
+            })
+        }
+    })
+}
+
+fn validate_target(target: &RemoteTarget) -> Result<(), RemoteBackupError> {
+    let missing = |field: &str| RemoteBackupError::InvalidConfig(format!("{field} is required"));
+    match target {
+        RemoteTarget::S3 {
+            endpoint,
+            region,
+            bucket,
+            access_key_id,
+            secret_access_key,
+            ..
+        } => {
+            if region.trim().is_empty() {
+                return Err(missing("region"));
+            }
+            if bucket.trim().is_empty() {
+                return Err(missing("bucket"));
+            }
+            if access_key_id.is_empty() || secret_access_key.is_empty() {
+                return Err(missing("access key"));
+            }
+            if let Some(endpoint) = endpoint {
+                if !endpoint.starts_with("https://") && !endpoint.starts_with("http://") {
+                    return Err(RemoteBackupError::InvalidConfig(
+                        "endpoint must be an http(s) URL".to_string(),
+                    ));
+                }
+            }
+        }
+        RemoteTarget::WebDav { url, username, .. } => {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                return Err(RemoteBackupError::InvalidConfig(
+                    "WebDAV url must be an http(s) URL".to_string(),
+                ));
+            }
+            if username.is_empty() {
+                return Err(missing("username"));
+            }
+        }
+        RemoteTarget::GoogleDrive {
+            access_token,
+            refresh_token,
+            client_id,
+            client_secret,
+            ..
+        } => {
+            let can_refresh =
+                refresh_token.is_some() && client_id.is_some() && client_secret.is_some();
+            if access_token.is_none() && !can_refresh {
+                return Err(RemoteBackupError::InvalidConfig(
+                    "an access token or refresh credentials are required".to_string(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn check(response: Response) -> Result<Response, RemoteBackupError> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    Err(RemoteBackupError::Status {
+        status: response.status().as_u16(),
+        body: response.text().await.unwrap_or_default(),
+    })
+}
+
+struct S3Target {
+    client: Client,
+    endpoint: String,
+    region: String,
+    bucket: String,
+    access_key_id: String,
+    secret_access_key: String,
+    prefix: String,
+    path_style: bool,
+}
+
+impl S3Target {
+    /// Builds a request signed with AWS Signature Version 4.
+    fn request(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, &str)],
+        body: Vec<u8>,
+    ) -> Result<RequestBuilder, RemoteBackupError> {
+        let (scheme, host) = self
+            .endpoint
+            .trim_end_matches('/')
+            .split_once("://")
+            .ok_or_else(|| RemoteBackupError::InvalidConfig("invalid endpoint".to_string()))?;
+        let (host, path) = if self.path_style {
+            (
+                host.to_string(),
+                format!("/{}/{}", self.bucket, uri_encode(key, false)),
+            )
+        } else {
+            (
+                format!("{}.{host}", self.bucket),
+                format!("/{}", uri_encode(key, false)),
+            )
+        };
+
+        let mut query: Vec<(String, String)> = query
+            .iter()
+            .map(|(k, v)| (uri_encode(k, true), uri_encode(v, true)))
+            .collect();
+        query.sort();
+        let canonical_query = query
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{method}\n{path}\n{canonical_query}\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}"
+        );
+        let scope = format!("{date}/{}/s3/aws4_request", self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let mut signing_key = format!("AWS4{}", self.secret_access_key).into_bytes();
+        for part in [date.as_str(), self.region.as_str(), "s3", "aws4_request"] {
+            signing_key = hmac_sha256(&signing_key, part.as_bytes());
+        }
+        let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            self.access_key_id
+        );
+
+        let mut url = format!("{scheme}://{host}{path}");
+        if !canonical_query.is_empty() {
+            url.push('?');
+            url.push_str(&canonical_query);
+        }
+        Ok(self
+            .client
+            .request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("authorization", authorization)
+            .body(body))
+    }
+
+    fn key(&self, name: &str) -> String {
+        format!("{}{name}", self.prefix)
+    }
+}
+
+#[async_trait]
+impl RemoteBackupTarget for S3Target {
+    async fn upload(&self, name: &str, data: Vec<u8>) -> Result<(), RemoteBackupError> {
+        let request = self.request(Method::PUT, &self.key(name), &[], data)?;
+        check(request.send().await?).await?;
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<RemoteObject>, RemoteBackupError> {
+        let prefix = self.key(OBJECT_PREFIX);
+        let mut objects = Vec::new();
+        let mut continuation: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", prefix.as_str())];
+            if let Some(token) = &continuation {
+                query.push(("continuation-token", token.as_str()));
+            }
+            let request = self.request(Method::GET, "", &query, Vec::new())?;
+            let body = check(request.send().await?).await?.text().await?;
+            objects.extend(
+                xml_values(&body, "Key")
+                    .into_iter()
+                    .map(|key| RemoteObject {
+                        name: key.rsplit('/').next().unwrap_or(&key).to_string(),
+                        handle: key,
+                    }),
+            );
+            continuation = xml_values(&body, "NextContinuationToken")
+                .into_iter()
+                .next();
+            if continuation.is_none() {
+                return Ok(objects);
+            }
+        }
+    }
+
+    async fn delete(&self, object: &RemoteObject) -> Result<(), RemoteBackupError> {
+        let request = self.request(Method::DELETE, &object.handle, &[], Vec::new())?;
+        check(request.send().await?).await?;
+        Ok(())
+    }
+}
+
+struct WebDavTarget {
+    client: Client,
+    url: String,
+    username: String,
+    password: String,
+}
+
+#[async_trait]
+impl RemoteBackupTarget for WebDavTarget {
+    async fn upload(&self, name: &str, data: Vec<u8>) -> Result<(), RemoteBackupError> {
+        let response = self
+            .client
+            .put(format!("{}{name}", self.url))
+            .basic_auth(&self.username, Some(&self.password))
+            .header("content-type", "application/octet-stream")
+            .body(data)
+            .send()
+            .await?;
+        check(response).await?;
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<RemoteObject>, RemoteBackupError> {
+        let propfind = Method::from_bytes(b"PROPFIND")
+            .map_err(|e| RemoteBackupError::InvalidConfig(e.to_string()))?;
+        let response = self
+            .client
+            .request(propfind, &self.url)
+            .basic_auth(&self.username, Some(&self.password))
+            .header("depth", "1")
+            .header("content-type", "application/xml")
+            .body(r#"<?xml version="1.0"?><d:propfind xmlns:d="DAV:"><d:prop><d:resourcetype/></d:prop></d:propfind>"#)
+            .send()
+            .await?;
+        let body = check(response).await?.text().await?;
+        Ok(xml_values(&body, "href")
+            .into_iter()
+            .filter_map(|href| {
+                let name = href.trim_end_matches('/').rsplit('/').next()?.to_string();
+                name.starts_with(OBJECT_PREFIX).then(|| RemoteObject {
+                    handle: name.clone(),
+                    name,
+                })
+            })
+            .collect())
+    }
+
+    async fn delete(&self, object: &RemoteObject) -> Result<(), RemoteBackupError> {
+        let response = self
+            .client
+            .delete(format!("{}{}", self.url, object.handle))
+            .basic_auth(&self.username, Some(&self.password))
+            .send()
+            .await?;
+        check(response).await?;
+        Ok(())
+    }
+}
+
+struct GoogleDriveTarget {
+    client: Client,
+    access_token: String,
+    folder_id: Option<String>,
+}
+
+#[async_trait]
+impl RemoteBackupTarget for GoogleDriveTarget {
+    async fn upload(&self, name: &str, data: Vec<u8>) -> Result<(), RemoteBackupError> {
+        let mut metadata = json!({ "name": name, "mimeType": "application/octet-stream" });
+        if let Some(folder) = &self.folder_id {
+            metadata["parents"] = json!([folder]);
+        }
+        let boundary = format!("backup-{}", Uuid::new_v4().simple());
+        let mut body = format!(
+            "--{boundary}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{metadata}\r\n--{boundary}\r\nContent-Type: application/octet-stream\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(&data);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+        let response = self
+            .client
+            .post(format!("{DRIVE_UPLOAD_API}?uploadType=multipart"))
+            .bearer_auth(&self.access_token)
+            .header(
+                "content-type",
+                format!("multipart/related; boundary={boundary}"),
+            )
+            .body(body)
+            .send()
+            .await?;
+        check(response).await?;
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<RemoteObject>, RemoteBackupError> {
+        let mut query = format!("name contains '{OBJECT_PREFIX}' and trashed = false");
+        if let Some(folder) = &self.folder_id {
+            query.push_str(&format!(" and '{folder}' in parents"));
+        }
+        let mut objects = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut request = self
+                .client
+                .get(DRIVE_API)
+                .bearer_auth(&self.access_token)
+                .query(&[
+                    ("q", query.as_str()),
+                    ("fields", "nextPageToken,files(id,name)"),
+                    ("pageSize", "1000"),
+                ]);
+            if let Some(token) = &page_token {
+                request = request.query(&[("pageToken", token)]);
+            }
+            let page: serde_json::Value = check(request.send().await?).await?.json().await?;
+            objects.extend(
+                page["files"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|file| {
+                        Some(RemoteObject {
+                            name: file["name"].as_str()?.to_string(),
+                            handle: file["id"].as_str()?.to_string(),
+                        })
+                    }),
+            );
+            page_token = page["nextPageToken"].as_str().map(str::to_string);
+            if page_token.is_none() {
+                return Ok(objects);
+            }
+        }
+    }
+
+    async fn delete(&self, object: &RemoteObject) -> Result<(), RemoteBackupError> {
+        let response = self
+            .client
+            .delete(format!("{DRIVE_API}/{}", object.handle))
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?;
+        check(response).await?;
+        Ok(())
+    }
+}
+
+async fn refresh_google_token(
+    client: &Client,
+    refresh_token: &str,
+    client_id: &str,
+    client_secret: &str,
+) -> Result<String, RemoteBackupError> {
+    let response = client
+        .post(GOOGLE_TOKEN_URL)
+        .form(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+        ])
+        .send()
+        .await?;
+    let token: serde_json::Value = check(response).await?.json().await?;
+    token["access_token"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| {
+            RemoteBackupError::InvalidConfig("token response had no access_token".to_string())
+        })
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Percent-encodes everything outside the RFC 3986 unreserved set, as
+/// SigV4 canonicalization requires.
+fn uri_encode(value: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Text content of every element named `tag`, ignoring namespace prefixes.
+/// Enough for S3 listings and WebDAV multistatus replies without pulling in
+/// an XML parser.
+fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let name = &rest[..end];
+        rest = &rest[end + 1..];
+        if name.starts_with('/') || name.ends_with('/') {
+            continue;
+        }
+        let name = name.split_whitespace().next().unwrap_or_default();
+        let local = name.rsplit(':').next().unwrap_or(name);
+        if local != tag {
+            continue;
+        }
+        if let Some(close) = rest.find(&format!("</{name}>")) {
+            values.push(rest[..close].trim().to_string());
+            rest = &rest[close..];
+        }
+    }
+    values
+}
+
+fn object_name(at: DateTime<Utc>) -> String {
+    format!(
+        "{OBJECT_PREFIX}{}{OBJECT_SUFFIX}",
+        at.format(OBJECT_TIME_FORMAT)
+    )
+}
+
+fn object_timestamp(name: &str) -> Option<DateTime<Utc>> {
+    let stamp = name
+        .strip_prefix(OBJECT_PREFIX)?
+        .strip_suffix(OBJECT_SUFFIX)?;
+    NaiveDateTime::parse_from_str(stamp, OBJECT_TIME_FORMAT)
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+fn derive_remote_key(master_key: &[u8]) -> Result<[u8; 32], RemoteBackupError> {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(None, master_key)
+        .expand(REMOTE_KEY_INFO, &mut key)
+        .map_err(|_| RemoteBackupError::Encryption)?;
+    Ok(key)
+}
+
+/// Encrypts an archive on this device before it is handed to any target,
+/// so providers only ever store ciphertext.
+pub fn encrypt_for_remote(
+    master_key: &[u8],
+    data: &[u8],
+) -> Result<EncryptedBackup, RemoteBackupError> {
+    let key = derive_remote_key(master_key)?;
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(&key));
+    let mut nonce = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = cipher
+        .encrypt(GenericArray::from_slice(&nonce), data)
+        .map_err(|_| RemoteBackupError::Encryption)?;
+
+    Ok(EncryptedBackup {
+        version: REMOTE_BACKUP_VERSION,
+        nonce: BASE64_ENGINE.encode(nonce),
+        ciphertext: BASE64_ENGINE.encode(ciphertext),
+        checksum: format!("{:x}", Sha256::digest(data)),
+        created_at: Utc::now(),
+    })
+}
+
+pub fn decrypt_from_remote(
+    master_key: &[u8],
+    backup: &EncryptedBackup,
+) -> Result<Vec<u8>, RemoteBackupError> {
+    let key = derive_remote_key(master_key)?;
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(&key));
+    let nonce = BASE64_ENGINE
+        .decode(backup.nonce.as_bytes())
+        .map_err(|_| RemoteBackupError::Decryption)?;
+    let ciphertext = BASE64_ENGINE
+        .decode(backup.ciphertext.as_bytes())
+        .map_err(|_| RemoteBackupError::Decryption)?;
+    let plaintext = cipher
+        .decrypt(GenericArray::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| RemoteBackupError::Decryption)?;
+    if format!("{:x}", Sha256::digest(&plaintext)) != backup.checksum {
+        return Err(BackupError::IntegrityCheckFailed.into());
+    }
+    Ok(plaintext)
+}
+
+fn config_path(app: &AppHandle) -> Result<PathBuf, RemoteBackupError> {
+    let mut path = app.path().profile_data_dir().map_err(|e| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("App data directory not found: {}", e),
+        )
+    })?;
+    if !path.exists() {
+        fs::create_dir_all(&path)?;
+    }
+    path.push(REMOTE_CONFIG_FILE);
+    Ok(path)
+}
+
+/// Target configs hold provider credentials, so they are stored encrypted
+/// with the same derived key as the archives.
+fn load_remotes(
+    app: &AppHandle,
+    master_key: &[u8],
+) -> Result<Vec<RemoteBackupConfig>, RemoteBackupError> {
+    let path = config_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let encrypted: EncryptedBackup = serde_json::from_slice(&fs::read(path)?)?;
+    let plaintext = decrypt_from_remote(master_key, &encrypted)?;
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+fn save_remotes(
+    app: &AppHandle,
+    master_key: &[u8],
+    remotes: &[RemoteBackupConfig],
+) -> Result<(), RemoteBackupError> {
+    let encrypted = encrypt_for_remote(master_key, &serde_json::to_vec(remotes)?)?;
+    fs::write(config_path(app)?, serde_json::to_vec(&encrypted)?)?;
+    Ok(())
+}
+
+async fn sync_one(
+    app: &AppHandle,
+    service: &BackupService,
+    master_key: &[u8],
+    client: &Client,
+    remote: &RemoteBackupConfig,
+) -> Result<RemoteSyncReport, RemoteBackupError> {
+    let target = connect_remote(&remote.target, client.clone()).await?;
+    let domains = capture_domains(app, remote.sections.as_deref()).await;
+    let settings = service.export_settings(remote.sections.clone())?;
+    let encrypted = encrypt_for_remote(
+        master_key,
+        &serde_json::to_vec(&BackupArchive { settings, domains })?,
+    )?;
+    let archive = serde_json::to_vec(&encrypted)?;
+    let size_bytes = archive.len() as u64;
+    let name = object_name(encrypted.created_at);
+    target.upload(&name, archive).await?;
+
+    // A failed prune leaves extra archives behind but the new one is safe,
+    // so it is reported rather than failing the sync.
+    let mut pruned = Vec::new();
+    let mut error = None;
+    match target.list().await {
+        Ok(objects) => {
+            for object in remote.retention.expired(&objects, Utc::now()) {
+                match target.delete(object).await {
+                    Ok(()) => pruned.push(object.name.clone()),
+                    Err(e) => error = Some(format!("Failed to prune {}: {}", object.name, e)),
+                }
+            }
+        }
+        Err(e) => error = Some(format!("Failed to list archives for retention: {}", e)),
+    }
+
+    Ok(RemoteSyncReport {
+        remote_id: remote.id.clone(),
+        uploaded: Some(name),
+        size_bytes,
+        pruned,
+        error,
+        synced_at: encrypted.created_at,
+    })
+}
+
+#[tauri::command]
+pub async fn backup_configure_remote(
+    app: AppHandle,
+    mut config: RemoteBackupConfig,
+    keystore: State<'_, Keystore>,
+    backup_service: State<'_, SharedBackupService>,
+) -> Result<RemoteBackupConfig, String> {
+    validate_target(&config.target).map_err(|e| e.to_string())?;
+    let service = backup_service.read().await;
+    let master_key = service
+        .get_or_create_backup_key(keystore.inner())
+        .map_err(|e| e.to_string())?;
+    let mut remotes = load_remotes(&app, &master_key).map_err(|e| e.to_string())?;
+
+    if config.id.is_empty() {
+        config.id = Uuid::new_v4().to_string();
+    }
+    match remotes.iter_mut().find(|r| r.id == config.id) {
+        Some(existing) => {
+            config.last_sync = existing.last_sync;
+            config.last_error = existing.last_error.clone();
+            *existing = config.clone();
+        }
+        None => remotes.push(config.clone()),
+    }
+
+    save_remotes(&app, &master_key, &remotes).map_err(|e| e.to_string())?;
+    Ok(config)
+}
+
+#[tauri::command]
+pub async fn backup_list_remotes(
+    app: AppHandle,
+    keystore: State<'_, Keystore>,
+    backup_service: State<'_, SharedBackupService>,
+) -> Result<Vec<RemoteBackupConfig>, String> {
+    let service = backup_service.read().await;
+    let master_key = service
+        .get_or_create_backup_key(keystore.inner())
+        .map_err(|e| e.to_string())?;
+    load_remotes(&app, &master_key).map_err(|e| e.to_string())
+}
+
+/// Uploads a fresh encrypted archive to one remote, or to every enabled
+/// remote when `remote_id` is omitted, then applies retention.
+#[tauri::command]
+pub async fn backup_sync_remote(
+    app: AppHandle,
+    remote_id: Option<String>,
+    keystore: State<'_, Keystore>,
+    backup_service: State<'_, SharedBackupService>,
+) -> Result<Vec<RemoteSyncReport>, String> {
+    let service = backup_service.read().await;
+    let master_key = service
+        .get_or_create_backup_key(keystore.inner())
+        .map_err(|e| e.to_string())?;
+    let mut remotes = load_remotes(&app, &master_key).map_err(|e| e.to_string())?;
+
+    let selected: Vec<usize> = match &remote_id {
+        Some(id) => {
+            let index = remotes
+                .iter()
+                .position(|r| &r.id == id)
+                .ok_or_else(|| RemoteBackupError::NotFound.to_string())?;
+            vec![index]
+        }
+        None => (0..remotes.len()).filter(|&i| remotes[i].enabled).collect(),
+    };
+
+    let client = Client::new();
+    let mut reports = Vec::with_capacity(selected.len());
+    for index in selected {
+        let remote = &mut remotes[index];
+        let report = match sync_one(&app, &service, &master_key, &client, remote).await {
+            Ok(report) => {
+                remote.last_sync = Some(report.synced_at);
+                report
+            }
+            Err(e) => RemoteSyncReport {
+                remote_id: remote.id.clone(),
+                uploaded: None,
+                size_bytes: 0,
+                pruned: Vec::new(),
+                error: Some(e.to_string()),
+                synced_at: Utc::now(),
+            },
+        };
+        remote.last_error = report.error.clone();
+        reports.push(report);
+    }
+
+    save_remotes(&app, &master_key, &remotes).map_err(|e| e.to_string())?;
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn object(at: DateTime<Utc>) -> RemoteObject {
+        let name = object_name(at);
+        RemoteObject {
+            handle: format!("prefix/{name}"),
+            name,
+        }
+    }
+
+    #[test]
+    fn test_retention_keeps_newest_and_ignores_foreign_files() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let mut objects: Vec<RemoteObject> = (0..5)
+            .map(|days| object(now - Duration::days(days * 10)))
+            .collect();
+        objects.push(RemoteObject {
+            name: "notes.txt".to_string(),
+            handle: "notes.txt".to_string(),
+        });
+
+        let by_count = RemoteRetentionPolicy {
+            keep_last: 2,
+            max_age_days: None,
+        };
+        let expired: Vec<_> = by_count
+            .expired(&objects, now)
+            .iter()
+            .map(|o| o.name.clone())
+            .collect();
+        assert_eq!(expired.len(), 3);
+        assert!(!expired.contains(&objects[0].name));
+        assert!(!expired.contains(&"notes.txt".to_string()));
+
+        let by_age = RemoteRetentionPolicy {
+            keep_last: 10,
+            max_age_days: Some(15),
+        };
+        assert_eq!(by_age.expired(&objects, now).len(), 3);
+
+        // Everything is too old, but the newest archive survives.
+        let later = now + Duration::days(365);
+        assert_eq!(by_age.expired(&objects, later).len(), 4);
+    }
+
+    #[test]
+    fn test_remote_encryption_round_trip_and_key_separation() {
+        let master = [7u8; 32];
+        let encrypted = encrypt_for_remote(&master, b"settings").unwrap();
+        assert_eq!(
+            decrypt_from_remote(&master, &encrypted).unwrap(),
+            b"settings"
+        );
+        assert!(decrypt_from_remote(&[8u8; 32], &encrypted).is_err());
+
+        // The archive key is derived, not the master key itself.
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(&master));
+        let nonce = BASE64_ENGINE.decode(&encrypted.nonce).unwrap();
+        let ciphertext = BASE64_ENGINE.decode(&encrypted.ciphertext).unwrap();
+        assert!(cipher
+            .decrypt(GenericArray::from_slice(&nonce), ciphertext.as_ref())
+            .is_err());
+    }
+
+    #[test]
+    fn test_xml_values_ignores_namespace_prefixes() {
+        let s3 = "<ListBucketResult><Contents><Key>a/backup_1.enc</Key></Contents><Contents><Key>a/backup_2.enc</Key></Contents></ListBucketResult>";
+        assert_eq!(
+            xml_values(s3, "Key"),
+            vec!["a/backup_1.enc", "a/backup_2.enc"]
+        );
+
+        let dav = r#"<d:multistatus xmlns:d="DAV:"><d:response><d:href>/dav/backup_1.enc</d:href></d:response></d:multistatus>"#;
+        assert_eq!(xml_values(dav, "href"), vec!["/dav/backup_1.enc"]);
+    }
+}
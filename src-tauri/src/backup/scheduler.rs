@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -107,7 +108,7 @@ impl BackupScheduler {
     }
 
     fn config_path(&self) -> Result<PathBuf, SchedulerError> {
-        let mut path = self.app_handle.path().app_data_dir().map_err(|e| {
+        let mut path = self.app_handle.path().profile_data_dir().map_err(|e| {
             std::io::Error::new(std::io::ErrorKind::NotFound, format!("App data directory not found: {}", e))
         })?;
 
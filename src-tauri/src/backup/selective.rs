@@ -0,0 +1,633 @@
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::alerts::{PriceAlert, SharedAlertManager};
+use crate::journal::{JournalEntry, JournalFilters, SharedJournalDatabase};
+use crate::portfolio::watchlists::{SharedWatchlistManager, Watchlist};
+
+use super::cloud_providers::CloudProvider;
+use super::service::SharedBackupService;
+use super::settings_manager::AppSettings;
+
+const JOURNAL_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupDomain {
+    Watchlists,
+    Alerts,
+    Journal,
+    /// Trading, security, appearance, notification and custom settings.
+    Settings,
+    /// The `api` settings section: Birdeye and Helius keys and custom RPC.
+    ApiKeys,
+}
+
+impl BackupDomain {
+    pub const ALL: [BackupDomain; 5] = [
+        BackupDomain::Watchlists,
+        BackupDomain::Alerts,
+        BackupDomain::Journal,
+        BackupDomain::Settings,
+        BackupDomain::ApiKeys,
+    ];
+
+    /// Name in the `sections` filter accepted by backup creation. The
+    /// settings domains are also filtered by their individual section
+    /// names, such as `trading`.
+    pub fn section(&self) -> &'static str {
+        match self {
+            BackupDomain::Watchlists => "watchlists",
+            BackupDomain::Alerts => "alerts",
+            BackupDomain::Journal => "journal",
+            BackupDomain::Settings => "settings",
+            BackupDomain::ApiKeys => "api",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// Backup copy replaces the local copy.
+    Overwrite,
+    /// Whichever copy was updated last wins.
+    KeepNewer,
+    /// Local copy is kept; only items missing locally are restored.
+    Skip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreResolution {
+    Create,
+    Overwrite,
+    KeepLocal,
+    Unchanged,
+}
+
+/// Records captured alongside settings in each archive. Archives written
+/// before these domains existed deserialize with every field `None`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupDomainData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watchlists: Option<Vec<Watchlist>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alerts: Option<Vec<PriceAlert>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub journal: Option<Vec<JournalEntry>>,
+}
+
+/// Decrypted archive contents. Settings stay at the top level so archives
+/// remain readable as plain `AppSettings` by `restore_backup`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupArchive {
+    #[serde(flatten)]
+    pub settings: AppSettings,
+    #[serde(flatten)]
+    pub domains: BackupDomainData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreItem {
+    pub domain: BackupDomain,
+    pub id: String,
+    pub label: String,
+    pub local_updated_at: Option<DateTime<Utc>>,
+    pub backup_updated_at: Option<DateTime<Utc>>,
+    pub resolution: RestoreResolution,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl RestoreItem {
+    pub fn is_conflict(&self) -> bool {
+        matches!(
+            self.resolution,
+            RestoreResolution::Overwrite | RestoreResolution::KeepLocal
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectiveRestoreReport {
+    pub filename: String,
+    pub strategy: MergeStrategy,
+    pub preview: bool,
+    pub domains: Vec<BackupDomain>,
+    /// Requested domains the archive does not contain.
+    pub missing_domains: Vec<BackupDomain>,
+    pub items: Vec<RestoreItem>,
+    pub conflicts: usize,
+    pub applied: usize,
+    pub failed: usize,
+}
+
+/// Decides what happens to one record. Identical copies are left alone;
+/// records missing locally are always created.
+pub fn resolve_restore(
+    strategy: MergeStrategy,
+    local: Option<Option<DateTime<Utc>>>,
+    backup_updated_at: Option<DateTime<Utc>>,
+    identical: bool,
+) -> RestoreResolution {
+    let Some(local_updated_at) = local else {
+        return RestoreResolution::Create;
+    };
+    if identical {
+        return RestoreResolution::Unchanged;
+    }
+    match strategy {
+        MergeStrategy::Overwrite => RestoreResolution::Overwrite,
+        MergeStrategy::Skip => RestoreResolution::KeepLocal,
+        // Without a timestamp on either side there is nothing to compare,
+        // so the local copy wins.
+        MergeStrategy::KeepNewer => match (local_updated_at, backup_updated_at) {
+            (Some(local), Some(backup)) if backup > local => RestoreResolution::Overwrite,
+            (None, Some(_)) => RestoreResolution::Overwrite,
+            _ => RestoreResolution::KeepLocal,
+        },
+    }
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+fn unix_time(seconds: i64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_opt(seconds, 0).single()
+}
+
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+fn wants(sections: Option<&[String]>, domain: BackupDomain) -> bool {
+    sections.map_or(true, |sections| {
+        sections.iter().any(|s| s == domain.section())
+    })
+}
+
+/// Reads watchlists, alerts and journal entries for a new archive. A
+/// domain whose store is not initialized is left out rather than failing
+/// the backup.
+pub async fn capture_domains(app: &AppHandle, sections: Option<&[String]>) -> BackupDomainData {
+    let mut data = BackupDomainData::default();
+
+    if wants(sections, BackupDomain::Watchlists) {
+        if let Some(manager) = app.try_state::<SharedWatchlistManager>() {
+            match manager.read().await.list_watchlists().await {
+                Ok(watchlists) => data.watchlists = Some(watchlists),
+                Err(e) => eprintln!("Failed to back up watchlists: {}", e),
+            }
+        }
+    }
+    if wants(sections, BackupDomain::Alerts) {
+        if let Some(manager) = app.try_state::<SharedAlertManager>() {
+            match manager.read().await.list_alerts().await {
+                Ok(alerts) => data.alerts = Some(alerts),
+                Err(e) => eprintln!("Failed to back up alerts: {}", e),
+            }
+        }
+    }
+    if wants(sections, BackupDomain::Journal) {
+        if let Some(db) = app.try_state::<SharedJournalDatabase>() {
+            match all_journal_entries(&db).await {
+                Ok(entries) => data.journal = Some(entries),
+                Err(e) => eprintln!("Failed to back up journal: {}", e),
+            }
+        }
+    }
+
+    data
+}
+
+async fn all_journal_entries(db: &SharedJournalDatabase) -> Result<Vec<JournalEntry>, String> {
+    let db = db.read().await;
+    let filters = JournalFilters::default();
+    let mut entries = Vec::new();
+    loop {
+        let page = db
+            .get_entries(&filters, JOURNAL_PAGE_SIZE, entries.len() as i64)
+            .await
+            .map_err(|e| e.to_string())?;
+        let done = (page.len() as i64) < JOURNAL_PAGE_SIZE;
+        entries.extend(page);
+        if done {
+            return Ok(entries);
+        }
+    }
+}
+
+/// Settings sections restored by a domain, with the archive and local
+/// values of each.
+fn settings_sections(
+    domain: BackupDomain,
+    backup: &AppSettings,
+    local: &AppSettings,
+) -> Vec<(
+    &'static str,
+    Option<serde_json::Value>,
+    Option<serde_json::Value>,
+)> {
+    let value = |v: &Option<_>| v.as_ref().and_then(|v| serde_json::to_value(v).ok());
+    match domain {
+        BackupDomain::Settings => vec![
+            ("trading", value(&backup.trading), value(&local.trading)),
+            ("security", value(&backup.security), value(&local.security)),
+            (
+                "appearance",
+                value(&backup.appearance),
+                value(&local.appearance),
+            ),
+            (
+                "notifications",
+                value(&backup.notifications),
+                value(&local.notifications),
+            ),
+            ("custom", value(&backup.custom), value(&local.custom)),
+        ],
+        BackupDomain::ApiKeys => vec![("api", value(&backup.api), value(&local.api))],
+        _ => Vec::new(),
+    }
+}
+
+async fn plan_and_apply(
+    app: &AppHandle,
+    backup_service: &SharedBackupService,
+    provider: &CloudProvider,
+    filename: &str,
+    domains: Vec<BackupDomain>,
+    strategy: MergeStrategy,
+    preview: bool,
+) -> Result<SelectiveRestoreReport, String> {
+    let (archive, local_settings, settings_modified_at) = {
+        let service = backup_service.read().await;
+        let archive = service
+            .load_archive(provider, filename)
+            .map_err(|e| e.to_string())?;
+        let local = service.export_settings(None).map_err(|e| e.to_string())?;
+        (archive, local, service.settings_modified_at())
+    };
+
+    let requested = if domains.is_empty() {
+        BackupDomain::ALL.to_vec()
+    } else {
+        domains
+    };
+    let mut domains = Vec::with_capacity(requested.len());
+    for domain in requested {
+        if !domains.contains(&domain) {
+            domains.push(domain);
+        }
+    }
+
+    let mut items = Vec::new();
+    let mut missing_domains = Vec::new();
+    let mut restored_settings = AppSettings {
+        version: archive.settings.version,
+        exported_at: archive.settings.exported_at,
+        trading: None,
+        security: None,
+        appearance: None,
+        api: None,
+        notifications: None,
+        custom: None,
+    };
+
+    for &domain in &domains {
+        match domain {
+            BackupDomain::Watchlists => {
+                let (Some(backup), Some(manager)) = (
+                    archive.domains.watchlists.as_ref(),
+                    app.try_state::<SharedWatchlistManager>(),
+                ) else {
+                    missing_domains.push(domain);
+                    continue;
+                };
+                let manager = manager.read().await;
+                let local = manager.list_watchlists().await.map_err(|e| e.to_string())?;
+                for watchlist in backup {
+                    let existing = local.iter().find(|w| w.id == watchlist.id);
+                    let mut item = RestoreItem {
+                        domain,
+                        id: watchlist.id.clone(),
+                        label: watchlist.name.clone(),
+                        local_updated_at: existing.and_then(|w| parse_time(&w.updated_at)),
+                        backup_updated_at: parse_time(&watchlist.updated_at),
+                        resolution: RestoreResolution::Unchanged,
+                        error: None,
+                    };
+                    item.resolution = resolve_restore(
+                        strategy,
+                        existing.map(|_| item.local_updated_at),
+                        item.backup_updated_at,
+                        existing.is_some_and(|w| same(w, watchlist)),
+                    );
+                    if !preview && writes(item.resolution) {
+                        item.error = manager
+                            .restore_watchlist(watchlist)
+                            .await
+                            .err()
+                            .map(|e| e.to_string());
+                    }
+                    items.push(item);
+                }
+            }
+            BackupDomain::Alerts => {
+                let (Some(backup), Some(manager)) = (
+                    archive.domains.alerts.as_ref(),
+                    app.try_state::<SharedAlertManager>(),
+                ) else {
+                    missing_domains.push(domain);
+                    continue;
+                };
+                let manager = manager.read().await;
+                let local = manager.list_alerts().await.map_err(|e| e.to_string())?;
+                for alert in backup {
+                    let existing = local.iter().find(|a| a.id == alert.id);
+                    let mut item = RestoreItem {
+                        domain,
+                        id: alert.id.clone(),
+                        label: alert.name.clone(),
+                        local_updated_at: existing.and_then(|a| parse_time(&a.updated_at)),
+                        backup_updated_at: parse_time(&alert.updated_at),
+                        resolution: RestoreResolution::Unchanged,
+                        error: None,
+                    };
+                    item.resolution = resolve_restore(
+                        strategy,
+                        existing.map(|_| item.local_updated_at),
+                        item.backup_updated_at,
+                        existing.is_some_and(|a| same(a, alert)),
+                    );
+                    if !preview && writes(item.resolution) {
+                        item.error = manager
+                            .upsert_alert(alert)
+                            .await
+                            .err()
+                            .map(|e| e.to_string());
+                    }
+                    items.push(item);
+                }
+            }
+            BackupDomain::Journal => {
+                let (Some(backup), Some(db)) = (
+                    archive.domains.journal.as_ref(),
+                    app.try_state::<SharedJournalDatabase>(),
+                ) else {
+                    missing_domains.push(domain);
+                    continue;
+                };
+                let db = db.read().await;
+                for entry in backup {
+                    let existing = db.get_entry(&entry.id).await.map_err(|e| e.to_string())?;
+                    let mut item = RestoreItem {
+                        domain,
+                        id: entry.id.clone(),
+                        label: journal_label(entry),
+                        local_updated_at: existing.as_ref().and_then(|e| unix_time(e.updated_at)),
+                        backup_updated_at: unix_time(entry.updated_at),
+                        resolution: RestoreResolution::Unchanged,
+                        error: None,
+                    };
+                    item.resolution = resolve_restore(
+                        strategy,
+                        existing.as_ref().map(|_| item.local_updated_at),
+                        item.backup_updated_at,
+                        existing.as_ref().is_some_and(|e| same(e, entry)),
+                    );
+                    if !preview && writes(item.resolution) {
+                        let result = match item.resolution {
+                            RestoreResolution::Create => db.create_entry(entry).await,
+                            _ => db.update_entry(entry).await,
+                        };
+                        item.error = result.err().map(|e| e.to_string());
+                    }
+                    items.push(item);
+                }
+            }
+            BackupDomain::Settings | BackupDomain::ApiKeys => {
+                let sections = settings_sections(domain, &archive.settings, &local_settings);
+                if sections.iter().all(|(_, backup, _)| backup.is_none()) {
+                    missing_domains.push(domain);
+                    continue;
+                }
+                // Settings carry no per-section timestamps, so keep-newer
+                // compares the archive's export time with the settings file.
+                let backup_updated_at = Some(archive.settings.exported_at);
+                for (section, backup, local) in sections {
+                    let Some(backup) = backup else {
+                        continue;
+                    };
+                    let resolution = resolve_restore(
+                        strategy,
+                        local.as_ref().map(|_| settings_modified_at),
+                        backup_updated_at,
+                        local.as_ref() == Some(&backup),
+                    );
+                    if writes(resolution) {
+                        take_section(&mut restored_settings, &archive.settings, section);
+                    }
+                    items.push(RestoreItem {
+                        domain,
+                        id: section.to_string(),
+                        label: format!("{} settings", section),
+                        local_updated_at: local.as_ref().and(settings_modified_at),
+                        backup_updated_at,
+                        resolution,
+                        error: None,
+                    });
+                }
+            }
+        }
+    }
+
+    if !preview && has_sections(&restored_settings) {
+        let result = backup_service
+            .read()
+            .await
+            .import_settings(restored_settings, true)
+            .map_err(|e| e.to_string());
+        if let Err(error) = result {
+            for item in items.iter_mut().filter(|i| {
+                matches!(i.domain, BackupDomain::Settings | BackupDomain::ApiKeys)
+                    && writes(i.resolution)
+            }) {
+                item.error = Some(error.clone());
+            }
+        }
+    }
+
+    let conflicts = items.iter().filter(|i| i.is_conflict()).count();
+    let failed = items.iter().filter(|i| i.error.is_some()).count();
+    let applied = if preview {
+        0
+    } else {
+        items
+            .iter()
+            .filter(|i| writes(i.resolution) && i.error.is_none())
+            .count()
+    };
+
+    Ok(SelectiveRestoreReport {
+        filename: filename.to_string(),
+        strategy,
+        preview,
+        domains,
+        missing_domains,
+        items,
+        conflicts,
+        applied,
+        failed,
+    })
+}
+
+fn writes(resolution: RestoreResolution) -> bool {
+    matches!(
+        resolution,
+        RestoreResolution::Create | RestoreResolution::Overwrite
+    )
+}
+
+fn journal_label(entry: &JournalEntry) -> String {
+    let note: String = entry.notes.chars().take(40).collect();
+    match unix_time(entry.timestamp) {
+        Some(at) => format!("{} {}", at.format("%Y-%m-%d"), note),
+        None => note,
+    }
+}
+
+fn take_section(target: &mut AppSettings, source: &AppSettings, section: &str) {
+    match section {
+        "trading" => target.trading = source.trading.clone(),
+        "security" => target.security = source.security.clone(),
+        "appearance" => target.appearance = source.appearance.clone(),
+        "notifications" => target.notifications = source.notifications.clone(),
+        "custom" => target.custom = source.custom.clone(),
+        "api" => target.api = source.api.clone(),
+        _ => {}
+    }
+}
+
+fn has_sections(settings: &AppSettings) -> bool {
+    settings.trading.is_some()
+        || settings.security.is_some()
+        || settings.appearance.is_some()
+        || settings.notifications.is_some()
+        || settings.custom.is_some()
+        || settings.api.is_some()
+}
+
+/// Lists what restoring the given domains would do, including every
+/// conflict, without writing anything.
+#[tauri::command]
+pub async fn preview_selective_restore(
+    app: AppHandle,
+    provider: CloudProvider,
+    filename: String,
+    domains: Vec<BackupDomain>,
+    strategy: MergeStrategy,
+    backup_service: State<'_, SharedBackupService>,
+) -> Result<SelectiveRestoreReport, String> {
+    plan_and_apply(
+        &app,
+        backup_service.inner(),
+        &provider,
+        &filename,
+        domains,
+        strategy,
+        true,
+    )
+    .await
+}
+
+/// Restores only the given domains from an archive. An empty `domains`
+/// list restores everything the archive contains.
+#[tauri::command]
+pub async fn restore_backup_selective(
+    app: AppHandle,
+    provider: CloudProvider,
+    filename: String,
+    domains: Vec<BackupDomain>,
+    strategy: MergeStrategy,
+    backup_service: State<'_, SharedBackupService>,
+) -> Result<SelectiveRestoreReport, String> {
+    plan_and_apply(
+        &app,
+        backup_service.inner(),
+        &provider,
+        &filename,
+        domains,
+        strategy,
+        false,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_follows_strategy_on_conflict() {
+        let older = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).single();
+        let newer = Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).single();
+
+        assert_eq!(
+            resolve_restore(MergeStrategy::Skip, None, newer, false),
+            RestoreResolution::Create
+        );
+        assert_eq!(
+            resolve_restore(MergeStrategy::Overwrite, Some(newer), older, true),
+            RestoreResolution::Unchanged
+        );
+        assert_eq!(
+            resolve_restore(MergeStrategy::Overwrite, Some(newer), older, false),
+            RestoreResolution::Overwrite
+        );
+        assert_eq!(
+            resolve_restore(MergeStrategy::Skip, Some(older), newer, false),
+            RestoreResolution::KeepLocal
+        );
+        assert_eq!(
+            resolve_restore(MergeStrategy::KeepNewer, Some(older), newer, false),
+            RestoreResolution::Overwrite
+        );
+        assert_eq!(
+            resolve_restore(MergeStrategy::KeepNewer, Some(newer), older, false),
+            RestoreResolution::KeepLocal
+        );
+        assert_eq!(
+            resolve_restore(MergeStrategy::KeepNewer, Some(older), None, false),
+            RestoreResolution::KeepLocal
+        );
+    }
+
+    #[test]
+    fn test_settings_only_archive_has_no_domain_data() {
+        let legacy = serde_json::json!({
+            "version": 1,
+            "exportedAt": "2026-01-01T00:00:00Z",
+            "trading": null,
+            "security": null,
+            "appearance": null,
+            "api": { "birdeyeKey": "k", "heliusKey": null, "customRpc": null },
+            "notifications": null,
+            "custom": null
+        });
+        let archive: BackupArchive = serde_json::from_value(legacy).unwrap();
+        assert!(archive.domains.watchlists.is_none());
+        assert!(archive.domains.journal.is_none());
+        assert_eq!(
+            archive.settings.api.and_then(|api| api.birdeye_key),
+            Some("k".to_string())
+        );
+    }
+}
@@ -5,6 +5,7 @@ use aes_gcm::{
 };
 use base64::{engine::general_purpose::STANDARD as BASE64_ENGINE, Engine};
 use chrono::Utc;
+use crate::profiles::ProfilePaths;
 use rand_core::RngCore;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -23,6 +24,7 @@ use super::cloud_providers::{
 use super::scheduler::{
     BackupSchedule, BackupScheduler, BackupStatus, SchedulerError, SharedBackupScheduler,
 };
+use super::selective::{capture_domains, BackupArchive, BackupDomainData};
 use super::settings_manager::{AppSettings, SettingsError, SettingsManager};
 
 const BACKUP_KEY_ID: &str = "backup.encryption_key";
@@ -79,7 +81,7 @@ impl BackupService {
         }
     }
 
-    fn get_or_create_backup_key(&self, keystore: &Keystore) -> Result<Vec<u8>, BackupError> {
+    pub(super) fn get_or_create_backup_key(&self, keystore: &Keystore) -> Result<Vec<u8>, BackupError> {
         match keystore.retrieve_secret(BACKUP_KEY_ID) {
             Ok(key) => Ok(key.to_vec()),
             Err(KeystoreError::NotFound) => {
@@ -157,6 +159,7 @@ impl BackupService {
         &self,
         provider: &CloudProvider,
         sections: Option<Vec<String>>,
+        domains: BackupDomainData,
     ) -> Result<BackupMetadata, BackupError> {
         let keystore = self
             .app_handle
@@ -167,7 +170,7 @@ impl BackupService {
         let settings = self.settings_manager.export_settings(sections)?;
 
         // Serialize to JSON
-        let json = serde_json::to_vec(&settings)?;
+        let json = serde_json::to_vec(&BackupArchive { settings, domains })?;
 
         // Encrypt
         let encrypted = self.encrypt_data(&*keystore, &json)?;
@@ -193,7 +196,7 @@ impl BackupService {
     }
 
     fn config_path(&self) -> Result<PathBuf, BackupError> {
-        let mut path = self.app_handle.path().app_data_dir().map_err(|e| {
+        let mut path = self.app_handle.path().profile_data_dir().map_err(|e| {
             std::io::Error::new(std::io::ErrorKind::NotFound, format!("App data directory not found: {}", e))
         })?;
         if !path.exists() {
@@ -332,6 +335,7 @@ impl BackupService {
         &self,
         provider_id: &str,
         sections: Option<Vec<String>>,
+        domains: BackupDomainData,
     ) -> Result<BackupMetadata, BackupError> {
         let keystore = self
             .app_handle
@@ -354,13 +358,16 @@ impl BackupService {
         }
 
         let provider = configs[index].provider.clone();
-        let metadata = self.create_backup_with_provider(&provider, sections)?;
+        let metadata = self.create_backup_with_provider(&provider, sections, domains)?;
         configs[index].last_sync = Some(metadata.created_at);
         self.save_provider_configs_internal(&*keystore, &configs)?;
         Ok(metadata)
     }
 
-    pub fn create_default_backup(&self) -> Result<Option<BackupMetadata>, BackupError> {
+    pub fn create_default_backup(
+        &self,
+        domains: BackupDomainData,
+    ) -> Result<Option<BackupMetadata>, BackupError> {
         let keystore = self
             .app_handle
             .try_state::<Keystore>()
@@ -377,7 +384,7 @@ impl BackupService {
         };
 
         let provider = configs[index].provider.clone();
-        let metadata = self.create_backup_with_provider(&provider, None)?;
+        let metadata = self.create_backup_with_provider(&provider, None, domains)?;
         configs[index].last_sync = Some(metadata.created_at);
         self.save_provider_configs_internal(&*keystore, &configs)?;
         Ok(Some(metadata))
@@ -412,6 +419,27 @@ impl BackupService {
         Ok(())
     }
 
+    /// Downloads and decrypts an archive without applying any of it.
+    pub fn load_archive(
+        &self,
+        provider: &CloudProvider,
+        filename: &str,
+    ) -> Result<BackupArchive, BackupError> {
+        let keystore = self
+            .app_handle
+            .try_state::<Keystore>()
+            .ok_or(BackupError::KeystoreUnavailable)?;
+
+        let backup_data = self.cloud_manager.download_backup(provider, filename)?;
+        let encrypted: EncryptedBackup = serde_json::from_slice(&backup_data)?;
+        let plaintext = self.decrypt_data(&*keystore, &encrypted)?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    pub fn settings_modified_at(&self) -> Option<chrono::DateTime<Utc>> {
+        self.settings_manager.last_modified()
+    }
+
     pub fn list_backups(
         &self,
         provider: &CloudProvider,
@@ -474,13 +502,15 @@ impl BackupService {
 // Tauri commands
 #[tauri::command]
 pub async fn create_backup(
+    app: AppHandle,
     provider: CloudProvider,
     sections: Option<Vec<String>>,
     backup_service: State<'_, SharedBackupService>,
 ) -> Result<BackupMetadata, String> {
+    let domains = capture_domains(&app, sections.as_deref()).await;
     let service = backup_service.read().await;
     service
-        .create_backup_with_provider(&provider, sections)
+        .create_backup_with_provider(&provider, sections, domains)
         .map_err(|e| e.to_string())
 }
 
@@ -597,6 +627,7 @@ pub async fn get_backup_status(
 
 #[tauri::command]
 pub async fn trigger_manual_backup(
+    app: AppHandle,
     provider: CloudProvider,
     backup_service: State<'_, SharedBackupService>,
     scheduler: State<'_, SharedBackupScheduler>,
@@ -606,9 +637,10 @@ pub async fn trigger_manual_backup(
         sched.start_backup();
     }
 
+    let domains = capture_domains(&app, None).await;
     let result = {
         let service = backup_service.read().await;
-        service.create_backup_with_provider(&provider, None)
+        service.create_backup_with_provider(&provider, None, domains)
     };
 
     match result {
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -108,7 +109,7 @@ impl SettingsManager {
         let mut path = self
             .app_handle
             .path()
-            .app_data_dir()
+            .profile_data_dir()
             .map_err(|e| {
                 SettingsError::Io(std::io::Error::new(
                     std::io::ErrorKind::NotFound,
@@ -124,6 +125,15 @@ impl SettingsManager {
         Ok(path)
     }
 
+    /// When the settings file was last written, if it exists.
+    pub fn last_modified(&self) -> Option<DateTime<Utc>> {
+        let modified = fs::metadata(self.settings_path().ok()?)
+            .ok()?
+            .modified()
+            .ok()?;
+        Some(DateTime::<Utc>::from(modified))
+    }
+
     pub fn export_settings(
         &self,
         sections: Option<Vec<String>>,
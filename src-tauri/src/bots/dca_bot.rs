@@ -1,6 +1,8 @@
 use crate::api::jupiter::{
-    jupiter_quote, PriorityFeeConfig, QuoteCommandInput, QuoteResult, SwapMode,
+    fetch_quote, PriorityFeeConfig, QuoteCommandInput, QuoteResult, SwapMode,
 };
+use crate::profiles::ProfilePaths;
+use crate::trading::token_policy::{check_token_policy, ExecutionPath};
 use crate::utils::{OptionalRfc3339DateTime, Rfc3339DateTime};
 use chrono::{DateTime, NaiveDateTime, Utc};
 use cron::Schedule;
@@ -420,6 +422,25 @@ impl DcaDatabase {
         .await
     }
 
+    /// Signatures of every landed DCA execution for `wallet_address`.
+    pub async fn execution_signatures(
+        &self,
+        wallet_address: &str,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT e.tx_signature
+            FROM dca_executions e
+            JOIN dca_configs c ON c.id = e.dca_config_id
+            WHERE c.wallet_address = ?1 AND e.tx_signature IS NOT NULL
+            "#,
+        )
+        .bind(wallet_address)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(|row| row.try_get("tx_signature")).collect()
+    }
+
     pub async fn execution_summary(
         &self,
         dca_id: &str,
@@ -502,6 +523,7 @@ impl DcaManager {
         if request.max_price_impact_pct <= 0.0 {
             return Err("Max price impact must be greater than zero".into());
         }
+        check_token_policy(ExecutionPath::Bot, &request.output_mint).await?;
 
         let schedule = Schedule::from_str(&request.schedule_cron)
             .map_err(|e| format!("Invalid cron expression: {e}"))?;
@@ -760,6 +782,13 @@ impl DcaManager {
             }
         }
 
+        if let Err(reason) = check_token_policy(ExecutionPath::Bot, &config.output_mint).await {
+            self.log_execution(config, 0.0, 0.0, 0.0, "skipped", Some(reason), None)
+                .await?;
+            self.schedule_next(config, None).await?;
+            return Ok(());
+        }
+
         let amount_in_units = to_base_units(config.amount_per_execution, config.input_decimals)?;
 
         let quote_input = QuoteCommandInput {
@@ -778,7 +807,7 @@ impl DcaManager {
             }),
         };
 
-        let quote_result: QuoteResult = jupiter_quote(quote_input)
+        let quote_result: QuoteResult = fetch_quote(&quote_input)
             .await
             .map_err(|e| format!("Failed to fetch quote: {e}"))?;
 
@@ -934,7 +963,7 @@ impl DcaManager {
     }
 }
 
-fn to_base_units(amount: f64, decimals: i32) -> Result<u64, String> {
+pub(super) fn to_base_units(amount: f64, decimals: i32) -> Result<u64, String> {
     if amount < 0.0 {
         return Err("Amount cannot be negative".into());
     }
@@ -949,7 +978,7 @@ fn to_base_units(amount: f64, decimals: i32) -> Result<u64, String> {
     Ok(value as u64)
 }
 
-fn parse_amount(raw: &str, decimals: i32) -> f64 {
+pub(super) fn parse_amount(raw: &str, decimals: i32) -> f64 {
     raw.parse::<f64>().unwrap_or_default() / 10f64.powi(decimals)
 }
 
@@ -983,7 +1012,7 @@ pub async fn init_dca(app_handle: &AppHandle) -> Result<(), String> {
 
     let app_dir = app_handle
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|e| format!("Unable to resolve app data directory: {}", e))?;
 
     std::fs::create_dir_all(&app_dir)
@@ -1023,6 +1052,21 @@ fn require_state<'a>() -> Result<&'a DcaState, String> {
         .ok_or_else(|| "DCA module not initialized".to_string())
 }
 
+/// DCA execution signatures for a wallet, or none when the DCA module has
+/// not been initialized this session.
+pub async fn dca_trade_signatures(wallet_address: &str) -> Result<Vec<String>, String> {
+    let Some(state) = DCA_STATE.get() else {
+        return Ok(Vec::new());
+    };
+    state
+        .db
+        .read()
+        .await
+        .execution_signatures(wallet_address)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn dca_init(handle: AppHandle) -> Result<(), String> {
     init_dca(&handle).await
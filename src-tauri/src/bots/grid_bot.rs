@@ -0,0 +1,1131 @@
+use super::dca_bot::{parse_amount, to_base_units};
+use crate::api::jupiter::{fetch_quote, QuoteCommandInput, SwapMode};
+use crate::profiles::ProfilePaths;
+use crate::trading::token_policy::{check_token_policy, ExecutionPath};
+use crate::utils::Rfc3339DateTime;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Row, Sqlite, SqlitePool};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{OnceCell, RwLock};
+use tokio::time::{interval, Duration};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GridSpacing {
+    /// Levels an equal price distance apart.
+    #[default]
+    Arithmetic,
+    /// Levels an equal percentage apart.
+    Geometric,
+}
+
+impl GridSpacing {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GridSpacing::Arithmetic => "arithmetic",
+            GridSpacing::Geometric => "geometric",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "arithmetic" => Some(GridSpacing::Arithmetic),
+            "geometric" => Some(GridSpacing::Geometric),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GridSide {
+    Buy,
+    Sell,
+}
+
+impl GridSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GridSide::Buy => "buy",
+            GridSide::Sell => "sell",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "buy" => Some(GridSide::Buy),
+            "sell" => Some(GridSide::Sell),
+            _ => None,
+        }
+    }
+}
+
+/// A simulated grid. Fills are booked at Jupiter quote prices and no
+/// swap is ever sent, so the grid trades paper balances only.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridConfig {
+    pub id: String,
+    pub name: String,
+    pub wallet_address: String,
+    pub base_mint: String,
+    pub quote_mint: String,
+    pub base_symbol: String,
+    pub quote_symbol: String,
+    pub base_decimals: i32,
+    pub quote_decimals: i32,
+    pub lower_price: f64,
+    pub upper_price: f64,
+    pub grid_count: i32,
+    /// Quote spent by each buy level.
+    pub order_size_quote: f64,
+    pub spacing: GridSpacing,
+    pub base_held: f64,
+    /// Quote paid for the base currently held.
+    pub cost_basis: f64,
+    pub realized_pnl: f64,
+    pub total_invested: f64,
+    pub fill_count: i64,
+    pub is_active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_price: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl GridConfig {
+    fn record_buy(&mut self, base_amount: f64, quote_amount: f64) {
+        self.base_held += base_amount;
+        self.cost_basis += quote_amount;
+    }
+
+    /// Books the sale against the average cost of the base held and returns
+    /// the realized profit.
+    fn record_sell(&mut self, base_amount: f64, quote_amount: f64) -> f64 {
+        let average_cost = if self.base_held > 0.0 {
+            self.cost_basis / self.base_held
+        } else {
+            0.0
+        };
+        let sold = base_amount.min(self.base_held);
+        let realized = quote_amount - sold * average_cost;
+        self.base_held -= sold;
+        self.cost_basis -= sold * average_cost;
+        self.realized_pnl += realized;
+        realized
+    }
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> for GridConfig {
+    fn from_row(row: &'r sqlx::sqlite::SqliteRow) -> Result<Self, sqlx::Error> {
+        Ok(GridConfig {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            wallet_address: row.try_get("wallet_address")?,
+            base_mint: row.try_get("base_mint")?,
+            quote_mint: row.try_get("quote_mint")?,
+            base_symbol: row.try_get("base_symbol")?,
+            quote_symbol: row.try_get("quote_symbol")?,
+            base_decimals: row.try_get("base_decimals")?,
+            quote_decimals: row.try_get("quote_decimals")?,
+            lower_price: row.try_get("lower_price")?,
+            upper_price: row.try_get("upper_price")?,
+            grid_count: row.try_get("grid_count")?,
+            order_size_quote: row.try_get("order_size_quote")?,
+            spacing: GridSpacing::from_str(&row.try_get::<String, _>("spacing")?)
+                .unwrap_or_default(),
+            base_held: row.try_get("base_held")?,
+            cost_basis: row.try_get("cost_basis")?,
+            realized_pnl: row.try_get("realized_pnl")?,
+            total_invested: row.try_get("total_invested")?,
+            fill_count: row.try_get("fill_count")?,
+            is_active: row.try_get("is_active")?,
+            last_price: row.try_get("last_price")?,
+            created_at: Rfc3339DateTime::try_from(row.try_get::<String, _>("created_at")?)?.into(),
+            updated_at: Rfc3339DateTime::try_from(row.try_get::<String, _>("updated_at")?)?.into(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridLevel {
+    pub level_index: i32,
+    pub price: f64,
+    /// None for the idle level nearest the market.
+    pub side: Option<GridSide>,
+    /// Base to sell; unused by buy levels, which spend `order_size_quote`.
+    pub base_amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridFill {
+    pub id: String,
+    pub grid_id: String,
+    /// None for the initial inventory purchase.
+    pub level_index: Option<i32>,
+    pub side: GridSide,
+    pub price: f64,
+    pub base_amount: f64,
+    pub quote_amount: f64,
+    pub realized_pnl: f64,
+    pub executed_at: DateTime<Utc>,
+    /// Always None: grid fills are simulated.
+    pub tx_signature: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateGridRequest {
+    pub name: String,
+    pub wallet_address: String,
+    pub base_mint: String,
+    pub quote_mint: String,
+    pub base_symbol: String,
+    pub quote_symbol: String,
+    pub base_decimals: i32,
+    pub quote_decimals: i32,
+    pub lower_price: f64,
+    pub upper_price: f64,
+    pub grid_count: i32,
+    pub order_size_quote: f64,
+    #[serde(default)]
+    pub spacing: GridSpacing,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GridPerformance {
+    pub grid_id: String,
+    pub current_price: Option<f64>,
+    pub in_range: bool,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    pub total_pnl: f64,
+    pub total_invested: f64,
+    pub roi_pct: f64,
+    pub base_held: f64,
+    pub average_cost: f64,
+    pub fill_count: i64,
+    pub levels: Vec<GridLevel>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GridFillEvent {
+    pub grid_id: String,
+    pub name: String,
+    pub side: GridSide,
+    pub level_index: Option<i32>,
+    pub price: f64,
+    pub base_amount: f64,
+    pub quote_amount: f64,
+    pub realized_pnl: f64,
+}
+
+/// Level prices from `lower` to `upper` inclusive.
+pub fn grid_prices(
+    lower: f64,
+    upper: f64,
+    count: usize,
+    spacing: GridSpacing,
+) -> Result<Vec<f64>, String> {
+    if !(lower > 0.0 && upper > lower) {
+        return Err("Upper price must be above a positive lower price".into());
+    }
+    if count < 2 {
+        return Err("A grid needs at least two levels".into());
+    }
+    let steps = (count - 1) as f64;
+    let prices = match spacing {
+        GridSpacing::Arithmetic => {
+            let step = (upper - lower) / steps;
+            (0..count).map(|i| lower + step * i as f64).collect()
+        }
+        GridSpacing::Geometric => {
+            let ratio = (upper / lower).powf(1.0 / steps);
+            (0..count).map(|i| lower * ratio.powi(i as i32)).collect()
+        }
+    };
+    Ok(prices)
+}
+
+/// Buys below the market, sells above it, and leaves the level nearest the
+/// current price idle.
+pub fn initial_levels(prices: &[f64], current_price: f64) -> Vec<GridLevel> {
+    let idle = prices
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| {
+            (*a - current_price)
+                .abs()
+                .total_cmp(&(*b - current_price).abs())
+        })
+        .map(|(i, _)| i)
+        .unwrap_or_default();
+    prices
+        .iter()
+        .enumerate()
+        .map(|(i, &price)| GridLevel {
+            level_index: i as i32,
+            price,
+            side: match i.cmp(&idle) {
+                std::cmp::Ordering::Less => Some(GridSide::Buy),
+                std::cmp::Ordering::Equal => None,
+                std::cmp::Ordering::Greater => Some(GridSide::Sell),
+            },
+            base_amount: 0.0,
+        })
+        .collect()
+}
+
+/// The level that `price` has crossed, taking the one nearest the idle
+/// level first so fills happen in the order the market reached them.
+pub fn next_triggered(levels: &[GridLevel], price: f64) -> Option<usize> {
+    let buy = levels
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.side == Some(GridSide::Buy) && price <= l.price)
+        .max_by(|(_, a), (_, b)| a.price.total_cmp(&b.price))
+        .map(|(i, _)| i);
+    let sell = levels
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.side == Some(GridSide::Sell) && price >= l.price)
+        .min_by(|(_, a), (_, b)| a.price.total_cmp(&b.price))
+        .map(|(i, _)| i);
+    buy.or(sell)
+}
+
+/// After a fill the filled level goes idle and the neighbour on the other
+/// side of it takes the opposite order: a buy is sold one level up, a sell
+/// is bought back one level down.
+pub fn rebalance(levels: &mut [GridLevel], index: usize, base_filled: f64) {
+    let Some(side) = levels[index].side.take() else {
+        return;
+    };
+    levels[index].base_amount = 0.0;
+    match side {
+        GridSide::Buy => {
+            if let Some(above) = levels.get_mut(index + 1) {
+                above.side = Some(GridSide::Sell);
+                above.base_amount = base_filled;
+            }
+        }
+        GridSide::Sell => {
+            if let Some(below) = index.checked_sub(1).and_then(|i| levels.get_mut(i)) {
+                below.side = Some(GridSide::Buy);
+                below.base_amount = 0.0;
+            }
+        }
+    }
+}
+
+pub struct GridDatabase {
+    pool: Pool<Sqlite>,
+}
+
+impl GridDatabase {
+    pub async fn new(db_path: PathBuf) -> Result<Self, sqlx::Error> {
+        let db_url = format!("sqlite:{}?mode=rwc", db_path.display());
+        let pool = SqlitePool::connect(&db_url).await?;
+        let db = Self { pool };
+        db.initialize().await?;
+        Ok(db)
+    }
+
+    async fn initialize(&self) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS grid_bots (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                wallet_address TEXT NOT NULL,
+                base_mint TEXT NOT NULL,
+                quote_mint TEXT NOT NULL,
+                base_symbol TEXT NOT NULL,
+                quote_symbol TEXT NOT NULL,
+                base_decimals INTEGER NOT NULL,
+                quote_decimals INTEGER NOT NULL,
+                lower_price REAL NOT NULL,
+                upper_price REAL NOT NULL,
+                grid_count INTEGER NOT NULL,
+                order_size_quote REAL NOT NULL,
+                spacing TEXT NOT NULL,
+                -- Unused: simulated fills take no slippage or priority fee.
+                slippage_bps INTEGER NOT NULL DEFAULT 0,
+                priority_fee_micro_lamports INTEGER NOT NULL DEFAULT 0,
+                base_held REAL NOT NULL DEFAULT 0,
+                cost_basis REAL NOT NULL DEFAULT 0,
+                realized_pnl REAL NOT NULL DEFAULT 0,
+                total_invested REAL NOT NULL DEFAULT 0,
+                fill_count INTEGER NOT NULL DEFAULT 0,
+                is_active INTEGER NOT NULL DEFAULT 1,
+                last_price REAL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS grid_levels (
+                grid_id TEXT NOT NULL,
+                level_index INTEGER NOT NULL,
+                price REAL NOT NULL,
+                side TEXT,
+                base_amount REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY (grid_id, level_index),
+                FOREIGN KEY (grid_id) REFERENCES grid_bots(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS grid_fills (
+                id TEXT PRIMARY KEY,
+                grid_id TEXT NOT NULL,
+                level_index INTEGER,
+                side TEXT NOT NULL,
+                price REAL NOT NULL,
+                base_amount REAL NOT NULL,
+                quote_amount REAL NOT NULL,
+                realized_pnl REAL NOT NULL,
+                executed_at TEXT NOT NULL,
+                tx_signature TEXT,
+                FOREIGN KEY (grid_id) REFERENCES grid_bots(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_grid_bots_wallet ON grid_bots(wallet_address);
+            CREATE INDEX IF NOT EXISTS idx_grid_bots_active ON grid_bots(is_active);
+            CREATE INDEX IF NOT EXISTS idx_grid_fills_grid ON grid_fills(grid_id);
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn create_grid(
+        &self,
+        config: &GridConfig,
+        levels: &[GridLevel],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO grid_bots (
+                id, name, wallet_address, base_mint, quote_mint,
+                base_symbol, quote_symbol, base_decimals, quote_decimals,
+                lower_price, upper_price, grid_count, order_size_quote, spacing,
+                slippage_bps, priority_fee_micro_lamports,
+                base_held, cost_basis, realized_pnl, total_invested, fill_count,
+                is_active, last_price, created_at, updated_at
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5,
+                ?6, ?7, ?8, ?9,
+                ?10, ?11, ?12, ?13, ?14,
+                0, 0,
+                ?15, ?16, ?17, ?18, ?19,
+                ?20, ?21, ?22, ?23
+            )
+            "#,
+        )
+        .bind(&config.id)
+        .bind(&config.name)
+        .bind(&config.wallet_address)
+        .bind(&config.base_mint)
+        .bind(&config.quote_mint)
+        .bind(&config.base_symbol)
+        .bind(&config.quote_symbol)
+        .bind(config.base_decimals)
+        .bind(config.quote_decimals)
+        .bind(config.lower_price)
+        .bind(config.upper_price)
+        .bind(config.grid_count)
+        .bind(config.order_size_quote)
+        .bind(config.spacing.as_str())
+        .bind(config.base_held)
+        .bind(config.cost_basis)
+        .bind(config.realized_pnl)
+        .bind(config.total_invested)
+        .bind(config.fill_count)
+        .bind(if config.is_active { 1 } else { 0 })
+        .bind(config.last_price)
+        .bind(config.created_at.to_rfc3339())
+        .bind(config.updated_at.to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+
+        for level in levels {
+            sqlx::query(
+                r#"
+                INSERT INTO grid_levels (grid_id, level_index, price, side, base_amount)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                "#,
+            )
+            .bind(&config.id)
+            .bind(level.level_index)
+            .bind(level.price)
+            .bind(level.side.map(|s| s.as_str()))
+            .bind(level.base_amount)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    pub async fn get_grid(&self, id: &str) -> Result<Option<GridConfig>, sqlx::Error> {
+        sqlx::query_as::<_, GridConfig>("SELECT * FROM grid_bots WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn list_grids(&self, wallet_address: &str) -> Result<Vec<GridConfig>, sqlx::Error> {
+        sqlx::query_as::<_, GridConfig>(
+            "SELECT * FROM grid_bots WHERE wallet_address = ?1 ORDER BY created_at DESC",
+        )
+        .bind(wallet_address)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_active_grids(&self) -> Result<Vec<GridConfig>, sqlx::Error> {
+        sqlx::query_as::<_, GridConfig>("SELECT * FROM grid_bots WHERE is_active = 1")
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    pub async fn get_levels(&self, grid_id: &str) -> Result<Vec<GridLevel>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT level_index, price, side, base_amount FROM grid_levels WHERE grid_id = ?1 ORDER BY level_index",
+        )
+        .bind(grid_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(GridLevel {
+                    level_index: row.try_get("level_index")?,
+                    price: row.try_get("price")?,
+                    side: row
+                        .try_get::<Option<String>, _>("side")?
+                        .as_deref()
+                        .and_then(GridSide::from_str),
+                    base_amount: row.try_get("base_amount")?,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn update_status(&self, id: &str, is_active: bool) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE grid_bots SET is_active = ?1, updated_at = ?2 WHERE id = ?3")
+            .bind(if is_active { 1 } else { 0 })
+            .bind(Utc::now().to_rfc3339())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_last_price(&self, id: &str, price: f64) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE grid_bots SET last_price = ?1 WHERE id = ?2")
+            .bind(price)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Stores a fill together with the grid's new book and levels so they
+    /// cannot drift apart.
+    pub async fn record_fill(
+        &self,
+        config: &GridConfig,
+        levels: &[GridLevel],
+        fill: &GridFill,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO grid_fills (
+                id, grid_id, level_index, side, price, base_amount, quote_amount,
+                realized_pnl, executed_at, tx_signature
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#,
+        )
+        .bind(&fill.id)
+        .bind(&fill.grid_id)
+        .bind(fill.level_index)
+        .bind(fill.side.as_str())
+        .bind(fill.price)
+        .bind(fill.base_amount)
+        .bind(fill.quote_amount)
+        .bind(fill.realized_pnl)
+        .bind(fill.executed_at.to_rfc3339())
+        .bind(&fill.tx_signature)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE grid_bots
+            SET base_held = ?1, cost_basis = ?2, realized_pnl = ?3, fill_count = ?4,
+                last_price = ?5, updated_at = ?6
+            WHERE id = ?7
+            "#,
+        )
+        .bind(config.base_held)
+        .bind(config.cost_basis)
+        .bind(config.realized_pnl)
+        .bind(config.fill_count)
+        .bind(fill.price)
+        .bind(Utc::now().to_rfc3339())
+        .bind(&config.id)
+        .execute(&mut *tx)
+        .await?;
+
+        for level in levels {
+            sqlx::query(
+                "UPDATE grid_levels SET side = ?1, base_amount = ?2 WHERE grid_id = ?3 AND level_index = ?4",
+            )
+            .bind(level.side.map(|s| s.as_str()))
+            .bind(level.base_amount)
+            .bind(&config.id)
+            .bind(level.level_index)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+}
+
+pub type SharedGridDatabase = Arc<RwLock<GridDatabase>>;
+
+pub struct GridManager {
+    db: SharedGridDatabase,
+    app_handle: AppHandle,
+}
+
+impl GridManager {
+    pub fn new(db: SharedGridDatabase, app_handle: AppHandle) -> Self {
+        Self { db, app_handle }
+    }
+
+    /// Lays out the ladder around the current price and books a paper
+    /// purchase of the base the sell levels need up front.
+    pub async fn create_grid(&self, request: CreateGridRequest) -> Result<GridConfig, String> {
+        if request.order_size_quote <= 0.0 {
+            return Err("Order size must be greater than zero".into());
+        }
+        let count = usize::try_from(request.grid_count)
+            .map_err(|_| "Grid count must be positive".to_string())?;
+        let prices = grid_prices(
+            request.lower_price,
+            request.upper_price,
+            count,
+            request.spacing,
+        )?;
+
+        let now = Utc::now();
+        let mut config = GridConfig {
+            id: Uuid::new_v4().to_string(),
+            name: request.name,
+            wallet_address: request.wallet_address,
+            base_mint: request.base_mint,
+            quote_mint: request.quote_mint,
+            base_symbol: request.base_symbol,
+            quote_symbol: request.quote_symbol,
+            base_decimals: request.base_decimals,
+            quote_decimals: request.quote_decimals,
+            lower_price: request.lower_price,
+            upper_price: request.upper_price,
+            grid_count: request.grid_count,
+            order_size_quote: request.order_size_quote,
+            spacing: request.spacing,
+            base_held: 0.0,
+            cost_basis: 0.0,
+            realized_pnl: 0.0,
+            total_invested: 0.0,
+            fill_count: 0,
+            is_active: true,
+            last_price: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let current_price = self.current_price(&config).await?;
+        let mut levels = initial_levels(&prices, current_price);
+        let sell_weights: Vec<f64> = levels
+            .iter()
+            .map(|l| {
+                if l.side == Some(GridSide::Sell) {
+                    config.order_size_quote / l.price
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let sell_levels = sell_weights.iter().filter(|w| **w > 0.0).count();
+        let buy_levels = levels
+            .iter()
+            .filter(|l| l.side == Some(GridSide::Buy))
+            .count();
+
+        let mut initial_fill = None;
+        if sell_levels > 0 {
+            let quote_spent = config.order_size_quote * sell_levels as f64;
+            let base_received = self.swap(&config, GridSide::Buy, quote_spent).await?;
+            let total_weight: f64 = sell_weights.iter().sum();
+            for (level, weight) in levels.iter_mut().zip(&sell_weights) {
+                level.base_amount = base_received * weight / total_weight;
+            }
+            config.record_buy(base_received, quote_spent);
+            config.fill_count = 1;
+            initial_fill = Some(GridFill {
+                id: Uuid::new_v4().to_string(),
+                grid_id: config.id.clone(),
+                level_index: None,
+                side: GridSide::Buy,
+                price: quote_spent / base_received,
+                base_amount: base_received,
+                quote_amount: quote_spent,
+                realized_pnl: 0.0,
+                executed_at: now,
+                tx_signature: None,
+            });
+        }
+        config.total_invested = config.order_size_quote * (sell_levels + buy_levels) as f64;
+        config.last_price = Some(current_price);
+
+        let db = self.db.write().await;
+        db.create_grid(&config, &levels)
+            .await
+            .map_err(|e| format!("Failed to persist grid: {e}"))?;
+        if let Some(fill) = initial_fill {
+            db.record_fill(&config, &levels, &fill)
+                .await
+                .map_err(|e| format!("Failed to record initial grid purchase: {e}"))?;
+        }
+
+        Ok(config)
+    }
+
+    pub async fn get_grid(&self, id: &str) -> Result<GridConfig, String> {
+        self.db
+            .read()
+            .await
+            .get_grid(id)
+            .await
+            .map_err(|e| format!("Failed to load grid: {e}"))?
+            .ok_or_else(|| "Grid not found".to_string())
+    }
+
+    pub async fn list_grids(&self, wallet_address: &str) -> Result<Vec<GridConfig>, String> {
+        self.db
+            .read()
+            .await
+            .list_grids(wallet_address)
+            .await
+            .map_err(|e| format!("Failed to list grids: {e}"))
+    }
+
+    pub async fn set_active(&self, id: &str, is_active: bool) -> Result<GridConfig, String> {
+        let mut config = self.get_grid(id).await?;
+        self.db
+            .write()
+            .await
+            .update_status(id, is_active)
+            .await
+            .map_err(|e| format!("Failed to update grid status: {e}"))?;
+        config.is_active = is_active;
+        Ok(config)
+    }
+
+    pub async fn performance(&self, id: &str) -> Result<GridPerformance, String> {
+        let config = self.get_grid(id).await?;
+        let levels = self
+            .db
+            .read()
+            .await
+            .get_levels(id)
+            .await
+            .map_err(|e| format!("Failed to load grid levels: {e}"))?;
+
+        let current_price = match self.current_price(&config).await {
+            Ok(price) => Some(price),
+            Err(_) => config.last_price,
+        };
+        let unrealized_pnl = current_price
+            .map(|price| config.base_held * price - config.cost_basis)
+            .unwrap_or_default();
+        let total_pnl = config.realized_pnl + unrealized_pnl;
+        let roi_pct = if config.total_invested > 0.0 {
+            total_pnl / config.total_invested * 100.0
+        } else {
+            0.0
+        };
+
+        Ok(GridPerformance {
+            grid_id: config.id,
+            current_price,
+            in_range: current_price
+                .is_some_and(|p| p >= config.lower_price && p <= config.upper_price),
+            realized_pnl: config.realized_pnl,
+            unrealized_pnl,
+            total_pnl,
+            total_invested: config.total_invested,
+            roi_pct,
+            base_held: config.base_held,
+            average_cost: if config.base_held > 0.0 {
+                config.cost_basis / config.base_held
+            } else {
+                0.0
+            },
+            fill_count: config.fill_count,
+            levels,
+        })
+    }
+
+    pub async fn check_grids(&self) -> Result<(), String> {
+        let grids = self
+            .db
+            .read()
+            .await
+            .get_active_grids()
+            .await
+            .map_err(|e| format!("Failed to load active grids: {e}"))?;
+
+        for grid in grids {
+            if let Err(err) = self.run_grid(grid.clone()).await {
+                eprintln!("Failed to run grid {}: {}", grid.id, err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Fills every level the price has crossed since the last check,
+    /// rebalancing after each one.
+    async fn run_grid(&self, mut config: GridConfig) -> Result<(), String> {
+        let price = self.current_price(&config).await?;
+        let mut levels = self
+            .db
+            .read()
+            .await
+            .get_levels(&config.id)
+            .await
+            .map_err(|e| format!("Failed to load grid levels: {e}"))?;
+
+        let mut filled = false;
+        // Each fill moves the idle level one step toward the price, so this
+        // is bounded by the number of levels.
+        for _ in 0..levels.len() {
+            let Some(index) = next_triggered(&levels, price) else {
+                break;
+            };
+            let level = levels[index].clone();
+            let side = level.side.unwrap_or(GridSide::Buy);
+            let (base_amount, quote_amount, realized_pnl) = match side {
+                GridSide::Buy => {
+                    let base = self.swap(&config, side, config.order_size_quote).await?;
+                    config.record_buy(base, config.order_size_quote);
+                    (base, config.order_size_quote, 0.0)
+                }
+                GridSide::Sell => {
+                    let base = level.base_amount.min(config.base_held);
+                    let quote = self.swap(&config, side, base).await?;
+                    let realized = config.record_sell(base, quote);
+                    (base, quote, realized)
+                }
+            };
+            config.fill_count += 1;
+            rebalance(&mut levels, index, base_amount);
+
+            let fill = GridFill {
+                id: Uuid::new_v4().to_string(),
+                grid_id: config.id.clone(),
+                level_index: Some(level.level_index),
+                side,
+                price: if base_amount > 0.0 {
+                    quote_amount / base_amount
+                } else {
+                    level.price
+                },
+                base_amount,
+                quote_amount,
+                realized_pnl,
+                executed_at: Utc::now(),
+                tx_signature: None,
+            };
+            self.db
+                .write()
+                .await
+                .record_fill(&config, &levels, &fill)
+                .await
+                .map_err(|e| format!("Failed to record grid fill: {e}"))?;
+            self.emit_fill_event(&config, &fill);
+            filled = true;
+        }
+
+        if !filled {
+            self.db
+                .write()
+                .await
+                .update_last_price(&config.id, price)
+                .await
+                .map_err(|e| format!("Failed to update grid price: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// Price of one base token in quote, from a Jupiter quote sized like a
+    /// single level so it reflects the depth the grid actually trades.
+    async fn current_price(&self, config: &GridConfig) -> Result<f64, String> {
+        let reference = config
+            .last_price
+            .unwrap_or((config.lower_price + config.upper_price) / 2.0);
+        let base_amount = config.order_size_quote / reference;
+        let quote_amount = self.swap(config, GridSide::Sell, base_amount).await?;
+        if base_amount <= 0.0 || quote_amount <= 0.0 {
+            return Err("Quote returned no output".into());
+        }
+        Ok(quote_amount / base_amount)
+    }
+
+    /// Quotes the swap through Jupiter and returns the output amount the
+    /// paper fill is booked at; nothing is sent on-chain. Buys spend quote
+    /// for base; sells spend base for quote.
+    async fn swap(&self, config: &GridConfig, side: GridSide, amount: f64) -> Result<f64, String> {
+        let (input_mint, output_mint, input_decimals, output_decimals) = match side {
+            GridSide::Buy => (
+                &config.quote_mint,
+                &config.base_mint,
+                config.quote_decimals,
+                config.base_decimals,
+            ),
+            GridSide::Sell => (
+                &config.base_mint,
+                &config.quote_mint,
+                config.base_decimals,
+                config.quote_decimals,
+            ),
+        };
+        check_token_policy(ExecutionPath::Bot, output_mint).await?;
+        let quote_input = QuoteCommandInput {
+            input_mint: input_mint.clone(),
+            output_mint: output_mint.clone(),
+            amount: to_base_units(amount, input_decimals)?,
+            slippage_bps: None,
+            swap_mode: Some(SwapMode::ExactIn),
+            platform_fee_bps: None,
+            only_direct_routes: None,
+            referral_account: None,
+            as_legacy_transaction: None,
+            priority_fee_config: None,
+        };
+        let quote = fetch_quote(&quote_input)
+            .await
+            .map_err(|e| format!("Failed to fetch quote: {e}"))?;
+        Ok(parse_amount(&quote.quote.output_amount, output_decimals))
+    }
+
+    fn emit_fill_event(&self, config: &GridConfig, fill: &GridFill) {
+        let event = GridFillEvent {
+            grid_id: config.id.clone(),
+            name: config.name.clone(),
+            side: fill.side,
+            level_index: fill.level_index,
+            price: fill.price,
+            base_amount: fill.base_amount,
+            quote_amount: fill.quote_amount,
+            realized_pnl: fill.realized_pnl,
+        };
+        let _ = self.app_handle.emit("grid_fill", event);
+    }
+
+    pub async fn start_monitoring(manager: Arc<Self>) {
+        let mut ticker = interval(Duration::from_secs(30));
+        loop {
+            ticker.tick().await;
+            if let Err(err) = manager.check_grids().await {
+                eprintln!("Error running grid bots: {err}");
+            }
+        }
+    }
+}
+
+pub type SharedGridManager = Arc<GridManager>;
+
+pub struct GridState {
+    pub db: SharedGridDatabase,
+    pub manager: SharedGridManager,
+}
+
+static GRID_STATE: OnceCell<GridState> = OnceCell::const_new();
+
+pub async fn init_grid(app_handle: &AppHandle) -> Result<(), String> {
+    if GRID_STATE.get().is_some() {
+        return Ok(());
+    }
+
+    let app_dir = app_handle
+        .path()
+        .profile_data_dir()
+        .map_err(|e| format!("Unable to resolve app data directory: {}", e))?;
+
+    std::fs::create_dir_all(&app_dir)
+        .map_err(|e| format!("Failed to create app data directory: {e}"))?;
+
+    let db = GridDatabase::new(app_dir.join("automation.db"))
+        .await
+        .map_err(|e| format!("Failed to initialize grid database: {e}"))?;
+
+    let shared_db = Arc::new(RwLock::new(db));
+    let manager = Arc::new(GridManager::new(shared_db.clone(), app_handle.clone()));
+
+    let manager_for_task = manager.clone();
+    tauri::async_runtime::spawn(async move {
+        GridManager::start_monitoring(manager_for_task).await;
+    });
+
+    GRID_STATE
+        .set(GridState {
+            db: shared_db,
+            manager,
+        })
+        .map_err(|_| "Grid state already initialized".to_string())?;
+
+    Ok(())
+}
+
+fn require_state<'a>() -> Result<&'a GridState, String> {
+    GRID_STATE
+        .get()
+        .ok_or_else(|| "Grid module not initialized".to_string())
+}
+
+#[tauri::command]
+pub async fn grid_init(handle: AppHandle) -> Result<(), String> {
+    init_grid(&handle).await
+}
+
+#[tauri::command]
+pub async fn grid_create(request: CreateGridRequest) -> Result<GridConfig, String> {
+    let state = require_state()?;
+    state.manager.create_grid(request).await
+}
+
+#[tauri::command]
+pub async fn grid_list(wallet_address: String) -> Result<Vec<GridConfig>, String> {
+    let state = require_state()?;
+    state.manager.list_grids(&wallet_address).await
+}
+
+#[tauri::command]
+pub async fn grid_pause(id: String) -> Result<GridConfig, String> {
+    let state = require_state()?;
+    state.manager.set_active(&id, false).await
+}
+
+#[tauri::command]
+pub async fn grid_resume(id: String) -> Result<GridConfig, String> {
+    let state = require_state()?;
+    state.manager.set_active(&id, true).await
+}
+
+#[tauri::command]
+pub async fn grid_performance(id: String) -> Result<GridPerformance, String> {
+    let state = require_state()?;
+    state.manager.performance(&id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geometric_levels_share_a_ratio() {
+        let prices = grid_prices(100.0, 400.0, 3, GridSpacing::Geometric).unwrap();
+        assert!((prices[1] - 200.0).abs() < 1e-9);
+        assert!((prices[2] - 400.0).abs() < 1e-9);
+        assert!(grid_prices(100.0, 100.0, 3, GridSpacing::Arithmetic).is_err());
+        assert!(grid_prices(100.0, 200.0, 1, GridSpacing::Arithmetic).is_err());
+    }
+
+    #[test]
+    fn buy_fill_moves_sell_up_one_level() {
+        let prices = grid_prices(90.0, 110.0, 5, GridSpacing::Arithmetic).unwrap();
+        let mut levels = initial_levels(&prices, 101.0);
+        let sides: Vec<_> = levels.iter().map(|l| l.side).collect();
+        assert_eq!(
+            sides,
+            vec![
+                Some(GridSide::Buy),
+                Some(GridSide::Buy),
+                None,
+                Some(GridSide::Sell),
+                Some(GridSide::Sell),
+            ]
+        );
+
+        // 94 crosses the 95 buy but not the 90 one.
+        let index = next_triggered(&levels, 94.0).unwrap();
+        assert_eq!(levels[index].price, 95.0);
+        rebalance(&mut levels, index, 0.5);
+        assert_eq!(levels[1].side, None);
+        assert_eq!(levels[2].side, Some(GridSide::Sell));
+        assert_eq!(levels[2].base_amount, 0.5);
+        assert!(next_triggered(&levels, 94.0).is_none());
+    }
+
+    #[test]
+    fn sells_realize_against_average_cost() {
+        let mut grid = GridConfig {
+            id: "g".into(),
+            name: "g".into(),
+            wallet_address: "w".into(),
+            base_mint: "SOL".into(),
+            quote_mint: "USDC".into(),
+            base_symbol: "SOL".into(),
+            quote_symbol: "USDC".into(),
+            base_decimals: 9,
+            quote_decimals: 6,
+            lower_price: 90.0,
+            upper_price: 110.0,
+            grid_count: 5,
+            order_size_quote: 100.0,
+            spacing: GridSpacing::Arithmetic,
+            base_held: 0.0,
+            cost_basis: 0.0,
+            realized_pnl: 0.0,
+            total_invested: 0.0,
+            fill_count: 0,
+            is_active: true,
+            last_price: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        grid.record_buy(1.0, 95.0);
+        grid.record_buy(1.0, 105.0);
+        let realized = grid.record_sell(1.0, 110.0);
+        assert!((realized - 10.0).abs() < 1e-9);
+        assert!((grid.cost_basis - 100.0).abs() < 1e-9);
+        assert!((grid.base_held - 1.0).abs() < 1e-9);
+    }
+}
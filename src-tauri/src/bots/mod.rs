@@ -1,3 +1,5 @@
 pub mod dca_bot;
+pub mod grid_bot;
 
 pub use dca_bot::*;
+pub use grid_bot::*;
@@ -2,23 +2,36 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tauri::State;
 
+use super::registry::{
+    rank_quotes, BridgeIncident, BridgeReliability, RankedBridgeQuote, SharedBridgeRegistry,
+};
 use super::types::*;
 use super::{AllBridgeAdapter, SynapseAdapter, WormholeAdapter};
+use crate::chains::validate_address;
+use crate::environment::active_environment;
+
 use super::{
-    BridgeProvider, BridgeQuote, BridgeQuoteRequest, BridgeTransaction, BridgeTransactionRequest,
+    BridgeProvider, BridgeQuoteRequest, BridgeTransaction, BridgeTransactionRequest,
     BridgeTransactionStatus, SharedBridgeManager,
 };
 
 #[tauri::command]
 pub async fn bridge_get_quote(
-    request: BridgeQuoteRequest,
+    mut request: BridgeQuoteRequest,
     provider: Option<String>,
-) -> Result<Vec<BridgeQuote>, String> {
+    registry: State<'_, SharedBridgeRegistry>,
+) -> Result<Vec<RankedBridgeQuote>, String> {
+    // Routes can be browsed before a recipient is entered.
+    if !request.recipient_address.trim().is_empty() {
+        request.recipient_address = validate_address(&request.to_chain, &request.recipient_address)
+            .map_err(|e| format!("Invalid recipient: {}", e))?;
+    }
     let mut quotes = Vec::new();
 
     if let Some(prov_str) = provider {
         let prov = BridgeProvider::from_str(&prov_str)
             .ok_or_else(|| format!("Invalid bridge provider: {}", prov_str))?;
+        ensure_provider_available(&prov)?;
         let adapter = get_bridge_adapter(&prov);
         let quote = adapter.quote(&request).await?;
         quotes.push(quote);
@@ -38,20 +51,66 @@ pub async fn bridge_get_quote(
         }
     }
 
-    quotes.sort_by(|a, b| {
-        b.amount_out
-            .partial_cmp(&a.amount_out)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
+    let environment = active_environment().environment;
+    quotes.retain(|quote| quote.provider.supports_environment(environment));
+
+    let registry = registry.read().await;
+    Ok(rank_quotes(quotes, &registry, chrono::Utc::now()))
+}
+
+/// Feeds a transfer's outcome into the reliability registry the first time
+/// it reaches a terminal status.
+async fn record_outcome(
+    transaction: &BridgeTransaction,
+    status: &BridgeTransactionStatus,
+    registry: &SharedBridgeRegistry,
+) -> Result<(), String> {
+    let already_final = matches!(
+        transaction.status,
+        BridgeTransactionStatus::Completed | BridgeTransactionStatus::Failed
+    );
+    let succeeded = match status {
+        BridgeTransactionStatus::Completed => true,
+        BridgeTransactionStatus::Failed => false,
+        _ => return Ok(()),
+    };
+    if already_final {
+        return Ok(());
+    }
+    let finality_seconds = chrono::DateTime::parse_from_rfc3339(&transaction.created_at)
+        .ok()
+        .map(|created| (chrono::Utc::now() - created.with_timezone(&chrono::Utc)).num_seconds())
+        .filter(|seconds| *seconds > 0)
+        .map(|seconds| seconds as f64);
+    registry
+        .write()
+        .await
+        .record_outcome(&transaction.provider, succeeded, finality_seconds)
+}
 
-    Ok(quotes)
+fn ensure_provider_available(provider: &BridgeProvider) -> Result<(), String> {
+    let environment = active_environment().environment;
+    if provider.supports_environment(environment) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} is not available on {}",
+            provider.as_str(),
+            environment.as_str()
+        ))
+    }
 }
 
 #[tauri::command]
 pub async fn bridge_create_transaction(
-    request: BridgeTransactionRequest,
+    mut request: BridgeTransactionRequest,
     bridge_manager: State<'_, SharedBridgeManager>,
 ) -> Result<BridgeTransaction, String> {
+    request.sender_address = validate_address(&request.from_chain, &request.sender_address)
+        .map_err(|e| format!("Invalid sender: {}", e))?;
+    request.recipient_address = validate_address(&request.to_chain, &request.recipient_address)
+        .map_err(|e| format!("Invalid recipient: {}", e))?;
+    ensure_provider_available(&request.provider)?;
     let adapter = get_bridge_adapter(&request.provider);
     let mut transaction = adapter.prepare_transaction(&request).await?;
 
@@ -105,6 +164,7 @@ pub async fn bridge_update_transaction_status(
     transaction_id: String,
     status: String,
     bridge_manager: State<'_, SharedBridgeManager>,
+    registry: State<'_, SharedBridgeRegistry>,
 ) -> Result<(), String> {
     let status_enum = match status.to_lowercase().as_str() {
         "pending" => BridgeTransactionStatus::Pending,
@@ -117,6 +177,9 @@ pub async fn bridge_update_transaction_status(
     };
 
     let mut manager = bridge_manager.write().await;
+    if let Some(transaction) = manager.get_transaction(&transaction_id) {
+        record_outcome(transaction, &status_enum, &registry).await?;
+    }
     manager.update_transaction_status(&transaction_id, status_enum)
 }
 
@@ -136,6 +199,7 @@ pub async fn bridge_poll_status(
     transaction_id: String,
     provider: String,
     bridge_manager: State<'_, SharedBridgeManager>,
+    registry: State<'_, SharedBridgeRegistry>,
 ) -> Result<BridgeTransactionStatus, String> {
     let prov = BridgeProvider::from_str(&provider)
         .ok_or_else(|| format!("Invalid bridge provider: {}", provider))?;
@@ -144,11 +208,32 @@ pub async fn bridge_poll_status(
     let status = adapter.poll_status(&transaction_id).await?;
 
     let mut manager = bridge_manager.write().await;
+    if let Some(transaction) = manager.get_transaction(&transaction_id) {
+        record_outcome(transaction, &status, &registry).await?;
+    }
     manager.update_transaction_status(&transaction_id, status.clone())?;
 
     Ok(status)
 }
 
+#[tauri::command]
+pub async fn bridge_get_registry(
+    registry: State<'_, SharedBridgeRegistry>,
+) -> Result<Vec<BridgeReliability>, String> {
+    Ok(registry.read().await.list())
+}
+
+#[tauri::command]
+pub async fn bridge_record_incident(
+    provider: String,
+    incident: BridgeIncident,
+    registry: State<'_, SharedBridgeRegistry>,
+) -> Result<(), String> {
+    let prov = BridgeProvider::from_str(&provider)
+        .ok_or_else(|| format!("Invalid bridge provider: {}", provider))?;
+    registry.write().await.record_incident(&prov, incident)
+}
+
 fn get_bridge_adapter(provider: &BridgeProvider) -> SharedBridgeAdapter {
     match provider {
         BridgeProvider::Wormhole => Arc::new(WormholeAdapter::new()),
@@ -1,11 +1,13 @@
 pub mod allbridge;
 pub mod commands;
+pub mod registry;
 pub mod synapse;
 pub mod types;
 pub mod wormhole;
 
 pub use allbridge::*;
 pub use commands::*;
+pub use registry::*;
 pub use synapse::*;
 pub use types::*;
 pub use wormhole::*;
@@ -16,6 +18,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::chains::ChainId;
+use crate::environment::NetworkEnvironment;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -42,6 +45,18 @@ impl BridgeProvider {
             _ => None,
         }
     }
+
+    /// Only Wormhole runs a public testnet deployment; the others bridge
+    /// mainnet liquidity exclusively.
+    pub fn supports_environment(&self, environment: NetworkEnvironment) -> bool {
+        match environment {
+            NetworkEnvironment::Mainnet => true,
+            NetworkEnvironment::Devnet | NetworkEnvironment::Testnet => {
+                matches!(self, BridgeProvider::Wormhole)
+            }
+            NetworkEnvironment::Custom => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,351 @@
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::RwLock;
+
+use super::{BridgeProvider, BridgeQuote};
+use crate::profiles::ProfilePaths;
+
+const REGISTRY_FILE: &str = "bridge_registry.json";
+
+const OUTPUT_WEIGHT: f64 = 0.40;
+const RELIABILITY_WEIGHT: f64 = 0.25;
+const SPEED_WEIGHT: f64 = 0.15;
+const SECURITY_WEIGHT: f64 = 0.20;
+
+/// Pseudo-observations blended into the success rate so a bridge with a
+/// handful of transfers is not ranked on luck.
+const PRIOR_TRANSFERS: f64 = 20.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeIncident {
+    pub date: DateTime<Utc>,
+    pub description: String,
+    pub loss_usd: f64,
+    /// Users were made whole.
+    pub reimbursed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeReliability {
+    pub provider: BridgeProvider,
+    /// Long-run success rate used before there is local history.
+    pub baseline_success_rate: f64,
+    pub completed_transfers: u64,
+    pub failed_transfers: u64,
+    /// Rolling average of time from submission to completion on this
+    /// machine, when known.
+    pub observed_finality_seconds: Option<f64>,
+    pub incidents: Vec<BridgeIncident>,
+}
+
+impl BridgeReliability {
+    pub fn success_rate(&self) -> f64 {
+        let completed = self.completed_transfers as f64;
+        let total = completed + self.failed_transfers as f64;
+        (completed + self.baseline_success_rate * PRIOR_TRANSFERS) / (total + PRIOR_TRANSFERS)
+    }
+
+    /// 100 for a clean record. Each incident costs less as it ages, halving
+    /// every year; unreimbursed losses count double.
+    pub fn security_score(&self, now: DateTime<Utc>) -> f64 {
+        let penalty: f64 = self
+            .incidents
+            .iter()
+            .map(|incident| {
+                let base = if incident.reimbursed { 30.0 } else { 60.0 };
+                let years = (now - incident.date).num_days().max(0) as f64 / 365.0;
+                base * 0.5_f64.powf(years)
+            })
+            .sum();
+        (100.0 - penalty).max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteScore {
+    pub total: f64,
+    pub output_score: f64,
+    pub reliability_score: f64,
+    pub speed_score: f64,
+    pub security_score: f64,
+    pub success_rate: f64,
+    pub finality_seconds: f64,
+    pub recent_incidents: Vec<BridgeIncident>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedBridgeQuote {
+    #[serde(flatten)]
+    pub quote: BridgeQuote,
+    pub rank: usize,
+    pub score: RouteScore,
+}
+
+fn date(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
+}
+
+fn default_entries() -> Vec<BridgeReliability> {
+    vec![
+        BridgeReliability {
+            provider: BridgeProvider::Wormhole,
+            baseline_success_rate: 0.995,
+            completed_transfers: 0,
+            failed_transfers: 0,
+            observed_finality_seconds: None,
+            incidents: vec![BridgeIncident {
+                date: date(2022, 2, 2),
+                description: "Signature verification bypass minted 120k wETH on Solana".to_string(),
+                loss_usd: 325_000_000.0,
+                reimbursed: true,
+            }],
+        },
+        BridgeReliability {
+            provider: BridgeProvider::AllBridge,
+            baseline_success_rate: 0.985,
+            completed_transfers: 0,
+            failed_transfers: 0,
+            observed_finality_seconds: None,
+            incidents: vec![BridgeIncident {
+                date: date(2023, 4, 2),
+                description: "Flash-loan price manipulation of BNB Chain pools".to_string(),
+                loss_usd: 570_000.0,
+                reimbursed: true,
+            }],
+        },
+        BridgeReliability {
+            provider: BridgeProvider::Synapse,
+            baseline_success_rate: 0.99,
+            completed_transfers: 0,
+            failed_transfers: 0,
+            observed_finality_seconds: None,
+            incidents: vec![BridgeIncident {
+                date: date(2021, 11, 6),
+                description: "nUSD metapool exploited through an imbalanced pool".to_string(),
+                loss_usd: 8_000_000.0,
+                reimbursed: false,
+            }],
+        },
+    ]
+}
+
+/// Scores `quotes` and returns them best first. Output is measured against
+/// the best quote, speed against the fastest.
+pub fn rank_quotes(
+    quotes: Vec<BridgeQuote>,
+    registry: &BridgeRegistry,
+    now: DateTime<Utc>,
+) -> Vec<RankedBridgeQuote> {
+    let best_output = quotes.iter().map(|q| q.amount_out).fold(0.0_f64, f64::max);
+    let finality = |quote: &BridgeQuote| {
+        registry
+            .get(&quote.provider)
+            .and_then(|entry| entry.observed_finality_seconds)
+            .unwrap_or(quote.estimated_time_seconds as f64)
+            .max(1.0)
+    };
+    let fastest = quotes.iter().map(finality).fold(f64::INFINITY, f64::min);
+
+    let mut ranked: Vec<RankedBridgeQuote> = quotes
+        .into_iter()
+        .map(|quote| {
+            let entry = registry.get(&quote.provider);
+            let success_rate = entry.map_or(0.9, BridgeReliability::success_rate);
+            let security_score = entry.map_or(50.0, |e| e.security_score(now));
+            let finality_seconds = finality(&quote);
+            let output_score = if best_output > 0.0 {
+                quote.amount_out / best_output * 100.0
+            } else {
+                0.0
+            };
+            let speed_score = fastest / finality_seconds * 100.0;
+            // Most well-run bridges sit above 95%, so stretch that band.
+            let reliability_score = ((success_rate - 0.9) / 0.1 * 100.0).clamp(0.0, 100.0);
+            let total = output_score * OUTPUT_WEIGHT
+                + reliability_score * RELIABILITY_WEIGHT
+                + speed_score * SPEED_WEIGHT
+                + security_score * SECURITY_WEIGHT;
+            RankedBridgeQuote {
+                score: RouteScore {
+                    total,
+                    output_score,
+                    reliability_score,
+                    speed_score,
+                    security_score,
+                    success_rate,
+                    finality_seconds,
+                    recent_incidents: entry
+                        .map(|e| {
+                            e.incidents
+                                .iter()
+                                .filter(|i| (now - i.date).num_days() <= 3 * 365)
+                                .cloned()
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                },
+                rank: 0,
+                quote,
+            }
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.score.total.total_cmp(&a.score.total));
+    for (index, quote) in ranked.iter_mut().enumerate() {
+        quote.rank = index + 1;
+    }
+    ranked
+}
+
+/// Local reliability record per bridge: seeded with known incidents and
+/// updated as this app's own transfers complete or fail.
+pub struct BridgeRegistry {
+    entries: HashMap<String, BridgeReliability>,
+    path: Option<PathBuf>,
+}
+
+impl BridgeRegistry {
+    pub fn load(app_handle: &AppHandle) -> Self {
+        let path = app_handle
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(REGISTRY_FILE));
+        let mut entries: HashMap<String, BridgeReliability> = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        for entry in default_entries() {
+            entries
+                .entry(entry.provider.as_str().to_string())
+                .or_insert(entry);
+        }
+        Self { entries, path }
+    }
+
+    pub fn get(&self, provider: &BridgeProvider) -> Option<&BridgeReliability> {
+        self.entries.get(provider.as_str())
+    }
+
+    pub fn list(&self) -> Vec<BridgeReliability> {
+        let mut entries: Vec<_> = self.entries.values().cloned().collect();
+        entries.sort_by(|a, b| a.provider.as_str().cmp(b.provider.as_str()));
+        entries
+    }
+
+    pub fn record_outcome(
+        &mut self,
+        provider: &BridgeProvider,
+        succeeded: bool,
+        finality_seconds: Option<f64>,
+    ) -> Result<(), String> {
+        let entry = self
+            .entries
+            .get_mut(provider.as_str())
+            .ok_or_else(|| format!("{} is not in the bridge registry", provider.as_str()))?;
+        if succeeded {
+            entry.completed_transfers += 1;
+            if let Some(seconds) = finality_seconds {
+                // Exponential moving average so recent transfers dominate.
+                entry.observed_finality_seconds = Some(match entry.observed_finality_seconds {
+                    Some(previous) => previous * 0.8 + seconds * 0.2,
+                    None => seconds,
+                });
+            }
+        } else {
+            entry.failed_transfers += 1;
+        }
+        self.save()
+    }
+
+    pub fn record_incident(
+        &mut self,
+        provider: &BridgeProvider,
+        incident: BridgeIncident,
+    ) -> Result<(), String> {
+        self.entries
+            .get_mut(provider.as_str())
+            .ok_or_else(|| format!("{} is not in the bridge registry", provider.as_str()))?
+            .incidents
+            .push(incident);
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let contents = serde_json::to_string(&self.entries).map_err(|e| e.to_string())?;
+        fs::write(path, contents).map_err(|e| e.to_string())
+    }
+}
+
+pub type SharedBridgeRegistry = Arc<RwLock<BridgeRegistry>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chains::ChainId;
+
+    fn registry() -> BridgeRegistry {
+        BridgeRegistry {
+            entries: default_entries()
+                .into_iter()
+                .map(|e| (e.provider.as_str().to_string(), e))
+                .collect(),
+            path: None,
+        }
+    }
+
+    fn quote(provider: BridgeProvider, amount_out: f64, seconds: u64) -> BridgeQuote {
+        BridgeQuote {
+            provider,
+            from_chain: ChainId::Solana,
+            to_chain: ChainId::Ethereum,
+            amount_in: 100.0,
+            amount_out,
+            estimated_time_seconds: seconds,
+            fee_amount: 100.0 - amount_out,
+            fee_currency: "USD".to_string(),
+            route_info: String::new(),
+        }
+    }
+
+    #[test]
+    fn failures_can_outweigh_a_slightly_better_price() {
+        let mut registry = registry();
+        let now = date(2026, 6, 1);
+        let quotes = || {
+            vec![
+                quote(BridgeProvider::Wormhole, 99.5, 300),
+                quote(BridgeProvider::Synapse, 99.7, 300),
+            ]
+        };
+        let ranked = rank_quotes(quotes(), &registry, now);
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].rank, 1);
+
+        for _ in 0..40 {
+            registry
+                .record_outcome(&BridgeProvider::Synapse, false, None)
+                .unwrap();
+        }
+        let ranked = rank_quotes(quotes(), &registry, now);
+        assert_eq!(ranked[0].quote.provider, BridgeProvider::Wormhole);
+        assert!(ranked[1].score.success_rate < 0.5);
+    }
+
+    #[test]
+    fn incidents_fade_with_time() {
+        let wormhole = registry().get(&BridgeProvider::Wormhole).cloned().unwrap();
+        let soon_after = wormhole.security_score(date(2022, 3, 1));
+        let years_later = wormhole.security_score(date(2026, 3, 1));
+        assert!(soon_after < years_later);
+        assert!(years_later <= 100.0);
+    }
+}
@@ -1,10 +1,11 @@
 use crate::core::cache_manager::{
     CacheManager, CacheStatistics, CacheTtlConfig, CacheType, SharedCacheManager, WarmProgress,
 };
+use crate::core::cache_warmup::{CacheWarmupPlan, CacheWarmupSettings, SharedCacheWarmupService};
 use serde::Serialize;
 use serde_json::json;
 use std::time::Instant;
-use tauri::State;
+use tauri::{AppHandle, State};
 use tokio::time::{sleep, Duration};
 
 #[tauri::command]
@@ -125,6 +126,47 @@ pub async fn reset_ttl_config(
     manager.reset_ttl_config().await
 }
 
+#[tauri::command]
+pub async fn get_cache_warmup_settings(
+    warmup: State<'_, SharedCacheWarmupService>,
+) -> Result<CacheWarmupSettings, String> {
+    Ok(warmup.settings().await)
+}
+
+/// Takes effect on the next scheduled warmup; use `run_cache_warmup` to apply now.
+#[tauri::command]
+pub async fn update_cache_warmup_settings(
+    warmup: State<'_, SharedCacheWarmupService>,
+    settings: CacheWarmupSettings,
+) -> Result<(), String> {
+    warmup.update_settings(settings).await
+}
+
+#[tauri::command]
+pub async fn preview_cache_warmup(
+    app: AppHandle,
+    cache_manager: State<'_, SharedCacheManager>,
+    warmup: State<'_, SharedCacheWarmupService>,
+) -> Result<CacheWarmupPlan, String> {
+    Ok(warmup.preview(&app, cache_manager.inner()).await)
+}
+
+#[tauri::command]
+pub async fn run_cache_warmup(
+    app: AppHandle,
+    cache_manager: State<'_, SharedCacheManager>,
+    warmup: State<'_, SharedCacheWarmupService>,
+) -> Result<CacheWarmupPlan, String> {
+    warmup.run(&app, cache_manager.inner()).await
+}
+
+#[tauri::command]
+pub async fn get_last_cache_warmup(
+    warmup: State<'_, SharedCacheWarmupService>,
+) -> Result<Option<CacheWarmupPlan>, String> {
+    Ok(warmup.last_plan().await)
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CacheTestResult {
@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::keccak;
+
+use super::ChainId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressFormat {
+    Solana,
+    Evm,
+}
+
+impl AddressFormat {
+    pub fn for_chain(chain: &ChainId) -> Self {
+        match chain {
+            ChainId::Solana => AddressFormat::Solana,
+            ChainId::Ethereum | ChainId::Base | ChainId::Polygon | ChainId::Arbitrum => {
+                AddressFormat::Evm
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressValidation {
+    pub chain: ChainId,
+    pub valid: bool,
+    /// Canonical form: EIP-55 checksummed for EVM, unchanged for Solana.
+    pub normalized: Option<String>,
+    pub detected_format: Option<AddressFormat>,
+    pub error: Option<String>,
+}
+
+/// Which family an address looks like, without checking it in detail.
+pub fn detect_address_format(address: &str) -> Option<AddressFormat> {
+    let address = address.trim();
+    if address.starts_with("0x") || address.starts_with("0X") {
+        return Some(AddressFormat::Evm);
+    }
+    match bs58::decode(address).into_vec() {
+        Ok(bytes) if bytes.len() == 32 => Some(AddressFormat::Solana),
+        _ => None,
+    }
+}
+
+/// EIP-55 mixed-case checksum of a 40-character hex body.
+fn evm_checksum(hex_body: &str) -> String {
+    let lower = hex_body.to_ascii_lowercase();
+    let hash = keccak::hash(lower.as_bytes()).to_bytes();
+    lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+            if c.is_ascii_alphabetic() && nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+fn validate_evm(address: &str) -> Result<String, String> {
+    let body = address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+        .ok_or_else(|| "EVM addresses start with 0x".to_string())?;
+    if body.len() != 40 || !body.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("EVM addresses are 0x followed by 40 hex characters".to_string());
+    }
+    let checksummed = evm_checksum(body);
+    // All-lowercase or all-uppercase input carries no checksum to verify.
+    let mixed_case = body.chars().any(|c| c.is_ascii_lowercase())
+        && body.chars().any(|c| c.is_ascii_uppercase());
+    if mixed_case && body != checksummed {
+        return Err("EVM address checksum does not match; check for a typo".to_string());
+    }
+    Ok(format!("0x{checksummed}"))
+}
+
+fn validate_solana(address: &str) -> Result<String, String> {
+    if !(32..=44).contains(&address.len()) {
+        return Err("Solana addresses are 32 to 44 base58 characters".to_string());
+    }
+    let bytes = bs58::decode(address)
+        .into_vec()
+        .map_err(|_| "Solana address is not valid base58".to_string())?;
+    if bytes.len() != 32 {
+        return Err("Solana address does not decode to a 32-byte public key".to_string());
+    }
+    Ok(address.to_string())
+}
+
+/// Checks that `address` is well-formed for `chain` and returns it in
+/// canonical form. Addresses from the wrong chain family are rejected with
+/// a message saying so, since that is the mistake that loses funds.
+pub fn validate_address(chain: &ChainId, address: &str) -> Result<String, String> {
+    let address = address.trim();
+    let expected = AddressFormat::for_chain(chain);
+    let detected = detect_address_format(address);
+    if let Some(detected) = detected.filter(|d| *d != expected) {
+        return Err(format!(
+            "This looks like {} address, not a {} address",
+            match detected {
+                AddressFormat::Solana => "a Solana",
+                AddressFormat::Evm => "an EVM",
+            },
+            chain.as_str()
+        ));
+    }
+    match expected {
+        AddressFormat::Solana => validate_solana(address),
+        AddressFormat::Evm => validate_evm(address),
+    }
+}
+
+pub fn check_address(chain: &ChainId, address: &str) -> AddressValidation {
+    let result = validate_address(chain, address);
+    AddressValidation {
+        chain: chain.clone(),
+        valid: result.is_ok(),
+        detected_format: detect_address_format(address),
+        normalized: result.as_ref().ok().cloned(),
+        error: result.err(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evm_checksums_are_enforced() {
+        // Test vector from EIP-55.
+        let valid = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert_eq!(validate_address(&ChainId::Ethereum, valid).unwrap(), valid);
+        assert_eq!(
+            validate_address(&ChainId::Base, &valid.to_lowercase()).unwrap(),
+            valid
+        );
+        let typo = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD";
+        assert!(validate_address(&ChainId::Ethereum, typo).is_err());
+    }
+
+    #[test]
+    fn rejects_addresses_from_the_other_chain_family() {
+        let solana = "So11111111111111111111111111111111111111112";
+        let evm = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert!(validate_address(&ChainId::Solana, solana).is_ok());
+        let err = validate_address(&ChainId::Solana, evm).unwrap_err();
+        assert!(err.contains("EVM"));
+        assert!(validate_address(&ChainId::Polygon, solana).is_err());
+        assert!(validate_address(&ChainId::Solana, "not-an-address").is_err());
+    }
+}
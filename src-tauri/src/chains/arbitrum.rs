@@ -1,6 +1,6 @@
 use super::ethereum::EthereumAdapter;
 use super::types::*;
-use super::ChainId;
+use super::{ChainId, EvmClient};
 
 #[derive(Debug)]
 pub struct ArbitrumAdapter {
@@ -13,6 +13,12 @@ impl ArbitrumAdapter {
             inner: EthereumAdapter::new(rpc_url, "Arbitrum", "ETH"),
         }
     }
+
+    pub fn with_client(client: EvmClient) -> Self {
+        Self {
+            inner: EthereumAdapter::with_client(client, "Arbitrum", "ETH"),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -25,8 +31,6 @@ impl ChainAdapter for ArbitrumAdapter {
 
     async fn get_fee_estimate(&self, wallet: &WalletInfo) -> Result<ChainFeeEstimate, String> {
         let mut estimate = self.inner.get_fee_estimate(wallet).await?;
-        estimate.max_fee *= 0.2;
-        estimate.avg_fee *= 0.2;
         estimate.estimated_time_seconds = 5;
         Ok(estimate)
     }
@@ -1,6 +1,6 @@
 use super::ethereum::EthereumAdapter;
 use super::types::*;
-use super::ChainId;
+use super::{ChainId, EvmClient};
 
 #[derive(Debug)]
 pub struct BaseAdapter {
@@ -13,6 +13,12 @@ impl BaseAdapter {
             inner: EthereumAdapter::new(rpc_url, "Base", "ETH"),
         }
     }
+
+    pub fn with_client(client: EvmClient) -> Self {
+        Self {
+            inner: EthereumAdapter::with_client(client, "Base", "ETH"),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -24,10 +30,7 @@ impl ChainAdapter for BaseAdapter {
     }
 
     async fn get_fee_estimate(&self, wallet: &WalletInfo) -> Result<ChainFeeEstimate, String> {
-        let mut estimate = self.inner.get_fee_estimate(wallet).await?;
-        estimate.max_fee *= 0.5;
-        estimate.avg_fee *= 0.5;
-        Ok(estimate)
+        self.inner.get_fee_estimate(wallet).await
     }
 
     async fn build_transfer(
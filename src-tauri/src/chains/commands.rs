@@ -2,9 +2,15 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tauri::State;
 
+use crate::security::keystore::Keystore;
+
 use super::types::*;
+use super::{check_address, validate_address, AddressValidation};
+use super::{import_signing_key, remove_signing_key};
 use super::{ArbitrumAdapter, BaseAdapter, EthereumAdapter, PolygonAdapter, SolanaAdapter};
-use super::{ChainConfig, ChainId, ChainManager, SharedChainManager};
+use super::{ChainConfig, ChainId, ChainManager, EvmClient, SharedChainManager};
+use super::{RoutingHint, RpcEndpoint, RpcPool, RpcPoolSettings, RpcPoolSnapshot, SharedRpcPool};
+use super::SharedCrossChainReconciler;
 
 #[tauri::command]
 pub async fn chain_get_active(
@@ -58,6 +64,7 @@ pub async fn chain_get_balance(
     wallet_address: String,
     chain_id: String,
     chain_manager: State<'_, SharedChainManager>,
+    rpc_pool: State<'_, SharedRpcPool>,
 ) -> Result<ChainBalance, String> {
     let chain =
         ChainId::from_str(&chain_id).ok_or_else(|| format!("Invalid chain ID: {}", chain_id))?;
@@ -68,12 +75,12 @@ pub async fn chain_get_balance(
         .ok_or_else(|| format!("Chain config not found for {:?}", chain))?;
 
     let wallet_info = WalletInfo {
-        public_key: wallet_address,
+        public_key: validate_address(&chain, &wallet_address)?,
         label: None,
         chain_id: chain.clone(),
     };
 
-    let adapter = get_chain_adapter(&chain, &config.rpc_url);
+    let adapter = chain_adapter(&manager, &chain, &config.rpc_url, &rpc_pool).await;
     adapter.get_balance(&wallet_info).await
 }
 
@@ -82,6 +89,7 @@ pub async fn chain_get_fee_estimate(
     wallet_address: String,
     chain_id: String,
     chain_manager: State<'_, SharedChainManager>,
+    rpc_pool: State<'_, SharedRpcPool>,
 ) -> Result<ChainFeeEstimate, String> {
     let chain =
         ChainId::from_str(&chain_id).ok_or_else(|| format!("Invalid chain ID: {}", chain_id))?;
@@ -92,12 +100,12 @@ pub async fn chain_get_fee_estimate(
         .ok_or_else(|| format!("Chain config not found for {:?}", chain))?;
 
     let wallet_info = WalletInfo {
-        public_key: wallet_address,
+        public_key: validate_address(&chain, &wallet_address)?,
         label: None,
         chain_id: chain.clone(),
     };
 
-    let adapter = get_chain_adapter(&chain, &config.rpc_url);
+    let adapter = chain_adapter(&manager, &chain, &config.rpc_url, &rpc_pool).await;
     adapter.get_fee_estimate(&wallet_info).await
 }
 
@@ -105,6 +113,7 @@ pub async fn chain_get_fee_estimate(
 pub async fn chain_get_status(
     chain_id: String,
     chain_manager: State<'_, SharedChainManager>,
+    rpc_pool: State<'_, SharedRpcPool>,
 ) -> Result<ChainStatus, String> {
     let chain =
         ChainId::from_str(&chain_id).ok_or_else(|| format!("Invalid chain ID: {}", chain_id))?;
@@ -114,7 +123,7 @@ pub async fn chain_get_status(
         .get_chain_config(&chain)
         .ok_or_else(|| format!("Chain config not found for {:?}", chain))?;
 
-    let adapter = get_chain_adapter(&chain, &config.rpc_url);
+    let adapter = chain_adapter(&manager, &chain, &config.rpc_url, &rpc_pool).await;
     adapter.get_status().await
 }
 
@@ -122,6 +131,8 @@ pub async fn chain_get_status(
 pub async fn chain_get_cross_chain_portfolio(
     wallet_addresses: HashMap<String, String>,
     chain_manager: State<'_, SharedChainManager>,
+    rpc_pool: State<'_, SharedRpcPool>,
+    reconciler: State<'_, SharedCrossChainReconciler>,
 ) -> Result<CrossChainPortfolioSummary, String> {
     let manager = chain_manager.read().await;
     let mut summary = CrossChainPortfolioSummary::default();
@@ -143,9 +154,13 @@ pub async fn chain_get_cross_chain_portfolio(
             chain_id: chain.clone(),
         };
 
-        let adapter = get_chain_adapter(&chain, &config.rpc_url);
+        let adapter = chain_adapter(&manager, &chain, &config.rpc_url, &rpc_pool).await;
 
         if let Ok(balance) = adapter.get_balance(&wallet_info).await {
+            reconciler
+                .write()
+                .await
+                .record_pushed(&chain, address, &balance);
             summary.total_value_usd += balance.total_usd_value;
             summary.per_chain.push(ChainPortfolioSnapshot {
                 chain_id: chain.clone(),
@@ -162,14 +177,113 @@ pub async fn chain_get_cross_chain_portfolio(
     Ok(summary)
 }
 
-fn get_chain_adapter(chain: &ChainId, rpc_url: &str) -> SharedChainAdapter {
+/// Solana reads are routed through the Solana RPC pool and EVM calls
+/// through their chain's endpoint pool; `configured` is the fallback for
+/// either.
+async fn chain_adapter(
+    manager: &ChainManager,
+    chain: &ChainId,
+    configured: &str,
+    rpc_pool: &SharedRpcPool,
+) -> SharedChainAdapter {
+    let client = || match manager.rpc_pools() {
+        Some(pools) => EvmClient::pooled(pools, chain.clone(), configured.to_string()),
+        None => EvmClient::new(configured),
+    };
     match chain {
-        ChainId::Solana => std::sync::Arc::new(SolanaAdapter::new(rpc_url.to_string())),
+        ChainId::Solana => {
+            let rpc_url = rpc_pool
+                .read()
+                .await
+                .select(RoutingHint::Read)
+                .unwrap_or_else(|| configured.to_string());
+            std::sync::Arc::new(SolanaAdapter::new(rpc_url))
+        }
         ChainId::Ethereum => {
-            std::sync::Arc::new(EthereumAdapter::new(rpc_url.to_string(), "Ethereum", "ETH"))
+            std::sync::Arc::new(EthereumAdapter::with_client(client(), "Ethereum", "ETH"))
         }
-        ChainId::Base => std::sync::Arc::new(BaseAdapter::new(rpc_url.to_string())),
-        ChainId::Polygon => std::sync::Arc::new(PolygonAdapter::new(rpc_url.to_string())),
-        ChainId::Arbitrum => std::sync::Arc::new(ArbitrumAdapter::new(rpc_url.to_string())),
+        ChainId::Base => std::sync::Arc::new(BaseAdapter::with_client(client())),
+        ChainId::Polygon => std::sync::Arc::new(PolygonAdapter::with_client(client())),
+        ChainId::Arbitrum => std::sync::Arc::new(ArbitrumAdapter::with_client(client())),
     }
 }
+
+#[tauri::command]
+pub async fn chain_rpc_pool_get(
+    rpc_pool: State<'_, SharedRpcPool>,
+) -> Result<RpcPoolSnapshot, String> {
+    Ok(rpc_pool.read().await.snapshot())
+}
+
+#[tauri::command]
+pub async fn chain_rpc_pool_upsert_endpoint(
+    endpoint: RpcEndpoint,
+    rpc_pool: State<'_, SharedRpcPool>,
+) -> Result<RpcEndpoint, String> {
+    rpc_pool.write().await.upsert_endpoint(endpoint)
+}
+
+#[tauri::command]
+pub async fn chain_rpc_pool_remove_endpoint(
+    id: String,
+    rpc_pool: State<'_, SharedRpcPool>,
+) -> Result<(), String> {
+    rpc_pool.write().await.remove_endpoint(&id)
+}
+
+#[tauri::command]
+pub async fn chain_rpc_pool_update_settings(
+    settings: RpcPoolSettings,
+    rpc_pool: State<'_, SharedRpcPool>,
+) -> Result<(), String> {
+    rpc_pool.write().await.update_settings(settings)
+}
+
+#[tauri::command]
+pub async fn chain_rpc_pool_probe(
+    rpc_pool: State<'_, SharedRpcPool>,
+) -> Result<RpcPoolSnapshot, String> {
+    RpcPool::probe(&rpc_pool).await;
+    Ok(rpc_pool.read().await.snapshot())
+}
+
+#[tauri::command]
+pub async fn chain_rpc_pool_select(
+    hint: RoutingHint,
+    rpc_pool: State<'_, SharedRpcPool>,
+) -> Result<String, String> {
+    rpc_pool
+        .read()
+        .await
+        .select(hint)
+        .ok_or_else(|| "No RPC endpoints available".to_string())
+}
+
+#[tauri::command]
+pub async fn validate_chain_address(
+    chain_id: String,
+    address: String,
+) -> Result<AddressValidation, String> {
+    let chain =
+        ChainId::from_str(&chain_id).ok_or_else(|| format!("Invalid chain ID: {}", chain_id))?;
+    Ok(check_address(&chain, &address))
+}
+
+/// Stores an EVM private key so `wallet_send_transaction` can sign for its
+/// address; returns that address.
+#[tauri::command]
+pub async fn chain_evm_import_key(
+    private_key: String,
+    keystore: State<'_, Keystore>,
+) -> Result<String, String> {
+    import_signing_key(&keystore, &private_key)
+}
+
+#[tauri::command]
+pub async fn chain_evm_remove_key(
+    address: String,
+    keystore: State<'_, Keystore>,
+) -> Result<(), String> {
+    let address = validate_address(&ChainId::Ethereum, &address)?;
+    remove_signing_key(&keystore, &address)
+}
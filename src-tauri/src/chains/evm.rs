@@ -0,0 +1,650 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_sdk::keccak;
+use std::collections::HashMap;
+use std::time::Instant;
+
+use super::{validate_address, ChainId, SharedChainManager};
+use crate::api::SharedRpcPoolManager;
+use crate::security::keystore::Keystore;
+
+pub const NATIVE_TRANSFER_GAS: u64 = 21_000;
+pub const WEI_PER_ETH: f64 = 1e18;
+const EIP1559_TX_TYPE: u8 = 0x02;
+const FEE_HISTORY_BLOCKS: u64 = 20;
+const REWARD_PERCENTILES: [f64; 3] = [10.0, 50.0, 90.0];
+const SIGNING_KEY_PREFIX: &str = "evm.key.";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeTier {
+    pub max_priority_fee_per_gas: u128,
+    pub max_fee_per_gas: u128,
+}
+
+/// EIP-1559 fee suggestion, all values in wei per gas.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip1559Fees {
+    /// Projected base fee of the next block.
+    pub base_fee_per_gas: u128,
+    pub slow: FeeTier,
+    pub standard: FeeTier,
+    pub fast: FeeTier,
+}
+
+impl Eip1559Fees {
+    /// Fees for RPCs without `eth_feeHistory`: the legacy gas price is both
+    /// the cap and the tip.
+    fn from_gas_price(gas_price: u128) -> Self {
+        let tier = FeeTier {
+            max_priority_fee_per_gas: gas_price,
+            max_fee_per_gas: gas_price,
+        };
+        Self {
+            base_fee_per_gas: 0,
+            slow: tier,
+            standard: tier,
+            fast: tier,
+        }
+    }
+
+    /// Likely cost of `gas` units at the standard tip, in ETH.
+    pub fn expected_cost(&self, gas: u64) -> f64 {
+        let per_gas = (self.base_fee_per_gas + self.standard.max_priority_fee_per_gas)
+            .min(self.standard.max_fee_per_gas);
+        per_gas as f64 * gas as f64 / WEI_PER_ETH
+    }
+
+    /// Worst-case cost of `gas` units at the fast tier's fee cap, in ETH.
+    pub fn max_cost(&self, gas: u64) -> f64 {
+        self.fast.max_fee_per_gas as f64 * gas as f64 / WEI_PER_ETH
+    }
+}
+
+pub(super) fn parse_quantity(value: &Value) -> Result<u128, String> {
+    let hex = value
+        .as_str()
+        .ok_or_else(|| format!("Expected a hex quantity, got {}", value))?;
+    let digits = hex.trim_start_matches("0x");
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    u128::from_str_radix(digits, 16).map_err(|e| format!("Invalid quantity {}: {}", hex, e))
+}
+
+fn quantity(value: u128) -> String {
+    format!("0x{:x}", value)
+}
+
+/// Builds fee tiers from an `eth_feeHistory` result. The last base fee is
+/// the next block's; tips are the median of each reward percentile. The fee
+/// cap allows the base fee to double, which covers about six full blocks.
+fn fees_from_history(history: &Value) -> Option<Eip1559Fees> {
+    let base_fee_per_gas = parse_quantity(history["baseFeePerGas"].as_array()?.last()?).ok()?;
+    let rewards = history["reward"].as_array()?;
+    let tip = |index: usize| -> u128 {
+        let mut tips: Vec<u128> = rewards
+            .iter()
+            .filter_map(|block| block.get(index).and_then(|r| parse_quantity(r).ok()))
+            .collect();
+        tips.sort_unstable();
+        tips.get(tips.len() / 2).copied().unwrap_or_default()
+    };
+    let tier = |index: usize| {
+        let max_priority_fee_per_gas = tip(index);
+        FeeTier {
+            max_priority_fee_per_gas,
+            max_fee_per_gas: base_fee_per_gas * 2 + max_priority_fee_per_gas,
+        }
+    };
+    Some(Eip1559Fees {
+        base_fee_per_gas,
+        slow: tier(0),
+        standard: tier(1),
+        fast: tier(2),
+    })
+}
+
+/// Parses a decimal ETH amount into wei without going through float math
+/// for the fractional digits.
+pub fn eth_to_wei(amount: f64) -> Result<u128, String> {
+    if !amount.is_finite() || amount < 0.0 {
+        return Err(format!("Invalid amount: {}", amount));
+    }
+    let text = format!("{:.18}", amount);
+    let (whole, fraction) = text.split_once('.').unwrap_or((&text, ""));
+    let whole: u128 = whole
+        .parse()
+        .map_err(|_| format!("Invalid amount: {}", amount))?;
+    let fraction: u128 = format!("{:0<18}", fraction)[..18]
+        .parse()
+        .map_err(|_| format!("Invalid amount: {}", amount))?;
+    whole
+        .checked_mul(1_000_000_000_000_000_000)
+        .and_then(|wei| wei.checked_add(fraction))
+        .ok_or_else(|| format!("Amount too large: {}", amount))
+}
+
+fn parse_address(address: &str) -> Result<[u8; 20], String> {
+    let bytes = hex::decode(address.trim().trim_start_matches("0x"))
+        .map_err(|e| format!("Invalid address {}: {}", address, e))?;
+    bytes
+        .try_into()
+        .map_err(|_| format!("Invalid address {}: expected 20 bytes", address))
+}
+
+fn rlp_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        out.push(bytes[0]);
+    } else {
+        rlp_length(bytes.len(), 0x80, out);
+        out.extend_from_slice(bytes);
+    }
+}
+
+fn rlp_uint(value: u128, out: &mut Vec<u8>) {
+    let bytes = value.to_be_bytes();
+    let first = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    rlp_bytes(&bytes[first..], out);
+}
+
+fn rlp_list(items: &[u8], out: &mut Vec<u8>) {
+    rlp_length(items.len(), 0xc0, out);
+    out.extend_from_slice(items);
+}
+
+fn rlp_length(len: usize, offset: u8, out: &mut Vec<u8>) {
+    if len < 56 {
+        out.push(offset + len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let first = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+        out.push(offset + 55 + (bytes.len() - first) as u8);
+        out.extend_from_slice(&bytes[first..]);
+    }
+}
+
+/// An EIP-1559 (type 2) transaction with an empty access list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Eip1559Transaction {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: u128,
+    pub max_fee_per_gas: u128,
+    pub gas_limit: u64,
+    pub to: [u8; 20],
+    pub value: u128,
+    pub data: Vec<u8>,
+}
+
+impl Eip1559Transaction {
+    fn encode_fields(&self, out: &mut Vec<u8>) {
+        rlp_uint(self.chain_id.into(), out);
+        rlp_uint(self.nonce.into(), out);
+        rlp_uint(self.max_priority_fee_per_gas, out);
+        rlp_uint(self.max_fee_per_gas, out);
+        rlp_uint(self.gas_limit.into(), out);
+        rlp_bytes(&self.to, out);
+        rlp_uint(self.value, out);
+        rlp_bytes(&self.data, out);
+        rlp_list(&[], out);
+    }
+
+    fn envelope(fields: &[u8]) -> Vec<u8> {
+        let mut envelope = vec![EIP1559_TX_TYPE];
+        rlp_list(fields, &mut envelope);
+        envelope
+    }
+
+    pub fn signing_hash(&self) -> [u8; 32] {
+        let mut fields = Vec::new();
+        self.encode_fields(&mut fields);
+        keccak::hash(&Self::envelope(&fields)).to_bytes()
+    }
+
+    /// The signed raw transaction, ready for `eth_sendRawTransaction`.
+    pub fn sign(&self, key: &libsecp256k1::SecretKey) -> Vec<u8> {
+        let message = libsecp256k1::Message::parse(&self.signing_hash());
+        let (signature, recovery_id) = libsecp256k1::sign(&message, key);
+        let signature = signature.serialize();
+
+        let mut fields = Vec::new();
+        self.encode_fields(&mut fields);
+        rlp_uint(recovery_id.serialize().into(), &mut fields);
+        rlp_bytes(strip_zeros(&signature[..32]), &mut fields);
+        rlp_bytes(strip_zeros(&signature[32..]), &mut fields);
+        Self::envelope(&fields)
+    }
+}
+
+fn strip_zeros(bytes: &[u8]) -> &[u8] {
+    let first = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    &bytes[first..]
+}
+
+/// Address of a secp256k1 key: the last 20 bytes of the keccak hash of the
+/// uncompressed public key, EIP-55 checksummed.
+pub fn evm_address(key: &libsecp256k1::SecretKey) -> String {
+    let public = libsecp256k1::PublicKey::from_secret_key(key).serialize();
+    let hash = keccak::hash(&public[1..]).to_bytes();
+    validate_address(
+        &ChainId::Ethereum,
+        &format!("0x{}", hex::encode(&hash[12..])),
+    )
+    .expect("derived addresses are well formed")
+}
+
+fn signing_key_name(address: &str) -> String {
+    format!("{}{}", SIGNING_KEY_PREFIX, address.to_ascii_lowercase())
+}
+
+/// Stores a hex private key in the keystore and returns its address.
+pub fn import_signing_key(keystore: &Keystore, private_key: &str) -> Result<String, String> {
+    let bytes = hex::decode(private_key.trim().trim_start_matches("0x"))
+        .map_err(|_| "Private key must be 32 bytes of hex".to_string())?;
+    let key = libsecp256k1::SecretKey::parse_slice(&bytes)
+        .map_err(|_| "Private key is not a valid secp256k1 key".to_string())?;
+    let address = evm_address(&key);
+    keystore
+        .store_secret(&signing_key_name(&address), &key.serialize())
+        .map_err(|e| format!("Failed to store signing key: {}", e))?;
+    Ok(address)
+}
+
+pub fn remove_signing_key(keystore: &Keystore, address: &str) -> Result<(), String> {
+    keystore
+        .remove_secret(&signing_key_name(address))
+        .map_err(|e| format!("Failed to remove signing key: {}", e))
+}
+
+fn signing_key(keystore: &Keystore, address: &str) -> Result<libsecp256k1::SecretKey, String> {
+    let secret = keystore
+        .retrieve_secret(&signing_key_name(address))
+        .map_err(|_| {
+            format!(
+                "No signing key stored for {}; import one or pass a signed transaction",
+                address
+            )
+        })?;
+    libsecp256k1::SecretKey::parse_slice(&secret).map_err(|_| "Stored key is invalid".to_string())
+}
+
+/// Hands out nonces per chain and sender. The node's pending count is the
+/// floor, but transactions we just broadcast may not have reached the node
+/// we ask next, so our own counter wins when it is ahead.
+#[derive(Debug, Default)]
+pub struct EvmNonceManager {
+    next: Mutex<HashMap<(u64, String), u64>>,
+}
+
+impl EvmNonceManager {
+    pub fn reserve(&self, chain_id: u64, address: &str, pending: u64) -> u64 {
+        let mut next = self.next.lock();
+        let entry = next
+            .entry((chain_id, address.to_ascii_lowercase()))
+            .or_insert(pending);
+        let nonce = (*entry).max(pending);
+        *entry = nonce + 1;
+        nonce
+    }
+
+    /// Gives back a nonce whose transaction never reached the mempool, if no
+    /// later one was handed out meanwhile.
+    pub fn release(&self, chain_id: u64, address: &str, nonce: u64) {
+        if let Some(entry) = self
+            .next
+            .lock()
+            .get_mut(&(chain_id, address.to_ascii_lowercase()))
+        {
+            if *entry == nonce + 1 {
+                *entry = nonce;
+            }
+        }
+    }
+
+    /// Forgets local state so the next reservation follows the node again.
+    pub fn reset(&self, chain_id: u64, address: &str) {
+        self.next
+            .lock()
+            .remove(&(chain_id, address.to_ascii_lowercase()));
+    }
+}
+
+/// Why a JSON-RPC call failed: the endpoint itself (worth failing over)
+/// or the node rejecting the request.
+enum CallError {
+    Endpoint(String),
+    Rpc(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct EvmClient {
+    rpc_url: String,
+    pool: Option<(SharedRpcPoolManager, ChainId)>,
+    http: reqwest::Client,
+}
+
+impl EvmClient {
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            pool: None,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Routes calls through the chain's endpoint pool, failing over down
+    /// the ranking; `fallback_url` is used if the pool has no endpoints.
+    pub fn pooled(pools: SharedRpcPoolManager, chain: ChainId, fallback_url: String) -> Self {
+        Self {
+            rpc_url: fallback_url,
+            pool: Some((pools, chain)),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value, String> {
+        let payload = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let Some((pools, chain)) = &self.pool else {
+            return self
+                .send(&self.rpc_url, method, &payload)
+                .await
+                .map_err(|e| match e {
+                    CallError::Endpoint(e) | CallError::Rpc(e) => e,
+                });
+        };
+
+        let mut candidates = pools.read().await.ranked(chain, rand::random::<f64>());
+        if candidates.is_empty() {
+            candidates.push((String::new(), self.rpc_url.clone()));
+        }
+        let mut last_error = String::new();
+        for (id, url) in candidates {
+            let started = Instant::now();
+            match self.send(&url, method, &payload).await {
+                Ok(value) => {
+                    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+                    pools.write().await.record_success(chain, &id, latency_ms);
+                    return Ok(value);
+                }
+                Err(CallError::Endpoint(error)) => {
+                    tracing::debug!(
+                        "{} endpoint {} failed, failing over: {}",
+                        chain.as_str(),
+                        id,
+                        error
+                    );
+                    pools
+                        .write()
+                        .await
+                        .record_failure(chain, &id, error.clone());
+                    last_error = error;
+                }
+                Err(CallError::Rpc(error)) => return Err(error),
+            }
+        }
+        Err(format!(
+            "All {} RPC endpoints failed: {}",
+            chain.as_str(),
+            last_error
+        ))
+    }
+
+    async fn send(&self, url: &str, method: &str, payload: &Value) -> Result<Value, CallError> {
+        let response = self
+            .http
+            .post(url)
+            .json(payload)
+            .send()
+            .await
+            .map_err(|e| CallError::Endpoint(format!("{} request failed: {}", method, e)))?;
+        if !response.status().is_success() {
+            return Err(CallError::Endpoint(format!(
+                "{} failed: {}",
+                method,
+                response.status()
+            )));
+        }
+        let mut data: Value = response.json().await.map_err(|e| {
+            CallError::Endpoint(format!("Failed to parse {} response: {}", method, e))
+        })?;
+        if let Some(error) = data.get("error") {
+            let message = error["message"].as_str().unwrap_or("unknown error");
+            return Err(CallError::Rpc(format!("{} error: {}", method, message)));
+        }
+        Ok(data["result"].take())
+    }
+
+    pub async fn chain_id(&self) -> Result<u64, String> {
+        let id = parse_quantity(&self.call("eth_chainId", json!([])).await?)?;
+        u64::try_from(id).map_err(|_| format!("Invalid chain id: {}", id))
+    }
+
+    pub async fn balance_wei(&self, address: &str) -> Result<u128, String> {
+        parse_quantity(
+            &self
+                .call("eth_getBalance", json!([address, "latest"]))
+                .await?,
+        )
+    }
+
+    pub async fn pending_nonce(&self, address: &str) -> Result<u64, String> {
+        let nonce = parse_quantity(
+            &self
+                .call("eth_getTransactionCount", json!([address, "pending"]))
+                .await?,
+        )?;
+        u64::try_from(nonce).map_err(|_| format!("Invalid nonce: {}", nonce))
+    }
+
+    pub async fn estimate_gas(
+        &self,
+        from: Option<&str>,
+        to: &str,
+        value: u128,
+        data: &[u8],
+    ) -> Result<u64, String> {
+        let mut call = json!({ "to": to, "value": quantity(value) });
+        if let Some(from) = from {
+            call["from"] = json!(from);
+        }
+        if !data.is_empty() {
+            call["data"] = json!(format!("0x{}", hex::encode(data)));
+        }
+        let gas = parse_quantity(&self.call("eth_estimateGas", json!([call])).await?)?;
+        u64::try_from(gas).map_err(|_| format!("Invalid gas estimate: {}", gas))
+    }
+
+    pub async fn fee_estimate(&self) -> Result<Eip1559Fees, String> {
+        let history = self
+            .call(
+                "eth_feeHistory",
+                json!([
+                    quantity(FEE_HISTORY_BLOCKS.into()),
+                    "latest",
+                    REWARD_PERCENTILES
+                ]),
+            )
+            .await;
+        if let Some(fees) = history.ok().as_ref().and_then(fees_from_history) {
+            return Ok(fees);
+        }
+        let gas_price = parse_quantity(&self.call("eth_gasPrice", json!([])).await?)?;
+        Ok(Eip1559Fees::from_gas_price(gas_price))
+    }
+
+    pub async fn send_raw(&self, raw: &[u8]) -> Result<String, String> {
+        let hash = self
+            .call(
+                "eth_sendRawTransaction",
+                json!([format!("0x{}", hex::encode(raw))]),
+            )
+            .await?;
+        hash.as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "Invalid transaction hash".to_string())
+    }
+}
+
+/// Client for an EVM chain's configured RPC.
+pub async fn evm_client(
+    chain_manager: &SharedChainManager,
+    chain: &ChainId,
+) -> Result<EvmClient, String> {
+    if *chain == ChainId::Solana {
+        return Err("Solana is not an EVM chain".to_string());
+    }
+    let manager = chain_manager.read().await;
+    let config = manager
+        .get_chain_config(chain)
+        .filter(|config| config.enabled)
+        .ok_or_else(|| format!("Chain {} is not enabled", chain.as_str()))?;
+    Ok(match manager.rpc_pools() {
+        Some(pools) => EvmClient::pooled(pools, chain.clone(), config.rpc_url.clone()),
+        None => EvmClient::new(config.rpc_url.clone()),
+    })
+}
+
+/// Sends `amount` of the chain's native token. A wallet-signed raw
+/// transaction is broadcast as is; otherwise the transfer is built and
+/// signed with the key stored for `from`.
+pub async fn send_native_transfer(
+    chain_manager: &SharedChainManager,
+    keystore: &Keystore,
+    chain: &ChainId,
+    from: &str,
+    to: &str,
+    amount: f64,
+    signed_transaction: Option<&str>,
+) -> Result<String, String> {
+    let client = evm_client(chain_manager, chain).await?;
+    if let Some(raw) = signed_transaction {
+        let raw = hex::decode(raw.trim().trim_start_matches("0x"))
+            .map_err(|e| format!("Invalid signed transaction: {}", e))?;
+        return client.send_raw(&raw).await;
+    }
+
+    let key = signing_key(keystore, from)?;
+    let value = eth_to_wei(amount)?;
+    let chain_id = client.chain_id().await?;
+    let gas_limit = client.estimate_gas(Some(from), to, value, &[]).await?;
+    let fees = client.fee_estimate().await?;
+    let nonces = chain_manager.read().await.evm_nonces();
+    let nonce = nonces.reserve(chain_id, from, client.pending_nonce(from).await?);
+
+    let transaction = Eip1559Transaction {
+        chain_id,
+        nonce,
+        max_priority_fee_per_gas: fees.standard.max_priority_fee_per_gas,
+        max_fee_per_gas: fees.standard.max_fee_per_gas,
+        gas_limit,
+        to: parse_address(to)?,
+        value,
+        data: Vec::new(),
+    };
+    match client.send_raw(&transaction.sign(&key)).await {
+        Ok(hash) => Ok(hash),
+        Err(e) => {
+            let message = e.to_ascii_lowercase();
+            if message.contains("nonce too low") || message.contains("already known") {
+                nonces.reset(chain_id, from);
+            } else {
+                nonces.release(chain_id, from, nonce);
+            }
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rlp_matches_reference_encodings() {
+        let mut out = Vec::new();
+        rlp_bytes(b"dog", &mut out);
+        assert_eq!(out, hex::decode("83646f67").unwrap());
+
+        let mut items = Vec::new();
+        rlp_bytes(b"cat", &mut items);
+        rlp_bytes(b"dog", &mut items);
+        let mut out = Vec::new();
+        rlp_list(&items, &mut out);
+        assert_eq!(out, hex::decode("c88363617483646f67").unwrap());
+
+        for (value, expected) in [(0u128, "80"), (15, "0f"), (1024, "820400")] {
+            let mut out = Vec::new();
+            rlp_uint(value, &mut out);
+            assert_eq!(hex::encode(out), expected);
+        }
+
+        let long = [b'a'; 60];
+        let mut out = Vec::new();
+        rlp_bytes(&long, &mut out);
+        assert_eq!(&out[..2], &[0xb8, 60]);
+    }
+
+    #[test]
+    fn keys_derive_checksummed_addresses_and_signatures_recover() {
+        let key = libsecp256k1::SecretKey::parse_slice(
+            &hex::decode("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            evm_address(&key),
+            "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23"
+        );
+
+        let transaction = Eip1559Transaction {
+            chain_id: 8453,
+            nonce: 7,
+            max_priority_fee_per_gas: 1_000_000,
+            max_fee_per_gas: 50_000_000,
+            gas_limit: NATIVE_TRANSFER_GAS,
+            to: parse_address("0x2c7536E3605D9C16a7a3D7b1898e529396a65c23").unwrap(),
+            value: eth_to_wei(0.25).unwrap(),
+            data: Vec::new(),
+        };
+        let raw = transaction.sign(&key);
+        assert_eq!(raw[0], EIP1559_TX_TYPE);
+
+        let message = libsecp256k1::Message::parse(&transaction.signing_hash());
+        let (signature, recovery_id) = libsecp256k1::sign(&message, &key);
+        let recovered = libsecp256k1::recover(&message, &signature, &recovery_id).unwrap();
+        assert_eq!(recovered, libsecp256k1::PublicKey::from_secret_key(&key));
+        // Signing is deterministic, so the raw transaction ends with this s.
+        assert!(raw.ends_with(strip_zeros(&signature.serialize()[32..])));
+    }
+
+    #[test]
+    fn fee_history_yields_tiers_and_amounts_convert_exactly() {
+        let history = json!({
+            "baseFeePerGas": ["0x3b9aca00", "0x3b9aca00", "0x77359400"],
+            "reward": [
+                ["0x1", "0x5f5e100", "0x3b9aca00"],
+                ["0x2", "0x5f5e100", "0x77359400"],
+                ["0x3", "0xbebc200", "0x77359400"],
+            ],
+        });
+        let fees = fees_from_history(&history).unwrap();
+        assert_eq!(fees.base_fee_per_gas, 2_000_000_000);
+        assert_eq!(fees.slow.max_priority_fee_per_gas, 2);
+        assert_eq!(fees.standard.max_priority_fee_per_gas, 100_000_000);
+        assert_eq!(fees.fast.max_fee_per_gas, 6_000_000_000);
+        assert!((fees.expected_cost(NATIVE_TRANSFER_GAS) - 0.0000441).abs() < 1e-12);
+        assert!(fees_from_history(&json!({})).is_none());
+
+        assert_eq!(eth_to_wei(1.5).unwrap(), 1_500_000_000_000_000_000);
+        assert_eq!(eth_to_wei(0.000000001).unwrap(), 1_000_000_000);
+        assert!(eth_to_wei(-1.0).is_err());
+    }
+}
@@ -1,16 +1,24 @@
+pub mod address;
 pub mod arbitrum;
 pub mod base;
 pub mod commands;
 pub mod ethereum;
+pub mod evm;
 pub mod polygon;
+pub mod reconciliation;
+pub mod rpc_pool;
 pub mod solana;
 pub mod types;
 
+pub use address::*;
 pub use arbitrum::*;
 pub use base::*;
 pub use commands::*;
 pub use ethereum::*;
+pub use evm::*;
 pub use polygon::*;
+pub use reconciliation::*;
+pub use rpc_pool::*;
 pub use solana::*;
 pub use types::*;
 
@@ -19,6 +27,8 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use crate::api::SharedRpcPoolManager;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum ChainId {
@@ -70,19 +80,33 @@ pub struct ChainConfig {
 pub struct ChainManager {
     configs: HashMap<ChainId, ChainConfig>,
     active_chain: ChainId,
+    evm_nonces: Arc<EvmNonceManager>,
+    rpc_pools: Option<SharedRpcPoolManager>,
 }
 
 impl ChainManager {
     pub fn new() -> Self {
         let mut configs = HashMap::new();
+        let environment = crate::environment::active_environment();
+        // Test environments point every chain at its public testnet so
+        // rehearsed flows never touch mainnet funds.
+        let test = environment.is_test();
+        let pick = |mainnet: &str, testnet: &str| if test { testnet } else { mainnet }.to_string();
+        let solana_explorer = match environment.environment {
+            crate::environment::NetworkEnvironment::Mainnet => "https://solscan.io".to_string(),
+            crate::environment::NetworkEnvironment::Custom => {
+                "https://solscan.io/?cluster=custom".to_string()
+            }
+            other => format!("https://solscan.io/?cluster={}", other.as_str()),
+        };
 
         // Initialize with default configs
         configs.insert(
             ChainId::Solana,
             ChainConfig {
                 chain_id: ChainId::Solana,
-                rpc_url: "https://api.mainnet-beta.solana.com".to_string(),
-                explorer_url: "https://solscan.io".to_string(),
+                rpc_url: environment.solana_rpc_url(),
+                explorer_url: solana_explorer,
                 native_token: "SOL".to_string(),
                 enabled: true,
             },
@@ -92,8 +116,8 @@ impl ChainManager {
             ChainId::Ethereum,
             ChainConfig {
                 chain_id: ChainId::Ethereum,
-                rpc_url: "https://eth.llamarpc.com".to_string(),
-                explorer_url: "https://etherscan.io".to_string(),
+                rpc_url: pick("https://eth.llamarpc.com", "https://rpc.sepolia.org"),
+                explorer_url: pick("https://etherscan.io", "https://sepolia.etherscan.io"),
                 native_token: "ETH".to_string(),
                 enabled: true,
             },
@@ -103,8 +127,8 @@ impl ChainManager {
             ChainId::Base,
             ChainConfig {
                 chain_id: ChainId::Base,
-                rpc_url: "https://mainnet.base.org".to_string(),
-                explorer_url: "https://basescan.org".to_string(),
+                rpc_url: pick("https://mainnet.base.org", "https://sepolia.base.org"),
+                explorer_url: pick("https://basescan.org", "https://sepolia.basescan.org"),
                 native_token: "ETH".to_string(),
                 enabled: true,
             },
@@ -114,8 +138,11 @@ impl ChainManager {
             ChainId::Polygon,
             ChainConfig {
                 chain_id: ChainId::Polygon,
-                rpc_url: "https://polygon-rpc.com".to_string(),
-                explorer_url: "https://polygonscan.com".to_string(),
+                rpc_url: pick(
+                    "https://polygon-rpc.com",
+                    "https://rpc-amoy.polygon.technology",
+                ),
+                explorer_url: pick("https://polygonscan.com", "https://amoy.polygonscan.com"),
                 native_token: "MATIC".to_string(),
                 enabled: true,
             },
@@ -125,8 +152,11 @@ impl ChainManager {
             ChainId::Arbitrum,
             ChainConfig {
                 chain_id: ChainId::Arbitrum,
-                rpc_url: "https://arb1.arbitrum.io/rpc".to_string(),
-                explorer_url: "https://arbiscan.io".to_string(),
+                rpc_url: pick(
+                    "https://arb1.arbitrum.io/rpc",
+                    "https://sepolia-rollup.arbitrum.io/rpc",
+                ),
+                explorer_url: pick("https://arbiscan.io", "https://sepolia.arbiscan.io"),
                 native_token: "ETH".to_string(),
                 enabled: true,
             },
@@ -135,6 +165,8 @@ impl ChainManager {
         ChainManager {
             configs,
             active_chain: ChainId::Solana,
+            evm_nonces: Arc::new(EvmNonceManager::default()),
+            rpc_pools: None,
         }
     }
 
@@ -163,6 +195,19 @@ impl ChainManager {
         self.configs.insert(config.chain_id.clone(), config);
     }
 
+    pub fn evm_nonces(&self) -> Arc<EvmNonceManager> {
+        self.evm_nonces.clone()
+    }
+
+    /// Endpoint pools the EVM chains route and fail over through.
+    pub fn set_rpc_pools(&mut self, pools: SharedRpcPoolManager) {
+        self.rpc_pools = Some(pools);
+    }
+
+    pub fn rpc_pools(&self) -> Option<SharedRpcPoolManager> {
+        self.rpc_pools.clone()
+    }
+
     pub fn list_chains(&self) -> Vec<ChainConfig> {
         self.configs.values().cloned().collect()
     }
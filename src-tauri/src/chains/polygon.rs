@@ -1,6 +1,6 @@
 use super::ethereum::EthereumAdapter;
 use super::types::*;
-use super::ChainId;
+use super::{ChainId, EvmClient};
 
 #[derive(Debug)]
 pub struct PolygonAdapter {
@@ -13,6 +13,12 @@ impl PolygonAdapter {
             inner: EthereumAdapter::new(rpc_url, "Polygon", "MATIC"),
         }
     }
+
+    pub fn with_client(client: EvmClient) -> Self {
+        Self {
+            inner: EthereumAdapter::with_client(client, "Polygon", "MATIC"),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -25,8 +31,6 @@ impl ChainAdapter for PolygonAdapter {
 
     async fn get_fee_estimate(&self, wallet: &WalletInfo) -> Result<ChainFeeEstimate, String> {
         let mut estimate = self.inner.get_fee_estimate(wallet).await?;
-        estimate.max_fee *= 0.01;
-        estimate.avg_fee *= 0.01;
         estimate.fee_currency = "MATIC".to_string();
         estimate.estimated_time_seconds = 2;
         Ok(estimate)
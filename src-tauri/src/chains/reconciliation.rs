@@ -0,0 +1,822 @@
+//! On-chain reconciliation of the cross-chain portfolio.
+//!
+//! The registered wallets are scanned on every enabled chain they belong
+//! to, token by token, and compared with what the portfolio last held for
+//! them, whether that came from a previous scan or from balances pushed
+//! through `chain_get_cross_chain_portfolio`. Tokens the portfolio did not
+//! know about, holdings that are gone on chain and amounts that drifted are
+//! flagged, and the stored state is then brought in line with the chain.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::RwLock;
+
+use super::evm::parse_quantity;
+use super::types::ChainBalance;
+use super::{
+    evm_client, ChainId, EvmClient, RoutingHint, RpcPool, SharedChainManager, SharedRpcPool,
+};
+use crate::portfolio::dust::fetch_holdings;
+use crate::profiles::ProfilePaths;
+use crate::wallet::multi_wallet::MultiWalletManager;
+
+const HOLDINGS_FILE: &str = "cross_chain_holdings.json";
+const SCHEDULER_TICK: StdDuration = StdDuration::from_secs(5 * 60);
+const NATIVE_TOKEN: &str = "native";
+const TRANSFER_TOPIC: &str = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+const BALANCE_OF_SELECTOR: &str = "70a08231";
+const DECIMALS_SELECTOR: &str = "0x313ce567";
+const SYMBOL_SELECTOR: &str = "0x95d89b41";
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+pub const CROSS_CHAIN_RECONCILIATION_EVENT: &str = "cross_chain_reconciliation";
+
+/// Well-known tokens checked on every scan, on top of whatever the
+/// transfer logs turn up. Mainnet contracts only.
+const KNOWN_EVM_TOKENS: &[(ChainId, &str, &str, u8)] = &[
+    (
+        ChainId::Ethereum,
+        "USDC",
+        "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+        6,
+    ),
+    (
+        ChainId::Ethereum,
+        "USDT",
+        "0xdac17f958d2ee523a2206206994597c13d831ec7",
+        6,
+    ),
+    (
+        ChainId::Ethereum,
+        "WETH",
+        "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+        18,
+    ),
+    (
+        ChainId::Base,
+        "USDC",
+        "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913",
+        6,
+    ),
+    (
+        ChainId::Base,
+        "WETH",
+        "0x4200000000000000000000000000000000000006",
+        18,
+    ),
+    (
+        ChainId::Polygon,
+        "USDC",
+        "0x3c499c542cef5e3811e1192ce70d8cc03d5c3359",
+        6,
+    ),
+    (
+        ChainId::Polygon,
+        "USDT",
+        "0xc2132d05d31c914a87c6611c10748aeb04b58e8f",
+        6,
+    ),
+    (
+        ChainId::Polygon,
+        "WETH",
+        "0x7ceb23fd6bc0add59e62ac25578270cff1b9f619",
+        18,
+    ),
+    (
+        ChainId::Arbitrum,
+        "USDC",
+        "0xaf88d065e77c8cc2239327c5edb3a432268e5831",
+        6,
+    ),
+    (
+        ChainId::Arbitrum,
+        "USDT",
+        "0xfd086bc7cd5c481dcc9c85ebe478a1c0b69fcbb9",
+        6,
+    ),
+    (
+        ChainId::Arbitrum,
+        "WETH",
+        "0x82af49447d8a07e3bd95bd0d56f35241523fbab1",
+        18,
+    ),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReconciliationSettings {
+    pub enabled: bool,
+    pub interval_minutes: u32,
+    /// How far back transfer logs are searched for new EVM tokens.
+    pub log_lookback_blocks: u64,
+    /// Amount drift tolerated before a holding is flagged, in percent.
+    pub amount_tolerance_pct: f64,
+    pub last_run_at: Option<DateTime<Utc>>,
+}
+
+impl Default for ReconciliationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_minutes: 60,
+            log_lookback_blocks: 5_000,
+            amount_tolerance_pct: 0.5,
+            last_run_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HoldingSource {
+    Pushed,
+    OnChain,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HeldToken {
+    pub symbol: Option<String>,
+    pub amount: f64,
+    pub decimals: u8,
+}
+
+/// Everything one wallet holds on one chain, keyed by mint or contract;
+/// the native balance is under `native`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletHoldings {
+    pub chain_id: ChainId,
+    pub wallet: String,
+    pub tokens: HashMap<String, HeldToken>,
+    pub source: HoldingSource,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WalletHoldings {
+    fn key(&self) -> String {
+        holdings_key(&self.chain_id, &self.wallet)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscrepancyKind {
+    /// Held on chain but unknown to the portfolio.
+    UnknownToken,
+    /// In the portfolio but no longer held on chain.
+    MissingOnChain,
+    AmountMismatch,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Discrepancy {
+    pub chain_id: ChainId,
+    pub wallet: String,
+    pub token: String,
+    pub symbol: Option<String>,
+    pub kind: DiscrepancyKind,
+    pub stored_amount: Option<f64>,
+    pub on_chain_amount: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationReport {
+    pub scanned_at: DateTime<Utc>,
+    pub wallets_scanned: usize,
+    pub holdings: Vec<WalletHoldings>,
+    pub discrepancies: Vec<Discrepancy>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ReconcilerFile {
+    settings: ReconciliationSettings,
+    holdings: HashMap<String, WalletHoldings>,
+    last_report: Option<ReconciliationReport>,
+}
+
+pub struct CrossChainReconciler {
+    data: ReconcilerFile,
+    path: Option<PathBuf>,
+}
+
+pub type SharedCrossChainReconciler = Arc<RwLock<CrossChainReconciler>>;
+
+fn holdings_key(chain: &ChainId, wallet: &str) -> String {
+    format!("{}:{}", chain.as_str(), wallet)
+}
+
+impl CrossChainReconciler {
+    pub fn new(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(HOLDINGS_FILE));
+        let data = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self { data, path }
+    }
+
+    pub fn settings(&self) -> &ReconciliationSettings {
+        &self.data.settings
+    }
+
+    pub fn update_settings(&mut self, mut settings: ReconciliationSettings) {
+        settings.last_run_at = self.data.settings.last_run_at;
+        self.data.settings = settings;
+        self.save();
+    }
+
+    pub fn last_report(&self) -> Option<&ReconciliationReport> {
+        self.data.last_report.as_ref()
+    }
+
+    fn stored(&self, chain: &ChainId, wallet: &str) -> Option<&WalletHoldings> {
+        self.data.holdings.get(&holdings_key(chain, wallet))
+    }
+
+    /// Keeps what `chain_get_cross_chain_portfolio` reported so the next
+    /// scan can check it against the chain.
+    pub fn record_pushed(&mut self, chain: &ChainId, wallet: &str, balance: &ChainBalance) {
+        let wallet = if is_evm(chain) {
+            wallet.to_lowercase()
+        } else {
+            wallet.to_string()
+        };
+        let mut tokens: HashMap<String, HeldToken> = balance
+            .tokens
+            .iter()
+            .map(|token| {
+                (
+                    token.mint.clone(),
+                    HeldToken {
+                        symbol: Some(token.symbol.clone()),
+                        amount: token.amount,
+                        decimals: token.decimals,
+                    },
+                )
+            })
+            .collect();
+        let key = holdings_key(chain, &wallet);
+        // The adapters only report native balances; keep the tokens a scan
+        // found rather than dropping them.
+        if let Some(existing) = self.data.holdings.get(&key) {
+            for (mint, token) in &existing.tokens {
+                if mint != NATIVE_TOKEN {
+                    tokens.entry(mint.clone()).or_insert_with(|| token.clone());
+                }
+            }
+        }
+        tokens.insert(
+            NATIVE_TOKEN.to_string(),
+            HeldToken {
+                symbol: None,
+                amount: balance.native_balance,
+                decimals: 0,
+            },
+        );
+        self.data.holdings.insert(
+            key,
+            WalletHoldings {
+                chain_id: chain.clone(),
+                wallet,
+                tokens,
+                source: HoldingSource::Pushed,
+                updated_at: Utc::now(),
+            },
+        );
+        self.save();
+    }
+
+    fn apply_report(&mut self, report: &ReconciliationReport) {
+        for holdings in &report.holdings {
+            self.data.holdings.insert(holdings.key(), holdings.clone());
+        }
+        self.data.settings.last_run_at = Some(report.scanned_at);
+        self.data.last_report = Some(report.clone());
+        self.save();
+    }
+
+    fn is_due(&self, now: DateTime<Utc>) -> bool {
+        let settings = &self.data.settings;
+        settings.enabled
+            && settings.last_run_at.map_or(true, |last| {
+                now - last >= Duration::minutes(settings.interval_minutes.max(1) as i64)
+            })
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        match serde_json::to_string_pretty(&self.data) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(path, contents) {
+                    eprintln!("Failed to save cross-chain holdings: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize cross-chain holdings: {}", e),
+        }
+    }
+}
+
+/// Compares a fresh scan with the stored holdings. A wallet seen for the
+/// first time is taken as the baseline and flags nothing.
+pub fn reconcile_holdings(
+    stored: Option<&WalletHoldings>,
+    scanned: &WalletHoldings,
+    tolerance_pct: f64,
+) -> Vec<Discrepancy> {
+    let Some(stored) = stored else {
+        return Vec::new();
+    };
+    let flag =
+        |token: &str, symbol: Option<String>, kind, stored_amount, on_chain_amount| Discrepancy {
+            chain_id: scanned.chain_id.clone(),
+            wallet: scanned.wallet.clone(),
+            token: token.to_string(),
+            symbol,
+            kind,
+            stored_amount,
+            on_chain_amount,
+        };
+
+    let tokens: BTreeSet<&String> = stored.tokens.keys().chain(scanned.tokens.keys()).collect();
+    tokens
+        .into_iter()
+        .filter_map(|token| {
+            let before = stored.tokens.get(token).filter(|t| t.amount > 0.0);
+            let now = scanned.tokens.get(token).filter(|t| t.amount > 0.0);
+            match (before, now) {
+                (None, Some(now)) => Some(flag(
+                    token,
+                    now.symbol.clone(),
+                    DiscrepancyKind::UnknownToken,
+                    None,
+                    Some(now.amount),
+                )),
+                (Some(before), None) => Some(flag(
+                    token,
+                    before.symbol.clone(),
+                    DiscrepancyKind::MissingOnChain,
+                    Some(before.amount),
+                    Some(0.0),
+                )),
+                (Some(before), Some(now)) => {
+                    let drift_pct = (now.amount - before.amount).abs() / before.amount * 100.0;
+                    (drift_pct > tolerance_pct).then(|| {
+                        flag(
+                            token,
+                            now.symbol.clone().or_else(|| before.symbol.clone()),
+                            DiscrepancyKind::AmountMismatch,
+                            Some(before.amount),
+                            Some(now.amount),
+                        )
+                    })
+                }
+                (None, None) => None,
+            }
+        })
+        .collect()
+}
+
+/// Decodes an ABI-encoded string return value, accepting the `bytes32`
+/// symbols some older tokens return.
+pub fn decode_abi_string(result: &str) -> Option<String> {
+    let bytes = hex::decode(result.trim_start_matches("0x")).ok()?;
+    let text = if bytes.len() >= 64 {
+        let offset = word_to_usize(&bytes[..32])?;
+        let length = word_to_usize(bytes.get(offset..offset + 32)?)?;
+        bytes.get(offset + 32..offset + 32 + length)?.to_vec()
+    } else if bytes.len() == 32 {
+        bytes.into_iter().take_while(|b| *b != 0).collect()
+    } else {
+        return None;
+    };
+    String::from_utf8(text)
+        .ok()
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+fn word_to_usize(word: &[u8]) -> Option<usize> {
+    if word[..24].iter().any(|b| *b != 0) {
+        return None;
+    }
+    let mut tail = [0u8; 8];
+    tail.copy_from_slice(&word[24..32]);
+    usize::try_from(u64::from_be_bytes(tail)).ok()
+}
+
+fn padded_address(address: &str) -> String {
+    format!("{:0>64}", address.trim_start_matches("0x").to_lowercase())
+}
+
+fn is_evm(chain: &ChainId) -> bool {
+    *chain != ChainId::Solana
+}
+
+async fn scan_solana(pool: &SharedRpcPool, wallet: &str) -> Result<WalletHoldings, String> {
+    let owner =
+        Pubkey::from_str(wallet).map_err(|e| format!("Invalid Solana wallet {wallet}: {e}"))?;
+    let lamports = RpcPool::call(pool, RoutingHint::Read, move |client| {
+        client.get_balance(&owner)
+    })
+    .await
+    .map_err(|e| format!("Failed to fetch SOL balance for {wallet}: {e}"))?;
+    let mut tokens: HashMap<String, HeldToken> = fetch_holdings(pool, wallet)
+        .await?
+        .into_iter()
+        .map(|holding| {
+            (
+                holding.mint,
+                HeldToken {
+                    symbol: None,
+                    amount: holding.ui_amount,
+                    decimals: holding.decimals,
+                },
+            )
+        })
+        .collect();
+    tokens.insert(
+        NATIVE_TOKEN.to_string(),
+        HeldToken {
+            symbol: Some("SOL".to_string()),
+            amount: lamports as f64 / LAMPORTS_PER_SOL,
+            decimals: 9,
+        },
+    );
+    Ok(WalletHoldings {
+        chain_id: ChainId::Solana,
+        wallet: wallet.to_string(),
+        tokens,
+        source: HoldingSource::OnChain,
+        updated_at: Utc::now(),
+    })
+}
+
+/// Token contracts that sent to `wallet` within the lookback window.
+async fn discover_evm_tokens(
+    client: &EvmClient,
+    wallet: &str,
+    lookback_blocks: u64,
+) -> Result<BTreeSet<String>, String> {
+    let latest = parse_quantity(&client.call("eth_blockNumber", json!([])).await?)?;
+    let from = latest.saturating_sub(lookback_blocks as u128);
+    let logs = client
+        .call(
+            "eth_getLogs",
+            json!([{
+                "fromBlock": format!("0x{:x}", from),
+                "toBlock": "latest",
+                "topics": [TRANSFER_TOPIC, Value::Null, format!("0x{}", padded_address(wallet))]
+            }]),
+        )
+        .await?;
+    Ok(logs
+        .as_array()
+        .map(|logs| logs.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|log| log["address"].as_str())
+        .map(str::to_lowercase)
+        .collect())
+}
+
+async fn evm_call(client: &EvmClient, contract: &str, data: String) -> Result<Value, String> {
+    client
+        .call(
+            "eth_call",
+            json!([{ "to": contract, "data": data }, "latest"]),
+        )
+        .await
+}
+
+async fn scan_evm(
+    chain_manager: &SharedChainManager,
+    chain: &ChainId,
+    wallet: &str,
+    lookback_blocks: u64,
+) -> Result<WalletHoldings, String> {
+    let client = evm_client(chain_manager, chain).await?;
+    let native_symbol = chain_manager
+        .read()
+        .await
+        .get_chain_config(chain)
+        .map(|config| config.native_token.clone());
+    let wei = client.balance_wei(wallet).await?;
+
+    let mut tokens = HashMap::new();
+    tokens.insert(
+        NATIVE_TOKEN.to_string(),
+        HeldToken {
+            symbol: native_symbol,
+            amount: wei as f64 / super::WEI_PER_ETH,
+            decimals: 18,
+        },
+    );
+
+    let known: HashMap<&str, (&str, u8)> = if crate::environment::active_environment().is_test() {
+        HashMap::new()
+    } else {
+        KNOWN_EVM_TOKENS
+            .iter()
+            .filter(|(token_chain, ..)| token_chain == chain)
+            .map(|(_, symbol, address, decimals)| (*address, (*symbol, *decimals)))
+            .collect()
+    };
+    let mut contracts: BTreeSet<String> = known.keys().map(|address| address.to_string()).collect();
+    match discover_evm_tokens(&client, wallet, lookback_blocks).await {
+        Ok(found) => contracts.extend(found),
+        Err(e) => eprintln!("Transfer log scan failed on {}: {}", chain.as_str(), e),
+    }
+
+    let balance_call = format!("0x{}{}", BALANCE_OF_SELECTOR, padded_address(wallet));
+    for contract in contracts {
+        let Ok(raw) = evm_call(&client, &contract, balance_call.clone()).await else {
+            continue;
+        };
+        let amount = parse_quantity(&raw).unwrap_or(0);
+        if amount == 0 {
+            continue;
+        }
+        let (symbol, decimals) = match known.get(contract.as_str()) {
+            Some((symbol, decimals)) => (Some(symbol.to_string()), *decimals),
+            None => {
+                let decimals = evm_call(&client, &contract, DECIMALS_SELECTOR.to_string())
+                    .await
+                    .ok()
+                    .and_then(|raw| parse_quantity(&raw).ok())
+                    .and_then(|decimals| u8::try_from(decimals).ok())
+                    .unwrap_or(18);
+                let symbol = evm_call(&client, &contract, SYMBOL_SELECTOR.to_string())
+                    .await
+                    .ok()
+                    .and_then(|raw| raw.as_str().and_then(decode_abi_string));
+                (symbol, decimals)
+            }
+        };
+        tokens.insert(
+            contract,
+            HeldToken {
+                symbol,
+                amount: amount as f64 / 10f64.powi(decimals as i32),
+                decimals,
+            },
+        );
+    }
+
+    Ok(WalletHoldings {
+        chain_id: chain.clone(),
+        wallet: wallet.to_string(),
+        tokens,
+        source: HoldingSource::OnChain,
+        updated_at: Utc::now(),
+    })
+}
+
+/// Every registered wallet paired with the enabled chains its address is
+/// valid on: Solana keys on Solana, EVM addresses on every EVM chain.
+async fn registered_targets(app: &AppHandle) -> Vec<(ChainId, String)> {
+    let Some(chain_manager) = app.try_state::<SharedChainManager>() else {
+        return Vec::new();
+    };
+    let enabled: Vec<ChainId> = chain_manager
+        .read()
+        .await
+        .list_enabled_chains()
+        .into_iter()
+        .map(|config| config.chain_id)
+        .collect();
+    let wallets = app
+        .try_state::<MultiWalletManager>()
+        .and_then(|manager| manager.list_wallets().ok())
+        .unwrap_or_default();
+
+    let mut targets = BTreeSet::new();
+    for wallet in wallets {
+        let home = ChainId::from_str(&wallet.chain_id).unwrap_or_default();
+        for chain in &enabled {
+            if is_evm(chain) == is_evm(&home) {
+                let address = if is_evm(chain) {
+                    wallet.public_key.to_lowercase()
+                } else {
+                    wallet.public_key.clone()
+                };
+                targets.insert((chain.as_str(), address));
+            }
+        }
+    }
+    targets
+        .into_iter()
+        .filter_map(|(chain, address)| ChainId::from_str(chain).map(|chain| (chain, address)))
+        .collect()
+}
+
+/// Scans every registered wallet, flags differences with the stored state
+/// and then replaces that state with what is on chain.
+pub async fn run_reconciliation(
+    app: &AppHandle,
+    reconciler: &SharedCrossChainReconciler,
+) -> Result<ReconciliationReport, String> {
+    let chain_manager = app
+        .try_state::<SharedChainManager>()
+        .map(|state| state.inner().clone())
+        .ok_or_else(|| "Chain manager is not available".to_string())?;
+    let rpc_pool = app
+        .try_state::<SharedRpcPool>()
+        .map(|state| state.inner().clone());
+    let settings = reconciler.read().await.settings().clone();
+    let targets = registered_targets(app).await;
+
+    let mut report = ReconciliationReport {
+        scanned_at: Utc::now(),
+        wallets_scanned: targets.len(),
+        holdings: Vec::new(),
+        discrepancies: Vec::new(),
+        errors: Vec::new(),
+    };
+    for (chain, wallet) in targets {
+        let scanned = match &chain {
+            ChainId::Solana => match &rpc_pool {
+                Some(pool) => scan_solana(pool, &wallet).await,
+                None => Err("Solana RPC pool is not available".to_string()),
+            },
+            _ => {
+                scan_evm(
+                    &chain_manager,
+                    &chain,
+                    &wallet,
+                    settings.log_lookback_blocks,
+                )
+                .await
+            }
+        };
+        match scanned {
+            Ok(holdings) => {
+                let stored = reconciler.read().await.stored(&chain, &wallet).cloned();
+                report.discrepancies.extend(reconcile_holdings(
+                    stored.as_ref(),
+                    &holdings,
+                    settings.amount_tolerance_pct,
+                ));
+                report.holdings.push(holdings);
+            }
+            Err(e) => report
+                .errors
+                .push(format!("{} {}: {}", chain.as_str(), wallet, e)),
+        }
+    }
+
+    reconciler.write().await.apply_report(&report);
+    if !report.discrepancies.is_empty() {
+        let _ = app.emit(CROSS_CHAIN_RECONCILIATION_EVENT, &report);
+    }
+    Ok(report)
+}
+
+pub fn start_cross_chain_reconciliation(app: AppHandle, reconciler: SharedCrossChainReconciler) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(SCHEDULER_TICK);
+        loop {
+            ticker.tick().await;
+            if !reconciler.read().await.is_due(Utc::now()) {
+                continue;
+            }
+            if let Err(e) = run_reconciliation(&app, &reconciler).await {
+                eprintln!("Cross-chain reconciliation failed: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn chain_scan_portfolio(
+    app: AppHandle,
+    reconciler: State<'_, SharedCrossChainReconciler>,
+) -> Result<ReconciliationReport, String> {
+    run_reconciliation(&app, reconciler.inner()).await
+}
+
+#[tauri::command]
+pub async fn chain_get_reconciliation(
+    reconciler: State<'_, SharedCrossChainReconciler>,
+) -> Result<Option<ReconciliationReport>, String> {
+    Ok(reconciler.read().await.last_report().cloned())
+}
+
+#[tauri::command]
+pub async fn chain_get_reconciliation_settings(
+    reconciler: State<'_, SharedCrossChainReconciler>,
+) -> Result<ReconciliationSettings, String> {
+    Ok(reconciler.read().await.settings().clone())
+}
+
+#[tauri::command]
+pub async fn chain_update_reconciliation_settings(
+    settings: ReconciliationSettings,
+    reconciler: State<'_, SharedCrossChainReconciler>,
+) -> Result<ReconciliationSettings, String> {
+    if settings.interval_minutes == 0 {
+        return Err("Scan interval must be at least one minute".into());
+    }
+    if settings.amount_tolerance_pct < 0.0 {
+        return Err("Amount tolerance cannot be negative".into());
+    }
+    let mut reconciler = reconciler.write().await;
+    reconciler.update_settings(settings);
+    Ok(reconciler.settings().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holdings(tokens: &[(&str, f64)]) -> WalletHoldings {
+        WalletHoldings {
+            chain_id: ChainId::Base,
+            wallet: "0xabc".into(),
+            tokens: tokens
+                .iter()
+                .map(|(token, amount)| {
+                    (
+                        token.to_string(),
+                        HeldToken {
+                            symbol: None,
+                            amount: *amount,
+                            decimals: 18,
+                        },
+                    )
+                })
+                .collect(),
+            source: HoldingSource::OnChain,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn reconciliation_flags_new_missing_and_drifted_tokens() {
+        let stored = holdings(&[
+            ("native", 1.0),
+            ("0xusdc", 100.0),
+            ("0xold", 5.0),
+            ("0xsame", 10.0),
+        ]);
+        let scanned = holdings(&[
+            ("native", 1.002),
+            ("0xusdc", 80.0),
+            ("0xnew", 3.0),
+            ("0xsame", 10.0),
+        ]);
+
+        let mut found: Vec<(String, DiscrepancyKind)> =
+            reconcile_holdings(Some(&stored), &scanned, 0.5)
+                .into_iter()
+                .map(|d| (d.token, d.kind))
+                .collect();
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(
+            found,
+            vec![
+                ("0xnew".to_string(), DiscrepancyKind::UnknownToken),
+                ("0xold".to_string(), DiscrepancyKind::MissingOnChain),
+                ("0xusdc".to_string(), DiscrepancyKind::AmountMismatch),
+            ]
+        );
+        assert!(reconcile_holdings(None, &scanned, 0.5).is_empty());
+    }
+
+    #[test]
+    fn abi_strings_decode_dynamic_and_bytes32_symbols() {
+        let dynamic = concat!(
+            "0x",
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000004",
+            "5553444300000000000000000000000000000000000000000000000000000000"
+        );
+        assert_eq!(decode_abi_string(dynamic).as_deref(), Some("USDC"));
+
+        let bytes32 = "0x4d4b520000000000000000000000000000000000000000000000000000000000";
+        assert_eq!(decode_abi_string(bytes32).as_deref(), Some("MKR"));
+
+        assert_eq!(decode_abi_string("0x"), None);
+    }
+}
@@ -0,0 +1,745 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    rpc_client::RpcClient,
+    rpc_request::RpcError,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::sync::RwLock;
+
+use crate::environment::{
+    active_environment, guard_send_cluster, guard_send_url, NetworkEnvironment,
+};
+use crate::profiles::ProfilePaths;
+
+const RPC_POOL_FILE: &str = "rpc_pool.json";
+const HELIUS_ENDPOINT_ID: &str = "helius";
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Latency assumed for endpoints that have not been probed yet.
+const UNPROBED_LATENCY_MS: f64 = 250.0;
+const LATENCY_EWMA_ALPHA: f64 = 0.3;
+/// JSON-RPC error code returned by nodes that are behind or unhealthy.
+const NODE_UNHEALTHY_CODE: i64 = -32005;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoutingHint {
+    Read,
+    Send,
+}
+
+/// Which traffic an endpoint accepts. Send-only endpoints are typically
+/// staked or transaction-landing RPCs that should not serve reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndpointRole {
+    Any,
+    Read,
+    Send,
+}
+
+impl EndpointRole {
+    fn accepts(&self, hint: RoutingHint) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Read => hint == RoutingHint::Read,
+            Self::Send => hint == RoutingHint::Send,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndpointSource {
+    Default,
+    Helius,
+    Custom,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcEndpoint {
+    pub id: String,
+    pub label: String,
+    pub url: String,
+    pub weight: u32,
+    pub role: EndpointRole,
+    pub enabled: bool,
+    pub source: EndpointSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointHealth {
+    pub healthy: bool,
+    pub latency_ms: Option<f64>,
+    pub consecutive_failures: u32,
+    pub total_requests: u64,
+    pub total_failures: u64,
+    pub last_error: Option<String>,
+    pub last_checked: Option<DateTime<Utc>>,
+    /// Cluster the endpoint reported, used to keep test environments from
+    /// sending to mainnet.
+    #[serde(default)]
+    pub genesis_hash: Option<String>,
+}
+
+impl EndpointHealth {
+    pub fn record_success(&mut self, latency_ms: f64) {
+        self.total_requests += 1;
+        self.consecutive_failures = 0;
+        self.healthy = true;
+        self.last_error = None;
+        self.latency_ms = Some(match self.latency_ms {
+            Some(previous) => previous + LATENCY_EWMA_ALPHA * (latency_ms - previous),
+            None => latency_ms,
+        });
+    }
+
+    /// Takes the endpoint out of rotation after `failover_threshold`
+    /// consecutive failures.
+    pub fn record_failure(&mut self, error: String, failover_threshold: u32) {
+        self.total_requests += 1;
+        self.total_failures += 1;
+        self.consecutive_failures += 1;
+        self.last_error = Some(error);
+        if self.consecutive_failures >= failover_threshold {
+            self.healthy = false;
+        }
+    }
+}
+
+impl Default for EndpointHealth {
+    fn default() -> Self {
+        Self {
+            healthy: true,
+            latency_ms: None,
+            consecutive_failures: 0,
+            total_requests: 0,
+            total_failures: 0,
+            last_error: None,
+            last_checked: None,
+            genesis_hash: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPoolSettings {
+    pub probe_interval_secs: u64,
+    /// Consecutive failures before an endpoint is taken out of rotation.
+    pub failover_threshold: u32,
+}
+
+impl Default for RpcPoolSettings {
+    fn default() -> Self {
+        Self {
+            probe_interval_secs: 30,
+            failover_threshold: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedPool {
+    endpoints: Vec<RpcEndpoint>,
+    #[serde(default)]
+    settings: RpcPoolSettings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcEndpointStatus {
+    pub endpoint: RpcEndpoint,
+    pub health: EndpointHealth,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPoolSnapshot {
+    pub endpoints: Vec<RpcEndpointStatus>,
+    pub settings: RpcPoolSettings,
+}
+
+pub type SharedRpcPool = Arc<RwLock<RpcPool>>;
+
+/// Set of Solana RPC endpoints with latency-weighted routing. Reads and
+/// sends each pick from the endpoints that accept them, and callers
+/// fail over down the ranked list when an endpoint stops responding.
+pub struct RpcPool {
+    endpoints: Vec<RpcEndpoint>,
+    settings: RpcPoolSettings,
+    health: HashMap<String, EndpointHealth>,
+    clients: HashMap<String, Arc<RpcClient>>,
+    path: Option<PathBuf>,
+}
+
+impl RpcPool {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        let path = app_handle
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(RPC_POOL_FILE));
+        let persisted = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str::<PersistedPool>(&contents).ok());
+
+        let mut pool = match persisted {
+            Some(persisted) => Self::from_endpoints(persisted.endpoints, persisted.settings),
+            None => Self::default(),
+        };
+        pool.path = path;
+        pool
+    }
+
+    fn from_endpoints(endpoints: Vec<RpcEndpoint>, settings: RpcPoolSettings) -> Self {
+        let mut pool = Self {
+            endpoints: Vec::new(),
+            settings,
+            health: HashMap::new(),
+            clients: HashMap::new(),
+            path: None,
+        };
+        for endpoint in endpoints {
+            pool.insert(endpoint);
+        }
+        pool
+    }
+
+    pub fn settings(&self) -> &RpcPoolSettings {
+        &self.settings
+    }
+
+    pub fn snapshot(&self) -> RpcPoolSnapshot {
+        RpcPoolSnapshot {
+            endpoints: self
+                .endpoints
+                .iter()
+                .map(|endpoint| RpcEndpointStatus {
+                    endpoint: endpoint.clone(),
+                    health: self.health.get(&endpoint.id).cloned().unwrap_or_default(),
+                })
+                .collect(),
+            settings: self.settings.clone(),
+        }
+    }
+
+    fn insert(&mut self, endpoint: RpcEndpoint) {
+        self.clients.insert(
+            endpoint.id.clone(),
+            Arc::new(RpcClient::new(endpoint.url.clone())),
+        );
+        self.health.entry(endpoint.id.clone()).or_default();
+        match self.endpoints.iter_mut().find(|e| e.id == endpoint.id) {
+            Some(existing) => *existing = endpoint,
+            None => self.endpoints.push(endpoint),
+        }
+    }
+
+    pub fn upsert_endpoint(&mut self, mut endpoint: RpcEndpoint) -> Result<RpcEndpoint, String> {
+        validate_endpoint(&mut endpoint)?;
+        self.insert(endpoint.clone());
+        self.save()?;
+        Ok(endpoint)
+    }
+
+    /// Replaces the user-managed endpoints in one step. The Helius endpoint
+    /// follows the stored API key and is kept as is.
+    pub fn replace_endpoints(&mut self, mut endpoints: Vec<RpcEndpoint>) -> Result<(), String> {
+        for endpoint in endpoints.iter_mut() {
+            validate_endpoint(endpoint)?;
+        }
+        endpoints.retain(|endpoint| endpoint.id != HELIUS_ENDPOINT_ID);
+        if !endpoints.iter().any(|endpoint| endpoint.enabled)
+            && !self
+                .endpoints
+                .iter()
+                .any(|endpoint| endpoint.id == HELIUS_ENDPOINT_ID && endpoint.enabled)
+        {
+            return Err("At least one Solana RPC endpoint must stay enabled".to_string());
+        }
+
+        let kept: Vec<String> = endpoints.iter().map(|e| e.id.clone()).collect();
+        self.endpoints
+            .retain(|e| e.id == HELIUS_ENDPOINT_ID || kept.contains(&e.id));
+        self.health
+            .retain(|id, _| id == HELIUS_ENDPOINT_ID || kept.contains(id));
+        self.clients
+            .retain(|id, _| id == HELIUS_ENDPOINT_ID || kept.contains(id));
+        for endpoint in endpoints {
+            self.insert(endpoint);
+        }
+        self.save()
+    }
+
+    pub fn remove_endpoint(&mut self, id: &str) -> Result<(), String> {
+        let before = self.endpoints.len();
+        self.endpoints.retain(|endpoint| endpoint.id != id);
+        if self.endpoints.len() == before {
+            return Err(format!("RPC endpoint {id} not found"));
+        }
+        self.health.remove(id);
+        self.clients.remove(id);
+        self.save()
+    }
+
+    pub fn update_settings(&mut self, settings: RpcPoolSettings) -> Result<(), String> {
+        if settings.failover_threshold == 0 {
+            return Err("Failover threshold must be at least 1".to_string());
+        }
+        self.settings = RpcPoolSettings {
+            probe_interval_secs: settings.probe_interval_secs.max(5),
+            ..settings
+        };
+        self.save()
+    }
+
+    /// Adds, replaces or removes the endpoint backed by the user's Helius
+    /// API key. The key is part of the URL, so this endpoint is rebuilt
+    /// from the keystore on startup rather than persisted.
+    pub fn sync_helius(&mut self, api_key: Option<&str>) {
+        let host = match active_environment().environment {
+            NetworkEnvironment::Mainnet => Some("mainnet.helius-rpc.com"),
+            NetworkEnvironment::Devnet => Some("devnet.helius-rpc.com"),
+            NetworkEnvironment::Testnet | NetworkEnvironment::Custom => None,
+        };
+        match host.zip(api_key.filter(|key| !key.trim().is_empty())) {
+            Some((host, key)) => {
+                let existing = self.endpoints.iter().find(|e| e.id == HELIUS_ENDPOINT_ID);
+                let endpoint = RpcEndpoint {
+                    id: HELIUS_ENDPOINT_ID.to_string(),
+                    label: "Helius".to_string(),
+                    url: format!("https://{}/?api-key={}", host, key.trim()),
+                    weight: existing.map(|e| e.weight).unwrap_or(3),
+                    role: existing.map(|e| e.role).unwrap_or(EndpointRole::Any),
+                    enabled: existing.map(|e| e.enabled).unwrap_or(true),
+                    source: EndpointSource::Helius,
+                };
+                self.insert(endpoint);
+            }
+            None => {
+                self.endpoints.retain(|e| e.id != HELIUS_ENDPOINT_ID);
+                self.health.remove(HELIUS_ENDPOINT_ID);
+                self.clients.remove(HELIUS_ENDPOINT_ID);
+            }
+        }
+    }
+
+    /// URL of the endpoint the next call with `hint` would be routed to.
+    pub fn select(&self, hint: RoutingHint) -> Option<String> {
+        let id = self
+            .ranked(hint, rand::random::<f64>())
+            .into_iter()
+            .next()?;
+        self.endpoints
+            .iter()
+            .find(|endpoint| endpoint.id == id)
+            .map(|endpoint| endpoint.url.clone())
+    }
+
+    /// Endpoint ids in the order a call should try them; see
+    /// [`rank_endpoints`].
+    pub fn ranked(&self, hint: RoutingHint, roll: f64) -> Vec<String> {
+        rank_endpoints(&self.endpoints, &self.health, hint, roll)
+    }
+
+    fn candidates(&self, hint: RoutingHint) -> Vec<(String, Arc<RpcClient>)> {
+        self.ranked(hint, rand::random::<f64>())
+            .into_iter()
+            .filter(|id| hint != RoutingHint::Send || self.send_allowed(id).is_ok())
+            .filter_map(|id| {
+                let client = self.clients.get(&id)?.clone();
+                Some((id, client))
+            })
+            .collect()
+    }
+
+    /// Whether transactions may be sent through `id` in the active
+    /// environment, judged by its URL and, once probed, its genesis hash.
+    fn send_allowed(&self, id: &str) -> Result<(), String> {
+        let endpoint = self
+            .endpoints
+            .iter()
+            .find(|endpoint| endpoint.id == id)
+            .ok_or_else(|| format!("RPC endpoint {id} not found"))?;
+        guard_send_url(&endpoint.url).map_err(|e| e.to_string())?;
+        if let Some(genesis_hash) = self.health.get(id).and_then(|h| h.genesis_hash.as_deref()) {
+            guard_send_cluster(genesis_hash).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    pub fn record_success(&mut self, id: &str, latency_ms: f64) {
+        self.health
+            .entry(id.to_string())
+            .or_default()
+            .record_success(latency_ms);
+    }
+
+    pub fn record_failure(&mut self, id: &str, error: String) {
+        let threshold = self.settings.failover_threshold;
+        self.health
+            .entry(id.to_string())
+            .or_default()
+            .record_failure(error, threshold);
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create RPC pool directory: {e}"))?;
+            }
+            let persisted = PersistedPool {
+                endpoints: self
+                    .endpoints
+                    .iter()
+                    .filter(|e| e.source != EndpointSource::Helius)
+                    .cloned()
+                    .collect(),
+                settings: self.settings.clone(),
+            };
+            let contents = serde_json::to_string_pretty(&persisted)
+                .map_err(|e| format!("Failed to serialize RPC pool: {e}"))?;
+            fs::write(path, contents).map_err(|e| format!("Failed to persist RPC pool: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// Probes every enabled endpoint with `getHealth` and folds the
+    /// results into the pool. The lock is only held to snapshot and
+    /// record, not while requests are in flight.
+    pub async fn probe(pool: &SharedRpcPool) {
+        let endpoints: Vec<RpcEndpoint> = pool
+            .read()
+            .await
+            .endpoints
+            .iter()
+            .filter(|e| e.enabled)
+            .cloned()
+            .collect();
+        let client = reqwest::Client::new();
+        let results = futures_util::future::join_all(
+            endpoints
+                .iter()
+                .map(|endpoint| probe_endpoint(&client, &endpoint.url)),
+        )
+        .await;
+
+        let mut guard = pool.write().await;
+        let now = Utc::now();
+        for (endpoint, result) in endpoints.iter().zip(results) {
+            let genesis_hash = match result {
+                Ok((latency_ms, genesis_hash)) => {
+                    guard.record_success(&endpoint.id, latency_ms);
+                    genesis_hash
+                }
+                Err(error) => {
+                    guard.record_failure(&endpoint.id, error);
+                    None
+                }
+            };
+            if let Some(health) = guard.health.get_mut(&endpoint.id) {
+                health.last_checked = Some(now);
+                if genesis_hash.is_some() {
+                    health.genesis_hash = genesis_hash;
+                }
+            }
+        }
+    }
+
+    /// Runs a blocking RPC call against the pool, failing over to the next
+    /// ranked endpoint on transport errors or unhealthy-node responses.
+    /// Errors returned by the node for the request itself are not retried.
+    pub async fn call<T, F>(pool: &SharedRpcPool, hint: RoutingHint, op: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: Fn(&RpcClient) -> ClientResult<T> + Send + Sync + 'static,
+    {
+        let candidates = pool.read().await.candidates(hint);
+        if candidates.is_empty() {
+            if hint == RoutingHint::Send && active_environment().is_test() {
+                return Err(format!(
+                    "No {} RPC endpoint available for sending",
+                    active_environment().environment.as_str()
+                ));
+            }
+            return Err("No RPC endpoints available".to_string());
+        }
+
+        let op = Arc::new(op);
+        let mut last_error = String::new();
+        for (id, client) in candidates {
+            let op = op.clone();
+            let started = Instant::now();
+            // RpcClient does not expose payload sizes, so only the call is counted.
+            crate::monitor::record_network_usage("rpc", 0, 0);
+            let result = tokio::task::spawn_blocking(move || op(&client))
+                .await
+                .map_err(|e| e.to_string())?;
+            match result {
+                Ok(value) => {
+                    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+                    pool.write().await.record_success(&id, latency_ms);
+                    return Ok(value);
+                }
+                Err(err) if is_endpoint_failure(&err) => {
+                    tracing::debug!("RPC endpoint {} failed, failing over: {}", id, err);
+                    last_error = err.to_string();
+                    pool.write().await.record_failure(&id, last_error.clone());
+                }
+                Err(err) => return Err(err.to_string()),
+            }
+        }
+        Err(format!("All RPC endpoints failed: {last_error}"))
+    }
+}
+
+/// Endpoint ids in the order a call should try them. The first entry is
+/// drawn with probability proportional to weight over latency among
+/// healthy endpoints (`roll` in `[0, 1)`); the rest follow by score, with
+/// unhealthy endpoints kept as a last resort.
+pub fn rank_endpoints(
+    endpoints: &[RpcEndpoint],
+    health: &HashMap<String, EndpointHealth>,
+    hint: RoutingHint,
+    roll: f64,
+) -> Vec<String> {
+    let mut healthy: Vec<(&RpcEndpoint, f64)> = Vec::new();
+    let mut unhealthy: Vec<(&RpcEndpoint, f64)> = Vec::new();
+    for endpoint in endpoints
+        .iter()
+        .filter(|e| e.enabled && e.role.accepts(hint))
+    {
+        let health = health.get(&endpoint.id);
+        let latency = health
+            .and_then(|h| h.latency_ms)
+            .unwrap_or(UNPROBED_LATENCY_MS)
+            .max(1.0);
+        let score = endpoint.weight as f64 / latency;
+        if health.map(|h| h.healthy).unwrap_or(true) {
+            healthy.push((endpoint, score));
+        } else {
+            unhealthy.push((endpoint, score));
+        }
+    }
+
+    let by_score = |a: &(&RpcEndpoint, f64), b: &(&RpcEndpoint, f64)| {
+        b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal)
+    };
+    healthy.sort_by(by_score);
+    unhealthy.sort_by(by_score);
+
+    let total: f64 = healthy.iter().map(|(_, score)| score).sum();
+    if total > 0.0 {
+        let mut target = roll.clamp(0.0, 1.0) * total;
+        let picked = healthy
+            .iter()
+            .position(|(_, score)| {
+                target -= score;
+                target < 0.0
+            })
+            .unwrap_or(healthy.len() - 1);
+        let first = healthy.remove(picked);
+        healthy.insert(0, first);
+    }
+
+    healthy
+        .into_iter()
+        .chain(unhealthy)
+        .map(|(endpoint, _)| endpoint.id.clone())
+        .collect()
+}
+
+/// Checks an endpoint before it enters a pool, assigning an id to new ones.
+pub fn validate_endpoint(endpoint: &mut RpcEndpoint) -> Result<(), String> {
+    url::Url::parse(&endpoint.url).map_err(|e| format!("Invalid RPC URL: {e}"))?;
+    if endpoint.id.is_empty() {
+        endpoint.id = uuid::Uuid::new_v4().to_string();
+    }
+    if endpoint.weight == 0 {
+        return Err("Endpoint weight must be at least 1".to_string());
+    }
+    Ok(())
+}
+
+impl Default for RpcPool {
+    fn default() -> Self {
+        let mut endpoints = Vec::new();
+        if let Ok(url) = std::env::var("SOLANA_RPC_URL") {
+            endpoints.push(RpcEndpoint {
+                id: "env".to_string(),
+                label: "SOLANA_RPC_URL".to_string(),
+                url,
+                weight: 2,
+                role: EndpointRole::Any,
+                enabled: true,
+                source: EndpointSource::Custom,
+            });
+        }
+        let environment = active_environment();
+        endpoints.push(RpcEndpoint {
+            id: format!("solana-{}", environment.environment.as_str()),
+            label: match environment.environment {
+                NetworkEnvironment::Mainnet => "Solana Mainnet (public)".to_string(),
+                NetworkEnvironment::Devnet => "Solana Devnet (public)".to_string(),
+                NetworkEnvironment::Testnet => "Solana Testnet (public)".to_string(),
+                NetworkEnvironment::Custom => "Custom cluster".to_string(),
+            },
+            url: environment.solana_rpc_url(),
+            weight: 1,
+            role: EndpointRole::Any,
+            enabled: true,
+            source: EndpointSource::Default,
+        });
+        Self::from_endpoints(endpoints, RpcPoolSettings::default())
+    }
+}
+
+fn is_endpoint_failure(err: &ClientError) -> bool {
+    match err.kind() {
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => true,
+        ClientErrorKind::RpcError(RpcError::RpcRequestError(_)) => true,
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            *code == NODE_UNHEALTHY_CODE
+        }
+        _ => false,
+    }
+}
+
+/// Returns the `getHealth` round trip in milliseconds and the endpoint's
+/// genesis hash.
+async fn probe_endpoint(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<(f64, Option<String>), String> {
+    let started = Instant::now();
+    let body = rpc_request(client, url, "getHealth").await?;
+    if let Some(error) = body.get("error") {
+        return Err(format!("Node unhealthy: {error}"));
+    }
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+    let genesis_hash = rpc_request(client, url, "getGenesisHash")
+        .await
+        .ok()
+        .and_then(|body| body.get("result")?.as_str().map(|hash| hash.to_string()));
+    Ok((latency_ms, genesis_hash))
+}
+
+async fn rpc_request(
+    client: &reqwest::Client,
+    url: &str,
+    method: &str,
+) -> Result<serde_json::Value, String> {
+    let response = client
+        .post(url)
+        .timeout(PROBE_TIMEOUT)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method }))
+        .send()
+        .await
+        .map_err(|e| format!("Probe failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("Probe returned {}", response.status()));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid probe response: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(id: &str, weight: u32, role: EndpointRole) -> RpcEndpoint {
+        RpcEndpoint {
+            id: id.to_string(),
+            label: id.to_string(),
+            url: format!("https://{id}.example.com"),
+            weight,
+            role,
+            enabled: true,
+            source: EndpointSource::Custom,
+        }
+    }
+
+    #[test]
+    fn routing_respects_hints_and_latency_weighting() {
+        let mut pool = RpcPool::from_endpoints(
+            vec![
+                endpoint("fast", 1, EndpointRole::Any),
+                endpoint("slow", 1, EndpointRole::Any),
+                endpoint("sender", 1, EndpointRole::Send),
+            ],
+            RpcPoolSettings::default(),
+        );
+        pool.record_success("fast", 50.0);
+        pool.record_success("slow", 450.0);
+
+        // fast scores 1/50, slow 1/450: low rolls land on the fast endpoint
+        assert_eq!(pool.ranked(RoutingHint::Read, 0.0), vec!["fast", "slow"]);
+        assert_eq!(pool.ranked(RoutingHint::Read, 0.99), vec!["slow", "fast"]);
+        assert!(pool
+            .ranked(RoutingHint::Send, 0.0)
+            .contains(&"sender".to_string()));
+        assert!(!pool
+            .ranked(RoutingHint::Read, 0.5)
+            .contains(&"sender".to_string()));
+    }
+
+    #[test]
+    fn failing_endpoints_drop_to_last_resort() {
+        let mut pool = RpcPool::from_endpoints(
+            vec![
+                endpoint("primary", 10, EndpointRole::Any),
+                endpoint("backup", 1, EndpointRole::Any),
+            ],
+            RpcPoolSettings {
+                failover_threshold: 2,
+                ..RpcPoolSettings::default()
+            },
+        );
+
+        pool.record_failure("primary", "timeout".to_string());
+        assert_eq!(pool.ranked(RoutingHint::Read, 0.0)[0], "primary");
+
+        pool.record_failure("primary", "timeout".to_string());
+        assert_eq!(
+            pool.ranked(RoutingHint::Read, 0.0),
+            vec!["backup", "primary"]
+        );
+
+        pool.record_success("primary", 80.0);
+        assert!(pool.snapshot().endpoints[0].health.healthy);
+    }
+
+    #[test]
+    fn helius_endpoint_follows_api_key() {
+        let mut pool = RpcPool::default();
+        pool.sync_helius(Some("abc123"));
+        assert!(pool
+            .snapshot()
+            .endpoints
+            .iter()
+            .any(|status| status.endpoint.url.ends_with("api-key=abc123")));
+
+        pool.sync_helius(None);
+        assert!(pool
+            .snapshot()
+            .endpoints
+            .iter()
+            .all(|status| status.endpoint.source != EndpointSource::Helius));
+    }
+}
@@ -65,6 +65,7 @@ impl ChainAdapter for SolanaAdapter {
             avg_fee: 0.000005,
             fee_currency: "SOL".to_string(),
             estimated_time_seconds: 1,
+            eip1559: None,
         })
     }
 
@@ -105,6 +106,7 @@ impl ChainAdapter for SolanaAdapter {
                 avg_fee: 0.000005,
                 fee_currency: "SOL".to_string(),
                 estimated_time_seconds: 1,
+                eip1559: None,
             },
         })
     }
@@ -27,6 +27,9 @@ pub struct ChainFeeEstimate {
     pub avg_fee: f64,
     pub fee_currency: String,
     pub estimated_time_seconds: u64,
+    /// Per-gas fee tiers behind the totals, for EVM chains.
+    #[serde(default)]
+    pub eip1559: Option<super::Eip1559Fees>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
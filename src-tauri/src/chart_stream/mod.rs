@@ -1,3 +1,7 @@
+pub mod orderbook;
+
+pub use orderbook::*;
+
 use crate::core::price_engine::{get_price_engine, PriceUpdate};
 use crate::core::WebSocketManager;
 use serde::{Deserialize, Serialize};
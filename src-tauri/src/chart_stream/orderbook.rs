@@ -0,0 +1,393 @@
+use crate::market::{DriftAdapter, DriftOrderBook, DriftOrderBookLevel};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::RwLock;
+use tokio::time::Duration;
+
+const DEFAULT_DEPTH: usize = 25;
+const MIN_INTERVAL_MS: u64 = 250;
+
+/// Prices are keyed in nano-units so levels order and compare exactly.
+const PRICE_SCALE: f64 = 1e9;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BookLevel {
+    pub price: f64,
+    /// Zero in a diff means the level was removed.
+    pub size: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderBookSnapshotEvent {
+    pub symbol: String,
+    pub sequence: u64,
+    pub timestamp: i64,
+    /// Best bid first.
+    pub bids: Vec<BookLevel>,
+    /// Best ask first.
+    pub asks: Vec<BookLevel>,
+}
+
+/// Levels that changed since `sequence - 1`. A client that sees a gap in
+/// `sequence` should call `get_orderbook_snapshot` and start over.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderBookDiffEvent {
+    pub symbol: String,
+    pub sequence: u64,
+    pub timestamp: i64,
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+}
+
+fn price_key(price: f64) -> i64 {
+    (price * PRICE_SCALE).round() as i64
+}
+
+fn key_price(key: i64) -> f64 {
+    key as f64 / PRICE_SCALE
+}
+
+fn side_diff(old: &BTreeMap<i64, f64>, new: &BTreeMap<i64, f64>) -> Vec<BookLevel> {
+    let removed = old
+        .keys()
+        .filter(|key| !new.contains_key(key))
+        .map(|key| BookLevel {
+            price: key_price(*key),
+            size: 0.0,
+        });
+    let changed = new
+        .iter()
+        .filter(|(key, size)| old.get(key) != Some(size))
+        .map(|(key, size)| BookLevel {
+            price: key_price(*key),
+            size: *size,
+        });
+    removed.chain(changed).collect()
+}
+
+/// Aggregated price-level book, trimmed to `depth` levels per side.
+#[derive(Debug, Clone, Default)]
+pub struct L2Book {
+    bids: BTreeMap<i64, f64>,
+    asks: BTreeMap<i64, f64>,
+    sequence: u64,
+}
+
+impl L2Book {
+    fn side_from(levels: impl Iterator<Item = BookLevel>) -> BTreeMap<i64, f64> {
+        let mut side = BTreeMap::new();
+        for level in levels.filter(|l| l.size > 0.0 && l.price > 0.0) {
+            // Several sources can quote the same price; aggregate them.
+            *side.entry(price_key(level.price)).or_insert(0.0) += level.size;
+        }
+        side
+    }
+
+    /// Replaces the book with fresh levels and returns what changed, or
+    /// `None` when nothing did.
+    pub fn replace(
+        &mut self,
+        bids: impl Iterator<Item = BookLevel>,
+        asks: impl Iterator<Item = BookLevel>,
+        depth: usize,
+    ) -> Option<(Vec<BookLevel>, Vec<BookLevel>)> {
+        let mut new_bids = Self::side_from(bids);
+        let mut new_asks = Self::side_from(asks);
+        while new_bids.len() > depth {
+            new_bids.pop_first();
+        }
+        while new_asks.len() > depth {
+            new_asks.pop_last();
+        }
+
+        let bid_diff = side_diff(&self.bids, &new_bids);
+        let ask_diff = side_diff(&self.asks, &new_asks);
+        self.bids = new_bids;
+        self.asks = new_asks;
+        if bid_diff.is_empty() && ask_diff.is_empty() {
+            return None;
+        }
+        self.sequence += 1;
+        Some((bid_diff, ask_diff))
+    }
+
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    pub fn bids(&self) -> Vec<BookLevel> {
+        self.bids
+            .iter()
+            .rev()
+            .map(|(key, size)| BookLevel {
+                price: key_price(*key),
+                size: *size,
+            })
+            .collect()
+    }
+
+    pub fn asks(&self) -> Vec<BookLevel> {
+        self.asks
+            .iter()
+            .map(|(key, size)| BookLevel {
+                price: key_price(*key),
+                size: *size,
+            })
+            .collect()
+    }
+}
+
+struct OrderBookSubscription {
+    market_index: u32,
+    depth: usize,
+    interval_ms: u64,
+    ref_count: u32,
+    book: L2Book,
+}
+
+lazy_static::lazy_static! {
+    static ref ORDERBOOK_SUBS: Arc<RwLock<HashMap<String, OrderBookSubscription>>> =
+        Arc::new(RwLock::new(HashMap::new()));
+}
+
+/// Resolves "SOL", "SOL-PERP" or a numeric market index to a Drift perp
+/// market index.
+async fn resolve_market(adapter: &DriftAdapter, symbol: &str) -> Result<u32, String> {
+    if let Ok(index) = symbol.parse::<u32>() {
+        return Ok(index);
+    }
+    let wanted = symbol.to_uppercase();
+    adapter
+        .fetch_markets()
+        .await?
+        .into_iter()
+        .find(|m| {
+            m.market_type.eq_ignore_ascii_case("perp")
+                && (m.symbol.eq_ignore_ascii_case(&wanted)
+                    || m.base_asset_symbol.eq_ignore_ascii_case(&wanted))
+        })
+        .map(|m| m.market_index)
+        .ok_or_else(|| format!("No order book market found for {}", symbol))
+}
+
+fn levels(book: &DriftOrderBook) -> (Vec<BookLevel>, Vec<BookLevel>) {
+    let convert = |levels: &[DriftOrderBookLevel]| {
+        levels
+            .iter()
+            .map(|l| BookLevel {
+                price: l.price,
+                size: l.size,
+            })
+            .collect::<Vec<_>>()
+    };
+    (convert(&book.bids), convert(&book.asks))
+}
+
+async fn snapshot_event(symbol: &str) -> Option<OrderBookSnapshotEvent> {
+    let subs = ORDERBOOK_SUBS.read().await;
+    subs.get(symbol).map(|sub| OrderBookSnapshotEvent {
+        symbol: symbol.to_string(),
+        sequence: sub.book.sequence(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        bids: sub.book.bids(),
+        asks: sub.book.asks(),
+    })
+}
+
+/// Streams an L2 book for `symbol` from the Drift DLOB. Emits one
+/// `orderbook_snapshot` event and then `orderbook_update` diffs whenever a
+/// level changes.
+#[tauri::command]
+pub async fn subscribe_orderbook_stream(
+    app_handle: AppHandle,
+    symbol: String,
+    depth: Option<usize>,
+    interval_ms: Option<u64>,
+) -> Result<OrderBookSnapshotEvent, String> {
+    let depth = depth.unwrap_or(DEFAULT_DEPTH).max(1);
+    let interval_ms = interval_ms.unwrap_or(1000).max(MIN_INTERVAL_MS);
+
+    let already_streaming = {
+        let mut subs = ORDERBOOK_SUBS.write().await;
+        match subs.get_mut(&symbol) {
+            Some(sub) => {
+                sub.ref_count += 1;
+                sub.depth = sub.depth.max(depth);
+                true
+            }
+            None => false,
+        }
+    };
+    if already_streaming {
+        return snapshot_event(&symbol)
+            .await
+            .ok_or_else(|| "Order book stream closed".to_string());
+    }
+
+    let adapter = DriftAdapter::new();
+    let market_index = resolve_market(&adapter, &symbol).await?;
+    let initial = adapter.fetch_order_book(market_index).await?;
+
+    {
+        let mut subs = ORDERBOOK_SUBS.write().await;
+        if let Some(sub) = subs.get_mut(&symbol) {
+            // Another caller started the stream while we were fetching.
+            sub.ref_count += 1;
+        } else {
+            let mut book = L2Book::default();
+            let (bids, asks) = levels(&initial);
+            book.replace(bids.into_iter(), asks.into_iter(), depth);
+            subs.insert(
+                symbol.clone(),
+                OrderBookSubscription {
+                    market_index,
+                    depth,
+                    interval_ms,
+                    ref_count: 1,
+                    book,
+                },
+            );
+            spawn_stream(app_handle.clone(), adapter, symbol.clone());
+        }
+    }
+
+    let snapshot = snapshot_event(&symbol)
+        .await
+        .ok_or_else(|| "Order book stream closed".to_string())?;
+    let _ = app_handle.emit("orderbook_snapshot", &snapshot);
+    Ok(snapshot)
+}
+
+fn spawn_stream(app_handle: AppHandle, adapter: DriftAdapter, symbol: String) {
+    tokio::spawn(async move {
+        loop {
+            let params = {
+                let subs = ORDERBOOK_SUBS.read().await;
+                subs.get(&symbol)
+                    .map(|sub| (sub.market_index, sub.interval_ms))
+            };
+            let Some((market_index, interval_ms)) = params else {
+                break;
+            };
+
+            tokio::time::sleep(Duration::from_millis(interval_ms)).await;
+
+            let fetched = match adapter.fetch_order_book(market_index).await {
+                Ok(book) => book,
+                Err(e) => {
+                    eprintln!("Order book refresh for {} failed: {}", symbol, e);
+                    continue;
+                }
+            };
+            let (bids, asks) = levels(&fetched);
+
+            let diff = {
+                let mut subs = ORDERBOOK_SUBS.write().await;
+                let Some(sub) = subs.get_mut(&symbol) else {
+                    break;
+                };
+                let depth = sub.depth;
+                sub.book
+                    .replace(bids.into_iter(), asks.into_iter(), depth)
+                    .map(|(bids, asks)| OrderBookDiffEvent {
+                        symbol: symbol.clone(),
+                        sequence: sub.book.sequence(),
+                        timestamp: chrono::Utc::now().timestamp_millis(),
+                        bids,
+                        asks,
+                    })
+            };
+
+            if let Some(diff) = diff {
+                let _ = app_handle.emit("orderbook_update", &diff);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn unsubscribe_orderbook_stream(symbol: String) -> Result<(), String> {
+    let mut subs = ORDERBOOK_SUBS.write().await;
+    if let Some(sub) = subs.get_mut(&symbol) {
+        sub.ref_count = sub.ref_count.saturating_sub(1);
+        if sub.ref_count == 0 {
+            subs.remove(&symbol);
+        }
+    }
+    Ok(())
+}
+
+/// Current book for an active stream, used to resync after a missed diff.
+#[tauri::command]
+pub async fn get_orderbook_snapshot(symbol: String) -> Result<OrderBookSnapshotEvent, String> {
+    snapshot_event(&symbol)
+        .await
+        .ok_or_else(|| format!("No active order book stream for {}", symbol))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(price: f64, size: f64) -> BookLevel {
+        BookLevel { price, size }
+    }
+
+    #[test]
+    fn diffs_report_changed_and_removed_levels() {
+        let mut book = L2Book::default();
+        let first = book.replace(
+            vec![level(99.0, 5.0), level(98.5, 2.0)].into_iter(),
+            vec![level(100.0, 3.0)].into_iter(),
+            10,
+        );
+        assert!(first.is_some());
+        assert_eq!(book.sequence(), 1);
+
+        let (bids, asks) = book
+            .replace(
+                vec![level(99.0, 4.0)].into_iter(),
+                vec![level(100.0, 3.0)].into_iter(),
+                10,
+            )
+            .unwrap();
+        assert_eq!(bids.len(), 2);
+        assert!(bids.contains(&level(98.5, 0.0)));
+        assert!(bids.contains(&level(99.0, 4.0)));
+        assert!(asks.is_empty());
+
+        let unchanged = book.replace(
+            vec![level(99.0, 4.0)].into_iter(),
+            vec![level(100.0, 3.0)].into_iter(),
+            10,
+        );
+        assert!(unchanged.is_none());
+        assert_eq!(book.sequence(), 2);
+    }
+
+    #[test]
+    fn keeps_best_levels_and_aggregates_duplicates() {
+        let mut book = L2Book::default();
+        book.replace(
+            vec![level(97.0, 1.0), level(99.0, 1.0), level(98.0, 1.0)].into_iter(),
+            vec![
+                level(101.0, 1.0),
+                level(100.0, 1.0),
+                level(100.0, 2.0),
+                level(102.0, 1.0),
+            ]
+            .into_iter(),
+            2,
+        );
+        let bids: Vec<f64> = book.bids().iter().map(|l| l.price).collect();
+        assert_eq!(bids, vec![99.0, 98.0]);
+        let asks = book.asks();
+        assert_eq!(asks.len(), 2);
+        assert_eq!(asks[0], level(100.0, 3.0));
+        assert_eq!(asks[1].price, 101.0);
+    }
+}
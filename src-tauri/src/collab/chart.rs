@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use parking_lot::RwLock;
+use uuid::Uuid;
+
+use crate::collab::types::{ChartContext, ChartCursor, ShareChartRequest};
+
+const MAX_INDICATORS: usize = 32;
+const MAX_DRAWINGS: usize = 256;
+const MAX_POINTS_PER_DRAWING: usize = 16;
+/// Cursor moves from one user are relayed at most this often (~30 fps);
+/// moves in between only update the stored position.
+const CURSOR_RELAY_INTERVAL: Duration = Duration::from_millis(33);
+
+struct CursorSlot {
+    cursor: ChartCursor,
+    last_relayed: Option<Instant>,
+}
+
+/// Shared chart context and live cursor positions per room. Kept apart from
+/// `RoomManager` because cursor traffic is high-frequency and should not
+/// contend with the room locks.
+#[derive(Clone, Default)]
+pub struct ChartSessionManager {
+    contexts: Arc<RwLock<HashMap<Uuid, ChartContext>>>,
+    cursors: Arc<RwLock<HashMap<Uuid, HashMap<String, CursorSlot>>>>,
+}
+
+impl ChartSessionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the room's chart context. Switching token or timeframe
+    /// clears cursors, since their coordinates belonged to the old chart.
+    pub fn share_context(&self, request: ShareChartRequest, user_id: &str) -> Result<ChartContext> {
+        validate_chart(&request)?;
+        let token = request.token.trim().to_string();
+        let timeframe = request.timeframe.trim().to_string();
+
+        let mut contexts = self.contexts.write();
+        let previous = contexts.get(&request.room_id);
+        let current_version = previous.map(|c| c.version).unwrap_or(0);
+        if let Some(base_version) = request.base_version {
+            if base_version != current_version {
+                return Err(anyhow!(
+                    "Chart changed since version {} (now {})",
+                    base_version,
+                    current_version
+                ));
+            }
+        }
+        let chart_switched = previous.is_some_and(|c| c.token != token || c.timeframe != timeframe);
+
+        let context = ChartContext {
+            room_id: request.room_id,
+            token,
+            timeframe,
+            indicators: request.indicators,
+            drawings: request.drawings,
+            shared_by: user_id.to_string(),
+            version: current_version + 1,
+            updated_at: Utc::now(),
+        };
+        contexts.insert(request.room_id, context.clone());
+        drop(contexts);
+
+        if chart_switched {
+            self.cursors.write().remove(&request.room_id);
+        }
+        Ok(context)
+    }
+
+    pub fn get_context(&self, room_id: &Uuid) -> Option<ChartContext> {
+        self.contexts.read().get(room_id).cloned()
+    }
+
+    /// Stores the cursor and reports whether this move should be relayed
+    /// now or coalesced into a later one.
+    pub fn move_cursor(&self, room_id: Uuid, cursor: ChartCursor) -> bool {
+        let now = Instant::now();
+        let mut cursors = self.cursors.write();
+        let slot = cursors
+            .entry(room_id)
+            .or_default()
+            .entry(cursor.user_id.clone())
+            .or_insert_with(|| CursorSlot {
+                cursor: cursor.clone(),
+                last_relayed: None,
+            });
+        slot.cursor = cursor;
+
+        let due = slot
+            .last_relayed
+            .map_or(true, |at| now.duration_since(at) >= CURSOR_RELAY_INTERVAL);
+        if due {
+            slot.last_relayed = Some(now);
+        }
+        due
+    }
+
+    pub fn cursors(&self, room_id: &Uuid) -> Vec<ChartCursor> {
+        self.cursors
+            .read()
+            .get(room_id)
+            .map(|slots| slots.values().map(|slot| slot.cursor.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns whether the user had a cursor to clear.
+    pub fn clear_cursor(&self, room_id: &Uuid, user_id: &str) -> bool {
+        self.cursors
+            .write()
+            .get_mut(room_id)
+            .is_some_and(|slots| slots.remove(user_id).is_some())
+    }
+
+    pub fn clear_room(&self, room_id: &Uuid) {
+        self.contexts.write().remove(room_id);
+        self.cursors.write().remove(room_id);
+    }
+}
+
+fn validate_chart(request: &ShareChartRequest) -> Result<()> {
+    if request.token.trim().is_empty() {
+        anyhow::bail!("Chart token cannot be empty");
+    }
+    if request.timeframe.trim().is_empty() {
+        anyhow::bail!("Chart timeframe cannot be empty");
+    }
+    if request.indicators.len() > MAX_INDICATORS {
+        anyhow::bail!("Too many indicators (max 32)");
+    }
+    if request.drawings.len() > MAX_DRAWINGS {
+        anyhow::bail!("Too many drawings (max 256)");
+    }
+    for drawing in &request.drawings {
+        if drawing.points.is_empty() || drawing.points.len() > MAX_POINTS_PER_DRAWING {
+            anyhow::bail!("Drawing {} must have between 1 and 16 points", drawing.id);
+        }
+        if drawing.points.iter().any(|point| !point.price.is_finite()) {
+            anyhow::bail!("Drawing {} has a non-finite price", drawing.id);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collab::types::ChartPoint;
+
+    fn request(room_id: Uuid, token: &str, base_version: Option<u64>) -> ShareChartRequest {
+        ShareChartRequest {
+            room_id,
+            token: token.to_string(),
+            timeframe: "1h".to_string(),
+            indicators: Vec::new(),
+            drawings: Vec::new(),
+            base_version,
+        }
+    }
+
+    fn cursor(user_id: &str) -> ChartCursor {
+        ChartCursor {
+            user_id: user_id.to_string(),
+            username: user_id.to_string(),
+            position: ChartPoint {
+                time: 1_700_000_000,
+                price: 1.5,
+            },
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn stale_updates_are_rejected_and_switching_charts_clears_cursors() {
+        let charts = ChartSessionManager::new();
+        let room_id = Uuid::new_v4();
+
+        let first = charts
+            .share_context(request(room_id, "SOL", None), "alice")
+            .unwrap();
+        assert_eq!(first.version, 1);
+        charts.move_cursor(room_id, cursor("bob"));
+
+        assert!(charts
+            .share_context(request(room_id, "SOL", Some(0)), "bob")
+            .is_err());
+        charts
+            .share_context(request(room_id, "SOL", Some(1)), "bob")
+            .unwrap();
+        assert_eq!(charts.cursors(&room_id).len(), 1);
+
+        charts
+            .share_context(request(room_id, "BONK", None), "alice")
+            .unwrap();
+        assert!(charts.cursors(&room_id).is_empty());
+    }
+
+    #[test]
+    fn rapid_cursor_moves_are_coalesced() {
+        let charts = ChartSessionManager::new();
+        let room_id = Uuid::new_v4();
+
+        assert!(charts.move_cursor(room_id, cursor("bob")));
+        assert!(!charts.move_cursor(room_id, cursor("bob")));
+        assert!(charts.move_cursor(room_id, cursor("carol")));
+        assert_eq!(charts.cursors(&room_id).len(), 2);
+    }
+}
@@ -3,9 +3,8 @@ use chrono::Utc;
 use tauri::State;
 use uuid::Uuid;
 
-use crate::collab::crypto::RoomEncryption;
 use crate::collab::moderation::ModerationManager;
-use crate::collab::permissions::{can_modify_permissions, default_permissions_for_role};
+use crate::collab::permissions::role_rank;
 use crate::collab::state::CollabState;
 use crate::collab::types::*;
 
@@ -20,8 +19,7 @@ pub async fn collab_create_room(
         .create_room(request, user_id.clone())
         .map_err(|e| e.to_string())?;
 
-    let encryption_key = RoomEncryption::generate_key();
-    state.set_encryption_key(room.id, encryption_key);
+    state.init_room_key(room.id);
 
     state
         .websocket
@@ -68,6 +66,8 @@ pub async fn collab_delete_room(
 
     state.websocket.clean_room(&uuid);
     state.rtc.clear_room(&uuid);
+    state.forget_room_keys(&uuid);
+    state.charts.clear_room(&uuid);
 
     Ok(())
 }
@@ -78,6 +78,10 @@ pub async fn collab_join_room(
     user_id: String,
     state: State<'_, CollabState>,
 ) -> Result<Participant, String> {
+    if state.moderation.is_banned(&request.room_id, &user_id) {
+        return Err("You are banned from this room".to_string());
+    }
+
     let participant = state
         .rooms
         .join_room(request.clone(), user_id.clone())
@@ -95,6 +99,10 @@ pub async fn collab_join_room(
         )
         .map_err(|e| e.to_string())?;
 
+    state
+        .share_room_key(request.room_id, &participant)
+        .map_err(|e| e.to_string())?;
+
     Ok(participant)
 }
 
@@ -124,20 +132,29 @@ pub async fn collab_leave_room(
             uuid,
             CollabMessage::ParticipantLeft {
                 participant_id: participant.id,
-                user_id,
+                user_id: user_id.clone(),
             },
         )
         .map_err(|e| e.to_string())?;
 
+    clear_chart_cursor(&state, uuid, &user_id).map_err(|e| e.to_string())?;
+
+    state.rotate_room_key(uuid).map_err(|e| e.to_string())?;
+
     Ok(())
 }
 
 #[tauri::command]
 pub async fn collab_get_participants(
     room_id: String,
+    user_id: String,
     state: State<'_, CollabState>,
 ) -> Result<Vec<Participant>, String> {
     let uuid = Uuid::parse_str(&room_id).map_err(|e| e.to_string())?;
+    state
+        .rooms
+        .ensure_member(&uuid, &user_id)
+        .map_err(|e| e.to_string())?;
     Ok(state.rooms.get_participants(&uuid))
 }
 
@@ -149,18 +166,21 @@ pub async fn collab_update_permissions(
 ) -> Result<(), String> {
     let moderator = state
         .rooms
-        .get_participant(&request.room_id, &moderator_id)
+        .authorize(&request.room_id, &moderator_id, RoomCapability::AssignRoles)
         .map_err(|e| e.to_string())?;
 
-    if !can_modify_permissions(moderator.role) {
-        return Err("Insufficient permissions".to_string());
-    }
-
     let mut participant = state
         .rooms
         .get_participant(&request.room_id, &request.user_id)
         .map_err(|e| e.to_string())?;
 
+    if role_rank(moderator.role) <= role_rank(participant.role) {
+        return Err("Cannot change permissions of a user with equal or higher role".to_string());
+    }
+    if !request.permissions.is_subset_of(&moderator.permissions) {
+        return Err("Cannot grant permissions you do not hold".to_string());
+    }
+
     participant.permissions = request.permissions;
 
     state
@@ -186,9 +206,17 @@ pub async fn collab_send_message(
     username: String,
     state: State<'_, CollabState>,
 ) -> Result<ChatMessage, String> {
-    let message = state
+    let (ciphertext, epoch) = state
+        .seal_message(&request.room_id, &request.content)
+        .map_err(|e| e.to_string())?;
+    let sealed = SendMessageRequest {
+        content: ciphertext,
+        ..request.clone()
+    };
+
+    let mut message = state
         .rooms
-        .send_message(request.clone(), user_id, username)
+        .send_message(sealed, user_id, username, Some(epoch))
         .map_err(|e| e.to_string())?;
 
     state
@@ -201,17 +229,27 @@ pub async fn collab_send_message(
         )
         .map_err(|e| e.to_string())?;
 
+    message.content = request.content;
     Ok(message)
 }
 
 #[tauri::command]
 pub async fn collab_get_messages(
     room_id: String,
+    user_id: String,
     limit: Option<usize>,
     state: State<'_, CollabState>,
 ) -> Result<Vec<ChatMessage>, String> {
     let uuid = Uuid::parse_str(&room_id).map_err(|e| e.to_string())?;
-    Ok(state.rooms.get_messages(&uuid, limit))
+    state
+        .rooms
+        .ensure_member(&uuid, &user_id)
+        .map_err(|e| e.to_string())?;
+    let mut messages = state.rooms.get_messages(&uuid, limit);
+    for message in &mut messages {
+        state.open_message(message);
+    }
+    Ok(messages)
 }
 
 #[tauri::command]
@@ -223,6 +261,10 @@ pub async fn collab_share_watchlist(
     state: State<'_, CollabState>,
 ) -> Result<SharedWatchlist, String> {
     let uuid = Uuid::parse_str(&room_id).map_err(|e| e.to_string())?;
+    state
+        .rooms
+        .authorize(&uuid, &user_id, RoomCapability::ShareWatchlists)
+        .map_err(|e| e.to_string())?;
 
     let watchlist = SharedWatchlist {
         id: Uuid::new_v4(),
@@ -255,9 +297,14 @@ pub async fn collab_share_watchlist(
 #[tauri::command]
 pub async fn collab_get_watchlists(
     room_id: String,
+    user_id: String,
     state: State<'_, CollabState>,
 ) -> Result<Vec<SharedWatchlist>, String> {
     let uuid = Uuid::parse_str(&room_id).map_err(|e| e.to_string())?;
+    state
+        .rooms
+        .ensure_member(&uuid, &user_id)
+        .map_err(|e| e.to_string())?;
     Ok(state.rooms.get_watchlists(&uuid))
 }
 
@@ -268,6 +315,11 @@ pub async fn collab_share_order(
     username: String,
     state: State<'_, CollabState>,
 ) -> Result<SharedOrder, String> {
+    state
+        .rooms
+        .authorize(&request.room_id, &user_id, RoomCapability::ShareOrders)
+        .map_err(|e| e.to_string())?;
+
     let order = SharedOrder {
         id: Uuid::new_v4(),
         room_id: request.room_id,
@@ -304,9 +356,14 @@ pub async fn collab_share_order(
 #[tauri::command]
 pub async fn collab_get_orders(
     room_id: String,
+    user_id: String,
     state: State<'_, CollabState>,
 ) -> Result<Vec<SharedOrder>, String> {
     let uuid = Uuid::parse_str(&room_id).map_err(|e| e.to_string())?;
+    state
+        .rooms
+        .ensure_member(&uuid, &user_id)
+        .map_err(|e| e.to_string())?;
     Ok(state.rooms.get_orders(&uuid))
 }
 
@@ -315,13 +372,21 @@ pub async fn collab_update_order(
     order_id: String,
     room_id: String,
     status: OrderStatus,
+    user_id: String,
     state: State<'_, CollabState>,
 ) -> Result<(), String> {
     let order_uuid = Uuid::parse_str(&order_id).map_err(|e| e.to_string())?;
     let room_uuid = Uuid::parse_str(&room_id).map_err(|e| e.to_string())?;
+    let participant = state
+        .rooms
+        .ensure_member(&room_uuid, &user_id)
+        .map_err(|e| e.to_string())?;
 
     let mut orders = state.rooms.get_orders(&room_uuid);
     if let Some(order) = orders.iter_mut().find(|o| o.id == order_uuid) {
+        if order.user_id != user_id && !participant.permissions.can_moderate {
+            return Err("Only the order's author or a moderator can update it".to_string());
+        }
         order.status = status;
         state
             .rooms
@@ -351,6 +416,11 @@ pub async fn collab_share_strategy(
     username: String,
     state: State<'_, CollabState>,
 ) -> Result<Strategy, String> {
+    state
+        .rooms
+        .authorize(&request.room_id, &user_id, RoomCapability::ShareStrategies)
+        .map_err(|e| e.to_string())?;
+
     let strategy = Strategy {
         id: Uuid::new_v4(),
         room_id: request.room_id,
@@ -386,6 +456,19 @@ pub async fn collab_send_webrtc_signal(
 ) -> Result<(), String> {
     let uuid = Uuid::parse_str(&room_id).map_err(|e| e.to_string())?;
 
+    let sender = state
+        .rooms
+        .ensure_member(&uuid, &signal.from_user_id)
+        .map_err(|e| e.to_string())?;
+    let permissions = &sender.permissions;
+    if !(permissions.can_speak || permissions.can_share_video || permissions.can_share_screen) {
+        return Err("Missing room permission: speak, share video or share screen".to_string());
+    }
+    state
+        .rooms
+        .ensure_member(&uuid, &signal.to_user_id)
+        .map_err(|e| e.to_string())?;
+
     state.rtc.enqueue_signal(uuid, signal.clone());
 
     state
@@ -403,6 +486,10 @@ pub async fn collab_get_webrtc_signals(
     state: State<'_, CollabState>,
 ) -> Result<Vec<WebRTCSignal>, String> {
     let uuid = Uuid::parse_str(&room_id).map_err(|e| e.to_string())?;
+    state
+        .rooms
+        .ensure_member(&uuid, &user_id)
+        .map_err(|e| e.to_string())?;
     Ok(state.rtc.take_signals(&uuid, &user_id))
 }
 
@@ -440,13 +527,31 @@ pub async fn collab_moderate_user(
         .apply_moderation(
             request.room_id,
             moderator_id,
-            request.target_user_id,
+            request.target_user_id.clone(),
             request.action_type,
             request.reason,
             duration,
         )
         .map_err(|e| e.to_string())?;
 
+    if matches!(
+        request.action_type,
+        ModerationActionType::Kick | ModerationActionType::Ban
+    ) {
+        state
+            .rooms
+            .leave_room(&request.room_id, &request.target_user_id)
+            .map_err(|e| e.to_string())?;
+        state
+            .websocket
+            .unsubscribe_from_room(&request.room_id, &request.target_user_id);
+        state
+            .rotate_room_key(request.room_id)
+            .map_err(|e| e.to_string())?;
+        clear_chart_cursor(&state, request.room_id, &request.target_user_id)
+            .map_err(|e| e.to_string())?;
+    }
+
     state
         .websocket
         .broadcast(
@@ -463,17 +568,32 @@ pub async fn collab_moderate_user(
 #[tauri::command]
 pub async fn collab_get_room_state(
     room_id: String,
+    user_id: String,
     state: State<'_, CollabState>,
 ) -> Result<RoomState, String> {
     let uuid = Uuid::parse_str(&room_id).map_err(|e| e.to_string())?;
+    state
+        .rooms
+        .ensure_member(&uuid, &user_id)
+        .map_err(|e| e.to_string())?;
     state.get_room_state(&uuid).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn collab_set_competition(
     competition: Competition,
+    user_id: String,
     state: State<'_, CollabState>,
 ) -> Result<(), String> {
+    state
+        .rooms
+        .authorize(
+            &competition.room_id,
+            &user_id,
+            RoomCapability::StartCompetitions,
+        )
+        .map_err(|e| e.to_string())?;
+
     state
         .rooms
         .set_competition(competition.clone())
@@ -493,9 +613,14 @@ pub async fn collab_set_competition(
 #[tauri::command]
 pub async fn collab_get_competition(
     room_id: String,
+    user_id: String,
     state: State<'_, CollabState>,
 ) -> Result<Option<Competition>, String> {
     let uuid = Uuid::parse_str(&room_id).map_err(|e| e.to_string())?;
+    state
+        .rooms
+        .ensure_member(&uuid, &user_id)
+        .map_err(|e| e.to_string())?;
     Ok(state.rooms.get_competition(&uuid))
 }
 
@@ -503,9 +628,14 @@ pub async fn collab_get_competition(
 pub async fn collab_update_leaderboard(
     room_id: String,
     leaderboard: Vec<LeaderboardEntry>,
+    user_id: String,
     state: State<'_, CollabState>,
 ) -> Result<(), String> {
     let uuid = Uuid::parse_str(&room_id).map_err(|e| e.to_string())?;
+    state
+        .rooms
+        .authorize(&uuid, &user_id, RoomCapability::StartCompetitions)
+        .map_err(|e| e.to_string())?;
 
     state
         .websocket
@@ -520,3 +650,190 @@ pub async fn collab_update_leaderboard(
 
     Ok(())
 }
+
+#[tauri::command]
+pub async fn collab_assign_role(
+    request: AssignRoleRequest,
+    assigner_id: String,
+    state: State<'_, CollabState>,
+) -> Result<Participant, String> {
+    let participant = state
+        .rooms
+        .assign_role(
+            &request.room_id,
+            &assigner_id,
+            &request.user_id,
+            request.role,
+        )
+        .map_err(|e| e.to_string())?;
+
+    state
+        .websocket
+        .broadcast(
+            request.room_id,
+            CollabMessage::ParticipantUpdated {
+                participant: participant.clone(),
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(participant)
+}
+
+#[tauri::command]
+pub async fn collab_update_role_grants(
+    request: UpdateRoleGrantsRequest,
+    user_id: String,
+    state: State<'_, CollabState>,
+) -> Result<Room, String> {
+    let (room, updated) = state
+        .rooms
+        .set_role_grants(
+            &request.room_id,
+            &user_id,
+            request.role,
+            request.permissions,
+        )
+        .map_err(|e| e.to_string())?;
+
+    state
+        .websocket
+        .broadcast(
+            request.room_id,
+            CollabMessage::RoomUpdated { room: room.clone() },
+        )
+        .map_err(|e| e.to_string())?;
+    for participant in updated {
+        state
+            .websocket
+            .broadcast(
+                request.room_id,
+                CollabMessage::ParticipantUpdated { participant },
+            )
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(room)
+}
+
+#[tauri::command]
+pub async fn collab_get_identity_key(state: State<'_, CollabState>) -> Result<String, String> {
+    Ok(state.local_public_key())
+}
+
+#[tauri::command]
+pub async fn collab_get_key_envelopes(
+    room_id: String,
+    user_id: String,
+    state: State<'_, CollabState>,
+) -> Result<Vec<RoomKeyEnvelope>, String> {
+    let uuid = Uuid::parse_str(&room_id).map_err(|e| e.to_string())?;
+    state
+        .rooms
+        .ensure_member(&uuid, &user_id)
+        .map_err(|e| e.to_string())?;
+    Ok(state.key_envelopes_for(&uuid, &user_id))
+}
+
+#[tauri::command]
+pub async fn collab_accept_room_key(
+    envelope: RoomKeyEnvelope,
+    state: State<'_, CollabState>,
+) -> Result<(), String> {
+    state.accept_room_key(&envelope).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn collab_share_chart(
+    request: ShareChartRequest,
+    user_id: String,
+    state: State<'_, CollabState>,
+) -> Result<ChartContext, String> {
+    state
+        .rooms
+        .authorize(&request.room_id, &user_id, RoomCapability::ShareCharts)
+        .map_err(|e| e.to_string())?;
+
+    let room_id = request.room_id;
+    let context = state
+        .charts
+        .share_context(request, &user_id)
+        .map_err(|e| e.to_string())?;
+
+    state
+        .websocket
+        .broadcast(
+            room_id,
+            CollabMessage::ChartContextUpdated {
+                context: context.clone(),
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(context)
+}
+
+#[tauri::command]
+pub async fn collab_move_chart_cursor(
+    room_id: String,
+    user_id: String,
+    position: ChartPoint,
+    state: State<'_, CollabState>,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&room_id).map_err(|e| e.to_string())?;
+    let participant = state
+        .rooms
+        .ensure_member(&uuid, &user_id)
+        .map_err(|e| e.to_string())?;
+    if state.charts.get_context(&uuid).is_none() {
+        return Err("No chart is being shared in this room".to_string());
+    }
+
+    let cursor = ChartCursor {
+        user_id,
+        username: participant.username,
+        position,
+        updated_at: Utc::now(),
+    };
+    if state.charts.move_cursor(uuid, cursor.clone()) {
+        state
+            .websocket
+            .broadcast(
+                uuid,
+                CollabMessage::ChartCursorMoved {
+                    room_id: uuid,
+                    cursor,
+                },
+            )
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn collab_clear_chart_cursor(
+    room_id: String,
+    user_id: String,
+    state: State<'_, CollabState>,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&room_id).map_err(|e| e.to_string())?;
+    state
+        .rooms
+        .ensure_member(&uuid, &user_id)
+        .map_err(|e| e.to_string())?;
+    clear_chart_cursor(&state, uuid, &user_id).map_err(|e| e.to_string())
+}
+
+fn clear_chart_cursor(state: &CollabState, room_id: Uuid, user_id: &str) -> Result<()> {
+    if state.charts.clear_cursor(&room_id, user_id) {
+        state.websocket.broadcast(
+            room_id,
+            CollabMessage::ChartCursorCleared {
+                room_id,
+                user_id: user_id.to_string(),
+            },
+        )?;
+    }
+    Ok(())
+}
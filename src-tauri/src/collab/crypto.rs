@@ -1,12 +1,18 @@
 use aes_gcm::{
-    aead::{Aead, KeyInit},
+    aead::{Aead, KeyInit, Payload},
     Aes256Gcm, Nonce,
 };
 use anyhow::{Context, Result};
 use base64::{engine::general_purpose, Engine as _};
 use rand_core::{OsRng, RngCore};
+use uuid::Uuid;
+
+use crate::security::key_agreement::{hkdf_sha256, x25519_public, x25519_shared};
+use crate::security::keystore::{Keystore, KeystoreError};
 
 const NONCE_SIZE: usize = 12;
+const IDENTITY_SECRET_KEY: &str = "collab-identity-secret";
+const KEY_WRAP_INFO: &[u8] = b"eclipse-collab-room-key-v1";
 
 pub struct RoomEncryption {
     cipher: Aes256Gcm,
@@ -61,6 +67,179 @@ impl RoomEncryption {
     }
 }
 
+/// This device's long-lived X25519 key. Other participants wrap room keys
+/// to its public half; the secret never leaves the process.
+pub struct IdentityKeyPair {
+    secret: [u8; 32],
+    public: [u8; 32],
+}
+
+impl IdentityKeyPair {
+    pub fn generate() -> Self {
+        let mut secret = [0u8; 32];
+        OsRng.fill_bytes(&mut secret);
+        Self::from_secret(secret)
+    }
+
+    /// Loads the device key from the keystore, creating and saving one on
+    /// first use. Envelopes wrapped to this device stay openable across
+    /// restarts.
+    pub fn load_or_create(keystore: &Keystore) -> Result<Self> {
+        match keystore.retrieve_secret(IDENTITY_SECRET_KEY) {
+            Ok(bytes) => {
+                let secret: [u8; 32] = bytes
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("Stored collab identity key is corrupt"))?;
+                Ok(Self::from_secret(secret))
+            }
+            Err(KeystoreError::NotFound) => {
+                let identity = Self::generate();
+                keystore
+                    .store_secret(IDENTITY_SECRET_KEY, &identity.secret)
+                    .context("Failed to save collab identity key")?;
+                Ok(identity)
+            }
+            Err(e) => Err(e).context("Failed to load collab identity key"),
+        }
+    }
+
+    fn from_secret(secret: [u8; 32]) -> Self {
+        let public = x25519_public(&secret);
+        Self { secret, public }
+    }
+
+    pub fn public_key(&self) -> [u8; 32] {
+        self.public
+    }
+
+    pub fn public_key_base64(&self) -> String {
+        general_purpose::STANDARD.encode(self.public)
+    }
+}
+
+/// A room key sealed to one recipient: an ephemeral X25519 agreement feeds
+/// HKDF, and the derived key encrypts the room key with AES-GCM. The room id
+/// and epoch are bound as associated data so an envelope cannot be replayed
+/// into another room or epoch.
+#[derive(Debug, Clone)]
+pub struct WrappedRoomKey {
+    pub ephemeral_public: String,
+    pub ciphertext: String,
+}
+
+pub fn decode_public_key(encoded: &str) -> Result<[u8; 32]> {
+    let bytes = general_purpose::STANDARD
+        .decode(encoded.trim())
+        .context("Public key is not valid base64")?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Public key must be 32 bytes"))
+}
+
+pub fn wrap_room_key(
+    room_key: &[u8; 32],
+    recipient_public: &[u8; 32],
+    room_id: Uuid,
+    epoch: u32,
+) -> Result<WrappedRoomKey> {
+    let mut ephemeral_secret = [0u8; 32];
+    OsRng.fill_bytes(&mut ephemeral_secret);
+    let ephemeral_public = x25519_public(&ephemeral_secret);
+
+    let wrap_key = derive_wrap_key(
+        &ephemeral_secret,
+        recipient_public,
+        &ephemeral_public,
+        recipient_public,
+    )?;
+    let cipher = Aes256Gcm::new((&wrap_key).into());
+
+    let mut nonce_bytes = [0u8; NONCE_SIZE];
+    OsRng.fill_bytes(&mut nonce_bytes);
+    let aad = envelope_aad(room_id, epoch);
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce_bytes),
+            Payload {
+                msg: room_key,
+                aad: &aad,
+            },
+        )
+        .map_err(|e| anyhow::anyhow!("Key wrap failed: {}", e))?;
+
+    let mut sealed = nonce_bytes.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+
+    Ok(WrappedRoomKey {
+        ephemeral_public: general_purpose::STANDARD.encode(ephemeral_public),
+        ciphertext: general_purpose::STANDARD.encode(sealed),
+    })
+}
+
+pub fn unwrap_room_key(
+    identity: &IdentityKeyPair,
+    wrapped: &WrappedRoomKey,
+    room_id: Uuid,
+    epoch: u32,
+) -> Result<[u8; 32]> {
+    let ephemeral_public = decode_public_key(&wrapped.ephemeral_public)?;
+    let wrap_key = derive_wrap_key(
+        &identity.secret,
+        &ephemeral_public,
+        &ephemeral_public,
+        &identity.public,
+    )?;
+    let cipher = Aes256Gcm::new((&wrap_key).into());
+
+    let data = general_purpose::STANDARD
+        .decode(&wrapped.ciphertext)
+        .context("Base64 decode failed")?;
+    if data.len() < NONCE_SIZE {
+        anyhow::bail!("Invalid wrapped key: too short");
+    }
+    let (nonce_bytes, sealed) = data.split_at(NONCE_SIZE);
+    let aad = envelope_aad(room_id, epoch);
+    let room_key = cipher
+        .decrypt(
+            Nonce::from_slice(nonce_bytes),
+            Payload {
+                msg: sealed,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| anyhow::anyhow!("Room key was not sealed for this device"))?;
+
+    room_key
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Unwrapped room key has the wrong length"))
+}
+
+fn derive_wrap_key(
+    secret: &[u8; 32],
+    peer_public: &[u8; 32],
+    ephemeral_public: &[u8; 32],
+    recipient_public: &[u8; 32],
+) -> Result<[u8; 32]> {
+    let shared = x25519_shared(secret, peer_public);
+    if shared == [0u8; 32] {
+        anyhow::bail!("Refusing low-order public key");
+    }
+
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(ephemeral_public);
+    salt[32..].copy_from_slice(recipient_public);
+
+    Ok(hkdf_sha256(Some(&salt), &shared, KEY_WRAP_INFO))
+}
+
+fn envelope_aad(room_id: Uuid, epoch: u32) -> [u8; 20] {
+    let mut aad = [0u8; 20];
+    aad[..16].copy_from_slice(room_id.as_bytes());
+    aad[16..].copy_from_slice(&epoch.to_be_bytes());
+    aad
+}
+
 pub fn hash_password(password: &str) -> Result<String> {
     use argon2::{
         password_hash::{rand_core::OsRng, PasswordHasher, SaltString},
@@ -106,6 +285,38 @@ mod tests {
         assert_eq!(plaintext, decrypted);
     }
 
+    #[test]
+    fn test_room_key_wrapping() {
+        let recipient = IdentityKeyPair::generate();
+        let outsider = IdentityKeyPair::generate();
+        let room_id = Uuid::new_v4();
+        let room_key = RoomEncryption::generate_key();
+
+        let wrapped = wrap_room_key(&room_key, &recipient.public_key(), room_id, 2).unwrap();
+        assert_eq!(
+            unwrap_room_key(&recipient, &wrapped, room_id, 2).unwrap(),
+            room_key
+        );
+        assert!(unwrap_room_key(&outsider, &wrapped, room_id, 2).is_err());
+        assert!(unwrap_room_key(&recipient, &wrapped, room_id, 3).is_err());
+        assert!(unwrap_room_key(&recipient, &wrapped, Uuid::new_v4(), 2).is_err());
+    }
+
+    #[test]
+    fn test_restored_identity_opens_earlier_envelopes() {
+        let original = IdentityKeyPair::generate();
+        let restored = IdentityKeyPair::from_secret(original.secret);
+        let room_id = Uuid::new_v4();
+        let room_key = RoomEncryption::generate_key();
+
+        let wrapped = wrap_room_key(&room_key, &original.public_key(), room_id, 1).unwrap();
+        assert_eq!(restored.public_key(), original.public_key());
+        assert_eq!(
+            unwrap_room_key(&restored, &wrapped, room_id, 1).unwrap(),
+            room_key
+        );
+    }
+
     #[test]
     fn test_password_hashing() {
         let password = "test_password_123";
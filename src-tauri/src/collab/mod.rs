@@ -1,3 +1,4 @@
+pub mod chart;
 pub mod commands;
 pub mod crypto;
 pub mod moderation;
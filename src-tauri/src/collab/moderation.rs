@@ -6,6 +6,7 @@ use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
 use uuid::Uuid;
 
+use crate::collab::permissions::role_rank;
 use crate::collab::types::{
     ModerationAction, ModerationActionType, ParticipantPermissions, ParticipantRole,
 };
@@ -112,8 +113,11 @@ pub fn ensure_moderation_permission(
             if !permissions.can_kick {
                 return Err(anyhow!("Moderator cannot remove participants"));
             }
-            if moderator_role == target_role {
-                return Err(anyhow!("Cannot moderate user with equal role"));
+            if action == ModerationActionType::Ban && !permissions.can_ban {
+                return Err(anyhow!("Moderator cannot ban participants"));
+            }
+            if role_rank(moderator_role) <= role_rank(target_role) {
+                return Err(anyhow!("Cannot moderate user with equal or higher role"));
             }
         }
         ModerationActionType::Warning => {
@@ -1,4 +1,6 @@
-use crate::collab::types::{ParticipantPermissions, ParticipantRole};
+use anyhow::{anyhow, Result};
+
+use crate::collab::types::{ParticipantPermissions, ParticipantRole, Room, RoomCapability};
 
 pub fn default_permissions_for_role(role: ParticipantRole) -> ParticipantPermissions {
     match role {
@@ -12,6 +14,10 @@ pub fn default_permissions_for_role(role: ParticipantRole) -> ParticipantPermiss
             can_moderate: true,
             can_kick: true,
             can_ban: true,
+            can_share_watchlists: true,
+            can_start_competitions: true,
+            can_assign_roles: true,
+            can_share_charts: true,
         },
         ParticipantRole::Moderator => ParticipantPermissions {
             can_speak: true,
@@ -23,8 +29,12 @@ pub fn default_permissions_for_role(role: ParticipantRole) -> ParticipantPermiss
             can_moderate: true,
             can_kick: true,
             can_ban: false,
+            can_share_watchlists: true,
+            can_start_competitions: true,
+            can_assign_roles: true,
+            can_share_charts: true,
         },
-        ParticipantRole::Member => ParticipantPermissions {
+        ParticipantRole::Trader => ParticipantPermissions {
             can_speak: true,
             can_share_video: true,
             can_share_screen: true,
@@ -34,8 +44,12 @@ pub fn default_permissions_for_role(role: ParticipantRole) -> ParticipantPermiss
             can_moderate: false,
             can_kick: false,
             can_ban: false,
+            can_share_watchlists: true,
+            can_start_competitions: false,
+            can_assign_roles: false,
+            can_share_charts: true,
         },
-        ParticipantRole::Guest => ParticipantPermissions {
+        ParticipantRole::Viewer => ParticipantPermissions {
             can_speak: false,
             can_share_video: false,
             can_share_screen: false,
@@ -45,10 +59,167 @@ pub fn default_permissions_for_role(role: ParticipantRole) -> ParticipantPermiss
             can_moderate: false,
             can_kick: false,
             can_ban: false,
+            can_share_watchlists: false,
+            can_start_competitions: false,
+            can_assign_roles: false,
+            can_share_charts: false,
         },
     }
 }
 
-pub fn can_modify_permissions(role: ParticipantRole) -> bool {
-    matches!(role, ParticipantRole::Owner | ParticipantRole::Moderator)
+/// Higher roles outrank lower ones for moderation and role changes.
+pub fn role_rank(role: ParticipantRole) -> u8 {
+    match role {
+        ParticipantRole::Owner => 3,
+        ParticipantRole::Moderator => 2,
+        ParticipantRole::Trader => 1,
+        ParticipantRole::Viewer => 0,
+    }
+}
+
+pub fn capability_name(capability: RoomCapability) -> &'static str {
+    match capability {
+        RoomCapability::Chat => "chat",
+        RoomCapability::Speak => "speak",
+        RoomCapability::ShareVideo => "share video",
+        RoomCapability::ShareScreen => "share screen",
+        RoomCapability::ShareWatchlists => "share watchlists",
+        RoomCapability::ShareOrders => "share orders",
+        RoomCapability::ShareStrategies => "share strategies",
+        RoomCapability::StartCompetitions => "start competitions",
+        RoomCapability::Moderate => "moderate",
+        RoomCapability::Kick => "kick",
+        RoomCapability::Ban => "ban",
+        RoomCapability::AssignRoles => "assign roles",
+        RoomCapability::ShareCharts => "share charts",
+    }
+}
+
+impl ParticipantPermissions {
+    pub fn allows(&self, capability: RoomCapability) -> bool {
+        match capability {
+            RoomCapability::Chat => self.can_chat,
+            RoomCapability::Speak => self.can_speak,
+            RoomCapability::ShareVideo => self.can_share_video,
+            RoomCapability::ShareScreen => self.can_share_screen,
+            RoomCapability::ShareWatchlists => self.can_share_watchlists,
+            RoomCapability::ShareOrders => self.can_share_orders,
+            RoomCapability::ShareStrategies => self.can_share_strategies,
+            RoomCapability::StartCompetitions => self.can_start_competitions,
+            RoomCapability::Moderate => self.can_moderate,
+            RoomCapability::Kick => self.can_kick,
+            RoomCapability::Ban => self.can_ban,
+            RoomCapability::AssignRoles => self.can_assign_roles,
+            RoomCapability::ShareCharts => self.can_share_charts,
+        }
+    }
+
+    /// True when every grant in `self` is also held by `other`.
+    pub fn is_subset_of(&self, other: &ParticipantPermissions) -> bool {
+        const ALL: [RoomCapability; 13] = [
+            RoomCapability::Chat,
+            RoomCapability::Speak,
+            RoomCapability::ShareVideo,
+            RoomCapability::ShareScreen,
+            RoomCapability::ShareWatchlists,
+            RoomCapability::ShareOrders,
+            RoomCapability::ShareStrategies,
+            RoomCapability::StartCompetitions,
+            RoomCapability::Moderate,
+            RoomCapability::Kick,
+            RoomCapability::Ban,
+            RoomCapability::AssignRoles,
+            RoomCapability::ShareCharts,
+        ];
+        ALL.iter()
+            .all(|capability| !self.allows(*capability) || other.allows(*capability))
+    }
+}
+
+/// The role's grants in this room, narrowed by what the room's settings
+/// allow at all.
+pub fn effective_permissions(room: &Room, role: ParticipantRole) -> ParticipantPermissions {
+    let mut permissions = room
+        .role_grants
+        .get(&role)
+        .cloned()
+        .unwrap_or_else(|| default_permissions_for_role(role));
+
+    let settings = &room.settings;
+    permissions.can_speak &= settings.allow_voice_chat;
+    permissions.can_share_video &= settings.allow_video_chat;
+    permissions.can_share_screen &= settings.allow_screen_share;
+    permissions.can_share_orders &= settings.allow_order_sharing;
+    permissions.can_share_strategies &= settings.allow_strategy_sharing;
+    if !settings.moderation_enabled && role != ParticipantRole::Owner {
+        permissions.can_moderate = false;
+        permissions.can_kick = false;
+        permissions.can_ban = false;
+    }
+    permissions
+}
+
+/// Role changes only flow downhill: the actor must outrank both the target's
+/// current role and the role being handed out. Ownership is not transferable
+/// this way.
+pub fn ensure_can_assign_role(
+    actor_role: ParticipantRole,
+    actor_permissions: &ParticipantPermissions,
+    target_role: ParticipantRole,
+    new_role: ParticipantRole,
+) -> Result<()> {
+    if !actor_permissions.can_assign_roles {
+        return Err(anyhow!("Insufficient permissions to assign roles"));
+    }
+    if new_role == ParticipantRole::Owner || target_role == ParticipantRole::Owner {
+        return Err(anyhow!("The owner role cannot be assigned or changed"));
+    }
+    if role_rank(actor_role) <= role_rank(target_role)
+        || role_rank(actor_role) <= role_rank(new_role)
+    {
+        return Err(anyhow!("Cannot assign a role at or above your own"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moderators_can_only_assign_below_themselves() {
+        let moderator = default_permissions_for_role(ParticipantRole::Moderator);
+        assert!(ensure_can_assign_role(
+            ParticipantRole::Moderator,
+            &moderator,
+            ParticipantRole::Viewer,
+            ParticipantRole::Trader,
+        )
+        .is_ok());
+        assert!(ensure_can_assign_role(
+            ParticipantRole::Moderator,
+            &moderator,
+            ParticipantRole::Trader,
+            ParticipantRole::Moderator,
+        )
+        .is_err());
+
+        let trader = default_permissions_for_role(ParticipantRole::Trader);
+        assert!(ensure_can_assign_role(
+            ParticipantRole::Trader,
+            &trader,
+            ParticipantRole::Viewer,
+            ParticipantRole::Viewer,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn subset_compares_every_capability() {
+        let viewer = default_permissions_for_role(ParticipantRole::Viewer);
+        let owner = default_permissions_for_role(ParticipantRole::Owner);
+        assert!(viewer.is_subset_of(&owner));
+        assert!(!owner.is_subset_of(&viewer));
+        assert!(!viewer.allows(RoomCapability::ShareOrders));
+    }
 }
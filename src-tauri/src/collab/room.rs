@@ -6,11 +6,12 @@ use chrono::Utc;
 use parking_lot::RwLock;
 use uuid::Uuid;
 
-use crate::collab::crypto::{hash_password, verify_password};
-use crate::collab::permissions::default_permissions_for_role;
+use crate::collab::crypto::{decode_public_key, hash_password, verify_password};
+use crate::collab::permissions::{capability_name, effective_permissions, ensure_can_assign_role};
 use crate::collab::types::{
-    ChatMessage, Competition, CreateRoomRequest, JoinRoomRequest, Participant, ParticipantRole,
-    ParticipantStatus, Room, RoomState, SendMessageRequest, SharedOrder, SharedWatchlist,
+    ChatMessage, Competition, CreateRoomRequest, JoinRoomRequest, Participant,
+    ParticipantPermissions, ParticipantRole, ParticipantStatus, Room, RoomCapability, RoomState,
+    SendMessageRequest, SharedOrder, SharedWatchlist,
 };
 
 #[derive(Clone)]
@@ -58,6 +59,8 @@ impl RoomManager {
             video_enabled: request.settings.allow_video_chat,
             screen_share_enabled: request.settings.allow_screen_share,
             settings: request.settings,
+            role_assignments: HashMap::from([(owner_id.clone(), ParticipantRole::Owner)]),
+            role_grants: HashMap::new(),
         };
 
         self.rooms.write().insert(room_id, room.clone());
@@ -115,6 +118,14 @@ impl RoomManager {
             }
         }
 
+        if room.encryption_enabled {
+            let public_key = request
+                .public_key
+                .as_deref()
+                .ok_or_else(|| anyhow!("A public key is required to join an encrypted room"))?;
+            decode_public_key(public_key)?;
+        }
+
         let participants = self.participants.read();
         let current_participants = participants
             .get(&request.room_id)
@@ -127,12 +138,23 @@ impl RoomManager {
 
         drop(participants);
 
+        if self
+            .participants
+            .read()
+            .get(&request.room_id)
+            .is_some_and(|p| p.iter().any(|p| p.user_id == user_id))
+        {
+            return Err(anyhow!("Already in this room"));
+        }
+
         let role = if user_id == room.owner_id {
             ParticipantRole::Owner
+        } else if let Some(role) = room.role_assignments.get(&user_id) {
+            *role
         } else if room.settings.allow_guest_join {
-            ParticipantRole::Guest
+            ParticipantRole::Viewer
         } else {
-            ParticipantRole::Member
+            ParticipantRole::Trader
         };
 
         let participant = Participant {
@@ -143,11 +165,12 @@ impl RoomManager {
             joined_at: Utc::now(),
             last_active: Utc::now(),
             role,
-            permissions: default_permissions_for_role(role),
+            permissions: effective_permissions(&room, role),
             status: ParticipantStatus::Active,
             is_muted: false,
             is_video_off: false,
             is_screen_sharing: false,
+            public_key: request.public_key,
         };
 
         self.participants
@@ -185,6 +208,101 @@ impl RoomManager {
             .ok_or_else(|| anyhow!("Participant not found"))
     }
 
+    /// Looks up the participant and checks they hold `capability`.
+    pub fn authorize(
+        &self,
+        room_id: &Uuid,
+        user_id: &str,
+        capability: RoomCapability,
+    ) -> Result<Participant> {
+        let participant = self
+            .get_participant(room_id, user_id)
+            .context("Only room participants can do this")?;
+        if !participant.permissions.allows(capability) {
+            return Err(anyhow!(
+                "Missing room permission: {}",
+                capability_name(capability)
+            ));
+        }
+        Ok(participant)
+    }
+
+    /// Gate for read access to room content.
+    pub fn ensure_member(&self, room_id: &Uuid, user_id: &str) -> Result<Participant> {
+        self.get_participant(room_id, user_id)
+            .context("Only room participants can view this")
+    }
+
+    /// Changes a participant's role and resets their grants to that role's.
+    /// The assignment is recorded on the room so it applies on rejoin.
+    pub fn assign_role(
+        &self,
+        room_id: &Uuid,
+        actor_id: &str,
+        target_user_id: &str,
+        role: ParticipantRole,
+    ) -> Result<Participant> {
+        if actor_id == target_user_id {
+            return Err(anyhow!("Cannot change your own role"));
+        }
+        let actor = self.get_participant(room_id, actor_id)?;
+        let mut target = self.get_participant(room_id, target_user_id)?;
+        ensure_can_assign_role(actor.role, &actor.permissions, target.role, role)?;
+
+        let room = {
+            let mut rooms = self.rooms.write();
+            let room = rooms
+                .get_mut(room_id)
+                .ok_or_else(|| anyhow!("Room not found"))?;
+            room.role_assignments
+                .insert(target_user_id.to_string(), role);
+            room.updated_at = Utc::now();
+            room.clone()
+        };
+
+        target.role = role;
+        target.permissions = effective_permissions(&room, role);
+        self.update_participant(target.clone())?;
+        Ok(target)
+    }
+
+    /// Replaces a role's grants for this room and reapplies them to everyone
+    /// holding the role. Only the owner may do this.
+    pub fn set_role_grants(
+        &self,
+        room_id: &Uuid,
+        actor_id: &str,
+        role: ParticipantRole,
+        permissions: ParticipantPermissions,
+    ) -> Result<(Room, Vec<Participant>)> {
+        let room = self.get_room(room_id)?;
+        if room.owner_id != actor_id {
+            return Err(anyhow!("Only the room owner can change role grants"));
+        }
+        if role == ParticipantRole::Owner {
+            return Err(anyhow!("Owner grants cannot be changed"));
+        }
+
+        let room = {
+            let mut rooms = self.rooms.write();
+            let room = rooms
+                .get_mut(room_id)
+                .ok_or_else(|| anyhow!("Room not found"))?;
+            room.role_grants.insert(role, permissions);
+            room.updated_at = Utc::now();
+            room.clone()
+        };
+
+        let mut updated = Vec::new();
+        if let Some(participants) = self.participants.write().get_mut(room_id) {
+            for participant in participants.iter_mut().filter(|p| p.role == role) {
+                participant.permissions = effective_permissions(&room, role);
+                updated.push(participant.clone());
+            }
+        }
+        Ok((room, updated))
+    }
+
     pub fn update_participant(&self, participant: Participant) -> Result<()> {
         let mut participants = self.participants.write();
         if let Some(room_participants) = participants.get_mut(&participant.room_id) {
@@ -202,13 +320,19 @@ impl RoomManager {
         }
     }
 
+    /// Stores a message as given. For encrypted rooms `request.content` must
+    /// already be ciphertext sealed under `key_epoch`.
     pub fn send_message(
         &self,
         request: SendMessageRequest,
         user_id: String,
         username: String,
+        key_epoch: Option<u32>,
     ) -> Result<ChatMessage> {
         let room = self.get_room(&request.room_id)?;
+        if room.encryption_enabled && key_epoch.is_none() {
+            return Err(anyhow!("Encrypted rooms do not accept plaintext messages"));
+        }
         let participant = self.get_participant(&request.room_id, &user_id)?;
 
         if !participant.permissions.can_chat {
@@ -222,9 +346,10 @@ impl RoomManager {
             username,
             content: request.content,
             timestamp: Utc::now(),
-            encrypted: room.encryption_enabled,
+            encrypted: key_epoch.is_some(),
             mentions: Vec::new(),
             replied_to: request.replied_to,
+            key_epoch,
         };
 
         self.chat_messages
@@ -315,6 +440,8 @@ impl RoomManager {
             watchlists,
             active_orders,
             competition,
+            chart: None,
+            chart_cursors: Vec::new(),
         })
     }
 }
@@ -1,57 +1,260 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
 use parking_lot::RwLock;
 use tauri::State;
 use uuid::Uuid;
 
-use crate::collab::crypto::RoomEncryption;
+use crate::collab::chart::ChartSessionManager;
+use crate::collab::crypto::{
+    decode_public_key, unwrap_room_key, wrap_room_key, IdentityKeyPair, RoomEncryption,
+    WrappedRoomKey,
+};
 use crate::collab::moderation::ModerationManager;
 use crate::collab::room::RoomManager;
 use crate::collab::rtc::RtcSessionManager;
-use crate::collab::types::{CollabMessage, RoomState};
+use crate::collab::types::{ChatMessage, CollabMessage, Participant, RoomKeyEnvelope, RoomState};
 use crate::collab::websocket::CollabWebSocketManager;
 
+/// Room keys this device holds, by epoch. Older epochs are kept so history
+/// sealed before a rotation stays readable to members who were present.
+#[derive(Default)]
+struct RoomKeyring {
+    current_epoch: u32,
+    keys: HashMap<u32, [u8; 32]>,
+}
+
+impl RoomKeyring {
+    fn current(&self) -> Option<(u32, [u8; 32])> {
+        self.keys
+            .get(&self.current_epoch)
+            .map(|key| (self.current_epoch, *key))
+    }
+}
+
 #[derive(Clone)]
 pub struct CollabState {
     pub rooms: Arc<RoomManager>,
     pub rtc: Arc<RtcSessionManager>,
     pub websocket: Arc<CollabWebSocketManager>,
     pub moderation: Arc<ModerationManager>,
-    encryption_keys: Arc<RwLock<HashMap<Uuid, [u8; 32]>>>,
+    pub charts: Arc<ChartSessionManager>,
+    identity: Arc<IdentityKeyPair>,
+    keyrings: Arc<RwLock<HashMap<Uuid, RoomKeyring>>>,
+    key_envelopes: Arc<RwLock<HashMap<Uuid, Vec<RoomKeyEnvelope>>>>,
 }
 
 impl CollabState {
-    pub fn new(websocket: CollabWebSocketManager) -> Self {
+    pub fn new(websocket: CollabWebSocketManager, identity: IdentityKeyPair) -> Self {
         Self {
             rooms: Arc::new(RoomManager::new()),
             rtc: Arc::new(RtcSessionManager::new()),
             websocket: Arc::new(websocket),
             moderation: Arc::new(ModerationManager::new()),
-            encryption_keys: Arc::new(RwLock::new(HashMap::new())),
+            charts: Arc::new(ChartSessionManager::new()),
+            identity: Arc::new(identity),
+            keyrings: Arc::new(RwLock::new(HashMap::new())),
+            key_envelopes: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn local_public_key(&self) -> String {
+        self.identity.public_key_base64()
+    }
+
+    /// Starts a room at epoch 1 with a fresh key held by this device.
+    pub fn init_room_key(&self, room_id: Uuid) {
+        let mut keyring = RoomKeyring {
+            current_epoch: 1,
+            ..RoomKeyring::default()
+        };
+        keyring.keys.insert(1, RoomEncryption::generate_key());
+        self.keyrings.write().insert(room_id, keyring);
+    }
+
+    /// Wraps the current room key for a newly joined participant and relays
+    /// the envelope to them alone. Returns `None` when this device does not
+    /// hold the key or the participant is this device.
+    pub fn share_room_key(
+        &self,
+        room_id: Uuid,
+        participant: &Participant,
+    ) -> Result<Option<RoomKeyEnvelope>> {
+        let Some((epoch, key)) = self
+            .keyrings
+            .read()
+            .get(&room_id)
+            .and_then(RoomKeyring::current)
+        else {
+            return Ok(None);
+        };
+
+        let envelope = match self.seal_for(room_id, epoch, &key, participant)? {
+            Some(envelope) => envelope,
+            None => return Ok(None),
+        };
+        self.key_envelopes
+            .write()
+            .entry(room_id)
+            .or_default()
+            .push(envelope.clone());
+        self.websocket.send_key_envelope(&envelope)?;
+        Ok(Some(envelope))
+    }
+
+    /// Moves the room to a new key after someone leaves, so nothing sealed
+    /// from here on is readable with a key the departed member received.
+    /// Envelopes addressed to former members are dropped from the relay.
+    pub fn rotate_room_key(&self, room_id: Uuid) -> Result<Option<u32>> {
+        let key = RoomEncryption::generate_key();
+        let epoch = {
+            let mut keyrings = self.keyrings.write();
+            let Some(keyring) = keyrings.get_mut(&room_id) else {
+                return Ok(None);
+            };
+            keyring.current_epoch += 1;
+            keyring.keys.insert(keyring.current_epoch, key);
+            keyring.current_epoch
+        };
+
+        let participants = self.rooms.get_participants(&room_id);
+        let mut sealed = Vec::new();
+        for participant in &participants {
+            if let Some(envelope) = self.seal_for(room_id, epoch, &key, participant)? {
+                sealed.push(envelope);
+            }
+        }
+
+        {
+            let mut envelopes = self.key_envelopes.write();
+            let room_envelopes = envelopes.entry(room_id).or_default();
+            room_envelopes.retain(|envelope| {
+                participants
+                    .iter()
+                    .any(|p| p.user_id == envelope.recipient_user_id)
+            });
+            room_envelopes.extend(sealed.iter().cloned());
         }
+
+        for envelope in &sealed {
+            self.websocket.send_key_envelope(envelope)?;
+        }
+        self.websocket
+            .broadcast(room_id, CollabMessage::RoomKeyRotated { room_id, epoch })?;
+        Ok(Some(epoch))
+    }
+
+    /// Opens an envelope addressed to this device and adds the key to the
+    /// room's keyring.
+    pub fn accept_room_key(&self, envelope: &RoomKeyEnvelope) -> Result<()> {
+        if envelope.recipient_public_key != self.local_public_key() {
+            return Err(anyhow!("Key envelope is addressed to another device"));
+        }
+        let wrapped = WrappedRoomKey {
+            ephemeral_public: envelope.ephemeral_public_key.clone(),
+            ciphertext: envelope.wrapped_key.clone(),
+        };
+        let key = unwrap_room_key(&self.identity, &wrapped, envelope.room_id, envelope.epoch)?;
+
+        let mut keyrings = self.keyrings.write();
+        let keyring = keyrings.entry(envelope.room_id).or_default();
+        keyring.keys.insert(envelope.epoch, key);
+        keyring.current_epoch = keyring.current_epoch.max(envelope.epoch);
+        Ok(())
     }
 
-    pub fn set_encryption_key(&self, room_id: Uuid, key: [u8; 32]) {
-        self.encryption_keys.write().insert(room_id, key);
+    pub fn key_envelopes_for(&self, room_id: &Uuid, user_id: &str) -> Vec<RoomKeyEnvelope> {
+        self.key_envelopes
+            .read()
+            .get(room_id)
+            .map(|envelopes| {
+                envelopes
+                    .iter()
+                    .filter(|envelope| envelope.recipient_user_id == user_id)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
     }
 
-    pub fn get_encryption(&self, room_id: &Uuid) -> Option<RoomEncryption> {
-        self.encryption_keys
+    /// Encrypts outgoing chat content under the room's current key.
+    pub fn seal_message(&self, room_id: &Uuid, plaintext: &str) -> Result<(String, u32)> {
+        let (epoch, key) = self
+            .keyrings
             .read()
             .get(room_id)
-            .and_then(|key| RoomEncryption::new(key).ok())
+            .and_then(RoomKeyring::current)
+            .ok_or_else(|| anyhow!("Room key not available on this device yet"))?;
+        let ciphertext = RoomEncryption::new(&key)?.encrypt(plaintext)?;
+        Ok((ciphertext, epoch))
+    }
+
+    /// Decrypts a stored message in place. Messages sealed under an epoch
+    /// this device never received are left as ciphertext.
+    pub fn open_message(&self, message: &mut ChatMessage) {
+        let Some(epoch) = message.key_epoch.filter(|_| message.encrypted) else {
+            return;
+        };
+        let key = self
+            .keyrings
+            .read()
+            .get(&message.room_id)
+            .and_then(|keyring| keyring.keys.get(&epoch).copied());
+        if let Some(plaintext) = key
+            .and_then(|key| RoomEncryption::new(&key).ok())
+            .and_then(|cipher| cipher.decrypt(&message.content).ok())
+        {
+            message.content = plaintext;
+        }
+    }
+
+    pub fn forget_room_keys(&self, room_id: &Uuid) {
+        self.keyrings.write().remove(room_id);
+        self.key_envelopes.write().remove(room_id);
+    }
+
+    fn seal_for(
+        &self,
+        room_id: Uuid,
+        epoch: u32,
+        key: &[u8; 32],
+        participant: &Participant,
+    ) -> Result<Option<RoomKeyEnvelope>> {
+        let Some(public_key) = participant.public_key.as_deref() else {
+            return Ok(None);
+        };
+        if public_key == self.local_public_key() {
+            return Ok(None);
+        }
+
+        let recipient = decode_public_key(public_key)?;
+        let wrapped = wrap_room_key(key, &recipient, room_id, epoch)?;
+        Ok(Some(RoomKeyEnvelope {
+            room_id,
+            epoch,
+            recipient_user_id: participant.user_id.clone(),
+            recipient_public_key: public_key.to_string(),
+            ephemeral_public_key: wrapped.ephemeral_public,
+            wrapped_key: wrapped.ciphertext,
+            created_at: Utc::now(),
+        }))
     }
 
     pub fn broadcast_state(&self, room_id: Uuid) -> Result<()> {
-        let state = self.rooms.get_room_state(&room_id)?;
+        let state = self.get_room_state(&room_id)?;
         self.websocket
             .broadcast(room_id, CollabMessage::StateSync { state })
     }
 
+    /// Full room snapshot, including the shared chart and current cursors
+    /// so late joiners render the same view.
     pub fn get_room_state(&self, room_id: &Uuid) -> Result<RoomState> {
-        self.rooms.get_room_state(room_id)
+        let mut state = self.rooms.get_room_state(room_id)?;
+        state.chart = self.charts.get_context(room_id);
+        state.chart_cursors = self.charts.cursors(room_id);
+        Ok(state)
     }
 }
 
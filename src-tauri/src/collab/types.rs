@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -18,6 +20,13 @@ pub struct Room {
     pub video_enabled: bool,
     pub screen_share_enabled: bool,
     pub settings: RoomSettings,
+    /// Roles handed out in this room, by user id, so they survive leaving
+    /// and rejoining.
+    #[serde(default)]
+    pub role_assignments: HashMap<String, ParticipantRole>,
+    /// Per-room overrides of a role's default grants.
+    #[serde(default)]
+    pub role_grants: HashMap<ParticipantRole, ParticipantPermissions>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,14 +72,37 @@ pub struct Participant {
     pub is_muted: bool,
     pub is_video_off: bool,
     pub is_screen_sharing: bool,
+    /// Base64 X25519 key room keys are wrapped to for this participant.
+    #[serde(default)]
+    pub public_key: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ParticipantRole {
     Owner,
     Moderator,
-    Member,
-    Guest,
+    #[serde(alias = "Member")]
+    Trader,
+    #[serde(alias = "Guest")]
+    Viewer,
+}
+
+/// A single thing a participant may be allowed to do in a room.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RoomCapability {
+    Chat,
+    Speak,
+    ShareVideo,
+    ShareScreen,
+    ShareWatchlists,
+    ShareOrders,
+    ShareStrategies,
+    StartCompetitions,
+    Moderate,
+    Kick,
+    Ban,
+    AssignRoles,
+    ShareCharts,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,6 +116,14 @@ pub struct ParticipantPermissions {
     pub can_moderate: bool,
     pub can_kick: bool,
     pub can_ban: bool,
+    #[serde(default)]
+    pub can_share_watchlists: bool,
+    #[serde(default)]
+    pub can_start_competitions: bool,
+    #[serde(default)]
+    pub can_assign_roles: bool,
+    #[serde(default)]
+    pub can_share_charts: bool,
 }
 
 impl Default for ParticipantPermissions {
@@ -98,6 +138,10 @@ impl Default for ParticipantPermissions {
             can_moderate: false,
             can_kick: false,
             can_ban: false,
+            can_share_watchlists: true,
+            can_start_competitions: false,
+            can_assign_roles: false,
+            can_share_charts: true,
         }
     }
 }
@@ -121,6 +165,22 @@ pub struct ChatMessage {
     pub encrypted: bool,
     pub mentions: Vec<String>,
     pub replied_to: Option<Uuid>,
+    /// Room key epoch `content` is sealed under when `encrypted` is set.
+    #[serde(default)]
+    pub key_epoch: Option<u32>,
+}
+
+/// A room key wrapped for a single participant. Relayed as-is; only the
+/// holder of `recipient_public_key`'s secret can open it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomKeyEnvelope {
+    pub room_id: Uuid,
+    pub epoch: u32,
+    pub recipient_user_id: String,
+    pub recipient_public_key: String,
+    pub ephemeral_public_key: String,
+    pub wrapped_key: String,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -340,6 +400,21 @@ pub enum CollabMessage {
     StateSync {
         state: RoomState,
     },
+    RoomKeyRotated {
+        room_id: Uuid,
+        epoch: u32,
+    },
+    ChartContextUpdated {
+        context: ChartContext,
+    },
+    ChartCursorMoved {
+        room_id: Uuid,
+        cursor: ChartCursor,
+    },
+    ChartCursorCleared {
+        room_id: Uuid,
+        user_id: String,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -349,6 +424,66 @@ pub struct RoomState {
     pub watchlists: Vec<SharedWatchlist>,
     pub active_orders: Vec<SharedOrder>,
     pub competition: Option<Competition>,
+    #[serde(default)]
+    pub chart: Option<ChartContext>,
+    #[serde(default)]
+    pub chart_cursors: Vec<ChartCursor>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ChartDrawingKind {
+    TrendLine,
+    HorizontalLine,
+    VerticalLine,
+    Rectangle,
+    FibonacciRetracement,
+    Text,
+}
+
+/// A point in chart space: unix seconds on the time axis, price on the other.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ChartPoint {
+    pub time: i64,
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChartIndicator {
+    pub name: String,
+    #[serde(default)]
+    pub params: HashMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChartDrawing {
+    pub id: Uuid,
+    pub author_id: String,
+    pub kind: ChartDrawingKind,
+    pub points: Vec<ChartPoint>,
+    pub color: Option<String>,
+    pub label: Option<String>,
+}
+
+/// The chart everyone in the room is looking at. `version` increases on
+/// every change so clients can drop stale updates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChartContext {
+    pub room_id: Uuid,
+    pub token: String,
+    pub timeframe: String,
+    pub indicators: Vec<ChartIndicator>,
+    pub drawings: Vec<ChartDrawing>,
+    pub shared_by: String,
+    pub version: u64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChartCursor {
+    pub user_id: String,
+    pub username: String,
+    pub position: ChartPoint,
+    pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -366,6 +501,10 @@ pub struct JoinRoomRequest {
     pub room_id: Uuid,
     pub password: Option<String>,
     pub username: String,
+    /// The joiner's X25519 key; required for encrypted rooms. For the local
+    /// user this is `collab_get_identity_key`.
+    #[serde(default)]
+    pub public_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -375,6 +514,20 @@ pub struct UpdatePermissionsRequest {
     pub permissions: ParticipantPermissions,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignRoleRequest {
+    pub room_id: Uuid,
+    pub user_id: String,
+    pub role: ParticipantRole,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRoleGrantsRequest {
+    pub room_id: Uuid,
+    pub role: ParticipantRole,
+    pub permissions: ParticipantPermissions,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SendMessageRequest {
     pub room_id: Uuid,
@@ -382,6 +535,20 @@ pub struct SendMessageRequest {
     pub replied_to: Option<Uuid>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareChartRequest {
+    pub room_id: Uuid,
+    pub token: String,
+    pub timeframe: String,
+    #[serde(default)]
+    pub indicators: Vec<ChartIndicator>,
+    #[serde(default)]
+    pub drawings: Vec<ChartDrawing>,
+    /// Version the sender edited; the update is rejected if the chart has
+    /// moved on since.
+    pub base_version: Option<u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShareOrderRequest {
     pub room_id: Uuid,
@@ -7,7 +7,7 @@ use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::broadcast;
 use uuid::Uuid;
 
-use crate::collab::types::CollabMessage;
+use crate::collab::types::{CollabMessage, RoomKeyEnvelope};
 
 #[derive(Clone)]
 pub struct CollabWebSocketManager {
@@ -63,6 +63,22 @@ impl CollabWebSocketManager {
         Ok(())
     }
 
+    /// Delivers a wrapped room key to its recipient only, on a per-user
+    /// channel rather than the room-wide one.
+    pub fn send_key_envelope(&self, envelope: &RoomKeyEnvelope) -> Result<()> {
+        if let Some(app_handle) = &self.app_handle {
+            let event_name = format!(
+                "collab:room:{}:keys:{}",
+                envelope.room_id, envelope.recipient_user_id
+            );
+            app_handle
+                .emit(&event_name, envelope)
+                .context("Failed to emit room key envelope")?;
+        }
+
+        Ok(())
+    }
+
     pub fn subscribe(&self) -> broadcast::Receiver<(Uuid, CollabMessage)> {
         self.broadcast_tx.subscribe()
     }
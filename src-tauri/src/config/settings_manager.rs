@@ -1,3 +1,4 @@
+use crate::profiles::ProfilePaths;
 use super::settings_schema::*;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -90,7 +91,7 @@ impl SettingsManager {
 
     fn settings_path(&self) -> Result<PathBuf, SettingsError> {
         let app_handle = self.app_handle.clone();
-        let mut path = app_handle.path().app_data_dir().map_err(|e| {
+        let mut path = app_handle.path().profile_data_dir().map_err(|e| {
             SettingsError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 format!("App data directory not found: {}", e),
@@ -107,7 +108,7 @@ impl SettingsManager {
 
     fn profiles_path(&self) -> Result<PathBuf, SettingsError> {
         let app_handle = self.app_handle.clone();
-        let mut path = app_handle.path().app_data_dir().map_err(|e| {
+        let mut path = app_handle.path().profile_data_dir().map_err(|e| {
             SettingsError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 format!("App data directory not found: {}", e),
@@ -11,6 +11,9 @@ use parking_lot::Mutex as ParkingMutex;
 
 const TTL_CONFIG_PATH: &str = "config/cache_ttl.json";
 const DISK_CACHE_DIR: &str = "cache/disk";
+const QUERY_COUNTS_FILE: &str = "cache_query_counts.json";
+/// Key prefixes whose suffix is a token mint, used for usage tracking.
+const TOKEN_KEY_PREFIXES: &[&str] = &["token_price_", "token_info_"];
 const MIN_TTL_MS: u64 = 100;
 const MAX_TTL_MS: u64 = 7 * 24 * 60 * 60 * 1000;
 
@@ -417,6 +420,9 @@ pub struct CacheManager {
     max_size_bytes: usize,
     max_entries: usize,
     time_provider: Arc<dyn TimeProvider>,
+    /// Lookups per token mint, kept across evictions so warmup can follow usage.
+    query_counts: Arc<RwLock<HashMap<String, u64>>>,
+    query_counts_path: PathBuf,
 }
 
 impl CacheManager {
@@ -447,6 +453,15 @@ impl CacheManager {
             .unwrap_or_else(|| PathBuf::from(DISK_CACHE_DIR));
         let disk_cache = Arc::new(DiskCacheBackend::new(disk_path));
 
+        let query_counts_path = ttl_config_path
+            .parent()
+            .map(|parent| parent.join(QUERY_COUNTS_FILE))
+            .unwrap_or_else(|| PathBuf::from(QUERY_COUNTS_FILE));
+        let query_counts = fs::read(&query_counts_path)
+            .ok()
+            .and_then(|raw| serde_json::from_slice(&raw).ok())
+            .unwrap_or_default();
+
         let manager = Self {
             cache: Arc::new(RwLock::new(HashMap::new())),
             stats: Arc::new(RwLock::new(CacheStatistics::default())),
@@ -456,6 +471,8 @@ impl CacheManager {
             max_size_bytes: max_size_mb * 1024 * 1024,
             max_entries,
             time_provider,
+            query_counts: Arc::new(RwLock::new(query_counts)),
+            query_counts_path,
         };
 
         manager.disk_cache.prune_expired(manager.now_ms());
@@ -579,6 +596,7 @@ impl CacheManager {
     }
 
     pub async fn get(&self, key: &str, cache_type: CacheType) -> Option<serde_json::Value> {
+        self.record_query(key).await;
         let current_time = self.now_ms();
         {
             let mut cache = self.cache.write().await;
@@ -860,6 +878,47 @@ impl CacheManager {
         entries.iter().take(limit).map(|e| e.key.clone()).collect()
     }
 
+    async fn record_query(&self, key: &str) {
+        let Some(mint) = TOKEN_KEY_PREFIXES
+            .iter()
+            .find_map(|prefix| key.strip_prefix(prefix))
+            .filter(|mint| !mint.is_empty())
+        else {
+            return;
+        };
+        let mut counts = self.query_counts.write().await;
+        *counts.entry(mint.to_string()).or_insert(0) += 1;
+    }
+
+    /// Token mints ordered by how often they were looked up.
+    pub async fn most_queried_tokens(&self, limit: usize) -> Vec<(String, u64)> {
+        let counts = self.query_counts.read().await;
+        let mut tokens: Vec<(String, u64)> = counts
+            .iter()
+            .map(|(mint, count)| (mint.clone(), *count))
+            .collect();
+        tokens.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        tokens.truncate(limit);
+        tokens
+    }
+
+    /// Halves every query count so stale interest fades, then saves them.
+    pub async fn decay_query_counts(&self) -> Result<(), String> {
+        let snapshot = {
+            let mut counts = self.query_counts.write().await;
+            counts.retain(|_, count| {
+                *count /= 2;
+                *count > 0
+            });
+            counts.clone()
+        };
+
+        let data = serde_json::to_vec(&snapshot)
+            .map_err(|e| format!("Failed to serialize cache query counts: {e}"))?;
+        fs::write(&self.query_counts_path, data)
+            .map_err(|e| format!("Failed to write cache query counts: {e}"))
+    }
+
     pub async fn warm_cache<F, Fut>(
         &self,
         keys: Vec<String>,
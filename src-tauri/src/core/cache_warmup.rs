@@ -0,0 +1,428 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::RwLock;
+
+use super::cache_manager::{CacheType, SharedCacheManager, WarmProgress};
+use crate::portfolio::{SharedPortfolioData, SharedWatchlistManager};
+
+const WARMUP_CONFIG_PATH: &str = "config/cache_warmup.json";
+const DISK_PRELOAD_LIMIT: usize = 64;
+/// Gives portfolio and watchlist state time to be registered during setup.
+const STARTUP_DELAY_SECS: u64 = 15;
+const MIN_REFRESH_HOURS: u64 = 1;
+
+const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const USDT_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
+
+/// Used only when holdings, watchlists and usage yield nothing, e.g. on a
+/// fresh install.
+const FALLBACK_TOKENS: &[&str] = &[
+    SOL_MINT,
+    USDC_MINT,
+    USDT_MINT,
+    "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263", // BONK
+    "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN",  // JUP
+    "7vfCXTUXx5WJV5JADk17DUJ4ksgau7utNKj4b963voxs", // ETH
+    "mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So",  // mSOL
+    "7dHbWXmci3dT8UFYWYZweBLXgycu7Y3iL6trKn1Y7ARj", // stSOL
+    "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE",  // ORCA
+    "4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R", // RAY
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheWarmupSettings {
+    pub enabled: bool,
+    pub include_holdings: bool,
+    pub include_watchlists: bool,
+    pub include_most_queried: bool,
+    pub most_queried_limit: usize,
+    /// Always warmed, ahead of anything derived from usage.
+    pub pinned_tokens: Vec<String>,
+    pub excluded_tokens: Vec<String>,
+    pub max_tokens: usize,
+    pub refresh_interval_hours: u64,
+}
+
+impl Default for CacheWarmupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            include_holdings: true,
+            include_watchlists: true,
+            include_most_queried: true,
+            most_queried_limit: 20,
+            pinned_tokens: vec![
+                SOL_MINT.to_string(),
+                USDC_MINT.to_string(),
+                USDT_MINT.to_string(),
+            ],
+            excluded_tokens: Vec::new(),
+            max_tokens: 50,
+            refresh_interval_hours: 24,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WarmupSource {
+    Pinned,
+    Holding,
+    Watchlist,
+    MostQueried,
+    Fallback,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WarmupToken {
+    pub mint: String,
+    pub sources: Vec<WarmupSource>,
+    /// Lookups recorded by the cache, when known.
+    pub query_count: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheWarmupPlan {
+    pub tokens: Vec<WarmupToken>,
+    pub generated_at: i64,
+    pub progress: Option<WarmProgress>,
+}
+
+/// Inputs gathered from the rest of the app for one evaluation.
+#[derive(Debug, Clone, Default)]
+pub struct WarmupCandidates {
+    pub holdings: Vec<String>,
+    pub watchlists: Vec<String>,
+    pub most_queried: Vec<(String, u64)>,
+}
+
+/// Merges candidate sources in priority order: pinned, holdings,
+/// watchlists, then most queried. Duplicates collect every source.
+pub fn build_warmup_set(
+    settings: &CacheWarmupSettings,
+    candidates: &WarmupCandidates,
+) -> Vec<WarmupToken> {
+    let excluded: HashSet<&str> = settings
+        .excluded_tokens
+        .iter()
+        .map(|mint| mint.as_str())
+        .collect();
+    let mut tokens: Vec<WarmupToken> = Vec::new();
+
+    for mint in &settings.pinned_tokens {
+        push_token(&mut tokens, &excluded, mint, WarmupSource::Pinned, None);
+    }
+    if settings.include_holdings {
+        for mint in &candidates.holdings {
+            push_token(&mut tokens, &excluded, mint, WarmupSource::Holding, None);
+        }
+    }
+    if settings.include_watchlists {
+        for mint in &candidates.watchlists {
+            push_token(&mut tokens, &excluded, mint, WarmupSource::Watchlist, None);
+        }
+    }
+    if settings.include_most_queried {
+        for (mint, count) in candidates
+            .most_queried
+            .iter()
+            .take(settings.most_queried_limit)
+        {
+            push_token(
+                &mut tokens,
+                &excluded,
+                mint,
+                WarmupSource::MostQueried,
+                Some(*count),
+            );
+        }
+    }
+    if tokens.is_empty() {
+        for mint in FALLBACK_TOKENS {
+            push_token(&mut tokens, &excluded, mint, WarmupSource::Fallback, None);
+        }
+    }
+
+    tokens.truncate(settings.max_tokens);
+    tokens
+}
+
+fn push_token(
+    tokens: &mut Vec<WarmupToken>,
+    excluded: &HashSet<&str>,
+    mint: &str,
+    source: WarmupSource,
+    query_count: Option<u64>,
+) {
+    let mint = mint.trim();
+    if mint.is_empty() || excluded.contains(mint) {
+        return;
+    }
+    if let Some(existing) = tokens.iter_mut().find(|t| t.mint == mint) {
+        if !existing.sources.contains(&source) {
+            existing.sources.push(source);
+        }
+        existing.query_count = existing.query_count.or(query_count);
+        return;
+    }
+    tokens.push(WarmupToken {
+        mint: mint.to_string(),
+        sources: vec![source],
+        query_count,
+    });
+}
+
+pub struct CacheWarmupService {
+    settings: RwLock<CacheWarmupSettings>,
+    settings_path: PathBuf,
+    last_plan: RwLock<Option<CacheWarmupPlan>>,
+}
+
+pub type SharedCacheWarmupService = Arc<CacheWarmupService>;
+
+impl CacheWarmupService {
+    pub fn new() -> Self {
+        Self::with_path(PathBuf::from(WARMUP_CONFIG_PATH))
+    }
+
+    pub fn with_path(settings_path: PathBuf) -> Self {
+        let settings = Self::load_settings(&settings_path).unwrap_or_default();
+        Self {
+            settings: RwLock::new(settings),
+            settings_path,
+            last_plan: RwLock::new(None),
+        }
+    }
+
+    fn load_settings(path: &Path) -> Result<CacheWarmupSettings, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read warmup settings: {e}"))?;
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse warmup settings: {e}"))
+    }
+
+    fn validate_settings(settings: &CacheWarmupSettings) -> Result<(), String> {
+        if settings.max_tokens == 0 {
+            return Err("Warmup must allow at least one token".to_string());
+        }
+        if settings.refresh_interval_hours < MIN_REFRESH_HOURS {
+            return Err(format!(
+                "Warmup refresh interval must be at least {MIN_REFRESH_HOURS} hour"
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn settings(&self) -> CacheWarmupSettings {
+        self.settings.read().await.clone()
+    }
+
+    pub async fn update_settings(&self, settings: CacheWarmupSettings) -> Result<(), String> {
+        Self::validate_settings(&settings)?;
+        if let Some(parent) = self.settings_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {e}"))?;
+        }
+        let content = serde_json::to_string_pretty(&settings)
+            .map_err(|e| format!("Failed to serialize warmup settings: {e}"))?;
+        fs::write(&self.settings_path, content)
+            .map_err(|e| format!("Failed to write warmup settings: {e}"))?;
+        *self.settings.write().await = settings;
+        Ok(())
+    }
+
+    pub async fn last_plan(&self) -> Option<CacheWarmupPlan> {
+        self.last_plan.read().await.clone()
+    }
+
+    /// Evaluates the warmup set without fetching anything.
+    pub async fn preview(
+        &self,
+        app: &AppHandle,
+        cache_manager: &SharedCacheManager,
+    ) -> CacheWarmupPlan {
+        let settings = self.settings().await;
+        let candidates = gather_candidates(app, cache_manager, &settings).await;
+        CacheWarmupPlan {
+            tokens: build_warmup_set(&settings, &candidates),
+            generated_at: chrono::Utc::now().timestamp(),
+            progress: None,
+        }
+    }
+
+    pub async fn run(
+        &self,
+        app: &AppHandle,
+        cache_manager: &SharedCacheManager,
+    ) -> Result<CacheWarmupPlan, String> {
+        let mut plan = self.preview(app, cache_manager).await;
+        let keys: Vec<String> = plan
+            .tokens
+            .iter()
+            .map(|token| format!("token_price_{}", token.mint))
+            .collect();
+
+        let progress = {
+            let manager = cache_manager.read().await;
+            manager
+                .warm_cache(keys, |_key| async move {
+                    // Mock data - in real implementation would fetch from API
+                    let data = json!({
+                        "price": 100.0,
+                        "change24h": 5.0,
+                        "volume": 1000000.0,
+                    });
+                    Ok((data, CacheType::TokenPrice))
+                })
+                .await?
+        };
+
+        plan.progress = Some(progress);
+        *self.last_plan.write().await = Some(plan.clone());
+        Ok(plan)
+    }
+}
+
+impl Default for CacheWarmupService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn gather_candidates(
+    app: &AppHandle,
+    cache_manager: &SharedCacheManager,
+    settings: &CacheWarmupSettings,
+) -> WarmupCandidates {
+    let mut candidates = WarmupCandidates::default();
+
+    if settings.include_holdings {
+        if let Some(portfolio) = app.try_state::<SharedPortfolioData>() {
+            if let Ok(data) = portfolio.lock() {
+                let mut positions = data.positions();
+                positions.sort_by(|a, b| {
+                    b.total_value
+                        .partial_cmp(&a.total_value)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+                candidates.holdings = positions.into_iter().map(|p| p.mint).collect();
+            }
+        }
+    }
+
+    if settings.include_watchlists {
+        if let Some(watchlists) = app.try_state::<SharedWatchlistManager>() {
+            match watchlists.read().await.list_watchlists().await {
+                Ok(lists) => {
+                    candidates.watchlists = lists
+                        .into_iter()
+                        .flat_map(|list| list.items.into_iter().map(|item| item.mint))
+                        .collect();
+                }
+                Err(err) => eprintln!("Failed to read watchlists for cache warmup: {err}"),
+            }
+        }
+    }
+
+    if settings.include_most_queried {
+        let manager = cache_manager.read().await;
+        candidates.most_queried = manager
+            .most_queried_tokens(settings.most_queried_limit)
+            .await;
+    }
+
+    candidates
+}
+
+/// Loads recent disk entries, then re-evaluates and warms the token set on
+/// the configured interval so warmed data tracks actual usage.
+pub fn start_cache_warmup_scheduler(
+    app: AppHandle,
+    cache_manager: SharedCacheManager,
+    service: SharedCacheWarmupService,
+) {
+    tauri::async_runtime::spawn(async move {
+        {
+            let manager = cache_manager.read().await;
+            let warmed_from_disk = manager.populate_from_disk(DISK_PRELOAD_LIMIT).await;
+            tracing::info!(
+                preloaded_entries = warmed_from_disk,
+                "cache warmup from disk"
+            );
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(STARTUP_DELAY_SECS)).await;
+
+        loop {
+            let settings = service.settings().await;
+            if settings.enabled {
+                match service.run(&app, &cache_manager).await {
+                    Ok(plan) => tracing::info!(tokens = plan.tokens.len(), "cache warmup ran"),
+                    Err(err) => eprintln!("Cache warmup failed: {err}"),
+                }
+            }
+
+            let interval_hours = settings.refresh_interval_hours.max(MIN_REFRESH_HOURS);
+            tokio::time::sleep(std::time::Duration::from_secs(interval_hours * 3600)).await;
+
+            let manager = cache_manager.read().await;
+            if let Err(err) = manager.decay_query_counts().await {
+                eprintln!("Failed to decay cache query counts: {err}");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_sources_in_priority_order() {
+        let settings = CacheWarmupSettings {
+            pinned_tokens: vec!["SOL".to_string()],
+            excluded_tokens: vec!["SCAM".to_string()],
+            max_tokens: 4,
+            ..CacheWarmupSettings::default()
+        };
+        let candidates = WarmupCandidates {
+            holdings: vec!["BONK".to_string(), "SOL".to_string()],
+            watchlists: vec!["SCAM".to_string(), "JUP".to_string()],
+            most_queried: vec![
+                ("BONK".to_string(), 9),
+                ("RAY".to_string(), 5),
+                ("ORCA".to_string(), 1),
+            ],
+        };
+
+        let tokens = build_warmup_set(&settings, &candidates);
+        let mints: Vec<&str> = tokens.iter().map(|t| t.mint.as_str()).collect();
+        assert_eq!(mints, vec!["SOL", "BONK", "JUP", "RAY"]);
+        assert_eq!(
+            tokens[1].sources,
+            vec![WarmupSource::Holding, WarmupSource::MostQueried]
+        );
+        assert_eq!(tokens[1].query_count, Some(9));
+    }
+
+    #[test]
+    fn falls_back_when_nothing_is_known() {
+        let settings = CacheWarmupSettings {
+            pinned_tokens: Vec::new(),
+            ..CacheWarmupSettings::default()
+        };
+
+        let tokens = build_warmup_set(&settings, &WarmupCandidates::default());
+        assert_eq!(tokens.len(), FALLBACK_TOKENS.len());
+        assert!(tokens
+            .iter()
+            .all(|t| t.sources == vec![WarmupSource::Fallback]));
+    }
+}
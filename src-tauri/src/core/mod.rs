@@ -1,7 +1,9 @@
 pub mod cache_manager;
+pub mod cache_warmup;
 pub mod price_engine;
 pub mod websocket_manager;
 
 pub use cache_manager::*;
+pub use cache_warmup::*;
 pub use price_engine::*;
 pub use websocket_manager::*;
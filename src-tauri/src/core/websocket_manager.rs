@@ -1,4 +1,4 @@
-use crate::market::get_coin_price;
+use crate::market::fetch_coin_price;
 use crate::websocket::birdeye::BirdeyeStream;
 use crate::websocket::helius::HeliusStream;
 use crate::websocket::reconnect::ExponentialBackoff;
@@ -542,7 +542,7 @@ impl WebSocketManager {
                 match provider {
                     StreamProvider::Birdeye => {
                         for symbol in &subs.prices {
-                            if let Ok(price) = get_coin_price(symbol.clone(), None).await {
+                            if let Ok(price) = fetch_coin_price(symbol.clone(), None).await {
                                 let delta = PriceDelta {
                                     symbol: symbol.clone(),
                                     price: Some(price.price),
@@ -603,6 +603,17 @@ impl WebSocketManager {
         }
     }
 
+    /// Async counterpart of `subscribe_events` for callers already running
+    /// on the runtime, where a blocking read would panic.
+    pub async fn event_receiver(
+        &self,
+        provider: StreamProvider,
+    ) -> Option<broadcast::Receiver<StreamEvent>> {
+        self.get_connection(&provider)
+            .await
+            .map(|conn| conn.event_tx.subscribe())
+    }
+
     pub fn subscribe_events(
         &self,
         provider: StreamProvider,
@@ -1,4 +1,5 @@
 use crate::data::database::{CompressionConfig, CompressionStats, SharedCompressionManager};
+use crate::profiles::ProfilePaths;
 use tauri::{Manager, State};
 
 #[tauri::command]
@@ -69,7 +70,7 @@ pub async fn get_database_size(app_handle: tauri::AppHandle) -> Result<DatabaseS
 
     let mut data_dir = app_handle
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|e| format!("Unable to resolve app data directory: {}", e))?;
 
     let mut total_size = 0u64;
@@ -1,10 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, Pool, Sqlite, SqlitePool};
+use sqlx::{FromRow, Pool, Row, Sqlite, SqlitePool};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -86,6 +86,17 @@ pub struct EventRecord {
     pub timestamp: String,
 }
 
+/// An event together with its position in the store. Cursors increase
+/// across all aggregates, so a subscriber can resume from the last cursor
+/// it saw.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamedEvent {
+    pub cursor: i64,
+    #[serde(flatten)]
+    pub record: EventRecord,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct SnapshotRecord {
     pub id: String,
@@ -105,10 +116,14 @@ pub struct EventFilter {
     pub offset: Option<i64>,
 }
 
+/// Live events buffered per subscriber before it is considered lagging.
+const LIVE_CHANNEL_CAPACITY: usize = 1024;
+
 pub struct EventStore {
     pool: Pool<Sqlite>,
     sequence_counters: Arc<RwLock<HashMap<String, i64>>>,
     point_in_time_cache: Arc<RwLock<HashMap<String, (DateTime<Utc>, String)>>>,
+    live: broadcast::Sender<StreamedEvent>,
 }
 
 impl EventStore {
@@ -129,6 +144,7 @@ impl EventStore {
             pool,
             sequence_counters: Arc::new(RwLock::new(HashMap::new())),
             point_in_time_cache: Arc::new(RwLock::new(HashMap::new())),
+            live: broadcast::channel(LIVE_CHANNEL_CAPACITY).0,
         };
 
         store.initialize().await?;
@@ -230,7 +246,7 @@ impl EventStore {
         let sequence = self.get_next_sequence(aggregate_id).await;
         let timestamp = Utc::now().to_rfc3339();
 
-        sqlx::query(
+        let cursor = sqlx::query(
             r#"
             INSERT INTO events (id, event_type, event_data, aggregate_id, sequence, timestamp)
             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
@@ -243,7 +259,21 @@ impl EventStore {
         .bind(sequence)
         .bind(&timestamp)
         .execute(&self.pool)
-        .await?;
+        .await?
+        .last_insert_rowid();
+
+        // Nobody listening is not an error.
+        let _ = self.live.send(StreamedEvent {
+            cursor,
+            record: EventRecord {
+                id: event_id.clone(),
+                event_type,
+                event_data,
+                aggregate_id: aggregate_id.to_string(),
+                sequence,
+                timestamp,
+            },
+        });
 
         // Check if we should create a snapshot (every 1000 events)
         if sequence % 1000 == 0 {
@@ -445,6 +475,36 @@ impl EventStore {
         Ok(snapshot_id)
     }
 
+    pub async fn get_snapshot(
+        &self,
+        snapshot_id: &str,
+    ) -> Result<Option<SnapshotRecord>, sqlx::Error> {
+        sqlx::query_as::<_, SnapshotRecord>("SELECT * FROM snapshots WHERE id = ?1")
+            .bind(snapshot_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// Snapshots for one aggregate, newest first.
+    pub async fn list_snapshots(
+        &self,
+        aggregate_id: &str,
+        limit: i64,
+    ) -> Result<Vec<SnapshotRecord>, sqlx::Error> {
+        sqlx::query_as::<_, SnapshotRecord>(
+            r#"
+            SELECT * FROM snapshots
+            WHERE aggregate_id = ?1
+            ORDER BY timestamp DESC
+            LIMIT ?2
+            "#,
+        )
+        .bind(aggregate_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
     async fn create_snapshot_internal(&self, aggregate_id: &str) -> Result<String, sqlx::Error> {
         // Create a simple snapshot with current sequence number
         let state_data = serde_json::json!({
@@ -610,6 +670,58 @@ impl EventStore {
         }
     }
 
+    /// Receives every event published from now on. Receivers that fall
+    /// more than the channel capacity behind get `Lagged` and should catch
+    /// up with `events_after`.
+    pub fn subscribe_live(&self) -> broadcast::Receiver<StreamedEvent> {
+        self.live.subscribe()
+    }
+
+    /// Cursor of the newest stored event, or 0 when the store is empty.
+    pub async fn latest_cursor(&self) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar::<_, i64>("SELECT COALESCE(MAX(rowid), 0) FROM events")
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    /// Stored events after `cursor`, oldest first.
+    pub async fn events_after(
+        &self,
+        cursor: i64,
+        aggregate_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<StreamedEvent>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT rowid AS cursor, id, event_type, event_data, aggregate_id, sequence, timestamp
+            FROM events
+            WHERE rowid > ?1 AND (?2 IS NULL OR aggregate_id = ?2)
+            ORDER BY rowid ASC
+            LIMIT ?3
+            "#,
+        )
+        .bind(cursor)
+        .bind(aggregate_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| StreamedEvent {
+                cursor: row.get("cursor"),
+                record: EventRecord {
+                    id: row.get("id"),
+                    event_type: row.get("event_type"),
+                    event_data: row.get("event_data"),
+                    aggregate_id: row.get("aggregate_id"),
+                    sequence: row.get("sequence"),
+                    timestamp: row.get("timestamp"),
+                },
+            })
+            .collect())
+    }
+
     pub async fn get_event_count(&self, aggregate_id: Option<&str>) -> Result<i64, sqlx::Error> {
         if let Some(aggregate_id) = aggregate_id {
             let (count,) =
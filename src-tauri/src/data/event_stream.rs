@@ -0,0 +1,489 @@
+//! Streaming subscriptions over the event store.
+//!
+//! A subscription names the event types it wants (`order.filled`,
+//! `order.*`, or `*`), optionally one aggregate, and optionally a cursor to
+//! replay from. Its task first replays stored events after that cursor,
+//! then forwards live events as Tauri events named `event_stream:<id>`, in
+//! batches.
+//!
+//! Two things keep a slow consumer from being flooded or losing events.
+//! Subscriptions that ask for acknowledgements stop sending once too many
+//! batches are unacknowledged. And whenever the task falls behind the live
+//! channel, it catches up from the database instead of dropping events.
+
+use chrono::Utc;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use super::event_store::{SharedEventStore, StreamedEvent};
+use crate::api::cancellation::CancellationToken;
+
+const DEFAULT_BATCH_SIZE: usize = 100;
+const MAX_BATCH_SIZE: usize = 1000;
+const DEFAULT_MAX_UNACKED_BATCHES: usize = 4;
+
+/// Turns `order.filled` or `Order-Filled` into the stored `order_filled`.
+/// A trailing `*` is kept as a prefix wildcard.
+pub fn normalize_event_pattern(pattern: &str) -> String {
+    pattern.trim().to_lowercase().replace(['.', '-'], "_")
+}
+
+/// Whether any pattern matches. No patterns means every event.
+pub fn event_type_matches(patterns: &[String], event_type: &str) -> bool {
+    patterns.is_empty()
+        || patterns
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => event_type.starts_with(prefix),
+                None => pattern == event_type,
+            })
+}
+
+/// Tracks batches that have been sent but not acknowledged.
+#[derive(Debug, Clone)]
+pub struct AckWindow {
+    /// Last cursor of each unacknowledged batch, oldest first.
+    pending: VecDeque<i64>,
+    max_pending: usize,
+    acked_cursor: i64,
+}
+
+impl AckWindow {
+    pub fn new(max_pending: usize, acked_cursor: i64) -> Self {
+        Self {
+            pending: VecDeque::new(),
+            max_pending: max_pending.max(1),
+            acked_cursor,
+        }
+    }
+
+    pub fn record(&mut self, last_cursor: i64) {
+        self.pending.push_back(last_cursor);
+    }
+
+    /// Acknowledges everything up to and including `cursor`.
+    pub fn ack(&mut self, cursor: i64) {
+        self.acked_cursor = self.acked_cursor.max(cursor);
+        while self
+            .pending
+            .front()
+            .is_some_and(|last| *last <= self.acked_cursor)
+        {
+            self.pending.pop_front();
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.pending.len() >= self.max_pending
+    }
+
+    pub fn acked_cursor(&self) -> i64 {
+        self.acked_cursor
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSubscriptionRequest {
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    pub aggregate_id: Option<String>,
+    /// Replay stored events after this cursor first. Without it the
+    /// subscription only sees events published from now on.
+    pub from_cursor: Option<i64>,
+    pub batch_size: Option<usize>,
+    /// Pause the stream while `max_unacked_batches` batches await
+    /// `event_stream_ack`.
+    #[serde(default)]
+    pub require_ack: bool,
+    pub max_unacked_batches: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventBatch {
+    pub subscription_id: String,
+    pub events: Vec<StreamedEvent>,
+    pub last_cursor: i64,
+    /// The events came from the database rather than the live channel,
+    /// either as the requested replay or to catch up after lagging.
+    pub replayed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSubscriptionInfo {
+    pub id: String,
+    pub event_name: String,
+    pub event_types: Vec<String>,
+    pub aggregate_id: Option<String>,
+    pub delivered_cursor: i64,
+    pub acked_cursor: Option<i64>,
+    pub paused: bool,
+    pub created_at: i64,
+}
+
+struct Subscription {
+    id: String,
+    patterns: Vec<String>,
+    aggregate_id: Option<String>,
+    batch_size: usize,
+    created_at: i64,
+    delivered_cursor: Mutex<i64>,
+    window: Option<Mutex<AckWindow>>,
+    acked: Notify,
+    paused: Mutex<bool>,
+    token: CancellationToken,
+}
+
+impl Subscription {
+    fn event_name(&self) -> String {
+        format!("event_stream:{}", self.id)
+    }
+
+    fn info(&self) -> EventSubscriptionInfo {
+        EventSubscriptionInfo {
+            id: self.id.clone(),
+            event_name: self.event_name(),
+            event_types: self.patterns.clone(),
+            aggregate_id: self.aggregate_id.clone(),
+            delivered_cursor: *self.delivered_cursor.lock(),
+            acked_cursor: self
+                .window
+                .as_ref()
+                .map(|window| window.lock().acked_cursor()),
+            paused: *self.paused.lock(),
+            created_at: self.created_at,
+        }
+    }
+
+    fn wants(&self, event: &StreamedEvent) -> bool {
+        event_type_matches(&self.patterns, &event.record.event_type)
+            && self
+                .aggregate_id
+                .as_deref()
+                .map_or(true, |id| id == event.record.aggregate_id)
+    }
+
+    /// Waits until the ack window has room. Returns false if the
+    /// subscription was cancelled meanwhile.
+    async fn wait_for_capacity(&self) -> bool {
+        let Some(window) = &self.window else {
+            return !self.token.is_cancelled();
+        };
+        loop {
+            let acked = self.acked.notified();
+            if self.token.is_cancelled() {
+                return false;
+            }
+            if !window.lock().is_full() {
+                *self.paused.lock() = false;
+                return true;
+            }
+            *self.paused.lock() = true;
+            tokio::select! {
+                _ = acked => {}
+                _ = self.token.cancelled() => return false,
+            }
+        }
+    }
+
+    /// Emits the matching events in batches, advancing the delivered
+    /// cursor to `scanned_to` even when nothing matched.
+    async fn deliver(
+        &self,
+        app: &AppHandle,
+        events: Vec<StreamedEvent>,
+        scanned_to: i64,
+        replayed: bool,
+    ) -> bool {
+        let wanted: Vec<StreamedEvent> = events.into_iter().filter(|e| self.wants(e)).collect();
+        for chunk in wanted.chunks(self.batch_size) {
+            if !self.wait_for_capacity().await {
+                return false;
+            }
+            let last_cursor = chunk.last().map_or(scanned_to, |event| event.cursor);
+            let batch = EventBatch {
+                subscription_id: self.id.clone(),
+                events: chunk.to_vec(),
+                last_cursor,
+                replayed,
+            };
+            if let Err(e) = app.emit(&self.event_name(), &batch) {
+                eprintln!("Failed to emit event stream batch: {}", e);
+            }
+            if let Some(window) = &self.window {
+                window.lock().record(last_cursor);
+            }
+            *self.delivered_cursor.lock() = last_cursor;
+        }
+        let mut delivered = self.delivered_cursor.lock();
+        *delivered = (*delivered).max(scanned_to);
+        true
+    }
+}
+
+/// Replays stored events after the delivered cursor until the store has
+/// nothing newer.
+async fn catch_up(app: &AppHandle, store: &SharedEventStore, subscription: &Subscription) -> bool {
+    loop {
+        let cursor = *subscription.delivered_cursor.lock();
+        let page = store
+            .read()
+            .await
+            .events_after(
+                cursor,
+                subscription.aggregate_id.as_deref(),
+                subscription.batch_size as i64,
+            )
+            .await;
+        let page = match page {
+            Ok(page) => page,
+            Err(e) => {
+                eprintln!("Event stream catch-up failed: {}", e);
+                return !subscription.token.is_cancelled();
+            }
+        };
+        let Some(scanned_to) = page.last().map(|event| event.cursor) else {
+            return true;
+        };
+        if !subscription.deliver(app, page, scanned_to, true).await {
+            return false;
+        }
+    }
+}
+
+async fn run_subscription(
+    app: AppHandle,
+    store: SharedEventStore,
+    subscription: Arc<Subscription>,
+    replay: bool,
+) {
+    // Subscribe before replaying so events published during the replay are
+    // buffered rather than missed.
+    let mut live = store.read().await.subscribe_live();
+    if replay && !catch_up(&app, &store, &subscription).await {
+        return;
+    }
+
+    loop {
+        let first = tokio::select! {
+            received = live.recv() => received,
+            _ = subscription.token.cancelled() => return,
+        };
+        let mut lagged = false;
+        let mut events = Vec::new();
+        match first {
+            Ok(event) => events.push(event),
+            Err(RecvError::Lagged(_)) => lagged = true,
+            Err(RecvError::Closed) => return,
+        }
+        while !lagged && events.len() < subscription.batch_size {
+            match live.try_recv() {
+                Ok(event) => events.push(event),
+                Err(TryRecvError::Lagged(_)) => lagged = true,
+                Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => break,
+            }
+        }
+
+        if lagged {
+            // Whatever was dropped is still in the database.
+            if !catch_up(&app, &store, &subscription).await {
+                return;
+            }
+            continue;
+        }
+        let delivered = *subscription.delivered_cursor.lock();
+        events.retain(|event| event.cursor > delivered);
+        let Some(scanned_to) = events.last().map(|event| event.cursor) else {
+            continue;
+        };
+        if !subscription.deliver(&app, events, scanned_to, false).await {
+            return;
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct EventSubscriptions {
+    subscriptions: Mutex<HashMap<String, Arc<Subscription>>>,
+}
+
+pub type SharedEventSubscriptions = Arc<EventSubscriptions>;
+
+impl EventSubscriptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn subscribe(
+        &self,
+        app: &AppHandle,
+        store: &SharedEventStore,
+        request: EventSubscriptionRequest,
+    ) -> Result<EventSubscriptionInfo, String> {
+        let start_cursor = match request.from_cursor {
+            Some(cursor) if cursor < 0 => return Err("Cursor cannot be negative".into()),
+            Some(cursor) => cursor,
+            None => store
+                .read()
+                .await
+                .latest_cursor()
+                .await
+                .map_err(|e| format!("Failed to read event cursor: {}", e))?,
+        };
+        let subscription = Arc::new(Subscription {
+            id: Uuid::new_v4().to_string(),
+            patterns: request
+                .event_types
+                .iter()
+                .map(|pattern| normalize_event_pattern(pattern))
+                .filter(|pattern| !pattern.is_empty() && pattern != "*")
+                .collect(),
+            aggregate_id: request.aggregate_id.filter(|id| !id.is_empty()),
+            batch_size: request
+                .batch_size
+                .unwrap_or(DEFAULT_BATCH_SIZE)
+                .clamp(1, MAX_BATCH_SIZE),
+            created_at: Utc::now().timestamp_millis(),
+            delivered_cursor: Mutex::new(start_cursor),
+            window: request.require_ack.then(|| {
+                let max = request
+                    .max_unacked_batches
+                    .unwrap_or(DEFAULT_MAX_UNACKED_BATCHES);
+                Mutex::new(AckWindow::new(max, start_cursor))
+            }),
+            acked: Notify::new(),
+            paused: Mutex::new(false),
+            token: CancellationToken::new(),
+        });
+        self.subscriptions
+            .lock()
+            .insert(subscription.id.clone(), subscription.clone());
+
+        let info = subscription.info();
+        tauri::async_runtime::spawn(run_subscription(
+            app.clone(),
+            store.clone(),
+            subscription,
+            request.from_cursor.is_some(),
+        ));
+        Ok(info)
+    }
+
+    pub fn ack(&self, id: &str, cursor: i64) -> Result<EventSubscriptionInfo, String> {
+        let subscription = self
+            .subscriptions
+            .lock()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("Unknown event subscription {}", id))?;
+        if let Some(window) = &subscription.window {
+            window.lock().ack(cursor);
+            subscription.acked.notify_waiters();
+        }
+        Ok(subscription.info())
+    }
+
+    pub fn unsubscribe(&self, id: &str) -> bool {
+        match self.subscriptions.lock().remove(id) {
+            Some(subscription) => {
+                subscription.token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn list(&self) -> Vec<EventSubscriptionInfo> {
+        let mut infos: Vec<EventSubscriptionInfo> = self
+            .subscriptions
+            .lock()
+            .values()
+            .map(|subscription| subscription.info())
+            .collect();
+        infos.sort_by_key(|info| info.created_at);
+        infos
+    }
+}
+
+#[tauri::command]
+pub async fn event_stream_subscribe(
+    app: AppHandle,
+    request: EventSubscriptionRequest,
+    event_store: State<'_, SharedEventStore>,
+    subscriptions: State<'_, SharedEventSubscriptions>,
+) -> Result<EventSubscriptionInfo, String> {
+    subscriptions
+        .subscribe(&app, event_store.inner(), request)
+        .await
+}
+
+#[tauri::command]
+pub async fn event_stream_ack(
+    subscription_id: String,
+    cursor: i64,
+    subscriptions: State<'_, SharedEventSubscriptions>,
+) -> Result<EventSubscriptionInfo, String> {
+    subscriptions.ack(&subscription_id, cursor)
+}
+
+#[tauri::command]
+pub async fn event_stream_unsubscribe(
+    subscription_id: String,
+    subscriptions: State<'_, SharedEventSubscriptions>,
+) -> Result<bool, String> {
+    Ok(subscriptions.unsubscribe(&subscription_id))
+}
+
+#[tauri::command]
+pub async fn event_stream_list(
+    subscriptions: State<'_, SharedEventSubscriptions>,
+) -> Result<Vec<EventSubscriptionInfo>, String> {
+    Ok(subscriptions.list())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns_match_stored_event_types() {
+        let patterns: Vec<String> = ["order.filled", "Position.*"]
+            .iter()
+            .map(|p| normalize_event_pattern(p))
+            .collect();
+        assert_eq!(patterns, ["order_filled", "position_*"]);
+        assert!(event_type_matches(&patterns, "order_filled"));
+        assert!(event_type_matches(&patterns, "position_closed"));
+        assert!(!event_type_matches(&patterns, "order_placed"));
+        assert!(event_type_matches(&[], "anything"));
+    }
+
+    #[test]
+    fn ack_window_pauses_until_acknowledged() {
+        let mut window = AckWindow::new(2, 10);
+        window.record(15);
+        assert!(!window.is_full());
+        window.record(22);
+        assert!(window.is_full());
+
+        // Acking mid-way releases only the batches that end at or before it.
+        window.ack(18);
+        assert!(!window.is_full());
+        window.record(30);
+        assert!(window.is_full());
+        window.ack(30);
+        assert!(!window.is_full());
+        assert_eq!(window.acked_cursor(), 30);
+
+        window.ack(5);
+        assert_eq!(window.acked_cursor(), 30);
+    }
+}
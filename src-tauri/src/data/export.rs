@@ -0,0 +1,608 @@
+//! Central dataset export.
+//!
+//! Each dataset is read from the store that owns it and flattened into
+//! rows of named columns. The rows are then filtered to the date range,
+//! narrowed to the requested columns and written as CSV, JSON Lines or
+//! Parquet. Nested values become JSON text in CSV and Parquet and stay
+//! nested in JSON Lines.
+
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use parquet::basic::{LogicalType, Repetition, Type as PhysicalType};
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DoubleType};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::types::Type;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use crate::ai_legacy::SharedRiskAnalyzer;
+use crate::alerts::SharedAlertManager;
+use crate::data::event_store::{EventFilter, SharedEventStore};
+use crate::journal::{DateRange, JournalFilters, SharedJournalDatabase};
+use crate::profiles::ProfilePaths;
+use crate::trading::limit_orders::require_state;
+use crate::trading::types::Order;
+
+const EXPORT_DIR: &str = "exports";
+
+pub type DatasetRow = Map<String, Value>;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportDataset {
+    Trades,
+    Orders,
+    Alerts,
+    JournalEntries,
+    RiskScores,
+    Events,
+}
+
+impl ExportDataset {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportDataset::Trades => "trades",
+            ExportDataset::Orders => "orders",
+            ExportDataset::Alerts => "alerts",
+            ExportDataset::JournalEntries => "journal_entries",
+            ExportDataset::RiskScores => "risk_scores",
+            ExportDataset::Events => "events",
+        }
+    }
+
+    /// Column the date range applies to.
+    fn timestamp_column(&self) -> &'static str {
+        match self {
+            ExportDataset::Trades => "executed_at",
+            ExportDataset::Orders => "created_at",
+            ExportDataset::Alerts => "createdAt",
+            ExportDataset::JournalEntries => "timestamp",
+            ExportDataset::RiskScores => "timestamp",
+            ExportDataset::Events => "timestamp",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DatasetFormat {
+    Csv,
+    JsonLines,
+    Parquet,
+}
+
+impl DatasetFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            DatasetFormat::Csv => "csv",
+            DatasetFormat::JsonLines => "jsonl",
+            DatasetFormat::Parquet => "parquet",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportDatasetRequest {
+    pub dataset: ExportDataset,
+    pub format: DatasetFormat,
+    /// Columns to keep, in output order; all columns when absent.
+    pub columns: Option<Vec<String>>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Defaults to a timestamped file in the profile's exports folder.
+    pub output_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetExport {
+    pub dataset: ExportDataset,
+    pub format: DatasetFormat,
+    pub path: String,
+    pub rows: usize,
+    pub columns: Vec<String>,
+    pub bytes: usize,
+}
+
+fn to_row<T: Serialize>(value: &T) -> Option<DatasetRow> {
+    match serde_json::to_value(value).ok()? {
+        Value::Object(row) => Some(row),
+        _ => None,
+    }
+}
+
+fn trade_row(order: &Order) -> DatasetRow {
+    let row = json!({
+        "order_id": order.id,
+        "wallet_address": order.wallet_address,
+        "order_type": order.order_type.to_string(),
+        "side": order.side.to_string(),
+        "status": order.status.to_string(),
+        "input_mint": order.input_mint,
+        "output_mint": order.output_mint,
+        "input_symbol": order.input_symbol,
+        "output_symbol": order.output_symbol,
+        "filled_amount": order.filled_amount,
+        "limit_price": order.limit_price,
+        "tx_signature": order.tx_signature,
+        "executed_at": order.triggered_at.unwrap_or(order.updated_at).to_rfc3339(),
+    });
+    match row {
+        Value::Object(row) => row,
+        _ => Map::new(),
+    }
+}
+
+async fn load_rows(
+    app: &AppHandle,
+    dataset: ExportDataset,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<Vec<DatasetRow>, String> {
+    let missing = |name: &str| format!("{} are not available", name);
+    match dataset {
+        ExportDataset::Trades | ExportDataset::Orders => {
+            let state = require_state()?;
+            // Trades are filtered on fill time below, so every order is read.
+            let (from, to) = match dataset {
+                ExportDataset::Orders => (from, to),
+                _ => (None, None),
+            };
+            let orders = state
+                .db
+                .read()
+                .await
+                .get_orders_between(from, to)
+                .await
+                .map_err(|e| format!("Failed to read orders: {}", e))?;
+            Ok(match dataset {
+                ExportDataset::Orders => orders.iter().filter_map(to_row).collect(),
+                _ => orders
+                    .iter()
+                    .filter(|order| order.filled_amount > 0.0)
+                    .map(trade_row)
+                    .collect(),
+            })
+        }
+        ExportDataset::Alerts => {
+            let alerts = app
+                .try_state::<SharedAlertManager>()
+                .ok_or_else(|| missing("Alerts"))?;
+            let alerts = alerts
+                .read()
+                .await
+                .list_alerts()
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(alerts.iter().filter_map(to_row).collect())
+        }
+        ExportDataset::JournalEntries => {
+            let journal = app
+                .try_state::<SharedJournalDatabase>()
+                .ok_or_else(|| missing("Journal entries"))?;
+            let filters = JournalFilters {
+                date_range: (from.is_some() || to.is_some()).then(|| DateRange {
+                    start: from.map_or(0, |t| t.timestamp()),
+                    end: to.map_or(i64::MAX, |t| t.timestamp()),
+                }),
+                ..JournalFilters::default()
+            };
+            let entries = journal
+                .read()
+                .await
+                .get_entries(&filters, i64::MAX, 0)
+                .await
+                .map_err(|e| format!("Failed to read journal entries: {}", e))?;
+            Ok(entries.iter().filter_map(to_row).collect())
+        }
+        ExportDataset::RiskScores => {
+            let analyzer = app
+                .try_state::<SharedRiskAnalyzer>()
+                .ok_or_else(|| missing("Risk scores"))?;
+            let scores = analyzer
+                .read()
+                .await
+                .get_scores_between(from, to)
+                .await
+                .map_err(|e| format!("Failed to read risk scores: {}", e))?;
+            Ok(scores.iter().filter_map(to_row).collect())
+        }
+        ExportDataset::Events => {
+            let store = app
+                .try_state::<SharedEventStore>()
+                .ok_or_else(|| missing("Events"))?;
+            let events = store
+                .read()
+                .await
+                .get_events(EventFilter {
+                    aggregate_id: None,
+                    event_type: None,
+                    from_time: from,
+                    to_time: to,
+                    limit: None,
+                    offset: None,
+                })
+                .await
+                .map_err(|e| format!("Failed to read events: {}", e))?;
+            Ok(events
+                .into_iter()
+                .filter_map(|record| {
+                    to_row(&json!({
+                        "id": record.id,
+                        "event_type": record.event_type,
+                        "aggregate_id": record.aggregate_id,
+                        "sequence": record.sequence,
+                        "timestamp": record.timestamp,
+                        "data": serde_json::from_str::<Value>(&record.event_data)
+                            .unwrap_or(Value::String(record.event_data)),
+                    }))
+                })
+                .collect())
+        }
+    }
+}
+
+/// Reads a column value as a time: RFC 3339 or SQLite-style text, or Unix
+/// seconds (milliseconds when implausibly large).
+fn value_time(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::String(text) => DateTime::parse_from_rfc3339(text)
+            .map(|t| t.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
+                    .ok()
+                    .map(|t| Utc.from_utc_datetime(&t))
+            }),
+        Value::Number(number) => {
+            let raw = number.as_i64()?;
+            if raw > 100_000_000_000 {
+                Utc.timestamp_millis_opt(raw).single()
+            } else {
+                Utc.timestamp_opt(raw, 0).single()
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Keeps rows whose timestamp column falls within the range. Rows without
+/// a readable time are kept only when no range is given.
+pub fn filter_by_date(
+    rows: Vec<DatasetRow>,
+    column: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Vec<DatasetRow> {
+    if from.is_none() && to.is_none() {
+        return rows;
+    }
+    rows.into_iter()
+        .filter(|row| {
+            row.get(column).and_then(value_time).is_some_and(|at| {
+                from.map_or(true, |from| at >= from) && to.map_or(true, |to| at <= to)
+            })
+        })
+        .collect()
+}
+
+/// Every column in the rows, in order of first appearance.
+pub fn dataset_columns(rows: &[DatasetRow]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    rows.iter()
+        .flat_map(|row| row.keys())
+        .filter(|column| seen.insert(column.as_str()))
+        .cloned()
+        .collect()
+}
+
+pub fn select_columns(
+    rows: &[DatasetRow],
+    requested: Option<&[String]>,
+) -> Result<Vec<String>, String> {
+    let available = dataset_columns(rows);
+    let Some(requested) = requested.filter(|requested| !requested.is_empty()) else {
+        return Ok(available);
+    };
+    if !rows.is_empty() {
+        let unknown: Vec<&str> = requested
+            .iter()
+            .filter(|column| !available.contains(column))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            return Err(format!(
+                "Unknown column(s) {}; available: {}",
+                unknown.join(", "),
+                available.join(", ")
+            ));
+        }
+    }
+    Ok(requested.to_vec())
+}
+
+fn cell_text(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+pub fn encode_csv(rows: &[DatasetRow], columns: &[String]) -> Vec<u8> {
+    let mut out = columns
+        .iter()
+        .map(|column| csv_field(column))
+        .collect::<Vec<_>>()
+        .join(",");
+    out.push('\n');
+    for row in rows {
+        let line = columns
+            .iter()
+            .map(|column| csv_field(&cell_text(row.get(column)).unwrap_or_default()))
+            .collect::<Vec<_>>()
+            .join(",");
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out.into_bytes()
+}
+
+pub fn encode_json_lines(rows: &[DatasetRow], columns: &[String]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    for row in rows {
+        let selected: Map<String, Value> = columns
+            .iter()
+            .map(|column| {
+                (
+                    column.clone(),
+                    row.get(column).cloned().unwrap_or(Value::Null),
+                )
+            })
+            .collect();
+        serde_json::to_writer(&mut out, &selected).map_err(|e| e.to_string())?;
+        out.push(b'\n');
+    }
+    Ok(out)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    Double,
+    Boolean,
+    Text,
+}
+
+/// A column is numeric or boolean only if every non-null value is.
+fn column_kind(rows: &[DatasetRow], column: &str) -> ColumnKind {
+    let mut values = rows
+        .iter()
+        .filter_map(|row| row.get(column))
+        .filter(|value| !value.is_null())
+        .peekable();
+    if values.peek().is_none() {
+        return ColumnKind::Text;
+    }
+    let values: Vec<&Value> = values.collect();
+    if values.iter().all(|value| value.is_number()) {
+        ColumnKind::Double
+    } else if values.iter().all(|value| value.is_boolean()) {
+        ColumnKind::Boolean
+    } else {
+        ColumnKind::Text
+    }
+}
+
+pub fn encode_parquet(rows: &[DatasetRow], columns: &[String]) -> Result<Vec<u8>, String> {
+    let parquet_error = |e: parquet::errors::ParquetError| format!("Parquet export failed: {}", e);
+    let kinds: Vec<ColumnKind> = columns
+        .iter()
+        .map(|column| column_kind(rows, column))
+        .collect();
+    let fields = columns
+        .iter()
+        .zip(&kinds)
+        .map(|(column, kind)| {
+            let builder = match kind {
+                ColumnKind::Double => Type::primitive_type_builder(column, PhysicalType::DOUBLE),
+                ColumnKind::Boolean => Type::primitive_type_builder(column, PhysicalType::BOOLEAN),
+                ColumnKind::Text => Type::primitive_type_builder(column, PhysicalType::BYTE_ARRAY)
+                    .with_logical_type(Some(LogicalType::String)),
+            };
+            builder
+                .with_repetition(Repetition::OPTIONAL)
+                .build()
+                .map(Arc::new)
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(parquet_error)?;
+    let schema = Type::group_type_builder("dataset")
+        .with_fields(fields)
+        .build()
+        .map_err(parquet_error)?;
+
+    let mut buffer = Vec::new();
+    let mut writer = SerializedFileWriter::new(
+        &mut buffer,
+        Arc::new(schema),
+        Arc::new(WriterProperties::builder().build()),
+    )
+    .map_err(parquet_error)?;
+    let mut row_group = writer.next_row_group().map_err(parquet_error)?;
+    for (column, kind) in columns.iter().zip(&kinds) {
+        let Some(mut writer) = row_group.next_column().map_err(parquet_error)? else {
+            break;
+        };
+        let values: Vec<Option<&Value>> = rows
+            .iter()
+            .map(|row| row.get(column).filter(|value| !value.is_null()))
+            .collect();
+        let levels: Vec<i16> = values
+            .iter()
+            .map(|value| i16::from(value.is_some()))
+            .collect();
+        let written = match kind {
+            ColumnKind::Double => {
+                let present: Vec<f64> =
+                    values.iter().flatten().filter_map(|v| v.as_f64()).collect();
+                writer
+                    .typed::<DoubleType>()
+                    .write_batch(&present, Some(levels.as_slice()), None)
+            }
+            ColumnKind::Boolean => {
+                let present: Vec<bool> = values
+                    .iter()
+                    .flatten()
+                    .filter_map(|v| v.as_bool())
+                    .collect();
+                writer
+                    .typed::<BoolType>()
+                    .write_batch(&present, Some(levels.as_slice()), None)
+            }
+            ColumnKind::Text => {
+                let present: Vec<ByteArray> = values
+                    .iter()
+                    .filter_map(|value| cell_text(*value))
+                    .map(|text| ByteArray::from(text.as_str()))
+                    .collect();
+                writer
+                    .typed::<ByteArrayType>()
+                    .write_batch(&present, Some(levels.as_slice()), None)
+            }
+        };
+        written.map_err(parquet_error)?;
+        writer.close().map_err(parquet_error)?;
+    }
+    row_group.close().map_err(parquet_error)?;
+    writer.close().map_err(parquet_error)?;
+    Ok(buffer)
+}
+
+fn output_path(app: &AppHandle, request: &ExportDatasetRequest) -> Result<PathBuf, String> {
+    if let Some(path) = request
+        .output_path
+        .as_deref()
+        .filter(|p| !p.trim().is_empty())
+    {
+        return Ok(PathBuf::from(path));
+    }
+    let dir = app
+        .path()
+        .profile_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+        .join(EXPORT_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create export directory: {}", e))?;
+    Ok(dir.join(format!(
+        "{}_{}.{}",
+        request.dataset.as_str(),
+        Utc::now().format("%Y%m%d_%H%M%S"),
+        request.format.extension()
+    )))
+}
+
+#[tauri::command]
+pub async fn export_dataset(
+    app: AppHandle,
+    request: ExportDatasetRequest,
+) -> Result<DatasetExport, String> {
+    if let (Some(from), Some(to)) = (request.from, request.to) {
+        if from > to {
+            return Err("Export range starts after it ends".into());
+        }
+    }
+    let rows = load_rows(&app, request.dataset, request.from, request.to).await?;
+    let rows = filter_by_date(
+        rows,
+        request.dataset.timestamp_column(),
+        request.from,
+        request.to,
+    );
+    let columns = select_columns(&rows, request.columns.as_deref())?;
+    let bytes = match request.format {
+        DatasetFormat::Csv => encode_csv(&rows, &columns),
+        DatasetFormat::JsonLines => encode_json_lines(&rows, &columns)?,
+        DatasetFormat::Parquet => encode_parquet(&rows, &columns)?,
+    };
+
+    let path = output_path(&app, &request)?;
+    fs::write(&path, &bytes).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(DatasetExport {
+        dataset: request.dataset,
+        format: request.format,
+        path: path.display().to_string(),
+        rows: rows.len(),
+        columns,
+        bytes: bytes.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows() -> Vec<DatasetRow> {
+        [
+            json!({ "id": "a", "price": 1.5, "note": "plain", "timestamp": "2024-03-01T00:00:00Z" }),
+            json!({ "id": "b", "price": 2, "note": "has, comma \"quoted\"", "timestamp": 1_709_424_000 }),
+            json!({ "id": "c", "note": null, "extra": { "k": 1 }, "timestamp": "2024-04-01 12:00:00" }),
+        ]
+        .into_iter()
+        .filter_map(|value| to_row(&value))
+        .collect()
+    }
+
+    #[test]
+    fn csv_selects_columns_filters_dates_and_quotes() {
+        let from = Utc.with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap();
+        let filtered = filter_by_date(rows(), "timestamp", Some(from), None);
+        assert_eq!(filtered.len(), 2);
+
+        let columns = vec!["id".to_string(), "note".to_string(), "extra".to_string()];
+        let selected = select_columns(&filtered, Some(&columns)).unwrap();
+        let csv = String::from_utf8(encode_csv(&filtered, &selected)).unwrap();
+        assert_eq!(
+            csv,
+            "id,note,extra\nb,\"has, comma \"\"quoted\"\"\",\nc,,\"{\"\"k\"\":1}\"\n"
+        );
+
+        let missing = vec!["nope".to_string()];
+        assert!(select_columns(&filtered, Some(&missing)).is_err());
+        let mut all = dataset_columns(&rows());
+        all.sort();
+        assert_eq!(all, ["extra", "id", "note", "price", "timestamp"]);
+    }
+
+    #[test]
+    fn parquet_and_json_lines_cover_every_row() {
+        let rows = rows();
+        let columns = dataset_columns(&rows);
+        assert_eq!(column_kind(&rows, "price"), ColumnKind::Double);
+        assert_eq!(column_kind(&rows, "timestamp"), ColumnKind::Text);
+
+        let parquet = encode_parquet(&rows, &columns).unwrap();
+        assert_eq!(&parquet[..4], b"PAR1");
+        assert_eq!(&parquet[parquet.len() - 4..], b"PAR1");
+
+        let lines =
+            String::from_utf8(encode_json_lines(&rows, &["id".into(), "price".into()]).unwrap())
+                .unwrap();
+        assert_eq!(
+            lines,
+            "{\"id\":\"a\",\"price\":1.5}\n{\"id\":\"b\",\"price\":2}\n{\"id\":\"c\",\"price\":null}\n"
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::profiles::ProfilePaths;
 use super::counterfactual::{
     compute_hold_counterfactual, CounterfactualRequest, CounterfactualResult,
 };
@@ -33,7 +34,7 @@ impl HistoricalReplayManager {
     ) -> Result<Self, String> {
         let mut db_path = app_handle
             .path()
-            .app_data_dir()
+            .profile_data_dir()
             .map_err(|e| format!("Unable to resolve app data directory: {}", e))?;
 
         std::fs::create_dir_all(&db_path)
@@ -91,6 +92,20 @@ impl HistoricalReplayManager {
             .await
     }
 
+    /// Only what is already stored; unlike `fetch_orderbooks` nothing is
+    /// generated to fill gaps.
+    pub async fn stored_orderbooks(
+        &self,
+        symbol: &str,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<Vec<OrderBookSnapshot>, String> {
+        self.storage
+            .get_orderbook_snapshots(symbol, start_time, end_time)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     pub async fn run_simulation(
         &self,
         payload: SimulationPayload,
@@ -116,6 +131,19 @@ impl HistoricalReplayManager {
         Ok(compute_hold_counterfactual(request, &data))
     }
 
+    pub async fn price_history(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<Vec<HistoricalDataPoint>, String> {
+        self.storage
+            .get_price_data(symbol, interval, start_time, end_time)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     pub async fn get_cache_stats(&self, symbol: &str) -> Result<HashMap<String, u64>, String> {
         self.storage
             .get_cache_stats(symbol)
@@ -1,9 +1,19 @@
 pub mod compression_commands;
 pub mod database;
 pub mod event_store;
+pub mod event_stream;
+pub mod export;
 pub mod historical;
+pub mod retention;
+pub mod sql_console;
+pub mod state_restore;
 
 pub use compression_commands::*;
 pub use database::*;
 pub use event_store::*;
+pub use event_stream::*;
+pub use export::*;
 pub use historical::*;
+pub use retention::*;
+pub use sql_console::*;
+pub use state_restore::*;
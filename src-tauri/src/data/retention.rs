@@ -0,0 +1,669 @@
+use chrono::{DateTime, Utc};
+use parking_lot::{Mutex, RwLock};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection};
+use sqlx::{ConnectOptions, Connection};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::anomalies::SharedAnomalyDetector;
+use crate::security::activity_log::{ActivityLogger, DEFAULT_RETENTION_DAYS};
+
+const CONFIG_FILE: &str = "retention_policies.json";
+const DEFAULT_INTERVAL_HOURS: u32 = 24;
+const BUSY_TIMEOUT_SECS: u64 = 5;
+pub const ACTIVITY_LOG_SUBSYSTEM: &str = "activity_log";
+pub const ANOMALIES_SUBSYSTEM: &str = "anomalies";
+
+/// How a table stores the column its policy ages rows by.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampFormat {
+    /// RFC 3339 or SQLite `datetime()` text.
+    Text,
+    UnixSeconds,
+    UnixMillis,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    pub subsystem: String,
+    /// Database file in the profile directory. `None` for stores the
+    /// subsystem keeps in memory.
+    #[serde(default)]
+    pub database: Option<String>,
+    pub table: String,
+    pub timestamp_column: String,
+    pub timestamp_format: TimestampFormat,
+    pub max_age_days: Option<u32>,
+    pub max_rows: Option<u64>,
+    pub max_size_mb: Option<u64>,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionConfig {
+    pub interval_hours: u32,
+    pub policies: Vec<RetentionPolicy>,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        let policy = |subsystem: &str,
+                      database: Option<&str>,
+                      table: &str,
+                      timestamp_format: TimestampFormat,
+                      max_age_days: Option<u32>,
+                      max_rows: Option<u64>,
+                      max_size_mb: Option<u64>| RetentionPolicy {
+            subsystem: subsystem.to_string(),
+            database: database.map(str::to_string),
+            table: table.to_string(),
+            timestamp_column: "timestamp".to_string(),
+            timestamp_format,
+            max_age_days,
+            max_rows,
+            max_size_mb,
+            enabled: true,
+        };
+
+        Self {
+            interval_hours: DEFAULT_INTERVAL_HOURS,
+            policies: vec![
+                policy(
+                    ACTIVITY_LOG_SUBSYSTEM,
+                    Some("activity_logs.db"),
+                    "activity_logs",
+                    TimestampFormat::Text,
+                    Some(DEFAULT_RETENTION_DAYS as u32),
+                    Some(500_000),
+                    Some(256),
+                ),
+                policy(
+                    "api_health",
+                    Some("api_health.db"),
+                    "health_checks",
+                    TimestampFormat::Text,
+                    Some(30),
+                    Some(1_000_000),
+                    None,
+                ),
+                policy(
+                    "chat_delivery",
+                    Some("chat_integrations.db"),
+                    "delivery_logs",
+                    TimestampFormat::Text,
+                    Some(30),
+                    None,
+                    None,
+                ),
+                policy(
+                    "social",
+                    Some("social_intel.db"),
+                    "social_posts",
+                    TimestampFormat::UnixSeconds,
+                    Some(30),
+                    None,
+                    Some(512),
+                ),
+                policy(
+                    ANOMALIES_SUBSYSTEM,
+                    None,
+                    "anomalies",
+                    TimestampFormat::UnixSeconds,
+                    None,
+                    Some(200),
+                    None,
+                ),
+            ],
+        }
+    }
+}
+
+impl RetentionConfig {
+    pub fn policy(&self, subsystem: &str) -> Option<&RetentionPolicy> {
+        self.policies.iter().find(|p| p.subsystem == subsystem)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.interval_hours == 0 {
+            return Err("Retention interval must be at least one hour".to_string());
+        }
+        for policy in &self.policies {
+            for name in [&policy.table, &policy.timestamp_column] {
+                if !identifier_regex().is_match(name) {
+                    return Err(format!(
+                        "Invalid identifier '{}' in {} retention policy",
+                        name, policy.subsystem
+                    ));
+                }
+            }
+            if let Some(database) = &policy.database {
+                if database.contains(['/', '\\']) || database.starts_with('.') {
+                    return Err(format!(
+                        "Retention policy for {} must name a database in the profile directory",
+                        policy.subsystem
+                    ));
+                }
+            }
+            if policy.max_age_days == Some(0)
+                || policy.max_rows == Some(0)
+                || policy.max_size_mb == Some(0)
+            {
+                return Err(format!(
+                    "Retention limits for {} must be positive; leave a limit unset to disable it",
+                    policy.subsystem
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What a policy removed, or would remove on a dry run. Rows caught by more
+/// than one limit are counted once, under the first limit that reaches them
+/// (age, then row count, then size).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionOutcome {
+    pub subsystem: String,
+    pub database: Option<String>,
+    pub table: String,
+    pub rows: u64,
+    pub size_bytes: u64,
+    pub expired_rows: u64,
+    pub over_row_limit: u64,
+    pub over_size_limit: u64,
+    pub rows_to_delete: u64,
+    pub deleted: u64,
+    pub skipped: Option<String>,
+    pub error: Option<String>,
+}
+
+impl RetentionOutcome {
+    fn new(policy: &RetentionPolicy) -> Self {
+        Self {
+            subsystem: policy.subsystem.clone(),
+            database: policy.database.clone(),
+            table: policy.table.clone(),
+            rows: 0,
+            size_bytes: 0,
+            expired_rows: 0,
+            over_row_limit: 0,
+            over_size_limit: 0,
+            rows_to_delete: 0,
+            deleted: 0,
+            skipped: None,
+            error: None,
+        }
+    }
+
+    /// Splits the rows left after age pruning between the row and size
+    /// limits.
+    fn apply_limits(&mut self, policy: &RetentionPolicy) {
+        let remaining = self.rows.saturating_sub(self.expired_rows);
+        self.over_row_limit = policy
+            .max_rows
+            .map_or(0, |max| remaining.saturating_sub(max));
+
+        let remaining = remaining - self.over_row_limit;
+        self.over_size_limit = match policy.max_size_mb {
+            Some(max_mb) if self.rows > 0 && self.size_bytes > 0 => {
+                let bytes_per_row = (self.size_bytes as f64 / self.rows as f64).max(1.0);
+                let keep = ((max_mb * 1024 * 1024) as f64 / bytes_per_row).floor() as u64;
+                remaining.saturating_sub(keep)
+            }
+            _ => 0,
+        };
+        self.rows_to_delete = self.expired_rows + self.over_row_limit + self.over_size_limit;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionReport {
+    pub generated_at: DateTime<Utc>,
+    pub dry_run: bool,
+    pub outcomes: Vec<RetentionOutcome>,
+}
+
+impl RetentionReport {
+    pub fn total_rows_to_delete(&self) -> u64 {
+        self.outcomes.iter().map(|o| o.rows_to_delete).sum()
+    }
+}
+
+fn identifier_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap())
+}
+
+/// SQL predicate matching rows older than `days`, for the column's format.
+fn expired_predicate(policy: &RetentionPolicy, days: u32) -> String {
+    let column = &policy.timestamp_column;
+    match policy.timestamp_format {
+        TimestampFormat::Text => {
+            format!("julianday({column}) < julianday('now', '-{days} days')")
+        }
+        TimestampFormat::UnixSeconds => {
+            format!("{column} < CAST(strftime('%s', 'now', '-{days} days') AS INTEGER)")
+        }
+        TimestampFormat::UnixMillis => {
+            format!("{column} < CAST(strftime('%s', 'now', '-{days} days') AS INTEGER) * 1000")
+        }
+    }
+}
+
+async fn connect(path: &Path) -> Result<SqliteConnection, String> {
+    let mut options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(false)
+        .busy_timeout(Duration::from_secs(BUSY_TIMEOUT_SECS));
+    options.disable_statement_logging();
+    options
+        .connect()
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+}
+
+/// Bytes the table occupies, from the `dbstat` virtual table. SQLite builds
+/// without it report zero, which leaves size limits inactive rather than
+/// charging the whole file to one table.
+async fn table_size_bytes(conn: &mut SqliteConnection, table: &str) -> u64 {
+    sqlx::query_scalar::<_, i64>("SELECT COALESCE(SUM(pgsize), 0) FROM dbstat WHERE name = ?")
+        .bind(table)
+        .fetch_one(&mut *conn)
+        .await
+        .map_or(0, |bytes| bytes.max(0) as u64)
+}
+
+async fn enforce_table(
+    conn: &mut SqliteConnection,
+    policy: &RetentionPolicy,
+    outcome: &mut RetentionOutcome,
+    dry_run: bool,
+) -> Result<(), sqlx::Error> {
+    let exists: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(&policy.table)
+            .fetch_one(&mut *conn)
+            .await?;
+    if exists == 0 {
+        outcome.skipped = Some("table not found".to_string());
+        return Ok(());
+    }
+
+    let table = &policy.table;
+    outcome.rows = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM {table}"))
+        .fetch_one(&mut *conn)
+        .await?
+        .max(0) as u64;
+    outcome.size_bytes = table_size_bytes(conn, table).await;
+
+    let expired = policy
+        .max_age_days
+        .map(|days| expired_predicate(policy, days));
+    if let Some(predicate) = &expired {
+        outcome.expired_rows = sqlx::query_scalar::<_, i64>(&format!(
+            "SELECT COUNT(*) FROM {table} WHERE {predicate}"
+        ))
+        .fetch_one(&mut *conn)
+        .await?
+        .max(0) as u64;
+    }
+    outcome.apply_limits(policy);
+
+    if dry_run || outcome.rows_to_delete == 0 {
+        return Ok(());
+    }
+
+    let mut tx = conn.begin().await?;
+    if let Some(predicate) = &expired {
+        outcome.deleted += sqlx::query(&format!("DELETE FROM {table} WHERE {predicate}"))
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    }
+    let oldest = outcome.over_row_limit + outcome.over_size_limit;
+    if oldest > 0 {
+        let column = &policy.timestamp_column;
+        outcome.deleted += sqlx::query(&format!(
+            "DELETE FROM {table} WHERE rowid IN \
+             (SELECT rowid FROM {table} ORDER BY {column} ASC LIMIT ?)"
+        ))
+        .bind(oldest as i64)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Applies one on-disk policy to its table.
+pub async fn enforce_database_policy(
+    data_dir: &Path,
+    policy: &RetentionPolicy,
+    dry_run: bool,
+) -> RetentionOutcome {
+    let mut outcome = RetentionOutcome::new(policy);
+    let Some(database) = &policy.database else {
+        outcome.skipped = Some("not stored in a database".to_string());
+        return outcome;
+    };
+    let path = data_dir.join(database);
+    if !path.exists() {
+        outcome.skipped = Some("database not found".to_string());
+        return outcome;
+    }
+
+    match connect(&path).await {
+        Ok(mut conn) => {
+            if let Err(e) = enforce_table(&mut conn, policy, &mut outcome, dry_run).await {
+                outcome.error = Some(e.to_string());
+            }
+            let _ = conn.close().await;
+        }
+        Err(e) => outcome.error = Some(e),
+    }
+    outcome
+}
+
+/// Holds the retention policies of every subsystem and prunes their
+/// stores on a schedule, replacing the per-subsystem cleanup loops.
+pub struct RetentionService {
+    data_dir: PathBuf,
+    config: RwLock<RetentionConfig>,
+    running: AtomicBool,
+    last_report: Mutex<Option<RetentionReport>>,
+}
+
+pub type SharedRetentionService = Arc<RetentionService>;
+
+impl RetentionService {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let config = std::fs::read_to_string(data_dir.join(CONFIG_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            data_dir,
+            config: RwLock::new(config),
+            running: AtomicBool::new(false),
+            last_report: Mutex::new(None),
+        }
+    }
+
+    pub fn config(&self) -> RetentionConfig {
+        self.config.read().clone()
+    }
+
+    pub fn last_report(&self) -> Option<RetentionReport> {
+        self.last_report.lock().clone()
+    }
+
+    pub fn update_config(&self, config: RetentionConfig) -> Result<(), String> {
+        config.validate()?;
+        std::fs::create_dir_all(&self.data_dir)
+            .map_err(|e| format!("Failed to create profile directory: {e}"))?;
+        let content = serde_json::to_string_pretty(&config)
+            .map_err(|e| format!("Failed to serialize retention policies: {e}"))?;
+        std::fs::write(self.data_dir.join(CONFIG_FILE), content)
+            .map_err(|e| format!("Failed to write retention policies: {e}"))?;
+        *self.config.write() = config;
+        Ok(())
+    }
+
+    /// Keeps a subsystem's own age setting and its policy in step, for
+    /// subsystems that expose their retention in their own settings.
+    pub fn set_max_age_days(&self, subsystem: &str, days: u32) -> Result<(), String> {
+        let mut config = self.config();
+        let Some(policy) = config
+            .policies
+            .iter_mut()
+            .find(|p| p.subsystem == subsystem)
+        else {
+            return Ok(());
+        };
+        if policy.max_age_days == Some(days) {
+            return Ok(());
+        }
+        policy.max_age_days = Some(days);
+        self.update_config(config)
+    }
+
+    /// Pushes limits to subsystems that trim in-memory stores themselves.
+    pub async fn apply_live_limits(&self, app: &AppHandle) {
+        let config = self.config();
+        if let (Some(policy), Some(detector)) = (
+            config.policy(ANOMALIES_SUBSYSTEM),
+            app.try_state::<SharedAnomalyDetector>(),
+        ) {
+            let max = policy
+                .max_rows
+                .filter(|_| policy.enabled)
+                .map_or(usize::MAX, |max| max as usize);
+            detector.write().await.set_max_anomalies(max);
+        }
+        if let (Some(days), Some(logger)) = (
+            config
+                .policy(ACTIVITY_LOG_SUBSYSTEM)
+                .and_then(|policy| policy.max_age_days),
+            app.try_state::<ActivityLogger>(),
+        ) {
+            if let Err(e) = logger.set_retention_days(days as i64) {
+                eprintln!("Failed to sync activity log retention: {}", e);
+            }
+        }
+    }
+
+    async fn enforce_in_memory(
+        &self,
+        app: &AppHandle,
+        policy: &RetentionPolicy,
+        dry_run: bool,
+    ) -> RetentionOutcome {
+        let mut outcome = RetentionOutcome::new(policy);
+        match policy.subsystem.as_str() {
+            ANOMALIES_SUBSYSTEM => {
+                let Some(detector) = app.try_state::<SharedAnomalyDetector>() else {
+                    outcome.skipped = Some("anomaly detector not running".to_string());
+                    return outcome;
+                };
+                let max_age_secs = policy.max_age_days.map(|days| days as i64 * 86_400);
+                let mut detector = detector.write().await;
+                outcome.rows = detector.anomaly_count() as u64;
+                outcome.expired_rows = detector.count_older_than(max_age_secs) as u64;
+                outcome.apply_limits(policy);
+                if !dry_run && outcome.rows_to_delete > 0 {
+                    outcome.deleted = detector.prune_anomalies(
+                        max_age_secs,
+                        (outcome.rows - outcome.rows_to_delete) as usize,
+                    ) as u64;
+                }
+            }
+            _ => outcome.skipped = Some("no in-memory store for subsystem".to_string()),
+        }
+        outcome
+    }
+
+    /// Applies every enabled policy. With `dry_run` nothing is deleted and
+    /// the report lists what would be.
+    pub async fn run(&self, app: &AppHandle, dry_run: bool) -> Result<RetentionReport, String> {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Err("Retention pruning is already running".to_string());
+        }
+
+        let config = self.config();
+        let mut outcomes = Vec::new();
+        for policy in config.policies.iter().filter(|p| p.enabled) {
+            let outcome = if policy.database.is_some() {
+                enforce_database_policy(&self.data_dir, policy, dry_run).await
+            } else {
+                self.enforce_in_memory(app, policy, dry_run).await
+            };
+            outcomes.push(outcome);
+        }
+        self.running.store(false, Ordering::SeqCst);
+
+        let report = RetentionReport {
+            generated_at: Utc::now(),
+            dry_run,
+            outcomes,
+        };
+        if !dry_run {
+            *self.last_report.lock() = Some(report.clone());
+        }
+        Ok(report)
+    }
+}
+
+pub fn start_retention_pruning(app: AppHandle, service: SharedRetentionService) {
+    tauri::async_runtime::spawn(async move {
+        service.apply_live_limits(&app).await;
+        loop {
+            match service.run(&app, false).await {
+                Ok(report) => {
+                    let deleted: u64 = report.outcomes.iter().map(|o| o.deleted).sum();
+                    if deleted > 0 {
+                        tracing::info!("Retention pruning removed {} rows", deleted);
+                    }
+                }
+                Err(e) => eprintln!("Retention pruning failed: {}", e),
+            }
+            let hours = service.config().interval_hours.max(1) as u64;
+            tokio::time::sleep(Duration::from_secs(hours * 60 * 60)).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_retention_policies(
+    service: State<'_, SharedRetentionService>,
+) -> Result<RetentionConfig, String> {
+    Ok(service.config())
+}
+
+#[tauri::command]
+pub async fn update_retention_policies(
+    app: AppHandle,
+    config: RetentionConfig,
+    service: State<'_, SharedRetentionService>,
+) -> Result<RetentionConfig, String> {
+    service.update_config(config)?;
+    service.apply_live_limits(&app).await;
+    Ok(service.config())
+}
+
+/// Dry run: reports what the current policies would delete.
+#[tauri::command]
+pub async fn preview_retention(
+    app: AppHandle,
+    service: State<'_, SharedRetentionService>,
+) -> Result<RetentionReport, String> {
+    service.run(&app, true).await
+}
+
+#[tauri::command]
+pub async fn run_retention_now(
+    app: AppHandle,
+    service: State<'_, SharedRetentionService>,
+) -> Result<RetentionReport, String> {
+    service.run(&app, false).await
+}
+
+#[tauri::command]
+pub async fn get_last_retention_report(
+    service: State<'_, SharedRetentionService>,
+) -> Result<Option<RetentionReport>, String> {
+    Ok(service.last_report())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(max_age_days: Option<u32>, max_rows: Option<u64>) -> RetentionPolicy {
+        RetentionPolicy {
+            subsystem: "test".to_string(),
+            database: Some("retention.db".to_string()),
+            table: "events".to_string(),
+            timestamp_column: "timestamp".to_string(),
+            timestamp_format: TimestampFormat::UnixSeconds,
+            max_age_days,
+            max_rows,
+            max_size_mb: None,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn limits_do_not_double_count_rows() {
+        let mut outcome = RetentionOutcome::new(&policy(Some(30), Some(50)));
+        outcome.rows = 100;
+        outcome.expired_rows = 20;
+        outcome.apply_limits(&policy(Some(30), Some(50)));
+        assert_eq!(outcome.over_row_limit, 30);
+        assert_eq!(outcome.rows_to_delete, 50);
+
+        let mut sized = policy(None, None);
+        sized.max_size_mb = Some(1);
+        let mut outcome = RetentionOutcome::new(&sized);
+        outcome.rows = 4_096;
+        outcome.size_bytes = 4 * 1024 * 1024;
+        outcome.apply_limits(&sized);
+        assert_eq!(outcome.over_size_limit, 3_072);
+    }
+
+    #[test]
+    fn invalid_identifiers_are_rejected() {
+        let mut config = RetentionConfig::default();
+        assert!(config.validate().is_ok());
+        config.policies[0].table = "logs; DROP TABLE wallets".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn dry_run_reports_without_deleting() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("retention.db");
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .create_if_missing(true);
+        let mut conn = options.connect().await.unwrap();
+        sqlx::query("CREATE TABLE events (id INTEGER PRIMARY KEY, timestamp INTEGER NOT NULL)")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        let now = Utc::now().timestamp();
+        for i in 0..10i64 {
+            // Four rows are older than 30 days.
+            let age_days = if i < 4 { 40 } else { 1 };
+            sqlx::query("INSERT INTO events (timestamp) VALUES (?)")
+                .bind(now - age_days * 86_400 - i)
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+        conn.close().await.unwrap();
+
+        let policy = policy(Some(30), Some(5));
+        let preview = enforce_database_policy(dir.path(), &policy, true).await;
+        assert_eq!(preview.error, None);
+        assert_eq!(preview.expired_rows, 4);
+        assert_eq!(preview.over_row_limit, 1);
+        assert_eq!(preview.deleted, 0);
+
+        let applied = enforce_database_policy(dir.path(), &policy, false).await;
+        assert_eq!(applied.deleted, 5);
+        let after = enforce_database_policy(dir.path(), &policy, true).await;
+        assert_eq!(after.rows, 5);
+        assert_eq!(after.rows_to_delete, 0);
+    }
+}
@@ -0,0 +1,325 @@
+//! Read-only SQL console over the app's local databases.
+//!
+//! Queries are checked twice: a lexical pass rejects anything but a single
+//! SELECT (or WITH ... SELECT) statement with a readable error, and the
+//! database itself is opened read-only so whatever slips past the check
+//! still cannot write. Results are paged by wrapping the statement in a
+//! LIMIT/OFFSET subquery.
+
+use base64::{engine::general_purpose::STANDARD as BASE64_ENGINE, Engine};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection, SqliteRow};
+use sqlx::{Column, ConnectOptions, Connection, Row, TypeInfo, ValueRef};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::profiles::ProfilePaths;
+
+const BUSY_TIMEOUT_SECS: u64 = 5;
+const QUERY_TIMEOUT: Duration = Duration::from_secs(15);
+const DEFAULT_PAGE_SIZE: u32 = 100;
+const MAX_PAGE_SIZE: u32 = 1000;
+
+/// Keywords that only appear in statements which change the database or
+/// the connection. `REPLACE` is handled separately because it is also a
+/// string function.
+const BLOCKED_KEYWORDS: &[&str] = &[
+    "INSERT",
+    "UPDATE",
+    "DELETE",
+    "UPSERT",
+    "DROP",
+    "CREATE",
+    "ALTER",
+    "ATTACH",
+    "DETACH",
+    "PRAGMA",
+    "VACUUM",
+    "REINDEX",
+    "ANALYZE",
+    "BEGIN",
+    "COMMIT",
+    "ROLLBACK",
+    "SAVEPOINT",
+    "RELEASE",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryDatabase {
+    Journal,
+    Performance,
+    Events,
+    Multisig,
+}
+
+impl QueryDatabase {
+    fn file_name(&self) -> &'static str {
+        match self {
+            QueryDatabase::Journal => "journal.db",
+            QueryDatabase::Performance => "performance.db",
+            QueryDatabase::Events => "events.db",
+            QueryDatabase::Multisig => "multisig.db",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlQueryRequest {
+    pub database: QueryDatabase,
+    pub sql: String,
+    /// Zero-based page index.
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlQueryResult {
+    pub database: QueryDatabase,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    pub page: u32,
+    pub page_size: u32,
+    pub has_more: bool,
+    pub elapsed_ms: u64,
+}
+
+/// Splits SQL into upper-cased bare words and punctuation, each with its
+/// character offset, dropping string literals, quoted identifiers and
+/// comments. Fails on unterminated quotes or comments.
+fn sql_tokens(sql: &str) -> Result<Vec<(String, usize)>, String> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '-' && chars.get(i + 1) == Some(&'-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            let end = (i + 2..chars.len().saturating_sub(1))
+                .find(|&j| chars[j] == '*' && chars[j + 1] == '/')
+                .ok_or("Unterminated comment")?;
+            i = end + 2;
+        } else if matches!(c, '\'' | '"' | '`' | '[') {
+            let close = if c == '[' { ']' } else { c };
+            let mut j = i + 1;
+            loop {
+                match chars.get(j) {
+                    None => return Err("Unterminated quote".into()),
+                    // A doubled quote is an escaped quote inside the literal.
+                    Some(&ch)
+                        if ch == close && close != ']' && chars.get(j + 1) == Some(&close) =>
+                    {
+                        j += 2
+                    }
+                    Some(&ch) if ch == close => break,
+                    Some(_) => j += 1,
+                }
+            }
+            // Literals and quoted names are opaque, but keep a placeholder
+            // so the statement shape is preserved.
+            tokens.push(("?".into(), i));
+            i = j + 1;
+        } else if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((
+                chars[start..i].iter().collect::<String>().to_uppercase(),
+                start,
+            ));
+        } else {
+            tokens.push((c.to_string(), i));
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+/// Accepts a single read-only statement and returns it without any
+/// trailing semicolon.
+pub fn check_read_only(sql: &str) -> Result<String, String> {
+    let tokens = sql_tokens(sql)?;
+    let statement_end = tokens.iter().position(|(token, _)| token == ";");
+    if let Some(end) = statement_end {
+        if tokens[end + 1..].iter().any(|(token, _)| token != ";") {
+            return Err("Only one statement can be run at a time".into());
+        }
+    }
+    let statement: String = match statement_end {
+        Some(end) => sql.chars().take(tokens[end].1).collect(),
+        None => sql.to_string(),
+    };
+    let tokens: Vec<String> = tokens
+        .into_iter()
+        .take(statement_end.unwrap_or(usize::MAX))
+        .map(|(token, _)| token)
+        .collect();
+    match tokens.first().map(String::as_str) {
+        Some("SELECT") | Some("WITH") => {}
+        Some(_) => return Err("Only SELECT queries are allowed".into()),
+        None => return Err("Query is empty".into()),
+    }
+    for (index, token) in tokens.iter().enumerate() {
+        if BLOCKED_KEYWORDS.contains(&token.as_str()) {
+            return Err(format!("{} is not allowed in the SQL console", token));
+        }
+        if token == "REPLACE" && tokens.get(index + 1).is_some_and(|next| next == "INTO") {
+            return Err("REPLACE is not allowed in the SQL console".into());
+        }
+    }
+
+    Ok(statement.trim().to_string())
+}
+
+fn paged_sql(statement: &str, page: u32, page_size: u32) -> String {
+    // The line break keeps a trailing `--` comment from swallowing the
+    // closing parenthesis.
+    format!(
+        "SELECT * FROM (\n{}\n) LIMIT {} OFFSET {}",
+        statement,
+        page_size + 1,
+        u64::from(page) * u64::from(page_size)
+    )
+}
+
+fn cell_value(row: &SqliteRow, index: usize) -> Value {
+    let Ok(raw) = row.try_get_raw(index) else {
+        return Value::Null;
+    };
+    if raw.is_null() {
+        return Value::Null;
+    }
+    // SQLite columns are dynamically typed, so decode by the value's own
+    // storage class rather than the declared column type.
+    let decoded = match raw.type_info().name() {
+        "INTEGER" | "BOOLEAN" => row.try_get_unchecked::<i64, _>(index).map(Value::from),
+        "REAL" => row.try_get_unchecked::<f64, _>(index).map(Value::from),
+        "BLOB" => row
+            .try_get_unchecked::<Vec<u8>, _>(index)
+            .map(|bytes| Value::String(BASE64_ENGINE.encode(bytes))),
+        _ => row.try_get_unchecked::<String, _>(index).map(Value::String),
+    };
+    decoded.unwrap_or(Value::Null)
+}
+
+async fn connect_read_only(path: &Path) -> Result<SqliteConnection, String> {
+    if !path.exists() {
+        return Err(format!("{} has not been created yet", path.display()));
+    }
+    let mut options = SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(true)
+        .create_if_missing(false)
+        .busy_timeout(Duration::from_secs(BUSY_TIMEOUT_SECS));
+    options.disable_statement_logging();
+    options
+        .connect()
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+}
+
+#[tauri::command]
+pub async fn data_query_sql(
+    app: AppHandle,
+    request: SqlQueryRequest,
+) -> Result<SqlQueryResult, String> {
+    let statement = check_read_only(&request.sql)?;
+    let page = request.page.unwrap_or(0);
+    let page_size = request
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let path = app
+        .path()
+        .profile_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+        .join(request.database.file_name());
+    let mut conn = connect_read_only(&path).await?;
+
+    let started = Instant::now();
+    let sql = paged_sql(&statement, page, page_size);
+    let fetched = tokio::time::timeout(QUERY_TIMEOUT, sqlx::query(&sql).fetch_all(&mut conn))
+        .await
+        .map_err(|_| format!("Query timed out after {}s", QUERY_TIMEOUT.as_secs()))?
+        .map_err(|e| format!("Query failed: {}", e));
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    let _ = conn.close().await;
+    let mut fetched = fetched?;
+
+    let has_more = fetched.len() > page_size as usize;
+    fetched.truncate(page_size as usize);
+    let columns = fetched
+        .first()
+        .map(|row| {
+            row.columns()
+                .iter()
+                .map(|column| column.name().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    let rows = fetched
+        .iter()
+        .map(|row| (0..row.len()).map(|index| cell_value(row, index)).collect())
+        .collect();
+
+    Ok(SqlQueryResult {
+        database: request.database,
+        columns,
+        rows,
+        page,
+        page_size,
+        has_more,
+        elapsed_ms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_single_select_statements() {
+        assert_eq!(
+            check_read_only("  SELECT * FROM journal_entries; -- all of them").unwrap(),
+            "SELECT * FROM journal_entries"
+        );
+        assert!(check_read_only(
+            "WITH recent AS (SELECT * FROM events) SELECT replace(event_type, '_', ' ') FROM recent"
+        )
+        .is_ok());
+        // Keywords inside literals, quoted names and comments are ignored.
+        assert!(
+            check_read_only("SELECT 'drop table x; --', \"update\" FROM t /* delete */").is_ok()
+        );
+    }
+
+    #[test]
+    fn blocks_writes_and_multiple_statements() {
+        for sql in [
+            "DELETE FROM events",
+            "SELECT 1; DROP TABLE events",
+            "WITH x AS (SELECT 1) INSERT INTO t SELECT * FROM x",
+            "WITH x AS (SELECT 1) REPLACE INTO t SELECT * FROM x",
+            "PRAGMA journal_mode",
+            "SELECT 'unterminated",
+            "   ",
+        ] {
+            assert!(check_read_only(sql).is_err(), "{sql} should be rejected");
+        }
+        assert_eq!(
+            paged_sql("SELECT 1 -- note", 2, 50),
+            "SELECT * FROM (\nSELECT 1 -- note\n) LIMIT 51 OFFSET 100"
+        );
+    }
+}
@@ -0,0 +1,668 @@
+//! Rebuilds portfolio, order and alert state from an event-store snapshot.
+//!
+//! `capture_state_snapshot` stores the three managers' state together with
+//! the event cursor at that moment. Restoring loads the snapshot, replays
+//! the order, position and trade events recorded after that cursor, and
+//! diffs the result against live state. A dry run stops at the diff;
+//! otherwise the managers are overwritten with the restored state. The
+//! store has no alert events, so alerts come back exactly as captured.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use tauri::{AppHandle, Manager, State};
+
+use crate::alerts::{PriceAlert, SharedAlertManager};
+use crate::data::event_store::{Event, SharedEventStore, StreamedEvent};
+use crate::portfolio::{PortfolioDataState, Position, SharedPortfolioData};
+use crate::trading::limit_orders::require_state;
+use crate::trading::types::{Order, OrderStatus};
+
+/// Aggregate that restorable snapshots are stored under.
+pub const STATE_SNAPSHOT_AGGREGATE: &str = "app_state";
+const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+const REPLAY_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedState {
+    pub positions: Vec<Position>,
+    pub orders: Vec<Order>,
+    pub alerts: Vec<PriceAlert>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedStateSnapshot {
+    pub version: u32,
+    pub captured_at: DateTime<Utc>,
+    /// Cursor of the newest event when the snapshot was taken.
+    pub event_cursor: i64,
+    #[serde(flatten)]
+    pub state: ManagedState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateSnapshotInfo {
+    pub id: String,
+    pub captured_at: DateTime<Utc>,
+    pub event_cursor: i64,
+    pub positions: usize,
+    pub orders: usize,
+    pub alerts: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateChange {
+    pub kind: ChangeKind,
+    pub id: String,
+    /// Top-level fields that differ; empty for additions and removals.
+    pub fields: Vec<String>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateDiff {
+    pub positions: Vec<StateChange>,
+    pub orders: Vec<StateChange>,
+    pub alerts: Vec<StateChange>,
+}
+
+impl StateDiff {
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty() && self.orders.is_empty() && self.alerts.is_empty()
+    }
+}
+
+/// An event that touches restored state but could not be applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedEvent {
+    pub cursor: i64,
+    pub event_type: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+    pub snapshot_id: String,
+    pub snapshot_captured_at: DateTime<Utc>,
+    pub dry_run: bool,
+    pub events_applied: usize,
+    /// Events after the snapshot that do not affect restored state, such as
+    /// setting or wallet connection changes.
+    pub events_ignored: usize,
+    pub skipped: Vec<SkippedEvent>,
+    pub diff: StateDiff,
+}
+
+/// Open positions seen during replay, so a later close knows what it
+/// closes.
+#[derive(Debug, Default)]
+struct ReplayContext {
+    opened_positions: HashMap<String, (String, f64)>,
+}
+
+enum ReplayOutcome {
+    Applied,
+    Ignored,
+    Skipped(String),
+}
+
+fn find_position<'a>(positions: &'a mut [Position], token: &str) -> Option<&'a mut Position> {
+    positions
+        .iter_mut()
+        .find(|p| p.symbol.eq_ignore_ascii_case(token) || p.mint == token)
+}
+
+fn new_position(symbol: &str, amount: f64, price: f64) -> Position {
+    Position {
+        symbol: symbol.to_string(),
+        mint: String::new(),
+        amount,
+        current_price: price,
+        avg_entry_price: price,
+        total_value: 0.0,
+        unrealized_pnl: 0.0,
+        unrealized_pnl_percent: 0.0,
+        allocation: 0.0,
+    }
+}
+
+/// Applies one event to the state being rebuilt. `stored_orders` supplies
+/// the full order for placements that happened after the snapshot, since
+/// the event itself only carries a summary.
+fn apply_event(
+    state: &mut ManagedState,
+    context: &mut ReplayContext,
+    stored_orders: &HashMap<String, Order>,
+    event: &Event,
+) -> ReplayOutcome {
+    match event {
+        Event::OrderPlaced {
+            order_id,
+            timestamp,
+            ..
+        } => {
+            if state.orders.iter().any(|o| &o.id == order_id) {
+                return ReplayOutcome::Applied;
+            }
+            let Some(stored) = stored_orders.get(order_id) else {
+                return ReplayOutcome::Skipped(format!(
+                    "Order {} is no longer in the order database",
+                    order_id
+                ));
+            };
+            let mut order = stored.clone();
+            order.status = OrderStatus::Pending;
+            order.filled_amount = 0.0;
+            order.triggered_at = None;
+            order.tx_signature = None;
+            order.error_message = None;
+            order.updated_at = *timestamp;
+            state.orders.push(order);
+            ReplayOutcome::Applied
+        }
+        Event::OrderFilled {
+            order_id,
+            filled_quantity,
+            timestamp,
+            ..
+        } => match state.orders.iter_mut().find(|o| &o.id == order_id) {
+            Some(order) => {
+                order.filled_amount = *filled_quantity;
+                order.status = if *filled_quantity >= order.amount {
+                    OrderStatus::Filled
+                } else {
+                    OrderStatus::PartiallyFilled
+                };
+                order.triggered_at = Some(*timestamp);
+                order.updated_at = *timestamp;
+                ReplayOutcome::Applied
+            }
+            None => ReplayOutcome::Skipped(format!("Order {} is unknown", order_id)),
+        },
+        Event::OrderCancelled {
+            order_id,
+            timestamp,
+            ..
+        } => match state.orders.iter_mut().find(|o| &o.id == order_id) {
+            Some(order) => {
+                order.status = OrderStatus::Cancelled;
+                order.updated_at = *timestamp;
+                ReplayOutcome::Applied
+            }
+            None => ReplayOutcome::Skipped(format!("Order {} is unknown", order_id)),
+        },
+        Event::PositionOpened {
+            position_id,
+            symbol,
+            quantity,
+            entry_price,
+            ..
+        } => {
+            context
+                .opened_positions
+                .insert(position_id.clone(), (symbol.clone(), *quantity));
+            match find_position(&mut state.positions, symbol) {
+                Some(position) => {
+                    let amount = position.amount + quantity;
+                    if amount > f64::EPSILON {
+                        position.avg_entry_price = (position.avg_entry_price * position.amount
+                            + entry_price * quantity)
+                            / amount;
+                    }
+                    position.amount = amount;
+                }
+                None => state
+                    .positions
+                    .push(new_position(symbol, *quantity, *entry_price)),
+            }
+            ReplayOutcome::Applied
+        }
+        Event::PositionClosed {
+            position_id,
+            exit_price,
+            ..
+        } => {
+            let Some((symbol, quantity)) = context.opened_positions.remove(position_id) else {
+                return ReplayOutcome::Skipped(format!(
+                    "Position {} was opened before the snapshot",
+                    position_id
+                ));
+            };
+            match find_position(&mut state.positions, &symbol) {
+                Some(position) => {
+                    position.amount = (position.amount - quantity).max(0.0);
+                    position.current_price = *exit_price;
+                    ReplayOutcome::Applied
+                }
+                None => ReplayOutcome::Skipped(format!("No position for {}", symbol)),
+            }
+        }
+        Event::TradeExecuted {
+            from_token,
+            to_token,
+            from_amount,
+            to_amount,
+            ..
+        } => {
+            let Some(from) = find_position(&mut state.positions, from_token) else {
+                return ReplayOutcome::Skipped(format!("No position for {}", from_token));
+            };
+            from.amount = (from.amount - from_amount).max(0.0);
+            let spent_value = from.current_price * from_amount;
+            match find_position(&mut state.positions, to_token) {
+                Some(to) => to.amount += to_amount,
+                None => {
+                    let price = if *to_amount > f64::EPSILON {
+                        spent_value / to_amount
+                    } else {
+                        0.0
+                    };
+                    state
+                        .positions
+                        .push(new_position(to_token, *to_amount, price));
+                }
+            }
+            ReplayOutcome::Applied
+        }
+        // Balances are tracked per wallet, while portfolio positions are
+        // aggregated, so a single balance cannot be mapped onto them.
+        Event::BalanceChanged { .. }
+        | Event::SettingChanged { .. }
+        | Event::WalletConnected { .. }
+        | Event::WalletDisconnected { .. } => ReplayOutcome::Ignored,
+    }
+}
+
+/// Positions keep live prices where the token is still held, so the
+/// restore brings back holdings rather than stale market data.
+fn reprice_positions(restored: Vec<Position>, current: &[Position]) -> Vec<Position> {
+    let mut positions = restored;
+    for position in positions.iter_mut() {
+        if let Some(live) = current.iter().find(|p| p.symbol == position.symbol) {
+            position.current_price = live.current_price;
+            if position.mint.is_empty() {
+                position.mint = live.mint.clone();
+            }
+        }
+    }
+    let mut recalculated = PortfolioDataState::new();
+    recalculated.replace_positions(positions);
+    recalculated.positions()
+}
+
+fn diff_by_id<T: Serialize>(
+    current: &[T],
+    restored: &[T],
+    id: impl Fn(&T) -> String,
+) -> Vec<StateChange> {
+    let to_value = |item: &T| serde_json::to_value(item).unwrap_or(Value::Null);
+    let current: HashMap<String, Value> = current.iter().map(|i| (id(i), to_value(i))).collect();
+    let restored: HashMap<String, Value> = restored.iter().map(|i| (id(i), to_value(i))).collect();
+
+    let ids: BTreeSet<&String> = current.keys().chain(restored.keys()).collect();
+    ids.into_iter()
+        .filter_map(|key| match (current.get(key), restored.get(key)) {
+            (Some(before), Some(after)) if before != after => {
+                let mut fields: Vec<String> = match (before, after) {
+                    (Value::Object(b), Value::Object(a)) => b
+                        .keys()
+                        .chain(a.keys())
+                        .filter(|field| b.get(*field) != a.get(*field))
+                        .cloned()
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                fields.sort();
+                fields.dedup();
+                Some(StateChange {
+                    kind: ChangeKind::Modified,
+                    id: key.clone(),
+                    fields,
+                    before: Some(before.clone()),
+                    after: Some(after.clone()),
+                })
+            }
+            (Some(_), Some(_)) => None,
+            (Some(before), None) => Some(StateChange {
+                kind: ChangeKind::Removed,
+                id: key.clone(),
+                fields: Vec::new(),
+                before: Some(before.clone()),
+                after: None,
+            }),
+            (None, Some(after)) => Some(StateChange {
+                kind: ChangeKind::Added,
+                id: key.clone(),
+                fields: Vec::new(),
+                before: None,
+                after: Some(after.clone()),
+            }),
+            (None, None) => None,
+        })
+        .collect()
+}
+
+fn diff_state(current: &ManagedState, restored: &ManagedState) -> StateDiff {
+    StateDiff {
+        positions: diff_by_id(&current.positions, &restored.positions, |p| {
+            p.symbol.clone()
+        }),
+        orders: diff_by_id(&current.orders, &restored.orders, |o| o.id.clone()),
+        alerts: diff_by_id(&current.alerts, &restored.alerts, |a| a.id.clone()),
+    }
+}
+
+async fn load_current_state(app: &AppHandle) -> Result<ManagedState, String> {
+    let positions = app
+        .try_state::<SharedPortfolioData>()
+        .ok_or("Portfolio state is not available")?
+        .lock()
+        .map_err(|_| "Portfolio data locked".to_string())?
+        .positions();
+    let orders = require_state()?
+        .db
+        .read()
+        .await
+        .get_orders_between(None, None)
+        .await
+        .map_err(|e| format!("Failed to read orders: {}", e))?;
+    let alerts = app
+        .try_state::<SharedAlertManager>()
+        .ok_or("Alerts are not available")?
+        .read()
+        .await
+        .list_alerts()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(ManagedState {
+        positions,
+        orders,
+        alerts,
+    })
+}
+
+async fn events_since(
+    event_store: &SharedEventStore,
+    cursor: i64,
+) -> Result<Vec<StreamedEvent>, String> {
+    let store = event_store.read().await;
+    let mut events = Vec::new();
+    let mut cursor = cursor;
+    loop {
+        let page = store
+            .events_after(cursor, None, REPLAY_PAGE_SIZE)
+            .await
+            .map_err(|e| format!("Failed to read events: {}", e))?;
+        let Some(last) = page.last() else { break };
+        cursor = last.cursor;
+        let full_page = page.len() as i64 == REPLAY_PAGE_SIZE;
+        events.extend(page);
+        if !full_page {
+            break;
+        }
+    }
+    Ok(events)
+}
+
+async fn write_state(
+    app: &AppHandle,
+    restored: &ManagedState,
+    diff: &StateDiff,
+) -> Result<(), String> {
+    if !diff.positions.is_empty() {
+        app.try_state::<SharedPortfolioData>()
+            .ok_or("Portfolio state is not available")?
+            .lock()
+            .map_err(|_| "Portfolio data locked".to_string())?
+            .replace_positions(restored.positions.clone());
+    }
+
+    let restored_orders: HashMap<&str, &Order> =
+        restored.orders.iter().map(|o| (o.id.as_str(), o)).collect();
+    let db = require_state()?.db.read().await;
+    for change in &diff.orders {
+        let result = match restored_orders.get(change.id.as_str()) {
+            Some(order) => db.upsert_order(order).await,
+            None => db.delete_order(&change.id).await,
+        };
+        result.map_err(|e| format!("Failed to restore order {}: {}", change.id, e))?;
+    }
+    drop(db);
+
+    let restored_alerts: HashMap<&str, &PriceAlert> =
+        restored.alerts.iter().map(|a| (a.id.as_str(), a)).collect();
+    let alerts = app
+        .try_state::<SharedAlertManager>()
+        .ok_or("Alerts are not available")?;
+    let alerts = alerts.read().await;
+    for change in &diff.alerts {
+        let result = match restored_alerts.get(change.id.as_str()) {
+            Some(alert) => alerts.upsert_alert(alert).await,
+            None => alerts.delete_alert(&change.id).await,
+        };
+        result.map_err(|e| format!("Failed to restore alert {}: {}", change.id, e))?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn capture_state_snapshot(
+    app: AppHandle,
+    event_store: State<'_, SharedEventStore>,
+) -> Result<String, String> {
+    let state = load_current_state(&app).await?;
+    let store = event_store.read().await;
+    let snapshot = ManagedStateSnapshot {
+        version: SNAPSHOT_FORMAT_VERSION,
+        captured_at: Utc::now(),
+        event_cursor: store.latest_cursor().await.map_err(|e| e.to_string())?,
+        state,
+    };
+    let state_data = serde_json::to_string(&snapshot).map_err(|e| e.to_string())?;
+    store
+        .create_snapshot(STATE_SNAPSHOT_AGGREGATE, &state_data)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_state_snapshots(
+    event_store: State<'_, SharedEventStore>,
+    limit: Option<i64>,
+) -> Result<Vec<StateSnapshotInfo>, String> {
+    let records = event_store
+        .read()
+        .await
+        .list_snapshots(STATE_SNAPSHOT_AGGREGATE, limit.unwrap_or(50))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(records
+        .into_iter()
+        .filter_map(|record| {
+            let snapshot: ManagedStateSnapshot = serde_json::from_str(&record.state_data).ok()?;
+            Some(StateSnapshotInfo {
+                id: record.id,
+                captured_at: snapshot.captured_at,
+                event_cursor: snapshot.event_cursor,
+                positions: snapshot.state.positions.len(),
+                orders: snapshot.state.orders.len(),
+                alerts: snapshot.state.alerts.len(),
+            })
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn restore_from_snapshot(
+    app: AppHandle,
+    event_store: State<'_, SharedEventStore>,
+    snapshot_id: String,
+    dry_run: bool,
+) -> Result<RestoreReport, String> {
+    let record = event_store
+        .read()
+        .await
+        .get_snapshot(&snapshot_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Snapshot {} not found", snapshot_id))?;
+    let snapshot: ManagedStateSnapshot = serde_json::from_str(&record.state_data).map_err(|_| {
+        format!(
+            "Snapshot {} does not contain restorable state; capture one with capture_state_snapshot",
+            snapshot_id
+        )
+    })?;
+
+    let current = load_current_state(&app).await?;
+    let events = events_since(event_store.inner(), snapshot.event_cursor).await?;
+
+    let stored_orders: HashMap<String, Order> = current
+        .orders
+        .iter()
+        .map(|order| (order.id.clone(), order.clone()))
+        .collect();
+    let mut restored = snapshot.state;
+    let mut context = ReplayContext::default();
+    let mut events_applied = 0;
+    let mut events_ignored = 0;
+    let mut skipped = Vec::new();
+    for streamed in &events {
+        let outcome = match serde_json::from_str::<Event>(&streamed.record.event_data) {
+            Ok(event) => apply_event(&mut restored, &mut context, &stored_orders, &event),
+            Err(e) => ReplayOutcome::Skipped(format!("Unreadable event: {}", e)),
+        };
+        match outcome {
+            ReplayOutcome::Applied => events_applied += 1,
+            ReplayOutcome::Ignored => events_ignored += 1,
+            ReplayOutcome::Skipped(reason) => skipped.push(SkippedEvent {
+                cursor: streamed.cursor,
+                event_type: streamed.record.event_type.clone(),
+                reason,
+            }),
+        }
+    }
+    restored.positions = reprice_positions(restored.positions, &current.positions);
+
+    let diff = diff_state(&current, &restored);
+    if !dry_run && !diff.is_empty() {
+        write_state(&app, &restored, &diff).await?;
+    }
+
+    Ok(RestoreReport {
+        snapshot_id,
+        snapshot_captured_at: snapshot.captured_at,
+        dry_run,
+        events_applied,
+        events_ignored,
+        skipped,
+        diff,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replays_trades_and_positions_over_snapshot() {
+        let mut state = ManagedState {
+            positions: vec![new_position("USDC", 1000.0, 1.0)],
+            ..ManagedState::default()
+        };
+        let mut context = ReplayContext::default();
+        let orders = HashMap::new();
+        let now = Utc::now();
+
+        let events = [
+            Event::TradeExecuted {
+                trade_id: "t1".into(),
+                from_token: "USDC".into(),
+                to_token: "SOL".into(),
+                from_amount: 400.0,
+                to_amount: 4.0,
+                price: 100.0,
+                timestamp: now,
+            },
+            Event::PositionOpened {
+                position_id: "p1".into(),
+                symbol: "SOL".into(),
+                quantity: 1.0,
+                entry_price: 110.0,
+                timestamp: now,
+            },
+            Event::PositionClosed {
+                position_id: "p1".into(),
+                exit_price: 120.0,
+                pnl: 10.0,
+                timestamp: now,
+            },
+        ];
+        for event in &events {
+            assert!(matches!(
+                apply_event(&mut state, &mut context, &orders, event),
+                ReplayOutcome::Applied
+            ));
+        }
+
+        let sol = state.positions.iter().find(|p| p.symbol == "SOL").unwrap();
+        assert_eq!(sol.amount, 4.0);
+        assert_eq!(sol.avg_entry_price, 102.0);
+        assert_eq!(state.positions[0].amount, 600.0);
+
+        let unknown_fill = Event::OrderFilled {
+            order_id: "missing".into(),
+            fill_price: 1.0,
+            filled_quantity: 1.0,
+            timestamp: now,
+        };
+        assert!(matches!(
+            apply_event(&mut state, &mut context, &orders, &unknown_fill),
+            ReplayOutcome::Skipped(_)
+        ));
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_fields() {
+        let current = vec![
+            new_position("SOL", 1.0, 100.0),
+            new_position("BONK", 5.0, 0.1),
+        ];
+        let restored = vec![
+            new_position("SOL", 2.0, 100.0),
+            new_position("USDC", 10.0, 1.0),
+        ];
+        let changes = diff_by_id(&current, &restored, |p| p.symbol.clone());
+
+        let kinds: Vec<(&str, ChangeKind)> =
+            changes.iter().map(|c| (c.id.as_str(), c.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("BONK", ChangeKind::Removed),
+                ("SOL", ChangeKind::Modified),
+                ("USDC", ChangeKind::Added),
+            ]
+        );
+        assert_eq!(changes[1].fields, vec!["amount".to_string()]);
+    }
+}
@@ -0,0 +1,789 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::defi::protocol_risk::protocol_risk_score;
+use crate::defi::types::{Protocol, RiskLevel, RiskMetrics};
+use crate::market::DriftAdapter;
+use crate::profiles::ProfilePaths;
+
+const DERIVATIVES_FILE: &str = "derivative_positions.json";
+/// Used to estimate liquidation prices when the venue did not report one.
+const DEFAULT_MAINTENANCE_MARGIN_RATIO: f64 = 0.05;
+const HIGH_LEVERAGE: f64 = 10.0;
+const EXPIRY_WARNING_HOURS: i64 = 24;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum DerivativeVenue {
+    Drift,
+    Zeta,
+}
+
+impl DerivativeVenue {
+    pub fn protocol(&self) -> Protocol {
+        match self {
+            DerivativeVenue::Drift => Protocol::Other("Drift".to_string()),
+            DerivativeVenue::Zeta => Protocol::Other("Zeta".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DerivativeSide {
+    Long,
+    Short,
+}
+
+impl DerivativeSide {
+    fn sign(&self) -> f64 {
+        match self {
+            DerivativeSide::Long => 1.0,
+            DerivativeSide::Short => -1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OptionKind {
+    Call,
+    Put,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DerivativeInstrument {
+    Perpetual,
+    #[serde(rename_all = "camelCase")]
+    Option {
+        kind: OptionKind,
+        strike: f64,
+        expiry: DateTime<Utc>,
+    },
+}
+
+/// A position as reported by the venue or its SDK.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivativePositionInput {
+    pub market: String,
+    #[serde(default)]
+    pub market_index: Option<u32>,
+    pub underlying: String,
+    pub instrument: DerivativeInstrument,
+    pub side: DerivativeSide,
+    /// Contracts, in units of the underlying.
+    pub size: f64,
+    /// Entry price for perps, premium paid or received per unit for options.
+    pub entry_price: f64,
+    pub mark_price: f64,
+    #[serde(default)]
+    pub underlying_price: Option<f64>,
+    #[serde(default)]
+    pub collateral_usd: f64,
+    #[serde(default)]
+    pub leverage: Option<f64>,
+    /// Funding settled into the position so far; positive when received.
+    #[serde(default)]
+    pub accumulated_funding_usd: f64,
+    #[serde(default)]
+    pub funding_rate_hourly: Option<f64>,
+    #[serde(default)]
+    pub liquidation_price: Option<f64>,
+    #[serde(default)]
+    pub maintenance_margin_ratio: Option<f64>,
+    #[serde(default)]
+    pub implied_volatility: Option<f64>,
+    /// Per-unit delta of a long position, when the venue reports greeks.
+    #[serde(default)]
+    pub delta: Option<f64>,
+    #[serde(default)]
+    pub opened_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivativePosition {
+    pub id: String,
+    pub wallet: String,
+    pub venue: DerivativeVenue,
+    pub market: String,
+    pub market_index: Option<u32>,
+    pub underlying: String,
+    pub instrument: DerivativeInstrument,
+    pub side: DerivativeSide,
+    pub size: f64,
+    pub entry_price: f64,
+    pub mark_price: f64,
+    pub underlying_price: Option<f64>,
+    pub collateral_usd: f64,
+    pub accumulated_funding_usd: f64,
+    pub funding_rate_hourly: Option<f64>,
+    pub maintenance_margin_ratio: f64,
+    pub implied_volatility: Option<f64>,
+    pub reported_leverage: Option<f64>,
+    pub reported_liquidation_price: Option<f64>,
+    pub reported_delta: Option<f64>,
+    /// Exposure to the underlying in USD.
+    pub notional_usd: f64,
+    pub unrealized_pnl: f64,
+    /// What the position is worth if closed at the mark.
+    pub equity_usd: f64,
+    pub leverage: Option<f64>,
+    pub liquidation_price: Option<f64>,
+    pub liquidation_price_estimated: bool,
+    /// Underlying units the position moves like; signed by side.
+    pub delta_units: Option<f64>,
+    pub opened_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnderlyingExposure {
+    pub underlying: String,
+    pub delta_units: f64,
+    pub delta_notional_usd: f64,
+    pub gross_notional_usd: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivativesSummary {
+    pub position_count: usize,
+    pub equity_usd: f64,
+    pub gross_notional_usd: f64,
+    pub unrealized_pnl: f64,
+    pub accumulated_funding_usd: f64,
+    /// Gross notional over equity across all positions.
+    pub effective_leverage: f64,
+    pub exposures: Vec<UnderlyingExposure>,
+    /// Options without a reported delta or the inputs to estimate one.
+    pub positions_without_delta: usize,
+}
+
+/// Abramowitz-Stegun approximation, accurate to about 1e-7.
+fn normal_cdf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.2316419 * x.abs());
+    let poly = t
+        * (0.31938153
+            + t * (-0.356563782 + t * (1.781477937 + t * (-1.821255978 + t * 1.330274429))));
+    let tail = (-x * x / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt() * poly;
+    if x >= 0.0 {
+        1.0 - tail
+    } else {
+        tail
+    }
+}
+
+/// Black-Scholes delta of a long option with zero rates.
+fn black_scholes_delta(
+    kind: OptionKind,
+    spot: f64,
+    strike: f64,
+    years: f64,
+    volatility: f64,
+) -> Option<f64> {
+    if spot <= 0.0 || strike <= 0.0 || volatility <= 0.0 {
+        return None;
+    }
+    if years <= 0.0 {
+        let in_the_money = match kind {
+            OptionKind::Call => spot > strike,
+            OptionKind::Put => spot < strike,
+        };
+        let delta = if in_the_money { 1.0 } else { 0.0 };
+        return Some(if kind == OptionKind::Put {
+            -delta
+        } else {
+            delta
+        });
+    }
+    let d1 = ((spot / strike).ln() + volatility * volatility * years / 2.0)
+        / (volatility * years.sqrt());
+    Some(match kind {
+        OptionKind::Call => normal_cdf(d1),
+        OptionKind::Put => normal_cdf(d1) - 1.0,
+    })
+}
+
+impl DerivativePosition {
+    pub fn from_input(
+        wallet: &str,
+        venue: DerivativeVenue,
+        input: DerivativePositionInput,
+        now: DateTime<Utc>,
+    ) -> Result<Self, String> {
+        if input.size <= 0.0 || !input.size.is_finite() {
+            return Err(format!("{}: size must be positive", input.market));
+        }
+        if input.entry_price < 0.0 || input.mark_price < 0.0 {
+            return Err(format!("{}: prices cannot be negative", input.market));
+        }
+        if let DerivativeInstrument::Option { strike, .. } = &input.instrument {
+            if *strike <= 0.0 {
+                return Err(format!("{}: strike must be positive", input.market));
+            }
+        }
+
+        let mut position = Self {
+            id: Uuid::new_v4().to_string(),
+            wallet: wallet.to_string(),
+            venue,
+            market: input.market,
+            market_index: input.market_index,
+            underlying: input.underlying.to_uppercase(),
+            instrument: input.instrument,
+            side: input.side,
+            size: input.size,
+            entry_price: input.entry_price,
+            mark_price: input.mark_price,
+            underlying_price: input.underlying_price,
+            collateral_usd: input.collateral_usd,
+            accumulated_funding_usd: input.accumulated_funding_usd,
+            funding_rate_hourly: input.funding_rate_hourly,
+            maintenance_margin_ratio: input
+                .maintenance_margin_ratio
+                .unwrap_or(DEFAULT_MAINTENANCE_MARGIN_RATIO),
+            implied_volatility: input.implied_volatility,
+            reported_leverage: input.leverage,
+            reported_liquidation_price: input.liquidation_price,
+            reported_delta: input.delta,
+            notional_usd: 0.0,
+            unrealized_pnl: 0.0,
+            equity_usd: 0.0,
+            leverage: None,
+            liquidation_price: None,
+            liquidation_price_estimated: false,
+            delta_units: None,
+            opened_at: input.opened_at.unwrap_or(now),
+            updated_at: now,
+        };
+        position.recompute(now);
+        Ok(position)
+    }
+
+    fn is_same_contract(&self, other: &Self) -> bool {
+        self.wallet == other.wallet
+            && self.venue == other.venue
+            && self.market == other.market
+            && self.instrument == other.instrument
+            && self.side == other.side
+    }
+
+    /// Refreshes every derived figure from the reported inputs.
+    pub fn recompute(&mut self, now: DateTime<Utc>) {
+        let sign = self.side.sign();
+        self.unrealized_pnl = sign * self.size * (self.mark_price - self.entry_price);
+
+        match &self.instrument {
+            DerivativeInstrument::Perpetual => {
+                self.notional_usd = self.size * self.mark_price;
+                self.equity_usd =
+                    self.collateral_usd + self.unrealized_pnl + self.accumulated_funding_usd;
+                self.leverage = self.reported_leverage.or_else(|| {
+                    (self.equity_usd > 0.0).then(|| self.notional_usd / self.equity_usd)
+                });
+                self.delta_units = Some(sign * self.size);
+
+                self.liquidation_price_estimated = self.reported_liquidation_price.is_none();
+                self.liquidation_price = self
+                    .reported_liquidation_price
+                    .or_else(|| self.estimate_liquidation_price());
+            }
+            DerivativeInstrument::Option {
+                kind,
+                strike,
+                expiry,
+            } => {
+                let spot = self.underlying_price.unwrap_or(0.0);
+                self.notional_usd = self.size * spot;
+                self.equity_usd = self.collateral_usd + sign * self.size * self.mark_price;
+                let premium = self.size * self.mark_price;
+                self.leverage = self
+                    .reported_leverage
+                    .or_else(|| (premium > 0.0).then(|| self.notional_usd / premium));
+
+                let years = (*expiry - now).num_seconds() as f64 / (365.25 * 86_400.0);
+                let delta = self.reported_delta.or_else(|| {
+                    black_scholes_delta(*kind, spot, *strike, years, self.implied_volatility?)
+                });
+                self.delta_units = delta.map(|d| sign * self.size * d);
+
+                // Option liquidation depends on the venue's margin engine
+                // and is only known when it reports one.
+                self.liquidation_price = self.reported_liquidation_price;
+                self.liquidation_price_estimated = false;
+            }
+        }
+    }
+
+    /// Price at which equity falls to the maintenance requirement, or
+    /// `None` when the position cannot be liquidated by price alone.
+    fn estimate_liquidation_price(&self) -> Option<f64> {
+        let collateral = self.collateral_usd + self.accumulated_funding_usd;
+        let mm = self.maintenance_margin_ratio;
+        if collateral <= 0.0 {
+            return None;
+        }
+        let price = match self.side {
+            DerivativeSide::Long => {
+                (self.size * self.entry_price - collateral) / (self.size * (1.0 - mm))
+            }
+            DerivativeSide::Short => {
+                (collateral + self.size * self.entry_price) / (self.size * (1.0 + mm))
+            }
+        };
+        (price > 0.0 && price.is_finite()).then_some(price)
+    }
+
+    /// How far the mark can move against the position before liquidation,
+    /// as a percentage of the mark.
+    pub fn distance_to_liquidation_pct(&self) -> Option<f64> {
+        let liquidation = self.liquidation_price?;
+        if self.mark_price <= 0.0 {
+            return None;
+        }
+        let distance = match self.side {
+            DerivativeSide::Long => self.mark_price - liquidation,
+            DerivativeSide::Short => liquidation - self.mark_price,
+        };
+        Some(distance / self.mark_price * 100.0)
+    }
+}
+
+pub fn summarize_derivatives(positions: &[DerivativePosition]) -> DerivativesSummary {
+    let mut exposures: BTreeMap<String, UnderlyingExposure> = BTreeMap::new();
+    let mut summary = DerivativesSummary {
+        position_count: positions.len(),
+        ..Default::default()
+    };
+
+    for position in positions {
+        summary.equity_usd += position.equity_usd;
+        summary.gross_notional_usd += position.notional_usd;
+        summary.unrealized_pnl += position.unrealized_pnl;
+        summary.accumulated_funding_usd += position.accumulated_funding_usd;
+
+        let exposure = exposures
+            .entry(position.underlying.clone())
+            .or_insert_with(|| UnderlyingExposure {
+                underlying: position.underlying.clone(),
+                ..Default::default()
+            });
+        exposure.gross_notional_usd += position.notional_usd;
+        match position.delta_units {
+            Some(units) => {
+                let spot = position.underlying_price.unwrap_or(position.mark_price);
+                exposure.delta_units += units;
+                exposure.delta_notional_usd += units * spot;
+            }
+            None => summary.positions_without_delta += 1,
+        }
+    }
+
+    summary.effective_leverage = if summary.equity_usd > 0.0 {
+        summary.gross_notional_usd / summary.equity_usd
+    } else {
+        0.0
+    };
+    summary.exposures = exposures.into_values().collect();
+    summary
+}
+
+/// Risk rows for derivatives in the same shape as DeFi positions. Venue
+/// risk is reported but does not raise the level: Drift and Zeta have no
+/// protocol profile yet, so it would mark every position high.
+pub fn derivative_risk_metrics(
+    positions: &[DerivativePosition],
+    now: DateTime<Utc>,
+) -> Vec<RiskMetrics> {
+    positions
+        .iter()
+        .map(|position| {
+            let mut warnings = Vec::new();
+            let mut level = RiskLevel::Low;
+            let mut raise = |candidate: RiskLevel| {
+                if candidate.rank() > level.rank() {
+                    level = candidate;
+                }
+            };
+
+            if let Some(distance) = position.distance_to_liquidation_pct() {
+                if distance < 5.0 {
+                    raise(RiskLevel::Critical);
+                } else if distance < 15.0 {
+                    raise(RiskLevel::High);
+                } else if distance < 30.0 {
+                    raise(RiskLevel::Medium);
+                }
+                if distance < 15.0 {
+                    warnings.push(format!(
+                        "{} is {:.1}% from liquidation at {:.4}",
+                        position.market,
+                        distance.max(0.0),
+                        position.liquidation_price.unwrap_or_default()
+                    ));
+                }
+            }
+            if let Some(leverage) = position.leverage {
+                if leverage >= HIGH_LEVERAGE {
+                    raise(RiskLevel::High);
+                    warnings.push(format!("Leverage of {:.1}x", leverage));
+                }
+            }
+            if let Some(rate) = position.funding_rate_hourly {
+                // Longs pay positive funding, shorts pay negative funding.
+                if rate * position.side.sign() > 0.0 {
+                    warnings.push(format!(
+                        "Paying funding at {:.4}% per hour",
+                        rate.abs() * 100.0
+                    ));
+                }
+            }
+            if let DerivativeInstrument::Option { kind, expiry, .. } = &position.instrument {
+                if position.side == DerivativeSide::Short && *kind == OptionKind::Call {
+                    raise(RiskLevel::High);
+                    warnings.push("Short call has unbounded loss".to_string());
+                }
+                let hours_left = (*expiry - now).num_hours();
+                if hours_left < EXPIRY_WARNING_HOURS {
+                    raise(RiskLevel::Medium);
+                    warnings.push(if hours_left < 0 {
+                        "Option has expired and awaits settlement".to_string()
+                    } else {
+                        format!("Option expires in {} hours", hours_left)
+                    });
+                }
+            }
+
+            let venue_risk = protocol_risk_score(&position.venue.protocol());
+            let maintenance = position.maintenance_margin_ratio * position.notional_usd;
+            RiskMetrics {
+                position_id: position.id.clone(),
+                risk_level: level,
+                liquidation_price: position.liquidation_price,
+                health_factor: (maintenance > 0.0
+                    && position.instrument == DerivativeInstrument::Perpetual)
+                    .then(|| position.equity_usd / maintenance),
+                collateral_ratio: (position.notional_usd > 0.0)
+                    .then(|| position.equity_usd / position.notional_usd),
+                protocol_risk_score: venue_risk.score,
+                protocol_risk_level: venue_risk.level,
+                warnings,
+            }
+        })
+        .collect()
+}
+
+pub struct DerivativesTracker {
+    positions: HashMap<String, DerivativePosition>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedDerivativesTracker = Arc<RwLock<DerivativesTracker>>;
+
+impl DerivativesTracker {
+    pub fn new(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(DERIVATIVES_FILE));
+        let positions: Vec<DerivativePosition> = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            positions: positions
+                .into_iter()
+                .map(|position| (position.id.clone(), position))
+                .collect(),
+            path,
+        }
+    }
+
+    pub fn list(&self, wallet: Option<&str>) -> Vec<DerivativePosition> {
+        let mut positions: Vec<DerivativePosition> = self
+            .positions
+            .values()
+            .filter(|p| wallet.map_or(true, |wallet| p.wallet == wallet))
+            .cloned()
+            .collect();
+        positions.sort_by(|a, b| {
+            b.notional_usd
+                .partial_cmp(&a.notional_usd)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        positions
+    }
+
+    /// Replaces the wallet's positions on `venue` with a fresh report.
+    /// Contracts that were already tracked keep their id and open time so
+    /// risk rows stay stable across syncs; missing ones are treated as
+    /// closed.
+    pub fn sync(
+        &mut self,
+        wallet: &str,
+        venue: DerivativeVenue,
+        inputs: Vec<DerivativePositionInput>,
+    ) -> Result<Vec<DerivativePosition>, String> {
+        let now = Utc::now();
+        let mut incoming = inputs
+            .into_iter()
+            .map(|input| DerivativePosition::from_input(wallet, venue, input, now))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let previous: Vec<DerivativePosition> = self
+            .positions
+            .values()
+            .filter(|p| p.wallet == wallet && p.venue == venue)
+            .cloned()
+            .collect();
+        for position in &mut incoming {
+            if let Some(existing) = previous.iter().find(|p| p.is_same_contract(position)) {
+                position.id = existing.id.clone();
+                position.opened_at = existing.opened_at.min(position.opened_at);
+            }
+        }
+
+        self.positions
+            .retain(|_, p| !(p.wallet == wallet && p.venue == venue));
+        for position in &incoming {
+            self.positions.insert(position.id.clone(), position.clone());
+        }
+        self.save();
+        Ok(incoming)
+    }
+
+    pub fn remove(&mut self, id: &str) -> bool {
+        let removed = self.positions.remove(id).is_some();
+        if removed {
+            self.save();
+        }
+        removed
+    }
+
+    /// Applies Drift mark prices, oracle prices and funding rates to tracked
+    /// Drift perps. Returns how many positions were updated.
+    pub async fn refresh_drift_marks(&mut self, adapter: &DriftAdapter) -> Result<usize, String> {
+        if !self
+            .positions
+            .values()
+            .any(|p| p.venue == DerivativeVenue::Drift)
+        {
+            return Ok(0);
+        }
+        let markets = adapter.fetch_markets().await?;
+        let now = Utc::now();
+        let mut updated = 0;
+
+        for position in self.positions.values_mut() {
+            if position.venue != DerivativeVenue::Drift
+                || position.instrument != DerivativeInstrument::Perpetual
+            {
+                continue;
+            }
+            let market = markets.iter().find(|m| {
+                m.market_type.eq_ignore_ascii_case("perp")
+                    && match position.market_index {
+                        Some(index) => m.market_index == index,
+                        None => m.symbol.eq_ignore_ascii_case(&position.market),
+                    }
+            });
+            if let Some(market) = market {
+                position.mark_price = market.mark_price;
+                position.underlying_price = Some(market.oracle_price);
+                position.funding_rate_hourly = Some(market.funding_rate);
+                position.updated_at = now;
+                position.recompute(now);
+                updated += 1;
+            }
+        }
+
+        if updated > 0 {
+            self.save();
+        }
+        Ok(updated)
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let positions: Vec<&DerivativePosition> = self.positions.values().collect();
+        match serde_json::to_string_pretty(&positions) {
+            Ok(contents) => {
+                if let Err(err) = fs::write(path, contents) {
+                    eprintln!("Failed to persist derivative positions: {}", err);
+                }
+            }
+            Err(err) => eprintln!("Failed to serialize derivative positions: {}", err),
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn ingest_derivative_positions(
+    wallet: String,
+    venue: DerivativeVenue,
+    positions: Vec<DerivativePositionInput>,
+    tracker: State<'_, SharedDerivativesTracker>,
+) -> Result<Vec<DerivativePosition>, String> {
+    tracker.write().await.sync(&wallet, venue, positions)
+}
+
+#[tauri::command]
+pub async fn list_derivative_positions(
+    wallet: Option<String>,
+    tracker: State<'_, SharedDerivativesTracker>,
+) -> Result<Vec<DerivativePosition>, String> {
+    Ok(tracker.read().await.list(wallet.as_deref()))
+}
+
+#[tauri::command]
+pub async fn remove_derivative_position(
+    id: String,
+    tracker: State<'_, SharedDerivativesTracker>,
+) -> Result<bool, String> {
+    Ok(tracker.write().await.remove(&id))
+}
+
+#[tauri::command]
+pub async fn refresh_derivative_marks(
+    tracker: State<'_, SharedDerivativesTracker>,
+) -> Result<usize, String> {
+    let adapter = DriftAdapter::new();
+    tracker.write().await.refresh_drift_marks(&adapter).await
+}
+
+#[tauri::command]
+pub async fn get_derivatives_summary(
+    wallet: Option<String>,
+    tracker: State<'_, SharedDerivativesTracker>,
+) -> Result<DerivativesSummary, String> {
+    Ok(summarize_derivatives(
+        &tracker.read().await.list(wallet.as_deref()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn perp(side: DerivativeSide, collateral: f64) -> DerivativePositionInput {
+        DerivativePositionInput {
+            market: "SOL-PERP".into(),
+            market_index: Some(0),
+            underlying: "sol".into(),
+            instrument: DerivativeInstrument::Perpetual,
+            side,
+            size: 10.0,
+            entry_price: 100.0,
+            mark_price: 110.0,
+            underlying_price: None,
+            collateral_usd: collateral,
+            leverage: None,
+            accumulated_funding_usd: -5.0,
+            funding_rate_hourly: None,
+            liquidation_price: None,
+            maintenance_margin_ratio: None,
+            implied_volatility: None,
+            delta: None,
+            opened_at: None,
+        }
+    }
+
+    #[test]
+    fn perp_figures_and_liquidation_estimate() {
+        let now = Utc::now();
+        let long = DerivativePosition::from_input(
+            "w",
+            DerivativeVenue::Drift,
+            perp(DerivativeSide::Long, 205.0),
+            now,
+        )
+        .unwrap();
+        assert!((long.unrealized_pnl - 100.0).abs() < 1e-9);
+        assert!((long.equity_usd - 300.0).abs() < 1e-9);
+        assert!((long.leverage.unwrap() - 1100.0 / 300.0).abs() < 1e-9);
+        // Equity equals 5% of notional when 200 + 10 (p - 100) = 0.5 p.
+        let liquidation = long.liquidation_price.unwrap();
+        assert!((liquidation - 800.0 / 9.5).abs() < 1e-9);
+        assert!(long.liquidation_price_estimated);
+
+        let short = DerivativePosition::from_input(
+            "w",
+            DerivativeVenue::Drift,
+            perp(DerivativeSide::Short, 205.0),
+            now,
+        )
+        .unwrap();
+        assert!(short.unrealized_pnl < 0.0);
+        assert!(short.liquidation_price.unwrap() > short.mark_price);
+        assert_eq!(short.delta_units, Some(-10.0));
+    }
+
+    #[test]
+    fn option_delta_feeds_exposure() {
+        let now = Utc::now();
+        let mut input = perp(DerivativeSide::Long, 0.0);
+        input.market = "SOL-CALL-100".into();
+        input.instrument = DerivativeInstrument::Option {
+            kind: OptionKind::Call,
+            strike: 100.0,
+            expiry: now + Duration::days(30),
+        };
+        input.entry_price = 4.0;
+        input.mark_price = 5.0;
+        input.underlying_price = Some(100.0);
+        input.implied_volatility = Some(0.8);
+        input.accumulated_funding_usd = 0.0;
+
+        let call = DerivativePosition::from_input("w", DerivativeVenue::Zeta, input, now).unwrap();
+        let delta = call.delta_units.unwrap() / call.size;
+        assert!(delta > 0.5 && delta < 0.6);
+        assert!((call.equity_usd - 50.0).abs() < 1e-9);
+        assert!(call.liquidation_price.is_none());
+
+        let hedge = DerivativePosition::from_input(
+            "w",
+            DerivativeVenue::Drift,
+            perp(DerivativeSide::Short, 500.0),
+            now,
+        )
+        .unwrap();
+        let summary = summarize_derivatives(&[call.clone(), hedge]);
+        let sol = &summary.exposures[0];
+        assert_eq!(sol.underlying, "SOL");
+        assert!((sol.delta_units - (call.delta_units.unwrap() - 10.0)).abs() < 1e-9);
+        assert_eq!(summary.positions_without_delta, 0);
+    }
+
+    #[test]
+    fn risk_escalates_near_liquidation() {
+        let now = Utc::now();
+        let mut input = perp(DerivativeSide::Long, 0.0);
+        input.liquidation_price = Some(107.0);
+        let position =
+            DerivativePosition::from_input("w", DerivativeVenue::Drift, input, now).unwrap();
+
+        let metrics = derivative_risk_metrics(&[position], now);
+        assert_eq!(metrics[0].risk_level, RiskLevel::Critical);
+        assert_eq!(metrics[0].liquidation_price, Some(107.0));
+        assert!(!metrics[0].warnings.is_empty());
+    }
+}
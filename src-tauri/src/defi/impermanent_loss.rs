@@ -0,0 +1,614 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::api::jupiter::{fetch_quote, QuoteCommandInput, SwapMode};
+use crate::defi::types::{ImpermanentLossData, Protocol};
+use crate::profiles::ProfilePaths;
+
+const IL_POSITIONS_FILE: &str = "lp_impermanent_loss.json";
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const USDC_DECIMALS: u8 = 6;
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// Thirty days of samples at the default interval.
+const MAX_HISTORY_SAMPLES: usize = 2_880;
+
+/// One side of a two-token pool as deposited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LpLeg {
+    pub symbol: String,
+    /// Needed for automatic revaluation; positions without mints are only
+    /// updated when prices are supplied.
+    #[serde(default)]
+    pub mint: Option<String>,
+    #[serde(default)]
+    pub decimals: Option<u8>,
+    pub entry_amount: f64,
+    pub entry_price_usd: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LpPositionStatus {
+    Open,
+    Closed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LpEntryInput {
+    /// Reuses the adapter's position id when given.
+    #[serde(default)]
+    pub id: Option<String>,
+    pub wallet: String,
+    pub protocol: Protocol,
+    pub pool_address: String,
+    pub token_a: LpLeg,
+    pub token_b: LpLeg,
+    #[serde(default)]
+    pub opened_at: Option<i64>,
+}
+
+/// Pool composition and IL at one point in time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LpSample {
+    pub price_a: f64,
+    pub price_b: f64,
+    pub amount_a: f64,
+    pub amount_b: f64,
+    pub il: ImpermanentLossData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LpPositionRecord {
+    pub id: String,
+    pub wallet: String,
+    pub protocol: Protocol,
+    pub pool_address: String,
+    pub token_a: LpLeg,
+    pub token_b: LpLeg,
+    pub status: LpPositionStatus,
+    pub opened_at: i64,
+    #[serde(default)]
+    pub closed_at: Option<i64>,
+    #[serde(default)]
+    pub fees_earned_usd: f64,
+    #[serde(default)]
+    pub history: Vec<LpSample>,
+    /// Locked in from the withdrawn amounts once the position is closed.
+    #[serde(default)]
+    pub realized: Option<ImpermanentLossData>,
+}
+
+impl LpPositionRecord {
+    fn from_input(input: LpEntryInput) -> Result<Self, String> {
+        for leg in [&input.token_a, &input.token_b] {
+            if leg.entry_amount <= 0.0 || leg.entry_price_usd <= 0.0 {
+                return Err(format!(
+                    "{} needs a positive entry amount and price",
+                    leg.symbol
+                ));
+            }
+        }
+        let now = Utc::now().timestamp();
+        let mut record = Self {
+            id: input.id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            wallet: input.wallet,
+            protocol: input.protocol,
+            pool_address: input.pool_address,
+            status: LpPositionStatus::Open,
+            opened_at: input.opened_at.unwrap_or(now),
+            closed_at: None,
+            fees_earned_usd: 0.0,
+            history: Vec::new(),
+            realized: None,
+            token_a: input.token_a,
+            token_b: input.token_b,
+        };
+        let entry = record.sample(
+            record.token_a.entry_price_usd,
+            record.token_b.entry_price_usd,
+            None,
+            record.opened_at,
+        );
+        record.history.push(entry);
+        Ok(record)
+    }
+
+    /// Values the position at the given prices. Without observed amounts
+    /// the composition follows the constant-product curve from entry.
+    fn sample(
+        &self,
+        price_a: f64,
+        price_b: f64,
+        observed: Option<(f64, f64)>,
+        timestamp: i64,
+    ) -> LpSample {
+        let (a0, b0) = (self.token_a.entry_amount, self.token_b.entry_amount);
+        let (amount_a, amount_b) = observed.unwrap_or_else(|| {
+            let invariant = a0 * b0;
+            let ratio = price_a / price_b;
+            ((invariant / ratio).sqrt(), (invariant * ratio).sqrt())
+        });
+
+        let initial_value_usd =
+            a0 * self.token_a.entry_price_usd + b0 * self.token_b.entry_price_usd;
+        let hold_value_usd = a0 * price_a + b0 * price_b;
+        let current_value_usd = amount_a * price_a + amount_b * price_b;
+        let il_usd = hold_value_usd - current_value_usd;
+
+        LpSample {
+            price_a,
+            price_b,
+            amount_a,
+            amount_b,
+            il: ImpermanentLossData {
+                lp_position_id: self.id.clone(),
+                initial_value_usd,
+                current_value_usd,
+                hold_value_usd,
+                il_percentage: if hold_value_usd > 0.0 {
+                    il_usd / hold_value_usd * 100.0
+                } else {
+                    0.0
+                },
+                il_usd,
+                fees_earned_usd: self.fees_earned_usd,
+                // Positive when fees more than made up for the IL.
+                net_result_usd: self.fees_earned_usd - il_usd,
+                calculation_time: timestamp,
+            },
+        }
+    }
+
+    fn push_sample(&mut self, sample: LpSample) {
+        self.history.push(sample);
+        if self.history.len() > MAX_HISTORY_SAMPLES {
+            // Keep the entry sample so the series always starts at deposit.
+            self.history.remove(1);
+        }
+    }
+
+    pub fn latest(&self) -> Option<&LpSample> {
+        self.history.last()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpermanentLossReport {
+    pub wallet: String,
+    pub positions: Vec<LpPositionRecord>,
+    /// Current IL on open positions.
+    pub unrealized_il_usd: f64,
+    /// IL locked in by closed positions.
+    pub realized_il_usd: f64,
+    pub fees_earned_usd: f64,
+    /// Fees minus IL across all positions; negative means holding the
+    /// tokens would have done better.
+    pub net_vs_hold_usd: f64,
+    pub generated_at: i64,
+}
+
+pub struct ImpermanentLossTracker {
+    positions: HashMap<String, LpPositionRecord>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedImpermanentLossTracker = Arc<RwLock<ImpermanentLossTracker>>;
+
+impl ImpermanentLossTracker {
+    pub fn new(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(IL_POSITIONS_FILE));
+        let positions: Vec<LpPositionRecord> = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            positions: positions
+                .into_iter()
+                .map(|position| (position.id.clone(), position))
+                .collect(),
+            path,
+        }
+    }
+
+    pub fn record_entry(&mut self, input: LpEntryInput) -> Result<LpPositionRecord, String> {
+        let record = LpPositionRecord::from_input(input)?;
+        if self
+            .positions
+            .get(&record.id)
+            .is_some_and(|existing| existing.status == LpPositionStatus::Open)
+        {
+            return Err(format!("LP position {} is already tracked", record.id));
+        }
+        self.positions.insert(record.id.clone(), record.clone());
+        self.save();
+        Ok(record)
+    }
+
+    /// Adds a sample at the given prices. `amounts` are the pool's current
+    /// holdings for the position when the caller knows them, which is
+    /// required for concentrated liquidity.
+    pub fn record_observation(
+        &mut self,
+        id: &str,
+        price_a: f64,
+        price_b: f64,
+        amounts: Option<(f64, f64)>,
+        fees_earned_usd: Option<f64>,
+    ) -> Result<LpSample, String> {
+        if price_a <= 0.0 || price_b <= 0.0 {
+            return Err("Prices must be positive".to_string());
+        }
+        let position = self.open_position(id)?;
+        if let Some(fees) = fees_earned_usd {
+            position.fees_earned_usd = fees.max(0.0);
+        }
+        let sample = position.sample(price_a, price_b, amounts, Utc::now().timestamp());
+        position.push_sample(sample.clone());
+        self.save();
+        Ok(sample)
+    }
+
+    /// Closes the position with the amounts actually withdrawn and locks in
+    /// the realized IL.
+    pub fn close_position(
+        &mut self,
+        id: &str,
+        price_a: f64,
+        price_b: f64,
+        withdrawn: (f64, f64),
+        fees_earned_usd: Option<f64>,
+    ) -> Result<ImpermanentLossData, String> {
+        if price_a <= 0.0 || price_b <= 0.0 {
+            return Err("Prices must be positive".to_string());
+        }
+        let position = self.open_position(id)?;
+        if let Some(fees) = fees_earned_usd {
+            position.fees_earned_usd = fees.max(0.0);
+        }
+        let now = Utc::now().timestamp();
+        let sample = position.sample(price_a, price_b, Some(withdrawn), now);
+        let realized = sample.il.clone();
+        position.push_sample(sample);
+        position.status = LpPositionStatus::Closed;
+        position.closed_at = Some(now);
+        position.realized = Some(realized.clone());
+        self.save();
+        Ok(realized)
+    }
+
+    pub fn remove(&mut self, id: &str) -> bool {
+        let removed = self.positions.remove(id).is_some();
+        if removed {
+            self.save();
+        }
+        removed
+    }
+
+    /// Mints of open positions that can be revalued automatically.
+    fn quotable_mints(&self) -> Vec<(String, u8)> {
+        let mut mints: Vec<(String, u8)> = Vec::new();
+        for position in self.open_positions() {
+            for leg in [&position.token_a, &position.token_b] {
+                if let (Some(mint), Some(decimals)) = (&leg.mint, leg.decimals) {
+                    if !mints.iter().any(|(known, _)| known == mint) {
+                        mints.push((mint.clone(), decimals));
+                    }
+                }
+            }
+        }
+        mints
+    }
+
+    /// Samples every open position whose two mints have a price.
+    fn apply_prices(&mut self, prices: &HashMap<String, f64>) -> usize {
+        let now = Utc::now().timestamp();
+        let mut updated = 0;
+        for position in self.positions.values_mut() {
+            if position.status != LpPositionStatus::Open {
+                continue;
+            }
+            let price = |leg: &LpLeg| leg.mint.as_ref().and_then(|mint| prices.get(mint)).copied();
+            if let (Some(price_a), Some(price_b)) =
+                (price(&position.token_a), price(&position.token_b))
+            {
+                let sample = position.sample(price_a, price_b, None, now);
+                position.push_sample(sample);
+                updated += 1;
+            }
+        }
+        if updated > 0 {
+            self.save();
+        }
+        updated
+    }
+
+    pub fn report(&self, wallet: &str, history_limit: Option<usize>) -> ImpermanentLossReport {
+        let mut positions: Vec<LpPositionRecord> = self
+            .positions
+            .values()
+            .filter(|p| p.wallet == wallet)
+            .cloned()
+            .collect();
+        positions.sort_by(|a, b| b.opened_at.cmp(&a.opened_at));
+
+        let unrealized_il_usd = positions
+            .iter()
+            .filter(|p| p.status == LpPositionStatus::Open)
+            .filter_map(|p| p.latest())
+            .map(|sample| sample.il.il_usd)
+            .sum();
+        let realized_il_usd = positions
+            .iter()
+            .filter_map(|p| p.realized.as_ref())
+            .map(|il| il.il_usd)
+            .sum::<f64>();
+        let fees_earned_usd = positions.iter().map(|p| p.fees_earned_usd).sum::<f64>();
+
+        if let Some(limit) = history_limit {
+            for position in &mut positions {
+                let skip = position.history.len().saturating_sub(limit);
+                position.history.drain(..skip);
+            }
+        }
+
+        ImpermanentLossReport {
+            wallet: wallet.to_string(),
+            positions,
+            unrealized_il_usd,
+            realized_il_usd,
+            fees_earned_usd,
+            net_vs_hold_usd: fees_earned_usd - unrealized_il_usd - realized_il_usd,
+            generated_at: Utc::now().timestamp(),
+        }
+    }
+
+    fn open_positions(&self) -> impl Iterator<Item = &LpPositionRecord> {
+        self.positions
+            .values()
+            .filter(|p| p.status == LpPositionStatus::Open)
+    }
+
+    fn open_position(&mut self, id: &str) -> Result<&mut LpPositionRecord, String> {
+        match self.positions.get_mut(id) {
+            Some(position) if position.status == LpPositionStatus::Open => Ok(position),
+            Some(_) => Err(format!("LP position {id} is already closed")),
+            None => Err(format!("LP position {id} is not tracked")),
+        }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let positions: Vec<&LpPositionRecord> = self.positions.values().collect();
+        match serde_json::to_string_pretty(&positions) {
+            Ok(contents) => {
+                if let Err(err) = fs::write(path, contents) {
+                    eprintln!("Failed to persist LP impermanent loss data: {}", err);
+                }
+            }
+            Err(err) => eprintln!("Failed to serialize LP impermanent loss data: {}", err),
+        }
+    }
+}
+
+/// USD price of one whole token, from a Jupiter quote into USDC.
+async fn quote_usd_price(mint: &str, decimals: u8) -> Result<f64, String> {
+    if mint == USDC_MINT {
+        return Ok(1.0);
+    }
+    let quote = fetch_quote(&QuoteCommandInput {
+        input_mint: mint.to_string(),
+        output_mint: USDC_MINT.to_string(),
+        amount: 10u64.pow(u32::from(decimals)),
+        slippage_bps: Some(50),
+        swap_mode: Some(SwapMode::ExactIn),
+        platform_fee_bps: None,
+        only_direct_routes: None,
+        referral_account: None,
+        as_legacy_transaction: None,
+        priority_fee_config: None,
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let output = quote
+        .quote
+        .output_amount
+        .parse::<f64>()
+        .map_err(|e| format!("Invalid quote output: {e}"))?
+        / 10f64.powi(i32::from(USDC_DECIMALS));
+    let fee_rate = (quote.route.total_fee_bps as f64 / 10_000.0).min(0.5);
+    Ok(output / (1.0 - fee_rate))
+}
+
+/// Revalues open positions from live quotes. Returns how many were sampled.
+pub async fn refresh_impermanent_loss(tracker: &SharedImpermanentLossTracker) -> usize {
+    let mints = tracker.read().await.quotable_mints();
+    if mints.is_empty() {
+        return 0;
+    }
+    let mut prices = HashMap::new();
+    for (mint, decimals) in mints {
+        match quote_usd_price(&mint, decimals).await {
+            Ok(price) if price > 0.0 => {
+                prices.insert(mint, price);
+            }
+            Ok(_) => {}
+            Err(err) => log::debug!("Skipping IL sample for {mint}: {err}"),
+        }
+    }
+    tracker.write().await.apply_prices(&prices)
+}
+
+pub fn start_impermanent_loss_sampling(tracker: SharedImpermanentLossTracker) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            refresh_impermanent_loss(&tracker).await;
+            tokio::time::sleep(SAMPLE_INTERVAL).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn record_lp_entry(
+    entry: LpEntryInput,
+    tracker: State<'_, SharedImpermanentLossTracker>,
+) -> Result<LpPositionRecord, String> {
+    tracker.write().await.record_entry(entry)
+}
+
+#[tauri::command]
+pub async fn record_lp_observation(
+    position_id: String,
+    price_a: f64,
+    price_b: f64,
+    amount_a: Option<f64>,
+    amount_b: Option<f64>,
+    fees_earned_usd: Option<f64>,
+    tracker: State<'_, SharedImpermanentLossTracker>,
+) -> Result<LpSample, String> {
+    let amounts = amount_a.zip(amount_b);
+    tracker.write().await.record_observation(
+        &position_id,
+        price_a,
+        price_b,
+        amounts,
+        fees_earned_usd,
+    )
+}
+
+#[tauri::command]
+pub async fn close_lp_position(
+    position_id: String,
+    price_a: f64,
+    price_b: f64,
+    amount_a: f64,
+    amount_b: f64,
+    fees_earned_usd: Option<f64>,
+    tracker: State<'_, SharedImpermanentLossTracker>,
+) -> Result<ImpermanentLossData, String> {
+    tracker.write().await.close_position(
+        &position_id,
+        price_a,
+        price_b,
+        (amount_a, amount_b),
+        fees_earned_usd,
+    )
+}
+
+#[tauri::command]
+pub async fn remove_lp_position(
+    position_id: String,
+    tracker: State<'_, SharedImpermanentLossTracker>,
+) -> Result<bool, String> {
+    Ok(tracker.write().await.remove(&position_id))
+}
+
+#[tauri::command]
+pub async fn get_impermanent_loss_report(
+    wallet: String,
+    history_limit: Option<usize>,
+    tracker: State<'_, SharedImpermanentLossTracker>,
+) -> Result<ImpermanentLossReport, String> {
+    Ok(tracker.read().await.report(&wallet, history_limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> ImpermanentLossTracker {
+        ImpermanentLossTracker {
+            positions: HashMap::new(),
+            path: None,
+        }
+    }
+
+    fn sol_usdc_entry() -> LpEntryInput {
+        // 10 SOL at $100 paired with 1,000 USDC.
+        LpEntryInput {
+            id: Some("sol-usdc".to_string()),
+            wallet: "wallet".to_string(),
+            protocol: Protocol::Raydium,
+            pool_address: "pool".to_string(),
+            token_a: LpLeg {
+                symbol: "SOL".to_string(),
+                mint: None,
+                decimals: None,
+                entry_amount: 10.0,
+                entry_price_usd: 100.0,
+            },
+            token_b: LpLeg {
+                symbol: "USDC".to_string(),
+                mint: None,
+                decimals: None,
+                entry_amount: 1_000.0,
+                entry_price_usd: 1.0,
+            },
+            opened_at: Some(0),
+        }
+    }
+
+    #[test]
+    fn price_doubling_costs_the_textbook_amount() {
+        let mut tracker = tracker();
+        let entry = tracker.record_entry(sol_usdc_entry()).unwrap();
+        assert_eq!(entry.history[0].il.il_usd, 0.0);
+
+        let sample = tracker
+            .record_observation("sol-usdc", 200.0, 1.0, None, Some(20.0))
+            .unwrap();
+
+        // A 2x move on a constant-product pool loses about 5.72% vs holding.
+        assert!((sample.il.il_percentage - 5.719).abs() < 0.01);
+        assert!((sample.amount_a - 7.0711).abs() < 1e-3);
+        assert!((sample.il.hold_value_usd - 3_000.0).abs() < 1e-9);
+        assert!((sample.il.net_result_usd - (20.0 - sample.il.il_usd)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn closing_locks_in_realized_loss() {
+        let mut tracker = tracker();
+        tracker.record_entry(sol_usdc_entry()).unwrap();
+        tracker
+            .record_observation("sol-usdc", 150.0, 1.0, None, None)
+            .unwrap();
+        let realized = tracker
+            .close_position("sol-usdc", 150.0, 1.0, (8.0, 1_200.0), Some(15.0))
+            .unwrap();
+
+        // Withdrew $2,400 against $2,500 had the tokens been held.
+        assert!((realized.il_usd - 100.0).abs() < 1e-9);
+        assert!(tracker
+            .record_observation("sol-usdc", 160.0, 1.0, None, None)
+            .is_err());
+
+        let report = tracker.report("wallet", Some(1));
+        assert_eq!(report.unrealized_il_usd, 0.0);
+        assert!((report.realized_il_usd - 100.0).abs() < 1e-9);
+        assert!((report.net_vs_hold_usd + 85.0).abs() < 1e-9);
+        assert_eq!(report.positions[0].history.len(), 1);
+    }
+}
@@ -69,6 +69,52 @@ impl KaminoAdapter {
         Ok(farms)
     }
 
+    /// Kamino Lend reserves on the main market.
+    pub async fn get_lending_pools(&self) -> Result<Vec<LendingPool>, String> {
+        Ok(self.generate_mock_lending_pools())
+    }
+
+    fn generate_mock_lending_pools(&self) -> Vec<LendingPool> {
+        vec![
+            LendingPool {
+                pool_address: "kamino-lend-usdc".to_string(),
+                protocol: Protocol::Kamino,
+                asset: "USDC".to_string(),
+                total_supply: rand::random_range(40_000_000.0..160_000_000.0),
+                total_borrowed: rand::random_range(40_000_000.0..160_000_000.0) * 0.6,
+                supply_apy: rand::random_range(3.0..9.0),
+                borrow_apy: rand::random_range(5.5..13.0),
+                utilization_rate: rand::random_range(0.45..0.88),
+                liquidation_threshold: 0.80,
+                liquidation_bonus: 0.05,
+            },
+            LendingPool {
+                pool_address: "kamino-lend-sol".to_string(),
+                protocol: Protocol::Kamino,
+                asset: "SOL".to_string(),
+                total_supply: rand::random_range(600_000.0..2_500_000.0),
+                total_borrowed: rand::random_range(600_000.0..2_500_000.0) * 0.6,
+                supply_apy: rand::random_range(3.0..9.0),
+                borrow_apy: rand::random_range(5.5..13.0),
+                utilization_rate: rand::random_range(0.45..0.88),
+                liquidation_threshold: 0.75,
+                liquidation_bonus: 0.05,
+            },
+            LendingPool {
+                pool_address: "kamino-lend-usdt".to_string(),
+                protocol: Protocol::Kamino,
+                asset: "USDT".to_string(),
+                total_supply: rand::random_range(20_000_000.0..90_000_000.0),
+                total_borrowed: rand::random_range(20_000_000.0..90_000_000.0) * 0.6,
+                supply_apy: rand::random_range(3.0..9.0),
+                borrow_apy: rand::random_range(5.5..13.0),
+                utilization_rate: rand::random_range(0.45..0.88),
+                liquidation_threshold: 0.80,
+                liquidation_bonus: 0.05,
+            },
+        ]
+    }
+
     fn generate_mock_vaults(&self) -> Vec<KaminoVault> {
         use rand::Rng;
 
@@ -0,0 +1,719 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::alerts::logic::{
+    Action, ActionParameters, ActionType, Condition, ConditionParameters, ConditionType,
+    CreateSmartRuleRequest, NotificationPriority, RuleNode, SharedSmartAlertManager,
+    SmartRuleFilter, UpdateSmartRuleRequest,
+};
+use crate::defi::kamino::KaminoAdapter;
+use crate::defi::marginfi::MarginfiAdapter;
+use crate::defi::solend::SolendAdapter;
+use crate::defi::types::*;
+
+/// Tag shared by every rule this module manages.
+const ALERT_TAG: &str = "liquidation-risk";
+
+/// Alerts fire this far before the liquidation price is reached.
+const DEFAULT_ALERT_BUFFER_PCT: f64 = 10.0;
+
+/// MarginFi does not expose per-bank thresholds through the adapter; the
+/// reported health factor calibrates the projection anyway.
+const MARGINFI_DEFAULT_THRESHOLD: f64 = 0.80;
+
+const STABLE_ASSETS: &[&str] = &["USDC", "USDT", "PYUSD", "USDS", "USDH", "UXD", "DAI"];
+
+fn is_stable(asset: &str) -> bool {
+    STABLE_ASSETS.iter().any(|s| s.eq_ignore_ascii_case(asset))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceShockScenario {
+    pub name: String,
+    /// Price move applied to volatile assets, e.g. -20 for a 20% drop.
+    pub shock_pct: f64,
+    /// Restricts the shock to one asset; every non-stable asset otherwise.
+    #[serde(default)]
+    pub asset: Option<String>,
+}
+
+impl PriceShockScenario {
+    fn multiplier(&self, asset: &str) -> f64 {
+        let applies = match &self.asset {
+            Some(target) => target.eq_ignore_ascii_case(asset),
+            None => !is_stable(asset),
+        };
+        if applies {
+            1.0 + self.shock_pct / 100.0
+        } else {
+            1.0
+        }
+    }
+}
+
+fn default_scenarios() -> Vec<PriceShockScenario> {
+    [-10.0, -25.0, -40.0]
+        .into_iter()
+        .map(|shock_pct| PriceShockScenario {
+            name: format!("Market {shock_pct:.0}%"),
+            shock_pct,
+            asset: None,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginLeg {
+    pub asset: String,
+    pub amount: f64,
+    pub value_usd: f64,
+    pub price: f64,
+    /// Share of the value counted towards borrowing power; zero for debt.
+    pub liquidation_threshold: f64,
+}
+
+impl MarginLeg {
+    fn new(asset: &str, amount: f64, value_usd: f64, liquidation_threshold: f64) -> Self {
+        Self {
+            asset: asset.to_string(),
+            amount,
+            value_usd,
+            price: if amount > 0.0 {
+                value_usd / amount
+            } else {
+                0.0
+            },
+            liquidation_threshold,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LiquidationDirection {
+    /// Net collateral in the asset: liquidated if the price falls.
+    Below,
+    /// Net debt in the asset: liquidated if the price rises.
+    Above,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiquidationLevel {
+    pub asset: String,
+    pub current_price: f64,
+    /// Price at which the account is liquidated, other prices unchanged.
+    pub liquidation_price: f64,
+    pub direction: LiquidationDirection,
+    /// Move needed from the current price, negative for a drop.
+    pub distance_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioProjection {
+    pub scenario: String,
+    pub shock_pct: f64,
+    pub health_factor: Option<f64>,
+    pub risk_level: RiskLevel,
+    pub liquidated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginAccountRisk {
+    pub protocol: Protocol,
+    pub collateral: Vec<MarginLeg>,
+    pub debt: Vec<MarginLeg>,
+    pub collateral_value_usd: f64,
+    pub debt_value_usd: f64,
+    /// Reported by the protocol where available, computed otherwise.
+    pub health_factor: Option<f64>,
+    pub risk_level: RiskLevel,
+    pub liquidation_levels: Vec<LiquidationLevel>,
+    pub scenarios: Vec<ScenarioProjection>,
+    /// Scales nominal thresholds so the computed health factor matches the
+    /// reported one.
+    #[serde(skip)]
+    calibration: f64,
+}
+
+impl MarginAccountRisk {
+    fn new(
+        protocol: Protocol,
+        collateral: Vec<MarginLeg>,
+        debt: Vec<MarginLeg>,
+        reported_health_factor: Option<f64>,
+    ) -> Self {
+        let mut account = Self {
+            protocol,
+            collateral_value_usd: collateral.iter().map(|leg| leg.value_usd).sum(),
+            debt_value_usd: debt.iter().map(|leg| leg.value_usd).sum(),
+            collateral,
+            debt,
+            health_factor: None,
+            risk_level: RiskLevel::Low,
+            liquidation_levels: Vec::new(),
+            scenarios: Vec::new(),
+            calibration: 1.0,
+        };
+
+        let computed = account.health_at(|_| 1.0);
+        if let (Some(reported), Some(computed)) = (reported_health_factor, computed) {
+            if computed > 0.0 {
+                account.calibration = reported / computed;
+            }
+        }
+        account.health_factor = reported_health_factor.or(computed);
+        account.risk_level = risk_level_for(account.health_factor);
+        account.liquidation_levels = account.compute_liquidation_levels();
+        account
+    }
+
+    /// Health factor with each asset's price scaled by `multiplier`; `None`
+    /// while the account has no debt.
+    fn health_at(&self, multiplier: impl Fn(&str) -> f64) -> Option<f64> {
+        let debt: f64 = self
+            .debt
+            .iter()
+            .map(|leg| leg.value_usd * multiplier(&leg.asset))
+            .sum();
+        if debt <= 0.0 {
+            return None;
+        }
+        let weighted: f64 = self
+            .collateral
+            .iter()
+            .map(|leg| leg.value_usd * leg.liquidation_threshold * multiplier(&leg.asset))
+            .sum();
+        Some(weighted * self.calibration / debt)
+    }
+
+    fn compute_liquidation_levels(&self) -> Vec<LiquidationLevel> {
+        if self.debt_value_usd <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut prices: HashMap<&str, f64> = HashMap::new();
+        for leg in self.collateral.iter().chain(&self.debt) {
+            if leg.price > 0.0 && !is_stable(&leg.asset) {
+                prices.entry(leg.asset.as_str()).or_insert(leg.price);
+            }
+        }
+
+        let mut levels: Vec<LiquidationLevel> = prices
+            .into_iter()
+            .filter_map(|(asset, price)| {
+                let (mut weighted_asset, mut weighted_other) = (0.0, 0.0);
+                for leg in &self.collateral {
+                    let weighted = leg.value_usd * leg.liquidation_threshold * self.calibration;
+                    if leg.asset == asset {
+                        weighted_asset += weighted;
+                    } else {
+                        weighted_other += weighted;
+                    }
+                }
+                let (mut debt_asset, mut debt_other) = (0.0, 0.0);
+                for leg in &self.debt {
+                    if leg.asset == asset {
+                        debt_asset += leg.value_usd;
+                    } else {
+                        debt_other += leg.value_usd;
+                    }
+                }
+
+                // Solve weighted_other + weighted_asset * m == debt_other + debt_asset * m
+                // for the price multiplier m that brings the health factor to 1.
+                let net_exposure = weighted_asset - debt_asset;
+                if net_exposure.abs() < f64::EPSILON {
+                    return None;
+                }
+                let multiplier = (debt_other - weighted_other) / net_exposure;
+                if multiplier <= 0.0 {
+                    return None;
+                }
+                let direction = if net_exposure > 0.0 {
+                    LiquidationDirection::Below
+                } else {
+                    LiquidationDirection::Above
+                };
+                Some(LiquidationLevel {
+                    asset: asset.to_string(),
+                    current_price: price,
+                    liquidation_price: price * multiplier,
+                    direction,
+                    distance_pct: (multiplier - 1.0) * 100.0,
+                })
+            })
+            .collect();
+        levels.sort_by(|a, b| a.distance_pct.abs().total_cmp(&b.distance_pct.abs()));
+        levels
+    }
+
+    fn project(&mut self, scenarios: &[PriceShockScenario]) {
+        self.scenarios = scenarios
+            .iter()
+            .map(|scenario| {
+                let health_factor = self.health_at(|asset| scenario.multiplier(asset));
+                ScenarioProjection {
+                    scenario: scenario.name.clone(),
+                    shock_pct: scenario.shock_pct,
+                    health_factor,
+                    risk_level: risk_level_for(health_factor),
+                    liquidated: health_factor.is_some_and(|hf| hf < 1.0),
+                }
+            })
+            .collect();
+    }
+}
+
+/// Same cut-offs as the borrowing positions in the DeFi risk metrics.
+fn risk_level_for(health_factor: Option<f64>) -> RiskLevel {
+    match health_factor {
+        None => RiskLevel::Low,
+        Some(hf) if hf < 1.1 => RiskLevel::Critical,
+        Some(hf) if hf < 1.5 => RiskLevel::High,
+        Some(hf) if hf < 2.0 => RiskLevel::Medium,
+        Some(_) => RiskLevel::Low,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiquidationRiskReport {
+    pub wallet: String,
+    pub accounts: Vec<MarginAccountRisk>,
+    pub total_collateral_usd: f64,
+    pub total_debt_usd: f64,
+    pub lowest_health_factor: Option<f64>,
+    pub overall_risk_level: RiskLevel,
+    /// Smart alert rules created or refreshed for the liquidation levels.
+    pub alert_rule_ids: Vec<String>,
+    pub generated_at: i64,
+}
+
+async fn solend_account(wallet: &str) -> Result<Option<MarginAccountRisk>, String> {
+    let adapter = SolendAdapter::new();
+    let Some(obligation) = adapter.fetch_obligation(wallet).await? else {
+        return Ok(None);
+    };
+    let thresholds: HashMap<String, f64> = adapter
+        .fetch_reserves()
+        .await?
+        .into_iter()
+        .map(|reserve| (reserve.address, reserve.liquidation_threshold))
+        .collect();
+
+    let collateral = obligation
+        .deposits
+        .iter()
+        .map(|deposit| {
+            let threshold = thresholds
+                .get(&deposit.reserve_address)
+                .copied()
+                .unwrap_or(obligation.liquidation_threshold);
+            MarginLeg::new(
+                &deposit.symbol,
+                deposit.amount,
+                deposit.value_usd,
+                threshold,
+            )
+        })
+        .collect();
+    let debt = obligation
+        .borrows
+        .iter()
+        .map(|borrow| MarginLeg::new(&borrow.symbol, borrow.amount, borrow.value_usd, 0.0))
+        .collect();
+
+    Ok(Some(MarginAccountRisk::new(
+        Protocol::Solend,
+        collateral,
+        debt,
+        Some(obligation.health_factor),
+    )))
+}
+
+async fn marginfi_account(wallet: &str) -> Result<Option<MarginAccountRisk>, String> {
+    let Some(account) = MarginfiAdapter::new().get_account(wallet).await? else {
+        return Ok(None);
+    };
+    let collateral = account
+        .assets
+        .iter()
+        .map(|asset| {
+            MarginLeg::new(
+                &asset.symbol,
+                asset.amount,
+                asset.value_usd,
+                MARGINFI_DEFAULT_THRESHOLD,
+            )
+        })
+        .collect();
+    let debt = account
+        .liabilities
+        .iter()
+        .map(|liability| {
+            MarginLeg::new(
+                &liability.symbol,
+                liability.amount,
+                liability.value_usd,
+                0.0,
+            )
+        })
+        .collect();
+
+    Ok(Some(MarginAccountRisk::new(
+        Protocol::MarginFi,
+        collateral,
+        debt,
+        Some(account.health_factor),
+    )))
+}
+
+async fn kamino_account(wallet: &str) -> Result<Option<MarginAccountRisk>, String> {
+    let adapter = KaminoAdapter::new();
+    let positions: Vec<DeFiPosition> = adapter
+        .get_user_positions(wallet)
+        .await?
+        .into_iter()
+        .filter(|p| {
+            matches!(
+                p.position_type,
+                PositionType::Lending | PositionType::Borrowing
+            )
+        })
+        .collect();
+    if positions.is_empty() {
+        return Ok(None);
+    }
+    let thresholds: HashMap<String, f64> = adapter
+        .get_lending_pools()
+        .await?
+        .into_iter()
+        .map(|pool| (pool.asset, pool.liquidation_threshold))
+        .collect();
+
+    let mut collateral = Vec::new();
+    let mut debt = Vec::new();
+    let mut reported = None;
+    for position in &positions {
+        reported = reported.or(position.health_factor);
+        if position.position_type == PositionType::Borrowing {
+            debt.push(MarginLeg::new(
+                &position.asset,
+                position.amount,
+                position.value_usd,
+                0.0,
+            ));
+        } else {
+            let threshold = thresholds.get(&position.asset).copied().unwrap_or(0.0);
+            collateral.push(MarginLeg::new(
+                &position.asset,
+                position.amount,
+                position.value_usd,
+                threshold,
+            ));
+        }
+    }
+
+    Ok(Some(MarginAccountRisk::new(
+        Protocol::Kamino,
+        collateral,
+        debt,
+        reported,
+    )))
+}
+
+pub async fn build_liquidation_risk_report(
+    wallet: &str,
+    scenarios: &[PriceShockScenario],
+) -> Result<LiquidationRiskReport, String> {
+    if let Some(bad) = scenarios.iter().find(|s| s.shock_pct <= -100.0) {
+        return Err(format!(
+            "Scenario '{}' shocks prices to zero or below",
+            bad.name
+        ));
+    }
+
+    let (solend, marginfi, kamino) = tokio::join!(
+        solend_account(wallet),
+        marginfi_account(wallet),
+        kamino_account(wallet)
+    );
+    let mut accounts: Vec<MarginAccountRisk> = [solend?, marginfi?, kamino?]
+        .into_iter()
+        .flatten()
+        .collect();
+    for account in &mut accounts {
+        account.project(scenarios);
+    }
+
+    let lowest_health_factor = accounts
+        .iter()
+        .filter_map(|a| a.health_factor)
+        .min_by(|a, b| a.total_cmp(b));
+    let overall_risk_level = accounts
+        .iter()
+        .map(|a| a.risk_level.clone())
+        .max_by_key(|level| level.rank())
+        .unwrap_or(RiskLevel::Low);
+
+    Ok(LiquidationRiskReport {
+        wallet: wallet.to_string(),
+        total_collateral_usd: accounts.iter().map(|a| a.collateral_value_usd).sum(),
+        total_debt_usd: accounts.iter().map(|a| a.debt_value_usd).sum(),
+        accounts,
+        lowest_health_factor,
+        overall_risk_level,
+        alert_rule_ids: Vec::new(),
+        generated_at: Utc::now().timestamp(),
+    })
+}
+
+fn protocol_name(protocol: &Protocol) -> String {
+    match protocol {
+        Protocol::Other(name) => name.clone(),
+        other => format!("{other:?}"),
+    }
+}
+
+fn wallet_tag(wallet: &str) -> String {
+    format!("{ALERT_TAG}:{wallet}")
+}
+
+fn level_tag(wallet: &str, protocol: &Protocol, asset: &str) -> String {
+    format!("{ALERT_TAG}:{wallet}:{}:{asset}", protocol_name(protocol))
+}
+
+/// Smart alert rule firing `buffer_pct` before `level` is reached.
+fn alert_rule_for(
+    wallet: &str,
+    protocol: &Protocol,
+    level: &LiquidationLevel,
+    buffer_pct: f64,
+) -> CreateSmartRuleRequest {
+    let (condition_type, threshold) = match level.direction {
+        LiquidationDirection::Below => (
+            ConditionType::Below,
+            level.liquidation_price * (1.0 + buffer_pct / 100.0),
+        ),
+        LiquidationDirection::Above => (
+            ConditionType::Above,
+            level.liquidation_price * (1.0 - buffer_pct / 100.0),
+        ),
+    };
+    let tag = level_tag(wallet, protocol, &level.asset);
+    let protocol = protocol_name(protocol);
+    let message = format!(
+        "{} is approaching the {protocol} liquidation price of ${:.4}",
+        level.asset, level.liquidation_price
+    );
+
+    CreateSmartRuleRequest {
+        name: format!("Liquidation risk: {protocol} {}", level.asset),
+        description: Some(format!(
+            "Managed by the liquidation risk dashboard for {wallet}"
+        )),
+        rule_tree: RuleNode {
+            id: None,
+            label: Some(format!("{} near liquidation", level.asset)),
+            condition: Some(Condition {
+                id: None,
+                condition_type,
+                parameters: ConditionParameters {
+                    threshold: Some(threshold),
+                    ..Default::default()
+                },
+                description: Some(message.clone()),
+            }),
+            group: None,
+            metadata: Some(serde_json::json!({
+                "wallet": wallet,
+                "protocol": protocol,
+                "liquidationPrice": level.liquidation_price,
+            })),
+        },
+        actions: vec![Action {
+            id: None,
+            action_type: ActionType::Notify,
+            parameters: ActionParameters {
+                title: Some(format!("{protocol} liquidation risk")),
+                message: Some(message),
+                priority: Some(NotificationPriority::Critical),
+                ..Default::default()
+            },
+            description: None,
+            enabled: true,
+        }],
+        enabled: true,
+        symbol: Some(level.asset.clone()),
+        owner_id: None,
+        team_id: None,
+        shared_with: Vec::new(),
+        tags: vec![ALERT_TAG.to_string(), wallet_tag(wallet), tag],
+    }
+}
+
+/// Creates or refreshes one rule per liquidation level and removes rules
+/// for positions that no longer carry one. Rules the user disabled stay
+/// disabled.
+async fn sync_alert_rules(
+    smart_alerts: &SharedSmartAlertManager,
+    report: &LiquidationRiskReport,
+    buffer_pct: f64,
+) -> Result<Vec<String>, String> {
+    let manager = smart_alerts.read().await;
+    let existing = manager
+        .list_rules(Some(SmartRuleFilter {
+            tag: Some(wallet_tag(&report.wallet)),
+            include_disabled: true,
+            ..Default::default()
+        }))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut ids = Vec::new();
+    let mut live_tags = HashSet::new();
+    for account in &report.accounts {
+        for level in &account.liquidation_levels {
+            let tag = level_tag(&report.wallet, &account.protocol, &level.asset);
+            let request = alert_rule_for(&report.wallet, &account.protocol, level, buffer_pct);
+            let rule = match existing.iter().find(|rule| rule.tags.contains(&tag)) {
+                Some(rule) => {
+                    manager
+                        .update_rule(
+                            &rule.id,
+                            UpdateSmartRuleRequest {
+                                name: Some(request.name),
+                                description: Some(request.description),
+                                rule_tree: Some(request.rule_tree),
+                                actions: Some(request.actions),
+                                tags: Some(request.tags),
+                                ..Default::default()
+                            },
+                        )
+                        .await
+                }
+                None => manager.create_rule(request).await,
+            }
+            .map_err(|e| e.to_string())?;
+            ids.push(rule.id);
+            live_tags.insert(tag);
+        }
+    }
+
+    for rule in &existing {
+        let managed = rule
+            .tags
+            .iter()
+            .any(|tag| tag.starts_with(&format!("{}:", wallet_tag(&report.wallet))));
+        if managed && !rule.tags.iter().any(|tag| live_tags.contains(tag)) {
+            manager
+                .delete_rule(&rule.id)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(ids)
+}
+
+/// Aggregates health factors across Solend, MarginFi and Kamino, projects
+/// them under price shocks and keeps a smart alert armed ahead of every
+/// liquidation price.
+#[tauri::command]
+pub async fn get_liquidation_risk(
+    wallet: String,
+    scenarios: Option<Vec<PriceShockScenario>>,
+    alert_buffer_pct: Option<f64>,
+    smart_alerts: State<'_, SharedSmartAlertManager>,
+) -> Result<LiquidationRiskReport, String> {
+    let scenarios = scenarios.unwrap_or_else(default_scenarios);
+    let buffer_pct = alert_buffer_pct
+        .unwrap_or(DEFAULT_ALERT_BUFFER_PCT)
+        .clamp(0.0, 50.0);
+
+    let mut report = build_liquidation_risk_report(&wallet, &scenarios).await?;
+    report.alert_rule_ids = sync_alert_rules(&smart_alerts, &report, buffer_pct).await?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sol_backed_account() -> MarginAccountRisk {
+        // 100 SOL at $100 with an 80% threshold against $4,000 of USDC debt.
+        MarginAccountRisk::new(
+            Protocol::Solend,
+            vec![MarginLeg::new("SOL", 100.0, 10_000.0, 0.8)],
+            vec![MarginLeg::new("USDC", 4_000.0, 4_000.0, 0.0)],
+            None,
+        )
+    }
+
+    #[test]
+    fn liquidation_price_brings_health_factor_to_one() {
+        let account = sol_backed_account();
+        assert!((account.health_factor.unwrap() - 2.0).abs() < 1e-9);
+
+        let level = &account.liquidation_levels[0];
+        assert_eq!(level.asset, "SOL");
+        assert_eq!(level.direction, LiquidationDirection::Below);
+        assert!((level.liquidation_price - 50.0).abs() < 1e-9);
+        assert!((level.distance_pct + 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn shocks_skip_stables_and_flag_liquidations() {
+        let mut account = sol_backed_account();
+        account.project(&[
+            PriceShockScenario {
+                name: "Mild".to_string(),
+                shock_pct: -25.0,
+                asset: None,
+            },
+            PriceShockScenario {
+                name: "Crash".to_string(),
+                shock_pct: -60.0,
+                asset: None,
+            },
+        ]);
+
+        let mild = &account.scenarios[0];
+        assert!((mild.health_factor.unwrap() - 1.5).abs() < 1e-9);
+        assert!(!mild.liquidated);
+        assert!(account.scenarios[1].liquidated);
+        assert_eq!(account.scenarios[1].risk_level, RiskLevel::Critical);
+    }
+
+    #[test]
+    fn reported_health_factor_calibrates_projections() {
+        let mut account = MarginAccountRisk::new(
+            Protocol::MarginFi,
+            vec![MarginLeg::new("USDC", 10_000.0, 10_000.0, 0.8)],
+            vec![MarginLeg::new("SOL", 40.0, 4_000.0, 0.0)],
+            Some(1.6),
+        );
+        assert_eq!(account.health_factor, Some(1.6));
+
+        // SOL debt is liquidated on a rally: a 60% move eats the whole
+        // 1.6 health factor.
+        let level = &account.liquidation_levels[0];
+        assert_eq!(level.direction, LiquidationDirection::Above);
+        assert!((level.liquidation_price - 160.0).abs() < 1e-9);
+
+        account.project(&[PriceShockScenario {
+            name: "SOL squeeze".to_string(),
+            shock_pct: 60.0,
+            asset: Some("SOL".to_string()),
+        }]);
+        assert!((account.scenarios[0].health_factor.unwrap() - 1.0).abs() < 1e-9);
+    }
+}
@@ -5,6 +5,7 @@ pub mod types;
 pub mod jupiter;
 pub mod yield_tracker;
 pub mod lp_analyzer;
+pub mod impermanent_loss;
 
 // Export existing DeFi modules
 pub mod solend;
@@ -15,6 +16,12 @@ pub mod yield_farming;
 pub mod position_manager;
 pub mod governance;
 pub mod auto_compound;
+pub mod reward_claims;
+pub mod protocol_risk;
+pub mod liquidation_risk;
+pub mod rates;
+pub mod strategy_builder;
+pub mod derivatives;
 
 pub use types::*;
 pub use yield_tracker::YieldTracker;
@@ -22,8 +29,15 @@ pub use lp_analyzer::LpAnalyzer;
 
 // Tauri command exports - wildcards ensure new commands are automatically available
 pub use yield_farming::*;
+pub use impermanent_loss::*;
 pub use position_manager::*;
 pub use auto_compound::*;
+pub use reward_claims::*;
+pub use protocol_risk::*;
+pub use liquidation_risk::*;
+pub use rates::*;
+pub use strategy_builder::*;
+pub use derivatives::*;
 // Explicit exports for governance to avoid naming conflict with standalone governance module
 pub use governance::{get_governance_proposals, vote_on_proposal, get_governance_participation};
 // Protocol-specific command exports
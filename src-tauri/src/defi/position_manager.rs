@@ -1,11 +1,17 @@
 use crate::defi::auto_compound::AutoCompoundEngine;
+use crate::defi::derivatives::{
+    derivative_risk_metrics, summarize_derivatives, DerivativePosition, DerivativesSummary,
+    SharedDerivativesTracker,
+};
 use crate::defi::kamino::KaminoAdapter;
 use crate::defi::marginfi::MarginfiAdapter;
+use crate::defi::protocol_risk::protocol_risk_score;
 use crate::defi::solend::SolendAdapter;
 use crate::defi::staking::StakingAdapter;
 use crate::defi::types::*;
 use crate::defi::yield_farming::YieldFarmingAdapter;
 use serde::{Deserialize, Serialize};
+use tauri::State;
 
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
@@ -13,6 +19,8 @@ pub struct PositionSnapshot {
     pub wallet: String,
     pub summary: PortfolioSummary,
     pub risk_metrics: Vec<RiskMetrics>,
+    #[serde(default)]
+    pub derivatives: DerivativesSummary,
 }
 
 #[derive(Clone)]
@@ -22,6 +30,7 @@ pub struct PositionManager {
     kamino: KaminoAdapter,
     staking: StakingAdapter,
     farming: YieldFarmingAdapter,
+    derivatives: Vec<DerivativePosition>,
 }
 
 impl Default for PositionManager {
@@ -38,9 +47,17 @@ impl PositionManager {
             kamino: KaminoAdapter::new(),
             staking: StakingAdapter::new(),
             farming: YieldFarmingAdapter::new(),
+            derivatives: Vec::new(),
         }
     }
 
+    /// Includes tracked perp and option positions in the summary and risk
+    /// metrics.
+    pub fn with_derivatives(mut self, derivatives: Vec<DerivativePosition>) -> Self {
+        self.derivatives = derivatives;
+        self
+    }
+
     pub async fn build_portfolio_summary(&self, wallet: &str) -> Result<PortfolioSummary, String> {
         let solend_positions = self.solend.get_user_positions(wallet).await?;
         let marginfi_positions = self.marginfi.get_positions(wallet).await?;
@@ -55,7 +72,9 @@ impl PositionManager {
         positions.extend(staking_positions);
         positions.extend(farming_positions);
 
-        let total_value_usd: f64 = positions.iter().map(|p| p.value_usd).sum();
+        let derivatives_value: f64 = self.derivatives.iter().map(|p| p.equity_usd).sum();
+        let total_value_usd: f64 =
+            positions.iter().map(|p| p.value_usd).sum::<f64>() + derivatives_value;
         let lending_value = positions
             .iter()
             .filter(|p| p.position_type == PositionType::Lending)
@@ -100,6 +119,8 @@ impl PositionManager {
             total_earnings_24h,
             average_apy,
             positions,
+            derivatives_value,
+            derivatives: self.derivatives.clone(),
         })
     }
 
@@ -139,6 +160,23 @@ impl PositionManager {
             if risk_level == RiskLevel::High || risk_level == RiskLevel::Critical {
                 warnings.push("Position health requires attention".to_string());
             }
+
+            // A healthy position is still only as safe as the protocol
+            // holding it.
+            let protocol_risk = protocol_risk_score(&position.protocol);
+            let risk_level = if protocol_risk.level.rank() > risk_level.rank() {
+                protocol_risk.level.clone()
+            } else {
+                risk_level
+            };
+            if protocol_risk.level.rank() >= RiskLevel::High.rank() {
+                if let Some(concern) = protocol_risk.main_concern() {
+                    warnings.push(format!(
+                        "{:?} protocol risk is {:?}: {}",
+                        position.protocol, protocol_risk.level, concern.detail
+                    ));
+                }
+            }
             if position.apy > 35.0 {
                 warnings.push("Yield may be unsustainable".to_string());
             }
@@ -149,10 +187,17 @@ impl PositionManager {
                 liquidation_price: None,
                 health_factor: position.health_factor,
                 collateral_ratio: position.health_factor.map(|hf| hf * 0.5),
+                protocol_risk_score: protocol_risk.score,
+                protocol_risk_level: protocol_risk.level,
                 warnings,
             });
         }
 
+        metrics.extend(derivative_risk_metrics(
+            &self.derivatives,
+            chrono::Utc::now(),
+        ));
+
         Ok(metrics)
     }
 
@@ -163,6 +208,7 @@ impl PositionManager {
             wallet: wallet.to_string(),
             summary,
             risk_metrics,
+            derivatives: summarize_derivatives(&self.derivatives),
         })
     }
 
@@ -177,21 +223,44 @@ impl PositionManager {
     }
 }
 
+async fn manager_with_derivatives(
+    wallet: &str,
+    derivatives: &SharedDerivativesTracker,
+) -> PositionManager {
+    PositionManager::new().with_derivatives(derivatives.read().await.list(Some(wallet)))
+}
+
 #[tauri::command]
-pub async fn get_defi_portfolio_summary(wallet: String) -> Result<PortfolioSummary, String> {
-    PositionManager::new()
+pub async fn get_defi_portfolio_summary(
+    wallet: String,
+    derivatives: State<'_, SharedDerivativesTracker>,
+) -> Result<PortfolioSummary, String> {
+    manager_with_derivatives(&wallet, &derivatives)
+        .await
         .build_portfolio_summary(&wallet)
         .await
 }
 
 #[tauri::command]
-pub async fn get_defi_risk_metrics(wallet: String) -> Result<Vec<RiskMetrics>, String> {
-    PositionManager::new().calculate_risk_metrics(&wallet).await
+pub async fn get_defi_risk_metrics(
+    wallet: String,
+    derivatives: State<'_, SharedDerivativesTracker>,
+) -> Result<Vec<RiskMetrics>, String> {
+    manager_with_derivatives(&wallet, &derivatives)
+        .await
+        .calculate_risk_metrics(&wallet)
+        .await
 }
 
 #[tauri::command]
-pub async fn get_defi_snapshot(wallet: String) -> Result<PositionSnapshot, String> {
-    PositionManager::new().snapshot(&wallet).await
+pub async fn get_defi_snapshot(
+    wallet: String,
+    derivatives: State<'_, SharedDerivativesTracker>,
+) -> Result<PositionSnapshot, String> {
+    manager_with_derivatives(&wallet, &derivatives)
+        .await
+        .snapshot(&wallet)
+        .await
 }
 
 #[tauri::command]
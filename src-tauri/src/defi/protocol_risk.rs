@@ -0,0 +1,465 @@
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::defi::types::*;
+
+const TVL_WEIGHT: f64 = 0.20;
+const AUDIT_WEIGHT: f64 = 0.25;
+const ORACLE_WEIGHT: f64 = 0.15;
+const ADMIN_WEIGHT: f64 = 0.25;
+const INCIDENT_WEIGHT: f64 = 0.15;
+
+/// Audits older than this no longer count towards the current code.
+const AUDIT_FRESHNESS_DAYS: i64 = 730;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolAudit {
+    pub auditor: String,
+    pub date: DateTime<Utc>,
+    pub open_critical_findings: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolIncident {
+    pub date: DateTime<Utc>,
+    pub description: String,
+    pub loss_usd: f64,
+    pub reimbursed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AdminKeyConfig {
+    /// Program upgrade authority revoked.
+    Immutable,
+    #[serde(rename_all = "camelCase")]
+    Multisig {
+        threshold: u8,
+        signers: u8,
+        timelock_hours: u32,
+    },
+    SingleKey,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolProfile {
+    pub protocol: Protocol,
+    /// Weekly TVL, oldest first.
+    pub tvl_history_usd: Vec<f64>,
+    pub audits: Vec<ProtocolAudit>,
+    /// Price feeds the protocol relies on; empty for pure AMMs.
+    pub oracles: Vec<String>,
+    pub admin: AdminKeyConfig,
+    pub incidents: Vec<ProtocolIncident>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProtocolRiskFactor {
+    TvlTrend,
+    AuditHistory,
+    OracleDependency,
+    AdminKeys,
+    Incidents,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolRiskComponent {
+    pub factor: ProtocolRiskFactor,
+    /// 0 (no concern) to 100.
+    pub score: f64,
+    pub weight: f64,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolRiskScore {
+    pub protocol: Protocol,
+    /// Weighted total from 0 (safest) to 100.
+    pub score: u8,
+    pub level: RiskLevel,
+    pub components: Vec<ProtocolRiskComponent>,
+}
+
+impl ProtocolRiskScore {
+    /// The component contributing most to the total.
+    pub fn main_concern(&self) -> Option<&ProtocolRiskComponent> {
+        self.components
+            .iter()
+            .max_by(|a, b| (a.score * a.weight).total_cmp(&(b.score * b.weight)))
+    }
+}
+
+fn tvl_component(history: &[f64]) -> ProtocolRiskComponent {
+    let (score, detail) = match (history.first(), history.last()) {
+        (Some(&first), Some(&last)) if first > 0.0 && history.len() > 1 => {
+            let change_pct = (last - first) / first * 100.0;
+            // A 50% drawdown maxes the factor out; growth scores zero.
+            let mut score = (-change_pct * 2.0).clamp(0.0, 100.0);
+            if last < 10_000_000.0 {
+                score = (score + 30.0).min(100.0);
+            }
+            (
+                score,
+                format!(
+                    "TVL {change_pct:+.1}% over {} weeks to ${:.0}M",
+                    history.len() - 1,
+                    last / 1_000_000.0
+                ),
+            )
+        }
+        _ => (60.0, "No TVL history".to_string()),
+    };
+    ProtocolRiskComponent {
+        factor: ProtocolRiskFactor::TvlTrend,
+        score,
+        weight: TVL_WEIGHT,
+        detail,
+    }
+}
+
+fn audit_component(audits: &[ProtocolAudit], now: DateTime<Utc>) -> ProtocolRiskComponent {
+    let recent = audits
+        .iter()
+        .filter(|a| (now - a.date).num_days() <= AUDIT_FRESHNESS_DAYS)
+        .count();
+    let open_criticals: u32 = audits.iter().map(|a| a.open_critical_findings).sum();
+    let score = if audits.is_empty() {
+        100.0
+    } else {
+        (85.0 - 30.0 * recent as f64 + 40.0 * open_criticals as f64).clamp(0.0, 100.0)
+    };
+    let mut detail = format!("{} audit(s), {recent} in the last two years", audits.len());
+    if open_criticals > 0 {
+        detail.push_str(&format!(
+            ", {open_criticals} critical finding(s) unresolved"
+        ));
+    }
+    ProtocolRiskComponent {
+        factor: ProtocolRiskFactor::AuditHistory,
+        score,
+        weight: AUDIT_WEIGHT,
+        detail,
+    }
+}
+
+fn oracle_component(oracles: &[String]) -> ProtocolRiskComponent {
+    let (score, detail) = match oracles.len() {
+        0 => (10.0, "Does not depend on external price feeds".to_string()),
+        1 => (
+            65.0,
+            format!(
+                "Single price source ({}) can be manipulated or go stale",
+                oracles[0]
+            ),
+        ),
+        _ => (
+            25.0,
+            format!("Cross-checks prices from {}", oracles.join(", ")),
+        ),
+    };
+    ProtocolRiskComponent {
+        factor: ProtocolRiskFactor::OracleDependency,
+        score,
+        weight: ORACLE_WEIGHT,
+        detail,
+    }
+}
+
+fn admin_component(admin: &AdminKeyConfig) -> ProtocolRiskComponent {
+    let (score, detail) = match admin {
+        AdminKeyConfig::Immutable => (0.0, "Programs are immutable".to_string()),
+        AdminKeyConfig::Multisig {
+            threshold,
+            signers,
+            timelock_hours,
+        } => {
+            let mut score: f64 = if *timelock_hours > 0 { 20.0 } else { 45.0 };
+            // A minority of signers being able to upgrade is close to a
+            // single key in practice.
+            if (*threshold as f64) * 2.0 <= *signers as f64 {
+                score += 20.0;
+            }
+            let timelock = if *timelock_hours > 0 {
+                format!("{timelock_hours}h timelock")
+            } else {
+                "no timelock".to_string()
+            };
+            (
+                score,
+                format!("{threshold}-of-{signers} upgrade multisig, {timelock}"),
+            )
+        }
+        AdminKeyConfig::SingleKey => (90.0, "A single key can upgrade the programs".to_string()),
+    };
+    ProtocolRiskComponent {
+        factor: ProtocolRiskFactor::AdminKeys,
+        score,
+        weight: ADMIN_WEIGHT,
+        detail,
+    }
+}
+
+fn incident_component(incidents: &[ProtocolIncident], now: DateTime<Utc>) -> ProtocolRiskComponent {
+    // Each incident weighs less the longer the protocol has run cleanly
+    // since, halving every year.
+    let score: f64 = incidents
+        .iter()
+        .map(|incident| {
+            let base = if incident.reimbursed { 25.0 } else { 50.0 };
+            let years = (now - incident.date).num_days().max(0) as f64 / 365.0;
+            base * 0.5_f64.powf(years)
+        })
+        .sum();
+    let detail = match incidents.iter().max_by_key(|i| i.date) {
+        Some(latest) => format!(
+            "{} incident(s); latest {}: {}",
+            incidents.len(),
+            latest.date.format("%Y-%m"),
+            latest.description
+        ),
+        None => "No known incidents".to_string(),
+    };
+    ProtocolRiskComponent {
+        factor: ProtocolRiskFactor::Incidents,
+        score: score.min(100.0),
+        weight: INCIDENT_WEIGHT,
+        detail,
+    }
+}
+
+fn level_for_score(score: u8) -> RiskLevel {
+    match score {
+        0..=24 => RiskLevel::Low,
+        25..=49 => RiskLevel::Medium,
+        50..=74 => RiskLevel::High,
+        _ => RiskLevel::Critical,
+    }
+}
+
+pub fn score_protocol(profile: &ProtocolProfile, now: DateTime<Utc>) -> ProtocolRiskScore {
+    let components = vec![
+        tvl_component(&profile.tvl_history_usd),
+        audit_component(&profile.audits, now),
+        oracle_component(&profile.oracles),
+        admin_component(&profile.admin),
+        incident_component(&profile.incidents, now),
+    ];
+    let score = components
+        .iter()
+        .map(|c| c.score * c.weight)
+        .sum::<f64>()
+        .round()
+        .clamp(0.0, 100.0) as u8;
+    ProtocolRiskScore {
+        protocol: profile.protocol.clone(),
+        score,
+        level: level_for_score(score),
+        components,
+    }
+}
+
+fn date(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
+}
+
+fn audit(auditor: &str, date: DateTime<Utc>) -> ProtocolAudit {
+    ProtocolAudit {
+        auditor: auditor.to_string(),
+        date,
+        open_critical_findings: 0,
+    }
+}
+
+/// Curated profiles for the protocols the DeFi adapters integrate with.
+pub fn protocol_profiles() -> Vec<ProtocolProfile> {
+    vec![
+        ProtocolProfile {
+            protocol: Protocol::Solend,
+            tvl_history_usd: vec![
+                190e6, 186e6, 181e6, 178e6, 176e6, 171e6, 168e6, 165e6, 163e6,
+            ],
+            audits: vec![
+                audit("Kudelski Security", date(2021, 8, 1)),
+                audit("OtterSec", date(2024, 3, 1)),
+            ],
+            oracles: vec!["Pyth".to_string(), "Switchboard".to_string()],
+            admin: AdminKeyConfig::Multisig {
+                threshold: 3,
+                signers: 5,
+                timelock_hours: 0,
+            },
+            incidents: vec![ProtocolIncident {
+                date: date(2022, 11, 2),
+                description: "USDH oracle manipulation left bad debt".to_string(),
+                loss_usd: 1_260_000.0,
+                reimbursed: false,
+            }],
+        },
+        ProtocolProfile {
+            protocol: Protocol::MarginFi,
+            tvl_history_usd: vec![
+                420e6, 428e6, 433e6, 441e6, 438e6, 446e6, 452e6, 455e6, 461e6,
+            ],
+            audits: vec![
+                audit("OtterSec", date(2023, 2, 1)),
+                audit("OtterSec", date(2025, 4, 1)),
+            ],
+            oracles: vec!["Pyth".to_string(), "Switchboard".to_string()],
+            admin: AdminKeyConfig::Multisig {
+                threshold: 3,
+                signers: 6,
+                timelock_hours: 0,
+            },
+            incidents: Vec::new(),
+        },
+        ProtocolProfile {
+            protocol: Protocol::Kamino,
+            tvl_history_usd: vec![
+                1.48e9, 1.50e9, 1.53e9, 1.55e9, 1.54e9, 1.58e9, 1.61e9, 1.63e9, 1.66e9,
+            ],
+            audits: vec![
+                audit("OtterSec", date(2024, 1, 1)),
+                audit("Offside Labs", date(2024, 9, 1)),
+                audit("Certora", date(2025, 2, 1)),
+            ],
+            oracles: vec![
+                "Pyth".to_string(),
+                "Switchboard".to_string(),
+                "Scope".to_string(),
+            ],
+            admin: AdminKeyConfig::Multisig {
+                threshold: 4,
+                signers: 6,
+                timelock_hours: 24,
+            },
+            incidents: Vec::new(),
+        },
+        ProtocolProfile {
+            protocol: Protocol::Raydium,
+            tvl_history_usd: vec![
+                1.10e9, 1.08e9, 1.12e9, 1.05e9, 1.01e9, 1.04e9, 0.99e9, 1.02e9, 1.00e9,
+            ],
+            audits: vec![
+                audit("Kudelski Security", date(2021, 6, 1)),
+                audit("MadShield", date(2023, 3, 1)),
+            ],
+            oracles: Vec::new(),
+            admin: AdminKeyConfig::Multisig {
+                threshold: 2,
+                signers: 4,
+                timelock_hours: 0,
+            },
+            incidents: vec![ProtocolIncident {
+                date: date(2022, 12, 16),
+                description: "Pool owner key compromised and liquidity drained".to_string(),
+                loss_usd: 4_400_000.0,
+                reimbursed: true,
+            }],
+        },
+        ProtocolProfile {
+            protocol: Protocol::Orca,
+            tvl_history_usd: vec![
+                320e6, 318e6, 325e6, 331e6, 329e6, 335e6, 338e6, 336e6, 342e6,
+            ],
+            audits: vec![
+                audit("Kudelski Security", date(2022, 3, 1)),
+                audit("Neodyme", date(2024, 5, 1)),
+            ],
+            oracles: Vec::new(),
+            admin: AdminKeyConfig::Multisig {
+                threshold: 3,
+                signers: 5,
+                timelock_hours: 24,
+            },
+            incidents: Vec::new(),
+        },
+    ]
+}
+
+/// Score for `protocol`. Protocols without a profile are treated as
+/// unaudited with unknown admin controls.
+pub fn protocol_risk_score(protocol: &Protocol) -> ProtocolRiskScore {
+    let profile = protocol_profiles()
+        .into_iter()
+        .find(|p| &p.protocol == protocol)
+        .unwrap_or_else(|| ProtocolProfile {
+            protocol: protocol.clone(),
+            tvl_history_usd: Vec::new(),
+            audits: Vec::new(),
+            oracles: Vec::new(),
+            admin: AdminKeyConfig::SingleKey,
+            incidents: Vec::new(),
+        });
+    score_protocol(&profile, Utc::now())
+}
+
+#[tauri::command]
+pub async fn get_protocol_risk_scores() -> Result<Vec<ProtocolRiskScore>, String> {
+    let now = Utc::now();
+    Ok(protocol_profiles()
+        .iter()
+        .map(|profile| score_protocol(profile, now))
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_protocol_risk_profile(protocol: Protocol) -> Result<ProtocolProfile, String> {
+    protocol_profiles()
+        .into_iter()
+        .find(|p| p.protocol == protocol)
+        .ok_or_else(|| format!("No risk profile for {protocol:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(admin: AdminKeyConfig, incidents: Vec<ProtocolIncident>) -> ProtocolProfile {
+        ProtocolProfile {
+            protocol: Protocol::Other("Test".to_string()),
+            tvl_history_usd: vec![100e6, 105e6, 110e6],
+            audits: vec![audit("Auditor", date(2026, 1, 1))],
+            oracles: vec!["Pyth".to_string(), "Switchboard".to_string()],
+            admin,
+            incidents,
+        }
+    }
+
+    #[test]
+    fn admin_keys_and_incidents_raise_the_score() {
+        let now = date(2026, 6, 1);
+        let safe = score_protocol(&profile(AdminKeyConfig::Immutable, Vec::new()), now);
+        let exploit = ProtocolIncident {
+            date: date(2026, 3, 1),
+            description: "Oracle exploit".to_string(),
+            loss_usd: 5e6,
+            reimbursed: false,
+        };
+        let risky = score_protocol(&profile(AdminKeyConfig::SingleKey, vec![exploit]), now);
+
+        assert_eq!(safe.level, RiskLevel::Low);
+        assert!(risky.score > safe.score + 20);
+        assert_eq!(
+            risky.main_concern().map(|c| c.factor),
+            Some(ProtocolRiskFactor::AdminKeys)
+        );
+    }
+
+    #[test]
+    fn unknown_protocols_score_high() {
+        let unknown = protocol_risk_score(&Protocol::Other("NewFork".to_string()));
+        assert!(matches!(
+            unknown.level,
+            RiskLevel::High | RiskLevel::Critical
+        ));
+        for profile in protocol_profiles() {
+            assert!(protocol_risk_score(&profile.protocol).score < unknown.score);
+        }
+    }
+}
@@ -0,0 +1,518 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::RwLock;
+
+use crate::api::trading_execution::get_priority_fee_estimates;
+use crate::defi::kamino::KaminoAdapter;
+use crate::defi::marginfi::MarginfiAdapter;
+use crate::defi::solend::SolendAdapter;
+use crate::defi::types::*;
+use crate::portfolio::dust::sol_price_usd;
+use crate::profiles::ProfilePaths;
+
+const RATE_HISTORY_FILE: &str = "defi_rate_history.json";
+const SAMPLES_PER_MARKET: usize = 2_000;
+const TRACKING_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+const BASE_FEE_LAMPORTS: f64 = 5_000.0;
+const MOVE_COMPUTE_UNITS: f64 = 300_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RateSide {
+    Supply,
+    Borrow,
+}
+
+impl RateSide {
+    /// Withdraw and redeposit for supply; borrow moves also have to shift
+    /// collateral, so repay, withdraw, deposit and borrow.
+    fn transactions(&self) -> f64 {
+        match self {
+            RateSide::Supply => 2.0,
+            RateSide::Borrow => 4.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VenueRate {
+    pub protocol: Protocol,
+    pub pool_address: String,
+    pub asset: String,
+    pub supply_apy: f64,
+    pub borrow_apy: f64,
+    pub utilization: f64,
+    pub available_liquidity: f64,
+    pub fetched_at: i64,
+}
+
+impl VenueRate {
+    fn apy(&self, side: RateSide) -> f64 {
+        match side {
+            RateSide::Supply => self.supply_apy,
+            RateSide::Borrow => self.borrow_apy,
+        }
+    }
+
+    fn from_pool(pool: LendingPool, fetched_at: i64) -> Self {
+        Self {
+            protocol: pool.protocol,
+            available_liquidity: (pool.total_supply - pool.total_borrowed).max(0.0),
+            pool_address: pool.pool_address,
+            asset: pool.asset,
+            supply_apy: pool.supply_apy,
+            borrow_apy: pool.borrow_apy,
+            utilization: pool.utilization_rate,
+            fetched_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateComparison {
+    pub asset: String,
+    pub side: RateSide,
+    /// Best venue first.
+    pub venues: Vec<VenueRate>,
+    /// Difference between the best and worst venue, in basis points.
+    pub spread_bps: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateSample {
+    pub timestamp: i64,
+    pub protocol: Protocol,
+    pub asset: String,
+    pub supply_apy: f64,
+    pub borrow_apy: f64,
+    pub utilization: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateMoveRequest {
+    pub asset: String,
+    pub side: RateSide,
+    pub from: Protocol,
+    pub amount_usd: f64,
+    /// Defaults to 50 bps.
+    pub min_improvement_bps: Option<f64>,
+    /// Period the improvement must pay back the move over. Defaults to 30 days.
+    pub horizon_days: Option<f64>,
+}
+
+/// Advice only: nothing here builds or sends the withdraw and deposit, so
+/// the user carries out a recommended move from the venues themselves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateMoveSuggestion {
+    pub asset: String,
+    pub side: RateSide,
+    pub amount_usd: f64,
+    pub from: Protocol,
+    pub to: Option<Protocol>,
+    pub to_pool_address: Option<String>,
+    pub current_apy: f64,
+    pub target_apy: Option<f64>,
+    pub improvement_bps: f64,
+    pub expected_gain_usd: f64,
+    pub estimated_cost_usd: f64,
+    pub breakeven_days: Option<f64>,
+    pub recommended: bool,
+    pub reason: String,
+}
+
+/// Above this a pool is close to drained and suppliers may not be able to
+/// withdraw, so a higher headline rate there is not worth chasing.
+const MAX_TARGET_UTILIZATION: f64 = 0.95;
+
+/// Finds the best venue to move `amount_usd` to and whether the gain over
+/// the horizon clears both the minimum improvement and the cost of moving.
+pub fn suggest_move(
+    side: RateSide,
+    current: &VenueRate,
+    venues: &[VenueRate],
+    amount_usd: f64,
+    cost_usd: f64,
+    min_improvement_bps: f64,
+    horizon_days: f64,
+) -> RateMoveSuggestion {
+    let improvement = |venue: &VenueRate| match side {
+        RateSide::Supply => (venue.supply_apy - current.supply_apy) * 100.0,
+        RateSide::Borrow => (current.borrow_apy - venue.borrow_apy) * 100.0,
+    };
+    let best = venues
+        .iter()
+        .filter(|venue| venue.protocol != current.protocol)
+        .filter(|venue| match side {
+            RateSide::Supply => venue.utilization <= MAX_TARGET_UTILIZATION,
+            // Borrowers need the target to have the liquidity to lend.
+            RateSide::Borrow => venue.available_liquidity >= amount_usd,
+        })
+        .max_by(|a, b| improvement(a).total_cmp(&improvement(b)));
+
+    let mut suggestion = RateMoveSuggestion {
+        asset: current.asset.clone(),
+        side,
+        amount_usd,
+        from: current.protocol.clone(),
+        to: None,
+        to_pool_address: None,
+        current_apy: current.apy(side),
+        target_apy: None,
+        improvement_bps: 0.0,
+        expected_gain_usd: 0.0,
+        estimated_cost_usd: cost_usd,
+        breakeven_days: None,
+        recommended: false,
+        reason: format!("{:?} already has the best usable rate", current.protocol),
+    };
+    let Some(best) = best.filter(|best| improvement(best) > 0.0) else {
+        return suggestion;
+    };
+
+    let improvement_bps = improvement(best);
+    let daily_gain = amount_usd * improvement_bps / 10_000.0 / 365.0;
+    suggestion.to = Some(best.protocol.clone());
+    suggestion.to_pool_address = Some(best.pool_address.clone());
+    suggestion.target_apy = Some(best.apy(side));
+    suggestion.improvement_bps = improvement_bps;
+    suggestion.expected_gain_usd = daily_gain * horizon_days;
+    suggestion.breakeven_days = (daily_gain > 0.0).then(|| cost_usd / daily_gain);
+    suggestion.reason = if improvement_bps < min_improvement_bps {
+        format!(
+            "{:?} is better by {improvement_bps:.0} bps, below the {min_improvement_bps:.0} bps minimum",
+            best.protocol
+        )
+    } else if suggestion.expected_gain_usd <= cost_usd {
+        format!(
+            "Moving costs ~${cost_usd:.2} but earns only ${:.2} over {horizon_days:.0} days",
+            suggestion.expected_gain_usd
+        )
+    } else {
+        suggestion.recommended = true;
+        format!(
+            "Move to {:?} for +{improvement_bps:.0} bps; pays back in {:.1} days",
+            best.protocol,
+            suggestion.breakeven_days.unwrap_or_default()
+        )
+    };
+    suggestion
+}
+
+/// Collects lending rates from Solend, MarginFi and Kamino and keeps a
+/// rolling history per market.
+pub struct RateAggregator {
+    solend: SolendAdapter,
+    marginfi: MarginfiAdapter,
+    kamino: KaminoAdapter,
+    history: RwLock<HashMap<String, VecDeque<RateSample>>>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedRateAggregator = Arc<RateAggregator>;
+
+fn market_key(protocol: &Protocol, asset: &str) -> String {
+    format!("{protocol:?}:{}", asset.to_uppercase())
+}
+
+impl RateAggregator {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        let path = app_handle
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(RATE_HISTORY_FILE));
+        let history = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            solend: SolendAdapter::new(),
+            marginfi: MarginfiAdapter::new(),
+            kamino: KaminoAdapter::new(),
+            history: RwLock::new(history),
+            path,
+        }
+    }
+
+    /// Current rates at every venue, recorded into the history.
+    pub async fn fetch_rates(&self) -> Result<Vec<VenueRate>, String> {
+        let now = Utc::now().timestamp();
+        let mut pools = self.solend.get_lending_pools().await?;
+        pools.extend(self.kamino.get_lending_pools().await?);
+        pools.extend(
+            self.marginfi
+                .get_banks()
+                .await?
+                .into_iter()
+                .map(|bank| LendingPool {
+                    pool_address: bank.address,
+                    protocol: Protocol::MarginFi,
+                    asset: bank.symbol,
+                    total_supply: bank.total_deposits,
+                    total_borrowed: bank.total_loans,
+                    supply_apy: bank.lending_apy,
+                    borrow_apy: bank.borrowing_apy,
+                    utilization_rate: bank.utilization,
+                    liquidation_threshold: 0.0,
+                    liquidation_bonus: 0.0,
+                }),
+        );
+        let rates: Vec<VenueRate> = pools
+            .into_iter()
+            .map(|pool| VenueRate::from_pool(pool, now))
+            .collect();
+
+        {
+            let mut history = self.history.write().await;
+            for rate in &rates {
+                let samples = history
+                    .entry(market_key(&rate.protocol, &rate.asset))
+                    .or_default();
+                if samples.len() >= SAMPLES_PER_MARKET {
+                    samples.pop_front();
+                }
+                samples.push_back(RateSample {
+                    timestamp: now,
+                    protocol: rate.protocol.clone(),
+                    asset: rate.asset.clone(),
+                    supply_apy: rate.supply_apy,
+                    borrow_apy: rate.borrow_apy,
+                    utilization: rate.utilization,
+                });
+            }
+        }
+        if let Err(e) = self.save().await {
+            eprintln!("Failed to persist lending rate history: {e}");
+        }
+        Ok(rates)
+    }
+
+    pub async fn compare(&self, asset: &str, side: RateSide) -> Result<RateComparison, String> {
+        let mut venues: Vec<VenueRate> = self
+            .fetch_rates()
+            .await?
+            .into_iter()
+            .filter(|rate| rate.asset.eq_ignore_ascii_case(asset))
+            .collect();
+        venues.sort_by(|a, b| match side {
+            RateSide::Supply => b.supply_apy.total_cmp(&a.supply_apy),
+            RateSide::Borrow => a.borrow_apy.total_cmp(&b.borrow_apy),
+        });
+        let spread_bps = match (venues.first(), venues.last()) {
+            (Some(best), Some(worst)) => (best.apy(side) - worst.apy(side)).abs() * 100.0,
+            _ => 0.0,
+        };
+        Ok(RateComparison {
+            asset: asset.to_uppercase(),
+            side,
+            venues,
+            spread_bps,
+        })
+    }
+
+    pub async fn history(
+        &self,
+        asset: &str,
+        protocol: Option<&Protocol>,
+        since: Option<i64>,
+    ) -> Vec<RateSample> {
+        let history = self.history.read().await;
+        let mut samples: Vec<RateSample> = history
+            .values()
+            .flatten()
+            .filter(|s| s.asset.eq_ignore_ascii_case(asset))
+            .filter(|s| protocol.map_or(true, |p| &s.protocol == p))
+            .filter(|s| since.map_or(true, |since| s.timestamp >= since))
+            .cloned()
+            .collect();
+        samples.sort_by_key(|s| s.timestamp);
+        samples
+    }
+
+    pub async fn suggest(&self, request: &RateMoveRequest) -> Result<RateMoveSuggestion, String> {
+        if request.amount_usd <= 0.0 {
+            return Err("Amount must be positive".to_string());
+        }
+        let comparison = self.compare(&request.asset, request.side).await?;
+        let current = comparison
+            .venues
+            .iter()
+            .find(|venue| venue.protocol == request.from)
+            .ok_or_else(|| format!("{:?} has no {} market", request.from, request.asset))?;
+        let cost_usd = estimate_move_cost(request.side).await?;
+        Ok(suggest_move(
+            request.side,
+            current,
+            &comparison.venues,
+            request.amount_usd,
+            cost_usd,
+            request.min_improvement_bps.unwrap_or(50.0),
+            request.horizon_days.unwrap_or(30.0),
+        ))
+    }
+
+    async fn save(&self) -> Result<(), String> {
+        if let Some(path) = &self.path {
+            let contents = serde_json::to_string(&*self.history.read().await)
+                .map_err(|e| format!("Failed to serialize rate history: {e}"))?;
+            fs::write(path, contents).map_err(|e| format!("Failed to write rate history: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Network cost of moving a position at the current priority fee.
+async fn estimate_move_cost(side: RateSide) -> Result<f64, String> {
+    let sol_usd = sol_price_usd().await?;
+    let priority_micro_lamports = get_priority_fee_estimates()
+        .await
+        .ok()
+        .and_then(|estimates| {
+            estimates
+                .into_iter()
+                .find(|estimate| estimate.preset == "normal")
+        })
+        .map(|estimate| estimate.micro_lamports)
+        .unwrap_or(5_000);
+    let lamports =
+        BASE_FEE_LAMPORTS + priority_micro_lamports as f64 * MOVE_COMPUTE_UNITS / 1_000_000.0;
+    Ok(side.transactions() * lamports / LAMPORTS_PER_SOL * sol_usd)
+}
+
+/// Samples rates periodically so history accrues while the app is open.
+pub fn start_rate_tracking(aggregator: SharedRateAggregator) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TRACKING_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = aggregator.fetch_rates().await {
+                eprintln!("Lending rate sample failed: {e}");
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn compare_lending_rates(
+    asset: String,
+    side: RateSide,
+    aggregator: State<'_, SharedRateAggregator>,
+) -> Result<RateComparison, String> {
+    aggregator.compare(&asset, side).await
+}
+
+#[tauri::command]
+pub async fn get_lending_rate_history(
+    asset: String,
+    protocol: Option<Protocol>,
+    since: Option<i64>,
+    aggregator: State<'_, SharedRateAggregator>,
+) -> Result<Vec<RateSample>, String> {
+    Ok(aggregator.history(&asset, protocol.as_ref(), since).await)
+}
+
+#[tauri::command]
+pub async fn suggest_lending_move(
+    request: RateMoveRequest,
+    aggregator: State<'_, SharedRateAggregator>,
+) -> Result<RateMoveSuggestion, String> {
+    aggregator.suggest(&request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn venue(protocol: Protocol, supply_apy: f64, borrow_apy: f64, utilization: f64) -> VenueRate {
+        VenueRate {
+            protocol,
+            pool_address: String::new(),
+            asset: "USDC".to_string(),
+            supply_apy,
+            borrow_apy,
+            utilization,
+            available_liquidity: 1_000_000.0,
+            fetched_at: 0,
+        }
+    }
+
+    #[test]
+    fn recommends_only_when_gain_beats_threshold_and_cost() {
+        let venues = vec![
+            venue(Protocol::Solend, 5.0, 8.0, 0.6),
+            venue(Protocol::MarginFi, 6.5, 9.0, 0.7),
+            // Highest rate but nearly drained.
+            venue(Protocol::Kamino, 9.0, 12.0, 0.97),
+        ];
+        let current = &venues[0];
+
+        let move_ = suggest_move(
+            RateSide::Supply,
+            current,
+            &venues,
+            50_000.0,
+            1.0,
+            50.0,
+            30.0,
+        );
+        assert!(move_.recommended);
+        assert_eq!(move_.to, Some(Protocol::MarginFi));
+        assert!((move_.improvement_bps - 150.0).abs() < 1e-9);
+
+        let small = suggest_move(RateSide::Supply, current, &venues, 20.0, 1.0, 50.0, 30.0);
+        assert!(!small.recommended);
+
+        let strict = suggest_move(
+            RateSide::Supply,
+            current,
+            &venues,
+            50_000.0,
+            1.0,
+            200.0,
+            30.0,
+        );
+        assert!(!strict.recommended);
+    }
+
+    #[test]
+    fn borrow_side_prefers_the_cheapest_venue() {
+        let venues = vec![
+            venue(Protocol::Solend, 5.0, 8.0, 0.6),
+            venue(Protocol::Kamino, 4.0, 6.0, 0.5),
+        ];
+        let move_ = suggest_move(
+            RateSide::Borrow,
+            &venues[0],
+            &venues,
+            100_000.0,
+            2.0,
+            50.0,
+            30.0,
+        );
+        assert_eq!(move_.to, Some(Protocol::Kamino));
+        assert!(move_.recommended);
+
+        let already_best = suggest_move(
+            RateSide::Borrow,
+            &venues[1],
+            &venues,
+            100_000.0,
+            2.0,
+            50.0,
+            30.0,
+        );
+        assert_eq!(already_best.to, None);
+    }
+}
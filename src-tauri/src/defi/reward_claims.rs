@@ -0,0 +1,963 @@
+//! Staking reward claim scheduler for auto-compound.
+//!
+//! On a schedule, every wallet is scanned for claimable staking rewards, and
+//! each reward is weighed against the fee of claiming it. Worthwhile claims
+//! are either prepared straight away as unsigned transactions for the
+//! wallet to sign, or queued for approval first. Every decision, including
+//! the ones skipped as not worth the gas, is kept in its own table.
+//!
+//! Native stake accounts compound inflation rewards into the delegation by
+//! themselves, so the claimable part is the lamports above the delegation
+//! and rent reserve, such as MEV tips paid to the stake account. Liquid
+//! staking rewards come from the staking adapter; those are claimed in the
+//! protocol, so they are queued for approval rather than prepared here.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    message::{Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    stake::instruction::withdraw,
+    transaction::VersionedTransaction,
+};
+use sqlx::{Pool, Row, Sqlite, SqlitePool};
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::chains::{RoutingHint, RpcPool, SharedRpcPool};
+use crate::defi::staking::StakingAdapter;
+use crate::portfolio::dust::sol_price_usd;
+use crate::profiles::ProfilePaths;
+use crate::wallet::flows::own_wallet_addresses;
+use crate::wallet::tx_lifecycle::{SharedTransactionLifecycle, SubmitTransactionRequest};
+
+const CLAIMS_DB_FILE: &str = "staking_rewards.db";
+const SETTINGS_FILE: &str = "staking_reward_settings.json";
+const STAKE_PROGRAM: &str = "Stake11111111111111111111111111111111111111";
+/// Offset of the authorized withdrawer in a stake account.
+const WITHDRAWER_OFFSET: usize = 44;
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+const NATIVE_CLAIM_COMPUTE_UNITS: u64 = 5_000;
+const PROTOCOL_CLAIM_COMPUTE_UNITS: u64 = 200_000;
+const SCHEDULER_TICK: StdDuration = StdDuration::from_secs(15 * 60);
+
+pub const STAKING_CLAIM_READY_EVENT: &str = "staking_claim_ready";
+pub const STAKING_CLAIM_PENDING_EVENT: &str = "staking_claim_pending";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardSource {
+    NativeStake,
+    LiquidStaking,
+}
+
+impl RewardSource {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RewardSource::NativeStake => "native_stake",
+            RewardSource::LiquidStaking => "liquid_staking",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "native_stake" => RewardSource::NativeStake,
+            _ => RewardSource::LiquidStaking,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimStatus {
+    PendingApproval,
+    AwaitingSignature,
+    Submitted,
+    /// Not worth claiming at the time it was seen.
+    Skipped,
+    Rejected,
+    Failed,
+}
+
+impl ClaimStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ClaimStatus::PendingApproval => "pending_approval",
+            ClaimStatus::AwaitingSignature => "awaiting_signature",
+            ClaimStatus::Submitted => "submitted",
+            ClaimStatus::Skipped => "skipped",
+            ClaimStatus::Rejected => "rejected",
+            ClaimStatus::Failed => "failed",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "pending_approval" => ClaimStatus::PendingApproval,
+            "awaiting_signature" => ClaimStatus::AwaitingSignature,
+            "submitted" => ClaimStatus::Submitted,
+            "skipped" => ClaimStatus::Skipped,
+            "rejected" => ClaimStatus::Rejected,
+            _ => ClaimStatus::Failed,
+        }
+    }
+
+    fn is_open(&self) -> bool {
+        matches!(
+            self,
+            ClaimStatus::PendingApproval | ClaimStatus::AwaitingSignature
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimMode {
+    /// Prepare claim transactions as soon as they are worthwhile.
+    Auto,
+    /// Queue worthwhile claims until they are approved.
+    Approval,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RewardClaimSettings {
+    pub enabled: bool,
+    pub mode: ClaimMode,
+    pub interval_hours: u32,
+    pub min_reward_usd: f64,
+    /// A claim must be worth at least this many times its fee.
+    pub min_reward_to_gas_ratio: f64,
+    pub priority_fee_micro_lamports: u64,
+    pub last_run_at: Option<DateTime<Utc>>,
+}
+
+impl Default for RewardClaimSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: ClaimMode::Approval,
+            interval_hours: 24,
+            min_reward_usd: 1.0,
+            min_reward_to_gas_ratio: 10.0,
+            priority_fee_micro_lamports: 10_000,
+            last_run_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimableReward {
+    pub wallet: String,
+    pub source: RewardSource,
+    pub protocol: String,
+    /// Stake account or protocol position holding the reward.
+    pub account: String,
+    pub reward_token: String,
+    pub reward_amount: f64,
+    /// Native rewards in lamports, for building the withdrawal.
+    pub lamports: Option<u64>,
+    pub reward_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimEstimate {
+    #[serde(flatten)]
+    pub reward: ClaimableReward,
+    pub gas_usd: f64,
+    pub net_usd: f64,
+    pub worthwhile: bool,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RewardClaimRecord {
+    pub id: String,
+    pub wallet: String,
+    pub source: RewardSource,
+    pub protocol: String,
+    pub account: String,
+    pub reward_token: String,
+    pub reward_amount: f64,
+    pub lamports: Option<u64>,
+    pub reward_usd: f64,
+    pub gas_usd: f64,
+    pub net_usd: f64,
+    pub status: ClaimStatus,
+    /// Unsigned claim for the wallet to sign.
+    pub transaction_base64: Option<String>,
+    pub last_valid_block_height: Option<u64>,
+    pub signature: Option<String>,
+    pub note: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Fee of one claim transaction in lamports.
+pub fn claim_fee_lamports(source: RewardSource, priority_fee_micro_lamports: u64) -> u64 {
+    let compute_units = match source {
+        RewardSource::NativeStake => NATIVE_CLAIM_COMPUTE_UNITS,
+        RewardSource::LiquidStaking => PROTOCOL_CLAIM_COMPUTE_UNITS,
+    };
+    LAMPORTS_PER_SIGNATURE + compute_units * priority_fee_micro_lamports / 1_000_000
+}
+
+pub fn estimate_claim(
+    reward: ClaimableReward,
+    sol_usd: f64,
+    settings: &RewardClaimSettings,
+) -> ClaimEstimate {
+    let fee = claim_fee_lamports(reward.source, settings.priority_fee_micro_lamports);
+    let gas_usd = fee as f64 / LAMPORTS_PER_SOL * sol_usd;
+    let reason = if reward.reward_usd < settings.min_reward_usd {
+        Some(format!(
+            "Reward ${:.2} is below the ${:.2} minimum",
+            reward.reward_usd, settings.min_reward_usd
+        ))
+    } else if reward.reward_usd < gas_usd * settings.min_reward_to_gas_ratio {
+        Some(format!(
+            "Reward ${:.2} is less than {:.0}x the ${:.4} fee",
+            reward.reward_usd, settings.min_reward_to_gas_ratio, gas_usd
+        ))
+    } else {
+        None
+    };
+    ClaimEstimate {
+        net_usd: reward.reward_usd - gas_usd,
+        worthwhile: reason.is_none(),
+        reason,
+        gas_usd,
+        reward,
+    }
+}
+
+/// Reads a jsonParsed `getProgramAccounts` result for the stake program.
+/// Only delegated accounts are considered; lamports above the delegation
+/// and rent reserve are the claimable reward.
+pub fn parse_native_claimables(
+    wallet: &str,
+    accounts: &Value,
+    sol_usd: f64,
+) -> Vec<ClaimableReward> {
+    accounts
+        .as_array()
+        .map(|accounts| accounts.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|entry| {
+            let parsed = &entry["account"]["data"]["parsed"];
+            if parsed["type"].as_str() != Some("delegated") {
+                return None;
+            }
+            let info = &parsed["info"];
+            let lamports = entry["account"]["lamports"].as_u64()?;
+            let reserve = lamport_field(&info["meta"]["rentExemptReserve"])?;
+            let delegated = lamport_field(&info["stake"]["delegation"]["stake"])?;
+            let excess = lamports
+                .checked_sub(reserve + delegated)
+                .filter(|l| *l > 0)?;
+            let amount = excess as f64 / LAMPORTS_PER_SOL;
+            Some(ClaimableReward {
+                wallet: wallet.to_string(),
+                source: RewardSource::NativeStake,
+                protocol: "Native staking".to_string(),
+                account: entry["pubkey"].as_str()?.to_string(),
+                reward_token: "SOL".to_string(),
+                reward_amount: amount,
+                lamports: Some(excess),
+                reward_usd: amount * sol_usd,
+            })
+        })
+        .collect()
+}
+
+fn lamport_field(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|text| text.parse().ok()))
+}
+
+pub struct RewardClaimScheduler {
+    pool: Pool<Sqlite>,
+    settings: RewardClaimSettings,
+    settings_path: Option<PathBuf>,
+}
+
+pub type SharedRewardClaimScheduler = Arc<RwLock<RewardClaimScheduler>>;
+
+impl RewardClaimScheduler {
+    pub async fn new(app: &AppHandle) -> Result<Self, String> {
+        let data_dir = app
+            .path()
+            .profile_data_dir()
+            .map_err(|e| format!("Unable to resolve app data directory: {e}"))?;
+        fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
+        let db_url = format!(
+            "sqlite:{}?mode=rwc",
+            data_dir.join(CLAIMS_DB_FILE).display()
+        );
+        let pool = SqlitePool::connect(&db_url)
+            .await
+            .map_err(|e| format!("Failed to open staking rewards database: {e}"))?;
+
+        let settings_path = data_dir.join(SETTINGS_FILE);
+        let settings = fs::read_to_string(&settings_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        let scheduler = Self {
+            pool,
+            settings,
+            settings_path: Some(settings_path),
+        };
+        scheduler.initialize().await?;
+        Ok(scheduler)
+    }
+
+    async fn initialize(&self) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS staking_reward_claims (
+                id TEXT PRIMARY KEY,
+                wallet TEXT NOT NULL,
+                source TEXT NOT NULL,
+                protocol TEXT NOT NULL,
+                account TEXT NOT NULL,
+                reward_token TEXT NOT NULL,
+                reward_amount REAL NOT NULL,
+                lamports INTEGER,
+                reward_usd REAL NOT NULL,
+                gas_usd REAL NOT NULL,
+                net_usd REAL NOT NULL,
+                status TEXT NOT NULL,
+                transaction_base64 TEXT,
+                last_valid_block_height INTEGER,
+                signature TEXT,
+                note TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to create staking reward table: {e}"))?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_reward_claims_account ON staking_reward_claims(account, status)",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to index staking reward table: {e}"))?;
+        Ok(())
+    }
+
+    pub fn settings(&self) -> &RewardClaimSettings {
+        &self.settings
+    }
+
+    pub fn update_settings(&mut self, settings: RewardClaimSettings) {
+        self.settings = settings;
+        self.save_settings();
+    }
+
+    fn mark_run(&mut self, at: DateTime<Utc>) {
+        self.settings.last_run_at = Some(at);
+        self.save_settings();
+    }
+
+    fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.settings.enabled
+            && self.settings.last_run_at.map_or(true, |last| {
+                now - last >= Duration::hours(self.settings.interval_hours.max(1) as i64)
+            })
+    }
+
+    fn save_settings(&self) {
+        let Some(path) = &self.settings_path else {
+            return;
+        };
+        match serde_json::to_string_pretty(&self.settings) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(path, contents) {
+                    eprintln!("Failed to save staking reward settings: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize staking reward settings: {}", e),
+        }
+    }
+
+    async fn has_open_claim(&self, account: &str) -> Result<bool, String> {
+        let rows = sqlx::query("SELECT status FROM staking_reward_claims WHERE account = ?1")
+            .bind(account)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to read staking reward claims: {e}"))?;
+        Ok(rows.iter().any(|row| {
+            row.try_get::<String, _>("status")
+                .map(|status| ClaimStatus::parse(&status).is_open())
+                .unwrap_or(false)
+        }))
+    }
+
+    async fn insert(&self, record: &RewardClaimRecord) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            INSERT INTO staking_reward_claims (
+                id, wallet, source, protocol, account, reward_token, reward_amount,
+                lamports, reward_usd, gas_usd, net_usd, status, transaction_base64,
+                last_valid_block_height, signature, note, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+            "#,
+        )
+        .bind(&record.id)
+        .bind(&record.wallet)
+        .bind(record.source.as_str())
+        .bind(&record.protocol)
+        .bind(&record.account)
+        .bind(&record.reward_token)
+        .bind(record.reward_amount)
+        .bind(record.lamports.map(|l| l as i64))
+        .bind(record.reward_usd)
+        .bind(record.gas_usd)
+        .bind(record.net_usd)
+        .bind(record.status.as_str())
+        .bind(&record.transaction_base64)
+        .bind(record.last_valid_block_height.map(|h| h as i64))
+        .bind(&record.signature)
+        .bind(&record.note)
+        .bind(&record.created_at)
+        .bind(&record.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to record staking reward claim: {e}"))?;
+        Ok(())
+    }
+
+    async fn update(&self, record: &RewardClaimRecord) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            UPDATE staking_reward_claims
+            SET status = ?1, transaction_base64 = ?2, last_valid_block_height = ?3,
+                signature = ?4, note = ?5, updated_at = ?6
+            WHERE id = ?7
+            "#,
+        )
+        .bind(record.status.as_str())
+        .bind(&record.transaction_base64)
+        .bind(record.last_valid_block_height.map(|h| h as i64))
+        .bind(&record.signature)
+        .bind(&record.note)
+        .bind(&record.updated_at)
+        .bind(&record.id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to update staking reward claim: {e}"))?;
+        Ok(())
+    }
+
+    pub async fn get(&self, id: &str) -> Result<RewardClaimRecord, String> {
+        let row = sqlx::query("SELECT * FROM staking_reward_claims WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to read staking reward claim: {e}"))?
+            .ok_or_else(|| format!("Staking reward claim {id} not found"))?;
+        row_to_record(&row)
+    }
+
+    pub async fn history(
+        &self,
+        status: Option<ClaimStatus>,
+        limit: i64,
+    ) -> Result<Vec<RewardClaimRecord>, String> {
+        let rows = match status {
+            Some(status) => {
+                sqlx::query(
+                    "SELECT * FROM staking_reward_claims WHERE status = ?1 ORDER BY created_at DESC LIMIT ?2",
+                )
+                .bind(status.as_str())
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await
+            }
+            None => {
+                sqlx::query("SELECT * FROM staking_reward_claims ORDER BY created_at DESC LIMIT ?1")
+                    .bind(limit)
+                    .fetch_all(&self.pool)
+                    .await
+            }
+        }
+        .map_err(|e| format!("Failed to read staking reward claims: {e}"))?;
+        rows.iter().map(row_to_record).collect()
+    }
+}
+
+fn row_to_record(row: &sqlx::sqlite::SqliteRow) -> Result<RewardClaimRecord, String> {
+    let read = |e: sqlx::Error| format!("Failed to read staking reward claim: {e}");
+    Ok(RewardClaimRecord {
+        id: row.try_get("id").map_err(read)?,
+        wallet: row.try_get("wallet").map_err(read)?,
+        source: RewardSource::parse(&row.try_get::<String, _>("source").map_err(read)?),
+        protocol: row.try_get("protocol").map_err(read)?,
+        account: row.try_get("account").map_err(read)?,
+        reward_token: row.try_get("reward_token").map_err(read)?,
+        reward_amount: row.try_get("reward_amount").map_err(read)?,
+        lamports: row
+            .try_get::<Option<i64>, _>("lamports")
+            .map_err(read)?
+            .map(|l| l as u64),
+        reward_usd: row.try_get("reward_usd").map_err(read)?,
+        gas_usd: row.try_get("gas_usd").map_err(read)?,
+        net_usd: row.try_get("net_usd").map_err(read)?,
+        status: ClaimStatus::parse(&row.try_get::<String, _>("status").map_err(read)?),
+        transaction_base64: row.try_get("transaction_base64").map_err(read)?,
+        last_valid_block_height: row
+            .try_get::<Option<i64>, _>("last_valid_block_height")
+            .map_err(read)?
+            .map(|h| h as u64),
+        signature: row.try_get("signature").map_err(read)?,
+        note: row.try_get("note").map_err(read)?,
+        created_at: row.try_get("created_at").map_err(read)?,
+        updated_at: row.try_get("updated_at").map_err(read)?,
+    })
+}
+
+async fn fetch_native_claimables(
+    pool: &SharedRpcPool,
+    wallet: &str,
+    sol_usd: f64,
+) -> Result<Vec<ClaimableReward>, String> {
+    let params = json!([
+        STAKE_PROGRAM,
+        {
+            "encoding": "jsonParsed",
+            "commitment": "confirmed",
+            "filters": [{ "memcmp": { "offset": WITHDRAWER_OFFSET, "bytes": wallet } }]
+        }
+    ]);
+    let accounts: Value = RpcPool::call(pool, RoutingHint::Read, move |client| {
+        client.send(RpcRequest::GetProgramAccounts, params.clone())
+    })
+    .await
+    .map_err(|e| format!("Failed to list stake accounts for {wallet}: {e}"))?;
+    Ok(parse_native_claimables(wallet, &accounts, sol_usd))
+}
+
+async fn fetch_liquid_claimables(wallet: &str) -> Result<Vec<ClaimableReward>, String> {
+    let positions = StakingAdapter::new().get_positions(wallet).await?;
+    Ok(positions
+        .into_iter()
+        .flat_map(|position| {
+            let protocol = format!("{:?}", position.protocol);
+            let account = position.id.clone();
+            position
+                .rewards
+                .into_iter()
+                .filter(|reward| reward.amount > 0.0)
+                .map(move |reward| ClaimableReward {
+                    wallet: wallet.to_string(),
+                    source: RewardSource::LiquidStaking,
+                    protocol: protocol.clone(),
+                    account: account.clone(),
+                    reward_token: reward.token,
+                    reward_amount: reward.amount,
+                    lamports: None,
+                    reward_usd: reward.value_usd,
+                })
+        })
+        .collect())
+}
+
+/// Every claimable reward across the user's wallets, with fee estimates.
+pub async fn scan_claimable_rewards(
+    app: &AppHandle,
+    settings: &RewardClaimSettings,
+) -> Result<Vec<ClaimEstimate>, String> {
+    let sol_usd = sol_price_usd().await?;
+    let pool = app
+        .try_state::<SharedRpcPool>()
+        .map(|pool| pool.inner().clone());
+    let mut rewards = Vec::new();
+    for wallet in own_wallet_addresses(app) {
+        if let Some(pool) = &pool {
+            match fetch_native_claimables(pool, &wallet, sol_usd).await {
+                Ok(found) => rewards.extend(found),
+                Err(e) => eprintln!("Native stake scan failed: {}", e),
+            }
+        }
+        match fetch_liquid_claimables(&wallet).await {
+            Ok(found) => rewards.extend(found),
+            Err(e) => eprintln!("Liquid staking scan failed for {}: {}", wallet, e),
+        }
+    }
+    Ok(rewards
+        .into_iter()
+        .map(|reward| estimate_claim(reward, sol_usd, settings))
+        .collect())
+}
+
+/// Builds the unsigned withdrawal of a native stake account's excess
+/// lamports back to the withdrawer.
+async fn build_native_claim(
+    pool: &SharedRpcPool,
+    record: &RewardClaimRecord,
+) -> Result<(String, u64), String> {
+    let lamports = record
+        .lamports
+        .ok_or_else(|| "Native claim is missing its lamport amount".to_string())?;
+    let stake =
+        Pubkey::from_str(&record.account).map_err(|e| format!("Invalid stake account: {e}"))?;
+    let wallet = Pubkey::from_str(&record.wallet).map_err(|e| format!("Invalid wallet: {e}"))?;
+    let (blockhash, last_valid_block_height) = RpcPool::call(pool, RoutingHint::Read, |client| {
+        client.get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())
+    })
+    .await?;
+
+    let instruction = withdraw(&stake, &wallet, &wallet, lamports, None);
+    let message = Message::new_with_blockhash(&[instruction], Some(&wallet), &blockhash);
+    let transaction = VersionedTransaction {
+        signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+        message: VersionedMessage::Legacy(message),
+    };
+    let bytes = bincode::serialize(&transaction).map_err(|e| e.to_string())?;
+    Ok((STANDARD.encode(bytes), last_valid_block_height))
+}
+
+/// Moves an approved or auto-claimed record on: native claims get an
+/// unsigned transaction, liquid staking claims stay with the user.
+async fn prepare_claim(app: &AppHandle, record: &mut RewardClaimRecord) {
+    record.updated_at = Utc::now().to_rfc3339();
+    if record.source != RewardSource::NativeStake {
+        record.status = ClaimStatus::PendingApproval;
+        record.note = Some(format!(
+            "Claim in {} to collect this reward",
+            record.protocol
+        ));
+        return;
+    }
+    if let Err(e) = crate::environment::require_mainnet("Staking reward claims") {
+        record.status = ClaimStatus::Failed;
+        record.note = Some(e.to_string());
+        return;
+    }
+    let Some(pool) = app.try_state::<SharedRpcPool>() else {
+        record.status = ClaimStatus::Failed;
+        record.note = Some("RPC pool is not available".to_string());
+        return;
+    };
+    match build_native_claim(pool.inner(), record).await {
+        Ok((transaction, last_valid_block_height)) => {
+            record.status = ClaimStatus::AwaitingSignature;
+            record.transaction_base64 = Some(transaction);
+            record.last_valid_block_height = Some(last_valid_block_height);
+            record.note = None;
+            let _ = app.emit(STAKING_CLAIM_READY_EVENT, &*record);
+        }
+        Err(e) => {
+            record.status = ClaimStatus::Failed;
+            record.note = Some(e);
+        }
+    }
+}
+
+/// One scheduled pass: records every claimable reward not already open and
+/// claims or queues the worthwhile ones according to the mode.
+pub async fn run_reward_claims(
+    app: &AppHandle,
+    scheduler: &SharedRewardClaimScheduler,
+) -> Result<Vec<RewardClaimRecord>, String> {
+    let settings = scheduler.read().await.settings().clone();
+    let estimates = scan_claimable_rewards(app, &settings).await?;
+    let mut recorded = Vec::new();
+    for estimate in estimates {
+        if scheduler
+            .read()
+            .await
+            .has_open_claim(&estimate.reward.account)
+            .await?
+        {
+            continue;
+        }
+        let now = Utc::now().to_rfc3339();
+        let reward = estimate.reward;
+        let mut record = RewardClaimRecord {
+            id: Uuid::new_v4().to_string(),
+            wallet: reward.wallet,
+            source: reward.source,
+            protocol: reward.protocol,
+            account: reward.account,
+            reward_token: reward.reward_token,
+            reward_amount: reward.reward_amount,
+            lamports: reward.lamports,
+            reward_usd: reward.reward_usd,
+            gas_usd: estimate.gas_usd,
+            net_usd: estimate.net_usd,
+            status: ClaimStatus::Skipped,
+            transaction_base64: None,
+            last_valid_block_height: None,
+            signature: None,
+            note: estimate.reason,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        if estimate.worthwhile {
+            match settings.mode {
+                ClaimMode::Auto => prepare_claim(app, &mut record).await,
+                ClaimMode::Approval => {
+                    record.status = ClaimStatus::PendingApproval;
+                    let _ = app.emit(STAKING_CLAIM_PENDING_EVENT, &record);
+                }
+            }
+        }
+        scheduler.read().await.insert(&record).await?;
+        recorded.push(record);
+    }
+    scheduler.write().await.mark_run(Utc::now());
+    Ok(recorded)
+}
+
+pub fn start_reward_claim_scheduler(app: AppHandle, scheduler: SharedRewardClaimScheduler) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(SCHEDULER_TICK);
+        loop {
+            ticker.tick().await;
+            if !scheduler.read().await.is_due(Utc::now()) {
+                continue;
+            }
+            if let Err(e) = run_reward_claims(&app, &scheduler).await {
+                eprintln!("Staking reward claim run failed: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn staking_rewards_scan(
+    app: AppHandle,
+    scheduler: State<'_, SharedRewardClaimScheduler>,
+) -> Result<Vec<ClaimEstimate>, String> {
+    let settings = scheduler.read().await.settings().clone();
+    scan_claimable_rewards(&app, &settings).await
+}
+
+#[tauri::command]
+pub async fn staking_rewards_run_now(
+    app: AppHandle,
+    scheduler: State<'_, SharedRewardClaimScheduler>,
+) -> Result<Vec<RewardClaimRecord>, String> {
+    run_reward_claims(&app, scheduler.inner()).await
+}
+
+#[tauri::command]
+pub async fn staking_rewards_get_settings(
+    scheduler: State<'_, SharedRewardClaimScheduler>,
+) -> Result<RewardClaimSettings, String> {
+    Ok(scheduler.read().await.settings().clone())
+}
+
+#[tauri::command]
+pub async fn staking_rewards_update_settings(
+    mut settings: RewardClaimSettings,
+    scheduler: State<'_, SharedRewardClaimScheduler>,
+) -> Result<RewardClaimSettings, String> {
+    if settings.interval_hours == 0 {
+        return Err("Claim interval must be at least one hour".into());
+    }
+    let mut scheduler = scheduler.write().await;
+    settings.last_run_at = scheduler.settings().last_run_at;
+    scheduler.update_settings(settings.clone());
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn staking_rewards_history(
+    status: Option<ClaimStatus>,
+    limit: Option<i64>,
+    scheduler: State<'_, SharedRewardClaimScheduler>,
+) -> Result<Vec<RewardClaimRecord>, String> {
+    scheduler
+        .read()
+        .await
+        .history(status, limit.unwrap_or(100).clamp(1, 1000))
+        .await
+}
+
+#[tauri::command]
+pub async fn staking_rewards_approve(
+    app: AppHandle,
+    id: String,
+    scheduler: State<'_, SharedRewardClaimScheduler>,
+) -> Result<RewardClaimRecord, String> {
+    let mut record = scheduler.read().await.get(&id).await?;
+    if record.status != ClaimStatus::PendingApproval {
+        return Err(format!("Claim {id} is not awaiting approval"));
+    }
+    if record.source != RewardSource::NativeStake {
+        return Err(format!(
+            "Claim {} rewards in {}; they cannot be claimed from here",
+            record.reward_token, record.protocol
+        ));
+    }
+    prepare_claim(&app, &mut record).await;
+    scheduler.read().await.update(&record).await?;
+    Ok(record)
+}
+
+#[tauri::command]
+pub async fn staking_rewards_reject(
+    id: String,
+    scheduler: State<'_, SharedRewardClaimScheduler>,
+) -> Result<RewardClaimRecord, String> {
+    let scheduler = scheduler.read().await;
+    let mut record = scheduler.get(&id).await?;
+    if !record.status.is_open() {
+        return Err(format!("Claim {id} is already closed"));
+    }
+    record.status = ClaimStatus::Rejected;
+    record.transaction_base64 = None;
+    record.updated_at = Utc::now().to_rfc3339();
+    scheduler.update(&record).await?;
+    Ok(record)
+}
+
+/// Submits the claim transaction the wallet signed.
+#[tauri::command]
+pub async fn staking_rewards_submit(
+    app: AppHandle,
+    id: String,
+    signed_transaction: String,
+    scheduler: State<'_, SharedRewardClaimScheduler>,
+    lifecycle: State<'_, SharedTransactionLifecycle>,
+) -> Result<RewardClaimRecord, String> {
+    let mut record = scheduler.read().await.get(&id).await?;
+    if record.status != ClaimStatus::AwaitingSignature {
+        return Err(format!("Claim {id} is not awaiting a signature"));
+    }
+    let submitted = lifecycle
+        .submit(
+            app,
+            SubmitTransactionRequest {
+                transaction_base64: signed_transaction,
+                last_valid_block_height: record.last_valid_block_height,
+                max_attempts: None,
+                fee_schedule: None,
+                label: Some(format!("Staking reward claim {}", record.account)),
+            },
+        )
+        .await;
+    match submitted {
+        Ok(tracked) => {
+            record.status = ClaimStatus::Submitted;
+            record.signature = Some(tracked.signature);
+            record.note = None;
+        }
+        Err(e) => {
+            record.status = ClaimStatus::Failed;
+            record.note = Some(e);
+        }
+    }
+    record.transaction_base64 = None;
+    record.updated_at = Utc::now().to_rfc3339();
+    scheduler.read().await.update(&record).await?;
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn native_claimables_are_the_lamports_above_delegation_and_rent() {
+        let accounts = json!([
+            {
+                "pubkey": "StakeAcct1",
+                "account": {
+                    "lamports": 10_002_282_880u64 + 50_000_000,
+                    "data": { "parsed": { "type": "delegated", "info": {
+                        "meta": { "rentExemptReserve": "2282880" },
+                        "stake": { "delegation": { "stake": "10000000000" } }
+                    } } }
+                }
+            },
+            {
+                "pubkey": "StakeAcct2",
+                "account": {
+                    "lamports": 10_002_282_880u64,
+                    "data": { "parsed": { "type": "delegated", "info": {
+                        "meta": { "rentExemptReserve": "2282880" },
+                        "stake": { "delegation": { "stake": "10000000000" } }
+                    } } }
+                }
+            },
+            {
+                "pubkey": "Undelegated",
+                "account": {
+                    "lamports": 5_000_000_000u64,
+                    "data": { "parsed": { "type": "initialized", "info": {
+                        "meta": { "rentExemptReserve": "2282880" }
+                    } } }
+                }
+            }
+        ]);
+
+        let claimables = parse_native_claimables("wallet", &accounts, 150.0);
+
+        assert_eq!(claimables.len(), 1);
+        assert_eq!(claimables[0].account, "StakeAcct1");
+        assert_eq!(claimables[0].lamports, Some(50_000_000));
+        assert!((claimables[0].reward_usd - 7.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn claims_must_clear_the_minimum_and_the_fee_multiple() {
+        let settings = RewardClaimSettings {
+            min_reward_usd: 1.0,
+            min_reward_to_gas_ratio: 10.0,
+            priority_fee_micro_lamports: 1_000_000,
+            ..RewardClaimSettings::default()
+        };
+        let reward = |source, reward_usd| ClaimableReward {
+            wallet: "wallet".into(),
+            source,
+            protocol: "p".into(),
+            account: "a".into(),
+            reward_token: "SOL".into(),
+            reward_amount: 0.0,
+            lamports: None,
+            reward_usd,
+        };
+
+        // Native: 5000 + 5000 lamports = 0.00001 SOL = $0.0015 at $150.
+        let native = estimate_claim(reward(RewardSource::NativeStake, 2.0), 150.0, &settings);
+        assert!(native.worthwhile);
+        assert!((native.gas_usd - 0.0015).abs() < 1e-12);
+
+        let tiny = estimate_claim(reward(RewardSource::NativeStake, 0.5), 150.0, &settings);
+        assert!(!tiny.worthwhile);
+
+        // Protocol claims burn 200k CU: 205_000 lamports = $0.03075, so $0.25
+        // clears the minimum but not 10x the fee.
+        let mut cheap = settings.clone();
+        cheap.min_reward_usd = 0.1;
+        let liquid = estimate_claim(reward(RewardSource::LiquidStaking, 0.25), 150.0, &cheap);
+        assert!(!liquid.worthwhile);
+        assert!(liquid.reason.unwrap().contains("10x"));
+    }
+}
@@ -0,0 +1,724 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::defi::protocol_risk::protocol_risk_score;
+use crate::defi::solend::SolendAdapter;
+use crate::defi::staking::StakingAdapter;
+use crate::defi::types::*;
+use crate::defi::yield_farming::YieldFarmingAdapter;
+use crate::profiles::ProfilePaths;
+
+const STRATEGIES_FILE: &str = "yield_strategies.json";
+const SECONDS_PER_YEAR: f64 = 365.0 * 86_400.0;
+const DEFAULT_COMPOUND_COST_USD: f64 = 0.01;
+const CASH_FLOW_POINTS: u32 = 12;
+
+/// One link in a strategy chain. Capital moves through the steps in order,
+/// so each yield-bearing step keeps earning while the next one is layered
+/// on top (e.g. a liquid staking token that is then supplied to an LP).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StrategyStep {
+    #[serde(rename_all = "camelCase")]
+    Stake { pool_address: String },
+    #[serde(rename_all = "camelCase")]
+    Lend { pool_address: String },
+    #[serde(rename_all = "camelCase")]
+    ProvideLiquidity { farm_id: String },
+    /// Reinvests everything earned by the steps before it.
+    #[serde(rename_all = "camelCase")]
+    AutoCompound {
+        frequency_secs: u64,
+        cost_per_compound_usd: Option<f64>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyDraft {
+    pub name: String,
+    pub principal_usd: f64,
+    pub horizon_days: u32,
+    pub steps: Vec<StrategyStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedStep {
+    pub label: String,
+    pub protocol: Protocol,
+    pub apy_low: f64,
+    pub apy_base: f64,
+    pub apy_high: f64,
+    /// Entry plus exit fees, in percent of the capital passing through.
+    pub fee_pct: f64,
+    pub lock_days: Option<u64>,
+    pub early_withdrawal_penalty_pct: f64,
+    pub risk_score: u8,
+    /// Share of the base APY paid in incentive tokens rather than fees or
+    /// protocol revenue.
+    pub emissions_share: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StrategyRiskKind {
+    Lockup,
+    EarlyWithdrawalPenalty,
+    ImpermanentLoss,
+    EmissionDependence,
+    ProtocolRisk,
+    Composability,
+    CompoundingCost,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyRiskFactor {
+    pub kind: StrategyRiskKind,
+    pub step_index: Option<usize>,
+    pub severity: RiskLevel,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CashFlowPoint {
+    pub day: u32,
+    pub value_low: f64,
+    pub value_base: f64,
+    pub value_high: f64,
+    pub earned_base: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyProjection {
+    pub draft: StrategyDraft,
+    pub steps: Vec<ResolvedStep>,
+    pub apy_low: f64,
+    pub apy_base: f64,
+    pub apy_high: f64,
+    pub fees_usd: f64,
+    pub compounding_cost_usd: f64,
+    pub cash_flows: Vec<CashFlowPoint>,
+    pub risk_factors: Vec<StrategyRiskFactor>,
+    pub overall_risk: RiskLevel,
+    pub generated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StrategyValuation {
+    pub timestamp: i64,
+    pub value_usd: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DeployedStrategyStatus {
+    Active,
+    Closed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployedStrategy {
+    pub id: String,
+    pub wallet: String,
+    pub projection: StrategyProjection,
+    pub deployed_at: i64,
+    pub status: DeployedStrategyStatus,
+    #[serde(default)]
+    pub valuations: Vec<StrategyValuation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YieldStrategyPerformance {
+    pub strategy_id: String,
+    pub days_elapsed: f64,
+    pub expected_low: f64,
+    pub expected_base: f64,
+    pub expected_high: f64,
+    pub actual_value: Option<f64>,
+    /// Actual against the base projection, in percent.
+    pub deviation_pct: Option<f64>,
+    pub within_range: Option<bool>,
+}
+
+/// Rates the projection works with once an auto-compound step has been
+/// folded in: periods per year and the cost of each period.
+#[derive(Debug, Clone, Copy)]
+struct Compounding {
+    periods_per_year: f64,
+    cost_per_period_usd: f64,
+}
+
+fn severity_for_score(score: u8) -> RiskLevel {
+    match score {
+        0..=30 => RiskLevel::Low,
+        31..=55 => RiskLevel::Medium,
+        56..=80 => RiskLevel::High,
+        _ => RiskLevel::Critical,
+    }
+}
+
+/// Value after `days` at `apy` percent, net of compounding costs.
+fn value_at(invested: f64, apy: f64, days: f64, compounding: Option<Compounding>) -> f64 {
+    let years = days / 365.0;
+    match compounding {
+        Some(c) => {
+            let periods = c.periods_per_year * years;
+            invested * (1.0 + apy / 100.0 / c.periods_per_year).powf(periods)
+                - c.cost_per_period_usd * periods
+        }
+        None => invested * (1.0 + apy / 100.0 * years),
+    }
+}
+
+fn step_risks(index: usize, step: &ResolvedStep, horizon_days: u32) -> Vec<StrategyRiskFactor> {
+    let mut risks = vec![StrategyRiskFactor {
+        kind: StrategyRiskKind::ProtocolRisk,
+        step_index: Some(index),
+        severity: severity_for_score(step.risk_score),
+        description: format!(
+            "{} on {:?} carries a protocol risk score of {}",
+            step.label, step.protocol, step.risk_score
+        ),
+    }];
+    if let Some(lock) = step.lock_days {
+        risks.push(StrategyRiskFactor {
+            kind: StrategyRiskKind::Lockup,
+            step_index: Some(index),
+            severity: if lock > horizon_days as u64 {
+                RiskLevel::High
+            } else {
+                RiskLevel::Medium
+            },
+            description: format!("{} locks funds for {lock} days", step.label),
+        });
+        if step.early_withdrawal_penalty_pct > 0.0 && lock > horizon_days as u64 {
+            risks.push(StrategyRiskFactor {
+                kind: StrategyRiskKind::EarlyWithdrawalPenalty,
+                step_index: Some(index),
+                severity: RiskLevel::High,
+                description: format!(
+                    "Exiting after {horizon_days} days costs a {:.1}% early-withdrawal penalty",
+                    step.early_withdrawal_penalty_pct
+                ),
+            });
+        }
+    }
+    if step.emissions_share > 0.5 {
+        risks.push(StrategyRiskFactor {
+            kind: StrategyRiskKind::EmissionDependence,
+            step_index: Some(index),
+            severity: RiskLevel::Medium,
+            description: format!(
+                "{:.0}% of the {} yield is paid in incentive tokens that can be cut or sold down",
+                step.emissions_share * 100.0,
+                step.label
+            ),
+        });
+    }
+    risks
+}
+
+/// Projects cash flows, the APY range and risk factors for a chain whose
+/// steps have already been resolved against live pool data.
+pub fn project_strategy(
+    draft: StrategyDraft,
+    steps: Vec<ResolvedStep>,
+) -> Result<StrategyProjection, String> {
+    if draft.principal_usd <= 0.0 {
+        return Err("Principal must be positive".to_string());
+    }
+    if draft.horizon_days == 0 {
+        return Err("Horizon must be at least one day".to_string());
+    }
+    let mut compounding = None;
+    for (index, step) in draft.steps.iter().enumerate() {
+        if let StrategyStep::AutoCompound {
+            frequency_secs,
+            cost_per_compound_usd,
+        } = step
+        {
+            if index == 0 {
+                return Err("Auto-compound needs a yield step before it".to_string());
+            }
+            if compounding.is_some() {
+                return Err("A strategy can only auto-compound once".to_string());
+            }
+            if *frequency_secs == 0 {
+                return Err("Compound frequency must be positive".to_string());
+            }
+            compounding = Some(Compounding {
+                periods_per_year: SECONDS_PER_YEAR / *frequency_secs as f64,
+                cost_per_period_usd: cost_per_compound_usd.unwrap_or(DEFAULT_COMPOUND_COST_USD),
+            });
+        }
+    }
+    if steps.is_empty() {
+        return Err("A strategy needs at least one yield step".to_string());
+    }
+
+    let fee_pct: f64 = steps.iter().map(|s| s.fee_pct).sum();
+    let fees_usd = draft.principal_usd * fee_pct / 100.0;
+    let invested = draft.principal_usd - fees_usd;
+    let (apy_low, apy_base, apy_high) = steps.iter().fold((0.0, 0.0, 0.0), |acc, s| {
+        (acc.0 + s.apy_low, acc.1 + s.apy_base, acc.2 + s.apy_high)
+    });
+
+    let interval = (draft.horizon_days / CASH_FLOW_POINTS).max(1);
+    let mut days: Vec<u32> = (0..=draft.horizon_days)
+        .step_by(interval as usize)
+        .collect();
+    if days.last() != Some(&draft.horizon_days) {
+        days.push(draft.horizon_days);
+    }
+    let cash_flows: Vec<CashFlowPoint> = days
+        .into_iter()
+        .map(|day| {
+            let value_base = value_at(invested, apy_base, day as f64, compounding);
+            CashFlowPoint {
+                day,
+                value_low: value_at(invested, apy_low, day as f64, compounding),
+                value_base,
+                value_high: value_at(invested, apy_high, day as f64, compounding),
+                earned_base: value_base - invested,
+            }
+        })
+        .collect();
+
+    let mut risk_factors: Vec<StrategyRiskFactor> = steps
+        .iter()
+        .enumerate()
+        .flat_map(|(index, step)| step_risks(index, step, draft.horizon_days))
+        .collect();
+    if let Some(index) = draft
+        .steps
+        .iter()
+        .position(|s| matches!(s, StrategyStep::ProvideLiquidity { .. }))
+    {
+        risk_factors.push(StrategyRiskFactor {
+            kind: StrategyRiskKind::ImpermanentLoss,
+            step_index: Some(index),
+            severity: RiskLevel::Medium,
+            description: "LP value drifts from holding if the pair's prices diverge; not modeled in the projection".to_string(),
+        });
+    }
+    if steps.len() > 1 {
+        risk_factors.push(StrategyRiskFactor {
+            kind: StrategyRiskKind::Composability,
+            step_index: None,
+            severity: if steps.len() > 2 {
+                RiskLevel::High
+            } else {
+                RiskLevel::Medium
+            },
+            description: format!(
+                "{} protocols are stacked; a failure in any of them affects the whole position",
+                steps.len()
+            ),
+        });
+    }
+    let compounding_cost_usd = compounding.map_or(0.0, |c| {
+        c.cost_per_period_usd * c.periods_per_year * draft.horizon_days as f64 / 365.0
+    });
+    let simple_gain = invested * apy_base / 100.0 * draft.horizon_days as f64 / 365.0;
+    if compounding.is_some() && compounding_cost_usd > simple_gain * 0.25 {
+        risk_factors.push(StrategyRiskFactor {
+            kind: StrategyRiskKind::CompoundingCost,
+            step_index: None,
+            severity: RiskLevel::Medium,
+            description: format!(
+                "Compounding costs ${compounding_cost_usd:.2}, over a quarter of the expected yield; compound less often"
+            ),
+        });
+    }
+    let overall_risk = risk_factors
+        .iter()
+        .map(|r| r.severity.clone())
+        .max_by_key(RiskLevel::rank)
+        .unwrap_or(RiskLevel::Low);
+
+    Ok(StrategyProjection {
+        draft,
+        steps,
+        apy_low,
+        apy_base,
+        apy_high,
+        fees_usd,
+        compounding_cost_usd,
+        cash_flows,
+        risk_factors,
+        overall_risk,
+        generated_at: Utc::now().timestamp(),
+    })
+}
+
+/// Looks up every yield-bearing step against the pools offered by the
+/// venue adapters.
+async fn resolve_steps(steps: &[StrategyStep]) -> Result<Vec<ResolvedStep>, String> {
+    let staking_pools = StakingAdapter::new().get_pools().await?;
+    let lending_pools = SolendAdapter::new().get_lending_pools().await?;
+    let farms = YieldFarmingAdapter::new().get_all_farms().await?;
+
+    let mut resolved = Vec::new();
+    for step in steps {
+        match step {
+            StrategyStep::Stake { pool_address } => {
+                let pool = staking_pools
+                    .iter()
+                    .find(|p| &p.pool_address == pool_address)
+                    .ok_or_else(|| format!("Staking pool {pool_address} not found"))?;
+                resolved.push(ResolvedStep {
+                    label: format!("Stake {}", pool.stake_token),
+                    protocol: pool.protocol.clone(),
+                    apy_low: pool.apy * 0.9,
+                    apy_base: pool.apy,
+                    apy_high: pool.apy * 1.05,
+                    fee_pct: 0.0,
+                    lock_days: pool.lock_duration,
+                    early_withdrawal_penalty_pct: pool.early_withdrawal_penalty,
+                    risk_score: protocol_risk_score(&pool.protocol).score,
+                    emissions_share: if pool.reward_token == pool.stake_token {
+                        0.0
+                    } else {
+                        1.0
+                    },
+                });
+            }
+            StrategyStep::Lend { pool_address } => {
+                let pool = lending_pools
+                    .iter()
+                    .find(|p| &p.pool_address == pool_address)
+                    .ok_or_else(|| format!("Lending pool {pool_address} not found"))?;
+                // Supply rates follow utilization and swing more than staking.
+                resolved.push(ResolvedStep {
+                    label: format!("Lend {}", pool.asset),
+                    protocol: pool.protocol.clone(),
+                    apy_low: pool.supply_apy * 0.6,
+                    apy_base: pool.supply_apy,
+                    apy_high: pool.supply_apy * 1.3,
+                    fee_pct: 0.0,
+                    lock_days: None,
+                    early_withdrawal_penalty_pct: 0.0,
+                    risk_score: (pool.utilization_rate * 60.0).round() as u8,
+                    emissions_share: 0.0,
+                });
+            }
+            StrategyStep::ProvideLiquidity { farm_id } => {
+                let farm = farms
+                    .iter()
+                    .find(|f| &f.id == farm_id)
+                    .ok_or_else(|| format!("Farm {farm_id} not found"))?;
+                let apy_base = farm.base_apy + farm.reward_apy;
+                // Trading fees move with volume; emissions are the part most
+                // likely to be cut.
+                resolved.push(ResolvedStep {
+                    label: format!("LP {}", farm.lp_token),
+                    protocol: farm.protocol.clone(),
+                    apy_low: farm.base_apy * 0.7 + farm.reward_apy * 0.4,
+                    apy_base,
+                    apy_high: farm.base_apy * 1.3 + farm.reward_apy * 1.1,
+                    fee_pct: farm.deposit_fee + farm.withdrawal_fee,
+                    lock_days: farm.lock_period,
+                    early_withdrawal_penalty_pct: 0.0,
+                    risk_score: farm.risk_score,
+                    emissions_share: if apy_base > 0.0 {
+                        farm.reward_apy / apy_base
+                    } else {
+                        0.0
+                    },
+                });
+            }
+            StrategyStep::AutoCompound { .. } => {}
+        }
+    }
+    Ok(resolved)
+}
+
+pub async fn build_projection(draft: StrategyDraft) -> Result<StrategyProjection, String> {
+    let steps = resolve_steps(&draft.steps).await?;
+    project_strategy(draft, steps)
+}
+
+/// Deployed strategies and their recorded valuations, kept so they can be
+/// checked against what was projected at deployment.
+pub struct StrategyBook {
+    strategies: RwLock<HashMap<String, DeployedStrategy>>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedStrategyBook = Arc<StrategyBook>;
+
+impl StrategyBook {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        let path = app_handle
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(STRATEGIES_FILE));
+        let strategies = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            strategies: RwLock::new(strategies),
+            path,
+        }
+    }
+
+    pub async fn list(&self, wallet: Option<&str>) -> Vec<DeployedStrategy> {
+        let mut strategies: Vec<_> = self
+            .strategies
+            .read()
+            .await
+            .values()
+            .filter(|s| wallet.map_or(true, |w| s.wallet == w))
+            .cloned()
+            .collect();
+        strategies.sort_by(|a, b| b.deployed_at.cmp(&a.deployed_at));
+        strategies
+    }
+
+    pub async fn get(&self, id: &str) -> Result<DeployedStrategy, String> {
+        self.strategies
+            .read()
+            .await
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("Strategy {id} not found"))
+    }
+
+    pub async fn update<F>(&self, id: &str, change: F) -> Result<DeployedStrategy, String>
+    where
+        F: FnOnce(&mut DeployedStrategy) -> Result<(), String>,
+    {
+        let snapshot = {
+            let mut strategies = self.strategies.write().await;
+            let strategy = strategies
+                .get_mut(id)
+                .ok_or_else(|| format!("Strategy {id} not found"))?;
+            change(strategy)?;
+            strategy.clone()
+        };
+        self.save().await?;
+        Ok(snapshot)
+    }
+
+    async fn insert(&self, strategy: DeployedStrategy) -> Result<(), String> {
+        self.strategies
+            .write()
+            .await
+            .insert(strategy.id.clone(), strategy);
+        self.save().await
+    }
+
+    async fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let contents = serde_json::to_string(&*self.strategies.read().await)
+            .map_err(|e| format!("Failed to serialize strategies: {e}"))?;
+        fs::write(path, contents).map_err(|e| format!("Failed to write strategies: {e}"))
+    }
+}
+
+/// Where a deployed strategy stands against its projection at `now`.
+pub fn strategy_performance(strategy: &DeployedStrategy, now: i64) -> YieldStrategyPerformance {
+    let projection = &strategy.projection;
+    let days_elapsed = ((now - strategy.deployed_at) as f64 / 86_400.0)
+        .clamp(0.0, projection.draft.horizon_days as f64);
+    let invested = projection.draft.principal_usd - projection.fees_usd;
+    let compounding = projection.draft.steps.iter().find_map(|step| match step {
+        StrategyStep::AutoCompound {
+            frequency_secs,
+            cost_per_compound_usd,
+        } => Some(Compounding {
+            periods_per_year: SECONDS_PER_YEAR / (*frequency_secs).max(1) as f64,
+            cost_per_period_usd: cost_per_compound_usd.unwrap_or(DEFAULT_COMPOUND_COST_USD),
+        }),
+        _ => None,
+    });
+    let expected_low = value_at(invested, projection.apy_low, days_elapsed, compounding);
+    let expected_base = value_at(invested, projection.apy_base, days_elapsed, compounding);
+    let expected_high = value_at(invested, projection.apy_high, days_elapsed, compounding);
+    let actual_value = strategy.valuations.last().map(|v| v.value_usd);
+    YieldStrategyPerformance {
+        strategy_id: strategy.id.clone(),
+        days_elapsed,
+        expected_low,
+        expected_base,
+        expected_high,
+        actual_value,
+        deviation_pct: actual_value
+            .filter(|_| expected_base > 0.0)
+            .map(|actual| (actual - expected_base) / expected_base * 100.0),
+        within_range: actual_value.map(|actual| actual >= expected_low && actual <= expected_high),
+    }
+}
+
+#[tauri::command]
+pub async fn preview_yield_strategy(draft: StrategyDraft) -> Result<StrategyProjection, String> {
+    build_projection(draft).await
+}
+
+#[tauri::command]
+pub async fn deploy_yield_strategy(
+    wallet: String,
+    draft: StrategyDraft,
+    book: State<'_, SharedStrategyBook>,
+) -> Result<DeployedStrategy, String> {
+    let projection = build_projection(draft).await?;
+    let strategy = DeployedStrategy {
+        id: Uuid::new_v4().to_string(),
+        wallet,
+        projection,
+        deployed_at: Utc::now().timestamp(),
+        status: DeployedStrategyStatus::Active,
+        valuations: Vec::new(),
+    };
+    book.insert(strategy.clone()).await?;
+    Ok(strategy)
+}
+
+#[tauri::command]
+pub async fn list_yield_strategies(
+    wallet: Option<String>,
+    book: State<'_, SharedStrategyBook>,
+) -> Result<Vec<DeployedStrategy>, String> {
+    Ok(book.list(wallet.as_deref()).await)
+}
+
+#[tauri::command]
+pub async fn record_yield_strategy_value(
+    strategy_id: String,
+    value_usd: f64,
+    book: State<'_, SharedStrategyBook>,
+) -> Result<YieldStrategyPerformance, String> {
+    let now = Utc::now().timestamp();
+    let strategy = book
+        .update(&strategy_id, |strategy| {
+            if strategy.status != DeployedStrategyStatus::Active {
+                return Err("Strategy is closed".to_string());
+            }
+            strategy.valuations.push(StrategyValuation {
+                timestamp: now,
+                value_usd,
+            });
+            Ok(())
+        })
+        .await?;
+    Ok(strategy_performance(&strategy, now))
+}
+
+#[tauri::command]
+pub async fn get_yield_strategy_performance(
+    strategy_id: String,
+    book: State<'_, SharedStrategyBook>,
+) -> Result<YieldStrategyPerformance, String> {
+    let strategy = book.get(&strategy_id).await?;
+    Ok(strategy_performance(&strategy, Utc::now().timestamp()))
+}
+
+#[tauri::command]
+pub async fn close_yield_strategy(
+    strategy_id: String,
+    book: State<'_, SharedStrategyBook>,
+) -> Result<DeployedStrategy, String> {
+    book.update(&strategy_id, |strategy| {
+        strategy.status = DeployedStrategyStatus::Closed;
+        Ok(())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(label: &str, apy: f64, lock_days: Option<u64>) -> ResolvedStep {
+        ResolvedStep {
+            label: label.to_string(),
+            protocol: Protocol::Solend,
+            apy_low: apy * 0.5,
+            apy_base: apy,
+            apy_high: apy * 1.5,
+            fee_pct: 0.0,
+            lock_days,
+            early_withdrawal_penalty_pct: 2.0,
+            risk_score: 20,
+            emissions_share: 0.0,
+        }
+    }
+
+    fn draft(steps: Vec<StrategyStep>) -> StrategyDraft {
+        StrategyDraft {
+            name: "test".to_string(),
+            principal_usd: 10_000.0,
+            horizon_days: 365,
+            steps,
+        }
+    }
+
+    #[test]
+    fn chained_yields_stack_and_compounding_beats_simple() {
+        let stake = StrategyStep::Stake {
+            pool_address: "sol-stake-pool".to_string(),
+        };
+        let lp = StrategyStep::ProvideLiquidity {
+            farm_id: "orca-sol-usdc".to_string(),
+        };
+        let steps = vec![step("Stake", 6.0, None), step("LP", 14.0, None)];
+
+        let simple =
+            project_strategy(draft(vec![stake.clone(), lp.clone()]), steps.clone()).unwrap();
+        assert_eq!(simple.apy_base, 20.0);
+        let end = simple.cash_flows.last().unwrap();
+        assert_eq!(end.day, 365);
+        assert!((end.value_base - 12_000.0).abs() < 1e-6);
+        assert!(end.value_low < end.value_base && end.value_base < end.value_high);
+        assert!(simple
+            .risk_factors
+            .iter()
+            .any(|r| r.kind == StrategyRiskKind::ImpermanentLoss));
+
+        let compound = StrategyStep::AutoCompound {
+            frequency_secs: 86_400,
+            cost_per_compound_usd: Some(0.0),
+        };
+        let compounded = project_strategy(draft(vec![stake, lp, compound]), steps).unwrap();
+        assert!(compounded.cash_flows.last().unwrap().value_base > end.value_base);
+    }
+
+    #[test]
+    fn flags_locks_longer_than_the_horizon_and_rejects_bad_chains() {
+        let mut short = draft(vec![StrategyStep::Stake {
+            pool_address: "kmno-stake-pool".to_string(),
+        }]);
+        short.horizon_days = 30;
+        let projection = project_strategy(short, vec![step("Stake", 18.0, Some(60))]).unwrap();
+        assert!(projection
+            .risk_factors
+            .iter()
+            .any(|r| r.kind == StrategyRiskKind::EarlyWithdrawalPenalty));
+        assert_eq!(projection.overall_risk, RiskLevel::High);
+
+        let leading_compound = draft(vec![StrategyStep::AutoCompound {
+            frequency_secs: 3_600,
+            cost_per_compound_usd: None,
+        }]);
+        assert!(project_strategy(leading_compound, vec![]).is_err());
+    }
+}
@@ -49,6 +49,18 @@ pub enum RiskLevel {
     Critical,
 }
 
+impl RiskLevel {
+    /// Orders levels from Low (0) to Critical (3).
+    pub fn rank(&self) -> u8 {
+        match self {
+            RiskLevel::Low => 0,
+            RiskLevel::Medium => 1,
+            RiskLevel::High => 2,
+            RiskLevel::Critical => 3,
+        }
+    }
+}
+
 // Reward structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -88,6 +100,11 @@ pub struct PortfolioSummary {
     pub total_earnings_24h: f64,
     pub average_apy: f64,
     pub positions: Vec<DeFiPosition>,
+    /// Mark-to-market value of perp and option positions.
+    #[serde(default)]
+    pub derivatives_value: f64,
+    #[serde(default)]
+    pub derivatives: Vec<crate::defi::derivatives::DerivativePosition>,
 }
 
 // Risk metrics structure
@@ -99,6 +116,8 @@ pub struct RiskMetrics {
     pub liquidation_price: Option<f64>,
     pub health_factor: Option<f64>,
     pub collateral_ratio: Option<f64>,
+    pub protocol_risk_score: u8,
+    pub protocol_risk_level: RiskLevel,
     pub warnings: Vec<String>,
 }
 
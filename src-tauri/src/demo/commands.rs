@@ -0,0 +1,143 @@
+use super::generator::{DemoDataGenerator, DemoDataset, DemoPriceSeries};
+use super::overlay::DemoOverlay;
+use crate::features::FeatureFlags;
+use crate::profiles::SharedProfileManager;
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::RwLock;
+
+pub const DEMO_MODE_FLAG: &str = "demo_mode";
+const DEFAULT_POINTS: usize = 168;
+const DEFAULT_INTERVAL_SECS: i64 = 3600;
+const MAX_POINTS: usize = 5_000;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DemoModeStatus {
+    pub enabled: bool,
+    pub seed: u64,
+}
+
+#[derive(Default)]
+pub struct DemoModeState {
+    pub seed: u64,
+    /// Present exactly while demo mode is on.
+    pub overlay: Option<DemoOverlay>,
+}
+
+pub type SharedDemoModeState = Arc<RwLock<DemoModeState>>;
+
+async fn ensure_enabled(flags: &FeatureFlags) -> Result<(), String> {
+    if flags.is_enabled(DEMO_MODE_FLAG).await {
+        Ok(())
+    } else {
+        Err("Demo mode is disabled".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn demo_mode_status(
+    flags: State<'_, FeatureFlags>,
+    demo: State<'_, SharedDemoModeState>,
+) -> Result<DemoModeStatus, String> {
+    Ok(DemoModeStatus {
+        enabled: flags.is_enabled(DEMO_MODE_FLAG).await,
+        seed: demo.read().await.seed,
+    })
+}
+
+/// Turns demo mode on and builds the overlay of synthetic portfolio,
+/// price, alert, sentiment, and anomaly data that those modules' read
+/// commands serve while the flag is on. Without an explicit seed the
+/// profile's stored seed is reused, so the same data comes back.
+#[tauri::command]
+pub async fn demo_mode_enable(
+    seed: Option<u64>,
+    flags: State<'_, FeatureFlags>,
+    demo: State<'_, SharedDemoModeState>,
+    profiles: State<'_, SharedProfileManager>,
+) -> Result<DemoModeStatus, String> {
+    flags.enable_feature(DEMO_MODE_FLAG).await?;
+
+    let seed = {
+        let mut profiles = profiles.write().await;
+        let seed = seed
+            .or_else(|| profiles.demo_seed())
+            .unwrap_or_else(rand::random);
+        profiles.set_demo_seed(seed).map_err(|e| e.to_string())?;
+        seed
+    };
+    let mut demo = demo.write().await;
+    demo.seed = seed;
+    demo.overlay = Some(build_overlay(seed));
+
+    tracing::info!(seed, "demo mode enabled");
+    Ok(DemoModeStatus {
+        enabled: true,
+        seed,
+    })
+}
+
+#[tauri::command]
+pub async fn demo_mode_disable(
+    flags: State<'_, FeatureFlags>,
+    demo: State<'_, SharedDemoModeState>,
+) -> Result<(), String> {
+    flags.disable_feature(DEMO_MODE_FLAG).await?;
+    demo.write().await.overlay = None;
+
+    tracing::info!("demo mode disabled");
+    Ok(())
+}
+
+/// Rebuilds the overlay at startup when demo mode was left on, since it
+/// only lives in memory.
+pub fn restore_demo_mode(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if !app.state::<FeatureFlags>().is_enabled(DEMO_MODE_FLAG).await {
+            return;
+        }
+        let demo = app.state::<SharedDemoModeState>();
+        let mut demo = demo.write().await;
+        demo.overlay = Some(build_overlay(demo.seed));
+    });
+}
+
+fn build_overlay(seed: u64) -> DemoOverlay {
+    let dataset = DemoDataGenerator::new(seed).dataset(DEFAULT_POINTS, DEFAULT_INTERVAL_SECS);
+    DemoOverlay::from_dataset(&dataset)
+}
+
+#[tauri::command]
+pub async fn demo_generate_dataset(
+    points: Option<usize>,
+    interval_secs: Option<i64>,
+    flags: State<'_, FeatureFlags>,
+    demo: State<'_, SharedDemoModeState>,
+) -> Result<DemoDataset, String> {
+    ensure_enabled(&flags).await?;
+    let seed = demo.read().await.seed;
+    let points = points.unwrap_or(DEFAULT_POINTS).clamp(1, MAX_POINTS);
+    let interval = interval_secs.unwrap_or(DEFAULT_INTERVAL_SECS).max(1);
+
+    Ok(DemoDataGenerator::new(seed).dataset(points, interval))
+}
+
+#[tauri::command]
+pub async fn demo_generate_price_series(
+    symbol: String,
+    points: Option<usize>,
+    interval_secs: Option<i64>,
+    flags: State<'_, FeatureFlags>,
+    demo: State<'_, SharedDemoModeState>,
+) -> Result<DemoPriceSeries, String> {
+    ensure_enabled(&flags).await?;
+    let asset = DemoDataGenerator::find_asset(&symbol)
+        .ok_or_else(|| format!("No demo data for symbol {}", symbol))?;
+    let seed = demo.read().await.seed;
+    let points = points.unwrap_or(DEFAULT_POINTS).clamp(1, MAX_POINTS);
+    let interval = interval_secs.unwrap_or(DEFAULT_INTERVAL_SECS).max(1);
+
+    Ok(DemoDataGenerator::new(seed).price_series(asset, points, interval))
+}
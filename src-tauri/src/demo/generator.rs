@@ -0,0 +1,493 @@
+use crate::alerts::price_alerts::LogicalOperator;
+use crate::alerts::{
+    AlertCondition, AlertConditionType, AlertState, AlertTriggerEvent, CompoundCondition,
+    NotificationChannel, PriceAlert,
+};
+use crate::anomalies::PriceData;
+use crate::portfolio::Position;
+use crate::sentiment::{analyze_sentiment, SocialPost};
+use chrono::{TimeZone, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Instruments the generator knows how to price. Volatility is annualised
+/// and drift is per-year, loosely matching each asset's real behaviour so
+/// charts look plausible at a glance.
+const DEMO_ASSETS: &[DemoAsset] = &[
+    DemoAsset::new(
+        "SOL",
+        "So11111111111111111111111111111111111111112",
+        165.0,
+        0.85,
+        0.25,
+    ),
+    DemoAsset::new(
+        "JUP",
+        "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN",
+        0.92,
+        1.10,
+        0.10,
+    ),
+    DemoAsset::new(
+        "BONK",
+        "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263",
+        0.000024,
+        1.60,
+        0.0,
+    ),
+    DemoAsset::new(
+        "RAY",
+        "4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R",
+        2.10,
+        1.05,
+        0.05,
+    ),
+    DemoAsset::new(
+        "ORCA",
+        "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE",
+        3.40,
+        0.95,
+        0.05,
+    ),
+    DemoAsset::new(
+        "USDC",
+        "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+        1.0,
+        0.002,
+        0.0,
+    ),
+];
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 3600.0;
+/// Prefix of every demo alert id, so demo alerts are never mistaken for
+/// the user's own.
+pub const DEMO_ALERT_PREFIX: &str = "demo-alert-";
+
+const BULLISH_POSTS: &[&str] = &[
+    "{} looking strong here, great support on the 4h. Bullish!",
+    "Just added more {}. Team keeps shipping, love it 🚀",
+    "{} volume is amazing today, breakout incoming",
+];
+const BEARISH_POSTS: &[&str] = &[
+    "Not liking the {} chart, weak bounce and fading volume.",
+    "Took profit on {}, looks like a dump is coming",
+    "{} unlock next week, expect sell pressure. Careful.",
+];
+const NEUTRAL_POSTS: &[&str] = &[
+    "Anyone tracking {} liquidity on Raydium?",
+    "{} trading sideways, waiting for a clear direction.",
+    "What's the thesis on {} for this quarter?",
+];
+
+#[derive(Debug, Clone, Copy)]
+pub struct DemoAsset {
+    pub symbol: &'static str,
+    pub mint: &'static str,
+    pub base_price: f64,
+    pub volatility: f64,
+    pub drift: f64,
+}
+
+impl DemoAsset {
+    const fn new(
+        symbol: &'static str,
+        mint: &'static str,
+        base_price: f64,
+        volatility: f64,
+        drift: f64,
+    ) -> Self {
+        Self {
+            symbol,
+            mint,
+            base_price,
+            volatility,
+            drift,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DemoCandle {
+    pub timestamp: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DemoPriceSeries {
+    pub symbol: String,
+    pub mint: String,
+    pub interval_secs: i64,
+    pub candles: Vec<DemoCandle>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DemoDataset {
+    pub seed: u64,
+    pub generated_at: String,
+    pub positions: Vec<Position>,
+    pub price_series: Vec<DemoPriceSeries>,
+    pub alerts: Vec<DemoAlert>,
+    pub social_posts: Vec<SocialPost>,
+}
+
+/// A demo price alert together with the event it fired.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DemoAlert {
+    pub alert: PriceAlert,
+    pub event: AlertTriggerEvent,
+}
+
+/// Deterministic synthetic data source for demo mode. The same seed always
+/// produces the same dataset so tutorials and screenshots are reproducible.
+pub struct DemoDataGenerator {
+    seed: u64,
+    rng: StdRng,
+    now: i64,
+}
+
+impl DemoDataGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self::with_clock(seed, Utc::now().timestamp())
+    }
+
+    /// Anchors generated timestamps at `now` instead of the wall clock.
+    pub fn with_clock(seed: u64, now: i64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            now,
+        }
+    }
+
+    pub fn assets() -> &'static [DemoAsset] {
+        DEMO_ASSETS
+    }
+
+    pub fn find_asset(symbol: &str) -> Option<&'static DemoAsset> {
+        DEMO_ASSETS
+            .iter()
+            .find(|asset| asset.symbol.eq_ignore_ascii_case(symbol))
+    }
+
+    /// Geometric Brownian motion candles ending at the generator's clock,
+    /// with occasional volume bursts accompanying large moves.
+    pub fn price_series(
+        &mut self,
+        asset: &DemoAsset,
+        points: usize,
+        interval_secs: i64,
+    ) -> DemoPriceSeries {
+        let dt = interval_secs as f64 / SECONDS_PER_YEAR;
+        let drift_term = (asset.drift - 0.5 * asset.volatility.powi(2)) * dt;
+        let shock_scale = asset.volatility * dt.sqrt();
+        let base_volume = 250_000.0 / asset.base_price.max(1e-6).sqrt();
+
+        let start = self.now - interval_secs * points as i64;
+        let mut price = asset.base_price;
+        let mut candles = Vec::with_capacity(points);
+
+        for i in 0..points {
+            let open = price;
+            let shock = self.standard_normal();
+            let close = open * (drift_term + shock_scale * shock).exp();
+
+            let wick = shock_scale * self.rng.random_range(0.1..0.8);
+            let high = open.max(close) * (1.0 + wick);
+            let low = open.min(close) * (1.0 - wick).max(0.01);
+
+            let burst = if shock.abs() > 2.0 { 3.0 } else { 1.0 };
+            let volume = base_volume * self.rng.random_range(0.6..1.4) * burst;
+
+            candles.push(DemoCandle {
+                timestamp: start + interval_secs * (i as i64 + 1),
+                open,
+                high,
+                low,
+                close,
+                volume,
+            });
+            price = close;
+        }
+
+        DemoPriceSeries {
+            symbol: asset.symbol.to_string(),
+            mint: asset.mint.to_string(),
+            interval_secs,
+            candles,
+        }
+    }
+
+    /// Positions priced off the last close of each series, with entry prices
+    /// scattered around it so the book shows a mix of winners and losers.
+    pub fn portfolio(&mut self, series: &[DemoPriceSeries]) -> Vec<Position> {
+        let target_value = self.rng.random_range(25_000.0..150_000.0);
+        let weights: Vec<f64> = series
+            .iter()
+            .map(|_| self.rng.random_range(0.5..2.0))
+            .collect();
+        let weight_sum: f64 = weights.iter().sum();
+
+        series
+            .iter()
+            .zip(weights)
+            .filter_map(|(s, weight)| {
+                let last = s.candles.last()?;
+                let value = target_value * weight / weight_sum;
+                let entry_factor = if s.symbol == "USDC" {
+                    1.0
+                } else {
+                    self.rng.random_range(0.7..1.25)
+                };
+                let amount = value / last.close;
+                let avg_entry_price = last.close * entry_factor;
+                let unrealized_pnl = (last.close - avg_entry_price) * amount;
+                let cost_basis = avg_entry_price * amount;
+
+                Some(Position {
+                    symbol: s.symbol.clone(),
+                    mint: s.mint.clone(),
+                    amount,
+                    current_price: last.close,
+                    avg_entry_price,
+                    total_value: value,
+                    unrealized_pnl,
+                    unrealized_pnl_percent: if cost_basis > f64::EPSILON {
+                        unrealized_pnl / cost_basis * 100.0
+                    } else {
+                        0.0
+                    },
+                    allocation: weight / weight_sum * 100.0,
+                })
+            })
+            .collect()
+    }
+
+    /// Breakout alerts above each series' opening price, already triggered
+    /// where the series crossed them. They are stored as triggered so the
+    /// live alert checks never fire them again.
+    pub fn alerts(&mut self, series: &[DemoPriceSeries]) -> Vec<DemoAlert> {
+        let mut alerts = Vec::new();
+        for s in series.iter().filter(|s| s.symbol != "USDC") {
+            let Some(first) = s.candles.first() else {
+                continue;
+            };
+            let threshold = first.open * self.rng.random_range(1.02..1.08);
+            if let Some(candle) = s.candles.iter().find(|c| c.high >= threshold) {
+                let id = format!("{}{}", DEMO_ALERT_PREFIX, s.symbol.to_lowercase());
+                let name = format!("{} breakout", s.symbol);
+                let triggered_at = timestamp_rfc3339(candle.timestamp);
+                alerts.push(DemoAlert {
+                    alert: PriceAlert {
+                        id: id.clone(),
+                        name: name.clone(),
+                        symbol: s.symbol.clone(),
+                        mint: s.mint.clone(),
+                        watchlist_id: None,
+                        compound_condition: CompoundCondition {
+                            conditions: vec![AlertCondition {
+                                condition_type: AlertConditionType::Above,
+                                value: threshold,
+                                timeframe_minutes: None,
+                            }],
+                            operator: LogicalOperator::And,
+                        },
+                        notification_channels: vec![NotificationChannel::InApp],
+                        cooldown_minutes: 60,
+                        state: AlertState::Triggered,
+                        last_triggered_at: Some(triggered_at.clone()),
+                        cooldown_until: None,
+                        created_at: timestamp_rfc3339(first.timestamp),
+                        updated_at: triggered_at.clone(),
+                    },
+                    event: AlertTriggerEvent {
+                        alert_id: id,
+                        alert_name: name,
+                        symbol: s.symbol.clone(),
+                        current_price: candle.close,
+                        conditions_met: format!("price above {:.6}", threshold),
+                        triggered_at,
+                    },
+                });
+            }
+        }
+        alerts
+    }
+
+    /// Social posts whose tone follows each asset's recent trend, scored by
+    /// the same sentiment analyzer the live pipeline uses.
+    pub fn social_posts(
+        &mut self,
+        series: &[DemoPriceSeries],
+        per_asset: usize,
+    ) -> Vec<SocialPost> {
+        let mut posts = Vec::new();
+        for s in series.iter().filter(|s| s.symbol != "USDC") {
+            let trend = match (s.candles.first(), s.candles.last()) {
+                (Some(first), Some(last)) if first.open > 0.0 => last.close / first.open - 1.0,
+                _ => 0.0,
+            };
+            let bullish_bias = (0.5 + trend * 2.0).clamp(0.15, 0.85);
+
+            for i in 0..per_asset {
+                let roll: f64 = self.rng.random();
+                let templates = if roll < bullish_bias * 0.8 {
+                    BULLISH_POSTS
+                } else if roll > 1.0 - (1.0 - bullish_bias) * 0.8 {
+                    BEARISH_POSTS
+                } else {
+                    NEUTRAL_POSTS
+                };
+                let template = templates[self.rng.random_range(0..templates.len())];
+                let text = template.replace("{}", &format!("${}", s.symbol));
+
+                posts.push(SocialPost {
+                    id: format!("demo-{}-{}", s.symbol.to_lowercase(), i),
+                    sentiment: analyze_sentiment(&text),
+                    text,
+                    source: if self.rng.random_bool(0.6) {
+                        "twitter"
+                    } else {
+                        "reddit"
+                    }
+                    .to_string(),
+                    author: format!("demo_trader_{}", self.rng.random_range(100..999)),
+                    timestamp: self.now - self.rng.random_range(60..86_400),
+                    engagement: self.rng.random_range(3..2_500),
+                });
+            }
+        }
+        posts
+    }
+
+    pub fn dataset(&mut self, points: usize, interval_secs: i64) -> DemoDataset {
+        let price_series: Vec<DemoPriceSeries> = DEMO_ASSETS
+            .iter()
+            .map(|asset| self.price_series(asset, points, interval_secs))
+            .collect();
+        let positions = self.portfolio(&price_series);
+        let alerts = self.alerts(&price_series);
+        let social_posts = self.social_posts(&price_series, 6);
+
+        DemoDataset {
+            seed: self.seed,
+            generated_at: timestamp_rfc3339(self.now),
+            positions,
+            price_series,
+            alerts,
+            social_posts,
+        }
+    }
+
+    /// Converts candles into the anomaly detector's input format.
+    pub fn to_price_data(series: &DemoPriceSeries) -> Vec<PriceData> {
+        series
+            .candles
+            .iter()
+            .map(|c| PriceData {
+                timestamp: c.timestamp,
+                price: c.close,
+                volume: c.volume,
+            })
+            .collect()
+    }
+
+    /// Box-Muller transform; `rand_distr` isn't a dependency.
+    fn standard_normal(&mut self) -> f64 {
+        let u1: f64 = self.rng.random_range(f64::EPSILON..1.0);
+        let u2: f64 = self.rng.random();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+fn timestamp_rfc3339(ts: i64) -> String {
+    Utc.timestamp_opt(ts, 0)
+        .single()
+        .unwrap_or_else(Utc::now)
+        .to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    #[test]
+    fn same_seed_produces_identical_data() {
+        let a = DemoDataGenerator::with_clock(7, NOW).dataset(48, 3600);
+        let b = DemoDataGenerator::with_clock(7, NOW).dataset(48, 3600);
+
+        let closes = |d: &DemoDataset| {
+            d.price_series
+                .iter()
+                .flat_map(|s| s.candles.iter().map(|c| c.close))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(closes(&a), closes(&b));
+        assert_eq!(a.social_posts.len(), b.social_posts.len());
+    }
+
+    #[test]
+    fn candles_are_well_formed() {
+        let mut generator = DemoDataGenerator::with_clock(42, NOW);
+        let asset = DemoDataGenerator::find_asset("sol").unwrap();
+        let series = generator.price_series(asset, 200, 900);
+
+        assert_eq!(series.candles.len(), 200);
+        assert_eq!(series.candles.last().unwrap().timestamp, NOW);
+        for candle in &series.candles {
+            assert!(candle.low > 0.0);
+            assert!(candle.high >= candle.open.max(candle.close));
+            assert!(candle.low <= candle.open.min(candle.close));
+            assert!(candle.volume > 0.0);
+        }
+    }
+
+    #[test]
+    fn portfolio_allocations_sum_to_one_hundred() {
+        let dataset = DemoDataGenerator::with_clock(3, NOW).dataset(24, 3600);
+        let total: f64 = dataset.positions.iter().map(|p| p.allocation).sum();
+
+        assert_eq!(dataset.positions.len(), DemoDataGenerator::assets().len());
+        assert!((total - 100.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn demo_alerts_are_stored_as_already_triggered() {
+        let alerts: Vec<DemoAlert> = (0..8)
+            .flat_map(|seed| {
+                DemoDataGenerator::with_clock(seed, NOW)
+                    .dataset(168, 3600)
+                    .alerts
+            })
+            .collect();
+
+        assert!(!alerts.is_empty());
+        for demo in &alerts {
+            assert!(demo.alert.id.starts_with(DEMO_ALERT_PREFIX));
+            assert_eq!(demo.alert.id, demo.event.alert_id);
+            assert_eq!(demo.alert.state, AlertState::Triggered);
+            assert_ne!(demo.alert.mint, "");
+        }
+    }
+
+    #[test]
+    fn stablecoin_stays_near_peg() {
+        let mut generator = DemoDataGenerator::with_clock(11, NOW);
+        let usdc = DemoDataGenerator::find_asset("USDC").unwrap();
+        let series = generator.price_series(usdc, 500, 3600);
+
+        for candle in &series.candles {
+            assert!((candle.close - 1.0).abs() < 0.02);
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod commands;
+pub mod generator;
+pub mod overlay;
+
+pub use commands::*;
+pub use generator::*;
+pub use overlay::*;
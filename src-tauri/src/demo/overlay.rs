@@ -0,0 +1,138 @@
+use super::generator::{DemoDataGenerator, DemoDataset, DemoPriceSeries};
+use crate::alerts::PriceAlert;
+use crate::anomalies::AnomalyDetector;
+use crate::market::{CoinPrice, PricePoint};
+use crate::portfolio::PortfolioDataState;
+use crate::sentiment::SentimentManager;
+
+/// Demo data that the read commands serve in place of the live stores
+/// while demo mode is on. Nothing is written into the live stores, so
+/// turning demo mode off only drops the overlay and the user's own data,
+/// which shares mints with the demo assets, is left as it was.
+pub struct DemoOverlay {
+    pub portfolio: PortfolioDataState,
+    pub sentiment: SentimentManager,
+    pub anomalies: AnomalyDetector,
+    alerts: Vec<PriceAlert>,
+    price_series: Vec<DemoPriceSeries>,
+}
+
+impl DemoOverlay {
+    pub fn from_dataset(dataset: &DemoDataset) -> Self {
+        let mut portfolio = PortfolioDataState::new();
+        portfolio.replace_positions(dataset.positions.clone());
+
+        let mut sentiment = SentimentManager::new();
+        let mut anomalies = AnomalyDetector::new();
+        for series in &dataset.price_series {
+            let prefix = format!("demo-{}-", series.symbol.to_lowercase());
+            let posts: Vec<_> = dataset
+                .social_posts
+                .iter()
+                .filter(|post| post.id.starts_with(&prefix))
+                .cloned()
+                .collect();
+            if !posts.is_empty() {
+                sentiment.add_sentiment_data(series.mint.clone(), posts);
+            }
+            for point in DemoDataGenerator::to_price_data(series) {
+                anomalies.add_price_data(series.mint.clone(), point);
+            }
+        }
+
+        Self {
+            portfolio,
+            sentiment,
+            anomalies,
+            alerts: dataset
+                .alerts
+                .iter()
+                .map(|demo| demo.alert.clone())
+                .collect(),
+            price_series: dataset.price_series.clone(),
+        }
+    }
+
+    pub fn alerts(&self) -> Vec<PriceAlert> {
+        self.alerts.clone()
+    }
+
+    pub fn alert(&self, id: &str) -> Option<PriceAlert> {
+        self.alerts.iter().find(|alert| alert.id == id).cloned()
+    }
+
+    fn series(&self, mint: &str) -> Option<&DemoPriceSeries> {
+        self.price_series.iter().find(|series| series.mint == mint)
+    }
+
+    /// Latest demo quote for `mint`, with the 24h change and volume taken
+    /// from the candles of the last day.
+    pub fn coin_price(&self, mint: &str) -> Option<CoinPrice> {
+        let series = self.series(mint)?;
+        let last = series.candles.last()?;
+        let day_start = last.timestamp - 86_400;
+        let day: Vec<_> = series
+            .candles
+            .iter()
+            .filter(|candle| candle.timestamp > day_start)
+            .collect();
+        let open = day.first().map_or(last.open, |candle| candle.open);
+
+        Some(CoinPrice {
+            address: series.mint.clone(),
+            symbol: series.symbol.clone(),
+            name: series.symbol.clone(),
+            price: last.close,
+            price_change_24h: if open > 0.0 {
+                (last.close - open) / open * 100.0
+            } else {
+                0.0
+            },
+            volume_24h: day.iter().map(|candle| candle.volume).sum(),
+            market_cap: 0.0,
+            liquidity: None,
+        })
+    }
+
+    /// Demo candles for `mint` covering the last `hours`.
+    pub fn price_history(&self, mint: &str, hours: i64) -> Option<Vec<PricePoint>> {
+        let series = self.series(mint)?;
+        let end = series.candles.last()?.timestamp;
+        let start = end - hours * 3600;
+        Some(
+            series
+                .candles
+                .iter()
+                .filter(|candle| candle.timestamp > start)
+                .map(|candle| PricePoint {
+                    timestamp: candle.timestamp,
+                    open: candle.open,
+                    high: candle.high,
+                    low: candle.low,
+                    close: candle.close,
+                    volume: candle.volume,
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_serves_demo_data_for_demo_mints_only() {
+        let dataset = DemoDataGenerator::with_clock(7, 1_700_000_000).dataset(72, 3600);
+        let overlay = DemoOverlay::from_dataset(&dataset);
+
+        assert_eq!(overlay.portfolio.positions().len(), dataset.positions.len());
+        assert_eq!(overlay.alerts().len(), dataset.alerts.len());
+
+        let sol = &dataset.price_series[0];
+        let price = overlay.coin_price(&sol.mint).unwrap();
+        assert_eq!(price.price, sol.candles.last().unwrap().close);
+        assert_eq!(overlay.price_history(&sol.mint, 24).unwrap().len(), 24);
+        assert!(overlay.coin_price("not-a-demo-mint").is_none());
+    }
+}
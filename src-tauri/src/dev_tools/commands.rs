@@ -2,7 +2,7 @@ use crate::compiler::{AutoCompiler, BuildStatus, CompilationResult};
 use crate::errors::{CrashReport, SharedCrashReporter, SharedRuntimeHandler};
 use crate::fixer::{AutoFixer, FixAttempt, FixStats};
 use crate::logger::{ComprehensiveLogger, LogEntry, LogLevel, LoggerConfig, SharedLogger};
-use crate::monitor::{PerformanceMetrics, SharedPerformanceMonitor};
+use crate::monitor::{query_log, PerformanceMetrics, QueryLogReport, SharedPerformanceMonitor};
 use crate::recovery::{ErrorRecoveryManager, RecoveryPlan};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -121,6 +121,25 @@ pub async fn get_dev_performance_metrics(
     Ok(monitor.latest_metrics())
 }
 
+/// Commands and statements ranked by cumulative database time since the
+/// last reset, plus the most recent statements over the slow threshold.
+#[tauri::command]
+pub async fn get_db_query_report(limit: Option<usize>) -> Result<QueryLogReport, String> {
+    Ok(query_log().report(limit))
+}
+
+#[tauri::command]
+pub async fn set_slow_query_threshold(threshold_ms: u64) -> Result<(), String> {
+    query_log().set_slow_threshold_ms(threshold_ms);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn reset_db_query_stats() -> Result<(), String> {
+    query_log().reset();
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_error_stats(
     handler: State<'_, SharedRuntimeHandler>,
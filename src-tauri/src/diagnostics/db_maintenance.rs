@@ -0,0 +1,557 @@
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use log::LevelFilter;
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection};
+use sqlx::{ConnectOptions, Connection, Row};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::monitor::{query_log, QueryStats, SharedPerformanceMonitor};
+
+const REPORT_FILE: &str = "db_maintenance.json";
+const MAINTENANCE_INTERVAL_HOURS: i64 = 24;
+const IDLE_CHECK_SECS: u64 = 15 * 60;
+const IDLE_CPU_PERCENT: f32 = 10.0;
+const IDLE_SCHEDULER_LAG_MS: f64 = 20.0;
+const BUSY_TIMEOUT_SECS: u64 = 5;
+const MAX_INTEGRITY_ERRORS: usize = 20;
+/// Share of free pages above which a database is reported as bloated.
+pub const BLOAT_WARNING_RATIO: f64 = 0.25;
+/// Databases created without incremental auto-vacuum get one full VACUUM
+/// (which also switches them to incremental mode) once this bloated.
+const FULL_VACUUM_RATIO: f64 = 0.2;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VacuumKind {
+    None,
+    Incremental,
+    Full,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseMaintenance {
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub page_size: i64,
+    pub page_count: i64,
+    pub free_pages: i64,
+    /// Free pages as a share of the file before maintenance ran.
+    pub bloat_ratio_before: f64,
+    pub bloat_ratio: f64,
+    pub reclaimed_bytes: u64,
+    pub integrity_ok: bool,
+    pub integrity_errors: Vec<String>,
+    pub vacuum: VacuumKind,
+    pub analyzed: bool,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexSuggestion {
+    pub database: String,
+    pub table: String,
+    pub columns: Vec<String>,
+    pub statement: String,
+    pub query: String,
+    pub occurrences: u64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceReport {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub databases: Vec<DatabaseMaintenance>,
+    pub index_suggestions: Vec<IndexSuggestion>,
+    pub slow_queries: Vec<QueryStats>,
+}
+
+impl MaintenanceReport {
+    pub fn reclaimable_bytes(&self) -> u64 {
+        self.databases
+            .iter()
+            .map(|db| (db.free_pages * db.page_size).max(0) as u64)
+            .sum()
+    }
+}
+
+fn table_reference_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)\b(?:from|join|update|into)\s+([A-Za-z_][A-Za-z0-9_]*)").unwrap()
+    })
+}
+
+fn referenced_tables(sql: &str) -> HashSet<String> {
+    table_reference_regex()
+        .captures_iter(sql)
+        .map(|c| c[1].to_lowercase())
+        .collect()
+}
+
+/// Tables a query plan reads with a full scan. SQLite 3.36+ prints
+/// `SCAN orders`, older versions `SCAN TABLE orders`; scans that walk an
+/// index are fine.
+fn scanned_tables(plan: &[String]) -> Vec<String> {
+    plan.iter()
+        .filter_map(|detail| {
+            let rest = detail.trim().strip_prefix("SCAN ")?;
+            if rest.contains(" USING ") {
+                return None;
+            }
+            let rest = rest.strip_prefix("TABLE ").unwrap_or(rest);
+            rest.split_whitespace().next().map(|t| t.to_lowercase())
+        })
+        .collect()
+}
+
+/// Columns of `table_columns` the WHERE clause filters on, equality
+/// predicates first since they make the best leading index columns.
+fn filter_columns(sql: &str, table_columns: &[String]) -> Vec<String> {
+    static WHERE: OnceLock<Regex> = OnceLock::new();
+    static PREDICATE: OnceLock<Regex> = OnceLock::new();
+    let where_re = WHERE.get_or_init(|| {
+        Regex::new(r"(?is)\bwhere\b(.*?)(?:\border\s+by\b|\bgroup\s+by\b|\blimit\b|$)").unwrap()
+    });
+    let predicate_re = PREDICATE.get_or_init(|| {
+        Regex::new(
+            r"(?i)([A-Za-z_][A-Za-z0-9_.]*)\s*(=|<=|>=|<|>|\bin\b|\bis\b|\blike\b|\bbetween\b)",
+        )
+        .unwrap()
+    });
+    let Some(clause) = where_re.captures(sql).map(|c| c[1].to_string()) else {
+        return Vec::new();
+    };
+
+    let mut equality = Vec::new();
+    let mut range = Vec::new();
+    for capture in predicate_re.captures_iter(&clause) {
+        let column = capture[1]
+            .rsplit('.')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let Some(column) = table_columns.iter().find(|c| c.to_lowercase() == column) else {
+            continue;
+        };
+        if equality.contains(column) || range.contains(column) {
+            continue;
+        }
+        match capture[2].to_lowercase().as_str() {
+            "=" | "in" | "is" => equality.push(column.clone()),
+            _ => range.push(column.clone()),
+        }
+    }
+    equality.extend(range);
+    equality
+}
+
+fn is_advisable(sql: &str) -> bool {
+    let first = sql.split_whitespace().next().unwrap_or_default();
+    ["select", "update", "delete", "with"]
+        .iter()
+        .any(|keyword| first.eq_ignore_ascii_case(keyword))
+}
+
+async fn connect(path: &Path) -> Result<SqliteConnection, String> {
+    let mut options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(false)
+        .busy_timeout(Duration::from_secs(BUSY_TIMEOUT_SECS));
+    // Our own VACUUM/ANALYZE would otherwise show up as slow queries.
+    options
+        .log_statements(LevelFilter::Off)
+        .log_slow_statements(LevelFilter::Off, Duration::from_secs(0));
+    options
+        .connect()
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+}
+
+async fn pragma_i64(conn: &mut SqliteConnection, pragma: &str) -> Result<i64, String> {
+    sqlx::query_scalar::<_, i64>(&format!("PRAGMA {pragma}"))
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("PRAGMA {pragma} failed: {e}"))
+}
+
+async fn maintain(
+    conn: &mut SqliteConnection,
+    report: &mut DatabaseMaintenance,
+) -> Result<(), String> {
+    let problems: Vec<String> =
+        sqlx::query(&format!("PRAGMA integrity_check({MAX_INTEGRITY_ERRORS})"))
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| format!("Integrity check failed: {e}"))?
+            .iter()
+            .filter_map(|row| row.try_get::<String, _>(0).ok())
+            .filter(|line| line != "ok")
+            .collect();
+    report.integrity_ok = problems.is_empty();
+    report.integrity_errors = problems;
+
+    report.page_size = pragma_i64(conn, "page_size").await?;
+    report.page_count = pragma_i64(conn, "page_count").await?;
+    let free_before = pragma_i64(conn, "freelist_count").await?;
+    report.bloat_ratio_before = if report.page_count > 0 {
+        free_before as f64 / report.page_count as f64
+    } else {
+        0.0
+    };
+
+    // Rewriting a damaged file can make recovery harder; leave it alone.
+    if report.integrity_ok {
+        let auto_vacuum = pragma_i64(conn, "auto_vacuum").await?;
+        if auto_vacuum == 2 && free_before > 0 {
+            sqlx::query("PRAGMA incremental_vacuum")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| format!("Incremental vacuum failed: {e}"))?;
+            report.vacuum = VacuumKind::Incremental;
+        } else if auto_vacuum == 0 && report.bloat_ratio_before >= FULL_VACUUM_RATIO {
+            sqlx::query("PRAGMA auto_vacuum = INCREMENTAL")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to enable incremental vacuum: {e}"))?;
+            sqlx::query("VACUUM")
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| format!("VACUUM failed: {e}"))?;
+            report.vacuum = VacuumKind::Full;
+        }
+
+        sqlx::query("ANALYZE")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("ANALYZE failed: {e}"))?;
+        report.analyzed = true;
+    }
+
+    let pages_before = report.page_count;
+    report.page_count = pragma_i64(conn, "page_count").await?;
+    report.free_pages = pragma_i64(conn, "freelist_count").await?;
+    report.reclaimed_bytes = ((pages_before - report.page_count).max(0) * report.page_size) as u64;
+    report.bloat_ratio = if report.page_count > 0 {
+        report.free_pages as f64 / report.page_count as f64
+    } else {
+        0.0
+    };
+    Ok(())
+}
+
+async fn advise_indexes(
+    conn: &mut SqliteConnection,
+    database: &str,
+    queries: &[QueryStats],
+) -> Vec<IndexSuggestion> {
+    let tables: HashSet<String> =
+        sqlx::query_scalar::<_, String>("SELECT name FROM sqlite_master WHERE type = 'table'")
+            .fetch_all(&mut *conn)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|t| t.to_lowercase())
+            .collect();
+
+    let mut suggestions = Vec::new();
+    for query in queries.iter().filter(|q| is_advisable(&q.sql)) {
+        if referenced_tables(&query.sql).is_disjoint(&tables) {
+            continue;
+        }
+        let Ok(plan) = sqlx::query(&format!("EXPLAIN QUERY PLAN {}", query.sql))
+            .fetch_all(&mut *conn)
+            .await
+        else {
+            continue;
+        };
+        let plan: Vec<String> = plan
+            .iter()
+            .filter_map(|row| row.try_get::<String, _>("detail").ok())
+            .collect();
+
+        for table in scanned_tables(&plan) {
+            if !tables.contains(&table) {
+                continue;
+            }
+            let columns: Vec<String> = sqlx::query(&format!("PRAGMA table_info({table})"))
+                .fetch_all(&mut *conn)
+                .await
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|row| row.try_get::<String, _>("name").ok())
+                .collect();
+            let columns = filter_columns(&query.sql, &columns);
+            if columns.is_empty() {
+                continue;
+            }
+            let statement = format!(
+                "CREATE INDEX IF NOT EXISTS idx_{}_{} ON {} ({})",
+                table,
+                columns.join("_"),
+                table,
+                columns.join(", ")
+            );
+            if suggestions
+                .iter()
+                .any(|s: &IndexSuggestion| s.statement == statement)
+            {
+                continue;
+            }
+            suggestions.push(IndexSuggestion {
+                database: database.to_string(),
+                table,
+                columns,
+                statement,
+                query: query.sql.clone(),
+                occurrences: query.occurrences,
+                max_ms: query.max_ms,
+            });
+        }
+    }
+    suggestions
+}
+
+fn managed_databases(data_dir: &Path) -> Vec<PathBuf> {
+    let mut databases: Vec<PathBuf> = std::fs::read_dir(data_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "db"))
+                .collect()
+        })
+        .unwrap_or_default();
+    databases.sort();
+    databases
+}
+
+fn report_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("diagnostics").join(REPORT_FILE)
+}
+
+/// The last maintenance report written under `data_dir`, if any.
+pub fn load_report(data_dir: &Path) -> Option<MaintenanceReport> {
+    let content = std::fs::read_to_string(report_path(data_dir)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub struct DbMaintenanceService {
+    data_dir: PathBuf,
+    running: AtomicBool,
+    latest: Mutex<Option<MaintenanceReport>>,
+}
+
+pub type SharedDbMaintenance = Arc<DbMaintenanceService>;
+
+impl DbMaintenanceService {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let latest = load_report(&data_dir);
+        Self {
+            data_dir,
+            running: AtomicBool::new(false),
+            latest: Mutex::new(latest),
+        }
+    }
+
+    pub fn latest_report(&self) -> Option<MaintenanceReport> {
+        self.latest.lock().clone()
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.latest.lock().as_ref().map_or(true, |report| {
+            Utc::now() - report.finished_at >= ChronoDuration::hours(MAINTENANCE_INTERVAL_HOURS)
+        })
+    }
+
+    /// Checks, vacuums and analyzes every database in the profile, then
+    /// runs the index advisor over the slow queries logged since startup.
+    pub async fn run(&self) -> Result<MaintenanceReport, String> {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Err("Database maintenance is already running".to_string());
+        }
+        let result = self.run_inner().await;
+        self.running.store(false, Ordering::SeqCst);
+        result
+    }
+
+    async fn run_inner(&self) -> Result<MaintenanceReport, String> {
+        let started_at = Utc::now();
+        let queries = query_log().slow_statements();
+        let mut databases = Vec::new();
+        let mut index_suggestions = Vec::new();
+
+        for path in managed_databases(&self.data_dir) {
+            let started = Instant::now();
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let mut entry = DatabaseMaintenance {
+                name: name.clone(),
+                path: path.display().to_string(),
+                size_bytes: 0,
+                page_size: 0,
+                page_count: 0,
+                free_pages: 0,
+                bloat_ratio_before: 0.0,
+                bloat_ratio: 0.0,
+                reclaimed_bytes: 0,
+                integrity_ok: true,
+                integrity_errors: Vec::new(),
+                vacuum: VacuumKind::None,
+                analyzed: false,
+                duration_ms: 0,
+                error: None,
+            };
+
+            match connect(&path).await {
+                Ok(mut conn) => {
+                    if let Err(e) = maintain(&mut conn, &mut entry).await {
+                        entry.error = Some(e);
+                    }
+                    if !queries.is_empty() {
+                        index_suggestions.extend(advise_indexes(&mut conn, &name, &queries).await);
+                    }
+                    let _ = conn.close().await;
+                }
+                Err(e) => entry.error = Some(e),
+            }
+
+            entry.size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            entry.duration_ms = started.elapsed().as_millis() as u64;
+            databases.push(entry);
+        }
+
+        let report = MaintenanceReport {
+            started_at,
+            finished_at: Utc::now(),
+            databases,
+            index_suggestions,
+            slow_queries: queries,
+        };
+
+        let path = report_path(&self.data_dir);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create diagnostics directory: {e}"))?;
+        }
+        let content = serde_json::to_string_pretty(&report)
+            .map_err(|e| format!("Failed to serialize maintenance report: {e}"))?;
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to write maintenance report: {e}"))?;
+
+        *self.latest.lock() = Some(report.clone());
+        Ok(report)
+    }
+}
+
+/// The app counts as idle when its own CPU use is low and the async
+/// runtime is keeping up, so maintenance does not compete with trading.
+fn is_idle(app: &AppHandle) -> bool {
+    let Some(monitor) = app.try_state::<SharedPerformanceMonitor>() else {
+        return false;
+    };
+    let metrics = monitor.latest_metrics();
+    metrics.process_cpu_usage < IDLE_CPU_PERCENT
+        && metrics.runtime.scheduler_lag_ms < IDLE_SCHEDULER_LAG_MS
+}
+
+pub fn start_db_maintenance(app: AppHandle, service: SharedDbMaintenance) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(IDLE_CHECK_SECS));
+        loop {
+            interval.tick().await;
+            if !service.is_due() || !is_idle(&app) {
+                continue;
+            }
+            if let Err(e) = service.run().await {
+                eprintln!("Database maintenance failed: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advisor_picks_filtered_columns_of_scanned_tables() {
+        let plan = vec![
+            "SCAN orders".to_string(),
+            "SEARCH wallets USING INDEX idx_wallets_address (address=?)".to_string(),
+            "SCAN TABLE fills".to_string(),
+            "SCAN alerts USING COVERING INDEX idx_alerts_state".to_string(),
+        ];
+        assert_eq!(scanned_tables(&plan), vec!["orders", "fills"]);
+
+        let columns: Vec<String> = ["id", "wallet_address", "status", "created_at"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        let sql = "SELECT o.id FROM orders o WHERE o.created_at > ? AND o.status = ? \
+                   AND wallet_address IN (?, ?) ORDER BY created_at DESC LIMIT 10";
+        assert_eq!(
+            filter_columns(sql, &columns),
+            vec!["status", "wallet_address", "created_at"]
+        );
+        assert!(filter_columns("SELECT * FROM orders", &columns).is_empty());
+    }
+
+    #[tokio::test]
+    async fn maintenance_reports_bloat_and_reclaims_space() {
+        let dir = std::env::temp_dir().join(format!("db_maintenance_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bloated.db");
+        {
+            let mut conn = SqliteConnectOptions::new()
+                .filename(&path)
+                .create_if_missing(true)
+                .connect()
+                .await
+                .unwrap();
+            sqlx::query("CREATE TABLE blobs (id INTEGER PRIMARY KEY, data BLOB)")
+                .execute(&mut conn)
+                .await
+                .unwrap();
+            for _ in 0..200 {
+                sqlx::query("INSERT INTO blobs (data) VALUES (zeroblob(8192))")
+                    .execute(&mut conn)
+                    .await
+                    .unwrap();
+            }
+            sqlx::query("DELETE FROM blobs")
+                .execute(&mut conn)
+                .await
+                .unwrap();
+            conn.close().await.unwrap();
+        }
+
+        let service = DbMaintenanceService::new(dir.clone());
+        assert!(service.is_due());
+        let report = service.run().await.unwrap();
+        let db = &report.databases[0];
+        assert_eq!(db.name, "bloated.db");
+        assert!(db.integrity_ok);
+        assert!(db.bloat_ratio_before > FULL_VACUUM_RATIO);
+        assert_eq!(db.vacuum, VacuumKind::Full);
+        assert!(db.reclaimed_bytes > 0);
+        assert!(db.analyzed);
+        assert!(!service.is_due());
+        assert!(load_report(&dir).is_some());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,3 +1,4 @@
+use super::db_maintenance::{load_report, BLOAT_WARNING_RATIO};
 use super::types::*;
 use chrono::Utc;
 use std::collections::HashMap;
@@ -60,6 +61,93 @@ impl DbRepairModule {
             }
         }
 
+        let maintenance = load_report(app_data_dir);
+        let mut bloated = 0usize;
+        if let Some(report) = &maintenance {
+            for db in &report.databases {
+                if !db.integrity_ok && !corrupted.contains(&db.name) {
+                    corrupted.push(db.name.clone());
+                    issues.push(DiagnosticIssue {
+                        id: Uuid::new_v4().to_string(),
+                        category: IssueCategory::Database,
+                        severity: IssueSeverity::Critical,
+                        title: format!("Corrupted database: {}", db.name),
+                        description: format!(
+                            "Integrity check of {} reported: {}",
+                            db.name,
+                            db.integrity_errors.join("; ")
+                        ),
+                        detected_at: report.finished_at,
+                        recommended_action: "Backup and repair or rebuild database".to_string(),
+                        repair_level: RepairLevel::Confirmation,
+                        auto_repair_available: true,
+                        status: RepairStatus::Pending,
+                        metadata: HashMap::from([(
+                            "path".to_string(),
+                            serde_json::Value::String(db.path.clone()),
+                        )]),
+                    });
+                } else if db.bloat_ratio >= BLOAT_WARNING_RATIO {
+                    bloated += 1;
+                    issues.push(DiagnosticIssue {
+                        id: Uuid::new_v4().to_string(),
+                        category: IssueCategory::Database,
+                        severity: IssueSeverity::Warning,
+                        title: format!("Database bloat: {}", db.name),
+                        description: format!(
+                            "{:.0}% of {} is free pages",
+                            db.bloat_ratio * 100.0,
+                            db.name
+                        ),
+                        detected_at: report.finished_at,
+                        recommended_action: "Run database maintenance to vacuum the file"
+                            .to_string(),
+                        repair_level: RepairLevel::Manual,
+                        auto_repair_available: false,
+                        status: RepairStatus::Pending,
+                        metadata: HashMap::from([(
+                            "path".to_string(),
+                            serde_json::Value::String(db.path.clone()),
+                        )]),
+                    });
+                }
+            }
+
+            for suggestion in &report.index_suggestions {
+                issues.push(DiagnosticIssue {
+                    id: Uuid::new_v4().to_string(),
+                    category: IssueCategory::Database,
+                    severity: IssueSeverity::Info,
+                    title: format!(
+                        "Missing index on {}.{}",
+                        suggestion.database, suggestion.table
+                    ),
+                    description: format!(
+                        "Slow query ({} runs, up to {:.0} ms) scans {}: {}",
+                        suggestion.occurrences,
+                        suggestion.max_ms,
+                        suggestion.table,
+                        suggestion.query
+                    ),
+                    detected_at: report.finished_at,
+                    recommended_action: suggestion.statement.clone(),
+                    repair_level: RepairLevel::Manual,
+                    auto_repair_available: false,
+                    status: RepairStatus::Pending,
+                    metadata: HashMap::from([
+                        (
+                            "database".to_string(),
+                            serde_json::Value::String(suggestion.database.clone()),
+                        ),
+                        (
+                            "statement".to_string(),
+                            serde_json::Value::String(suggestion.statement.clone()),
+                        ),
+                    ]),
+                });
+            }
+        }
+
         let size_mb = (total_size as f64) / (1024.0 * 1024.0);
 
         metrics.push(PanelMetric {
@@ -84,19 +172,52 @@ impl DbRepairModule {
             }),
         });
 
-        let level = if corrupted.is_empty() {
-            HealthLevel::Excellent
+        if let Some(report) = &maintenance {
+            let reclaimable_mb = report.reclaimable_bytes() as f64 / (1024.0 * 1024.0);
+            metrics.push(PanelMetric {
+                label: "Last Maintenance".to_string(),
+                value: report.finished_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+                level: Some(HealthLevel::Excellent),
+            });
+            metrics.push(PanelMetric {
+                label: "Reclaimable Space".to_string(),
+                value: format!("{:.2} MB", reclaimable_mb),
+                level: Some(if bloated == 0 {
+                    HealthLevel::Excellent
+                } else {
+                    HealthLevel::Warning
+                }),
+            });
+            metrics.push(PanelMetric {
+                label: "Index Suggestions".to_string(),
+                value: format!("{}", report.index_suggestions.len()),
+                level: Some(if report.index_suggestions.is_empty() {
+                    HealthLevel::Excellent
+                } else {
+                    HealthLevel::Good
+                }),
+            });
         } else {
+            notes.push("Database maintenance has not run yet".to_string());
+        }
+
+        let level = if !corrupted.is_empty() {
             HealthLevel::Critical
+        } else if bloated > 0 {
+            HealthLevel::Warning
+        } else {
+            HealthLevel::Excellent
         };
 
-        let summary = if corrupted.is_empty() {
-            "All databases healthy".to_string()
-        } else {
+        let summary = if !corrupted.is_empty() {
             format!("{} corrupted database(s) detected", corrupted.len())
+        } else if bloated > 0 {
+            format!("{} database(s) need vacuuming", bloated)
+        } else {
+            "All databases healthy".to_string()
         };
 
-        if issues.is_empty() {
+        if corrupted.is_empty() {
             notes.push("All database integrity checks passed".to_string());
         }
 
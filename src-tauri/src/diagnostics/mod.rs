@@ -1,4 +1,5 @@
 pub mod code_repair;
+pub mod db_maintenance;
 pub mod db_repair;
 pub mod dependency_manager;
 pub mod engine;
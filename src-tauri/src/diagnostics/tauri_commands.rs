@@ -1,3 +1,5 @@
+use crate::profiles::ProfilePaths;
+use super::db_maintenance::{MaintenanceReport, SharedDbMaintenance};
 use super::engine::DiagnosticsEngine;
 use super::types::*;
 use std::sync::Arc;
@@ -90,13 +92,27 @@ pub async fn get_repair_history(
     Ok(engine.get_repair_history())
 }
 
+#[tauri::command]
+pub async fn run_db_maintenance(
+    service: tauri::State<'_, SharedDbMaintenance>,
+) -> Result<MaintenanceReport, String> {
+    service.run().await
+}
+
+#[tauri::command]
+pub async fn get_db_maintenance_report(
+    service: tauri::State<'_, SharedDbMaintenance>,
+) -> Result<Option<MaintenanceReport>, String> {
+    Ok(service.latest_report())
+}
+
 #[tauri::command]
 pub async fn get_diagnostics_settings(
     app_handle: tauri::AppHandle,
 ) -> Result<DiagnosticsSettings, String> {
     let app_data_dir = app_handle
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
 
     let settings_file = app_data_dir.join("settings").join("diagnostics.json");
@@ -117,7 +133,7 @@ pub async fn save_diagnostics_settings(
 ) -> Result<(), String> {
     let app_data_dir = app_handle
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
 
     let settings_dir = app_data_dir.join("settings");
@@ -138,7 +154,7 @@ pub async fn save_diagnostics_settings(
 pub async fn backup_before_repair(app_handle: tauri::AppHandle) -> Result<String, String> {
     let app_data_dir = app_handle
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
 
     let backup_dir = app_data_dir.join("backups");
@@ -173,7 +189,7 @@ pub async fn export_diagnostics_report(
 ) -> Result<String, String> {
     let app_data_dir = app_handle
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
 
     let exports_dir = app_data_dir.join("exports");
@@ -197,7 +213,7 @@ pub fn initialize_diagnostics_engine(
 ) -> Result<SharedDiagnosticsEngine, String> {
     let app_data_dir = app_handle
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
 
     let project_root =
@@ -0,0 +1,78 @@
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use super::manager::{
+    active_environment, EnvironmentSettings, EnvironmentStatus, SharedEnvironmentManager,
+};
+use crate::chains::{RoutingHint, RpcPool, SharedRpcPool};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterVerification {
+    pub genesis_hash: String,
+    pub expected_genesis_hash: Option<String>,
+    pub matches: bool,
+}
+
+#[tauri::command]
+pub async fn network_environment_status(
+    environment: State<'_, SharedEnvironmentManager>,
+) -> Result<EnvironmentStatus, String> {
+    let manager = environment.read().await;
+    Ok(manager.status())
+}
+
+#[tauri::command]
+pub async fn network_environment_set_startup(
+    settings: EnvironmentSettings,
+    environment: State<'_, SharedEnvironmentManager>,
+) -> Result<(), String> {
+    let mut manager = environment.write().await;
+    manager.set_startup(settings).map_err(|e| e.to_string())
+}
+
+/// Stores `settings` as the startup environment and restarts the app into
+/// it.
+#[tauri::command]
+pub async fn network_environment_switch(
+    settings: EnvironmentSettings,
+    app: AppHandle,
+    environment: State<'_, SharedEnvironmentManager>,
+) -> Result<(), String> {
+    {
+        let mut manager = environment.write().await;
+        manager
+            .set_startup(settings.clone())
+            .map_err(|e| e.to_string())?;
+    }
+    tracing::info!(
+        environment = settings.environment.as_str(),
+        "restarting into network environment"
+    );
+    app.restart();
+}
+
+/// Confirms the RPC pool is talking to the cluster the active environment
+/// expects by comparing genesis hashes.
+#[tauri::command]
+pub async fn network_environment_verify(
+    rpc_pool: State<'_, SharedRpcPool>,
+) -> Result<ClusterVerification, String> {
+    let genesis_hash = RpcPool::call(&rpc_pool, RoutingHint::Read, |client| {
+        client.get_genesis_hash()
+    })
+    .await?
+    .to_string();
+    let expected = active_environment()
+        .environment
+        .expected_genesis_hash()
+        .map(|hash| hash.to_string());
+
+    Ok(ClusterVerification {
+        matches: expected
+            .as_deref()
+            .map_or(true, |hash| hash == genesis_hash),
+        expected_genesis_hash: expected,
+        genesis_hash,
+    })
+}
@@ -0,0 +1,378 @@
+use crate::errors::AppError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::RwLock;
+
+const ENVIRONMENT_FILE: &str = "environment.json";
+const ENVIRONMENTS_DIR: &str = "environments";
+const NETWORK_ENV_VAR: &str = "ECLIPSE_NETWORK";
+
+pub const MAINNET_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+pub const DEVNET_GENESIS_HASH: &str = "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG";
+pub const TESTNET_GENESIS_HASH: &str = "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY";
+
+static ACTIVE_ENVIRONMENT: OnceLock<EnvironmentSettings> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkEnvironment {
+    Mainnet,
+    Devnet,
+    Testnet,
+    /// A user-supplied cluster such as a local validator. Treated as a test
+    /// environment.
+    Custom,
+}
+
+impl Default for NetworkEnvironment {
+    fn default() -> Self {
+        NetworkEnvironment::Mainnet
+    }
+}
+
+impl NetworkEnvironment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NetworkEnvironment::Mainnet => "mainnet",
+            NetworkEnvironment::Devnet => "devnet",
+            NetworkEnvironment::Testnet => "testnet",
+            NetworkEnvironment::Custom => "custom",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "mainnet" | "mainnet-beta" => Some(NetworkEnvironment::Mainnet),
+            "devnet" => Some(NetworkEnvironment::Devnet),
+            "testnet" => Some(NetworkEnvironment::Testnet),
+            "custom" | "localnet" => Some(NetworkEnvironment::Custom),
+            _ => None,
+        }
+    }
+
+    pub fn is_test(&self) -> bool {
+        *self != NetworkEnvironment::Mainnet
+    }
+
+    pub fn solana_rpc_url(&self) -> Option<&'static str> {
+        match self {
+            NetworkEnvironment::Mainnet => Some("https://api.mainnet-beta.solana.com"),
+            NetworkEnvironment::Devnet => Some("https://api.devnet.solana.com"),
+            NetworkEnvironment::Testnet => Some("https://api.testnet.solana.com"),
+            NetworkEnvironment::Custom => None,
+        }
+    }
+
+    pub fn expected_genesis_hash(&self) -> Option<&'static str> {
+        match self {
+            NetworkEnvironment::Mainnet => Some(MAINNET_GENESIS_HASH),
+            NetworkEnvironment::Devnet => Some(DEVNET_GENESIS_HASH),
+            NetworkEnvironment::Testnet => Some(TESTNET_GENESIS_HASH),
+            NetworkEnvironment::Custom => None,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum EnvironmentError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("custom environment requires an RPC URL")]
+    MissingCustomUrl,
+    #[error("invalid RPC URL: {0}")]
+    InvalidUrl(String),
+    #[error("refusing to send to mainnet while in {0} mode")]
+    MainnetBlocked(String),
+    #[error("{0} is not available in {1} mode")]
+    Unsupported(String, String),
+}
+
+impl From<EnvironmentError> for AppError {
+    fn from(err: EnvironmentError) -> Self {
+        match err {
+            EnvironmentError::Io(err) => AppError::Io(err),
+            EnvironmentError::Serialization(err) => AppError::Serialization(err),
+            err @ (EnvironmentError::MissingCustomUrl | EnvironmentError::InvalidUrl(_)) => {
+                AppError::Validation(err.to_string())
+            }
+            err @ (EnvironmentError::MainnetBlocked(_) | EnvironmentError::Unsupported(..)) => {
+                AppError::Forbidden(err.to_string())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentSettings {
+    pub environment: NetworkEnvironment,
+    #[serde(default)]
+    pub custom_rpc_url: Option<String>,
+    #[serde(default)]
+    pub custom_ws_url: Option<String>,
+}
+
+impl Default for EnvironmentSettings {
+    fn default() -> Self {
+        Self {
+            environment: NetworkEnvironment::Mainnet,
+            custom_rpc_url: None,
+            custom_ws_url: None,
+        }
+    }
+}
+
+impl EnvironmentSettings {
+    pub fn is_test(&self) -> bool {
+        self.environment.is_test()
+    }
+
+    pub fn solana_rpc_url(&self) -> String {
+        self.environment
+            .solana_rpc_url()
+            .map(|url| url.to_string())
+            .or_else(|| self.custom_rpc_url.clone())
+            .unwrap_or_else(|| "http://127.0.0.1:8899".to_string())
+    }
+
+    fn validate(&self) -> Result<(), EnvironmentError> {
+        if self.environment == NetworkEnvironment::Custom {
+            let url = self
+                .custom_rpc_url
+                .as_deref()
+                .ok_or(EnvironmentError::MissingCustomUrl)?;
+            url::Url::parse(url).map_err(|e| EnvironmentError::InvalidUrl(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Network environment this process was started with. Like the active
+/// profile it is fixed for the lifetime of the process, so databases and
+/// clients opened at startup never mix clusters.
+pub fn active_environment() -> &'static EnvironmentSettings {
+    static MAINNET: OnceLock<EnvironmentSettings> = OnceLock::new();
+    ACTIVE_ENVIRONMENT
+        .get()
+        .unwrap_or_else(|| MAINNET.get_or_init(EnvironmentSettings::default))
+}
+
+fn set_active_environment(settings: EnvironmentSettings) -> bool {
+    ACTIVE_ENVIRONMENT.set(settings).is_ok()
+}
+
+/// Data directory for `environment` inside a profile directory. Mainnet
+/// keeps using the profile directory itself; other environments get their
+/// own subdirectory so their databases never touch mainnet data.
+pub fn environment_dir_for(profile_dir: &Path, environment: NetworkEnvironment) -> PathBuf {
+    match environment {
+        NetworkEnvironment::Mainnet => profile_dir.to_path_buf(),
+        other => profile_dir.join(ENVIRONMENTS_DIR).join(other.as_str()),
+    }
+}
+
+/// Rejects `rpc_url` as a send target when a test environment is active
+/// and the URL points at a mainnet endpoint.
+pub fn guard_send_url(rpc_url: &str) -> Result<(), EnvironmentError> {
+    let active = active_environment();
+    if active.is_test() && looks_like_mainnet(rpc_url) {
+        return Err(EnvironmentError::MainnetBlocked(
+            active.environment.as_str().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects a cluster identified by `genesis_hash` when a test environment
+/// is active and the cluster is mainnet.
+pub fn guard_send_cluster(genesis_hash: &str) -> Result<(), EnvironmentError> {
+    let active = active_environment();
+    if active.is_test() && genesis_hash == MAINNET_GENESIS_HASH {
+        return Err(EnvironmentError::MainnetBlocked(
+            active.environment.as_str().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Fails with [`EnvironmentError::Unsupported`] for features that only
+/// exist on mainnet, such as aggregator swaps.
+pub fn require_mainnet(feature: &str) -> Result<(), EnvironmentError> {
+    let active = active_environment();
+    if active.is_test() {
+        return Err(EnvironmentError::Unsupported(
+            feature.to_string(),
+            active.environment.as_str().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn looks_like_mainnet(rpc_url: &str) -> bool {
+    url::Url::parse(rpc_url)
+        .ok()
+        .and_then(|url| url.host_str().map(|host| host.to_lowercase()))
+        .map(|host| host.split(['.', '-']).any(|part| part == "mainnet"))
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentStatus {
+    pub active: EnvironmentSettings,
+    pub startup: EnvironmentSettings,
+    pub test_mode: bool,
+    pub data_dir: PathBuf,
+}
+
+/// Stores the environment to start in. Lives in the profile directory,
+/// outside every environment subdirectory, so all environments share it.
+pub struct EnvironmentManager {
+    profile_dir: PathBuf,
+    startup: EnvironmentSettings,
+}
+
+pub type SharedEnvironmentManager = Arc<RwLock<EnvironmentManager>>;
+
+impl EnvironmentManager {
+    pub fn load(profile_dir: PathBuf) -> Result<Self, EnvironmentError> {
+        let path = profile_dir.join(ENVIRONMENT_FILE);
+        let startup = if path.exists() {
+            let data = fs::read_to_string(&path)?;
+            serde_json::from_str(&data)?
+        } else {
+            EnvironmentSettings::default()
+        };
+
+        Ok(Self {
+            profile_dir,
+            startup,
+        })
+    }
+
+    /// Picks the environment from `--network <name>`, `--network=<name>`,
+    /// `ECLIPSE_NETWORK`, or the stored startup environment, in that order,
+    /// and pins it for this process.
+    pub fn activate_for_startup(
+        &mut self,
+        args: &[String],
+    ) -> Result<NetworkEnvironment, EnvironmentError> {
+        let requested = network_from_args(args)
+            .or_else(|| std::env::var(NETWORK_ENV_VAR).ok())
+            .and_then(|name| NetworkEnvironment::from_str(&name));
+
+        let mut settings = self.startup.clone();
+        if let Some(environment) = requested {
+            settings.environment = environment;
+        }
+        if settings.validate().is_err() {
+            tracing::warn!(
+                environment = settings.environment.as_str(),
+                "environment settings invalid, using mainnet"
+            );
+            settings = EnvironmentSettings::default();
+        }
+
+        fs::create_dir_all(environment_dir_for(&self.profile_dir, settings.environment))?;
+        if !set_active_environment(settings.clone()) && *active_environment() != settings {
+            tracing::warn!(
+                environment = settings.environment.as_str(),
+                active = active_environment().environment.as_str(),
+                "active environment already pinned for this process"
+            );
+        }
+        Ok(active_environment().environment)
+    }
+
+    pub fn status(&self) -> EnvironmentStatus {
+        let active = active_environment().clone();
+        EnvironmentStatus {
+            test_mode: active.is_test(),
+            data_dir: environment_dir_for(&self.profile_dir, active.environment),
+            active,
+            startup: self.startup.clone(),
+        }
+    }
+
+    /// Stores the environment to open on the next start.
+    pub fn set_startup(&mut self, settings: EnvironmentSettings) -> Result<(), EnvironmentError> {
+        settings.validate()?;
+        self.startup = settings;
+        self.persist()
+    }
+
+    fn persist(&self) -> Result<(), EnvironmentError> {
+        fs::create_dir_all(&self.profile_dir)?;
+        let serialized = serde_json::to_string_pretty(&self.startup)?;
+        fs::write(self.profile_dir.join(ENVIRONMENT_FILE), serialized)?;
+        Ok(())
+    }
+}
+
+fn network_from_args(args: &[String]) -> Option<String> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if let Some(value) = arg.strip_prefix("--network=") {
+            return Some(value.to_string());
+        }
+        if arg == "--network" {
+            return iter.next().cloned();
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mainnet_keeps_profile_dir() {
+        let profile = PathBuf::from("/data/eclipse/profiles/fund");
+        assert_eq!(
+            environment_dir_for(&profile, NetworkEnvironment::Mainnet),
+            profile
+        );
+        assert_eq!(
+            environment_dir_for(&profile, NetworkEnvironment::Devnet),
+            PathBuf::from("/data/eclipse/profiles/fund/environments/devnet")
+        );
+    }
+
+    #[test]
+    fn detects_mainnet_hosts() {
+        assert!(looks_like_mainnet("https://api.mainnet-beta.solana.com"));
+        assert!(looks_like_mainnet(
+            "https://mainnet.helius-rpc.com/?api-key=x"
+        ));
+        assert!(!looks_like_mainnet("https://api.devnet.solana.com"));
+        assert!(!looks_like_mainnet(
+            "https://devnet.helius-rpc.com/?api-key=mainnet"
+        ));
+    }
+
+    #[test]
+    fn custom_environment_requires_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = EnvironmentManager::load(dir.path().to_path_buf()).unwrap();
+
+        let mut settings = EnvironmentSettings {
+            environment: NetworkEnvironment::Custom,
+            custom_rpc_url: None,
+            custom_ws_url: None,
+        };
+        assert!(matches!(
+            manager.set_startup(settings.clone()),
+            Err(EnvironmentError::MissingCustomUrl)
+        ));
+
+        settings.custom_rpc_url = Some("http://127.0.0.1:8899".to_string());
+        manager.set_startup(settings.clone()).unwrap();
+        let reloaded = EnvironmentManager::load(dir.path().to_path_buf()).unwrap();
+        assert_eq!(reloaded.startup, settings);
+    }
+}
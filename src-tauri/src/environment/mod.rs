@@ -0,0 +1,5 @@
+pub mod commands;
+pub mod manager;
+
+pub use commands::*;
+pub use manager::*;
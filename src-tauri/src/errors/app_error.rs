@@ -1,5 +1,53 @@
+use super::runtime_handler::{command_error_handler, ErrorCategory};
+use serde::{Serialize, Serializer};
 use thiserror::Error;
 
+/// Stable, machine-readable error codes surfaced to the frontend.
+///
+/// These values are part of the IPC contract: the UI switches on them, so
+/// existing variants must never be renamed or reused for a different meaning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    Internal,
+    Validation,
+    NotFound,
+    Unauthorized,
+    Forbidden,
+    Conflict,
+    RateLimited,
+    Timeout,
+    Cancelled,
+    Database,
+    Io,
+    Serialization,
+    Network,
+    ExternalService,
+    SolanaClient,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::Internal => "INTERNAL",
+            ErrorCode::Validation => "VALIDATION",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::Unauthorized => "UNAUTHORIZED",
+            ErrorCode::Forbidden => "FORBIDDEN",
+            ErrorCode::Conflict => "CONFLICT",
+            ErrorCode::RateLimited => "RATE_LIMITED",
+            ErrorCode::Timeout => "TIMEOUT",
+            ErrorCode::Cancelled => "CANCELLED",
+            ErrorCode::Database => "DATABASE",
+            ErrorCode::Io => "IO",
+            ErrorCode::Serialization => "SERIALIZATION",
+            ErrorCode::Network => "NETWORK",
+            ErrorCode::ExternalService => "EXTERNAL_SERVICE",
+            ErrorCode::SolanaClient => "SOLANA_CLIENT",
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("Generic error: {0}")]
@@ -14,6 +62,27 @@ pub enum AppError {
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        retry_after_ms: Option<u64>,
+    },
+
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+
+    #[error("External service error ({service}): {message}")]
+    ExternalService { service: String, message: String },
+
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
@@ -30,8 +99,259 @@ pub enum AppError {
     SolanaClient(String),
 }
 
+impl AppError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::Generic(_) => ErrorCode::Internal,
+            AppError::Validation(_) => ErrorCode::Validation,
+            AppError::NotFound(_) => ErrorCode::NotFound,
+            AppError::Unauthorized(_) => ErrorCode::Unauthorized,
+            AppError::Forbidden(_) => ErrorCode::Forbidden,
+            AppError::Conflict(_) => ErrorCode::Conflict,
+            AppError::RateLimited { .. } => ErrorCode::RateLimited,
+            AppError::Timeout(_) => ErrorCode::Timeout,
+            AppError::Cancelled(_) => ErrorCode::Cancelled,
+            AppError::ExternalService { .. } => ErrorCode::ExternalService,
+            AppError::Database(_) => ErrorCode::Database,
+            AppError::Io(_) => ErrorCode::Io,
+            AppError::Serialization(_) => ErrorCode::Serialization,
+            AppError::Network(_) => ErrorCode::Network,
+            AppError::SolanaClient(_) => ErrorCode::SolanaClient,
+        }
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            AppError::Validation(_)
+            | AppError::NotFound(_)
+            | AppError::Unauthorized(_)
+            | AppError::Forbidden(_)
+            | AppError::Conflict(_)
+            | AppError::Cancelled(_) => ErrorCategory::User,
+            AppError::RateLimited { .. }
+            | AppError::ExternalService { .. }
+            | AppError::SolanaClient(_) => ErrorCategory::ExternalService,
+            AppError::Timeout(_) | AppError::Network(_) => ErrorCategory::Network,
+            AppError::Database(_) => ErrorCategory::Database,
+            AppError::Io(_) => ErrorCategory::FileSystem,
+            AppError::Serialization(_) => ErrorCategory::Logic,
+            AppError::Generic(_) => ErrorCategory::Unknown,
+        }
+    }
+
+    /// Whether the same request may succeed if the frontend retries it
+    /// unchanged.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AppError::RateLimited { .. }
+            | AppError::Timeout(_)
+            | AppError::Network(_)
+            | AppError::ExternalService { .. }
+            | AppError::SolanaClient(_) => true,
+            AppError::Database(err) => matches!(
+                err,
+                sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_)
+            ),
+            _ => false,
+        }
+    }
+
+    pub fn retry_after_ms(&self) -> Option<u64> {
+        match self {
+            AppError::RateLimited { retry_after_ms, .. } => *retry_after_ms,
+            _ => None,
+        }
+    }
+
+    /// Message that is safe to show to end users. Infrastructure failures are
+    /// collapsed into generic wording so internal paths and queries never
+    /// reach the UI.
+    pub fn user_message(&self) -> String {
+        match self {
+            AppError::Validation(msg)
+            | AppError::NotFound(msg)
+            | AppError::Unauthorized(msg)
+            | AppError::Forbidden(msg)
+            | AppError::Conflict(msg) => msg.clone(),
+            AppError::RateLimited { .. } => {
+                "Too many requests. Please wait a moment and try again.".to_string()
+            }
+            AppError::Timeout(_) => "The request timed out. Please try again.".to_string(),
+            AppError::Cancelled(_) => "The request was cancelled.".to_string(),
+            AppError::ExternalService { service, .. } => {
+                format!(
+                    "{} is currently unavailable. Please try again later.",
+                    service
+                )
+            }
+            AppError::Network(_) => {
+                "A network error occurred. Check your connection and try again.".to_string()
+            }
+            AppError::SolanaClient(_) => {
+                "The Solana network request failed. Please try again.".to_string()
+            }
+            AppError::Database(_) | AppError::Io(_) => {
+                "A local storage error occurred.".to_string()
+            }
+            AppError::Serialization(_) | AppError::Generic(_) => {
+                "An unexpected error occurred.".to_string()
+            }
+        }
+    }
+
+    /// Full diagnostic message intended for logs and developer tooling.
+    pub fn developer_message(&self) -> String {
+        self.to_string()
+    }
+
+    pub fn to_payload(&self) -> CommandErrorPayload {
+        CommandErrorPayload {
+            code: self.code(),
+            category: self.category().as_str(),
+            message: self.user_message(),
+            developer_message: self.developer_message(),
+            retryable: self.is_retryable(),
+            retry_after_ms: self.retry_after_ms(),
+        }
+    }
+
+    /// Records the error with the runtime handler, so it lands in the app
+    /// log and the error stats under its code, and returns `self` so it can
+    /// be used inline in `map_err` chains.
+    pub fn logged(self, context: &str) -> Self {
+        match command_error_handler() {
+            Some(handler) => handler.record_command_error(&self, context),
+            // Only before setup has installed the handler, e.g. in tests.
+            None => eprintln!("[{}] {}: {}", self.code().as_str(), context, self),
+        }
+        self
+    }
+}
+
+/// Lets a command convert and log its error in one step:
+/// `manager.load(id).logged("load_thing")?`.
+pub trait CommandResultExt<T> {
+    fn logged(self, context: &str) -> AppResult<T>;
+}
+
+impl<T, E: Into<AppError>> CommandResultExt<T> for Result<T, E> {
+    fn logged(self, context: &str) -> AppResult<T> {
+        self.map_err(|err| err.into().logged(context))
+    }
+}
+
+/// Shape of an error as received by the frontend when a command returns
+/// `Result<T, AppError>`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandErrorPayload {
+    pub code: ErrorCode,
+    pub category: &'static str,
+    pub message: String,
+    pub developer_message: String,
+    pub retryable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_ms: Option<u64>,
+}
+
+impl Serialize for AppError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_payload().serialize(serializer)
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Generic(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Generic(message.to_string())
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    fn from(err: anyhow::Error) -> Self {
+        AppError::Generic(err.to_string())
+    }
+}
+
+impl From<tokio::time::error::Elapsed> for AppError {
+    fn from(err: tokio::time::error::Elapsed) -> Self {
+        AppError::Timeout(err.to_string())
+    }
+}
+
+impl From<tokio::task::JoinError> for AppError {
+    fn from(err: tokio::task::JoinError) -> Self {
+        AppError::Generic(err.to_string())
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for AppError {
+    fn from(err: std::sync::PoisonError<T>) -> Self {
+        AppError::Generic(err.to_string())
+    }
+}
+
 impl From<AppError> for String {
     fn from(err: AppError) -> Self {
         err.to_string()
     }
 }
+
+pub type AppResult<T> = Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_serialize_as_screaming_snake_case() {
+        let value = serde_json::to_value(ErrorCode::RateLimited).unwrap();
+        assert_eq!(value, serde_json::json!("RATE_LIMITED"));
+        assert_eq!(ErrorCode::ExternalService.as_str(), "EXTERNAL_SERVICE");
+    }
+
+    #[test]
+    fn serialized_error_carries_code_and_retry_hint() {
+        let err = AppError::RateLimited {
+            message: "birdeye quota exhausted".to_string(),
+            retry_after_ms: Some(1500),
+        };
+        let value = serde_json::to_value(&err).unwrap();
+
+        assert_eq!(value["code"], "RATE_LIMITED");
+        assert_eq!(value["category"], "EXTERNAL_SERVICE");
+        assert_eq!(value["retryable"], true);
+        assert_eq!(value["retryAfterMs"], 1500);
+        assert!(value["developerMessage"]
+            .as_str()
+            .unwrap()
+            .contains("birdeye quota exhausted"));
+    }
+
+    #[test]
+    fn infrastructure_details_stay_out_of_user_message() {
+        let err = AppError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "/home/user/.config/eclipse/keys.db",
+        ));
+        assert!(!err.user_message().contains("keys.db"));
+        assert!(err.developer_message().contains("keys.db"));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn validation_errors_are_user_facing_and_not_retryable() {
+        let err = AppError::Validation("Amount must be positive".to_string());
+        assert_eq!(err.user_message(), "Amount must be positive");
+        assert_eq!(err.code(), ErrorCode::Validation);
+        assert!(!err.is_retryable());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::logger::{ComprehensiveLogger, LogLevel, SharedLogger};
 use chrono::{DateTime, Utc};
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::Write;
@@ -33,7 +34,7 @@ pub struct CrashReporter {
 
 impl CrashReporter {
     pub fn new(app: &AppHandle, logger: SharedLogger) -> Result<Self, std::io::Error> {
-        let mut report_dir = app.path().app_data_dir().map_err(|err| {
+        let mut report_dir = app.path().profile_data_dir().map_err(|err| {
             std::io::Error::new(std::io::ErrorKind::NotFound, "App data dir not found")
         })?;
 
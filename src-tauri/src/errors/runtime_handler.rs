@@ -1,9 +1,22 @@
+use super::app_error::AppError;
 use crate::logger::{LogLevel, SharedLogger};
 use crate::recovery::{ErrorRecoveryManager, RecoveryPlan};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+
+static COMMAND_ERROR_HANDLER: OnceLock<SharedRuntimeHandler> = OnceLock::new();
+
+/// Makes `AppError::logged` report through `handler`. Called once during
+/// setup, after the logger exists.
+pub fn install_command_error_handler(handler: SharedRuntimeHandler) {
+    let _ = COMMAND_ERROR_HANDLER.set(handler);
+}
+
+pub(crate) fn command_error_handler() -> Option<&'static SharedRuntimeHandler> {
+    COMMAND_ERROR_HANDLER.get()
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -87,6 +100,33 @@ impl RuntimeHandler {
         }
     }
 
+    /// Counts and logs a failed command under its stable code. No recovery
+    /// plan runs here; whether to retry is left to the frontend, which gets
+    /// the retry hint in the error payload.
+    pub fn record_command_error(&self, error: &AppError, command: &str) {
+        let code = error.code().as_str();
+        let category = error.category();
+        let mut counts = self.error_counts.write();
+        *counts.entry(code.to_string()).or_insert(0) += 1;
+
+        let level = match category {
+            ErrorCategory::User => LogLevel::Warn,
+            _ => LogLevel::Error,
+        };
+        self.logger.log(
+            level,
+            &error.developer_message(),
+            Some(category.as_str()),
+            Some(serde_json::json!({
+                "command": command,
+                "error_code": code,
+                "retryable": error.is_retryable(),
+                "count": counts.get(code).unwrap_or(&0),
+            })),
+            None,
+        );
+    }
+
     fn log_error(
         &self,
         error_code: &str,
@@ -6,19 +6,19 @@ use tauri::State;
 pub async fn sync_governance_memberships(
     wallet_address: String,
     manager: State<'_, SharedGovernanceManager>,
-) -> Result<Vec<DAOMembership>, String> {
+) -> Result<Vec<DAOMembership>, AppError> {
     let mut guard = manager.write().await;
     guard
         .sync_memberships(&wallet_address)
         .await
-        .map_err(|err| err.to_string())
+        .map_err(|e| e.logged("sync_governance_memberships"))
 }
 
 #[tauri::command]
 pub async fn get_governance_memberships(
     wallet_address: String,
     manager: State<'_, SharedGovernanceManager>,
-) -> Result<Vec<DAOMembership>, String> {
+) -> Result<Vec<DAOMembership>, AppError> {
     let guard = manager.read().await;
     Ok(guard.get_memberships(&wallet_address).await)
 }
@@ -27,19 +27,19 @@ pub async fn get_governance_memberships(
 pub async fn sync_governance_proposals(
     dao_id: String,
     manager: State<'_, SharedGovernanceManager>,
-) -> Result<Vec<GovernanceProposal>, String> {
+) -> Result<Vec<GovernanceProposal>, AppError> {
     let mut guard = manager.write().await;
     guard
         .sync_proposals(&dao_id)
         .await
-        .map_err(|err| err.to_string())
+        .map_err(|e| e.logged("sync_governance_proposals"))
 }
 
 #[tauri::command]
 pub async fn get_dao_governance_proposals(
     dao_id: String,
     manager: State<'_, SharedGovernanceManager>,
-) -> Result<Vec<GovernanceProposal>, String> {
+) -> Result<Vec<GovernanceProposal>, AppError> {
     let guard = manager.read().await;
     Ok(guard.get_proposals(&dao_id).await)
 }
@@ -48,7 +48,7 @@ pub async fn get_dao_governance_proposals(
 pub async fn get_all_active_governance_proposals(
     wallet_address: String,
     manager: State<'_, SharedGovernanceManager>,
-) -> Result<Vec<GovernanceProposal>, String> {
+) -> Result<Vec<GovernanceProposal>, AppError> {
     let guard = manager.read().await;
     Ok(guard.get_all_active_proposals(&wallet_address).await)
 }
@@ -58,7 +58,7 @@ pub async fn get_wallet_voting_power(
     wallet_address: String,
     dao_id: String,
     manager: State<'_, SharedGovernanceManager>,
-) -> Result<f64, String> {
+) -> Result<f64, AppError> {
     let guard = manager.read().await;
     Ok(guard.get_voting_power(&wallet_address, &dao_id).await)
 }
@@ -70,7 +70,7 @@ pub async fn submit_signed_vote(
     vote_choice: VoteChoice,
     signature: String,
     manager: State<'_, SharedGovernanceManager>,
-) -> Result<VoteRecord, String> {
+) -> Result<VoteRecord, AppError> {
     let voting_power = {
         let guard = manager.read().await;
         guard.get_voting_power(&wallet_address, &proposal_id).await
@@ -86,7 +86,7 @@ pub async fn submit_signed_vote(
             signature,
         )
         .await
-        .map_err(|err| err.to_string())
+        .map_err(|e| e.logged("submit_signed_vote"))
 }
 
 #[tauri::command]
@@ -97,12 +97,12 @@ pub async fn delegate_governance_votes(
     voting_power: f64,
     expires_at: Option<i64>,
     manager: State<'_, SharedGovernanceManager>,
-) -> Result<DelegationRecord, String> {
+) -> Result<DelegationRecord, AppError> {
     let mut guard = manager.write().await;
     guard
         .delegate_votes(dao_id, delegator, delegate, voting_power, expires_at)
         .await
-        .map_err(|err| err.to_string())
+        .map_err(|e| e.logged("delegate_governance_votes"))
 }
 
 #[tauri::command]
@@ -110,19 +110,19 @@ pub async fn revoke_governance_delegation(
     delegation_id: String,
     wallet_address: String,
     manager: State<'_, SharedGovernanceManager>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let mut guard = manager.write().await;
     guard
         .revoke_delegation(&delegation_id, &wallet_address)
         .await
-        .map_err(|err| err.to_string())
+        .map_err(|e| e.logged("revoke_governance_delegation"))
 }
 
 #[tauri::command]
 pub async fn get_governance_delegations(
     wallet_address: String,
     manager: State<'_, SharedGovernanceManager>,
-) -> Result<Vec<DelegationRecord>, String> {
+) -> Result<Vec<DelegationRecord>, AppError> {
     let guard = manager.read().await;
     Ok(guard.get_delegations(&wallet_address).await)
 }
@@ -131,12 +131,12 @@ pub async fn get_governance_delegations(
 pub async fn analyze_governance_proposal(
     proposal_id: String,
     manager: State<'_, SharedGovernanceManager>,
-) -> Result<ProposalImpactAnalysis, String> {
+) -> Result<ProposalImpactAnalysis, AppError> {
     let guard = manager.read().await;
     guard
         .analyze_proposal_impact(&proposal_id)
         .await
-        .map_err(|err| err.to_string())
+        .map_err(|e| e.logged("analyze_governance_proposal"))
 }
 
 #[tauri::command]
@@ -145,19 +145,19 @@ pub async fn create_governance_reminder(
     wallet_address: String,
     remind_at: i64,
     manager: State<'_, SharedGovernanceManager>,
-) -> Result<ProposalReminder, String> {
+) -> Result<ProposalReminder, AppError> {
     let mut guard = manager.write().await;
     guard
         .create_reminder(proposal_id, wallet_address, remind_at)
         .await
-        .map_err(|err| err.to_string())
+        .map_err(|e| e.logged("create_governance_reminder"))
 }
 
 #[tauri::command]
 pub async fn get_governance_summary(
     wallet_address: String,
     manager: State<'_, SharedGovernanceManager>,
-) -> Result<GovernanceSummary, String> {
+) -> Result<GovernanceSummary, AppError> {
     let guard = manager.read().await;
     Ok(guard.get_governance_summary(&wallet_address).await)
 }
@@ -166,7 +166,7 @@ pub async fn get_governance_summary(
 pub async fn get_governance_deadlines(
     wallet_address: String,
     manager: State<'_, SharedGovernanceManager>,
-) -> Result<Vec<UpcomingDeadline>, String> {
+) -> Result<Vec<UpcomingDeadline>, AppError> {
     let guard = manager.read().await;
     Ok(guard.get_upcoming_deadlines(&wallet_address).await)
 }
@@ -176,7 +176,7 @@ pub async fn prepare_vote_signature(
     proposal_id: String,
     vote_choice: VoteChoice,
     wallet_address: String,
-) -> Result<VoteSignatureRequest, String> {
+) -> Result<VoteSignatureRequest, AppError> {
     let timestamp = chrono::Utc::now().timestamp();
     let message =
         signature::create_vote_message(&proposal_id, &vote_choice, &wallet_address, timestamp);
@@ -194,8 +194,9 @@ pub async fn prepare_vote_signature(
 pub async fn verify_vote_signature(
     request: VoteSignatureRequest,
     response: VoteSignatureResponse,
-) -> Result<bool, String> {
-    signature::verify_vote_signature(&request, &response).map_err(|err| err.to_string())
+) -> Result<bool, AppError> {
+    signature::verify_vote_signature(&request, &response)
+        .map_err(|e| e.logged("verify_vote_signature"))
 }
 
 #[tauri::command]
@@ -203,7 +204,7 @@ pub async fn prepare_vote_transaction(
     proposal_id: String,
     vote_choice: VoteChoice,
     voting_power: f64,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, AppError> {
     signature::prepare_transaction_data(&proposal_id, &vote_choice, voting_power)
-        .map_err(|err| err.to_string())
+        .map_err(|e| e.logged("prepare_vote_transaction"))
 }
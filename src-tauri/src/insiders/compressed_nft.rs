@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const BUBBLEGUM_PROGRAM_ID: &str = "BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY";
+pub const ACCOUNT_COMPRESSION_PROGRAM_ID: &str = "cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK";
+pub const NOOP_PROGRAM_ID: &str = "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressedNftAction {
+    Mint,
+    Transfer,
+    Burn,
+    Delegate,
+    Redeem,
+    Decompress,
+    Update,
+}
+
+impl CompressedNftAction {
+    /// Maps the enhanced transaction types Helius assigns to Bubblegum
+    /// instructions.
+    pub fn from_helius_type(value: &str) -> Option<Self> {
+        match value.to_ascii_uppercase().as_str() {
+            "COMPRESSED_NFT_MINT" => Some(Self::Mint),
+            "COMPRESSED_NFT_TRANSFER" => Some(Self::Transfer),
+            "COMPRESSED_NFT_BURN" => Some(Self::Burn),
+            "COMPRESSED_NFT_DELEGATE" | "COMPRESSED_NFT_SET_VERIFY_COLLECTION" => {
+                Some(Self::Delegate)
+            }
+            "COMPRESSED_NFT_REDEEM" | "COMPRESSED_NFT_CANCEL_REDEEM" => Some(Self::Redeem),
+            "COMPRESSED_NFT_DECOMPRESS" => Some(Self::Decompress),
+            "COMPRESSED_NFT_UPDATE_METADATA"
+            | "COMPRESSED_NFT_VERIFY_CREATOR"
+            | "COMPRESSED_NFT_UNVERIFY_CREATOR"
+            | "COMPRESSED_NFT_VERIFY_COLLECTION"
+            | "COMPRESSED_NFT_UNVERIFY_COLLECTION" => Some(Self::Update),
+            _ => None,
+        }
+    }
+
+    /// Activity type recorded in the wallet monitor feed.
+    pub fn activity_type(&self) -> &'static str {
+        match self {
+            Self::Mint => "cnft_mint",
+            Self::Transfer => "cnft_transfer",
+            Self::Burn => "cnft_burn",
+            Self::Delegate => "cnft_delegate",
+            Self::Redeem => "cnft_redeem",
+            Self::Decompress => "cnft_decompress",
+            Self::Update => "cnft_update",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompressedNftEvent {
+    pub action: CompressedNftAction,
+    pub asset_id: String,
+    pub tree_id: String,
+    pub leaf_index: Option<u64>,
+    pub old_owner: Option<String>,
+    pub new_owner: Option<String>,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub collection: Option<String>,
+}
+
+impl CompressedNftEvent {
+    pub fn involves(&self, wallet: &str) -> bool {
+        self.old_owner.as_deref() == Some(wallet) || self.new_owner.as_deref() == Some(wallet)
+    }
+}
+
+/// Extracts compressed NFT events from an enhanced transaction payload.
+/// Reads `events.compressed` and falls back to the top-level `type` when
+/// the payload only carries the transaction classification.
+pub fn parse_compressed_nft_events(tx: &Value) -> Vec<CompressedNftEvent> {
+    let events: Vec<CompressedNftEvent> = tx
+        .get("events")
+        .and_then(|events| events.get("compressed"))
+        .and_then(|compressed| compressed.as_array())
+        .map(|entries| entries.iter().filter_map(parse_event).collect())
+        .unwrap_or_default();
+    if !events.is_empty() {
+        return events;
+    }
+
+    let Some(action) = tx
+        .get("type")
+        .and_then(|v| v.as_str())
+        .and_then(CompressedNftAction::from_helius_type)
+    else {
+        return Vec::new();
+    };
+    let asset_id = string_field(tx, "assetId").unwrap_or_default();
+    vec![CompressedNftEvent {
+        action,
+        asset_id,
+        tree_id: string_field(tx, "treeId").unwrap_or_default(),
+        leaf_index: tx.get("leafIndex").and_then(|v| v.as_u64()),
+        old_owner: string_field(tx, "from"),
+        new_owner: string_field(tx, "to"),
+        name: None,
+        symbol: None,
+        collection: None,
+    }]
+}
+
+/// Program ids invoked by the transaction's top-level and inner
+/// instructions.
+pub fn collect_program_ids(tx: &Value) -> Vec<String> {
+    let mut ids = Vec::new();
+    let mut push = |instruction: &Value| {
+        if let Some(id) = instruction.get("programId").and_then(|v| v.as_str()) {
+            if !ids.iter().any(|existing| existing == id) {
+                ids.push(id.to_string());
+            }
+        }
+    };
+
+    if let Some(instructions) = tx.get("instructions").and_then(|v| v.as_array()) {
+        for instruction in instructions {
+            push(instruction);
+            if let Some(inner) = instruction
+                .get("innerInstructions")
+                .and_then(|v| v.as_array())
+            {
+                inner.iter().for_each(&mut push);
+            }
+        }
+    }
+    ids
+}
+
+/// True when the transaction invokes Bubblegum, even if no compressed
+/// event could be decoded from it.
+pub fn is_bubblegum_transaction(program_ids: &[String]) -> bool {
+    program_ids.iter().any(|id| id == BUBBLEGUM_PROGRAM_ID)
+}
+
+fn parse_event(entry: &Value) -> Option<CompressedNftEvent> {
+    let action = entry
+        .get("type")
+        .and_then(|v| v.as_str())
+        .and_then(CompressedNftAction::from_helius_type)?;
+    let metadata = entry.get("metadata");
+
+    Some(CompressedNftEvent {
+        action,
+        asset_id: string_field(entry, "assetId")?,
+        tree_id: string_field(entry, "treeId").unwrap_or_default(),
+        leaf_index: entry.get("leafIndex").and_then(|v| v.as_u64()),
+        old_owner: string_field(entry, "oldLeafOwner"),
+        new_owner: string_field(entry, "newLeafOwner"),
+        name: metadata.and_then(|m| string_field(m, "name")),
+        symbol: metadata.and_then(|m| string_field(m, "symbol")),
+        collection: metadata
+            .and_then(|m| m.get("collection"))
+            .and_then(|c| string_field(c, "key")),
+    })
+}
+
+fn string_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_compressed_events_with_metadata() {
+        let tx = json!({
+            "type": "COMPRESSED_NFT_MINT",
+            "events": {
+                "compressed": [{
+                    "type": "COMPRESSED_NFT_MINT",
+                    "treeId": "tree1",
+                    "assetId": "asset1",
+                    "leafIndex": 42,
+                    "newLeafOwner": "walletA",
+                    "metadata": {
+                        "name": "Drop #42",
+                        "symbol": "DROP",
+                        "collection": { "key": "collection1", "verified": true }
+                    }
+                }]
+            },
+            "instructions": [{ "programId": BUBBLEGUM_PROGRAM_ID, "innerInstructions": [
+                { "programId": ACCOUNT_COMPRESSION_PROGRAM_ID },
+                { "programId": NOOP_PROGRAM_ID }
+            ]}]
+        });
+
+        let events = parse_compressed_nft_events(&tx);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, CompressedNftAction::Mint);
+        assert_eq!(events[0].leaf_index, Some(42));
+        assert_eq!(events[0].collection.as_deref(), Some("collection1"));
+        assert!(events[0].involves("walletA"));
+
+        let programs = collect_program_ids(&tx);
+        assert_eq!(programs.len(), 3);
+        assert!(is_bubblegum_transaction(&programs));
+    }
+
+    #[test]
+    fn falls_back_to_transaction_type() {
+        let tx = json!({
+            "type": "COMPRESSED_NFT_TRANSFER",
+            "assetId": "asset2",
+            "from": "walletA",
+            "to": "walletB"
+        });
+        let events = parse_compressed_nft_events(&tx);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action.activity_type(), "cnft_transfer");
+        assert_eq!(events[0].new_owner.as_deref(), Some("walletB"));
+
+        assert!(parse_compressed_nft_events(&json!({ "type": "SWAP" })).is_empty());
+    }
+}
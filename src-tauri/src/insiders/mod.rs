@@ -1,11 +1,13 @@
 pub mod alert_manager;
 pub mod commands;
+pub mod compressed_nft;
 pub mod smart_money;
 pub mod types;
 pub mod wallet_monitor;
 
 pub use alert_manager::*;
 pub use commands::*;
+pub use compressed_nft::*;
 pub use smart_money::*;
 pub use types::*;
 pub use wallet_monitor::*;
@@ -169,6 +169,8 @@ pub struct WalletStatistics {
     pub buy_count: i64,
     pub sell_count: i64,
     pub transfer_count: i64,
+    #[serde(default)]
+    pub compressed_nft_count: i64,
     pub total_volume_usd: f64,
     pub avg_transaction_size: f64,
     pub last_activity: Option<DateTime<Utc>>,
@@ -617,6 +619,7 @@ impl WalletMonitorDatabase {
                 SUM(CASE WHEN action_type = 'buy' THEN 1 ELSE 0 END) as buys,
                 SUM(CASE WHEN action_type = 'sell' THEN 1 ELSE 0 END) as sells,
                 SUM(CASE WHEN action_type = 'transfer' THEN 1 ELSE 0 END) as transfers,
+                SUM(CASE WHEN action_type LIKE 'cnft_%' THEN 1 ELSE 0 END) as compressed_nfts,
                 COALESCE(SUM(amount_usd), 0) as volume,
                 COALESCE(AVG(amount_usd), 0) as avg_size,
                 MAX(timestamp) as last_activity
@@ -634,6 +637,7 @@ impl WalletMonitorDatabase {
             buy_count: row.try_get("buys").unwrap_or(0),
             sell_count: row.try_get("sells").unwrap_or(0),
             transfer_count: row.try_get("transfers").unwrap_or(0),
+            compressed_nft_count: row.try_get("compressed_nfts").unwrap_or(0),
             total_volume_usd: row.try_get("volume").unwrap_or(0.0),
             avg_transaction_size: row.try_get("avg_size").unwrap_or(0.0),
             last_activity: row
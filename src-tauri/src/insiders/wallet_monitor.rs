@@ -1,5 +1,9 @@
+use crate::profiles::ProfilePaths;
+use super::compressed_nft::is_bubblegum_transaction;
 use super::{types::*, AlertManager, SmartMoneyDetector};
 use crate::core::WebSocketManager;
+use crate::portfolio::SharedCompressedNftLedger;
+use crate::wallet::display_names::local_display_name;
 use crate::websocket::types::{StreamEvent, TransactionUpdate};
 use chrono::Utc;
 use serde_json::json;
@@ -222,6 +226,12 @@ impl WalletMonitor {
             .ok_or_else(|| "Wallet not found after update".to_string())
     }
 
+    /// The monitor's own label wins; otherwise use the name the rest of the
+    /// app shows for the address so alerts match wallet and contact lists.
+    fn display_label(&self, label: Option<String>, address: &str) -> Option<String> {
+        label.or_else(|| local_display_name(&self.app_handle, address))
+    }
+
     pub async fn list_wallets(&self) -> Result<Vec<MonitoredWallet>, String> {
         self.db
             .read()
@@ -260,7 +270,10 @@ impl WalletMonitor {
                 WalletActivity {
                     id: r.id,
                     wallet_address: r.wallet_address.clone(),
-                    wallet_label: wallet_map.get(&r.wallet_address).cloned().flatten(),
+                    wallet_label: self.display_label(
+                        wallet_map.get(&r.wallet_address).cloned().flatten(),
+                        &r.wallet_address,
+                    ),
                     tx_signature: r.tx_signature,
                     action_type: r.action_type,
                     input_mint: r.input_mint,
@@ -323,6 +336,10 @@ impl WalletMonitor {
             }
         }
 
+        if !tx.compressed_nft_events.is_empty() || is_bubblegum_transaction(&tx.program_ids) {
+            return self.process_compressed_nft_transaction(&tx).await;
+        }
+
         let from_address = tx.from.clone().unwrap_or_default();
         let to_address = tx.to.clone().unwrap_or_default();
 
@@ -371,7 +388,8 @@ impl WalletMonitor {
             let wallet_activity = WalletActivity {
                 id: activity.id.clone(),
                 wallet_address: wallet_address.clone(),
-                wallet_label: wallet_info.and_then(|w| w.label.clone()),
+                wallet_label: self
+                    .display_label(wallet_info.and_then(|w| w.label.clone()), &wallet_address),
                 tx_signature: activity.tx_signature.clone(),
                 action_type: activity.action_type.clone(),
                 input_mint: activity.input_mint.clone(),
@@ -456,6 +474,123 @@ impl WalletMonitor {
         Ok(())
     }
 
+    /// Records Bubblegum mints, transfers and burns as activity and keeps
+    /// the portfolio's cNFT holdings in sync. These carry no token amounts,
+    /// so they skip whale and smart money processing.
+    async fn process_compressed_nft_transaction(
+        &self,
+        tx: &TransactionUpdate,
+    ) -> Result<(), String> {
+        let timestamp =
+            chrono::DateTime::from_timestamp(tx.timestamp, 0).unwrap_or_else(|| Utc::now());
+
+        if let Some(ledger) = self.app_handle.try_state::<SharedCompressedNftLedger>() {
+            let mut ledger = ledger.write().await;
+            let mut changed = false;
+            for event in &tx.compressed_nft_events {
+                changed |= ledger.apply(event, &tx.signature, timestamp);
+            }
+            if changed {
+                if let Err(err) = ledger.save() {
+                    eprintln!("Failed to persist compressed NFT holdings: {err}");
+                }
+            }
+        }
+
+        let monitored = self.monitored_wallets.read().await.clone();
+        let mut activities = Vec::new();
+        for event in &tx.compressed_nft_events {
+            let mut wallets: Vec<&String> = [&event.new_owner, &event.old_owner]
+                .into_iter()
+                .flatten()
+                .filter(|wallet| monitored.contains(*wallet))
+                .collect();
+            wallets.dedup();
+
+            for wallet_address in wallets {
+                activities.push(WalletActivityRecord {
+                    id: Uuid::new_v4().to_string(),
+                    wallet_address: wallet_address.clone(),
+                    tx_signature: tx.signature.clone(),
+                    action_type: event.action.activity_type().to_string(),
+                    input_mint: None,
+                    output_mint: Some(event.asset_id.clone()),
+                    input_symbol: None,
+                    output_symbol: event.name.clone().or_else(|| event.symbol.clone()),
+                    amount: Some(1.0),
+                    amount_usd: None,
+                    price: None,
+                    timestamp,
+                });
+            }
+        }
+
+        // Bubblegum calls we could not decode still belong to the wallet;
+        // label them instead of letting them surface as unknown programs.
+        if tx.compressed_nft_events.is_empty() {
+            let wallet = [&tx.from, &tx.to]
+                .into_iter()
+                .flatten()
+                .find(|address| monitored.contains(*address));
+            if let Some(wallet_address) = wallet {
+                activities.push(WalletActivityRecord {
+                    id: Uuid::new_v4().to_string(),
+                    wallet_address: wallet_address.clone(),
+                    tx_signature: tx.signature.clone(),
+                    action_type: "cnft_other".to_string(),
+                    input_mint: None,
+                    output_mint: None,
+                    input_symbol: None,
+                    output_symbol: tx.symbol.clone(),
+                    amount: None,
+                    amount_usd: None,
+                    price: None,
+                    timestamp,
+                });
+            }
+        }
+
+        if activities.is_empty() {
+            return Ok(());
+        }
+
+        let wallets = self.list_wallets().await?;
+        for activity in activities {
+            self.db
+                .write()
+                .await
+                .add_activity(&activity)
+                .await
+                .map_err(|e| format!("Failed to save activity: {e}"))?;
+
+            let wallet_info = wallets
+                .iter()
+                .find(|w| w.wallet_address == activity.wallet_address);
+            let wallet_activity = WalletActivity {
+                id: activity.id,
+                wallet_label: self.display_label(
+                    wallet_info.and_then(|w| w.label.clone()),
+                    &activity.wallet_address,
+                ),
+                is_whale: wallet_info.map(|w| w.is_whale).unwrap_or(false),
+                wallet_address: activity.wallet_address,
+                tx_signature: activity.tx_signature,
+                action_type: activity.action_type,
+                input_mint: activity.input_mint,
+                output_mint: activity.output_mint,
+                input_symbol: activity.input_symbol,
+                output_symbol: activity.output_symbol,
+                amount: activity.amount,
+                amount_usd: activity.amount_usd,
+                price: activity.price,
+                timestamp: activity.timestamp,
+            };
+            let _ = self.app_handle.emit("wallet_activity", &wallet_activity);
+        }
+
+        Ok(())
+    }
+
     pub async fn start_monitoring(monitor: Arc<Self>) {
         let mut ticker = interval(Duration::from_secs(120));
         loop {
@@ -484,7 +619,7 @@ pub async fn init_wallet_monitor(app_handle: &AppHandle) -> Result<(), String> {
     let app = app_handle.clone();
     let app_dir = app
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|e| format!("Unable to resolve app data directory: {}", e))?;
     std::fs::create_dir_all(&app_dir)
         .map_err(|e| format!("Failed to create app data directory: {e}"))?;
@@ -96,6 +96,7 @@ mod tests {
             buy_count: 60,
             sell_count: 30,
             transfer_count: 10,
+            compressed_nft_count: 0,
             total_volume_usd: 500000.0,
             avg_transaction_size: 5000.0,
             last_activity: Some(Utc::now()),
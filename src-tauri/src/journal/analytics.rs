@@ -1,4 +1,5 @@
 use super::types::*;
+use crate::research::ThesisReview;
 use chrono::Utc;
 use std::collections::HashMap;
 
@@ -163,6 +164,7 @@ impl JournalAnalytics {
             strategy_performance,
             psychological_insights,
             recommendations,
+            thesis_review: ThesisReview::default(),
             created_at: now,
         }
     }
@@ -1,7 +1,9 @@
 use super::analytics::JournalAnalytics;
 use super::database::SharedJournalDatabase;
 use super::types::*;
-use chrono::Utc;
+use crate::alerts::SharedAlertManager;
+use crate::research::{sync_theses, SharedResearchStore};
+use chrono::{DateTime, Utc};
 
 #[tauri::command]
 pub async fn create_journal_entry(
@@ -95,6 +97,8 @@ pub async fn get_journal_entries_count(
 pub async fn generate_weekly_report(
     week_start: Option<i64>,
     db: tauri::State<'_, SharedJournalDatabase>,
+    research: tauri::State<'_, SharedResearchStore>,
+    alerts: tauri::State<'_, SharedAlertManager>,
 ) -> Result<WeeklyReport, String> {
     let now = Utc::now().timestamp();
     let week_start = week_start.unwrap_or(now - (7 * 24 * 60 * 60));
@@ -121,7 +125,22 @@ pub async fn generate_weekly_report(
         .map_err(|e| e.to_string())?;
     drop(db_lock);
 
-    let report = JournalAnalytics::generate_weekly_report(&entries);
+    let mut report = JournalAnalytics::generate_weekly_report(&entries);
+
+    if let Err(e) = sync_theses(&research, &alerts).await {
+        eprintln!("Failed to sync thesis alerts for weekly report: {}", e);
+    }
+    let window = |ts: i64| DateTime::from_timestamp(ts, 0).unwrap_or_default();
+    report.thesis_review = research
+        .thesis_review(window(week_start), window(week_end))
+        .await;
+    let invalidated = report.thesis_review.invalidated.len();
+    if invalidated > 0 {
+        report.recommendations.push(format!(
+            "{} thesis(es) invalidated this week. Check that you exited where you planned to.",
+            invalidated
+        ));
+    }
 
     let db_lock = db.write().await;
     db_lock
@@ -86,6 +86,7 @@ impl JournalDatabase {
                 strategy_performance TEXT NOT NULL,
                 psychological_insights TEXT NOT NULL,
                 recommendations TEXT NOT NULL,
+                thesis_review TEXT NOT NULL DEFAULT '{}',
                 created_at INTEGER NOT NULL
             )
             "#,
@@ -93,6 +94,13 @@ impl JournalDatabase {
         .execute(&self.pool)
         .await?;
 
+        // Added after the table shipped; fails harmlessly once present.
+        let _ = sqlx::query(
+            "ALTER TABLE weekly_reports ADD COLUMN thesis_review TEXT NOT NULL DEFAULT '{}'",
+        )
+        .execute(&self.pool)
+        .await;
+
         sqlx::query(
             r#"
             CREATE INDEX IF NOT EXISTS idx_weekly_reports_week_start ON weekly_reports(week_start);
@@ -351,6 +359,7 @@ impl JournalDatabase {
             serde_json::to_string(&report.psychological_insights).unwrap_or_default();
         let recommendations_json =
             serde_json::to_string(&report.recommendations).unwrap_or_default();
+        let thesis_review_json = serde_json::to_string(&report.thesis_review).unwrap_or_default();
 
         sqlx::query(
             r#"
@@ -359,9 +368,9 @@ impl JournalDatabase {
                 trades_won, trades_lost, win_rate, total_pnl,
                 average_confidence, emotion_breakdown, discipline_metrics,
                 pattern_insights, strategy_performance, psychological_insights,
-                recommendations, created_at
+                recommendations, thesis_review, created_at
             ) VALUES (
-                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18
             )
             "#,
         )
@@ -381,6 +390,7 @@ impl JournalDatabase {
         .bind(strategy_performance_json)
         .bind(psychological_insights_json)
         .bind(recommendations_json)
+        .bind(thesis_review_json)
         .bind(report.created_at)
         .execute(&self.pool)
         .await?;
@@ -499,6 +509,11 @@ impl JournalDatabase {
                     cognitive_biases_detected: vec![],
                 }),
             recommendations: serde_json::from_str(row.get("recommendations")).unwrap_or_default(),
+            thesis_review: row
+                .try_get::<String, _>("thesis_review")
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default(),
             created_at: row.get("created_at"),
         }
     }
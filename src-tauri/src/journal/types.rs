@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::research::ThesisReview;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct JournalEntry {
@@ -145,6 +146,10 @@ pub struct WeeklyReport {
     pub strategy_performance: Vec<StrategyPerformance>,
     pub psychological_insights: PsychologicalInsights,
     pub recommendations: Vec<String>,
+    /// Research theses that hit an invalidation or confirmation level
+    /// during the week.
+    #[serde(default)]
+    pub thesis_review: ThesisReview,
     pub created_at: i64,
 }
 
@@ -0,0 +1,116 @@
+use super::privacy::StreamerModeSettings;
+use super::server::{
+    LanDashboardServer, LanDashboardSettings, LanDashboardStatus, SharedLanDashboardServer,
+};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+#[tauri::command]
+pub async fn lan_dashboard_get_settings(
+    server: State<'_, SharedLanDashboardServer>,
+) -> Result<LanDashboardSettings, String> {
+    Ok(server.read().await.settings())
+}
+
+/// Saves the settings and restarts the server if it was running, so new
+/// channels or a new port take effect immediately.
+#[tauri::command]
+pub async fn lan_dashboard_update_settings(
+    app: AppHandle,
+    server: State<'_, SharedLanDashboardServer>,
+    settings: LanDashboardSettings,
+) -> Result<LanDashboardStatus, String> {
+    let mut server = server.write().await;
+    server.update_settings(settings)?;
+    restart_if_running(&app, &mut server).await
+}
+
+/// Issues a new access token and disconnects clients using the old one.
+#[tauri::command]
+pub async fn lan_dashboard_rotate_token(
+    app: AppHandle,
+    server: State<'_, SharedLanDashboardServer>,
+) -> Result<String, String> {
+    let mut server = server.write().await;
+    let token = server.rotate_token()?;
+    restart_if_running(&app, &mut server).await?;
+    Ok(token)
+}
+
+#[tauri::command]
+pub async fn lan_dashboard_start(
+    app: AppHandle,
+    server: State<'_, SharedLanDashboardServer>,
+) -> Result<LanDashboardStatus, String> {
+    let mut server = server.write().await;
+    server.start(&app).await
+}
+
+#[tauri::command]
+pub async fn lan_dashboard_stop(
+    app: AppHandle,
+    server: State<'_, SharedLanDashboardServer>,
+) -> Result<LanDashboardStatus, String> {
+    let mut server = server.write().await;
+    Ok(server.stop(&app))
+}
+
+#[tauri::command]
+pub async fn lan_dashboard_status(
+    server: State<'_, SharedLanDashboardServer>,
+) -> Result<LanDashboardStatus, String> {
+    Ok(server.read().await.status())
+}
+
+#[tauri::command]
+pub async fn streamer_mode_get(
+    server: State<'_, SharedLanDashboardServer>,
+) -> Result<StreamerModeSettings, String> {
+    Ok(server.read().await.streamer_mode())
+}
+
+/// Applies streamer mode to the dashboard feed and tells the UI, which masks
+/// its own views from the same settings.
+#[tauri::command]
+pub async fn streamer_mode_update(
+    app: AppHandle,
+    server: State<'_, SharedLanDashboardServer>,
+    settings: StreamerModeSettings,
+) -> Result<StreamerModeSettings, String> {
+    server.write().await.set_streamer_mode(settings.clone())?;
+    let _ = app.emit("streamer_mode_changed", &settings);
+    Ok(settings)
+}
+
+/// Masks an arbitrary payload with the current streamer mode rules, so UI
+/// widgets and overlays can share one definition of what is sensitive.
+#[tauri::command]
+pub async fn streamer_mode_sanitize(
+    server: State<'_, SharedLanDashboardServer>,
+    payload: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    Ok(server.read().await.streamer_mode().sanitize(payload))
+}
+
+async fn restart_if_running(
+    app: &AppHandle,
+    server: &mut LanDashboardServer,
+) -> Result<LanDashboardStatus, String> {
+    if !server.is_running() {
+        return Ok(server.status());
+    }
+    server.stop(app);
+
+    // The old accept loop releases the port once it sees the shutdown signal.
+    let mut attempts = 0;
+    loop {
+        match server.start(app).await {
+            Ok(status) => return Ok(status),
+            Err(e) if attempts >= 10 => return Err(e),
+            Err(_) => {
+                attempts += 1;
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod commands;
+pub mod privacy;
+pub mod server;
+
+pub use commands::*;
+pub use privacy::*;
+pub use server::*;
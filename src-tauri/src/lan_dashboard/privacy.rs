@@ -0,0 +1,231 @@
+//! Privacy masking for streamer mode.
+//!
+//! Masking works on field names rather than per-event schemas, so new events
+//! added to a dashboard channel are covered without extra wiring. The rules
+//! err on the side of hiding: anything that looks like a size or a wallet
+//! address is masked unless it is clearly a percentage or a token mint.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Characters kept at each end of a masked address.
+const ADDRESS_KEEP: usize = 4;
+
+/// Fields holding absolute sizes: balances, quantities, notional values, PnL.
+const SIZE_FIELDS: &[&str] = &[
+    "balance",
+    "amount",
+    "quantity",
+    "qty",
+    "size",
+    "value",
+    "notional",
+    "total",
+    "cost",
+    "pnl",
+    "profit",
+    "loss",
+    "fee",
+    "equity",
+    "collateral",
+    "spent",
+    "proceeds",
+    "holdings",
+    "lamports",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StreamerModeSettings {
+    pub enabled: bool,
+    /// Mask balances, position sizes, fees and absolute PnL.
+    pub hide_balances: bool,
+    /// Shorten wallet addresses and signatures to `AbCd…WxYz`.
+    pub mask_addresses: bool,
+    /// Mask every absolute number, prices included, leaving percentages,
+    /// timestamps and counts.
+    pub percentages_only: bool,
+}
+
+impl Default for StreamerModeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hide_balances: true,
+            mask_addresses: true,
+            percentages_only: false,
+        }
+    }
+}
+
+impl StreamerModeSettings {
+    /// Returns a masked copy of `payload`, or the payload unchanged while
+    /// streamer mode is off.
+    pub fn sanitize(&self, payload: Value) -> Value {
+        if !self.enabled {
+            return payload;
+        }
+        let mut payload = payload;
+        self.mask_value(None, &mut payload);
+        payload
+    }
+
+    fn mask_value(&self, key: Option<&str>, value: &mut Value) {
+        match value {
+            Value::Object(map) => self.mask_object(map),
+            Value::Array(items) => {
+                for item in items {
+                    self.mask_value(key, item);
+                }
+            }
+            Value::Number(_) => {
+                if key.is_some_and(|key| self.hides_number(key)) {
+                    *value = Value::Null;
+                }
+            }
+            Value::String(text) => {
+                if key.is_some_and(|key| self.hides_number(key)) && text.parse::<f64>().is_ok() {
+                    *value = Value::Null;
+                } else if self.mask_addresses
+                    && !key.is_some_and(is_mint_field)
+                    && looks_like_address(text)
+                {
+                    *value = Value::String(shorten(text));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn mask_object(&self, map: &mut Map<String, Value>) {
+        for (key, value) in map.iter_mut() {
+            self.mask_value(Some(key), value);
+        }
+    }
+
+    fn hides_number(&self, key: &str) -> bool {
+        let key = normalize(key);
+        if is_percentage_field(&key) {
+            return false;
+        }
+        if self.percentages_only {
+            return !is_neutral_field(&key);
+        }
+        self.hide_balances && SIZE_FIELDS.iter().any(|field| key.contains(field))
+    }
+}
+
+fn normalize(key: &str) -> String {
+    key.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+fn is_percentage_field(key: &str) -> bool {
+    key.contains("pct")
+        || key.contains("percent")
+        || key.contains("ratio")
+        || key.ends_with("bps")
+        || key.ends_with("rate")
+        || key.ends_with("change")
+}
+
+/// Numbers that say nothing about the user's size.
+fn is_neutral_field(key: &str) -> bool {
+    key.contains("time")
+        || key.ends_with("at")
+        || key.ends_with("id")
+        || key.ends_with("count")
+        || key.contains("slot")
+        || key.contains("decimals")
+        || key.contains("confidence")
+        || key.contains("score")
+}
+
+fn is_mint_field(key: &str) -> bool {
+    normalize(key).contains("mint")
+}
+
+/// Base58 strings the length of a public key or a transaction signature.
+fn looks_like_address(text: &str) -> bool {
+    matches!(text.len(), 32..=44 | 86..=88)
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l'))
+}
+
+fn shorten(text: &str) -> String {
+    format!(
+        "{}…{}",
+        &text[..ADDRESS_KEEP],
+        &text[text.len() - ADDRESS_KEEP..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const WALLET: &str = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
+    const MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    fn enabled() -> StreamerModeSettings {
+        StreamerModeSettings {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn hides_sizes_and_addresses_but_keeps_percentages() {
+        let payload = json!({
+            "wallet": WALLET,
+            "outputMint": MINT,
+            "symbol": "SOL",
+            "price": 142.5,
+            "quantity": 12.0,
+            "unrealized_pnl": 310.0,
+            "pnlPct": 4.2,
+            "fills": [{ "amount": "5.5", "priceChange": -0.3 }]
+        });
+
+        let masked = enabled().sanitize(payload);
+
+        assert_eq!(masked["wallet"], "9xQe…VFin");
+        assert_eq!(masked["outputMint"], MINT);
+        assert_eq!(masked["price"], 142.5);
+        assert!(masked["quantity"].is_null());
+        assert!(masked["unrealized_pnl"].is_null());
+        assert_eq!(masked["pnlPct"], 4.2);
+        assert!(masked["fills"][0]["amount"].is_null());
+        assert_eq!(masked["fills"][0]["priceChange"], -0.3);
+    }
+
+    #[test]
+    fn percentages_only_masks_prices_too() {
+        let settings = StreamerModeSettings {
+            percentages_only: true,
+            ..enabled()
+        };
+        let masked = settings.sanitize(json!({
+            "price": 142.5,
+            "changePercent": 1.5,
+            "timestamp": 1_700_000_000,
+        }));
+
+        assert!(masked["price"].is_null());
+        assert_eq!(masked["changePercent"], 1.5);
+        assert_eq!(masked["timestamp"], 1_700_000_000);
+    }
+
+    #[test]
+    fn disabled_mode_passes_payload_through() {
+        let payload = json!({ "balance": 1000.0, "wallet": WALLET });
+        assert_eq!(
+            StreamerModeSettings::default().sanitize(payload.clone()),
+            payload
+        );
+    }
+}
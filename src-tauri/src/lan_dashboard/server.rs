@@ -0,0 +1,611 @@
+//! Read-only WebSocket feed for LAN dashboards and stream overlays.
+//!
+//! Clients connect to `ws://<host>:<port>/stream?token=<token>` and may add
+//! `&channels=prices,alerts` to narrow the feed to a subset of the channels
+//! the user enabled. Every frame wraps an app event the desktop UI already
+//! receives; anything a client sends other than pings is ignored.
+
+use chrono::{DateTime, Utc};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, EventId, Listener, Manager};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::{oneshot, RwLock};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::privacy::StreamerModeSettings;
+use crate::profiles::ProfilePaths;
+
+pub const DEFAULT_DASHBOARD_PORT: u16 = 8790;
+const SETTINGS_FILE: &str = "lan_dashboard.json";
+const STREAM_PATH: &str = "/stream";
+const BROADCAST_CAPACITY: usize = 512;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const MIN_TOKEN_LEN: usize = 16;
+const MAX_CLIENTS_LIMIT: usize = 64;
+
+pub type SharedLanDashboardServer = Arc<RwLock<LanDashboardServer>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DashboardChannel {
+    Prices,
+    Alerts,
+    Strategies,
+    Orders,
+}
+
+impl DashboardChannel {
+    pub const ALL: [DashboardChannel; 4] = [
+        DashboardChannel::Prices,
+        DashboardChannel::Alerts,
+        DashboardChannel::Strategies,
+        DashboardChannel::Orders,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DashboardChannel::Prices => "prices",
+            DashboardChannel::Alerts => "alerts",
+            DashboardChannel::Strategies => "strategies",
+            DashboardChannel::Orders => "orders",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|channel| channel.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    /// App events forwarded on this channel.
+    pub fn events(&self) -> &'static [&'static str] {
+        match self {
+            DashboardChannel::Prices => &["price_update", "chart_price_update"],
+            DashboardChannel::Alerts => &[
+                "alert_triggered",
+                "whale_alert",
+                "smart_money_alert",
+                "insider_pattern_alert",
+            ],
+            DashboardChannel::Strategies => &[
+                "auto_trading_webhook_signal",
+                "dca_execution",
+                "grid_fill",
+                "copy_trade_execution",
+                "mobile_remote_halt",
+            ],
+            DashboardChannel::Orders => &["order_update", "order_triggered"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LanDashboardSettings {
+    /// Start the server when the app launches.
+    pub auto_start: bool,
+    /// Listen on every interface rather than loopback only. Overlays on the
+    /// same machine don't need this; wall-mounted tablets do.
+    pub allow_lan: bool,
+    pub port: u16,
+    pub channels: Vec<DashboardChannel>,
+    pub access_token: String,
+    pub max_clients: usize,
+    /// Masking applied to every frame while streamer mode is on.
+    pub streamer_mode: StreamerModeSettings,
+}
+
+impl Default for LanDashboardSettings {
+    fn default() -> Self {
+        Self {
+            auto_start: false,
+            allow_lan: true,
+            port: DEFAULT_DASHBOARD_PORT,
+            channels: DashboardChannel::ALL.to_vec(),
+            access_token: generate_token(),
+            max_clients: 8,
+            streamer_mode: StreamerModeSettings::default(),
+        }
+    }
+}
+
+impl LanDashboardSettings {
+    fn validate(&self) -> Result<(), String> {
+        if self.port < 1024 {
+            return Err("Dashboard port must be 1024 or higher".to_string());
+        }
+        if self.channels.is_empty() {
+            return Err("Enable at least one dashboard channel".to_string());
+        }
+        if self.access_token.len() < MIN_TOKEN_LEN {
+            return Err(format!(
+                "Access token must be at least {} characters",
+                MIN_TOKEN_LEN
+            ));
+        }
+        if self.max_clients == 0 || self.max_clients > MAX_CLIENTS_LIMIT {
+            return Err(format!(
+                "Max clients must be between 1 and {}",
+                MAX_CLIENTS_LIMIT
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanDashboardStatus {
+    pub running: bool,
+    pub bind_address: Option<String>,
+    pub connected_clients: usize,
+    pub frames_sent: u64,
+    pub started_at: Option<DateTime<Utc>>,
+    /// Connection URLs without the token, for display in settings.
+    pub urls: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardFrame {
+    pub channel: DashboardChannel,
+    pub event: String,
+    pub payload: Value,
+    /// Whether streamer mode masked the payload.
+    pub masked: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+type FrameSender = broadcast::Sender<(DashboardChannel, Arc<str>)>;
+
+pub struct LanDashboardServer {
+    settings: LanDashboardSettings,
+    settings_path: Option<PathBuf>,
+    shutdown: Option<oneshot::Sender<()>>,
+    listeners: Vec<EventId>,
+    bound: Option<SocketAddr>,
+    started_at: Option<DateTime<Utc>>,
+    clients: Arc<AtomicUsize>,
+    frames_sent: Arc<AtomicU64>,
+    /// Shared with the event listeners so toggling streamer mode applies to
+    /// the next frame without a restart.
+    privacy: Arc<parking_lot::RwLock<StreamerModeSettings>>,
+}
+
+impl LanDashboardServer {
+    pub fn new(app: &AppHandle) -> Self {
+        let settings_path = app
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(SETTINGS_FILE));
+        let stored = settings_path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str::<LanDashboardSettings>(&contents).ok());
+
+        let settings = stored.clone().unwrap_or_default();
+        let server = Self {
+            privacy: Arc::new(parking_lot::RwLock::new(settings.streamer_mode.clone())),
+            settings,
+            settings_path,
+            shutdown: None,
+            listeners: Vec::new(),
+            bound: None,
+            started_at: None,
+            clients: Arc::new(AtomicUsize::new(0)),
+            frames_sent: Arc::new(AtomicU64::new(0)),
+        };
+        // Persist the generated token so it survives restarts.
+        if stored.is_none() {
+            if let Err(e) = server.persist() {
+                eprintln!("{}", e);
+            }
+        }
+        server
+    }
+
+    fn persist(&self) -> Result<(), String> {
+        let Some(path) = &self.settings_path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create dashboard settings dir: {}", e))?;
+        }
+        let contents = serde_json::to_string_pretty(&self.settings)
+            .map_err(|e| format!("Failed to serialize dashboard settings: {}", e))?;
+        fs::write(path, contents).map_err(|e| format!("Failed to save dashboard settings: {}", e))
+    }
+
+    pub fn settings(&self) -> LanDashboardSettings {
+        self.settings.clone()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.shutdown.is_some()
+    }
+
+    /// Saves new settings; a running server must be restarted to apply them.
+    pub fn update_settings(&mut self, mut settings: LanDashboardSettings) -> Result<(), String> {
+        let mut seen = Vec::new();
+        settings.channels.retain(|channel| {
+            let first = !seen.contains(channel);
+            seen.push(*channel);
+            first
+        });
+        settings.validate()?;
+        *self.privacy.write() = settings.streamer_mode.clone();
+        self.settings = settings;
+        self.persist()
+    }
+
+    pub fn streamer_mode(&self) -> StreamerModeSettings {
+        self.settings.streamer_mode.clone()
+    }
+
+    /// Takes effect immediately for connected clients.
+    pub fn set_streamer_mode(&mut self, streamer_mode: StreamerModeSettings) -> Result<(), String> {
+        *self.privacy.write() = streamer_mode.clone();
+        self.settings.streamer_mode = streamer_mode;
+        self.persist()
+    }
+
+    pub fn rotate_token(&mut self) -> Result<String, String> {
+        self.settings.access_token = generate_token();
+        self.persist()?;
+        Ok(self.settings.access_token.clone())
+    }
+
+    pub fn status(&self) -> LanDashboardStatus {
+        let urls = match self.bound {
+            Some(addr) => {
+                let mut hosts = vec![IpAddr::V4(Ipv4Addr::LOCALHOST)];
+                if addr.ip().is_unspecified() {
+                    hosts.extend(lan_address());
+                }
+                hosts
+                    .into_iter()
+                    .map(|host| format!("ws://{}:{}{}", host, addr.port(), STREAM_PATH))
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
+        LanDashboardStatus {
+            running: self.is_running(),
+            bind_address: self.bound.map(|addr| addr.to_string()),
+            connected_clients: self.clients.load(Ordering::Relaxed),
+            frames_sent: self.frames_sent.load(Ordering::Relaxed),
+            started_at: self.started_at,
+            urls,
+        }
+    }
+
+    pub async fn start(&mut self, app: &AppHandle) -> Result<LanDashboardStatus, String> {
+        if self.is_running() {
+            return Err("Dashboard server already running".to_string());
+        }
+
+        let ip = if self.settings.allow_lan {
+            IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+        } else {
+            IpAddr::V4(Ipv4Addr::LOCALHOST)
+        };
+        let listener = TcpListener::bind((ip, self.settings.port))
+            .await
+            .map_err(|e| format!("Failed to bind dashboard server: {}", e))?;
+        let bound = listener.local_addr().map_err(|e| e.to_string())?;
+
+        let (frames, _) = broadcast::channel(BROADCAST_CAPACITY);
+        self.listeners =
+            forward_app_events(app, &self.settings.channels, &frames, self.privacy.clone());
+
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+        let token: Arc<str> = self.settings.access_token.as_str().into();
+        let enabled = self.settings.channels.clone();
+        let max_clients = self.settings.max_clients;
+        let clients = self.clients.clone();
+        let frames_sent = self.frames_sent.clone();
+
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    accepted = listener.accept() => {
+                        let (stream, _) = match accepted {
+                            Ok(conn) => conn,
+                            Err(e) => {
+                                eprintln!("Dashboard accept failed: {}", e);
+                                continue;
+                            }
+                        };
+                        let client = ClientContext {
+                            token: token.clone(),
+                            enabled: enabled.clone(),
+                            max_clients,
+                            clients: clients.clone(),
+                            frames_sent: frames_sent.clone(),
+                        };
+                        let rx = frames.subscribe();
+                        tauri::async_runtime::spawn(async move {
+                            serve_client(stream, client, rx).await;
+                        });
+                    }
+                }
+            }
+        });
+
+        self.shutdown = Some(shutdown_tx);
+        self.bound = Some(bound);
+        self.started_at = Some(Utc::now());
+        Ok(self.status())
+    }
+
+    /// Stops accepting connections and drops the event listeners, which
+    /// closes the feed for every connected client.
+    pub fn stop(&mut self, app: &AppHandle) -> LanDashboardStatus {
+        for id in self.listeners.drain(..) {
+            app.unlisten(id);
+        }
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        self.bound = None;
+        self.started_at = None;
+        self.status()
+    }
+}
+
+fn forward_app_events(
+    app: &AppHandle,
+    channels: &[DashboardChannel],
+    frames: &FrameSender,
+    privacy: Arc<parking_lot::RwLock<StreamerModeSettings>>,
+) -> Vec<EventId> {
+    let mut ids = Vec::new();
+    for &channel in channels {
+        for &event_name in channel.events() {
+            let frames = frames.clone();
+            let privacy = privacy.clone();
+            let id = app.listen_any(event_name, move |event| {
+                if frames.receiver_count() == 0 {
+                    return;
+                }
+                let payload = serde_json::from_str(event.payload()).unwrap_or(Value::Null);
+                let streamer_mode = privacy.read().clone();
+                let frame = DashboardFrame {
+                    channel,
+                    event: event_name.to_string(),
+                    masked: streamer_mode.enabled,
+                    payload: streamer_mode.sanitize(payload),
+                    timestamp: Utc::now(),
+                };
+                if let Ok(text) = serde_json::to_string(&frame) {
+                    let _ = frames.send((channel, text.into()));
+                }
+            });
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+struct ClientContext {
+    token: Arc<str>,
+    enabled: Vec<DashboardChannel>,
+    max_clients: usize,
+    clients: Arc<AtomicUsize>,
+    frames_sent: Arc<AtomicU64>,
+}
+
+enum HandshakeOutcome {
+    Accepted(Vec<DashboardChannel>),
+    Rejected,
+}
+
+/// Checks the path, token and client limit for an upgrade request and
+/// returns the channels the client will receive.
+fn authorize_request(
+    path: &str,
+    query: Option<&str>,
+    client: &ClientContext,
+) -> Result<Vec<DashboardChannel>, (StatusCode, &'static str)> {
+    if path != STREAM_PATH {
+        return Err((StatusCode::NOT_FOUND, "unknown path"));
+    }
+
+    let params: HashMap<String, String> = query
+        .and_then(|query| serde_urlencoded::from_str(query).ok())
+        .unwrap_or_default();
+    let token = params.get("token").map(String::as_str).unwrap_or("");
+    if !tokens_match(token, &client.token) {
+        return Err((StatusCode::UNAUTHORIZED, "invalid token"));
+    }
+    if client.clients.load(Ordering::Relaxed) >= client.max_clients {
+        return Err((StatusCode::SERVICE_UNAVAILABLE, "too many clients"));
+    }
+
+    let channels = match params.get("channels") {
+        Some(requested) => requested
+            .split(',')
+            .filter_map(DashboardChannel::parse)
+            .filter(|channel| client.enabled.contains(channel))
+            .collect::<Vec<_>>(),
+        None => client.enabled.clone(),
+    };
+    if channels.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "no enabled channels requested"));
+    }
+    Ok(channels)
+}
+
+async fn serve_client(
+    stream: TcpStream,
+    client: ClientContext,
+    mut frames: broadcast::Receiver<(DashboardChannel, Arc<str>)>,
+) {
+    let mut outcome = HandshakeOutcome::Rejected;
+    let callback = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        match authorize_request(request.uri().path(), request.uri().query(), &client) {
+            Ok(channels) => {
+                outcome = HandshakeOutcome::Accepted(channels);
+                Ok(response)
+            }
+            Err((status, reason)) => {
+                let mut error = ErrorResponse::new(Some(reason.to_string()));
+                *error.status_mut() = status;
+                Err(error)
+            }
+        }
+    };
+
+    let ws = match tokio::time::timeout(
+        HANDSHAKE_TIMEOUT,
+        tokio_tungstenite::accept_hdr_async(stream, callback),
+    )
+    .await
+    {
+        Ok(Ok(ws)) => ws,
+        _ => return,
+    };
+    let HandshakeOutcome::Accepted(channels) = outcome else {
+        return;
+    };
+
+    client.clients.fetch_add(1, Ordering::Relaxed);
+    let (mut sink, mut source) = ws.split();
+
+    let hello = json!({
+        "event": "hello",
+        "channels": channels,
+        "timestamp": Utc::now(),
+    });
+    if sink.send(Message::Text(hello.to_string())).await.is_ok() {
+        loop {
+            tokio::select! {
+                frame = frames.recv() => match frame {
+                    Ok((channel, text)) => {
+                        if !channels.contains(&channel) {
+                            continue;
+                        }
+                        if sink.send(Message::Text(text.to_string())).await.is_err() {
+                            break;
+                        }
+                        client.frames_sent.fetch_add(1, Ordering::Relaxed);
+                    }
+                    // A slow client misses frames rather than holding up the rest.
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                },
+                incoming = source.next() => match incoming {
+                    Some(Ok(Message::Ping(data))) => {
+                        if sink.send(Message::Pong(data)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                },
+            }
+        }
+    }
+
+    let _ = sink.close().await;
+    client.clients.fetch_sub(1, Ordering::Relaxed);
+}
+
+fn generate_token() -> String {
+    hex::encode(rand::random::<[u8; 24]>())
+}
+
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    if provided.len() != expected.len() {
+        return false;
+    }
+    provided
+        .bytes()
+        .zip(expected.bytes())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+/// The address other devices on the network would reach this machine on.
+/// Connecting a UDP socket only picks a route; nothing is sent.
+fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 168, 0, 1), 80)).ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(enabled: Vec<DashboardChannel>) -> ClientContext {
+        ClientContext {
+            token: "0123456789abcdef".into(),
+            enabled,
+            max_clients: 2,
+            clients: Arc::new(AtomicUsize::new(0)),
+            frames_sent: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    #[test]
+    fn authorizes_token_and_narrows_channels() {
+        let client = context(vec![DashboardChannel::Prices, DashboardChannel::Alerts]);
+
+        let channels = authorize_request(
+            "/stream",
+            Some("token=0123456789abcdef&channels=alerts,orders"),
+            &client,
+        )
+        .unwrap();
+        assert_eq!(channels, vec![DashboardChannel::Alerts]);
+
+        let all = authorize_request("/stream", Some("token=0123456789abcdef"), &client).unwrap();
+        assert_eq!(all, client.enabled);
+
+        let err = authorize_request("/stream", Some("token=wrong"), &client).unwrap_err();
+        assert_eq!(err.0, StatusCode::UNAUTHORIZED);
+        let err = authorize_request("/other", Some("token=0123456789abcdef"), &client).unwrap_err();
+        assert_eq!(err.0, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn rejects_clients_over_the_limit() {
+        let client = context(DashboardChannel::ALL.to_vec());
+        client.clients.store(2, Ordering::Relaxed);
+
+        let err =
+            authorize_request("/stream", Some("token=0123456789abcdef"), &client).unwrap_err();
+        assert_eq!(err.0, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn settings_validation() {
+        let mut settings = LanDashboardSettings::default();
+        assert!(settings.validate().is_ok());
+
+        settings.port = 80;
+        assert!(settings.validate().is_err());
+
+        settings.port = DEFAULT_DASHBOARD_PORT;
+        settings.access_token = "short".to_string();
+        assert!(settings.validate().is_err());
+    }
+}
@@ -21,6 +21,7 @@ mod config;
 mod core;
 mod data;
 mod defi;
+mod demo;
 mod dev_tools;
 mod diagnostics;
 mod drawings;
@@ -31,6 +32,7 @@ mod governance;
 mod indicators;
 mod insiders;
 mod journal;
+mod lan_dashboard;
 mod launchpad;
 mod logger;
 mod market;
@@ -39,15 +41,19 @@ mod monitor;
 mod notifications;
 mod portfolio;
 mod position_manager;
+mod environment;
+mod profiles;
 mod auto_compound;
 mod yield_farming;
 mod recovery;
+mod research;
 mod security;
 mod sentiment;
 mod social;
 mod stocks;
 mod stream_commands;
 mod tax;
+mod token_extensions;
 mod token_flow;
 mod trading;
 mod tray;
@@ -85,6 +91,7 @@ pub use config::*;
 pub use core::*;
 pub use data::*;
 pub use defi::*;
+pub use demo::*;
 pub use dev_tools::*;
 pub use drawings::*;
 pub use errors::*;
@@ -101,25 +108,37 @@ pub use notifications::*;
 pub use p2p::*;
 pub use portfolio::*;
 pub use position_manager::*;
+pub use profiles::*;
 pub use auto_compound::*;
 pub use yield_farming::*;
 pub use recovery::*;
+pub use research::*;
 pub use sentiment::*;
 pub use social::*;
 pub use stocks::*;
 pub use tax::*;
+pub use token_extensions::*;
 pub use token_flow::*;
 pub use trading::*;
 pub use tray::*;
 pub use ui::theme_engine::*;
 pub use updater::*;
 pub use voice::*;
+pub use wallet::fee_relayer::*;
 pub use wallet::hardware_wallet::*;
 pub use wallet::ledger::*;
 pub use wallet::multi_wallet::*;
 pub use wallet::operations::*;
 pub use wallet::phantom::*;
+pub use wallet::tx_builder::*;
+pub use wallet::tx_lifecycle::*;
+pub use wallet::walletconnect::*;
+pub use wallet::history_backfill::*;
+pub use wallet::flows::*;
+pub use wallet::receipts::*;
+pub use wallet::display_names::*;
 pub use webhooks::*;
+pub use lan_dashboard::*;
 
 pub use wallet::multisig::*;
 pub use wallet::performance::*;
@@ -132,15 +151,17 @@ use ai_legacy::launch_predictor::{
 };
 use alerts::{AlertManager, SharedAlertManager, SharedSmartAlertManager, SmartAlertManager};
 use api::{ApiHealthMonitor, SharedApiHealthMonitor};
+use auth::app_lock::{AppLockManager, SharedAppLock};
+use auth::command_policy::CommandPolicyManager;
+use auth::passkey::PasskeyManager;
 use auth::session_manager::SessionManager;
 use auth::two_factor::TwoFactorManager;
 use auto_start::{AutoStartManager, SharedAutoStartManager};
-use bridges::{BridgeManager, SharedBridgeManager};
-use chains::{ChainManager, SharedChainManager};
+use bridges::{BridgeManager, BridgeRegistry, SharedBridgeManager, SharedBridgeRegistry};
+use chains::{ChainManager, RpcPool, SharedChainManager, SharedRpcPool};
 use chrono::{Timelike, Utc};
 use collab::state::CollabState;
 use config::settings_manager::{SettingsManager, SharedSettingsManager};
-use core::cache_manager::{CacheType, SharedCacheManager};
 use data::event_store::{EventStore, SharedEventStore};
 use data::historical::{HistoricalReplayManager, SharedHistoricalReplayManager};
 use drawings::{DrawingManager, SharedDrawingManager};
@@ -157,6 +178,7 @@ use p2p::init_p2p_system;
 use portfolio::{
     AIPortfolioAdvisor, SharedAIPortfolioAdvisor, SharedWatchlistManager, WatchlistManager,
 };
+use profiles::ProfilePaths;
 use security::activity_log::ActivityLogger;
 use security::audit::AuditCache;
 use security::keystore::Keystore;
@@ -169,6 +191,7 @@ use tauri::Manager;
 use tokio::sync::RwLock;
 use tray::{attach_window_listeners, SharedTrayManager, TrayManager};
 use voice::commands::{SharedVoiceState, VoiceState};
+use wallet::fee_relayer::FeeRelayerManager;
 use wallet::hardware_wallet::HardwareWalletState;
 use wallet::ledger::LedgerState;
 use wallet::multi_wallet::MultiWalletManager;
@@ -176,7 +199,10 @@ use wallet::multisig::{MultisigDatabase, SharedMultisigDatabase};
 use wallet::operations::WalletOperationsManager;
 use wallet::performance::{PerformanceDatabase, SharedPerformanceDatabase};
 use wallet::phantom::{hydrate_wallet_state, WalletState};
-use webhooks::{SharedWebhookManager, WebhookManager};
+use wallet::walletconnect::WalletConnectState;
+use webhooks::{
+    InboundWebhookServer, SharedInboundWebhookServer, SharedWebhookManager, WebhookManager,
+};
 use updater::{SharedUpdaterState, UpdaterState};
 
 macro_rules! startup_log {
@@ -200,56 +226,6 @@ macro_rules! manage_state {
     }};
 }
 
-async fn warm_cache_on_startup(
-    _app_handle: tauri::AppHandle,
-    cache_manager: SharedCacheManager,
-) -> Result<(), String> {
-    use serde_json::json;
-
-    // Define top tokens to warm
-    let top_tokens = vec![
-        "So11111111111111111111111111111111111111112",  // SOL
-        "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", // USDC
-        "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB", // USDT
-        "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263", // BONK
-        "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN",  // JUP
-        "7vfCXTUXx5WJV5JADk17DUJ4ksgau7utNKj4b963voxs", // ETH
-        "mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So",  // mSOL
-        "7dHbWXmci3dT8UFYWYZweBLXgycu7Y3iL6trKn1Y7ARj", // stSOL
-        "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE",  // ORCA
-        "4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R", // RAY
-    ];
-
-    let manager = cache_manager.read().await;
-
-    // Preload frequently accessed entries from disk cache first
-    let warmed_from_disk = manager.populate_from_disk(64).await;
-    tracing::info!(
-        preloaded_entries = warmed_from_disk,
-        "cache warmup from disk"
-    );
-
-    // Warm cache with top tokens
-    let keys: Vec<String> = top_tokens
-        .iter()
-        .map(|addr| format!("token_price_{}", addr))
-        .collect();
-
-    let _ = manager
-        .warm_cache(keys, |key| async move {
-            // Mock data - in real implementation would fetch from API
-            let data = json!({
-                "price": 100.0,
-                "change24h": 5.0,
-                "volume": 1000000.0,
-            });
-            Ok((data, CacheType::TokenPrice))
-        })
-        .await;
-
-    Ok(())
-}
-
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     startup_log!("run() invoked");
@@ -269,11 +245,60 @@ pub fn run() {
     let builder = builder.manage(HardwareWalletState::new());
     startup_log!("Hardware wallet state registered");
 
+    let builder = builder.manage(WalletConnectState::new());
+    startup_log!("WalletConnect state registered");
+
     let builder = builder.manage(LedgerState::new());
     startup_log!("Ledger state registered");
 
     let builder = builder.setup(|app| {
             startup_log!("setup() closure entered");
+            // Capture sqlx statement logs before any pool opens
+            monitor::install_query_log();
+
+            // Resolve the active profile before anything touches the data dir
+            let base_data_dir = app
+                .path()
+                .app_data_dir()
+                .map_err(|e| Box::new(e) as Box<dyn Error>)?;
+            let mut profile_manager = profiles::ProfileManager::load(base_data_dir.clone())
+                .map_err(|e| {
+                    startup_error!("Failed to load profile registry: {}", e);
+                    Box::new(e) as Box<dyn Error>
+                })?;
+            let startup_args: Vec<String> = std::env::args().collect();
+            let active_profile = profile_manager
+                .activate_for_startup(&startup_args)
+                .map_err(|e| {
+                    startup_error!("Failed to activate profile: {}", e);
+                    Box::new(e) as Box<dyn Error>
+                })?;
+            startup_log!("Active profile: {}", active_profile);
+            let stored_demo_seed = profile_manager.demo_seed();
+            let profile_state: profiles::SharedProfileManager =
+                Arc::new(RwLock::new(profile_manager));
+            manage_state!(app, profile_state, "ProfileManager");
+
+            // The network environment picks the data dir inside the profile,
+            // so it must be pinned before anything touches it as well
+            let mut environment_manager = environment::EnvironmentManager::load(
+                profiles::profile_dir_for(&base_data_dir, &active_profile),
+            )
+            .map_err(|e| {
+                startup_error!("Failed to load network environment: {}", e);
+                Box::new(e) as Box<dyn Error>
+            })?;
+            let active_environment = environment_manager
+                .activate_for_startup(&startup_args)
+                .map_err(|e| {
+                    startup_error!("Failed to activate network environment: {}", e);
+                    Box::new(e) as Box<dyn Error>
+                })?;
+            startup_log!("Active network environment: {}", active_environment.as_str());
+            let environment_state: environment::SharedEnvironmentManager =
+                Arc::new(RwLock::new(environment_manager));
+            manage_state!(app, environment_state, "EnvironmentManager");
+
             if let Err(e) = hydrate_wallet_state(&app.handle()) {
                 startup_error!("Failed to hydrate wallet state: {}", e);
             }
@@ -287,7 +312,7 @@ pub fn run() {
 
             let app_data_dir = app
                 .path()
-                .app_data_dir()
+                .profile_data_dir()
                 .map_err(|e| Box::new(e) as Box<dyn Error>)?;
             std::fs::create_dir_all(&app_data_dir)
                 .map_err(|e| Box::new(e) as Box<dyn Error>)?;
@@ -299,6 +324,9 @@ pub fn run() {
             let audit_cache = AuditCache::new();
             manage_state!(app, audit_cache, "AuditCache");
 
+            let phishing_protection = security::phishing::PhishingProtection::new(&app.handle());
+            manage_state!(app, phishing_protection, "PhishingProtection");
+
             let session_manager = SessionManager::new();
             startup_log!("Session manager created");
             if let Err(e) = session_manager.hydrate(&keystore) {
@@ -315,6 +343,30 @@ pub fn run() {
                 startup_log!("2FA manager hydrated");
             }
 
+            let command_policy = CommandPolicyManager::new();
+            if let Err(e) = command_policy.hydrate(&keystore) {
+                startup_error!("Failed to hydrate command policy: {}", e);
+            } else {
+                startup_log!("Command policy hydrated");
+            }
+
+            let passkey_manager = PasskeyManager::new();
+            if let Err(e) = passkey_manager.hydrate(&keystore) {
+                startup_error!("Failed to hydrate passkey manager: {}", e);
+            } else {
+                startup_log!("Passkey manager hydrated");
+            }
+
+            let app_lock: SharedAppLock = Arc::new(AppLockManager::new());
+            if let Err(e) = app_lock.hydrate(&keystore) {
+                startup_error!("Failed to hydrate app lock: {}", e);
+            }
+            manage_state!(app, app_lock, "AppLock");
+            if let Err(e) = auth::app_lock::register_boss_key(&app.handle(), None) {
+                startup_error!("Failed to register boss key: {}", e);
+            }
+            auth::app_lock::start_sleep_watch(app.handle().clone());
+
             let ws_manager = core::websocket_manager::WebSocketManager::new(app.handle().clone());
             startup_log!("WebSocket manager created");
 
@@ -333,6 +385,12 @@ pub fn run() {
                 })?;
             startup_log!("Wallet operations manager initialized");
 
+            let fee_relayer_manager = FeeRelayerManager::initialize(&keystore).map_err(|e| {
+                startup_error!("Failed to initialize fee relayer manager: {}", e);
+                Box::new(e) as Box<dyn Error>
+            })?;
+            startup_log!("Fee relayer manager initialized");
+
             startup_log!("Initializing activity logger");
             let activity_logger =
                 tauri::async_runtime::block_on(async { ActivityLogger::new(&app.handle()).await })
@@ -342,8 +400,6 @@ pub fn run() {
                     })?;
             startup_log!("Activity logger initialized");
 
-            let cleanup_logger = activity_logger.clone();
-
             // Initialize reputation engine
             startup_log!("Initializing reputation engine");
             let reputation_engine = tauri::async_runtime::block_on(async {
@@ -370,6 +426,21 @@ pub fn run() {
             startup_log!("P2P system initialized");
             manage_state!(app, p2p_db.clone(), "P2PDatabase");
 
+            let otc_desk: p2p::otc::SharedOtcDesk =
+                Arc::new(p2p::otc::OtcDesk::new(&app.handle()));
+            manage_state!(app, otc_desk, "OtcDesk");
+
+            let rate_aggregator: SharedRateAggregator =
+                Arc::new(RateAggregator::new(&app.handle()));
+            start_rate_tracking(rate_aggregator.clone());
+            manage_state!(app, rate_aggregator, "RateAggregator");
+
+            let strategy_book: SharedStrategyBook = Arc::new(StrategyBook::new(&app.handle()));
+            manage_state!(app, strategy_book, "StrategyBook");
+
+            let research_store: SharedResearchStore = Arc::new(ResearchStore::load(&app.handle()));
+            manage_state!(app, research_store, "ResearchStore");
+
             // Initialize academy engine
             startup_log!("Initializing academy engine");
             let academy_engine = tauri::async_runtime::block_on(async {
@@ -388,6 +459,10 @@ pub fn run() {
                 Arc::new(RwLock::new(academy_engine));
             manage_state!(app, shared_academy_engine.clone(), "SharedAcademyEngine");
 
+            let feature_gate: academy::SharedFeatureGate =
+                Arc::new(RwLock::new(academy::FeatureGate::new(&app.handle())));
+            manage_state!(app, feature_gate, "SharedFeatureGate");
+
             // Initialize API config manager
             let api_config_manager = api_config::ApiConfigManager::new();
             startup_log!("API config manager created");
@@ -413,20 +488,65 @@ pub fn run() {
 
             manage_state!(app, multi_wallet_manager, "MultiWalletManager");
             manage_state!(app, wallet_operations_manager, "WalletOperationsManager");
+            manage_state!(app, fee_relayer_manager, "FeeRelayerManager");
             manage_state!(app, session_manager, "SessionManager");
             manage_state!(app, two_factor_manager, "TwoFactorManager");
+            manage_state!(app, command_policy, "CommandPolicyManager");
+            manage_state!(app, passkey_manager, "PasskeyManager");
             manage_state!(app, ws_manager, "WebSocketManager");
             manage_state!(app, activity_logger, "ActivityLogger");
             manage_state!(app, api_config_manager, "ApiConfigManager");
             manage_state!(app, api_health_state.clone(), "ApiHealthMonitor");
 
+            let request_registry: api::SharedRequestRegistry =
+                Arc::new(api::RequestRegistry::new());
+            manage_state!(app, request_registry, "RequestRegistry");
+
             startup_log!("Creating chain manager");
             let chain_manager: SharedChainManager = Arc::new(RwLock::new(ChainManager::new()));
             manage_state!(app, chain_manager.clone(), "ChainManager");
 
+            startup_log!("Creating RPC pool");
+            let mut rpc_pool = RpcPool::new(&app.handle());
+            rpc_pool.sync_helius(api_config::stored_helius_key(&keystore).as_deref());
+            let rpc_pool_state: SharedRpcPool = Arc::new(RwLock::new(rpc_pool));
+            manage_state!(app, rpc_pool_state.clone(), "RpcPool");
+
+            // EVM chains get their own endpoint pools, seeded from the chain configs
+            let chain_configs = tauri::async_runtime::block_on(chain_manager.read()).list_chains();
+            let rpc_pools: api::SharedRpcPoolManager = Arc::new(RwLock::new(
+                api::RpcPoolManager::new(&app.handle(), &chain_configs),
+            ));
+            tauri::async_runtime::block_on(chain_manager.write()).set_rpc_pools(rpc_pools.clone());
+            manage_state!(app, rpc_pools.clone(), "RpcPoolManager");
+
+            let reconciler: chains::SharedCrossChainReconciler = Arc::new(RwLock::new(
+                chains::CrossChainReconciler::new(&app.handle()),
+            ));
+            manage_state!(app, reconciler.clone(), "CrossChainReconciler");
+            chains::start_cross_chain_reconciliation(app.handle().clone(), reconciler);
+
+            let limit_orders: api::SharedLimitOrderTracker =
+                Arc::new(RwLock::new(api::LimitOrderTracker::new(&app.handle())));
+            manage_state!(app, limit_orders.clone(), "LimitOrderTracker");
+            api::start_limit_order_reconciliation(limit_orders, rpc_pool_state.clone());
+
+            let rpc_pool_probe = rpc_pool_state.clone();
+            let rpc_health = api_health_state.clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    api::probe_rpc_pools(&rpc_pool_probe, &rpc_pools, &rpc_health).await;
+                    let interval = rpc_pool_probe.read().await.settings().probe_interval_secs;
+                    tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+                }
+            });
+
             startup_log!("Creating bridge manager");
             let bridge_manager: SharedBridgeManager = Arc::new(RwLock::new(BridgeManager::new()));
             manage_state!(app, bridge_manager.clone(), "BridgeManager");
+            let bridge_registry: SharedBridgeRegistry =
+                Arc::new(RwLock::new(BridgeRegistry::load(&app.handle())));
+            manage_state!(app, bridge_registry, "BridgeRegistry");
 
             startup_log!("Initializing API usage tracker");
             let usage_tracker =
@@ -448,7 +568,9 @@ pub fn run() {
             manage_state!(app, settings_state.clone(), "SettingsManager");
 
             // Initialize launchpad state
-            let rpc_url = "https://api.mainnet-beta.solana.com".to_string();
+            let rpc_url = tauri::async_runtime::block_on(rpc_pool_state.read())
+                .select(RoutingHint::Read)
+                .unwrap_or_else(|| environment::active_environment().solana_rpc_url());
             startup_log!("Creating launchpad state");
             let launchpad_state = launchpad::commands::create_launchpad_state(rpc_url);
             manage_state!(app, launchpad_state, "LaunchpadState");
@@ -456,30 +578,24 @@ pub fn run() {
             // Initialize collaborative rooms state
             startup_log!("Initializing collaborative rooms state");
             let collab_websocket = collab::websocket::CollabWebSocketManager::new(app.handle().clone());
-            let collab_state = CollabState::new(collab_websocket);
+            let collab_identity = collab::crypto::IdentityKeyPair::load_or_create(&keystore)
+                .unwrap_or_else(|e| {
+                    startup_error!("{}; collab room keys will not survive a restart", e);
+                    collab::crypto::IdentityKeyPair::generate()
+                });
+            let collab_state = CollabState::new(collab_websocket, collab_identity);
             manage_state!(app, collab_state, "CollabState");
 
-            startup_log!("Spawning activity log cleanup task");
-            tauri::async_runtime::spawn(async move {
-                use tokio::time::{sleep, Duration};
-
-                if let Err(err) = cleanup_logger.cleanup_old_logs(None).await {
-                    startup_error!("Failed to run initial activity log cleanup: {}", err);
-                }
-
-                loop {
-                    sleep(Duration::from_secs(24 * 60 * 60)).await;
-                    if let Err(err) = cleanup_logger.cleanup_old_logs(None).await {
-                        startup_error!("Failed to run scheduled activity log cleanup: {}", err);
-                    }
-                }
-            });
-
             startup_log!("Registering trading states");
             trading::register_trading_state(&app.handle());
             trading::register_paper_trading_state(&app.handle());
             trading::register_auto_trading_state(&app);
             trading::register_optimizer_state(&app);
+            trading::register_experiment_state(&app);
+            trading::register_mev_state(&app);
+            trading::register_token_policy_state(&app);
+            trading::register_alert_automation_state(&app);
+            trading::register_session_hud_state(&app);
             startup_log!("Trading states registered");
 
             // Initialize safety engine
@@ -489,10 +605,19 @@ pub fn run() {
             let safety_state: trading::SharedSafetyEngine = Arc::new(RwLock::new(safety_engine));
             manage_state!(app, safety_state.clone(), "SafetyEngine");
 
+            // Initialize Token-2022 extension service
+            let token_extension_state: SharedTokenExtensionService =
+                Arc::new(RwLock::new(TokenExtensionService::new(rpc_pool_state.clone())));
+            manage_state!(app, token_extension_state, "TokenExtensionService");
+
             // Initialize contract risk service
             startup_log!("Initializing contract risk service");
             let contract_risk_service = tauri::async_runtime::block_on(async {
-                trading::contract_risk::ContractVerificationService::new(&app.handle()).await
+                trading::contract_risk::ContractVerificationService::new(
+                    &app.handle(),
+                    rpc_pool_state.clone(),
+                )
+                .await
             })
             .map_err(|e| {
                 startup_error!("Failed to initialize contract risk service: {}", e);
@@ -516,7 +641,7 @@ pub fn run() {
             // Initialize multisig database
             let mut multisig_db_path = app
                 .path()
-                .app_data_dir()
+                .profile_data_dir()
                 .map_err(|e| Box::new(e) as Box<dyn Error>)?;
 
             std::fs::create_dir_all(&multisig_db_path)
@@ -540,10 +665,28 @@ pub fn run() {
             let multisig_state: SharedMultisigDatabase = Arc::new(RwLock::new(multisig_db));
             manage_state!(app, multisig_state.clone(), "MultisigDatabase");
 
+            let tx_builder_state: SharedTransactionBatchBuilder = Arc::new(RwLock::new(
+                wallet::tx_builder::TransactionBatchBuilder::new(
+                    &app.handle(),
+                    rpc_pool_state.clone(),
+                ),
+            ));
+            manage_state!(app, tx_builder_state, "TransactionBatchBuilder");
+
+            let tx_lifecycle_state: SharedTransactionLifecycle =
+                Arc::new(wallet::tx_lifecycle::TransactionLifecycleService::new(
+                    rpc_pool_state.clone(),
+                ));
+            manage_state!(app, tx_lifecycle_state, "TransactionLifecycleService");
+
+            let display_name_resolver: SharedDisplayNameResolver =
+                Arc::new(wallet::display_names::DisplayNameResolver::new());
+            manage_state!(app, display_name_resolver, "DisplayNameResolver");
+
             // Initialize performance database
             let mut performance_db_path = app
                 .path()
-                .app_data_dir()
+                .profile_data_dir()
                 .map_err(|e| Box::new(e) as Box<dyn Error>)?;
 
             std::fs::create_dir_all(&performance_db_path)
@@ -564,10 +707,20 @@ pub fn run() {
                 Arc::new(RwLock::new(performance_db));
             manage_state!(app, performance_state.clone(), "PerformanceDatabase");
 
+            let backfill_state: SharedWalletBackfill = Arc::new(
+                wallet::history_backfill::WalletBackfillService::new(&app.handle()),
+            );
+            manage_state!(app, backfill_state, "WalletBackfillService");
+
+            let flow_ledger: SharedFlowLedger = Arc::new(parking_lot::RwLock::new(
+                wallet::flows::FlowLedger::new(&app.handle()),
+            ));
+            manage_state!(app, flow_ledger, "FlowLedger");
+
             // Initialize journal database
             let mut journal_db_path = app
                 .path()
-                .app_data_dir()
+                .profile_data_dir()
                 .map_err(|e| Box::new(e) as Box<dyn Error>)?;
 
             journal_db_path.push("journal.db");
@@ -607,6 +760,9 @@ pub fn run() {
                 if let Err(err) = bots::init_dca(&automation_handle).await {
                     startup_error!("Failed to initialize DCA bots: {}", err);
                 }
+                if let Err(err) = bots::init_grid(&automation_handle).await {
+                    startup_error!("Failed to initialize grid bots: {}", err);
+                }
                 if let Err(err) = trading::init_copy_trading(&automation_handle).await {
                     startup_error!("Failed to initialize copy trading: {}", err);
                 }
@@ -628,8 +784,46 @@ pub fn run() {
                 "RebalancerState"
             );
             manage_state!(app, std::sync::Mutex::new(tax_lots_state), "TaxLotsState");
+            let optimization_store: ai_chat::SharedOptimizationStore =
+                Arc::new(RwLock::new(ai_chat::OptimizationStore::default()));
+            manage_state!(app, optimization_store, "OptimizationStore");
             manage_state!(app, tax_engine.clone(), "TaxEngine");
 
+            let compressed_nft_ledger: portfolio::SharedCompressedNftLedger = Arc::new(
+                RwLock::new(portfolio::CompressedNftLedger::new(&app.handle())),
+            );
+            manage_state!(app, compressed_nft_ledger, "CompressedNftLedger");
+            let nft_portfolio: portfolio::SharedNftPortfolio =
+                Arc::new(RwLock::new(portfolio::NftPortfolio::new(&app.handle())));
+            manage_state!(app, nft_portfolio, "NftPortfolio");
+            let dust_consolidator: portfolio::SharedDustConsolidator =
+                Arc::new(RwLock::new(portfolio::DustConsolidator::default()));
+            manage_state!(app, dust_consolidator, "DustConsolidator");
+            let flatten_coordinator: portfolio::SharedFlattenCoordinator =
+                Arc::new(RwLock::new(portfolio::FlattenCoordinator::default()));
+            manage_state!(app, flatten_coordinator, "FlattenCoordinator");
+            let derivatives_tracker: defi::SharedDerivativesTracker =
+                Arc::new(RwLock::new(defi::DerivativesTracker::new(&app.handle())));
+            manage_state!(app, derivatives_tracker, "DerivativesTracker");
+            let impermanent_loss: defi::SharedImpermanentLossTracker = Arc::new(RwLock::new(
+                defi::ImpermanentLossTracker::new(&app.handle()),
+            ));
+            manage_state!(app, impermanent_loss.clone(), "ImpermanentLossTracker");
+            defi::start_impermanent_loss_sampling(impermanent_loss);
+
+            startup_log!("Initializing staking reward claim scheduler");
+            let reward_claims = tauri::async_runtime::block_on(async {
+                defi::RewardClaimScheduler::new(&app.handle()).await
+            })
+            .map_err(|e| {
+                startup_error!("Failed to initialize staking reward claims: {}", e);
+                Box::new(std::io::Error::new(std::io::ErrorKind::Other, e)) as Box<dyn Error>
+            })?;
+            let reward_claims: defi::SharedRewardClaimScheduler =
+                Arc::new(RwLock::new(reward_claims));
+            manage_state!(app, reward_claims.clone(), "RewardClaimScheduler");
+            defi::start_reward_claim_scheduler(app.handle().clone(), reward_claims);
+
             // Initialize new coins scanner
             startup_log!("Initializing new coins scanner");
             let new_coins_scanner = tauri::async_runtime::block_on(async {
@@ -645,6 +839,17 @@ pub fn run() {
                 Arc::new(RwLock::new(new_coins_scanner));
             manage_state!(app, scanner_state.clone(), "NewCoinsScanner");
 
+            let coin_triage = tauri::async_runtime::block_on(async {
+                let pool = scanner_state.read().await.pool().clone();
+                market::NewCoinTriage::new(pool).await
+            })
+            .map_err(|e| {
+                startup_error!("Failed to initialize new coins triage: {}", e);
+                Box::new(e) as Box<dyn Error>
+            })?;
+            let coin_triage_state: market::SharedNewCoinTriage = Arc::new(coin_triage);
+            manage_state!(app, coin_triage_state, "NewCoinTriage");
+
             // Start background scanning task
             let scanner_for_loop = scanner_state.clone();
             market::start_new_coins_scanner(scanner_for_loop);
@@ -684,6 +889,35 @@ pub fn run() {
             let alert_state: SharedAlertManager = Arc::new(RwLock::new(alert_manager));
             manage_state!(app, alert_state.clone(), "AlertManager");
 
+            startup_log!("Initializing funding rate monitor");
+            let funding_monitor = tauri::async_runtime::block_on(async {
+                market::FundingRateMonitor::new(&app.handle()).await
+            })
+            .map_err(|e| {
+                startup_error!("Failed to initialize funding rate monitor: {}", e);
+                Box::new(e) as Box<dyn Error>
+            })?;
+            let funding_state: market::SharedFundingRateMonitor =
+                Arc::new(RwLock::new(funding_monitor));
+            manage_state!(app, funding_state.clone(), "FundingRateMonitor");
+            market::start_funding_rate_monitor(
+                app.handle().clone(),
+                funding_state,
+                alert_state.clone(),
+            );
+
+            let unlock_state: market::SharedTokenUnlockTracker = Arc::new(RwLock::new(
+                market::TokenUnlockTracker::new(&app.handle()),
+            ));
+            manage_state!(app, unlock_state.clone(), "TokenUnlockTracker");
+            market::start_token_unlock_monitor(app.handle().clone(), unlock_state);
+
+            let index_state: market::SharedSyntheticIndexTracker = Arc::new(RwLock::new(
+                market::SyntheticIndexTracker::new(&app.handle()),
+            ));
+            manage_state!(app, index_state.clone(), "SyntheticIndexTracker");
+            market::start_synthetic_index_tracker(app.handle().clone(), index_state);
+
             startup_log!("Initializing smart alert manager");
             let smart_alert_manager = tauri::async_runtime::block_on(async {
                 SmartAlertManager::new(&app.handle()).await
@@ -727,10 +961,22 @@ pub fn run() {
                 Arc::new(RwLock::new(notification_router));
             manage_state!(app, notification_state.clone(), "NotificationRouter");
 
+            let dead_man_state: security::dead_man_switch::SharedDeadManSwitch = Arc::new(
+                RwLock::new(security::dead_man_switch::DeadManSwitch::new(&app.handle())),
+            );
+            manage_state!(app, dead_man_state.clone(), "DeadManSwitch");
+            security::dead_man_switch::start_dead_man_switch(app.handle().clone(), dead_man_state);
+
+            academy::start_mentor_reminders(
+                app.handle().clone(),
+                shared_academy_engine.clone(),
+                notification_state.clone(),
+            );
+
             // Initialize indicator manager
             let app_data_dir = app
                 .path()
-                .app_data_dir()
+                .profile_data_dir()
                 .map_err(|e| Box::new(e) as Box<dyn Error>)?;
 
             startup_log!("Initializing indicator manager");
@@ -746,17 +992,37 @@ pub fn run() {
 
             // Initialize webhook manager
             startup_log!("Initializing webhook manager");
-            let webhook_manager =
-                tauri::async_runtime::block_on(async { WebhookManager::new(&app.handle()).await })
-                    .map_err(|e| {
-                        startup_error!("Failed to initialize webhook manager: {}", e);
-                        Box::new(e) as Box<dyn Error>
-                    })?;
+            let webhook_manager = tauri::async_runtime::block_on(async {
+                WebhookManager::new(&app.handle(), &keystore).await
+            })
+            .map_err(|e| {
+                startup_error!("Failed to initialize webhook manager: {}", e);
+                Box::new(e) as Box<dyn Error>
+            })?;
             startup_log!("Webhook manager initialized");
 
             let webhook_state: SharedWebhookManager = Arc::new(RwLock::new(webhook_manager));
             manage_state!(app, webhook_state.clone(), "WebhookManager");
 
+            let inbound_webhook_state: SharedInboundWebhookServer =
+                Arc::new(RwLock::new(InboundWebhookServer::new()));
+            manage_state!(app, inbound_webhook_state, "InboundWebhookServer");
+
+            let lan_dashboard = LanDashboardServer::new(&app.handle());
+            let lan_dashboard_auto_start = lan_dashboard.settings().auto_start;
+            let lan_dashboard_state: SharedLanDashboardServer =
+                Arc::new(RwLock::new(lan_dashboard));
+            if lan_dashboard_auto_start {
+                let dashboard = lan_dashboard_state.clone();
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = dashboard.write().await.start(&handle).await {
+                        eprintln!("Failed to start LAN dashboard server: {}", e);
+                    }
+                });
+            }
+            manage_state!(app, lan_dashboard_state, "LanDashboardServer");
+
             // Initialize cache manager
             startup_log!("Initializing cache manager");
             let cache_manager = core::cache_manager::CacheManager::new(100, 1000);
@@ -764,14 +1030,15 @@ pub fn run() {
             manage_state!(app, shared_cache_manager.clone(), "CacheManager");
 
             // Start background cache warming
-            let app_handle = app.handle().clone();
-            let cache_manager_handle = shared_cache_manager.clone();
-            startup_log!("Spawning cache warmup task");
-            tauri::async_runtime::spawn(async move {
-                if let Err(err) = warm_cache_on_startup(app_handle.clone(), cache_manager_handle).await {
-                    startup_error!("Failed to warm cache on startup: {}", err);
-                }
-            });
+            let cache_warmup: core::cache_warmup::SharedCacheWarmupService =
+                Arc::new(core::cache_warmup::CacheWarmupService::new());
+            manage_state!(app, cache_warmup.clone(), "CacheWarmupService");
+            startup_log!("Spawning cache warmup scheduler");
+            core::cache_warmup::start_cache_warmup_scheduler(
+                app.handle().clone(),
+                shared_cache_manager.clone(),
+                cache_warmup,
+            );
 
             // Initialize sentiment manager
             startup_log!("Initializing sentiment manager");
@@ -799,7 +1066,7 @@ pub fn run() {
             // Initialize social analysis service
             let mut social_data_dir = app
                 .path()
-                .app_data_dir()
+                .profile_data_dir()
                 .map_err(|e| Box::new(e) as Box<dyn Error>)?;
             social_data_dir.push("social");
             std::fs::create_dir_all(&social_data_dir)
@@ -842,10 +1109,29 @@ pub fn run() {
                 Arc::new(RwLock::new(anomaly_detector));
             manage_state!(app, anomaly_state.clone(), "AnomalyDetector");
 
+            // Retention policies prune the activity log, API health history,
+            // delivery logs, social posts and anomalies on one schedule
+            if let Ok(profile_dir) = app.path().profile_data_dir() {
+                startup_log!("Initializing retention service");
+                let retention: data::SharedRetentionService =
+                    Arc::new(data::RetentionService::new(profile_dir));
+                if let Some(logger) = app.try_state::<ActivityLogger>() {
+                    if let Ok(days) = logger.current_retention_days() {
+                        if let Err(e) = retention
+                            .set_max_age_days(data::ACTIVITY_LOG_SUBSYSTEM, days as u32)
+                        {
+                            startup_error!("Failed to sync activity log retention: {}", e);
+                        }
+                    }
+                }
+                manage_state!(app, retention.clone(), "RetentionService");
+                data::start_retention_pruning(app.handle().clone(), retention);
+            }
+
             // Initialize event store
             let mut event_store_path = app
                 .path()
-                .app_data_dir()
+                .profile_data_dir()
                 .map_err(|e| Box::new(e) as Box<dyn Error>)?;
 
             event_store_path.push("events.db");
@@ -861,10 +1147,14 @@ pub fn run() {
             let shared_event_store: SharedEventStore = Arc::new(RwLock::new(event_store));
             manage_state!(app, shared_event_store.clone(), "EventStore");
 
+            let event_subscriptions: data::event_stream::SharedEventSubscriptions =
+                Arc::new(data::event_stream::EventSubscriptions::new());
+            manage_state!(app, event_subscriptions, "EventSubscriptions");
+
             // Initialize compression manager
             let mut compression_db_path = app
                 .path()
-                .app_data_dir()
+                .profile_data_dir()
                 .map_err(|e| Box::new(e) as Box<dyn Error>)?;
 
             compression_db_path.push("events.db");
@@ -915,6 +1205,10 @@ pub fn run() {
             let shared_risk_analyzer: ai_legacy::SharedRiskAnalyzer = Arc::new(RwLock::new(risk_analyzer));
             manage_state!(app, shared_risk_analyzer.clone(), "RiskAnalyzer");
 
+            let portfolio_risk_scorer: ai_legacy::SharedPortfolioRiskScorer =
+                Arc::new(ai_legacy::PortfolioRiskScorer::new());
+            manage_state!(app, portfolio_risk_scorer, "PortfolioRiskScorer");
+
             // Initialize AI portfolio advisor
             startup_log!("Initializing AI portfolio advisor");
             let ai_advisor = tauri::async_runtime::block_on(async {
@@ -1101,10 +1395,15 @@ pub fn run() {
 
             // Initialize prediction market service
             startup_log!("Initializing prediction market service");
-            let prediction_service = market::PredictionMarketService::new();
+            let mut prediction_service = market::PredictionMarketService::new();
+            if let Ok(profile_dir) = app.path().profile_data_dir() {
+                prediction_service = prediction_service
+                    .with_positions_file(profile_dir.join("prediction_positions.json"));
+            }
             let shared_prediction_service: market::SharedPredictionMarketService =
                 Arc::new(RwLock::new(prediction_service));
             manage_state!(app, shared_prediction_service.clone(), "PredictionMarketService");
+            market::start_prediction_resolver(app.handle().clone());
 
             // Initialize diagnostics engine
             startup_log!("Initializing diagnostics engine");
@@ -1118,6 +1417,17 @@ pub fn run() {
             startup_log!("Diagnostics engine initialized");
             manage_state!(app, diagnostics_engine.clone(), "DiagnosticsEngine");
 
+            if let Ok(profile_dir) = app.path().profile_data_dir() {
+                let db_maintenance: diagnostics::db_maintenance::SharedDbMaintenance = Arc::new(
+                    diagnostics::db_maintenance::DbMaintenanceService::new(profile_dir),
+                );
+                manage_state!(app, db_maintenance.clone(), "DbMaintenance");
+                diagnostics::db_maintenance::start_db_maintenance(
+                    app.handle().clone(),
+                    db_maintenance,
+                );
+            }
+
             let diagnostics_state = diagnostics_engine.clone();
             startup_log!("Spawning diagnostics maintenance task");
             tauri::async_runtime::spawn(async move {
@@ -1152,8 +1462,10 @@ pub fn run() {
 
             let runtime_handler = errors::RuntimeHandler::new(shared_logger.clone());
             let shared_runtime_handler: errors::SharedRuntimeHandler = Arc::new(runtime_handler);
+            errors::install_command_error_handler(shared_runtime_handler.clone());
             manage_state!(app, shared_runtime_handler.clone(), "RuntimeHandler");
 
+            monitor::install_profiler();
             let performance_monitor = monitor::PerformanceMonitor::new();
             let shared_performance_monitor: monitor::SharedPerformanceMonitor =
                 Arc::new(performance_monitor);
@@ -1178,7 +1490,7 @@ pub fn run() {
             // Initialize mobile managers
             let mut mobile_data_dir = app
                 .path()
-                .app_data_dir()
+                .profile_data_dir()
                 .map_err(|e| Box::new(e) as Box<dyn Error>)?;
             mobile_data_dir.push("mobile");
             std::fs::create_dir_all(&mobile_data_dir)
@@ -1208,15 +1520,23 @@ pub fn run() {
             manage_state!(app, mobile_sync_state.clone(), "MobileSyncManager");
 
             startup_log!("Initializing mobile trade engine");
-            let mobile_trade_engine = MobileTradeEngine::new();
+            let mut mobile_trade_engine = MobileTradeEngine::new(mobile_data_dir.clone());
+            if let Err(e) = tauri::async_runtime::block_on(mobile_trade_engine.load()) {
+                startup_error!("Failed to load mobile trade limits: {}", e);
+            }
             let mobile_trade_state: Arc<RwLock<MobileTradeEngine>> =
                 Arc::new(RwLock::new(mobile_trade_engine));
             manage_state!(app, mobile_trade_state.clone(), "MobileTradeEngine");
 
+            let mobile_remote_control: mobile::SharedMobileRemoteControl =
+                Arc::new(RwLock::new(mobile::MobileRemoteControl::new()));
+            manage_state!(app, mobile_remote_control, "MobileRemoteControl");
+
             startup_log!("Initializing widget manager");
             let widget_manager = WidgetManager::new();
             let widget_state: Arc<RwLock<WidgetManager>> = Arc::new(RwLock::new(widget_manager));
             manage_state!(app, widget_state.clone(), "WidgetManager");
+            mobile::start_widget_refresher(app.handle().clone(), widget_state.clone());
 
             // Initialize governance manager
             startup_log!("Initializing governance manager");
@@ -1228,7 +1548,7 @@ pub fn run() {
             // Initialize feature flags database
             let mut features_db_path = app
                 .path()
-                .app_data_dir()
+                .profile_data_dir()
                 .map_err(|e| Box::new(e) as Box<dyn Error>)?;
 
             features_db_path.push("features.db");
@@ -1257,6 +1577,14 @@ pub fn run() {
             let feature_flags = features::FeatureFlags::new(features_pool);
             manage_state!(app, feature_flags, "FeatureFlags");
 
+            let demo_state: demo::SharedDemoModeState =
+                Arc::new(RwLock::new(demo::DemoModeState {
+                    seed: stored_demo_seed.unwrap_or_default(),
+                    overlay: None,
+                }));
+            manage_state!(app, demo_state, "DemoModeState");
+            demo::restore_demo_mode(app.handle().clone());
+
             startup_log!("setup() closure completed successfully");
 
             Ok(())
@@ -1276,7 +1604,13 @@ pub fn run() {
             disconnect_hardware_wallet,
             get_hardware_wallet_address,
             sign_with_hardware_wallet,
+            sign_message_with_hardware_wallet,
+            set_hardware_wallet_simulation,
             get_firmware_version,
+            wc_pair,
+            wc_list_sessions,
+            wc_sign_transaction,
+            wc_disconnect,
             ledger_register_device,
             ledger_list_devices,
             ledger_get_device,
@@ -1305,6 +1639,10 @@ pub fn run() {
             wallet_get_token_balances,
             wallet_estimate_fee,
             wallet_send_transaction,
+            wallet_build_relayed_transfer,
+            fee_relayer_get_status,
+            fee_relayer_update_config,
+            fee_relayer_health_check,
             wallet_generate_qr,
             wallet_generate_solana_pay_qr,
             address_book_add_contact,
@@ -1314,6 +1652,9 @@ pub fn run() {
             address_book_search_contacts,
             address_book_export,
             address_book_import,
+            address_book_set_chain_address,
+            address_book_remove_chain_address,
+            address_book_resolve_address,
             swap_history_add_entry,
             swap_history_get_recent,
             wallet_get_bridge_providers,
@@ -1327,15 +1668,43 @@ pub fn run() {
             get_best_worst_trades_data,
             get_benchmark_comparison_data,
             get_performance_alerts,
+            // Wallet History Backfill
+            wallet_backfill_start,
+            wallet_backfill_status,
+            wallet_backfill_list,
+            wallet_backfill_cancel,
+            // Wallet Flows
+            wallet_flows_list,
+            wallet_flows_summary,
+            wallet_flow_retag,
+            wallet_flows_adjusted_pnl,
+            // Display Names
+            resolve_display_names,
+            invalidate_display_names,
+            // Transaction Receipts
+            generate_transaction_receipt,
+            verify_transaction_receipt,
             // Multisig
             create_multisig_wallet,
             list_multisig_wallets,
             get_multisig_wallet,
             create_proposal,
+            create_batch_proposal,
             list_proposals,
             sign_proposal,
             execute_proposal,
             cancel_proposal,
+            // Transaction Builder
+            tx_builder_build_batch,
+            tx_builder_plan_lookup_table,
+            tx_builder_register_lookup_table,
+            tx_builder_list_lookup_tables,
+            // Transaction Lifecycle
+            tx_lifecycle_submit,
+            tx_lifecycle_provide_signature,
+            tx_lifecycle_get,
+            tx_lifecycle_list,
+            tx_lifecycle_recent_blockhash,
             // Auth
             biometric_get_status,
             biometric_enroll,
@@ -1343,22 +1712,41 @@ pub fn run() {
             biometric_disable,
             biometric_verify_fallback,
             connect_phantom,
+            // App Lock
+            auth::app_lock::app_lock_status,
+            auth::app_lock::app_lock_configure,
+            auth::app_lock::app_lock_lock,
+            auth::app_lock::app_lock_unlock,
+            auth::app_lock::app_lock_report_os_event,
+            auth::passkey::passkey_registration_options,
+            auth::passkey::passkey_register,
+            auth::passkey::passkey_authentication_options,
+            auth::passkey::passkey_authenticate,
+            auth::passkey::passkey_list,
+            auth::passkey::passkey_rename,
+            auth::passkey::passkey_remove,
+            auth::passkey::passkey_status,
+            auth::passkey::passkey_set_gated_categories,
             // Session Management
-            // TODO: Re-enable when session commands are implemented
-            // session_create,
-            // session_renew,
-            // session_end,
-            // session_status,
-            // session_verify,
-            // session_update_activity,
-            // session_configure_timeout,
+            auth::session_manager::session_create,
+            auth::session_manager::session_renew,
+            auth::session_manager::session_end,
+            auth::session_manager::session_status,
+            auth::session_manager::session_verify,
+            auth::session_manager::session_update_activity,
+            auth::session_manager::session_configure_timeout,
+            auth::session_manager::session_get_config,
             // 2FA
-            // TODO: Re-enable when 2FA commands are implemented
-            // two_factor_enroll,
-            // two_factor_verify,
-            // two_factor_disable,
-            // two_factor_status,
-            // two_factor_regenerate_backup_codes,
+            auth::two_factor::two_factor_enroll,
+            auth::two_factor::two_factor_confirm_enrollment,
+            auth::two_factor::two_factor_verify,
+            auth::two_factor::two_factor_disable,
+            auth::two_factor::two_factor_status,
+            auth::two_factor::two_factor_regenerate_backup_codes,
+            auth::two_factor::two_factor_set_drift_window,
+            auth::two_factor::two_factor_set_large_transfer_threshold,
+            auth::command_policy::command_policy_list,
+            auth::command_policy::command_policy_set,
             // API Config
             save_api_key,
             remove_api_key,
@@ -1367,6 +1755,7 @@ pub fn run() {
             get_api_status,
             rotate_api_key,
             check_rotation_reminders,
+            check_api_key_scopes,
             export_api_keys,
             import_api_keys,
             // API Analytics
@@ -1387,6 +1776,8 @@ pub fn run() {
             get_token_risk_score,
             get_risk_history,
             get_latest_risk_score,
+            score_portfolio_risk,
+            get_portfolio_risk_digest,
             // Social Data
             // TODO: Re-enable when social commands are implemented
             // social_fetch_reddit,
@@ -1407,6 +1798,7 @@ pub fn run() {
             // social_get_token_trends,
             // social_get_influencer_scores,
             // social_get_fomo_fud,
+            social::commands::social_sentiment_price_correlation,
             // Launch Predictor
             extract_token_features,
             predict_launch_success,
@@ -1433,6 +1825,12 @@ pub fn run() {
             get_new_coins,
             get_coin_safety_report,
             scan_for_new_coins,
+            triage_list_queue,
+            triage_set_verdict,
+            triage_reopen,
+            triage_get_automations,
+            triage_set_automations,
+            triage_get_stats,
             // Top Coins
             get_top_coins,
             refresh_top_coins,
@@ -1451,6 +1849,7 @@ pub fn run() {
             set_tax_lot_strategy,
             get_tax_lot_strategy,
             dispose_tax_lot,
+            tax_import_csv,
             generate_tax_report,
             export_tax_report,
             get_tax_loss_harvesting_suggestions,
@@ -1460,7 +1859,20 @@ pub fn run() {
             calculate_portfolio_analytics,
             get_concentration_alerts,
             get_sector_allocation,
+            get_performance_attribution,
+            simulate_portfolio_paths,
             clear_portfolio_cache,
+            portfolio_get_compressed_nfts,
+            nft_list_holdings,
+            nft_get_collection_stats,
+            nft_set_include_in_totals,
+            dust_scan,
+            dust_get_plan,
+            dust_prepare,
+            dust_execute,
+            flatten_all_positions,
+            flatten_all_get_plan,
+            flatten_all_execute,
             watchlist_create,
             watchlist_list,
             watchlist_get,
@@ -1497,6 +1909,20 @@ pub fn run() {
             smart_alert_get_rule,
             smart_alert_dry_run,
             smart_alert_execute,
+            // Alert-to-Order Automation
+            alert_automation_list,
+            alert_automation_create,
+            alert_automation_update,
+            alert_automation_set_armed,
+            alert_automation_delete,
+            alert_automation_executions,
+            // Session HUD
+            session_hud_get,
+            session_hud_reset,
+            session_hud_set_risk_budget,
+            alert_bundle_export,
+            alert_bundle_inspect,
+            alert_bundle_import,
             // Chat Integrations
             chat_integration_get_settings,
             chat_integration_save_settings,
@@ -1524,10 +1950,33 @@ pub fn run() {
             trigger_webhook,
             test_webhook,
             list_webhook_delivery_logs,
+            list_inbound_webhooks,
+            create_inbound_webhook,
+            update_inbound_webhook,
+            delete_inbound_webhook,
+            rotate_inbound_webhook_secret,
+            start_inbound_webhook_server,
+            stop_inbound_webhook_server,
+            get_inbound_webhook_server_status,
+            list_inbound_webhook_events,
+            // LAN Dashboard Stream
+            lan_dashboard_get_settings,
+            lan_dashboard_update_settings,
+            lan_dashboard_rotate_token,
+            lan_dashboard_start,
+            lan_dashboard_stop,
+            lan_dashboard_status,
+            streamer_mode_get,
+            streamer_mode_update,
+            streamer_mode_sanitize,
             // API Health
             get_api_health_dashboard,
             get_service_health_metrics,
             cleanup_health_records,
+            // Request Cancellation
+            cancel_request,
+            cancel_all_requests,
+            list_inflight_requests,
             // WebSocket Streams
             subscribe_price_stream,
             unsubscribe_price_stream,
@@ -1539,9 +1988,16 @@ pub fn run() {
             subscribe_chart_prices,
             unsubscribe_chart_prices,
             get_chart_subscriptions,
+            subscribe_orderbook_stream,
+            unsubscribe_orderbook_stream,
+            get_orderbook_snapshot,
             // Jupiter v6 & execution safeguards
             jupiter_quote,
             jupiter_swap,
+            jupiter_create_limit_order,
+            jupiter_cancel_limit_orders,
+            jupiter_list_limit_orders,
+            jupiter_reconcile_limit_orders,
             get_network_congestion,
             get_priority_fee_estimates,
             submit_with_mev_protection,
@@ -1549,12 +2005,15 @@ pub fn run() {
             // Trading & Orders
             trading_init,
             create_order,
+            create_order_group,
             cancel_order,
             get_active_orders,
             get_order_history,
             get_order,
             acknowledge_order,
             update_order_prices,
+            calculate_position_size,
+            get_sizing_presets,
             // Auto Trading Engine
             auto_trading_create_strategy,
             auto_trading_update_strategy,
@@ -1568,12 +2027,25 @@ pub fn run() {
             auto_trading_get_strategy,
             auto_trading_get_executions,
             auto_trading_apply_parameters,
+            strategy_script_validate,
+            strategy_script_backtest,
+            strategy_script_evaluate,
             // Backtesting & Optimization
             backtest_run,
             optimizer_start,
             optimizer_cancel,
             optimizer_get_runs,
             optimizer_get_run,
+            // Experiment Tracking
+            experiments_list,
+            experiments_get,
+            experiments_log_run,
+            experiments_add_artifact,
+            experiments_tag,
+            experiments_set_notes,
+            experiments_delete,
+            experiments_compare,
+            experiments_export,
             // Paper Trading Simulation
             paper_trading_init,
             get_paper_account,
@@ -1583,6 +2055,11 @@ pub fn run() {
             get_paper_trade_history,
             get_paper_performance,
             update_paper_position_prices,
+            place_paper_order,
+            cancel_paper_order,
+            get_paper_open_orders,
+            get_paper_execution_config,
+            update_paper_execution_config,
             // DCA Bots
             dca_init,
             dca_create,
@@ -1593,6 +2070,12 @@ pub fn run() {
             dca_delete,
             dca_history,
             dca_performance,
+            grid_init,
+            grid_create,
+            grid_list,
+            grid_pause,
+            grid_resume,
+            grid_performance,
             // Copy Trading
             copy_trading_init,
             copy_trading_create,
@@ -1630,11 +2113,28 @@ pub fn run() {
             security::activity_log::cleanup_activity_logs,
             security::activity_log::get_activity_retention,
             security::activity_log::set_activity_retention,
+            get_retention_policies,
+            update_retention_policies,
+            preview_retention,
+            run_retention_now,
+            get_last_retention_report,
+            security::keystore_access::get_keystore_access_log,
             // Smart Contract Security
             security::audit::scan_contract,
             security::audit::get_cached_audit,
             security::audit::clear_audit_cache,
             security::audit::check_risk_threshold,
+            security::phishing::check_url_safety,
+            security::phishing::check_token_impersonation,
+            security::phishing::open_token_website,
+            security::phishing::phishing_blocklist_list,
+            security::phishing::phishing_blocklist_set,
+            // Dead-man Switch
+            security::dead_man_switch::dead_man_switch_get_status,
+            security::dead_man_switch::dead_man_switch_update_settings,
+            security::dead_man_switch::dead_man_switch_check_in,
+            security::dead_man_switch::dead_man_switch_set_passphrase,
+            security::dead_man_switch::dead_man_switch_export_package,
             // Reputation System
             security::reputation::get_wallet_reputation,
             security::reputation::get_token_reputation,
@@ -1679,8 +2179,24 @@ pub fn run() {
             academy::submit_challenge,
             academy::get_challenge_submissions,
             academy::record_webinar_attendance,
+            academy::join_webinar,
+            academy::webinar_check_in,
+            academy::start_webinar,
+            academy::end_webinar,
+            academy::handle_webinar_webhook,
+            academy::get_webinar_webhook_secret,
+            academy::get_webinar_attendees,
             academy::create_mentor_session,
             academy::get_user_mentor_sessions,
+            academy::set_mentor_availability,
+            academy::get_mentor_availability,
+            academy::get_mentor_open_slots,
+            academy::book_mentor_session,
+            academy::cancel_mentor_session,
+            academy::complete_mentor_session,
+            academy::rate_mentor_session,
+            academy::get_mentor_sessions,
+            academy::get_mentor_leaderboard,
             academy::get_user_stats,
             academy::get_leaderboard,
             academy::create_badge,
@@ -1695,6 +2211,10 @@ pub fn run() {
             academy::claim_reward,
             academy::claim_all_rewards,
             academy::get_reward_stats,
+            academy::academy_get_feature_gates,
+            academy::academy_update_feature_gates,
+            academy::academy_set_gate_override,
+            academy::academy_get_unlock_status,
             // Performance & Diagnostics
             get_performance_metrics,
             run_performance_test,
@@ -1707,6 +2227,11 @@ pub fn run() {
             cache_commands::update_ttl_config,
             cache_commands::reset_ttl_config,
             cache_commands::test_cache_performance,
+            cache_commands::get_cache_warmup_settings,
+            cache_commands::update_cache_warmup_settings,
+            cache_commands::preview_cache_warmup,
+            cache_commands::run_cache_warmup,
+            cache_commands::get_last_cache_warmup,
             // Market Surveillance & Anomaly Detection
             add_price_data,
             add_transaction_data,
@@ -1724,6 +2249,18 @@ pub fn run() {
             data::event_store::export_audit_trail_command,
             data::event_store::create_snapshot_command,
             data::event_store::get_event_stats,
+            data::event_stream::event_stream_subscribe,
+            data::event_stream::event_stream_ack,
+            data::event_stream::event_stream_unsubscribe,
+            data::event_stream::event_stream_list,
+            // Dataset Export
+            data::export::export_dataset,
+            // SQL Console
+            data::sql_console::data_query_sql,
+            // Snapshot Restore
+            data::state_restore::capture_state_snapshot,
+            data::state_restore::list_state_snapshots,
+            data::state_restore::restore_from_snapshot,
             // Data Compression
             data::compression_commands::get_compression_stats,
             data::compression_commands::compress_old_data,
@@ -1777,6 +2314,33 @@ pub fn run() {
             market::get_portfolio_comparison,
             market::get_consensus_data,
             market::record_prediction_performance,
+            market::record_prediction_position,
+            market::close_prediction_position,
+            market::get_prediction_positions,
+            market::sync_prediction_positions,
+            market::reconcile_prediction_settlement,
+            market::get_prediction_pnl,
+            market::resolve_custom_predictions_now,
+            market::get_prediction_calibration,
+            market::get_funding_rates,
+            market::get_funding_rate_history,
+            market::refresh_funding_rates,
+            market::get_token_unlocks,
+            market::add_token_unlock,
+            market::remove_token_unlock,
+            market::get_token_unlock_settings,
+            market::update_token_unlock_settings,
+            market::refresh_token_unlocks,
+            market::synthetic_index_list,
+            market::synthetic_index_get,
+            market::synthetic_index_create,
+            market::synthetic_index_update,
+            market::synthetic_index_delete,
+            market::synthetic_index_refresh,
+            market::synthetic_index_performance,
+            market::synthetic_index_history,
+            market::synthetic_index_rebalance_suggestions,
+            market::synthetic_index_rebalance,
             // Indicator & drawing commands
             indicator_save_state,
             indicator_list_presets,
@@ -1798,10 +2362,25 @@ pub fn run() {
             chain_list_chains,
             chain_list_enabled,
             chain_update_config,
+            validate_chain_address,
             chain_get_balance,
             chain_get_fee_estimate,
             chain_get_status,
             chain_get_cross_chain_portfolio,
+            chain_scan_portfolio,
+            chain_get_reconciliation,
+            chain_get_reconciliation_settings,
+            chain_update_reconciliation_settings,
+            chain_evm_import_key,
+            chain_evm_remove_key,
+            chain_rpc_pool_get,
+            chain_rpc_pool_upsert_endpoint,
+            chain_rpc_pool_remove_endpoint,
+            chain_rpc_pool_update_settings,
+            chain_rpc_pool_probe,
+            chain_rpc_pool_select,
+            rpc_pool_get_status,
+            rpc_pool_update_endpoints,
             // Bridge integrations
             bridge_get_quote,
             bridge_create_transaction,
@@ -1811,6 +2390,8 @@ pub fn run() {
             bridge_update_transaction_status,
             bridge_update_transaction_hash,
             bridge_poll_status,
+            bridge_get_registry,
+            bridge_record_incident,
             // Launchpad commands
             create_launch_config,
             update_launch_config,
@@ -1845,6 +2426,10 @@ pub fn run() {
             stocks::get_insider_activity,
             stocks::create_stock_alert,
             stocks::get_stock_alerts,
+            stocks::scan_insider_patterns,
+            stocks::get_insider_pattern_signals,
+            stocks::get_earnings_reaction_stats,
+            stocks::get_pre_earnings_reminders,
             // DeFi commands
             get_solend_reserves,
             get_solend_pools,
@@ -1860,17 +2445,47 @@ pub fn run() {
             get_yield_farms,
             get_farming_opportunities,
             get_farming_positions,
+            record_lp_entry,
+            record_lp_observation,
+            close_lp_position,
+            remove_lp_position,
+            get_impermanent_loss_report,
             get_defi_portfolio_summary,
             get_defi_risk_metrics,
             get_defi_snapshot,
+            ingest_derivative_positions,
+            list_derivative_positions,
+            remove_derivative_position,
+            refresh_derivative_marks,
+            get_derivatives_summary,
             get_auto_compound_recommendations,
             configure_auto_compound,
             get_auto_compound_config,
             get_compound_history,
             estimate_compound_apy_boost,
+            staking_rewards_scan,
+            staking_rewards_run_now,
+            staking_rewards_get_settings,
+            staking_rewards_update_settings,
+            staking_rewards_history,
+            staking_rewards_approve,
+            staking_rewards_reject,
+            staking_rewards_submit,
             get_governance_proposals,
             vote_on_proposal,
             get_governance_participation,
+            get_protocol_risk_scores,
+            get_protocol_risk_profile,
+            get_liquidation_risk,
+            compare_lending_rates,
+            get_lending_rate_history,
+            suggest_lending_move,
+            preview_yield_strategy,
+            deploy_yield_strategy,
+            list_yield_strategies,
+            record_yield_strategy_value,
+            get_yield_strategy_performance,
+            close_yield_strategy,
             // Updater commands
             get_update_settings,
             save_update_settings,
@@ -1903,6 +2518,11 @@ pub fn run() {
             backup::service::update_backup_schedule,
             backup::service::get_backup_status,
             backup::service::trigger_manual_backup,
+            backup::selective::preview_selective_restore,
+            backup::selective::restore_backup_selective,
+            backup::remote::backup_configure_remote,
+            backup::remote::backup_list_remotes,
+            backup::remote::backup_sync_remote,
             // Universal Settings
             config::commands::get_all_settings,
             config::commands::update_setting,
@@ -1975,6 +2595,7 @@ pub fn run() {
             ai_execute_quick_action,
             ai_optimize_portfolio,
             ai_apply_optimization,
+            ai_get_optimization_execution,
             ai_get_pattern_warnings,
             ai_dismiss_pattern_warning,
             // Voice Trading
@@ -1991,6 +2612,8 @@ pub fn run() {
             // Safety Mode Engine
             check_trade_safety,
             pre_trade_contract_check,
+            get_safety_decision_traces,
+            get_safety_decision_trace,
             approve_trade,
             get_safety_policy,
             update_safety_policy,
@@ -2008,6 +2631,19 @@ pub fn run() {
             unmonitor_contract,
             list_monitored_contracts,
             refresh_monitored_contracts,
+            // MEV Detection
+            analyze_transaction_mev,
+            get_mev_report,
+            // Token Whitelist/Blacklist
+            get_token_policy,
+            token_policy_add,
+            token_policy_remove,
+            token_policy_set_whitelist_mode,
+            token_policy_import_reputation,
+            token_policy_check,
+            // Token-2022 Extensions
+            get_token_extensions,
+            calculate_transfer_fee,
             // Theme Engine
             theme_get_presets,
             theme_get_settings,
@@ -2034,8 +2670,20 @@ pub fn run() {
             mobile_get_cached_sync_data,
             mobile_execute_quick_trade,
             mobile_safety_checks,
+            mobile_get_device_trade_limits,
+            mobile_set_device_trade_limits,
+            mobile_get_pending_trade_approvals,
+            mobile_approve_quick_trade,
+            mobile_reject_quick_trade,
+            mobile_create_halt_challenge,
+            mobile_confirm_remote_halt,
             mobile_get_widget_data,
             mobile_get_all_widgets,
+            mobile_get_widget_payload,
+            mobile_get_widget_payloads,
+            mobile_request_widget_refresh,
+            mobile_get_widget_budgets,
+            mobile_set_widget_budget,
             // Collaborative Rooms
             collab::commands::collab_create_room,
             collab::commands::collab_list_rooms,
@@ -2045,6 +2693,14 @@ pub fn run() {
             collab::commands::collab_leave_room,
             collab::commands::collab_get_participants,
             collab::commands::collab_update_permissions,
+            collab::commands::collab_assign_role,
+            collab::commands::collab_update_role_grants,
+            collab::commands::collab_get_identity_key,
+            collab::commands::collab_get_key_envelopes,
+            collab::commands::collab_accept_room_key,
+            collab::commands::collab_share_chart,
+            collab::commands::collab_move_chart_cursor,
+            collab::commands::collab_clear_chart_cursor,
             collab::commands::collab_send_message,
             collab::commands::collab_get_messages,
             collab::commands::collab_share_watchlist,
@@ -2070,6 +2726,8 @@ pub fn run() {
             diagnostics::tauri_commands::download_missing,
             diagnostics::tauri_commands::restore_defaults,
             diagnostics::tauri_commands::get_repair_history,
+            diagnostics::tauri_commands::run_db_maintenance,
+            diagnostics::tauri_commands::get_db_maintenance_report,
             diagnostics::tauri_commands::get_diagnostics_settings,
             diagnostics::tauri_commands::save_diagnostics_settings,
             diagnostics::tauri_commands::backup_before_repair,
@@ -2105,6 +2763,21 @@ pub fn run() {
             get_weekly_reports,
             get_behavioral_analytics,
             get_journal_stats,
+            // Token research
+            get_token_research,
+            list_token_research,
+            add_research_note,
+            update_research_note,
+            delete_research_note,
+            attach_research_file,
+            remove_research_attachment,
+            link_research_signal,
+            unlink_research_signal,
+            generate_research_summary,
+            export_token_research,
+            add_research_thesis,
+            close_research_thesis,
+            get_thesis_review,
             // Dev Tools
             compile_now,
             get_build_status,
@@ -2119,6 +2792,11 @@ pub fn run() {
             log_message,
             get_logger_config,
             set_logger_config,
+            get_dev_performance_metrics,
+            capture_performance_profile,
+            get_db_query_report,
+            set_slow_query_threshold,
+            reset_db_query_stats,
             get_error_stats,
             report_crash,
             get_crash_report,
@@ -2150,11 +2828,40 @@ pub fn run() {
             get_trader_profile,
             check_p2p_compliance,
             get_p2p_stats,
+            // OTC Desk
+            p2p::otc::otc_create_deal,
+            p2p::otc::otc_list_deals,
+            p2p::otc::otc_get_deal,
+            p2p::otc::otc_amend_terms,
+            p2p::otc::otc_accept_terms,
+            p2p::otc::otc_open_escrow,
+            p2p::otc::otc_complete_step,
+            p2p::otc::otc_settle,
+            p2p::otc::otc_cancel_deal,
+            // User Profiles
+            profiles::user_profile_status,
+            profiles::user_profile_list,
+            profiles::user_profile_create,
+            profiles::user_profile_rename,
+            profiles::user_profile_delete,
+            profiles::user_profile_set_startup,
+            profiles::user_profile_switch,
+            // Network Environment
+            environment::network_environment_status,
+            environment::network_environment_set_startup,
+            environment::network_environment_switch,
+            environment::network_environment_verify,
             // Feature Flags
             get_feature_flags,
             enable_feature_flag,
             disable_feature_flag,
             is_feature_enabled,
+            // Demo Mode
+            demo::demo_mode_status,
+            demo::demo_mode_enable,
+            demo::demo_mode_disable,
+            demo::demo_generate_dataset,
+            demo::demo_generate_price_series,
         ]);
 
     startup_log!("Invoke handler attached");
@@ -1,5 +1,6 @@
 use crate::logger::{LogBuffer, LogEntry, LogLevel, SharedLogBuffer};
 use chrono::Utc;
+use crate::profiles::ProfilePaths;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::fs::{File, OpenOptions};
@@ -54,7 +55,7 @@ pub struct ComprehensiveLogger {
 
 impl ComprehensiveLogger {
     pub fn new(app: &AppHandle) -> Result<Self, std::io::Error> {
-        let mut log_dir = app.path().app_data_dir().map_err(|err| {
+        let mut log_dir = app.path().profile_data_dir().map_err(|err| {
             std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 format!("App data dir not found: {err}"),
@@ -0,0 +1,60 @@
+use serde_json::Value;
+
+const HISTORY_PRICE_URL: &str = "https://public-api.birdeye.so/defi/history_price";
+
+/// Hourly USD prices for `mint` from Birdeye between `from` and `to` (unix
+/// seconds), as `(unix_time, price)` pairs in the order Birdeye returns them.
+pub(crate) async fn hourly_price_history(
+    client: &reqwest::Client,
+    api_key: &str,
+    mint: &str,
+    from: i64,
+    to: i64,
+) -> Result<Vec<(i64, f64)>, String> {
+    let url = format!(
+        "{}?address={}&address_type=token&type=1H&time_from={}&time_to={}",
+        HISTORY_PRICE_URL, mint, from, to
+    );
+    let body: Value = client
+        .get(&url)
+        .header("X-API-KEY", api_key)
+        .send()
+        .await
+        .map_err(|e| format!("Price history request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse price history: {}", e))?;
+    Ok(history_items(&body))
+}
+
+fn history_items(body: &Value) -> Vec<(i64, f64)> {
+    body["data"]["items"]
+        .as_array()
+        .map(|items| items.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|item| Some((item["unixTime"].as_i64()?, item["value"].as_f64()?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn skips_items_without_time_or_price() {
+        let body = json!({
+            "data": {
+                "items": [
+                    { "unixTime": 3600, "value": 1.5 },
+                    { "unixTime": 7200 },
+                    { "value": 2.0 },
+                    { "unixTime": 10800, "value": 2.5 },
+                ]
+            }
+        });
+        assert_eq!(history_items(&body), vec![(3600, 1.5), (10800, 2.5)]);
+        assert!(history_items(&json!({ "success": false })).is_empty());
+    }
+}
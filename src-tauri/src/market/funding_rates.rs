@@ -0,0 +1,533 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::{Pool, Row, Sqlite, SqlitePool};
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::RwLock;
+
+use super::drift_adapter::{DriftAdapter, DriftMarket};
+use crate::alerts::SharedAlertManager;
+use crate::profiles::ProfilePaths;
+
+const FUNDING_DB_FILE: &str = "funding_rates.db";
+const HYPERLIQUID_INFO_URL: &str = "https://api.hyperliquid.xyz/info";
+const MANGO_PERP_SUMMARY_URL: &str = "https://api.mngo.cloud/data/v4/stats/perp-market-summary";
+const POLL_INTERVAL: StdDuration = StdDuration::from_secs(300);
+const HISTORY_RETENTION_DAYS: i64 = 30;
+const DEFAULT_HISTORY_HOURS: i64 = 24;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum FundingVenue {
+    Drift,
+    Mango,
+    Hyperliquid,
+}
+
+impl FundingVenue {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FundingVenue::Drift => "drift",
+            FundingVenue::Mango => "mango",
+            FundingVenue::Hyperliquid => "hyperliquid",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "drift" => Some(FundingVenue::Drift),
+            "mango" => Some(FundingVenue::Mango),
+            "hyperliquid" => Some(FundingVenue::Hyperliquid),
+            _ => None,
+        }
+    }
+}
+
+/// One funding observation. Rates from every venue are normalised to
+/// percent per hour so they can be compared and alerted on directly.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRateSnapshot {
+    pub venue: FundingVenue,
+    /// Normalised market name, e.g. "SOL-PERP".
+    pub market: String,
+    pub rate_pct_per_hour: f64,
+    pub annualized_pct: f64,
+    pub mark_price: f64,
+    pub open_interest: Option<f64>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl FundingRateSnapshot {
+    fn new(
+        venue: FundingVenue,
+        market: &str,
+        rate_pct_per_hour: f64,
+        mark_price: f64,
+        open_interest: Option<f64>,
+        recorded_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            venue,
+            market: normalize_perp_market(market),
+            rate_pct_per_hour,
+            annualized_pct: rate_pct_per_hour * 24.0 * 365.0,
+            mark_price,
+            open_interest,
+            recorded_at,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FundingRateError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Request(String),
+}
+
+/// "sol", "SOL", "SOL-PERP" and "sol-perp" all become "SOL-PERP".
+pub fn normalize_perp_market(market: &str) -> String {
+    let upper = market.trim().to_uppercase();
+    if upper.ends_with("-PERP") {
+        upper
+    } else {
+        format!("{upper}-PERP")
+    }
+}
+
+/// Drift reports the hourly funding rate as a fraction.
+pub fn drift_funding_snapshots(
+    markets: &[DriftMarket],
+    now: DateTime<Utc>,
+) -> Vec<FundingRateSnapshot> {
+    markets
+        .iter()
+        .filter(|m| m.market_type.eq_ignore_ascii_case("perp"))
+        .map(|m| {
+            FundingRateSnapshot::new(
+                FundingVenue::Drift,
+                &m.symbol,
+                m.funding_rate * 100.0,
+                m.mark_price,
+                Some(m.open_interest),
+                now,
+            )
+        })
+        .collect()
+}
+
+/// Parses a `metaAndAssetCtxs` response: the universe in the first element
+/// and per-asset contexts, in the same order, in the second. Hyperliquid
+/// funding is an hourly fraction sent as a string.
+pub fn parse_hyperliquid_funding(body: &Value, now: DateTime<Utc>) -> Vec<FundingRateSnapshot> {
+    let universe = body
+        .get(0)
+        .and_then(|meta| meta.get("universe"))
+        .and_then(Value::as_array);
+    let contexts = body.get(1).and_then(Value::as_array);
+    let (Some(universe), Some(contexts)) = (universe, contexts) else {
+        return Vec::new();
+    };
+
+    universe
+        .iter()
+        .zip(contexts)
+        .filter_map(|(asset, ctx)| {
+            let name = asset.get("name")?.as_str()?;
+            let funding = number_field(ctx, "funding")?;
+            let mark = number_field(ctx, "markPx").unwrap_or(0.0);
+            Some(FundingRateSnapshot::new(
+                FundingVenue::Hyperliquid,
+                name,
+                funding * 100.0,
+                mark,
+                number_field(ctx, "openInterest"),
+                now,
+            ))
+        })
+        .collect()
+}
+
+/// Parses Mango's perp market summary, keyed by market name with either a
+/// single entry or a list per market. Mango already quotes the hourly
+/// funding rate as a percentage.
+pub fn parse_mango_funding(body: &Value, now: DateTime<Utc>) -> Vec<FundingRateSnapshot> {
+    let Some(markets) = body.as_object() else {
+        return Vec::new();
+    };
+
+    markets
+        .iter()
+        .filter_map(|(name, entry)| {
+            let entry = match entry {
+                Value::Array(items) => items.first()?,
+                other => other,
+            };
+            let funding = number_field(entry, "funding_rate")?;
+            Some(FundingRateSnapshot::new(
+                FundingVenue::Mango,
+                name,
+                funding,
+                number_field(entry, "price").unwrap_or(0.0),
+                number_field(entry, "open_interest"),
+                now,
+            ))
+        })
+        .collect()
+}
+
+fn number_field(value: &Value, key: &str) -> Option<f64> {
+    match value.get(key)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+pub struct FundingRateMonitor {
+    pool: Pool<Sqlite>,
+    client: reqwest::Client,
+    drift: DriftAdapter,
+}
+
+pub type SharedFundingRateMonitor = Arc<RwLock<FundingRateMonitor>>;
+
+impl FundingRateMonitor {
+    pub async fn new(app: &AppHandle) -> Result<Self, FundingRateError> {
+        let app_data_dir = app.path().profile_data_dir().map_err(|e| {
+            FundingRateError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Unable to resolve app data directory: {e}"),
+            ))
+        })?;
+        std::fs::create_dir_all(&app_data_dir)?;
+        let db_url = format!(
+            "sqlite:{}?mode=rwc",
+            app_data_dir.join(FUNDING_DB_FILE).display()
+        );
+        let pool = SqlitePool::connect(&db_url).await?;
+
+        let monitor = Self {
+            pool,
+            client: reqwest::Client::builder()
+                .timeout(StdDuration::from_secs(20))
+                .build()
+                .unwrap_or_else(|_| reqwest::Client::new()),
+            drift: DriftAdapter::new(),
+        };
+        monitor.initialize().await?;
+        Ok(monitor)
+    }
+
+    async fn initialize(&self) -> Result<(), FundingRateError> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS funding_rates (
+                venue TEXT NOT NULL,
+                market TEXT NOT NULL,
+                rate_pct_per_hour REAL NOT NULL,
+                mark_price REAL NOT NULL,
+                open_interest REAL,
+                recorded_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_funding_market_time ON funding_rates(market, recorded_at)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Fetches current funding from every venue. A venue that fails is
+    /// logged and skipped so one outage does not blank the others.
+    pub async fn poll(&self) -> Vec<FundingRateSnapshot> {
+        let now = Utc::now();
+        let (drift, hyperliquid, mango) = tokio::join!(
+            self.drift.fetch_markets(),
+            self.fetch_json(
+                self.client
+                    .post(HYPERLIQUID_INFO_URL)
+                    .json(&json!({ "type": "metaAndAssetCtxs" }))
+            ),
+            self.fetch_json(self.client.get(MANGO_PERP_SUMMARY_URL)),
+        );
+
+        let mut snapshots = Vec::new();
+        match drift {
+            Ok(markets) => snapshots.extend(drift_funding_snapshots(&markets, now)),
+            Err(e) => eprintln!("Drift funding poll failed: {}", e),
+        }
+        match hyperliquid {
+            Ok(body) => snapshots.extend(parse_hyperliquid_funding(&body, now)),
+            Err(e) => eprintln!("Hyperliquid funding poll failed: {}", e),
+        }
+        match mango {
+            Ok(body) => snapshots.extend(parse_mango_funding(&body, now)),
+            Err(e) => eprintln!("Mango funding poll failed: {}", e),
+        }
+        snapshots
+    }
+
+    async fn fetch_json(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<Value, FundingRateError> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| FundingRateError::Request(format!("request failed: {e}")))?;
+        if !response.status().is_success() {
+            return Err(FundingRateError::Request(format!(
+                "returned status: {}",
+                response.status()
+            )));
+        }
+        response
+            .json::<Value>()
+            .await
+            .map_err(|e| FundingRateError::Request(format!("invalid response: {e}")))
+    }
+
+    pub async fn record(&self, snapshots: &[FundingRateSnapshot]) -> Result<(), FundingRateError> {
+        let mut tx = self.pool.begin().await?;
+        for snapshot in snapshots {
+            sqlx::query(
+                r#"
+                INSERT INTO funding_rates (
+                    venue, market, rate_pct_per_hour, mark_price, open_interest, recorded_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+            )
+            .bind(snapshot.venue.as_str())
+            .bind(&snapshot.market)
+            .bind(snapshot.rate_pct_per_hour)
+            .bind(snapshot.mark_price)
+            .bind(snapshot.open_interest)
+            .bind(snapshot.recorded_at.to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let cutoff = Utc::now() - Duration::days(HISTORY_RETENTION_DAYS);
+        sqlx::query("DELETE FROM funding_rates WHERE recorded_at < ?1")
+            .bind(cutoff.to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Most recent observation for every venue and market.
+    pub async fn latest(&self) -> Result<Vec<FundingRateSnapshot>, FundingRateError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT f.venue, f.market, f.rate_pct_per_hour, f.mark_price, f.open_interest, f.recorded_at
+            FROM funding_rates f
+            JOIN (
+                SELECT venue, market, MAX(recorded_at) AS recorded_at
+                FROM funding_rates
+                GROUP BY venue, market
+            ) latest
+            ON f.venue = latest.venue AND f.market = latest.market AND f.recorded_at = latest.recorded_at
+            ORDER BY f.market ASC, f.venue ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().filter_map(row_to_snapshot).collect())
+    }
+
+    pub async fn history(
+        &self,
+        market: &str,
+        venue: Option<FundingVenue>,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<FundingRateSnapshot>, FundingRateError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT venue, market, rate_pct_per_hour, mark_price, open_interest, recorded_at
+            FROM funding_rates
+            WHERE market = ?1 AND (?2 IS NULL OR venue = ?2) AND recorded_at >= ?3
+            ORDER BY recorded_at ASC
+            "#,
+        )
+        .bind(normalize_perp_market(market))
+        .bind(venue.map(|v| v.as_str()))
+        .bind(since.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().filter_map(row_to_snapshot).collect())
+    }
+}
+
+fn row_to_snapshot(row: &sqlx::sqlite::SqliteRow) -> Option<FundingRateSnapshot> {
+    let venue: String = row.try_get("venue").ok()?;
+    let market: String = row.try_get("market").ok()?;
+    let recorded_at: String = row.try_get("recorded_at").ok()?;
+    Some(FundingRateSnapshot::new(
+        FundingVenue::from_str(&venue)?,
+        &market,
+        row.try_get("rate_pct_per_hour").ok()?,
+        row.try_get("mark_price").ok()?,
+        row.try_get("open_interest").ok()?,
+        DateTime::parse_from_rfc3339(&recorded_at)
+            .ok()?
+            .with_timezone(&Utc),
+    ))
+}
+
+/// Polls, stores and checks funding alerts once. Each venue's rate is
+/// checked separately, so an alert fires on the first venue that crosses
+/// its threshold and then sits in cooldown.
+async fn refresh_and_alert(
+    app: &AppHandle,
+    monitor: &SharedFundingRateMonitor,
+    alerts: &SharedAlertManager,
+) -> Result<Vec<FundingRateSnapshot>, FundingRateError> {
+    let snapshots = {
+        let monitor = monitor.read().await;
+        let snapshots = monitor.poll().await;
+        monitor.record(&snapshots).await?;
+        snapshots
+    };
+
+    let alerts = alerts.read().await;
+    for snapshot in &snapshots {
+        if let Err(e) = alerts
+            .check_funding_alerts(
+                &snapshot.market,
+                snapshot.mark_price,
+                snapshot.rate_pct_per_hour,
+            )
+            .await
+        {
+            eprintln!(
+                "Funding alert check failed for {} on {}: {}",
+                snapshot.market,
+                snapshot.venue.as_str(),
+                e
+            );
+        }
+    }
+
+    let _ = app.emit("funding_rates_updated", &snapshots);
+    Ok(snapshots)
+}
+
+pub fn start_funding_rate_monitor(
+    app: AppHandle,
+    monitor: SharedFundingRateMonitor,
+    alerts: SharedAlertManager,
+) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = refresh_and_alert(&app, &monitor, &alerts).await {
+                eprintln!("Funding rate monitor error: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_funding_rates(
+    monitor: State<'_, SharedFundingRateMonitor>,
+) -> Result<Vec<FundingRateSnapshot>, String> {
+    monitor
+        .read()
+        .await
+        .latest()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_funding_rate_history(
+    market: String,
+    venue: Option<FundingVenue>,
+    hours: Option<i64>,
+    monitor: State<'_, SharedFundingRateMonitor>,
+) -> Result<Vec<FundingRateSnapshot>, String> {
+    let hours = hours
+        .unwrap_or(DEFAULT_HISTORY_HOURS)
+        .clamp(1, HISTORY_RETENTION_DAYS * 24);
+    monitor
+        .read()
+        .await
+        .history(&market, venue, Utc::now() - Duration::hours(hours))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn refresh_funding_rates(
+    app: AppHandle,
+    monitor: State<'_, SharedFundingRateMonitor>,
+    alerts: State<'_, SharedAlertManager>,
+) -> Result<Vec<FundingRateSnapshot>, String> {
+    refresh_and_alert(&app, monitor.inner(), alerts.inner())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hyperliquid_contexts_line_up_with_the_universe() {
+        let body = json!([
+            { "universe": [{ "name": "BTC" }, { "name": "SOL" }] },
+            [
+                { "funding": "0.0000125", "markPx": "64000.0", "openInterest": "1200.5" },
+                { "funding": "0.0006", "markPx": "150.25", "openInterest": "90000" }
+            ]
+        ]);
+        let snapshots = parse_hyperliquid_funding(&body, Utc::now());
+
+        assert_eq!(snapshots.len(), 2);
+        let sol = &snapshots[1];
+        assert_eq!(sol.market, "SOL-PERP");
+        assert!((sol.rate_pct_per_hour - 0.06).abs() < 1e-12);
+        assert!((sol.annualized_pct - 0.06 * 8760.0).abs() < 1e-9);
+        assert_eq!(sol.mark_price, 150.25);
+    }
+
+    #[test]
+    fn mango_summary_accepts_lists_and_skips_markets_without_funding() {
+        let body = json!({
+            "SOL-PERP": [{ "funding_rate": 0.012, "price": 150.0, "open_interest": 5000.0 }],
+            "BTC-PERP": { "funding_rate": -0.004, "price": 64000.0 },
+            "ETH-PERP": [{ "price": 3100.0 }]
+        });
+        let mut snapshots = parse_mango_funding(&body, Utc::now());
+        snapshots.sort_by(|a, b| a.market.cmp(&b.market));
+
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].market, "BTC-PERP");
+        assert_eq!(snapshots[0].rate_pct_per_hour, -0.004);
+        assert_eq!(snapshots[1].open_interest, Some(5000.0));
+    }
+
+    #[test]
+    fn market_names_normalise_to_perp_symbols() {
+        assert_eq!(normalize_perp_market("sol"), "SOL-PERP");
+        assert_eq!(normalize_perp_market(" SOL-PERP "), "SOL-PERP");
+        assert_eq!(normalize_perp_market("kPEPE"), "KPEPE-PERP");
+    }
+}
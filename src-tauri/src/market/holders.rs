@@ -1,4 +1,6 @@
+use crate::api::SharedRequestRegistry;
 use chrono::Utc;
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use std::collections::HashMap;
@@ -652,7 +654,7 @@ impl HolderAnalyzer {
 }
 
 fn holder_db_path(app: &AppHandle) -> Result<PathBuf, HolderError> {
-    let mut path = app.path().app_data_dir().map_err(|err| {
+    let mut path = app.path().profile_data_dir().map_err(|err| {
         HolderError::Internal(format!("Unable to resolve app data directory: {err}"))
     })?;
 
@@ -668,12 +670,18 @@ fn holder_db_path(app: &AppHandle) -> Result<PathBuf, HolderError> {
 #[tauri::command]
 pub async fn get_holder_distribution(
     token_address: String,
+    request_id: Option<String>,
     analyzer: State<'_, SharedHolderAnalyzer>,
+    requests: State<'_, SharedRequestRegistry>,
 ) -> Result<HolderDistribution, String> {
     let analyzer = analyzer.read().await;
-    analyzer
-        .get_holder_distribution(&token_address)
-        .await
+    requests
+        .track(
+            request_id.as_deref(),
+            "get_holder_distribution",
+            analyzer.get_holder_distribution(&token_address),
+        )
+        .await?
         .map_err(|e| e.to_string())
 }
 
@@ -1,13 +1,20 @@
 mod trending_coins;
 pub use trending_coins::*;
+pub mod birdeye_history;
 pub mod drift_adapter;
+pub mod funding_rates;
 pub mod holders;
 pub mod new_coins_scanner_clean;
+pub mod new_coins_triage;
 pub mod polymarket_adapter;
+pub mod prediction_resolution;
 pub mod predictions;
+pub mod synthetic_index;
+pub mod token_unlocks;
 pub mod top_coins;
 
 pub use drift_adapter::*;
+pub use funding_rates::*;
 pub use holders::*;
 // Exclude HolderInfo from new_coins_scanner_clean to avoid conflict with holders::HolderInfo
 pub use new_coins_scanner_clean::{
@@ -15,12 +22,18 @@ pub use new_coins_scanner_clean::{
     SafetyChecks, SafetyReport, SharedNewCoinsScanner, start_new_coins_scanner,
     get_new_coins, get_coin_safety_report, scan_for_new_coins,
 };
+pub use new_coins_triage::*;
 pub use polymarket_adapter::*;
+pub use prediction_resolution::*;
 pub use predictions::*;
+pub use synthetic_index::*;
+pub use token_unlocks::*;
 pub use top_coins::*;
 
+use crate::demo::SharedDemoModeState;
 use reqwest;
 use serde::{Deserialize, Serialize};
+use tauri::State;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CoinPrice {
@@ -53,7 +66,7 @@ pub struct TokenSearchResult {
 }
 
 // Birdeye API integration
-async fn fetch_birdeye_price(token: &str, api_key: &str) -> Result<CoinPrice, String> {
+pub(crate) async fn fetch_birdeye_price(token: &str, api_key: &str) -> Result<CoinPrice, String> {
     let client = reqwest::Client::new();
     let url = format!("https://public-api.birdeye.so/defi/price?address={}", token);
 
@@ -139,7 +152,28 @@ fn generate_mock_history(hours: i64) -> Vec<PricePoint> {
 }
 
 #[tauri::command]
-pub async fn get_coin_price(address: String, api_key: Option<String>) -> Result<CoinPrice, String> {
+pub async fn get_coin_price(
+    address: String,
+    api_key: Option<String>,
+    demo: State<'_, SharedDemoModeState>,
+) -> Result<CoinPrice, String> {
+    if let Some(price) = demo
+        .read()
+        .await
+        .overlay
+        .as_ref()
+        .and_then(|overlay| overlay.coin_price(&address))
+    {
+        return Ok(price);
+    }
+    fetch_coin_price(address, api_key).await
+}
+
+/// Quote for `address` that ignores demo mode, for background pollers.
+pub async fn fetch_coin_price(
+    address: String,
+    api_key: Option<String>,
+) -> Result<CoinPrice, String> {
     // If API key provided, use real API
     if let Some(key) = api_key {
         if !key.is_empty() {
@@ -159,6 +193,7 @@ pub async fn get_price_history(
     address: String,
     timeframe: String,
     _api_key: Option<String>,
+    demo: State<'_, SharedDemoModeState>,
 ) -> Result<Vec<PricePoint>, String> {
     let hours = match timeframe.as_str() {
         "1H" => 1,
@@ -169,6 +204,16 @@ pub async fn get_price_history(
         _ => 24,
     };
 
+    if let Some(history) = demo
+        .read()
+        .await
+        .overlay
+        .as_ref()
+        .and_then(|overlay| overlay.price_history(&address, hours))
+    {
+        return Ok(history);
+    }
+
     // For now, return mock data
     Ok(generate_mock_history(hours))
 }
@@ -1,4 +1,9 @@
 use chrono::{Duration as ChronoDuration, Utc};
+use super::new_coins_triage::SharedNewCoinTriage;
+use crate::profiles::ProfilePaths;
+use crate::security::audit::Severity;
+use crate::security::keystore::Keystore;
+use crate::security::phishing::{check_token_identity, PhishingProtection, ProtectionWarning};
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use std::collections::HashMap;
@@ -50,6 +55,8 @@ pub struct SafetyReport {
     pub holder_info: HolderInfo,
     pub creator_info: CreatorInfo,
     pub recommendation: String,
+    #[serde(default)]
+    pub protection_warnings: Vec<ProtectionWarning>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -120,6 +127,10 @@ impl NewCoinsScanner {
         Ok(scanner)
     }
 
+    pub fn pool(&self) -> &Pool<Sqlite> {
+        &self.pool
+    }
+
     async fn initialize(&self) -> Result<(), NewCoinsScannerError> {
         sqlx::query(
             r#"
@@ -175,6 +186,16 @@ impl NewCoinsScanner {
             self.store_coin(coin).await?;
         }
 
+        if let Some(triage) = self
+            .app_handle
+            .as_ref()
+            .and_then(|app| app.try_state::<SharedNewCoinTriage>())
+        {
+            if let Err(e) = triage.enqueue(&mock_coins).await {
+                eprintln!("Failed to queue new coins for triage: {}", e);
+            }
+        }
+
         // Emit event for high-safety coins
         if let Some(app) = &self.app_handle {
             for coin in &mock_coins {
@@ -382,6 +403,11 @@ impl NewCoinsScanner {
             suspicious_activity: creator_reputation < 0.3,
         };
 
+        let address: String = coin.get("address");
+        let symbol: String = coin.get("symbol");
+        let name: String = coin.get("name");
+        let protection_warnings = check_token_identity(&address, &symbol, &name);
+
         let recommendation = if safety_score >= 80 {
             "Safe - Low risk for investment".to_string()
         } else if safety_score >= 50 {
@@ -390,17 +416,20 @@ impl NewCoinsScanner {
             "High Risk - Not recommended, likely scam".to_string()
         };
 
-        Ok(SafetyReport {
-            address: coin.get("address"),
-            symbol: coin.get("symbol"),
-            name: coin.get("name"),
+        let mut report = SafetyReport {
+            address,
+            symbol,
+            name,
             safety_score,
             checks,
             liquidity_info,
             holder_info,
             creator_info,
             recommendation,
-        })
+            protection_warnings,
+        };
+        apply_protection_verdict(&mut report);
+        Ok(report)
     }
 
     pub async fn cleanup_old_coins(&self, days: i64) -> Result<(), NewCoinsScannerError> {
@@ -415,6 +444,19 @@ impl NewCoinsScanner {
     }
 }
 
+/// Impersonation or phishing findings override an otherwise good score.
+fn apply_protection_verdict(report: &mut SafetyReport) {
+    let severe = report
+        .protection_warnings
+        .iter()
+        .any(|w| matches!(w.severity, Severity::High | Severity::Critical));
+    if severe {
+        report.checks.not_flagged_as_spam = false;
+        report.recommendation =
+            "High Risk - Impersonates a known token or links to a phishing site".to_string();
+    }
+}
+
 pub type SharedNewCoinsScanner = Arc<RwLock<NewCoinsScanner>>;
 
 pub fn start_new_coins_scanner(scanner: SharedNewCoinsScanner) {
@@ -433,7 +475,7 @@ pub fn start_new_coins_scanner(scanner: SharedNewCoinsScanner) {
 }
 
 fn get_new_coins_db_path(app: &AppHandle) -> Result<PathBuf, NewCoinsScannerError> {
-    let mut path = app.path().app_data_dir().map_err(|err| {
+    let mut path = app.path().profile_data_dir().map_err(|err| {
         NewCoinsScannerError::Internal(format!("Unable to resolve app data directory: {err}"))
     })?;
 
@@ -459,13 +501,24 @@ pub async fn get_new_coins(
 #[tauri::command]
 pub async fn get_coin_safety_report(
     scanner: tauri::State<'_, SharedNewCoinsScanner>,
+    protection: tauri::State<'_, PhishingProtection>,
+    keystore: tauri::State<'_, Keystore>,
     token_address: String,
 ) -> Result<SafetyReport, String> {
-    let scanner = scanner.read().await;
-    scanner
-        .get_safety_report(&token_address)
-        .await
-        .map_err(|e| e.to_string())
+    let mut report = {
+        let scanner = scanner.read().await;
+        scanner
+            .get_safety_report(&token_address)
+            .await
+            .map_err(|e| e.to_string())?
+    };
+
+    // Re-run with the metadata links, which need network access.
+    report.protection_warnings = protection
+        .check_token(&keystore, &report.address, &report.symbol, &report.name)
+        .await;
+    apply_protection_verdict(&mut report);
+    Ok(report)
 }
 
 #[tauri::command]
@@ -0,0 +1,826 @@
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Row, Sqlite};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::new_coins_scanner_clean::{NewCoin, NewCoinsScannerError};
+use super::SharedHolderAnalyzer;
+use crate::academy::{enforce_feature_gate, GatedFeature, SharedAcademyEngine, SharedFeatureGate};
+use crate::ai_legacy::{score_token_risk, SharedRiskAnalyzer};
+use crate::api_config::stored_birdeye_key;
+use crate::portfolio::{SharedWatchlistManager, WatchlistError};
+use crate::security::keystore::Keystore;
+use crate::wallet::history_backfill::PriceOracle;
+
+pub const TRIAGE_UPDATED_EVENT: &str = "coin_triage_updated";
+
+/// How long after a verdict the price is checked to judge whether the
+/// verdict was right.
+const OUTCOME_HORIZON_HOURS: i64 = 24;
+/// Outcome lookups per stats request, so opening the stats panel does not
+/// fan out into hundreds of price calls.
+const MAX_EVALUATIONS_PER_PASS: i64 = 25;
+const MAX_BATCH: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TriageVerdict {
+    Ignore,
+    Watch,
+    Research,
+    SnipeCandidate,
+}
+
+impl TriageVerdict {
+    pub const ALL: [TriageVerdict; 4] = [
+        TriageVerdict::Ignore,
+        TriageVerdict::Watch,
+        TriageVerdict::Research,
+        TriageVerdict::SnipeCandidate,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            TriageVerdict::Ignore => "ignore",
+            TriageVerdict::Watch => "watch",
+            TriageVerdict::Research => "research",
+            TriageVerdict::SnipeCandidate => "snipe_candidate",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.as_str() == value)
+    }
+
+    /// Ignoring is right when the price fell; every other verdict bets on
+    /// the coin going up.
+    fn is_hit(&self, return_pct: f64) -> bool {
+        match self {
+            TriageVerdict::Ignore => return_pct <= 0.0,
+            _ => return_pct > 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TriageAction {
+    AddToWatchlist,
+    ScoreRisk,
+}
+
+/// What runs automatically when a verdict is given.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriageAutomations {
+    /// Watchlist that `AddToWatchlist` targets; created on first use.
+    pub watchlist_name: String,
+    pub ignore: Vec<TriageAction>,
+    pub watch: Vec<TriageAction>,
+    pub research: Vec<TriageAction>,
+    pub snipe_candidate: Vec<TriageAction>,
+}
+
+impl Default for TriageAutomations {
+    fn default() -> Self {
+        Self {
+            watchlist_name: "Triage".to_string(),
+            ignore: Vec::new(),
+            watch: vec![TriageAction::AddToWatchlist],
+            research: vec![TriageAction::ScoreRisk],
+            snipe_candidate: vec![TriageAction::AddToWatchlist, TriageAction::ScoreRisk],
+        }
+    }
+}
+
+impl TriageAutomations {
+    fn actions_for(&self, verdict: TriageVerdict) -> &[TriageAction] {
+        match verdict {
+            TriageVerdict::Ignore => &self.ignore,
+            TriageVerdict::Watch => &self.watch,
+            TriageVerdict::Research => &self.research,
+            TriageVerdict::SnipeCandidate => &self.snipe_candidate,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationResult {
+    pub action: TriageAction,
+    pub success: bool,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriageEntry {
+    pub address: String,
+    pub symbol: String,
+    pub name: String,
+    pub safety_score: i64,
+    pub liquidity: f64,
+    pub enqueued_at: String,
+    pub verdict: Option<TriageVerdict>,
+    pub decided_at: Option<String>,
+    pub note: Option<String>,
+    pub price_at_verdict: Option<f64>,
+    /// Price change over the outcome horizon, filled in once it has passed.
+    pub outcome_return_pct: Option<f64>,
+    pub automation_log: Vec<AutomationResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriageDecision {
+    pub addresses: Vec<String>,
+    pub verdict: TriageVerdict,
+    pub note: Option<String>,
+}
+
+/// Returned by verdict and reopen calls. `next` is the item the queue would
+/// show next, so a keyboard-driven UI can advance without another request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriageBatchResult {
+    pub updated: Vec<TriageEntry>,
+    pub missing: Vec<String>,
+    pub next: Option<TriageEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerdictStats {
+    pub verdict: TriageVerdict,
+    pub count: usize,
+    pub evaluated: usize,
+    pub hit_rate: Option<f64>,
+    pub avg_return_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriageStats {
+    pub pending: usize,
+    pub triaged_total: usize,
+    pub triaged_last_24h: usize,
+    /// Mean time from a coin entering the queue to its verdict.
+    pub avg_decision_secs: Option<f64>,
+    pub oldest_pending_secs: Option<i64>,
+    pub per_verdict: Vec<VerdictStats>,
+    pub overall_hit_rate: Option<f64>,
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+pub fn compute_triage_stats(entries: &[TriageEntry], now: DateTime<Utc>) -> TriageStats {
+    let (decided, pending): (Vec<&TriageEntry>, Vec<&TriageEntry>) =
+        entries.iter().partition(|e| e.verdict.is_some());
+
+    let day_ago = now - ChronoDuration::hours(24);
+    let triaged_last_24h = decided
+        .iter()
+        .filter(|e| {
+            e.decided_at
+                .as_deref()
+                .and_then(parse_time)
+                .is_some_and(|t| t >= day_ago)
+        })
+        .count();
+
+    let decision_secs: Vec<f64> = decided
+        .iter()
+        .filter_map(|e| {
+            let enqueued = parse_time(&e.enqueued_at)?;
+            let decided = parse_time(e.decided_at.as_deref()?)?;
+            Some((decided - enqueued).num_seconds().max(0) as f64)
+        })
+        .collect();
+    let avg_decision_secs = (!decision_secs.is_empty())
+        .then(|| decision_secs.iter().sum::<f64>() / decision_secs.len() as f64);
+
+    let oldest_pending_secs = pending
+        .iter()
+        .filter_map(|e| parse_time(&e.enqueued_at))
+        .min()
+        .map(|t| (now - t).num_seconds().max(0));
+
+    let mut total_evaluated = 0;
+    let mut total_hits = 0;
+    let per_verdict = TriageVerdict::ALL
+        .into_iter()
+        .map(|verdict| {
+            let with_verdict: Vec<&&TriageEntry> = decided
+                .iter()
+                .filter(|e| e.verdict == Some(verdict))
+                .collect();
+            let returns: Vec<f64> = with_verdict
+                .iter()
+                .filter_map(|e| e.outcome_return_pct)
+                .collect();
+            let hits = returns.iter().filter(|r| verdict.is_hit(**r)).count();
+            total_evaluated += returns.len();
+            total_hits += hits;
+            VerdictStats {
+                verdict,
+                count: with_verdict.len(),
+                evaluated: returns.len(),
+                hit_rate: (!returns.is_empty()).then(|| hits as f64 / returns.len() as f64),
+                avg_return_pct: (!returns.is_empty())
+                    .then(|| returns.iter().sum::<f64>() / returns.len() as f64),
+            }
+        })
+        .collect();
+
+    TriageStats {
+        pending: pending.len(),
+        triaged_total: decided.len(),
+        triaged_last_24h,
+        avg_decision_secs,
+        oldest_pending_secs,
+        per_verdict,
+        overall_hit_rate: (total_evaluated > 0).then(|| total_hits as f64 / total_evaluated as f64),
+    }
+}
+
+pub type SharedNewCoinTriage = Arc<NewCoinTriage>;
+
+/// Persistent verdict queue over the scanner's output. Lives in the
+/// scanner's database so queue rows and coin rows can be joined.
+pub struct NewCoinTriage {
+    pool: Pool<Sqlite>,
+}
+
+impl NewCoinTriage {
+    pub async fn new(pool: Pool<Sqlite>) -> Result<Self, NewCoinsScannerError> {
+        let triage = Self { pool };
+        triage.initialize().await?;
+        Ok(triage)
+    }
+
+    async fn initialize(&self) -> Result<(), NewCoinsScannerError> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS coin_triage (
+                address TEXT PRIMARY KEY,
+                symbol TEXT NOT NULL,
+                name TEXT NOT NULL,
+                safety_score INTEGER NOT NULL,
+                liquidity REAL NOT NULL,
+                enqueued_at TEXT NOT NULL,
+                verdict TEXT,
+                decided_at TEXT,
+                note TEXT,
+                price_at_verdict REAL,
+                outcome_return_pct REAL,
+                automation_log TEXT NOT NULL DEFAULT '[]'
+            );
+            CREATE INDEX IF NOT EXISTS idx_coin_triage_verdict ON coin_triage(verdict);
+            CREATE INDEX IF NOT EXISTS idx_coin_triage_decided ON coin_triage(decided_at);
+            CREATE TABLE IF NOT EXISTS coin_triage_settings (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                automations TEXT NOT NULL
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Adds scanner results that are not already queued. Spam never enters
+    /// the queue, and coins keep their verdict if the scanner sees them again.
+    pub async fn enqueue(&self, coins: &[NewCoin]) -> Result<usize, NewCoinsScannerError> {
+        let now = Utc::now().to_rfc3339();
+        let mut added = 0;
+        for coin in coins.iter().filter(|c| !c.is_spam) {
+            let result = sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO coin_triage
+                    (address, symbol, name, safety_score, liquidity, enqueued_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+            )
+            .bind(&coin.address)
+            .bind(&coin.symbol)
+            .bind(&coin.name)
+            .bind(coin.safety_score)
+            .bind(coin.liquidity)
+            .bind(&now)
+            .execute(&self.pool)
+            .await?;
+            added += result.rows_affected() as usize;
+        }
+        Ok(added)
+    }
+
+    fn row_to_entry(row: &sqlx::sqlite::SqliteRow) -> TriageEntry {
+        let verdict: Option<String> = row.get("verdict");
+        let log: String = row.get("automation_log");
+        TriageEntry {
+            address: row.get("address"),
+            symbol: row.get("symbol"),
+            name: row.get("name"),
+            safety_score: row.get("safety_score"),
+            liquidity: row.get("liquidity"),
+            enqueued_at: row.get("enqueued_at"),
+            verdict: verdict.as_deref().and_then(TriageVerdict::parse),
+            decided_at: row.get("decided_at"),
+            note: row.get("note"),
+            price_at_verdict: row.get("price_at_verdict"),
+            outcome_return_pct: row.get("outcome_return_pct"),
+            automation_log: serde_json::from_str(&log).unwrap_or_default(),
+        }
+    }
+
+    /// Pending items come best-first (highest safety score, then oldest) so
+    /// the strongest candidates are seen before they go stale.
+    pub async fn list(
+        &self,
+        pending_only: bool,
+        verdict: Option<TriageVerdict>,
+        limit: i64,
+    ) -> Result<Vec<TriageEntry>, NewCoinsScannerError> {
+        let rows = match (pending_only, verdict) {
+            (true, _) => {
+                sqlx::query(
+                    "SELECT * FROM coin_triage WHERE verdict IS NULL ORDER BY safety_score DESC, enqueued_at ASC LIMIT ?1",
+                )
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await?
+            }
+            (false, Some(verdict)) => {
+                sqlx::query(
+                    "SELECT * FROM coin_triage WHERE verdict = ?1 ORDER BY decided_at DESC LIMIT ?2",
+                )
+                .bind(verdict.as_str())
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await?
+            }
+            (false, None) => {
+                sqlx::query(
+                    "SELECT * FROM coin_triage ORDER BY verdict IS NOT NULL, decided_at DESC, safety_score DESC LIMIT ?1",
+                )
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await?
+            }
+        };
+        Ok(rows.iter().map(Self::row_to_entry).collect())
+    }
+
+    pub async fn get(&self, address: &str) -> Result<Option<TriageEntry>, NewCoinsScannerError> {
+        let row = sqlx::query("SELECT * FROM coin_triage WHERE address = ?1")
+            .bind(address)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.as_ref().map(Self::row_to_entry))
+    }
+
+    pub async fn next_pending(&self) -> Result<Option<TriageEntry>, NewCoinsScannerError> {
+        Ok(self.list(true, None, 1).await?.into_iter().next())
+    }
+
+    async fn record_verdict(
+        &self,
+        address: &str,
+        verdict: TriageVerdict,
+        note: Option<&str>,
+        price: Option<f64>,
+    ) -> Result<(), NewCoinsScannerError> {
+        sqlx::query(
+            r#"
+            UPDATE coin_triage
+            SET verdict = ?2, decided_at = ?3, note = ?4, price_at_verdict = ?5,
+                outcome_return_pct = NULL, automation_log = '[]'
+            WHERE address = ?1
+            "#,
+        )
+        .bind(address)
+        .bind(verdict.as_str())
+        .bind(Utc::now().to_rfc3339())
+        .bind(note)
+        .bind(price)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn record_automations(
+        &self,
+        address: &str,
+        log: &[AutomationResult],
+    ) -> Result<(), NewCoinsScannerError> {
+        sqlx::query("UPDATE coin_triage SET automation_log = ?2 WHERE address = ?1")
+            .bind(address)
+            .bind(serde_json::to_string(log)?)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Puts items back in the pending queue, e.g. to undo a mis-keyed
+    /// verdict. Automations that already ran are not reversed.
+    pub async fn reopen(&self, address: &str) -> Result<bool, NewCoinsScannerError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE coin_triage
+            SET verdict = NULL, decided_at = NULL, note = NULL, price_at_verdict = NULL,
+                outcome_return_pct = NULL
+            WHERE address = ?1
+            "#,
+        )
+        .bind(address)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn automations(&self) -> Result<TriageAutomations, NewCoinsScannerError> {
+        let stored: Option<String> =
+            sqlx::query_scalar("SELECT automations FROM coin_triage_settings WHERE id = 1")
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(match stored {
+            Some(json) => serde_json::from_str(&json)?,
+            None => TriageAutomations::default(),
+        })
+    }
+
+    pub async fn set_automations(
+        &self,
+        automations: &TriageAutomations,
+    ) -> Result<(), NewCoinsScannerError> {
+        sqlx::query("INSERT OR REPLACE INTO coin_triage_settings (id, automations) VALUES (1, ?1)")
+            .bind(serde_json::to_string(automations)?)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn all_entries(&self) -> Result<Vec<TriageEntry>, NewCoinsScannerError> {
+        let rows = sqlx::query("SELECT * FROM coin_triage")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.iter().map(Self::row_to_entry).collect())
+    }
+
+    /// Fills in outcomes for verdicts whose horizon has passed, using the
+    /// price at the horizon rather than now so every verdict is judged over
+    /// the same window.
+    pub async fn evaluate_outcomes(
+        &self,
+        oracle: &mut PriceOracle,
+    ) -> Result<usize, NewCoinsScannerError> {
+        let cutoff = (Utc::now() - ChronoDuration::hours(OUTCOME_HORIZON_HOURS)).to_rfc3339();
+        let rows = sqlx::query(
+            r#"
+            SELECT address, decided_at, price_at_verdict FROM coin_triage
+            WHERE verdict IS NOT NULL AND price_at_verdict IS NOT NULL
+              AND outcome_return_pct IS NULL AND decided_at <= ?1
+            ORDER BY decided_at ASC LIMIT ?2
+            "#,
+        )
+        .bind(cutoff)
+        .bind(MAX_EVALUATIONS_PER_PASS)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut evaluated = 0;
+        for row in rows {
+            let address: String = row.get("address");
+            let decided_at: String = row.get("decided_at");
+            let entry_price: f64 = row.get("price_at_verdict");
+            let Some(decided) = parse_time(&decided_at) else {
+                continue;
+            };
+            let horizon = decided + ChronoDuration::hours(OUTCOME_HORIZON_HOURS);
+            let Some(exit_price) = oracle.price_at(&address, horizon).await else {
+                continue;
+            };
+            if entry_price <= 0.0 {
+                continue;
+            }
+            sqlx::query("UPDATE coin_triage SET outcome_return_pct = ?2 WHERE address = ?1")
+                .bind(&address)
+                .bind((exit_price / entry_price - 1.0) * 100.0)
+                .execute(&self.pool)
+                .await?;
+            evaluated += 1;
+        }
+        Ok(evaluated)
+    }
+}
+
+async fn add_to_watchlist(
+    app: &AppHandle,
+    list_name: &str,
+    entry: &TriageEntry,
+) -> AutomationResult {
+    let result = async {
+        let manager = app
+            .try_state::<SharedWatchlistManager>()
+            .ok_or_else(|| "Watchlists are unavailable".to_string())?;
+        let manager = manager.read().await;
+        let existing = manager
+            .list_watchlists()
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .find(|w| w.name == list_name);
+        let watchlist = match existing {
+            Some(watchlist) => watchlist,
+            None => manager
+                .create_watchlist(list_name.to_string())
+                .await
+                .map_err(|e| e.to_string())?,
+        };
+        match manager
+            .add_item(&watchlist.id, entry.symbol.clone(), entry.address.clone())
+            .await
+        {
+            Ok(_) => Ok(format!("Added to {}", watchlist.name)),
+            Err(WatchlistError::DuplicateItem(_)) => Ok(format!("Already on {}", watchlist.name)),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+    .await;
+    automation_result(TriageAction::AddToWatchlist, result)
+}
+
+async fn score_risk(app: &AppHandle, entry: &TriageEntry) -> AutomationResult {
+    let result = async {
+        let risk = app
+            .try_state::<SharedRiskAnalyzer>()
+            .ok_or_else(|| "Risk analyzer is unavailable".to_string())?;
+        let holders = app
+            .try_state::<SharedHolderAnalyzer>()
+            .ok_or_else(|| "Holder analyzer is unavailable".to_string())?;
+        let score = score_token_risk(&entry.address, &risk, &holders).await?;
+        Ok(format!(
+            "Risk {:.0}/100 ({})",
+            score.score, score.risk_level
+        ))
+    }
+    .await;
+    automation_result(TriageAction::ScoreRisk, result)
+}
+
+fn automation_result(action: TriageAction, result: Result<String, String>) -> AutomationResult {
+    match result {
+        Ok(detail) => AutomationResult {
+            action,
+            success: true,
+            detail: Some(detail),
+        },
+        Err(e) => AutomationResult {
+            action,
+            success: false,
+            detail: Some(e),
+        },
+    }
+}
+
+async fn run_automations(
+    app: &AppHandle,
+    automations: &TriageAutomations,
+    verdict: TriageVerdict,
+    entry: &TriageEntry,
+) -> Vec<AutomationResult> {
+    let mut log = Vec::new();
+    for action in automations.actions_for(verdict) {
+        log.push(match action {
+            TriageAction::AddToWatchlist => {
+                add_to_watchlist(app, &automations.watchlist_name, entry).await
+            }
+            TriageAction::ScoreRisk => score_risk(app, entry).await,
+        });
+    }
+    log
+}
+
+fn price_oracle(app: &AppHandle) -> PriceOracle {
+    let key = app
+        .try_state::<Keystore>()
+        .and_then(|keystore| stored_birdeye_key(&keystore));
+    PriceOracle::new(key)
+}
+
+// Tauri Commands
+#[tauri::command]
+pub async fn triage_list_queue(
+    pending_only: Option<bool>,
+    verdict: Option<TriageVerdict>,
+    limit: Option<i64>,
+    triage: State<'_, SharedNewCoinTriage>,
+) -> Result<Vec<TriageEntry>, String> {
+    triage
+        .list(
+            pending_only.unwrap_or(true),
+            verdict,
+            limit.unwrap_or(100).clamp(1, 1000),
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Applies one verdict to every listed coin and runs that verdict's
+/// automations for each.
+#[tauri::command]
+pub async fn triage_set_verdict(
+    app: AppHandle,
+    decision: TriageDecision,
+    triage: State<'_, SharedNewCoinTriage>,
+    gate: State<'_, SharedFeatureGate>,
+    academy: State<'_, SharedAcademyEngine>,
+) -> Result<TriageBatchResult, String> {
+    if decision.addresses.len() > MAX_BATCH {
+        return Err(format!("At most {MAX_BATCH} coins can be triaged at once"));
+    }
+    if decision.verdict == TriageVerdict::SnipeCandidate {
+        enforce_feature_gate(GatedFeature::Sniper, &app, &gate, &academy).await?;
+    }
+    let automations = triage.automations().await.map_err(|e| e.to_string())?;
+    let mut oracle = price_oracle(&app);
+    let mut updated = Vec::new();
+    let mut missing = Vec::new();
+
+    for address in &decision.addresses {
+        if triage
+            .get(address)
+            .await
+            .map_err(|e| e.to_string())?
+            .is_none()
+        {
+            missing.push(address.clone());
+            continue;
+        }
+        let price = oracle.price_at(address, Utc::now()).await;
+        triage
+            .record_verdict(address, decision.verdict, decision.note.as_deref(), price)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let Some(entry) = triage.get(address).await.map_err(|e| e.to_string())? else {
+            continue;
+        };
+        let log = run_automations(&app, &automations, decision.verdict, &entry).await;
+        triage
+            .record_automations(address, &log)
+            .await
+            .map_err(|e| e.to_string())?;
+        updated.push(TriageEntry {
+            automation_log: log,
+            ..entry
+        });
+    }
+
+    let next = triage.next_pending().await.map_err(|e| e.to_string())?;
+    let _ = app.emit(TRIAGE_UPDATED_EVENT, &updated);
+    Ok(TriageBatchResult {
+        updated,
+        missing,
+        next,
+    })
+}
+
+#[tauri::command]
+pub async fn triage_reopen(
+    app: AppHandle,
+    addresses: Vec<String>,
+    triage: State<'_, SharedNewCoinTriage>,
+) -> Result<TriageBatchResult, String> {
+    let mut updated = Vec::new();
+    let mut missing = Vec::new();
+    for address in addresses {
+        if !triage.reopen(&address).await.map_err(|e| e.to_string())? {
+            missing.push(address);
+            continue;
+        }
+        if let Some(entry) = triage.get(&address).await.map_err(|e| e.to_string())? {
+            updated.push(entry);
+        }
+    }
+    let next = triage.next_pending().await.map_err(|e| e.to_string())?;
+    let _ = app.emit(TRIAGE_UPDATED_EVENT, &updated);
+    Ok(TriageBatchResult {
+        updated,
+        missing,
+        next,
+    })
+}
+
+#[tauri::command]
+pub async fn triage_get_automations(
+    triage: State<'_, SharedNewCoinTriage>,
+) -> Result<TriageAutomations, String> {
+    triage.automations().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn triage_set_automations(
+    automations: TriageAutomations,
+    triage: State<'_, SharedNewCoinTriage>,
+) -> Result<(), String> {
+    if automations.watchlist_name.trim().is_empty() {
+        return Err("Watchlist name cannot be empty".to_string());
+    }
+    triage
+        .set_automations(&automations)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Throughput and hit-rate statistics. Verdicts whose outcome horizon has
+/// passed are priced first, a batch at a time.
+#[tauri::command]
+pub async fn triage_get_stats(
+    app: AppHandle,
+    triage: State<'_, SharedNewCoinTriage>,
+) -> Result<TriageStats, String> {
+    let mut oracle = price_oracle(&app);
+    if let Err(e) = triage.evaluate_outcomes(&mut oracle).await {
+        eprintln!("Failed to evaluate triage outcomes: {}", e);
+    }
+    let entries = triage.all_entries().await.map_err(|e| e.to_string())?;
+    Ok(compute_triage_stats(&entries, Utc::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        enqueued: DateTime<Utc>,
+        decided: Option<(TriageVerdict, DateTime<Utc>)>,
+        outcome: Option<f64>,
+    ) -> TriageEntry {
+        TriageEntry {
+            address: "mint".to_string(),
+            symbol: "TEST".to_string(),
+            name: "Test".to_string(),
+            safety_score: 80,
+            liquidity: 10_000.0,
+            enqueued_at: enqueued.to_rfc3339(),
+            verdict: decided.map(|(v, _)| v),
+            decided_at: decided.map(|(_, t)| t.to_rfc3339()),
+            note: None,
+            price_at_verdict: outcome.map(|_| 1.0),
+            outcome_return_pct: outcome,
+            automation_log: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_stats_throughput_and_hit_rate() {
+        let now = Utc::now();
+        let hour = ChronoDuration::hours(1);
+        let entries = vec![
+            entry(now - hour * 3, None, None),
+            entry(
+                now - hour * 50,
+                Some((TriageVerdict::Watch, now - hour * 48)),
+                Some(12.0),
+            ),
+            entry(
+                now - hour * 50,
+                Some((TriageVerdict::Watch, now - hour * 49)),
+                Some(-8.0),
+            ),
+            entry(
+                now - hour * 40,
+                Some((TriageVerdict::Ignore, now - hour * 38)),
+                Some(-30.0),
+            ),
+            entry(
+                now - hour * 2,
+                Some((TriageVerdict::SnipeCandidate, now - hour)),
+                None,
+            ),
+        ];
+
+        let stats = compute_triage_stats(&entries, now);
+        assert_eq!(stats.pending, 1);
+        assert_eq!(stats.triaged_total, 4);
+        assert_eq!(stats.triaged_last_24h, 1);
+        assert_eq!(stats.oldest_pending_secs, Some(3 * 3600));
+        // 2h, 1h, 2h and 1h from enqueue to verdict.
+        assert_eq!(stats.avg_decision_secs, Some(1.5 * 3600.0));
+
+        let watch = &stats.per_verdict[1];
+        assert_eq!(watch.verdict, TriageVerdict::Watch);
+        assert_eq!((watch.count, watch.evaluated), (2, 2));
+        assert_eq!(watch.hit_rate, Some(0.5));
+        // The ignored coin fell, so ignoring it was a hit.
+        assert_eq!(stats.per_verdict[0].hit_rate, Some(1.0));
+        assert_eq!(stats.per_verdict[3].evaluated, 0);
+        assert!((stats.overall_hit_rate.unwrap() - 2.0 / 3.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,461 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_client::rpc_request::RpcRequest;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::{interval, Duration};
+
+use super::predictions::{CustomPrediction, PredictionPerformance, SharedPredictionMarketService};
+use crate::academy::SharedAcademyEngine;
+use crate::api_config::stored_birdeye_key;
+use crate::chains::{RoutingHint, RpcPool, SharedRpcPool};
+use crate::security::keystore::Keystore;
+use crate::wallet::history_backfill::PriceOracle;
+
+const RESOLVER_INTERVAL_SECS: u64 = 300;
+const SIGNATURE_LOOKBACK: usize = 100;
+const CALIBRATION_BUCKETS: usize = 10;
+/// XP for a perfect forecast; scaled down by the Brier score.
+const MAX_RESOLUTION_XP: f64 = 50.0;
+/// Floor for the log score so a confident miss does not produce -inf.
+const MIN_PROBABILITY: f64 = 1e-6;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceComparison {
+    Above,
+    Below,
+}
+
+/// Data source and condition that decide which outcome a custom prediction
+/// resolves to.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ResolutionRule {
+    /// Resolves at `resolve_at` from the oracle's price for `mint` at that
+    /// time.
+    #[serde(rename_all = "camelCase")]
+    PriceThreshold {
+        mint: String,
+        comparison: PriceComparison,
+        threshold: f64,
+        resolve_at: i64,
+        outcome_if_true: usize,
+        outcome_if_false: usize,
+    },
+    /// Resolves as soon as `address` has a successful transaction after the
+    /// prediction was made, or as not-occurred once `deadline` passes.
+    #[serde(rename_all = "camelCase")]
+    OnChainEvent {
+        address: String,
+        deadline: i64,
+        outcome_if_occurred: usize,
+        outcome_if_not: usize,
+    },
+}
+
+impl ResolutionRule {
+    pub fn validate(&self, outcome_count: usize) -> Result<(), String> {
+        let outcomes = match self {
+            ResolutionRule::PriceThreshold {
+                threshold,
+                outcome_if_true,
+                outcome_if_false,
+                ..
+            } => {
+                if !(threshold.is_finite() && *threshold > 0.0) {
+                    return Err("Price threshold must be positive".to_string());
+                }
+                [*outcome_if_true, *outcome_if_false]
+            }
+            ResolutionRule::OnChainEvent {
+                outcome_if_occurred,
+                outcome_if_not,
+                ..
+            } => [*outcome_if_occurred, *outcome_if_not],
+        };
+        if outcomes.iter().any(|o| *o >= outcome_count) {
+            return Err("Resolution rule refers to an outcome that does not exist".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PredictionResolution {
+    pub outcome: usize,
+    pub resolved_at: i64,
+    /// What the data source reported, e.g. the observed price or the
+    /// transaction signature.
+    pub evidence: String,
+    pub brier_score: f64,
+    pub log_score: f64,
+    pub correct: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationBucket {
+    pub lower: f64,
+    pub upper: f64,
+    pub forecasts: usize,
+    pub mean_forecast: f64,
+    pub observed_rate: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationReport {
+    pub user_id: String,
+    pub resolved_predictions: usize,
+    pub accuracy_rate: f64,
+    pub average_brier_score: f64,
+    /// Forecast-weighted gap between stated probability and observed
+    /// frequency; 0 is perfectly calibrated.
+    pub calibration_error: f64,
+    pub buckets: Vec<CalibrationBucket>,
+}
+
+/// Multi-outcome Brier score (0 best, 2 worst), log score of the winning
+/// outcome, and whether the user's most likely outcome won.
+pub fn score_forecast(probabilities: &[f64], winner: usize) -> (f64, f64, bool) {
+    let brier = probabilities
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let observed = if i == winner { 1.0 } else { 0.0 };
+            (p - observed).powi(2)
+        })
+        .sum();
+    let log_score = probabilities
+        .get(winner)
+        .copied()
+        .unwrap_or(0.0)
+        .max(MIN_PROBABILITY)
+        .ln();
+    let favourite = probabilities
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(i, _)| i);
+    (brier, log_score, favourite == Some(winner))
+}
+
+pub fn calibration_report(
+    user_id: &str,
+    performances: &[PredictionPerformance],
+) -> CalibrationReport {
+    let resolved: Vec<_> = performances
+        .iter()
+        .filter(|p| p.user_id == user_id)
+        .filter_map(|p| Some((p, p.actual_outcome?)))
+        .collect();
+
+    let mut sums = vec![(0usize, 0.0f64, 0.0f64); CALIBRATION_BUCKETS];
+    let mut correct = 0;
+    let mut brier_total = 0.0;
+    for (performance, winner) in &resolved {
+        let (brier, _, hit) = score_forecast(&performance.initial_prediction, *winner);
+        brier_total += brier;
+        if hit {
+            correct += 1;
+        }
+        for (i, p) in performance.initial_prediction.iter().enumerate() {
+            let bucket = ((p * CALIBRATION_BUCKETS as f64) as usize).min(CALIBRATION_BUCKETS - 1);
+            let entry = &mut sums[bucket];
+            entry.0 += 1;
+            entry.1 += p;
+            entry.2 += if i == *winner { 1.0 } else { 0.0 };
+        }
+    }
+
+    let total_forecasts: usize = sums.iter().map(|s| s.0).sum();
+    let mut calibration_error = 0.0;
+    let buckets = sums
+        .into_iter()
+        .enumerate()
+        .filter(|(_, (count, _, _))| *count > 0)
+        .map(|(i, (count, forecast, observed))| {
+            let mean_forecast = forecast / count as f64;
+            let observed_rate = observed / count as f64;
+            calibration_error +=
+                (mean_forecast - observed_rate).abs() * count as f64 / total_forecasts as f64;
+            CalibrationBucket {
+                lower: i as f64 / CALIBRATION_BUCKETS as f64,
+                upper: (i + 1) as f64 / CALIBRATION_BUCKETS as f64,
+                forecasts: count,
+                mean_forecast,
+                observed_rate,
+            }
+        })
+        .collect();
+
+    let n = resolved.len();
+    CalibrationReport {
+        user_id: user_id.to_string(),
+        resolved_predictions: n,
+        accuracy_rate: if n > 0 {
+            correct as f64 / n as f64
+        } else {
+            0.0
+        },
+        average_brier_score: if n > 0 { brier_total / n as f64 } else { 0.0 },
+        calibration_error,
+        buckets,
+    }
+}
+
+/// Successful transactions on `address` within `[from, to]`, newest first.
+async fn signatures_between(
+    pool: &SharedRpcPool,
+    address: &str,
+    from: i64,
+    to: i64,
+) -> Result<Vec<String>, String> {
+    let params = json!([
+        address,
+        { "limit": SIGNATURE_LOOKBACK, "commitment": "finalized" }
+    ]);
+    let page: Vec<Value> = RpcPool::call(pool, RoutingHint::Read, move |client| {
+        client.send(RpcRequest::GetSignaturesForAddress, params.clone())
+    })
+    .await
+    .map_err(|e| format!("Failed to fetch signatures: {e}"))?;
+
+    Ok(page
+        .into_iter()
+        .filter(|entry| entry["err"].is_null())
+        .filter(|entry| {
+            entry["blockTime"]
+                .as_i64()
+                .is_some_and(|at| at >= from && at <= to)
+        })
+        .filter_map(|entry| entry["signature"].as_str().map(str::to_string))
+        .collect())
+}
+
+/// The outcome and evidence for `prediction`, or None if its rule cannot
+/// decide yet.
+async fn evaluate_rule(
+    prediction: &CustomPrediction,
+    now: i64,
+    oracle: &mut PriceOracle,
+    pool: Option<&SharedRpcPool>,
+) -> Result<Option<(usize, String)>, String> {
+    let Some(rule) = &prediction.resolution_rule else {
+        return Ok(None);
+    };
+    match rule {
+        ResolutionRule::PriceThreshold {
+            mint,
+            comparison,
+            threshold,
+            resolve_at,
+            outcome_if_true,
+            outcome_if_false,
+        } => {
+            if now < *resolve_at {
+                return Ok(None);
+            }
+            let at = DateTime::<Utc>::from_timestamp(*resolve_at, 0)
+                .ok_or_else(|| "Invalid resolution time".to_string())?;
+            let Some(price) = oracle.price_at(mint, at).await else {
+                return Err(format!("No oracle price for {mint} at {at}"));
+            };
+            let met = match comparison {
+                PriceComparison::Above => price > *threshold,
+                PriceComparison::Below => price < *threshold,
+            };
+            let outcome = if met {
+                *outcome_if_true
+            } else {
+                *outcome_if_false
+            };
+            Ok(Some((outcome, format!("Oracle price {price} at {at}"))))
+        }
+        ResolutionRule::OnChainEvent {
+            address,
+            deadline,
+            outcome_if_occurred,
+            outcome_if_not,
+        } => {
+            let pool = pool.ok_or_else(|| "RPC pool not available".to_string())?;
+            let signatures =
+                signatures_between(pool, address, prediction.created_at, *deadline).await?;
+            if let Some(signature) = signatures.last() {
+                return Ok(Some((
+                    *outcome_if_occurred,
+                    format!("Transaction {signature}"),
+                )));
+            }
+            if now >= *deadline {
+                return Ok(Some((
+                    *outcome_if_not,
+                    format!("No transaction on {address} before deadline"),
+                )));
+            }
+            Ok(None)
+        }
+    }
+}
+
+/// Resolves every custom prediction whose rule can now decide, scores it,
+/// and credits the result to the user's academy stats.
+pub async fn resolve_custom_predictions(app: &AppHandle) -> Result<Vec<CustomPrediction>, String> {
+    let service = app.state::<SharedPredictionMarketService>().inner().clone();
+    let pending = service.read().await.pending_resolutions().await;
+    if pending.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let pool = app.try_state::<SharedRpcPool>().map(|p| p.inner().clone());
+    let mut oracle = PriceOracle::new(
+        app.try_state::<Keystore>()
+            .and_then(|keystore| stored_birdeye_key(&keystore)),
+    );
+    let academy = app
+        .try_state::<SharedAcademyEngine>()
+        .map(|a| a.inner().clone());
+    let now = Utc::now().timestamp();
+    let mut resolved = Vec::new();
+
+    for prediction in pending {
+        let (outcome, evidence) =
+            match evaluate_rule(&prediction, now, &mut oracle, pool.as_ref()).await {
+                Ok(Some(decision)) => decision,
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!("Failed to resolve prediction {}: {}", prediction.id, e);
+                    continue;
+                }
+            };
+        let (brier_score, log_score, correct) =
+            score_forecast(&prediction.user_prediction, outcome);
+        let resolution = PredictionResolution {
+            outcome,
+            resolved_at: now,
+            evidence,
+            brier_score,
+            log_score,
+            correct,
+        };
+        let updated = service
+            .read()
+            .await
+            .apply_resolution(&prediction.id, resolution)
+            .await?;
+
+        if let Some(academy) = &academy {
+            let tracker = academy.read().await.progress_tracker();
+            let tracker = tracker.read().await;
+            let xp = (MAX_RESOLUTION_XP * (1.0 - brier_score / 2.0)).round() as i64;
+            if let Err(e) = tracker
+                .record_prediction_result(&updated.user_id, correct, brier_score)
+                .await
+            {
+                eprintln!("Failed to record prediction stats: {}", e);
+            }
+            if xp > 0 {
+                let _ = tracker.add_xp(&updated.user_id, xp).await;
+            }
+        }
+
+        let _ = app.emit("custom_prediction_resolved", &updated);
+        resolved.push(updated);
+    }
+    Ok(resolved)
+}
+
+pub fn start_prediction_resolver(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(RESOLVER_INTERVAL_SECS));
+        loop {
+            ticker.tick().await;
+            if let Err(e) = resolve_custom_predictions(&app).await {
+                eprintln!("Prediction resolver error: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn resolve_custom_predictions_now(
+    app_handle: AppHandle,
+) -> Result<Vec<CustomPrediction>, String> {
+    resolve_custom_predictions(&app_handle).await
+}
+
+#[tauri::command]
+pub async fn get_prediction_calibration(
+    user_id: String,
+    service: tauri::State<'_, SharedPredictionMarketService>,
+) -> Result<CalibrationReport, String> {
+    let performances = service.read().await.get_performances(&user_id).await;
+    Ok(calibration_report(&user_id, &performances))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn performance(forecast: Vec<f64>, outcome: usize) -> PredictionPerformance {
+        PredictionPerformance {
+            prediction_id: "p".to_string(),
+            user_id: "alice".to_string(),
+            initial_prediction: forecast,
+            actual_outcome: Some(outcome),
+            accuracy_score: None,
+            brier_score: None,
+            log_score: None,
+            market_comparison: None,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn brier_and_favourite_scoring() {
+        let (brier, log_score, correct) = score_forecast(&[0.8, 0.2], 0);
+        assert!((brier - 0.08).abs() < 1e-9);
+        assert!((log_score - 0.8f64.ln()).abs() < 1e-9);
+        assert!(correct);
+
+        let (brier, log_score, correct) = score_forecast(&[1.0, 0.0], 1);
+        assert!((brier - 2.0).abs() < 1e-9);
+        assert!(log_score.is_finite());
+        assert!(!correct);
+
+        let rule = ResolutionRule::OnChainEvent {
+            address: "addr".to_string(),
+            deadline: 0,
+            outcome_if_occurred: 0,
+            outcome_if_not: 2,
+        };
+        assert!(rule.validate(2).is_err());
+    }
+
+    #[test]
+    fn calibration_groups_forecasts_into_buckets() {
+        let performances = vec![
+            performance(vec![0.7, 0.3], 0),
+            performance(vec![0.7, 0.3], 1),
+            performance(vec![0.75, 0.25], 0),
+        ];
+        let report = calibration_report("alice", &performances);
+        assert_eq!(report.resolved_predictions, 3);
+        assert!((report.accuracy_rate - 2.0 / 3.0).abs() < 1e-9);
+
+        let seventy = report
+            .buckets
+            .iter()
+            .find(|b| (b.lower - 0.7).abs() < 1e-9)
+            .unwrap();
+        assert_eq!(seventy.forecasts, 3);
+        assert!((seventy.observed_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert!(report.calibration_error > 0.0);
+        assert_eq!(
+            calibration_report("bob", &performances).resolved_predictions,
+            0
+        );
+    }
+}
@@ -2,10 +2,20 @@ use super::drift_adapter::{generate_mock_drift_predictions, DriftAdapter, DriftP
 use super::polymarket_adapter::{
     generate_mock_polymarket_markets, PolymarketAdapter, PolymarketMarket,
 };
+use super::prediction_resolution::{PredictionResolution, ResolutionRule};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
 use tokio::sync::RwLock;
 
+/// Outcome price at or above which a closed market is treated as settled to
+/// that outcome when the venue does not report a winner.
+const SETTLED_PRICE_THRESHOLD: f64 = 0.99;
+/// Payout shortfalls smaller than this are rounding, not a discrepancy.
+const RECONCILE_TOLERANCE: f64 = 0.01;
+
 // Normalized prediction market structure
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -41,6 +51,11 @@ pub struct CustomPrediction {
     pub created_at: i64,
     pub updated_at: i64,
     pub notes: Option<String>,
+    /// How the prediction resolves itself; without one it stays open.
+    #[serde(default)]
+    pub resolution_rule: Option<ResolutionRule>,
+    #[serde(default)]
+    pub resolution: Option<PredictionResolution>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -87,11 +102,224 @@ pub struct PortfolioComparison {
     pub recent_performance: Vec<f64>, // Last N accuracy scores
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PredictionPositionStatus {
+    Open,
+    /// Sold before the market resolved.
+    Closed,
+    /// The market resolved and the position paid out (or expired worthless).
+    Settled,
+}
+
+/// Shares of one outcome held on an external venue.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PredictionPosition {
+    pub id: String,
+    pub market_id: String,
+    pub venue: String,
+    pub title: String,
+    pub category: String,
+    pub outcome_index: usize,
+    pub outcome: String,
+    pub shares: f64,
+    pub avg_price: f64,
+    pub current_price: f64,
+    pub status: PredictionPositionStatus,
+    pub realized_pnl: f64,
+    pub winning_outcome: Option<usize>,
+    /// Payout owed at settlement: one unit per winning share.
+    pub expected_payout: Option<f64>,
+    /// Payout the user confirmed receiving from the venue.
+    pub received_payout: Option<f64>,
+    pub opened_at: i64,
+    pub updated_at: i64,
+    pub settled_at: Option<i64>,
+}
+
+impl PredictionPosition {
+    pub fn cost_basis(&self) -> f64 {
+        self.shares * self.avg_price
+    }
+
+    pub fn market_value(&self) -> f64 {
+        match self.status {
+            PredictionPositionStatus::Open => self.shares * self.current_price,
+            _ => 0.0,
+        }
+    }
+
+    pub fn unrealized_pnl(&self) -> f64 {
+        match self.status {
+            PredictionPositionStatus::Open => self.market_value() - self.cost_basis(),
+            _ => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordPredictionPositionRequest {
+    pub market_id: String,
+    pub outcome_index: usize,
+    pub shares: f64,
+    /// Price paid per share, 0.0 to 1.0.
+    pub price: f64,
+    /// Needed for venues whose markets are not listed by this service.
+    pub venue: Option<String>,
+    pub title: Option<String>,
+    pub category: Option<String>,
+    pub outcome: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PredictionSyncResult {
+    pub updated: usize,
+    pub settled: Vec<PredictionPosition>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SettlementReconciliation {
+    pub position_id: String,
+    pub expected_payout: f64,
+    pub received_payout: f64,
+    pub discrepancy: f64,
+    pub matched: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PredictionCategoryPnl {
+    pub category: String,
+    pub cost_basis: f64,
+    pub market_value: f64,
+    pub unrealized_pnl: f64,
+    pub realized_pnl: f64,
+    pub open_positions: usize,
+    pub settled_positions: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PredictionPnlSummary {
+    /// Category the summary was filtered to, if any.
+    pub category: Option<String>,
+    pub cost_basis: f64,
+    pub market_value: f64,
+    pub unrealized_pnl: f64,
+    pub realized_pnl: f64,
+    pub total_pnl: f64,
+    pub open_positions: usize,
+    pub settled_positions: usize,
+    pub unreconciled_settlements: usize,
+    pub by_category: Vec<PredictionCategoryPnl>,
+}
+
+/// The outcome a resolved market settled to, inferred from prices when the
+/// venue only reports that the market closed.
+pub fn settlement_outcome(market: &PredictionMarket) -> Option<usize> {
+    if !market.resolved {
+        return None;
+    }
+    market.winning_outcome.or_else(|| {
+        market
+            .outcome_prices
+            .iter()
+            .position(|price| *price >= SETTLED_PRICE_THRESHOLD)
+    })
+}
+
+/// Refreshes an open position from its market. Returns true if this
+/// settled it.
+pub fn apply_market_update(
+    position: &mut PredictionPosition,
+    market: &PredictionMarket,
+    now: i64,
+) -> bool {
+    if position.status != PredictionPositionStatus::Open {
+        return false;
+    }
+    if let Some(price) = market.outcome_prices.get(position.outcome_index) {
+        position.current_price = *price;
+    }
+    position.updated_at = now;
+
+    let Some(winner) = settlement_outcome(market) else {
+        return false;
+    };
+    let payout = if winner == position.outcome_index {
+        position.shares
+    } else {
+        0.0
+    };
+    position.realized_pnl += payout - position.cost_basis();
+    position.current_price = if payout > 0.0 { 1.0 } else { 0.0 };
+    position.winning_outcome = Some(winner);
+    position.expected_payout = Some(payout);
+    position.status = PredictionPositionStatus::Settled;
+    position.settled_at = Some(now);
+    true
+}
+
+pub fn summarize_prediction_pnl(
+    positions: &[PredictionPosition],
+    category: Option<&str>,
+) -> PredictionPnlSummary {
+    let mut by_category: BTreeMap<String, PredictionCategoryPnl> = BTreeMap::new();
+    let mut summary = PredictionPnlSummary {
+        category: category.map(str::to_string),
+        ..Default::default()
+    };
+
+    for position in positions
+        .iter()
+        .filter(|p| category.map_or(true, |c| p.category.eq_ignore_ascii_case(c)))
+    {
+        let entry = by_category
+            .entry(position.category.clone())
+            .or_insert_with(|| PredictionCategoryPnl {
+                category: position.category.clone(),
+                ..Default::default()
+            });
+        let open = position.status == PredictionPositionStatus::Open;
+        if open {
+            entry.cost_basis += position.cost_basis();
+            entry.open_positions += 1;
+        }
+        if position.status == PredictionPositionStatus::Settled {
+            entry.settled_positions += 1;
+            if position.received_payout.is_none() {
+                summary.unreconciled_settlements += 1;
+            }
+        }
+        entry.market_value += position.market_value();
+        entry.unrealized_pnl += position.unrealized_pnl();
+        entry.realized_pnl += position.realized_pnl;
+    }
+
+    for entry in by_category.values() {
+        summary.cost_basis += entry.cost_basis;
+        summary.market_value += entry.market_value;
+        summary.unrealized_pnl += entry.unrealized_pnl;
+        summary.realized_pnl += entry.realized_pnl;
+        summary.open_positions += entry.open_positions;
+        summary.settled_positions += entry.settled_positions;
+    }
+    summary.total_pnl = summary.unrealized_pnl + summary.realized_pnl;
+    summary.by_category = by_category.into_values().collect();
+    summary
+}
+
 pub struct PredictionMarketService {
     polymarket_adapter: PolymarketAdapter,
     drift_adapter: DriftAdapter,
     custom_predictions: Arc<RwLock<Vec<CustomPrediction>>>,
     performances: Arc<RwLock<Vec<PredictionPerformance>>>,
+    positions: Arc<RwLock<Vec<PredictionPosition>>>,
+    positions_path: Option<PathBuf>,
 }
 
 impl PredictionMarketService {
@@ -101,9 +329,33 @@ impl PredictionMarketService {
             drift_adapter: DriftAdapter::new(),
             custom_predictions: Arc::new(RwLock::new(Vec::new())),
             performances: Arc::new(RwLock::new(Vec::new())),
+            positions: Arc::new(RwLock::new(Vec::new())),
+            positions_path: None,
         }
     }
 
+    /// Keeps positions in `path`, loading any saved there.
+    pub fn with_positions_file(mut self, path: PathBuf) -> Self {
+        let saved: Vec<PredictionPosition> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        self.positions = Arc::new(RwLock::new(saved));
+        self.positions_path = Some(path);
+        self
+    }
+
+    fn save_positions(&self, positions: &[PredictionPosition]) -> Result<(), String> {
+        let Some(path) = &self.positions_path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let contents = serde_json::to_string(positions).map_err(|e| e.to_string())?;
+        std::fs::write(path, contents).map_err(|e| e.to_string())
+    }
+
     // Normalize Polymarket markets to common format
     fn normalize_polymarket(market: &PolymarketMarket) -> PredictionMarket {
         let end_timestamp = market
@@ -220,6 +472,9 @@ impl PredictionMarketService {
         &self,
         prediction: CustomPrediction,
     ) -> Result<CustomPrediction, String> {
+        if let Some(rule) = &prediction.resolution_rule {
+            rule.validate(prediction.outcomes.len())?;
+        }
         let mut predictions = self.custom_predictions.write().await;
         predictions.push(prediction.clone());
         Ok(prediction)
@@ -242,8 +497,14 @@ impl PredictionMarketService {
         id: &str,
         updated: CustomPrediction,
     ) -> Result<CustomPrediction, String> {
+        if let Some(rule) = &updated.resolution_rule {
+            rule.validate(updated.outcomes.len())?;
+        }
         let mut predictions = self.custom_predictions.write().await;
         if let Some(pred) = predictions.iter_mut().find(|p| p.id == id) {
+            if pred.resolution.is_some() {
+                return Err("Prediction has already resolved".to_string());
+            }
             *pred = updated.clone();
             Ok(updated)
         } else {
@@ -260,6 +521,62 @@ impl PredictionMarketService {
         Ok(())
     }
 
+    /// Unresolved predictions that carry a resolution rule.
+    pub async fn pending_resolutions(&self) -> Vec<CustomPrediction> {
+        self.custom_predictions
+            .read()
+            .await
+            .iter()
+            .filter(|p| p.resolution_rule.is_some() && p.resolution.is_none())
+            .cloned()
+            .collect()
+    }
+
+    /// Stores the resolution and scores the user's forecast against it.
+    pub async fn apply_resolution(
+        &self,
+        id: &str,
+        resolution: PredictionResolution,
+    ) -> Result<CustomPrediction, String> {
+        let resolved = {
+            let mut predictions = self.custom_predictions.write().await;
+            let prediction = predictions
+                .iter_mut()
+                .find(|p| p.id == id)
+                .ok_or_else(|| "Prediction not found".to_string())?;
+            if prediction.resolution.is_some() {
+                return Err("Prediction has already resolved".to_string());
+            }
+            prediction.resolution = Some(resolution.clone());
+            prediction.updated_at = resolution.resolved_at;
+            prediction.clone()
+        };
+
+        self.record_performance(PredictionPerformance {
+            prediction_id: resolved.id.clone(),
+            user_id: resolved.user_id.clone(),
+            initial_prediction: resolved.user_prediction.clone(),
+            actual_outcome: Some(resolution.outcome),
+            accuracy_score: Some(1.0 - resolution.brier_score / 2.0),
+            brier_score: Some(resolution.brier_score),
+            log_score: Some(resolution.log_score),
+            market_comparison: None,
+            timestamp: resolution.resolved_at,
+        })
+        .await?;
+        Ok(resolved)
+    }
+
+    pub async fn get_performances(&self, user_id: &str) -> Vec<PredictionPerformance> {
+        self.performances
+            .read()
+            .await
+            .iter()
+            .filter(|p| p.user_id == user_id)
+            .cloned()
+            .collect()
+    }
+
     pub async fn get_portfolio_comparison(
         &self,
         user_id: &str,
@@ -322,6 +639,226 @@ impl PredictionMarketService {
         })
     }
 
+    /// Adds shares to the open position on the same outcome, averaging the
+    /// entry price, or opens a new one.
+    pub async fn record_position(
+        &self,
+        request: RecordPredictionPositionRequest,
+        use_mock: bool,
+    ) -> Result<PredictionPosition, String> {
+        if request.shares <= 0.0 {
+            return Err("Shares must be greater than zero".to_string());
+        }
+        if !(0.0..=1.0).contains(&request.price) {
+            return Err("Price must be between 0 and 1".to_string());
+        }
+
+        let market = self
+            .fetch_all_markets(use_mock)
+            .await
+            .ok()
+            .and_then(|markets| markets.into_iter().find(|m| m.id == request.market_id));
+        let (venue, title, category, outcome, current_price) = match &market {
+            Some(market) => {
+                let outcome = market
+                    .outcomes
+                    .get(request.outcome_index)
+                    .cloned()
+                    .ok_or_else(|| "Outcome not found for market".to_string())?;
+                (
+                    market.source.clone(),
+                    market.title.clone(),
+                    market.category.clone(),
+                    outcome,
+                    market
+                        .outcome_prices
+                        .get(request.outcome_index)
+                        .copied()
+                        .unwrap_or(request.price),
+                )
+            }
+            None => (
+                request
+                    .venue
+                    .clone()
+                    .ok_or_else(|| "Unknown market; venue is required".to_string())?,
+                request
+                    .title
+                    .clone()
+                    .ok_or_else(|| "Unknown market; title is required".to_string())?,
+                request
+                    .category
+                    .clone()
+                    .unwrap_or_else(|| "General".to_string()),
+                request
+                    .outcome
+                    .clone()
+                    .unwrap_or_else(|| format!("Outcome {}", request.outcome_index + 1)),
+                request.price,
+            ),
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        let mut positions = self.positions.write().await;
+        let position = match positions.iter_mut().find(|p| {
+            p.status == PredictionPositionStatus::Open
+                && p.market_id == request.market_id
+                && p.outcome_index == request.outcome_index
+        }) {
+            Some(existing) => {
+                let shares = existing.shares + request.shares;
+                existing.avg_price =
+                    (existing.cost_basis() + request.shares * request.price) / shares;
+                existing.shares = shares;
+                existing.current_price = current_price;
+                existing.updated_at = now;
+                existing.clone()
+            }
+            None => {
+                let position = PredictionPosition {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    market_id: request.market_id,
+                    venue,
+                    title,
+                    category,
+                    outcome_index: request.outcome_index,
+                    outcome,
+                    shares: request.shares,
+                    avg_price: request.price,
+                    current_price,
+                    status: PredictionPositionStatus::Open,
+                    realized_pnl: 0.0,
+                    winning_outcome: None,
+                    expected_payout: None,
+                    received_payout: None,
+                    opened_at: now,
+                    updated_at: now,
+                    settled_at: None,
+                };
+                positions.push(position.clone());
+                position
+            }
+        };
+        self.save_positions(&positions)?;
+        Ok(position)
+    }
+
+    /// Sells `shares` of an open position at `price`, closing it once none
+    /// are left.
+    pub async fn close_position(
+        &self,
+        position_id: &str,
+        shares: f64,
+        price: f64,
+    ) -> Result<PredictionPosition, String> {
+        let mut positions = self.positions.write().await;
+        let position = positions
+            .iter_mut()
+            .find(|p| p.id == position_id)
+            .ok_or_else(|| "Position not found".to_string())?;
+        if position.status != PredictionPositionStatus::Open {
+            return Err("Position is no longer open".to_string());
+        }
+        if shares <= 0.0 || shares > position.shares + f64::EPSILON {
+            return Err("Shares must be between 0 and the position size".to_string());
+        }
+
+        let sold = shares.min(position.shares);
+        position.realized_pnl += sold * (price - position.avg_price);
+        position.shares -= sold;
+        position.updated_at = chrono::Utc::now().timestamp();
+        if position.shares <= f64::EPSILON {
+            position.shares = 0.0;
+            position.status = PredictionPositionStatus::Closed;
+        }
+        let position = position.clone();
+        self.save_positions(&positions)?;
+        Ok(position)
+    }
+
+    pub async fn get_positions(
+        &self,
+        category: Option<&str>,
+        status: Option<PredictionPositionStatus>,
+    ) -> Vec<PredictionPosition> {
+        self.positions
+            .read()
+            .await
+            .iter()
+            .filter(|p| category.map_or(true, |c| p.category.eq_ignore_ascii_case(c)))
+            .filter(|p| status.map_or(true, |s| p.status == s))
+            .cloned()
+            .collect()
+    }
+
+    /// Pulls current outcome prices for open positions and settles those
+    /// whose markets have resolved.
+    pub async fn sync_positions(&self, use_mock: bool) -> Result<PredictionSyncResult, String> {
+        let markets = self.fetch_all_markets(use_mock).await?;
+        let now = chrono::Utc::now().timestamp();
+        let mut positions = self.positions.write().await;
+        let mut result = PredictionSyncResult {
+            updated: 0,
+            settled: Vec::new(),
+        };
+
+        for position in positions
+            .iter_mut()
+            .filter(|p| p.status == PredictionPositionStatus::Open)
+        {
+            let Some(market) = markets.iter().find(|m| m.id == position.market_id) else {
+                continue;
+            };
+            result.updated += 1;
+            if apply_market_update(position, market, now) {
+                result.settled.push(position.clone());
+            }
+        }
+
+        if result.updated > 0 {
+            self.save_positions(&positions)?;
+        }
+        Ok(result)
+    }
+
+    /// Records what the venue actually paid for a settled position and
+    /// compares it with what the position was owed.
+    pub async fn reconcile_settlement(
+        &self,
+        position_id: &str,
+        received_payout: f64,
+    ) -> Result<SettlementReconciliation, String> {
+        let mut positions = self.positions.write().await;
+        let position = positions
+            .iter_mut()
+            .find(|p| p.id == position_id)
+            .ok_or_else(|| "Position not found".to_string())?;
+        let expected_payout = match (position.status, position.expected_payout) {
+            (PredictionPositionStatus::Settled, Some(expected)) => expected,
+            _ => return Err("Position has not settled".to_string()),
+        };
+
+        // Realized P&L follows the cash actually received.
+        position.realized_pnl +=
+            received_payout - position.received_payout.unwrap_or(expected_payout);
+        position.received_payout = Some(received_payout);
+        position.updated_at = chrono::Utc::now().timestamp();
+        let discrepancy = received_payout - expected_payout;
+        let reconciliation = SettlementReconciliation {
+            position_id: position.id.clone(),
+            expected_payout,
+            received_payout,
+            discrepancy,
+            matched: discrepancy.abs() < RECONCILE_TOLERANCE,
+        };
+        self.save_positions(&positions)?;
+        Ok(reconciliation)
+    }
+
+    pub async fn pnl_summary(&self, category: Option<&str>) -> PredictionPnlSummary {
+        summarize_prediction_pnl(&self.positions.read().await, category)
+    }
+
     pub async fn calculate_consensus(
         &self,
         market_id: &str,
@@ -446,3 +983,164 @@ pub async fn record_prediction_performance(
     let svc = service.read().await;
     svc.record_performance(performance).await
 }
+
+#[tauri::command]
+pub async fn record_prediction_position(
+    request: RecordPredictionPositionRequest,
+    use_mock: bool,
+    service: tauri::State<'_, SharedPredictionMarketService>,
+) -> Result<PredictionPosition, String> {
+    let svc = service.read().await;
+    svc.record_position(request, use_mock).await
+}
+
+#[tauri::command]
+pub async fn close_prediction_position(
+    position_id: String,
+    shares: f64,
+    price: f64,
+    service: tauri::State<'_, SharedPredictionMarketService>,
+) -> Result<PredictionPosition, String> {
+    let svc = service.read().await;
+    svc.close_position(&position_id, shares, price).await
+}
+
+#[tauri::command]
+pub async fn get_prediction_positions(
+    category: Option<String>,
+    status: Option<PredictionPositionStatus>,
+    service: tauri::State<'_, SharedPredictionMarketService>,
+) -> Result<Vec<PredictionPosition>, String> {
+    let svc = service.read().await;
+    Ok(svc.get_positions(category.as_deref(), status).await)
+}
+
+#[tauri::command]
+pub async fn sync_prediction_positions(
+    use_mock: bool,
+    app_handle: AppHandle,
+    service: tauri::State<'_, SharedPredictionMarketService>,
+) -> Result<PredictionSyncResult, String> {
+    let svc = service.read().await;
+    let result = svc.sync_positions(use_mock).await?;
+    for position in &result.settled {
+        let _ = app_handle.emit("prediction_position_settled", position);
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn reconcile_prediction_settlement(
+    position_id: String,
+    received_payout: f64,
+    service: tauri::State<'_, SharedPredictionMarketService>,
+) -> Result<SettlementReconciliation, String> {
+    let svc = service.read().await;
+    svc.reconcile_settlement(&position_id, received_payout)
+        .await
+}
+
+#[tauri::command]
+pub async fn get_prediction_pnl(
+    category: Option<String>,
+    service: tauri::State<'_, SharedPredictionMarketService>,
+) -> Result<PredictionPnlSummary, String> {
+    let svc = service.read().await;
+    Ok(svc.pnl_summary(category.as_deref()).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(resolved: bool, prices: Vec<f64>, winner: Option<usize>) -> PredictionMarket {
+        PredictionMarket {
+            id: "polymarket_1".to_string(),
+            source: "polymarket".to_string(),
+            title: "Will SOL close above $300?".to_string(),
+            description: String::new(),
+            category: "Crypto".to_string(),
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            outcome_prices: prices,
+            volume_24h: 0.0,
+            total_volume: 0.0,
+            liquidity: 0.0,
+            created_at: None,
+            end_date: None,
+            resolved,
+            winning_outcome: winner,
+            tags: vec![],
+            image_url: None,
+        }
+    }
+
+    fn position(category: &str, outcome_index: usize) -> PredictionPosition {
+        PredictionPosition {
+            id: format!("{category}-{outcome_index}"),
+            market_id: "polymarket_1".to_string(),
+            venue: "polymarket".to_string(),
+            title: "Will SOL close above $300?".to_string(),
+            category: category.to_string(),
+            outcome_index,
+            outcome: "Yes".to_string(),
+            shares: 100.0,
+            avg_price: 0.4,
+            current_price: 0.4,
+            status: PredictionPositionStatus::Open,
+            realized_pnl: 0.0,
+            winning_outcome: None,
+            expected_payout: None,
+            received_payout: None,
+            opened_at: 0,
+            updated_at: 0,
+            settled_at: None,
+        }
+    }
+
+    #[test]
+    fn closed_market_settles_from_prices() {
+        let mut yes = position("Crypto", 0);
+        assert!(!apply_market_update(
+            &mut yes,
+            &market(false, vec![0.55, 0.45], None),
+            1
+        ));
+        assert_eq!(yes.current_price, 0.55);
+
+        assert!(apply_market_update(
+            &mut yes,
+            &market(true, vec![0.995, 0.005], None),
+            2
+        ));
+        assert_eq!(yes.status, PredictionPositionStatus::Settled);
+        assert_eq!(yes.expected_payout, Some(100.0));
+        assert!((yes.realized_pnl - 60.0).abs() < 1e-9);
+
+        let mut no = position("Crypto", 1);
+        assert!(apply_market_update(
+            &mut no,
+            &market(true, vec![0.5, 0.5], Some(0)),
+            2
+        ));
+        assert!((no.realized_pnl + 40.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pnl_summary_filters_by_category() {
+        let mut settled = position("Crypto", 0);
+        apply_market_update(&mut settled, &market(true, vec![1.0, 0.0], Some(0)), 1);
+        let mut open = position("Politics", 0);
+        open.current_price = 0.5;
+        let positions = vec![settled, open];
+
+        let all = summarize_prediction_pnl(&positions, None);
+        assert_eq!(all.by_category.len(), 2);
+        assert!((all.total_pnl - 70.0).abs() < 1e-9);
+        assert_eq!(all.unreconciled_settlements, 1);
+
+        let politics = summarize_prediction_pnl(&positions, Some("politics"));
+        assert_eq!(politics.open_positions, 1);
+        assert!((politics.unrealized_pnl - 10.0).abs() < 1e-9);
+        assert_eq!(politics.realized_pnl, 0.0);
+    }
+}
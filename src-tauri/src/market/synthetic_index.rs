@@ -0,0 +1,843 @@
+//! User-defined token baskets tracked as synthetic instruments.
+//!
+//! An index holds fixed units of each constituent, sized from the target
+//! weights at the moment it was created or last rebalanced. Its value is
+//! the sum of units times price, sampled hourly, so it drifts away from the
+//! target weights as prices move until it is rebalanced. On creation the
+//! same units are priced over the past month to give the chart a history.
+//!
+//! Each sample is also checked against price alerts under the index symbol,
+//! so a basket can be alerted on and charted like any single token.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::birdeye_history::hourly_price_history;
+use super::PricePoint;
+use crate::alerts::SharedAlertManager;
+use crate::api_config::stored_birdeye_key;
+use crate::profiles::ProfilePaths;
+use crate::security::keystore::Keystore;
+use crate::wallet::history_backfill::PriceOracle;
+
+const INDEX_FILE: &str = "synthetic_indexes.json";
+const CHECK_INTERVAL: StdDuration = StdDuration::from_secs(15 * 60);
+const BACKFILL_DAYS: i64 = 30;
+const MAX_POINTS: usize = 24 * 180;
+const HOURS_PER_YEAR: f64 = 24.0 * 365.0;
+
+pub const SYNTHETIC_INDEX_UPDATED_EVENT: &str = "synthetic_index_updated";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexConstituent {
+    pub mint: String,
+    pub symbol: String,
+    /// Target weight; weights are normalized to sum to one.
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexPoint {
+    pub timestamp: i64,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyntheticIndex {
+    pub id: String,
+    pub name: String,
+    /// Symbol alerts and charts refer to the index by, e.g. `IDX:AI`.
+    pub symbol: String,
+    pub description: Option<String>,
+    pub constituents: Vec<IndexConstituent>,
+    /// Units of each constituent held, by mint.
+    pub units: HashMap<String, f64>,
+    pub base_value: f64,
+    /// Weight drift in percentage points before a rebalance is suggested.
+    pub rebalance_threshold_pct: f64,
+    pub last_prices: HashMap<String, f64>,
+    pub series: Vec<IndexPoint>,
+    pub created_at: DateTime<Utc>,
+    pub rebalanced_at: DateTime<Utc>,
+}
+
+impl SyntheticIndex {
+    /// Value at the given prices, or `None` if a constituent is unpriced.
+    pub fn value_at(&self, prices: &HashMap<String, f64>) -> Option<f64> {
+        self.units
+            .iter()
+            .map(|(mint, units)| prices.get(mint).map(|price| units * price))
+            .sum()
+    }
+
+    pub fn current_value(&self) -> Option<f64> {
+        self.series.last().map(|point| point.value)
+    }
+
+    /// Resizes the units so each constituent is at its target weight of
+    /// `value` at `prices`.
+    fn set_units(&mut self, prices: &HashMap<String, f64>, value: f64) -> Result<(), String> {
+        let mut units = HashMap::new();
+        for constituent in &self.constituents {
+            let price = prices
+                .get(&constituent.mint)
+                .filter(|price| **price > 0.0)
+                .ok_or_else(|| format!("No price for {}", constituent.symbol))?;
+            units.insert(constituent.mint.clone(), constituent.weight * value / price);
+        }
+        self.units = units;
+        Ok(())
+    }
+
+    fn push_point(&mut self, point: IndexPoint) {
+        let hour = point.timestamp / 3600;
+        if let Some(last) = self.series.last_mut() {
+            if last.timestamp / 3600 == hour {
+                *last = point;
+                return;
+            }
+        }
+        self.series.push(point);
+        if self.series.len() > MAX_POINTS {
+            let excess = self.series.len() - MAX_POINTS;
+            self.series.drain(..excess);
+        }
+    }
+
+    fn value_hours_ago(&self, hours: i64) -> Option<f64> {
+        let last = self.series.last()?;
+        let cutoff = last.timestamp - hours * 3600;
+        self.series
+            .iter()
+            .rev()
+            .find(|point| point.timestamp <= cutoff)
+            .map(|point| point.value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSyntheticIndexRequest {
+    pub name: String,
+    pub symbol: String,
+    pub description: Option<String>,
+    pub constituents: Vec<IndexConstituent>,
+    pub base_value: Option<f64>,
+    pub rebalance_threshold_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSyntheticIndexRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// New target basket; the units are resized at the current value.
+    pub constituents: Option<Vec<IndexConstituent>>,
+    pub rebalance_threshold_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexPerformance {
+    pub current_value: f64,
+    pub change_24h_pct: Option<f64>,
+    pub change_7d_pct: Option<f64>,
+    pub change_30d_pct: Option<f64>,
+    pub since_start_pct: Option<f64>,
+    /// Annualized from hourly log returns.
+    pub volatility_pct: Option<f64>,
+    pub max_drawdown_pct: f64,
+    pub points: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebalanceLeg {
+    pub mint: String,
+    pub symbol: String,
+    pub target_weight: f64,
+    pub current_weight: f64,
+    /// Current minus target weight, in percentage points.
+    pub drift_pct: f64,
+    /// Positive to buy, negative to sell, sized to the notional.
+    pub trade_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebalanceSuggestion {
+    pub index_id: String,
+    pub notional_usd: f64,
+    pub max_drift_pct: f64,
+    pub needs_rebalance: bool,
+    pub legs: Vec<RebalanceLeg>,
+}
+
+pub fn normalize_constituents(
+    constituents: Vec<IndexConstituent>,
+) -> Result<Vec<IndexConstituent>, String> {
+    if constituents.is_empty() {
+        return Err("An index needs at least one constituent".into());
+    }
+    let mut seen = HashSet::new();
+    for constituent in &constituents {
+        if !constituent.weight.is_finite() || constituent.weight <= 0.0 {
+            return Err(format!(
+                "Weight for {} must be positive",
+                constituent.symbol
+            ));
+        }
+        if !seen.insert(constituent.mint.as_str()) {
+            return Err(format!("{} is listed more than once", constituent.symbol));
+        }
+    }
+    let total: f64 = constituents.iter().map(|c| c.weight).sum();
+    Ok(constituents
+        .into_iter()
+        .map(|constituent| IndexConstituent {
+            weight: constituent.weight / total,
+            ..constituent
+        })
+        .collect())
+}
+
+pub fn index_performance(series: &[IndexPoint]) -> IndexPerformance {
+    let Some(last) = series.last() else {
+        return IndexPerformance::default();
+    };
+    let change_since = |hours: i64| {
+        let cutoff = last.timestamp - hours * 3600;
+        series
+            .iter()
+            .rev()
+            .find(|point| point.timestamp <= cutoff)
+            .filter(|point| point.value > 0.0)
+            .map(|point| (last.value / point.value - 1.0) * 100.0)
+    };
+
+    let returns: Vec<f64> = series
+        .windows(2)
+        .filter(|pair| pair[0].value > 0.0 && pair[1].value > 0.0)
+        .map(|pair| (pair[1].value / pair[0].value).ln())
+        .collect();
+    let volatility_pct = (returns.len() >= 2).then(|| {
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance =
+            returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+        variance.sqrt() * HOURS_PER_YEAR.sqrt() * 100.0
+    });
+
+    let mut peak = f64::MIN;
+    let mut max_drawdown_pct: f64 = 0.0;
+    for point in series {
+        peak = peak.max(point.value);
+        if peak > 0.0 {
+            max_drawdown_pct = max_drawdown_pct.max((peak - point.value) / peak * 100.0);
+        }
+    }
+
+    IndexPerformance {
+        current_value: last.value,
+        change_24h_pct: change_since(24),
+        change_7d_pct: change_since(24 * 7),
+        change_30d_pct: change_since(24 * 30),
+        since_start_pct: series
+            .first()
+            .filter(|first| first.value > 0.0)
+            .map(|first| (last.value / first.value - 1.0) * 100.0),
+        volatility_pct,
+        max_drawdown_pct,
+        points: series.len(),
+    }
+}
+
+/// Trades that bring the basket back to its target weights, scaled to a
+/// position of `notional_usd` in the index.
+pub fn rebalance_suggestion(
+    index: &SyntheticIndex,
+    notional_usd: f64,
+) -> Result<RebalanceSuggestion, String> {
+    let value = index
+        .value_at(&index.last_prices)
+        .filter(|value| *value > 0.0)
+        .ok_or_else(|| format!("{} has not been priced yet", index.symbol))?;
+    let legs: Vec<RebalanceLeg> = index
+        .constituents
+        .iter()
+        .map(|constituent| {
+            let held = index.units.get(&constituent.mint).copied().unwrap_or(0.0)
+                * index
+                    .last_prices
+                    .get(&constituent.mint)
+                    .copied()
+                    .unwrap_or(0.0);
+            let current_weight = held / value;
+            RebalanceLeg {
+                mint: constituent.mint.clone(),
+                symbol: constituent.symbol.clone(),
+                target_weight: constituent.weight,
+                current_weight,
+                drift_pct: (current_weight - constituent.weight) * 100.0,
+                trade_usd: (constituent.weight - current_weight) * notional_usd,
+            }
+        })
+        .collect();
+    let max_drift_pct = legs
+        .iter()
+        .map(|leg| leg.drift_pct.abs())
+        .fold(0.0, f64::max);
+    Ok(RebalanceSuggestion {
+        index_id: index.id.clone(),
+        notional_usd,
+        max_drift_pct,
+        needs_rebalance: max_drift_pct >= index.rebalance_threshold_pct,
+        legs,
+    })
+}
+
+/// Hourly candles over the window, one per sample: each opens at the
+/// previous sample and closes at its own.
+pub fn index_candles(series: &[IndexPoint], hours: i64) -> Vec<PricePoint> {
+    let Some(last) = series.last() else {
+        return Vec::new();
+    };
+    let cutoff = last.timestamp - hours * 3600;
+    let start = series
+        .iter()
+        .position(|point| point.timestamp > cutoff)
+        .unwrap_or(series.len());
+    (start..series.len())
+        .map(|i| {
+            let close = series[i].value;
+            let open = if i > 0 { series[i - 1].value } else { close };
+            PricePoint {
+                timestamp: series[i].timestamp,
+                open,
+                high: open.max(close),
+                low: open.min(close),
+                close,
+                volume: 0.0,
+            }
+        })
+        .collect()
+}
+
+async fn current_prices(
+    oracle: &mut PriceOracle,
+    constituents: &[IndexConstituent],
+) -> Result<HashMap<String, f64>, String> {
+    let now = Utc::now();
+    let mut prices = HashMap::new();
+    for constituent in constituents {
+        let price = oracle
+            .price_at(&constituent.mint, now)
+            .await
+            .ok_or_else(|| format!("No price for {}", constituent.symbol))?;
+        prices.insert(constituent.mint.clone(), price);
+    }
+    Ok(prices)
+}
+
+/// Prices the index's units over the past month, keeping only the hours
+/// where every constituent has a price.
+async fn backfill_series(index: &SyntheticIndex, api_key: &str) -> Vec<IndexPoint> {
+    let client = reqwest::Client::new();
+    let to = Utc::now().timestamp();
+    let from = to - BACKFILL_DAYS * 24 * 3600;
+    let mut histories = Vec::new();
+    for constituent in &index.constituents {
+        match hourly_price_history(&client, api_key, &constituent.mint, from, to).await {
+            Ok(history) => {
+                let by_hour: BTreeMap<i64, f64> = history
+                    .into_iter()
+                    .map(|(time, price)| (time / 3600, price))
+                    .collect();
+                histories.push((constituent.mint.clone(), by_hour));
+            }
+            Err(e) => {
+                eprintln!("Index backfill failed for {}: {}", constituent.symbol, e);
+                return Vec::new();
+            }
+        }
+    }
+    let Some((_, first)) = histories.first() else {
+        return Vec::new();
+    };
+    first
+        .keys()
+        .filter_map(|hour| {
+            let prices: HashMap<String, f64> = histories
+                .iter()
+                .map(|(mint, history)| history.get(hour).map(|price| (mint.clone(), *price)))
+                .collect::<Option<_>>()?;
+            Some(IndexPoint {
+                timestamp: hour * 3600,
+                value: index.value_at(&prices)?,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct IndexFile {
+    indexes: Vec<SyntheticIndex>,
+}
+
+pub struct SyntheticIndexTracker {
+    indexes: Vec<SyntheticIndex>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedSyntheticIndexTracker = Arc<RwLock<SyntheticIndexTracker>>;
+
+impl SyntheticIndexTracker {
+    pub fn new(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(INDEX_FILE));
+        let file: IndexFile = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            indexes: file.indexes,
+            path,
+        }
+    }
+
+    pub fn list(&self) -> &[SyntheticIndex] {
+        &self.indexes
+    }
+
+    pub fn get(&self, id: &str) -> Result<&SyntheticIndex, String> {
+        self.indexes
+            .iter()
+            .find(|index| index.id == id)
+            .ok_or_else(|| format!("Synthetic index {} not found", id))
+    }
+
+    fn get_mut(&mut self, id: &str) -> Result<&mut SyntheticIndex, String> {
+        self.indexes
+            .iter_mut()
+            .find(|index| index.id == id)
+            .ok_or_else(|| format!("Synthetic index {} not found", id))
+    }
+
+    fn insert(&mut self, index: SyntheticIndex) -> Result<(), String> {
+        if self
+            .indexes
+            .iter()
+            .any(|existing| existing.symbol.eq_ignore_ascii_case(&index.symbol))
+        {
+            return Err(format!(
+                "An index with symbol {} already exists",
+                index.symbol
+            ));
+        }
+        self.indexes.push(index);
+        self.save();
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &str) -> Result<(), String> {
+        let before = self.indexes.len();
+        self.indexes.retain(|index| index.id != id);
+        if self.indexes.len() == before {
+            return Err(format!("Synthetic index {} not found", id));
+        }
+        self.save();
+        Ok(())
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let file = IndexFile {
+            indexes: self.indexes.clone(),
+        };
+        match serde_json::to_string_pretty(&file) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(path, contents) {
+                    eprintln!("Failed to save synthetic indexes: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize synthetic indexes: {}", e),
+        }
+    }
+}
+
+fn birdeye_key(app: &AppHandle) -> Option<String> {
+    app.try_state::<Keystore>()
+        .and_then(|keystore| stored_birdeye_key(&keystore))
+}
+
+/// Prices one index now, records the sample and checks alerts on it.
+async fn sample_index(
+    app: &AppHandle,
+    tracker: &SharedSyntheticIndexTracker,
+    oracle: &mut PriceOracle,
+    id: &str,
+) -> Result<SyntheticIndex, String> {
+    let constituents = tracker.read().await.get(id)?.constituents.clone();
+    let prices = current_prices(oracle, &constituents).await?;
+
+    let index = {
+        let mut tracker = tracker.write().await;
+        let index = tracker.get_mut(id)?;
+        let value = index
+            .value_at(&prices)
+            .ok_or_else(|| format!("{} could not be priced", index.symbol))?;
+        index.last_prices = prices;
+        index.push_point(IndexPoint {
+            timestamp: Utc::now().timestamp(),
+            value,
+        });
+        let index = index.clone();
+        tracker.save();
+        index
+    };
+
+    if let (Some(alerts), Some(value)) =
+        (app.try_state::<SharedAlertManager>(), index.current_value())
+    {
+        if let Err(e) = alerts
+            .read()
+            .await
+            .check_and_trigger_alerts(&index.symbol, value, index.value_hours_ago(24), None)
+            .await
+        {
+            eprintln!("Alert check failed for {}: {}", index.symbol, e);
+        }
+    }
+    let _ = app.emit(SYNTHETIC_INDEX_UPDATED_EVENT, &index);
+    Ok(index)
+}
+
+pub fn start_synthetic_index_tracker(app: AppHandle, tracker: SharedSyntheticIndexTracker) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let hour = Utc::now().timestamp() / 3600;
+            let due: Vec<String> = tracker
+                .read()
+                .await
+                .list()
+                .iter()
+                .filter(|index| {
+                    index
+                        .series
+                        .last()
+                        .map_or(true, |point| point.timestamp / 3600 < hour)
+                })
+                .map(|index| index.id.clone())
+                .collect();
+            if due.is_empty() {
+                continue;
+            }
+            let mut oracle = PriceOracle::new(birdeye_key(&app));
+            for id in due {
+                if let Err(e) = sample_index(&app, &tracker, &mut oracle, &id).await {
+                    eprintln!("Synthetic index update failed: {}", e);
+                }
+            }
+        }
+    });
+}
+
+fn validate_threshold(threshold: f64) -> Result<f64, String> {
+    if threshold.is_nan() || threshold <= 0.0 || threshold > 100.0 {
+        return Err("Rebalance threshold must be between 0 and 100".into());
+    }
+    Ok(threshold)
+}
+
+#[tauri::command]
+pub async fn synthetic_index_list(
+    tracker: State<'_, SharedSyntheticIndexTracker>,
+) -> Result<Vec<SyntheticIndex>, String> {
+    Ok(tracker.read().await.list().to_vec())
+}
+
+#[tauri::command]
+pub async fn synthetic_index_get(
+    id: String,
+    tracker: State<'_, SharedSyntheticIndexTracker>,
+) -> Result<SyntheticIndex, String> {
+    tracker.read().await.get(&id).cloned()
+}
+
+#[tauri::command]
+pub async fn synthetic_index_create(
+    app: AppHandle,
+    request: CreateSyntheticIndexRequest,
+    tracker: State<'_, SharedSyntheticIndexTracker>,
+) -> Result<SyntheticIndex, String> {
+    let symbol = request.symbol.trim().to_uppercase();
+    if request.name.trim().is_empty() || symbol.is_empty() {
+        return Err("An index needs a name and a symbol".into());
+    }
+    let base_value = request.base_value.unwrap_or(100.0);
+    if !base_value.is_finite() || base_value <= 0.0 {
+        return Err("Base value must be positive".into());
+    }
+    let constituents = normalize_constituents(request.constituents)?;
+    let api_key = birdeye_key(&app);
+    let mut oracle = PriceOracle::new(api_key.clone());
+    let prices = current_prices(&mut oracle, &constituents).await?;
+
+    let now = Utc::now();
+    let mut index = SyntheticIndex {
+        id: Uuid::new_v4().to_string(),
+        name: request.name.trim().to_string(),
+        symbol,
+        description: request.description,
+        constituents,
+        units: HashMap::new(),
+        base_value,
+        rebalance_threshold_pct: validate_threshold(
+            request.rebalance_threshold_pct.unwrap_or(5.0),
+        )?,
+        last_prices: HashMap::new(),
+        series: Vec::new(),
+        created_at: now,
+        rebalanced_at: now,
+    };
+    index.set_units(&prices, base_value)?;
+    if let Some(api_key) = &api_key {
+        index.series = backfill_series(&index, api_key).await;
+    }
+    index.last_prices = prices;
+    index.push_point(IndexPoint {
+        timestamp: now.timestamp(),
+        value: base_value,
+    });
+
+    tracker.write().await.insert(index.clone())?;
+    Ok(index)
+}
+
+#[tauri::command]
+pub async fn synthetic_index_update(
+    app: AppHandle,
+    id: String,
+    request: UpdateSyntheticIndexRequest,
+    tracker: State<'_, SharedSyntheticIndexTracker>,
+) -> Result<SyntheticIndex, String> {
+    let constituents = request
+        .constituents
+        .map(normalize_constituents)
+        .transpose()?;
+    let threshold = request
+        .rebalance_threshold_pct
+        .map(validate_threshold)
+        .transpose()?;
+    let prices = match &constituents {
+        Some(constituents) => {
+            let mut oracle = PriceOracle::new(birdeye_key(&app));
+            Some(current_prices(&mut oracle, constituents).await?)
+        }
+        None => None,
+    };
+
+    let mut tracker = tracker.write().await;
+    let index = tracker.get_mut(&id)?;
+    if let Some(name) = request.name.filter(|name| !name.trim().is_empty()) {
+        index.name = name.trim().to_string();
+    }
+    if request.description.is_some() {
+        index.description = request.description;
+    }
+    if let Some(threshold) = threshold {
+        index.rebalance_threshold_pct = threshold;
+    }
+    if let (Some(constituents), Some(prices)) = (constituents, prices) {
+        let value = index
+            .value_at(&prices)
+            .or_else(|| index.current_value())
+            .unwrap_or(index.base_value);
+        index.constituents = constituents;
+        index.set_units(&prices, value)?;
+        index.last_prices = prices;
+        index.rebalanced_at = Utc::now();
+    }
+    let index = index.clone();
+    tracker.save();
+    Ok(index)
+}
+
+#[tauri::command]
+pub async fn synthetic_index_delete(
+    id: String,
+    tracker: State<'_, SharedSyntheticIndexTracker>,
+) -> Result<(), String> {
+    tracker.write().await.remove(&id)
+}
+
+#[tauri::command]
+pub async fn synthetic_index_refresh(
+    app: AppHandle,
+    id: String,
+    tracker: State<'_, SharedSyntheticIndexTracker>,
+) -> Result<SyntheticIndex, String> {
+    let mut oracle = PriceOracle::new(birdeye_key(&app));
+    sample_index(&app, tracker.inner(), &mut oracle, &id).await
+}
+
+#[tauri::command]
+pub async fn synthetic_index_performance(
+    id: String,
+    tracker: State<'_, SharedSyntheticIndexTracker>,
+) -> Result<IndexPerformance, String> {
+    Ok(index_performance(&tracker.read().await.get(&id)?.series))
+}
+
+/// Chart data in the same shape and timeframes as `get_price_history`.
+#[tauri::command]
+pub async fn synthetic_index_history(
+    id: String,
+    timeframe: String,
+    tracker: State<'_, SharedSyntheticIndexTracker>,
+) -> Result<Vec<PricePoint>, String> {
+    let hours = match timeframe.as_str() {
+        "1H" => 1,
+        "4H" => 4,
+        "1D" => 24,
+        "1W" => 168,
+        "1M" => 720,
+        _ => 24,
+    };
+    Ok(index_candles(&tracker.read().await.get(&id)?.series, hours))
+}
+
+#[tauri::command]
+pub async fn synthetic_index_rebalance_suggestions(
+    id: String,
+    notional_usd: Option<f64>,
+    tracker: State<'_, SharedSyntheticIndexTracker>,
+) -> Result<RebalanceSuggestion, String> {
+    let tracker = tracker.read().await;
+    let index = tracker.get(&id)?;
+    let notional = notional_usd
+        .or_else(|| index.current_value())
+        .unwrap_or(index.base_value);
+    rebalance_suggestion(index, notional)
+}
+
+/// Resets the units to the target weights at the last sampled prices; the
+/// index value is unchanged.
+#[tauri::command]
+pub async fn synthetic_index_rebalance(
+    id: String,
+    tracker: State<'_, SharedSyntheticIndexTracker>,
+) -> Result<SyntheticIndex, String> {
+    let mut tracker = tracker.write().await;
+    let index = tracker.get_mut(&id)?;
+    let prices = index.last_prices.clone();
+    let value = index
+        .value_at(&prices)
+        .ok_or_else(|| format!("{} has not been priced yet", index.symbol))?;
+    index.set_units(&prices, value)?;
+    index.rebalanced_at = Utc::now();
+    let index = index.clone();
+    tracker.save();
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(prices: &[(&str, f64)], weights: &[(&str, f64)]) -> SyntheticIndex {
+        let now = Utc::now();
+        let mut index = SyntheticIndex {
+            id: "idx".into(),
+            name: "Test".into(),
+            symbol: "IDX:TEST".into(),
+            description: None,
+            constituents: normalize_constituents(
+                weights
+                    .iter()
+                    .map(|(mint, weight)| IndexConstituent {
+                        mint: mint.to_string(),
+                        symbol: mint.to_uppercase(),
+                        weight: *weight,
+                    })
+                    .collect(),
+            )
+            .unwrap(),
+            units: HashMap::new(),
+            base_value: 100.0,
+            rebalance_threshold_pct: 5.0,
+            last_prices: HashMap::new(),
+            series: Vec::new(),
+            created_at: now,
+            rebalanced_at: now,
+        };
+        let prices: HashMap<String, f64> =
+            prices.iter().map(|(m, p)| (m.to_string(), *p)).collect();
+        index.set_units(&prices, 100.0).unwrap();
+        index.last_prices = prices;
+        index
+    }
+
+    #[test]
+    fn drifted_basket_suggests_trades_back_to_target() {
+        let mut basket = index(&[("a", 10.0), ("b", 2.0)], &[("a", 3.0), ("b", 1.0)]);
+        assert!((basket.units["a"] - 7.5).abs() < 1e-9);
+        assert!((basket.units["b"] - 12.5).abs() < 1e-9);
+
+        // A doubles: 150 + 25 = 175, so A is now ~85.7% against a 75% target.
+        basket.last_prices.insert("a".into(), 20.0);
+        let suggestion = rebalance_suggestion(&basket, 1_000.0).unwrap();
+        assert!(suggestion.needs_rebalance);
+        let a = suggestion.legs.iter().find(|leg| leg.mint == "a").unwrap();
+        assert!((a.current_weight - 150.0 / 175.0).abs() < 1e-9);
+        assert!(a.trade_usd < 0.0);
+        let net: f64 = suggestion.legs.iter().map(|leg| leg.trade_usd).sum();
+        assert!(net.abs() < 1e-9);
+
+        assert!(normalize_constituents(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn performance_covers_changes_drawdown_and_candles() {
+        let series: Vec<IndexPoint> = [100.0, 110.0, 88.0, 99.0]
+            .iter()
+            .enumerate()
+            .map(|(i, value)| IndexPoint {
+                timestamp: i as i64 * 12 * 3600,
+                value: *value,
+            })
+            .collect();
+
+        let performance = index_performance(&series);
+        assert_eq!(performance.current_value, 99.0);
+        assert!((performance.change_24h_pct.unwrap() + 10.0).abs() < 1e-9);
+        assert!((performance.since_start_pct.unwrap() + 1.0).abs() < 1e-9);
+        assert!((performance.max_drawdown_pct - 20.0).abs() < 1e-9);
+        assert!(performance.volatility_pct.unwrap() > 0.0);
+        assert!(performance.change_7d_pct.is_none());
+
+        let candles = index_candles(&series, 24);
+        assert_eq!(candles.len(), 2);
+        assert_eq!((candles[0].open, candles[0].close), (110.0, 88.0));
+        assert_eq!(candles[0].high, 110.0);
+    }
+}
@@ -0,0 +1,648 @@
+//! Token unlock and vesting calendar.
+//!
+//! Unlock events come from a configurable JSON feed or are entered by hand.
+//! Each unlock is sized against circulating supply, and large unlocks for
+//! tokens in a watchlist or in one of the user's wallets raise an alert a
+//! configurable number of days ahead, once per event.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::chains::SharedRpcPool;
+use crate::notifications::router::SharedNotificationRouter;
+use crate::portfolio::dust::fetch_holdings;
+use crate::portfolio::SharedWatchlistManager;
+use crate::profiles::ProfilePaths;
+use crate::wallet::flows::own_wallet_addresses;
+
+const UNLOCKS_FILE: &str = "token_unlocks.json";
+const CHECK_INTERVAL: StdDuration = StdDuration::from_secs(60 * 60);
+/// Past unlocks are kept this long for reference, then pruned.
+const PAST_RETENTION_DAYS: i64 = 30;
+pub const TOKEN_UNLOCK_ALERT_EVENT: &str = "token_unlock_alert";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UnlockSource {
+    Manual,
+    Feed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUnlockEvent {
+    pub id: String,
+    pub mint: String,
+    pub symbol: String,
+    /// Unix seconds.
+    pub unlock_at: i64,
+    /// Tokens released.
+    pub amount: f64,
+    #[serde(default)]
+    pub circulating_supply: Option<f64>,
+    #[serde(default)]
+    pub usd_value: Option<f64>,
+    /// Allocation the tokens come from, such as team or investors.
+    #[serde(default)]
+    pub category: Option<String>,
+    pub source: UnlockSource,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub alerted_at: Option<i64>,
+}
+
+impl TokenUnlockEvent {
+    /// Unlock size as a share of circulating supply.
+    pub fn supply_pct(&self) -> Option<f64> {
+        self.circulating_supply
+            .filter(|supply| *supply > 0.0)
+            .map(|supply| self.amount / supply * 100.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUnlockInput {
+    pub mint: String,
+    pub symbol: String,
+    pub unlock_at: i64,
+    pub amount: f64,
+    #[serde(default)]
+    pub circulating_supply: Option<f64>,
+    #[serde(default)]
+    pub usd_value: Option<f64>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TokenUnlockSettings {
+    /// JSON feed of unlock events; see `parse_unlock_feed` for the shapes
+    /// accepted.
+    pub feed_url: Option<String>,
+    pub feed_api_key: Option<String>,
+    pub alert_days_before: i64,
+    /// Unlocks at or above this share of circulating supply are large.
+    pub min_supply_pct: f64,
+    /// Unlocks worth at least this much are large regardless of supply.
+    pub min_usd_value: Option<f64>,
+}
+
+impl Default for TokenUnlockSettings {
+    fn default() -> Self {
+        Self {
+            feed_url: None,
+            feed_api_key: None,
+            alert_days_before: 7,
+            min_supply_pct: 1.0,
+            min_usd_value: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpcomingUnlock {
+    #[serde(flatten)]
+    pub event: TokenUnlockEvent,
+    pub supply_pct: Option<f64>,
+    pub days_until: f64,
+    pub is_large: bool,
+    /// The token is in a watchlist or a wallet.
+    pub relevant: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredUnlocks {
+    settings: TokenUnlockSettings,
+    events: Vec<TokenUnlockEvent>,
+}
+
+pub struct TokenUnlockTracker {
+    settings: TokenUnlockSettings,
+    events: Vec<TokenUnlockEvent>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedTokenUnlockTracker = Arc<RwLock<TokenUnlockTracker>>;
+
+impl TokenUnlockTracker {
+    pub fn new(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(UNLOCKS_FILE));
+        let stored: StoredUnlocks = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            settings: stored.settings,
+            events: stored.events,
+            path,
+        }
+    }
+
+    pub fn settings(&self) -> &TokenUnlockSettings {
+        &self.settings
+    }
+
+    pub fn update_settings(&mut self, settings: TokenUnlockSettings) {
+        self.settings = settings;
+        self.save();
+    }
+
+    pub fn add(&mut self, input: TokenUnlockInput) -> Result<TokenUnlockEvent, String> {
+        if input.mint.trim().is_empty() {
+            return Err("Token mint is required".into());
+        }
+        if input.amount <= 0.0 {
+            return Err("Unlock amount must be greater than zero".into());
+        }
+        let event = TokenUnlockEvent {
+            id: Uuid::new_v4().to_string(),
+            mint: input.mint.trim().to_string(),
+            symbol: input.symbol,
+            unlock_at: input.unlock_at,
+            amount: input.amount,
+            circulating_supply: input.circulating_supply,
+            usd_value: input.usd_value,
+            category: input.category,
+            source: UnlockSource::Manual,
+            notes: input.notes,
+            alerted_at: None,
+        };
+        self.events.push(event.clone());
+        self.save();
+        Ok(event)
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<(), String> {
+        let before = self.events.len();
+        self.events.retain(|event| event.id != id);
+        if self.events.len() == before {
+            return Err(format!("Unlock event {id} not found"));
+        }
+        self.save();
+        Ok(())
+    }
+
+    /// Merges feed events by id, keeping the alert state of ones already
+    /// known, and prunes unlocks that are long past.
+    pub fn merge_feed(&mut self, incoming: Vec<TokenUnlockEvent>, now: DateTime<Utc>) -> usize {
+        let mut added = 0;
+        for mut event in incoming {
+            match self
+                .events
+                .iter_mut()
+                .find(|existing| existing.id == event.id)
+            {
+                Some(existing) => {
+                    event.alerted_at = existing.alerted_at;
+                    *existing = event;
+                }
+                None => {
+                    self.events.push(event);
+                    added += 1;
+                }
+            }
+        }
+        let cutoff = (now - Duration::days(PAST_RETENTION_DAYS)).timestamp();
+        self.events.retain(|event| event.unlock_at >= cutoff);
+        self.save();
+        added
+    }
+
+    pub fn upcoming(
+        &self,
+        now: DateTime<Utc>,
+        days: i64,
+        relevant: &HashSet<String>,
+    ) -> Vec<UpcomingUnlock> {
+        let start = now.timestamp();
+        let end = (now + Duration::days(days)).timestamp();
+        let mut upcoming: Vec<UpcomingUnlock> = self
+            .events
+            .iter()
+            .filter(|event| event.unlock_at >= start && event.unlock_at <= end)
+            .map(|event| UpcomingUnlock {
+                supply_pct: event.supply_pct(),
+                days_until: (event.unlock_at - start) as f64 / 86_400.0,
+                is_large: self.is_large(event),
+                relevant: relevant.contains(&event.mint),
+                event: event.clone(),
+            })
+            .collect();
+        upcoming.sort_by_key(|unlock| unlock.event.unlock_at);
+        upcoming
+    }
+
+    fn is_large(&self, event: &TokenUnlockEvent) -> bool {
+        event
+            .supply_pct()
+            .is_some_and(|pct| pct >= self.settings.min_supply_pct)
+            || self
+                .settings
+                .min_usd_value
+                .zip(event.usd_value)
+                .is_some_and(|(min, value)| value >= min)
+    }
+
+    /// Large, relevant unlocks inside the alert window that have not been
+    /// alerted yet. They are marked as alerted.
+    pub fn take_due_alerts(
+        &mut self,
+        now: DateTime<Utc>,
+        relevant: &HashSet<String>,
+    ) -> Vec<UpcomingUnlock> {
+        let due: Vec<UpcomingUnlock> = self
+            .upcoming(now, self.settings.alert_days_before, relevant)
+            .into_iter()
+            .filter(|unlock| {
+                unlock.is_large && unlock.relevant && unlock.event.alerted_at.is_none()
+            })
+            .collect();
+        if due.is_empty() {
+            return due;
+        }
+        for event in self.events.iter_mut() {
+            if due.iter().any(|unlock| unlock.event.id == event.id) {
+                event.alerted_at = Some(now.timestamp());
+            }
+        }
+        self.save();
+        due
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let stored = StoredUnlocks {
+            settings: self.settings.clone(),
+            events: self.events.clone(),
+        };
+        match serde_json::to_string_pretty(&stored) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(path, contents) {
+                    eprintln!("Failed to save token unlocks: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize token unlocks: {}", e),
+        }
+    }
+}
+
+/// Reads a feed that is either an array of events or an object wrapping
+/// one under `data`, `unlocks` or `events`. Field names follow the common
+/// camelCase and snake_case spellings; dates may be unix seconds,
+/// milliseconds or RFC 3339. Entries without a mint, date or amount are
+/// skipped.
+pub fn parse_unlock_feed(body: &Value) -> Vec<TokenUnlockEvent> {
+    let items = body
+        .as_array()
+        .or_else(|| {
+            ["data", "unlocks", "events"]
+                .iter()
+                .find_map(|key| body[*key].as_array())
+        })
+        .cloned()
+        .unwrap_or_default();
+
+    items
+        .iter()
+        .filter_map(|item| {
+            let mint = first_str(item, &["mint", "address", "tokenAddress", "token_address"])?;
+            let unlock_at = first_field(
+                item,
+                &["unlockAt", "unlock_at", "unlockDate", "date", "timestamp"],
+            )
+            .and_then(parse_timestamp)?;
+            let amount =
+                first_number(item, &["amount", "unlockAmount", "unlock_amount", "tokens"])?;
+            Some(TokenUnlockEvent {
+                id: format!("feed:{mint}:{unlock_at}"),
+                symbol: first_str(item, &["symbol", "tokenSymbol"])
+                    .unwrap_or_else(|| mint.chars().take(6).collect()),
+                mint,
+                unlock_at,
+                amount,
+                circulating_supply: first_number(
+                    item,
+                    &["circulatingSupply", "circulating_supply"],
+                ),
+                usd_value: first_number(item, &["valueUsd", "usdValue", "usd_value", "value"]),
+                category: first_str(item, &["category", "allocation"]),
+                source: UnlockSource::Feed,
+                notes: None,
+                alerted_at: None,
+            })
+        })
+        .collect()
+}
+
+fn first_field<'a>(item: &'a Value, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter()
+        .map(|key| &item[*key])
+        .find(|value| !value.is_null())
+}
+
+fn first_str(item: &Value, keys: &[&str]) -> Option<String> {
+    first_field(item, keys)?
+        .as_str()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+fn first_number(item: &Value, keys: &[&str]) -> Option<f64> {
+    let value = first_field(item, keys)?;
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|text| text.parse().ok()))
+}
+
+fn parse_timestamp(value: &Value) -> Option<i64> {
+    if let Some(text) = value.as_str() {
+        if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+            return Some(date.timestamp());
+        }
+    }
+    let raw = value
+        .as_i64()
+        .or_else(|| value.as_str().and_then(|text| text.parse().ok()))?;
+    // Anything past the year 33658 in seconds is a millisecond timestamp.
+    Some(if raw > 1_000_000_000_000 {
+        raw / 1000
+    } else {
+        raw
+    })
+}
+
+async fn fetch_feed(settings: &TokenUnlockSettings) -> Result<Vec<TokenUnlockEvent>, String> {
+    let Some(url) = settings.feed_url.as_deref().filter(|url| !url.is_empty()) else {
+        return Ok(Vec::new());
+    };
+    let mut request = reqwest::Client::new()
+        .get(url)
+        .timeout(StdDuration::from_secs(20));
+    if let Some(key) = settings
+        .feed_api_key
+        .as_deref()
+        .filter(|key| !key.is_empty())
+    {
+        request = request.header("X-API-KEY", key);
+    }
+    let body: Value = request
+        .send()
+        .await
+        .map_err(|e| format!("Unlock feed request failed: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Unlock feed request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unlock feed parse failed: {}", e))?;
+    Ok(parse_unlock_feed(&body))
+}
+
+/// Mints in any watchlist or held by one of the user's wallets.
+async fn relevant_mints(app: &AppHandle) -> HashSet<String> {
+    let mut mints = HashSet::new();
+    if let Some(watchlists) = app.try_state::<SharedWatchlistManager>() {
+        match watchlists.read().await.list_watchlists().await {
+            Ok(lists) => mints.extend(
+                lists
+                    .into_iter()
+                    .flat_map(|list| list.items)
+                    .map(|item| item.mint),
+            ),
+            Err(e) => eprintln!("Failed to load watchlists for unlock alerts: {}", e),
+        }
+    }
+    if let Some(pool) = app.try_state::<SharedRpcPool>() {
+        for wallet in own_wallet_addresses(app) {
+            match fetch_holdings(pool.inner(), &wallet).await {
+                Ok(holdings) => mints.extend(
+                    holdings
+                        .into_iter()
+                        .filter(|holding| holding.amount > 0)
+                        .map(|holding| holding.mint),
+                ),
+                Err(e) => eprintln!(
+                    "Failed to load holdings of {} for unlock alerts: {}",
+                    wallet, e
+                ),
+            }
+        }
+    }
+    mints
+}
+
+/// Pulls the feed, then raises alerts for unlocks now inside the window.
+async fn refresh_and_alert(
+    app: &AppHandle,
+    tracker: &SharedTokenUnlockTracker,
+) -> Result<Vec<UpcomingUnlock>, String> {
+    let settings = tracker.read().await.settings().clone();
+    let incoming = fetch_feed(&settings).await?;
+    let now = Utc::now();
+    if !incoming.is_empty() {
+        tracker.write().await.merge_feed(incoming, now);
+    }
+
+    let relevant = relevant_mints(app).await;
+    let due = tracker.write().await.take_due_alerts(now, &relevant);
+    let router = app
+        .try_state::<SharedNotificationRouter>()
+        .map(|router| router.inner().clone());
+    for unlock in &due {
+        let _ = app.emit(TOKEN_UNLOCK_ALERT_EVENT, unlock);
+        let Some(router) = &router else {
+            continue;
+        };
+        let size = match unlock.supply_pct {
+            Some(pct) => format!("{:.2}% of circulating supply", pct),
+            None => format!("{:.0} tokens", unlock.event.amount),
+        };
+        let message = format!(
+            "{} unlocks {} in {:.1} days.",
+            unlock.event.symbol, size, unlock.days_until
+        );
+        if let Err(e) = router
+            .read()
+            .await
+            .send_text_notification(&unlock.event.id, "Large token unlock ahead", &message)
+            .await
+        {
+            eprintln!("Failed to send token unlock alert: {}", e);
+        }
+    }
+    Ok(due)
+}
+
+pub fn start_token_unlock_monitor(app: AppHandle, tracker: SharedTokenUnlockTracker) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = refresh_and_alert(&app, &tracker).await {
+                eprintln!("Token unlock monitor error: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_token_unlocks(
+    app: AppHandle,
+    days: Option<i64>,
+    relevant_only: Option<bool>,
+    tracker: State<'_, SharedTokenUnlockTracker>,
+) -> Result<Vec<UpcomingUnlock>, String> {
+    let relevant = relevant_mints(&app).await;
+    let mut unlocks =
+        tracker
+            .read()
+            .await
+            .upcoming(Utc::now(), days.unwrap_or(30).clamp(1, 365), &relevant);
+    if relevant_only.unwrap_or(false) {
+        unlocks.retain(|unlock| unlock.relevant);
+    }
+    Ok(unlocks)
+}
+
+#[tauri::command]
+pub async fn add_token_unlock(
+    input: TokenUnlockInput,
+    tracker: State<'_, SharedTokenUnlockTracker>,
+) -> Result<TokenUnlockEvent, String> {
+    tracker.write().await.add(input)
+}
+
+#[tauri::command]
+pub async fn remove_token_unlock(
+    id: String,
+    tracker: State<'_, SharedTokenUnlockTracker>,
+) -> Result<(), String> {
+    tracker.write().await.remove(&id)
+}
+
+#[tauri::command]
+pub async fn get_token_unlock_settings(
+    tracker: State<'_, SharedTokenUnlockTracker>,
+) -> Result<TokenUnlockSettings, String> {
+    Ok(tracker.read().await.settings().clone())
+}
+
+#[tauri::command]
+pub async fn update_token_unlock_settings(
+    settings: TokenUnlockSettings,
+    tracker: State<'_, SharedTokenUnlockTracker>,
+) -> Result<TokenUnlockSettings, String> {
+    if settings.alert_days_before < 0 {
+        return Err("Alert lead time cannot be negative".into());
+    }
+    tracker.write().await.update_settings(settings.clone());
+    Ok(settings)
+}
+
+/// Syncs the feed and runs the alert check now; returns the alerts raised.
+#[tauri::command]
+pub async fn refresh_token_unlocks(
+    app: AppHandle,
+    tracker: State<'_, SharedTokenUnlockTracker>,
+) -> Result<Vec<UpcomingUnlock>, String> {
+    refresh_and_alert(&app, tracker.inner()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tracker(events: Vec<TokenUnlockEvent>) -> TokenUnlockTracker {
+        TokenUnlockTracker {
+            settings: TokenUnlockSettings::default(),
+            events,
+            path: None,
+        }
+    }
+
+    #[test]
+    fn feed_accepts_wrapped_lists_and_mixed_date_formats() {
+        let body = json!({
+            "data": [
+                {
+                    "tokenAddress": "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN",
+                    "symbol": "JUP",
+                    "unlockDate": "2026-01-31T00:00:00Z",
+                    "unlockAmount": "53470000",
+                    "circulatingSupply": 1350000000.0,
+                    "category": "team"
+                },
+                { "mint": "Bonk", "timestamp": 1_800_000_000_000i64, "amount": 5.0 },
+                { "mint": "NoDate", "amount": 1.0 }
+            ]
+        });
+
+        let events = parse_unlock_feed(&body);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].symbol, "JUP");
+        assert_eq!(events[0].unlock_at, 1_769_817_600);
+        assert!((events[0].supply_pct().unwrap() - 3.96).abs() < 0.01);
+        assert_eq!(events[1].unlock_at, 1_800_000_000);
+        assert_eq!(events[1].id, "feed:Bonk:1800000000");
+    }
+
+    #[test]
+    fn alerts_only_large_relevant_unlocks_in_the_window_once() {
+        let now = Utc::now();
+        let event = |mint: &str, days: i64, amount: f64| TokenUnlockEvent {
+            id: mint.to_string(),
+            mint: mint.to_string(),
+            symbol: mint.to_string(),
+            unlock_at: (now + Duration::days(days)).timestamp(),
+            amount,
+            circulating_supply: Some(1_000.0),
+            usd_value: None,
+            category: None,
+            source: UnlockSource::Manual,
+            notes: None,
+            alerted_at: None,
+        };
+        let mut tracker = tracker(vec![
+            event("held", 3, 50.0),
+            event("small", 3, 5.0),
+            event("later", 20, 50.0),
+            event("unrelated", 3, 50.0),
+        ]);
+        let relevant: HashSet<String> = ["held", "small", "later"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let due = tracker.take_due_alerts(now, &relevant);
+        assert_eq!(
+            due.iter().map(|u| u.event.id.as_str()).collect::<Vec<_>>(),
+            vec!["held"]
+        );
+        assert!(tracker.take_due_alerts(now, &relevant).is_empty());
+    }
+}
@@ -3,12 +3,18 @@ use crate::security::keystore::Keystore;
 use anyhow::{anyhow, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+/// How far a device's attestation timestamp may drift from the desktop clock.
+const ATTESTATION_MAX_SKEW_SECS: i64 = 120;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BiometricChallenge {
     pub challenge_id: String,
@@ -37,6 +43,7 @@ pub struct MobileAuthManager {
     devices: HashMap<String, MobileDevice>,
     sessions: HashMap<String, MobileSession>,
     challenges: HashMap<String, BiometricChallenge>,
+    attestation_nonces: HashMap<String, i64>,
     data_dir: PathBuf,
 }
 
@@ -46,12 +53,18 @@ impl MobileAuthManager {
             devices: HashMap::new(),
             sessions: HashMap::new(),
             challenges: HashMap::new(),
+            attestation_nonces: HashMap::new(),
             data_dir,
         }
     }
 
     /// Register a new mobile device
     pub async fn register_device(&mut self, req: MobileAuthRequest) -> Result<MobileDevice> {
+        if let Some(key) = req.biometric_public_key.as_deref() {
+            Pubkey::from_str(key)
+                .map_err(|_| anyhow!("Device public key must be base58 Ed25519"))?;
+        }
+
         let device_id = if req.device_id.is_empty() {
             Uuid::new_v4().to_string()
         } else {
@@ -65,6 +78,7 @@ impl MobileAuthManager {
             push_token: None,
             last_sync: None,
             biometric_enabled: req.biometric_public_key.is_some(),
+            attestation_key: req.biometric_public_key,
         };
 
         self.devices.insert(device_id.clone(), device.clone());
@@ -176,6 +190,68 @@ impl MobileAuthManager {
         Ok(session.clone())
     }
 
+    /// Verify that `payload` was signed by the device's registered key.
+    ///
+    /// Each nonce is accepted once and the signing time must be recent, so a
+    /// captured attestation cannot be replayed later.
+    pub fn verify_device_attestation(
+        &mut self,
+        device_id: &str,
+        payload: &str,
+        signature: &str,
+        nonce: &str,
+        signed_at: i64,
+    ) -> Result<MobileDevice> {
+        let now = Utc::now().timestamp();
+        if (now - signed_at).abs() > ATTESTATION_MAX_SKEW_SECS {
+            return Err(anyhow!("Device attestation is stale"));
+        }
+        if nonce.is_empty() {
+            return Err(anyhow!("Device attestation nonce required"));
+        }
+
+        self.attestation_nonces
+            .retain(|_, expires_at| *expires_at > now);
+        let nonce_key = format!("{}:{}", device_id, nonce);
+        if self.attestation_nonces.contains_key(&nonce_key) {
+            return Err(anyhow!("Device attestation already used"));
+        }
+
+        let device = self.verify_device_signature(device_id, payload, signature)?;
+
+        self.attestation_nonces
+            .insert(nonce_key, signed_at + ATTESTATION_MAX_SKEW_SECS);
+
+        Ok(device)
+    }
+
+    /// Check a base58 Ed25519 signature over `payload` against the device's
+    /// registered key. Callers are responsible for replay protection.
+    pub fn verify_device_signature(
+        &self,
+        device_id: &str,
+        payload: &str,
+        signature: &str,
+    ) -> Result<MobileDevice> {
+        let device = self
+            .devices
+            .get(device_id)
+            .ok_or_else(|| anyhow!("Device not registered"))?;
+        let key = device
+            .attestation_key
+            .as_deref()
+            .ok_or_else(|| anyhow!("Device has no attestation key; re-register it"))?;
+
+        let pubkey = Pubkey::from_str(key).map_err(|_| anyhow!("Invalid device key"))?;
+        let signature =
+            Signature::from_str(signature).map_err(|_| anyhow!("Invalid device signature"))?;
+        if !signature.verify(pubkey.as_ref(), payload.as_bytes()) {
+            return Err(anyhow!("Device signature verification failed"));
+        }
+
+        Ok(device.clone())
+    }
+
     /// Revoke a session
     pub async fn revoke_session(&mut self, session_token: String) -> Result<()> {
         if let Some(session) = self.sessions.get_mut(&session_token) {
@@ -1,13 +1,17 @@
 pub mod auth;
 pub mod push;
+pub mod remote_control;
 pub mod sync;
 pub mod trades;
+pub mod widget_payloads;
 pub mod widgets;
 
 pub use auth::*;
 pub use push::*;
+pub use remote_control::*;
 pub use sync::*;
 pub use trades::*;
+pub use widget_payloads::*;
 pub use widgets::*;
 
 use serde::{Deserialize, Serialize};
@@ -22,6 +26,9 @@ pub struct MobileDevice {
     pub push_token: Option<String>,
     pub last_sync: Option<i64>,
     pub biometric_enabled: bool,
+    /// Base58 Ed25519 key the device signs quick-trade attestations with.
+    #[serde(default)]
+    pub attestation_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
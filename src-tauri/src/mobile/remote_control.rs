@@ -0,0 +1,321 @@
+use crate::mobile::SharedMobileAuthManager;
+use crate::security::activity_log::{ActivityAction, ActivityLogger};
+use crate::trading::safety::SharedSafetyEngine;
+use crate::trading::SharedAutoTradingEngine;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Seconds a paired device has to sign a halt challenge.
+const HALT_CHALLENGE_TTL_SECS: i64 = 120;
+
+pub type SharedMobileRemoteControl = Arc<RwLock<MobileRemoteControl>>;
+
+/// What a remote halt switches off. Re-arming is only possible from the
+/// desktop, so a lost phone can stop trading but never restart it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteHaltAction {
+    /// Stop every running auto-trading strategy.
+    KillSwitch,
+    /// Block all new trades through the safety engine.
+    EmergencyHalt,
+    /// Both of the above.
+    All,
+}
+
+impl RemoteHaltAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RemoteHaltAction::KillSwitch => "kill_switch",
+            RemoteHaltAction::EmergencyHalt => "emergency_halt",
+            RemoteHaltAction::All => "all",
+        }
+    }
+
+    fn stops_auto_trading(&self) -> bool {
+        matches!(self, RemoteHaltAction::KillSwitch | RemoteHaltAction::All)
+    }
+
+    fn halts_trading(&self) -> bool {
+        matches!(
+            self,
+            RemoteHaltAction::EmergencyHalt | RemoteHaltAction::All
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteHaltChallenge {
+    pub challenge_id: String,
+    pub device_id: String,
+    pub action: RemoteHaltAction,
+    pub nonce: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
+
+impl RemoteHaltChallenge {
+    /// The exact string the device must sign to confirm the halt.
+    pub fn signing_payload(&self) -> String {
+        format!(
+            "remote_halt|{}|{}|{}|{}",
+            self.challenge_id,
+            self.device_id,
+            self.action.as_str(),
+            self.nonce
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteHaltResult {
+    pub device_id: String,
+    pub action: RemoteHaltAction,
+    pub kill_switch_active: bool,
+    pub emergency_halt: bool,
+    pub executed_at: i64,
+}
+
+#[derive(Default)]
+pub struct MobileRemoteControl {
+    challenges: HashMap<String, RemoteHaltChallenge>,
+}
+
+impl MobileRemoteControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn create_challenge(
+        &mut self,
+        device_id: String,
+        action: RemoteHaltAction,
+    ) -> RemoteHaltChallenge {
+        let now = Utc::now().timestamp();
+        self.challenges
+            .retain(|_, challenge| challenge.expires_at > now);
+
+        let challenge = RemoteHaltChallenge {
+            challenge_id: Uuid::new_v4().to_string(),
+            device_id,
+            action,
+            nonce: hex::encode(rand::random::<[u8; 16]>()),
+            created_at: now,
+            expires_at: now + HALT_CHALLENGE_TTL_SECS,
+        };
+        self.challenges
+            .insert(challenge.challenge_id.clone(), challenge.clone());
+        challenge
+    }
+
+    /// Removes and returns the challenge, so each one can be answered once.
+    pub fn take_challenge(
+        &mut self,
+        challenge_id: &str,
+        device_id: &str,
+    ) -> Result<RemoteHaltChallenge> {
+        let challenge = self
+            .challenges
+            .remove(challenge_id)
+            .ok_or_else(|| anyhow!("Invalid halt challenge"))?;
+
+        if challenge.device_id != device_id {
+            return Err(anyhow!("Halt challenge was issued to another device"));
+        }
+        if Utc::now().timestamp() > challenge.expires_at {
+            return Err(anyhow!("Halt challenge expired"));
+        }
+
+        Ok(challenge)
+    }
+}
+
+async fn apply_halt(app: &AppHandle, action: RemoteHaltAction) -> Result<(bool, bool)> {
+    let mut kill_switch_active = false;
+    if let Some(engine) = app.try_state::<SharedAutoTradingEngine>() {
+        let mut engine = engine.lock().map_err(|e| anyhow!(e.to_string()))?;
+        if action.stops_auto_trading() {
+            engine.activate_kill_switch();
+        }
+        kill_switch_active = engine.is_kill_switch_active();
+    } else if action.stops_auto_trading() {
+        return Err(anyhow!("Auto trading engine unavailable"));
+    }
+
+    let mut emergency_halt = false;
+    if let Some(safety) = app.try_state::<SharedSafetyEngine>() {
+        let mut safety = safety.write().await;
+        if action.halts_trading() {
+            safety.set_emergency_halt(true);
+        }
+        emergency_halt = safety.is_emergency_halt();
+    } else if action.halts_trading() {
+        return Err(anyhow!("Safety engine unavailable"));
+    }
+
+    Ok((kill_switch_active, emergency_halt))
+}
+
+async fn log_remote_halt(
+    app: &AppHandle,
+    device_id: &str,
+    action: RemoteHaltAction,
+    success: bool,
+    error: Option<&str>,
+) {
+    if let Some(logger) = app.try_state::<ActivityLogger>() {
+        let details = json!({
+            "source": "mobile",
+            "device_id": device_id,
+            "action": action.as_str(),
+            "error": error,
+        });
+        if let Err(e) = logger
+            .log_activity(
+                &format!("mobile:{}", device_id),
+                ActivityAction::RemoteHalt,
+                details,
+                success,
+                None,
+            )
+            .await
+        {
+            eprintln!("Failed to log remote halt: {}", e);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn mobile_create_halt_challenge(
+    session_token: String,
+    action: RemoteHaltAction,
+    mobile_auth: tauri::State<'_, SharedMobileAuthManager>,
+    remote_control: tauri::State<'_, SharedMobileRemoteControl>,
+) -> Result<RemoteHaltChallenge, String> {
+    let session = {
+        let auth = mobile_auth.read().await;
+        auth.authenticate_session(session_token)
+            .await
+            .map_err(|e| e.to_string())?
+    };
+
+    let mut control = remote_control.write().await;
+    Ok(control.create_challenge(session.device_id, action))
+}
+
+#[tauri::command]
+pub async fn mobile_confirm_remote_halt(
+    app: AppHandle,
+    session_token: String,
+    challenge_id: String,
+    signature: String,
+    mobile_auth: tauri::State<'_, SharedMobileAuthManager>,
+    remote_control: tauri::State<'_, SharedMobileRemoteControl>,
+) -> Result<RemoteHaltResult, String> {
+    let session = {
+        let auth = mobile_auth.read().await;
+        auth.authenticate_session(session_token)
+            .await
+            .map_err(|e| e.to_string())?
+    };
+
+    let challenge = {
+        let mut control = remote_control.write().await;
+        control
+            .take_challenge(&challenge_id, &session.device_id)
+            .map_err(|e| e.to_string())?
+    };
+
+    let verified = {
+        let auth = mobile_auth.read().await;
+        auth.verify_device_signature(&session.device_id, &challenge.signing_payload(), &signature)
+    };
+    if let Err(e) = verified {
+        let message = e.to_string();
+        log_remote_halt(
+            &app,
+            &session.device_id,
+            challenge.action,
+            false,
+            Some(&message),
+        )
+        .await;
+        return Err(message);
+    }
+
+    let (kill_switch_active, emergency_halt) = match apply_halt(&app, challenge.action).await {
+        Ok(state) => state,
+        Err(e) => {
+            let message = e.to_string();
+            log_remote_halt(
+                &app,
+                &session.device_id,
+                challenge.action,
+                false,
+                Some(&message),
+            )
+            .await;
+            return Err(message);
+        }
+    };
+
+    log_remote_halt(&app, &session.device_id, challenge.action, true, None).await;
+
+    let result = RemoteHaltResult {
+        device_id: session.device_id,
+        action: challenge.action,
+        kill_switch_active,
+        emergency_halt,
+        executed_at: Utc::now().timestamp(),
+    };
+    let _ = app.emit("mobile_remote_halt", &result);
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn challenge_is_single_use_and_device_bound() {
+        let mut control = MobileRemoteControl::new();
+        let challenge = control.create_challenge("phone".to_string(), RemoteHaltAction::All);
+
+        assert!(control
+            .take_challenge(&challenge.challenge_id, "other-phone")
+            .is_err());
+
+        let challenge = control.create_challenge("phone".to_string(), RemoteHaltAction::All);
+        assert!(control
+            .take_challenge(&challenge.challenge_id, "phone")
+            .is_ok());
+        assert!(control
+            .take_challenge(&challenge.challenge_id, "phone")
+            .is_err());
+    }
+
+    #[test]
+    fn signing_payload_covers_action_and_nonce() {
+        let challenge = RemoteHaltChallenge {
+            challenge_id: "c1".to_string(),
+            device_id: "phone".to_string(),
+            action: RemoteHaltAction::KillSwitch,
+            nonce: "abcd".to_string(),
+            created_at: 0,
+            expires_at: 120,
+        };
+
+        assert_eq!(
+            challenge.signing_payload(),
+            "remote_halt|c1|phone|kill_switch|abcd"
+        );
+    }
+}
@@ -1,11 +1,17 @@
-use crate::mobile::{MobileSession, SharedMobileAuthManager};
+use crate::mobile::{MobileDevice, SharedMobileAuthManager};
 use crate::trading::safety::policy::{SafetyCheck, SafetyPolicy};
 use anyhow::{anyhow, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
+use tauri::Emitter;
 use tokio::sync::RwLock;
 
+/// How long an over-limit trade waits for desktop approval before lapsing.
+const APPROVAL_TTL_SECS: i64 = 900;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuickTradeRequest {
     pub session_token: String,
@@ -13,15 +19,33 @@ pub struct QuickTradeRequest {
     pub side: TradeSide,
     pub amount: f64,
     pub biometric_signature: String,
+    /// One-time value included in the device attestation.
+    #[serde(default)]
+    pub nonce: String,
+    /// Unix time at which the device signed the attestation.
+    #[serde(default)]
+    pub signed_at: i64,
+    /// Base58 Ed25519 signature over [`quick_trade_attestation_payload`].
+    #[serde(default)]
+    pub device_signature: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TradeSide {
     Buy,
     Sell,
 }
 
+impl TradeSide {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TradeSide::Buy => "buy",
+            TradeSide::Sell => "sell",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuickTradeConfirmation {
     pub trade_id: String,
@@ -31,6 +55,9 @@ pub struct QuickTradeConfirmation {
     pub executed_price: f64,
     pub timestamp: i64,
     pub status: TradeStatus,
+    /// Device limits the trade exceeded; non-empty when it awaits desktop approval.
+    #[serde(default)]
+    pub limit_violations: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,53 +68,170 @@ pub enum TradeStatus {
     Pending,
 }
 
+/// Quick-trade limits bound to a single mobile device. Amounts are USD notional.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceTradeLimits {
+    pub max_trade_size: f64,
+    pub whitelisted_tokens: Vec<String>,
+    pub daily_budget: f64,
+}
+
+impl Default for DeviceTradeLimits {
+    fn default() -> Self {
+        Self {
+            max_trade_size: 250.0,
+            whitelisted_tokens: vec!["SOL".to_string(), "USDC".to_string(), "USDT".to_string()],
+            daily_budget: 1_000.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DeviceTradeUsage {
+    day: String,
+    spent: f64,
+}
+
+/// A mobile trade that exceeded its device limits and needs confirming on the desktop.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTradeApproval {
+    pub approval_id: String,
+    pub device_id: String,
+    pub device_name: String,
+    pub symbol: String,
+    pub side: TradeSide,
+    pub amount: f64,
+    pub reasons: Vec<String>,
+    pub requested_at: i64,
+    pub expires_at: i64,
+}
+
+/// The exact string a device signs to attest a quick trade.
+///
+/// The amount uses Rust's default `f64` formatting, so clients must sign the
+/// same shortest round-trip representation (e.g. `25` rather than `25.0`).
+pub fn quick_trade_attestation_payload(device_id: &str, trade: &QuickTradeRequest) -> String {
+    format!(
+        "quick_trade|{}|{}|{}|{}|{}|{}",
+        device_id,
+        trade.symbol.to_uppercase(),
+        trade.side.as_str(),
+        trade.amount,
+        trade.nonce,
+        trade.signed_at
+    )
+}
+
+/// Lists every device limit a trade would break given what the device has
+/// already spent today.
+pub fn device_limit_violations(
+    limits: &DeviceTradeLimits,
+    spent_today: f64,
+    symbol: &str,
+    amount: f64,
+) -> Vec<String> {
+    let mut violations = Vec::new();
+
+    if amount > limits.max_trade_size {
+        violations.push(format!(
+            "Trade amount ${:.2} exceeds device maximum ${:.2}",
+            amount, limits.max_trade_size
+        ));
+    }
+
+    if !limits
+        .whitelisted_tokens
+        .iter()
+        .any(|token| token.eq_ignore_ascii_case(symbol))
+    {
+        violations.push(format!("{} is not whitelisted for this device", symbol));
+    }
+
+    if spent_today + amount > limits.daily_budget {
+        violations.push(format!(
+            "Daily budget ${:.2} would be exceeded (${:.2} already spent)",
+            limits.daily_budget, spent_today
+        ));
+    }
+
+    violations
+}
+
 pub struct MobileTradeEngine {
     safety_policy: SafetyPolicy,
+    limits: HashMap<String, DeviceTradeLimits>,
+    usage: HashMap<String, DeviceTradeUsage>,
+    pending: HashMap<String, PendingTradeApproval>,
+    data_dir: PathBuf,
 }
 
 impl MobileTradeEngine {
-    pub fn new() -> Self {
+    pub fn new(data_dir: PathBuf) -> Self {
         Self {
             safety_policy: SafetyPolicy::default(),
+            limits: HashMap::new(),
+            usage: HashMap::new(),
+            pending: HashMap::new(),
+            data_dir,
         }
     }
 
     pub async fn execute_quick_trade(
-        &self,
+        &mut self,
         trade: QuickTradeRequest,
         mobile_auth: SharedMobileAuthManager,
     ) -> Result<QuickTradeConfirmation> {
         if trade.biometric_signature.is_empty() {
             return Err(anyhow!("Biometric signature required"));
         }
+        if trade.device_signature.is_empty() {
+            return Err(anyhow!("Device attestation required"));
+        }
 
-        let session = {
-            let auth = mobile_auth.read().await;
-            auth.authenticate_session(trade.session_token.clone())
-                .await?
+        let device = {
+            let mut auth = mobile_auth.write().await;
+            let session = auth
+                .authenticate_session(trade.session_token.clone())
+                .await?;
+            let payload = quick_trade_attestation_payload(&session.device_id, &trade);
+            auth.verify_device_attestation(
+                &session.device_id,
+                &payload,
+                &trade.device_signature,
+                &trade.nonce,
+                trade.signed_at,
+            )?
         };
 
-        self.enforce_safety_checks(&session, &trade)?;
+        self.enforce_safety_checks(&trade)?;
 
-        // Simulated execution
-        let confirmation = QuickTradeConfirmation {
-            trade_id: uuid::Uuid::new_v4().to_string(),
-            symbol: trade.symbol,
-            side: trade.side,
-            amount: trade.amount,
-            executed_price: 123.45,
-            timestamp: Utc::now().timestamp(),
-            status: TradeStatus::Executed,
-        };
+        let limits = self.device_limits(&device.device_id);
+        let spent_today = self.spent_today(&device.device_id);
+        let violations = device_limit_violations(&limits, spent_today, &trade.symbol, trade.amount);
 
-        Ok(confirmation)
+        if !violations.is_empty() {
+            let approval = self.queue_for_approval(&device, &trade, violations);
+            return Ok(QuickTradeConfirmation {
+                trade_id: approval.approval_id,
+                symbol: trade.symbol,
+                side: trade.side,
+                amount: trade.amount,
+                executed_price: 0.0,
+                timestamp: approval.requested_at,
+                status: TradeStatus::Pending,
+                limit_violations: approval.reasons,
+            });
+        }
+
+        self.record_spend(&device.device_id, trade.amount).await?;
+        Ok(Self::simulate_execution(
+            trade.symbol,
+            trade.side,
+            trade.amount,
+        ))
     }
 
-    fn enforce_safety_checks(
-        &self,
-        _session: &MobileSession,
-        trade: &QuickTradeRequest,
-    ) -> Result<()> {
+    fn enforce_safety_checks(&self, trade: &QuickTradeRequest) -> Result<()> {
         let checks = vec![
             SafetyCheck::MaxNotionalValue(50_000.0),
             SafetyCheck::MaxOrderSize(1_000.0),
@@ -101,19 +245,184 @@ impl MobileTradeEngine {
 
         Ok(())
     }
+
+    // Simulated execution
+    fn simulate_execution(symbol: String, side: TradeSide, amount: f64) -> QuickTradeConfirmation {
+        QuickTradeConfirmation {
+            trade_id: uuid::Uuid::new_v4().to_string(),
+            symbol,
+            side,
+            amount,
+            executed_price: 123.45,
+            timestamp: Utc::now().timestamp(),
+            status: TradeStatus::Executed,
+            limit_violations: Vec::new(),
+        }
+    }
+
+    fn queue_for_approval(
+        &mut self,
+        device: &MobileDevice,
+        trade: &QuickTradeRequest,
+        reasons: Vec<String>,
+    ) -> PendingTradeApproval {
+        let now = Utc::now().timestamp();
+        let approval = PendingTradeApproval {
+            approval_id: uuid::Uuid::new_v4().to_string(),
+            device_id: device.device_id.clone(),
+            device_name: device.device_name.clone(),
+            symbol: trade.symbol.clone(),
+            side: trade.side,
+            amount: trade.amount,
+            reasons,
+            requested_at: now,
+            expires_at: now + APPROVAL_TTL_SECS,
+        };
+        self.pending
+            .insert(approval.approval_id.clone(), approval.clone());
+        approval
+    }
+
+    pub fn device_limits(&self, device_id: &str) -> DeviceTradeLimits {
+        self.limits.get(device_id).cloned().unwrap_or_default()
+    }
+
+    pub async fn set_device_limits(
+        &mut self,
+        device_id: String,
+        limits: DeviceTradeLimits,
+    ) -> Result<()> {
+        if limits.max_trade_size < 0.0 || limits.daily_budget < 0.0 {
+            return Err(anyhow!("Trade limits cannot be negative"));
+        }
+
+        let mut limits = limits;
+        limits.whitelisted_tokens = limits
+            .whitelisted_tokens
+            .into_iter()
+            .map(|token| token.trim().to_uppercase())
+            .filter(|token| !token.is_empty())
+            .collect();
+        limits.whitelisted_tokens.sort();
+        limits.whitelisted_tokens.dedup();
+
+        self.limits.insert(device_id, limits);
+        self.save_limits().await
+    }
+
+    fn spent_today(&self, device_id: &str) -> f64 {
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        self.usage
+            .get(device_id)
+            .filter(|usage| usage.day == today)
+            .map(|usage| usage.spent)
+            .unwrap_or(0.0)
+    }
+
+    async fn record_spend(&mut self, device_id: &str, amount: f64) -> Result<()> {
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        let usage = self
+            .usage
+            .entry(device_id.to_string())
+            .or_insert_with(|| DeviceTradeUsage {
+                day: today.clone(),
+                spent: 0.0,
+            });
+        if usage.day != today {
+            usage.day = today;
+            usage.spent = 0.0;
+        }
+        usage.spent += amount;
+        self.save_usage().await
+    }
+
+    pub fn pending_approvals(&mut self) -> Vec<PendingTradeApproval> {
+        let now = Utc::now().timestamp();
+        self.pending.retain(|_, approval| approval.expires_at > now);
+
+        let mut approvals: Vec<_> = self.pending.values().cloned().collect();
+        approvals.sort_by_key(|approval| approval.requested_at);
+        approvals
+    }
+
+    /// Execute a queued trade after the desktop user confirms it. Approved
+    /// trades still count towards the device's daily spend.
+    pub async fn approve_trade(&mut self, approval_id: &str) -> Result<QuickTradeConfirmation> {
+        let approval = self
+            .pending
+            .remove(approval_id)
+            .ok_or_else(|| anyhow!("Approval request not found"))?;
+
+        if Utc::now().timestamp() > approval.expires_at {
+            return Err(anyhow!("Approval request expired"));
+        }
+
+        self.record_spend(&approval.device_id, approval.amount)
+            .await?;
+        Ok(Self::simulate_execution(
+            approval.symbol,
+            approval.side,
+            approval.amount,
+        ))
+    }
+
+    pub fn reject_trade(&mut self, approval_id: &str) -> Result<PendingTradeApproval> {
+        self.pending
+            .remove(approval_id)
+            .ok_or_else(|| anyhow!("Approval request not found"))
+    }
+
+    async fn save_limits(&self) -> Result<()> {
+        let path = self.data_dir.join("mobile_trade_limits.json");
+        let json = serde_json::to_string_pretty(&self.limits)?;
+        tokio::fs::write(path, json).await?;
+        Ok(())
+    }
+
+    async fn save_usage(&self) -> Result<()> {
+        let path = self.data_dir.join("mobile_trade_usage.json");
+        let json = serde_json::to_string_pretty(&self.usage)?;
+        tokio::fs::write(path, json).await?;
+        Ok(())
+    }
+
+    pub async fn load(&mut self) -> Result<()> {
+        let limits_path = self.data_dir.join("mobile_trade_limits.json");
+        if limits_path.exists() {
+            let content = tokio::fs::read_to_string(limits_path).await?;
+            self.limits = serde_json::from_str(&content)?;
+        }
+
+        let usage_path = self.data_dir.join("mobile_trade_usage.json");
+        if usage_path.exists() {
+            let content = tokio::fs::read_to_string(usage_path).await?;
+            self.usage = serde_json::from_str(&content)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[tauri::command]
 pub async fn mobile_execute_quick_trade(
+    app: tauri::AppHandle,
     trade: QuickTradeRequest,
     trade_engine: tauri::State<'_, Arc<RwLock<MobileTradeEngine>>>,
     mobile_auth: tauri::State<'_, Arc<RwLock<crate::mobile::auth::MobileAuthManager>>>,
 ) -> Result<QuickTradeConfirmation, String> {
-    let engine = trade_engine.read().await;
-    engine
+    let mut engine = trade_engine.write().await;
+    let confirmation = engine
         .execute_quick_trade(trade, mobile_auth.inner().clone())
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    if matches!(confirmation.status, TradeStatus::Pending) {
+        if let Some(approval) = engine.pending.get(&confirmation.trade_id) {
+            let _ = app.emit("mobile_trade_approval_requested", approval);
+        }
+    }
+
+    Ok(confirmation)
 }
 
 #[tauri::command]
@@ -128,3 +437,121 @@ pub async fn mobile_safety_checks(
         .map(|rule| format!("{}", rule))
         .collect())
 }
+
+#[tauri::command]
+pub async fn mobile_get_device_trade_limits(
+    device_id: String,
+    trade_engine: tauri::State<'_, Arc<RwLock<MobileTradeEngine>>>,
+) -> Result<DeviceTradeLimits, String> {
+    let engine = trade_engine.read().await;
+    Ok(engine.device_limits(&device_id))
+}
+
+#[tauri::command]
+pub async fn mobile_set_device_trade_limits(
+    device_id: String,
+    limits: DeviceTradeLimits,
+    trade_engine: tauri::State<'_, Arc<RwLock<MobileTradeEngine>>>,
+    mobile_auth: tauri::State<'_, Arc<RwLock<crate::mobile::auth::MobileAuthManager>>>,
+) -> Result<DeviceTradeLimits, String> {
+    {
+        let auth = mobile_auth.read().await;
+        if !auth
+            .get_devices()
+            .iter()
+            .any(|device| device.device_id == device_id)
+        {
+            return Err("Device not registered".to_string());
+        }
+    }
+
+    let mut engine = trade_engine.write().await;
+    engine
+        .set_device_limits(device_id.clone(), limits)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(engine.device_limits(&device_id))
+}
+
+#[tauri::command]
+pub async fn mobile_get_pending_trade_approvals(
+    trade_engine: tauri::State<'_, Arc<RwLock<MobileTradeEngine>>>,
+) -> Result<Vec<PendingTradeApproval>, String> {
+    let mut engine = trade_engine.write().await;
+    Ok(engine.pending_approvals())
+}
+
+#[tauri::command]
+pub async fn mobile_approve_quick_trade(
+    app: tauri::AppHandle,
+    approval_id: String,
+    trade_engine: tauri::State<'_, Arc<RwLock<MobileTradeEngine>>>,
+) -> Result<QuickTradeConfirmation, String> {
+    let mut engine = trade_engine.write().await;
+    let confirmation = engine
+        .approve_trade(&approval_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let _ = app.emit(
+        "mobile_trade_approval_resolved",
+        serde_json::json!({ "approval_id": approval_id, "approved": true, "trade": &confirmation }),
+    );
+
+    Ok(confirmation)
+}
+
+#[tauri::command]
+pub async fn mobile_reject_quick_trade(
+    app: tauri::AppHandle,
+    approval_id: String,
+    trade_engine: tauri::State<'_, Arc<RwLock<MobileTradeEngine>>>,
+) -> Result<(), String> {
+    let mut engine = trade_engine.write().await;
+    engine
+        .reject_trade(&approval_id)
+        .map_err(|e| e.to_string())?;
+
+    let _ = app.emit(
+        "mobile_trade_approval_resolved",
+        serde_json::json!({ "approval_id": approval_id, "approved": false }),
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trade_within_limits_has_no_violations() {
+        let limits = DeviceTradeLimits::default();
+        assert!(device_limit_violations(&limits, 100.0, "sol", 200.0).is_empty());
+    }
+
+    #[test]
+    fn every_exceeded_limit_is_reported() {
+        let limits = DeviceTradeLimits::default();
+        let violations = device_limit_violations(&limits, 900.0, "BONK", 300.0);
+        assert_eq!(violations.len(), 3);
+    }
+
+    #[test]
+    fn attestation_payload_is_canonical() {
+        let trade = QuickTradeRequest {
+            session_token: "token".to_string(),
+            symbol: "sol".to_string(),
+            side: TradeSide::Buy,
+            amount: 25.0,
+            biometric_signature: "bio".to_string(),
+            nonce: "n1".to_string(),
+            signed_at: 1_700_000_000,
+            device_signature: String::new(),
+        };
+        assert_eq!(
+            quick_trade_attestation_payload("device-1", &trade),
+            "quick_trade|device-1|SOL|buy|25|n1|1700000000"
+        );
+    }
+}
@@ -0,0 +1,484 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::widgets::WidgetType;
+use crate::alerts::{AlertState, PriceAlert};
+use crate::portfolio::Position;
+
+/// Home-screen widgets draw a small line; more points only cost bytes.
+pub const SPARKLINE_POINTS: usize = 24;
+const MAX_WIDGET_ROWS: usize = 3;
+/// Changes smaller than this read as flat on a widget.
+const FLAT_THRESHOLD_PCT: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WidgetTrend {
+    Up,
+    Down,
+    Flat,
+}
+
+impl WidgetTrend {
+    pub fn from_change(change_pct: f64) -> Self {
+        if change_pct >= FLAT_THRESHOLD_PCT {
+            WidgetTrend::Up
+        } else if change_pct <= -FLAT_THRESHOLD_PCT {
+            WidgetTrend::Down
+        } else {
+            WidgetTrend::Flat
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetRow {
+    pub label: String,
+    pub value_text: String,
+    pub delta_text: Option<String>,
+    pub trend: WidgetTrend,
+}
+
+/// A widget rendered ahead of time: every string is display-ready and the
+/// sparkline is already scaled, so the phone only lays it out.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetPayload {
+    pub widget_id: String,
+    pub widget_type: WidgetType,
+    pub headline: String,
+    pub delta_text: Option<String>,
+    pub trend: WidgetTrend,
+    /// Points scaled to 0-100 between the window's low and high.
+    pub sparkline: Vec<u8>,
+    pub alert_count: Option<u32>,
+    pub rows: Vec<WidgetRow>,
+    pub generated_at: i64,
+    /// When the next scheduled render is expected; the phone can show the
+    /// payload as stale after this.
+    pub expires_at: i64,
+}
+
+/// Hourly closes for one symbol, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolSeries {
+    pub symbol: String,
+    pub points: Vec<(i64, f64)>,
+}
+
+impl SymbolSeries {
+    pub fn change_pct(&self) -> Option<f64> {
+        let first = self.points.first()?.1;
+        let last = self.points.last()?.1;
+        (first > 0.0).then(|| (last - first) / first * 100.0)
+    }
+
+    pub fn last_price(&self) -> Option<f64> {
+        self.points.last().map(|(_, close)| *close)
+    }
+}
+
+/// Everything a render pass needs, gathered once per scheduled refresh.
+#[derive(Debug, Clone, Default)]
+pub struct WidgetInputs {
+    pub positions: Vec<Position>,
+    pub series: Vec<SymbolSeries>,
+    pub alerts: Vec<PriceAlert>,
+}
+
+impl WidgetInputs {
+    fn series_for(&self, symbol: &str) -> Option<&SymbolSeries> {
+        self.series.iter().find(|s| s.symbol == symbol)
+    }
+}
+
+/// Averages `values` into at most `points` buckets and scales them to
+/// 0-100. A flat series sits in the middle.
+pub fn downsample_sparkline(values: &[f64], points: usize) -> Vec<u8> {
+    if values.is_empty() || points == 0 {
+        return Vec::new();
+    }
+    let buckets = points.min(values.len());
+    let averaged: Vec<f64> = (0..buckets)
+        .map(|i| {
+            let start = i * values.len() / buckets;
+            let end = ((i + 1) * values.len() / buckets).max(start + 1);
+            let slice = &values[start..end];
+            slice.iter().sum::<f64>() / slice.len() as f64
+        })
+        .collect();
+
+    let low = averaged.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = averaged.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = high - low;
+    averaged
+        .iter()
+        .map(|v| {
+            if range <= f64::EPSILON {
+                50
+            } else {
+                ((v - low) / range * 100.0).round() as u8
+            }
+        })
+        .collect()
+}
+
+pub fn format_usd(value: f64) -> String {
+    let sign = if value < 0.0 { "-" } else { "" };
+    let value = value.abs();
+    if value > 0.0 && value < 0.01 {
+        return format!("{sign}${value:.6}");
+    }
+    let cents = (value * 100.0).round() as u64;
+    let whole = (cents / 100).to_string();
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    format!("{sign}${grouped}.{:02}", cents % 100)
+}
+
+pub fn format_delta_pct(change_pct: f64) -> String {
+    format!("{change_pct:+.2}%")
+}
+
+fn payload(
+    widget_type: WidgetType,
+    headline: String,
+    now: DateTime<Utc>,
+    ttl_secs: i64,
+) -> WidgetPayload {
+    WidgetPayload {
+        widget_id: widget_type.widget_id().to_string(),
+        widget_type,
+        headline,
+        delta_text: None,
+        trend: WidgetTrend::Flat,
+        sparkline: Vec::new(),
+        alert_count: None,
+        rows: Vec::new(),
+        generated_at: now.timestamp(),
+        expires_at: (now + Duration::seconds(ttl_secs)).timestamp(),
+    }
+}
+
+fn price_row(series: &SymbolSeries) -> Option<WidgetRow> {
+    let price = series.last_price()?;
+    let change = series.change_pct();
+    Some(WidgetRow {
+        label: series.symbol.clone(),
+        value_text: format_usd(price),
+        delta_text: change.map(format_delta_pct),
+        trend: change.map_or(WidgetTrend::Flat, WidgetTrend::from_change),
+    })
+}
+
+/// Top watched symbol as the headline with its 24h line, the rest as rows.
+pub fn render_price_watch(
+    inputs: &WidgetInputs,
+    watch: &[String],
+    now: DateTime<Utc>,
+    ttl_secs: i64,
+) -> WidgetPayload {
+    let watched: Vec<&SymbolSeries> = watch
+        .iter()
+        .filter_map(|symbol| inputs.series_for(symbol))
+        .filter(|s| !s.points.is_empty())
+        .collect();
+    let Some(lead) = watched.first() else {
+        return payload(
+            WidgetType::PriceWatch,
+            "No price data".to_string(),
+            now,
+            ttl_secs,
+        );
+    };
+
+    let mut widget = payload(
+        WidgetType::PriceWatch,
+        format!(
+            "{} {}",
+            lead.symbol,
+            format_usd(lead.last_price().unwrap_or(0.0))
+        ),
+        now,
+        ttl_secs,
+    );
+    if let Some(change) = lead.change_pct() {
+        widget.delta_text = Some(format_delta_pct(change));
+        widget.trend = WidgetTrend::from_change(change);
+    }
+    let closes: Vec<f64> = lead.points.iter().map(|(_, close)| *close).collect();
+    widget.sparkline = downsample_sparkline(&closes, SPARKLINE_POINTS);
+    widget.rows = watched
+        .iter()
+        .skip(1)
+        .take(MAX_WIDGET_ROWS)
+        .filter_map(|s| price_row(s))
+        .collect();
+    widget
+}
+
+/// Portfolio value now and over the window, valuing current holdings at
+/// each hour where every held symbol has a close.
+pub fn render_portfolio_summary(
+    inputs: &WidgetInputs,
+    now: DateTime<Utc>,
+    ttl_secs: i64,
+) -> WidgetPayload {
+    let held: Vec<&Position> = inputs
+        .positions
+        .iter()
+        .filter(|p| p.total_value > 0.0)
+        .collect();
+    let total: f64 = held.iter().map(|p| p.total_value).sum();
+    let mut widget = payload(
+        WidgetType::PortfolioSummary,
+        format_usd(total),
+        now,
+        ttl_secs,
+    );
+
+    let mut by_time: BTreeMap<i64, (f64, usize)> = BTreeMap::new();
+    let mut priced = 0;
+    for position in &held {
+        let Some(series) = inputs.series_for(&position.symbol) else {
+            continue;
+        };
+        if series.points.is_empty() {
+            continue;
+        }
+        priced += 1;
+        for (ts, close) in &series.points {
+            let entry = by_time.entry(*ts).or_insert((0.0, 0));
+            entry.0 += close * position.amount;
+            entry.1 += 1;
+        }
+    }
+    // Holdings without history are a constant offset on the line.
+    let unpriced: f64 = held
+        .iter()
+        .filter(|p| {
+            inputs
+                .series_for(&p.symbol)
+                .map_or(true, |s| s.points.is_empty())
+        })
+        .map(|p| p.total_value)
+        .sum();
+    let values: Vec<f64> = by_time
+        .values()
+        .filter(|(_, count)| *count == priced)
+        .map(|(value, _)| value + unpriced)
+        .collect();
+
+    if priced > 0 && values.len() >= 2 {
+        let first = values[0];
+        let last = values[values.len() - 1];
+        if first > 0.0 {
+            let change = (last - first) / first * 100.0;
+            widget.delta_text = Some(format!(
+                "{} ({})",
+                format_usd(last - first),
+                format_delta_pct(change)
+            ));
+            widget.trend = WidgetTrend::from_change(change);
+        }
+        widget.sparkline = downsample_sparkline(&values, SPARKLINE_POINTS);
+    }
+
+    let mut top = held.clone();
+    top.sort_by(|a, b| {
+        b.total_value
+            .partial_cmp(&a.total_value)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    widget.rows = top
+        .iter()
+        .take(MAX_WIDGET_ROWS)
+        .map(|p| {
+            let change = inputs.series_for(&p.symbol).and_then(|s| s.change_pct());
+            WidgetRow {
+                label: p.symbol.clone(),
+                value_text: format_usd(p.total_value),
+                delta_text: change.map(format_delta_pct),
+                trend: change.map_or(WidgetTrend::Flat, WidgetTrend::from_change),
+            }
+        })
+        .collect();
+    widget
+}
+
+pub fn render_alerts(inputs: &WidgetInputs, now: DateTime<Utc>, ttl_secs: i64) -> WidgetPayload {
+    let active = inputs
+        .alerts
+        .iter()
+        .filter(|a| a.state != AlertState::Disabled)
+        .count() as u32;
+    let day_ago = now - Duration::hours(24);
+    let mut triggered: Vec<(DateTime<Utc>, &PriceAlert)> = inputs
+        .alerts
+        .iter()
+        .filter_map(|a| {
+            let at = DateTime::parse_from_rfc3339(a.last_triggered_at.as_deref()?)
+                .ok()?
+                .with_timezone(&Utc);
+            (at >= day_ago).then_some((at, a))
+        })
+        .collect();
+    triggered.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut widget = payload(
+        WidgetType::Alerts,
+        format!("{} active", active),
+        now,
+        ttl_secs,
+    );
+    widget.alert_count = Some(active);
+    widget.delta_text = Some(format!("{} triggered today", triggered.len()));
+    widget.rows = triggered
+        .iter()
+        .take(MAX_WIDGET_ROWS)
+        .map(|(at, alert)| WidgetRow {
+            label: alert.symbol.clone(),
+            value_text: alert.name.clone(),
+            delta_text: Some(format!("{}h ago", (now - *at).num_hours())),
+            trend: WidgetTrend::Flat,
+        })
+        .collect();
+    widget
+}
+
+pub fn render_top_movers(
+    inputs: &WidgetInputs,
+    now: DateTime<Utc>,
+    ttl_secs: i64,
+) -> WidgetPayload {
+    let mut movers: Vec<(&SymbolSeries, f64)> = inputs
+        .series
+        .iter()
+        .filter_map(|s| s.change_pct().map(|change| (s, change)))
+        .collect();
+    movers.sort_by(|a, b| {
+        b.1.abs()
+            .partial_cmp(&a.1.abs())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let headline = movers.first().map_or_else(
+        || "No movers yet".to_string(),
+        |(series, change)| format!("{} {}", series.symbol, format_delta_pct(*change)),
+    );
+    let mut widget = payload(WidgetType::TopMovers, headline, now, ttl_secs);
+    if let Some((series, change)) = movers.first() {
+        widget.trend = WidgetTrend::from_change(*change);
+        let closes: Vec<f64> = series.points.iter().map(|(_, close)| *close).collect();
+        widget.sparkline = downsample_sparkline(&closes, SPARKLINE_POINTS);
+    }
+    widget.rows = movers
+        .iter()
+        .skip(1)
+        .take(MAX_WIDGET_ROWS)
+        .filter_map(|(series, _)| price_row(series))
+        .collect();
+    widget
+}
+
+pub fn render_quick_actions(now: DateTime<Utc>, ttl_secs: i64) -> WidgetPayload {
+    let mut widget = payload(
+        WidgetType::QuickActions,
+        "Quick actions".to_string(),
+        now,
+        ttl_secs,
+    );
+    widget.rows = [
+        ("buy_sol", "Buy SOL"),
+        ("sell_sol", "Sell SOL"),
+        ("view_portfolio", "View Portfolio"),
+    ]
+    .iter()
+    .map(|(id, label)| WidgetRow {
+        label: label.to_string(),
+        value_text: id.to_string(),
+        delta_text: None,
+        trend: WidgetTrend::Flat,
+    })
+    .collect();
+    widget
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(symbol: &str, closes: &[f64]) -> SymbolSeries {
+        SymbolSeries {
+            symbol: symbol.to_string(),
+            points: closes
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (i as i64 * 3600, *c))
+                .collect(),
+        }
+    }
+
+    fn position(symbol: &str, amount: f64, price: f64) -> Position {
+        Position {
+            symbol: symbol.to_string(),
+            mint: format!("{symbol}-mint"),
+            amount,
+            current_price: price,
+            avg_entry_price: price,
+            total_value: amount * price,
+            unrealized_pnl: 0.0,
+            unrealized_pnl_percent: 0.0,
+            allocation: 0.0,
+        }
+    }
+
+    #[test]
+    fn sparkline_is_bucketed_and_scaled() {
+        let values: Vec<f64> = (0..48).map(|i| i as f64).collect();
+        let line = downsample_sparkline(&values, SPARKLINE_POINTS);
+        assert_eq!(line.len(), SPARKLINE_POINTS);
+        assert_eq!(line[0], 0);
+        assert_eq!(line[SPARKLINE_POINTS - 1], 100);
+        assert!(line.windows(2).all(|w| w[0] <= w[1]));
+
+        assert_eq!(downsample_sparkline(&[5.0, 5.0, 5.0], 24), vec![50, 50, 50]);
+        assert!(downsample_sparkline(&[], 24).is_empty());
+    }
+
+    #[test]
+    fn display_text_is_ready_to_draw() {
+        assert_eq!(format_usd(12345.678), "$12,345.68");
+        assert_eq!(format_usd(-999.5), "-$999.50");
+        assert_eq!(format_usd(0.000023), "$0.000023");
+        assert_eq!(format_delta_pct(1.934), "+1.93%");
+        assert_eq!(format_delta_pct(-2.0), "-2.00%");
+    }
+
+    #[test]
+    fn portfolio_line_only_uses_hours_every_holding_has() {
+        let inputs = WidgetInputs {
+            positions: vec![position("SOL", 10.0, 110.0), position("JUP", 100.0, 1.0)],
+            series: vec![
+                series("SOL", &[100.0, 105.0, 110.0]),
+                series("JUP", &[1.0, 1.0]),
+            ],
+            alerts: Vec::new(),
+        };
+        let widget = render_portfolio_summary(&inputs, Utc::now(), 900);
+
+        assert_eq!(widget.headline, "$1,200.00");
+        // Only the first two hours are shared: 1100 -> 1150.
+        assert_eq!(widget.delta_text.as_deref(), Some("$50.00 (+4.55%)"));
+        assert_eq!(widget.trend, WidgetTrend::Up);
+        assert_eq!(widget.sparkline, vec![0, 100]);
+        assert_eq!(widget.rows[0].label, "SOL");
+    }
+}
@@ -1,8 +1,27 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::RwLock;
 
+use super::widget_payloads::{
+    render_alerts, render_portfolio_summary, render_price_watch, render_quick_actions,
+    render_top_movers, SymbolSeries, WidgetInputs, WidgetPayload,
+};
+use crate::alerts::SharedAlertManager;
+use crate::data::historical::SharedHistoricalReplayManager;
+use crate::portfolio::SharedPortfolioData;
+
+const REFRESH_TICK: StdDuration = StdDuration::from_secs(60);
+const SPARKLINE_WINDOW_SECS: i64 = 24 * 3600;
+/// Held symbols beyond this many (by value) are left off the widgets so a
+/// long tail of dust does not multiply the history queries.
+const MAX_WIDGET_SYMBOLS: usize = 8;
+const MIN_REFRESH_INTERVAL_SECS: i64 = 60;
+const MAX_RENDERS_PER_HOUR: u32 = 60;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WidgetData {
     pub widget_id: String,
@@ -11,7 +30,7 @@ pub struct WidgetData {
     pub last_update: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum WidgetType {
     PriceWatch,
@@ -21,14 +40,199 @@ pub enum WidgetType {
     QuickActions,
 }
 
+impl WidgetType {
+    pub const ALL: [WidgetType; 5] = [
+        WidgetType::PriceWatch,
+        WidgetType::PortfolioSummary,
+        WidgetType::Alerts,
+        WidgetType::TopMovers,
+        WidgetType::QuickActions,
+    ];
+
+    pub fn widget_id(&self) -> &'static str {
+        match self {
+            WidgetType::PriceWatch => "price_watch",
+            WidgetType::PortfolioSummary => "portfolio_summary",
+            WidgetType::Alerts => "alerts",
+            WidgetType::TopMovers => "top_movers",
+            WidgetType::QuickActions => "quick_actions",
+        }
+    }
+
+    fn needs_prices(&self) -> bool {
+        matches!(
+            self,
+            WidgetType::PriceWatch | WidgetType::PortfolioSummary | WidgetType::TopMovers
+        )
+    }
+}
+
+/// How often a widget is re-rendered on schedule and the most renders it
+/// may cost in any rolling hour, including refreshes the phone asks for.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetRefreshBudget {
+    pub refresh_interval_secs: i64,
+    pub max_renders_per_hour: u32,
+}
+
+impl WidgetRefreshBudget {
+    pub fn default_for(widget_type: WidgetType) -> Self {
+        let (refresh_interval_secs, max_renders_per_hour) = match widget_type {
+            WidgetType::PriceWatch | WidgetType::Alerts => (300, 15),
+            WidgetType::PortfolioSummary | WidgetType::TopMovers => (900, 6),
+            WidgetType::QuickActions => (86_400, 2),
+        };
+        Self {
+            refresh_interval_secs,
+            max_renders_per_hour,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetRefreshTicket {
+    pub widget_type: WidgetType,
+    pub queued: bool,
+    /// Earliest time a fresh payload can be expected.
+    pub next_render_at: i64,
+}
+
 pub struct WidgetManager {
-    widgets: Vec<WidgetData>,
+    payloads: HashMap<WidgetType, WidgetPayload>,
+    budgets: HashMap<WidgetType, WidgetRefreshBudget>,
+    /// Render timestamps inside the last hour, oldest first.
+    renders: HashMap<WidgetType, VecDeque<i64>>,
+    pending: HashSet<WidgetType>,
+    watch_symbols: Vec<String>,
 }
 
 impl WidgetManager {
     pub fn new() -> Self {
         Self {
-            widgets: Vec::new(),
+            payloads: HashMap::new(),
+            budgets: WidgetType::ALL
+                .iter()
+                .map(|t| (*t, WidgetRefreshBudget::default_for(*t)))
+                .collect(),
+            renders: HashMap::new(),
+            pending: HashSet::new(),
+            watch_symbols: vec!["SOL".to_string(), "JUP".to_string(), "BONK".to_string()],
+        }
+    }
+
+    pub fn budget(&self, widget_type: WidgetType) -> WidgetRefreshBudget {
+        self.budgets
+            .get(&widget_type)
+            .copied()
+            .unwrap_or_else(|| WidgetRefreshBudget::default_for(widget_type))
+    }
+
+    pub fn budgets(&self) -> HashMap<WidgetType, WidgetRefreshBudget> {
+        WidgetType::ALL
+            .iter()
+            .map(|t| (*t, self.budget(*t)))
+            .collect()
+    }
+
+    pub fn set_budget(
+        &mut self,
+        widget_type: WidgetType,
+        budget: WidgetRefreshBudget,
+    ) -> Result<(), String> {
+        if budget.refresh_interval_secs < MIN_REFRESH_INTERVAL_SECS {
+            return Err(format!(
+                "Widgets refresh at most every {} seconds",
+                MIN_REFRESH_INTERVAL_SECS
+            ));
+        }
+        if budget.max_renders_per_hour == 0 || budget.max_renders_per_hour > MAX_RENDERS_PER_HOUR {
+            return Err(format!(
+                "Renders per hour must be between 1 and {}",
+                MAX_RENDERS_PER_HOUR
+            ));
+        }
+        self.budgets.insert(widget_type, budget);
+        Ok(())
+    }
+
+    pub fn watch_symbols(&self) -> Vec<String> {
+        self.watch_symbols.clone()
+    }
+
+    pub fn payload(&self, widget_type: WidgetType) -> Option<WidgetPayload> {
+        self.payloads.get(&widget_type).cloned()
+    }
+
+    pub fn payloads(&self) -> Vec<WidgetPayload> {
+        WidgetType::ALL
+            .iter()
+            .filter_map(|t| self.payloads.get(t).cloned())
+            .collect()
+    }
+
+    fn renders_in_last_hour(&self, widget_type: WidgetType, now: i64) -> Vec<i64> {
+        self.renders
+            .get(&widget_type)
+            .map(|times| times.iter().copied().filter(|t| now - t < 3600).collect())
+            .unwrap_or_default()
+    }
+
+    fn has_budget(&self, widget_type: WidgetType, now: i64) -> bool {
+        (self.renders_in_last_hour(widget_type, now).len() as u32)
+            < self.budget(widget_type).max_renders_per_hour
+    }
+
+    /// Widgets the scheduler should render now: those past their interval
+    /// or with a queued refresh, as long as the hourly budget allows.
+    pub fn due_widgets(&self, now: i64) -> Vec<WidgetType> {
+        WidgetType::ALL
+            .iter()
+            .copied()
+            .filter(|t| self.has_budget(*t, now))
+            .filter(|t| {
+                self.pending.contains(t)
+                    || self.payloads.get(t).map_or(true, |p| {
+                        now - p.generated_at >= self.budget(*t).refresh_interval_secs
+                    })
+            })
+            .collect()
+    }
+
+    pub fn store_payload(&mut self, payload: WidgetPayload) {
+        let widget_type = payload.widget_type;
+        let now = payload.generated_at;
+        let times = self.renders.entry(widget_type).or_default();
+        times.push_back(now);
+        while times.front().is_some_and(|t| now - t >= 3600) {
+            times.pop_front();
+        }
+        self.pending.remove(&widget_type);
+        self.payloads.insert(widget_type, payload);
+    }
+
+    /// Queues a render for the next scheduler tick. The phone never renders
+    /// directly; a request past the hourly budget is refused with the time
+    /// the budget frees up.
+    pub fn request_refresh(&mut self, widget_type: WidgetType, now: i64) -> WidgetRefreshTicket {
+        if self.has_budget(widget_type, now) {
+            self.pending.insert(widget_type);
+            return WidgetRefreshTicket {
+                widget_type,
+                queued: true,
+                next_render_at: now + REFRESH_TICK.as_secs() as i64,
+            };
+        }
+        let oldest = self
+            .renders_in_last_hour(widget_type, now)
+            .first()
+            .copied()
+            .unwrap_or(now);
+        WidgetRefreshTicket {
+            widget_type,
+            queued: false,
+            next_render_at: oldest + 3600,
         }
     }
 
@@ -136,6 +340,130 @@ impl WidgetManager {
     }
 }
 
+pub fn render_widget(
+    widget_type: WidgetType,
+    inputs: &WidgetInputs,
+    watch: &[String],
+    budget: WidgetRefreshBudget,
+    now: DateTime<Utc>,
+) -> WidgetPayload {
+    let ttl = budget.refresh_interval_secs;
+    match widget_type {
+        WidgetType::PriceWatch => render_price_watch(inputs, watch, now, ttl),
+        WidgetType::PortfolioSummary => render_portfolio_summary(inputs, now, ttl),
+        WidgetType::Alerts => render_alerts(inputs, now, ttl),
+        WidgetType::TopMovers => render_top_movers(inputs, now, ttl),
+        WidgetType::QuickActions => render_quick_actions(now, ttl),
+    }
+}
+
+/// Collects the data for one render pass. Price history is only queried
+/// when a due widget draws prices, and only for the watch list plus the
+/// largest holdings.
+async fn gather_widget_inputs(
+    app: &AppHandle,
+    watch: &[String],
+    needs_prices: bool,
+    now: DateTime<Utc>,
+) -> WidgetInputs {
+    let mut inputs = WidgetInputs::default();
+
+    if let Some(portfolio) = app.try_state::<SharedPortfolioData>() {
+        if let Ok(data) = portfolio.lock() {
+            inputs.positions = data.positions();
+        }
+    }
+    if let Some(alerts) = app.try_state::<SharedAlertManager>() {
+        match alerts.read().await.list_alerts().await {
+            Ok(list) => inputs.alerts = list,
+            Err(e) => eprintln!("Widget alert load failed: {}", e),
+        }
+    }
+    if !needs_prices {
+        return inputs;
+    }
+
+    let mut holdings = inputs.positions.clone();
+    holdings.sort_by(|a, b| {
+        b.total_value
+            .partial_cmp(&a.total_value)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let mut symbols: Vec<String> = watch.to_vec();
+    for position in holdings {
+        if symbols.len() >= MAX_WIDGET_SYMBOLS {
+            break;
+        }
+        if !symbols.contains(&position.symbol) {
+            symbols.push(position.symbol);
+        }
+    }
+
+    if let Some(historical) = app.try_state::<SharedHistoricalReplayManager>() {
+        let end = now.timestamp();
+        let manager = historical.read().await;
+        for symbol in symbols {
+            match manager
+                .price_history(&symbol, "1h", end - SPARKLINE_WINDOW_SECS, end)
+                .await
+            {
+                Ok(points) => inputs.series.push(SymbolSeries {
+                    symbol,
+                    points: points.iter().map(|p| (p.timestamp, p.close)).collect(),
+                }),
+                Err(e) => eprintln!("Widget price history failed for {}: {}", symbol, e),
+            }
+        }
+    }
+    inputs
+}
+
+async fn refresh_due_widgets(app: &AppHandle, state: &Arc<RwLock<WidgetManager>>) {
+    let now = Utc::now();
+    let (due, watch, budgets) = {
+        let manager = state.read().await;
+        (
+            manager.due_widgets(now.timestamp()),
+            manager.watch_symbols(),
+            manager.budgets(),
+        )
+    };
+    if due.is_empty() {
+        return;
+    }
+
+    let needs_prices = due.iter().any(|t| t.needs_prices());
+    let inputs = gather_widget_inputs(app, &watch, needs_prices, now).await;
+    let rendered: Vec<WidgetPayload> = due
+        .iter()
+        .map(|t| {
+            let budget = budgets
+                .get(t)
+                .copied()
+                .unwrap_or_else(|| WidgetRefreshBudget::default_for(*t));
+            render_widget(*t, &inputs, &watch, budget, now)
+        })
+        .collect();
+
+    {
+        let mut manager = state.write().await;
+        for payload in &rendered {
+            manager.store_payload(payload.clone());
+        }
+    }
+    let _ = app.emit("mobile_widgets_updated", &rendered);
+}
+
+pub fn start_widget_refresher(app: AppHandle, state: Arc<RwLock<WidgetManager>>) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(REFRESH_TICK);
+        loop {
+            ticker.tick().await;
+            refresh_due_widgets(&app, &state).await;
+        }
+    });
+}
+
 #[tauri::command]
 pub async fn mobile_get_widget_data(
     widget_type: WidgetType,
@@ -152,3 +480,118 @@ pub async fn mobile_get_all_widgets(
     let manager = widget_manager.read().await;
     Ok(manager.get_all_widget_data().await)
 }
+
+#[tauri::command]
+pub async fn mobile_get_widget_payload(
+    widget_type: WidgetType,
+    widget_manager: tauri::State<'_, Arc<RwLock<WidgetManager>>>,
+) -> Result<Option<WidgetPayload>, String> {
+    Ok(widget_manager.read().await.payload(widget_type))
+}
+
+#[tauri::command]
+pub async fn mobile_get_widget_payloads(
+    widget_manager: tauri::State<'_, Arc<RwLock<WidgetManager>>>,
+) -> Result<Vec<WidgetPayload>, String> {
+    Ok(widget_manager.read().await.payloads())
+}
+
+#[tauri::command]
+pub async fn mobile_request_widget_refresh(
+    device_id: String,
+    widget_type: WidgetType,
+    widget_manager: tauri::State<'_, Arc<RwLock<WidgetManager>>>,
+    mobile_auth: tauri::State<'_, Arc<RwLock<crate::mobile::auth::MobileAuthManager>>>,
+) -> Result<WidgetRefreshTicket, String> {
+    let registered = mobile_auth
+        .read()
+        .await
+        .get_devices()
+        .iter()
+        .any(|device| device.device_id == device_id);
+    if !registered {
+        return Err("Device not registered".into());
+    }
+
+    let mut manager = widget_manager.write().await;
+    Ok(manager.request_refresh(widget_type, Utc::now().timestamp()))
+}
+
+#[tauri::command]
+pub async fn mobile_get_widget_budgets(
+    widget_manager: tauri::State<'_, Arc<RwLock<WidgetManager>>>,
+) -> Result<HashMap<WidgetType, WidgetRefreshBudget>, String> {
+    Ok(widget_manager.read().await.budgets())
+}
+
+#[tauri::command]
+pub async fn mobile_set_widget_budget(
+    widget_type: WidgetType,
+    budget: WidgetRefreshBudget,
+    widget_manager: tauri::State<'_, Arc<RwLock<WidgetManager>>>,
+) -> Result<(), String> {
+    widget_manager.write().await.set_budget(widget_type, budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendered(widget_type: WidgetType, at: i64) -> WidgetPayload {
+        let now = DateTime::from_timestamp(at, 0).unwrap();
+        render_widget(
+            widget_type,
+            &WidgetInputs::default(),
+            &[],
+            WidgetRefreshBudget::default_for(widget_type),
+            now,
+        )
+    }
+
+    #[test]
+    fn widgets_render_on_their_interval() {
+        let mut manager = WidgetManager::new();
+        let start = 1_700_000_000;
+        assert_eq!(manager.due_widgets(start).len(), WidgetType::ALL.len());
+
+        for t in WidgetType::ALL {
+            manager.store_payload(rendered(t, start));
+        }
+        assert!(manager.due_widgets(start + 60).is_empty());
+        assert_eq!(
+            manager.due_widgets(start + 300),
+            vec![WidgetType::PriceWatch, WidgetType::Alerts]
+        );
+    }
+
+    #[test]
+    fn phone_refreshes_are_queued_within_the_hourly_budget() {
+        let mut manager = WidgetManager::new();
+        manager
+            .set_budget(
+                WidgetType::TopMovers,
+                WidgetRefreshBudget {
+                    refresh_interval_secs: 900,
+                    max_renders_per_hour: 2,
+                },
+            )
+            .unwrap();
+        let start = 1_700_000_000;
+        manager.store_payload(rendered(WidgetType::TopMovers, start));
+
+        let ticket = manager.request_refresh(WidgetType::TopMovers, start + 10);
+        assert!(ticket.queued);
+        assert!(manager
+            .due_widgets(start + 10)
+            .contains(&WidgetType::TopMovers));
+        manager.store_payload(rendered(WidgetType::TopMovers, start + 60));
+
+        // Two renders this hour: the next request waits for the first to age out.
+        let ticket = manager.request_refresh(WidgetType::TopMovers, start + 120);
+        assert!(!ticket.queued);
+        assert_eq!(ticket.next_render_at, start + 3600);
+        assert!(!manager
+            .due_widgets(start + 1000)
+            .contains(&WidgetType::TopMovers));
+    }
+}
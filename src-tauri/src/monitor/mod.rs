@@ -1,3 +1,11 @@
+pub mod network;
 pub mod performance;
+pub mod profiler;
+pub mod query_log;
+pub mod runtime;
 
+pub use network::*;
 pub use performance::*;
+pub use profiler::*;
+pub use query_log::*;
+pub use runtime::*;
@@ -0,0 +1,123 @@
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+static NETWORK_USAGE: OnceLock<Mutex<HashMap<&'static str, NetworkCounters>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NetworkCounters {
+    pub sent_bytes: u64,
+    pub received_bytes: u64,
+    pub messages: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsystemNetworkUsage {
+    pub subsystem: String,
+    pub sent_bytes_total: u64,
+    pub received_bytes_total: u64,
+    pub messages_total: u64,
+    pub sent_kb_per_sec: f64,
+    pub recv_kb_per_sec: f64,
+    pub messages_per_sec: f64,
+}
+
+/// Attributes traffic to a subsystem. The OS only reports per-interface
+/// totals, so subsystems report what they send and receive themselves;
+/// callers that cannot see the payload size record the message with zero
+/// bytes.
+pub fn record_network_usage(subsystem: &'static str, sent_bytes: u64, received_bytes: u64) {
+    let mut usage = NETWORK_USAGE.get_or_init(Default::default).lock();
+    let counters = usage.entry(subsystem).or_default();
+    counters.sent_bytes += sent_bytes;
+    counters.received_bytes += received_bytes;
+    counters.messages += 1;
+}
+
+pub fn network_totals() -> HashMap<&'static str, NetworkCounters> {
+    NETWORK_USAGE
+        .get()
+        .map(|usage| usage.lock().clone())
+        .unwrap_or_default()
+}
+
+/// Per-subsystem throughput between two snapshots of the counters.
+pub fn subsystem_rates(
+    previous: &HashMap<&'static str, NetworkCounters>,
+    current: &HashMap<&'static str, NetworkCounters>,
+    elapsed_secs: f64,
+) -> Vec<SubsystemNetworkUsage> {
+    let per_sec = |delta: u64| {
+        if elapsed_secs > 0.0 {
+            delta as f64 / elapsed_secs
+        } else {
+            0.0
+        }
+    };
+    let mut usage: Vec<SubsystemNetworkUsage> = current
+        .iter()
+        .map(|(subsystem, now)| {
+            let before = previous.get(subsystem).copied().unwrap_or_default();
+            SubsystemNetworkUsage {
+                subsystem: subsystem.to_string(),
+                sent_bytes_total: now.sent_bytes,
+                received_bytes_total: now.received_bytes,
+                messages_total: now.messages,
+                sent_kb_per_sec: per_sec(now.sent_bytes.saturating_sub(before.sent_bytes)) / 1024.0,
+                recv_kb_per_sec: per_sec(now.received_bytes.saturating_sub(before.received_bytes))
+                    / 1024.0,
+                messages_per_sec: per_sec(now.messages.saturating_sub(before.messages)),
+            }
+        })
+        .collect();
+    usage.sort_by(|a, b| a.subsystem.cmp(&b.subsystem));
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rates_are_computed_from_counter_deltas() {
+        let previous = HashMap::from([(
+            "rpc",
+            NetworkCounters {
+                sent_bytes: 1024,
+                received_bytes: 2048,
+                messages: 4,
+            },
+        )]);
+        let current = HashMap::from([
+            (
+                "rpc",
+                NetworkCounters {
+                    sent_bytes: 3072,
+                    received_bytes: 6144,
+                    messages: 8,
+                },
+            ),
+            (
+                "birdeye_ws",
+                NetworkCounters {
+                    sent_bytes: 0,
+                    received_bytes: 1024,
+                    messages: 1,
+                },
+            ),
+        ]);
+
+        let usage = subsystem_rates(&previous, &current, 2.0);
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].subsystem, "birdeye_ws");
+        assert_eq!(usage[0].recv_kb_per_sec, 0.5);
+        assert_eq!(usage[1].sent_kb_per_sec, 1.0);
+        assert_eq!(usage[1].recv_kb_per_sec, 2.0);
+        assert_eq!(usage[1].messages_per_sec, 2.0);
+        assert!(subsystem_rates(&previous, &current, 0.0)
+            .iter()
+            .all(|u| u.messages_per_sec == 0.0));
+    }
+}
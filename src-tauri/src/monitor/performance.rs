@@ -2,10 +2,14 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
-use sysinfo::{CpuExt, DiskExt, NetworkExt, ProcessExt, System, SystemExt};
+use sysinfo::{CpuExt, NetworkExt, ProcessExt, System, SystemExt};
 use tokio::sync::broadcast;
-use tokio::time::{self, Duration};
+use tokio::time::{self, Duration, Instant};
+
+use super::network::{network_totals, subsystem_rates, NetworkCounters, SubsystemNetworkUsage};
+use super::runtime::{sample_runtime, RuntimeMetricsSnapshot};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -15,10 +19,18 @@ pub struct PerformanceMetrics {
     pub memory_usage: f32,
     pub total_memory_mb: f32,
     pub used_memory_mb: f32,
+    /// Totals for this process since it started.
     pub disk_read_kb: f64,
     pub disk_write_kb: f64,
+    /// Totals across all interfaces since boot.
     pub net_sent_kb: f64,
     pub net_recv_kb: f64,
+    pub disk_read_kb_per_sec: f64,
+    pub disk_write_kb_per_sec: f64,
+    pub net_sent_kb_per_sec: f64,
+    pub net_recv_kb_per_sec: f64,
+    pub network_by_subsystem: Vec<SubsystemNetworkUsage>,
+    pub runtime: RuntimeMetricsSnapshot,
     pub process_cpu_usage: f32,
     pub process_memory_mb: f64,
     pub fps_estimate: Option<f32>,
@@ -27,6 +39,15 @@ pub struct PerformanceMetrics {
 
 pub type SharedPerformanceMonitor = Arc<PerformanceMonitor>;
 
+/// State carried between refreshes to turn counters into rates.
+#[derive(Default)]
+struct Sample {
+    elapsed_secs: f64,
+    previous_network: HashMap<&'static str, NetworkCounters>,
+    current_network: HashMap<&'static str, NetworkCounters>,
+    runtime: RuntimeMetricsSnapshot,
+}
+
 pub struct PerformanceMonitor {
     system: Arc<RwLock<System>>,
     latest_metrics: Arc<RwLock<PerformanceMetrics>>,
@@ -38,7 +59,7 @@ impl PerformanceMonitor {
         let mut system = System::new_all();
         system.refresh_all();
 
-        let metrics = Self::capture_metrics(&system);
+        let metrics = Self::capture_metrics(&system, &Sample::default());
         let (tx, _rx) = broadcast::channel(128);
 
         Self {
@@ -55,12 +76,23 @@ impl PerformanceMonitor {
 
         tauri::async_runtime::spawn(async move {
             let mut interval = time::interval(Duration::from_millis(500));
+            let mut last_refresh = Instant::now();
+            let mut previous_network = network_totals();
             loop {
                 interval.tick().await;
+                let runtime = sample_runtime().await;
+                let network = network_totals();
                 {
                     let mut system = system.write();
                     system.refresh_all();
-                    let metrics = PerformanceMonitor::capture_metrics(&system);
+                    let sample = Sample {
+                        elapsed_secs: last_refresh.elapsed().as_secs_f64(),
+                        previous_network: std::mem::replace(&mut previous_network, network.clone()),
+                        current_network: network,
+                        runtime,
+                    };
+                    last_refresh = Instant::now();
+                    let metrics = PerformanceMonitor::capture_metrics(&system, &sample);
                     *latest_metrics.write() = metrics.clone();
                     let _ = tx.send(metrics);
                 }
@@ -70,34 +102,21 @@ impl PerformanceMonitor {
         Ok(())
     }
 
-    fn capture_metrics(system: &System) -> PerformanceMetrics {
+    fn capture_metrics(system: &System, sample: &Sample) -> PerformanceMetrics {
+        let per_sec = |bytes: u64| {
+            if sample.elapsed_secs > 0.0 {
+                bytes as f64 / 1024.0 / sample.elapsed_secs
+            } else {
+                0.0
+            }
+        };
+
         let global_cpu = system.global_cpu_info();
         let cpu_usage = global_cpu.cpu_usage();
 
         let total_memory = system.total_memory() as f32 / 1024.0;
         let used_memory = system.used_memory() as f32 / 1024.0;
 
-        let disk_read = system
-            .disks()
-            .iter()
-            .map(|d| {
-                // Use available disk methods in sysinfo 0.29
-                // Fall back to 0 if no method available
-                0u64
-            })
-            .sum::<u64>() as f64
-            / 1024.0;
-        let disk_write = system
-            .disks()
-            .iter()
-            .map(|d| {
-                // Use available disk methods in sysinfo 0.29
-                // Fall back to 0 if no method available
-                0u64
-            })
-            .sum::<u64>() as f64
-            / 1024.0;
-
         let net_sent = system
             .networks()
             .into_iter()
@@ -110,6 +129,12 @@ impl PerformanceMonitor {
             .map(|(_, data)| data.total_received())
             .sum::<u64>() as f64
             / 1024.0;
+        let (net_sent_delta, net_recv_delta) = system
+            .networks()
+            .into_iter()
+            .fold((0u64, 0u64), |(sent, recv), (_, data)| {
+                (sent + data.transmitted(), recv + data.received())
+            });
 
         let process = system.process(sysinfo::Pid::from(std::process::id() as usize));
 
@@ -118,6 +143,9 @@ impl PerformanceMonitor {
         } else {
             (0.0, 0.0)
         };
+        // sysinfo only reports disk I/O per process, which is what matters
+        // when diagnosing the app itself.
+        let disk = process.map(|p| p.disk_usage()).unwrap_or_default();
 
         PerformanceMetrics {
             timestamp: Utc::now(),
@@ -125,14 +153,24 @@ impl PerformanceMonitor {
             memory_usage: (used_memory / total_memory) * 100.0,
             total_memory_mb: total_memory,
             used_memory_mb: used_memory,
-            disk_read_kb: disk_read,
-            disk_write_kb: disk_write,
+            disk_read_kb: disk.total_read_bytes as f64 / 1024.0,
+            disk_write_kb: disk.total_written_bytes as f64 / 1024.0,
             net_sent_kb: net_sent,
             net_recv_kb: net_recv,
+            disk_read_kb_per_sec: per_sec(disk.read_bytes),
+            disk_write_kb_per_sec: per_sec(disk.written_bytes),
+            net_sent_kb_per_sec: per_sec(net_sent_delta),
+            net_recv_kb_per_sec: per_sec(net_recv_delta),
+            network_by_subsystem: subsystem_rates(
+                &sample.previous_network,
+                &sample.current_network,
+                sample.elapsed_secs,
+            ),
+            event_loop_lag_ms: Some(sample.runtime.scheduler_lag_ms),
+            runtime: sample.runtime.clone(),
             process_cpu_usage,
             process_memory_mb: process_memory,
             fps_estimate: None,
-            event_loop_lag_ms: None,
         }
     }
 
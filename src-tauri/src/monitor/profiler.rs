@@ -0,0 +1,334 @@
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::{LookupSpan, Registry};
+
+const DEFAULT_CAPTURE_MS: u64 = 5_000;
+const MAX_CAPTURE_MS: u64 = 60_000;
+const HOTTEST_SPANS: usize = 20;
+
+static PROFILER: OnceLock<Arc<Profiler>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadProfile {
+    pub thread: String,
+    /// Time spent inside top-level spans on this thread.
+    pub busy_ms: f64,
+    pub spans: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpanTiming {
+    pub thread: String,
+    pub stack: String,
+    pub calls: u64,
+    pub total_ms: f64,
+    pub self_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileCapture {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub threads: Vec<ThreadProfile>,
+    pub hottest: Vec<SpanTiming>,
+    /// Collapsed stacks (`thread;outer;inner <self time in µs>`) that
+    /// flamegraph.pl, inferno and speedscope load directly.
+    pub folded_stacks: String,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct StackStats {
+    calls: u64,
+    total: Duration,
+    /// Time in this frame minus time in child spans; may go negative
+    /// briefly while a child exits before its parent is recorded.
+    self_us: i64,
+}
+
+/// Times every span entered while a capture is running, keyed by thread and
+/// span stack. Async spans are entered once per poll, so the recorded time
+/// is time actually spent on the thread.
+#[derive(Default)]
+pub struct Profiler {
+    capturing: AtomicBool,
+    stacks: Mutex<HashMap<(String, String), StackStats>>,
+}
+
+struct EnteredAt(Instant);
+
+/// Command name of an IPC span, used to label profile frames and to
+/// attribute work done underneath the span to the command.
+struct CommandName(String);
+
+/// Picks out the command name of IPC spans so each command is its own frame.
+#[derive(Default)]
+struct CommandVisitor(Option<String>);
+
+impl Visit for CommandVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if matches!(field.name(), "cmd" | "command") {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if matches!(field.name(), "cmd" | "command") {
+            self.0 = Some(format!("{:?}", value).trim_matches('"').to_string());
+        }
+    }
+}
+
+fn thread_label() -> String {
+    let thread = std::thread::current();
+    format!("{}[{:?}]", thread.name().unwrap_or("unnamed"), thread.id())
+}
+
+impl Profiler {
+    fn record(&self, thread: String, stack: String, parent: Option<String>, elapsed: Duration) {
+        let mut stacks = self.stacks.lock();
+        let micros = elapsed.as_micros() as i64;
+        let entry = stacks.entry((thread.clone(), stack)).or_default();
+        entry.calls += 1;
+        entry.total += elapsed;
+        entry.self_us += micros;
+        if let Some(parent) = parent {
+            stacks.entry((thread, parent)).or_default().self_us -= micros;
+        }
+    }
+
+    fn begin(&self) -> Result<(), String> {
+        if self.capturing.swap(true, Ordering::SeqCst) {
+            return Err("A profile capture is already running".to_string());
+        }
+        self.stacks.lock().clear();
+        Ok(())
+    }
+
+    fn finish(&self, started_at: DateTime<Utc>, duration: Duration) -> ProfileCapture {
+        self.capturing.store(false, Ordering::SeqCst);
+        let stacks = std::mem::take(&mut *self.stacks.lock());
+        summarize(stacks, started_at, duration)
+    }
+}
+
+fn summarize(
+    stacks: HashMap<(String, String), StackStats>,
+    started_at: DateTime<Utc>,
+    duration: Duration,
+) -> ProfileCapture {
+    let mut threads: HashMap<String, ThreadProfile> = HashMap::new();
+    let mut folded = Vec::new();
+    let mut hottest = Vec::new();
+
+    for ((thread, stack), stats) in stacks {
+        let profile = threads
+            .entry(thread.clone())
+            .or_insert_with(|| ThreadProfile {
+                thread: thread.clone(),
+                busy_ms: 0.0,
+                spans: 0,
+            });
+        profile.spans += stats.calls;
+        if !stack.contains(';') {
+            profile.busy_ms += stats.total.as_secs_f64() * 1000.0;
+        }
+        if stats.self_us > 0 {
+            folded.push(format!("{};{} {}", thread, stack, stats.self_us));
+        }
+        hottest.push(SpanTiming {
+            thread,
+            stack,
+            calls: stats.calls,
+            total_ms: stats.total.as_secs_f64() * 1000.0,
+            self_ms: stats.self_us.max(0) as f64 / 1000.0,
+        });
+    }
+
+    folded.sort();
+    hottest.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+    hottest.truncate(HOTTEST_SPANS);
+    let mut threads: Vec<ThreadProfile> = threads.into_values().collect();
+    threads.sort_by(|a, b| b.busy_ms.total_cmp(&a.busy_ms));
+
+    let mut folded_stacks = String::new();
+    for line in folded {
+        let _ = writeln!(folded_stacks, "{line}");
+    }
+
+    ProfileCapture {
+        started_at,
+        duration_ms: duration.as_millis() as u64,
+        threads,
+        hottest,
+        folded_stacks,
+    }
+}
+
+pub struct ProfilingLayer {
+    profiler: Arc<Profiler>,
+}
+
+impl<S> Layer<S> for ProfilingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = CommandVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(command), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(CommandName(command));
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if !self.profiler.capturing.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().replace(EnteredAt(Instant::now()));
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let Some(EnteredAt(entered)) = span.extensions_mut().remove::<EnteredAt>() else {
+            return;
+        };
+        if !self.profiler.capturing.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let frames: Vec<String> = span
+            .scope()
+            .from_root()
+            .map(|frame| {
+                frame
+                    .extensions()
+                    .get::<CommandName>()
+                    .map(|command| format!("{}[{}]", frame.name(), command.0))
+                    .unwrap_or_else(|| frame.name().to_string())
+            })
+            .collect();
+        let stack = frames.join(";");
+        // Only charge the parent if it is running on this thread right now;
+        // otherwise the child ran in a separate poll.
+        let parent = span
+            .parent()
+            .filter(|parent| parent.extensions().get::<EnteredAt>().is_some())
+            .map(|_| frames[..frames.len() - 1].join(";"));
+        self.profiler
+            .record(thread_label(), stack, parent, entered.elapsed());
+    }
+}
+
+/// Installs the profiling layer as the global tracing subscriber. Spans are
+/// only timed while a capture is running.
+pub fn install_profiler() {
+    let profiler = PROFILER.get_or_init(Default::default).clone();
+    let subscriber = tracing_subscriber::registry().with(ProfilingLayer { profiler });
+    if let Err(e) = tracing::subscriber::set_global_default(subscriber) {
+        eprintln!("Profiler not installed: {}", e);
+    }
+}
+
+/// Name of the Tauri command whose span the current thread is inside, if
+/// any. Async commands are instrumented, so this holds across awaits.
+pub fn current_command() -> Option<String> {
+    tracing::dispatcher::get_default(|dispatch| {
+        let registry = dispatch.downcast_ref::<Registry>()?;
+        let id = dispatch.current_span().id()?.clone();
+        registry
+            .span(&id)?
+            .scope()
+            .find_map(|frame| frame.extensions().get::<CommandName>().map(|c| c.0.clone()))
+    })
+}
+
+/// Records span timings for `duration_ms` and returns them, for diagnosing
+/// UI freezes while they happen.
+#[tauri::command]
+pub async fn capture_performance_profile(
+    duration_ms: Option<u64>,
+) -> Result<ProfileCapture, String> {
+    let profiler = PROFILER
+        .get()
+        .cloned()
+        .ok_or_else(|| "Profiler is not installed".to_string())?;
+    let duration = Duration::from_millis(
+        duration_ms
+            .unwrap_or(DEFAULT_CAPTURE_MS)
+            .clamp(100, MAX_CAPTURE_MS),
+    );
+
+    profiler.begin()?;
+    let started_at = Utc::now();
+    tokio::time::sleep(duration).await;
+    Ok(profiler.finish(started_at, duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_spans_fold_into_self_time() {
+        let profiler = Arc::new(Profiler::default());
+        let subscriber = tracing_subscriber::registry().with(ProfilingLayer {
+            profiler: profiler.clone(),
+        });
+        profiler.begin().unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let outer = tracing::info_span!("outer");
+            let _outer = outer.enter();
+            std::thread::sleep(Duration::from_millis(5));
+            for _ in 0..2 {
+                let inner = tracing::info_span!("inner", cmd = "get_portfolio");
+                let _inner = inner.enter();
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        });
+
+        let capture = profiler.finish(Utc::now(), Duration::from_millis(20));
+        assert_eq!(capture.threads.len(), 1);
+        assert_eq!(capture.threads[0].spans, 3);
+
+        let inner = capture
+            .hottest
+            .iter()
+            .find(|s| s.stack == "outer;inner[get_portfolio]")
+            .unwrap();
+        assert_eq!(inner.calls, 2);
+        let outer = capture.hottest.iter().find(|s| s.stack == "outer").unwrap();
+        assert!(outer.self_ms < outer.total_ms - inner.total_ms + 1.0);
+        assert_eq!(capture.folded_stacks.lines().count(), 2);
+        assert!(capture
+            .folded_stacks
+            .lines()
+            .any(|l| l.contains(";outer;inner[get_portfolio] ")));
+
+        // Spans outside a capture are not recorded.
+        let subscriber = tracing_subscriber::registry().with(ProfilingLayer {
+            profiler: profiler.clone(),
+        });
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("idle").entered();
+        });
+        assert!(profiler.stacks.lock().is_empty());
+    }
+}
@@ -0,0 +1,373 @@
+use chrono::{DateTime, Utc};
+use log::{LevelFilter, Log, Metadata, Record};
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+use super::profiler::current_command;
+
+const DEFAULT_SLOW_QUERY_MS: u64 = 100;
+const MAX_TRACKED_STATEMENTS: usize = 500;
+const MAX_SLOW_ENTRIES: usize = 200;
+const DEFAULT_REPORT_LIMIT: usize = 20;
+const BACKGROUND_COMMAND: &str = "(background)";
+
+static QUERY_LOG: OnceLock<QueryLog> = OnceLock::new();
+static QUERY_LOG_SINK: QueryLogSink = QueryLogSink;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryStats {
+    pub sql: String,
+    pub occurrences: u64,
+    pub max_ms: f64,
+    pub total_ms: f64,
+    pub last_seen: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowQueryEntry {
+    pub at: DateTime<Utc>,
+    pub command: String,
+    pub sql: String,
+    pub elapsed_ms: f64,
+}
+
+/// Database time charged to one Tauri command across all of its statements.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandDbTime {
+    pub command: String,
+    pub statements: u64,
+    pub total_ms: f64,
+    pub max_ms: f64,
+    pub slow_statements: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryLogReport {
+    pub since: DateTime<Utc>,
+    pub slow_threshold_ms: u64,
+    pub total_statements: u64,
+    pub total_ms: f64,
+    pub commands: Vec<CommandDbTime>,
+    pub statements: Vec<QueryStats>,
+    pub recent_slow: Vec<SlowQueryEntry>,
+}
+
+struct QueryLogState {
+    since: DateTime<Utc>,
+    total_statements: u64,
+    total_ms: f64,
+    statements: HashMap<String, QueryStats>,
+    commands: HashMap<String, CommandDbTime>,
+    slow: VecDeque<SlowQueryEntry>,
+}
+
+impl Default for QueryLogState {
+    fn default() -> Self {
+        Self {
+            since: Utc::now(),
+            total_statements: 0,
+            total_ms: 0.0,
+            statements: HashMap::new(),
+            commands: HashMap::new(),
+            slow: VecDeque::new(),
+        }
+    }
+}
+
+/// Aggregates every statement sqlx executes: per normalized statement, per
+/// calling command, and a ring of the most recent slow ones.
+pub struct QueryLog {
+    slow_threshold_ms: AtomicU64,
+    state: Mutex<QueryLogState>,
+}
+
+impl Default for QueryLog {
+    fn default() -> Self {
+        Self {
+            slow_threshold_ms: AtomicU64::new(DEFAULT_SLOW_QUERY_MS),
+            state: Mutex::new(QueryLogState::default()),
+        }
+    }
+}
+
+impl QueryLog {
+    pub fn slow_threshold_ms(&self) -> u64 {
+        self.slow_threshold_ms.load(Ordering::Relaxed)
+    }
+
+    pub fn set_slow_threshold_ms(&self, threshold_ms: u64) {
+        self.slow_threshold_ms
+            .store(threshold_ms.max(1), Ordering::Relaxed);
+    }
+
+    fn record(&self, command: Option<String>, sql: &str, elapsed_ms: f64) {
+        let sql = redact_sql(sql);
+        let command = command.unwrap_or_else(|| BACKGROUND_COMMAND.to_string());
+        let slow = elapsed_ms >= self.slow_threshold_ms() as f64;
+        let now = Utc::now();
+
+        let mut state = self.state.lock();
+        state.total_statements += 1;
+        state.total_ms += elapsed_ms;
+
+        let per_command = state
+            .commands
+            .entry(command.clone())
+            .or_insert_with(|| CommandDbTime {
+                command: command.clone(),
+                statements: 0,
+                total_ms: 0.0,
+                max_ms: 0.0,
+                slow_statements: 0,
+            });
+        per_command.statements += 1;
+        per_command.total_ms += elapsed_ms;
+        per_command.max_ms = per_command.max_ms.max(elapsed_ms);
+        if slow {
+            per_command.slow_statements += 1;
+        }
+
+        if state.statements.len() < MAX_TRACKED_STATEMENTS || state.statements.contains_key(&sql) {
+            let stats = state
+                .statements
+                .entry(sql.clone())
+                .or_insert_with(|| QueryStats {
+                    sql: sql.clone(),
+                    occurrences: 0,
+                    max_ms: 0.0,
+                    total_ms: 0.0,
+                    last_seen: now,
+                });
+            stats.occurrences += 1;
+            stats.total_ms += elapsed_ms;
+            stats.max_ms = stats.max_ms.max(elapsed_ms);
+            stats.last_seen = now;
+        }
+
+        if slow {
+            if state.slow.len() >= MAX_SLOW_ENTRIES {
+                state.slow.pop_front();
+            }
+            state.slow.push_back(SlowQueryEntry {
+                at: now,
+                command: command.clone(),
+                sql: sql.clone(),
+                elapsed_ms,
+            });
+            drop(state);
+            eprintln!("Slow query ({:.1}ms) in {}: {}", elapsed_ms, command, sql);
+        }
+    }
+
+    /// Top offenders by cumulative time, `limit` entries per section.
+    pub fn report(&self, limit: Option<usize>) -> QueryLogReport {
+        let limit = limit.unwrap_or(DEFAULT_REPORT_LIMIT).max(1);
+        let state = self.state.lock();
+
+        let mut commands: Vec<CommandDbTime> = state.commands.values().cloned().collect();
+        commands.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+        commands.truncate(limit);
+
+        let mut statements: Vec<QueryStats> = state.statements.values().cloned().collect();
+        statements.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+        statements.truncate(limit);
+
+        QueryLogReport {
+            since: state.since,
+            slow_threshold_ms: self.slow_threshold_ms(),
+            total_statements: state.total_statements,
+            total_ms: state.total_ms,
+            commands,
+            statements,
+            recent_slow: state.slow.iter().rev().take(limit).cloned().collect(),
+        }
+    }
+
+    /// Statements that have exceeded the slow threshold at least once,
+    /// heaviest first.
+    pub fn slow_statements(&self) -> Vec<QueryStats> {
+        let threshold = self.slow_threshold_ms() as f64;
+        let mut statements: Vec<QueryStats> = self
+            .state
+            .lock()
+            .statements
+            .values()
+            .filter(|stats| stats.max_ms >= threshold)
+            .cloned()
+            .collect();
+        statements.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+        statements
+    }
+
+    pub fn reset(&self) {
+        *self.state.lock() = QueryLogState::default();
+    }
+}
+
+pub fn query_log() -> &'static QueryLog {
+    QUERY_LOG.get_or_init(Default::default)
+}
+
+/// sqlx logs each statement it runs under the `sqlx::query` target, so a
+/// `log` sink sees the queries of every pool in the app without configuring
+/// each one.
+struct QueryLogSink;
+
+impl Log for QueryLogSink {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target() == "sqlx::query" && metadata.level() <= log::Level::Debug
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if let Some((sql, elapsed_ms)) = parse_statement(&record.args().to_string()) {
+            query_log().record(current_command(), &sql, elapsed_ms);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+pub fn install_query_log() {
+    if log::set_logger(&QUERY_LOG_SINK).is_ok() {
+        log::set_max_level(LevelFilter::Debug);
+    }
+}
+
+fn parse_elapsed_ms(value: &str) -> Option<f64> {
+    let units = [
+        ("ms", 1.0),
+        ("µs", 1e-3),
+        ("us", 1e-3),
+        ("ns", 1e-6),
+        ("s", 1e3),
+    ];
+    units.iter().find_map(|(suffix, scale)| {
+        value
+            .strip_suffix(suffix)
+            .and_then(|n| n.parse::<f64>().ok())
+            .map(|n| n * scale)
+    })
+}
+
+/// Splits an sqlx statement log line (`<summary>; rows affected: .., rows
+/// returned: .., elapsed: 1.2s` followed by the formatted SQL when the
+/// summary truncates it) into the statement and its duration.
+fn parse_statement(message: &str) -> Option<(String, f64)> {
+    let (summary, rest) = message.split_once("; rows affected:")?;
+    let elapsed = rest.split_once("elapsed: ")?.1.split_whitespace().next()?;
+    let sql = rest
+        .split_once("\n\n")
+        .map(|(_, sql)| sql.trim())
+        .filter(|sql| !sql.is_empty())
+        .unwrap_or_else(|| summary.trim());
+    Some((
+        sql.split_whitespace().collect::<Vec<_>>().join(" "),
+        parse_elapsed_ms(elapsed)?,
+    ))
+}
+
+fn literal_regexes() -> &'static [(Regex, &'static str)] {
+    static RE: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    RE.get_or_init(|| {
+        vec![
+            (Regex::new(r"\?\d+").unwrap(), "?"),
+            (Regex::new(r"(?i)\bx'[0-9a-f]*'").unwrap(), "?"),
+            (Regex::new(r"'(?:[^']|'')*'").unwrap(), "?"),
+            (Regex::new(r"\b\d+(?:\.\d+)?\b").unwrap(), "?"),
+            (
+                Regex::new(r"(?i)\bIN\s*\(\s*\?(?:\s*,\s*\?)*\s*\)").unwrap(),
+                "IN (?)",
+            ),
+        ]
+    })
+}
+
+/// Bound parameters never reach the sqlx log, but statements assembled with
+/// `format!` carry their values inline. Literals are replaced with `?` so
+/// addresses and amounts are not kept, and so the same statement with
+/// different values aggregates under one entry.
+pub fn redact_sql(sql: &str) -> String {
+    literal_regexes()
+        .iter()
+        .fold(sql.to_string(), |sql, (re, replacement)| {
+            re.replace_all(&sql, *replacement).into_owned()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statement_log_lines_are_parsed() {
+        let (sql, ms) = parse_statement(
+            "SELECT * FROM orders …; rows affected: 0, rows returned: 12, elapsed: 1.250s\n\nSELECT\n  *\nFROM\n  orders\nWHERE\n  status = ?\n",
+        )
+        .unwrap();
+        assert_eq!(sql, "SELECT * FROM orders WHERE status = ?");
+        assert!((ms - 1250.0).abs() < 1e-9);
+
+        let (sql, ms) = parse_statement(
+            "DELETE FROM alerts; rows affected: 3, rows returned: 0, elapsed: 12.5ms",
+        )
+        .unwrap();
+        assert_eq!(sql, "DELETE FROM alerts");
+        assert!((ms - 12.5).abs() < 1e-9);
+        assert!(parse_statement("connection closed").is_none());
+    }
+
+    #[test]
+    fn literals_are_redacted() {
+        assert_eq!(
+            redact_sql(
+                "SELECT * FROM wallets_v2 WHERE address = 'O''Brien' AND balance > 1.5 AND key = X'ABCD' AND id IN (1, 2, 3) AND n = ?2"
+            ),
+            "SELECT * FROM wallets_v2 WHERE address = ? AND balance > ? AND key = ? AND id IN (?) AND n = ?"
+        );
+    }
+
+    #[test]
+    fn db_time_is_attributed_per_command() {
+        let log = QueryLog::default();
+        log.set_slow_threshold_ms(50);
+        log.record(
+            Some("get_portfolio".into()),
+            "SELECT * FROM holdings WHERE id = 1",
+            80.0,
+        );
+        log.record(
+            Some("get_portfolio".into()),
+            "SELECT * FROM holdings WHERE id = 2",
+            10.0,
+        );
+        log.record(None, "DELETE FROM alerts", 5.0);
+
+        let report = log.report(None);
+        assert_eq!(report.total_statements, 3);
+        assert_eq!(report.commands[0].command, "get_portfolio");
+        assert_eq!(report.commands[0].statements, 2);
+        assert_eq!(report.commands[0].slow_statements, 1);
+        assert_eq!(report.commands[1].command, BACKGROUND_COMMAND);
+        assert_eq!(
+            report.statements[0].sql,
+            "SELECT * FROM holdings WHERE id = ?"
+        );
+        assert_eq!(report.statements[0].occurrences, 2);
+        assert_eq!(report.recent_slow.len(), 1);
+        assert_eq!(log.slow_statements().len(), 1);
+
+        log.reset();
+        assert_eq!(log.report(None).total_statements, 0);
+    }
+}
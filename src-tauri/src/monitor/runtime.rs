@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Handle;
+use tokio::time::Instant;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeMetricsSnapshot {
+    pub workers: usize,
+    pub alive_tasks: usize,
+    /// Tasks waiting in the shared queue for a free worker.
+    pub global_queue_depth: usize,
+    /// How long a task that yields waits to be polled again. Sustained
+    /// spikes mean a worker thread is blocked by synchronous work.
+    pub scheduler_lag_ms: f64,
+    /// Blocking-pool metrics are only available in `--cfg tokio_unstable`
+    /// builds.
+    pub blocking_threads: Option<usize>,
+    pub busy_blocking_threads: Option<usize>,
+    pub blocking_queue_depth: Option<usize>,
+}
+
+/// Samples the runtime the caller is running on.
+pub async fn sample_runtime() -> RuntimeMetricsSnapshot {
+    let started = Instant::now();
+    tokio::task::yield_now().await;
+    let scheduler_lag_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+    let metrics = Handle::current().metrics();
+    #[allow(unused_mut)]
+    let mut snapshot = RuntimeMetricsSnapshot {
+        workers: metrics.num_workers(),
+        alive_tasks: metrics.num_alive_tasks(),
+        global_queue_depth: metrics.global_queue_depth(),
+        scheduler_lag_ms,
+        blocking_threads: None,
+        busy_blocking_threads: None,
+        blocking_queue_depth: None,
+    };
+
+    #[cfg(tokio_unstable)]
+    {
+        let blocking = metrics.num_blocking_threads();
+        snapshot.blocking_threads = Some(blocking);
+        snapshot.busy_blocking_threads =
+            Some(blocking.saturating_sub(metrics.num_idle_blocking_threads()));
+        snapshot.blocking_queue_depth = Some(metrics.blocking_queue_depth());
+    }
+
+    snapshot
+}
@@ -1,4 +1,5 @@
 use chrono::Utc;
+use crate::profiles::ProfilePaths;
 use lettre::message::{header, MultiPart, SinglePart};
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{Message, SmtpTransport, Transport};
@@ -555,7 +556,7 @@ fn email_db_path(app: &AppHandle) -> Result<PathBuf, EmailError> {
     let app_handle = app.clone();
     let app_dir = app_handle
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|e| EmailError::Internal(format!("Unable to resolve app data directory: {}", e)))?;
 
     std::fs::create_dir_all(&app_dir)
@@ -598,6 +598,98 @@ impl NotificationRouter {
         Ok(())
     }
 
+    /// Sends a plain message to every enabled chat integration, for
+    /// notifications that are not price alerts. `source_id` and `title` are
+    /// recorded in the delivery log in place of the alert id and name.
+    pub async fn send_text_notification(
+        &self,
+        source_id: &str,
+        title: &str,
+        message: &str,
+    ) -> Result<(), NotificationError> {
+        let settings = self.get_settings().await?;
+
+        for config in settings.telegram.iter().filter(|c| c.enabled) {
+            let result = self
+                .deliver_text(ChatServiceType::Telegram, &config.id, async {
+                    self.telegram_client
+                        .send_message(config, message, false)
+                        .await
+                })
+                .await;
+            self.log_delivery(
+                ChatServiceType::Telegram,
+                &config.id,
+                &config.name,
+                Some(source_id),
+                Some(title),
+                message,
+                &result,
+            )
+            .await;
+        }
+
+        for config in settings.slack.iter().filter(|c| c.enabled) {
+            let result = self
+                .deliver_text(ChatServiceType::Slack, &config.id, async {
+                    self.slack_client.send_message(config, message).await
+                })
+                .await;
+            self.log_delivery(
+                ChatServiceType::Slack,
+                &config.id,
+                &config.name,
+                Some(source_id),
+                Some(title),
+                message,
+                &result,
+            )
+            .await;
+        }
+
+        for config in settings.discord.iter().filter(|c| c.enabled) {
+            let result = self
+                .deliver_text(ChatServiceType::Discord, &config.id, async {
+                    self.discord_client
+                        .send_message(config, message, false)
+                        .await
+                })
+                .await;
+            self.log_delivery(
+                ChatServiceType::Discord,
+                &config.id,
+                &config.name,
+                Some(source_id),
+                Some(title),
+                message,
+                &result,
+            )
+            .await;
+        }
+
+        Ok(())
+    }
+
+    async fn deliver_text(
+        &self,
+        service: ChatServiceType,
+        config_id: &str,
+        send: impl std::future::Future<Output = Result<(), NotificationError>>,
+    ) -> Result<(), NotificationError> {
+        let rate_limiter = self.rate_limiter.read().await;
+        rate_limiter.acquire(&service, config_id).await?;
+        drop(rate_limiter);
+
+        match send.await {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let rate_limiter = self.rate_limiter.read().await;
+                rate_limiter.register_failure(&service, config_id).await;
+                Err(e)
+            }
+        }
+    }
+
     async fn send_telegram_alert(
         &self,
         config: &TelegramConfig,
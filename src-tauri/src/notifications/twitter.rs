@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use crate::profiles::ProfilePaths;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
@@ -781,7 +782,7 @@ impl TwitterManager {
 }
 
 fn twitter_db_path(app: &AppHandle) -> Result<PathBuf, TwitterError> {
-    let app_dir = app.path().app_data_dir().map_err(|err| {
+    let app_dir = app.path().profile_data_dir().map_err(|err| {
         TwitterError::Internal(format!("Unable to resolve app data directory: {err}"))
     })?;
 
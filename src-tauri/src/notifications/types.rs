@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
@@ -170,7 +171,7 @@ pub enum NotificationError {
 }
 
 pub fn notifications_db_path(app: &AppHandle) -> Result<PathBuf, NotificationError> {
-    let mut path = app.path().app_data_dir().map_err(|err| {
+    let mut path = app.path().profile_data_dir().map_err(|err| {
         NotificationError::Internal(format!("Unable to resolve app data directory: {err}"))
     })?;
 
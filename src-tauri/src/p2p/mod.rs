@@ -3,6 +3,7 @@ pub mod compliance;
 pub mod database;
 pub mod escrow;
 pub mod matching;
+pub mod otc;
 pub mod types;
 
 pub use commands::*;
@@ -12,6 +13,7 @@ pub use escrow::{EscrowSmartContract, EscrowStateMachine};
 pub use matching::LocalMatcher;
 pub use types::*;
 
+use crate::profiles::ProfilePaths;
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 use tokio::sync::RwLock;
@@ -23,7 +25,7 @@ pub async fn init_p2p_system(
 ) -> Result<SharedP2PDatabase, Box<dyn std::error::Error>> {
     let app_dir = app_handle
         .path()
-        .app_data_dir()?;
+        .profile_data_dir()?;
 
     std::fs::create_dir_all(&app_dir)?;
     let db_path = app_dir.join("p2p.db");
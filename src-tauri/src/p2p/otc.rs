@@ -0,0 +1,731 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::commands::{
+    cancel_p2p_escrow, confirm_payment_p2p, create_p2p_escrow, fund_p2p_escrow, release_p2p_escrow,
+};
+use super::types::{CreateEscrowRequest, CreateOfferRequest, OfferType};
+use super::SharedP2PDatabase;
+use crate::journal::{
+    Emotion, EmotionTracking, EntryType, JournalEntry, MarketConditions, MarketTrend,
+    SharedJournalDatabase, TradeOutcome, Volatility, VolumeLevel,
+};
+use crate::profiles::ProfilePaths;
+use crate::security::reputation::SharedReputationEngine;
+use crate::wallet::receipts::{build_receipt, ReceiptFormat, ReceiptRequest};
+
+const OTC_FILE: &str = "otc_deals.json";
+
+pub const OTC_DEAL_EVENT: &str = "otc_deal_updated";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtcTerms {
+    /// Our side of the deal.
+    pub side: OfferType,
+    pub token_address: String,
+    pub token_symbol: String,
+    pub amount: f64,
+    pub price: f64,
+    /// What the counterparty pays or receives in, e.g. `USD` or `USDC`.
+    pub quote_currency: String,
+    pub counterparty_address: String,
+    pub counterparty_name: Option<String>,
+    pub settlement_deadline: DateTime<Utc>,
+    pub notes: Option<String>,
+}
+
+impl OtcTerms {
+    pub fn notional(&self) -> f64 {
+        self.amount * self.price
+    }
+
+    /// Hex SHA-256 of the terms, quoted by both parties when accepting so
+    /// neither can later claim different terms were agreed.
+    pub fn fingerprint(&self) -> String {
+        let bytes = serde_json::to_vec(self).unwrap_or_default();
+        hex::encode(Sha256::digest(bytes))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OtcDealStatus {
+    Negotiating,
+    Agreed,
+    Settling,
+    Completed,
+    Cancelled,
+}
+
+/// Settlement steps, in the order they must be completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SettlementStep {
+    TermsAgreed,
+    ComplianceCleared,
+    EscrowFunded,
+    PaymentSent,
+    PaymentReceived,
+    AssetsReleased,
+}
+
+impl SettlementStep {
+    const ALL: [SettlementStep; 6] = [
+        SettlementStep::TermsAgreed,
+        SettlementStep::ComplianceCleared,
+        SettlementStep::EscrowFunded,
+        SettlementStep::PaymentSent,
+        SettlementStep::PaymentReceived,
+        SettlementStep::AssetsReleased,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            SettlementStep::TermsAgreed => "Both parties accepted the terms",
+            SettlementStep::ComplianceCleared => "Counterparty passed compliance checks",
+            SettlementStep::EscrowFunded => "Tokens deposited into escrow",
+            SettlementStep::PaymentSent => "Buyer reports payment sent",
+            SettlementStep::PaymentReceived => "Seller confirms payment received",
+            SettlementStep::AssetsReleased => "Escrow released to the buyer",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistItem {
+    pub step: SettlementStep,
+    pub label: String,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Transaction signature, bank reference or similar.
+    pub evidence: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtcAcceptance {
+    pub party: String,
+    pub fingerprint: String,
+    pub accepted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtcDeal {
+    pub id: String,
+    pub our_address: String,
+    pub terms: OtcTerms,
+    pub terms_fingerprint: String,
+    pub acceptances: Vec<OtcAcceptance>,
+    pub status: OtcDealStatus,
+    pub checklist: Vec<ChecklistItem>,
+    pub offer_id: Option<String>,
+    pub escrow_id: Option<String>,
+    pub receipt_id: Option<String>,
+    pub receipt_error: Option<String>,
+    pub journal_entry_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl OtcDeal {
+    pub fn new(our_address: String, terms: OtcTerms) -> Self {
+        let now = Utc::now();
+        Self {
+            id: format!("otc_{}", Uuid::new_v4()),
+            our_address,
+            terms_fingerprint: terms.fingerprint(),
+            terms,
+            acceptances: Vec::new(),
+            status: OtcDealStatus::Negotiating,
+            checklist: SettlementStep::ALL
+                .iter()
+                .map(|step| ChecklistItem {
+                    step: *step,
+                    label: step.label().to_string(),
+                    completed_at: None,
+                    evidence: None,
+                })
+                .collect(),
+            offer_id: None,
+            escrow_id: None,
+            receipt_id: None,
+            receipt_error: None,
+            journal_entry_id: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn seller_and_buyer(&self) -> (&str, &str) {
+        match self.terms.side {
+            OfferType::Sell => (&self.our_address, &self.terms.counterparty_address),
+            OfferType::Buy => (&self.terms.counterparty_address, &self.our_address),
+        }
+    }
+
+    /// Replaces the terms while still negotiating. Earlier acceptances no
+    /// longer apply.
+    pub fn amend(&mut self, terms: OtcTerms) -> Result<(), String> {
+        if self.status != OtcDealStatus::Negotiating {
+            return Err("Terms can only be changed before both parties accept".to_string());
+        }
+        self.terms_fingerprint = terms.fingerprint();
+        self.terms = terms;
+        self.acceptances.clear();
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    pub fn accept(&mut self, party: &str, fingerprint: &str) -> Result<(), String> {
+        if self.status != OtcDealStatus::Negotiating {
+            return Err("Deal is no longer open for acceptance".to_string());
+        }
+        if party != self.our_address && party != self.terms.counterparty_address {
+            return Err(format!("{party} is not a party to this deal"));
+        }
+        if fingerprint != self.terms_fingerprint {
+            return Err("Terms have changed since they were reviewed".to_string());
+        }
+        if !self.acceptances.iter().any(|a| a.party == party) {
+            self.acceptances.push(OtcAcceptance {
+                party: party.to_string(),
+                fingerprint: fingerprint.to_string(),
+                accepted_at: Utc::now(),
+            });
+        }
+        if self.acceptances.len() == 2 {
+            self.complete_step(SettlementStep::TermsAgreed, None)?;
+            self.status = OtcDealStatus::Agreed;
+        }
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    pub fn next_step(&self) -> Option<SettlementStep> {
+        self.checklist
+            .iter()
+            .find(|item| item.completed_at.is_none())
+            .map(|item| item.step)
+    }
+
+    /// Ticks off `step`, which must be the next open one.
+    pub fn complete_step(
+        &mut self,
+        step: SettlementStep,
+        evidence: Option<String>,
+    ) -> Result<(), String> {
+        if matches!(
+            self.status,
+            OtcDealStatus::Completed | OtcDealStatus::Cancelled
+        ) {
+            return Err("Deal is closed".to_string());
+        }
+        match self.next_step() {
+            Some(next) if next == step => {}
+            Some(next) => return Err(format!("{next:?} must be completed before {step:?}")),
+            None => return Err("Checklist is already complete".to_string()),
+        }
+        if let Some(item) = self.checklist.iter_mut().find(|item| item.step == step) {
+            item.completed_at = Some(Utc::now());
+            item.evidence = evidence;
+        }
+        if step != SettlementStep::TermsAgreed {
+            self.status = OtcDealStatus::Settling;
+        }
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    fn evidence(&self, step: SettlementStep) -> Option<&str> {
+        self.checklist
+            .iter()
+            .find(|item| item.step == step)
+            .and_then(|item| item.evidence.as_deref())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOtcDealRequest {
+    pub our_address: String,
+    pub terms: OtcTerms,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettleOtcDealRequest {
+    pub deal_id: String,
+    /// On-chain signature of the settlement to receipt. Defaults to the
+    /// escrow release transaction.
+    pub settlement_signature: Option<String>,
+}
+
+pub struct OtcDesk {
+    deals: RwLock<HashMap<String, OtcDeal>>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedOtcDesk = Arc<OtcDesk>;
+
+impl OtcDesk {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        let path = app_handle
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(OTC_FILE));
+        let deals = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            deals: RwLock::new(deals),
+            path,
+        }
+    }
+
+    pub async fn get(&self, deal_id: &str) -> Result<OtcDeal, String> {
+        self.deals
+            .read()
+            .await
+            .get(deal_id)
+            .cloned()
+            .ok_or_else(|| format!("OTC deal {deal_id} not found"))
+    }
+
+    pub async fn list(&self) -> Vec<OtcDeal> {
+        let mut deals: Vec<_> = self.deals.read().await.values().cloned().collect();
+        deals.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        deals
+    }
+
+    /// Applies `change` to a deal and persists it. Nothing is saved if the
+    /// change fails.
+    pub async fn update<F>(
+        &self,
+        app: &AppHandle,
+        deal_id: &str,
+        change: F,
+    ) -> Result<OtcDeal, String>
+    where
+        F: FnOnce(&mut OtcDeal) -> Result<(), String>,
+    {
+        let snapshot = {
+            let mut deals = self.deals.write().await;
+            let deal = deals
+                .get_mut(deal_id)
+                .ok_or_else(|| format!("OTC deal {deal_id} not found"))?;
+            let mut updated = deal.clone();
+            change(&mut updated)?;
+            *deal = updated.clone();
+            updated
+        };
+        self.save().await?;
+        let _ = app.emit(OTC_DEAL_EVENT, &snapshot);
+        Ok(snapshot)
+    }
+
+    async fn insert(&self, deal: OtcDeal) -> Result<(), String> {
+        self.deals.write().await.insert(deal.id.clone(), deal);
+        self.save().await
+    }
+
+    async fn save(&self) -> Result<(), String> {
+        if let Some(path) = &self.path {
+            let contents = serde_json::to_string_pretty(&*self.deals.read().await)
+                .map_err(|e| format!("Failed to serialize OTC deals: {e}"))?;
+            fs::write(path, contents).map_err(|e| format!("Failed to persist OTC deals: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+fn journal_entry(deal: &OtcDeal, receipt_id: Option<&str>) -> JournalEntry {
+    let now = Utc::now().timestamp();
+    let terms = &deal.terms;
+    let mut notes = format!(
+        "OTC {} of {} {} at {} {} ({:.2} {} notional) with {}.",
+        terms.side,
+        terms.amount,
+        terms.token_symbol,
+        terms.price,
+        terms.quote_currency,
+        terms.notional(),
+        terms.quote_currency,
+        terms
+            .counterparty_name
+            .as_deref()
+            .unwrap_or(&terms.counterparty_address)
+    );
+    notes.push_str(&format!("\nTerms fingerprint: {}", deal.terms_fingerprint));
+    for item in &deal.checklist {
+        notes.push_str(&format!(
+            "\n- {}: {}",
+            item.label,
+            item.evidence.as_deref().unwrap_or("done")
+        ));
+    }
+    if let Some(receipt_id) = receipt_id {
+        notes.push_str(&format!("\nReceipt: {receipt_id}"));
+    }
+
+    JournalEntry {
+        id: Uuid::new_v4().to_string(),
+        timestamp: now,
+        trade_id: Some(deal.id.clone()),
+        entry_type: EntryType::PostTrade,
+        strategy_tags: vec!["otc".to_string()],
+        emotions: EmotionTracking {
+            primary_emotion: Emotion::Neutral,
+            intensity: 0.0,
+            secondary_emotions: Vec::new(),
+            stress_level: 0.0,
+            clarity_level: 1.0,
+            fomo_level: 0.0,
+            revenge_trading: false,
+            discipline_score: 1.0,
+        },
+        notes,
+        market_conditions: MarketConditions {
+            trend: MarketTrend::Neutral,
+            volatility: Volatility::Medium,
+            volume: VolumeLevel::Medium,
+            news_sentiment: 0.0,
+            notes: String::new(),
+        },
+        confidence_level: 1.0,
+        position_size: Some(terms.notional() as f32),
+        entry_price: Some(terms.price as f32),
+        exit_price: None,
+        outcome: Some(TradeOutcome {
+            pnl: 0.0,
+            pnl_percent: 0.0,
+            success: true,
+            followed_plan: true,
+            risk_reward_ratio: 0.0,
+        }),
+        lessons_learned: None,
+        attachments: Vec::new(),
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+#[tauri::command]
+pub async fn otc_create_deal(
+    app: AppHandle,
+    request: CreateOtcDealRequest,
+    desk: State<'_, SharedOtcDesk>,
+) -> Result<OtcDeal, String> {
+    let terms = &request.terms;
+    if terms.amount <= 0.0 || terms.price <= 0.0 {
+        return Err("Amount and price must be positive".to_string());
+    }
+    if terms.counterparty_address == request.our_address {
+        return Err("Counterparty must be a different wallet".to_string());
+    }
+    if terms.settlement_deadline <= Utc::now() {
+        return Err("Settlement deadline must be in the future".to_string());
+    }
+    let deal = OtcDeal::new(request.our_address, request.terms);
+    desk.insert(deal.clone()).await?;
+    let _ = app.emit(OTC_DEAL_EVENT, &deal);
+    Ok(deal)
+}
+
+#[tauri::command]
+pub async fn otc_list_deals(desk: State<'_, SharedOtcDesk>) -> Result<Vec<OtcDeal>, String> {
+    Ok(desk.list().await)
+}
+
+#[tauri::command]
+pub async fn otc_get_deal(
+    deal_id: String,
+    desk: State<'_, SharedOtcDesk>,
+) -> Result<OtcDeal, String> {
+    desk.get(&deal_id).await
+}
+
+#[tauri::command]
+pub async fn otc_amend_terms(
+    app: AppHandle,
+    deal_id: String,
+    terms: OtcTerms,
+    desk: State<'_, SharedOtcDesk>,
+) -> Result<OtcDeal, String> {
+    desk.update(&app, &deal_id, |deal| deal.amend(terms)).await
+}
+
+/// Records a party's acceptance of the terms identified by `fingerprint`.
+#[tauri::command]
+pub async fn otc_accept_terms(
+    app: AppHandle,
+    deal_id: String,
+    party: String,
+    fingerprint: String,
+    desk: State<'_, SharedOtcDesk>,
+) -> Result<OtcDeal, String> {
+    desk.update(&app, &deal_id, |deal| deal.accept(&party, &fingerprint))
+        .await
+}
+
+/// Moves the agreed deal into P2P escrow: a private offer carrying the
+/// terms, an escrow that runs the usual compliance checks, then funding.
+#[tauri::command]
+pub async fn otc_open_escrow(
+    app: AppHandle,
+    deal_id: String,
+    desk: State<'_, SharedOtcDesk>,
+    db: State<'_, SharedP2PDatabase>,
+    reputation: State<'_, SharedReputationEngine>,
+) -> Result<OtcDeal, String> {
+    let deal = desk.get(&deal_id).await?;
+    if deal.next_step() != Some(SettlementStep::ComplianceCleared) {
+        return Err("Both parties must accept the terms before escrow".to_string());
+    }
+    let (seller, buyer) = deal.seller_and_buyer();
+    let terms = &deal.terms;
+    let minutes_left = (terms.settlement_deadline - Utc::now()).num_minutes();
+    if minutes_left <= 0 {
+        return Err("Settlement deadline has passed".to_string());
+    }
+
+    let offer = {
+        let db = db.read().await;
+        let offer = db
+            .create_offer(CreateOfferRequest {
+                creator: deal.our_address.clone(),
+                offer_type: terms.side.clone(),
+                token_address: terms.token_address.clone(),
+                token_symbol: terms.token_symbol.clone(),
+                amount: terms.amount,
+                price: terms.price,
+                fiat_currency: terms.quote_currency.clone(),
+                payment_methods: vec!["otc".to_string()],
+                min_amount: Some(terms.amount),
+                max_amount: Some(terms.amount),
+                terms: Some(format!("OTC deal {} ({})", deal.id, deal.terms_fingerprint)),
+                time_limit: minutes_left.min(i32::MAX as i64) as i32,
+                reputation_required: None,
+            })
+            .await
+            .map_err(|e| e.to_string())?;
+        // Negotiated off-book; keep it out of the public order book.
+        db.update_offer_status(&offer.id, false)
+            .await
+            .map_err(|e| e.to_string())?;
+        offer
+    };
+
+    let escrow = create_p2p_escrow(
+        CreateEscrowRequest {
+            offer_id: offer.id.clone(),
+            buyer: buyer.to_string(),
+            seller: seller.to_string(),
+            amount: terms.amount,
+            fiat_amount: terms.notional(),
+        },
+        db.clone(),
+        reputation,
+    )
+    .await?;
+    desk.update(&app, &deal_id, |deal| {
+        deal.offer_id = Some(offer.id.clone());
+        deal.escrow_id = Some(escrow.id.clone());
+        deal.complete_step(SettlementStep::ComplianceCleared, None)
+    })
+    .await?;
+
+    let funding = fund_p2p_escrow(escrow.id.clone(), db).await?;
+    desk.update(&app, &deal_id, |deal| {
+        deal.complete_step(SettlementStep::EscrowFunded, Some(funding))
+    })
+    .await
+}
+
+/// Ticks off the off-chain payment steps. Confirming receipt of payment
+/// also confirms it on the escrow.
+#[tauri::command]
+pub async fn otc_complete_step(
+    app: AppHandle,
+    deal_id: String,
+    step: SettlementStep,
+    evidence: Option<String>,
+    desk: State<'_, SharedOtcDesk>,
+    db: State<'_, SharedP2PDatabase>,
+) -> Result<OtcDeal, String> {
+    if !matches!(
+        step,
+        SettlementStep::PaymentSent | SettlementStep::PaymentReceived
+    ) {
+        return Err(format!("{step:?} is completed by the escrow workflow"));
+    }
+    let deal = desk.get(&deal_id).await?;
+    if deal.next_step() != Some(step) {
+        return Err(format!("{step:?} is not the next settlement step"));
+    }
+    if step == SettlementStep::PaymentReceived {
+        let escrow_id = deal.escrow_id.clone().ok_or("Deal has no escrow")?;
+        confirm_payment_p2p(escrow_id, db).await?;
+    }
+    desk.update(&app, &deal_id, |deal| deal.complete_step(step, evidence))
+        .await
+}
+
+/// Releases escrow and closes the deal, attaching a signed receipt and a
+/// journal entry. A receipt failure is recorded on the deal rather than
+/// undoing a settlement that already happened.
+#[tauri::command]
+pub async fn otc_settle(
+    app: AppHandle,
+    request: SettleOtcDealRequest,
+    desk: State<'_, SharedOtcDesk>,
+) -> Result<OtcDeal, String> {
+    let deal = desk.get(&request.deal_id).await?;
+    if deal.next_step() != Some(SettlementStep::AssetsReleased) {
+        return Err("Payment must be confirmed before releasing escrow".to_string());
+    }
+    let escrow_id = deal.escrow_id.clone().ok_or("Deal has no escrow")?;
+    let release = release_p2p_escrow(escrow_id, app.state::<SharedP2PDatabase>()).await?;
+    let deal = desk
+        .update(&app, &request.deal_id, |deal| {
+            deal.complete_step(SettlementStep::AssetsReleased, Some(release.clone()))
+        })
+        .await?;
+
+    let signature = request.settlement_signature.unwrap_or(release);
+    let receipt = build_receipt(
+        &app,
+        ReceiptRequest {
+            wallet_address: deal.our_address.clone(),
+            signature,
+            format: Some(ReceiptFormat::Json),
+            note: Some(format!(
+                "OTC deal {} with {}; terms {}",
+                deal.id, deal.terms.counterparty_address, deal.terms_fingerprint
+            )),
+        },
+    )
+    .await;
+    let (receipt_id, receipt_error) = match receipt {
+        Ok(document) => (Some(document.receipt.body.receipt_id), None),
+        Err(error) => (None, Some(error)),
+    };
+
+    let entry = journal_entry(&deal, receipt_id.as_deref());
+    let journal = app.state::<SharedJournalDatabase>();
+    let journal_entry_id = match journal.write().await.create_entry(&entry).await {
+        Ok(()) => Some(entry.id),
+        Err(e) => {
+            eprintln!("Failed to journal OTC deal {}: {e}", deal.id);
+            None
+        }
+    };
+
+    desk.update(&app, &request.deal_id, |deal| {
+        deal.receipt_id = receipt_id;
+        deal.receipt_error = receipt_error;
+        deal.journal_entry_id = journal_entry_id;
+        deal.status = OtcDealStatus::Completed;
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn otc_cancel_deal(
+    app: AppHandle,
+    deal_id: String,
+    desk: State<'_, SharedOtcDesk>,
+    db: State<'_, SharedP2PDatabase>,
+) -> Result<OtcDeal, String> {
+    let deal = desk.get(&deal_id).await?;
+    if deal.evidence(SettlementStep::PaymentSent).is_some()
+        || deal.next_step().is_none()
+        || deal.status == OtcDealStatus::Cancelled
+    {
+        return Err("Deal can no longer be cancelled; file a P2P dispute instead".to_string());
+    }
+    if let Some(escrow_id) = deal.escrow_id.clone() {
+        cancel_p2p_escrow(escrow_id, db).await?;
+    }
+    desk.update(&app, &deal_id, |deal| {
+        deal.status = OtcDealStatus::Cancelled;
+        deal.updated_at = Utc::now();
+        Ok(())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms() -> OtcTerms {
+        OtcTerms {
+            side: OfferType::Sell,
+            token_address: "So11111111111111111111111111111111111111112".to_string(),
+            token_symbol: "SOL".to_string(),
+            amount: 5_000.0,
+            price: 150.0,
+            quote_currency: "USDC".to_string(),
+            counterparty_address: "Desk".to_string(),
+            counterparty_name: Some("Desk Ltd".to_string()),
+            settlement_deadline: Utc::now() + chrono::Duration::days(1),
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn amending_terms_voids_earlier_acceptances() {
+        let mut deal = OtcDeal::new("Us".to_string(), terms());
+        let original = deal.terms_fingerprint.clone();
+        deal.accept("Us", &original).unwrap();
+        assert!(deal.accept("Stranger", &original).is_err());
+
+        deal.amend(OtcTerms {
+            price: 149.0,
+            ..terms()
+        })
+        .unwrap();
+        assert!(deal.acceptances.is_empty());
+        assert!(deal.accept("Desk", &original).is_err());
+
+        let amended = deal.terms_fingerprint.clone();
+        deal.accept("Desk", &amended).unwrap();
+        deal.accept("Us", &amended).unwrap();
+        assert_eq!(deal.status, OtcDealStatus::Agreed);
+        assert_eq!(deal.next_step(), Some(SettlementStep::ComplianceCleared));
+        assert!(deal.amend(terms()).is_err());
+    }
+
+    #[test]
+    fn checklist_must_be_completed_in_order() {
+        let mut deal = OtcDeal::new("Us".to_string(), terms());
+        let fingerprint = deal.terms_fingerprint.clone();
+        deal.accept("Us", &fingerprint).unwrap();
+        deal.accept("Desk", &fingerprint).unwrap();
+
+        assert!(deal
+            .complete_step(SettlementStep::PaymentReceived, None)
+            .is_err());
+        deal.complete_step(SettlementStep::ComplianceCleared, None)
+            .unwrap();
+        deal.complete_step(SettlementStep::EscrowFunded, Some("sig".to_string()))
+            .unwrap();
+        assert_eq!(deal.status, OtcDealStatus::Settling);
+        assert_eq!(deal.evidence(SettlementStep::EscrowFunded), Some("sig"));
+        assert_eq!(deal.seller_and_buyer(), ("Us", "Desk"));
+    }
+}
@@ -1,4 +1,7 @@
 use chrono::{DateTime, Utc};
+use crate::defi::protocol_risk::protocol_risk_score;
+use crate::defi::types::DeFiPosition;
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use std::collections::HashMap;
@@ -105,7 +108,7 @@ pub type SharedAIPortfolioAdvisor = Arc<RwLock<AIPortfolioAdvisor>>;
 
 impl AIPortfolioAdvisor {
     pub async fn new(app: &AppHandle) -> Result<Self, sqlx::Error> {
-        let mut db_path = app.path().app_data_dir().map_err(|err| {
+        let mut db_path = app.path().profile_data_dir().map_err(|err| {
             sqlx::Error::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 "App data dir not found",
@@ -295,6 +298,7 @@ impl AIPortfolioAdvisor {
         positions: Vec<super::Position>,
         risk_profile: UserRiskProfile,
         total_value: f64,
+        defi_positions: &[DeFiPosition],
     ) -> Result<PortfolioRecommendation, String> {
         let risk_weights = match risk_profile.profile.as_str() {
             "conservative" => (0.25, 0.75),
@@ -303,12 +307,14 @@ impl AIPortfolioAdvisor {
             _ => (0.50, 0.50),
         };
 
-        let target_allocations = self.calculate_optimal_allocations(
+        let mut target_allocations = self.calculate_optimal_allocations(
             &positions,
             risk_weights,
             risk_profile.risk_tolerance,
             total_value,
         );
+        let protocol_scores =
+            apply_protocol_risk(&mut target_allocations, defi_positions, risk_weights.1);
 
         let mut allocations = Vec::new();
         let mut factors = Vec::new();
@@ -328,7 +334,7 @@ impl AIPortfolioAdvisor {
 
             let amount = (deviation.abs() / 100.0) * total_value;
 
-            let reasoning = if action == "buy" {
+            let mut reasoning = if action == "buy" {
                 format!(
                     "Increase exposure to {} to reach target allocation of {:.1}% (currently {:.1}%)",
                     symbol, target_pct, current_pct
@@ -345,6 +351,13 @@ impl AIPortfolioAdvisor {
                 )
             };
 
+            if let Some(score) = protocol_scores.get(symbol) {
+                reasoning.push_str(&format!(
+                    "; target trimmed because its DeFi exposure carries a protocol risk score of {}",
+                    score
+                ));
+            }
+
             let mint = current_position.map(|p| p.mint.clone()).unwrap_or_default();
 
             allocations.push(AllocationRecommendation {
@@ -389,6 +402,24 @@ impl AIPortfolioAdvisor {
             ),
         });
 
+        if !protocol_scores.is_empty() {
+            let mut held: Vec<String> = protocol_scores
+                .iter()
+                .map(|(symbol, score)| format!("{} ({})", symbol, score))
+                .collect();
+            held.sort();
+            let average =
+                protocol_scores.values().map(|s| *s as f64).sum::<f64>() / held.len() as f64;
+            factors.push(RecommendationFactor {
+                name: "Protocol Risk".to_string(),
+                impact: -average * risk_weights.1,
+                description: format!(
+                    "Reduced targets for assets held in higher-risk DeFi protocols: {}",
+                    held.join(", ")
+                ),
+            });
+        }
+
         factors.push(RecommendationFactor {
             name: "Expected Return".to_string(),
             impact: expected_return,
@@ -701,7 +732,7 @@ impl AIPortfolioAdvisor {
         risk_profile: UserRiskProfile,
     ) -> Result<WeeklyUpdate, String> {
         let recommendations = vec![
-            self.generate_recommendation(positions, risk_profile, portfolio_value)
+            self.generate_recommendation(positions, risk_profile, portfolio_value, &[])
                 .await?,
         ];
 
@@ -817,6 +848,53 @@ impl AIPortfolioAdvisor {
     }
 }
 
+/// Trims the target for each asset whose DeFi exposure sits in protocols
+/// above the low-risk band, weighted by how much of the exposure is there,
+/// then renormalizes. Conservative profiles (higher stability weight) trim
+/// harder. Returns the value-weighted protocol score for trimmed assets.
+fn apply_protocol_risk(
+    allocations: &mut HashMap<String, f64>,
+    defi_positions: &[DeFiPosition],
+    stability_weight: f64,
+) -> HashMap<String, u8> {
+    let mut exposure: HashMap<String, (f64, f64)> = HashMap::new();
+    for position in defi_positions {
+        let Some(symbol) = allocations
+            .keys()
+            .find(|symbol| symbol.eq_ignore_ascii_case(&position.asset))
+        else {
+            continue;
+        };
+        let score = protocol_risk_score(&position.protocol).score as f64;
+        let entry = exposure.entry(symbol.clone()).or_insert((0.0, 0.0));
+        entry.0 += position.value_usd;
+        entry.1 += position.value_usd * score;
+    }
+
+    let mut trimmed = HashMap::new();
+    for (symbol, (value, weighted_score)) in exposure {
+        if value <= 0.0 {
+            continue;
+        }
+        let score = weighted_score / value;
+        let excess = (score - 25.0).max(0.0) / 100.0;
+        if excess > 0.0 {
+            if let Some(allocation) = allocations.get_mut(&symbol) {
+                *allocation *= 1.0 - stability_weight * excess;
+                trimmed.insert(symbol, score.round() as u8);
+            }
+        }
+    }
+
+    let total: f64 = allocations.values().sum();
+    if !trimmed.is_empty() && total > 0.0 {
+        for allocation in allocations.values_mut() {
+            *allocation = (*allocation / total) * 100.0;
+        }
+    }
+    trimmed
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -857,6 +935,34 @@ mod tests {
         assert_eq!(allocations.len(), 0);
     }
 
+    #[test]
+    fn test_apply_protocol_risk_trims_risky_exposure() {
+        use crate::defi::types::{PositionType, Protocol};
+
+        let mut allocations = HashMap::new();
+        allocations.insert("SOL".to_string(), 50.0);
+        allocations.insert("USDC".to_string(), 50.0);
+        let position = DeFiPosition {
+            id: "risky".to_string(),
+            protocol: Protocol::Other("Unaudited".to_string()),
+            position_type: PositionType::Lending,
+            asset: "usdc".to_string(),
+            amount: 1000.0,
+            value_usd: 1000.0,
+            apy: 12.0,
+            rewards: vec![],
+            health_factor: None,
+            created_at: 0,
+            last_updated: 0,
+        };
+
+        let trimmed = apply_protocol_risk(&mut allocations, &[position], 0.75);
+
+        assert!(trimmed.contains_key("USDC"));
+        assert!(allocations["USDC"] < allocations["SOL"]);
+        assert!((allocations.values().sum::<f64>() - 100.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_calculate_diversification_score() {
         let mut allocations = HashMap::new();
@@ -909,11 +1015,17 @@ pub async fn generate_portfolio_recommendation(
     positions: Vec<super::Position>,
     risk_profile: UserRiskProfile,
     total_value: f64,
+    defi_positions: Option<Vec<DeFiPosition>>,
     advisor: State<'_, SharedAIPortfolioAdvisor>,
 ) -> Result<PortfolioRecommendation, String> {
     let advisor = advisor.read().await;
     advisor
-        .generate_recommendation(positions, risk_profile, total_value)
+        .generate_recommendation(
+            positions,
+            risk_profile,
+            total_value,
+            &defi_positions.unwrap_or_default(),
+        )
         .await
 }
 
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::types::Position;
-use crate::market::PricePoint;
+use crate::market::{PredictionPnlSummary, PricePoint, SharedPredictionMarketService};
 
 // ==================== Data Types ====================
 
@@ -74,6 +74,14 @@ pub struct PortfolioAnalytics {
     pub concentration: Vec<RiskConcentration>,
     pub sharpe: SharpeMetrics,
     pub factors: FactorAnalysis,
+    /// Open and settled prediction market positions, kept out of the
+    /// token correlation and risk figures above.
+    #[serde(
+        rename = "predictionMarkets",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub prediction_markets: Option<PredictionPnlSummary>,
     #[serde(rename = "calculatedAt")]
     pub calculated_at: String,
 }
@@ -568,9 +576,23 @@ pub async fn calculate_portfolio_analytics(
     positions: Vec<Position>,
     time_series: HashMap<String, Vec<PricePoint>>,
     risk_free_rate: Option<f64>,
+    prediction_category: Option<String>,
+    predictions: tauri::State<'_, SharedPredictionMarketService>,
 ) -> Result<PortfolioAnalytics, String> {
+    // Prediction positions change independently of token positions, so
+    // they are summarized fresh rather than cached.
+    let prediction_markets = predictions
+        .read()
+        .await
+        .pnl_summary(prediction_category.as_deref())
+        .await;
+    let prediction_markets =
+        (prediction_markets.open_positions + prediction_markets.settled_positions > 0)
+            .then_some(prediction_markets);
+
     // Check cache first
-    if let Some(cached) = get_cached_analytics(&positions) {
+    if let Some(mut cached) = get_cached_analytics(&positions) {
+        cached.prediction_markets = prediction_markets;
         return Ok(cached);
     }
 
@@ -606,6 +628,7 @@ pub async fn calculate_portfolio_analytics(
         concentration,
         sharpe,
         factors,
+        prediction_markets,
         calculated_at: Utc::now().to_rfc3339(),
     };
 
@@ -849,6 +872,7 @@ mod tests {
                 systematic_risk: 0.0,
                 specific_risk: 0.0,
             },
+            prediction_markets: None,
             calculated_at: Utc::now().to_rfc3339(),
         };
 
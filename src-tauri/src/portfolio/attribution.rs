@@ -0,0 +1,323 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tauri::State;
+
+use super::analytics::classify_sector;
+use crate::wallet::performance::{SharedPerformanceDatabase, Trade};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeStrategy {
+    Dca,
+    CopyTrading,
+    Manual,
+}
+
+impl TradeStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeStrategy::Dca => "dca",
+            TradeStrategy::CopyTrading => "copy_trading",
+            TradeStrategy::Manual => "manual",
+        }
+    }
+}
+
+/// One slice of the portfolio's realized return.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributionBucket {
+    pub key: String,
+    pub trades: usize,
+    /// Sells in the window with a matched buy.
+    pub realized_trades: usize,
+    pub volume: f64,
+    /// Entry cost of the realized sells.
+    pub cost_basis: f64,
+    pub gross_pnl: f64,
+    pub fees: f64,
+    pub net_pnl: f64,
+    /// Net PnL over this bucket's own cost basis.
+    pub return_pct: f64,
+    /// Net PnL over the whole portfolio's cost basis; buckets of one
+    /// dimension sum to the portfolio return.
+    pub contribution_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceAttribution {
+    pub wallet_address: String,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub total: AttributionBucket,
+    pub by_token: Vec<AttributionBucket>,
+    pub by_sector: Vec<AttributionBucket>,
+    pub by_strategy: Vec<AttributionBucket>,
+    /// Sells in the window with no earlier buy to measure against.
+    pub unmatched_sells: usize,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Per-trade figures folded into every bucket the trade belongs to.
+struct TradeFigures {
+    volume: f64,
+    fees: f64,
+    /// Cost basis and PnL when the trade is a matched sell.
+    realized: Option<(f64, f64)>,
+}
+
+impl AttributionBucket {
+    fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            ..Default::default()
+        }
+    }
+
+    fn finish(&mut self, portfolio_cost_basis: f64) {
+        self.net_pnl = self.gross_pnl - self.fees;
+        self.return_pct = percent(self.net_pnl, self.cost_basis);
+        self.contribution_pct = percent(self.net_pnl, portfolio_cost_basis);
+    }
+
+    fn add(&mut self, figures: &TradeFigures) {
+        self.trades += 1;
+        self.volume += figures.volume;
+        self.fees += figures.fees;
+        if let Some((cost_basis, pnl)) = figures.realized {
+            self.realized_trades += 1;
+            self.cost_basis += cost_basis;
+            self.gross_pnl += pnl;
+        }
+    }
+}
+
+fn percent(value: f64, base: f64) -> f64 {
+    if base > 0.0 {
+        value / base * 100.0
+    } else {
+        0.0
+    }
+}
+
+fn in_window(trade: &Trade, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> bool {
+    start.map_or(true, |start| trade.timestamp >= start)
+        && end.map_or(true, |end| trade.timestamp <= end)
+}
+
+fn sorted_buckets(
+    map: HashMap<String, AttributionBucket>,
+    cost_basis: f64,
+) -> Vec<AttributionBucket> {
+    let mut buckets: Vec<AttributionBucket> = map
+        .into_values()
+        .map(|mut bucket| {
+            bucket.finish(cost_basis);
+            bucket
+        })
+        .collect();
+    buckets.sort_by(|a, b| {
+        b.net_pnl
+            .abs()
+            .partial_cmp(&a.net_pnl.abs())
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    buckets
+}
+
+/// Decomposes realized returns within `[start, end]` by token, sector and
+/// strategy. `trades` must be oldest first and may include trades before
+/// `start`, which are only used to find the buy each sell closes.
+///
+/// A sell is matched to the latest earlier buy of the same mint, the same
+/// rule the performance database uses for its recorded PnL, and inherits
+/// that buy's strategy: a DCA position sold by hand still counts as DCA.
+pub fn attribute_trades(
+    wallet_address: &str,
+    trades: &[Trade],
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    strategy_of: impl Fn(&Trade) -> TradeStrategy,
+) -> PerformanceAttribution {
+    let mut last_buy: HashMap<&str, &Trade> = HashMap::new();
+    let mut total = AttributionBucket::new("portfolio");
+    let mut by_token: HashMap<String, AttributionBucket> = HashMap::new();
+    let mut by_sector: HashMap<String, AttributionBucket> = HashMap::new();
+    let mut by_strategy: HashMap<String, AttributionBucket> = HashMap::new();
+    let mut unmatched_sells = 0;
+
+    for trade in trades {
+        let is_sell = trade.side == "sell";
+        let opener = if is_sell {
+            last_buy.get(trade.token_mint.as_str()).copied()
+        } else {
+            last_buy.insert(&trade.token_mint, trade);
+            None
+        };
+        if !in_window(trade, start, end) {
+            continue;
+        }
+
+        let realized = opener.map(|buy| {
+            let pnl = trade
+                .pnl
+                .unwrap_or((trade.price - buy.price) * trade.amount);
+            (buy.price * trade.amount, pnl)
+        });
+        if is_sell && realized.is_none() {
+            unmatched_sells += 1;
+        }
+        let strategy = strategy_of(opener.unwrap_or(trade));
+        let figures = TradeFigures {
+            volume: trade.total_value,
+            fees: trade.fee,
+            realized,
+        };
+
+        total.add(&figures);
+        by_token
+            .entry(trade.token_symbol.clone())
+            .or_insert_with(|| AttributionBucket::new(trade.token_symbol.clone()))
+            .add(&figures);
+        let sector = classify_sector(&trade.token_symbol);
+        by_sector
+            .entry(sector.clone())
+            .or_insert_with(|| AttributionBucket::new(sector))
+            .add(&figures);
+        by_strategy
+            .entry(strategy.as_str().to_string())
+            .or_insert_with(|| AttributionBucket::new(strategy.as_str()))
+            .add(&figures);
+    }
+
+    let cost_basis = total.cost_basis;
+    total.finish(cost_basis);
+
+    PerformanceAttribution {
+        wallet_address: wallet_address.to_string(),
+        start,
+        end,
+        by_token: sorted_buckets(by_token, cost_basis),
+        by_sector: sorted_buckets(by_sector, cost_basis),
+        by_strategy: sorted_buckets(by_strategy, cost_basis),
+        total,
+        unmatched_sells,
+        generated_at: Utc::now(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_performance_attribution(
+    wallet_address: String,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    db: State<'_, SharedPerformanceDatabase>,
+) -> Result<PerformanceAttribution, String> {
+    if let (Some(start), Some(end)) = (start, end) {
+        if start > end {
+            return Err("Attribution window start must be before its end".to_string());
+        }
+    }
+
+    let trades = db
+        .read()
+        .await
+        .trades_until(&wallet_address, end)
+        .await
+        .map_err(|e| e.to_string())?;
+    let dca: HashSet<String> = crate::bots::dca_trade_signatures(&wallet_address)
+        .await?
+        .into_iter()
+        .collect();
+    let copied: HashSet<String> = crate::trading::copy_trade_signatures(&wallet_address)
+        .await?
+        .into_iter()
+        .collect();
+
+    Ok(attribute_trades(
+        &wallet_address,
+        &trades,
+        start,
+        end,
+        |trade| {
+            if dca.contains(&trade.tx_signature) {
+                TradeStrategy::Dca
+            } else if copied.contains(&trade.tx_signature) {
+                TradeStrategy::CopyTrading
+            } else {
+                TradeStrategy::Manual
+            }
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn trade(symbol: &str, side: &str, price: f64, amount: f64, day: i64, sig: &str) -> Trade {
+        Trade {
+            id: format!("{sig}-{symbol}"),
+            wallet_address: "wallet".into(),
+            token_mint: format!("{symbol}-mint"),
+            token_symbol: symbol.into(),
+            side: side.into(),
+            amount,
+            price,
+            total_value: price * amount,
+            fee: 0.0,
+            tx_signature: sig.into(),
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::days(day),
+            pnl: None,
+            hold_duration_seconds: None,
+        }
+    }
+
+    #[test]
+    fn contributions_sum_to_portfolio_return() {
+        let trades = vec![
+            trade("SOL", "buy", 100.0, 10.0, 0, "dca-1"),
+            trade("BONK", "buy", 1.0, 500.0, 1, "manual-1"),
+            trade("SOL", "sell", 120.0, 10.0, 5, "manual-2"),
+            trade("BONK", "sell", 0.8, 500.0, 6, "manual-3"),
+        ];
+        let report = attribute_trades("wallet", &trades, None, None, |t| {
+            if t.tx_signature.starts_with("dca") {
+                TradeStrategy::Dca
+            } else {
+                TradeStrategy::Manual
+            }
+        });
+
+        // 200 gain on SOL, 100 loss on BONK, over 1500 of cost basis.
+        assert_eq!(report.total.cost_basis, 1500.0);
+        assert!((report.total.net_pnl - 100.0).abs() < 1e-9);
+        let contributions: f64 = report.by_token.iter().map(|b| b.contribution_pct).sum();
+        assert!((contributions - report.total.return_pct).abs() < 1e-9);
+
+        let dca = report.by_strategy.iter().find(|b| b.key == "dca").unwrap();
+        assert!((dca.net_pnl - 200.0).abs() < 1e-9);
+        let meme = report.by_sector.iter().find(|b| b.key == "Meme").unwrap();
+        assert!((meme.return_pct + 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn window_uses_earlier_buys_but_only_counts_trades_inside() {
+        let trades = vec![
+            trade("JUP", "buy", 1.0, 100.0, 0, "a"),
+            trade("JUP", "sell", 1.5, 100.0, 10, "b"),
+            trade("WIF", "sell", 2.0, 10.0, 11, "c"),
+        ];
+        let start = Some(Utc.with_ymd_and_hms(2024, 1, 5, 0, 0, 0).unwrap());
+        let report = attribute_trades("wallet", &trades, start, None, |_| TradeStrategy::Manual);
+
+        assert_eq!(report.total.trades, 2);
+        assert_eq!(report.total.realized_trades, 1);
+        assert_eq!(report.unmatched_sells, 1);
+        assert!((report.total.net_pnl - 50.0).abs() < 1e-9);
+    }
+}
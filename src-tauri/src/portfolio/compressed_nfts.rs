@@ -0,0 +1,209 @@
+use crate::insiders::compressed_nft::{CompressedNftAction, CompressedNftEvent};
+use crate::profiles::ProfilePaths;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::RwLock;
+
+const LEDGER_FILE: &str = "compressed_nfts.json";
+
+/// A compressed NFT currently held by a wallet. cNFTs live as leaves in a
+/// Merkle tree rather than token accounts, so holdings are tracked from
+/// Bubblegum events instead of balance lookups.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompressedNftHolding {
+    pub asset_id: String,
+    pub tree_id: String,
+    pub leaf_index: Option<u64>,
+    pub owner: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub collection: Option<String>,
+    pub acquired_at: DateTime<Utc>,
+    pub last_signature: String,
+}
+
+#[derive(Debug, Default)]
+pub struct CompressedNftLedger {
+    holdings: HashMap<String, CompressedNftHolding>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedCompressedNftLedger = Arc<RwLock<CompressedNftLedger>>;
+
+impl CompressedNftLedger {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        let path = app_handle
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(LEDGER_FILE));
+        let holdings = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        Self { holdings, path }
+    }
+
+    pub fn holdings_for(&self, owner: &str) -> Vec<CompressedNftHolding> {
+        let mut holdings: Vec<_> = self
+            .holdings
+            .values()
+            .filter(|holding| holding.owner == owner)
+            .cloned()
+            .collect();
+        holdings.sort_by(|a, b| b.acquired_at.cmp(&a.acquired_at));
+        holdings
+    }
+
+    /// Applies an event to the ledger. Returns whether holdings changed.
+    pub fn apply(
+        &mut self,
+        event: &CompressedNftEvent,
+        signature: &str,
+        timestamp: DateTime<Utc>,
+    ) -> bool {
+        if event.asset_id.is_empty() {
+            return false;
+        }
+
+        match event.action {
+            CompressedNftAction::Mint | CompressedNftAction::Transfer => {
+                let Some(owner) = event.new_owner.clone() else {
+                    return false;
+                };
+                let previous = self.holdings.get(&event.asset_id);
+                let holding = CompressedNftHolding {
+                    asset_id: event.asset_id.clone(),
+                    tree_id: event.tree_id.clone(),
+                    leaf_index: event.leaf_index,
+                    name: event
+                        .name
+                        .clone()
+                        .or_else(|| previous.and_then(|h| h.name.clone())),
+                    symbol: event
+                        .symbol
+                        .clone()
+                        .or_else(|| previous.and_then(|h| h.symbol.clone())),
+                    collection: event
+                        .collection
+                        .clone()
+                        .or_else(|| previous.and_then(|h| h.collection.clone())),
+                    owner,
+                    acquired_at: timestamp,
+                    last_signature: signature.to_string(),
+                };
+                self.holdings.insert(event.asset_id.clone(), holding);
+                true
+            }
+            // Burned and decompressed assets no longer exist as leaves.
+            CompressedNftAction::Burn | CompressedNftAction::Decompress => {
+                self.holdings.remove(&event.asset_id).is_some()
+            }
+            CompressedNftAction::Update => match self.holdings.get_mut(&event.asset_id) {
+                Some(holding) => {
+                    if event.name.is_some() {
+                        holding.name = event.name.clone();
+                    }
+                    if event.collection.is_some() {
+                        holding.collection = event.collection.clone();
+                    }
+                    holding.last_signature = signature.to_string();
+                    true
+                }
+                None => false,
+            },
+            CompressedNftAction::Delegate | CompressedNftAction::Redeem => false,
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create compressed NFT directory: {e}"))?;
+            }
+            let contents = serde_json::to_string_pretty(&self.holdings)
+                .map_err(|e| format!("Failed to serialize compressed NFTs: {e}"))?;
+            fs::write(path, contents)
+                .map_err(|e| format!("Failed to persist compressed NFTs: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub async fn portfolio_get_compressed_nfts(
+    owner: String,
+    ledger: State<'_, SharedCompressedNftLedger>,
+) -> Result<Vec<CompressedNftHolding>, String> {
+    Ok(ledger.read().await.holdings_for(&owner))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(action: CompressedNftAction, new_owner: Option<&str>) -> CompressedNftEvent {
+        CompressedNftEvent {
+            action,
+            asset_id: "asset1".to_string(),
+            tree_id: "tree1".to_string(),
+            leaf_index: Some(7),
+            old_owner: None,
+            new_owner: new_owner.map(|o| o.to_string()),
+            name: if action == CompressedNftAction::Mint {
+                Some("Drop #7".to_string())
+            } else {
+                None
+            },
+            symbol: None,
+            collection: None,
+        }
+    }
+
+    #[test]
+    fn transfers_move_holdings_between_owners() {
+        let mut ledger = CompressedNftLedger::default();
+        let now = Utc::now();
+
+        assert!(ledger.apply(
+            &event(CompressedNftAction::Mint, Some("alice")),
+            "sig1",
+            now
+        ));
+        assert_eq!(ledger.holdings_for("alice").len(), 1);
+
+        assert!(ledger.apply(
+            &event(CompressedNftAction::Transfer, Some("bob")),
+            "sig2",
+            now
+        ));
+        assert!(ledger.holdings_for("alice").is_empty());
+        let bob = ledger.holdings_for("bob");
+        assert_eq!(bob[0].name.as_deref(), Some("Drop #7"));
+        assert_eq!(bob[0].last_signature, "sig2");
+    }
+
+    #[test]
+    fn burns_remove_holdings() {
+        let mut ledger = CompressedNftLedger::default();
+        let now = Utc::now();
+        ledger.apply(
+            &event(CompressedNftAction::Mint, Some("alice")),
+            "sig1",
+            now,
+        );
+
+        assert!(ledger.apply(&event(CompressedNftAction::Burn, None), "sig2", now));
+        assert!(ledger.holdings_for("alice").is_empty());
+        assert!(!ledger.apply(&event(CompressedNftAction::Delegate, None), "sig3", now));
+    }
+}
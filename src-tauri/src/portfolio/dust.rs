@@ -0,0 +1,758 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_client::rpc_request::RpcRequest;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::api::jupiter::{
+    fetch_quote, jupiter_swap, QuoteCommandInput, QuoteResponse, SwapCommandInput,
+};
+use crate::api::trading_execution::get_priority_fee_estimates;
+use crate::chains::{RoutingHint, RpcPool, SharedRpcPool};
+use crate::journal::{
+    Emotion, EmotionTracking, EntryType, JournalEntry, MarketConditions, MarketTrend,
+    SharedJournalDatabase, TradeOutcome, Volatility, VolumeLevel,
+};
+use crate::wallet::tx_lifecycle::{
+    SharedTransactionLifecycle, SubmitTransactionRequest, TransactionLifecycleStatus,
+};
+
+const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+const BASE_FEE_LAMPORTS: f64 = 5_000.0;
+/// Compute budget assumed for a single-route Jupiter swap when pricing the
+/// priority fee.
+const SWAP_COMPUTE_UNITS: f64 = 200_000.0;
+/// Candidates quoted per scan, to stay within Jupiter's rate limits.
+const MAX_QUOTED_ACCOUNTS: usize = 40;
+/// Quotes go stale quickly; plans must be prepared within this window.
+const PLAN_TTL_SECONDS: i64 = 120;
+const SETTLE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+const SETTLE_TIMEOUT_SECONDS: i64 = 180;
+
+pub const DUST_CONSOLIDATION_EVENT: &str = "dust_consolidation_updated";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DustTarget {
+    Sol,
+    Usdc,
+}
+
+impl DustTarget {
+    fn mint(&self) -> &'static str {
+        match self {
+            DustTarget::Sol => SOL_MINT,
+            DustTarget::Usdc => USDC_MINT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DustScanRequest {
+    pub wallet_address: String,
+    /// Positions worth less than this (USD) count as dust.
+    #[serde(default = "default_threshold_usd")]
+    pub threshold_usd: f64,
+    #[serde(default = "default_target")]
+    pub target: DustTarget,
+    #[serde(default = "default_slippage_bps")]
+    pub slippage_bps: u16,
+    /// Minimum proceeds after fees for a swap to be worth executing.
+    #[serde(default)]
+    pub min_net_usd: f64,
+    #[serde(default = "default_max_price_impact")]
+    pub max_price_impact_pct: f64,
+}
+
+fn default_threshold_usd() -> f64 {
+    5.0
+}
+
+fn default_target() -> DustTarget {
+    DustTarget::Sol
+}
+
+fn default_slippage_bps() -> u16 {
+    100
+}
+
+fn default_max_price_impact() -> f64 {
+    5.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DustCandidate {
+    pub mint: String,
+    pub token_account: String,
+    pub amount: u64,
+    pub decimals: u8,
+    pub ui_amount: f64,
+    /// What the swap is quoted to return, in USD.
+    pub output_usd: f64,
+    pub network_fee_usd: f64,
+    pub price_impact_pct: f64,
+    pub net_usd: f64,
+    pub worthwhile: bool,
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote: Option<QuoteResponse>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DustPlanStatus {
+    Proposed,
+    AwaitingSignatures,
+    Executing,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DustSwapTransaction {
+    pub mint: String,
+    /// Unsigned swap; the wallet signs the whole batch in one prompt.
+    pub transaction_base64: String,
+    pub last_valid_block_height: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DustSwapOutcome {
+    pub mint: String,
+    pub lifecycle_id: Option<String>,
+    pub signature: Option<String>,
+    pub status: Option<TransactionLifecycleStatus>,
+    pub output_usd: f64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DustPlan {
+    pub id: String,
+    pub wallet_address: String,
+    pub target: DustTarget,
+    pub threshold_usd: f64,
+    pub candidates: Vec<DustCandidate>,
+    pub total_output_usd: f64,
+    pub total_net_usd: f64,
+    pub status: DustPlanStatus,
+    pub transactions: Vec<DustSwapTransaction>,
+    pub outcomes: Vec<DustSwapOutcome>,
+    pub journal_entry_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl DustPlan {
+    fn selected(&self) -> impl Iterator<Item = &DustCandidate> {
+        self.candidates.iter().filter(|c| c.worthwhile)
+    }
+}
+
+/// Decides whether a dust swap pays for itself. Returns the proceeds net of
+/// fees and, when it does not, the reason.
+pub fn evaluate_dust_swap(
+    output_usd: f64,
+    network_fee_usd: f64,
+    price_impact_pct: f64,
+    request: &DustScanRequest,
+) -> (f64, Option<String>) {
+    let net_usd = output_usd - network_fee_usd;
+    let reason = if price_impact_pct > request.max_price_impact_pct {
+        Some(format!(
+            "Price impact {:.2}% exceeds {:.2}%",
+            price_impact_pct, request.max_price_impact_pct
+        ))
+    } else if net_usd <= request.min_net_usd {
+        Some(format!(
+            "Fees of ${:.4} consume the ${:.4} proceeds",
+            network_fee_usd, output_usd
+        ))
+    } else {
+        None
+    };
+    (net_usd, reason)
+}
+
+#[derive(Default)]
+pub struct DustConsolidator {
+    plans: HashMap<String, DustPlan>,
+}
+
+pub type SharedDustConsolidator = Arc<RwLock<DustConsolidator>>;
+
+impl DustConsolidator {
+    pub fn get(&self, plan_id: &str) -> Option<DustPlan> {
+        self.plans.get(plan_id).cloned()
+    }
+
+    fn insert(&mut self, plan: DustPlan) {
+        let cutoff = Utc::now() - Duration::hours(24);
+        self.plans.retain(|_, plan| plan.updated_at > cutoff);
+        self.plans.insert(plan.id.clone(), plan);
+    }
+
+    fn update<F>(&mut self, plan_id: &str, change: F) -> Option<DustPlan>
+    where
+        F: FnOnce(&mut DustPlan),
+    {
+        let plan = self.plans.get_mut(plan_id)?;
+        change(plan);
+        plan.updated_at = Utc::now();
+        Some(plan.clone())
+    }
+}
+
+pub(crate) struct TokenHolding {
+    pub(crate) mint: String,
+    pub(crate) token_account: String,
+    pub(crate) amount: u64,
+    pub(crate) decimals: u8,
+    pub(crate) ui_amount: f64,
+}
+
+pub(crate) async fn fetch_holdings(
+    pool: &SharedRpcPool,
+    wallet_address: &str,
+) -> Result<Vec<TokenHolding>, String> {
+    let mut holdings = Vec::new();
+    for program in [TOKEN_PROGRAM, TOKEN_2022_PROGRAM] {
+        let params = json!([
+            wallet_address,
+            { "programId": program },
+            { "encoding": "jsonParsed", "commitment": "confirmed" }
+        ]);
+        let response: Value = RpcPool::call(pool, RoutingHint::Read, move |client| {
+            client.send(RpcRequest::GetTokenAccountsByOwner, params.clone())
+        })
+        .await
+        .map_err(|e| format!("Failed to fetch token accounts: {e}"))?;
+
+        for account in response["value"].as_array().into_iter().flatten() {
+            let info = &account["account"]["data"]["parsed"]["info"];
+            let token_amount = &info["tokenAmount"];
+            let (Some(mint), Some(token_account), Some(amount)) = (
+                info["mint"].as_str(),
+                account["pubkey"].as_str(),
+                token_amount["amount"]
+                    .as_str()
+                    .and_then(|a| a.parse::<u64>().ok()),
+            ) else {
+                continue;
+            };
+            if amount == 0 {
+                continue;
+            }
+            holdings.push(TokenHolding {
+                mint: mint.to_string(),
+                token_account: token_account.to_string(),
+                amount,
+                decimals: token_amount["decimals"].as_u64().unwrap_or(0) as u8,
+                ui_amount: token_amount["uiAmount"].as_f64().unwrap_or(0.0),
+            });
+        }
+    }
+    Ok(holdings)
+}
+
+pub(crate) async fn sol_price_usd() -> Result<f64, String> {
+    let quote = fetch_quote(&quote_input(
+        SOL_MINT,
+        USDC_MINT,
+        LAMPORTS_PER_SOL as u64,
+        50,
+    ))
+    .await
+    .map_err(|e| format!("Failed to price SOL: {e}"))?;
+    let out: f64 = quote.quote.output_amount.parse().unwrap_or(0.0);
+    Ok(out / 1_000_000.0)
+}
+
+pub(crate) fn quote_input(
+    input_mint: &str,
+    output_mint: &str,
+    amount: u64,
+    slippage_bps: u16,
+) -> QuoteCommandInput {
+    QuoteCommandInput {
+        input_mint: input_mint.to_string(),
+        output_mint: output_mint.to_string(),
+        amount,
+        slippage_bps: Some(slippage_bps),
+        swap_mode: None,
+        platform_fee_bps: None,
+        only_direct_routes: None,
+        referral_account: None,
+        as_legacy_transaction: None,
+        priority_fee_config: None,
+    }
+}
+
+/// Builds a consolidation plan: every token account worth less than the
+/// threshold, each with a live quote into the target and a verdict on
+/// whether the swap clears its fees.
+pub async fn scan_dust(
+    pool: &SharedRpcPool,
+    request: &DustScanRequest,
+) -> Result<DustPlan, String> {
+    let target = request.target.mint();
+    let sol_usd = sol_price_usd().await?;
+    let priority_micro_lamports = get_priority_fee_estimates()
+        .await
+        .ok()
+        .and_then(|estimates| {
+            estimates
+                .into_iter()
+                .find(|estimate| estimate.preset == "normal")
+        })
+        .map(|estimate| estimate.micro_lamports)
+        .unwrap_or(5_000);
+    let fee_lamports =
+        BASE_FEE_LAMPORTS + priority_micro_lamports as f64 * SWAP_COMPUTE_UNITS / 1_000_000.0;
+    let network_fee_usd = fee_lamports / LAMPORTS_PER_SOL * sol_usd;
+
+    let holdings = fetch_holdings(pool, &request.wallet_address).await?;
+    let mut candidates = Vec::new();
+    for holding in holdings
+        .into_iter()
+        .filter(|h| h.mint != target)
+        .take(MAX_QUOTED_ACCOUNTS)
+    {
+        let quote = fetch_quote(&quote_input(
+            &holding.mint,
+            target,
+            holding.amount,
+            request.slippage_bps,
+        ))
+        .await;
+
+        let mut candidate = DustCandidate {
+            mint: holding.mint,
+            token_account: holding.token_account,
+            amount: holding.amount,
+            decimals: holding.decimals,
+            ui_amount: holding.ui_amount,
+            output_usd: 0.0,
+            network_fee_usd,
+            price_impact_pct: 0.0,
+            net_usd: -network_fee_usd,
+            worthwhile: false,
+            reason: None,
+            quote: None,
+        };
+        match quote {
+            Ok(result) => {
+                let out: f64 = result.quote.output_amount.parse().unwrap_or(0.0);
+                let output_usd = match request.target {
+                    DustTarget::Sol => out / LAMPORTS_PER_SOL * sol_usd,
+                    DustTarget::Usdc => out / 1_000_000.0,
+                };
+                if output_usd >= request.threshold_usd {
+                    continue;
+                }
+                let price_impact_pct = result.quote.price_impact_pct * 100.0;
+                let (net_usd, reason) =
+                    evaluate_dust_swap(output_usd, network_fee_usd, price_impact_pct, request);
+                candidate.output_usd = output_usd;
+                candidate.price_impact_pct = price_impact_pct;
+                candidate.net_usd = net_usd;
+                candidate.worthwhile = reason.is_none();
+                candidate.reason = reason;
+                candidate.quote = Some(result.quote);
+            }
+            Err(e) => candidate.reason = Some(format!("No route: {e}")),
+        }
+        candidates.push(candidate);
+    }
+    candidates.sort_by(|a, b| {
+        b.net_usd
+            .partial_cmp(&a.net_usd)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let now = Utc::now();
+    let mut plan = DustPlan {
+        id: Uuid::new_v4().to_string(),
+        wallet_address: request.wallet_address.clone(),
+        target: request.target,
+        threshold_usd: request.threshold_usd,
+        candidates,
+        total_output_usd: 0.0,
+        total_net_usd: 0.0,
+        status: DustPlanStatus::Proposed,
+        transactions: Vec::new(),
+        outcomes: Vec::new(),
+        journal_entry_id: None,
+        created_at: now,
+        updated_at: now,
+    };
+    plan.total_output_usd = plan.selected().map(|c| c.output_usd).sum();
+    plan.total_net_usd = plan.selected().map(|c| c.net_usd).sum();
+    Ok(plan)
+}
+
+fn journal_entry(plan: &DustPlan) -> JournalEntry {
+    let now = Utc::now().timestamp();
+    let succeeded: Vec<_> = plan
+        .outcomes
+        .iter()
+        .filter(|o| {
+            matches!(
+                o.status,
+                Some(TransactionLifecycleStatus::Confirmed | TransactionLifecycleStatus::Finalized)
+            )
+        })
+        .collect();
+    let proceeds: f64 = succeeded.iter().map(|o| o.output_usd).sum();
+    let fees: f64 = plan.selected().map(|c| c.network_fee_usd).sum();
+
+    let mut notes = format!(
+        "Dust consolidation into {:?}: {}/{} swaps settled for ~${:.2}.",
+        plan.target,
+        succeeded.len(),
+        plan.outcomes.len(),
+        proceeds
+    );
+    for outcome in &plan.outcomes {
+        notes.push_str(&format!(
+            "\n- {}: {}",
+            outcome.mint,
+            match (&outcome.status, &outcome.error) {
+                (_, Some(error)) => format!("failed ({error})"),
+                (Some(status), None) => format!(
+                    "{:?} {}",
+                    status,
+                    outcome.signature.as_deref().unwrap_or_default()
+                ),
+                (None, None) => "unknown".to_string(),
+            }
+        ));
+    }
+
+    JournalEntry {
+        id: Uuid::new_v4().to_string(),
+        timestamp: now,
+        trade_id: Some(plan.id.clone()),
+        entry_type: EntryType::PostTrade,
+        strategy_tags: vec!["dust-consolidation".to_string()],
+        emotions: EmotionTracking {
+            primary_emotion: Emotion::Neutral,
+            intensity: 0.0,
+            secondary_emotions: Vec::new(),
+            stress_level: 0.0,
+            clarity_level: 1.0,
+            fomo_level: 0.0,
+            revenge_trading: false,
+            discipline_score: 1.0,
+        },
+        notes,
+        market_conditions: MarketConditions {
+            trend: MarketTrend::Neutral,
+            volatility: Volatility::Medium,
+            volume: VolumeLevel::Medium,
+            news_sentiment: 0.0,
+            notes: String::new(),
+        },
+        confidence_level: 1.0,
+        position_size: Some(proceeds as f32),
+        entry_price: None,
+        exit_price: None,
+        outcome: Some(TradeOutcome {
+            pnl: (proceeds - fees) as f32,
+            pnl_percent: 0.0,
+            success: !succeeded.is_empty() && succeeded.len() == plan.outcomes.len(),
+            followed_plan: true,
+            risk_reward_ratio: 0.0,
+        }),
+        lessons_learned: None,
+        attachments: Vec::new(),
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+/// Waits for every submitted swap to settle, then records the batch in the
+/// journal.
+async fn settle(
+    app: AppHandle,
+    plan_id: String,
+    consolidator: SharedDustConsolidator,
+    lifecycle: SharedTransactionLifecycle,
+    journal: SharedJournalDatabase,
+) {
+    let deadline = Utc::now() + Duration::seconds(SETTLE_TIMEOUT_SECONDS);
+    loop {
+        let Some(plan) = consolidator.read().await.get(&plan_id) else {
+            return;
+        };
+        let mut pending = false;
+        let mut outcomes = plan.outcomes.clone();
+        for outcome in outcomes.iter_mut() {
+            let Some(id) = &outcome.lifecycle_id else {
+                continue;
+            };
+            if let Some(tracked) = lifecycle.get(id).await {
+                outcome.signature = Some(tracked.signature);
+                outcome.status = Some(tracked.status);
+                if tracked.error.is_some() {
+                    outcome.error = tracked.error;
+                }
+                pending |= matches!(
+                    tracked.status,
+                    TransactionLifecycleStatus::Submitted
+                        | TransactionLifecycleStatus::AwaitingSignature
+                        | TransactionLifecycleStatus::Reorged
+                );
+            }
+        }
+
+        let done = !pending || Utc::now() > deadline;
+        let mut updated = consolidator
+            .write()
+            .await
+            .update(&plan_id, |plan| plan.outcomes = outcomes);
+        if done {
+            if let Some(plan) = &updated {
+                let entry = journal_entry(plan);
+                let entry_id = match journal.read().await.create_entry(&entry).await {
+                    Ok(()) => Some(entry.id),
+                    Err(e) => {
+                        eprintln!("Failed to journal dust consolidation {plan_id}: {e}");
+                        None
+                    }
+                };
+                let success = entry.outcome.map(|o| o.success).unwrap_or(false);
+                updated = consolidator.write().await.update(&plan_id, |plan| {
+                    plan.journal_entry_id = entry_id;
+                    plan.status = if success {
+                        DustPlanStatus::Completed
+                    } else {
+                        DustPlanStatus::Failed
+                    };
+                });
+            }
+        }
+        if let Some(plan) = &updated {
+            let _ = app.emit(DUST_CONSOLIDATION_EVENT, plan);
+        }
+        if done {
+            return;
+        }
+        tokio::time::sleep(SETTLE_POLL_INTERVAL).await;
+    }
+}
+
+#[tauri::command]
+pub async fn dust_scan(
+    request: DustScanRequest,
+    rpc_pool: State<'_, SharedRpcPool>,
+    consolidator: State<'_, SharedDustConsolidator>,
+) -> Result<DustPlan, String> {
+    let plan = scan_dust(&rpc_pool, &request).await?;
+    consolidator.write().await.insert(plan.clone());
+    Ok(plan)
+}
+
+#[tauri::command]
+pub async fn dust_get_plan(
+    plan_id: String,
+    consolidator: State<'_, SharedDustConsolidator>,
+) -> Result<Option<DustPlan>, String> {
+    Ok(consolidator.read().await.get(&plan_id))
+}
+
+/// Builds unsigned swaps for the worthwhile candidates (or the given
+/// subset) so the wallet can approve the whole batch at once.
+#[tauri::command]
+pub async fn dust_prepare(
+    plan_id: String,
+    mints: Option<Vec<String>>,
+    consolidator: State<'_, SharedDustConsolidator>,
+) -> Result<DustPlan, String> {
+    crate::environment::require_mainnet("Dust consolidation").map_err(|e| e.to_string())?;
+    let plan = consolidator
+        .read()
+        .await
+        .get(&plan_id)
+        .ok_or_else(|| format!("Dust plan {plan_id} not found"))?;
+    if plan.status != DustPlanStatus::Proposed {
+        return Err(format!("Dust plan {plan_id} was already prepared"));
+    }
+    if Utc::now() - plan.created_at > Duration::seconds(PLAN_TTL_SECONDS) {
+        return Err("Dust plan quotes expired; scan again".to_string());
+    }
+
+    let mut transactions = Vec::new();
+    for candidate in plan.candidates.iter().filter(|c| match &mints {
+        Some(mints) => mints.contains(&c.mint) && c.quote.is_some(),
+        None => c.worthwhile,
+    }) {
+        let Some(quote) = candidate.quote.clone() else {
+            continue;
+        };
+        let swap = jupiter_swap(SwapCommandInput {
+            quote,
+            user_public_key: plan.wallet_address.clone(),
+            fee_account: None,
+            wrap_and_unwrap_sol: Some(true),
+            as_legacy_transaction: None,
+            priority_fee_config: None,
+            simulate: None,
+        })
+        .await
+        .map_err(|e| format!("Failed to build swap for {}: {e}", candidate.mint))?;
+        transactions.push(DustSwapTransaction {
+            mint: candidate.mint.clone(),
+            transaction_base64: swap.transaction.base64,
+            last_valid_block_height: swap.last_valid_block_height,
+        });
+    }
+    if transactions.is_empty() {
+        return Err("No dust positions selected for consolidation".to_string());
+    }
+
+    let selected: Vec<String> = transactions.iter().map(|tx| tx.mint.clone()).collect();
+    consolidator
+        .write()
+        .await
+        .update(&plan_id, |plan| {
+            for candidate in plan.candidates.iter_mut() {
+                candidate.worthwhile = selected.contains(&candidate.mint);
+            }
+            plan.transactions = transactions;
+            plan.status = DustPlanStatus::AwaitingSignatures;
+        })
+        .ok_or_else(|| format!("Dust plan {plan_id} not found"))
+}
+
+/// Submits the batch the wallet signed, in the order the transactions were
+/// prepared, and tracks them until the outcome is journaled.
+#[tauri::command]
+pub async fn dust_execute(
+    app: AppHandle,
+    plan_id: String,
+    signed_transactions: Vec<String>,
+    consolidator: State<'_, SharedDustConsolidator>,
+    lifecycle: State<'_, SharedTransactionLifecycle>,
+    journal: State<'_, SharedJournalDatabase>,
+) -> Result<DustPlan, String> {
+    let plan = consolidator
+        .read()
+        .await
+        .get(&plan_id)
+        .ok_or_else(|| format!("Dust plan {plan_id} not found"))?;
+    if plan.status != DustPlanStatus::AwaitingSignatures {
+        return Err(format!("Dust plan {plan_id} is not awaiting signatures"));
+    }
+    if signed_transactions.len() != plan.transactions.len() {
+        return Err(format!(
+            "Expected {} signed transactions, got {}",
+            plan.transactions.len(),
+            signed_transactions.len()
+        ));
+    }
+
+    let mut outcomes = Vec::new();
+    for (prepared, signed) in plan.transactions.iter().zip(signed_transactions) {
+        let output_usd = plan
+            .candidates
+            .iter()
+            .find(|c| c.mint == prepared.mint)
+            .map(|c| c.output_usd)
+            .unwrap_or(0.0);
+        let submitted = lifecycle
+            .submit(
+                app.clone(),
+                SubmitTransactionRequest {
+                    transaction_base64: signed,
+                    last_valid_block_height: Some(prepared.last_valid_block_height),
+                    max_attempts: None,
+                    fee_schedule: None,
+                    label: Some(format!("Dust consolidation {}", prepared.mint)),
+                },
+            )
+            .await;
+        outcomes.push(match submitted {
+            Ok(tracked) => DustSwapOutcome {
+                mint: prepared.mint.clone(),
+                lifecycle_id: Some(tracked.id),
+                signature: Some(tracked.signature),
+                status: Some(tracked.status),
+                output_usd,
+                error: None,
+            },
+            Err(error) => DustSwapOutcome {
+                mint: prepared.mint.clone(),
+                lifecycle_id: None,
+                signature: None,
+                status: Some(TransactionLifecycleStatus::Failed),
+                output_usd,
+                error: Some(error),
+            },
+        });
+    }
+
+    let plan = consolidator
+        .write()
+        .await
+        .update(&plan_id, |plan| {
+            plan.outcomes = outcomes;
+            plan.status = DustPlanStatus::Executing;
+        })
+        .ok_or_else(|| format!("Dust plan {plan_id} not found"))?;
+
+    tauri::async_runtime::spawn(settle(
+        app,
+        plan_id,
+        consolidator.inner().clone(),
+        lifecycle.inner().clone(),
+        journal.inner().clone(),
+    ));
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> DustScanRequest {
+        DustScanRequest {
+            wallet_address: "wallet".to_string(),
+            threshold_usd: default_threshold_usd(),
+            target: default_target(),
+            slippage_bps: default_slippage_bps(),
+            min_net_usd: 0.0,
+            max_price_impact_pct: default_max_price_impact(),
+        }
+    }
+
+    #[test]
+    fn swaps_must_clear_their_fees() {
+        let (net, reason) = evaluate_dust_swap(1.20, 0.002, 0.4, &request());
+        assert!((net - 1.198).abs() < 1e-9);
+        assert!(reason.is_none());
+
+        let (net, reason) = evaluate_dust_swap(0.001, 0.002, 0.4, &request());
+        assert!(net < 0.0);
+        assert!(reason.unwrap().contains("Fees"));
+    }
+
+    #[test]
+    fn high_impact_swaps_are_rejected() {
+        let (_, reason) = evaluate_dust_swap(3.0, 0.002, 12.0, &request());
+        assert!(reason.unwrap().contains("Price impact"));
+    }
+}
@@ -0,0 +1,865 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_client::rpc_request::RpcRequest;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::dust::{fetch_holdings, quote_input, sol_price_usd};
+use crate::api::jupiter::{fetch_quote, jupiter_swap, QuoteResponse, SwapCommandInput};
+use crate::auth::two_factor::TwoFactorManager;
+use crate::chains::{RoutingHint, RpcPool, SharedRpcPool};
+use crate::journal::{
+    Emotion, EmotionTracking, EntryType, JournalEntry, MarketConditions, MarketTrend,
+    SharedJournalDatabase, TradeOutcome, Volatility, VolumeLevel,
+};
+use crate::security::keystore::Keystore;
+use crate::wallet::tx_lifecycle::{
+    SharedTransactionLifecycle, SubmitTransactionRequest, TransactionLifecycleStatus,
+};
+
+const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+/// SOL left behind for fees and rent when flattening into USDC.
+const SOL_FEE_RESERVE_LAMPORTS: u64 = 50_000_000;
+/// Price impact ceilings (percent) for the first two stages; everything
+/// else that clears the slippage limit goes last.
+const STAGE_IMPACT_LIMITS: [f64; 2] = [0.3, 1.0];
+const MAX_QUOTED_ACCOUNTS: usize = 60;
+/// Signed swaps must be handed back before their blockhash runs out.
+const SIGNATURE_WINDOW_SECONDS: i64 = 90;
+const SETTLE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+const STAGE_TIMEOUT_SECONDS: i64 = 120;
+
+pub const FLATTEN_CONFIRMATION_PHRASE: &str = "FLATTEN ALL";
+pub const FLATTEN_PROGRESS_EVENT: &str = "flatten_all_progress";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlattenTarget {
+    Usdc,
+    Sol,
+}
+
+impl FlattenTarget {
+    fn mint(&self) -> &'static str {
+        match self {
+            FlattenTarget::Usdc => USDC_MINT,
+            FlattenTarget::Sol => SOL_MINT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlattenRequest {
+    pub wallet_address: String,
+    #[serde(default = "default_target")]
+    pub target: FlattenTarget,
+    /// Per-leg limit; legs whose quoted impact exceeds it are left alone.
+    #[serde(default = "default_max_slippage_bps")]
+    pub max_slippage_bps: u16,
+    /// Holdings worth less than this (USD) are not sold.
+    #[serde(default = "default_dust_threshold_usd")]
+    pub dust_threshold_usd: f64,
+    /// Mints to keep, e.g. governance or staked tokens.
+    #[serde(default)]
+    pub keep_mints: Vec<String>,
+    /// Must be exactly [`FLATTEN_CONFIRMATION_PHRASE`].
+    pub confirmation: String,
+    pub two_factor_code: String,
+}
+
+fn default_target() -> FlattenTarget {
+    FlattenTarget::Usdc
+}
+
+fn default_max_slippage_bps() -> u16 {
+    150
+}
+
+fn default_dust_threshold_usd() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FlattenLegStatus {
+    Planned,
+    Skipped,
+    Submitted,
+    Confirmed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlattenLeg {
+    pub mint: String,
+    pub amount: u64,
+    pub decimals: u8,
+    pub ui_amount: f64,
+    /// Quoted proceeds in USD.
+    pub value_usd: f64,
+    pub price_impact_pct: f64,
+    /// 1-based execution stage; 0 for skipped legs.
+    pub stage: u8,
+    pub status: FlattenLegStatus,
+    pub skip_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_base64: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_valid_block_height: Option<u64>,
+    pub lifecycle_id: Option<String>,
+    pub signature: Option<String>,
+    pub error: Option<String>,
+    #[serde(skip)]
+    quote: Option<QuoteResponse>,
+}
+
+impl FlattenLeg {
+    fn executable(&self) -> bool {
+        self.stage > 0
+    }
+
+    fn skip(&mut self, reason: String) {
+        self.stage = 0;
+        self.status = FlattenLegStatus::Skipped;
+        self.skip_reason = Some(reason);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FlattenPlanStatus {
+    AwaitingSignatures,
+    Executing,
+    Completed,
+    PartiallyCompleted,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlattenPlan {
+    pub id: String,
+    pub wallet_address: String,
+    pub target: FlattenTarget,
+    pub max_slippage_bps: u16,
+    pub dust_threshold_usd: f64,
+    /// Executable legs in stage order, then skipped ones.
+    pub legs: Vec<FlattenLeg>,
+    pub stage_count: u8,
+    pub total_value_usd: f64,
+    pub status: FlattenPlanStatus,
+    pub journal_entry_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl FlattenPlan {
+    fn executable(&self) -> impl Iterator<Item = &FlattenLeg> {
+        self.legs.iter().filter(|leg| leg.executable())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlattenProgress {
+    pub plan_id: String,
+    pub stage: u8,
+    pub stage_count: u8,
+    pub mint: Option<String>,
+    pub leg_status: Option<FlattenLegStatus>,
+    pub settled_legs: usize,
+    pub total_legs: usize,
+    pub message: String,
+}
+
+#[derive(Default)]
+pub struct FlattenCoordinator {
+    plans: HashMap<String, FlattenPlan>,
+}
+
+pub type SharedFlattenCoordinator = Arc<RwLock<FlattenCoordinator>>;
+
+impl FlattenCoordinator {
+    pub fn get(&self, plan_id: &str) -> Option<FlattenPlan> {
+        self.plans.get(plan_id).cloned()
+    }
+
+    fn insert(&mut self, plan: FlattenPlan) {
+        let cutoff = Utc::now() - Duration::hours(24);
+        self.plans.retain(|_, plan| plan.updated_at > cutoff);
+        self.plans.insert(plan.id.clone(), plan);
+    }
+
+    fn update<F>(&mut self, plan_id: &str, change: F) -> Option<FlattenPlan>
+    where
+        F: FnOnce(&mut FlattenPlan),
+    {
+        let plan = self.plans.get_mut(plan_id)?;
+        change(plan);
+        plan.updated_at = Utc::now();
+        Some(plan.clone())
+    }
+
+    /// Attaches the signed transactions and moves the plan to `Executing`.
+    /// The status check and the transition happen under the same write
+    /// guard, so two concurrent executes cannot both start the plan.
+    fn begin_execution(
+        &mut self,
+        plan_id: &str,
+        signed_transactions: Vec<String>,
+        now: DateTime<Utc>,
+    ) -> Result<FlattenPlan, String> {
+        let plan = self
+            .plans
+            .get_mut(plan_id)
+            .ok_or_else(|| format!("Flatten plan {plan_id} not found"))?;
+        if plan.status != FlattenPlanStatus::AwaitingSignatures {
+            return Err(format!("Flatten plan {plan_id} is not awaiting signatures"));
+        }
+        if now - plan.created_at > Duration::seconds(SIGNATURE_WINDOW_SECONDS) {
+            return Err("Flatten plan expired before it was signed; run it again".to_string());
+        }
+        let expected = plan.executable().count();
+        if signed_transactions.len() != expected {
+            return Err(format!(
+                "Expected {expected} signed transactions, got {}",
+                signed_transactions.len()
+            ));
+        }
+
+        let mut signed = signed_transactions.into_iter();
+        for leg in plan.legs.iter_mut().filter(|leg| leg.executable()) {
+            leg.transaction_base64 = signed.next();
+        }
+        plan.status = FlattenPlanStatus::Executing;
+        plan.updated_at = now;
+        Ok(plan.clone())
+    }
+}
+
+/// Checks the typed phrase and the second factor. 2FA is mandatory here,
+/// so an unenrolled account cannot use the panic button.
+fn authorize(
+    request: &FlattenRequest,
+    two_factor: &TwoFactorManager,
+    keystore: &Keystore,
+) -> Result<(), String> {
+    check_confirmation(&request.confirmation)?;
+    let status = two_factor.status().map_err(|e| e.to_string())?;
+    if !status.enrolled {
+        return Err("Flatten-all requires two-factor authentication to be enabled".to_string());
+    }
+    match two_factor.verify(&request.two_factor_code, keystore) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("Invalid two-factor code".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn check_confirmation(confirmation: &str) -> Result<(), String> {
+    if confirmation.trim() == FLATTEN_CONFIRMATION_PHRASE {
+        Ok(())
+    } else {
+        Err(format!(
+            "Type \"{FLATTEN_CONFIRMATION_PHRASE}\" to confirm flattening every position"
+        ))
+    }
+}
+
+/// Orders legs most liquid first and assigns stages by price impact.
+/// Legs below the dust threshold or above the slippage limit are skipped.
+pub fn stage_legs(legs: &mut [FlattenLeg], max_slippage_bps: u16, dust_threshold_usd: f64) {
+    let max_impact_pct = f64::from(max_slippage_bps) / 100.0;
+    for leg in legs.iter_mut().filter(|leg| leg.skip_reason.is_none()) {
+        if leg.value_usd < dust_threshold_usd {
+            leg.skip(format!(
+                "Dust: ${:.2} is below the threshold",
+                leg.value_usd
+            ));
+        } else if leg.price_impact_pct > max_impact_pct {
+            leg.skip(format!(
+                "Price impact {:.2}% exceeds the {:.2}% limit",
+                leg.price_impact_pct, max_impact_pct
+            ));
+        } else {
+            leg.stage = STAGE_IMPACT_LIMITS
+                .iter()
+                .position(|limit| leg.price_impact_pct <= *limit)
+                .map(|index| index as u8 + 1)
+                .unwrap_or(STAGE_IMPACT_LIMITS.len() as u8 + 1);
+        }
+    }
+    legs.sort_by(|a, b| {
+        let stage = |leg: &FlattenLeg| if leg.executable() { leg.stage } else { u8::MAX };
+        stage(a)
+            .cmp(&stage(b))
+            .then(a.price_impact_pct.total_cmp(&b.price_impact_pct))
+            .then(b.value_usd.total_cmp(&a.value_usd))
+    });
+}
+
+async fn native_sol_lamports(pool: &SharedRpcPool, wallet_address: &str) -> Result<u64, String> {
+    let params = json!([wallet_address, { "commitment": "confirmed" }]);
+    let response: Value = RpcPool::call(pool, RoutingHint::Read, move |client| {
+        client.send(RpcRequest::GetBalance, params.clone())
+    })
+    .await
+    .map_err(|e| format!("Failed to fetch SOL balance: {e}"))?;
+    Ok(response["value"].as_u64().unwrap_or(0))
+}
+
+async fn build_plan(pool: &SharedRpcPool, request: &FlattenRequest) -> Result<FlattenPlan, String> {
+    let target = request.target.mint();
+    let sol_usd = match request.target {
+        FlattenTarget::Sol => sol_price_usd().await?,
+        FlattenTarget::Usdc => 0.0,
+    };
+
+    let mut holdings: Vec<(String, u64, u8, f64)> = fetch_holdings(pool, &request.wallet_address)
+        .await?
+        .into_iter()
+        .filter(|h| h.mint != target)
+        .map(|h| (h.mint, h.amount, h.decimals, h.ui_amount))
+        .collect();
+    if request.target == FlattenTarget::Usdc {
+        let lamports = native_sol_lamports(pool, &request.wallet_address)
+            .await?
+            .saturating_sub(SOL_FEE_RESERVE_LAMPORTS);
+        if lamports > 0 {
+            holdings.push((
+                SOL_MINT.to_string(),
+                lamports,
+                9,
+                lamports as f64 / LAMPORTS_PER_SOL,
+            ));
+        }
+    }
+
+    let mut legs = Vec::new();
+    for (mint, amount, decimals, ui_amount) in holdings.into_iter().take(MAX_QUOTED_ACCOUNTS) {
+        let mut leg = FlattenLeg {
+            mint,
+            amount,
+            decimals,
+            ui_amount,
+            value_usd: 0.0,
+            price_impact_pct: 0.0,
+            stage: 0,
+            status: FlattenLegStatus::Planned,
+            skip_reason: None,
+            transaction_base64: None,
+            last_valid_block_height: None,
+            lifecycle_id: None,
+            signature: None,
+            error: None,
+            quote: None,
+        };
+        if request.keep_mints.contains(&leg.mint) {
+            leg.skip("Kept by request".to_string());
+            legs.push(leg);
+            continue;
+        }
+        match fetch_quote(&quote_input(
+            &leg.mint,
+            target,
+            leg.amount,
+            request.max_slippage_bps,
+        ))
+        .await
+        {
+            Ok(result) => {
+                let out: f64 = result.quote.output_amount.parse().unwrap_or(0.0);
+                leg.value_usd = match request.target {
+                    FlattenTarget::Usdc => out / 1_000_000.0,
+                    FlattenTarget::Sol => out / LAMPORTS_PER_SOL * sol_usd,
+                };
+                leg.price_impact_pct = result.quote.price_impact_pct * 100.0;
+                leg.quote = Some(result.quote);
+            }
+            Err(e) => leg.skip(format!("No route: {e}")),
+        }
+        legs.push(leg);
+    }
+    stage_legs(
+        &mut legs,
+        request.max_slippage_bps,
+        request.dust_threshold_usd,
+    );
+
+    for leg in legs.iter_mut().filter(|leg| leg.executable()) {
+        let Some(quote) = leg.quote.clone() else {
+            continue;
+        };
+        match jupiter_swap(SwapCommandInput {
+            quote,
+            user_public_key: request.wallet_address.clone(),
+            fee_account: None,
+            wrap_and_unwrap_sol: Some(true),
+            as_legacy_transaction: None,
+            priority_fee_config: None,
+            simulate: None,
+        })
+        .await
+        {
+            Ok(swap) => {
+                leg.transaction_base64 = Some(swap.transaction.base64);
+                leg.last_valid_block_height = Some(swap.last_valid_block_height);
+            }
+            Err(e) => leg.skip(format!("Failed to build swap: {e}")),
+        }
+    }
+    // Legs whose swap could not be built drop out of the stage order.
+    stage_legs(
+        &mut legs,
+        request.max_slippage_bps,
+        request.dust_threshold_usd,
+    );
+
+    let now = Utc::now();
+    let mut plan = FlattenPlan {
+        id: Uuid::new_v4().to_string(),
+        wallet_address: request.wallet_address.clone(),
+        target: request.target,
+        max_slippage_bps: request.max_slippage_bps,
+        dust_threshold_usd: request.dust_threshold_usd,
+        stage_count: legs.iter().map(|leg| leg.stage).max().unwrap_or(0),
+        legs,
+        total_value_usd: 0.0,
+        status: FlattenPlanStatus::AwaitingSignatures,
+        journal_entry_id: None,
+        created_at: now,
+        updated_at: now,
+    };
+    plan.total_value_usd = plan.executable().map(|leg| leg.value_usd).sum();
+    Ok(plan)
+}
+
+fn journal_entry(plan: &FlattenPlan) -> JournalEntry {
+    let now = Utc::now().timestamp();
+    let executable: Vec<&FlattenLeg> = plan.executable().collect();
+    let confirmed: Vec<&&FlattenLeg> = executable
+        .iter()
+        .filter(|leg| leg.status == FlattenLegStatus::Confirmed)
+        .collect();
+    let proceeds: f64 = confirmed.iter().map(|leg| leg.value_usd).sum();
+
+    let mut notes = format!(
+        "Flatten-all into {:?}: {}/{} legs confirmed for ~${:.2} across {} stage(s); max slippage {} bps, dust threshold ${:.2}.",
+        plan.target,
+        confirmed.len(),
+        executable.len(),
+        proceeds,
+        plan.stage_count,
+        plan.max_slippage_bps,
+        plan.dust_threshold_usd
+    );
+    for leg in &plan.legs {
+        let detail = match (leg.status, &leg.error, &leg.skip_reason) {
+            (FlattenLegStatus::Skipped, _, Some(reason)) => format!("skipped ({reason})"),
+            (_, Some(error), _) => format!("failed ({error})"),
+            (status, None, _) => format!(
+                "{:?} {}",
+                status,
+                leg.signature.as_deref().unwrap_or_default()
+            ),
+        };
+        notes.push_str(&format!(
+            "\n- [stage {}] {} {:.6} (~${:.2}, impact {:.2}%): {}",
+            leg.stage, leg.mint, leg.ui_amount, leg.value_usd, leg.price_impact_pct, detail
+        ));
+    }
+
+    JournalEntry {
+        id: Uuid::new_v4().to_string(),
+        timestamp: now,
+        trade_id: Some(plan.id.clone()),
+        entry_type: EntryType::PostTrade,
+        strategy_tags: vec!["flatten-all".to_string()],
+        emotions: EmotionTracking {
+            primary_emotion: Emotion::Neutral,
+            intensity: 0.0,
+            secondary_emotions: Vec::new(),
+            stress_level: 0.0,
+            clarity_level: 1.0,
+            fomo_level: 0.0,
+            revenge_trading: false,
+            discipline_score: 1.0,
+        },
+        notes,
+        market_conditions: MarketConditions {
+            trend: MarketTrend::Neutral,
+            volatility: Volatility::High,
+            volume: VolumeLevel::Medium,
+            news_sentiment: 0.0,
+            notes: String::new(),
+        },
+        confidence_level: 1.0,
+        position_size: Some(plan.total_value_usd as f32),
+        entry_price: None,
+        exit_price: None,
+        outcome: Some(TradeOutcome {
+            pnl: 0.0,
+            pnl_percent: 0.0,
+            success: !executable.is_empty() && confirmed.len() == executable.len(),
+            followed_plan: true,
+            risk_reward_ratio: 0.0,
+        }),
+        lessons_learned: None,
+        attachments: Vec::new(),
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+fn emit_progress(
+    app: &AppHandle,
+    plan: &FlattenPlan,
+    stage: u8,
+    leg: Option<&FlattenLeg>,
+    message: String,
+) {
+    let settled_legs = plan
+        .executable()
+        .filter(|leg| {
+            matches!(
+                leg.status,
+                FlattenLegStatus::Confirmed | FlattenLegStatus::Failed
+            )
+        })
+        .count();
+    let _ = app.emit(
+        FLATTEN_PROGRESS_EVENT,
+        FlattenProgress {
+            plan_id: plan.id.clone(),
+            stage,
+            stage_count: plan.stage_count,
+            mint: leg.map(|leg| leg.mint.clone()),
+            leg_status: leg.map(|leg| leg.status),
+            settled_legs,
+            total_legs: plan.executable().count(),
+            message,
+        },
+    );
+}
+
+/// Submits one stage, then waits for it to settle before the next, so the
+/// deepest markets are exited before thinner ones move.
+async fn run_stages(
+    app: AppHandle,
+    plan_id: String,
+    coordinator: SharedFlattenCoordinator,
+    lifecycle: SharedTransactionLifecycle,
+    journal: SharedJournalDatabase,
+) {
+    let Some(plan) = coordinator.read().await.get(&plan_id) else {
+        return;
+    };
+
+    for stage in 1..=plan.stage_count {
+        let legs: Vec<FlattenLeg> = plan
+            .executable()
+            .filter(|leg| leg.stage == stage)
+            .cloned()
+            .collect();
+        for staged in legs {
+            let Some(transaction) = staged.transaction_base64.clone() else {
+                continue;
+            };
+            let submitted = lifecycle
+                .submit(
+                    app.clone(),
+                    SubmitTransactionRequest {
+                        transaction_base64: transaction,
+                        last_valid_block_height: staged.last_valid_block_height,
+                        max_attempts: None,
+                        fee_schedule: None,
+                        label: Some(format!("Flatten-all {}", staged.mint)),
+                    },
+                )
+                .await;
+            let updated = coordinator.write().await.update(&plan_id, |plan| {
+                if let Some(leg) = plan.legs.iter_mut().find(|l| l.mint == staged.mint) {
+                    match submitted {
+                        Ok(tracked) => {
+                            leg.lifecycle_id = Some(tracked.id);
+                            leg.signature = Some(tracked.signature);
+                            leg.status = FlattenLegStatus::Submitted;
+                        }
+                        Err(error) => {
+                            leg.status = FlattenLegStatus::Failed;
+                            leg.error = Some(error);
+                        }
+                    }
+                }
+            });
+            if let Some(plan) = &updated {
+                let leg = plan.legs.iter().find(|l| l.mint == staged.mint);
+                emit_progress(
+                    &app,
+                    plan,
+                    stage,
+                    leg,
+                    format!("Stage {stage}: submitted {}", staged.mint),
+                );
+            }
+        }
+
+        let deadline = Utc::now() + Duration::seconds(STAGE_TIMEOUT_SECONDS);
+        loop {
+            let Some(plan) = coordinator.read().await.get(&plan_id) else {
+                return;
+            };
+            let mut statuses = Vec::new();
+            for leg in plan
+                .executable()
+                .filter(|leg| leg.stage == stage && leg.status == FlattenLegStatus::Submitted)
+            {
+                let Some(id) = &leg.lifecycle_id else {
+                    continue;
+                };
+                if let Some(tracked) = lifecycle.get(id).await {
+                    statuses.push((leg.mint.clone(), tracked));
+                }
+            }
+
+            let timed_out = Utc::now() > deadline;
+            let mut pending = false;
+            let mut changed = Vec::new();
+            let updated = coordinator.write().await.update(&plan_id, |plan| {
+                for (mint, tracked) in statuses {
+                    let Some(leg) = plan.legs.iter_mut().find(|l| l.mint == mint) else {
+                        continue;
+                    };
+                    leg.signature = Some(tracked.signature);
+                    let status = match tracked.status {
+                        TransactionLifecycleStatus::Confirmed
+                        | TransactionLifecycleStatus::Finalized => FlattenLegStatus::Confirmed,
+                        TransactionLifecycleStatus::Expired
+                        | TransactionLifecycleStatus::Failed => FlattenLegStatus::Failed,
+                        _ if timed_out => FlattenLegStatus::Failed,
+                        _ => FlattenLegStatus::Submitted,
+                    };
+                    if status == FlattenLegStatus::Submitted {
+                        pending = true;
+                        continue;
+                    }
+                    leg.error = tracked.error.or_else(|| {
+                        (status == FlattenLegStatus::Failed && timed_out)
+                            .then(|| "Not confirmed before the stage timed out".to_string())
+                    });
+                    leg.status = status;
+                    changed.push(mint);
+                }
+            });
+            if let Some(plan) = &updated {
+                for mint in &changed {
+                    let leg = plan.legs.iter().find(|l| &l.mint == mint);
+                    emit_progress(
+                        &app,
+                        plan,
+                        stage,
+                        leg,
+                        format!("Stage {stage}: {mint} settled"),
+                    );
+                }
+            }
+            if !pending {
+                break;
+            }
+            tokio::time::sleep(SETTLE_POLL_INTERVAL).await;
+        }
+        if let Some(plan) = coordinator.read().await.get(&plan_id) {
+            emit_progress(
+                &app,
+                &plan,
+                stage,
+                None,
+                format!("Stage {stage} of {} complete", plan.stage_count),
+            );
+        }
+    }
+
+    let Some(plan) = coordinator.read().await.get(&plan_id) else {
+        return;
+    };
+    let entry = journal_entry(&plan);
+    let entry_id = match journal.read().await.create_entry(&entry).await {
+        Ok(()) => Some(entry.id.clone()),
+        Err(e) => {
+            eprintln!("Failed to journal flatten-all {plan_id}: {e}");
+            None
+        }
+    };
+    let confirmed = plan
+        .executable()
+        .filter(|leg| leg.status == FlattenLegStatus::Confirmed)
+        .count();
+    let total = plan.executable().count();
+    let updated = coordinator.write().await.update(&plan_id, |plan| {
+        plan.journal_entry_id = entry_id;
+        plan.status = if confirmed == total {
+            FlattenPlanStatus::Completed
+        } else if confirmed > 0 {
+            FlattenPlanStatus::PartiallyCompleted
+        } else {
+            FlattenPlanStatus::Failed
+        };
+    });
+    if let Some(plan) = &updated {
+        emit_progress(
+            &app,
+            plan,
+            plan.stage_count,
+            None,
+            format!("Flatten-all finished: {confirmed}/{total} legs confirmed"),
+        );
+    }
+}
+
+/// Panic button: after the typed confirmation and a 2FA code, builds a
+/// staged plan that sells every holding into the target with unsigned
+/// swaps for the wallet to approve in one batch.
+#[tauri::command]
+pub async fn flatten_all_positions(
+    request: FlattenRequest,
+    rpc_pool: State<'_, SharedRpcPool>,
+    coordinator: State<'_, SharedFlattenCoordinator>,
+    two_factor: State<'_, TwoFactorManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<FlattenPlan, String> {
+    crate::environment::require_mainnet("Flatten-all").map_err(|e| e.to_string())?;
+    authorize(&request, &two_factor, &keystore)?;
+
+    let plan = build_plan(&rpc_pool, &request).await?;
+    if plan.stage_count == 0 {
+        return Err("Nothing to flatten: every holding is dust, kept or unroutable".to_string());
+    }
+    coordinator.write().await.insert(plan.clone());
+    Ok(plan)
+}
+
+#[tauri::command]
+pub async fn flatten_all_get_plan(
+    plan_id: String,
+    coordinator: State<'_, SharedFlattenCoordinator>,
+) -> Result<Option<FlattenPlan>, String> {
+    Ok(coordinator.read().await.get(&plan_id))
+}
+
+/// Runs the signed plan stage by stage, emitting `flatten_all_progress`
+/// as legs settle. Signed transactions follow the plan's leg order.
+#[tauri::command]
+pub async fn flatten_all_execute(
+    app: AppHandle,
+    plan_id: String,
+    signed_transactions: Vec<String>,
+    coordinator: State<'_, SharedFlattenCoordinator>,
+    lifecycle: State<'_, SharedTransactionLifecycle>,
+    journal: State<'_, SharedJournalDatabase>,
+) -> Result<FlattenPlan, String> {
+    let now = Utc::now();
+    let plan = coordinator
+        .write()
+        .await
+        .begin_execution(&plan_id, signed_transactions, now)?;
+
+    tauri::async_runtime::spawn(run_stages(
+        app,
+        plan_id,
+        coordinator.inner().clone(),
+        lifecycle.inner().clone(),
+        journal.inner().clone(),
+    ));
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leg(mint: &str, value_usd: f64, price_impact_pct: f64) -> FlattenLeg {
+        FlattenLeg {
+            mint: mint.to_string(),
+            amount: 1,
+            decimals: 6,
+            ui_amount: 1.0,
+            value_usd,
+            price_impact_pct,
+            stage: 0,
+            status: FlattenLegStatus::Planned,
+            skip_reason: None,
+            transaction_base64: None,
+            last_valid_block_height: None,
+            lifecycle_id: None,
+            signature: None,
+            error: None,
+            quote: None,
+        }
+    }
+
+    #[test]
+    fn stages_most_liquid_first_and_skips_dust_and_thin_markets() {
+        let mut legs = vec![
+            leg("thin", 500.0, 1.2),
+            leg("dust", 0.4, 0.1),
+            leg("deep", 2_000.0, 0.05),
+            leg("illiquid", 300.0, 4.0),
+            leg("mid", 800.0, 0.6),
+        ];
+        stage_legs(&mut legs, 150, 1.0);
+
+        let order: Vec<(&str, u8)> = legs.iter().map(|l| (l.mint.as_str(), l.stage)).collect();
+        assert_eq!(order[..3], [("deep", 1), ("mid", 2), ("thin", 3)]);
+        let skipped: Vec<&FlattenLeg> = legs.iter().filter(|l| !l.executable()).collect();
+        assert_eq!(skipped.len(), 2);
+        assert!(skipped
+            .iter()
+            .all(|l| l.status == FlattenLegStatus::Skipped && l.skip_reason.is_some()));
+    }
+
+    #[test]
+    fn a_plan_can_only_start_executing_once() {
+        let mut legs = vec![leg("deep", 2_000.0, 0.05), leg("mid", 800.0, 0.6)];
+        stage_legs(&mut legs, 150, 1.0);
+        let now = Utc::now();
+        let mut coordinator = FlattenCoordinator::default();
+        coordinator.insert(FlattenPlan {
+            id: "plan".to_string(),
+            wallet_address: "wallet".to_string(),
+            target: FlattenTarget::Usdc,
+            max_slippage_bps: 150,
+            dust_threshold_usd: 1.0,
+            stage_count: 2,
+            legs,
+            total_value_usd: 2_800.0,
+            status: FlattenPlanStatus::AwaitingSignatures,
+            journal_entry_id: None,
+            created_at: now,
+            updated_at: now,
+        });
+        let signed = || vec!["tx-1".to_string(), "tx-2".to_string()];
+
+        assert!(coordinator
+            .begin_execution("plan", vec!["tx-1".to_string()], now)
+            .is_err());
+        let plan = coordinator.begin_execution("plan", signed(), now).unwrap();
+        assert_eq!(plan.status, FlattenPlanStatus::Executing);
+        assert!(coordinator.begin_execution("plan", signed(), now).is_err());
+    }
+
+    #[test]
+    fn confirmation_must_be_typed_exactly() {
+        assert!(check_confirmation("FLATTEN ALL").is_ok());
+        assert!(check_confirmation("  FLATTEN ALL ").is_ok());
+        assert!(check_confirmation("flatten all").is_err());
+        assert!(check_confirmation("yes").is_err());
+    }
+}
@@ -1,13 +1,27 @@
 pub mod ai_advisor;
 pub mod analytics;
+pub mod attribution;
+pub mod compressed_nfts;
+pub mod dust;
+pub mod flatten;
+pub mod monte_carlo;
+pub mod nfts;
 pub mod rebalancer;
+pub mod tax_import;
 pub mod tax_lots;
 pub mod types;
 pub mod watchlists;
 
 pub use ai_advisor::*;
 pub use analytics::*;
+pub use attribution::*;
+pub use compressed_nfts::*;
+pub use dust::*;
+pub use flatten::*;
+pub use monte_carlo::*;
+pub use nfts::*;
 pub use rebalancer::*;
+pub use tax_import::*;
 pub use tax_lots::*;
 pub use types::*;
 pub use watchlists::*;
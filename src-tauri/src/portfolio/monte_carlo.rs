@@ -0,0 +1,612 @@
+use chrono::{DateTime, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use tauri::State;
+
+use super::rebalancer::SharedPortfolioData;
+use super::types::Position;
+use crate::data::historical::{HistoricalDataPoint, SharedHistoricalReplayManager};
+
+const MAX_PATHS: usize = 20_000;
+const MAX_HORIZON_DAYS: u32 = 365;
+const MAX_STEPS: usize = 20_000;
+/// Percentile bands are reported at about this many points along the
+/// horizon regardless of how many steps are simulated.
+const BAND_POINTS: usize = 60;
+/// Fewer aligned returns than this make volatility estimates meaningless.
+const MIN_RETURN_SAMPLES: usize = 20;
+const DRAWDOWN_BUCKET_PCT: f64 = 5.0;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReturnModel {
+    /// Correlated normal log returns using the historical mean and
+    /// covariance.
+    #[default]
+    Gaussian,
+    /// Resamples whole historical return rows, keeping fat tails and the
+    /// cross-asset dependence of the sample.
+    Bootstrap,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonteCarloRequest {
+    #[serde(default = "default_paths")]
+    pub paths: usize,
+    #[serde(default = "default_horizon_days")]
+    pub horizon_days: u32,
+    #[serde(default)]
+    pub model: ReturnModel,
+    /// Candle interval of the history used for estimation, e.g. "1h" or "1d".
+    #[serde(default = "default_interval")]
+    pub interval: String,
+    #[serde(default = "default_lookback_days")]
+    pub lookback_days: u32,
+    /// Fixes the random stream so runs can be reproduced.
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+fn default_paths() -> usize {
+    2_000
+}
+
+fn default_horizon_days() -> u32 {
+    30
+}
+
+fn default_interval() -> String {
+    "1d".to_string()
+}
+
+fn default_lookback_days() -> u32 {
+    180
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PercentileBand {
+    pub day: f64,
+    pub p5: f64,
+    pub p25: f64,
+    pub p50: f64,
+    pub p75: f64,
+    pub p95: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DrawdownBucket {
+    pub from_pct: f64,
+    pub to_pct: f64,
+    pub paths: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DrawdownDistribution {
+    pub mean_pct: f64,
+    pub p50_pct: f64,
+    pub p95_pct: f64,
+    pub p99_pct: f64,
+    pub histogram: Vec<DrawdownBucket>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonteCarloResult {
+    pub initial_value: f64,
+    pub paths: usize,
+    pub horizon_days: u32,
+    pub steps: usize,
+    pub model: ReturnModel,
+    pub bands: Vec<PercentileBand>,
+    pub final_value: PercentileBand,
+    pub expected_return_pct: f64,
+    pub probability_of_loss: f64,
+    /// Loss at the 5th percentile of final value, as a positive amount.
+    pub value_at_risk_95: f64,
+    /// Average loss across the worst 5% of paths.
+    pub expected_shortfall_95: f64,
+    pub max_drawdown: DrawdownDistribution,
+    pub modeled_symbols: Vec<String>,
+    /// Holdings without enough history; they are held at today's value.
+    pub unmodeled_symbols: Vec<String>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Log returns of each modeled asset on the timestamps they all share.
+#[derive(Debug, Clone)]
+pub struct ReturnSample {
+    pub symbols: Vec<String>,
+    /// One row per interval, one column per symbol.
+    pub rows: Vec<Vec<f64>>,
+}
+
+impl ReturnSample {
+    pub fn from_history(history: &HashMap<String, Vec<HistoricalDataPoint>>) -> Self {
+        let mut symbols: Vec<String> = history.keys().cloned().collect();
+        symbols.sort();
+
+        let closes: Vec<HashMap<i64, f64>> = symbols
+            .iter()
+            .map(|symbol| {
+                history[symbol]
+                    .iter()
+                    .filter(|point| point.close > 0.0)
+                    .map(|point| (point.timestamp, point.close))
+                    .collect()
+            })
+            .collect();
+        let shared: BTreeSet<i64> = closes
+            .iter()
+            .map(|series| series.keys().copied().collect::<BTreeSet<i64>>())
+            .reduce(|a, b| a.intersection(&b).copied().collect())
+            .unwrap_or_default();
+        let timestamps: Vec<i64> = shared.into_iter().collect();
+
+        let rows = timestamps
+            .windows(2)
+            .map(|pair| {
+                closes
+                    .iter()
+                    .map(|series| (series[&pair[1]] / series[&pair[0]]).ln())
+                    .collect()
+            })
+            .collect();
+
+        Self { symbols, rows }
+    }
+
+    fn mean(&self) -> Vec<f64> {
+        let n = self.rows.len() as f64;
+        (0..self.symbols.len())
+            .map(|j| self.rows.iter().map(|row| row[j]).sum::<f64>() / n)
+            .collect()
+    }
+
+    fn covariance(&self, mean: &[f64]) -> Vec<Vec<f64>> {
+        let k = self.symbols.len();
+        let n = (self.rows.len().max(2) - 1) as f64;
+        let mut cov = vec![vec![0.0; k]; k];
+        for row in &self.rows {
+            let centered: Vec<f64> = row.iter().zip(mean).map(|(r, m)| r - m).collect();
+            for (i, ci) in centered.iter().enumerate() {
+                for (j, cj) in centered.iter().enumerate() {
+                    cov[i][j] += ci * cj / n;
+                }
+            }
+        }
+        cov
+    }
+}
+
+/// Lower-triangular Cholesky factor. Sample covariances of short or
+/// collinear histories are often only semi-definite, so the diagonal is
+/// nudged until the factorization succeeds.
+fn cholesky(matrix: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let k = matrix.len();
+    let scale = (0..k).map(|i| matrix[i][i]).fold(0.0, f64::max).max(1e-12);
+    let mut jitter = 0.0;
+    loop {
+        let mut lower = vec![vec![0.0; k]; k];
+        let mut ok = true;
+        'outer: for i in 0..k {
+            for j in 0..=i {
+                let sum: f64 = (0..j).map(|p| lower[i][p] * lower[j][p]).sum();
+                if i == j {
+                    let diagonal = matrix[i][i] + jitter - sum;
+                    if diagonal <= 0.0 {
+                        ok = false;
+                        break 'outer;
+                    }
+                    lower[i][j] = diagonal.sqrt();
+                } else {
+                    lower[i][j] = (matrix[i][j] - sum) / lower[j][j];
+                }
+            }
+        }
+        if ok {
+            return lower;
+        }
+        jitter = if jitter == 0.0 {
+            scale * 1e-10
+        } else {
+            jitter * 10.0
+        };
+    }
+}
+
+fn standard_normal(rng: &mut impl Rng) -> f64 {
+    // Box-Muller; `1 - u` keeps the logarithm finite.
+    let u1: f64 = 1.0 - rng.random::<f64>();
+    let u2: f64 = rng.random::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Linear interpolation between closest ranks of an ascending slice.
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = pct / 100.0 * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+fn sort_floats(values: &mut [f64]) {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+}
+
+fn band(day: f64, values: &mut [f64]) -> PercentileBand {
+    sort_floats(values);
+    PercentileBand {
+        day,
+        p5: percentile(values, 5.0),
+        p25: percentile(values, 25.0),
+        p50: percentile(values, 50.0),
+        p75: percentile(values, 75.0),
+        p95: percentile(values, 95.0),
+    }
+}
+
+fn drawdown_distribution(mut drawdowns: Vec<f64>) -> DrawdownDistribution {
+    sort_floats(&mut drawdowns);
+    let worst = drawdowns.last().copied().unwrap_or(0.0);
+    let buckets = ((worst / DRAWDOWN_BUCKET_PCT).floor() as usize + 1).min(20);
+    let mut histogram: Vec<DrawdownBucket> = (0..buckets)
+        .map(|i| DrawdownBucket {
+            from_pct: i as f64 * DRAWDOWN_BUCKET_PCT,
+            to_pct: (i + 1) as f64 * DRAWDOWN_BUCKET_PCT,
+            paths: 0,
+        })
+        .collect();
+    for drawdown in &drawdowns {
+        let index = ((drawdown / DRAWDOWN_BUCKET_PCT) as usize).min(buckets - 1);
+        histogram[index].paths += 1;
+    }
+    if let Some(last) = histogram.last_mut() {
+        last.to_pct = last.to_pct.max(worst);
+    }
+
+    DrawdownDistribution {
+        mean_pct: drawdowns.iter().sum::<f64>() / drawdowns.len().max(1) as f64,
+        p50_pct: percentile(&drawdowns, 50.0),
+        p95_pct: percentile(&drawdowns, 95.0),
+        p99_pct: percentile(&drawdowns, 99.0),
+        histogram,
+    }
+}
+
+/// Simulates buy-and-hold paths of the modeled holdings. `values` are the
+/// current USD values in `sample.symbols` order and `fixed_value` is the
+/// part of the portfolio held flat.
+pub fn simulate_paths(
+    sample: &ReturnSample,
+    values: &[f64],
+    fixed_value: f64,
+    request: &MonteCarloRequest,
+    steps: usize,
+) -> MonteCarloResult {
+    let mut rng = match request.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    };
+    let k = sample.symbols.len();
+    let mean = if k > 0 { sample.mean() } else { Vec::new() };
+    let lower = if k > 0 {
+        cholesky(&sample.covariance(&mean))
+    } else {
+        Vec::new()
+    };
+
+    let band_every = steps.div_ceil(BAND_POINTS).max(1);
+    let checkpoints: Vec<usize> = (band_every..=steps)
+        .step_by(band_every)
+        .chain((steps % band_every != 0).then_some(steps))
+        .collect();
+    let initial_value = values.iter().sum::<f64>() + fixed_value;
+
+    let mut at_checkpoint = vec![Vec::with_capacity(request.paths); checkpoints.len()];
+    let mut drawdowns = Vec::with_capacity(request.paths);
+    let mut shocks = vec![0.0; k];
+    let mut step_return = vec![0.0; k];
+
+    for _ in 0..request.paths {
+        let mut holdings = values.to_vec();
+        let mut peak = initial_value;
+        let mut max_drawdown: f64 = 0.0;
+        let mut next_checkpoint = 0;
+
+        for step in 1..=steps {
+            match request.model {
+                ReturnModel::Gaussian => {
+                    for shock in shocks.iter_mut() {
+                        *shock = standard_normal(&mut rng);
+                    }
+                    for i in 0..k {
+                        step_return[i] =
+                            mean[i] + (0..=i).map(|j| lower[i][j] * shocks[j]).sum::<f64>();
+                    }
+                }
+                ReturnModel::Bootstrap => {
+                    let row = &sample.rows[rng.random_range(0..sample.rows.len())];
+                    step_return.copy_from_slice(row);
+                }
+            }
+            for (holding, r) in holdings.iter_mut().zip(&step_return) {
+                *holding *= r.exp();
+            }
+
+            let value = holdings.iter().sum::<f64>() + fixed_value;
+            peak = peak.max(value);
+            if peak > 0.0 {
+                max_drawdown = max_drawdown.max((peak - value) / peak * 100.0);
+            }
+            if checkpoints.get(next_checkpoint) == Some(&step) {
+                at_checkpoint[next_checkpoint].push(value);
+                next_checkpoint += 1;
+            }
+        }
+        drawdowns.push(max_drawdown);
+    }
+
+    let days_per_step = request.horizon_days as f64 / steps as f64;
+    let mut bands: Vec<PercentileBand> = checkpoints
+        .iter()
+        .zip(at_checkpoint.iter_mut())
+        .map(|(step, values)| band(*step as f64 * days_per_step, values))
+        .collect();
+    bands.insert(
+        0,
+        PercentileBand {
+            day: 0.0,
+            p5: initial_value,
+            p25: initial_value,
+            p50: initial_value,
+            p75: initial_value,
+            p95: initial_value,
+        },
+    );
+
+    // `band` left the final checkpoint sorted ascending.
+    let finals = at_checkpoint.last().cloned().unwrap_or_default();
+    let tail = (finals.len() / 20).max(1).min(finals.len());
+    let tail_mean = finals.iter().take(tail).sum::<f64>() / tail.max(1) as f64;
+    let final_mean = finals.iter().sum::<f64>() / finals.len().max(1) as f64;
+    let final_value = bands.last().cloned().expect("at least the starting band");
+
+    MonteCarloResult {
+        initial_value,
+        paths: request.paths,
+        horizon_days: request.horizon_days,
+        steps,
+        model: request.model,
+        expected_return_pct: if initial_value > 0.0 {
+            (final_mean / initial_value - 1.0) * 100.0
+        } else {
+            0.0
+        },
+        probability_of_loss: finals.iter().filter(|v| **v < initial_value).count() as f64
+            / finals.len().max(1) as f64,
+        value_at_risk_95: (initial_value - final_value.p5).max(0.0),
+        expected_shortfall_95: (initial_value - tail_mean).max(0.0),
+        final_value,
+        bands,
+        max_drawdown: drawdown_distribution(drawdowns),
+        modeled_symbols: sample.symbols.clone(),
+        unmodeled_symbols: Vec::new(),
+        generated_at: Utc::now(),
+    }
+}
+
+fn interval_seconds(interval: &str) -> Option<i64> {
+    match interval {
+        "1m" => Some(60),
+        "5m" => Some(300),
+        "15m" => Some(900),
+        "1h" => Some(3600),
+        "4h" => Some(14_400),
+        "1d" => Some(86_400),
+        _ => None,
+    }
+}
+
+#[tauri::command]
+pub async fn simulate_portfolio_paths(
+    request: MonteCarloRequest,
+    portfolio: State<'_, SharedPortfolioData>,
+    historical: State<'_, SharedHistoricalReplayManager>,
+) -> Result<MonteCarloResult, String> {
+    if request.paths == 0 || request.paths > MAX_PATHS {
+        return Err(format!("paths must be between 1 and {}", MAX_PATHS));
+    }
+    if request.horizon_days == 0 || request.horizon_days > MAX_HORIZON_DAYS {
+        return Err(format!(
+            "horizon must be between 1 and {} days",
+            MAX_HORIZON_DAYS
+        ));
+    }
+    let step_seconds = interval_seconds(&request.interval)
+        .ok_or_else(|| format!("Unsupported interval: {}", request.interval))?;
+    let steps = (request.horizon_days as i64 * 86_400 / step_seconds).max(1) as usize;
+    if steps > MAX_STEPS {
+        return Err(format!(
+            "{} days of {} steps is too long; use a coarser interval",
+            request.horizon_days, request.interval
+        ));
+    }
+
+    let positions: Vec<Position> = portfolio
+        .lock()
+        .map_err(|_| "Portfolio data locked".to_string())?
+        .positions()
+        .into_iter()
+        .filter(|p| p.total_value > 0.0)
+        .collect();
+    if positions.is_empty() {
+        return Err("No holdings to simulate".to_string());
+    }
+
+    let end = Utc::now().timestamp();
+    let start = end - request.lookback_days as i64 * 86_400;
+    let mut history = HashMap::new();
+    {
+        let manager = historical.read().await;
+        for position in &positions {
+            let data = manager
+                .price_history(&position.symbol, &request.interval, start, end)
+                .await?;
+            if data.len() > MIN_RETURN_SAMPLES {
+                history.insert(position.symbol.clone(), data);
+            }
+        }
+    }
+
+    // Drop series that would shrink the shared window below the minimum,
+    // shortest first, so one thin history cannot disable the whole run.
+    let mut sample = ReturnSample::from_history(&history);
+    while sample.rows.len() < MIN_RETURN_SAMPLES && !history.is_empty() {
+        let thinnest = history
+            .iter()
+            .min_by_key(|(_, data)| data.len())
+            .map(|(symbol, _)| symbol.clone())
+            .expect("history is not empty");
+        history.remove(&thinnest);
+        sample = ReturnSample::from_history(&history);
+    }
+
+    let values: Vec<f64> = sample
+        .symbols
+        .iter()
+        .map(|symbol| {
+            positions
+                .iter()
+                .filter(|p| &p.symbol == symbol)
+                .map(|p| p.total_value)
+                .sum()
+        })
+        .collect();
+    let unmodeled: Vec<&Position> = positions
+        .iter()
+        .filter(|p| !sample.symbols.contains(&p.symbol))
+        .collect();
+    if sample.symbols.is_empty() {
+        return Err(format!(
+            "Not enough {} price history for any holding; fetch historical data first",
+            request.interval
+        ));
+    }
+    let fixed_value = unmodeled.iter().map(|p| p.total_value).sum();
+
+    let mut result = tauri::async_runtime::spawn_blocking(move || {
+        simulate_paths(&sample, &values, fixed_value, &request, steps)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    result.unmodeled_symbols = unmodeled.iter().map(|p| p.symbol.clone()).collect();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(model: ReturnModel) -> MonteCarloRequest {
+        MonteCarloRequest {
+            paths: 4_000,
+            horizon_days: 30,
+            model,
+            interval: "1d".into(),
+            lookback_days: 180,
+            seed: Some(7),
+        }
+    }
+
+    fn sample(rows: Vec<Vec<f64>>) -> ReturnSample {
+        ReturnSample {
+            symbols: (0..rows[0].len()).map(|i| format!("T{i}")).collect(),
+            rows,
+        }
+    }
+
+    #[test]
+    fn aligns_returns_on_shared_timestamps() {
+        let point = |timestamp, close| HistoricalDataPoint {
+            timestamp,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 0.0,
+        };
+        let mut history = HashMap::new();
+        history.insert(
+            "A".to_string(),
+            vec![point(1, 100.0), point(2, 110.0), point(3, 121.0)],
+        );
+        history.insert("B".to_string(), vec![point(1, 10.0), point(3, 20.0)]);
+
+        let sample = ReturnSample::from_history(&history);
+        assert_eq!(sample.symbols, vec!["A", "B"]);
+        assert_eq!(sample.rows.len(), 1);
+        assert!((sample.rows[0][0] - 1.21f64.ln()).abs() < 1e-12);
+        assert!((sample.rows[0][1] - 2f64.ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn zero_volatility_paths_are_deterministic() {
+        let rows = vec![vec![0.01]; 30];
+        let result = simulate_paths(
+            &sample(rows),
+            &[1_000.0],
+            0.0,
+            &request(ReturnModel::Gaussian),
+            30,
+        );
+
+        let expected = 1_000.0 * (0.3f64).exp();
+        assert!((result.final_value.p5 - expected).abs() < 1e-6);
+        assert!((result.final_value.p95 - expected).abs() < 1e-6);
+        assert_eq!(result.max_drawdown.p99_pct, 0.0);
+        assert_eq!(result.probability_of_loss, 0.0);
+    }
+
+    #[test]
+    fn bands_widen_and_cash_dampens_risk() {
+        let rows: Vec<Vec<f64>> = (0..60)
+            .map(|i| vec![if i % 2 == 0 { 0.05 } else { -0.05 }])
+            .collect();
+        let all_in = simulate_paths(
+            &sample(rows.clone()),
+            &[1_000.0],
+            0.0,
+            &request(ReturnModel::Bootstrap),
+            30,
+        );
+        let half_cash = simulate_paths(
+            &sample(rows),
+            &[500.0],
+            500.0,
+            &request(ReturnModel::Bootstrap),
+            30,
+        );
+
+        let first = &all_in.bands[1];
+        let last = all_in.bands.last().unwrap();
+        assert!(last.p95 - last.p5 > first.p95 - first.p5);
+        assert!(half_cash.max_drawdown.p95_pct < all_in.max_drawdown.p95_pct);
+        assert!(half_cash.value_at_risk_95 < all_in.value_at_risk_95);
+    }
+
+    #[test]
+    fn cholesky_handles_perfectly_correlated_assets() {
+        let lower = cholesky(&[vec![1.0, 1.0], vec![1.0, 1.0]]);
+        assert!(lower.iter().flatten().all(|v| v.is_finite()));
+        assert!((lower[1][0] - 1.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,607 @@
+//! NFT holdings across the user's wallets.
+//!
+//! Holdings are indexed with the Helius DAS `getAssetsByOwner` method, which
+//! returns regular and compressed NFTs alike along with their collection
+//! metadata. Collections are priced at their Magic Eden floor, so an NFT is
+//! valued at what it could be sold for right now rather than what was paid.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::RwLock;
+
+use crate::api_config::stored_helius_key;
+use crate::portfolio::dust::sol_price_usd;
+use crate::profiles::ProfilePaths;
+use crate::security::keystore::Keystore;
+use crate::wallet::flows::own_wallet_addresses;
+
+const NFT_FILE: &str = "nft_portfolio.json";
+const DAS_PAGE_LIMIT: usize = 1000;
+const MAX_DAS_PAGES: usize = 10;
+const MAGIC_EDEN_API: &str = "https://api-mainnet.magiceden.dev/v2";
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+/// Holdings older than this are re-indexed when listed.
+const STALE_AFTER_MINUTES: i64 = 15;
+
+/// DAS interfaces that are fungible tokens rather than NFTs.
+const FUNGIBLE_INTERFACES: &[&str] = &["FungibleToken", "FungibleAsset"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NftHolding {
+    pub asset_id: String,
+    pub owner: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub image: Option<String>,
+    /// Verified collection address, when the asset belongs to one.
+    pub collection: Option<String>,
+    pub compressed: bool,
+    #[serde(default)]
+    pub floor_price_sol: Option<f64>,
+    #[serde(default)]
+    pub value_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NftCollectionStats {
+    pub address: String,
+    pub name: Option<String>,
+    pub image: Option<String>,
+    /// Magic Eden's collection symbol, used for marketplace lookups.
+    pub marketplace_symbol: Option<String>,
+    pub floor_price_sol: Option<f64>,
+    pub listed_count: Option<u64>,
+    pub volume_all_sol: Option<f64>,
+    /// How many of the collection's NFTs the user holds.
+    pub held_count: usize,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NftSettings {
+    /// Adds the floor value of held NFTs to portfolio totals.
+    pub include_in_totals: bool,
+}
+
+impl Default for NftSettings {
+    fn default() -> Self {
+        Self {
+            include_in_totals: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NftHoldingsSummary {
+    pub holdings: Vec<NftHolding>,
+    pub total_value_sol: f64,
+    pub total_value_usd: f64,
+    /// Held NFTs with no known floor, which count as zero.
+    pub unpriced_count: usize,
+    pub include_in_totals: bool,
+    pub indexed_at: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredNftPortfolio {
+    settings: NftSettings,
+    holdings: Vec<NftHolding>,
+    collections: HashMap<String, NftCollectionStats>,
+    sol_price_usd: Option<f64>,
+    indexed_at: Option<DateTime<Utc>>,
+}
+
+pub struct NftPortfolio {
+    state: StoredNftPortfolio,
+    path: Option<PathBuf>,
+}
+
+pub type SharedNftPortfolio = Arc<RwLock<NftPortfolio>>;
+
+impl NftPortfolio {
+    pub fn new(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(NFT_FILE));
+        let state = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self { state, path }
+    }
+
+    pub fn settings(&self) -> &NftSettings {
+        &self.state.settings
+    }
+
+    pub fn set_include_in_totals(&mut self, include: bool) {
+        self.state.settings.include_in_totals = include;
+        self.save();
+    }
+
+    fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        self.state
+            .indexed_at
+            .map_or(true, |at| now - at > Duration::minutes(STALE_AFTER_MINUTES))
+    }
+
+    /// Floor value of everything held, in USD. Zero when NFTs are excluded
+    /// from totals.
+    pub fn value_for_totals(&self) -> f64 {
+        if !self.state.settings.include_in_totals {
+            return 0.0;
+        }
+        self.summary().total_value_usd
+    }
+
+    pub fn summary(&self) -> NftHoldingsSummary {
+        let sol_usd = self.state.sol_price_usd.unwrap_or(0.0);
+        let holdings: Vec<NftHolding> = self
+            .state
+            .holdings
+            .iter()
+            .cloned()
+            .map(|mut holding| {
+                holding.floor_price_sol = holding
+                    .collection
+                    .as_ref()
+                    .and_then(|address| self.state.collections.get(address))
+                    .and_then(|stats| stats.floor_price_sol);
+                holding.value_usd = holding.floor_price_sol.map(|floor| floor * sol_usd);
+                holding
+            })
+            .collect();
+        let total_value_sol: f64 = holdings.iter().filter_map(|h| h.floor_price_sol).sum();
+        NftHoldingsSummary {
+            unpriced_count: holdings
+                .iter()
+                .filter(|h| h.floor_price_sol.is_none())
+                .count(),
+            total_value_usd: total_value_sol * sol_usd,
+            total_value_sol,
+            include_in_totals: self.state.settings.include_in_totals,
+            indexed_at: self.state.indexed_at.map(|at| at.to_rfc3339()),
+            holdings,
+        }
+    }
+
+    pub fn collection_stats(&self, address: &str) -> Option<NftCollectionStats> {
+        self.state.collections.get(address).cloned()
+    }
+
+    /// Replaces the holdings and carries collection metadata from DAS into
+    /// the collection table, keeping any floor data already fetched.
+    fn replace_holdings(
+        &mut self,
+        holdings: Vec<NftHolding>,
+        das_collections: Vec<NftCollectionStats>,
+    ) {
+        for incoming in das_collections {
+            let entry = self
+                .state
+                .collections
+                .entry(incoming.address.clone())
+                .or_insert_with(|| incoming.clone());
+            entry.name = incoming.name.or(entry.name.take());
+            entry.image = incoming.image.or(entry.image.take());
+        }
+        for stats in self.state.collections.values_mut() {
+            stats.held_count = holdings
+                .iter()
+                .filter(|h| h.collection.as_deref() == Some(stats.address.as_str()))
+                .count();
+        }
+        self.state.holdings = holdings;
+        self.state.indexed_at = Some(Utc::now());
+        self.save();
+    }
+
+    fn held_collections(&self) -> Vec<(String, Option<String>, String)> {
+        self.state
+            .collections
+            .values()
+            .filter(|stats| stats.held_count > 0)
+            .filter_map(|stats| {
+                // Magic Eden resolves a collection symbol from any of its
+                // tokens, so keep one held asset per collection.
+                let sample = self
+                    .state
+                    .holdings
+                    .iter()
+                    .find(|h| h.collection.as_deref() == Some(stats.address.as_str()))?;
+                Some((
+                    stats.address.clone(),
+                    stats.marketplace_symbol.clone(),
+                    sample.asset_id.clone(),
+                ))
+            })
+            .collect()
+    }
+
+    fn update_collection(&mut self, address: &str, update: impl FnOnce(&mut NftCollectionStats)) {
+        if let Some(stats) = self.state.collections.get_mut(address) {
+            update(stats);
+            stats.updated_at = Some(Utc::now().to_rfc3339());
+        }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        match serde_json::to_string_pretty(&self.state) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(path, contents) {
+                    eprintln!("Failed to save NFT portfolio: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize NFT portfolio: {}", e),
+        }
+    }
+}
+
+/// Reads one page of a DAS `getAssetsByOwner` response into NFT holdings
+/// and the collections they belong to. Fungible assets are skipped.
+pub fn parse_das_assets(owner: &str, body: &Value) -> (Vec<NftHolding>, Vec<NftCollectionStats>) {
+    let mut holdings = Vec::new();
+    let mut collections: Vec<NftCollectionStats> = Vec::new();
+    let items = body["result"]["items"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+
+    for item in &items {
+        let interface = item["interface"].as_str().unwrap_or_default();
+        if FUNGIBLE_INTERFACES.contains(&interface) || item["burnt"].as_bool() == Some(true) {
+            continue;
+        }
+        let Some(asset_id) = item["id"].as_str() else {
+            continue;
+        };
+        let metadata = &item["content"]["metadata"];
+        let group = item["grouping"].as_array().and_then(|groups| {
+            groups
+                .iter()
+                .find(|group| group["group_key"].as_str() == Some("collection"))
+        });
+        let collection = group
+            .and_then(|group| group["group_value"].as_str())
+            .map(str::to_string);
+
+        if let (Some(address), Some(group)) = (&collection, group) {
+            if !collections.iter().any(|c| &c.address == address) {
+                let meta = &group["collection_metadata"];
+                collections.push(NftCollectionStats {
+                    address: address.clone(),
+                    name: non_empty(&meta["name"]),
+                    image: non_empty(&meta["image"]),
+                    marketplace_symbol: None,
+                    floor_price_sol: None,
+                    listed_count: None,
+                    volume_all_sol: None,
+                    held_count: 0,
+                    updated_at: None,
+                });
+            }
+        }
+
+        holdings.push(NftHolding {
+            asset_id: asset_id.to_string(),
+            owner: owner.to_string(),
+            name: non_empty(&metadata["name"]),
+            symbol: non_empty(&metadata["symbol"]),
+            image: non_empty(&item["content"]["links"]["image"]),
+            collection,
+            compressed: item["compression"]["compressed"].as_bool().unwrap_or(false),
+            floor_price_sol: None,
+            value_usd: None,
+        });
+    }
+
+    (holdings, collections)
+}
+
+fn non_empty(value: &Value) -> Option<String> {
+    value
+        .as_str()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+async fn fetch_owner_assets(
+    client: &reqwest::Client,
+    api_key: &str,
+    owner: &str,
+) -> Result<(Vec<NftHolding>, Vec<NftCollectionStats>), String> {
+    let url = format!("https://mainnet.helius-rpc.com/?api-key={}", api_key);
+    let mut holdings = Vec::new();
+    let mut collections = Vec::new();
+    for page in 1..=MAX_DAS_PAGES {
+        let body: Value = client
+            .post(&url)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": "nft-index",
+                "method": "getAssetsByOwner",
+                "params": {
+                    "ownerAddress": owner,
+                    "page": page,
+                    "limit": DAS_PAGE_LIMIT,
+                    "displayOptions": { "showCollectionMetadata": true }
+                }
+            }))
+            .send()
+            .await
+            .map_err(|e| format!("DAS request failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("DAS response parse failed: {}", e))?;
+        if let Some(error) = body.get("error") {
+            return Err(format!("DAS error: {}", error));
+        }
+        let page_len = body["result"]["items"].as_array().map_or(0, Vec::len);
+        let (page_holdings, page_collections) = parse_das_assets(owner, &body);
+        holdings.extend(page_holdings);
+        collections.extend(page_collections);
+        if page_len < DAS_PAGE_LIMIT {
+            break;
+        }
+    }
+    Ok((holdings, collections))
+}
+
+async fn fetch_marketplace_symbol(client: &reqwest::Client, mint: &str) -> Option<String> {
+    let body: Value = client
+        .get(format!("{}/tokens/{}", MAGIC_EDEN_API, mint))
+        .send()
+        .await
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+    non_empty(&body["collection"])
+}
+
+async fn fetch_floor_stats(
+    client: &reqwest::Client,
+    symbol: &str,
+) -> Result<(Option<f64>, Option<u64>, Option<f64>), String> {
+    let body: Value = client
+        .get(format!("{}/collections/{}/stats", MAGIC_EDEN_API, symbol))
+        .send()
+        .await
+        .map_err(|e| format!("Floor price request failed: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Floor price request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Floor price parse failed: {}", e))?;
+    Ok((
+        body["floorPrice"]
+            .as_f64()
+            .map(|lamports| lamports / LAMPORTS_PER_SOL),
+        body["listedCount"].as_u64(),
+        body["volumeAll"]
+            .as_f64()
+            .map(|lamports| lamports / LAMPORTS_PER_SOL),
+    ))
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(StdDuration::from_secs(20))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Re-indexes every wallet and refreshes floors for the collections held.
+async fn refresh_nfts(app: &AppHandle, portfolio: &SharedNftPortfolio) -> Result<(), String> {
+    let api_key = stored_helius_key(&app.state::<Keystore>())
+        .ok_or_else(|| "Add a Helius API key to index NFTs".to_string())?;
+    let client = http_client();
+
+    let mut holdings = Vec::new();
+    let mut collections = Vec::new();
+    for wallet in own_wallet_addresses(app) {
+        let (wallet_holdings, wallet_collections) =
+            fetch_owner_assets(&client, &api_key, &wallet).await?;
+        holdings.extend(wallet_holdings);
+        collections.extend(wallet_collections);
+    }
+    portfolio
+        .write()
+        .await
+        .replace_holdings(holdings, collections);
+
+    let held = portfolio.read().await.held_collections();
+    for (address, symbol, sample_mint) in held {
+        let symbol = match symbol {
+            Some(symbol) => Some(symbol),
+            None => fetch_marketplace_symbol(&client, &sample_mint).await,
+        };
+        let Some(symbol) = symbol else {
+            continue;
+        };
+        match fetch_floor_stats(&client, &symbol).await {
+            Ok((floor, listed, volume)) => {
+                portfolio
+                    .write()
+                    .await
+                    .update_collection(&address, |stats| {
+                        stats.marketplace_symbol = Some(symbol.clone());
+                        stats.floor_price_sol = floor;
+                        stats.listed_count = listed;
+                        stats.volume_all_sol = volume;
+                    });
+            }
+            Err(e) => eprintln!("Failed to refresh floor for {}: {}", symbol, e),
+        }
+    }
+
+    match sol_price_usd().await {
+        Ok(price) => {
+            let mut portfolio = portfolio.write().await;
+            portfolio.state.sol_price_usd = Some(price);
+            portfolio.save();
+        }
+        Err(e) => eprintln!("Failed to price SOL for NFT valuation: {}", e),
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn nft_list_holdings(
+    app: AppHandle,
+    refresh: Option<bool>,
+    portfolio: State<'_, SharedNftPortfolio>,
+) -> Result<NftHoldingsSummary, String> {
+    let stale = portfolio.read().await.is_stale(Utc::now());
+    if refresh.unwrap_or(false) || stale {
+        if let Err(e) = refresh_nfts(&app, portfolio.inner()).await {
+            // Serve the last index when a skipped refresh would leave the
+            // user with nothing; an explicit refresh reports the failure.
+            if refresh.unwrap_or(false) {
+                return Err(e);
+            }
+            eprintln!("NFT index refresh failed: {}", e);
+        }
+    }
+    Ok(portfolio.read().await.summary())
+}
+
+#[tauri::command]
+pub async fn nft_get_collection_stats(
+    collection: String,
+    portfolio: State<'_, SharedNftPortfolio>,
+) -> Result<NftCollectionStats, String> {
+    portfolio
+        .read()
+        .await
+        .collection_stats(&collection)
+        .ok_or_else(|| format!("Collection {} is not in the NFT index", collection))
+}
+
+#[tauri::command]
+pub async fn nft_set_include_in_totals(
+    include: bool,
+    portfolio: State<'_, SharedNftPortfolio>,
+) -> Result<NftSettings, String> {
+    let mut portfolio = portfolio.write().await;
+    portfolio.set_include_in_totals(include);
+    Ok(portfolio.settings().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn das_page_keeps_nfts_and_their_collections() {
+        let body = json!({
+            "result": {
+                "items": [
+                    {
+                        "id": "asset-1",
+                        "interface": "ProgrammableNFT",
+                        "content": {
+                            "metadata": { "name": "Mad Lad #1", "symbol": "MAD" },
+                            "links": { "image": "https://img/1.png" }
+                        },
+                        "grouping": [{
+                            "group_key": "collection",
+                            "group_value": "J1S9H3QjnRtBbbuD4HjPV6RpRhwuk4zKbxsnCHuTgh9w",
+                            "collection_metadata": { "name": "Mad Lads" }
+                        }],
+                        "compression": { "compressed": false }
+                    },
+                    {
+                        "id": "asset-2",
+                        "interface": "V1_NFT",
+                        "content": { "metadata": { "name": "Drip" } },
+                        "grouping": [],
+                        "compression": { "compressed": true }
+                    },
+                    { "id": "usdc", "interface": "FungibleToken" }
+                ]
+            }
+        });
+
+        let (holdings, collections) = parse_das_assets("owner", &body);
+
+        assert_eq!(holdings.len(), 2);
+        assert_eq!(
+            holdings[0].collection.as_deref(),
+            Some("J1S9H3QjnRtBbbuD4HjPV6RpRhwuk4zKbxsnCHuTgh9w")
+        );
+        assert!(holdings[1].compressed);
+        assert!(holdings[1].collection.is_none());
+        assert_eq!(collections.len(), 1);
+        assert_eq!(collections[0].name.as_deref(), Some("Mad Lads"));
+    }
+
+    #[test]
+    fn valuation_uses_floors_and_respects_the_totals_toggle() {
+        let holding = |id: &str, collection: Option<&str>| NftHolding {
+            asset_id: id.to_string(),
+            owner: "owner".to_string(),
+            name: None,
+            symbol: None,
+            image: None,
+            collection: collection.map(str::to_string),
+            compressed: false,
+            floor_price_sol: None,
+            value_usd: None,
+        };
+        let mut portfolio = NftPortfolio {
+            state: StoredNftPortfolio {
+                sol_price_usd: Some(150.0),
+                ..StoredNftPortfolio::default()
+            },
+            path: None,
+        };
+        portfolio.state.collections.insert(
+            "lads".to_string(),
+            NftCollectionStats {
+                address: "lads".to_string(),
+                name: None,
+                image: None,
+                marketplace_symbol: Some("mad_lads".to_string()),
+                floor_price_sol: Some(2.0),
+                listed_count: None,
+                volume_all_sol: None,
+                held_count: 2,
+                updated_at: None,
+            },
+        );
+        portfolio.state.holdings = vec![
+            holding("a", Some("lads")),
+            holding("b", Some("lads")),
+            holding("c", None),
+        ];
+
+        let summary = portfolio.summary();
+        assert_eq!(summary.total_value_sol, 4.0);
+        assert_eq!(summary.total_value_usd, 600.0);
+        assert_eq!(summary.unpriced_count, 1);
+        assert_eq!(portfolio.value_for_totals(), 600.0);
+
+        portfolio.set_include_in_totals(false);
+        assert_eq!(portfolio.value_for_totals(), 0.0);
+    }
+}
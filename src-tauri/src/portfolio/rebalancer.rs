@@ -5,6 +5,14 @@ use chrono::{DateTime, Duration, Utc};
 use serde::Deserialize;
 use tauri::State;
 
+use crate::defi::derivatives::{
+    summarize_derivatives, DerivativePosition, SharedDerivativesTracker,
+};
+use crate::demo::SharedDemoModeState;
+use crate::trading::token_policy::{check_token_policy, ExecutionPath};
+
+use super::nfts::SharedNftPortfolio;
+
 use super::types::{
     AllocationTarget, PortfolioMetrics, Position, RebalanceAction, RebalanceHistory,
     RebalanceProfile,
@@ -148,6 +156,9 @@ impl PortfolioDataState {
             all_time_pnl_percent: 28.7,
             realized_pnl: 14850.0,
             unrealized_pnl: 0.0,
+            derivatives_value: 0.0,
+            derivatives_unrealized_pnl: 0.0,
+            nft_value: 0.0,
             last_updated: now,
         }
     }
@@ -209,6 +220,11 @@ impl PortfolioDataState {
         self.positions.clone()
     }
 
+    pub fn replace_positions(&mut self, positions: Vec<Position>) {
+        self.positions = positions;
+        self.recalculate();
+    }
+
     pub fn recalculate(&mut self) {
         let total_value: f64 = self
             .positions
@@ -380,6 +396,21 @@ pub fn create_history(
     }
 }
 
+/// Applies legs planned outside a rebalance profile, such as AI
+/// optimization suggestions, and records them in the rebalance history.
+pub fn execute_planned_legs(
+    rebalancer: &mut RebalancerState,
+    portfolio: &mut PortfolioDataState,
+    plan_id: &str,
+    trigger_type: &str,
+    legs: Vec<RebalanceAction>,
+) -> RebalanceHistory {
+    portfolio.apply_rebalance(&legs);
+    let history = create_history(plan_id, trigger_type, legs, true);
+    rebalancer.record_history(history.clone());
+    history
+}
+
 fn should_trigger_deviation(profile: &RebalanceProfile, actions: &[RebalanceAction]) -> bool {
     actions
         .iter()
@@ -451,17 +482,49 @@ pub fn check_rebalance_triggers_internal(
     notifications
 }
 
+/// Folds derivative positions into spot metrics. Funding settled so far is
+/// counted as realized.
+pub fn apply_derivatives_to_metrics(
+    metrics: &mut PortfolioMetrics,
+    derivatives: &[DerivativePosition],
+) {
+    let summary = summarize_derivatives(derivatives);
+    metrics.derivatives_value = summary.equity_usd;
+    metrics.derivatives_unrealized_pnl = summary.unrealized_pnl;
+    metrics.total_value += summary.equity_usd;
+    metrics.unrealized_pnl += summary.unrealized_pnl;
+    metrics.realized_pnl += summary.accumulated_funding_usd;
+    metrics.all_time_pnl += summary.unrealized_pnl + summary.accumulated_funding_usd;
+}
+
 #[tauri::command]
-pub fn get_portfolio_metrics(
+pub async fn get_portfolio_metrics(
     data: State<'_, SharedPortfolioData>,
+    derivatives: State<'_, SharedDerivativesTracker>,
+    nfts: State<'_, SharedNftPortfolio>,
+    demo: State<'_, SharedDemoModeState>,
 ) -> Result<PortfolioMetrics, String> {
-    data.lock()
-        .map_err(|_| "Portfolio data locked".to_string())
-        .map(|guard| guard.metrics())
+    if let Some(overlay) = &demo.read().await.overlay {
+        return Ok(overlay.portfolio.metrics());
+    }
+    let mut metrics = data
+        .lock()
+        .map_err(|_| "Portfolio data locked".to_string())?
+        .metrics();
+    apply_derivatives_to_metrics(&mut metrics, &derivatives.read().await.list(None));
+    metrics.nft_value = nfts.read().await.value_for_totals();
+    metrics.total_value += metrics.nft_value;
+    Ok(metrics)
 }
 
 #[tauri::command]
-pub fn get_positions(data: State<'_, SharedPortfolioData>) -> Result<Vec<Position>, String> {
+pub async fn get_positions(
+    data: State<'_, SharedPortfolioData>,
+    demo: State<'_, SharedDemoModeState>,
+) -> Result<Vec<Position>, String> {
+    if let Some(overlay) = &demo.read().await.overlay {
+        return Ok(overlay.portfolio.positions());
+    }
     data.lock()
         .map_err(|_| "Portfolio data locked".to_string())
         .map(|guard| guard.positions())
@@ -536,12 +599,19 @@ pub fn preview_rebalance(
 }
 
 #[tauri::command]
-pub fn execute_rebalance(
+pub async fn execute_rebalance(
     profile_id: String,
     dry_run: bool,
     state: State<'_, SharedRebalancerState>,
     data: State<'_, SharedPortfolioData>,
 ) -> Result<RebalanceHistory, String> {
+    // Checked up front and before the locks are taken, so a rebalance never
+    // goes half through with a blocked buy.
+    let planned = preview_rebalance(profile_id.clone(), state.clone(), data.clone())?;
+    for action in planned.iter().filter(|action| action.action == "buy") {
+        check_token_policy(ExecutionPath::Rebalance, &action.mint).await?;
+    }
+
     let mut rebalancer = state
         .lock()
         .map_err(|_| "Rebalancer unavailable".to_string())?;
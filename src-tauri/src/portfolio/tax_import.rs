@@ -0,0 +1,590 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::tax_lots::{SharedTaxLotsState, TaxLotsState};
+use super::types::TaxLot;
+
+/// Quote currencies treated as USD when pricing exchange trades, longest
+/// first so suffix matching prefers `USDT` over `USD`.
+const USD_QUOTES: &[&str] = &["FDUSD", "BUSD", "USDT", "USDC", "ZUSD", "USD", "DAI"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExchangeSource {
+    Coinbase,
+    Binance,
+    Kraken,
+}
+
+impl ExchangeSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExchangeSource::Coinbase => "coinbase",
+            ExchangeSource::Binance => "binance",
+            ExchangeSource::Kraken => "kraken",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxImportRowError {
+    /// 1-based line number in the uploaded file.
+    pub row: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxImportResult {
+    pub source: ExchangeSource,
+    #[serde(rename = "rowsRead")]
+    pub rows_read: usize,
+    #[serde(rename = "lotsCreated")]
+    pub lots_created: usize,
+    pub disposals: usize,
+    pub duplicates: usize,
+    /// Transfers and other rows with no tax effect.
+    pub skipped: usize,
+    #[serde(rename = "realizedGain")]
+    pub realized_gain: f64,
+    pub errors: Vec<TaxImportRowError>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ImportSide {
+    Acquire,
+    Dispose,
+}
+
+/// One exchange row normalized to a USD-priced movement of a single asset.
+#[derive(Debug, Clone)]
+struct ImportedTrade {
+    key: String,
+    row: usize,
+    symbol: String,
+    side: ImportSide,
+    amount: f64,
+    price: f64,
+    fee: f64,
+    timestamp: DateTime<Utc>,
+}
+
+enum RowOutcome {
+    Trades(Vec<ImportedTrade>),
+    Skipped,
+}
+
+struct Columns(HashMap<String, usize>);
+
+impl Columns {
+    fn new(header: &[String]) -> Self {
+        Self(
+            header
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.trim().to_lowercase(), i))
+                .collect(),
+        )
+    }
+
+    fn has(&self, names: &[&str]) -> bool {
+        names.iter().all(|name| self.0.contains_key(*name))
+    }
+
+    fn get<'a>(&self, row: &'a [String], names: &[&str]) -> Option<&'a str> {
+        names
+            .iter()
+            .find_map(|name| self.0.get(*name))
+            .and_then(|&i| row.get(i))
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn require<'a>(&self, row: &'a [String], name: &str) -> Result<&'a str, String> {
+        self.get(row, &[name])
+            .ok_or_else(|| format!("Missing value for '{name}'"))
+    }
+}
+
+/// Splits CSV text into records, honouring quoted fields. Each record is
+/// paired with the line it starts on.
+fn parse_records(text: &str) -> Vec<(usize, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                fields.push(std::mem::take(&mut field));
+                if fields.iter().any(|f| !f.trim().is_empty()) {
+                    records.push((start, std::mem::take(&mut fields)));
+                }
+                fields.clear();
+                line += 1;
+                start = line;
+            }
+            '\n' => {
+                line += 1;
+                field.push(c);
+            }
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    if fields.iter().any(|f| !f.trim().is_empty()) {
+        records.push((start, fields));
+    }
+    records
+}
+
+fn parse_number(value: &str) -> Result<f64, String> {
+    let cleaned: String = value
+        .chars()
+        .filter(|c| !matches!(c, '$' | ',' | ' '))
+        .collect();
+    cleaned
+        .parse::<f64>()
+        .map_err(|_| format!("Invalid number '{value}'"))
+}
+
+/// Splits Binance amounts such as `0.5BTC` into the quantity and asset.
+fn parse_asset_amount(value: &str) -> Result<(f64, String), String> {
+    let split = value
+        .find(|c: char| c.is_ascii_alphabetic())
+        .ok_or_else(|| format!("Missing asset in '{value}'"))?;
+    let (amount, asset) = value.split_at(split);
+    Ok((parse_number(amount)?, asset.to_uppercase()))
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    let trimmed = value.trim_end_matches(" UTC").trim_end_matches('Z');
+    NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%d %H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%dT%H:%M:%S%.f"))
+        .map(|at| at.and_utc())
+        .map_err(|_| format!("Invalid timestamp '{value}'"))
+}
+
+fn is_usd(asset: &str) -> bool {
+    USD_QUOTES.contains(&asset.to_uppercase().as_str())
+}
+
+/// Kraken prefixes legacy asset codes with X (crypto) or Z (fiat) and
+/// calls bitcoin XBT.
+fn kraken_asset(code: &str) -> String {
+    let code = code.to_uppercase();
+    let code = match code.strip_prefix(|c: char| c == 'X' || c == 'Z') {
+        Some(rest) if code.len() == 4 => rest,
+        _ => code.as_str(),
+    };
+    match code {
+        "XBT" => "BTC",
+        "XDG" => "DOGE",
+        other => other,
+    }
+    .to_string()
+}
+
+fn kraken_base(pair: &str) -> Option<String> {
+    let pair = pair.replace('/', "").to_uppercase();
+    USD_QUOTES
+        .iter()
+        .filter_map(|quote| pair.strip_suffix(quote))
+        .find(|base| !base.is_empty())
+        .map(kraken_asset)
+}
+
+fn coinbase_row(columns: &Columns, row: &[String], line: usize) -> Result<RowOutcome, String> {
+    let kind = columns.require(row, "transaction type")?.to_lowercase();
+    let side = if kind.contains("sell") || kind == "convert" {
+        ImportSide::Dispose
+    } else if kind.contains("buy")
+        || kind.contains("reward")
+        || kind.contains("income")
+        || kind.contains("staking")
+    {
+        ImportSide::Acquire
+    } else {
+        return Ok(RowOutcome::Skipped);
+    };
+
+    let currency = columns
+        .get(row, &["spot price currency", "price currency"])
+        .unwrap_or("USD");
+    if !is_usd(currency) {
+        return Err(format!("Prices in {currency} are not supported"));
+    }
+    let symbol = columns.require(row, "asset")?.to_uppercase();
+    let amount = parse_number(columns.require(row, "quantity transacted")?)?.abs();
+    let price = parse_number(
+        columns
+            .get(row, &["spot price at transaction", "price at transaction"])
+            .ok_or("Missing spot price")?,
+    )?;
+    let fee = columns
+        .get(row, &["fees and/or spread", "fees"])
+        .map(parse_number)
+        .transpose()?
+        .unwrap_or(0.0)
+        .abs();
+    let timestamp = parse_timestamp(columns.require(row, "timestamp")?)?;
+    let id = columns
+        .get(row, &["id"])
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{}-{}-{}", timestamp.timestamp(), symbol, amount));
+
+    let mut trades = vec![ImportedTrade {
+        key: id.clone(),
+        row: line,
+        symbol,
+        side,
+        amount,
+        price,
+        fee,
+        timestamp,
+    }];
+
+    // Converts only list the asset given up; the asset received is in the
+    // notes, e.g. "Converted 0.5 ETH to 1,000.00 USDC".
+    if kind == "convert" {
+        let notes = columns.get(row, &["notes"]).unwrap_or_default();
+        let received: Vec<&str> = notes
+            .rsplit_once(" to ")
+            .map(|(_, to)| to.split_whitespace().collect())
+            .unwrap_or_default();
+        let [quantity, asset] = received[..] else {
+            return Err("Could not read the received asset from the notes".to_string());
+        };
+        if !is_usd(asset) {
+            let quantity = parse_number(quantity)?;
+            if quantity <= 0.0 {
+                return Err("Received quantity must be positive".to_string());
+            }
+            trades.push(ImportedTrade {
+                key: format!("{id}-in"),
+                row: line,
+                symbol: asset.to_uppercase(),
+                side: ImportSide::Acquire,
+                amount: quantity,
+                price: amount * price / quantity,
+                fee: 0.0,
+                timestamp,
+            });
+        }
+    }
+    Ok(RowOutcome::Trades(trades))
+}
+
+fn binance_row(columns: &Columns, row: &[String], line: usize) -> Result<RowOutcome, String> {
+    let side = match columns.require(row, "side")?.to_uppercase().as_str() {
+        "BUY" => ImportSide::Acquire,
+        "SELL" => ImportSide::Dispose,
+        other => return Err(format!("Unknown side '{other}'")),
+    };
+    let (mut amount, symbol) = parse_asset_amount(columns.require(row, "executed")?)?;
+    let (total, quote) = parse_asset_amount(columns.require(row, "amount")?)?;
+    if !is_usd(&quote) {
+        return Err(format!(
+            "Only USD-quoted pairs can be imported, got {quote}"
+        ));
+    }
+    let timestamp = parse_timestamp(columns.require(row, "date(utc)")?)?;
+
+    // Fees in the traded asset reduce what was received; fees paid in a
+    // third asset such as BNB have no USD value here and are ignored.
+    let (fee_amount, fee_asset) = parse_asset_amount(columns.require(row, "fee")?)?;
+    let mut fee = 0.0;
+    if fee_asset == quote {
+        fee = fee_amount;
+    } else if fee_asset == symbol && side == ImportSide::Acquire {
+        amount -= fee_amount;
+    }
+    if amount <= 0.0 {
+        return Err("Executed amount must be positive".to_string());
+    }
+
+    Ok(RowOutcome::Trades(vec![ImportedTrade {
+        key: row.join("|"),
+        row: line,
+        symbol,
+        side,
+        amount,
+        price: total / amount,
+        fee,
+        timestamp,
+    }]))
+}
+
+fn kraken_row(columns: &Columns, row: &[String], line: usize) -> Result<RowOutcome, String> {
+    let side = match columns.require(row, "type")?.to_lowercase().as_str() {
+        "buy" => ImportSide::Acquire,
+        "sell" => ImportSide::Dispose,
+        other => return Err(format!("Unknown trade type '{other}'")),
+    };
+    let pair = columns.require(row, "pair")?;
+    let symbol = kraken_base(pair)
+        .ok_or_else(|| format!("Only USD-quoted pairs can be imported, got {pair}"))?;
+    let amount = parse_number(columns.require(row, "vol")?)?;
+    if amount <= 0.0 {
+        return Err("Volume must be positive".to_string());
+    }
+
+    Ok(RowOutcome::Trades(vec![ImportedTrade {
+        key: columns.require(row, "txid")?.to_string(),
+        row: line,
+        symbol,
+        side,
+        amount,
+        price: parse_number(columns.require(row, "price")?)?,
+        fee: parse_number(columns.require(row, "fee")?)?,
+        timestamp: parse_timestamp(columns.require(row, "time")?)?,
+    }]))
+}
+
+/// Normalizes an exchange export into trades. Rows that cannot be read are
+/// reported instead of aborting the import.
+fn parse_export(
+    source: ExchangeSource,
+    text: &str,
+    result: &mut TaxImportResult,
+) -> Result<Vec<ImportedTrade>, String> {
+    let required: &[&str] = match source {
+        ExchangeSource::Coinbase => &["timestamp", "transaction type", "asset"],
+        ExchangeSource::Binance => &["date(utc)", "pair", "side", "executed", "amount"],
+        ExchangeSource::Kraken => &["txid", "pair", "time", "type", "vol"],
+    };
+    let records = parse_records(text);
+    // Coinbase exports open with a few lines of preamble before the header.
+    let header_index = records
+        .iter()
+        .position(|(_, fields)| Columns::new(fields).has(required))
+        .ok_or_else(|| format!("This does not look like a {} export", source.as_str()))?;
+    let columns = Columns::new(&records[header_index].1);
+
+    let mut trades = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for (line, row) in &records[header_index + 1..] {
+        result.rows_read += 1;
+        let outcome = match source {
+            ExchangeSource::Coinbase => coinbase_row(&columns, row, *line),
+            ExchangeSource::Binance => binance_row(&columns, row, *line),
+            ExchangeSource::Kraken => kraken_row(&columns, row, *line),
+        };
+        match outcome {
+            Ok(RowOutcome::Trades(rows)) => {
+                for mut trade in rows {
+                    // Identical rows are separate fills; number them so a
+                    // re-import still lines up with the first one.
+                    let count = seen.entry(trade.key.clone()).or_default();
+                    if *count > 0 {
+                        trade.key = format!("{}#{}", trade.key, count);
+                    }
+                    *count += 1;
+                    trades.push(trade);
+                }
+            }
+            Ok(RowOutcome::Skipped) => result.skipped += 1,
+            Err(message) => result.errors.push(TaxImportRowError {
+                row: *line,
+                message,
+            }),
+        }
+    }
+    Ok(trades)
+}
+
+/// Applies imported trades in time order: acquisitions open lots and
+/// disposals relieve open lots using the current lot strategy.
+fn apply_trades(
+    lots: &mut TaxLotsState,
+    source: ExchangeSource,
+    mut trades: Vec<ImportedTrade>,
+    result: &mut TaxImportResult,
+) {
+    trades.sort_by_key(|trade| trade.timestamp);
+    for trade in trades {
+        let lot_id = format!("import-{}-{}", source.as_str(), trade.key);
+        if lots.has_lot(&lot_id) || !lots.mark_imported(&lot_id) {
+            result.duplicates += 1;
+            continue;
+        }
+        let mint = lots
+            .mint_for_symbol(&trade.symbol)
+            .unwrap_or_else(|| trade.symbol.clone());
+
+        match trade.side {
+            ImportSide::Acquire => {
+                let cost_basis = trade.amount * trade.price + trade.fee;
+                lots.add_lot(TaxLot {
+                    id: lot_id,
+                    symbol: trade.symbol,
+                    mint,
+                    amount: trade.amount,
+                    cost_basis,
+                    price_per_unit: cost_basis / trade.amount,
+                    acquired_at: trade.timestamp.to_rfc3339(),
+                    disposed_amount: None,
+                    disposed_at: None,
+                    realized_gain: None,
+                });
+                result.lots_created += 1;
+            }
+            ImportSide::Dispose => {
+                let available = lots.open_amount(&mint);
+                if available + f64::EPSILON < trade.amount {
+                    result.errors.push(TaxImportRowError {
+                        row: trade.row,
+                        message: format!(
+                            "Sold {} {} but only {} was held; the rest has no cost basis",
+                            trade.amount, trade.symbol, available
+                        ),
+                    });
+                }
+                let proceeds = (trade.amount * trade.price - trade.fee).max(0.0);
+                result.realized_gain += lots.relieve(
+                    &mint,
+                    trade.amount,
+                    Some((proceeds / trade.amount, trade.timestamp)),
+                );
+                result.disposals += 1;
+            }
+        }
+    }
+}
+
+/// Imports a Coinbase, Binance or Kraken CSV export into the tax lots.
+/// Rows already imported from an earlier upload are counted as duplicates.
+#[tauri::command]
+pub fn tax_import_csv(
+    source: ExchangeSource,
+    csv: String,
+    state: State<'_, SharedTaxLotsState>,
+) -> Result<TaxImportResult, String> {
+    let mut result = TaxImportResult {
+        source,
+        rows_read: 0,
+        lots_created: 0,
+        disposals: 0,
+        duplicates: 0,
+        skipped: 0,
+        realized_gain: 0.0,
+        errors: Vec::new(),
+    };
+    let trades = parse_export(source, &csv, &mut result)?;
+    let mut lots = state
+        .lock()
+        .map_err(|_| "Tax lots unavailable".to_string())?;
+    apply_trades(&mut lots, source, trades, &mut result);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn import(state: &SharedTaxLotsState, source: ExchangeSource, csv: &str) -> TaxImportResult {
+        let mut result = TaxImportResult {
+            source,
+            rows_read: 0,
+            lots_created: 0,
+            disposals: 0,
+            duplicates: 0,
+            skipped: 0,
+            realized_gain: 0.0,
+            errors: Vec::new(),
+        };
+        let trades = parse_export(source, csv, &mut result).unwrap();
+        apply_trades(&mut state.lock().unwrap(), source, trades, &mut result);
+        result
+    }
+
+    #[test]
+    fn coinbase_export_creates_lots_and_skips_transfers() {
+        let csv = "\
+You can use this transaction report to inform your likely tax obligations.
+
+ID,Timestamp,Transaction Type,Asset,Quantity Transacted,Price Currency,Price at Transaction,Subtotal,Total (inclusive of fees and/or spread),Fees and/or Spread,Notes
+a1,2024-01-02 10:00:00 UTC,Buy,SOL,10,USD,$100.00,\"$1,000.00\",\"$1,010.00\",$10.00,Bought 10 SOL
+a2,2024-02-02 10:00:00 UTC,Send,SOL,-1,USD,$110.00,$110.00,$110.00,$0.00,Sent 1 SOL
+a3,2024-03-02 10:00:00 UTC,Convert,SOL,-2,USD,$120.00,$240.00,$240.00,$0.00,\"Converted 2 SOL to 0.1 ETH\"
+a4,2024-03-03 10:00:00 UTC,Buy,SOL,abc,USD,$120.00,$0,$0,$0,
+";
+        let state: SharedTaxLotsState = Mutex::new(TaxLotsState::default());
+        let result = import(&state, ExchangeSource::Coinbase, csv);
+        assert_eq!(result.rows_read, 4);
+        assert_eq!(result.lots_created, 2);
+        assert_eq!(result.disposals, 1);
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].row, 7);
+
+        let lots = state.lock().unwrap().all_lots();
+        let sol = lots.iter().find(|l| l.id == "import-coinbase-a1").unwrap();
+        assert!((sol.cost_basis - 1010.0).abs() < 1e-9);
+        // Known symbols reuse the mint of existing lots.
+        assert_eq!(sol.mint, "So11111111111111111111111111111111111111112");
+        let eth = lots
+            .iter()
+            .find(|l| l.id == "import-coinbase-a3-in")
+            .unwrap();
+        assert!((eth.cost_basis - 240.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn reimporting_binance_export_is_deduplicated() {
+        let csv = "\
+Date(UTC),Pair,Side,Price,Executed,Amount,Fee
+2024-01-05 12:00:00,BTCUSDT,BUY,40000,0.5BTC,20000USDT,0.0005BTC
+2024-01-05 12:00:00,BTCUSDT,BUY,40000,0.5BTC,20000USDT,0.0005BTC
+2024-01-06 12:00:00,ETHBTC,BUY,0.05,1ETH,0.05BTC,0.001ETH
+";
+        let state: SharedTaxLotsState = Mutex::new(TaxLotsState::default());
+        let first = import(&state, ExchangeSource::Binance, csv);
+        assert_eq!(first.lots_created, 2);
+        assert_eq!(first.errors.len(), 1);
+        let lots = state.lock().unwrap().all_lots();
+        let lot = lots
+            .iter()
+            .find(|l| l.id.starts_with("import-binance-"))
+            .unwrap();
+        assert!((lot.amount - 0.4995).abs() < 1e-9);
+
+        let second = import(&state, ExchangeSource::Binance, csv);
+        assert_eq!(second.lots_created, 0);
+        assert_eq!(second.duplicates, 2);
+    }
+
+    #[test]
+    fn kraken_sells_realize_gains_against_imported_lots() {
+        let csv = "\
+\"txid\",\"ordertxid\",\"pair\",\"time\",\"type\",\"ordertype\",\"price\",\"cost\",\"fee\",\"vol\",\"margin\",\"misc\",\"ledgers\"
+\"T1\",\"O1\",\"XXDGZUSD\",\"2024-01-01 00:00:00.0000\",\"buy\",\"limit\",\"0.10\",\"100.0\",\"1.0\",\"1000\",\"0\",\"\",\"L1\"
+\"T2\",\"O2\",\"XXDGZUSD\",\"2024-02-01 00:00:00.0000\",\"sell\",\"limit\",\"0.20\",\"200.0\",\"2.0\",\"1000\",\"0\",\"\",\"L2\"
+\"T3\",\"O3\",\"XETHZEUR\",\"2024-02-01 00:00:00.0000\",\"buy\",\"limit\",\"2000\",\"2000\",\"2.0\",\"1\",\"0\",\"\",\"L3\"
+";
+        let state: SharedTaxLotsState = Mutex::new(TaxLotsState::default());
+        let result = import(&state, ExchangeSource::Kraken, csv);
+        assert_eq!(result.lots_created, 1);
+        assert_eq!(result.disposals, 1);
+        assert_eq!(result.errors.len(), 1);
+        assert!((result.realized_gain - (198.0 - 101.0)).abs() < 1e-9);
+        assert_eq!(state.lock().unwrap().open_amount("DOGE"), 0.0);
+    }
+}
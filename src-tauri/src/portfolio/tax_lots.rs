@@ -1,7 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 
-use chrono::{DateTime, Datelike, Duration, Utc};
+use chrono::{DateTime, Datelike, Duration, Months, Utc};
 use serde::Deserialize;
 use tauri::State;
 
@@ -11,6 +11,8 @@ use super::types::{LotStrategy, TaxLossHarvestingSuggestion, TaxLot, TaxReport};
 pub struct TaxLotsState {
     lots: Vec<TaxLot>,
     strategy: LotStrategy,
+    /// Source rows already applied by the CSV importer.
+    imported: HashSet<String>,
 }
 
 impl Default for TaxLotsState {
@@ -124,6 +126,7 @@ impl Default for TaxLotsState {
         Self {
             lots,
             strategy: LotStrategy::FIFO,
+            imported: HashSet::new(),
         }
     }
 }
@@ -145,6 +148,98 @@ impl TaxLotsState {
         self.lots.push(lot);
     }
 
+    pub fn has_lot(&self, lot_id: &str) -> bool {
+        self.lots.iter().any(|l| l.id == lot_id)
+    }
+
+    /// Records an imported source row; false if it was already applied.
+    pub fn mark_imported(&mut self, key: &str) -> bool {
+        self.imported.insert(key.to_string())
+    }
+
+    pub fn mint_for_symbol(&self, symbol: &str) -> Option<String> {
+        self.lots
+            .iter()
+            .find(|l| l.symbol.eq_ignore_ascii_case(symbol))
+            .map(|l| l.mint.clone())
+    }
+
+    pub fn open_amount(&self, mint: &str) -> f64 {
+        self.lots
+            .iter()
+            .filter(|l| l.mint == mint && l.disposed_at.is_none())
+            .map(|l| l.amount)
+            .sum()
+    }
+
+    /// Removes `amount` of `mint` from open lots in strategy order. With a
+    /// `(sale_price, disposed_at)` the relieved portions are realized as
+    /// disposals; without one (an outgoing transfer) they simply leave the
+    /// books. Partially consumed lots are split so the remainder stays open.
+    /// Returns the realized gain.
+    pub fn relieve(
+        &mut self,
+        mint: &str,
+        amount: f64,
+        disposal: Option<(f64, DateTime<Utc>)>,
+    ) -> f64 {
+        let mut open: Vec<usize> = (0..self.lots.len())
+            .filter(|&i| self.lots[i].mint == mint && self.lots[i].disposed_at.is_none())
+            .collect();
+        match self.strategy {
+            LotStrategy::LIFO => {
+                open.sort_by(|&a, &b| self.lots[b].acquired_at.cmp(&self.lots[a].acquired_at))
+            }
+            LotStrategy::HIFO => open.sort_by(|&a, &b| {
+                self.lots[b]
+                    .price_per_unit
+                    .partial_cmp(&self.lots[a].price_per_unit)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            }),
+            LotStrategy::FIFO | LotStrategy::SPECIFIC => {
+                open.sort_by(|&a, &b| self.lots[a].acquired_at.cmp(&self.lots[b].acquired_at))
+            }
+        }
+
+        let mut remaining = amount;
+        let mut realized_total = 0.0;
+        let mut disposed = Vec::new();
+        for index in open {
+            if remaining <= f64::EPSILON {
+                break;
+            }
+            let lot = &mut self.lots[index];
+            let taken = remaining.min(lot.amount);
+            let cost_per_unit = if lot.amount > 0.0 {
+                lot.cost_basis / lot.amount
+            } else {
+                0.0
+            };
+            remaining -= taken;
+
+            let mut portion = lot.clone();
+            portion.id = format!("{}-{}", lot.id, uuid::Uuid::new_v4().simple());
+            portion.amount = taken;
+            portion.cost_basis = taken * cost_per_unit;
+            lot.amount -= taken;
+            lot.cost_basis -= portion.cost_basis;
+
+            if let Some((sale_price, disposed_at)) = disposal {
+                let realized = taken * sale_price - portion.cost_basis;
+                realized_total += realized;
+                portion.disposed_amount = Some(taken);
+                portion.disposed_at = Some(disposed_at.to_rfc3339());
+                portion.realized_gain = Some(realized);
+                disposed.push(portion);
+            }
+        }
+
+        self.lots
+            .retain(|l| l.disposed_at.is_some() || l.amount > f64::EPSILON);
+        self.lots.extend(disposed);
+        realized_total
+    }
+
     fn set_strategy(&mut self, strategy: LotStrategy) {
         self.strategy = strategy;
     }
@@ -202,8 +297,7 @@ impl TaxLotsState {
                 total_losses += realized.abs();
             }
 
-            let days = days_between(&lot.acquired_at, lot.disposed_at.as_deref());
-            if is_long_term(days) {
+            if held_long_term(&lot.acquired_at, lot.disposed_at.as_deref()) {
                 long_term_gains += realized;
             } else {
                 short_term_gains += realized;
@@ -246,6 +340,13 @@ impl TaxLotsState {
                 export_cointracker_format(&disposed_in_year, tax_year, self.strategy.clone())
             }
             "csv" => export_csv_format(&disposed_in_year, tax_year, self.strategy.clone()),
+            "form8949" => Ok(export_form_8949(
+                &disposed_in_year,
+                tax_year,
+                self.strategy.clone(),
+            )),
+            "txf" => Ok(export_txf(&disposed_in_year, tax_year)),
+            "koinly" => Ok(export_koinly_csv(&disposed_in_year)),
             other => Err(format!("Unsupported export format: {}", other)),
         }
     }
@@ -269,7 +370,11 @@ impl TaxLotsState {
             }
 
             let days_held = days_between(&lot.acquired_at, None);
-            let tax_rate = if is_long_term(days_held) { 0.15 } else { 0.30 };
+            let tax_rate = if held_long_term(&lot.acquired_at, None) {
+                0.15
+            } else {
+                0.30
+            };
             let potential_savings = unrealized.abs() * tax_rate;
 
             suggestions.push(TaxLossHarvestingSuggestion {
@@ -365,8 +470,20 @@ fn days_between(acquired: &str, disposed_or_now: Option<&str>) -> i64 {
     (disposed_dt - acquired_dt).num_days()
 }
 
-fn is_long_term(days: i64) -> bool {
-    days > 365
+/// IRS holding period: long-term only if sold after the first anniversary
+/// of the acquisition date, which a fixed day count gets wrong across leap
+/// years.
+fn held_long_term(acquired: &str, disposed_or_now: Option<&str>) -> bool {
+    let Ok(acquired) = parse_datetime(acquired) else {
+        return false;
+    };
+    let disposed = disposed_or_now
+        .and_then(|d| parse_datetime(d).ok())
+        .unwrap_or_else(Utc::now);
+    acquired
+        .date_naive()
+        .checked_add_months(Months::new(12))
+        .map_or(false, |anniversary| disposed.date_naive() > anniversary)
 }
 
 #[derive(Debug, Deserialize)]
@@ -426,8 +543,7 @@ fn export_turbotax_format(
             disposed_amount * (lot.realized_gain.unwrap_or(0.0) + cost) / disposed_amount.max(1.0);
         let gain = lot.realized_gain.unwrap_or(0.0);
 
-        let days = days_between(&lot.acquired_at, lot.disposed_at.as_deref());
-        let term = if is_long_term(days) {
+        let term = if held_long_term(&lot.acquired_at, lot.disposed_at.as_deref()) {
             "Long-Term"
         } else {
             "Short-Term"
@@ -532,6 +648,172 @@ fn export_csv_format(
     Ok(lines.join("\n"))
 }
 
+/// A disposed lot as it is reported on Form 8949 and its derivatives.
+struct DisposalLine<'a> {
+    lot: &'a TaxLot,
+    amount: f64,
+    acquired: Option<DateTime<Utc>>,
+    sold: Option<DateTime<Utc>>,
+    proceeds: f64,
+    cost: f64,
+    gain: f64,
+    long_term: bool,
+}
+
+/// Disposals in sale order. Which lots were sold, and so which side of the
+/// one-year line they fall on, was decided by the lot strategy when they
+/// were relieved.
+fn disposal_lines(lots: &[TaxLot]) -> Vec<DisposalLine<'_>> {
+    let mut lines: Vec<DisposalLine> = lots
+        .iter()
+        .filter_map(|lot| {
+            let amount = lot.disposed_amount.filter(|a| *a > 0.0)?;
+            let cost = if lot.amount > 0.0 {
+                amount * lot.cost_basis / lot.amount
+            } else {
+                0.0
+            };
+            let gain = lot.realized_gain.unwrap_or(0.0);
+            Some(DisposalLine {
+                lot,
+                amount,
+                acquired: parse_datetime(&lot.acquired_at).ok(),
+                sold: lot
+                    .disposed_at
+                    .as_deref()
+                    .and_then(|d| parse_datetime(d).ok()),
+                proceeds: cost + gain,
+                cost,
+                gain,
+                long_term: held_long_term(&lot.acquired_at, lot.disposed_at.as_deref()),
+            })
+        })
+        .collect();
+    lines.sort_by_key(|line| line.sold);
+    lines
+}
+
+fn format_date(date: Option<DateTime<Utc>>, format: &str) -> String {
+    date.map(|d| d.format(format).to_string())
+        .unwrap_or_else(|| "VARIOUS".to_string())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Form 8949 with short-term disposals in Part I and long-term in Part II.
+/// Crypto disposals without a broker 1099-B go in boxes C and F.
+fn export_form_8949(lots: &[TaxLot], tax_year: i32, strategy: LotStrategy) -> String {
+    let lines = disposal_lines(lots);
+    let mut rows = vec![
+        format!("Form 8949 {} (Lot method: {:?})", tax_year, strategy),
+        String::new(),
+    ];
+
+    for (long_term, part) in [
+        (false, "Part I - Short-Term (Box C)"),
+        (true, "Part II - Long-Term (Box F)"),
+    ] {
+        rows.push(part.to_string());
+        rows.push(
+            "(a) Description of property,(b) Date acquired,(c) Date sold or disposed of,\
+             (d) Proceeds,(e) Cost or other basis,(f) Code(s),(g) Amount of adjustment,\
+             (h) Gain or (loss)"
+                .to_string(),
+        );
+        let (mut proceeds, mut cost, mut gain) = (0.0, 0.0, 0.0);
+        for line in lines.iter().filter(|l| l.long_term == long_term) {
+            proceeds += line.proceeds;
+            cost += line.cost;
+            gain += line.gain;
+            rows.push(format!(
+                "{},{},{},{:.2},{:.2},,,{:.2}",
+                csv_field(&format!("{:.8} {}", line.amount, line.lot.symbol)),
+                format_date(line.acquired, "%m/%d/%Y"),
+                format_date(line.sold, "%m/%d/%Y"),
+                line.proceeds,
+                line.cost,
+                line.gain
+            ));
+        }
+        rows.push(format!(
+            "Totals,,,{:.2},{:.2},,,{:.2}",
+            proceeds, cost, gain
+        ));
+        rows.push(String::new());
+    }
+
+    rows.join("\n")
+}
+
+/// TurboTax TXF v042. Reference numbers 712 and 714 are Form 8949 box C
+/// (short-term) and box F (long-term) sales without a 1099-B.
+fn export_txf(lots: &[TaxLot], tax_year: i32) -> String {
+    let mut rows = vec![
+        "V042".to_string(),
+        format!("ATax Lots Export {}", tax_year),
+        format!("D{}", Utc::now().format("%m/%d/%Y")),
+        "^".to_string(),
+    ];
+    for line in disposal_lines(lots) {
+        rows.push("TD".to_string());
+        rows.push(if line.long_term { "N714" } else { "N712" }.to_string());
+        rows.push("C1".to_string());
+        rows.push("L1".to_string());
+        rows.push(format!("P{:.8} {}", line.amount, line.lot.symbol));
+        rows.push(format!("D{}", format_date(line.acquired, "%m/%d/%Y")));
+        rows.push(format!("D{}", format_date(line.sold, "%m/%d/%Y")));
+        rows.push(format!("${:.2}", line.cost));
+        rows.push(format!("${:.2}", line.proceeds));
+        rows.push("^".to_string());
+    }
+    rows.join("\n")
+}
+
+/// Koinly universal CSV. Each disposal is exported as the buy that opened
+/// the lot and the matching sell so Koinly reproduces the same basis.
+fn export_koinly_csv(lots: &[TaxLot]) -> String {
+    let mut rows = vec![
+        "Date,Sent Amount,Sent Currency,Received Amount,Received Currency,\
+                         Fee Amount,Fee Currency,Net Worth Amount,Net Worth Currency,Label,\
+                         Description,TxHash"
+            .to_string(),
+    ];
+    for line in disposal_lines(lots) {
+        let term = if line.long_term {
+            "long-term"
+        } else {
+            "short-term"
+        };
+        rows.push(format!(
+            "{},{:.2},USD,{:.8},{},,,{:.2},USD,,{},{}-buy",
+            format_date(line.acquired, "%Y-%m-%d %H:%M UTC"),
+            line.cost,
+            line.amount,
+            line.lot.symbol,
+            line.cost,
+            csv_field(&format!("Purchase of {}", line.lot.symbol)),
+            line.lot.id
+        ));
+        rows.push(format!(
+            "{},{:.8},{},{:.2},USD,,,{:.2},USD,,{},{}-sell",
+            format_date(line.sold, "%Y-%m-%d %H:%M UTC"),
+            line.amount,
+            line.lot.symbol,
+            line.proceeds,
+            line.proceeds,
+            csv_field(&format!("Sale of {} ({term})", line.lot.symbol)),
+            line.lot.id
+        ));
+    }
+    rows.join("\n")
+}
+
 #[tauri::command]
 pub fn get_tax_loss_harvesting_suggestions(
     state: State<'_, SharedTaxLotsState>,
@@ -546,6 +828,36 @@ pub fn get_tax_loss_harvesting_suggestions(
 mod tests {
     use super::*;
 
+    #[test]
+    fn relieve_splits_partial_lots_and_realizes_gain() {
+        let mut state = TaxLotsState::default();
+        let eth = "22222222222222222222222222222222";
+        let open_before: f64 = state
+            .open_lots()
+            .iter()
+            .filter(|l| l.mint == eth)
+            .map(|l| l.amount)
+            .sum();
+
+        // The oldest ETH lot is 25 @ 2400, so 30 consumes it and 5 of the next.
+        let realized = state.relieve(eth, 30.0, Some((3000.0, Utc::now())));
+        let expected = 25.0 * (3000.0 - 2400.0) + 5.0 * (3000.0 - 2800.0);
+        assert!((realized - expected).abs() < 1e-6);
+
+        let open_after: f64 = state
+            .open_lots()
+            .iter()
+            .filter(|l| l.mint == eth)
+            .map(|l| l.amount)
+            .sum();
+        assert!((open_before - open_after - 30.0).abs() < 1e-9);
+
+        // Transfers out leave the books without a disposal.
+        let disposals = state.all_lots().len() - state.open_lots().len();
+        assert_eq!(state.relieve(eth, 1.0, None), 0.0);
+        assert_eq!(state.all_lots().len() - state.open_lots().len(), disposals);
+    }
+
     #[test]
     fn fifo_strategy_selects_oldest_lots() {
         let state = TaxLotsState::default();
@@ -629,6 +941,72 @@ mod tests {
         assert_eq!(report.long_term_gains, 300.0);
     }
 
+    fn disposed_lot(id: &str, acquired: &str, disposed: &str, gain: f64) -> TaxLot {
+        TaxLot {
+            id: id.to_string(),
+            symbol: "SOL".to_string(),
+            mint: "So11111111111111111111111111111111111111112".to_string(),
+            amount: 2.0,
+            cost_basis: 200.0,
+            price_per_unit: 100.0,
+            acquired_at: acquired.to_string(),
+            disposed_amount: Some(2.0),
+            disposed_at: Some(disposed.to_string()),
+            realized_gain: Some(gain),
+        }
+    }
+
+    #[test]
+    fn form_8949_splits_parts_on_the_anniversary_date() {
+        // 366 days across a leap day is still not more than a year.
+        let short = disposed_lot(
+            "short",
+            "2023-03-01T00:00:00Z",
+            "2024-03-01T00:00:00Z",
+            50.0,
+        );
+        let long = disposed_lot(
+            "long",
+            "2023-03-01T00:00:00Z",
+            "2024-03-02T00:00:00Z",
+            -20.0,
+        );
+        let export = export_form_8949(&[long, short], 2024, LotStrategy::FIFO);
+
+        let (part_one, part_two) = export.split_once("Part II").unwrap();
+        assert!(part_one.contains("2.00000000 SOL,03/01/2023,03/01/2024,250.00,200.00,,,50.00"));
+        assert!(part_two.contains("03/02/2024,180.00,200.00,,,-20.00"));
+        assert!(part_two.contains("Totals,,,180.00,200.00,,,-20.00"));
+    }
+
+    #[test]
+    fn txf_uses_box_codes_for_holding_period() {
+        let short = disposed_lot(
+            "short",
+            "2024-01-01T00:00:00Z",
+            "2024-06-01T00:00:00Z",
+            10.0,
+        );
+        let long = disposed_lot("long", "2022-01-01T00:00:00Z", "2024-06-02T00:00:00Z", 30.0);
+        let txf = export_txf(&[short, long], 2024);
+
+        assert!(txf.starts_with("V042\n"));
+        let records: Vec<&str> = txf.split("TD\n").skip(1).collect();
+        assert_eq!(records.len(), 2);
+        assert!(records[0].starts_with("N712\n"));
+        assert!(records[0].contains("$200.00\n$210.00\n^"));
+        assert!(records[1].starts_with("N714\n"));
+
+        let koinly = export_koinly_csv(&[disposed_lot(
+            "lot",
+            "2024-01-01T00:00:00Z",
+            "2024-06-01T00:00:00Z",
+            10.0,
+        )]);
+        assert_eq!(koinly.lines().count(), 3);
+        assert!(koinly.contains("2024-06-01 00:00 UTC,2.00000000,SOL,210.00,USD"));
+    }
+
     #[test]
     fn tax_loss_harvesting_detects_losses() {
         let state = TaxLotsState::default();
@@ -42,6 +42,15 @@ pub struct PortfolioMetrics {
     pub realized_pnl: f64,
     #[serde(rename = "unrealizedPnl")]
     pub unrealized_pnl: f64,
+    /// Perp and option equity included in `total_value`.
+    #[serde(rename = "derivativesValue", default)]
+    pub derivatives_value: f64,
+    #[serde(rename = "derivativesUnrealizedPnl", default)]
+    pub derivatives_unrealized_pnl: f64,
+    /// NFT floor value included in `total_value`; zero when NFTs are
+    /// excluded from totals.
+    #[serde(rename = "nftValue", default)]
+    pub nft_value: f64,
     #[serde(rename = "lastUpdated")]
     pub last_updated: String,
 }
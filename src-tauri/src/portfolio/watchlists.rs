@@ -1,4 +1,5 @@
 use chrono::Utc;
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use std::path::PathBuf;
@@ -488,10 +489,68 @@ impl WatchlistManager {
 
         Ok(watchlist)
     }
+
+    /// Writes the watchlist with its original id and timestamps, replacing
+    /// any stored watchlist and items with the same id. Used by backup
+    /// restore, where `import_watchlist` would create a duplicate.
+    pub async fn restore_watchlist(&self, watchlist: &Watchlist) -> Result<(), WatchlistError> {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM watchlists WHERE id = ?1)")
+                .bind(&watchlist.id)
+                .fetch_one(&self.pool)
+                .await?;
+
+        if !exists {
+            let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM watchlists")
+                .fetch_one(&self.pool)
+                .await?;
+            if count >= MAX_WATCHLISTS as i64 {
+                return Err(WatchlistError::MaxWatchlistsReached(MAX_WATCHLISTS));
+            }
+        }
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO watchlists (id, name, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+        )
+        .bind(&watchlist.id)
+        .bind(&watchlist.name)
+        .bind(&watchlist.created_at)
+        .bind(&watchlist.updated_at)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM watchlist_items WHERE watchlist_id = ?1")
+            .bind(&watchlist.id)
+            .execute(&mut *tx)
+            .await?;
+
+        for item in &watchlist.items {
+            sqlx::query(
+                r#"
+                INSERT INTO watchlist_items (watchlist_id, symbol, mint, position, added_at)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                "#,
+            )
+            .bind(&watchlist.id)
+            .bind(&item.symbol)
+            .bind(&item.mint)
+            .bind(item.position)
+            .bind(&item.added_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
 }
 
 fn watchlist_db_path(app: &AppHandle) -> Result<PathBuf, WatchlistError> {
-    let app_data_dir = app.path().app_data_dir().map_err(|err| {
+    let app_data_dir = app.path().profile_data_dir().map_err(|err| {
         WatchlistError::Internal(format!("Unable to resolve app data directory: {err}"))
     })?;
 
@@ -0,0 +1,82 @@
+use super::manager::{ProfileStatus, SharedProfileManager, UserProfile};
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn user_profile_status(
+    profiles: State<'_, SharedProfileManager>,
+) -> Result<ProfileStatus, String> {
+    let manager = profiles.read().await;
+    Ok(manager.status())
+}
+
+#[tauri::command]
+pub async fn user_profile_list(
+    profiles: State<'_, SharedProfileManager>,
+) -> Result<Vec<UserProfile>, String> {
+    let manager = profiles.read().await;
+    Ok(manager.list())
+}
+
+#[tauri::command]
+pub async fn user_profile_create(
+    name: String,
+    display_name: Option<String>,
+    profiles: State<'_, SharedProfileManager>,
+) -> Result<UserProfile, String> {
+    let mut manager = profiles.write().await;
+    manager
+        .create(&name, display_name)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn user_profile_rename(
+    name: String,
+    display_name: String,
+    profiles: State<'_, SharedProfileManager>,
+) -> Result<UserProfile, String> {
+    let mut manager = profiles.write().await;
+    manager
+        .rename(&name, display_name)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn user_profile_delete(
+    name: String,
+    purge_data: Option<bool>,
+    profiles: State<'_, SharedProfileManager>,
+) -> Result<(), String> {
+    let mut manager = profiles.write().await;
+    manager
+        .delete(&name, purge_data.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn user_profile_set_startup(
+    name: String,
+    profiles: State<'_, SharedProfileManager>,
+) -> Result<(), String> {
+    let mut manager = profiles.write().await;
+    manager
+        .set_startup_profile(&name)
+        .map_err(|e| e.to_string())
+}
+
+/// Makes `name` the startup profile and restarts the app into it.
+#[tauri::command]
+pub async fn user_profile_switch(
+    name: String,
+    app: AppHandle,
+    profiles: State<'_, SharedProfileManager>,
+) -> Result<(), String> {
+    {
+        let mut manager = profiles.write().await;
+        manager
+            .set_startup_profile(&name)
+            .map_err(|e| e.to_string())?;
+    }
+    tracing::info!(profile = %name, "restarting into profile");
+    app.restart();
+}
@@ -0,0 +1,414 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use tauri::path::PathResolver;
+use tauri::Runtime;
+use tokio::sync::RwLock;
+
+use crate::environment::{active_environment, environment_dir_for};
+
+pub const DEFAULT_PROFILE: &str = "default";
+const PROFILES_FILE: &str = "profiles.json";
+const PROFILES_DIR: &str = "profiles";
+const PROFILE_ENV_VAR: &str = "ECLIPSE_PROFILE";
+const MAX_PROFILE_NAME_LEN: usize = 32;
+
+static ACTIVE_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Name of the profile this process was started with. Fixed for the
+/// lifetime of the process; switching profiles requires a restart so no
+/// subsystem ever holds handles into two profiles at once.
+pub fn active_profile() -> &'static str {
+    ACTIVE_PROFILE
+        .get()
+        .map(|name| name.as_str())
+        .unwrap_or(DEFAULT_PROFILE)
+}
+
+fn set_active_profile(name: &str) -> bool {
+    ACTIVE_PROFILE.set(name.to_string()).is_ok()
+}
+
+/// Data directory for `profile` under the application's base data dir. The
+/// default profile keeps using the base dir itself so existing installs
+/// keep their data.
+pub fn profile_dir_for(base: &Path, profile: &str) -> PathBuf {
+    if profile == DEFAULT_PROFILE {
+        base.to_path_buf()
+    } else {
+        base.join(PROFILES_DIR).join(profile)
+    }
+}
+
+/// Account name for an OS keyring entry, namespaced by the active profile
+/// so each profile gets its own master key.
+pub fn keyring_account(base: &str) -> String {
+    match active_profile() {
+        DEFAULT_PROFILE => base.to_string(),
+        profile => format!("{}:{}", base, profile),
+    }
+}
+
+/// Resolves per-profile paths. Subsystems must use `profile_data_dir`
+/// rather than `app_data_dir` so their files stay inside the active
+/// profile and network environment.
+pub trait ProfilePaths {
+    fn profile_data_dir(&self) -> tauri::Result<PathBuf>;
+}
+
+impl<R: Runtime> ProfilePaths for PathResolver<R> {
+    fn profile_data_dir(&self) -> tauri::Result<PathBuf> {
+        let base = self.app_data_dir()?;
+        Ok(environment_dir_for(
+            &profile_dir_for(&base, active_profile()),
+            active_environment().environment,
+        ))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("invalid profile name: {0}")]
+    InvalidName(String),
+    #[error("profile already exists: {0}")]
+    AlreadyExists(String),
+    #[error("profile not found: {0}")]
+    NotFound(String),
+    #[error("cannot delete profile: {0}")]
+    Protected(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserProfile {
+    pub name: String,
+    pub display_name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// Seed of the profile's demo dataset, kept so demo mode shows the same
+    /// data after a restart.
+    #[serde(default)]
+    pub demo_seed: Option<u64>,
+}
+
+impl UserProfile {
+    fn new(name: &str, display_name: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            display_name: display_name.unwrap_or_else(|| name.to_string()),
+            created_at: Utc::now(),
+            last_used_at: None,
+            demo_seed: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileRegistry {
+    profiles: Vec<UserProfile>,
+    /// Profile to open on the next start when none is given explicitly.
+    startup_profile: String,
+}
+
+impl Default for ProfileRegistry {
+    fn default() -> Self {
+        Self {
+            profiles: vec![UserProfile::new(
+                DEFAULT_PROFILE,
+                Some("Default".to_string()),
+            )],
+            startup_profile: DEFAULT_PROFILE.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileStatus {
+    pub active: String,
+    pub startup_profile: String,
+    pub data_dir: PathBuf,
+    pub profiles: Vec<UserProfile>,
+}
+
+/// Registry of named profiles. The registry itself lives in the base data
+/// dir, outside every profile, so it is shared by all of them.
+pub struct ProfileManager {
+    base_dir: PathBuf,
+    registry: ProfileRegistry,
+}
+
+pub type SharedProfileManager = Arc<RwLock<ProfileManager>>;
+
+impl ProfileManager {
+    pub fn load(base_dir: PathBuf) -> Result<Self, ProfileError> {
+        let path = base_dir.join(PROFILES_FILE);
+        let registry = if path.exists() {
+            let data = fs::read_to_string(&path)?;
+            serde_json::from_str(&data)?
+        } else {
+            ProfileRegistry::default()
+        };
+
+        Ok(Self { base_dir, registry })
+    }
+
+    /// Picks the profile for this process from `--profile <name>`,
+    /// `--profile=<name>`, `ECLIPSE_PROFILE`, or the stored startup profile,
+    /// in that order, and pins it as the active profile.
+    pub fn activate_for_startup(&mut self, args: &[String]) -> Result<String, ProfileError> {
+        let requested = profile_from_args(args)
+            .or_else(|| std::env::var(PROFILE_ENV_VAR).ok())
+            .unwrap_or_else(|| self.registry.startup_profile.clone());
+
+        let name = if self.exists(&requested) {
+            requested
+        } else {
+            tracing::warn!(profile = %requested, "requested profile not found, using default");
+            DEFAULT_PROFILE.to_string()
+        };
+
+        if let Some(profile) = self.registry.profiles.iter_mut().find(|p| p.name == name) {
+            profile.last_used_at = Some(Utc::now());
+        }
+        self.persist()?;
+
+        fs::create_dir_all(profile_dir_for(&self.base_dir, &name))?;
+        if !set_active_profile(&name) && active_profile() != name {
+            tracing::warn!(
+                profile = %name,
+                active = active_profile(),
+                "active profile already pinned for this process"
+            );
+        }
+        Ok(active_profile().to_string())
+    }
+
+    pub fn list(&self) -> Vec<UserProfile> {
+        self.registry.profiles.clone()
+    }
+
+    pub fn status(&self) -> ProfileStatus {
+        ProfileStatus {
+            active: active_profile().to_string(),
+            startup_profile: self.registry.startup_profile.clone(),
+            data_dir: profile_dir_for(&self.base_dir, active_profile()),
+            profiles: self.list(),
+        }
+    }
+
+    pub fn create(
+        &mut self,
+        name: &str,
+        display_name: Option<String>,
+    ) -> Result<UserProfile, ProfileError> {
+        validate_profile_name(name)?;
+        if self.exists(name) {
+            return Err(ProfileError::AlreadyExists(name.to_string()));
+        }
+
+        fs::create_dir_all(profile_dir_for(&self.base_dir, name))?;
+        let profile = UserProfile::new(name, display_name);
+        self.registry.profiles.push(profile.clone());
+        self.persist()?;
+        Ok(profile)
+    }
+
+    pub fn rename(
+        &mut self,
+        name: &str,
+        display_name: String,
+    ) -> Result<UserProfile, ProfileError> {
+        let profile = self
+            .registry
+            .profiles
+            .iter_mut()
+            .find(|p| p.name == name)
+            .ok_or_else(|| ProfileError::NotFound(name.to_string()))?;
+        profile.display_name = display_name;
+        let updated = profile.clone();
+        self.persist()?;
+        Ok(updated)
+    }
+
+    /// Removes a profile from the registry. Its data directory is only
+    /// deleted when `purge_data` is set.
+    pub fn delete(&mut self, name: &str, purge_data: bool) -> Result<(), ProfileError> {
+        if name == DEFAULT_PROFILE {
+            return Err(ProfileError::Protected("the default profile".to_string()));
+        }
+        if name == active_profile() {
+            return Err(ProfileError::Protected(
+                "profile is currently active".to_string(),
+            ));
+        }
+        if !self.exists(name) {
+            return Err(ProfileError::NotFound(name.to_string()));
+        }
+
+        self.registry.profiles.retain(|p| p.name != name);
+        if self.registry.startup_profile == name {
+            self.registry.startup_profile = DEFAULT_PROFILE.to_string();
+        }
+        self.persist()?;
+
+        if purge_data {
+            let dir = profile_dir_for(&self.base_dir, name);
+            if dir.exists() {
+                fs::remove_dir_all(dir)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Marks `name` as the profile to open on the next start.
+    pub fn set_startup_profile(&mut self, name: &str) -> Result<(), ProfileError> {
+        if !self.exists(name) {
+            return Err(ProfileError::NotFound(name.to_string()));
+        }
+        self.registry.startup_profile = name.to_string();
+        self.persist()
+    }
+
+    pub fn demo_seed(&self) -> Option<u64> {
+        self.registry
+            .profiles
+            .iter()
+            .find(|p| p.name == active_profile())
+            .and_then(|p| p.demo_seed)
+    }
+
+    /// Records the demo seed on the active profile.
+    pub fn set_demo_seed(&mut self, seed: u64) -> Result<(), ProfileError> {
+        let profile = self
+            .registry
+            .profiles
+            .iter_mut()
+            .find(|p| p.name == active_profile())
+            .ok_or_else(|| ProfileError::NotFound(active_profile().to_string()))?;
+        profile.demo_seed = Some(seed);
+        self.persist()
+    }
+
+    fn exists(&self, name: &str) -> bool {
+        self.registry.profiles.iter().any(|p| p.name == name)
+    }
+
+    fn persist(&self) -> Result<(), ProfileError> {
+        fs::create_dir_all(&self.base_dir)?;
+        let serialized = serde_json::to_string_pretty(&self.registry)?;
+        fs::write(self.base_dir.join(PROFILES_FILE), serialized)?;
+        Ok(())
+    }
+}
+
+fn profile_from_args(args: &[String]) -> Option<String> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if let Some(value) = arg.strip_prefix("--profile=") {
+            return Some(value.to_string());
+        }
+        if arg == "--profile" {
+            return iter.next().cloned();
+        }
+    }
+    None
+}
+
+fn validate_profile_name(name: &str) -> Result<(), ProfileError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_PROFILE_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ProfileError::InvalidName(format!(
+            "'{}' must be 1-{} characters of a-z, 0-9, '-' or '_'",
+            name, MAX_PROFILE_NAME_LEN
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_profile_maps_to_base_dir() {
+        let base = PathBuf::from("/data/eclipse");
+        assert_eq!(profile_dir_for(&base, DEFAULT_PROFILE), base);
+        assert_eq!(
+            profile_dir_for(&base, "fund"),
+            PathBuf::from("/data/eclipse/profiles/fund")
+        );
+    }
+
+    #[test]
+    fn parses_profile_flag_in_both_forms() {
+        let split = vec![
+            "app".to_string(),
+            "--profile".to_string(),
+            "fund".to_string(),
+        ];
+        let joined = vec!["app".to_string(), "--profile=personal".to_string()];
+        assert_eq!(profile_from_args(&split).as_deref(), Some("fund"));
+        assert_eq!(profile_from_args(&joined).as_deref(), Some("personal"));
+        assert_eq!(profile_from_args(&["app".to_string()]), None);
+    }
+
+    #[test]
+    fn rejects_names_that_escape_the_profiles_dir() {
+        assert!(validate_profile_name("fund").is_ok());
+        assert!(validate_profile_name("../keys").is_err());
+        assert!(validate_profile_name("Fund").is_err());
+        assert!(validate_profile_name("").is_err());
+    }
+
+    #[test]
+    fn create_and_delete_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = ProfileManager::load(dir.path().to_path_buf()).unwrap();
+
+        manager
+            .create("fund", Some("Fund Book".to_string()))
+            .unwrap();
+        assert!(dir.path().join("profiles/fund").exists());
+        assert!(matches!(
+            manager.create("fund", None),
+            Err(ProfileError::AlreadyExists(_))
+        ));
+
+        manager.set_startup_profile("fund").unwrap();
+        let reloaded = ProfileManager::load(dir.path().to_path_buf()).unwrap();
+        assert_eq!(reloaded.registry.startup_profile, "fund");
+
+        manager.delete("fund", true).unwrap();
+        assert!(!dir.path().join("profiles/fund").exists());
+        assert_eq!(manager.registry.startup_profile, DEFAULT_PROFILE);
+        assert!(matches!(
+            manager.delete(DEFAULT_PROFILE, false),
+            Err(ProfileError::Protected(_))
+        ));
+    }
+
+    #[test]
+    fn demo_seed_survives_a_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = ProfileManager::load(dir.path().to_path_buf()).unwrap();
+        assert_eq!(manager.demo_seed(), None);
+
+        manager.set_demo_seed(42).unwrap();
+        let reloaded = ProfileManager::load(dir.path().to_path_buf()).unwrap();
+        assert_eq!(reloaded.demo_seed(), Some(42));
+    }
+}
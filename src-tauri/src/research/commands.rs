@@ -0,0 +1,480 @@
+use chrono::{DateTime, Duration, Utc};
+use std::path::Path;
+use tauri::State;
+use uuid::Uuid;
+
+use super::thesis::{disable_alerts, level_alert_request, new_level, sync_theses, validate_levels};
+use super::types::*;
+use super::workspace::{merge_signals, render_markdown, summary_prompt, SharedResearchStore};
+use crate::ai_legacy::{SharedAIAssistant, SharedRiskAnalyzer};
+use crate::alerts::SharedAlertManager;
+use crate::anomalies::SharedAnomalyDetector;
+use crate::core::price_engine::get_price_engine;
+use crate::market::SharedHolderAnalyzer;
+
+const RESEARCH_USER_ID: &str = "research";
+
+/// Alerts configured for the token and anomalies detected on it.
+async fn discover_signals(
+    token_address: &str,
+    alerts: &SharedAlertManager,
+    anomalies: &SharedAnomalyDetector,
+) -> Vec<LinkedSignal> {
+    let now = Utc::now();
+    let mut signals = Vec::new();
+
+    if let Ok(price_alerts) = alerts.read().await.list_alerts().await {
+        signals.extend(
+            price_alerts
+                .into_iter()
+                .filter(|alert| alert.mint == token_address)
+                .map(|alert| LinkedSignal {
+                    kind: SignalKind::Alert,
+                    reference_id: alert.id,
+                    title: alert.name,
+                    detail: Some(format!("State: {}", alert.state.as_str())),
+                    occurred_at: alert.last_triggered_at,
+                    pinned: false,
+                    linked_at: now,
+                }),
+        );
+    }
+
+    let detector = anomalies.read().await;
+    signals.extend(
+        detector
+            .get_anomalies(Some(token_address), None)
+            .into_iter()
+            .map(|anomaly| LinkedSignal {
+                kind: SignalKind::Anomaly,
+                reference_id: anomaly.id,
+                title: format!("{} ({})", anomaly.anomaly_type, anomaly.severity),
+                detail: Some(anomaly.explanation),
+                occurred_at: chrono::DateTime::from_timestamp(anomaly.timestamp, 0)
+                    .map(|at| at.to_rfc3339()),
+                pinned: false,
+                linked_at: now,
+            }),
+    );
+    signals
+}
+
+async fn load_with_signals(
+    token_address: &str,
+    store: &SharedResearchStore,
+    alerts: &SharedAlertManager,
+    anomalies: &SharedAnomalyDetector,
+) -> TokenResearch {
+    let mut research = store.get(token_address).await;
+    let discovered = discover_signals(token_address, alerts, anomalies).await;
+    research.signals = merge_signals(&research.signals, discovered);
+    research
+}
+
+#[tauri::command]
+pub async fn get_token_research(
+    token_address: String,
+    store: State<'_, SharedResearchStore>,
+    alerts: State<'_, SharedAlertManager>,
+    anomalies: State<'_, SharedAnomalyDetector>,
+) -> Result<TokenResearch, String> {
+    if let Err(e) = sync_theses(&store, &alerts).await {
+        eprintln!("Failed to sync thesis alerts: {}", e);
+    }
+    Ok(load_with_signals(&token_address, &store, &alerts, &anomalies).await)
+}
+
+#[tauri::command]
+pub async fn list_token_research(
+    store: State<'_, SharedResearchStore>,
+) -> Result<Vec<ResearchOverview>, String> {
+    Ok(store.list().await)
+}
+
+/// Records a thesis and creates a price alert for each of its levels.
+#[tauri::command]
+pub async fn add_research_thesis(
+    request: AddThesisRequest,
+    store: State<'_, SharedResearchStore>,
+    alerts: State<'_, SharedAlertManager>,
+) -> Result<Thesis, String> {
+    if request.title.trim().is_empty() {
+        return Err("Thesis needs a title".to_string());
+    }
+    validate_levels(&request.levels)?;
+    let token = request.token_address.clone();
+    let symbol = match &request.symbol {
+        Some(symbol) => symbol.clone(),
+        None => store
+            .get(&token)
+            .await
+            .symbol
+            .unwrap_or_else(|| token.clone()),
+    };
+
+    let mut levels: Vec<ThesisLevel> = request.levels.iter().map(new_level).collect();
+    let mut created_alerts = Vec::new();
+    {
+        let manager = alerts.read().await;
+        for level in &mut levels {
+            let alert_request = level_alert_request(&token, &symbol, &request.title, level);
+            match manager.create_alert(alert_request).await {
+                Ok(alert) => {
+                    level.alert_id = Some(alert.id.clone());
+                    created_alerts.push(alert.id);
+                }
+                Err(e) => {
+                    for id in &created_alerts {
+                        let _ = manager.delete_alert(id).await;
+                    }
+                    return Err(format!("Failed to create level alert: {}", e));
+                }
+            }
+        }
+    }
+
+    let thesis = Thesis {
+        id: Uuid::new_v4().to_string(),
+        title: request.title,
+        body: request.body,
+        status: ThesisStatus::Open,
+        levels,
+        created_at: Utc::now(),
+        resolved_at: None,
+    };
+    let result = store
+        .update(&token, |research| {
+            if request.symbol.is_some() {
+                research.symbol = request.symbol;
+            }
+            research.theses.push(thesis.clone());
+            Ok(thesis)
+        })
+        .await;
+    if result.is_err() {
+        let manager = alerts.read().await;
+        for id in &created_alerts {
+            let _ = manager.delete_alert(id).await;
+        }
+    }
+    result
+}
+
+/// Closes an open thesis by hand and turns off its level alerts.
+#[tauri::command]
+pub async fn close_research_thesis(
+    token_address: String,
+    thesis_id: String,
+    store: State<'_, SharedResearchStore>,
+    alerts: State<'_, SharedAlertManager>,
+) -> Result<Thesis, String> {
+    let thesis = store
+        .update(&token_address, |research| {
+            let thesis = research
+                .theses
+                .iter_mut()
+                .find(|t| t.id == thesis_id)
+                .ok_or_else(|| "Thesis not found".to_string())?;
+            if thesis.status == ThesisStatus::Open {
+                thesis.status = ThesisStatus::Closed;
+                thesis.resolved_at = Some(Utc::now());
+            }
+            Ok(thesis.clone())
+        })
+        .await?;
+    let alert_ids: Vec<String> = thesis
+        .levels
+        .iter()
+        .filter_map(|level| level.alert_id.clone())
+        .collect();
+    disable_alerts(&alerts, &alert_ids).await;
+    Ok(thesis)
+}
+
+/// Theses invalidated or confirmed in the window, defaulting to the last
+/// seven days.
+#[tauri::command]
+pub async fn get_thesis_review(
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    store: State<'_, SharedResearchStore>,
+    alerts: State<'_, SharedAlertManager>,
+) -> Result<ThesisReview, String> {
+    sync_theses(&store, &alerts).await?;
+    let end = end.unwrap_or_else(Utc::now);
+    let start = start.unwrap_or(end - Duration::days(7));
+    Ok(store.thesis_review(start, end).await)
+}
+
+/// Saves a note along with the token's current price, holder concentration
+/// and latest risk score. Snapshot sources that fail are left empty rather
+/// than blocking the note.
+#[tauri::command]
+pub async fn add_research_note(
+    request: AddResearchNoteRequest,
+    store: State<'_, SharedResearchStore>,
+    holder_analyzer: State<'_, SharedHolderAnalyzer>,
+    risk_analyzer: State<'_, SharedRiskAnalyzer>,
+) -> Result<ResearchNote, String> {
+    if request.body.trim().is_empty() {
+        return Err("Note is empty".to_string());
+    }
+    let token = request.token_address.clone();
+
+    let holders = holder_analyzer
+        .read()
+        .await
+        .get_holder_distribution(&token)
+        .await
+        .ok()
+        .map(|d| HolderSnapshot {
+            total_holders: d.total_holders,
+            top_10_percentage: d.top_10_percentage,
+            gini_coefficient: d.gini_coefficient,
+            concentration_risk: d.concentration_risk,
+        });
+    let risk = risk_analyzer
+        .read()
+        .await
+        .get_latest_risk_score(&token)
+        .await
+        .ok()
+        .flatten()
+        .map(|r| RiskSnapshot {
+            score: r.score,
+            risk_level: r.risk_level,
+            scored_at: r.timestamp,
+        });
+    let price_usd = get_price_engine()
+        .get_cached_price(request.symbol.as_deref().unwrap_or(&token))
+        .map(|p| p.price);
+
+    let now = Utc::now();
+    let note = ResearchNote {
+        id: Uuid::new_v4().to_string(),
+        body: request.body,
+        tags: request.tags,
+        snapshot: NoteSnapshot {
+            captured_at: now,
+            price_usd,
+            holders,
+            risk,
+        },
+        created_at: now,
+        updated_at: now,
+    };
+
+    store
+        .update(&token, |research| {
+            if request.symbol.is_some() {
+                research.symbol = request.symbol;
+            }
+            research.notes.push(note.clone());
+            Ok(())
+        })
+        .await?;
+    Ok(note)
+}
+
+#[tauri::command]
+pub async fn update_research_note(
+    token_address: String,
+    note_id: String,
+    body: Option<String>,
+    tags: Option<Vec<String>>,
+    store: State<'_, SharedResearchStore>,
+) -> Result<ResearchNote, String> {
+    store
+        .update(&token_address, |research| {
+            let note = research
+                .notes
+                .iter_mut()
+                .find(|n| n.id == note_id)
+                .ok_or_else(|| "Note not found".to_string())?;
+            if let Some(body) = body {
+                note.body = body;
+            }
+            if let Some(tags) = tags {
+                note.tags = tags;
+            }
+            note.updated_at = Utc::now();
+            Ok(note.clone())
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn delete_research_note(
+    token_address: String,
+    note_id: String,
+    store: State<'_, SharedResearchStore>,
+) -> Result<(), String> {
+    store
+        .update(&token_address, |research| {
+            let before = research.notes.len();
+            research.notes.retain(|n| n.id != note_id);
+            if research.notes.len() == before {
+                return Err("Note not found".to_string());
+            }
+            // Files stay in the workspace, just no longer tied to the note.
+            for attachment in &mut research.attachments {
+                if attachment.note_id.as_deref() == Some(note_id.as_str()) {
+                    attachment.note_id = None;
+                }
+            }
+            Ok(())
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn attach_research_file(
+    token_address: String,
+    file_path: String,
+    note_id: Option<String>,
+    store: State<'_, SharedResearchStore>,
+) -> Result<ResearchAttachment, String> {
+    let mut attachment = store.store_file(&token_address, Path::new(&file_path))?;
+    attachment.note_id = note_id;
+    let stored_path = attachment.stored_path.clone();
+
+    let result = store
+        .update(&token_address, |research| {
+            if let Some(note_id) = &attachment.note_id {
+                if !research.notes.iter().any(|n| &n.id == note_id) {
+                    return Err("Note not found".to_string());
+                }
+            }
+            research.attachments.push(attachment.clone());
+            Ok(attachment)
+        })
+        .await;
+    if result.is_err() {
+        let _ = std::fs::remove_file(stored_path);
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn remove_research_attachment(
+    token_address: String,
+    attachment_id: String,
+    store: State<'_, SharedResearchStore>,
+) -> Result<(), String> {
+    let removed = store
+        .update(&token_address, |research| {
+            let index = research
+                .attachments
+                .iter()
+                .position(|a| a.id == attachment_id)
+                .ok_or_else(|| "Attachment not found".to_string())?;
+            Ok(research.attachments.remove(index))
+        })
+        .await?;
+    let _ = std::fs::remove_file(removed.stored_path);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn link_research_signal(
+    request: LinkSignalRequest,
+    store: State<'_, SharedResearchStore>,
+) -> Result<LinkedSignal, String> {
+    let signal = LinkedSignal {
+        kind: request.kind,
+        reference_id: request.reference_id,
+        title: request.title,
+        detail: request.detail,
+        occurred_at: request.occurred_at,
+        pinned: true,
+        linked_at: Utc::now(),
+    };
+    store
+        .update(&request.token_address, |research| {
+            research
+                .signals
+                .retain(|s| !(s.kind == signal.kind && s.reference_id == signal.reference_id));
+            research.signals.push(signal.clone());
+            Ok(signal)
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn unlink_research_signal(
+    token_address: String,
+    kind: SignalKind,
+    reference_id: String,
+    store: State<'_, SharedResearchStore>,
+) -> Result<(), String> {
+    store
+        .update(&token_address, |research| {
+            let before = research.signals.len();
+            research
+                .signals
+                .retain(|s| !(s.kind == kind && s.reference_id == reference_id));
+            if research.signals.len() == before {
+                return Err("Signal is not linked".to_string());
+            }
+            Ok(())
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn generate_research_summary(
+    token_address: String,
+    store: State<'_, SharedResearchStore>,
+    alerts: State<'_, SharedAlertManager>,
+    anomalies: State<'_, SharedAnomalyDetector>,
+    ai_assistant: State<'_, SharedAIAssistant>,
+) -> Result<ResearchSummary, String> {
+    let research = load_with_signals(&token_address, &store, &alerts, &anomalies).await;
+    if research.notes.is_empty() {
+        return Err("Add at least one note before summarizing".to_string());
+    }
+
+    let content = ai_assistant
+        .read()
+        .await
+        .complete(
+            RESEARCH_USER_ID,
+            summary_prompt(&research),
+            Some(
+                "You summarize a trader's own research notes. Do not give financial advice \
+                 or invent facts that are not in the notes."
+                    .to_string(),
+            ),
+        )
+        .await?;
+
+    let summary = ResearchSummary {
+        id: Uuid::new_v4().to_string(),
+        content,
+        note_count: research.notes.len(),
+        generated_at: Utc::now(),
+    };
+    store
+        .update(&token_address, |research| {
+            research.summaries.push(summary.clone());
+            Ok(summary)
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn export_token_research(
+    token_address: String,
+    format: ResearchExportFormat,
+    store: State<'_, SharedResearchStore>,
+    alerts: State<'_, SharedAlertManager>,
+    anomalies: State<'_, SharedAnomalyDetector>,
+) -> Result<String, String> {
+    let research = load_with_signals(&token_address, &store, &alerts, &anomalies).await;
+    match format {
+        ResearchExportFormat::Json => {
+            serde_json::to_string_pretty(&research).map_err(|e| e.to_string())
+        }
+        ResearchExportFormat::Markdown => Ok(render_markdown(&research)),
+    }
+}
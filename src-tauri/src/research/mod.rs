@@ -0,0 +1,9 @@
+pub mod commands;
+pub mod thesis;
+pub mod types;
+pub mod workspace;
+
+pub use commands::*;
+pub use thesis::*;
+pub use types::*;
+pub use workspace::*;
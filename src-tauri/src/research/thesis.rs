@@ -0,0 +1,274 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::types::*;
+use super::workspace::ResearchStore;
+use crate::alerts::price_alerts::LogicalOperator;
+use crate::alerts::{
+    AlertCondition, AlertConditionType, AlertState, CompoundCondition, CreateAlertRequest,
+    NotificationChannel, SharedAlertManager, UpdateAlertRequest,
+};
+
+/// Thesis alerts fire once and are disabled when the thesis resolves, so the
+/// cooldown only guards against repeats before the next sync.
+const THESIS_ALERT_COOLDOWN_MINUTES: i32 = 24 * 60;
+
+pub fn validate_levels(levels: &[ThesisLevelInput]) -> Result<(), String> {
+    if levels.is_empty() {
+        return Err("A thesis needs at least one invalidation or confirmation level".to_string());
+    }
+    if levels
+        .iter()
+        .any(|level| !level.price.is_finite() || level.price <= 0.0)
+    {
+        return Err("Level prices must be positive".to_string());
+    }
+    Ok(())
+}
+
+pub fn new_level(input: &ThesisLevelInput) -> ThesisLevel {
+    ThesisLevel {
+        id: Uuid::new_v4().to_string(),
+        kind: input.kind,
+        direction: input.direction,
+        price: input.price,
+        alert_id: None,
+        hit_at: None,
+    }
+}
+
+pub fn level_alert_request(
+    token_address: &str,
+    symbol: &str,
+    title: &str,
+    level: &ThesisLevel,
+) -> CreateAlertRequest {
+    let (condition_type, direction) = match level.direction {
+        LevelDirection::Above => (AlertConditionType::Above, "above"),
+        LevelDirection::Below => (AlertConditionType::Below, "below"),
+    };
+    let kind = match level.kind {
+        ThesisLevelKind::Invalidation => "invalidation",
+        ThesisLevelKind::Confirmation => "confirmation",
+    };
+    CreateAlertRequest {
+        name: format!("{symbol} {kind}: {direction} ${} ({title})", level.price),
+        symbol: symbol.to_string(),
+        mint: token_address.to_string(),
+        watchlist_id: None,
+        compound_condition: CompoundCondition {
+            conditions: vec![AlertCondition {
+                condition_type,
+                value: level.price,
+                timeframe_minutes: None,
+            }],
+            operator: LogicalOperator::And,
+        },
+        notification_channels: vec![NotificationChannel::InApp, NotificationChannel::System],
+        cooldown_minutes: THESIS_ALERT_COOLDOWN_MINUTES,
+    }
+}
+
+/// Marks levels whose alerts have fired since the thesis was written. The
+/// earliest hit decides whether the thesis was invalidated or confirmed.
+/// Returns true if the thesis resolved.
+pub fn apply_triggers(thesis: &mut Thesis, triggered: &HashMap<String, DateTime<Utc>>) -> bool {
+    if thesis.status != ThesisStatus::Open {
+        return false;
+    }
+    let created_at = thesis.created_at;
+    for level in &mut thesis.levels {
+        if level.hit_at.is_some() {
+            continue;
+        }
+        level.hit_at = level
+            .alert_id
+            .as_ref()
+            .and_then(|id| triggered.get(id))
+            .filter(|at| **at >= created_at)
+            .copied();
+    }
+    let Some(first) = thesis
+        .levels
+        .iter()
+        .filter(|level| level.hit_at.is_some())
+        .min_by_key(|level| level.hit_at)
+    else {
+        return false;
+    };
+    thesis.status = match first.kind {
+        ThesisLevelKind::Invalidation => ThesisStatus::Invalidated,
+        ThesisLevelKind::Confirmation => ThesisStatus::Confirmed,
+    };
+    thesis.resolved_at = first.hit_at;
+    true
+}
+
+/// Theses that were invalidated or confirmed between `start` and `end`.
+pub fn thesis_review<'a>(
+    workspaces: impl IntoIterator<Item = &'a TokenResearch>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> ThesisReview {
+    let mut review = ThesisReview::default();
+    for research in workspaces {
+        for thesis in &research.theses {
+            let Some(resolved_at) = thesis.resolved_at.filter(|at| *at >= start && *at < end)
+            else {
+                continue;
+            };
+            let level_price = thesis
+                .levels
+                .iter()
+                .find(|level| level.hit_at == Some(resolved_at))
+                .map(|level| level.price)
+                .unwrap_or_default();
+            let outcome = ThesisOutcome {
+                token_address: research.token_address.clone(),
+                symbol: research.symbol.clone(),
+                thesis_id: thesis.id.clone(),
+                title: thesis.title.clone(),
+                level_price,
+                resolved_at,
+            };
+            match thesis.status {
+                ThesisStatus::Invalidated => review.invalidated.push(outcome),
+                ThesisStatus::Confirmed => review.confirmed.push(outcome),
+                ThesisStatus::Open | ThesisStatus::Closed => {}
+            }
+        }
+    }
+    review.invalidated.sort_by_key(|o| o.resolved_at);
+    review.confirmed.sort_by_key(|o| o.resolved_at);
+    review
+}
+
+pub async fn disable_alerts(alerts: &SharedAlertManager, alert_ids: &[String]) {
+    let manager = alerts.read().await;
+    for id in alert_ids {
+        let disable = UpdateAlertRequest {
+            name: None,
+            compound_condition: None,
+            notification_channels: None,
+            cooldown_minutes: None,
+            state: Some(AlertState::Disabled),
+        };
+        if let Err(e) = manager.update_alert(id, disable).await {
+            eprintln!("Failed to disable thesis alert {}: {}", id, e);
+        }
+    }
+}
+
+/// Resolves open theses whose level alerts have fired and disables the
+/// alerts of theses that are no longer open.
+pub async fn sync_theses(store: &ResearchStore, alerts: &SharedAlertManager) -> Result<(), String> {
+    let triggered: HashMap<String, DateTime<Utc>> = alerts
+        .read()
+        .await
+        .list_alerts()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter_map(|alert| {
+            let at = DateTime::parse_from_rfc3339(alert.last_triggered_at.as_deref()?).ok()?;
+            Some((alert.id, at.with_timezone(&Utc)))
+        })
+        .collect();
+    if triggered.is_empty() {
+        return Ok(());
+    }
+
+    let mut resolved_alerts = Vec::new();
+    store
+        .update_all(|research| {
+            let mut changed = false;
+            for thesis in &mut research.theses {
+                if apply_triggers(thesis, &triggered) {
+                    resolved_alerts.extend(thesis.levels.iter().filter_map(|l| l.alert_id.clone()));
+                    changed = true;
+                }
+            }
+            changed
+        })
+        .await?;
+    disable_alerts(alerts, &resolved_alerts).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn thesis(created_at: DateTime<Utc>) -> Thesis {
+        let level = |id: &str, kind, direction, price| ThesisLevel {
+            id: id.to_string(),
+            kind,
+            direction,
+            price,
+            alert_id: Some(format!("alert-{id}")),
+            hit_at: None,
+        };
+        Thesis {
+            id: "t1".to_string(),
+            title: "Breakout holds".to_string(),
+            body: String::new(),
+            status: ThesisStatus::Open,
+            levels: vec![
+                level(
+                    "inv",
+                    ThesisLevelKind::Invalidation,
+                    LevelDirection::Below,
+                    1.0,
+                ),
+                level(
+                    "conf",
+                    ThesisLevelKind::Confirmation,
+                    LevelDirection::Above,
+                    2.0,
+                ),
+            ],
+            created_at,
+            resolved_at: None,
+        }
+    }
+
+    #[test]
+    fn earliest_level_hit_resolves_thesis() {
+        let created = Utc::now() - Duration::days(3);
+        let mut t = thesis(created);
+
+        let stale = HashMap::from([("alert-inv".to_string(), created - Duration::hours(1))]);
+        assert!(!apply_triggers(&mut t, &stale));
+        assert_eq!(t.status, ThesisStatus::Open);
+
+        let hits = HashMap::from([
+            ("alert-conf".to_string(), created + Duration::hours(5)),
+            ("alert-inv".to_string(), created + Duration::hours(2)),
+        ]);
+        assert!(apply_triggers(&mut t, &hits));
+        assert_eq!(t.status, ThesisStatus::Invalidated);
+        assert_eq!(t.resolved_at, Some(created + Duration::hours(2)));
+        assert!(!apply_triggers(&mut t, &hits));
+    }
+
+    #[test]
+    fn review_only_counts_theses_resolved_in_window() {
+        let now = Utc::now();
+        let mut research = TokenResearch::new("Mint111");
+        let mut confirmed = thesis(now - Duration::days(5));
+        let hit = HashMap::from([("alert-conf".to_string(), now - Duration::days(2))]);
+        apply_triggers(&mut confirmed, &hit);
+        let mut old = thesis(now - Duration::days(30));
+        old.id = "t2".to_string();
+        let old_hit = HashMap::from([("alert-inv".to_string(), now - Duration::days(20))]);
+        apply_triggers(&mut old, &old_hit);
+        research.theses = vec![confirmed, old];
+
+        let review = thesis_review([&research], now - Duration::days(7), now);
+        assert_eq!(review.confirmed.len(), 1);
+        assert_eq!(review.confirmed[0].level_price, 2.0);
+        assert!(review.invalidated.is_empty());
+    }
+}
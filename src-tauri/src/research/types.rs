@@ -0,0 +1,257 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Holder concentration at the moment a note was written.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HolderSnapshot {
+    pub total_holders: u64,
+    pub top_10_percentage: f64,
+    pub gini_coefficient: f64,
+    pub concentration_risk: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskSnapshot {
+    /// 0 (safe) to 100 (very risky).
+    pub score: f64,
+    pub risk_level: String,
+    pub scored_at: String,
+}
+
+/// Market context captured alongside a note so it can be read later
+/// against what the token looked like at the time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteSnapshot {
+    pub captured_at: DateTime<Utc>,
+    pub price_usd: Option<f64>,
+    pub holders: Option<HolderSnapshot>,
+    pub risk: Option<RiskSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResearchNote {
+    pub id: String,
+    pub body: String,
+    pub tags: Vec<String>,
+    pub snapshot: NoteSnapshot,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResearchAttachment {
+    pub id: String,
+    pub file_name: String,
+    /// Copy kept in the profile's research directory.
+    pub stored_path: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+    pub note_id: Option<String>,
+    pub added_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignalKind {
+    Alert,
+    Anomaly,
+    Incident,
+}
+
+impl SignalKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignalKind::Alert => "alert",
+            SignalKind::Anomaly => "anomaly",
+            SignalKind::Incident => "incident",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedSignal {
+    pub kind: SignalKind,
+    /// Id in the source system: alert id, anomaly id, or a free-form
+    /// reference for incidents.
+    pub reference_id: String,
+    pub title: String,
+    pub detail: Option<String>,
+    pub occurred_at: Option<String>,
+    /// False for signals found automatically when the workspace is read.
+    pub pinned: bool,
+    pub linked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResearchSummary {
+    pub id: String,
+    pub content: String,
+    pub note_count: usize,
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThesisLevelKind {
+    /// Price at which the thesis is wrong.
+    Invalidation,
+    /// Price at which the thesis has played out.
+    Confirmation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LevelDirection {
+    Above,
+    Below,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThesisLevel {
+    pub id: String,
+    pub kind: ThesisLevelKind,
+    pub direction: LevelDirection,
+    pub price: f64,
+    /// Price alert created for this level.
+    pub alert_id: Option<String>,
+    pub hit_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThesisStatus {
+    Open,
+    Invalidated,
+    Confirmed,
+    /// Closed by hand before any level was reached.
+    Closed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Thesis {
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    pub status: ThesisStatus,
+    pub levels: Vec<ThesisLevel>,
+    pub created_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenResearch {
+    pub token_address: String,
+    pub symbol: Option<String>,
+    pub notes: Vec<ResearchNote>,
+    pub attachments: Vec<ResearchAttachment>,
+    pub signals: Vec<LinkedSignal>,
+    pub summaries: Vec<ResearchSummary>,
+    #[serde(default)]
+    pub theses: Vec<Thesis>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TokenResearch {
+    pub fn new(token_address: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            token_address: token_address.to_string(),
+            symbol: None,
+            notes: Vec::new(),
+            attachments: Vec::new(),
+            signals: Vec::new(),
+            summaries: Vec::new(),
+            theses: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResearchOverview {
+    pub token_address: String,
+    pub symbol: Option<String>,
+    pub note_count: usize,
+    pub attachment_count: usize,
+    pub signal_count: usize,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddResearchNoteRequest {
+    pub token_address: String,
+    pub symbol: Option<String>,
+    pub body: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkSignalRequest {
+    pub token_address: String,
+    pub kind: SignalKind,
+    pub reference_id: String,
+    pub title: String,
+    pub detail: Option<String>,
+    pub occurred_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThesisLevelInput {
+    pub kind: ThesisLevelKind,
+    pub direction: LevelDirection,
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddThesisRequest {
+    pub token_address: String,
+    pub symbol: Option<String>,
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    pub levels: Vec<ThesisLevelInput>,
+}
+
+/// A thesis that resolved within a review window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThesisOutcome {
+    pub token_address: String,
+    pub symbol: Option<String>,
+    pub thesis_id: String,
+    pub title: String,
+    pub level_price: f64,
+    pub resolved_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThesisReview {
+    pub invalidated: Vec<ThesisOutcome>,
+    pub confirmed: Vec<ThesisOutcome>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResearchExportFormat {
+    Json,
+    Markdown,
+}
@@ -0,0 +1,396 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::thesis::thesis_review;
+use super::types::*;
+use crate::profiles::ProfilePaths;
+
+const RESEARCH_DIR: &str = "research";
+const WORKSPACES_FILE: &str = "workspaces.json";
+
+/// Adds automatically discovered signals that are not already linked.
+/// Pinned links win so the user's title and detail are kept.
+pub fn merge_signals(pinned: &[LinkedSignal], discovered: Vec<LinkedSignal>) -> Vec<LinkedSignal> {
+    let mut merged = pinned.to_vec();
+    for signal in discovered {
+        let already_linked = merged
+            .iter()
+            .any(|s| s.kind == signal.kind && s.reference_id == signal.reference_id);
+        if !already_linked {
+            merged.push(signal);
+        }
+    }
+    merged.sort_by(|a, b| b.occurred_at.cmp(&a.occurred_at));
+    merged
+}
+
+/// Prompt for the summary model, limited to the most recent notes so it
+/// stays within a single request.
+pub fn summary_prompt(research: &TokenResearch) -> String {
+    let name = research
+        .symbol
+        .as_deref()
+        .unwrap_or(research.token_address.as_str());
+    let mut prompt = format!(
+        "Summarize my research on {name} ({}). Highlight the thesis, the main risks, \
+         and anything that changed between notes. Keep it under 200 words.\n\n",
+        research.token_address
+    );
+    for note in research.notes.iter().rev().take(20).rev() {
+        let _ = writeln!(prompt, "Note from {}:", note.created_at.format("%Y-%m-%d"));
+        if let Some(risk) = &note.snapshot.risk {
+            let _ = writeln!(prompt, "(risk {:.0}/100, {})", risk.score, risk.risk_level);
+        }
+        if let Some(holders) = &note.snapshot.holders {
+            let _ = writeln!(
+                prompt,
+                "(holders {}, top 10 hold {:.1}%)",
+                holders.total_holders, holders.top_10_percentage
+            );
+        }
+        let _ = writeln!(prompt, "{}\n", note.body);
+    }
+    if !research.signals.is_empty() {
+        prompt.push_str("Linked signals:\n");
+        for signal in &research.signals {
+            let _ = writeln!(prompt, "- {}: {}", signal.kind.as_str(), signal.title);
+        }
+    }
+    prompt
+}
+
+pub fn render_markdown(research: &TokenResearch) -> String {
+    let mut out = String::new();
+    let title = research
+        .symbol
+        .as_deref()
+        .unwrap_or(research.token_address.as_str());
+    let _ = writeln!(out, "# Research: {title}\n");
+    let _ = writeln!(out, "Token: `{}`\n", research.token_address);
+
+    if let Some(summary) = research.summaries.last() {
+        let _ = writeln!(
+            out,
+            "## Latest summary ({})\n\n{}\n",
+            summary.generated_at.format("%Y-%m-%d"),
+            summary.content
+        );
+    }
+
+    out.push_str("## Notes\n\n");
+    for note in &research.notes {
+        let _ = writeln!(
+            out,
+            "### {}\n",
+            note.created_at.format("%Y-%m-%d %H:%M UTC")
+        );
+        if !note.tags.is_empty() {
+            let _ = writeln!(out, "Tags: {}\n", note.tags.join(", "));
+        }
+        let snapshot = &note.snapshot;
+        let mut context = Vec::new();
+        if let Some(price) = snapshot.price_usd {
+            context.push(format!("price ${price:.6}"));
+        }
+        if let Some(risk) = &snapshot.risk {
+            context.push(format!("risk {:.0}/100 ({})", risk.score, risk.risk_level));
+        }
+        if let Some(holders) = &snapshot.holders {
+            context.push(format!(
+                "{} holders, top 10 {:.1}%",
+                holders.total_holders, holders.top_10_percentage
+            ));
+        }
+        if !context.is_empty() {
+            let _ = writeln!(out, "_At the time: {}_\n", context.join("; "));
+        }
+        let _ = writeln!(out, "{}\n", note.body);
+    }
+
+    if !research.theses.is_empty() {
+        out.push_str("## Theses\n\n");
+        for thesis in &research.theses {
+            let status = match thesis.status {
+                ThesisStatus::Open => "open",
+                ThesisStatus::Invalidated => "invalidated",
+                ThesisStatus::Confirmed => "confirmed",
+                ThesisStatus::Closed => "closed",
+            };
+            let _ = writeln!(out, "### {} ({status})\n", thesis.title);
+            if !thesis.body.is_empty() {
+                let _ = writeln!(out, "{}\n", thesis.body);
+            }
+            for level in &thesis.levels {
+                let kind = match level.kind {
+                    ThesisLevelKind::Invalidation => "Invalidation",
+                    ThesisLevelKind::Confirmation => "Confirmation",
+                };
+                let direction = match level.direction {
+                    LevelDirection::Above => "above",
+                    LevelDirection::Below => "below",
+                };
+                let hit = level
+                    .hit_at
+                    .map(|at| format!(", hit {}", at.format("%Y-%m-%d")))
+                    .unwrap_or_default();
+                let _ = writeln!(out, "- {kind} {direction} ${}{hit}", level.price);
+            }
+            out.push('\n');
+        }
+    }
+
+    if !research.signals.is_empty() {
+        out.push_str("## Signals\n\n");
+        for signal in &research.signals {
+            let _ = writeln!(
+                out,
+                "- **{}** {}{}",
+                signal.kind.as_str(),
+                signal.title,
+                signal
+                    .occurred_at
+                    .as_deref()
+                    .map(|at| format!(" ({at})"))
+                    .unwrap_or_default()
+            );
+        }
+        out.push('\n');
+    }
+
+    if !research.attachments.is_empty() {
+        out.push_str("## Files\n\n");
+        for file in &research.attachments {
+            let _ = writeln!(
+                out,
+                "- {} ({} bytes, sha256 {})",
+                file.file_name, file.size_bytes, file.sha256
+            );
+        }
+    }
+    out
+}
+
+/// Per-token research workspaces, stored in the active profile with
+/// attached files copied alongside.
+pub struct ResearchStore {
+    workspaces: RwLock<HashMap<String, TokenResearch>>,
+    dir: Option<PathBuf>,
+}
+
+pub type SharedResearchStore = Arc<ResearchStore>;
+
+impl ResearchStore {
+    pub fn load(app_handle: &AppHandle) -> Self {
+        let dir = app_handle
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(RESEARCH_DIR));
+        let workspaces = dir
+            .as_ref()
+            .and_then(|dir| fs::read_to_string(dir.join(WORKSPACES_FILE)).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            workspaces: RwLock::new(workspaces),
+            dir,
+        }
+    }
+
+    pub async fn get(&self, token_address: &str) -> TokenResearch {
+        self.workspaces
+            .read()
+            .await
+            .get(token_address)
+            .cloned()
+            .unwrap_or_else(|| TokenResearch::new(token_address))
+    }
+
+    pub async fn list(&self) -> Vec<ResearchOverview> {
+        let workspaces = self.workspaces.read().await;
+        let mut overviews: Vec<_> = workspaces
+            .values()
+            .map(|w| ResearchOverview {
+                token_address: w.token_address.clone(),
+                symbol: w.symbol.clone(),
+                note_count: w.notes.len(),
+                attachment_count: w.attachments.len(),
+                signal_count: w.signals.len(),
+                updated_at: w.updated_at,
+            })
+            .collect();
+        overviews.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        overviews
+    }
+
+    /// Applies `change` to the workspace, creating it if needed, and
+    /// persists the result.
+    pub async fn update<T>(
+        &self,
+        token_address: &str,
+        change: impl FnOnce(&mut TokenResearch) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut workspaces = self.workspaces.write().await;
+        let is_new = !workspaces.contains_key(token_address);
+        let workspace = workspaces
+            .entry(token_address.to_string())
+            .or_insert_with(|| TokenResearch::new(token_address));
+        let result = match change(workspace) {
+            Ok(result) => result,
+            Err(e) => {
+                if is_new {
+                    workspaces.remove(token_address);
+                }
+                return Err(e);
+            }
+        };
+        workspace.updated_at = Utc::now();
+        self.save(&workspaces)?;
+        Ok(result)
+    }
+
+    /// Applies `change` to every workspace and persists if any reported a
+    /// change.
+    pub async fn update_all(
+        &self,
+        mut change: impl FnMut(&mut TokenResearch) -> bool,
+    ) -> Result<(), String> {
+        let mut workspaces = self.workspaces.write().await;
+        let now = Utc::now();
+        let mut changed = false;
+        for workspace in workspaces.values_mut() {
+            if change(workspace) {
+                workspace.updated_at = now;
+                changed = true;
+            }
+        }
+        if changed {
+            self.save(&workspaces)?;
+        }
+        Ok(())
+    }
+
+    pub async fn thesis_review(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> ThesisReview {
+        thesis_review(self.workspaces.read().await.values(), start, end)
+    }
+
+    /// Copies `source` into the token's research folder.
+    pub fn store_file(
+        &self,
+        token_address: &str,
+        source: &Path,
+    ) -> Result<ResearchAttachment, String> {
+        // The address becomes a directory name.
+        if token_address.is_empty() || !token_address.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err("Invalid token address".to_string());
+        }
+        let dir = self
+            .dir
+            .as_ref()
+            .ok_or_else(|| "No active profile directory".to_string())?
+            .join("files")
+            .join(token_address);
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+        let bytes = fs::read(source).map_err(|e| format!("Failed to read file: {}", e))?;
+        let file_name = source
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| "File has no name".to_string())?
+            .to_string();
+        let id = Uuid::new_v4().to_string();
+        let stored_path = dir.join(format!("{id}_{file_name}"));
+        fs::write(&stored_path, &bytes).map_err(|e| e.to_string())?;
+
+        Ok(ResearchAttachment {
+            id,
+            file_name,
+            stored_path: stored_path.to_string_lossy().to_string(),
+            size_bytes: bytes.len() as u64,
+            sha256: hex::encode(Sha256::digest(&bytes)),
+            note_id: None,
+            added_at: Utc::now(),
+        })
+    }
+
+    fn save(&self, workspaces: &HashMap<String, TokenResearch>) -> Result<(), String> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        let contents = serde_json::to_string(workspaces).map_err(|e| e.to_string())?;
+        fs::write(dir.join(WORKSPACES_FILE), contents).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal(kind: SignalKind, id: &str, title: &str, pinned: bool) -> LinkedSignal {
+        LinkedSignal {
+            kind,
+            reference_id: id.to_string(),
+            title: title.to_string(),
+            detail: None,
+            occurred_at: Some("2026-05-01T00:00:00Z".to_string()),
+            pinned,
+            linked_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn pinned_signals_are_not_duplicated() {
+        let pinned = vec![signal(
+            SignalKind::Anomaly,
+            "a1",
+            "Volume spike (mine)",
+            true,
+        )];
+        let discovered = vec![
+            signal(SignalKind::Anomaly, "a1", "Volume spike", false),
+            signal(SignalKind::Alert, "a1", "Price above $1", false),
+        ];
+        let merged = merge_signals(&pinned, discovered);
+        assert_eq!(merged.len(), 2);
+        assert!(merged
+            .iter()
+            .any(|s| s.kind == SignalKind::Anomaly && s.title == "Volume spike (mine)"));
+    }
+
+    #[test]
+    fn markdown_export_includes_note_context() {
+        let mut research = TokenResearch::new("Mint111");
+        research.symbol = Some("BONK".to_string());
+        research.notes.push(ResearchNote {
+            id: "n1".to_string(),
+            body: "Team doxxed, LP locked".to_string(),
+            tags: vec!["thesis".to_string()],
+            snapshot: NoteSnapshot {
+                captured_at: Utc::now(),
+                price_usd: Some(0.00002),
+                holders: None,
+                risk: Some(RiskSnapshot {
+                    score: 35.0,
+                    risk_level: "Medium".to_string(),
+                    scored_at: String::new(),
+                }),
+            },
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        });
+        let markdown = render_markdown(&research);
+        assert!(markdown.starts_with("# Research: BONK"));
+        assert!(markdown.contains("risk 35/100 (Medium)"));
+        assert!(markdown.contains("Team doxxed, LP locked"));
+        assert!(summary_prompt(&research).contains("Team doxxed"));
+    }
+}
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use crate::profiles::ProfilePaths;
 use serde::ser::Serialize as SerializeValue;
 use serde::{Deserialize, Serialize};
 use sqlx::sqlite::SqliteArguments;
@@ -24,6 +25,7 @@ pub enum ActivityAction {
     Swap,
     Approve,
     Reject,
+    RemoteHalt,
 }
 
 impl ActivityAction {
@@ -36,6 +38,7 @@ impl ActivityAction {
             ActivityAction::Swap => "swap",
             ActivityAction::Approve => "approve",
             ActivityAction::Reject => "reject",
+            ActivityAction::RemoteHalt => "remote_halt",
         }
     }
 }
@@ -689,7 +692,7 @@ fn bind_values<'q>(
 }
 
 fn activity_log_path(app: &AppHandle) -> Result<PathBuf, ActivityLogError> {
-    let mut path = app.path().app_data_dir().map_err(|err| {
+    let mut path = app.path().profile_data_dir().map_err(|err| {
         ActivityLogError::Internal(format!("Unable to resolve app data directory: {err}"))
     })?;
     if !path.exists() {
@@ -700,7 +703,7 @@ fn activity_log_path(app: &AppHandle) -> Result<PathBuf, ActivityLogError> {
 }
 
 fn activity_config_path(app: &AppHandle) -> Result<PathBuf, ActivityLogError> {
-    let mut path = app.path().app_data_dir().map_err(|err| {
+    let mut path = app.path().profile_data_dir().map_err(|err| {
         ActivityLogError::Internal(format!("Unable to resolve app data directory: {err}"))
     })?;
     if !path.exists() {
@@ -845,10 +848,15 @@ pub async fn get_activity_retention(
 
 #[tauri::command]
 pub async fn set_activity_retention(
+    app: AppHandle,
     retention_days: i64,
     logger: tauri::State<'_, ActivityLogger>,
 ) -> Result<i64, String> {
-    logger
+    let days = logger
         .set_retention_days(retention_days)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    if let Some(retention) = app.try_state::<crate::data::SharedRetentionService>() {
+        retention.set_max_age_days(crate::data::ACTIVITY_LOG_SUBSYSTEM, days as u32)?;
+    }
+    Ok(days)
 }
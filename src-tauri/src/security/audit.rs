@@ -1,3 +1,6 @@
+use crate::token_extensions::{
+    ExtensionRiskSeverity, SharedTokenExtensionService, TokenExtensionReport,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -88,6 +91,8 @@ pub struct AuditMetadata {
     pub creator_address: Option<String>,
     pub total_supply: Option<String>,
     pub holder_count: Option<u64>,
+    #[serde(default)]
+    pub token_extensions: Option<TokenExtensionReport>,
 }
 
 pub struct AuditCache {
@@ -254,6 +259,24 @@ impl HeuristicScanner {
             }
         }
 
+        if let Some(report) = &metadata.token_extensions {
+            for risk in &report.risks {
+                findings.push(Finding {
+                    severity: match risk.severity {
+                        ExtensionRiskSeverity::Critical => Severity::Critical,
+                        ExtensionRiskSeverity::High => Severity::High,
+                        ExtensionRiskSeverity::Medium => Severity::Medium,
+                        ExtensionRiskSeverity::Low => Severity::Low,
+                    },
+                    category: "Token Extensions".to_string(),
+                    title: format!("Token-2022 {} extension", risk.extension),
+                    description: risk.description.clone(),
+                    recommendation: None,
+                    source: "Token-2022".to_string(),
+                });
+            }
+        }
+
         findings
     }
 
@@ -417,7 +440,15 @@ pub async fn scan_contract(
     }
 
     // Fetch token metadata (mock for now)
-    let metadata = fetch_token_metadata(&contract_address).await?;
+    let mut metadata = fetch_token_metadata(&contract_address).await?;
+    if let Some(service) = app.try_state::<SharedTokenExtensionService>() {
+        let mut service = service.write().await;
+        let epoch = service.current_epoch().await;
+        if let Some(extensions) = service.try_get_extensions(&contract_address).await {
+            metadata.has_freeze_authority |= extensions.freeze_authority.is_some();
+            metadata.token_extensions = Some(TokenExtensionReport::new(extensions, epoch));
+        }
+    }
 
     // Perform audit
     let result = perform_audit(&contract_address, metadata).await?;
@@ -470,6 +501,7 @@ async fn fetch_token_metadata(contract_address: &str) -> Result<AuditMetadata, S
         creator_address: Some(format!("Creator{}", hash % 1000)),
         total_supply: Some(format!("{}", 1_000_000_000 + (hash % 1_000_000_000))),
         holder_count: Some(((hash % 10000) + 100)),
+        token_extensions: None,
     })
 }
 
@@ -521,6 +553,7 @@ mod tests {
             creator_address: None,
             total_supply: None,
             holder_count: None,
+            token_extensions: None,
         };
 
         let findings = HeuristicScanner::analyze_metadata(&metadata);
@@ -538,6 +571,7 @@ mod tests {
             creator_address: None,
             total_supply: None,
             holder_count: None,
+            token_extensions: None,
         };
 
         let findings = HeuristicScanner::analyze_metadata(&metadata);
@@ -555,6 +589,7 @@ mod tests {
             creator_address: None,
             total_supply: None,
             holder_count: Some(5),
+            token_extensions: None,
         };
 
         let findings = HeuristicScanner::analyze_metadata(&metadata);
@@ -572,6 +607,7 @@ mod tests {
             creator_address: Some("test".to_string()),
             total_supply: Some("1000000".to_string()),
             holder_count: Some(100),
+            token_extensions: None,
         };
 
         let result = perform_audit("test_address", metadata).await.unwrap();
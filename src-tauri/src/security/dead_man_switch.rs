@@ -0,0 +1,835 @@
+//! Inactivity dead-man switch.
+//!
+//! Once armed, the user has to check in within the configured period.
+//! As the deadline approaches the user is warned through the app and their
+//! chat integrations, one escalation at a time. Only after the last warning
+//! has gone unanswered for the grace period does the switch fire: it writes
+//! a passphrase-encrypted recovery package and sends it, with the user's
+//! instructions, to the designated contacts.
+//!
+//! The passphrase lives in the keystore and is never sent by the app; the
+//! user shares it with their contacts ahead of time.
+
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::{
+    aead::{Aead, KeyInit, OsRng},
+    Aes256Gcm,
+};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD as BASE64_ENGINE, Engine};
+use chrono::{DateTime, Duration, Utc};
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::RwLock;
+use zeroize::Zeroizing;
+
+use crate::notifications::email::{EmailAttachment, EmailManager, SendEmailRequest};
+use crate::notifications::router::SharedNotificationRouter;
+use crate::profiles::ProfilePaths;
+use crate::security::keystore::{Keystore, KeystoreError};
+use crate::wallet::multi_wallet::MultiWalletManager;
+
+const SWITCH_FILE: &str = "dead_man_switch.json";
+const PACKAGE_DIR: &str = "recovery_packages";
+const PASSPHRASE_KEY: &str = "dead_man_switch.passphrase";
+const MIN_PASSPHRASE_LEN: usize = 12;
+const CHECK_INTERVAL: StdDuration = StdDuration::from_secs(15 * 60);
+pub const DEAD_MAN_WARNING_EVENT: &str = "dead_man_switch_warning";
+pub const DEAD_MAN_TRIGGERED_EVENT: &str = "dead_man_switch_triggered";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DesignatedContact {
+    pub name: String,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadManSwitchSettings {
+    pub enabled: bool,
+    pub check_in_period_hours: i64,
+    /// Hours before the deadline at which each warning goes out.
+    pub warning_hours_before: Vec<i64>,
+    /// How long the final warning must go unanswered before triggering.
+    pub grace_hours: i64,
+    pub contacts: Vec<DesignatedContact>,
+    /// Also post the trigger notice to the enabled chat integrations.
+    pub notify_chat_channels: bool,
+    /// Warnings are emailed here as well as shown in the app.
+    pub owner_email: Option<String>,
+    pub recovery_instructions: String,
+    pub include_wallet_addresses: bool,
+}
+
+impl Default for DeadManSwitchSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            check_in_period_hours: 24 * 30,
+            warning_hours_before: vec![24 * 7, 72, 24],
+            grace_hours: 24,
+            contacts: Vec::new(),
+            notify_chat_channels: false,
+            owner_email: None,
+            recovery_instructions: String::new(),
+            include_wallet_addresses: true,
+        }
+    }
+}
+
+impl DeadManSwitchSettings {
+    /// Warning offsets from the earliest to the latest.
+    fn warning_schedule(&self) -> Vec<i64> {
+        let mut hours = self.warning_hours_before.clone();
+        hours.sort_unstable_by(|a, b| b.cmp(a));
+        hours.dedup();
+        hours
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadManSwitchState {
+    pub last_check_in_at: Option<DateTime<Utc>>,
+    /// Warnings sent since the last check-in.
+    pub warnings_sent: usize,
+    pub last_warning_at: Option<DateTime<Utc>>,
+    pub triggered_at: Option<DateTime<Utc>>,
+    pub package_path: Option<String>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SwitchAction {
+    Idle,
+    Warn {
+        level: usize,
+        deadline: DateTime<Utc>,
+    },
+    Trigger,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadManSwitchStatus {
+    pub settings: DeadManSwitchSettings,
+    pub state: DeadManSwitchState,
+    pub deadline: Option<DateTime<Utc>>,
+    pub passphrase_set: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadManWarning {
+    pub level: usize,
+    pub total_levels: usize,
+    pub deadline: DateTime<Utc>,
+    pub hours_left: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryWallet {
+    pub label: String,
+    pub public_key: String,
+    pub chain_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryContents {
+    pub created_at: DateTime<Utc>,
+    pub last_check_in_at: Option<DateTime<Utc>>,
+    pub instructions: String,
+    pub wallets: Vec<RecoveryWallet>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryPackage {
+    pub version: u32,
+    pub kdf: String,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What the switch should do at `now`. Warnings are issued one level per
+/// call and never skipped, so a switch whose deadline passed while the app
+/// was closed still walks through every escalation before it fires.
+pub fn next_action(
+    settings: &DeadManSwitchSettings,
+    state: &DeadManSwitchState,
+    now: DateTime<Utc>,
+) -> SwitchAction {
+    if !settings.enabled || state.triggered_at.is_some() {
+        return SwitchAction::Idle;
+    }
+    let Some(last_check_in) = state.last_check_in_at else {
+        return SwitchAction::Idle;
+    };
+    let deadline = last_check_in + Duration::hours(settings.check_in_period_hours);
+    let schedule = settings.warning_schedule();
+    let spacing_ok = state
+        .last_warning_at
+        .map_or(true, |at| now >= at + Duration::hours(settings.grace_hours));
+
+    if state.warnings_sent < schedule.len() {
+        let due = schedule
+            .iter()
+            .filter(|hours| now >= deadline - Duration::hours(**hours))
+            .count();
+        // Catch-up warnings after downtime keep the grace spacing.
+        let catching_up = now >= deadline;
+        if due > state.warnings_sent && (!catching_up || spacing_ok) {
+            return SwitchAction::Warn {
+                level: state.warnings_sent + 1,
+                deadline,
+            };
+        }
+        return SwitchAction::Idle;
+    }
+
+    if now >= deadline && spacing_ok {
+        SwitchAction::Trigger
+    } else {
+        SwitchAction::Idle
+    }
+}
+
+fn derive_package_key(passphrase: &[u8], salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, String> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::default()
+        .hash_password_into(passphrase, salt, &mut key[..])
+        .map_err(|e| format!("Failed to derive package key: {}", e))?;
+    Ok(key)
+}
+
+pub fn seal_recovery_package(
+    contents: &RecoveryContents,
+    passphrase: &[u8],
+) -> Result<RecoveryPackage, String> {
+    let plaintext = Zeroizing::new(serde_json::to_vec(contents).map_err(|e| e.to_string())?);
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let key = derive_package_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(&key[..]));
+    let ciphertext = cipher
+        .encrypt(GenericArray::from_slice(&nonce), plaintext.as_slice())
+        .map_err(|_| "Failed to encrypt recovery package".to_string())?;
+
+    Ok(RecoveryPackage {
+        version: 1,
+        kdf: "argon2id".into(),
+        salt: BASE64_ENGINE.encode(salt),
+        nonce: BASE64_ENGINE.encode(nonce),
+        ciphertext: BASE64_ENGINE.encode(ciphertext),
+        created_at: contents.created_at,
+    })
+}
+
+pub fn open_recovery_package(
+    package: &RecoveryPackage,
+    passphrase: &[u8],
+) -> Result<RecoveryContents, String> {
+    let decode = |field: &str| {
+        BASE64_ENGINE
+            .decode(field.as_bytes())
+            .map_err(|_| "Malformed recovery package".to_string())
+    };
+    let salt = decode(&package.salt)?;
+    let nonce = decode(&package.nonce)?;
+    let ciphertext = decode(&package.ciphertext)?;
+    if nonce.len() != 12 {
+        return Err("Malformed recovery package".into());
+    }
+
+    let key = derive_package_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(&key[..]));
+    let plaintext = Zeroizing::new(
+        cipher
+            .decrypt(GenericArray::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| "Wrong passphrase or corrupted package".to_string())?,
+    );
+    serde_json::from_slice(&plaintext).map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct StoredSwitch {
+    settings: DeadManSwitchSettings,
+    state: DeadManSwitchState,
+}
+
+pub type SharedDeadManSwitch = Arc<RwLock<DeadManSwitch>>;
+
+pub struct DeadManSwitch {
+    path: Option<PathBuf>,
+    settings: DeadManSwitchSettings,
+    state: DeadManSwitchState,
+}
+
+impl DeadManSwitch {
+    pub fn new(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|d| d.join(SWITCH_FILE));
+        let stored: StoredSwitch = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            settings: stored.settings,
+            state: stored.state,
+        }
+    }
+
+    pub fn settings(&self) -> &DeadManSwitchSettings {
+        &self.settings
+    }
+
+    pub fn state(&self) -> &DeadManSwitchState {
+        &self.state
+    }
+
+    pub fn deadline(&self) -> Option<DateTime<Utc>> {
+        self.state
+            .last_check_in_at
+            .map(|at| at + Duration::hours(self.settings.check_in_period_hours))
+    }
+
+    pub fn update_settings(&mut self, settings: DeadManSwitchSettings, now: DateTime<Utc>) {
+        // Arming starts a fresh period rather than counting from an old check-in.
+        if settings.enabled && !self.settings.enabled {
+            self.reset(now);
+        }
+        self.settings = settings;
+        self.save();
+    }
+
+    pub fn check_in(&mut self, now: DateTime<Utc>) {
+        self.reset(now);
+        self.save();
+    }
+
+    fn reset(&mut self, now: DateTime<Utc>) {
+        self.state = DeadManSwitchState {
+            last_check_in_at: Some(now),
+            ..DeadManSwitchState::default()
+        };
+    }
+
+    pub fn record_warning(&mut self, level: usize, now: DateTime<Utc>) {
+        self.state.warnings_sent = level;
+        self.state.last_warning_at = Some(now);
+        self.save();
+    }
+
+    pub fn record_trigger(
+        &mut self,
+        now: DateTime<Utc>,
+        package_path: Option<String>,
+        error: Option<String>,
+    ) {
+        self.state.triggered_at = Some(now);
+        self.state.package_path = package_path;
+        self.state.last_error = error;
+        self.save();
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let stored = StoredSwitch {
+            settings: self.settings.clone(),
+            state: self.state.clone(),
+        };
+        match serde_json::to_string_pretty(&stored) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(path, contents) {
+                    eprintln!("Failed to save dead-man switch: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize dead-man switch: {}", e),
+        }
+    }
+}
+
+fn passphrase_set(keystore: &Keystore) -> bool {
+    keystore.retrieve_secret(PASSPHRASE_KEY).is_ok()
+}
+
+fn validate_settings(settings: &DeadManSwitchSettings) -> Result<(), String> {
+    if settings.check_in_period_hours < 24 {
+        return Err("Check-in period must be at least 24 hours".into());
+    }
+    if settings.grace_hours < 1 {
+        return Err("Grace period must be at least one hour".into());
+    }
+    if settings.warning_hours_before.is_empty() {
+        return Err("At least one warning is required before the switch triggers".into());
+    }
+    if settings
+        .warning_hours_before
+        .iter()
+        .any(|hours| *hours <= 0 || *hours >= settings.check_in_period_hours)
+    {
+        return Err("Warnings must fall inside the check-in period".into());
+    }
+    if let Some(contact) = settings
+        .contacts
+        .iter()
+        .find(|contact| contact.name.trim().is_empty())
+    {
+        return Err(format!("Contact {:?} needs a name", contact.email));
+    }
+    let has_recipient = settings.notify_chat_channels
+        || settings.contacts.iter().any(|contact| {
+            contact
+                .email
+                .as_deref()
+                .is_some_and(|e| !e.trim().is_empty())
+        });
+    if settings.enabled && !has_recipient {
+        return Err("Add a contact email or enable chat channels before arming".into());
+    }
+    Ok(())
+}
+
+fn recovery_contents(
+    app: &AppHandle,
+    settings: &DeadManSwitchSettings,
+    state: &DeadManSwitchState,
+    now: DateTime<Utc>,
+) -> RecoveryContents {
+    let wallets = if settings.include_wallet_addresses {
+        app.try_state::<MultiWalletManager>()
+            .and_then(|manager| manager.list_wallets().ok())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|wallet| RecoveryWallet {
+                label: wallet.label,
+                public_key: wallet.public_key,
+                chain_id: wallet.chain_id,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    RecoveryContents {
+        created_at: now,
+        last_check_in_at: state.last_check_in_at,
+        instructions: settings.recovery_instructions.clone(),
+        wallets,
+    }
+}
+
+/// Seals the recovery package and writes it under the profile, or to
+/// `output_path` when given. Returns the path and the serialized package.
+fn write_recovery_package(
+    app: &AppHandle,
+    switch: &DeadManSwitch,
+    output_path: Option<PathBuf>,
+    now: DateTime<Utc>,
+) -> Result<(PathBuf, Vec<u8>), String> {
+    let keystore = app
+        .try_state::<Keystore>()
+        .ok_or_else(|| "Keystore is not available".to_string())?;
+    let passphrase = keystore
+        .retrieve_secret(PASSPHRASE_KEY)
+        .map_err(|e| match e {
+            KeystoreError::NotFound => "Set a recovery passphrase first".to_string(),
+            other => other.to_string(),
+        })?;
+    let contents = recovery_contents(app, switch.settings(), switch.state(), now);
+    let package = seal_recovery_package(&contents, &passphrase)?;
+    let bytes = serde_json::to_vec_pretty(&package).map_err(|e| e.to_string())?;
+
+    let path = match output_path {
+        Some(path) => path,
+        None => {
+            let dir = app
+                .path()
+                .profile_data_dir()
+                .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+                .join(PACKAGE_DIR);
+            fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create package directory: {}", e))?;
+            dir.join(format!("recovery_{}.json", now.format("%Y%m%d_%H%M%S")))
+        }
+    };
+    fs::write(&path, &bytes).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok((path, bytes))
+}
+
+async fn send_email(
+    app: &AppHandle,
+    to: Vec<String>,
+    subject: &str,
+    body: String,
+    attachment: Option<EmailAttachment>,
+) -> Result<(), String> {
+    let keystore = app
+        .try_state::<Keystore>()
+        .ok_or_else(|| "Keystore is not available".to_string())?;
+    let manager = EmailManager::new(app).await.map_err(|e| e.to_string())?;
+    let config = manager
+        .get_config(&keystore)
+        .await
+        .map_err(|e| e.to_string())?;
+    let request = SendEmailRequest {
+        to,
+        subject: subject.to_string(),
+        html_body: None,
+        text_body: Some(body),
+        template: None,
+        template_vars: None,
+        attachments: attachment.map(|a| vec![a]),
+        include_unsubscribe: false,
+    };
+    manager
+        .send_email(request, &config)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+async fn send_chat(app: &AppHandle, source_id: &str, title: &str, message: &str) {
+    let Some(router) = app.try_state::<SharedNotificationRouter>() else {
+        return;
+    };
+    if let Err(e) = router
+        .read()
+        .await
+        .send_text_notification(source_id, title, message)
+        .await
+    {
+        eprintln!("Failed to send dead-man switch notice: {}", e);
+    }
+}
+
+async fn send_warning(app: &AppHandle, settings: &DeadManSwitchSettings, warning: &DeadManWarning) {
+    let _ = app.emit(DEAD_MAN_WARNING_EVENT, warning);
+    let message = if warning.hours_left > 0.0 {
+        format!(
+            "Check in within {:.0} hours or your dead-man switch will notify your recovery contacts (warning {} of {}).",
+            warning.hours_left, warning.level, warning.total_levels
+        )
+    } else {
+        format!(
+            "Your check-in deadline has passed. Check in now or your dead-man switch will notify your recovery contacts (warning {} of {}).",
+            warning.level, warning.total_levels
+        )
+    };
+    send_chat(app, "dead_man_switch", "Dead-man switch check-in", &message).await;
+    if let Some(email) = settings
+        .owner_email
+        .as_deref()
+        .filter(|e| !e.trim().is_empty())
+    {
+        if let Err(e) = send_email(
+            app,
+            vec![email.to_string()],
+            "Dead-man switch: check in required",
+            message,
+            None,
+        )
+        .await
+        {
+            eprintln!("Failed to email dead-man switch warning: {}", e);
+        }
+    }
+}
+
+/// Runs the trigger plan. Each step is attempted even if an earlier one
+/// fails; the errors are collected for the status view.
+async fn trigger(app: &AppHandle, switch: &SharedDeadManSwitch, now: DateTime<Utc>) {
+    let mut errors = Vec::new();
+    let (settings, package) = {
+        let switch = switch.read().await;
+        let package = write_recovery_package(app, &switch, None, now);
+        (switch.settings().clone(), package)
+    };
+    let package = match package {
+        Ok(package) => Some(package),
+        Err(e) => {
+            errors.push(format!("Recovery package: {}", e));
+            None
+        }
+    };
+
+    let recipients: Vec<String> = settings
+        .contacts
+        .iter()
+        .filter_map(|contact| contact.email.clone())
+        .filter(|email| !email.trim().is_empty())
+        .collect();
+    if !recipients.is_empty() {
+        let mut body = format!(
+            "This message was sent automatically because the owner of this wallet app has not checked in since {}.\n\n",
+            switch
+                .read()
+                .await
+                .state()
+                .last_check_in_at
+                .map(|at| at.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_else(|| "an unknown date".into())
+        );
+        body.push_str(&settings.recovery_instructions);
+        let attachment = package.as_ref().map(|(path, bytes)| EmailAttachment {
+            filename: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "recovery_package.json".into()),
+            content: bytes.clone(),
+            mime_type: "application/json".into(),
+        });
+        if attachment.is_some() {
+            body.push_str(
+                "\n\nThe attached recovery package is encrypted with the passphrase you were given.",
+            );
+        }
+        if let Err(e) = send_email(app, recipients, "Recovery instructions", body, attachment).await
+        {
+            errors.push(format!("Email: {}", e));
+        }
+    }
+
+    if settings.notify_chat_channels {
+        let names: Vec<&str> = settings.contacts.iter().map(|c| c.name.as_str()).collect();
+        let message = format!(
+            "Dead-man switch triggered after missed check-ins. Recovery contacts: {}.",
+            if names.is_empty() {
+                "none listed".to_string()
+            } else {
+                names.join(", ")
+            }
+        );
+        send_chat(
+            app,
+            "dead_man_switch",
+            "Dead-man switch triggered",
+            &message,
+        )
+        .await;
+    }
+
+    let package_path = package.map(|(path, _)| path.display().to_string());
+    let error = (!errors.is_empty()).then(|| errors.join("; "));
+    let mut switch = switch.write().await;
+    switch.record_trigger(now, package_path, error);
+    let _ = app.emit(DEAD_MAN_TRIGGERED_EVENT, switch.state());
+}
+
+async fn evaluate(app: &AppHandle, switch: &SharedDeadManSwitch) {
+    let now = Utc::now();
+    let (action, settings) = {
+        let switch = switch.read().await;
+        (
+            next_action(switch.settings(), switch.state(), now),
+            switch.settings().clone(),
+        )
+    };
+    match action {
+        SwitchAction::Idle => {}
+        SwitchAction::Warn { level, deadline } => {
+            let warning = DeadManWarning {
+                level,
+                total_levels: settings.warning_schedule().len(),
+                deadline,
+                hours_left: (deadline - now).num_minutes() as f64 / 60.0,
+            };
+            switch.write().await.record_warning(level, now);
+            send_warning(app, &settings, &warning).await;
+        }
+        SwitchAction::Trigger => trigger(app, switch, now).await,
+    }
+}
+
+pub fn start_dead_man_switch(app: AppHandle, switch: SharedDeadManSwitch) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            evaluate(&app, &switch).await;
+        }
+    });
+}
+
+fn status(switch: &DeadManSwitch, keystore: &Keystore) -> DeadManSwitchStatus {
+    DeadManSwitchStatus {
+        settings: switch.settings().clone(),
+        state: switch.state().clone(),
+        deadline: switch.deadline(),
+        passphrase_set: passphrase_set(keystore),
+    }
+}
+
+#[tauri::command]
+pub async fn dead_man_switch_get_status(
+    switch: State<'_, SharedDeadManSwitch>,
+    keystore: State<'_, Keystore>,
+) -> Result<DeadManSwitchStatus, String> {
+    Ok(status(&switch.read().await, &keystore))
+}
+
+#[tauri::command]
+pub async fn dead_man_switch_update_settings(
+    settings: DeadManSwitchSettings,
+    switch: State<'_, SharedDeadManSwitch>,
+    keystore: State<'_, Keystore>,
+) -> Result<DeadManSwitchStatus, String> {
+    validate_settings(&settings)?;
+    if settings.enabled && !passphrase_set(&keystore) {
+        return Err("Set a recovery passphrase before arming the switch".into());
+    }
+    let mut switch = switch.write().await;
+    switch.update_settings(settings, Utc::now());
+    Ok(status(&switch, &keystore))
+}
+
+#[tauri::command]
+pub async fn dead_man_switch_check_in(
+    switch: State<'_, SharedDeadManSwitch>,
+    keystore: State<'_, Keystore>,
+) -> Result<DeadManSwitchStatus, String> {
+    let mut switch = switch.write().await;
+    switch.check_in(Utc::now());
+    Ok(status(&switch, &keystore))
+}
+
+#[tauri::command]
+pub async fn dead_man_switch_set_passphrase(
+    passphrase: String,
+    keystore: State<'_, Keystore>,
+) -> Result<(), String> {
+    let passphrase = Zeroizing::new(passphrase);
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!(
+            "Recovery passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        ));
+    }
+    keystore
+        .store_secret(PASSPHRASE_KEY, passphrase.as_bytes())
+        .map_err(|e| e.to_string())
+}
+
+/// Writes the recovery package now, so the user can check what their
+/// contacts would receive.
+#[tauri::command]
+pub async fn dead_man_switch_export_package(
+    app: AppHandle,
+    output_path: Option<String>,
+    switch: State<'_, SharedDeadManSwitch>,
+) -> Result<String, String> {
+    let switch = switch.read().await;
+    let output_path = output_path
+        .filter(|path| !path.trim().is_empty())
+        .map(PathBuf::from);
+    let (path, _) = write_recovery_package(&app, &switch, output_path, Utc::now())?;
+    Ok(path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn armed(now: DateTime<Utc>) -> (DeadManSwitchSettings, DeadManSwitchState) {
+        let settings = DeadManSwitchSettings {
+            enabled: true,
+            check_in_period_hours: 100,
+            warning_hours_before: vec![24, 48],
+            grace_hours: 6,
+            ..DeadManSwitchSettings::default()
+        };
+        let state = DeadManSwitchState {
+            last_check_in_at: Some(now),
+            ..DeadManSwitchState::default()
+        };
+        (settings, state)
+    }
+
+    #[test]
+    fn warnings_escalate_before_trigger() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let (settings, mut state) = armed(start);
+        let deadline = start + Duration::hours(100);
+
+        assert_eq!(
+            next_action(&settings, &state, start + Duration::hours(40)),
+            SwitchAction::Idle
+        );
+        assert_eq!(
+            next_action(&settings, &state, start + Duration::hours(60)),
+            SwitchAction::Warn { level: 1, deadline }
+        );
+        state.warnings_sent = 1;
+        state.last_warning_at = Some(start + Duration::hours(60));
+        assert_eq!(
+            next_action(&settings, &state, start + Duration::hours(80)),
+            SwitchAction::Warn { level: 2, deadline }
+        );
+        state.warnings_sent = 2;
+        state.last_warning_at = Some(start + Duration::hours(99));
+        // Deadline passed, but the last warning is still inside its grace period.
+        assert_eq!(
+            next_action(&settings, &state, start + Duration::hours(101)),
+            SwitchAction::Idle
+        );
+        assert_eq!(
+            next_action(&settings, &state, start + Duration::hours(105)),
+            SwitchAction::Trigger
+        );
+
+        // Returning after a long absence still issues every warning first.
+        let (settings, mut state) = armed(start);
+        let late = start + Duration::hours(500);
+        assert_eq!(
+            next_action(&settings, &state, late),
+            SwitchAction::Warn { level: 1, deadline }
+        );
+        state.warnings_sent = 1;
+        state.last_warning_at = Some(late);
+        assert_eq!(
+            next_action(&settings, &state, late + Duration::hours(1)),
+            SwitchAction::Idle
+        );
+    }
+
+    #[test]
+    fn recovery_package_round_trips_with_passphrase() {
+        let contents = RecoveryContents {
+            created_at: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            last_check_in_at: None,
+            instructions: "Hardware wallet is in the safe.".into(),
+            wallets: vec![RecoveryWallet {
+                label: "Main".into(),
+                public_key: "11111111111111111111111111111111".into(),
+                chain_id: "solana".into(),
+            }],
+        };
+        let package = seal_recovery_package(&contents, b"correct horse battery").unwrap();
+        assert!(!package.ciphertext.contains("safe"));
+
+        let opened = open_recovery_package(&package, b"correct horse battery").unwrap();
+        assert_eq!(opened.instructions, contents.instructions);
+        assert_eq!(opened.wallets.len(), 1);
+        assert!(open_recovery_package(&package, b"wrong passphrase").is_err());
+    }
+}
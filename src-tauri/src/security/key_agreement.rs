@@ -0,0 +1,68 @@
+use curve25519_dalek::constants::X25519_BASEPOINT;
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use curve25519_dalek::scalar::Scalar;
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+fn clamp_scalar(mut bytes: [u8; 32]) -> Scalar {
+    bytes[0] &= 248;
+    bytes[31] &= 127;
+    bytes[31] |= 64;
+    Scalar::from_bits(bytes)
+}
+
+/// X25519 public key for a raw 32-byte secret.
+pub(crate) fn x25519_public(secret: &[u8; 32]) -> [u8; 32] {
+    (&X25519_BASEPOINT * &clamp_scalar(*secret)).to_bytes()
+}
+
+/// X25519 shared secret between our `secret` and the peer's public key. A
+/// low-order peer key yields all zeros, which callers that bind keys to a
+/// peer identity should reject.
+pub(crate) fn x25519_shared(secret: &[u8; 32], peer_public: &[u8; 32]) -> [u8; 32] {
+    (&MontgomeryPoint(*peer_public) * &clamp_scalar(*secret)).to_bytes()
+}
+
+/// 32-byte HKDF-SHA256 output key.
+pub(crate) fn hkdf_sha256(salt: Option<&[u8]>, ikm: &[u8], info: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(salt, ikm)
+        .expand(info, &mut key)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(hex: &str) -> [u8; 32] {
+        hex::decode(hex).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn matches_rfc7748_vectors() {
+        let alice = bytes("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let bob = bytes("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+        let alice_public = x25519_public(&alice);
+        let bob_public = x25519_public(&bob);
+
+        assert_eq!(
+            alice_public,
+            bytes("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a")
+        );
+        assert_eq!(
+            bob_public,
+            bytes("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")
+        );
+
+        let shared = bytes("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+        assert_eq!(x25519_shared(&alice, &bob_public), shared);
+        assert_eq!(x25519_shared(&bob, &alice_public), shared);
+    }
+
+    #[test]
+    fn low_order_peer_yields_zero_secret() {
+        assert_eq!(x25519_shared(&[7u8; 32], &[0u8; 32]), [0u8; 32]);
+    }
+}
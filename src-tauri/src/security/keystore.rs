@@ -1,6 +1,7 @@
 use std::{
     collections::HashMap,
     fs,
+    panic::Location,
     path::PathBuf,
     sync::{Mutex, MutexGuard},
 };
@@ -13,10 +14,16 @@ use aes_gcm::{
 use argon2::{password_hash::SaltString, Algorithm, Argon2, Params, Version};
 use base64::{engine::general_purpose::STANDARD as BASE64_ENGINE, Engine};
 use chrono::{DateTime, Utc};
+use crate::errors::AppError;
+use crate::profiles::{keyring_account, ProfilePaths};
+use super::keystore_access::{
+    subsystem_from_location, KeystoreAccessEvent, KeystoreAccessKind, KeystoreAccessLog,
+    KeystoreAccessReport,
+};
 use keyring::Entry;
 use rand_core::{OsRng, RngCore};
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 use zeroize::{Zeroize, Zeroizing};
 
 const KEYRING_SERVICE: &str = "EclipseMarketPro";
@@ -51,6 +58,16 @@ pub enum KeystoreError {
     SerializationError,
 }
 
+impl From<KeystoreError> for AppError {
+    fn from(err: KeystoreError) -> Self {
+        match err {
+            KeystoreError::Io(err) => AppError::Io(err),
+            KeystoreError::Serialization(err) => AppError::Serialization(err),
+            err => AppError::Generic(err.to_string()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StoredSecret {
@@ -92,6 +109,8 @@ pub struct KeystoreBackup {
 pub struct Keystore {
     path: PathBuf,
     document: Mutex<KeystoreDocument>,
+    access_log: Mutex<KeystoreAccessLog>,
+    app: AppHandle,
 }
 
 impl Keystore {
@@ -107,10 +126,20 @@ impl Keystore {
         Ok(Self {
             path,
             document: Mutex::new(document),
+            access_log: Mutex::new(KeystoreAccessLog::default()),
+            app: app.clone(),
         })
     }
 
+    #[track_caller]
     pub fn store_secret(&self, key: &str, secret: &[u8]) -> Result<(), KeystoreError> {
+        let caller = Location::caller();
+        let result = self.write_secret(key, secret);
+        self.record_access(key, KeystoreAccessKind::Write, result.is_ok(), caller);
+        result
+    }
+
+    fn write_secret(&self, key: &str, secret: &[u8]) -> Result<(), KeystoreError> {
         let mut guard = self.lock_document()?;
         let mut salt = [0u8; SALT_LEN];
         let mut nonce = [0u8; NONCE_LEN];
@@ -148,7 +177,15 @@ impl Keystore {
         persist_document(&self.path, &guard)
     }
 
+    #[track_caller]
     pub fn retrieve_secret(&self, key: &str) -> Result<Zeroizing<Vec<u8>>, KeystoreError> {
+        let caller = Location::caller();
+        let result = self.read_secret(key);
+        self.record_access(key, KeystoreAccessKind::Read, result.is_ok(), caller);
+        result
+    }
+
+    fn read_secret(&self, key: &str) -> Result<Zeroizing<Vec<u8>>, KeystoreError> {
         let guard = self.lock_document()?;
         let entry = guard.secrets.get(key).ok_or(KeystoreError::NotFound)?;
 
@@ -173,15 +210,27 @@ impl Keystore {
         Ok(Zeroizing::new(plaintext))
     }
 
+    #[track_caller]
     pub fn remove_secret(&self, key: &str) -> Result<(), KeystoreError> {
+        let caller = Location::caller();
         let mut guard = self.lock_document()?;
         if guard.secrets.remove(key).is_some() {
-            persist_document(&self.path, &guard)?;
+            let result = persist_document(&self.path, &guard);
+            drop(guard);
+            self.record_access(key, KeystoreAccessKind::Remove, result.is_ok(), caller);
+            result?;
         }
         Ok(())
     }
 
+    #[track_caller]
     pub fn export_backup(&self, password: &str) -> Result<KeystoreBackup, KeystoreError> {
+        self.record_access(
+            "*",
+            KeystoreAccessKind::ExportBackup,
+            true,
+            Location::caller(),
+        );
         let guard = self.lock_document()?;
         let mut document = guard.clone();
         document.exported_at = Some(Utc::now());
@@ -208,11 +257,24 @@ impl Keystore {
         })
     }
 
+    #[track_caller]
     pub fn import_backup(
         &self,
         password: &str,
         backup: KeystoreBackup,
     ) -> Result<(), KeystoreError> {
+        let caller = Location::caller();
+        let result = self.restore_backup(password, backup);
+        self.record_access(
+            "*",
+            KeystoreAccessKind::ImportBackup,
+            result.is_ok(),
+            caller,
+        );
+        result
+    }
+
+    fn restore_backup(&self, password: &str, backup: KeystoreBackup) -> Result<(), KeystoreError> {
         if backup.version != KEYSTORE_VERSION {
             return Err(KeystoreError::Decryption);
         }
@@ -246,7 +308,20 @@ impl Keystore {
         Ok(())
     }
 
+    #[track_caller]
     pub fn rotate_master_key(&self) -> Result<(), KeystoreError> {
+        let caller = Location::caller();
+        let result = self.reencrypt_with_new_master_key();
+        self.record_access(
+            "*",
+            KeystoreAccessKind::RotateMasterKey,
+            result.is_ok(),
+            caller,
+        );
+        result
+    }
+
+    fn reencrypt_with_new_master_key(&self) -> Result<(), KeystoreError> {
         let old_master_key = Self::master_key()?;
 
         let mut new_key = Zeroizing::new(vec![0u8; 32]);
@@ -300,7 +375,7 @@ impl Keystore {
         guard.secrets = updated_entries;
         persist_document(&self.path, &guard)?;
 
-        let entry = Entry::new(KEYRING_SERVICE, MASTER_KEY_ID)?;
+        let entry = Entry::new(KEYRING_SERVICE, &keyring_account(MASTER_KEY_ID))?;
         entry.set_password(&new_key_b64)?;
 
         Ok(())
@@ -311,12 +386,48 @@ impl Keystore {
         Ok(guard.secrets.keys().cloned().collect())
     }
 
+    pub fn access_report(
+        &self,
+        key: Option<&str>,
+        limit: usize,
+    ) -> Result<KeystoreAccessReport, KeystoreError> {
+        let log = self
+            .access_log
+            .lock()
+            .map_err(|_| KeystoreError::LockError)?;
+        Ok(log.report(key, limit))
+    }
+
+    fn record_access(
+        &self,
+        key: &str,
+        kind: KeystoreAccessKind,
+        success: bool,
+        caller: &Location<'_>,
+    ) {
+        let event = KeystoreAccessEvent {
+            key: key.to_string(),
+            subsystem: subsystem_from_location(caller),
+            kind,
+            success,
+            timestamp: Utc::now(),
+        };
+        let alert = match self.access_log.lock() {
+            Ok(mut log) => log.record(event),
+            Err(_) => return,
+        };
+        if let Some(alert) = alert {
+            eprintln!("Keystore access anomaly: {}", alert.message);
+            let _ = self.app.emit("keystore_access_anomaly", &alert);
+        }
+    }
+
     fn lock_document(&self) -> Result<MutexGuard<'_, KeystoreDocument>, KeystoreError> {
         self.document.lock().map_err(|_| KeystoreError::Internal)
     }
 
     fn master_key() -> Result<Zeroizing<Vec<u8>>, KeystoreError> {
-        let entry = Entry::new(KEYRING_SERVICE, MASTER_KEY_ID)?;
+        let entry = Entry::new(KEYRING_SERVICE, &keyring_account(MASTER_KEY_ID))?;
         match entry.get_password() {
             Ok(value) => {
                 let decoded = BASE64_ENGINE
@@ -366,7 +477,7 @@ fn keystore_path(app: &AppHandle) -> Result<PathBuf, KeystoreError> {
     let app_handle = app.clone();
     let mut path = app_handle
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|_| KeystoreError::Internal)?;
     if !path.exists() {
         fs::create_dir_all(&path)?;
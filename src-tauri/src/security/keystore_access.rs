@@ -0,0 +1,373 @@
+use std::collections::{HashMap, VecDeque};
+use std::panic::Location;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::keystore::Keystore;
+
+const ACCESS_LOG_CAPACITY: usize = 2_000;
+const ALERT_HISTORY_LIMIT: usize = 100;
+const POST_UPDATE_WINDOW_SECS: i64 = 30;
+const POST_UPDATE_READ_THRESHOLD: usize = 5;
+const READ_BURST_WINDOW_SECS: i64 = 10;
+const READ_BURST_THRESHOLD: usize = 25;
+const FAILED_READ_WINDOW_SECS: i64 = 60;
+const FAILED_READ_THRESHOLD: usize = 5;
+const ALERT_COOLDOWN_SECS: i64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeystoreAccessKind {
+    Read,
+    Write,
+    Remove,
+    ExportBackup,
+    ImportBackup,
+    RotateMasterKey,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeystoreAccessEvent {
+    /// Secret id, or `*` for operations that touch the whole keystore.
+    pub key: String,
+    /// Module that called into the keystore, e.g. `api_config` or `wallet::multi_wallet`.
+    pub subsystem: String,
+    pub kind: KeystoreAccessKind,
+    pub success: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KeystoreAnomalyKind {
+    ReadBurstAfterUpdate,
+    ReadBurst,
+    RepeatedFailedReads,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeystoreAccessAlert {
+    pub key: String,
+    pub kind: KeystoreAnomalyKind,
+    pub subsystems: Vec<String>,
+    pub count: usize,
+    pub message: String,
+    pub detected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretAccessSummary {
+    pub key: String,
+    pub subsystem: String,
+    pub reads: usize,
+    pub failed_reads: usize,
+    pub last_read: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeystoreAccessReport {
+    pub events: Vec<KeystoreAccessEvent>,
+    pub summary: Vec<SecretAccessSummary>,
+    pub alerts: Vec<KeystoreAccessAlert>,
+}
+
+/// Maps a caller's source file to a module path: `src/wallet/multi_wallet.rs`
+/// becomes `wallet::multi_wallet` and `src/api_config/mod.rs` becomes `api_config`.
+pub fn subsystem_from_location(location: &Location<'_>) -> String {
+    let path = location.file().replace('\\', "/");
+    let path = path
+        .rsplit_once("src/")
+        .map(|(_, rest)| rest)
+        .unwrap_or(&path);
+    let path = path.strip_suffix(".rs").unwrap_or(path);
+    let path = path.strip_suffix("/mod").unwrap_or(path);
+    path.replace('/', "::")
+}
+
+/// In-memory record of keystore activity for the current session. Only
+/// secret ids are kept; secret values never reach this log.
+#[derive(Default)]
+pub struct KeystoreAccessLog {
+    events: VecDeque<KeystoreAccessEvent>,
+    alerts: VecDeque<KeystoreAccessAlert>,
+}
+
+impl KeystoreAccessLog {
+    /// Appends an event and returns any anomaly it completes.
+    pub fn record(&mut self, event: KeystoreAccessEvent) -> Option<KeystoreAccessAlert> {
+        let now = event.timestamp;
+        let key = event.key.clone();
+        let is_read = event.kind == KeystoreAccessKind::Read;
+
+        if self.events.len() >= ACCESS_LOG_CAPACITY {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+
+        if !is_read {
+            return None;
+        }
+
+        let alert = self.detect(&key, now)?;
+        if self.alerts.len() >= ALERT_HISTORY_LIMIT {
+            self.alerts.pop_front();
+        }
+        self.alerts.push_back(alert.clone());
+        Some(alert)
+    }
+
+    fn detect(&self, key: &str, now: DateTime<Utc>) -> Option<KeystoreAccessAlert> {
+        let reads_since = |since: DateTime<Utc>, success: bool| -> Vec<&KeystoreAccessEvent> {
+            self.events
+                .iter()
+                .filter(|e| {
+                    e.key == key
+                        && e.kind == KeystoreAccessKind::Read
+                        && e.success == success
+                        && e.timestamp >= since
+                })
+                .collect()
+        };
+
+        let failed = reads_since(now - Duration::seconds(FAILED_READ_WINDOW_SECS), false);
+        if failed.len() >= FAILED_READ_THRESHOLD {
+            return self.alert(
+                key,
+                KeystoreAnomalyKind::RepeatedFailedReads,
+                &failed,
+                now,
+                format!("{} failed reads of {key} in the last minute", failed.len()),
+            );
+        }
+
+        let last_write = self
+            .events
+            .iter()
+            .rev()
+            .find(|e| e.key == key && e.kind == KeystoreAccessKind::Write && e.success)
+            .map(|e| e.timestamp);
+        if let Some(written_at) = last_write {
+            if now - written_at <= Duration::seconds(POST_UPDATE_WINDOW_SECS) {
+                let reads = reads_since(written_at, true);
+                if reads.len() >= POST_UPDATE_READ_THRESHOLD {
+                    return self.alert(
+                        key,
+                        KeystoreAnomalyKind::ReadBurstAfterUpdate,
+                        &reads,
+                        now,
+                        format!(
+                            "{key} was read {} times within {POST_UPDATE_WINDOW_SECS}s of being updated",
+                            reads.len()
+                        ),
+                    );
+                }
+            }
+        }
+
+        let reads = reads_since(now - Duration::seconds(READ_BURST_WINDOW_SECS), true);
+        if reads.len() >= READ_BURST_THRESHOLD {
+            return self.alert(
+                key,
+                KeystoreAnomalyKind::ReadBurst,
+                &reads,
+                now,
+                format!(
+                    "{key} was read {} times in {READ_BURST_WINDOW_SECS}s",
+                    reads.len()
+                ),
+            );
+        }
+
+        None
+    }
+
+    fn alert(
+        &self,
+        key: &str,
+        kind: KeystoreAnomalyKind,
+        events: &[&KeystoreAccessEvent],
+        now: DateTime<Utc>,
+        message: String,
+    ) -> Option<KeystoreAccessAlert> {
+        let cooling_down = self.alerts.iter().any(|a| {
+            a.key == key
+                && a.kind == kind
+                && now - a.detected_at < Duration::seconds(ALERT_COOLDOWN_SECS)
+        });
+        if cooling_down {
+            return None;
+        }
+
+        let mut subsystems: Vec<String> = events.iter().map(|e| e.subsystem.clone()).collect();
+        subsystems.sort();
+        subsystems.dedup();
+
+        Some(KeystoreAccessAlert {
+            key: key.to_string(),
+            kind,
+            subsystems,
+            count: events.len(),
+            message,
+            detected_at: now,
+        })
+    }
+
+    pub fn report(&self, key: Option<&str>, limit: usize) -> KeystoreAccessReport {
+        let matches = |k: &str| key.map_or(true, |key| key == k);
+
+        let events = self
+            .events
+            .iter()
+            .rev()
+            .filter(|e| matches(&e.key))
+            .take(limit)
+            .cloned()
+            .collect();
+
+        let mut by_reader: HashMap<(String, String), SecretAccessSummary> = HashMap::new();
+        for event in self
+            .events
+            .iter()
+            .filter(|e| e.kind == KeystoreAccessKind::Read && matches(&e.key))
+        {
+            let entry = by_reader
+                .entry((event.key.clone(), event.subsystem.clone()))
+                .or_insert_with(|| SecretAccessSummary {
+                    key: event.key.clone(),
+                    subsystem: event.subsystem.clone(),
+                    reads: 0,
+                    failed_reads: 0,
+                    last_read: event.timestamp,
+                });
+            if event.success {
+                entry.reads += 1;
+            } else {
+                entry.failed_reads += 1;
+            }
+            entry.last_read = entry.last_read.max(event.timestamp);
+        }
+        let mut summary: Vec<SecretAccessSummary> = by_reader.into_values().collect();
+        summary.sort_by(|a, b| a.key.cmp(&b.key).then(a.subsystem.cmp(&b.subsystem)));
+
+        let alerts = self
+            .alerts
+            .iter()
+            .rev()
+            .filter(|a| matches(&a.key))
+            .cloned()
+            .collect();
+
+        KeystoreAccessReport {
+            events,
+            summary,
+            alerts,
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_keystore_access_log(
+    key: Option<String>,
+    limit: Option<usize>,
+    keystore: State<'_, Keystore>,
+) -> Result<KeystoreAccessReport, String> {
+    keystore
+        .access_report(key.as_deref(), limit.unwrap_or(200))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(
+        key: &str,
+        subsystem: &str,
+        kind: KeystoreAccessKind,
+        success: bool,
+        timestamp: DateTime<Utc>,
+    ) -> KeystoreAccessEvent {
+        KeystoreAccessEvent {
+            key: key.to_string(),
+            subsystem: subsystem.to_string(),
+            kind,
+            success,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn alerts_once_on_read_burst_after_update() {
+        let mut log = KeystoreAccessLog::default();
+        let start = Utc::now();
+        log.record(event(
+            "api_key_helius",
+            "api_config",
+            KeystoreAccessKind::Write,
+            true,
+            start,
+        ));
+
+        let mut alerts = Vec::new();
+        for i in 0..8 {
+            let at = start + Duration::seconds(i + 1);
+            let subsystem = if i % 2 == 0 {
+                "api_config"
+            } else {
+                "chains::rpc_pool"
+            };
+            if let Some(alert) = log.record(event(
+                "api_key_helius",
+                subsystem,
+                KeystoreAccessKind::Read,
+                true,
+                at,
+            )) {
+                alerts.push(alert);
+            }
+        }
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, KeystoreAnomalyKind::ReadBurstAfterUpdate);
+        assert_eq!(alerts[0].count, POST_UPDATE_READ_THRESHOLD);
+        assert_eq!(alerts[0].subsystems, vec!["api_config", "chains::rpc_pool"]);
+    }
+
+    #[test]
+    fn summarises_reads_per_subsystem() {
+        let mut log = KeystoreAccessLog::default();
+        let now = Utc::now();
+        log.record(event(
+            "a",
+            "wallet::multi_wallet",
+            KeystoreAccessKind::Read,
+            true,
+            now,
+        ));
+        log.record(event(
+            "a",
+            "wallet::multi_wallet",
+            KeystoreAccessKind::Read,
+            false,
+            now,
+        ));
+        log.record(event("a", "auth", KeystoreAccessKind::Read, true, now));
+        log.record(event("b", "auth", KeystoreAccessKind::Read, true, now));
+
+        let report = log.report(Some("a"), 10);
+        assert_eq!(report.events.len(), 3);
+        assert_eq!(report.summary.len(), 2);
+        let wallet = report
+            .summary
+            .iter()
+            .find(|s| s.subsystem == "wallet::multi_wallet")
+            .unwrap();
+        assert_eq!((wallet.reads, wallet.failed_reads), (1, 1));
+    }
+}
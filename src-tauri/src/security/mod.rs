@@ -7,10 +7,14 @@ pub mod tx_simulator;
 pub mod ledger;
 
 // Export existing security modules
+pub mod key_agreement;
 pub mod keystore;
+pub mod keystore_access;
 pub mod audit;
+pub mod phishing;
 pub mod activity_log;
 pub mod reputation;
+pub mod dead_man_switch;
 
 pub use types::*;
 pub use audit_logger::AuditLogger;
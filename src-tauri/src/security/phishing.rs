@@ -0,0 +1,639 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, State};
+
+use super::audit::Severity;
+use crate::api_config::stored_helius_key;
+use crate::environment::{active_environment, NetworkEnvironment};
+use crate::profiles::ProfilePaths;
+use crate::security::keystore::Keystore;
+
+const BLOCKLIST_FILE: &str = "phishing_blocklist.json";
+
+/// Official mints of widely held tokens that scam launches copy.
+const TOP_TOKENS: &[(&str, &str, &str)] = &[
+    (
+        "SOL",
+        "Wrapped SOL",
+        "So11111111111111111111111111111111111111112",
+    ),
+    (
+        "USDC",
+        "USD Coin",
+        "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+    ),
+    (
+        "USDT",
+        "Tether USD",
+        "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB",
+    ),
+    (
+        "JUP",
+        "Jupiter",
+        "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN",
+    ),
+    (
+        "BONK",
+        "Bonk",
+        "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263",
+    ),
+    (
+        "WIF",
+        "dogwifhat",
+        "EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm",
+    ),
+    (
+        "RAY",
+        "Raydium",
+        "4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R",
+    ),
+    (
+        "PYTH",
+        "Pyth Network",
+        "HZ1JovNiVvGrGNiiYvEozEVgZ58xaU3RKwX8eACQBCt3",
+    ),
+    ("JTO", "Jito", "jtojtomepa8beP8AuQc6eXt5FriJwfFMwQx2v2f9mCL"),
+    (
+        "ORCA",
+        "Orca",
+        "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE",
+    ),
+    (
+        "mSOL",
+        "Marinade staked SOL",
+        "mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So",
+    ),
+    (
+        "JitoSOL",
+        "Jito Staked SOL",
+        "J1toso1uCk3RLmjorhTtrVwY9HJ7X8V9yYac6Y7kGCPn",
+    ),
+];
+
+/// Domains of the wallets, DEXes and explorers most often cloned by
+/// phishing kits. Subdomains of these are trusted too.
+const OFFICIAL_DOMAINS: &[&str] = &[
+    "solana.com",
+    "phantom.app",
+    "solflare.com",
+    "backpack.app",
+    "jup.ag",
+    "raydium.io",
+    "orca.so",
+    "meteora.ag",
+    "magiceden.io",
+    "tensor.trade",
+    "pump.fun",
+    "marinade.finance",
+    "jito.network",
+    "birdeye.so",
+    "dexscreener.com",
+    "solscan.io",
+];
+
+const DRAINER_KEYWORDS: &[&str] = &[
+    "airdrop",
+    "claim",
+    "claims",
+    "reward",
+    "rewards",
+    "freemint",
+    "connect",
+    "verify",
+    "validate",
+    "walletsync",
+    "rectify",
+    "migrate",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UrlVerdict {
+    Safe,
+    Suspicious,
+    Blocked,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlSafetyCheck {
+    pub url: String,
+    pub domain: Option<String>,
+    pub verdict: UrlVerdict,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProtectionWarningKind {
+    TokenImpersonation,
+    LookalikeCharacters,
+    PhishingLink,
+    SuspiciousLink,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtectionWarning {
+    pub kind: ProtectionWarningKind,
+    pub severity: Severity,
+    pub message: String,
+    /// The impersonated mint or the offending URL.
+    pub subject: String,
+}
+
+/// Folds characters that render like Latin letters onto them so `B0NK` and
+/// `USDС` (Cyrillic С) compare equal to the real symbols.
+fn skeleton(value: &str) -> String {
+    value
+        .chars()
+        .filter_map(|c| {
+            let c = match c {
+                '0' | 'о' | 'О' | 'ο' | 'Ο' => 'o',
+                '1' | 'І' | 'і' | 'ӏ' | 'ı' | '|' => 'l',
+                'а' | 'А' | 'α' | 'Α' => 'a',
+                'е' | 'Е' | 'ε' | 'Ε' => 'e',
+                'р' | 'Р' | 'ρ' | 'Ρ' => 'p',
+                'с' | 'С' | 'ϲ' => 'c',
+                'у' | 'У' => 'y',
+                'х' | 'Х' | 'χ' => 'x',
+                'ѕ' | 'Ѕ' | '5' | '$' => 's',
+                'ԁ' => 'd',
+                'т' | 'Т' | 'τ' => 't',
+                'к' | 'К' | 'κ' => 'k',
+                'м' | 'М' => 'm',
+                'н' | 'Н' => 'h',
+                'в' | 'В' => 'b',
+                'ν' => 'v',
+                other => other,
+            };
+            c.is_alphanumeric().then(|| c.to_ascii_lowercase())
+        })
+        .collect::<String>()
+        .replace('i', "l")
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Flags tokens whose symbol or name copies a top token while the mint
+/// differs from the official one.
+pub fn check_token_identity(mint: &str, symbol: &str, name: &str) -> Vec<ProtectionWarning> {
+    if TOP_TOKENS.iter().any(|(_, _, official)| *official == mint) {
+        return Vec::new();
+    }
+
+    let mut warnings = Vec::new();
+    let symbol_skeleton = skeleton(symbol.trim_start_matches('$'));
+    let name_skeleton = skeleton(name);
+
+    for (top_symbol, top_name, official_mint) in TOP_TOKENS {
+        let top_symbol_skeleton = skeleton(top_symbol);
+        let copies_symbol = !symbol_skeleton.is_empty() && symbol_skeleton == top_symbol_skeleton;
+        let copies_name = !name_skeleton.is_empty() && name_skeleton == skeleton(top_name);
+        let near_symbol = top_symbol_skeleton.len() >= 4
+            && edit_distance(&symbol_skeleton, &top_symbol_skeleton) == 1;
+
+        if copies_symbol || copies_name {
+            warnings.push(ProtectionWarning {
+                kind: ProtectionWarningKind::TokenImpersonation,
+                severity: Severity::High,
+                message: format!(
+                    "{symbol} ({name}) presents itself as {top_symbol} ({top_name}) but is not the official mint"
+                ),
+                subject: official_mint.to_string(),
+            });
+        } else if near_symbol {
+            warnings.push(ProtectionWarning {
+                kind: ProtectionWarningKind::TokenImpersonation,
+                severity: Severity::Medium,
+                message: format!("{symbol} is one character away from {top_symbol} ({top_name})"),
+                subject: official_mint.to_string(),
+            });
+        }
+    }
+
+    if !symbol.is_ascii() || !name.is_ascii() {
+        warnings.push(ProtectionWarning {
+            kind: ProtectionWarningKind::LookalikeCharacters,
+            severity: Severity::Medium,
+            message: format!(
+                "{symbol} ({name}) contains non-Latin characters that can mimic another token"
+            ),
+            subject: mint.to_string(),
+        });
+    }
+
+    warnings
+}
+
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{domain}"))
+}
+
+pub fn evaluate_url(url: &str, blocklist: &HashSet<String>) -> UrlSafetyCheck {
+    let mut check = UrlSafetyCheck {
+        url: url.to_string(),
+        domain: None,
+        verdict: UrlVerdict::Safe,
+        reasons: Vec::new(),
+    };
+    let flag = |check: &mut UrlSafetyCheck, verdict: UrlVerdict, reason: String| {
+        if verdict == UrlVerdict::Blocked || check.verdict == UrlVerdict::Safe {
+            check.verdict = verdict;
+        }
+        check.reasons.push(reason);
+    };
+
+    let parsed = match url::Url::parse(url.trim()) {
+        Ok(parsed) => parsed,
+        Err(_) => {
+            flag(
+                &mut check,
+                UrlVerdict::Blocked,
+                "Not a valid URL".to_string(),
+            );
+            return check;
+        }
+    };
+    match parsed.scheme() {
+        "https" => {}
+        "http" => flag(
+            &mut check,
+            UrlVerdict::Suspicious,
+            "Connection is not encrypted (http)".to_string(),
+        ),
+        other => {
+            flag(
+                &mut check,
+                UrlVerdict::Blocked,
+                format!("Links using the {other}: scheme are never opened"),
+            );
+            return check;
+        }
+    }
+    let Some(host) = parsed
+        .host_str()
+        .map(|h| h.trim_end_matches('.').to_lowercase())
+    else {
+        flag(
+            &mut check,
+            UrlVerdict::Blocked,
+            "URL has no host".to_string(),
+        );
+        return check;
+    };
+    check.domain = Some(host.clone());
+
+    if let Some(entry) = blocklist.iter().find(|d| domain_matches(&host, d)) {
+        flag(
+            &mut check,
+            UrlVerdict::Blocked,
+            format!("{entry} is on the phishing blocklist"),
+        );
+        return check;
+    }
+    if OFFICIAL_DOMAINS.iter().any(|d| domain_matches(&host, d)) {
+        return check;
+    }
+
+    let labels: Vec<&str> = host.split(['.', '-']).collect();
+    if labels.iter().any(|label| label.starts_with("xn--")) {
+        flag(
+            &mut check,
+            UrlVerdict::Suspicious,
+            "Domain uses internationalised characters that can imitate other sites".to_string(),
+        );
+    }
+
+    for official in OFFICIAL_DOMAINS {
+        let brand = official.split('.').next().unwrap_or(official);
+        for label in &labels {
+            if *label == brand {
+                flag(
+                    &mut check,
+                    UrlVerdict::Suspicious,
+                    format!("Uses the {brand} name but is not {official}"),
+                );
+            } else if skeleton(label) == skeleton(brand)
+                || (brand.len() >= 5 && edit_distance(label, brand) == 1)
+            {
+                flag(
+                    &mut check,
+                    UrlVerdict::Blocked,
+                    format!("{host} imitates {official}"),
+                );
+            }
+        }
+    }
+
+    let compact = host.replace(['-', '.'], "");
+    if let Some(keyword) = DRAINER_KEYWORDS
+        .iter()
+        .find(|keyword| compact.contains(**keyword))
+    {
+        flag(
+            &mut check,
+            UrlVerdict::Suspicious,
+            format!("Domain contains \"{keyword}\", common on wallet-drainer sites"),
+        );
+    }
+
+    check
+}
+
+fn warning_for(check: &UrlSafetyCheck) -> Option<ProtectionWarning> {
+    let (kind, severity) = match check.verdict {
+        UrlVerdict::Safe => return None,
+        UrlVerdict::Suspicious => (ProtectionWarningKind::SuspiciousLink, Severity::Medium),
+        UrlVerdict::Blocked => (ProtectionWarningKind::PhishingLink, Severity::Critical),
+    };
+    Some(ProtectionWarning {
+        kind,
+        severity,
+        message: format!(
+            "Token metadata links to {}: {}",
+            check.domain.as_deref().unwrap_or(&check.url),
+            check.reasons.join("; ")
+        ),
+        subject: check.url.clone(),
+    })
+}
+
+fn collect_links(value: &Value, links: &mut Vec<String>) {
+    match value {
+        Value::String(s) if s.starts_with("http://") || s.starts_with("https://") => {
+            links.push(s.clone())
+        }
+        Value::Object(map) => map.values().for_each(|v| collect_links(v, links)),
+        Value::Array(items) => items.iter().for_each(|v| collect_links(v, links)),
+        _ => {}
+    }
+}
+
+/// Website and social links from a token's on-chain metadata and its
+/// off-chain JSON, via Helius DAS. Image and animation URLs are skipped.
+async fn fetch_metadata_links(helius_key: &str, mint: &str) -> Vec<String> {
+    let client = reqwest::Client::new();
+    let timeout = std::time::Duration::from_secs(8);
+    let asset: Value = match client
+        .post(format!(
+            "https://mainnet.helius-rpc.com/?api-key={helius_key}"
+        ))
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getAsset",
+            "params": { "id": mint }
+        }))
+        .timeout(timeout)
+        .send()
+        .await
+    {
+        Ok(response) => response.json().await.unwrap_or(Value::Null),
+        Err(_) => return Vec::new(),
+    };
+
+    let content = &asset["result"]["content"];
+    let mut links = Vec::new();
+    if let Some(external) = content["links"]["external_url"].as_str() {
+        links.push(external.to_string());
+    }
+
+    if let Some(json_uri) = content["json_uri"].as_str().filter(|uri| !uri.is_empty()) {
+        if let Ok(response) = client.get(json_uri).timeout(timeout).send().await {
+            if let Ok(metadata) = response.json::<Value>().await {
+                collect_links(&metadata["external_url"], &mut links);
+                collect_links(&metadata["extensions"], &mut links);
+            }
+        }
+    }
+
+    links.sort();
+    links.dedup();
+    links
+}
+
+pub struct PhishingProtection {
+    path: PathBuf,
+    blocklist: Mutex<HashSet<String>>,
+}
+
+impl PhishingProtection {
+    pub fn new(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .profile_data_dir()
+            .map(|dir| dir.join(BLOCKLIST_FILE))
+            .unwrap_or_else(|_| PathBuf::from(BLOCKLIST_FILE));
+        let blocklist = fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            blocklist: Mutex::new(blocklist),
+        }
+    }
+
+    pub fn check_url(&self, url: &str) -> UrlSafetyCheck {
+        let blocklist = self.blocklist.lock().map(|b| b.clone()).unwrap_or_default();
+        evaluate_url(url, &blocklist)
+    }
+
+    /// Identity heuristics plus a check of every link in the token's
+    /// metadata. Links are only fetched on mainnet with a Helius key.
+    pub async fn check_token(
+        &self,
+        keystore: &Keystore,
+        mint: &str,
+        symbol: &str,
+        name: &str,
+    ) -> Vec<ProtectionWarning> {
+        let mut warnings = check_token_identity(mint, symbol, name);
+        if active_environment().environment != NetworkEnvironment::Mainnet {
+            return warnings;
+        }
+        if let Some(key) = stored_helius_key(keystore) {
+            for link in fetch_metadata_links(&key, mint).await {
+                warnings.extend(warning_for(&self.check_url(&link)));
+            }
+        }
+        warnings
+    }
+
+    pub fn list(&self) -> Vec<String> {
+        let mut domains: Vec<String> = self
+            .blocklist
+            .lock()
+            .map(|b| b.iter().cloned().collect())
+            .unwrap_or_default();
+        domains.sort();
+        domains
+    }
+
+    pub fn set_blocked(&self, domain: &str, blocked: bool) -> Result<(), String> {
+        let domain = domain
+            .trim()
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .trim_end_matches('/')
+            .to_lowercase();
+        if domain.is_empty() || domain.contains('/') || !domain.contains('.') {
+            return Err("Expected a bare domain such as example.com".to_string());
+        }
+
+        let mut blocklist = self.blocklist.lock().map_err(|e| e.to_string())?;
+        if blocked {
+            blocklist.insert(domain);
+        } else {
+            blocklist.remove(&domain);
+        }
+        let serialized = serde_json::to_string_pretty(&*blocklist).map_err(|e| e.to_string())?;
+        fs::write(&self.path, serialized).map_err(|e| e.to_string())
+    }
+}
+
+fn open_in_browser(url: &str) -> std::io::Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = std::process::Command::new("explorer");
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let mut command = std::process::Command::new("xdg-open");
+
+    command.arg(url).spawn().map(|_| ())
+}
+
+#[tauri::command]
+pub async fn check_url_safety(
+    url: String,
+    protection: State<'_, PhishingProtection>,
+) -> Result<UrlSafetyCheck, String> {
+    Ok(protection.check_url(&url))
+}
+
+#[tauri::command]
+pub async fn check_token_impersonation(
+    mint: String,
+    symbol: String,
+    name: String,
+    protection: State<'_, PhishingProtection>,
+    keystore: State<'_, Keystore>,
+) -> Result<Vec<ProtectionWarning>, String> {
+    Ok(protection
+        .check_token(&keystore, &mint, &symbol, &name)
+        .await)
+}
+
+/// Opens a token's website in the system browser. Blocked domains are
+/// refused outright; suspicious ones need `confirmed` from the user.
+#[tauri::command]
+pub async fn open_token_website(
+    url: String,
+    confirmed: Option<bool>,
+    protection: State<'_, PhishingProtection>,
+) -> Result<UrlSafetyCheck, String> {
+    let check = protection.check_url(&url);
+    let refusal = match check.verdict {
+        UrlVerdict::Blocked => Some("Blocked opening"),
+        UrlVerdict::Suspicious if !confirmed.unwrap_or(false) => {
+            Some("Confirmation required to open")
+        }
+        _ => None,
+    };
+    if let Some(refusal) = refusal {
+        return Err(format!(
+            "{refusal} {}: {}",
+            check.domain.as_deref().unwrap_or(&url),
+            check.reasons.join("; ")
+        ));
+    }
+    open_in_browser(&url).map_err(|e| format!("Failed to open browser: {e}"))?;
+    Ok(check)
+}
+
+#[tauri::command]
+pub async fn phishing_blocklist_list(
+    protection: State<'_, PhishingProtection>,
+) -> Result<Vec<String>, String> {
+    Ok(protection.list())
+}
+
+#[tauri::command]
+pub async fn phishing_blocklist_set(
+    domain: String,
+    blocked: bool,
+    protection: State<'_, PhishingProtection>,
+) -> Result<Vec<String>, String> {
+    protection.set_blocked(&domain, blocked)?;
+    Ok(protection.list())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_symbol_copies_and_homoglyphs() {
+        let fake = "FakeMint1111111111111111111111111111111111";
+        let warnings = check_token_identity(fake, "USDС", "USD Coin");
+        assert!(warnings
+            .iter()
+            .any(|w| w.kind == ProtectionWarningKind::TokenImpersonation
+                && w.severity == Severity::High));
+        assert!(warnings
+            .iter()
+            .any(|w| w.kind == ProtectionWarningKind::LookalikeCharacters));
+
+        assert_eq!(
+            check_token_identity(fake, "B0NK", "Bonk Inu")[0].severity,
+            Severity::High
+        );
+        assert!(check_token_identity(
+            "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+            "USDC",
+            "USD Coin"
+        )
+        .is_empty());
+        assert!(check_token_identity(fake, "MEOW", "Cat Coin").is_empty());
+    }
+
+    #[test]
+    fn grades_urls() {
+        let blocklist = HashSet::from(["drainer.example".to_string()]);
+        let verdict = |url: &str| evaluate_url(url, &blocklist).verdict;
+
+        assert_eq!(verdict("https://jup.ag/swap"), UrlVerdict::Safe);
+        assert_eq!(verdict("https://docs.phantom.app"), UrlVerdict::Safe);
+        assert_eq!(verdict("https://mytoken.xyz"), UrlVerdict::Safe);
+        assert_eq!(
+            verdict("https://app.drainer.example/x"),
+            UrlVerdict::Blocked
+        );
+        assert_eq!(verdict("https://phant0m.app"), UrlVerdict::Blocked);
+        assert_eq!(verdict("https://solfIare.com"), UrlVerdict::Blocked);
+        assert_eq!(verdict("https://raydiums.io"), UrlVerdict::Blocked);
+        assert_eq!(verdict("javascript:alert(1)"), UrlVerdict::Blocked);
+        assert_eq!(verdict("https://jup-airdrop.io"), UrlVerdict::Suspicious);
+        assert_eq!(verdict("http://mytoken.xyz"), UrlVerdict::Suspicious);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::demo::SharedDemoModeState;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -340,7 +341,11 @@ pub async fn analyze_text_sentiment(text: String) -> Result<SentimentResult, Str
 pub async fn get_token_sentiment(
     token_address: String,
     manager: tauri::State<'_, SharedSentimentManager>,
+    demo: tauri::State<'_, SharedDemoModeState>,
 ) -> Result<Option<TokenSentiment>, String> {
+    if let Some(overlay) = &demo.read().await.overlay {
+        return Ok(overlay.sentiment.get_token_sentiment(&token_address));
+    }
     let mgr = manager.read().await;
     Ok(mgr.get_token_sentiment(&token_address))
 }
@@ -348,7 +353,11 @@ pub async fn get_token_sentiment(
 #[tauri::command]
 pub async fn get_all_token_sentiments(
     manager: tauri::State<'_, SharedSentimentManager>,
+    demo: tauri::State<'_, SharedDemoModeState>,
 ) -> Result<Vec<TokenSentiment>, String> {
+    if let Some(overlay) = &demo.read().await.overlay {
+        return Ok(overlay.sentiment.get_all_sentiments());
+    }
     let mgr = manager.read().await;
     Ok(mgr.get_all_sentiments())
 }
@@ -368,7 +377,11 @@ pub async fn ingest_social_data(
 pub async fn get_sentiment_alerts(
     token_address: Option<String>,
     manager: tauri::State<'_, SharedSentimentManager>,
+    demo: tauri::State<'_, SharedDemoModeState>,
 ) -> Result<Vec<SentimentAlert>, String> {
+    if let Some(overlay) = &demo.read().await.overlay {
+        return Ok(overlay.sentiment.get_alerts(token_address.as_deref()));
+    }
     let mgr = manager.read().await;
     Ok(mgr.get_alerts(token_address.as_deref()))
 }
@@ -395,7 +408,12 @@ pub async fn get_sentiment_alert_config(
 pub async fn dismiss_sentiment_alert(
     alert_id: String,
     manager: tauri::State<'_, SharedSentimentManager>,
+    demo: tauri::State<'_, SharedDemoModeState>,
 ) -> Result<(), String> {
+    if let Some(overlay) = demo.write().await.overlay.as_mut() {
+        overlay.sentiment.dismiss_alert(&alert_id);
+        return Ok(());
+    }
     let mut mgr = manager.write().await;
     mgr.dismiss_alert(&alert_id);
     Ok(())
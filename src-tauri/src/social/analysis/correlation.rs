@@ -0,0 +1,402 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::data::historical::HistoricalDataPoint;
+
+/// Fewer aligned pairs than this and a correlation is mostly noise.
+pub const MIN_CORRELATION_SAMPLES: usize = 10;
+pub const DEFAULT_MAX_LAG: i64 = 12;
+const BULLISH_THRESHOLD: f64 = 0.2;
+const BEARISH_THRESHOLD: f64 = -0.2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SentimentPriceCorrelationRequest {
+    pub token: String,
+    /// Symbol the price history is stored under, when it differs from the
+    /// token the social data is keyed by.
+    pub price_symbol: Option<String>,
+    /// One of the stored candle intervals (`5m`, `1h`, `4h`, `1d`, ...).
+    #[serde(default = "default_interval")]
+    pub interval: String,
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Largest lead/lag tested in either direction, in intervals.
+    pub max_lag: Option<i64>,
+}
+
+fn default_interval() -> String {
+    "1h".to_string()
+}
+
+/// Average sentiment of the posts scored within one interval.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SentimentBucket {
+    pub timestamp: i64,
+    pub avg_score: f64,
+    pub mentions: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlignedPoint {
+    pub timestamp: i64,
+    pub sentiment: Option<f64>,
+    pub mentions: i64,
+    pub price: Option<f64>,
+    /// Close-to-close change from the previous interval, in percent.
+    pub return_pct: Option<f64>,
+}
+
+/// Correlation between sentiment at `t` and the price return at
+/// `t + lag`. Positive lags mean sentiment leads price; negative lags mean
+/// price moves first and sentiment follows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LagCorrelation {
+    pub lag: i64,
+    pub correlation: f64,
+    pub samples: usize,
+    /// |t| above 2, roughly p < 0.05 for the sample sizes seen here.
+    pub significant: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SentimentRegime {
+    Bullish,
+    Neutral,
+    Bearish,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegimeBreakdown {
+    pub regime: SentimentRegime,
+    pub samples: usize,
+    /// Mean return over the interval after sentiment was in this regime.
+    pub avg_next_return_pct: f64,
+    /// Share of intervals where price then moved the way sentiment
+    /// pointed. Not defined for the neutral regime.
+    pub hit_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SentimentPriceCorrelation {
+    pub token: String,
+    pub price_symbol: String,
+    pub interval: String,
+    pub series: Vec<AlignedPoint>,
+    pub lags: Vec<LagCorrelation>,
+    pub best_lag: Option<LagCorrelation>,
+    pub regimes: Vec<RegimeBreakdown>,
+    pub verdict: String,
+}
+
+pub(super) fn interval_seconds(interval: &str) -> Option<i64> {
+    match interval {
+        "1m" => Some(60),
+        "5m" => Some(300),
+        "15m" => Some(900),
+        "1h" => Some(3600),
+        "4h" => Some(14_400),
+        "1d" => Some(86_400),
+        _ => None,
+    }
+}
+
+/// Puts sentiment buckets and candles on one grid of `step`-second
+/// intervals. Slots missing either side are kept so the chart shows gaps,
+/// and lags are measured in slots rather than array positions.
+pub fn align_series(
+    sentiment: &[SentimentBucket],
+    prices: &[HistoricalDataPoint],
+    step: i64,
+) -> Vec<AlignedPoint> {
+    let slot = |timestamp: i64| timestamp - timestamp.rem_euclid(step);
+    let mut grid: BTreeMap<i64, AlignedPoint> = BTreeMap::new();
+    let entry = |timestamp: i64| AlignedPoint {
+        timestamp,
+        sentiment: None,
+        mentions: 0,
+        price: None,
+        return_pct: None,
+    };
+
+    for bucket in sentiment {
+        let key = slot(bucket.timestamp);
+        let point = grid.entry(key).or_insert_with(|| entry(key));
+        point.sentiment = Some(bucket.avg_score);
+        point.mentions = bucket.mentions;
+    }
+    for candle in prices {
+        let key = slot(candle.timestamp);
+        let point = grid.entry(key).or_insert_with(|| entry(key));
+        point.price = Some(candle.close);
+    }
+
+    let closes: BTreeMap<i64, f64> = grid
+        .iter()
+        .filter_map(|(ts, p)| p.price.map(|price| (*ts, price)))
+        .collect();
+    for (ts, point) in grid.iter_mut() {
+        let (Some(price), Some(previous)) = (point.price, closes.get(&(ts - step))) else {
+            continue;
+        };
+        if *previous > 0.0 {
+            point.return_pct = Some((price / previous - 1.0) * 100.0);
+        }
+    }
+
+    grid.into_values().collect()
+}
+
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    let n = pairs.len() as f64;
+    if pairs.len() < 2 {
+        return None;
+    }
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let mut cov = 0.0;
+    let mut var_x = 0.0;
+    let mut var_y = 0.0;
+    for (x, y) in pairs {
+        cov += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+        var_y += (y - mean_y).powi(2);
+    }
+    if var_x <= f64::EPSILON || var_y <= f64::EPSILON {
+        return None;
+    }
+    Some(cov / (var_x.sqrt() * var_y.sqrt()))
+}
+
+fn is_significant(correlation: f64, samples: usize) -> bool {
+    if samples < MIN_CORRELATION_SAMPLES {
+        return false;
+    }
+    let denominator = (1.0 - correlation * correlation).max(f64::EPSILON);
+    let t = correlation * ((samples as f64 - 2.0) / denominator).sqrt();
+    t.abs() > 2.0
+}
+
+/// Sentiment at slot `i` paired with the return at slot `i + lag`.
+fn lagged_pairs(series: &[AlignedPoint], step: i64, lag: i64) -> Vec<(f64, f64)> {
+    let returns: BTreeMap<i64, f64> = series
+        .iter()
+        .filter_map(|p| p.return_pct.map(|r| (p.timestamp, r)))
+        .collect();
+    series
+        .iter()
+        .filter_map(|p| {
+            let sentiment = p.sentiment?;
+            let ret = returns.get(&(p.timestamp + lag * step))?;
+            Some((sentiment, *ret))
+        })
+        .collect()
+}
+
+pub fn lag_correlations(series: &[AlignedPoint], step: i64, max_lag: i64) -> Vec<LagCorrelation> {
+    (-max_lag..=max_lag)
+        .filter_map(|lag| {
+            let pairs = lagged_pairs(series, step, lag);
+            let correlation = pearson(&pairs)?;
+            Some(LagCorrelation {
+                lag,
+                correlation,
+                samples: pairs.len(),
+                significant: is_significant(correlation, pairs.len()),
+            })
+        })
+        .collect()
+}
+
+/// Strongest correlation among lags with enough samples to mean anything.
+pub fn best_lag(lags: &[LagCorrelation]) -> Option<LagCorrelation> {
+    lags.iter()
+        .filter(|l| l.samples >= MIN_CORRELATION_SAMPLES)
+        .max_by(|a, b| a.correlation.abs().total_cmp(&b.correlation.abs()))
+        .cloned()
+}
+
+fn regime_of(sentiment: f64) -> SentimentRegime {
+    if sentiment >= BULLISH_THRESHOLD {
+        SentimentRegime::Bullish
+    } else if sentiment <= BEARISH_THRESHOLD {
+        SentimentRegime::Bearish
+    } else {
+        SentimentRegime::Neutral
+    }
+}
+
+pub fn regime_breakdown(series: &[AlignedPoint], step: i64) -> Vec<RegimeBreakdown> {
+    let pairs = lagged_pairs(series, step, 1);
+    [
+        SentimentRegime::Bullish,
+        SentimentRegime::Neutral,
+        SentimentRegime::Bearish,
+    ]
+    .into_iter()
+    .filter_map(|regime| {
+        let returns: Vec<f64> = pairs
+            .iter()
+            .filter(|(sentiment, _)| regime_of(*sentiment) == regime)
+            .map(|(_, ret)| *ret)
+            .collect();
+        if returns.is_empty() {
+            return None;
+        }
+        let samples = returns.len();
+        let hits = match regime {
+            SentimentRegime::Bullish => Some(returns.iter().filter(|r| **r > 0.0).count()),
+            SentimentRegime::Bearish => Some(returns.iter().filter(|r| **r < 0.0).count()),
+            SentimentRegime::Neutral => None,
+        };
+        Some(RegimeBreakdown {
+            regime,
+            samples,
+            avg_next_return_pct: returns.iter().sum::<f64>() / samples as f64,
+            hit_rate: hits.map(|h| h as f64 / samples as f64),
+        })
+    })
+    .collect()
+}
+
+fn verdict(best: Option<&LagCorrelation>, interval: &str) -> String {
+    let Some(best) = best else {
+        return format!(
+            "Not enough overlapping sentiment and price data; at least {} aligned {} intervals are needed.",
+            MIN_CORRELATION_SAMPLES, interval
+        );
+    };
+    if !best.significant {
+        return format!(
+            "No reliable relationship: the strongest correlation ({:+.2} at lag {}) is within noise.",
+            best.correlation, best.lag
+        );
+    }
+    let direction = if best.correlation > 0.0 {
+        "in the same direction as"
+    } else {
+        "against"
+    };
+    match best.lag {
+        lag if lag > 0 => format!(
+            "Sentiment tends to lead price by {} x {}, which moves {} it (r = {:+.2}).",
+            lag, interval, direction, best.correlation
+        ),
+        lag if lag < 0 => format!(
+            "Price tends to lead sentiment by {} x {}; sentiment reacts to moves rather than predicting them (r = {:+.2}).",
+            -lag, interval, best.correlation
+        ),
+        _ => format!(
+            "Sentiment and price move together within the same {} interval (r = {:+.2}) but sentiment does not lead.",
+            interval, best.correlation
+        ),
+    }
+}
+
+pub fn analyze(
+    request: &SentimentPriceCorrelationRequest,
+    sentiment: &[SentimentBucket],
+    prices: &[HistoricalDataPoint],
+) -> Result<SentimentPriceCorrelation, String> {
+    let step = interval_seconds(&request.interval)
+        .ok_or_else(|| format!("Unsupported interval: {}", request.interval))?;
+    let max_lag = request.max_lag.unwrap_or(DEFAULT_MAX_LAG).clamp(0, 96);
+
+    let series = align_series(sentiment, prices, step);
+    let lags = lag_correlations(&series, step, max_lag);
+    let best = best_lag(&lags);
+    let regimes = regime_breakdown(&series, step);
+    let verdict = verdict(best.as_ref(), &request.interval);
+
+    Ok(SentimentPriceCorrelation {
+        token: request.token.clone(),
+        price_symbol: request
+            .price_symbol
+            .clone()
+            .unwrap_or_else(|| request.token.clone()),
+        interval: request.interval.clone(),
+        series,
+        lags,
+        best_lag: best,
+        regimes,
+        verdict,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(timestamp: i64, close: f64) -> HistoricalDataPoint {
+        HistoricalDataPoint {
+            timestamp,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_detects_sentiment_leading_price() {
+        let step = 3600;
+        // Sentiment alternates in a pattern the next interval's return copies.
+        let signs = [
+            1.0, -1.0, 1.0, 1.0, -1.0, -1.0, 1.0, -1.0, 1.0, 1.0, -1.0, 1.0, -1.0, -1.0,
+        ];
+        let mut sentiment = Vec::new();
+        let mut prices = vec![candle(0, 100.0)];
+        let mut close = 100.0;
+        for (i, sign) in signs.iter().enumerate() {
+            let ts = i as i64 * step;
+            sentiment.push(SentimentBucket {
+                timestamp: ts + 60,
+                avg_score: 0.5 * sign,
+                mentions: 3,
+            });
+            close *= 1.0 + 0.02 * sign;
+            prices.push(candle(ts + step, close));
+        }
+
+        let request = SentimentPriceCorrelationRequest {
+            token: "BONK".to_string(),
+            price_symbol: None,
+            interval: "1h".to_string(),
+            start_time: 0,
+            end_time: signs.len() as i64 * step,
+            max_lag: Some(3),
+        };
+        let result = analyze(&request, &sentiment, &prices).unwrap();
+        let best = result.best_lag.expect("best lag");
+        assert_eq!(best.lag, 1);
+        assert!(best.correlation > 0.99);
+        assert!(best.significant);
+
+        let bullish = result
+            .regimes
+            .iter()
+            .find(|r| r.regime == SentimentRegime::Bullish)
+            .unwrap();
+        assert_eq!(bullish.hit_rate, Some(1.0));
+    }
+
+    #[test]
+    fn test_sparse_overlap_yields_no_best_lag() {
+        let sentiment = vec![SentimentBucket {
+            timestamp: 0,
+            avg_score: 0.4,
+            mentions: 1,
+        }];
+        let prices = vec![candle(0, 1.0), candle(3600, 1.1)];
+        let series = align_series(&sentiment, &prices, 3600);
+        assert_eq!(series.len(), 2);
+        assert!(best_lag(&lag_correlations(&series, 3600, 2)).is_none());
+    }
+}
@@ -1,9 +1,14 @@
+pub mod correlation;
 pub mod gauges;
 pub mod influencer;
 pub mod sentiment_engine;
 pub mod service;
 pub mod trend_engine;
 
+pub use correlation::{
+    AlignedPoint, LagCorrelation, RegimeBreakdown, SentimentPriceCorrelation,
+    SentimentPriceCorrelationRequest, SentimentRegime,
+};
 pub use gauges::{GaugeEngine, GaugeReading};
 pub use influencer::{InfluencerEngine, InfluencerScore};
 pub use sentiment_engine::{LexiconEntry, SentimentEngine, SentimentSnapshot};
@@ -4,10 +4,15 @@ use sqlx::Row;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use super::correlation::{
+    analyze, interval_seconds, SentimentBucket, SentimentPriceCorrelation,
+    SentimentPriceCorrelationRequest,
+};
 use super::gauges::{GaugeEngine, GaugeReading};
 use super::influencer::{InfluencerEngine, InfluencerScore};
 use super::sentiment_engine::{SentimentEngine, SentimentSnapshot};
 use super::trend_engine::{TrendEngine, TrendRecord, DEFAULT_WINDOWS};
+use crate::data::historical::HistoricalDataPoint;
 use crate::social::cache::SocialCache;
 use crate::social::models::SocialPost;
 
@@ -248,4 +253,42 @@ impl SocialAnalysisService {
         let pool = self.cache.pool();
         Ok(self.gauge_engine.fetch_gauges(pool, token).await?)
     }
+
+    /// Buckets the stored per-post sentiment scores into the request's
+    /// interval and lines them up against `prices`.
+    pub async fn sentiment_price_correlation(
+        &self,
+        request: &SentimentPriceCorrelationRequest,
+        prices: &[HistoricalDataPoint],
+    ) -> Result<SentimentPriceCorrelation, AnalysisError> {
+        let step = interval_seconds(&request.interval).ok_or_else(|| {
+            AnalysisError::Internal(format!("Unsupported interval: {}", request.interval))
+        })?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT (timestamp / ?2) * ?2 AS bucket, AVG(score) AS avg_score, COUNT(*) AS mentions
+            FROM sentiment_scores
+            WHERE token = ?1 AND timestamp >= ?3 AND timestamp < ?4
+            GROUP BY bucket ORDER BY bucket ASC
+            "#,
+        )
+        .bind(&request.token)
+        .bind(step)
+        .bind(request.start_time)
+        .bind(request.end_time)
+        .fetch_all(self.cache.pool())
+        .await?;
+
+        let buckets: Vec<SentimentBucket> = rows
+            .into_iter()
+            .map(|r| SentimentBucket {
+                timestamp: r.try_get("bucket").unwrap_or(0),
+                avg_score: r.try_get("avg_score").unwrap_or(0.0),
+                mentions: r.try_get("mentions").unwrap_or(0),
+            })
+            .collect();
+
+        analyze(request, &buckets, prices).map_err(AnalysisError::Internal)
+    }
 }
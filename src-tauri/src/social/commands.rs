@@ -1,9 +1,12 @@
 use tauri::State;
 
+use crate::api::SharedRequestRegistry;
+use crate::data::historical::SharedHistoricalReplayManager;
 use crate::security::keystore::Keystore;
 
 use super::analysis::{
-    AnalysisSummary, GaugeReading, InfluencerScore, SentimentSnapshot as AnalysisSentimentSnapshot,
+    AnalysisSummary, GaugeReading, InfluencerScore, SentimentPriceCorrelation,
+    SentimentPriceCorrelationRequest, SentimentSnapshot as AnalysisSentimentSnapshot,
     SharedSocialAnalysisService, TrendRecord,
 };
 use super::cache::{MentionAggregate, TrendSnapshot};
@@ -16,11 +19,18 @@ pub async fn social_fetch_reddit(
     query: Option<String>,
     limit: Option<u32>,
     token: Option<String>,
+    request_id: Option<String>,
     service: State<'_, SharedSocialDataService>,
+    requests: State<'_, SharedRequestRegistry>,
 ) -> Result<SocialFetchResult, String> {
     let srv = service.read().await;
-    srv.fetch_reddit(&subreddit, query.as_deref(), limit, token.as_deref())
-        .await
+    requests
+        .track(
+            request_id.as_deref(),
+            "social_fetch_reddit",
+            srv.fetch_reddit(&subreddit, query.as_deref(), limit, token.as_deref()),
+        )
+        .await?
         .map_err(|e| e.to_string())
 }
 
@@ -30,12 +40,19 @@ pub async fn social_search_reddit_mentions(
     keyword: String,
     limit: Option<u32>,
     token: Option<String>,
+    request_id: Option<String>,
     service: State<'_, SharedSocialDataService>,
+    requests: State<'_, SharedRequestRegistry>,
 ) -> Result<Vec<SocialFetchResult>, String> {
     let srv = service.read().await;
     let subreddit_refs: Vec<&str> = subreddits.iter().map(|s| s.as_str()).collect();
-    srv.search_reddit_mentions(&subreddit_refs, &keyword, limit, token.as_deref())
-        .await
+    requests
+        .track(
+            request_id.as_deref(),
+            "social_search_reddit_mentions",
+            srv.search_reddit_mentions(&subreddit_refs, &keyword, limit, token.as_deref()),
+        )
+        .await?
         .map_err(|e| e.to_string())
 }
 
@@ -45,19 +62,26 @@ pub async fn social_fetch_twitter(
     max_results: Option<u32>,
     token: Option<String>,
     bearer_token_override: Option<String>,
+    request_id: Option<String>,
     service: State<'_, SharedSocialDataService>,
     keystore: State<'_, Keystore>,
+    requests: State<'_, SharedRequestRegistry>,
 ) -> Result<SocialFetchResult, String> {
     let srv = service.read().await;
-    srv.fetch_twitter(
-        &query,
-        max_results,
-        token.as_deref(),
-        bearer_token_override.as_deref(),
-        Some(&keystore),
-    )
-    .await
-    .map_err(|e| e.to_string())
+    requests
+        .track(
+            request_id.as_deref(),
+            "social_fetch_twitter",
+            srv.fetch_twitter(
+                &query,
+                max_results,
+                token.as_deref(),
+                bearer_token_override.as_deref(),
+                Some(&keystore),
+            ),
+        )
+        .await?
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -66,19 +90,26 @@ pub async fn social_fetch_twitter_user(
     max_results: Option<u32>,
     token: Option<String>,
     bearer_token_override: Option<String>,
+    request_id: Option<String>,
     service: State<'_, SharedSocialDataService>,
     keystore: State<'_, Keystore>,
+    requests: State<'_, SharedRequestRegistry>,
 ) -> Result<SocialFetchResult, String> {
     let srv = service.read().await;
-    srv.fetch_twitter_user(
-        &username,
-        max_results,
-        token.as_deref(),
-        bearer_token_override.as_deref(),
-        Some(&keystore),
-    )
-    .await
-    .map_err(|e| e.to_string())
+    requests
+        .track(
+            request_id.as_deref(),
+            "social_fetch_twitter_user",
+            srv.fetch_twitter_user(
+                &username,
+                max_results,
+                token.as_deref(),
+                bearer_token_override.as_deref(),
+                Some(&keystore),
+            ),
+        )
+        .await?
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -234,3 +265,38 @@ pub async fn social_get_fomo_fud(
         .await
         .map_err(|e| e.to_string())
 }
+
+/// Lines stored sentiment up with price history for one token and reports
+/// whether sentiment has led price moves, at which lag, and by regime.
+#[tauri::command]
+pub async fn social_sentiment_price_correlation(
+    request: SentimentPriceCorrelationRequest,
+    analysis_service: State<'_, SharedSocialAnalysisService>,
+    historical: State<'_, SharedHistoricalReplayManager>,
+) -> Result<SentimentPriceCorrelation, String> {
+    if request.end_time <= request.start_time {
+        return Err("end_time must be after start_time".to_string());
+    }
+    let symbol = request.price_symbol.as_deref().unwrap_or(&request.token);
+    let prices = historical
+        .read()
+        .await
+        .price_history(
+            symbol,
+            &request.interval,
+            request.start_time,
+            request.end_time,
+        )
+        .await?;
+    if prices.is_empty() {
+        return Err(format!(
+            "No stored {} price history for {}; fetch it in historical replay first",
+            request.interval, symbol
+        ));
+    }
+
+    let srv = analysis_service.read().await;
+    srv.sentiment_price_correlation(&request, &prices)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use crate::profiles::ProfilePaths;
 use tauri::{AppHandle, Manager};
 use tokio::sync::RwLock;
 
@@ -24,7 +25,7 @@ impl SocialDataService {
         let reddit_client = RedditClient::new().map_err(SocialError::from)?;
         let twitter_client = TwitterClient::new().map_err(SocialError::from)?;
 
-        let mut data_dir = app.path().app_data_dir().map_err(|err| {
+        let mut data_dir = app.path().profile_data_dir().map_err(|err| {
             SocialError::Internal("Failed to resolve app data directory".to_string())
         })?;
 
@@ -218,6 +218,85 @@ impl StockApiClient {
         Ok(self.generate_mock_earnings_calendar(30))
     }
 
+    /// Past earnings reports for `symbol`, newest first.
+    pub async fn fetch_earnings_history(&self, symbol: &str) -> Result<Vec<EarningsEvent>, String> {
+        if let Some(api_key) = &self.alpha_vantage_key {
+            return self.fetch_alpha_vantage_earnings_history(api_key, symbol).await;
+        }
+
+        Ok(self.generate_mock_earnings_history(symbol))
+    }
+
+    async fn fetch_alpha_vantage_earnings_history(
+        &self,
+        api_key: &str,
+        symbol: &str,
+    ) -> Result<Vec<EarningsEvent>, String> {
+        let url = format!(
+            "{}?function=EARNINGS&symbol={}&apikey={}",
+            ALPHA_VANTAGE_BASE_URL, symbol, api_key
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Alpha Vantage request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Alpha Vantage API error: {}", response.status()));
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct QuarterlyEarnings {
+            fiscal_date_ending: String,
+            reported_date: String,
+            #[serde(rename = "reportedEPS")]
+            reported_eps: Option<String>,
+            #[serde(rename = "estimatedEPS")]
+            estimated_eps: Option<String>,
+            surprise_percentage: Option<String>,
+            report_time: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct EarningsResponse {
+            #[serde(default)]
+            quarterly_earnings: Vec<QuarterlyEarnings>,
+        }
+
+        let data: EarningsResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Alpha Vantage response: {}", e))?;
+
+        let parse = |value: &Option<String>| value.as_deref().and_then(|v| v.parse::<f64>().ok());
+
+        Ok(data
+            .quarterly_earnings
+            .into_iter()
+            .map(|item| EarningsEvent {
+                symbol: symbol.to_string(),
+                name: symbol.to_string(),
+                date: item.reported_date.clone(),
+                time: match item.report_time.as_deref() {
+                    Some("pre-market") => EarningsTime::BeforeMarket,
+                    Some("post-market") => EarningsTime::AfterMarket,
+                    _ => EarningsTime::DuringMarket,
+                },
+                fiscal_quarter: item.fiscal_date_ending.clone(),
+                estimate_eps: parse(&item.estimated_eps),
+                actual_eps: parse(&item.reported_eps),
+                surprise_percent: parse(&item.surprise_percentage),
+                historical_reaction: None,
+                has_alert: false,
+            })
+            .collect())
+    }
+
     pub async fn fetch_stock_news(
         &self,
         symbol: &str,
@@ -332,7 +411,145 @@ impl StockApiClient {
         Ok(self.generate_mock_insider_activity(symbol))
     }
 
+    pub async fn fetch_daily_bars(
+        &self,
+        symbol: &str,
+        days: u32,
+    ) -> Result<Vec<DailyBar>, String> {
+        if let Some(api_key) = &self.polygon_key {
+            return self.fetch_polygon_daily_aggs(api_key, symbol, days).await;
+        }
+
+        Ok(self.generate_mock_daily_bars(days))
+    }
+
+    async fn fetch_polygon_daily_aggs(
+        &self,
+        api_key: &str,
+        symbol: &str,
+        days: u32,
+    ) -> Result<Vec<DailyBar>, String> {
+        let to = chrono::Utc::now();
+        let from = to - chrono::Duration::days(days as i64);
+        let url = format!(
+            "{}/v2/aggs/ticker/{}/range/1/day/{}/{}?adjusted=true&sort=asc&apiKey={}",
+            POLYGON_BASE_URL,
+            symbol,
+            from.format("%Y-%m-%d"),
+            to.format("%Y-%m-%d"),
+            api_key
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Polygon request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Polygon API error: {}", response.status()));
+        }
+
+        #[derive(Deserialize)]
+        struct PolygonAgg {
+            #[serde(rename = "t")]
+            timestamp_ms: i64,
+            #[serde(rename = "o")]
+            open: f64,
+            #[serde(rename = "h")]
+            high: f64,
+            #[serde(rename = "l")]
+            low: f64,
+            #[serde(rename = "c")]
+            close: f64,
+            #[serde(rename = "v")]
+            volume: f64,
+        }
+
+        #[derive(Deserialize)]
+        struct PolygonAggsResponse {
+            #[serde(default)]
+            results: Vec<PolygonAgg>,
+        }
+
+        let data: PolygonAggsResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Polygon response: {}", e))?;
+
+        Ok(data
+            .results
+            .into_iter()
+            .filter_map(|agg| {
+                chrono::DateTime::from_timestamp_millis(agg.timestamp_ms).map(|ts| DailyBar {
+                    date: ts.format("%Y-%m-%d").to_string(),
+                    open: agg.open,
+                    high: agg.high,
+                    low: agg.low,
+                    close: agg.close,
+                    volume: agg.volume,
+                })
+            })
+            .collect())
+    }
+
+    pub async fn fetch_options_activity(
+        &self,
+        symbol: &str,
+        days: u32,
+    ) -> Result<Vec<OptionsActivity>, String> {
+        // Options flow needs a paid Polygon tier; until that is wired up every
+        // provider falls back to the generated series.
+        Ok(self.generate_mock_options_activity(symbol, days))
+    }
+
     // Mock data generators for fallback
+    fn generate_mock_daily_bars(&self, days: u32) -> Vec<DailyBar> {
+        let today = chrono::Utc::now().date_naive();
+        (0..days as i64)
+            .rev()
+            .map(|offset| {
+                let wave = ((offset as f64) * 0.9).sin() * 0.08;
+                // The two sessions before today carry a build-up so the mock
+                // news published today has something to line up against.
+                let spike = match offset {
+                    1 => 2.6,
+                    2 => 1.9,
+                    _ => 1.0,
+                };
+                let close = 175.0 + wave * 20.0;
+                DailyBar {
+                    date: (today - chrono::Duration::days(offset)).to_string(),
+                    open: close - wave * 4.0,
+                    high: close * 1.012,
+                    low: close * 0.988,
+                    close,
+                    volume: 40_000_000.0 * (1.0 + wave) * spike,
+                }
+            })
+            .collect()
+    }
+
+    fn generate_mock_options_activity(&self, symbol: &str, days: u32) -> Vec<OptionsActivity> {
+        let today = chrono::Utc::now().date_naive();
+        (0..days as i64)
+            .rev()
+            .map(|offset| {
+                let call_boost = if offset == 1 { 3.4 } else { 1.0 };
+                OptionsActivity {
+                    symbol: symbol.to_string(),
+                    date: (today - chrono::Duration::days(offset)).to_string(),
+                    call_volume: 250_000.0 * call_boost,
+                    put_volume: 180_000.0,
+                    avg_call_volume: 250_000.0,
+                    avg_put_volume: 180_000.0,
+                    short_dated_otm_ratio: if offset == 1 { 0.62 } else { 0.18 },
+                }
+            })
+            .collect()
+    }
+
     fn generate_mock_trending_stocks(&self) -> Vec<TrendingStock> {
         vec![
             TrendingStock {
@@ -484,6 +701,31 @@ impl StockApiClient {
         ]
     }
 
+    fn generate_mock_earnings_history(&self, symbol: &str) -> Vec<EarningsEvent> {
+        let today = chrono::Utc::now().date_naive();
+        (1..=8i64)
+            .map(|quarter| {
+                let surprise = if quarter % 3 == 0 { -4.5 } else { 6.0 };
+                EarningsEvent {
+                    symbol: symbol.to_string(),
+                    name: symbol.to_string(),
+                    date: (today - chrono::Duration::days(quarter * 91)).to_string(),
+                    time: if quarter % 2 == 0 {
+                        EarningsTime::BeforeMarket
+                    } else {
+                        EarningsTime::AfterMarket
+                    },
+                    fiscal_quarter: format!("Q{}", (4 - (quarter - 1) % 4)),
+                    estimate_eps: Some(1.50),
+                    actual_eps: Some(1.50 * (1.0 + surprise / 100.0)),
+                    surprise_percent: Some(surprise),
+                    historical_reaction: None,
+                    has_alert: false,
+                }
+            })
+            .collect()
+    }
+
     fn generate_mock_stock_news(&self, symbol: &str, limit: usize) -> Vec<StockNews> {
         vec![
             StockNews {
@@ -1,7 +1,13 @@
 use super::api::StockApiClient;
+use super::earnings_reactions::{
+    compute_reactions, reminder_message, summarize, EarningsReactionStats, EarningsReminder,
+    DEFAULT_DRIFT_DAYS,
+};
+use super::insider_patterns::{InsiderPatternConfig, InsiderPatternDetector};
 use super::models::*;
+use crate::portfolio::SharedWatchlistManager;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use tokio::sync::RwLock;
 
 pub type SharedStockCache = Arc<RwLock<StockCache>>;
@@ -12,6 +18,8 @@ pub struct StockCache {
     ipos: Option<(Vec<NewIPO>, std::time::SystemTime)>,
     earnings: Option<(Vec<EarningsEvent>, std::time::SystemTime)>,
     news_cache: std::collections::HashMap<String, (Vec<StockNews>, std::time::SystemTime)>,
+    pattern_signals: std::collections::HashMap<String, Vec<InsiderPatternSignal>>,
+    alerts: Vec<StockAlert>,
 }
 
 impl Default for StockCache {
@@ -22,12 +30,17 @@ impl Default for StockCache {
             ipos: None,
             earnings: None,
             news_cache: std::collections::HashMap::new(),
+            pattern_signals: std::collections::HashMap::new(),
+            alerts: Vec::new(),
         }
     }
 }
 
 impl StockCache {
     const CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(60);
+    const MAX_ALERTS: usize = 200;
+    /// Roughly two years of sessions, enough for eight quarterly reports.
+    const EARNINGS_HISTORY_DAYS: u32 = 760;
 
     fn is_expired(timestamp: std::time::SystemTime) -> bool {
         std::time::SystemTime::now()
@@ -190,8 +203,213 @@ pub async fn create_stock_alert(
 }
 
 #[tauri::command]
-pub async fn get_stock_alerts(symbol: Option<String>) -> Result<Vec<StockAlert>, String> {
-    // This would fetch from alerts database
-    // For now, return empty
-    Ok(vec![])
+pub async fn get_stock_alerts(
+    cache: State<'_, SharedStockCache>,
+    symbol: Option<String>,
+) -> Result<Vec<StockAlert>, String> {
+    let cache_guard = cache.read().await;
+    Ok(cache_guard
+        .alerts
+        .iter()
+        .filter(|alert| symbol.as_ref().map_or(true, |s| &alert.symbol == s))
+        .cloned()
+        .collect())
+}
+
+/// Scans tracked tickers for unusual volume and options positioning ahead
+/// of news. Signals above the alert score are stored as stock alerts and
+/// emitted as `insider_pattern_alert` events.
+#[tauri::command]
+pub async fn scan_insider_patterns(
+    app: AppHandle,
+    cache: State<'_, SharedStockCache>,
+    symbols: Vec<String>,
+    config: Option<InsiderPatternConfig>,
+) -> Result<Vec<InsiderPatternSignal>, String> {
+    let config = config.unwrap_or_default();
+    let history_days = (config.lookback_days + config.baseline_days).max(1) as u32 + 5;
+    let detector = InsiderPatternDetector::new(config);
+    let client = StockApiClient::new(None, None, None, None);
+
+    let mut all_signals = Vec::new();
+    let mut new_alerts = Vec::new();
+
+    for symbol in symbols {
+        let symbol = symbol.to_uppercase();
+        let bars = client.fetch_daily_bars(&symbol, history_days).await?;
+        let options = client.fetch_options_activity(&symbol, history_days).await?;
+        let news = client.fetch_stock_news(&symbol, 50).await?;
+        let insiders = client.fetch_insider_activity(&symbol).await?;
+
+        let signals = detector.detect(&symbol, &bars, &options, &news, &insiders);
+        new_alerts.extend(detector.alerts_for(&signals));
+
+        cache
+            .write()
+            .await
+            .pattern_signals
+            .insert(symbol, signals.clone());
+        all_signals.extend(signals);
+    }
+
+    for alert in &new_alerts {
+        let _ = app.emit("insider_pattern_alert", alert);
+    }
+
+    let mut cache_guard = cache.write().await;
+    cache_guard.alerts.extend(new_alerts);
+    let overflow = cache_guard.alerts.len().saturating_sub(StockCache::MAX_ALERTS);
+    cache_guard.alerts.drain(..overflow);
+
+    all_signals.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(all_signals)
+}
+
+#[tauri::command]
+pub async fn get_insider_pattern_signals(
+    cache: State<'_, SharedStockCache>,
+    symbol: Option<String>,
+) -> Result<Vec<InsiderPatternSignal>, String> {
+    let cache_guard = cache.read().await;
+    let mut signals: Vec<InsiderPatternSignal> = match symbol {
+        Some(symbol) => cache_guard
+            .pattern_signals
+            .get(&symbol.to_uppercase())
+            .cloned()
+            .unwrap_or_default(),
+        None => cache_guard.pattern_signals.values().flatten().cloned().collect(),
+    };
+    signals.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(signals)
+}
+
+async fn resolve_symbols(
+    watchlists: &SharedWatchlistManager,
+    symbols: Option<Vec<String>>,
+    watchlist_id: Option<String>,
+) -> Result<Vec<String>, String> {
+    let mut resolved: Vec<String> = symbols.unwrap_or_default();
+    if let Some(id) = watchlist_id {
+        let watchlist = watchlists
+            .read()
+            .await
+            .get_watchlist(&id)
+            .await
+            .map_err(|e| e.to_string())?;
+        resolved.extend(watchlist.items.into_iter().map(|item| item.symbol));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    resolved.retain(|symbol| seen.insert(symbol.to_uppercase()));
+    Ok(resolved.into_iter().map(|s| s.to_uppercase()).collect())
+}
+
+async fn load_reaction_stats(
+    client: &StockApiClient,
+    symbol: &str,
+    drift_days: usize,
+) -> Result<EarningsReactionStats, String> {
+    let reports = client.fetch_earnings_history(symbol).await?;
+    let bars = client
+        .fetch_daily_bars(symbol, StockCache::EARNINGS_HISTORY_DAYS)
+        .await?;
+    let reactions = compute_reactions(&reports, &bars, drift_days);
+    Ok(summarize(symbol, reactions, drift_days))
+}
+
+/// Historical earnings-day gap, range, and post-earnings drift for the given
+/// symbols and/or the stocks on a watchlist.
+#[tauri::command]
+pub async fn get_earnings_reaction_stats(
+    watchlists: State<'_, SharedWatchlistManager>,
+    symbols: Option<Vec<String>>,
+    watchlist_id: Option<String>,
+    drift_days: Option<usize>,
+) -> Result<Vec<EarningsReactionStats>, String> {
+    let symbols = resolve_symbols(&watchlists, symbols, watchlist_id).await?;
+    let drift_days = drift_days.unwrap_or(DEFAULT_DRIFT_DAYS);
+    let client = StockApiClient::new(None, None, None, None);
+
+    let mut stats = Vec::with_capacity(symbols.len());
+    for symbol in symbols {
+        stats.push(load_reaction_stats(&client, &symbol, drift_days).await?);
+    }
+    Ok(stats)
+}
+
+/// Upcoming earnings for watched stocks with their historical reaction
+/// attached. New reminders are recorded as `EarningsUpcoming` stock alerts
+/// and emitted as `earnings_reminder` events.
+#[tauri::command]
+pub async fn get_pre_earnings_reminders(
+    app: AppHandle,
+    cache: State<'_, SharedStockCache>,
+    watchlists: State<'_, SharedWatchlistManager>,
+    symbols: Option<Vec<String>>,
+    watchlist_id: Option<String>,
+    days_ahead: Option<u32>,
+) -> Result<Vec<EarningsReminder>, String> {
+    let symbols = resolve_symbols(&watchlists, symbols, watchlist_id).await?;
+    let days_ahead = days_ahead.unwrap_or(7);
+    let client = StockApiClient::new(None, None, None, None);
+    let today = chrono::Utc::now().date_naive();
+
+    let calendar = client.fetch_earnings_calendar(days_ahead).await?;
+    let mut reminders = Vec::new();
+
+    for mut event in calendar {
+        if !symbols.iter().any(|s| s.eq_ignore_ascii_case(&event.symbol)) {
+            continue;
+        }
+        let Ok(date) = chrono::NaiveDate::parse_from_str(&event.date, "%Y-%m-%d") else {
+            continue;
+        };
+        let days_until = (date - today).num_days();
+        if days_until < 0 || days_until > days_ahead as i64 {
+            continue;
+        }
+
+        let stats = load_reaction_stats(&client, &event.symbol, DEFAULT_DRIFT_DAYS)
+            .await
+            .ok();
+        if let Some(reaction) = stats.as_ref().and_then(|s| s.to_historical_reaction()) {
+            event.historical_reaction = Some(reaction);
+        }
+        let message = reminder_message(&event, days_until, stats.as_ref());
+
+        reminders.push(EarningsReminder {
+            symbol: event.symbol.clone(),
+            event,
+            days_until,
+            stats,
+            message,
+        });
+    }
+
+    let mut cache_guard = cache.write().await;
+    for reminder in &reminders {
+        let already_sent = cache_guard.alerts.iter().any(|alert| {
+            matches!(alert.alert_type, StockAlertType::EarningsUpcoming)
+                && alert.symbol == reminder.symbol
+                && alert.data["event"]["date"] == reminder.event.date.as_str()
+        });
+        if already_sent {
+            continue;
+        }
+
+        let alert = StockAlert {
+            id: uuid::Uuid::new_v4().to_string(),
+            symbol: reminder.symbol.clone(),
+            alert_type: StockAlertType::EarningsUpcoming,
+            message: reminder.message.clone(),
+            triggered_at: chrono::Utc::now().to_rfc3339(),
+            data: serde_json::to_value(reminder).unwrap_or_default(),
+        };
+        let _ = app.emit("earnings_reminder", &alert);
+        cache_guard.alerts.push(alert);
+    }
+    let overflow = cache_guard.alerts.len().saturating_sub(StockCache::MAX_ALERTS);
+    cache_guard.alerts.drain(..overflow);
+
+    Ok(reminders)
 }
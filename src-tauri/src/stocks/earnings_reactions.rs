@@ -0,0 +1,287 @@
+use super::models::*;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_DRIFT_DAYS: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EarningsReaction {
+    pub report_date: String,
+    pub reaction_date: String,
+    pub time: EarningsTime,
+    pub surprise_percent: Option<f64>,
+    /// Reaction-day open vs. the prior close.
+    pub gap_percent: f64,
+    /// Reaction-day high-low range relative to the prior close.
+    pub intraday_range_percent: f64,
+    /// Reaction-day close vs. the prior close.
+    pub day_move_percent: f64,
+    /// Close `drift_days` sessions later vs. the reaction-day close.
+    pub drift_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EarningsReactionStats {
+    pub symbol: String,
+    pub sample_size: usize,
+    pub drift_days: usize,
+    pub avg_gap_percent: f64,
+    pub avg_abs_gap_percent: f64,
+    pub avg_intraday_range_percent: f64,
+    pub avg_abs_move_percent: f64,
+    pub avg_drift_percent: Option<f64>,
+    pub gap_up_ratio: f64,
+    pub beats: usize,
+    pub misses: usize,
+    pub avg_move_on_beat: Option<f64>,
+    pub avg_move_on_miss: Option<f64>,
+    pub reactions: Vec<EarningsReaction>,
+}
+
+impl EarningsReactionStats {
+    pub fn to_historical_reaction(&self) -> Option<HistoricalReaction> {
+        let last = self.reactions.first()?;
+        Some(HistoricalReaction {
+            avg_move_percent: self.avg_abs_move_percent,
+            last_reaction_percent: last.day_move_percent,
+            beat_miss_ratio: format!("{}/{}", self.beats, self.misses),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EarningsReminder {
+    pub symbol: String,
+    pub event: EarningsEvent,
+    pub days_until: i64,
+    pub stats: Option<EarningsReactionStats>,
+    pub message: String,
+}
+
+/// Builds the reaction for each past report that has price data around it.
+/// `bars` must be sorted by date ascending; reactions are returned newest
+/// first.
+pub fn compute_reactions(
+    reports: &[EarningsEvent],
+    bars: &[DailyBar],
+    drift_days: usize,
+) -> Vec<EarningsReaction> {
+    let dated: Vec<(NaiveDate, &DailyBar)> = bars
+        .iter()
+        .filter_map(|bar| parse_day(&bar.date).map(|date| (date, bar)))
+        .collect();
+
+    let mut reactions: Vec<EarningsReaction> = reports
+        .iter()
+        .filter_map(|report| {
+            let report_date = parse_day(&report.date)?;
+            // After-close reports are priced in on the next session.
+            let idx = dated.iter().position(|(date, _)| match report.time {
+                EarningsTime::AfterMarket => *date > report_date,
+                _ => *date >= report_date,
+            })?;
+            if idx == 0 {
+                return None;
+            }
+
+            let prev_close = dated[idx - 1].1.close;
+            let bar = dated[idx].1;
+            if prev_close <= 0.0 || bar.close <= 0.0 {
+                return None;
+            }
+
+            let drift_percent = dated
+                .get(idx + drift_days)
+                .filter(|_| drift_days > 0)
+                .map(|(_, later)| (later.close / bar.close - 1.0) * 100.0);
+
+            Some(EarningsReaction {
+                report_date: report.date.clone(),
+                reaction_date: bar.date.clone(),
+                time: report.time.clone(),
+                surprise_percent: report.surprise_percent,
+                gap_percent: (bar.open / prev_close - 1.0) * 100.0,
+                intraday_range_percent: (bar.high - bar.low) / prev_close * 100.0,
+                day_move_percent: (bar.close / prev_close - 1.0) * 100.0,
+                drift_percent,
+            })
+        })
+        .collect();
+
+    reactions.sort_by(|a, b| b.report_date.cmp(&a.report_date));
+    reactions
+}
+
+pub fn summarize(
+    symbol: &str,
+    reactions: Vec<EarningsReaction>,
+    drift_days: usize,
+) -> EarningsReactionStats {
+    let mean = |values: Vec<f64>| {
+        if values.is_empty() {
+            None
+        } else {
+            Some(values.iter().sum::<f64>() / values.len() as f64)
+        }
+    };
+
+    let gaps: Vec<f64> = reactions.iter().map(|r| r.gap_percent).collect();
+    let beat_moves: Vec<f64> = reactions
+        .iter()
+        .filter(|r| r.surprise_percent.map_or(false, |s| s > 0.0))
+        .map(|r| r.day_move_percent)
+        .collect();
+    let miss_moves: Vec<f64> = reactions
+        .iter()
+        .filter(|r| r.surprise_percent.map_or(false, |s| s < 0.0))
+        .map(|r| r.day_move_percent)
+        .collect();
+
+    EarningsReactionStats {
+        symbol: symbol.to_string(),
+        sample_size: reactions.len(),
+        drift_days,
+        avg_gap_percent: mean(gaps.clone()).unwrap_or(0.0),
+        avg_abs_gap_percent: mean(gaps.iter().map(|g| g.abs()).collect()).unwrap_or(0.0),
+        avg_intraday_range_percent: mean(
+            reactions.iter().map(|r| r.intraday_range_percent).collect(),
+        )
+        .unwrap_or(0.0),
+        avg_abs_move_percent: mean(reactions.iter().map(|r| r.day_move_percent.abs()).collect())
+            .unwrap_or(0.0),
+        avg_drift_percent: mean(reactions.iter().filter_map(|r| r.drift_percent).collect()),
+        gap_up_ratio: if gaps.is_empty() {
+            0.0
+        } else {
+            gaps.iter().filter(|g| **g > 0.0).count() as f64 / gaps.len() as f64
+        },
+        beats: beat_moves.len(),
+        misses: miss_moves.len(),
+        avg_move_on_beat: mean(beat_moves),
+        avg_move_on_miss: mean(miss_moves),
+        reactions,
+    }
+}
+
+pub fn reminder_message(
+    event: &EarningsEvent,
+    days_until: i64,
+    stats: Option<&EarningsReactionStats>,
+) -> String {
+    let when = match days_until {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        n => format!("in {} days", n),
+    };
+    match stats.filter(|s| s.sample_size > 0) {
+        Some(stats) => format!(
+            "{} reports earnings {} ({}). Last {} reports: avg move ±{:.1}%, avg gap {:+.1}%, beats/misses {}/{}",
+            event.symbol,
+            when,
+            event.fiscal_quarter,
+            stats.sample_size,
+            stats.avg_abs_move_percent,
+            stats.avg_gap_percent,
+            stats.beats,
+            stats.misses
+        ),
+        None => format!("{} reports earnings {} ({})", event.symbol, when, event.fiscal_quarter),
+    }
+}
+
+fn parse_day(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(date: &str, open: f64, high: f64, low: f64, close: f64) -> DailyBar {
+        DailyBar {
+            date: date.to_string(),
+            open,
+            high,
+            low,
+            close,
+            volume: 1_000_000.0,
+        }
+    }
+
+    fn report(date: &str, time: EarningsTime, surprise: f64) -> EarningsEvent {
+        EarningsEvent {
+            symbol: "ACME".to_string(),
+            name: "Acme".to_string(),
+            date: date.to_string(),
+            time,
+            fiscal_quarter: "Q1".to_string(),
+            estimate_eps: Some(1.0),
+            actual_eps: Some(1.0 + surprise / 100.0),
+            surprise_percent: Some(surprise),
+            historical_reaction: None,
+            has_alert: false,
+        }
+    }
+
+    fn bars() -> Vec<DailyBar> {
+        vec![
+            bar("2024-04-29", 99.0, 101.0, 98.0, 100.0),
+            bar("2024-04-30", 100.0, 101.0, 99.0, 100.0),
+            bar("2024-05-01", 108.0, 112.0, 106.0, 110.0),
+            bar("2024-05-02", 110.0, 111.0, 109.0, 111.0),
+            bar("2024-05-03", 111.0, 114.0, 110.0, 113.3),
+        ]
+    }
+
+    #[test]
+    fn after_market_reports_react_next_session() {
+        let reactions = compute_reactions(
+            &[report("2024-04-30", EarningsTime::AfterMarket, 8.0)],
+            &bars(),
+            2,
+        );
+
+        assert_eq!(reactions.len(), 1);
+        let reaction = &reactions[0];
+        assert_eq!(reaction.reaction_date, "2024-05-01");
+        assert!((reaction.gap_percent - 8.0).abs() < 1e-9);
+        assert!((reaction.intraday_range_percent - 6.0).abs() < 1e-9);
+        assert!((reaction.day_move_percent - 10.0).abs() < 1e-9);
+        assert!((reaction.drift_percent.unwrap() - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn summary_splits_beats_and_misses() {
+        let reactions = compute_reactions(
+            &[
+                report("2024-04-30", EarningsTime::AfterMarket, 8.0),
+                report("2024-05-02", EarningsTime::BeforeMarket, -3.0),
+            ],
+            &bars(),
+            0,
+        );
+        let stats = summarize("ACME", reactions, 0);
+
+        assert_eq!(stats.sample_size, 2);
+        assert_eq!((stats.beats, stats.misses), (1, 1));
+        assert_eq!(stats.reactions[0].report_date, "2024-05-02");
+        assert!(stats.avg_drift_percent.is_none());
+        assert_eq!(
+            stats.to_historical_reaction().unwrap().beat_miss_ratio,
+            "1/1"
+        );
+    }
+
+    #[test]
+    fn reports_without_prior_session_are_skipped() {
+        let reactions = compute_reactions(
+            &[report("2024-04-29", EarningsTime::BeforeMarket, 1.0)],
+            &bars(),
+            1,
+        );
+        assert!(reactions.is_empty());
+    }
+}
@@ -0,0 +1,402 @@
+use super::models::*;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsiderPatternConfig {
+    /// Trading days before a news item that are checked for positioning.
+    pub lookback_days: i64,
+    /// Days before the lookback window used to establish normal volume.
+    pub baseline_days: i64,
+    pub volume_z_threshold: f64,
+    /// Multiple of average call/put volume that counts as a skew.
+    pub options_ratio_threshold: f64,
+    pub otm_ratio_threshold: f64,
+    pub min_score: f64,
+    pub alert_score: f64,
+}
+
+impl Default for InsiderPatternConfig {
+    fn default() -> Self {
+        Self {
+            lookback_days: 5,
+            baseline_days: 20,
+            volume_z_threshold: 2.5,
+            options_ratio_threshold: 2.0,
+            otm_ratio_threshold: 0.45,
+            min_score: 30.0,
+            alert_score: 65.0,
+        }
+    }
+}
+
+pub struct InsiderPatternDetector {
+    config: InsiderPatternConfig,
+}
+
+impl InsiderPatternDetector {
+    pub fn new(config: InsiderPatternConfig) -> Self {
+        Self { config }
+    }
+
+    /// Scores activity in the days leading up to each medium or high impact
+    /// news item. Low impact news is ignored since it rarely moves price
+    /// enough for anyone to trade ahead of it.
+    pub fn detect(
+        &self,
+        symbol: &str,
+        bars: &[DailyBar],
+        options: &[OptionsActivity],
+        news: &[StockNews],
+        insiders: &[InsiderActivity],
+    ) -> Vec<InsiderPatternSignal> {
+        let mut signals: Vec<InsiderPatternSignal> = news
+            .iter()
+            .filter(|item| !matches!(item.impact_level, ImpactLevel::Low))
+            .filter_map(|item| self.score_news(symbol, item, bars, options, insiders))
+            .filter(|signal| signal.score >= self.config.min_score)
+            .collect();
+
+        signals.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        signals
+    }
+
+    pub fn alerts_for(&self, signals: &[InsiderPatternSignal]) -> Vec<StockAlert> {
+        signals
+            .iter()
+            .filter(|signal| signal.score >= self.config.alert_score)
+            .map(|signal| StockAlert {
+                id: Uuid::new_v4().to_string(),
+                symbol: signal.symbol.clone(),
+                alert_type: StockAlertType::InsiderPattern,
+                message: format!(
+                    "Unusual {} positioning in {} ahead of \"{}\" (score {:.0})",
+                    match signal.direction {
+                        Sentiment::Bullish => "bullish",
+                        Sentiment::Bearish => "bearish",
+                        Sentiment::Neutral => "directionless",
+                    },
+                    signal.symbol,
+                    signal.news_title.as_deref().unwrap_or("news"),
+                    signal.score
+                ),
+                triggered_at: signal.detected_at.clone(),
+                data: serde_json::to_value(signal).unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    fn score_news(
+        &self,
+        symbol: &str,
+        item: &StockNews,
+        bars: &[DailyBar],
+        options: &[OptionsActivity],
+        insiders: &[InsiderActivity],
+    ) -> Option<InsiderPatternSignal> {
+        let news_date = parse_date(&item.published_at)?;
+        let window_start = news_date - chrono::Duration::days(self.config.lookback_days);
+        let baseline_start = window_start - chrono::Duration::days(self.config.baseline_days);
+        let in_window = |date: NaiveDate| date >= window_start && date < news_date;
+
+        let mut indicators = Vec::new();
+        let mut bullish = 0.0;
+        let mut bearish = 0.0;
+
+        let baseline: Vec<f64> = bars
+            .iter()
+            .filter(|v| {
+                parse_date(&v.date)
+                    .map(|d| d >= baseline_start && d < window_start)
+                    .unwrap_or(false)
+            })
+            .map(|v| v.volume)
+            .collect();
+
+        let mut volume_component: f64 = 0.0;
+        if let Some((mean, std_dev)) = mean_std(&baseline) {
+            for day in bars {
+                let Some(date) = parse_date(&day.date) else {
+                    continue;
+                };
+                if !in_window(date) || std_dev <= f64::EPSILON {
+                    continue;
+                }
+                let z = (day.volume - mean) / std_dev;
+                if z >= self.config.volume_z_threshold {
+                    volume_component =
+                        volume_component.max(z / (self.config.volume_z_threshold * 2.0));
+                    indicators.push(PatternIndicator {
+                        kind: PatternIndicatorKind::VolumeSpike,
+                        date: day.date.clone(),
+                        value: day.volume,
+                        baseline: mean,
+                        description: format!("Volume {:.1} standard deviations above normal", z),
+                    });
+                }
+            }
+        }
+
+        let mut options_component: f64 = 0.0;
+        let mut otm_component: f64 = 0.0;
+        for day in options {
+            let Some(date) = parse_date(&day.date) else {
+                continue;
+            };
+            if !in_window(date) {
+                continue;
+            }
+
+            let call_ratio = ratio(day.call_volume, day.avg_call_volume);
+            let put_ratio = ratio(day.put_volume, day.avg_put_volume);
+            let threshold = self.config.options_ratio_threshold;
+
+            if call_ratio >= threshold && call_ratio > put_ratio {
+                options_component = options_component.max(call_ratio / (threshold * 2.0));
+                bullish += call_ratio;
+                indicators.push(PatternIndicator {
+                    kind: PatternIndicatorKind::CallSkew,
+                    date: day.date.clone(),
+                    value: day.call_volume,
+                    baseline: day.avg_call_volume,
+                    description: format!("Call volume {:.1}x average", call_ratio),
+                });
+            } else if put_ratio >= threshold {
+                options_component = options_component.max(put_ratio / (threshold * 2.0));
+                bearish += put_ratio;
+                indicators.push(PatternIndicator {
+                    kind: PatternIndicatorKind::PutSkew,
+                    date: day.date.clone(),
+                    value: day.put_volume,
+                    baseline: day.avg_put_volume,
+                    description: format!("Put volume {:.1}x average", put_ratio),
+                });
+            }
+
+            if day.short_dated_otm_ratio >= self.config.otm_ratio_threshold {
+                otm_component = otm_component.max(day.short_dated_otm_ratio);
+                indicators.push(PatternIndicator {
+                    kind: PatternIndicatorKind::ShortDatedOtm,
+                    date: day.date.clone(),
+                    value: day.short_dated_otm_ratio,
+                    baseline: self.config.otm_ratio_threshold,
+                    description: format!(
+                        "{:.0}% of options volume in short-dated OTM contracts",
+                        day.short_dated_otm_ratio * 100.0
+                    ),
+                });
+            }
+        }
+
+        let mut insider_component: f64 = 0.0;
+        for trade in insiders {
+            let Some(date) = parse_date(&trade.transaction_date) else {
+                continue;
+            };
+            if !in_window(date) {
+                continue;
+            }
+            match trade.transaction_type {
+                TransactionType::Buy => bullish += 1.0,
+                TransactionType::Sell => bearish += 1.0,
+                _ => continue,
+            }
+            insider_component = 1.0;
+            indicators.push(PatternIndicator {
+                kind: PatternIndicatorKind::InsiderTrade,
+                date: trade.transaction_date.clone(),
+                value: trade.value,
+                baseline: 0.0,
+                description: format!(
+                    "{} ({}) traded {:.0} shares",
+                    trade.insider_name, trade.insider_title, trade.shares
+                ),
+            });
+        }
+
+        if indicators.is_empty() {
+            return None;
+        }
+
+        let impact_weight = match item.impact_level {
+            ImpactLevel::High => 1.0,
+            ImpactLevel::Medium => 0.8,
+            ImpactLevel::Low => 0.5,
+        };
+        let raw = volume_component.min(1.0) * 35.0
+            + options_component.min(1.0) * 30.0
+            + otm_component.min(1.0) * 20.0
+            + insider_component * 15.0;
+        let score = (raw * impact_weight).clamp(0.0, 100.0);
+
+        let direction = if bullish > bearish {
+            Sentiment::Bullish
+        } else if bearish > bullish {
+            Sentiment::Bearish
+        } else {
+            item.sentiment.clone()
+        };
+
+        let lead_days = indicators
+            .iter()
+            .filter_map(|indicator| parse_date(&indicator.date))
+            .min()
+            .map(|earliest| (news_date - earliest).num_days());
+
+        Some(InsiderPatternSignal {
+            id: Uuid::new_v4().to_string(),
+            symbol: symbol.to_string(),
+            score,
+            severity: severity_for(score),
+            direction,
+            indicators,
+            news_id: Some(item.id.clone()),
+            news_title: Some(item.title.clone()),
+            lead_days,
+            detected_at: Utc::now().to_rfc3339(),
+        })
+    }
+}
+
+fn severity_for(score: f64) -> PatternSeverity {
+    if score >= 70.0 {
+        PatternSeverity::High
+    } else if score >= 45.0 {
+        PatternSeverity::Medium
+    } else {
+        PatternSeverity::Low
+    }
+}
+
+fn ratio(value: f64, average: f64) -> f64 {
+    if average > 0.0 {
+        value / average
+    } else {
+        0.0
+    }
+}
+
+fn mean_std(values: &[f64]) -> Option<(f64, f64)> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance =
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    Some((mean, variance.sqrt()))
+}
+
+/// Accepts both RFC 3339 timestamps (news) and plain `YYYY-MM-DD` dates
+/// (aggregates and filings).
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.date_naive())
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(offset: i64) -> String {
+        (NaiveDate::from_ymd_opt(2024, 3, 1).unwrap() + chrono::Duration::days(offset)).to_string()
+    }
+
+    fn news(offset: i64, impact: ImpactLevel) -> StockNews {
+        StockNews {
+            id: "n1".to_string(),
+            symbol: "ACME".to_string(),
+            title: "ACME to be acquired".to_string(),
+            summary: String::new(),
+            ai_summary: None,
+            url: String::new(),
+            source: "Wire".to_string(),
+            published_at: format!("{}T13:30:00Z", day(offset)),
+            sentiment: Sentiment::Bullish,
+            impact_level: impact,
+            topics: vec![],
+        }
+    }
+
+    fn bars(spike_at: Option<i64>) -> Vec<DailyBar> {
+        (0..30)
+            .map(|i| DailyBar {
+                date: day(i),
+                open: 50.0,
+                high: 50.5,
+                low: 49.5,
+                close: 50.0,
+                volume: if Some(i) == spike_at {
+                    9_000_000.0
+                } else {
+                    1_000_000.0 + (i % 3) as f64 * 50_000.0
+                },
+            })
+            .collect()
+    }
+
+    fn call_sweep(offset: i64) -> OptionsActivity {
+        OptionsActivity {
+            symbol: "ACME".to_string(),
+            date: day(offset),
+            call_volume: 40_000.0,
+            put_volume: 5_000.0,
+            avg_call_volume: 8_000.0,
+            avg_put_volume: 5_000.0,
+            short_dated_otm_ratio: 0.7,
+        }
+    }
+
+    #[test]
+    fn flags_volume_and_call_buying_before_news() {
+        let detector = InsiderPatternDetector::new(InsiderPatternConfig::default());
+        let signals = detector.detect(
+            "ACME",
+            &bars(Some(27)),
+            &[call_sweep(28)],
+            &[news(29, ImpactLevel::High)],
+            &[],
+        );
+
+        assert_eq!(signals.len(), 1);
+        let signal = &signals[0];
+        assert!(signal.score >= 65.0, "score was {}", signal.score);
+        assert_eq!(signal.severity, PatternSeverity::High);
+        assert!(matches!(signal.direction, Sentiment::Bullish));
+        assert_eq!(signal.lead_days, Some(2));
+        assert_eq!(detector.alerts_for(&signals).len(), 1);
+    }
+
+    #[test]
+    fn activity_after_news_is_not_flagged() {
+        let detector = InsiderPatternDetector::new(InsiderPatternConfig::default());
+        let signals = detector.detect(
+            "ACME",
+            &bars(Some(29)),
+            &[call_sweep(29)],
+            &[news(29, ImpactLevel::High)],
+            &[],
+        );
+        assert!(signals.is_empty());
+    }
+
+    #[test]
+    fn low_impact_news_is_ignored() {
+        let detector = InsiderPatternDetector::new(InsiderPatternConfig::default());
+        let signals = detector.detect(
+            "ACME",
+            &bars(Some(27)),
+            &[call_sweep(28)],
+            &[news(29, ImpactLevel::Low)],
+            &[],
+        );
+        assert!(signals.is_empty());
+    }
+}
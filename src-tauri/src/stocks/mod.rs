@@ -1,6 +1,10 @@
 mod api;
 mod commands;
+mod earnings_reactions;
+mod insider_patterns;
 mod models;
 
 pub use commands::*;
+pub use earnings_reactions::*;
+pub use insider_patterns::*;
 pub use models::*;
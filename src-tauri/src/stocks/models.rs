@@ -200,5 +200,74 @@ pub enum StockAlertType {
     SignificantMove,
     WhaleActivity,
     InsiderActivity,
+    InsiderPattern,
     NewIPO,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyBar {
+    pub date: String,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionsActivity {
+    pub symbol: String,
+    pub date: String,
+    pub call_volume: f64,
+    pub put_volume: f64,
+    pub avg_call_volume: f64,
+    pub avg_put_volume: f64,
+    /// Share of volume in contracts that are out of the money and expire
+    /// within two weeks, the classic footprint of informed positioning.
+    pub short_dated_otm_ratio: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsiderPatternSignal {
+    pub id: String,
+    pub symbol: String,
+    pub score: f64,
+    pub severity: PatternSeverity,
+    pub direction: Sentiment,
+    pub indicators: Vec<PatternIndicator>,
+    pub news_id: Option<String>,
+    pub news_title: Option<String>,
+    pub lead_days: Option<i64>,
+    pub detected_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatternIndicator {
+    pub kind: PatternIndicatorKind,
+    pub date: String,
+    pub value: f64,
+    pub baseline: f64,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PatternIndicatorKind {
+    VolumeSpike,
+    CallSkew,
+    PutSkew,
+    ShortDatedOtm,
+    InsiderTrade,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternSeverity {
+    Low,
+    Medium,
+    High,
+}
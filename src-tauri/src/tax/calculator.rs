@@ -17,6 +17,21 @@ impl TaxCalculator {
         sale_price: f64,
         sale_amount: f64,
         sale_date: DateTime<Utc>,
+    ) -> Result<CapitalGain, String> {
+        self.calculate_capital_gain_with_transfer_fee(lot, sale_price, sale_amount, sale_date, 0.0)
+    }
+
+    /// Same as [`calculate_capital_gain`](Self::calculate_capital_gain) for
+    /// Token-2022 mints that withhold `transfer_fee_amount` tokens on the
+    /// sale. The full amount leaves the lot, but proceeds only cover what
+    /// the buyer received.
+    pub fn calculate_capital_gain_with_transfer_fee(
+        &self,
+        lot: &TaxLot,
+        sale_price: f64,
+        sale_amount: f64,
+        sale_date: DateTime<Utc>,
+        transfer_fee_amount: f64,
     ) -> Result<CapitalGain, String> {
         let acquired_date = lot
             .acquired_at
@@ -28,7 +43,8 @@ impl TaxCalculator {
 
         let unit_cost_basis = lot.cost_basis / lot.amount;
         let cost_basis = unit_cost_basis * sale_amount;
-        let proceeds = sale_price * sale_amount;
+        let received_amount = sale_amount - transfer_fee_amount.clamp(0.0, sale_amount);
+        let proceeds = sale_price * received_amount;
         let gain_loss = proceeds - cost_basis;
 
         let tax_rate = if is_long_term {
@@ -239,4 +255,30 @@ mod tests {
         assert!(gain.is_long_term);
         assert_eq!(gain.tax_rate, 0.20);
     }
+
+    #[test]
+    fn test_transfer_fee_reduces_proceeds() {
+        let calculator = TaxCalculator::new(TaxJurisdiction::us_federal());
+
+        let lot = TaxLot {
+            id: "test-2022".to_string(),
+            symbol: "FEE".to_string(),
+            mint: "FeeMint".to_string(),
+            amount: 100.0,
+            cost_basis: 1000.0,
+            price_per_unit: 10.0,
+            acquired_at: (Utc::now() - Duration::days(30)).to_rfc3339(),
+            disposed_amount: None,
+            disposed_at: None,
+            realized_gain: None,
+        };
+
+        let gain = calculator
+            .calculate_capital_gain_with_transfer_fee(&lot, 20.0, 100.0, Utc::now(), 2.0)
+            .unwrap();
+        assert_eq!(gain.amount, 100.0);
+        assert_eq!(gain.cost_basis, 1000.0);
+        assert_eq!(gain.proceeds, 1960.0);
+        assert_eq!(gain.gain_loss, 960.0);
+    }
 }
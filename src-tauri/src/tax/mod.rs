@@ -15,6 +15,7 @@ use uuid::Uuid;
 
 use crate::portfolio::{SharedTaxLotsState, TaxLot, TaxReportParams};
 use crate::security::keystore::Keystore;
+use crate::token_extensions::SharedTokenExtensionService;
 use tauri::State;
 
 use calculator::TaxCalculator;
@@ -113,6 +114,7 @@ pub async fn get_tax_center_summary(
     tax_year: Option<i32>,
     engine: State<'_, SharedTaxPlanningEngine>,
     tax_lot_state: State<'_, SharedTaxLotsState>,
+    token_extensions: State<'_, SharedTokenExtensionService>,
 ) -> Result<TaxCenterSummary, String> {
     let tax_year = tax_year.unwrap_or_else(|| Utc::now().year());
 
@@ -158,11 +160,24 @@ pub async fn get_tax_center_summary(
                 lot.price_per_unit
             };
 
-            realized_gains.push(calculator.calculate_capital_gain(
+            // Token-2022 transfer fees are withheld from the tokens sold, so
+            // they come out of proceeds rather than the disposed amount.
+            let transfer_fee = {
+                let mut extensions = token_extensions.write().await;
+                let epoch = extensions.current_epoch().await;
+                extensions
+                    .try_get_extensions(&lot.mint)
+                    .await
+                    .map(|ext| ext.fee_for_ui_amount(disposed_amount, epoch))
+                    .unwrap_or(0.0)
+            };
+
+            realized_gains.push(calculator.calculate_capital_gain_with_transfer_fee(
                 lot,
                 sale_price,
                 disposed_amount,
                 sale_date,
+                transfer_fee,
             )?);
         }
     }
@@ -0,0 +1,32 @@
+use super::{SharedTokenExtensionService, TokenExtensionReport, TransferAmountBreakdown};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_token_extensions(
+    mint: String,
+    service: State<'_, SharedTokenExtensionService>,
+) -> Result<TokenExtensionReport, String> {
+    let mut service = service.write().await;
+    let extensions = service
+        .get_extensions(&mint)
+        .await
+        .map_err(|e| e.to_string())?;
+    let epoch = service.current_epoch().await;
+
+    Ok(TokenExtensionReport::new(extensions, epoch))
+}
+
+#[tauri::command]
+pub async fn calculate_transfer_fee(
+    mint: String,
+    amount: u64,
+    service: State<'_, SharedTokenExtensionService>,
+) -> Result<TransferAmountBreakdown, String> {
+    let mut service = service.write().await;
+    let extensions = service
+        .get_extensions(&mint)
+        .await
+        .map_err(|e| e.to_string())?;
+    let epoch = service.current_epoch().await;
+    Ok(extensions.breakdown(amount, epoch))
+}
@@ -0,0 +1,107 @@
+pub mod commands;
+pub mod types;
+
+pub use commands::*;
+pub use types::*;
+
+use chrono::{DateTime, Duration, Utc};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::chains::{RoutingHint, RpcPool, SharedRpcPool};
+
+const CACHE_TTL_SECONDS: i64 = 600;
+const EPOCH_TTL_SECONDS: i64 = 300;
+
+pub type SharedTokenExtensionService = Arc<RwLock<TokenExtensionService>>;
+
+/// Resolves and caches Token-2022 extension data for mints so balances,
+/// swaps, safety checks and tax lots can account for transfer fees and
+/// hooks without hitting RPC on every call.
+pub struct TokenExtensionService {
+    rpc_pool: SharedRpcPool,
+    cache: HashMap<String, (MintExtensions, DateTime<Utc>)>,
+    epoch: Option<(u64, DateTime<Utc>)>,
+}
+
+impl TokenExtensionService {
+    pub fn new(rpc_pool: SharedRpcPool) -> Self {
+        Self {
+            rpc_pool,
+            cache: HashMap::new(),
+            epoch: None,
+        }
+    }
+
+    /// Extensions already resolved for `mint`, without touching RPC.
+    pub fn cached(&self, mint: &str) -> Option<MintExtensions> {
+        self.cache
+            .get(mint)
+            .map(|(extensions, _)| extensions.clone())
+    }
+
+    /// Last known epoch, used for picking the active transfer fee when no
+    /// fresh epoch is needed.
+    pub fn cached_epoch(&self) -> u64 {
+        self.epoch.map(|(epoch, _)| epoch).unwrap_or(0)
+    }
+
+    pub async fn get_extensions(
+        &mut self,
+        mint: &str,
+    ) -> Result<MintExtensions, TokenExtensionError> {
+        if let Some((extensions, fetched_at)) = self.cache.get(mint) {
+            if Utc::now() - *fetched_at < Duration::seconds(CACHE_TTL_SECONDS) {
+                return Ok(extensions.clone());
+            }
+        }
+
+        let pubkey: Pubkey = mint
+            .parse()
+            .map_err(|_| TokenExtensionError::InvalidAddress(mint.to_string()))?;
+        let account = RpcPool::call(&self.rpc_pool, RoutingHint::Read, move |client| {
+            client.get_account(&pubkey)
+        })
+        .await
+        .map_err(TokenExtensionError::Rpc)?;
+
+        let extensions = parse_mint_extensions(mint, &account.owner.to_string(), &account.data)?;
+        self.cache
+            .insert(mint.to_string(), (extensions.clone(), Utc::now()));
+        Ok(extensions)
+    }
+
+    /// Like [`get_extensions`](Self::get_extensions) but treats lookup
+    /// failures as "no extension data" so callers can degrade gracefully.
+    pub async fn try_get_extensions(&mut self, mint: &str) -> Option<MintExtensions> {
+        match self.get_extensions(mint).await {
+            Ok(extensions) => Some(extensions),
+            Err(err) => {
+                tracing::debug!("token extension lookup failed for {}: {}", mint, err);
+                None
+            }
+        }
+    }
+
+    pub async fn current_epoch(&mut self) -> u64 {
+        if let Some((epoch, fetched_at)) = self.epoch {
+            if Utc::now() - fetched_at < Duration::seconds(EPOCH_TTL_SECONDS) {
+                return epoch;
+            }
+        }
+
+        match RpcPool::call(&self.rpc_pool, RoutingHint::Read, |client| {
+            client.get_epoch_info()
+        })
+        .await
+        {
+            Ok(info) => {
+                self.epoch = Some((info.epoch, Utc::now()));
+                info.epoch
+            }
+            _ => self.cached_epoch(),
+        }
+    }
+}
@@ -0,0 +1,520 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// Size of the base SPL mint layout.
+const MINT_LEN: usize = 82;
+/// Token-2022 pads mints to the token account length so the account type
+/// byte sits at the same offset for both account kinds.
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const MAX_FEE_BASIS_POINTS: u64 = 10_000;
+
+// Extension type discriminants from the Token-2022 program.
+const EXT_TRANSFER_FEE_CONFIG: u16 = 1;
+const EXT_MINT_CLOSE_AUTHORITY: u16 = 3;
+const EXT_CONFIDENTIAL_TRANSFER_MINT: u16 = 4;
+const EXT_DEFAULT_ACCOUNT_STATE: u16 = 6;
+const EXT_NON_TRANSFERABLE: u16 = 9;
+const EXT_INTEREST_BEARING_CONFIG: u16 = 10;
+const EXT_PERMANENT_DELEGATE: u16 = 12;
+const EXT_TRANSFER_HOOK: u16 = 14;
+const EXT_CONFIDENTIAL_TRANSFER_FEE_CONFIG: u16 = 16;
+const EXT_METADATA_POINTER: u16 = 18;
+const EXT_TOKEN_METADATA: u16 = 19;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TokenExtensionError {
+    #[error("account is not owned by a token program: {0}")]
+    NotATokenMint(String),
+    #[error("malformed mint data: {0}")]
+    Malformed(String),
+    #[error("invalid mint address: {0}")]
+    InvalidAddress(String),
+    #[error("rpc error: {0}")]
+    Rpc(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenProgramKind {
+    Legacy,
+    Token2022,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferFee {
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub basis_points: u16,
+}
+
+impl TransferFee {
+    /// Fee withheld on a transfer of `amount` base units, rounded up the
+    /// same way the program does.
+    pub fn calculate(&self, amount: u64) -> u64 {
+        if self.basis_points == 0 || amount == 0 {
+            return 0;
+        }
+        let raw =
+            (amount as u128 * self.basis_points as u128).div_ceil(MAX_FEE_BASIS_POINTS as u128);
+        raw.min(self.maximum_fee as u128) as u64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferFeeConfig {
+    pub config_authority: Option<String>,
+    pub withdraw_withheld_authority: Option<String>,
+    pub withheld_amount: u64,
+    pub older: TransferFee,
+    pub newer: TransferFee,
+}
+
+impl TransferFeeConfig {
+    pub fn active_fee(&self, epoch: u64) -> &TransferFee {
+        if epoch >= self.newer.epoch {
+            &self.newer
+        } else {
+            &self.older
+        }
+    }
+
+    pub fn fee_for(&self, amount: u64, epoch: u64) -> u64 {
+        self.active_fee(epoch).calculate(amount)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DefaultAccountState {
+    Uninitialized,
+    Initialized,
+    Frozen,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MintExtensions {
+    pub mint: String,
+    pub program: TokenProgramKind,
+    pub decimals: u8,
+    pub freeze_authority: Option<String>,
+    pub transfer_fee: Option<TransferFeeConfig>,
+    pub transfer_hook_program: Option<String>,
+    pub default_account_state: Option<DefaultAccountState>,
+    pub confidential_transfers: bool,
+    pub permanent_delegate: Option<String>,
+    pub non_transferable: bool,
+    pub interest_rate_bps: Option<i16>,
+    pub mint_close_authority: Option<String>,
+    pub has_metadata: bool,
+    /// Extension discriminants this parser does not interpret.
+    pub unknown_extensions: Vec<u16>,
+}
+
+impl MintExtensions {
+    pub fn is_token_2022(&self) -> bool {
+        self.program == TokenProgramKind::Token2022
+    }
+
+    pub fn transfer_fee_bps(&self, epoch: u64) -> Option<u16> {
+        self.transfer_fee
+            .as_ref()
+            .map(|config| config.active_fee(epoch).basis_points)
+            .filter(|bps| *bps > 0)
+    }
+
+    pub fn breakdown(&self, amount: u64, epoch: u64) -> TransferAmountBreakdown {
+        let fee = self
+            .transfer_fee
+            .as_ref()
+            .map(|config| config.fee_for(amount, epoch))
+            .unwrap_or(0);
+        TransferAmountBreakdown {
+            gross_amount: amount,
+            fee_amount: fee,
+            net_amount: amount.saturating_sub(fee),
+            decimals: self.decimals,
+        }
+    }
+
+    /// Fee withheld on a transfer of `amount` whole tokens, in whole tokens.
+    pub fn fee_for_ui_amount(&self, amount: f64, epoch: u64) -> f64 {
+        let scale = 10f64.powi(self.decimals as i32);
+        let raw = (amount.max(0.0) * scale).round() as u64;
+        self.breakdown(raw, epoch).fee_amount as f64 / scale
+    }
+
+    /// Risks a holder takes on because of the mint's extensions.
+    pub fn risks(&self, epoch: u64) -> Vec<ExtensionRisk> {
+        let mut risks = Vec::new();
+
+        if self.non_transferable {
+            risks.push(ExtensionRisk {
+                extension: "nonTransferable".to_string(),
+                severity: ExtensionRiskSeverity::Critical,
+                description: "Token cannot be transferred or sold once received".to_string(),
+            });
+        }
+        if let Some(delegate) = &self.permanent_delegate {
+            risks.push(ExtensionRisk {
+                extension: "permanentDelegate".to_string(),
+                severity: ExtensionRiskSeverity::High,
+                description: format!(
+                    "{} can transfer or burn tokens from any holder at any time",
+                    delegate
+                ),
+            });
+        }
+        if let Some(program) = &self.transfer_hook_program {
+            risks.push(ExtensionRisk {
+                extension: "transferHook".to_string(),
+                severity: ExtensionRiskSeverity::High,
+                description: format!(
+                    "Every transfer invokes program {}, which can block or tax sells",
+                    program
+                ),
+            });
+        }
+        if let Some(config) = &self.transfer_fee {
+            let bps = config.active_fee(epoch).basis_points;
+            if bps > 0 {
+                risks.push(ExtensionRisk {
+                    extension: "transferFee".to_string(),
+                    severity: if bps >= 1_000 {
+                        ExtensionRiskSeverity::High
+                    } else if bps >= 100 {
+                        ExtensionRiskSeverity::Medium
+                    } else {
+                        ExtensionRiskSeverity::Low
+                    },
+                    description: format!(
+                        "{:.2}% of every transfer is withheld as a fee",
+                        bps as f64 / 100.0
+                    ),
+                });
+            }
+            if config.config_authority.is_some() && config.newer != config.older {
+                risks.push(ExtensionRisk {
+                    extension: "transferFee".to_string(),
+                    severity: ExtensionRiskSeverity::Medium,
+                    description: format!(
+                        "Transfer fee changes to {:.2}% at epoch {}",
+                        config.newer.basis_points as f64 / 100.0,
+                        config.newer.epoch
+                    ),
+                });
+            }
+        }
+        if self.default_account_state == Some(DefaultAccountState::Frozen) {
+            risks.push(ExtensionRisk {
+                extension: "defaultAccountState".to_string(),
+                severity: ExtensionRiskSeverity::Medium,
+                description: "New token accounts start frozen and must be thawed by the issuer"
+                    .to_string(),
+            });
+        }
+        if self.confidential_transfers {
+            risks.push(ExtensionRisk {
+                extension: "confidentialTransfer".to_string(),
+                severity: ExtensionRiskSeverity::Low,
+                description: "Balances and transfers may be encrypted and not visible on-chain"
+                    .to_string(),
+            });
+        }
+        if self.mint_close_authority.is_some() {
+            risks.push(ExtensionRisk {
+                extension: "mintCloseAuthority".to_string(),
+                severity: ExtensionRiskSeverity::Low,
+                description: "Mint account can be closed by its close authority".to_string(),
+            });
+        }
+
+        risks
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtensionRiskSeverity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionRisk {
+    pub extension: String,
+    pub severity: ExtensionRiskSeverity,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferAmountBreakdown {
+    pub gross_amount: u64,
+    pub fee_amount: u64,
+    pub net_amount: u64,
+    pub decimals: u8,
+}
+
+/// Extensions of a mint evaluated at a specific epoch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenExtensionReport {
+    pub extensions: MintExtensions,
+    pub epoch: u64,
+    pub transfer_fee_bps: Option<u16>,
+    pub risks: Vec<ExtensionRisk>,
+}
+
+impl TokenExtensionReport {
+    pub fn new(extensions: MintExtensions, epoch: u64) -> Self {
+        Self {
+            transfer_fee_bps: extensions.transfer_fee_bps(epoch),
+            risks: extensions.risks(epoch),
+            extensions,
+            epoch,
+        }
+    }
+}
+
+/// Parses the extensions of a mint from its raw account data. Legacy SPL
+/// mints parse to an empty extension set.
+pub fn parse_mint_extensions(
+    mint: &str,
+    owner: &str,
+    data: &[u8],
+) -> Result<MintExtensions, TokenExtensionError> {
+    let program = match owner {
+        TOKEN_PROGRAM_ID => TokenProgramKind::Legacy,
+        TOKEN_2022_PROGRAM_ID => TokenProgramKind::Token2022,
+        other => return Err(TokenExtensionError::NotATokenMint(other.to_string())),
+    };
+    if data.len() < MINT_LEN {
+        return Err(TokenExtensionError::Malformed(format!(
+            "mint data is {} bytes, expected at least {}",
+            data.len(),
+            MINT_LEN
+        )));
+    }
+
+    let mut extensions = MintExtensions {
+        mint: mint.to_string(),
+        program,
+        decimals: data[44],
+        freeze_authority: read_coption_pubkey(&data[46..82]),
+        transfer_fee: None,
+        transfer_hook_program: None,
+        default_account_state: None,
+        confidential_transfers: false,
+        permanent_delegate: None,
+        non_transferable: false,
+        interest_rate_bps: None,
+        mint_close_authority: None,
+        has_metadata: false,
+        unknown_extensions: Vec::new(),
+    };
+
+    if program == TokenProgramKind::Legacy || data.len() <= ACCOUNT_TYPE_OFFSET {
+        return Ok(extensions);
+    }
+    if data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_MINT {
+        return Err(TokenExtensionError::Malformed(
+            "account type is not a mint".to_string(),
+        ));
+    }
+
+    let mut offset = ACCOUNT_TYPE_OFFSET + 1;
+    while offset + 4 <= data.len() {
+        let ext_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let len = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let start = offset + 4;
+        let end = start + len;
+        if ext_type == 0 {
+            break;
+        }
+        if end > data.len() {
+            return Err(TokenExtensionError::Malformed(format!(
+                "extension {} overruns account data",
+                ext_type
+            )));
+        }
+        let value = &data[start..end];
+
+        match ext_type {
+            EXT_TRANSFER_FEE_CONFIG => {
+                extensions.transfer_fee = Some(parse_transfer_fee_config(value)?);
+            }
+            EXT_MINT_CLOSE_AUTHORITY => {
+                extensions.mint_close_authority = read_optional_pubkey(value, 0);
+            }
+            EXT_CONFIDENTIAL_TRANSFER_MINT | EXT_CONFIDENTIAL_TRANSFER_FEE_CONFIG => {
+                extensions.confidential_transfers = true;
+            }
+            EXT_DEFAULT_ACCOUNT_STATE => {
+                extensions.default_account_state = value.first().map(|state| match state {
+                    2 => DefaultAccountState::Frozen,
+                    1 => DefaultAccountState::Initialized,
+                    _ => DefaultAccountState::Uninitialized,
+                });
+            }
+            EXT_NON_TRANSFERABLE => extensions.non_transferable = true,
+            EXT_INTEREST_BEARING_CONFIG => {
+                extensions.interest_rate_bps = value
+                    .get(50..52)
+                    .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]));
+            }
+            EXT_PERMANENT_DELEGATE => {
+                extensions.permanent_delegate = read_optional_pubkey(value, 0);
+            }
+            EXT_TRANSFER_HOOK => {
+                extensions.transfer_hook_program = read_optional_pubkey(value, 32);
+            }
+            EXT_METADATA_POINTER | EXT_TOKEN_METADATA => extensions.has_metadata = true,
+            other => extensions.unknown_extensions.push(other),
+        }
+
+        offset = end;
+    }
+
+    Ok(extensions)
+}
+
+fn parse_transfer_fee_config(value: &[u8]) -> Result<TransferFeeConfig, TokenExtensionError> {
+    if value.len() < 108 {
+        return Err(TokenExtensionError::Malformed(
+            "transfer fee config is truncated".to_string(),
+        ));
+    }
+    let fee_at = |offset: usize| TransferFee {
+        epoch: read_u64(value, offset),
+        maximum_fee: read_u64(value, offset + 8),
+        basis_points: u16::from_le_bytes([value[offset + 16], value[offset + 17]]),
+    };
+
+    Ok(TransferFeeConfig {
+        config_authority: read_optional_pubkey(value, 0),
+        withdraw_withheld_authority: read_optional_pubkey(value, 32),
+        withheld_amount: read_u64(value, 64),
+        older: fee_at(72),
+        newer: fee_at(90),
+    })
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// Token-2022 `OptionalNonZeroPubkey`: all zeroes means unset.
+fn read_optional_pubkey(data: &[u8], offset: usize) -> Option<String> {
+    let bytes: [u8; 32] = data.get(offset..offset + 32)?.try_into().ok()?;
+    if bytes.iter().all(|b| *b == 0) {
+        None
+    } else {
+        Some(Pubkey::new_from_array(bytes).to_string())
+    }
+}
+
+/// Base mint `COption<Pubkey>`: 4-byte tag followed by the key.
+fn read_coption_pubkey(data: &[u8]) -> Option<String> {
+    let tag = u32::from_le_bytes(data.get(..4)?.try_into().ok()?);
+    if tag == 0 {
+        None
+    } else {
+        read_optional_pubkey(data, 4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_mint(decimals: u8) -> Vec<u8> {
+        let mut data = vec![0u8; ACCOUNT_TYPE_OFFSET + 1];
+        data[44] = decimals;
+        data[45] = 1;
+        data[ACCOUNT_TYPE_OFFSET] = ACCOUNT_TYPE_MINT;
+        data
+    }
+
+    fn push_extension(data: &mut Vec<u8>, ext_type: u16, value: &[u8]) {
+        data.extend_from_slice(&ext_type.to_le_bytes());
+        data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+        data.extend_from_slice(value);
+    }
+
+    fn transfer_fee_value(bps: u16, max_fee: u64) -> Vec<u8> {
+        let mut value = vec![7u8; 32];
+        value.extend_from_slice(&[0u8; 32]);
+        value.extend_from_slice(&0u64.to_le_bytes());
+        for epoch in [0u64, 0u64] {
+            value.extend_from_slice(&epoch.to_le_bytes());
+            value.extend_from_slice(&max_fee.to_le_bytes());
+            value.extend_from_slice(&bps.to_le_bytes());
+        }
+        value
+    }
+
+    #[test]
+    fn parses_transfer_fee_and_hook() {
+        let mut data = base_mint(6);
+        push_extension(
+            &mut data,
+            EXT_TRANSFER_FEE_CONFIG,
+            &transfer_fee_value(250, 1_000_000),
+        );
+        let mut hook = vec![0u8; 32];
+        hook.extend_from_slice(&[9u8; 32]);
+        push_extension(&mut data, EXT_TRANSFER_HOOK, &hook);
+        push_extension(&mut data, EXT_DEFAULT_ACCOUNT_STATE, &[2]);
+
+        let ext = parse_mint_extensions("mint", TOKEN_2022_PROGRAM_ID, &data).unwrap();
+        assert!(ext.is_token_2022());
+        assert_eq!(ext.decimals, 6);
+        assert_eq!(ext.transfer_fee_bps(500), Some(250));
+        assert!(ext.transfer_hook_program.is_some());
+        assert_eq!(ext.default_account_state, Some(DefaultAccountState::Frozen));
+        assert!(ext
+            .transfer_fee
+            .as_ref()
+            .unwrap()
+            .withdraw_withheld_authority
+            .is_none());
+
+        let severities: Vec<_> = ext.risks(500).into_iter().map(|r| r.severity).collect();
+        assert!(severities.contains(&ExtensionRiskSeverity::High));
+    }
+
+    #[test]
+    fn fee_rounds_up_and_respects_maximum() {
+        let fee = TransferFee {
+            epoch: 0,
+            maximum_fee: 5_000,
+            basis_points: 100,
+        };
+        assert_eq!(fee.calculate(150), 2);
+        assert_eq!(fee.calculate(10_000), 100);
+        assert_eq!(fee.calculate(10_000_000), 5_000);
+        assert_eq!(fee.calculate(0), 0);
+    }
+
+    #[test]
+    fn legacy_mints_have_no_extensions() {
+        let data = vec![0u8; MINT_LEN];
+        let ext = parse_mint_extensions("mint", TOKEN_PROGRAM_ID, &data).unwrap();
+        assert!(!ext.is_token_2022());
+        assert!(ext.risks(0).is_empty());
+        assert_eq!(ext.breakdown(1_000, 0).net_amount, 1_000);
+        assert!(parse_mint_extensions("mint", "11111111111111111111111111111111", &data).is_err());
+    }
+}
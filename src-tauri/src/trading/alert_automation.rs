@@ -0,0 +1,675 @@
+//! Alert-to-order automation.
+//!
+//! An automation binds one alert to an order template. When that alert fires
+//! and the automation is armed, the template is priced, charged against the
+//! automation's daily USD budget, run through the safety engine and then
+//! submitted through the order manager like any other order. Every firing,
+//! including the ones that were blocked, is kept in an execution log.
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Listener, Manager, State};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::alerts::AlertTriggerEvent;
+use crate::api_config::stored_birdeye_key;
+use crate::errors::{AppError, CommandResultExt};
+use crate::insiders::WhaleAlert;
+use crate::profiles::ProfilePaths;
+use crate::security::keystore::Keystore;
+use crate::trading::limit_orders::require_state;
+use crate::trading::safety::{SafetyCheckRequest, SharedSafetyEngine};
+use crate::trading::types::CreateOrderRequest;
+use crate::wallet::history_backfill::PriceOracle;
+
+const AUTOMATIONS_FILE: &str = "alert_automations.json";
+const MAX_EXECUTIONS: usize = 500;
+pub const SMART_ALERT_TRIGGERED_EVENT: &str = "smart_alert_triggered";
+pub const AUTOMATION_EXECUTED_EVENT: &str = "alert_automation_executed";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AutomationAlertSource {
+    /// A price alert, matched by alert id.
+    Price,
+    /// A smart alert rule, matched by rule id.
+    Smart,
+    /// A whale alert, matched by the monitored wallet address.
+    OnChain,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertAutomation {
+    pub id: String,
+    pub name: String,
+    pub source: AutomationAlertSource,
+    pub alert_id: String,
+    pub order: CreateOrderRequest,
+    pub daily_budget_usd: f64,
+    pub armed: bool,
+    #[serde(default)]
+    pub spent_today_usd: f64,
+    #[serde(default)]
+    pub budget_day: Option<NaiveDate>,
+    #[serde(default)]
+    pub last_fired_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl AlertAutomation {
+    pub fn budget_remaining(&self, today: NaiveDate) -> f64 {
+        let spent = if self.budget_day == Some(today) {
+            self.spent_today_usd
+        } else {
+            0.0
+        };
+        (self.daily_budget_usd - spent).max(0.0)
+    }
+
+    /// Charges `amount_usd` against today's budget, starting a fresh day
+    /// when the date has rolled over.
+    fn reserve(&mut self, amount_usd: f64, today: NaiveDate) -> Result<(), String> {
+        let remaining = self.budget_remaining(today);
+        if amount_usd > remaining {
+            return Err(format!(
+                "Order worth ${:.2} exceeds the remaining daily budget of ${:.2}",
+                amount_usd, remaining
+            ));
+        }
+        if self.budget_day != Some(today) {
+            self.budget_day = Some(today);
+            self.spent_today_usd = 0.0;
+        }
+        self.spent_today_usd += amount_usd;
+        Ok(())
+    }
+
+    fn refund(&mut self, amount_usd: f64, today: NaiveDate) {
+        if self.budget_day == Some(today) {
+            self.spent_today_usd = (self.spent_today_usd - amount_usd).max(0.0);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAutomationRequest {
+    pub name: String,
+    pub source: AutomationAlertSource,
+    pub alert_id: String,
+    pub order: CreateOrderRequest,
+    pub daily_budget_usd: f64,
+    /// New automations start disarmed unless asked otherwise.
+    #[serde(default)]
+    pub armed: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAutomationRequest {
+    pub name: Option<String>,
+    pub order: Option<CreateOrderRequest>,
+    pub daily_budget_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AutomationOutcome {
+    Submitted,
+    Blocked,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationExecution {
+    pub id: String,
+    pub automation_id: String,
+    pub automation_name: String,
+    pub source: AutomationAlertSource,
+    pub alert_id: String,
+    pub outcome: AutomationOutcome,
+    pub reason: Option<String>,
+    pub order_id: Option<String>,
+    pub amount_usd: Option<f64>,
+    pub fired_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredAutomations {
+    automations: Vec<AlertAutomation>,
+    executions: Vec<AutomationExecution>,
+}
+
+pub struct AlertAutomationManager {
+    automations: Vec<AlertAutomation>,
+    executions: Vec<AutomationExecution>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedAlertAutomationManager = Arc<RwLock<AlertAutomationManager>>;
+
+impl AlertAutomationManager {
+    pub fn new(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(AUTOMATIONS_FILE));
+        let stored: StoredAutomations = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            automations: stored.automations,
+            executions: stored.executions,
+            path,
+        }
+    }
+
+    pub fn list(&self) -> Vec<AlertAutomation> {
+        self.automations.clone()
+    }
+
+    pub fn executions(
+        &self,
+        automation_id: Option<&str>,
+        limit: usize,
+    ) -> Vec<AutomationExecution> {
+        self.executions
+            .iter()
+            .rev()
+            .filter(|execution| automation_id.map_or(true, |id| execution.automation_id == id))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    pub fn create(&mut self, req: CreateAutomationRequest) -> Result<AlertAutomation, String> {
+        if req.alert_id.trim().is_empty() {
+            return Err("An alert to bind to is required".into());
+        }
+        validate_budget(req.daily_budget_usd)?;
+        validate_order(&req.order)?;
+        let now = Utc::now().to_rfc3339();
+        let automation = AlertAutomation {
+            id: Uuid::new_v4().to_string(),
+            name: req.name,
+            source: req.source,
+            alert_id: req.alert_id.trim().to_string(),
+            order: req.order,
+            daily_budget_usd: req.daily_budget_usd,
+            armed: req.armed,
+            spent_today_usd: 0.0,
+            budget_day: None,
+            last_fired_at: None,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        self.automations.push(automation.clone());
+        self.save();
+        Ok(automation)
+    }
+
+    pub fn update(
+        &mut self,
+        id: &str,
+        req: UpdateAutomationRequest,
+    ) -> Result<AlertAutomation, String> {
+        if let Some(budget) = req.daily_budget_usd {
+            validate_budget(budget)?;
+        }
+        if let Some(order) = &req.order {
+            validate_order(order)?;
+        }
+        let automation = self.find_mut(id)?;
+        if let Some(name) = req.name {
+            automation.name = name;
+        }
+        if let Some(order) = req.order {
+            automation.order = order;
+        }
+        if let Some(budget) = req.daily_budget_usd {
+            automation.daily_budget_usd = budget;
+        }
+        automation.updated_at = Utc::now().to_rfc3339();
+        let updated = automation.clone();
+        self.save();
+        Ok(updated)
+    }
+
+    pub fn set_armed(&mut self, id: &str, armed: bool) -> Result<AlertAutomation, String> {
+        let automation = self.find_mut(id)?;
+        automation.armed = armed;
+        automation.updated_at = Utc::now().to_rfc3339();
+        let updated = automation.clone();
+        self.save();
+        Ok(updated)
+    }
+
+    pub fn delete(&mut self, id: &str) -> Result<(), String> {
+        let before = self.automations.len();
+        self.automations.retain(|automation| automation.id != id);
+        if self.automations.len() == before {
+            return Err(format!("Automation {id} not found"));
+        }
+        self.save();
+        Ok(())
+    }
+
+    /// Armed automations bound to the alert that just fired.
+    pub fn armed_for(&self, source: AutomationAlertSource, alert_id: &str) -> Vec<AlertAutomation> {
+        self.automations
+            .iter()
+            .filter(|automation| {
+                automation.armed && automation.source == source && automation.alert_id == alert_id
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn find_mut(&mut self, id: &str) -> Result<&mut AlertAutomation, String> {
+        self.automations
+            .iter_mut()
+            .find(|automation| automation.id == id)
+            .ok_or_else(|| format!("Automation {id} not found"))
+    }
+
+    fn record(&mut self, execution: AutomationExecution) {
+        if let Some(automation) = self
+            .automations
+            .iter_mut()
+            .find(|automation| automation.id == execution.automation_id)
+        {
+            automation.last_fired_at = Some(execution.fired_at.clone());
+        }
+        self.executions.push(execution);
+        if self.executions.len() > MAX_EXECUTIONS {
+            let excess = self.executions.len() - MAX_EXECUTIONS;
+            self.executions.drain(..excess);
+        }
+        self.save();
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let stored = StoredAutomations {
+            automations: self.automations.clone(),
+            executions: self.executions.clone(),
+        };
+        match serde_json::to_string_pretty(&stored) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(path, contents) {
+                    eprintln!("Failed to save alert automations: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize alert automations: {}", e),
+        }
+    }
+}
+
+fn validate_budget(budget: f64) -> Result<(), String> {
+    if !budget.is_finite() || budget <= 0.0 {
+        return Err("Daily budget must be greater than zero".into());
+    }
+    Ok(())
+}
+
+fn validate_order(order: &CreateOrderRequest) -> Result<(), String> {
+    if order.amount <= 0.0 {
+        return Err("Order amount must be greater than zero".into());
+    }
+    if order.wallet_address.trim().is_empty() {
+        return Err("Order template needs a wallet address".into());
+    }
+    Ok(())
+}
+
+/// Runs every armed automation bound to the alert that fired.
+pub async fn handle_alert_fired(app: &AppHandle, source: AutomationAlertSource, alert_id: &str) {
+    let Some(manager) = app
+        .try_state::<SharedAlertAutomationManager>()
+        .map(|state| state.inner().clone())
+    else {
+        return;
+    };
+    let automations = manager.read().await.armed_for(source, alert_id);
+    for automation in automations {
+        let execution = run_automation(app, &manager, &automation).await;
+        let _ = app.emit(AUTOMATION_EXECUTED_EVENT, &execution);
+        manager.write().await.record(execution);
+    }
+}
+
+async fn run_automation(
+    app: &AppHandle,
+    manager: &SharedAlertAutomationManager,
+    automation: &AlertAutomation,
+) -> AutomationExecution {
+    let mut execution = AutomationExecution {
+        id: Uuid::new_v4().to_string(),
+        automation_id: automation.id.clone(),
+        automation_name: automation.name.clone(),
+        source: automation.source,
+        alert_id: automation.alert_id.clone(),
+        outcome: AutomationOutcome::Blocked,
+        reason: None,
+        order_id: None,
+        amount_usd: None,
+        fired_at: Utc::now().to_rfc3339(),
+    };
+    let order = &automation.order;
+
+    let birdeye_key = stored_birdeye_key(&app.state::<Keystore>());
+    let Some(price) = PriceOracle::new(birdeye_key)
+        .price_at(&order.input_mint, Utc::now())
+        .await
+    else {
+        execution.reason = Some(format!(
+            "No price for {}; the budget cannot be checked",
+            order.input_symbol
+        ));
+        return execution;
+    };
+    let amount_usd = order.amount * price;
+    execution.amount_usd = Some(amount_usd);
+
+    // Reserve the budget up front so two alerts firing together cannot both
+    // spend the same remainder.
+    let today = Utc::now().date_naive();
+    {
+        let mut manager = manager.write().await;
+        let reserved = match manager.find_mut(&automation.id) {
+            Ok(current) if current.armed => current.reserve(amount_usd, today),
+            Ok(_) => Err("Automation was disarmed".to_string()),
+            Err(e) => Err(e),
+        };
+        if let Err(reason) = reserved {
+            execution.reason = Some(reason);
+            return execution;
+        }
+    }
+
+    let result = submit_order(app, order, amount_usd).await;
+    match result {
+        Ok(order_id) => {
+            execution.outcome = AutomationOutcome::Submitted;
+            execution.order_id = Some(order_id);
+        }
+        Err((outcome, reason)) => {
+            execution.outcome = outcome;
+            execution.reason = Some(reason);
+            if let Ok(current) = manager.write().await.find_mut(&automation.id) {
+                current.refund(amount_usd, today);
+            }
+        }
+    }
+    execution
+}
+
+async fn submit_order(
+    app: &AppHandle,
+    order: &CreateOrderRequest,
+    amount_usd: f64,
+) -> Result<String, (AutomationOutcome, String)> {
+    let Some(safety) = app.try_state::<SharedSafetyEngine>() else {
+        return Err((
+            AutomationOutcome::Blocked,
+            "Safety engine is not available".to_string(),
+        ));
+    };
+    let request = SafetyCheckRequest {
+        wallet_address: order.wallet_address.clone(),
+        input_amount: order.amount,
+        input_mint: order.input_mint.clone(),
+        output_mint: order.output_mint.clone(),
+        input_symbol: order.input_symbol.clone(),
+        output_symbol: order.output_symbol.clone(),
+        amount_usd,
+        slippage_bps: order.slippage_bps.max(0) as u64,
+        price_impact_percent: 0.0,
+        security_score: None,
+        input_extensions: None,
+        output_extensions: None,
+    };
+    let check = safety
+        .write()
+        .await
+        .check_trade_safety(request)
+        .await
+        .map_err(|e| (AutomationOutcome::Failed, e))?;
+    if !check.allowed {
+        let mut reasons: Vec<String> = check
+            .policy_result
+            .violations
+            .iter()
+            .map(|violation| violation.message.clone())
+            .collect();
+        if check.cooldown_status.is_some() {
+            reasons.push("Wallet is in its trade cooldown".to_string());
+        }
+        return Err((
+            AutomationOutcome::Blocked,
+            format!("Safety engine blocked the order: {}", reasons.join("; ")),
+        ));
+    }
+
+    let state = require_state().map_err(|e| (AutomationOutcome::Failed, e))?;
+    let placed = state
+        .manager
+        .create_order(order.clone())
+        .await
+        .map_err(|e| (AutomationOutcome::Failed, e))?;
+    safety.write().await.approve_trade(&order.wallet_address);
+    Ok(placed.id)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SmartAlertFired {
+    rule_id: String,
+}
+
+fn listen_for_alerts<T, F>(
+    app: &AppHandle,
+    event: &'static str,
+    source: AutomationAlertSource,
+    key: F,
+) where
+    T: serde::de::DeserializeOwned,
+    F: Fn(T) -> String + Send + Sync + 'static,
+{
+    let handle = app.clone();
+    app.listen(event, move |event| {
+        let Ok(payload) = serde_json::from_str::<T>(event.payload()) else {
+            return;
+        };
+        let alert_id = key(payload);
+        let handle = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            handle_alert_fired(&handle, source, &alert_id).await;
+        });
+    });
+}
+
+pub fn register_alert_automation_state(app: &tauri::App) {
+    let manager: SharedAlertAutomationManager =
+        Arc::new(RwLock::new(AlertAutomationManager::new(app.handle())));
+    app.manage(manager);
+
+    let handle = app.handle();
+    listen_for_alerts(
+        handle,
+        "alert_triggered",
+        AutomationAlertSource::Price,
+        |event: AlertTriggerEvent| event.alert_id,
+    );
+    listen_for_alerts(
+        handle,
+        SMART_ALERT_TRIGGERED_EVENT,
+        AutomationAlertSource::Smart,
+        |event: SmartAlertFired| event.rule_id,
+    );
+    listen_for_alerts(
+        handle,
+        "whale_alert",
+        AutomationAlertSource::OnChain,
+        |alert: WhaleAlert| alert.wallet_address,
+    );
+}
+
+#[tauri::command]
+pub async fn alert_automation_list(
+    manager: State<'_, SharedAlertAutomationManager>,
+) -> Result<Vec<AlertAutomation>, AppError> {
+    Ok(manager.read().await.list())
+}
+
+#[tauri::command]
+pub async fn alert_automation_create(
+    request: CreateAutomationRequest,
+    manager: State<'_, SharedAlertAutomationManager>,
+) -> Result<AlertAutomation, AppError> {
+    manager
+        .write()
+        .await
+        .create(request)
+        .logged("alert_automation_create")
+}
+
+#[tauri::command]
+pub async fn alert_automation_update(
+    id: String,
+    request: UpdateAutomationRequest,
+    manager: State<'_, SharedAlertAutomationManager>,
+) -> Result<AlertAutomation, AppError> {
+    manager
+        .write()
+        .await
+        .update(&id, request)
+        .logged("alert_automation_update")
+}
+
+#[tauri::command]
+pub async fn alert_automation_set_armed(
+    id: String,
+    armed: bool,
+    manager: State<'_, SharedAlertAutomationManager>,
+) -> Result<AlertAutomation, AppError> {
+    manager
+        .write()
+        .await
+        .set_armed(&id, armed)
+        .logged("alert_automation_set_armed")
+}
+
+#[tauri::command]
+pub async fn alert_automation_delete(
+    id: String,
+    manager: State<'_, SharedAlertAutomationManager>,
+) -> Result<(), AppError> {
+    manager
+        .write()
+        .await
+        .delete(&id)
+        .logged("alert_automation_delete")
+}
+
+#[tauri::command]
+pub async fn alert_automation_executions(
+    automation_id: Option<String>,
+    limit: Option<usize>,
+    manager: State<'_, SharedAlertAutomationManager>,
+) -> Result<Vec<AutomationExecution>, AppError> {
+    Ok(manager
+        .read()
+        .await
+        .executions(automation_id.as_deref(), limit.unwrap_or(100)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::types::{OrderSide, OrderType};
+
+    fn automation(source: AutomationAlertSource, alert_id: &str, armed: bool) -> AlertAutomation {
+        AlertAutomation {
+            id: Uuid::new_v4().to_string(),
+            name: "dip buy".into(),
+            source,
+            alert_id: alert_id.into(),
+            order: CreateOrderRequest {
+                order_type: OrderType::Market,
+                side: OrderSide::Buy,
+                input_mint: "USDC".into(),
+                output_mint: "SOL".into(),
+                input_symbol: "USDC".into(),
+                output_symbol: "SOL".into(),
+                amount: 100.0,
+                limit_price: None,
+                stop_price: None,
+                trailing_percent: None,
+                trailing_amount: None,
+                linked_order_id: None,
+                slippage_bps: 50,
+                priority_fee_micro_lamports: 0,
+                wallet_address: "wallet".into(),
+            },
+            daily_budget_usd: 250.0,
+            armed,
+            spent_today_usd: 0.0,
+            budget_day: None,
+            last_fired_at: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn budget_blocks_overspend_and_resets_next_day() {
+        let mut automation = automation(AutomationAlertSource::Price, "a", true);
+        let day = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+
+        automation.reserve(100.0, day).unwrap();
+        automation.reserve(100.0, day).unwrap();
+        assert!(automation.reserve(100.0, day).is_err());
+        assert_eq!(automation.budget_remaining(day), 50.0);
+
+        automation.refund(100.0, day);
+        assert_eq!(automation.budget_remaining(day), 150.0);
+
+        let next = day.succ_opt().unwrap();
+        assert_eq!(automation.budget_remaining(next), 250.0);
+        automation.reserve(200.0, next).unwrap();
+        assert_eq!(automation.spent_today_usd, 200.0);
+    }
+
+    #[test]
+    fn only_armed_automations_for_the_fired_alert_run() {
+        let manager = AlertAutomationManager {
+            automations: vec![
+                automation(AutomationAlertSource::Price, "a", true),
+                automation(AutomationAlertSource::Price, "a", false),
+                automation(AutomationAlertSource::Smart, "a", true),
+                automation(AutomationAlertSource::Price, "b", true),
+            ],
+            executions: Vec::new(),
+            path: None,
+        };
+
+        let matched = manager.armed_for(AutomationAlertSource::Price, "a");
+        assert_eq!(matched.len(), 1);
+        assert!(matched[0].armed);
+        assert_eq!(matched[0].source, AutomationAlertSource::Price);
+    }
+}
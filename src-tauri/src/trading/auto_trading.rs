@@ -1,3 +1,5 @@
+use crate::academy::{enforce_feature_gate, GatedFeature, SharedAcademyEngine, SharedFeatureGate};
+use crate::errors::{AppError, CommandResultExt};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -98,6 +100,10 @@ pub struct StrategyExecution {
     pub strategy_id: String,
     pub strategy_name: String,
     pub status: ExecutionStatus,
+    /// Requested as a live run. Fills are still simulated against paper
+    /// capital; the flag only marks the execution until order routing exists.
+    #[serde(default)]
+    pub live: bool,
     #[serde(with = "chrono::serde::ts_milliseconds")]
     pub started_at: DateTime<Utc>,
     #[serde(
@@ -141,6 +147,7 @@ pub struct AutoTradingEngine {
     strategies: HashMap<String, TradingStrategy>,
     executions: HashMap<String, StrategyExecution>,
     positions: HashMap<String, Vec<Position>>,
+    external_signals: HashMap<String, HashMap<String, f64>>,
     kill_switch_active: bool,
     starting_capital: f64,
     current_capital: f64,
@@ -152,6 +159,7 @@ impl AutoTradingEngine {
             strategies: HashMap::new(),
             executions: HashMap::new(),
             positions: HashMap::new(),
+            external_signals: HashMap::new(),
             kill_switch_active: false,
             starting_capital,
             current_capital: starting_capital,
@@ -235,10 +243,15 @@ impl AutoTradingEngine {
         self.strategies.remove(id);
         self.executions.remove(id);
         self.positions.remove(id);
+        self.external_signals.remove(id);
         Ok(())
     }
 
-    pub fn start_strategy(&mut self, strategy_id: &str) -> Result<StrategyExecution, String> {
+    pub fn start_strategy(
+        &mut self,
+        strategy_id: &str,
+        live: bool,
+    ) -> Result<StrategyExecution, String> {
         if self.kill_switch_active {
             return Err("Kill switch is active".to_string());
         }
@@ -257,6 +270,7 @@ impl AutoTradingEngine {
             strategy_id: strategy_id.to_string(),
             strategy_name: strategy.name.clone(),
             status: ExecutionStatus::Running,
+            live,
             started_at: Utc::now(),
             stopped_at: None,
             trades_executed: 0,
@@ -357,6 +371,54 @@ impl AutoTradingEngine {
         }
     }
 
+    /// Stores the latest value pushed by an external signal source (e.g. a
+    /// TradingView webhook) and re-evaluates the strategy with it. Returns
+    /// whether the strategy's combined signal now fires.
+    pub fn record_external_signal(
+        &mut self,
+        strategy_id: &str,
+        source_id: &str,
+        value: f64,
+    ) -> Result<bool, String> {
+        if self.kill_switch_active {
+            return Err("Kill switch is active".to_string());
+        }
+
+        let strategy = self
+            .strategies
+            .get(strategy_id)
+            .ok_or_else(|| format!("Strategy {} not found", strategy_id))?;
+
+        if !strategy
+            .signal_sources
+            .iter()
+            .any(|source| source.id == source_id && source.enabled)
+        {
+            return Err(format!(
+                "Signal source {} is not enabled on strategy {}",
+                source_id, strategy_id
+            ));
+        }
+
+        let running = self
+            .executions
+            .get(strategy_id)
+            .map(|execution| execution.status == ExecutionStatus::Running)
+            .unwrap_or(false);
+        if !running {
+            return Err(format!("Strategy {} is not running", strategy_id));
+        }
+
+        let signals = self
+            .external_signals
+            .entry(strategy_id.to_string())
+            .or_default();
+        signals.insert(source_id.to_string(), value);
+        let signals = signals.clone();
+
+        Ok(self.evaluate_signals(strategy_id, &signals))
+    }
+
     pub fn calculate_position_size(
         &self,
         strategy_id: &str,
@@ -507,8 +569,8 @@ pub fn register_auto_trading_state(app: &tauri::App) {
 pub async fn auto_trading_create_strategy(
     strategy: TradingStrategyInput,
     engine: tauri::State<'_, SharedAutoTradingEngine>,
-) -> Result<TradingStrategy, String> {
-    let mut engine = engine.lock().map_err(|e| e.to_string())?;
+) -> Result<TradingStrategy, AppError> {
+    let mut engine = engine.lock().logged("auto_trading_create_strategy")?;
     Ok(engine.add_strategy(strategy))
 }
 
@@ -517,61 +579,109 @@ pub async fn auto_trading_update_strategy(
     id: String,
     updates: TradingStrategyUpdate,
     engine: tauri::State<'_, SharedAutoTradingEngine>,
-) -> Result<TradingStrategy, String> {
-    let mut engine = engine.lock().map_err(|e| e.to_string())?;
-    engine.update_strategy(&id, updates)
+) -> Result<TradingStrategy, AppError> {
+    let mut engine = engine.lock().logged("auto_trading_update_strategy")?;
+    engine
+        .update_strategy(&id, updates)
+        .logged("auto_trading_update_strategy")
 }
 
 #[tauri::command]
 pub async fn auto_trading_delete_strategy(
     id: String,
     engine: tauri::State<'_, SharedAutoTradingEngine>,
-) -> Result<(), String> {
-    let mut engine = engine.lock().map_err(|e| e.to_string())?;
-    engine.delete_strategy(&id)
+) -> Result<(), AppError> {
+    let mut engine = engine.lock().logged("auto_trading_delete_strategy")?;
+    engine
+        .delete_strategy(&id)
+        .logged("auto_trading_delete_strategy")
 }
 
 #[tauri::command]
 pub async fn auto_trading_start_strategy(
+    app: tauri::AppHandle,
     strategy_id: String,
+    live: Option<bool>,
     engine: tauri::State<'_, SharedAutoTradingEngine>,
-) -> Result<StrategyExecution, String> {
-    let mut engine = engine.lock().map_err(|e| e.to_string())?;
-    engine.start_strategy(&strategy_id)
+    gate: tauri::State<'_, SharedFeatureGate>,
+    academy: tauri::State<'_, SharedAcademyEngine>,
+) -> Result<StrategyExecution, AppError> {
+    let live = live.unwrap_or(false);
+    enforce_feature_gate(GatedFeature::AutoTrading, &app, &gate, &academy)
+        .await
+        .logged("auto_trading_start_strategy")?;
+    if live {
+        enforce_feature_gate(GatedFeature::AutoTradingLive, &app, &gate, &academy)
+            .await
+            .logged("auto_trading_start_strategy")?;
+    }
+    let mut engine = engine.lock().logged("auto_trading_start_strategy")?;
+    engine
+        .start_strategy(&strategy_id, live)
+        .logged("auto_trading_start_strategy")
 }
 
 #[tauri::command]
 pub async fn auto_trading_stop_strategy(
     strategy_id: String,
     engine: tauri::State<'_, SharedAutoTradingEngine>,
-) -> Result<(), String> {
-    let mut engine = engine.lock().map_err(|e| e.to_string())?;
-    engine.stop_strategy(&strategy_id)
+) -> Result<(), AppError> {
+    let mut engine = engine.lock().logged("auto_trading_stop_strategy")?;
+    engine
+        .stop_strategy(&strategy_id)
+        .logged("auto_trading_stop_strategy")
 }
 
 #[tauri::command]
 pub async fn auto_trading_pause_strategy(
     strategy_id: String,
     engine: tauri::State<'_, SharedAutoTradingEngine>,
-) -> Result<(), String> {
-    let mut engine = engine.lock().map_err(|e| e.to_string())?;
-    engine.pause_strategy(&strategy_id)
+) -> Result<(), AppError> {
+    let mut engine = engine.lock().logged("auto_trading_pause_strategy")?;
+    engine
+        .pause_strategy(&strategy_id)
+        .logged("auto_trading_pause_strategy")
 }
 
 #[tauri::command]
 pub async fn auto_trading_activate_kill_switch(
     engine: tauri::State<'_, SharedAutoTradingEngine>,
-) -> Result<(), String> {
-    let mut engine = engine.lock().map_err(|e| e.to_string())?;
+) -> Result<(), AppError> {
+    let mut engine = engine.lock().logged("auto_trading_activate_kill_switch")?;
     engine.activate_kill_switch();
     Ok(())
 }
 
 #[tauri::command]
 pub async fn auto_trading_deactivate_kill_switch(
+    two_factor_code: Option<String>,
     engine: tauri::State<'_, SharedAutoTradingEngine>,
-) -> Result<(), String> {
-    let mut engine = engine.lock().map_err(|e| e.to_string())?;
+    passkeys: tauri::State<'_, crate::auth::passkey::PasskeyManager>,
+    policy: tauri::State<'_, crate::auth::command_policy::CommandPolicyManager>,
+    two_factor: tauri::State<'_, crate::auth::two_factor::TwoFactorManager>,
+    keystore: tauri::State<'_, crate::security::keystore::Keystore>,
+) -> Result<(), AppError> {
+    passkeys
+        .require_presence(crate::auth::passkey::SensitiveCategory::Trading)
+        .logged("auto_trading_deactivate_kill_switch")?;
+    let outcome = policy
+        .authorize(
+            crate::auth::command_policy::GuardedCommand::AutoTradingDeactivateKillSwitch,
+            two_factor_code.as_deref(),
+            two_factor.inner(),
+            keystore.inner(),
+        )
+        .await
+        .logged("auto_trading_deactivate_kill_switch")?;
+    // Re-enabling live trading always needs 2FA when it is enrolled.
+    if outcome != crate::auth::command_policy::VerificationOutcome::TwoFactor {
+        two_factor
+            .require_code(two_factor_code.as_deref(), keystore.inner())
+            .logged("auto_trading_deactivate_kill_switch")?;
+    }
+    let mut engine = engine
+        .lock()
+        .logged("auto_trading_deactivate_kill_switch")?;
     engine.deactivate_kill_switch();
     Ok(())
 }
@@ -579,8 +689,8 @@ pub async fn auto_trading_deactivate_kill_switch(
 #[tauri::command]
 pub async fn auto_trading_get_strategies(
     engine: tauri::State<'_, SharedAutoTradingEngine>,
-) -> Result<Vec<TradingStrategy>, String> {
-    let engine = engine.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<TradingStrategy>, AppError> {
+    let engine = engine.lock().logged("auto_trading_get_strategies")?;
     Ok(engine.get_strategies())
 }
 
@@ -588,16 +698,16 @@ pub async fn auto_trading_get_strategies(
 pub async fn auto_trading_get_strategy(
     id: String,
     engine: tauri::State<'_, SharedAutoTradingEngine>,
-) -> Result<Option<TradingStrategy>, String> {
-    let engine = engine.lock().map_err(|e| e.to_string())?;
+) -> Result<Option<TradingStrategy>, AppError> {
+    let engine = engine.lock().logged("auto_trading_get_strategy")?;
     Ok(engine.get_strategy(&id))
 }
 
 #[tauri::command]
 pub async fn auto_trading_get_executions(
     engine: tauri::State<'_, SharedAutoTradingEngine>,
-) -> Result<Vec<StrategyExecution>, String> {
-    let engine = engine.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<StrategyExecution>, AppError> {
+    let engine = engine.lock().logged("auto_trading_get_executions")?;
     Ok(engine.get_all_executions())
 }
 
@@ -606,7 +716,9 @@ pub async fn auto_trading_apply_parameters(
     strategy_id: String,
     parameters: HashMap<String, f64>,
     engine: tauri::State<'_, SharedAutoTradingEngine>,
-) -> Result<TradingStrategy, String> {
-    let mut engine = engine.lock().map_err(|e| e.to_string())?;
-    engine.apply_parameters(&strategy_id, parameters)
+) -> Result<TradingStrategy, AppError> {
+    let mut engine = engine.lock().logged("auto_trading_apply_parameters")?;
+    engine
+        .apply_parameters(&strategy_id, parameters)
+        .logged("auto_trading_apply_parameters")
 }
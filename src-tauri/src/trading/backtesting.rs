@@ -1,8 +1,15 @@
+use super::experiments::SharedExperimentTracker;
+use crate::data::historical::{OrderBookSnapshot, SharedHistoricalReplayManager};
+use crate::errors::{AppError, CommandResultExt};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use tauri::Manager;
 use uuid::Uuid;
 
+/// Dust left after a partial exit that is treated as fully closed.
+const MIN_POSITION_QUANTITY: f64 = 1e-9;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BacktestConfig {
     pub strategy_id: String,
@@ -15,6 +22,57 @@ pub struct BacktestConfig {
     pub commission_rate: f64,  // percentage
     pub slippage_rate: f64,    // percentage
     pub data_interval: String, // 1m, 5m, 15m, 1h, 4h, 1d
+    /// Liquidity-aware fills; `None` keeps the flat `slippage_rate`.
+    #[serde(default)]
+    pub execution: Option<ExecutionModel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExecutionModel {
+    /// Walk stored order book snapshots from the historical replay manager.
+    pub use_orderbooks: bool,
+    /// Snapshots older than this relative to the bar are ignored.
+    pub max_snapshot_age_secs: i64,
+    /// Largest share of a bar's volume a single fill may take.
+    pub max_participation_rate: f64,
+    /// Square-root market impact used when no snapshot covers the bar.
+    pub impact_coefficient: f64,
+    /// Jupiter route and platform fees, in basis points of notional.
+    pub route_fee_bps: f64,
+    /// Leave the rest of an order unfilled instead of sweeping the book.
+    pub allow_partial_fills: bool,
+}
+
+impl Default for ExecutionModel {
+    fn default() -> Self {
+        Self {
+            use_orderbooks: true,
+            max_snapshot_age_secs: 3600,
+            max_participation_rate: 0.1,
+            impact_coefficient: 0.1,
+            route_fee_bps: 0.0,
+            allow_partial_fills: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulatedFill {
+    pub quantity: f64,
+    pub average_price: f64,
+    pub route_fee: f64,
+    pub from_orderbook: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecutionSummary {
+    pub total_commission: f64,
+    pub total_slippage: f64,
+    pub total_route_fees: f64,
+    pub partial_fills: u32,
+    pub orderbook_fills: u32,
+    pub average_fill_ratio: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +87,11 @@ pub struct Trade {
     pub commission: f64,
     pub slippage: f64,
     pub signal: Option<String>,
+    #[serde(default)]
+    pub route_fee: f64,
+    /// Filled share of the requested quantity.
+    #[serde(default)]
+    pub fill_ratio: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,6 +137,8 @@ pub struct BacktestResult {
     #[serde(with = "chrono::serde::ts_milliseconds")]
     pub completed_at: DateTime<Utc>,
     pub duration: i64, // milliseconds
+    #[serde(default)]
+    pub execution_summary: Option<ExecutionSummary>,
 }
 
 #[derive(Debug, Clone)]
@@ -94,6 +159,11 @@ pub struct BacktestEngine {
     current_position: Option<Position>,
     trades: Vec<Trade>,
     equity_curve: Vec<EquityPoint>,
+    bar_volume: f64,
+    bar_orderbook: Option<OrderBookSnapshot>,
+    /// Signal of an exit that only partially filled and continues next bar.
+    pending_exit: Option<String>,
+    orderbook_fills: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -114,13 +184,74 @@ impl BacktestEngine {
             current_position: None,
             trades: Vec::new(),
             equity_curve: Vec::new(),
+            bar_volume: 0.0,
+            bar_orderbook: None,
+            pending_exit: None,
+            orderbook_fills: 0,
+        }
+    }
+
+    /// Liquidity available to fills on the current bar.
+    pub fn set_market_context(&mut self, volume: f64, orderbook: Option<OrderBookSnapshot>) {
+        self.bar_volume = volume;
+        self.bar_orderbook = orderbook;
+    }
+
+    pub fn has_pending_exit(&self) -> bool {
+        self.pending_exit.is_some()
+    }
+
+    /// Retries an exit that only partially filled on an earlier bar.
+    pub fn continue_pending_exit(&mut self, timestamp: DateTime<Utc>, price: f64) {
+        if let Some(signal) = self.pending_exit.clone() {
+            self.execute_sell(timestamp, price, Some(signal));
         }
     }
 
+    /// Closes the whole position regardless of participation limits.
+    pub fn close_position(&mut self, timestamp: DateTime<Utc>, price: f64, signal: Option<String>) {
+        let Some(model) = self.config.execution.clone() else {
+            self.execute_sell(timestamp, price, signal);
+            return;
+        };
+        self.config.execution = Some(ExecutionModel {
+            allow_partial_fills: false,
+            ..model.clone()
+        });
+        self.execute_sell(timestamp, price, signal);
+        self.config.execution = Some(model);
+    }
+
+    fn model_fill(
+        &mut self,
+        model: &ExecutionModel,
+        is_buy: bool,
+        price: f64,
+        quantity: f64,
+    ) -> SimulatedFill {
+        let fill = simulate_fill(
+            model,
+            is_buy,
+            price,
+            quantity,
+            self.bar_volume,
+            self.bar_orderbook.as_ref(),
+            self.config.slippage_rate,
+        );
+        if fill.from_orderbook {
+            self.orderbook_fills += 1;
+        }
+        fill
+    }
+
     pub fn execute_buy(&mut self, timestamp: DateTime<Utc>, price: f64, signal: Option<String>) {
         if self.current_position.is_some() {
             return; // Already in a position
         }
+        if let Some(model) = self.config.execution.clone() {
+            self.execute_modeled_buy(&model, timestamp, price, signal);
+            return;
+        }
 
         // Apply slippage
         let execution_price = price * (1.0 + self.config.slippage_rate / 100.0);
@@ -149,16 +280,75 @@ impl BacktestEngine {
             commission,
             slippage: slippage_cost,
             signal,
+            route_fee: 0.0,
+            fill_ratio: 1.0,
         });
 
         self.equity -= quantity * execution_price + commission;
     }
 
+    fn execute_modeled_buy(
+        &mut self,
+        model: &ExecutionModel,
+        timestamp: DateTime<Utc>,
+        price: f64,
+        signal: Option<String>,
+    ) {
+        if price <= 0.0 {
+            return;
+        }
+        let budget = self.equity * 0.95; // Use 95% of capital
+        let fee_rate = self.config.commission_rate / 100.0 + model.route_fee_bps / 10_000.0;
+        let requested = budget / (price * (1.0 + fee_rate));
+        let mut fill = self.model_fill(model, true, price, requested);
+
+        // Impact can push the cost past the budget; trim to what is affordable.
+        let cost = fill.quantity * fill.average_price * (1.0 + fee_rate);
+        if cost > budget && cost > 0.0 {
+            let scale = budget / cost;
+            fill.quantity *= scale;
+            fill.route_fee *= scale;
+        }
+        if fill.quantity <= MIN_POSITION_QUANTITY {
+            return;
+        }
+
+        let value = fill.quantity * fill.average_price;
+        let commission = value * (self.config.commission_rate / 100.0);
+
+        self.current_position = Some(Position {
+            symbol: self.config.symbol.clone(),
+            quantity: fill.quantity,
+            entry_price: fill.average_price,
+            entry_time: timestamp,
+        });
+
+        self.trades.push(Trade {
+            timestamp,
+            side: "buy".to_string(),
+            symbol: self.config.symbol.clone(),
+            price: fill.average_price,
+            quantity: fill.quantity,
+            value,
+            commission,
+            slippage: fill.quantity * (fill.average_price - price),
+            signal,
+            route_fee: fill.route_fee,
+            fill_ratio: (fill.quantity / requested).min(1.0),
+        });
+
+        self.equity -= value + commission + fill.route_fee;
+    }
+
     pub fn execute_sell(&mut self, timestamp: DateTime<Utc>, price: f64, signal: Option<String>) {
         let position = match &self.current_position {
             Some(p) => p.clone(),
             None => return, // No position to close
         };
+        if let Some(model) = self.config.execution.clone() {
+            self.execute_modeled_sell(&model, position, timestamp, price, signal);
+            return;
+        }
 
         // Apply slippage (negative for sells)
         let execution_price = price * (1.0 - self.config.slippage_rate / 100.0);
@@ -178,12 +368,95 @@ impl BacktestEngine {
             commission,
             slippage: slippage_cost,
             signal,
+            route_fee: 0.0,
+            fill_ratio: 1.0,
         });
 
         self.equity += net_proceeds;
         self.current_position = None;
     }
 
+    fn execute_modeled_sell(
+        &mut self,
+        model: &ExecutionModel,
+        position: Position,
+        timestamp: DateTime<Utc>,
+        price: f64,
+        signal: Option<String>,
+    ) {
+        let fill = self.model_fill(model, false, price, position.quantity);
+        if fill.quantity <= MIN_POSITION_QUANTITY {
+            self.pending_exit = signal;
+            return;
+        }
+
+        let gross_proceeds = fill.quantity * fill.average_price;
+        let commission = gross_proceeds * (self.config.commission_rate / 100.0);
+
+        self.trades.push(Trade {
+            timestamp,
+            side: "sell".to_string(),
+            symbol: self.config.symbol.clone(),
+            price: fill.average_price,
+            quantity: fill.quantity,
+            value: gross_proceeds,
+            commission,
+            slippage: fill.quantity * (price - fill.average_price),
+            signal: signal.clone(),
+            route_fee: fill.route_fee,
+            fill_ratio: (fill.quantity / position.quantity).min(1.0),
+        });
+
+        self.equity += gross_proceeds - commission - fill.route_fee;
+
+        let remaining = position.quantity - fill.quantity;
+        if remaining > MIN_POSITION_QUANTITY {
+            self.current_position = Some(Position {
+                quantity: remaining,
+                ..position
+            });
+            self.pending_exit = signal;
+        } else {
+            self.current_position = None;
+            self.pending_exit = None;
+        }
+    }
+
+    fn execution_summary(&self) -> Option<ExecutionSummary> {
+        if self.config.execution.is_none() {
+            return None;
+        }
+        let fills = self.trades.len().max(1) as f64;
+        Some(ExecutionSummary {
+            total_commission: self.trades.iter().map(|t| t.commission).sum(),
+            total_slippage: self.trades.iter().map(|t| t.slippage).sum(),
+            total_route_fees: self.trades.iter().map(|t| t.route_fee).sum(),
+            partial_fills: self.trades.iter().filter(|t| t.fill_ratio < 1.0).count() as u32,
+            orderbook_fills: self.orderbook_fills,
+            average_fill_ratio: if self.trades.is_empty() {
+                1.0
+            } else {
+                self.trades.iter().map(|t| t.fill_ratio).sum::<f64>() / fills
+            },
+        })
+    }
+
+    /// Cash not tied up in the open position.
+    pub fn cash(&self) -> f64 {
+        self.equity
+    }
+
+    pub fn position_quantity(&self) -> f64 {
+        self.current_position
+            .as_ref()
+            .map(|p| p.quantity)
+            .unwrap_or(0.0)
+    }
+
+    pub fn position_entry_price(&self) -> Option<f64> {
+        self.current_position.as_ref().map(|p| p.entry_price)
+    }
+
     pub fn update_equity_curve(&mut self, timestamp: DateTime<Utc>, current_price: f64) {
         let position_value = self
             .current_position
@@ -445,6 +718,7 @@ impl BacktestEngine {
 
     pub fn finalize(self) -> BacktestResult {
         let metrics = self.calculate_metrics();
+        let execution_summary = self.execution_summary();
         let completed_at = Utc::now();
         let duration = (completed_at - self.config.start_date).num_milliseconds();
 
@@ -457,10 +731,123 @@ impl BacktestEngine {
             started_at: self.config.start_date,
             completed_at,
             duration,
+            execution_summary,
+        }
+    }
+}
+
+/// Fills `quantity` against a bar, walking the order book when one is given
+/// and falling back to square-root impact on bar volume otherwise.
+pub fn simulate_fill(
+    model: &ExecutionModel,
+    is_buy: bool,
+    price: f64,
+    quantity: f64,
+    bar_volume: f64,
+    orderbook: Option<&OrderBookSnapshot>,
+    base_slippage_rate: f64,
+) -> SimulatedFill {
+    let target = if model.allow_partial_fills && bar_volume > 0.0 {
+        quantity.min(bar_volume * model.max_participation_rate)
+    } else {
+        quantity
+    };
+
+    let walked = orderbook.and_then(|book| walk_orderbook(book, is_buy, price, target, model));
+    let (filled, average_price, from_orderbook) = match walked {
+        Some((filled, average_price)) => (filled, average_price, true),
+        None => {
+            let participation = if bar_volume > 0.0 {
+                target / bar_volume
+            } else {
+                1.0
+            };
+            let impact =
+                base_slippage_rate / 100.0 + model.impact_coefficient * participation.sqrt();
+            let average_price = if is_buy {
+                price * (1.0 + impact)
+            } else {
+                price * (1.0 - impact).max(0.0)
+            };
+            (target, average_price, false)
         }
+    };
+
+    SimulatedFill {
+        quantity: filled,
+        average_price,
+        route_fee: filled * average_price * model.route_fee_bps / 10_000.0,
+        from_orderbook,
     }
 }
 
+/// Consumes book levels rescaled around the bar price, so a snapshot still
+/// describes depth when its mid drifted from the bar close.
+fn walk_orderbook(
+    book: &OrderBookSnapshot,
+    is_buy: bool,
+    price: f64,
+    quantity: f64,
+    model: &ExecutionModel,
+) -> Option<(f64, f64)> {
+    let best_bid = book.bids.iter().map(|(p, _)| *p).fold(f64::NAN, f64::max);
+    let best_ask = book.asks.iter().map(|(p, _)| *p).fold(f64::NAN, f64::min);
+    if !best_bid.is_finite() || !best_ask.is_finite() || quantity <= 0.0 {
+        return None;
+    }
+    let scale = price / ((best_bid + best_ask) / 2.0);
+
+    let mut levels: Vec<(f64, f64)> = if is_buy {
+        book.asks.clone()
+    } else {
+        book.bids.clone()
+    };
+    levels.sort_by(|a, b| {
+        let ordering = a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal);
+        if is_buy {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    });
+
+    let mut remaining = quantity;
+    let mut notional = 0.0;
+    let mut worst_price = price;
+    for (level_price, level_quantity) in levels {
+        if remaining <= 0.0 {
+            break;
+        }
+        let take = remaining.min(level_quantity.max(0.0));
+        worst_price = level_price * scale;
+        notional += take * worst_price;
+        remaining -= take;
+    }
+
+    // Without partial fills the rest sweeps past the book at its worst level.
+    if remaining > 0.0 && !model.allow_partial_fills {
+        notional += remaining * worst_price;
+        remaining = 0.0;
+    }
+
+    let filled = quantity - remaining;
+    if filled <= 0.0 {
+        return None;
+    }
+    Some((filled, notional / filled))
+}
+
+/// Latest snapshot at or before `timestamp` (seconds) within the max age.
+fn orderbook_at(
+    books: &[OrderBookSnapshot],
+    timestamp: i64,
+    max_age_secs: i64,
+) -> Option<&OrderBookSnapshot> {
+    let index = books.partition_point(|book| book.timestamp <= timestamp);
+    let book = books.get(index.checked_sub(1)?)?;
+    (timestamp - book.timestamp <= max_age_secs).then_some(book)
+}
+
 // Generate mock historical data for testing
 pub fn generate_mock_historical_data(
     start: DateTime<Utc>,
@@ -497,7 +884,49 @@ pub fn generate_mock_historical_data(
 }
 
 #[tauri::command]
-pub async fn backtest_run(config: BacktestConfig) -> Result<BacktestResult, String> {
+pub async fn backtest_run(
+    app: tauri::AppHandle,
+    config: BacktestConfig,
+    experiment: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<BacktestResult, AppError> {
+    let wants_orderbooks = config
+        .execution
+        .as_ref()
+        .is_some_and(|model| model.use_orderbooks);
+    let mut orderbooks = Vec::new();
+    if wants_orderbooks {
+        if let Some(replay) = app.try_state::<SharedHistoricalReplayManager>() {
+            orderbooks = replay
+                .read()
+                .await
+                .stored_orderbooks(
+                    &config.symbol,
+                    config.start_date.timestamp(),
+                    config.end_date.timestamp(),
+                )
+                .await
+                .logged("backtest_run")?;
+        }
+    }
+    let result = run_backtest(config, orderbooks)
+        .await
+        .logged("backtest_run")?;
+    if let Some(tracker) = app.try_state::<SharedExperimentTracker>() {
+        tracker
+            .write()
+            .await
+            .log_backtest(&result, experiment, tags.unwrap_or_default());
+    }
+    Ok(result)
+}
+
+/// Runs the demo strategy. `orderbooks` must be sorted by timestamp and are
+/// only used when the config has an execution model.
+pub async fn run_backtest(
+    config: BacktestConfig,
+    orderbooks: Vec<OrderBookSnapshot>,
+) -> Result<BacktestResult, String> {
     // In a real implementation, fetch historical data from database or API
     let interval_minutes = match config.data_interval.as_str() {
         "1m" => 1,
@@ -516,6 +945,11 @@ pub async fn backtest_run(config: BacktestConfig) -> Result<BacktestResult, Stri
         100.0, // Initial price
     );
 
+    let max_snapshot_age = config
+        .execution
+        .as_ref()
+        .map(|model| model.max_snapshot_age_secs)
+        .unwrap_or(0);
     let mut engine = BacktestEngine::new(config);
 
     // Simple strategy for demonstration: Moving average crossover
@@ -528,6 +962,12 @@ pub async fn backtest_run(config: BacktestConfig) -> Result<BacktestResult, Stri
             continue;
         }
 
+        let book = orderbook_at(&orderbooks, data.timestamp.timestamp(), max_snapshot_age);
+        engine.set_market_context(data.volume, book.cloned());
+        if engine.has_pending_exit() {
+            engine.continue_pending_exit(data.timestamp, data.close);
+        }
+
         // Calculate moving averages
         let short_ma: f64 = historical_data[i - short_period..i]
             .iter()
@@ -573,7 +1013,7 @@ pub async fn backtest_run(config: BacktestConfig) -> Result<BacktestResult, Stri
     // Close any open positions at the end
     if engine.current_position.is_some() {
         let last_data = historical_data.last().unwrap();
-        engine.execute_sell(
+        engine.close_position(
             last_data.timestamp,
             last_data.close,
             Some("END_OF_PERIOD".to_string()),
@@ -602,3 +1042,63 @@ mod rand {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(timestamp: i64) -> OrderBookSnapshot {
+        OrderBookSnapshot {
+            timestamp,
+            symbol: "SOL".to_string(),
+            bids: vec![(99.0, 10.0), (98.0, 10.0)],
+            asks: vec![(102.0, 10.0), (101.0, 10.0)],
+        }
+    }
+
+    #[test]
+    fn buy_walks_asks_from_the_best_level() {
+        let model = ExecutionModel {
+            allow_partial_fills: false,
+            ..ExecutionModel::default()
+        };
+        let snapshot = book(0);
+
+        let fill = simulate_fill(&model, true, 100.0, 15.0, 0.0, Some(&snapshot), 0.0);
+        assert!(fill.from_orderbook);
+        assert_eq!(fill.quantity, 15.0);
+        assert!((fill.average_price - (10.0 * 101.0 + 5.0 * 102.0) / 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn partial_fills_respect_participation_and_depth() {
+        let model = ExecutionModel {
+            max_participation_rate: 0.5,
+            route_fee_bps: 10.0,
+            ..ExecutionModel::default()
+        };
+
+        // Half of a 20-unit bar, but the bid side only holds 20 either way.
+        let snapshot = book(0);
+        let fill = simulate_fill(&model, false, 100.0, 50.0, 20.0, Some(&snapshot), 0.0);
+        assert_eq!(fill.quantity, 10.0);
+        assert!((fill.average_price - 99.0).abs() < 1e-9);
+        assert!((fill.route_fee - 10.0 * 99.0 * 0.001).abs() < 1e-9);
+
+        // Without a book the same order pays square-root impact on volume.
+        let fill = simulate_fill(&model, true, 100.0, 5.0, 20.0, None, 0.0);
+        assert!(!fill.from_orderbook);
+        assert!((fill.average_price - 100.0 * (1.0 + 0.1 * 0.25f64.sqrt())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stale_snapshots_are_ignored() {
+        let books = vec![book(0), book(600)];
+        assert_eq!(
+            orderbook_at(&books, 650, 300).map(|b| b.timestamp),
+            Some(600)
+        );
+        assert!(orderbook_at(&books, 1_000, 300).is_none());
+        assert!(orderbook_at(&books, -1, 300).is_none());
+    }
+}
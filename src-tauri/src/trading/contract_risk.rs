@@ -1,6 +1,7 @@
 use chrono::{DateTime, Duration, Utc};
+use crate::chains::{RoutingHint, RpcPool, SharedRpcPool};
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
-use solana_client::rpc_client::RpcClient;
 use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
@@ -144,7 +145,7 @@ impl HeliusClient {
 }
 
 pub struct ContractVerificationService {
-    rpc_client: RpcClient,
+    rpc_pool: SharedRpcPool,
     helius_client: HeliusClient,
     cache: Arc<RwLock<HashMap<String, ContractAssessment>>>,
     monitored_contracts: Arc<RwLock<HashSet<String>>>,
@@ -152,8 +153,11 @@ pub struct ContractVerificationService {
 }
 
 impl ContractVerificationService {
-    pub async fn new(app_handle: &AppHandle) -> Result<Self, ContractRiskError> {
-        let app_dir = app_handle.path().app_data_dir().map_err(|_| {
+    pub async fn new(
+        app_handle: &AppHandle,
+        rpc_pool: SharedRpcPool,
+    ) -> Result<Self, ContractRiskError> {
+        let app_dir = app_handle.path().profile_data_dir().map_err(|_| {
             ContractRiskError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 "Unable to resolve app data directory",
@@ -166,7 +170,7 @@ impl ContractVerificationService {
         let pool = SqlitePool::connect(&db_url).await?;
 
         let service = Self {
-            rpc_client: RpcClient::new("https://api.mainnet-beta.solana.com".to_string()),
+            rpc_pool,
             helius_client: HeliusClient::new(std::env::var("HELIUS_API_KEY").ok()),
             cache: Arc::new(RwLock::new(HashMap::new())),
             monitored_contracts: Arc::new(RwLock::new(HashSet::new())),
@@ -347,14 +351,18 @@ impl ContractVerificationService {
         let deployment_time = now - Duration::days(deployment_offset);
         let fee_percent = ((hash % 1200) as f64) / 100.0;
 
-        let rpc_verified = self
-            .rpc_client
-            .get_account_with_commitment(&address.parse().map_err(|_| {
-                ContractRiskError::Validation("Invalid contract address".to_string())
-            })?,
-            solana_sdk::commitment_config::CommitmentConfig::processed())
-            .map(|response| response.value.is_some())
-            .unwrap_or(false);
+        let pubkey: solana_sdk::pubkey::Pubkey = address
+            .parse()
+            .map_err(|_| ContractRiskError::Validation("Invalid contract address".to_string()))?;
+        let rpc_verified = RpcPool::call(&self.rpc_pool, RoutingHint::Read, move |client| {
+            client.get_account_with_commitment(
+                &pubkey,
+                solana_sdk::commitment_config::CommitmentConfig::processed(),
+            )
+        })
+        .await
+        .map(|response| response.value.is_some())
+        .unwrap_or(false);
 
         let helius_verified = self.helius_client.check_verification(address).await;
         let code_verified = helius_verified.unwrap_or(rpc_verified || hash % 5 != 0);
@@ -1,3 +1,4 @@
+use crate::errors::{AppError, CommandResultExt};
 use crate::trading::contract_risk::{ContractAssessment, RiskEvent, SharedContractRiskService};
 use crate::trading::safety::{SafetyCheckRequest, SafetyCheckResult, SharedSafetyEngine};
 use serde::{Deserialize, Serialize};
@@ -15,12 +16,12 @@ pub struct PreTradeSafetyResult {
 pub async fn assess_contract_risk(
     contract_address: String,
     service: State<'_, SharedContractRiskService>,
-) -> Result<ContractAssessment, String> {
+) -> Result<ContractAssessment, AppError> {
     let service = service.read().await;
     service
         .assess_contract(&contract_address)
         .await
-        .map_err(|err| err.to_string())
+        .logged("assess_contract_risk")
 }
 
 #[tauri::command]
@@ -28,19 +29,19 @@ pub async fn get_contract_risk_events(
     contract_address: String,
     limit: Option<i64>,
     service: State<'_, SharedContractRiskService>,
-) -> Result<Vec<RiskEvent>, String> {
+) -> Result<Vec<RiskEvent>, AppError> {
     let service = service.read().await;
     service
         .list_risk_events(&contract_address, limit.unwrap_or(25))
         .await
-        .map_err(|err| err.to_string())
+        .logged("get_contract_risk_events")
 }
 
 #[tauri::command]
 pub async fn monitor_contract(
     contract_address: String,
     service: State<'_, SharedContractRiskService>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let service = service.read().await;
     service.monitor_contract(&contract_address).await;
     Ok(())
@@ -50,7 +51,7 @@ pub async fn monitor_contract(
 pub async fn unmonitor_contract(
     contract_address: String,
     service: State<'_, SharedContractRiskService>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let service = service.read().await;
     service.unmonitor_contract(&contract_address).await;
     Ok(())
@@ -59,7 +60,7 @@ pub async fn unmonitor_contract(
 #[tauri::command]
 pub async fn list_monitored_contracts(
     service: State<'_, SharedContractRiskService>,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<String>, AppError> {
     let service = service.read().await;
     Ok(service.list_monitored_contracts().await)
 }
@@ -67,12 +68,12 @@ pub async fn list_monitored_contracts(
 #[tauri::command]
 pub async fn refresh_monitored_contracts(
     service: State<'_, SharedContractRiskService>,
-) -> Result<Vec<ContractAssessment>, String> {
+) -> Result<Vec<ContractAssessment>, AppError> {
     let service = service.read().await;
     service
         .refresh_monitored_contracts()
         .await
-        .map_err(|err| err.to_string())
+        .logged("refresh_monitored_contracts")
 }
 
 #[tauri::command]
@@ -81,13 +82,13 @@ pub async fn pre_trade_contract_check(
     mut request: SafetyCheckRequest,
     service: State<'_, SharedContractRiskService>,
     safety_engine: State<'_, SharedSafetyEngine>,
-) -> Result<PreTradeSafetyResult, String> {
+) -> Result<PreTradeSafetyResult, AppError> {
     let assessment = {
         let service = service.read().await;
         service
             .assess_contract(&contract_address)
             .await
-            .map_err(|err| err.to_string())?
+            .logged("pre_trade_contract_check")?
     };
 
     let security_score = ((1.0 - assessment.risk_score) * 100.0).clamp(0.0, 100.0);
@@ -95,7 +96,10 @@ pub async fn pre_trade_contract_check(
 
     let safety_result = {
         let mut engine = safety_engine.write().await;
-        engine.check_trade_safety(request).await?
+        engine
+            .check_trade_safety(request)
+            .await
+            .logged("pre_trade_contract_check")?
     };
 
     Ok(PreTradeSafetyResult {
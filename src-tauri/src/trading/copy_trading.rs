@@ -1,3 +1,7 @@
+use crate::academy::{enforce_feature_gate, GatedFeature, SharedAcademyEngine, SharedFeatureGate};
+use crate::errors::{AppError, CommandResultExt};
+use crate::profiles::ProfilePaths;
+use crate::trading::token_policy::{check_token_policy, ExecutionPath};
 use crate::utils::Rfc3339DateTime;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -379,6 +383,24 @@ impl CopyTradeDatabase {
         .await
     }
 
+    /// Signatures of the copies placed from `wallet_address`.
+    pub async fn copied_signatures(&self, wallet_address: &str) -> Result<Vec<String>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT e.copied_tx_signature
+            FROM copy_trade_executions e
+            JOIN copy_trade_configs c ON c.id = e.config_id
+            WHERE c.wallet_address = ?1 AND e.copied_tx_signature IS NOT NULL
+            "#,
+        )
+        .bind(wallet_address)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter()
+            .map(|row| row.try_get("copied_tx_signature"))
+            .collect()
+    }
+
     pub async fn daily_trade_count(&self, config_id: &str) -> Result<i64, sqlx::Error> {
         let row = sqlx::query(
             r#"
@@ -731,6 +753,12 @@ impl CopyTradeManager {
         config: &CopyTradeConfig,
         activity: &WalletActivity,
     ) -> Result<TradeDecision, String> {
+        if let Err(reason) =
+            check_token_policy(ExecutionPath::CopyTrading, &activity.output_mint).await
+        {
+            return Ok(TradeDecision::Skip(reason));
+        }
+
         let allocation_amount =
             activity.amount * (config.allocation_percentage / 100.0) * config.multiplier;
 
@@ -945,7 +973,7 @@ pub async fn init_copy_trading(app_handle: &AppHandle) -> Result<(), String> {
 
     let app_dir = app_handle
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|e| format!("Unable to resolve app data directory: {}", e))?;
     std::fs::create_dir_all(&app_dir)
         .map_err(|e| format!("Failed to create app data directory: {e}"))?;
@@ -981,6 +1009,21 @@ pub async fn init_copy_trading(app_handle: &AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Copy-trade signatures for a wallet, or none when copy trading has not
+/// been initialized this session.
+pub async fn copy_trade_signatures(wallet_address: &str) -> Result<Vec<String>, String> {
+    let Some(state) = COPY_TRADING_STATE.get() else {
+        return Ok(Vec::new());
+    };
+    state
+        .db
+        .read()
+        .await
+        .copied_signatures(wallet_address)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 fn require_state<'a>() -> Result<&'a CopyTradingState, String> {
     COPY_TRADING_STATE
         .get()
@@ -988,69 +1031,111 @@ fn require_state<'a>() -> Result<&'a CopyTradingState, String> {
 }
 
 #[tauri::command]
-pub async fn copy_trading_init(handle: AppHandle) -> Result<(), String> {
-    init_copy_trading(&handle).await
+pub async fn copy_trading_init(handle: AppHandle) -> Result<(), AppError> {
+    init_copy_trading(&handle).await.logged("copy_trading_init")
 }
 
 #[tauri::command]
 pub async fn copy_trading_create(
+    handle: AppHandle,
     request: CreateCopyTradeRequest,
-) -> Result<CopyTradeConfig, String> {
-    let state = require_state()?;
-    state.manager.create_copy_trade(request).await
+    gate: tauri::State<'_, SharedFeatureGate>,
+    academy: tauri::State<'_, SharedAcademyEngine>,
+) -> Result<CopyTradeConfig, AppError> {
+    enforce_feature_gate(GatedFeature::CopyTrading, &handle, &gate, &academy)
+        .await
+        .logged("copy_trading_create")?;
+    let state = require_state().logged("copy_trading_create")?;
+    state
+        .manager
+        .create_copy_trade(request)
+        .await
+        .logged("copy_trading_create")
 }
 
 #[tauri::command]
-pub async fn copy_trading_list(wallet_address: String) -> Result<Vec<CopyTradeConfig>, String> {
-    let state = require_state()?;
-    state.manager.list_copy_trades(&wallet_address).await
+pub async fn copy_trading_list(wallet_address: String) -> Result<Vec<CopyTradeConfig>, AppError> {
+    let state = require_state().logged("copy_trading_list")?;
+    state
+        .manager
+        .list_copy_trades(&wallet_address)
+        .await
+        .logged("copy_trading_list")
 }
 
 #[tauri::command]
-pub async fn copy_trading_get(id: String) -> Result<CopyTradeConfig, String> {
-    let state = require_state()?;
-    state.manager.get_copy_trade(&id).await
+pub async fn copy_trading_get(id: String) -> Result<CopyTradeConfig, AppError> {
+    let state = require_state().logged("copy_trading_get")?;
+    state
+        .manager
+        .get_copy_trade(&id)
+        .await
+        .logged("copy_trading_get")
 }
 
 #[tauri::command]
-pub async fn copy_trading_pause(id: String) -> Result<(), String> {
-    let state = require_state()?;
-    state.manager.pause_copy_trade(&id).await
+pub async fn copy_trading_pause(id: String) -> Result<(), AppError> {
+    let state = require_state().logged("copy_trading_pause")?;
+    state
+        .manager
+        .pause_copy_trade(&id)
+        .await
+        .logged("copy_trading_pause")
 }
 
 #[tauri::command]
-pub async fn copy_trading_resume(id: String) -> Result<CopyTradeConfig, String> {
-    let state = require_state()?;
-    state.manager.resume_copy_trade(&id).await
+pub async fn copy_trading_resume(id: String) -> Result<CopyTradeConfig, AppError> {
+    let state = require_state().logged("copy_trading_resume")?;
+    state
+        .manager
+        .resume_copy_trade(&id)
+        .await
+        .logged("copy_trading_resume")
 }
 
 #[tauri::command]
-pub async fn copy_trading_delete(id: String) -> Result<(), String> {
-    let state = require_state()?;
-    state.manager.delete_copy_trade(&id).await
+pub async fn copy_trading_delete(id: String) -> Result<(), AppError> {
+    let state = require_state().logged("copy_trading_delete")?;
+    state
+        .manager
+        .delete_copy_trade(&id)
+        .await
+        .logged("copy_trading_delete")
 }
 
 #[tauri::command]
-pub async fn copy_trading_history(id: String) -> Result<Vec<CopyTradeExecution>, String> {
-    let state = require_state()?;
-    state.manager.get_executions(&id).await
+pub async fn copy_trading_history(id: String) -> Result<Vec<CopyTradeExecution>, AppError> {
+    let state = require_state().logged("copy_trading_history")?;
+    state
+        .manager
+        .get_executions(&id)
+        .await
+        .logged("copy_trading_history")
 }
 
 #[tauri::command]
-pub async fn copy_trading_performance(id: String) -> Result<CopyTradePerformance, String> {
-    let state = require_state()?;
-    state.manager.get_performance(&id).await
+pub async fn copy_trading_performance(id: String) -> Result<CopyTradePerformance, AppError> {
+    let state = require_state().logged("copy_trading_performance")?;
+    state
+        .manager
+        .get_performance(&id)
+        .await
+        .logged("copy_trading_performance")
 }
 
 #[tauri::command]
-pub async fn copy_trading_process_activity(activity: WalletActivity) -> Result<(), String> {
-    let state = require_state()?;
-    state.manager.process_wallet_activity(activity).await
+pub async fn copy_trading_process_activity(activity: WalletActivity) -> Result<(), AppError> {
+    let state = require_state().logged("copy_trading_process_activity")?;
+    state
+        .manager
+        .process_wallet_activity(activity)
+        .await
+        .logged("copy_trading_process_activity")
 }
 
 #[tauri::command]
-pub async fn copy_trading_followed_wallets() -> Result<Vec<String>, String> {
-    let state = require_state()?;
+pub async fn copy_trading_followed_wallets() -> Result<Vec<String>, AppError> {
+    let state = require_state().logged("copy_trading_followed_wallets")?;
     Ok(state.manager.followed_wallets().await)
 }
 
@@ -1,6 +1,6 @@
 use crate::trading::types::{Order, OrderStatus, OrderType};
-use chrono::Utc;
-use sqlx::{Pool, Sqlite, SqlitePool};
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Row, Sqlite, SqlitePool};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -37,6 +37,7 @@ impl OrderDatabase {
                 limit_price REAL,
                 stop_price REAL,
                 trailing_percent REAL,
+                trailing_amount REAL,
                 highest_price REAL,
                 lowest_price REAL,
                 linked_order_id TEXT,
@@ -54,6 +55,22 @@ impl OrderDatabase {
         .execute(&self.pool)
         .await?;
 
+        // Databases created before absolute trailing offsets existed lack
+        // the column.
+        let has_trailing_amount = sqlx::query("PRAGMA table_info(orders)")
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .any(|row| {
+                row.try_get::<String, _>("name")
+                    .is_ok_and(|name| name == "trailing_amount")
+            });
+        if !has_trailing_amount {
+            sqlx::query("ALTER TABLE orders ADD COLUMN trailing_amount REAL")
+                .execute(&self.pool)
+                .await?;
+        }
+
         sqlx::query(
             r#"
             CREATE INDEX IF NOT EXISTS idx_orders_status ON orders(status);
@@ -69,48 +86,60 @@ impl OrderDatabase {
     }
 
     pub async fn create_order(&self, order: &Order) -> Result<(), sqlx::Error> {
-        sqlx::query(
+        self.write_order(order, "INSERT").await
+    }
+
+    /// Inserts the order, replacing any stored row with the same id.
+    pub async fn upsert_order(&self, order: &Order) -> Result<(), sqlx::Error> {
+        self.write_order(order, "INSERT OR REPLACE").await
+    }
+
+    async fn write_order(&self, order: &Order, verb: &str) -> Result<(), sqlx::Error> {
+        let sql = format!(
             r#"
-            INSERT INTO orders (
+            {} INTO orders (
                 id, order_type, side, status, input_mint, output_mint,
                 input_symbol, output_symbol, amount, filled_amount,
-                limit_price, stop_price, trailing_percent,
+                limit_price, stop_price, trailing_percent, trailing_amount,
                 highest_price, lowest_price, linked_order_id,
                 slippage_bps, priority_fee_micro_lamports, wallet_address,
                 created_at, updated_at, triggered_at, tx_signature, error_message
             ) VALUES (
                 ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10,
                 ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19,
-                ?20, ?21, ?22, ?23, ?24
+                ?20, ?21, ?22, ?23, ?24, ?25
             )
             "#,
-        )
-        .bind(&order.id)
-        .bind(order.order_type.to_string())
-        .bind(order.side.to_string())
-        .bind(order.status.to_string())
-        .bind(&order.input_mint)
-        .bind(&order.output_mint)
-        .bind(&order.input_symbol)
-        .bind(&order.output_symbol)
-        .bind(order.amount)
-        .bind(order.filled_amount)
-        .bind(order.limit_price)
-        .bind(order.stop_price)
-        .bind(order.trailing_percent)
-        .bind(order.highest_price)
-        .bind(order.lowest_price)
-        .bind(&order.linked_order_id)
-        .bind(order.slippage_bps)
-        .bind(order.priority_fee_micro_lamports)
-        .bind(&order.wallet_address)
-        .bind(order.created_at.to_rfc3339())
-        .bind(order.updated_at.to_rfc3339())
-        .bind(order.triggered_at.map(|t| t.to_rfc3339()))
-        .bind(&order.tx_signature)
-        .bind(&order.error_message)
-        .execute(&self.pool)
-        .await?;
+            verb
+        );
+        sqlx::query(&sql)
+            .bind(&order.id)
+            .bind(order.order_type.to_string())
+            .bind(order.side.to_string())
+            .bind(order.status.to_string())
+            .bind(&order.input_mint)
+            .bind(&order.output_mint)
+            .bind(&order.input_symbol)
+            .bind(&order.output_symbol)
+            .bind(order.amount)
+            .bind(order.filled_amount)
+            .bind(order.limit_price)
+            .bind(order.stop_price)
+            .bind(order.trailing_percent)
+            .bind(order.trailing_amount)
+            .bind(order.highest_price)
+            .bind(order.lowest_price)
+            .bind(&order.linked_order_id)
+            .bind(order.slippage_bps)
+            .bind(order.priority_fee_micro_lamports)
+            .bind(&order.wallet_address)
+            .bind(order.created_at.to_rfc3339())
+            .bind(order.updated_at.to_rfc3339())
+            .bind(order.triggered_at.map(|t| t.to_rfc3339()))
+            .bind(&order.tx_signature)
+            .bind(&order.error_message)
+            .execute(&self.pool)
+            .await?;
 
         Ok(())
     }
@@ -124,6 +153,16 @@ impl OrderDatabase {
         Ok(order)
     }
 
+    pub async fn get_orders_by_tx_signature(
+        &self,
+        tx_signature: &str,
+    ) -> Result<Vec<Order>, sqlx::Error> {
+        sqlx::query_as::<_, Order>("SELECT * FROM orders WHERE tx_signature = ?1")
+            .bind(tx_signature)
+            .fetch_all(&self.pool)
+            .await
+    }
+
     pub async fn get_active_orders(&self, wallet_address: &str) -> Result<Vec<Order>, sqlx::Error> {
         let orders = sqlx::query_as::<_, Order>(
             r#"
@@ -175,6 +214,27 @@ impl OrderDatabase {
         Ok(orders)
     }
 
+    /// Orders created within the range, oldest first; either end is open
+    /// when `None`.
+    pub async fn get_orders_between(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Order>, sqlx::Error> {
+        sqlx::query_as::<_, Order>(
+            r#"
+            SELECT * FROM orders
+            WHERE (?1 IS NULL OR created_at >= ?1)
+              AND (?2 IS NULL OR created_at <= ?2)
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(from.map(|t| t.to_rfc3339()))
+        .bind(to.map(|t| t.to_rfc3339()))
+        .fetch_all(&self.pool)
+        .await
+    }
+
     pub async fn update_order_status(
         &self,
         id: &str,
@@ -256,11 +316,37 @@ impl OrderDatabase {
         Ok(())
     }
 
+    pub async fn delete_order(&self, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM orders WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
     pub async fn cancel_order(&self, id: &str) -> Result<(), sqlx::Error> {
         self.update_order_status(id, OrderStatus::Cancelled, None)
             .await
     }
 
+    pub async fn get_pending_linked_orders(
+        &self,
+        linked_id: &str,
+    ) -> Result<Vec<Order>, sqlx::Error> {
+        let orders = sqlx::query_as::<_, Order>(
+            r#"
+            SELECT * FROM orders
+            WHERE linked_order_id = ?1 AND status IN ('pending', 'partially_filled')
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(linked_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(orders)
+    }
+
     pub async fn cancel_linked_orders(&self, linked_id: &str) -> Result<(), sqlx::Error> {
         let now = Utc::now().to_rfc3339();
 
@@ -0,0 +1,755 @@
+//! Experiment tracking for strategy research.
+//!
+//! Every backtest and optimizer run is logged as a run with free-form
+//! parameters, numeric metrics and artifacts, so results can be tagged,
+//! compared and exported across sessions. Artifacts are written to their
+//! own files next to the run index to keep the index small.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::backtesting::BacktestResult;
+use super::optimizer::OptimizationRun;
+use crate::errors::{AppError, CommandResultExt};
+use crate::profiles::ProfilePaths;
+
+const EXPERIMENTS_FILE: &str = "experiments.json";
+const ARTIFACTS_DIR: &str = "experiment_artifacts";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RunSource {
+    Backtest,
+    Optimizer,
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunArtifact {
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExperimentRun {
+    pub id: String,
+    /// Runs sharing a name form one experiment.
+    pub experiment: String,
+    pub source: RunSource,
+    pub status: String,
+    pub params: BTreeMap<String, Value>,
+    pub metrics: BTreeMap<String, f64>,
+    #[serde(default)]
+    pub artifacts: Vec<RunArtifact>,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Backtest or optimizer run this was logged from.
+    #[serde(default)]
+    pub source_id: Option<String>,
+    pub started_at: i64,
+    pub completed_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExperimentFilter {
+    pub experiment: Option<String>,
+    pub source: Option<RunSource>,
+    /// Runs must carry every tag listed.
+    pub tags: Vec<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRunInput {
+    pub experiment: String,
+    #[serde(default)]
+    pub params: BTreeMap<String, Value>,
+    #[serde(default)]
+    pub metrics: BTreeMap<String, f64>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+/// One parameter or metric across the compared runs, in run order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonRow {
+    pub key: String,
+    pub values: Vec<Option<Value>>,
+    /// Whether the runs disagree on this key.
+    pub differs: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExperimentComparison {
+    pub run_ids: Vec<String>,
+    pub params: Vec<ComparisonRow>,
+    pub metrics: Vec<ComparisonRow>,
+    /// Run with the highest value per metric.
+    pub best_by_metric: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExperimentExportFormat {
+    Json,
+    Csv,
+}
+
+pub struct ExperimentTracker {
+    runs: Vec<ExperimentRun>,
+    path: Option<PathBuf>,
+    artifacts_dir: Option<PathBuf>,
+}
+
+pub type SharedExperimentTracker = Arc<RwLock<ExperimentTracker>>;
+
+impl ExperimentTracker {
+    pub fn new(app: &AppHandle) -> Self {
+        let dir = app.path().profile_data_dir().ok();
+        let path = dir.as_ref().map(|dir| dir.join(EXPERIMENTS_FILE));
+        let runs = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            runs,
+            path,
+            artifacts_dir: dir.map(|dir| dir.join(ARTIFACTS_DIR)),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ExperimentRun> {
+        self.runs.iter().find(|run| run.id == id)
+    }
+
+    fn get_mut(&mut self, id: &str) -> Result<&mut ExperimentRun, String> {
+        self.runs
+            .iter_mut()
+            .find(|run| run.id == id)
+            .ok_or_else(|| format!("Experiment run {id} not found"))
+    }
+
+    /// Newest first.
+    pub fn list(&self, filter: &ExperimentFilter) -> Vec<ExperimentRun> {
+        let mut runs: Vec<ExperimentRun> = self
+            .runs
+            .iter()
+            .filter(|run| {
+                filter
+                    .experiment
+                    .as_ref()
+                    .map_or(true, |name| &run.experiment == name)
+                    && filter.source.map_or(true, |source| run.source == source)
+                    && filter.tags.iter().all(|tag| run.tags.contains(tag))
+            })
+            .cloned()
+            .collect();
+        runs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        if let Some(limit) = filter.limit {
+            runs.truncate(limit);
+        }
+        runs
+    }
+
+    pub fn log_run(&mut self, run: ExperimentRun) -> ExperimentRun {
+        self.runs.push(run.clone());
+        self.save();
+        run
+    }
+
+    pub fn log_backtest(
+        &mut self,
+        result: &BacktestResult,
+        experiment: Option<String>,
+        tags: Vec<String>,
+    ) -> ExperimentRun {
+        let mut run = ExperimentRun {
+            id: Uuid::new_v4().to_string(),
+            experiment: experiment.unwrap_or_else(|| result.config.strategy_id.clone()),
+            source: RunSource::Backtest,
+            status: "completed".to_string(),
+            params: flatten_object(&result.config),
+            metrics: numeric_fields(&result.metrics),
+            artifacts: Vec::new(),
+            tags: tags.into_iter().collect(),
+            notes: None,
+            source_id: Some(result.id.clone()),
+            started_at: result.started_at.timestamp_millis(),
+            completed_at: Some(result.completed_at.timestamp_millis()),
+        };
+        for (name, value) in [
+            ("equity_curve", serde_json::to_value(&result.equity_curve)),
+            ("trades", serde_json::to_value(&result.trades)),
+        ] {
+            if let Ok(value) = value {
+                if let Err(e) = self.write_artifact(&mut run, name, &value) {
+                    eprintln!("Failed to store backtest artifact {name}: {e}");
+                }
+            }
+        }
+        self.log_run(run)
+    }
+
+    /// Logs the best parameter set of a finished optimization as the run's
+    /// parameters, with every evaluated candidate kept as an artifact.
+    pub fn log_optimization(
+        &mut self,
+        optimization: &OptimizationRun,
+        experiment: Option<String>,
+        tags: Vec<String>,
+    ) -> ExperimentRun {
+        let config = &optimization.config;
+        let mut params = flatten_object(&config.backtest_config);
+        params.insert("method".into(), Value::from(config.method.clone()));
+        params.insert(
+            "optimization_target".into(),
+            Value::from(config.optimization_target.clone()),
+        );
+        let mut metrics = BTreeMap::new();
+        if let Some(best) = &optimization.best_result {
+            for (name, value) in &best.parameter_set {
+                params.insert(format!("param.{name}"), Value::from(*value));
+            }
+            metrics = numeric_fields(&best.metrics);
+            metrics.insert("score".into(), best.score);
+        }
+        metrics.insert("candidates".into(), optimization.results.len() as f64);
+        if let Some(efficiency) = optimization
+            .walk_forward
+            .as_ref()
+            .and_then(|report| report.efficiency)
+        {
+            metrics.insert("walk_forward_efficiency".into(), efficiency);
+        }
+
+        let mut tags: BTreeSet<String> = tags.into_iter().collect();
+        tags.insert(config.method.clone());
+        let mut run = ExperimentRun {
+            id: Uuid::new_v4().to_string(),
+            experiment: experiment.unwrap_or_else(|| config.strategy_id.clone()),
+            source: RunSource::Optimizer,
+            status: optimization.status.clone(),
+            params,
+            metrics,
+            artifacts: Vec::new(),
+            tags,
+            notes: optimization.error.clone(),
+            source_id: Some(optimization.id.clone()),
+            started_at: optimization.started_at,
+            completed_at: optimization.completed_at,
+        };
+        if let Ok(value) = serde_json::to_value(&optimization.results) {
+            if let Err(e) = self.write_artifact(&mut run, "results", &value) {
+                eprintln!("Failed to store optimizer artifact: {e}");
+            }
+        }
+        self.log_run(run)
+    }
+
+    pub fn add_artifact(
+        &mut self,
+        id: &str,
+        name: &str,
+        content: &Value,
+    ) -> Result<RunArtifact, String> {
+        let mut run = self.get_mut(id)?.clone();
+        let artifact = self.write_artifact(&mut run, name, content)?;
+        *self.get_mut(id)? = run;
+        self.save();
+        Ok(artifact)
+    }
+
+    fn write_artifact(
+        &self,
+        run: &mut ExperimentRun,
+        name: &str,
+        content: &Value,
+    ) -> Result<RunArtifact, String> {
+        let dir = self
+            .artifacts_dir
+            .as_ref()
+            .ok_or("Experiment storage is unavailable")?
+            .join(&run.id);
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let file_name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let path = dir.join(format!("{file_name}.json"));
+        let contents = serde_json::to_string(content).map_err(|e| e.to_string())?;
+        fs::write(&path, &contents).map_err(|e| e.to_string())?;
+
+        let artifact = RunArtifact {
+            name: name.to_string(),
+            path: path.to_string_lossy().into_owned(),
+            size_bytes: contents.len() as u64,
+            created_at: Utc::now().timestamp_millis(),
+        };
+        run.artifacts.retain(|existing| existing.name != name);
+        run.artifacts.push(artifact.clone());
+        Ok(artifact)
+    }
+
+    pub fn set_tags(
+        &mut self,
+        id: &str,
+        add: Vec<String>,
+        remove: Vec<String>,
+    ) -> Result<ExperimentRun, String> {
+        let run = self.get_mut(id)?;
+        for tag in remove {
+            run.tags.remove(&tag);
+        }
+        run.tags.extend(
+            add.into_iter()
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty()),
+        );
+        let run = run.clone();
+        self.save();
+        Ok(run)
+    }
+
+    pub fn set_notes(&mut self, id: &str, notes: Option<String>) -> Result<ExperimentRun, String> {
+        let run = self.get_mut(id)?;
+        run.notes = notes;
+        let run = run.clone();
+        self.save();
+        Ok(run)
+    }
+
+    pub fn delete(&mut self, id: &str) -> Result<(), String> {
+        let before = self.runs.len();
+        self.runs.retain(|run| run.id != id);
+        if self.runs.len() == before {
+            return Err(format!("Experiment run {id} not found"));
+        }
+        if let Some(dir) = &self.artifacts_dir {
+            let _ = fs::remove_dir_all(dir.join(id));
+        }
+        self.save();
+        Ok(())
+    }
+
+    pub fn compare(&self, ids: &[String]) -> Result<ExperimentComparison, String> {
+        let runs = ids
+            .iter()
+            .map(|id| {
+                self.get(id)
+                    .ok_or_else(|| format!("Experiment run {id} not found"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(compare_runs(&runs))
+    }
+
+    pub fn export(&self, ids: &[String], format: ExperimentExportFormat) -> Result<String, String> {
+        let runs = if ids.is_empty() {
+            self.list(&ExperimentFilter::default())
+        } else {
+            ids.iter()
+                .map(|id| {
+                    self.get(id)
+                        .cloned()
+                        .ok_or_else(|| format!("Experiment run {id} not found"))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        match format {
+            ExperimentExportFormat::Json => {
+                serde_json::to_string_pretty(&runs).map_err(|e| e.to_string())
+            }
+            ExperimentExportFormat::Csv => Ok(runs_to_csv(&runs)),
+        }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        match serde_json::to_string_pretty(&self.runs) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(path, contents) {
+                    eprintln!("Failed to save experiments: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize experiments: {}", e),
+        }
+    }
+}
+
+/// Top-level fields of a serializable struct as parameters.
+fn flatten_object<T: Serialize>(value: &T) -> BTreeMap<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => map.into_iter().collect(),
+        _ => BTreeMap::new(),
+    }
+}
+
+fn numeric_fields<T: Serialize>(value: &T) -> BTreeMap<String, f64> {
+    flatten_object(value)
+        .into_iter()
+        .filter_map(|(key, value)| value.as_f64().map(|number| (key, number)))
+        .collect()
+}
+
+pub fn compare_runs(runs: &[&ExperimentRun]) -> ExperimentComparison {
+    let param_keys: BTreeSet<&String> = runs.iter().flat_map(|run| run.params.keys()).collect();
+    let params = param_keys
+        .into_iter()
+        .map(|key| comparison_row(key, runs.iter().map(|run| run.params.get(key).cloned())))
+        .collect();
+
+    let metric_keys: BTreeSet<&String> = runs.iter().flat_map(|run| run.metrics.keys()).collect();
+    let mut best_by_metric = HashMap::new();
+    let metrics = metric_keys
+        .into_iter()
+        .map(|key| {
+            if let Some(best) = runs
+                .iter()
+                .filter_map(|run| run.metrics.get(key).map(|value| (run, *value)))
+                .filter(|(_, value)| value.is_finite())
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+            {
+                best_by_metric.insert(key.clone(), best.0.id.clone());
+            }
+            comparison_row(
+                key,
+                runs.iter()
+                    .map(|run| run.metrics.get(key).map(|value| Value::from(*value))),
+            )
+        })
+        .collect();
+
+    ExperimentComparison {
+        run_ids: runs.iter().map(|run| run.id.clone()).collect(),
+        params,
+        metrics,
+        best_by_metric,
+    }
+}
+
+fn comparison_row(key: &str, values: impl Iterator<Item = Option<Value>>) -> ComparisonRow {
+    let values: Vec<Option<Value>> = values.collect();
+    let differs = values.windows(2).any(|pair| pair[0] != pair[1]);
+    ComparisonRow {
+        key: key.to_string(),
+        values,
+        differs,
+    }
+}
+
+/// One row per run; parameter columns are prefixed `param:` and metric
+/// columns `metric:`.
+fn runs_to_csv(runs: &[ExperimentRun]) -> String {
+    let param_keys: BTreeSet<&String> = runs.iter().flat_map(|run| run.params.keys()).collect();
+    let metric_keys: BTreeSet<&String> = runs.iter().flat_map(|run| run.metrics.keys()).collect();
+
+    let mut header = vec![
+        "id".to_string(),
+        "experiment".to_string(),
+        "source".to_string(),
+        "status".to_string(),
+        "tags".to_string(),
+        "started_at".to_string(),
+        "completed_at".to_string(),
+    ];
+    header.extend(param_keys.iter().map(|key| format!("param:{key}")));
+    header.extend(metric_keys.iter().map(|key| format!("metric:{key}")));
+
+    let mut csv = csv_line(&header);
+    for run in runs {
+        let source = serde_json::to_value(run.source)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let mut row = vec![
+            run.id.clone(),
+            run.experiment.clone(),
+            source,
+            run.status.clone(),
+            run.tags.iter().cloned().collect::<Vec<_>>().join(";"),
+            run.started_at.to_string(),
+            run.completed_at
+                .map(|at| at.to_string())
+                .unwrap_or_default(),
+        ];
+        row.extend(param_keys.iter().map(|key| match run.params.get(*key) {
+            Some(Value::String(text)) => text.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        }));
+        row.extend(metric_keys.iter().map(|key| {
+            run.metrics
+                .get(*key)
+                .map(|v| v.to_string())
+                .unwrap_or_default()
+        }));
+        csv.push_str(&csv_line(&row));
+    }
+    csv
+}
+
+fn csv_line(fields: &[String]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push('\n');
+    line
+}
+
+pub fn register_experiment_state(app: &tauri::App) {
+    let tracker: SharedExperimentTracker =
+        Arc::new(RwLock::new(ExperimentTracker::new(app.handle())));
+    app.manage(tracker);
+}
+
+#[tauri::command]
+pub async fn experiments_list(
+    filter: Option<ExperimentFilter>,
+    tracker: State<'_, SharedExperimentTracker>,
+) -> Result<Vec<ExperimentRun>, AppError> {
+    Ok(tracker.read().await.list(&filter.unwrap_or_default()))
+}
+
+#[tauri::command]
+pub async fn experiments_get(
+    id: String,
+    tracker: State<'_, SharedExperimentTracker>,
+) -> Result<Option<ExperimentRun>, AppError> {
+    Ok(tracker.read().await.get(&id).cloned())
+}
+
+#[tauri::command]
+pub async fn experiments_log_run(
+    input: LogRunInput,
+    tracker: State<'_, SharedExperimentTracker>,
+) -> Result<ExperimentRun, AppError> {
+    if input.experiment.trim().is_empty() {
+        return Err(
+            AppError::Validation("Experiment name is required".to_string())
+                .logged("experiments_log_run"),
+        );
+    }
+    let now = Utc::now().timestamp_millis();
+    let run = ExperimentRun {
+        id: Uuid::new_v4().to_string(),
+        experiment: input.experiment,
+        source: RunSource::Manual,
+        status: "completed".to_string(),
+        params: input.params,
+        metrics: input.metrics,
+        artifacts: Vec::new(),
+        tags: input.tags.into_iter().collect(),
+        notes: input.notes,
+        source_id: None,
+        started_at: now,
+        completed_at: Some(now),
+    };
+    Ok(tracker.write().await.log_run(run))
+}
+
+#[tauri::command]
+pub async fn experiments_add_artifact(
+    id: String,
+    name: String,
+    content: Value,
+    tracker: State<'_, SharedExperimentTracker>,
+) -> Result<RunArtifact, AppError> {
+    tracker
+        .write()
+        .await
+        .add_artifact(&id, &name, &content)
+        .logged("experiments_add_artifact")
+}
+
+#[tauri::command]
+pub async fn experiments_tag(
+    id: String,
+    add: Option<Vec<String>>,
+    remove: Option<Vec<String>>,
+    tracker: State<'_, SharedExperimentTracker>,
+) -> Result<ExperimentRun, AppError> {
+    tracker
+        .write()
+        .await
+        .set_tags(&id, add.unwrap_or_default(), remove.unwrap_or_default())
+        .logged("experiments_tag")
+}
+
+#[tauri::command]
+pub async fn experiments_set_notes(
+    id: String,
+    notes: Option<String>,
+    tracker: State<'_, SharedExperimentTracker>,
+) -> Result<ExperimentRun, AppError> {
+    tracker
+        .write()
+        .await
+        .set_notes(&id, notes)
+        .logged("experiments_set_notes")
+}
+
+#[tauri::command]
+pub async fn experiments_delete(
+    id: String,
+    tracker: State<'_, SharedExperimentTracker>,
+) -> Result<(), AppError> {
+    tracker
+        .write()
+        .await
+        .delete(&id)
+        .logged("experiments_delete")
+}
+
+#[tauri::command]
+pub async fn experiments_compare(
+    ids: Vec<String>,
+    tracker: State<'_, SharedExperimentTracker>,
+) -> Result<ExperimentComparison, AppError> {
+    if ids.len() < 2 {
+        return Err(
+            AppError::Validation("Select at least two runs to compare".to_string())
+                .logged("experiments_compare"),
+        );
+    }
+    tracker
+        .read()
+        .await
+        .compare(&ids)
+        .logged("experiments_compare")
+}
+
+/// Exports the given runs, or every run when `ids` is empty.
+#[tauri::command]
+pub async fn experiments_export(
+    ids: Vec<String>,
+    format: ExperimentExportFormat,
+    tracker: State<'_, SharedExperimentTracker>,
+) -> Result<String, AppError> {
+    tracker
+        .read()
+        .await
+        .export(&ids, format)
+        .logged("experiments_export")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run(id: &str, params: Value, metrics: &[(&str, f64)]) -> ExperimentRun {
+        let params = match params {
+            Value::Object(map) => map.into_iter().collect(),
+            _ => BTreeMap::new(),
+        };
+        ExperimentRun {
+            id: id.to_string(),
+            experiment: "ema-cross".to_string(),
+            source: RunSource::Manual,
+            status: "completed".to_string(),
+            params,
+            metrics: metrics
+                .iter()
+                .map(|(key, value)| (key.to_string(), *value))
+                .collect(),
+            artifacts: Vec::new(),
+            tags: BTreeSet::new(),
+            notes: None,
+            source_id: None,
+            started_at: 0,
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn comparison_flags_differences_and_best_runs() {
+        let a = run(
+            "a",
+            json!({ "fast": 9, "symbol": "SOL" }),
+            &[("sharpe_ratio", 1.2), ("max_drawdown_percent", 8.0)],
+        );
+        let b = run(
+            "b",
+            json!({ "fast": 12, "symbol": "SOL" }),
+            &[("sharpe_ratio", 1.8)],
+        );
+
+        let comparison = compare_runs(&[&a, &b]);
+
+        let fast = comparison
+            .params
+            .iter()
+            .find(|row| row.key == "fast")
+            .unwrap();
+        assert!(fast.differs);
+        let symbol = comparison
+            .params
+            .iter()
+            .find(|row| row.key == "symbol")
+            .unwrap();
+        assert!(!symbol.differs);
+        let drawdown = comparison
+            .metrics
+            .iter()
+            .find(|row| row.key == "max_drawdown_percent")
+            .unwrap();
+        assert_eq!(drawdown.values[1], None);
+        assert_eq!(comparison.best_by_metric["sharpe_ratio"], "b");
+    }
+
+    #[test]
+    fn csv_export_has_a_column_per_key_and_quotes_text() {
+        let mut a = run("a", json!({ "note": "fast, tight" }), &[("score", 2.0)]);
+        a.tags.insert("baseline".into());
+        let b = run("b", json!({ "fast": 12 }), &[]);
+
+        let csv = runs_to_csv(&[a, b]);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "id,experiment,source,status,tags,started_at,completed_at,param:fast,param:note,metric:score"
+        );
+        assert_eq!(
+            lines[1],
+            "a,ema-cross,manual,completed,baseline,0,,,\"fast, tight\",2"
+        );
+        assert_eq!(lines[2], "b,ema-cross,manual,completed,,0,,12,,");
+    }
+}
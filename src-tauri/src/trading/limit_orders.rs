@@ -1,6 +1,10 @@
+use crate::errors::{AppError, CommandResultExt};
+use crate::profiles::ProfilePaths;
 use crate::trading::database::{OrderDatabase, SharedOrderDatabase};
 use crate::trading::order_manager::{OrderManager, SharedOrderManager};
-use crate::trading::types::{CreateOrderRequest, Order, OrderStatus};
+use crate::trading::types::{
+    CreateOrderGroupRequest, CreateOrderRequest, Order, OrderGroup, OrderStatus,
+};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
@@ -20,7 +24,7 @@ pub async fn init_trading(app_handle: &AppHandle) -> Result<(), String> {
 
     let app_dir = app_handle
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
 
     std::fs::create_dir_all(&app_dir)
@@ -61,49 +65,72 @@ pub fn require_state<'a>() -> Result<&'a TradingState, String> {
 }
 
 #[tauri::command]
-pub async fn trading_init(handle: AppHandle) -> Result<(), String> {
-    init_trading(&handle).await
+pub async fn trading_init(handle: AppHandle) -> Result<(), AppError> {
+    init_trading(&handle).await.logged("trading_init")
 }
 
 #[tauri::command]
-pub async fn create_order(request: CreateOrderRequest) -> Result<Order, String> {
-    let state = require_state()?;
-    state.manager.create_order(request).await
+pub async fn create_order(request: CreateOrderRequest) -> Result<Order, AppError> {
+    let state = require_state().logged("create_order")?;
+    state
+        .manager
+        .create_order(request)
+        .await
+        .logged("create_order")
 }
 
 #[tauri::command]
-pub async fn cancel_order(order_id: String) -> Result<(), String> {
-    let state = require_state()?;
-    state.manager.cancel_order(&order_id).await
+pub async fn create_order_group(request: CreateOrderGroupRequest) -> Result<OrderGroup, AppError> {
+    let state = require_state().logged("create_order_group")?;
+    state
+        .manager
+        .create_order_group(request)
+        .await
+        .logged("create_order_group")
 }
 
 #[tauri::command]
-pub async fn get_active_orders(wallet_address: String) -> Result<Vec<Order>, String> {
-    let state = require_state()?;
-    state.manager.get_active_orders(&wallet_address).await
+pub async fn cancel_order(order_id: String) -> Result<(), AppError> {
+    let state = require_state().logged("cancel_order")?;
+    state
+        .manager
+        .cancel_order(&order_id)
+        .await
+        .logged("cancel_order")
+}
+
+#[tauri::command]
+pub async fn get_active_orders(wallet_address: String) -> Result<Vec<Order>, AppError> {
+    let state = require_state().logged("get_active_orders")?;
+    state
+        .manager
+        .get_active_orders(&wallet_address)
+        .await
+        .logged("get_active_orders")
 }
 
 #[tauri::command]
 pub async fn get_order_history(
     wallet_address: String,
     limit: Option<i64>,
-) -> Result<Vec<Order>, String> {
-    let state = require_state()?;
+) -> Result<Vec<Order>, AppError> {
+    let state = require_state().logged("get_order_history")?;
     state
         .manager
         .get_order_history(&wallet_address, limit.unwrap_or(100))
         .await
+        .logged("get_order_history")
 }
 
 #[tauri::command]
-pub async fn get_order(order_id: String) -> Result<Order, String> {
-    let state = require_state()?;
-    state.manager.get_order(&order_id).await
+pub async fn get_order(order_id: String) -> Result<Order, AppError> {
+    let state = require_state().logged("get_order")?;
+    state.manager.get_order(&order_id).await.logged("get_order")
 }
 
 #[tauri::command]
-pub async fn acknowledge_order(order_id: String) -> Result<(), String> {
-    let state = require_state()?;
+pub async fn acknowledge_order(order_id: String) -> Result<(), AppError> {
+    let state = require_state().logged("acknowledge_order")?;
     state
         .db
         .write()
@@ -111,6 +138,7 @@ pub async fn acknowledge_order(order_id: String) -> Result<(), String> {
         .update_order_status(&order_id, OrderStatus::Pending, None)
         .await
         .map_err(|e| format!("Failed to acknowledge order: {}", e))
+        .logged("acknowledge_order")
 }
 
 pub fn register_trading_state(app: &AppHandle) {
@@ -0,0 +1,508 @@
+//! Post-trade sandwich detection for the user's own swaps.
+//!
+//! A confirmed swap is checked against the rest of its block: a sandwich is
+//! a signer that swaps in the same direction just before the user and
+//! unwinds into the user's input token just after. The attacker's round
+//! trip profit, measured in the token the user sold, is what the user lost
+//! to the worse price.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_client::rpc_request::RpcRequest;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::RwLock;
+
+use crate::api_config::stored_birdeye_key;
+use crate::chains::{RoutingHint, RpcPool, SharedRpcPool};
+use crate::errors::{AppError, CommandResultExt};
+use crate::notifications::router::SharedNotificationRouter;
+use crate::profiles::ProfilePaths;
+use crate::security::keystore::Keystore;
+use crate::wallet::history_backfill::{
+    fetch_transaction, parse_transaction, symbol_for, HistoricalActivity, PriceOracle, TokenDelta,
+};
+
+const MEV_REPORTS_FILE: &str = "mev_reports.json";
+const MAX_STORED_ANALYSES: usize = 1_000;
+pub const MEV_SANDWICH_EVENT: &str = "mev_sandwich_detected";
+
+/// The front-run and back-run pair found around a swap.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SandwichAttack {
+    pub attacker: String,
+    pub frontrun_signature: String,
+    pub backrun_signature: String,
+    /// Positions within the block, for showing the ordering.
+    pub frontrun_index: usize,
+    pub victim_index: usize,
+    pub backrun_index: usize,
+    /// Attacker profit in the token the user sold.
+    pub extracted_amount: f64,
+    pub extracted_mint: String,
+    pub extracted_symbol: String,
+    pub extracted_usd: Option<f64>,
+    /// Extracted value as a share of the user's input.
+    pub extracted_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MevAnalysis {
+    pub signature: String,
+    pub wallet: String,
+    pub slot: u64,
+    pub block_time: i64,
+    pub sold_mint: String,
+    pub sold_amount: f64,
+    pub bought_mint: String,
+    pub bought_amount: f64,
+    pub sandwich: Option<SandwichAttack>,
+    pub analyzed_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MevReport {
+    pub wallet: String,
+    pub analyzed_swaps: usize,
+    pub sandwiched_swaps: usize,
+    pub sandwich_rate_pct: f64,
+    pub total_extracted_usd: f64,
+    /// Extracted amounts by sold mint, for attacks without a USD price.
+    pub extracted_by_mint: HashMap<String, f64>,
+    pub attacks: Vec<MevAnalysis>,
+}
+
+pub struct MevAnalyzer {
+    analyses: HashMap<String, MevAnalysis>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedMevAnalyzer = Arc<RwLock<MevAnalyzer>>;
+
+impl MevAnalyzer {
+    pub fn new(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(MEV_REPORTS_FILE));
+        let analyses: Vec<MevAnalysis> = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            analyses: analyses
+                .into_iter()
+                .map(|analysis| (analysis.signature.clone(), analysis))
+                .collect(),
+            path,
+        }
+    }
+
+    pub fn get(&self, signature: &str) -> Option<&MevAnalysis> {
+        self.analyses.get(signature)
+    }
+
+    pub fn record(&mut self, analysis: MevAnalysis) {
+        self.analyses.insert(analysis.signature.clone(), analysis);
+        if self.analyses.len() > MAX_STORED_ANALYSES {
+            let mut by_age: Vec<(String, i64)> = self
+                .analyses
+                .values()
+                .map(|analysis| (analysis.signature.clone(), analysis.block_time))
+                .collect();
+            by_age.sort_by_key(|(_, time)| *time);
+            let excess = self.analyses.len() - MAX_STORED_ANALYSES;
+            for (signature, _) in by_age.into_iter().take(excess) {
+                self.analyses.remove(&signature);
+            }
+        }
+        self.save();
+    }
+
+    pub fn report(&self, wallet: &str) -> MevReport {
+        let analyses: Vec<&MevAnalysis> = self
+            .analyses
+            .values()
+            .filter(|analysis| analysis.wallet == wallet)
+            .collect();
+        let mut attacks: Vec<MevAnalysis> = analyses
+            .iter()
+            .filter(|analysis| analysis.sandwich.is_some())
+            .map(|analysis| (*analysis).clone())
+            .collect();
+        attacks.sort_by(|a, b| b.block_time.cmp(&a.block_time));
+
+        let mut extracted_by_mint: HashMap<String, f64> = HashMap::new();
+        let mut total_extracted_usd = 0.0;
+        for attack in attacks
+            .iter()
+            .filter_map(|analysis| analysis.sandwich.as_ref())
+        {
+            *extracted_by_mint
+                .entry(attack.extracted_mint.clone())
+                .or_default() += attack.extracted_amount;
+            total_extracted_usd += attack.extracted_usd.unwrap_or(0.0);
+        }
+
+        MevReport {
+            wallet: wallet.to_string(),
+            analyzed_swaps: analyses.len(),
+            sandwiched_swaps: attacks.len(),
+            sandwich_rate_pct: if analyses.is_empty() {
+                0.0
+            } else {
+                attacks.len() as f64 / analyses.len() as f64 * 100.0
+            },
+            total_extracted_usd,
+            extracted_by_mint,
+            attacks,
+        }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let analyses: Vec<&MevAnalysis> = self.analyses.values().collect();
+        match serde_json::to_string_pretty(&analyses) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(path, contents) {
+                    eprintln!("Failed to save MEV reports: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize MEV reports: {}", e),
+        }
+    }
+}
+
+/// What the user's swap looked like and whether it was sandwiched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SandwichScan {
+    pub sold: TokenDelta,
+    pub bought: TokenDelta,
+    pub attack: Option<SandwichAttack>,
+}
+
+/// Scans a `getBlock` response (`jsonParsed`, full transaction details)
+/// around `signature`. Returns `None` when the transaction is not in the
+/// block or is not a swap for `wallet`. `extracted_usd` is left for the
+/// caller to price.
+pub fn detect_sandwich(wallet: &str, signature: &str, block: &Value) -> Option<SandwichScan> {
+    let block_time = block.get("blockTime").cloned().unwrap_or(Value::Null);
+    let transactions: Vec<Value> = block["transactions"]
+        .as_array()?
+        .iter()
+        .map(|tx| {
+            let mut tx = tx.clone();
+            // Block entries omit the per-transaction time the parser needs.
+            tx["blockTime"] = block_time.clone();
+            tx
+        })
+        .collect();
+
+    let victim_index = transactions
+        .iter()
+        .position(|tx| first_signature(tx) == Some(signature))?;
+    let (sold, bought) = swap_legs(wallet, signature, &transactions[victim_index])?;
+
+    let mut attack = None;
+    'front: for (front_index, front) in transactions[..victim_index].iter().enumerate() {
+        let (Some(attacker), Some(front_signature)) = (fee_payer(front), first_signature(front))
+        else {
+            continue;
+        };
+        if attacker == wallet {
+            continue;
+        }
+        let Some((front_sold, front_bought)) = swap_legs(attacker, front_signature, front) else {
+            continue;
+        };
+        if front_sold.mint != sold.mint || front_bought.mint != bought.mint {
+            continue;
+        }
+
+        for (offset, back) in transactions[victim_index + 1..].iter().enumerate() {
+            if fee_payer(back) != Some(attacker) {
+                continue;
+            }
+            let Some(back_signature) = first_signature(back) else {
+                continue;
+            };
+            let Some((back_sold, back_bought)) = swap_legs(attacker, back_signature, back) else {
+                continue;
+            };
+            if back_sold.mint != bought.mint || back_bought.mint != sold.mint {
+                continue;
+            }
+
+            let extracted = (back_bought.amount - front_sold.amount).max(0.0);
+            attack = Some(SandwichAttack {
+                attacker: attacker.to_string(),
+                frontrun_signature: front_signature.to_string(),
+                backrun_signature: back_signature.to_string(),
+                frontrun_index: front_index,
+                victim_index,
+                backrun_index: victim_index + 1 + offset,
+                extracted_amount: extracted,
+                extracted_mint: sold.mint.clone(),
+                extracted_symbol: symbol_for(&sold.mint),
+                extracted_usd: None,
+                extracted_pct: if sold.amount > 0.0 {
+                    extracted / sold.amount * 100.0
+                } else {
+                    0.0
+                },
+            });
+            break 'front;
+        }
+    }
+
+    Some(SandwichScan {
+        sold,
+        bought,
+        attack,
+    })
+}
+
+fn swap_legs(owner: &str, signature: &str, tx: &Value) -> Option<(TokenDelta, TokenDelta)> {
+    parse_transaction(owner, signature, tx)?
+        .activities
+        .into_iter()
+        .find_map(|activity| match activity {
+            HistoricalActivity::Swap { sold, bought } => Some((sold, bought)),
+            _ => None,
+        })
+}
+
+fn first_signature(tx: &Value) -> Option<&str> {
+    tx["transaction"]["signatures"][0].as_str()
+}
+
+fn fee_payer(tx: &Value) -> Option<&str> {
+    let key = &tx["transaction"]["message"]["accountKeys"][0];
+    key.as_str().or_else(|| key["pubkey"].as_str())
+}
+
+async fn fetch_block(pool: &SharedRpcPool, slot: u64) -> Result<Value, String> {
+    let params = json!([
+        slot,
+        {
+            "encoding": "jsonParsed",
+            "commitment": "finalized",
+            "maxSupportedTransactionVersion": 0,
+            "transactionDetails": "full",
+            "rewards": false,
+        }
+    ]);
+    RpcPool::call(pool, RoutingHint::Read, move |client| {
+        client.send(RpcRequest::GetBlock, params.clone())
+    })
+    .await
+    .map_err(|e| format!("Failed to fetch block {slot}: {e}"))
+}
+
+/// Analyses one confirmed swap, stores the result and raises an alert when
+/// it was sandwiched. Already analysed signatures are returned from the
+/// store.
+pub async fn analyze_swap_mev(
+    app: &AppHandle,
+    analyzer: &SharedMevAnalyzer,
+    pool: &SharedRpcPool,
+    birdeye_key: Option<String>,
+    router: Option<SharedNotificationRouter>,
+    wallet: &str,
+    signature: &str,
+) -> Result<MevAnalysis, String> {
+    if let Some(existing) = analyzer.read().await.get(signature) {
+        return Ok(existing.clone());
+    }
+
+    let tx = fetch_transaction(pool, signature)
+        .await?
+        .ok_or_else(|| format!("Transaction {signature} is not finalized yet"))?;
+    let slot = tx["slot"]
+        .as_u64()
+        .ok_or_else(|| format!("Transaction {signature} has no slot"))?;
+    let block = fetch_block(pool, slot).await?;
+    let block_time = block["blockTime"]
+        .as_i64()
+        .unwrap_or_else(|| Utc::now().timestamp());
+
+    let mut scan = detect_sandwich(wallet, signature, &block)
+        .ok_or_else(|| format!("Transaction {signature} is not a swap by {wallet}"))?;
+    if let Some(attack) = scan.attack.as_mut() {
+        let at = chrono::DateTime::from_timestamp(block_time, 0).unwrap_or_else(Utc::now);
+        let mut oracle = PriceOracle::new(birdeye_key);
+        attack.extracted_usd = oracle
+            .price_at(&attack.extracted_mint, at)
+            .await
+            .map(|price| price * attack.extracted_amount);
+    }
+
+    let analysis = MevAnalysis {
+        signature: signature.to_string(),
+        wallet: wallet.to_string(),
+        slot,
+        block_time,
+        sold_mint: scan.sold.mint,
+        sold_amount: scan.sold.amount,
+        bought_mint: scan.bought.mint,
+        bought_amount: scan.bought.amount,
+        sandwich: scan.attack,
+        analyzed_at: Utc::now().timestamp(),
+    };
+    analyzer.write().await.record(analysis.clone());
+
+    if let Some(attack) = &analysis.sandwich {
+        let _ = app.emit(MEV_SANDWICH_EVENT, &analysis);
+        if let Some(router) = router {
+            let value = match attack.extracted_usd {
+                Some(usd) => format!("${usd:.2}"),
+                None => format!("{:.6} {}", attack.extracted_amount, attack.extracted_symbol),
+            };
+            let message = format!(
+                "Swap {signature} was sandwiched by {}. About {value} ({:.2}% of the input) was extracted.",
+                attack.attacker, attack.extracted_pct
+            );
+            if let Err(e) = router
+                .read()
+                .await
+                .send_text_notification(signature, "Sandwich attack detected", &message)
+                .await
+            {
+                eprintln!("Failed to send MEV alert: {}", e);
+            }
+        }
+    }
+
+    Ok(analysis)
+}
+
+pub fn register_mev_state(app: &tauri::App) {
+    let analyzer: SharedMevAnalyzer = Arc::new(RwLock::new(MevAnalyzer::new(app.handle())));
+    app.manage(analyzer);
+}
+
+#[tauri::command]
+pub async fn analyze_transaction_mev(
+    app: AppHandle,
+    signature: String,
+    wallet_address: String,
+    analyzer: State<'_, SharedMevAnalyzer>,
+    rpc_pool: State<'_, SharedRpcPool>,
+    keystore: State<'_, Keystore>,
+) -> Result<MevAnalysis, AppError> {
+    let router = app
+        .try_state::<SharedNotificationRouter>()
+        .map(|router| router.inner().clone());
+    analyze_swap_mev(
+        &app,
+        analyzer.inner(),
+        rpc_pool.inner(),
+        stored_birdeye_key(&keystore),
+        router,
+        &wallet_address,
+        &signature,
+    )
+    .await
+    .logged("analyze_transaction_mev")
+}
+
+#[tauri::command]
+pub async fn get_mev_report(
+    wallet_address: String,
+    analyzer: State<'_, SharedMevAnalyzer>,
+) -> Result<MevReport, AppError> {
+    Ok(analyzer.read().await.report(&wallet_address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+    const USER: &str = "UserWa11et1111111111111111111111111111111111";
+    const BOT: &str = "SandwichBot11111111111111111111111111111111";
+
+    fn swap(signature: &str, signer: &str, sold: (&str, f64), bought: (&str, f64)) -> Value {
+        let balance = |mint: &str, amount: f64| {
+            json!({
+                "owner": signer,
+                "mint": mint,
+                "uiTokenAmount": { "uiAmountString": amount.to_string() },
+            })
+        };
+        json!({
+            "transaction": {
+                "signatures": [signature],
+                "message": { "accountKeys": [{ "pubkey": signer }] },
+            },
+            "meta": {
+                "err": null,
+                "fee": 5000,
+                "preBalances": [1_000_000_000u64],
+                "postBalances": [999_995_000u64],
+                "preTokenBalances": [balance(sold.0, sold.1), balance(bought.0, 0.0)],
+                "postTokenBalances": [balance(sold.0, 0.0), balance(bought.0, bought.1)],
+            },
+        })
+    }
+
+    fn block(transactions: Vec<Value>) -> Value {
+        json!({ "blockTime": 1_700_000_000, "transactions": transactions })
+    }
+
+    #[test]
+    fn finds_front_and_back_run_around_the_swap() {
+        let block = block(vec![
+            swap("front", BOT, (USDC, 1_000.0), (BONK, 50_000_000.0)),
+            swap("victim", USER, (USDC, 100.0), (BONK, 4_500_000.0)),
+            swap(
+                "other",
+                "Someone1111111111111111111111111111111111",
+                (BONK, 1.0),
+                (USDC, 1.0),
+            ),
+            swap("back", BOT, (BONK, 50_000_000.0), (USDC, 1_012.0)),
+        ]);
+
+        let scan = detect_sandwich(USER, "victim", &block).unwrap();
+        let attack = scan.attack.unwrap();
+
+        assert_eq!(scan.sold.mint, USDC);
+        assert_eq!(attack.attacker, BOT);
+        assert_eq!(
+            (
+                attack.frontrun_index,
+                attack.victim_index,
+                attack.backrun_index
+            ),
+            (0, 1, 3)
+        );
+        assert!((attack.extracted_amount - 12.0).abs() < 1e-6);
+        assert!((attack.extracted_pct - 12.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn same_direction_trade_without_unwind_is_not_a_sandwich() {
+        let block = block(vec![
+            swap("front", BOT, (USDC, 1_000.0), (BONK, 50_000_000.0)),
+            swap("victim", USER, (USDC, 100.0), (BONK, 4_500_000.0)),
+            swap("back", BOT, (USDC, 10.0), (BONK, 400_000.0)),
+        ]);
+
+        let scan = detect_sandwich(USER, "victim", &block).unwrap();
+        assert!(scan.attack.is_none());
+        assert!(detect_sandwich(USER, "missing", &block).is_none());
+    }
+}
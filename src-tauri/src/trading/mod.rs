@@ -1,33 +1,47 @@
+pub mod alert_automation;
 pub mod auto_trading;
 pub mod backtesting;
 pub mod contract_risk;
 pub mod contract_risk_commands;
 pub mod copy_trading;
 pub mod database;
+pub mod experiments;
 pub mod limit_orders;
+pub mod mev_detection;
 pub mod optimizer;
 pub mod order_manager;
 pub mod paper_trading;
+pub mod position_sizing;
 pub mod price_listener;
 pub mod safety;
 pub mod safety_commands;
+pub mod session_hud;
+pub mod strategy_script;
+pub mod token_policy;
 pub mod types;
 
+pub use alert_automation::*;
 pub use auto_trading::*;
 pub use backtesting::*;
 pub use contract_risk::*;
 pub use contract_risk_commands::*;
 pub use copy_trading::*;
 pub use database::{OrderDatabase, SharedOrderDatabase};
+pub use experiments::*;
 pub use limit_orders::*;
+pub use mev_detection::*;
 pub use optimizer::*;
 pub use order_manager::{OrderManager, SharedOrderManager};
 pub use paper_trading::*;
+pub use position_sizing::*;
 pub use price_listener::{start_price_listener, update_order_prices, PriceUpdate};
 pub use safety::{
-    ImpactPreview, InsuranceProvider, InsuranceQuote, InsuranceSelection, MevRiskLevel,
-    PolicyCheckResult, PolicyViolation, SafetyCheckRequest, SafetyCheckResult, SafetyEngine,
-    SafetyPolicy, SharedSafetyEngine, ViolationSeverity,
+    DecisionTrace, ImpactPreview, InsuranceProvider, InsuranceQuote, InsuranceSelection,
+    MevRiskLevel, PolicyCheckResult, PolicyViolation, SafetyCheckRequest, SafetyCheckResult,
+    SafetyEngine, SafetyPolicy, SharedSafetyEngine, ViolationSeverity,
 };
 pub use safety_commands::*;
+pub use session_hud::*;
+pub use strategy_script::*;
+pub use token_policy::*;
 pub use types::*;
@@ -1,4 +1,7 @@
-use super::backtesting::{backtest_run, BacktestConfig, BacktestMetrics, BacktestResult};
+use super::backtesting::{run_backtest, BacktestConfig, BacktestMetrics, BacktestResult};
+use super::experiments::SharedExperimentTracker;
+use crate::errors::{AppError, CommandResultExt};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
@@ -27,6 +30,22 @@ pub struct OptimizationConfig {
     pub iterations: Option<u32>,
     pub optimization_target: String, // sharpe_ratio, total_return, etc.
     pub max_drawdown_constraint: Option<f64>,
+    /// Runs rolling train/test windows instead of one in-sample sweep.
+    #[serde(default)]
+    pub walk_forward: Option<WalkForwardConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalkForwardConfig {
+    pub train_days: i64,
+    pub test_days: i64,
+    /// Defaults to `test_days`, so test windows don't overlap.
+    pub step_days: Option<i64>,
+    /// Keep the train window starting at the backtest start date.
+    #[serde(default)]
+    pub anchored: bool,
+    /// Out-of-sample score below this fraction of in-sample score flags overfitting.
+    pub min_efficiency: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +67,39 @@ pub struct OptimizationRun {
     pub started_at: i64,
     pub completed_at: Option<i64>,
     pub error: Option<String>,
+    #[serde(default)]
+    pub walk_forward: Option<WalkForwardReport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalkForwardWindow {
+    pub index: usize,
+    pub train_start: i64,
+    pub train_end: i64,
+    pub test_start: i64,
+    pub test_end: i64,
+    pub parameter_set: HashMap<String, f64>,
+    pub in_sample_metrics: BacktestMetrics,
+    pub in_sample_score: f64,
+    pub out_of_sample_metrics: BacktestMetrics,
+    pub out_of_sample_score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalkForwardReport {
+    pub windows: Vec<WalkForwardWindow>,
+    pub total_windows: usize,
+    pub avg_in_sample_score: f64,
+    pub avg_out_of_sample_score: f64,
+    /// Average out-of-sample score over average in-sample score.
+    pub efficiency: Option<f64>,
+    pub profitable_windows_percent: f64,
+    pub compounded_out_of_sample_return_percent: f64,
+    pub worst_out_of_sample_drawdown_percent: f64,
+    /// Coefficient of variation of each parameter across windows.
+    pub parameter_stability: HashMap<String, f64>,
+    pub overfit_warning: bool,
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -144,7 +196,7 @@ async fn evaluate_candidate(
         backtest_config.slippage_rate = *slippage;
     }
 
-    let result = backtest_run(backtest_config).await?;
+    let result = run_backtest(backtest_config, Vec::new()).await?;
     let score = score_metrics(
         &result.metrics,
         &config.optimization_target,
@@ -294,6 +346,269 @@ async fn run_genetic_algorithm(
     }
 }
 
+/// Train start, train end (= test start) and test end of one window.
+type WindowBounds = (DateTime<Utc>, DateTime<Utc>, DateTime<Utc>);
+
+/// Boundaries for every window that fits inside the backtest range.
+fn walk_forward_windows(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    wf: &WalkForwardConfig,
+) -> Result<Vec<WindowBounds>, String> {
+    if wf.train_days <= 0 || wf.test_days <= 0 {
+        return Err("Walk-forward train and test windows must be at least one day".to_string());
+    }
+    let step = Duration::days(wf.step_days.unwrap_or(wf.test_days).max(1));
+    let train = Duration::days(wf.train_days);
+    let test = Duration::days(wf.test_days);
+
+    let mut windows = Vec::new();
+    for k in 0.. {
+        let offset = step * k;
+        let train_end = start + train + offset;
+        let test_end = train_end + test;
+        if test_end > end {
+            break;
+        }
+        let train_start = if wf.anchored { start } else { start + offset };
+        windows.push((train_start, train_end, test_end));
+    }
+
+    if windows.is_empty() {
+        return Err(format!(
+            "Backtest range is shorter than one {}-day train plus {}-day test window",
+            wf.train_days, wf.test_days
+        ));
+    }
+    Ok(windows)
+}
+
+fn summarize_walk_forward(
+    windows: Vec<WalkForwardWindow>,
+    parameters: &[OptimizationParameter],
+    min_efficiency: f64,
+) -> WalkForwardReport {
+    let count = windows.len().max(1) as f64;
+    let avg_in_sample_score = windows.iter().map(|w| w.in_sample_score).sum::<f64>() / count;
+    let avg_out_of_sample_score =
+        windows.iter().map(|w| w.out_of_sample_score).sum::<f64>() / count;
+    let efficiency = if avg_in_sample_score > 0.0 {
+        Some(avg_out_of_sample_score / avg_in_sample_score)
+    } else {
+        None
+    };
+
+    let profitable = windows
+        .iter()
+        .filter(|w| w.out_of_sample_metrics.total_return > 0.0)
+        .count();
+    let profitable_windows_percent = profitable as f64 / count * 100.0;
+    let compounded = windows.iter().fold(1.0, |acc, w| {
+        acc * (1.0 + w.out_of_sample_metrics.total_return_percent / 100.0)
+    });
+    let worst_out_of_sample_drawdown_percent = windows
+        .iter()
+        .map(|w| w.out_of_sample_metrics.max_drawdown_percent)
+        .fold(0.0, f64::max);
+
+    let mut parameter_stability = HashMap::new();
+    for param in parameters {
+        let values: Vec<f64> = windows
+            .iter()
+            .filter_map(|w| w.parameter_set.get(&param.name).copied())
+            .collect();
+        if values.is_empty() {
+            continue;
+        }
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+        let cv = if mean.abs() > f64::EPSILON {
+            variance.sqrt() / mean.abs()
+        } else {
+            0.0
+        };
+        parameter_stability.insert(param.name.clone(), cv);
+    }
+
+    let mut warnings = Vec::new();
+    match efficiency {
+        Some(eff) if eff < min_efficiency => warnings.push(format!(
+            "Out-of-sample score is {:.0}% of in-sample, below the {:.0}% threshold",
+            eff * 100.0,
+            min_efficiency * 100.0
+        )),
+        None => warnings.push("In-sample score was never positive".to_string()),
+        _ => {}
+    }
+    if profitable_windows_percent < 50.0 {
+        warnings.push(format!(
+            "Only {} of {} test windows were profitable",
+            profitable,
+            windows.len()
+        ));
+    }
+    for (name, cv) in &parameter_stability {
+        if *cv > 0.5 {
+            warnings.push(format!("Parameter {} changes a lot between windows", name));
+        }
+    }
+
+    WalkForwardReport {
+        total_windows: windows.len(),
+        windows,
+        avg_in_sample_score,
+        avg_out_of_sample_score,
+        efficiency,
+        profitable_windows_percent,
+        compounded_out_of_sample_return_percent: (compounded - 1.0) * 100.0,
+        worst_out_of_sample_drawdown_percent,
+        parameter_stability,
+        overfit_warning: !warnings.is_empty(),
+        warnings,
+    }
+}
+
+/// Picks parameters on each train window with a random search, then scores
+/// them on the following unseen test window.
+async fn run_walk_forward(
+    config: OptimizationConfig,
+    wf: WalkForwardConfig,
+    state: Arc<Mutex<OptimizerState>>,
+    run_id: String,
+) {
+    let fail = |err: String| {
+        let mut state = state.lock().unwrap();
+        if let Some(run) = state.runs.get_mut(&run_id) {
+            run.status = "failed".to_string();
+            run.error = Some(err);
+            run.completed_at = Some(chrono::Utc::now().timestamp_millis());
+        }
+    };
+
+    let bounds = match walk_forward_windows(
+        config.backtest_config.start_date,
+        config.backtest_config.end_date,
+        &wf,
+    ) {
+        Ok(bounds) => bounds,
+        Err(err) => return fail(err),
+    };
+    let iterations = config.iterations.unwrap_or(50).max(1);
+    let mut windows: Vec<WalkForwardWindow> = Vec::new();
+
+    for (index, (train_start, train_end, test_end)) in bounds.iter().enumerate() {
+        let cancelled = state
+            .lock()
+            .unwrap()
+            .runs
+            .get(&run_id)
+            .map_or(true, |run| run.status == "cancelled");
+        if cancelled {
+            return;
+        }
+
+        let mut train_config = config.clone();
+        train_config.backtest_config.start_date = *train_start;
+        train_config.backtest_config.end_date = *train_end;
+
+        let mut best: Option<Candidate> = None;
+        for _ in 0..iterations {
+            let parameters = generate_random_candidate(&config.parameters);
+            match evaluate_candidate(&parameters, &train_config).await {
+                Ok((metrics, score)) => {
+                    if best.as_ref().map_or(true, |b| score > b.score) {
+                        best = Some(Candidate {
+                            parameters,
+                            metrics: Some(metrics),
+                            score,
+                        });
+                    }
+                }
+                Err(err) => return fail(err),
+            }
+        }
+        let Some(Candidate {
+            parameters: parameter_set,
+            metrics: Some(in_sample_metrics),
+            score: in_sample_score,
+        }) = best
+        else {
+            return fail("No candidates evaluated".to_string());
+        };
+
+        let mut test_config = config.clone();
+        test_config.backtest_config.start_date = *train_end;
+        test_config.backtest_config.end_date = *test_end;
+        let (out_of_sample_metrics, out_of_sample_score) =
+            match evaluate_candidate(&parameter_set, &test_config).await {
+                Ok(evaluated) => evaluated,
+                Err(err) => return fail(err),
+            };
+
+        windows.push(WalkForwardWindow {
+            index,
+            train_start: train_start.timestamp_millis(),
+            train_end: train_end.timestamp_millis(),
+            test_start: train_end.timestamp_millis(),
+            test_end: test_end.timestamp_millis(),
+            parameter_set,
+            in_sample_metrics,
+            in_sample_score,
+            out_of_sample_metrics,
+            out_of_sample_score,
+        });
+
+        let mut state = state.lock().unwrap();
+        if let Some(run) = state.runs.get_mut(&run_id) {
+            run.progress = ((index + 1) as f64 / bounds.len() as f64) * 100.0;
+        }
+    }
+
+    let mut results: Vec<OptimizationResult> = windows
+        .iter()
+        .map(|w| OptimizationResult {
+            parameter_set: w.parameter_set.clone(),
+            metrics: w.out_of_sample_metrics.clone(),
+            score: w.out_of_sample_score,
+            rank: 0,
+        })
+        .collect();
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    for (rank, result) in results.iter_mut().enumerate() {
+        result.rank = rank + 1;
+    }
+
+    // The latest window's pick is what would actually trade next.
+    let latest = windows.last().map(|w| OptimizationResult {
+        parameter_set: w.parameter_set.clone(),
+        metrics: w.out_of_sample_metrics.clone(),
+        score: w.out_of_sample_score,
+        rank: 1,
+    });
+    let report = summarize_walk_forward(
+        windows,
+        &config.parameters,
+        wf.min_efficiency.unwrap_or(0.5),
+    );
+
+    let mut state = state.lock().unwrap();
+    if let Some(run) = state.runs.get_mut(&run_id) {
+        if run.status == "cancelled" {
+            return;
+        }
+        run.results = results;
+        run.best_result = latest;
+        run.walk_forward = Some(report);
+        run.status = "completed".to_string();
+        run.completed_at = Some(chrono::Utc::now().timestamp_millis());
+        run.progress = 100.0;
+    }
+}
+
 async fn run_random_search(
     config: OptimizationConfig,
     state: Arc<Mutex<OptimizerState>>,
@@ -354,12 +669,25 @@ async fn run_random_search(
 #[tauri::command]
 pub async fn optimizer_start(
     config: OptimizationConfig,
+    experiment: Option<String>,
+    tags: Option<Vec<String>>,
     state: tauri::State<'_, SharedOptimizerState>,
-) -> Result<String, String> {
+    tracker: tauri::State<'_, SharedExperimentTracker>,
+) -> Result<String, AppError> {
+    if let Some(wf) = &config.walk_forward {
+        walk_forward_windows(
+            config.backtest_config.start_date,
+            config.backtest_config.end_date,
+            wf,
+        )
+        .map_err(AppError::Validation)
+        .logged("optimizer_start")?;
+    }
+
     let run_id = Uuid::new_v4().to_string();
 
     {
-        let mut state = state.lock().map_err(|e| e.to_string())?;
+        let mut state = state.lock().logged("optimizer_start")?;
         state.runs.insert(
             run_id.clone(),
             OptimizationRun {
@@ -372,6 +700,7 @@ pub async fn optimizer_start(
                 started_at: chrono::Utc::now().timestamp_millis(),
                 completed_at: None,
                 error: None,
+                walk_forward: None,
             },
         );
     }
@@ -379,21 +708,39 @@ pub async fn optimizer_start(
     let state_clone = state.inner().clone();
     let config_clone = config.clone();
     let run_id_clone = run_id.clone();
+    let tracker = tracker.inner().clone();
 
     tauri::async_runtime::spawn(async move {
-        match config_clone.method.as_str() {
-            "genetic" => run_genetic_algorithm(config_clone, state_clone, run_id_clone).await,
-            "random" | "monte_carlo" => {
-                run_random_search(config_clone, state_clone, run_id_clone).await;
-            }
-            "grid" => {
-                // For simplicity, grid search uses random search with more iterations
-                run_random_search(config_clone, state_clone, run_id_clone).await;
-            }
-            _ => {
-                run_random_search(config_clone, state_clone, run_id_clone).await;
+        let state = state_clone.clone();
+        let id = run_id_clone.clone();
+        if let Some(wf) = config_clone.walk_forward.clone() {
+            run_walk_forward(config_clone, wf, state_clone, run_id_clone).await;
+        } else {
+            match config_clone.method.as_str() {
+                "genetic" => run_genetic_algorithm(config_clone, state_clone, run_id_clone).await,
+                "random" | "monte_carlo" => {
+                    run_random_search(config_clone, state_clone, run_id_clone).await;
+                }
+                "grid" => {
+                    // For simplicity, grid search uses random search with more iterations
+                    run_random_search(config_clone, state_clone, run_id_clone).await;
+                }
+                _ => {
+                    run_random_search(config_clone, state_clone, run_id_clone).await;
+                }
             }
         }
+
+        let finished = state
+            .lock()
+            .ok()
+            .and_then(|state| state.runs.get(&id).cloned());
+        if let Some(run) = finished {
+            tracker
+                .write()
+                .await
+                .log_optimization(&run, experiment, tags.unwrap_or_default());
+        }
     });
 
     Ok(run_id)
@@ -403,8 +750,8 @@ pub async fn optimizer_start(
 pub async fn optimizer_cancel(
     id: String,
     state: tauri::State<'_, SharedOptimizerState>,
-) -> Result<(), String> {
-    let mut state = state.lock().map_err(|e| e.to_string())?;
+) -> Result<(), AppError> {
+    let mut state = state.lock().logged("optimizer_cancel")?;
     if let Some(run) = state.runs.get_mut(&id) {
         run.status = "cancelled".to_string();
         run.completed_at = Some(chrono::Utc::now().timestamp_millis());
@@ -415,8 +762,8 @@ pub async fn optimizer_cancel(
 #[tauri::command]
 pub async fn optimizer_get_runs(
     state: tauri::State<'_, SharedOptimizerState>,
-) -> Result<Vec<OptimizationRun>, String> {
-    let state = state.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<OptimizationRun>, AppError> {
+    let state = state.lock().logged("optimizer_get_runs")?;
     Ok(state.runs.values().cloned().collect())
 }
 
@@ -424,8 +771,8 @@ pub async fn optimizer_get_runs(
 pub async fn optimizer_get_run(
     id: String,
     state: tauri::State<'_, SharedOptimizerState>,
-) -> Result<Option<OptimizationRun>, String> {
-    let state = state.lock().map_err(|e| e.to_string())?;
+) -> Result<Option<OptimizationRun>, AppError> {
+    let state = state.lock().logged("optimizer_get_run")?;
     Ok(state.runs.get(&id).cloned())
 }
 
@@ -479,3 +826,106 @@ mod rand {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn metrics(total_return_percent: f64) -> BacktestMetrics {
+        BacktestMetrics {
+            total_return: total_return_percent * 100.0,
+            total_return_percent,
+            annualized_return: 0.0,
+            sharpe_ratio: 0.0,
+            sortino_ratio: 0.0,
+            max_drawdown: 0.0,
+            max_drawdown_percent: 5.0,
+            win_rate: 0.0,
+            profit_factor: 0.0,
+            total_trades: 0,
+            winning_trades: 0,
+            losing_trades: 0,
+            average_win: 0.0,
+            average_loss: 0.0,
+            largest_win: 0.0,
+            largest_loss: 0.0,
+            average_trade_duration: 0,
+            exposure_time: 0.0,
+        }
+    }
+
+    fn window(
+        in_sample_score: f64,
+        out_of_sample_score: f64,
+        oos_return: f64,
+    ) -> WalkForwardWindow {
+        WalkForwardWindow {
+            index: 0,
+            train_start: 0,
+            train_end: 0,
+            test_start: 0,
+            test_end: 0,
+            parameter_set: HashMap::from([("slippage_rate".to_string(), 0.1)]),
+            in_sample_metrics: metrics(20.0),
+            in_sample_score,
+            out_of_sample_metrics: metrics(oos_return),
+            out_of_sample_score,
+        }
+    }
+
+    #[test]
+    fn rolling_and_anchored_windows() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let end = start + Duration::days(100);
+        let mut wf = WalkForwardConfig {
+            train_days: 60,
+            test_days: 20,
+            step_days: None,
+            anchored: false,
+            min_efficiency: None,
+        };
+
+        let rolling = walk_forward_windows(start, end, &wf).unwrap();
+        assert_eq!(rolling.len(), 2);
+        assert_eq!(rolling[1].0, start + Duration::days(20));
+        assert_eq!(rolling[1].2, end);
+
+        wf.anchored = true;
+        let anchored = walk_forward_windows(start, end, &wf).unwrap();
+        assert!(anchored
+            .iter()
+            .all(|(train_start, _, _)| *train_start == start));
+
+        wf.train_days = 90;
+        assert!(walk_forward_windows(start, end, &wf).is_err());
+    }
+
+    #[test]
+    fn flags_overfit_when_out_of_sample_collapses() {
+        let params = vec![OptimizationParameter {
+            name: "slippage_rate".to_string(),
+            min: 0.0,
+            max: 1.0,
+            step: 0.1,
+            current_value: 0.1,
+        }];
+
+        let robust = summarize_walk_forward(
+            vec![window(2.0, 1.6, 5.0), window(2.0, 1.4, 10.0)],
+            &params,
+            0.5,
+        );
+        assert!(!robust.overfit_warning);
+        assert!((robust.efficiency.unwrap() - 0.75).abs() < 1e-9);
+        assert!((robust.compounded_out_of_sample_return_percent - 15.5).abs() < 1e-9);
+
+        let overfit = summarize_walk_forward(
+            vec![window(3.0, 0.3, -4.0), window(3.0, 0.1, 1.0)],
+            &params,
+            0.5,
+        );
+        assert!(overfit.overfit_warning);
+        assert_eq!(overfit.warnings.len(), 1);
+    }
+}
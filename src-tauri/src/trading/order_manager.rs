@@ -1,12 +1,16 @@
+use crate::core::price_engine::get_price_engine;
+use crate::core::WebSocketManager;
 use crate::data::event_store::{Event as AuditEvent, SharedEventStore};
 use crate::trading::database::{OrderDatabase, SharedOrderDatabase};
+use crate::trading::session_hud::{session_record_fill, session_record_price};
+use crate::trading::token_policy::{check_token_policy, ExecutionPath};
 use crate::trading::types::{
-    CreateOrderRequest, Order, OrderFill, OrderSide, OrderStatus, OrderType, OrderUpdate,
-    QuickTradeRequest,
+    CreateOrderGroupRequest, CreateOrderRequest, Order, OrderFill, OrderGroup,
+    OrderGroupCancelledEvent, OrderSide, OrderStatus, OrderType, OrderUpdate, QuickTradeRequest,
 };
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::RwLock;
@@ -30,6 +34,120 @@ pub struct OrderTriggeredEvent {
     pub amount: f64,
 }
 
+/// How far a trailing stop sits from its high- or low-water mark.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrailOffset {
+    Percent(f64),
+    Amount(f64),
+}
+
+impl TrailOffset {
+    pub fn from_order(order: &Order) -> Result<Self, String> {
+        match (order.trailing_percent, order.trailing_amount) {
+            (Some(percent), None) if percent > 0.0 && percent < 100.0 => {
+                Ok(TrailOffset::Percent(percent))
+            }
+            (None, Some(amount)) if amount > 0.0 => Ok(TrailOffset::Amount(amount)),
+            _ => Err(
+                "Trailing stop needs either a trailing percent between 0 and 100 or a positive trailing amount"
+                    .to_string(),
+            ),
+        }
+    }
+
+    fn below(&self, price: f64) -> f64 {
+        match self {
+            TrailOffset::Percent(percent) => price * (1.0 - percent / 100.0),
+            TrailOffset::Amount(amount) => price - amount,
+        }
+    }
+
+    fn above(&self, price: f64) -> f64 {
+        match self {
+            TrailOffset::Percent(percent) => price * (1.0 + percent / 100.0),
+            TrailOffset::Amount(amount) => price + amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TrailState {
+    pub highest_price: Option<f64>,
+    pub lowest_price: Option<f64>,
+    pub stop_price: Option<f64>,
+}
+
+/// Moves the trail for a new price and reports whether the stop was hit.
+/// Sells trail below the highest price seen and buys above the lowest, so
+/// the stop only ever moves in the order's favour.
+pub fn advance_trail(
+    side: OrderSide,
+    offset: TrailOffset,
+    state: TrailState,
+    price: f64,
+) -> (TrailState, bool) {
+    match side {
+        OrderSide::Sell => {
+            let highest = state.highest_price.map_or(price, |h| h.max(price));
+            let stop = offset.below(highest);
+            let next = TrailState {
+                highest_price: Some(highest),
+                stop_price: Some(stop),
+                ..state
+            };
+            (next, price <= stop)
+        }
+        OrderSide::Buy => {
+            let lowest = state.lowest_price.map_or(price, |l| l.min(price));
+            let stop = offset.above(lowest);
+            let next = TrailState {
+                lowest_price: Some(lowest),
+                stop_price: Some(stop),
+                ..state
+            };
+            (next, price >= stop)
+        }
+    }
+}
+
+/// Both legs must close the same position: same pair, side and wallet,
+/// with the take-profit on the far side of the market from the stop.
+pub fn validate_oco_legs(request: &CreateOrderGroupRequest) -> Result<(), String> {
+    let tp = &request.take_profit;
+    let sl = &request.stop_loss;
+    if !matches!(tp.order_type, OrderType::TakeProfit | OrderType::Limit) {
+        return Err("Take-profit leg must be a take-profit or limit order".to_string());
+    }
+    if !matches!(sl.order_type, OrderType::StopLoss | OrderType::TrailingStop) {
+        return Err("Stop-loss leg must be a stop-loss or trailing stop order".to_string());
+    }
+    if tp.side != sl.side
+        || tp.input_mint != sl.input_mint
+        || tp.output_mint != sl.output_mint
+        || tp.wallet_address != sl.wallet_address
+    {
+        return Err("Both legs must trade the same pair and side from the same wallet".to_string());
+    }
+    if let (Some(target), Some(stop)) = (tp.limit_price, sl.stop_price) {
+        let ordered = match tp.side {
+            OrderSide::Sell => target > stop,
+            OrderSide::Buy => target < stop,
+        };
+        if !ordered {
+            return Err("Take-profit price must be on the other side of the stop".to_string());
+        }
+    }
+    Ok(())
+}
+
+fn price_symbol(order: &Order) -> &str {
+    if order.side == OrderSide::Buy {
+        &order.output_symbol
+    } else {
+        &order.input_symbol
+    }
+}
+
 pub struct OrderManager {
     db: SharedOrderDatabase,
     app_handle: AppHandle,
@@ -52,7 +170,9 @@ impl OrderManager {
     }
 
     pub async fn create_order(&self, request: CreateOrderRequest) -> Result<Order, String> {
-        let order = Order {
+        check_token_policy(ExecutionPath::ManualOrder, &request.output_mint).await?;
+
+        let mut order = Order {
             id: Uuid::new_v4().to_string(),
             order_type: request.order_type,
             side: request.side,
@@ -66,6 +186,7 @@ impl OrderManager {
             limit_price: request.limit_price,
             stop_price: request.stop_price,
             trailing_percent: request.trailing_percent,
+            trailing_amount: request.trailing_amount,
             highest_price: None,
             lowest_price: None,
             linked_order_id: request.linked_order_id,
@@ -79,6 +200,24 @@ impl OrderManager {
             error_message: None,
         };
 
+        if order.order_type == OrderType::TrailingStop {
+            let offset = TrailOffset::from_order(&order)?;
+            let symbol = price_symbol(&order).to_string();
+            // Start the trail from the current price when one is known so
+            // the stop is visible straight away.
+            let current = match self.current_prices.read().await.get(&symbol) {
+                Some(price) => Some(*price),
+                None => get_price_engine().get_price(&symbol),
+            };
+            if let Some(price) = current {
+                let (state, _) = advance_trail(order.side, offset, TrailState::default(), price);
+                order.highest_price = state.highest_price;
+                order.lowest_price = state.lowest_price;
+                order.stop_price = state.stop_price;
+            }
+            self.watch_price(&symbol).await;
+        }
+
         self.db
             .write()
             .await
@@ -116,6 +255,79 @@ impl OrderManager {
         Ok(order)
     }
 
+    /// Places both legs linked under a new group id. If the second leg
+    /// cannot be placed the first is cancelled so no half-group is left.
+    pub async fn create_order_group(
+        &self,
+        request: CreateOrderGroupRequest,
+    ) -> Result<OrderGroup, String> {
+        validate_oco_legs(&request)?;
+        let group_id = format!("oco_{}", Uuid::new_v4());
+
+        let mut take_profit = request.take_profit;
+        take_profit.linked_order_id = Some(group_id.clone());
+        let mut stop_loss = request.stop_loss;
+        stop_loss.linked_order_id = Some(group_id.clone());
+
+        let take_profit = self.create_order(take_profit).await?;
+        let stop_loss = match self.create_order(stop_loss).await {
+            Ok(order) => order,
+            Err(e) => {
+                let _ = self.db.write().await.cancel_order(&take_profit.id).await;
+                return Err(format!("Failed to place stop-loss leg: {}", e));
+            }
+        };
+
+        Ok(OrderGroup {
+            group_id,
+            orders: vec![take_profit, stop_loss],
+        })
+    }
+
+    /// Cancels the other pending legs of `order`'s group and tells the
+    /// frontend which ones went.
+    async fn cancel_siblings(&self, order: &Order, reason: &str) {
+        let Some(group_id) = &order.linked_order_id else {
+            return;
+        };
+        let siblings = match self
+            .db
+            .read()
+            .await
+            .get_pending_linked_orders(group_id)
+            .await
+        {
+            Ok(orders) => orders,
+            Err(e) => {
+                eprintln!("Failed to load linked orders for {}: {}", group_id, e);
+                return;
+            }
+        };
+        if let Err(e) = self.db.write().await.cancel_linked_orders(group_id).await {
+            eprintln!("Failed to cancel linked orders for {}: {}", group_id, e);
+            return;
+        }
+
+        let mut cancelled_order_ids = Vec::new();
+        for mut sibling in siblings.into_iter().filter(|o| o.id != order.id) {
+            sibling.status = OrderStatus::Cancelled;
+            sibling.updated_at = Utc::now();
+            self.emit_order_update(&sibling);
+            cancelled_order_ids.push(sibling.id);
+        }
+        if cancelled_order_ids.is_empty() {
+            return;
+        }
+
+        let event = OrderGroupCancelledEvent {
+            group_id: group_id.clone(),
+            resolved_order_id: order.id.clone(),
+            cancelled_order_ids,
+            reason: reason.to_string(),
+        };
+        let _ = self.app_handle.emit("order_group_cancelled", event);
+    }
+
     pub async fn cancel_order(&self, order_id: &str) -> Result<(), String> {
         let order = self.get_order(order_id).await?;
 
@@ -130,9 +342,8 @@ impl OrderManager {
             .await
             .map_err(|e| format!("Failed to cancel order: {}", e))?;
 
-        if let Some(linked_id) = &order.linked_order_id {
-            let _ = self.db.write().await.cancel_linked_orders(linked_id).await;
-        }
+        self.cancel_siblings(&order, "Sibling order cancelled")
+            .await;
 
         // Publish event to event store
         if let Some(ref event_store) = self.event_store {
@@ -191,9 +402,41 @@ impl OrderManager {
             .map_err(|e| format!("Failed to get order history: {}", e))
     }
 
+    /// Subscribes the price stream to `symbol` so trailing stops keep
+    /// tracking without the frontend pushing prices.
+    async fn watch_price(&self, symbol: &str) {
+        if let Some(ws_manager) = self.app_handle.try_state::<WebSocketManager>() {
+            if let Err(e) = ws_manager.subscribe_prices(vec![symbol.to_string()]).await {
+                eprintln!("Failed to stream prices for {}: {}", symbol, e);
+            }
+        }
+    }
+
+    /// Resubscribes prices for trailing stops that were open before a
+    /// restart.
+    pub async fn watch_active_trailing_stops(&self) -> Result<(), String> {
+        let orders = self
+            .db
+            .read()
+            .await
+            .get_all_active_orders()
+            .await
+            .map_err(|e| format!("Failed to get active orders: {}", e))?;
+        for order in orders
+            .iter()
+            .filter(|o| o.order_type == OrderType::TrailingStop)
+        {
+            self.watch_price(price_symbol(order)).await;
+        }
+        Ok(())
+    }
+
     pub async fn update_price(&self, symbol: &str, price: f64) {
-        let mut prices = self.current_prices.write().await;
-        prices.insert(symbol.to_string(), price);
+        self.current_prices
+            .write()
+            .await
+            .insert(symbol.to_string(), price);
+        session_record_price(&self.app_handle, symbol, price).await;
     }
 
     pub async fn check_and_trigger_orders(&self) -> Result<(), String> {
@@ -206,8 +449,19 @@ impl OrderManager {
             .map_err(|e| format!("Failed to get active orders: {}", e))?;
 
         let prices = self.current_prices.read().await.clone();
+        // Groups that had a leg fill this pass; their siblings were
+        // cancelled but are still in `orders`.
+        let mut resolved_groups = HashSet::new();
 
         for order in orders {
+            if order
+                .linked_order_id
+                .as_ref()
+                .is_some_and(|group| resolved_groups.contains(group))
+            {
+                continue;
+            }
+
             let symbol = if order.side == OrderSide::Buy {
                 &order.output_symbol
             } else {
@@ -216,13 +470,21 @@ impl OrderManager {
 
             if let Some(&current_price) = prices.get(symbol) {
                 if self.should_trigger_order(&order, current_price).await? {
-                    if let Err(e) = self.execute_order(&order, current_price).await {
-                        eprintln!("Failed to execute order {}: {}", order.id, e);
-                        let _ = self.db.write().await.update_order_status(
-                            &order.id,
-                            OrderStatus::Failed,
-                            Some(e),
-                        );
+                    match self.execute_order(&order, current_price).await {
+                        Ok(()) => {
+                            if let Some(group) = &order.linked_order_id {
+                                resolved_groups.insert(group.clone());
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to execute order {}: {}", order.id, e);
+                            let _ = self
+                                .db
+                                .write()
+                                .await
+                                .update_order_status(&order.id, OrderStatus::Failed, Some(e))
+                                .await;
+                        }
                     }
                 }
             }
@@ -270,51 +532,45 @@ impl OrderManager {
         Ok(false)
     }
 
+    /// Advances the trail and persists it, so the high-water mark survives
+    /// restarts. A triggered stop is then filled at market by
+    /// `execute_order`.
     async fn check_trailing_stop(&self, order: &Order, current_price: f64) -> Result<bool, String> {
-        let trailing_percent = order.trailing_percent.ok_or("Trailing percent not set")?;
-
-        let mut should_trigger = false;
-        let mut new_highest = order.highest_price;
-        let mut new_lowest = order.lowest_price;
-        let mut new_stop_price = order.stop_price;
-
-        match order.side {
-            OrderSide::Sell => {
-                let highest = new_highest.unwrap_or(current_price);
-                if current_price > highest {
-                    new_highest = Some(current_price);
-                    new_stop_price = Some(current_price * (1.0 - trailing_percent / 100.0));
-                } else if let Some(stop_price) = new_stop_price {
-                    should_trigger = current_price <= stop_price;
-                }
-            }
-            OrderSide::Buy => {
-                let lowest = new_lowest.unwrap_or(current_price);
-                if current_price < lowest {
-                    new_lowest = Some(current_price);
-                    new_stop_price = Some(current_price * (1.0 + trailing_percent / 100.0));
-                } else if let Some(stop_price) = new_stop_price {
-                    should_trigger = current_price >= stop_price;
-                }
-            }
-        }
+        let offset = TrailOffset::from_order(order)?;
+        let state = TrailState {
+            highest_price: order.highest_price,
+            lowest_price: order.lowest_price,
+            stop_price: order.stop_price,
+        };
+        let (next, should_trigger) = advance_trail(order.side, offset, state, current_price);
 
-        if new_highest != order.highest_price
-            || new_lowest != order.lowest_price
-            || new_stop_price != order.stop_price
-        {
+        if next != state {
             self.db
                 .write()
                 .await
-                .update_trailing_stop(&order.id, new_highest, new_lowest, new_stop_price)
+                .update_trailing_stop(
+                    &order.id,
+                    next.highest_price,
+                    next.lowest_price,
+                    next.stop_price,
+                )
                 .await
                 .map_err(|e| format!("Failed to update trailing stop: {}", e))?;
+
+            let mut updated = order.clone();
+            updated.highest_price = next.highest_price;
+            updated.lowest_price = next.lowest_price;
+            updated.stop_price = next.stop_price;
+            updated.updated_at = Utc::now();
+            self.emit_order_update(&updated);
         }
 
         Ok(should_trigger)
     }
 
     async fn execute_order(&self, order: &Order, trigger_price: f64) -> Result<(), String> {
+        // The lists may have changed since the order was placed.
+        check_token_policy(ExecutionPath::ManualOrder, &order.output_mint).await?;
         self.emit_order_triggered(order, trigger_price);
 
         let tx_signature = format!("simulated_{}", Uuid::new_v4());
@@ -331,9 +587,7 @@ impl OrderManager {
             .await
             .map_err(|e| format!("Failed to update order: {}", e))?;
 
-        if let Some(linked_id) = &order.linked_order_id {
-            let _ = self.db.write().await.cancel_linked_orders(linked_id).await;
-        }
+        self.cancel_siblings(order, "Sibling order filled").await;
 
         let mut filled_order = order.clone();
         filled_order.status = OrderStatus::Filled;
@@ -359,6 +613,7 @@ impl OrderManager {
         }
 
         self.emit_order_update(&filled_order);
+        session_record_fill(&self.app_handle, &filled_order, trigger_price).await;
 
         Ok(())
     }
@@ -411,3 +666,87 @@ impl OrderManager {
 }
 
 pub type SharedOrderManager = Arc<OrderManager>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leg(order_type: OrderType, limit: Option<f64>, stop: Option<f64>) -> CreateOrderRequest {
+        CreateOrderRequest {
+            order_type,
+            side: OrderSide::Sell,
+            input_mint: "SOL".to_string(),
+            output_mint: "USDC".to_string(),
+            input_symbol: "SOL".to_string(),
+            output_symbol: "USDC".to_string(),
+            amount: 1.0,
+            limit_price: limit,
+            stop_price: stop,
+            trailing_percent: None,
+            trailing_amount: None,
+            linked_order_id: None,
+            slippage_bps: 50,
+            priority_fee_micro_lamports: 0,
+            wallet_address: "wallet".to_string(),
+        }
+    }
+
+    #[test]
+    fn oco_legs_must_bracket_the_market() {
+        let valid = CreateOrderGroupRequest {
+            take_profit: leg(OrderType::TakeProfit, Some(180.0), None),
+            stop_loss: leg(OrderType::StopLoss, None, Some(140.0)),
+        };
+        assert!(validate_oco_legs(&valid).is_ok());
+
+        let inverted = CreateOrderGroupRequest {
+            take_profit: leg(OrderType::TakeProfit, Some(130.0), None),
+            stop_loss: leg(OrderType::StopLoss, None, Some(140.0)),
+        };
+        assert!(validate_oco_legs(&inverted).is_err());
+
+        let mut other_wallet = valid.clone();
+        other_wallet.stop_loss.wallet_address = "other".to_string();
+        assert!(validate_oco_legs(&other_wallet).is_err());
+
+        let swapped = CreateOrderGroupRequest {
+            take_profit: valid.stop_loss.clone(),
+            stop_loss: valid.take_profit.clone(),
+        };
+        assert!(validate_oco_legs(&swapped).is_err());
+    }
+
+    #[test]
+    fn sell_trail_ratchets_up_and_triggers_on_pullback() {
+        let offset = TrailOffset::Percent(10.0);
+        let (state, hit) = advance_trail(OrderSide::Sell, offset, TrailState::default(), 100.0);
+        assert!(!hit);
+        assert_eq!(state.stop_price, Some(90.0));
+
+        let (state, hit) = advance_trail(OrderSide::Sell, offset, state, 120.0);
+        assert!(!hit);
+        assert_eq!(state.highest_price, Some(120.0));
+        assert_eq!(state.stop_price, Some(108.0));
+
+        // A dip that stays above the stop leaves the trail where it was.
+        let (state, hit) = advance_trail(OrderSide::Sell, offset, state, 110.0);
+        assert!(!hit);
+        assert_eq!(state.stop_price, Some(108.0));
+
+        let (_, hit) = advance_trail(OrderSide::Sell, offset, state, 107.5);
+        assert!(hit);
+    }
+
+    #[test]
+    fn buy_trail_uses_absolute_offset_from_the_low() {
+        let offset = TrailOffset::Amount(2.0);
+        let (state, _) = advance_trail(OrderSide::Buy, offset, TrailState::default(), 50.0);
+        let (state, hit) = advance_trail(OrderSide::Buy, offset, state, 45.0);
+        assert!(!hit);
+        assert_eq!(state.lowest_price, Some(45.0));
+        assert_eq!(state.stop_price, Some(47.0));
+
+        let (_, hit) = advance_trail(OrderSide::Buy, offset, state, 47.0);
+        assert!(hit);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::errors::{AppError, CommandResultExt};
+use crate::profiles::ProfilePaths;
 use crate::utils::Rfc3339DateTime;
 use chrono::{DateTime, Utc};
 use rand::Rng;
@@ -6,15 +8,26 @@ use sqlx::{FromRow, Pool, Sqlite, SqlitePool};
 use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, Manager};
-use tokio::sync::{OnceCell, RwLock};
+use tokio::sync::{Mutex, OnceCell, RwLock};
 use uuid::Uuid;
 
-use crate::trading::types::{OrderSide, OrderType};
+use crate::api::jupiter::{fetch_quote, QuoteCommandInput, SwapMode};
+use crate::trading::types::{OrderSide, OrderStatus, OrderType};
 
 const DEFAULT_INITIAL_BALANCE: f64 = 10_000.0;
 const MINIMUM_QUANTITY: f64 = 1e-9;
 
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const USDC_DECIMALS: u8 = 6;
+const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const SOL_DECIMALS: u8 = 9;
+
+const FILL_SOURCE_JUPITER: &str = "jupiter";
+const FILL_SOURCE_SIMULATED: &str = "simulated";
+const EXECUTION_CONFIG_KEY: &str = "execution_config";
+
 // ============================================================================
 // Types and Structs
 // ============================================================================
@@ -55,9 +68,15 @@ pub struct PaperTrade {
     pub network_fee: f64,
     pub price_impact_fee: f64,
     pub fee: f64,
+    /// Adverse move between the requested price and the fill, as a fraction.
     pub slippage: f64,
     pub total_cost: f64,
     pub timestamp: DateTime<Utc>,
+    /// `jupiter` when priced from a live quote, otherwise `simulated`.
+    pub fill_source: String,
+    pub latency_ms: i64,
+    /// The resting order this trade filled, if any.
+    pub order_id: Option<String>,
 }
 
 impl<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> for PaperTrade {
@@ -79,6 +98,9 @@ impl<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> for PaperTrade {
             slippage: row.try_get("slippage")?,
             total_cost: row.try_get("total_cost")?,
             timestamp: Rfc3339DateTime::try_from(row.try_get::<String, _>("timestamp")?)?.into(),
+            fill_source: row.try_get("fill_source")?,
+            latency_ms: row.try_get("latency_ms")?,
+            order_id: row.try_get("order_id")?,
         })
     }
 }
@@ -136,6 +158,81 @@ pub struct ExecutePaperTradeRequest {
     pub limit_price: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stop_price: Option<f64>,
+    /// Token mint used to price the fill from a live quote. SOL is resolved
+    /// from the symbol when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decimals: Option<u8>,
+}
+
+/// An order that rests until the market crosses its limit or stop price.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperOrder {
+    pub id: String,
+    pub account_id: String,
+    pub symbol: String,
+    pub mint: Option<String>,
+    pub decimals: Option<u8>,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub quantity: f64,
+    pub limit_price: Option<f64>,
+    pub stop_price: Option<f64>,
+    pub status: OrderStatus,
+    pub trade_id: Option<String>,
+    pub status_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> for PaperOrder {
+    fn from_row(row: &'r sqlx::sqlite::SqliteRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+
+        Ok(PaperOrder {
+            id: row.try_get("id")?,
+            account_id: row.try_get("account_id")?,
+            symbol: row.try_get("symbol")?,
+            mint: row.try_get("mint")?,
+            decimals: row
+                .try_get::<Option<i64>, _>("decimals")?
+                .map(|decimals| decimals as u8),
+            side: parse_side(&row.try_get::<String, _>("side")?)?,
+            order_type: parse_order_type(&row.try_get::<String, _>("order_type")?)?,
+            quantity: row.try_get("quantity")?,
+            limit_price: row.try_get("limit_price")?,
+            stop_price: row.try_get("stop_price")?,
+            status: parse_order_status(&row.try_get::<String, _>("status")?)?,
+            trade_id: row.try_get("trade_id")?,
+            status_reason: row.try_get("status_reason")?,
+            created_at: Rfc3339DateTime::try_from(row.try_get::<String, _>("created_at")?)?.into(),
+            updated_at: Rfc3339DateTime::try_from(row.try_get::<String, _>("updated_at")?)?.into(),
+        })
+    }
+}
+
+impl PaperOrder {
+    fn to_request(&self, market_price: f64) -> ExecutePaperTradeRequest {
+        ExecutePaperTradeRequest {
+            symbol: self.symbol.clone(),
+            side: self.side,
+            order_type: self.order_type,
+            quantity: self.quantity,
+            price: market_price,
+            limit_price: self.limit_price,
+            stop_price: self.stop_price,
+            mint: self.mint.clone(),
+            decimals: self.decimals,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperOrderResult {
+    pub order: PaperOrder,
+    /// Present when the order was already crossed and filled on placement.
+    pub fill: Option<PaperTradeResult>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -169,6 +266,10 @@ pub struct PaperPerformance {
     pub current_balance: f64,
     pub initial_balance: f64,
     pub return_percentage: f64,
+    /// Fees paid across all trades, split by kind.
+    pub fees: FeeBreakdown,
+    pub avg_slippage_bps: f64,
+    pub live_quote_fills: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -209,6 +310,42 @@ impl Default for FeeConfig {
     }
 }
 
+/// How fills are priced. Live quotes reflect real route depth and AMM fees;
+/// the slippage model is used whenever a quote can't be had.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaperExecutionConfig {
+    pub use_live_quotes: bool,
+    pub min_latency_ms: u64,
+    pub max_latency_ms: u64,
+    /// Largest random move of the reference price per second of latency.
+    pub drift_bps_per_sec: f64,
+    /// Market and stop fills that slip further than this are rejected, as
+    /// the swap would fail on chain.
+    pub slippage_tolerance_bps: u16,
+}
+
+impl Default for PaperExecutionConfig {
+    fn default() -> Self {
+        Self {
+            use_live_quotes: true,
+            min_latency_ms: 150,
+            max_latency_ms: 600,
+            drift_bps_per_sec: 5.0,
+            slippage_tolerance_bps: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PaperFill {
+    price: f64,
+    model_slippage: f64,
+    /// Route fee rate when the price came from a quote.
+    route_fee_rate: Option<f64>,
+    source: &'static str,
+}
+
 #[derive(Debug, Clone)]
 struct PositionLot {
     quantity: f64,
@@ -265,6 +402,9 @@ impl PaperTradingDatabase {
                 slippage REAL NOT NULL,
                 total_cost REAL NOT NULL,
                 timestamp TEXT NOT NULL,
+                fill_source TEXT NOT NULL DEFAULT 'simulated',
+                latency_ms INTEGER NOT NULL DEFAULT 0,
+                order_id TEXT,
                 FOREIGN KEY (account_id) REFERENCES paper_accounts(id) ON DELETE CASCADE
             )
             "#,
@@ -272,6 +412,54 @@ impl PaperTradingDatabase {
         .execute(&self.pool)
         .await?;
 
+        // Trade tables created before live-quote fills; each fails harmlessly
+        // once the column exists.
+        for column in [
+            "fill_source TEXT NOT NULL DEFAULT 'simulated'",
+            "latency_ms INTEGER NOT NULL DEFAULT 0",
+            "order_id TEXT",
+        ] {
+            let _ = sqlx::query(&format!("ALTER TABLE paper_trades ADD COLUMN {column}"))
+                .execute(&self.pool)
+                .await;
+        }
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS paper_orders (
+                id TEXT PRIMARY KEY,
+                account_id TEXT NOT NULL,
+                symbol TEXT NOT NULL,
+                mint TEXT,
+                decimals INTEGER,
+                side TEXT NOT NULL,
+                order_type TEXT NOT NULL,
+                quantity REAL NOT NULL,
+                limit_price REAL,
+                stop_price REAL,
+                status TEXT NOT NULL,
+                trade_id TEXT,
+                status_reason TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (account_id) REFERENCES paper_accounts(id) ON DELETE CASCADE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS paper_settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
         sqlx::query(
             r#"
             CREATE TABLE IF NOT EXISTS paper_positions (
@@ -297,6 +485,7 @@ impl PaperTradingDatabase {
             CREATE INDEX IF NOT EXISTS idx_paper_trades_timestamp ON paper_trades(timestamp);
             CREATE INDEX IF NOT EXISTS idx_paper_positions_account ON paper_positions(account_id);
             CREATE INDEX IF NOT EXISTS idx_paper_positions_symbol ON paper_positions(symbol);
+            CREATE INDEX IF NOT EXISTS idx_paper_orders_open ON paper_orders(account_id, status, symbol);
             "#,
         )
         .execute(&self.pool)
@@ -374,6 +563,9 @@ impl PaperTradingDatabase {
     }
 
     pub async fn reset_account(&self, initial_balance: f64) -> Result<PaperAccount, sqlx::Error> {
+        sqlx::query("DELETE FROM paper_orders")
+            .execute(&self.pool)
+            .await?;
         sqlx::query("DELETE FROM paper_positions")
             .execute(&self.pool)
             .await?;
@@ -393,11 +585,11 @@ impl PaperTradingDatabase {
             INSERT INTO paper_trades (
                 id, account_id, symbol, side, order_type, quantity,
                 price, trading_fee, network_fee, price_impact_fee, fee,
-                slippage, total_cost, timestamp
+                slippage, total_cost, timestamp, fill_source, latency_ms, order_id
             ) VALUES (
                 ?1, ?2, ?3, ?4, ?5, ?6,
                 ?7, ?8, ?9, ?10, ?11,
-                ?12, ?13, ?14
+                ?12, ?13, ?14, ?15, ?16, ?17
             )
             "#,
         )
@@ -415,6 +607,110 @@ impl PaperTradingDatabase {
         .bind(trade.slippage)
         .bind(trade.total_cost)
         .bind(trade.timestamp.to_rfc3339())
+        .bind(&trade.fill_source)
+        .bind(trade.latency_ms)
+        .bind(&trade.order_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn create_order(&self, order: &PaperOrder) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO paper_orders (
+                id, account_id, symbol, mint, decimals, side, order_type,
+                quantity, limit_price, stop_price, status, trade_id,
+                status_reason, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+            "#,
+        )
+        .bind(&order.id)
+        .bind(&order.account_id)
+        .bind(&order.symbol)
+        .bind(&order.mint)
+        .bind(order.decimals.map(i64::from))
+        .bind(order.side.to_string())
+        .bind(order.order_type.to_string())
+        .bind(order.quantity)
+        .bind(order.limit_price)
+        .bind(order.stop_price)
+        .bind(order.status.to_string())
+        .bind(&order.trade_id)
+        .bind(&order.status_reason)
+        .bind(order.created_at.to_rfc3339())
+        .bind(order.updated_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_order(&self, order: &PaperOrder) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE paper_orders
+            SET status = ?1, trade_id = ?2, status_reason = ?3, updated_at = ?4
+            WHERE id = ?5
+            "#,
+        )
+        .bind(order.status.to_string())
+        .bind(&order.trade_id)
+        .bind(&order.status_reason)
+        .bind(order.updated_at.to_rfc3339())
+        .bind(&order.id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_order(&self, order_id: &str) -> Result<Option<PaperOrder>, sqlx::Error> {
+        sqlx::query_as::<_, PaperOrder>("SELECT * FROM paper_orders WHERE id = ?1")
+            .bind(order_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn get_open_orders(
+        &self,
+        account_id: &str,
+        symbol: Option<&str>,
+    ) -> Result<Vec<PaperOrder>, sqlx::Error> {
+        sqlx::query_as::<_, PaperOrder>(
+            r#"
+            SELECT * FROM paper_orders
+            WHERE account_id = ?1 AND status = ?2 AND (?3 IS NULL OR symbol = ?3)
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(account_id)
+        .bind(OrderStatus::Pending.to_string())
+        .bind(symbol)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_setting(&self, key: &str) -> Result<Option<String>, sqlx::Error> {
+        use sqlx::Row;
+
+        let row = sqlx::query("SELECT value FROM paper_settings WHERE key = ?1")
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?;
+        row.map(|row| row.try_get("value")).transpose()
+    }
+
+    pub async fn set_setting(&self, key: &str, value: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO paper_settings (key, value) VALUES (?1, ?2)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value
+            "#,
+        )
+        .bind(key)
+        .bind(value)
         .execute(&self.pool)
         .await?;
 
@@ -534,6 +830,14 @@ impl PaperTradingDatabase {
         let mut largest_win = 0.0_f64;
         let mut largest_loss = 0.0_f64;
         let mut total_fees = 0.0_f64;
+        let mut fees = FeeBreakdown {
+            trading_fee: 0.0,
+            network_fee: 0.0,
+            price_impact_fee: 0.0,
+            total_fee: 0.0,
+        };
+        let mut total_slippage = 0.0_f64;
+        let mut live_quote_fills = 0;
 
         let mut lots: HashMap<String, VecDeque<PositionLot>> = HashMap::new();
 
@@ -541,6 +845,13 @@ impl PaperTradingDatabase {
 
         for trade in &trades {
             total_fees += trade.fee;
+            fees.trading_fee += trade.trading_fee;
+            fees.network_fee += trade.network_fee;
+            fees.price_impact_fee += trade.price_impact_fee;
+            total_slippage += trade.slippage;
+            if trade.fill_source == FILL_SOURCE_JUPITER {
+                live_quote_fills += 1;
+            }
             let fee_per_unit = if trade.quantity.abs() > MINIMUM_QUANTITY {
                 trade.fee / trade.quantity
             } else {
@@ -626,6 +937,12 @@ impl PaperTradingDatabase {
         } else {
             0.0
         };
+        fees.total_fee = total_fees;
+        let avg_slippage_bps = if total_trades > 0 {
+            total_slippage / total_trades as f64 * 10_000.0
+        } else {
+            0.0
+        };
 
         Ok(PaperPerformance {
             total_trades,
@@ -641,6 +958,9 @@ impl PaperTradingDatabase {
             current_balance: account.balance,
             initial_balance: account.initial_balance,
             return_percentage,
+            fees,
+            avg_slippage_bps,
+            live_quote_fills,
         })
     }
 }
@@ -656,6 +976,10 @@ pub struct PaperTradingManager {
     slippage_config: SlippageConfig,
     fee_config: FeeConfig,
     current_prices: Arc<RwLock<HashMap<String, f64>>>,
+    execution_config: RwLock<PaperExecutionConfig>,
+    /// Serialises order placement and triggering so a resting order can't
+    /// fill twice from concurrent price updates.
+    order_lock: Mutex<()>,
 }
 
 impl PaperTradingManager {
@@ -673,9 +997,61 @@ impl PaperTradingManager {
             slippage_config,
             fee_config,
             current_prices: Arc::new(RwLock::new(HashMap::new())),
+            execution_config: RwLock::new(PaperExecutionConfig::default()),
+            order_lock: Mutex::new(()),
         }
     }
 
+    pub async fn load_execution_config(&self) -> Result<(), String> {
+        let stored = self
+            .db
+            .read()
+            .await
+            .get_setting(EXECUTION_CONFIG_KEY)
+            .await
+            .map_err(|e| format!("Failed to load paper execution config: {e}"))?;
+
+        if let Some(raw) = stored {
+            let config = serde_json::from_str(&raw)
+                .map_err(|e| format!("Invalid paper execution config: {e}"))?;
+            *self.execution_config.write().await = config;
+        }
+        Ok(())
+    }
+
+    pub async fn execution_config(&self) -> PaperExecutionConfig {
+        self.execution_config.read().await.clone()
+    }
+
+    pub async fn update_execution_config(
+        &self,
+        config: PaperExecutionConfig,
+    ) -> Result<PaperExecutionConfig, String> {
+        if config.min_latency_ms > config.max_latency_ms {
+            return Err("Minimum latency cannot exceed maximum latency".to_string());
+        }
+        if config.max_latency_ms > 10_000 {
+            return Err("Latency cannot exceed 10 seconds".to_string());
+        }
+        if !(0.0..=1_000.0).contains(&config.drift_bps_per_sec) {
+            return Err("Drift must be between 0 and 1000 bps per second".to_string());
+        }
+        if config.slippage_tolerance_bps == 0 || config.slippage_tolerance_bps > 5_000 {
+            return Err("Slippage tolerance must be between 1 and 5000 bps".to_string());
+        }
+
+        let raw = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+        self.db
+            .read()
+            .await
+            .set_setting(EXECUTION_CONFIG_KEY, &raw)
+            .await
+            .map_err(|e| format!("Failed to save paper execution config: {e}"))?;
+
+        *self.execution_config.write().await = config.clone();
+        Ok(config)
+    }
+
     fn validate_request(&self, request: &ExecutePaperTradeRequest) -> Result<(), String> {
         if request.quantity <= 0.0 {
             return Err("Quantity must be greater than zero".to_string());
@@ -770,26 +1146,111 @@ impl PaperTradingManager {
         }
     }
 
+    /// Prices a fill at the reference price the market drifted to during
+    /// the simulated latency, from a live Jupiter quote when possible.
+    async fn price_fill(
+        &self,
+        request: &ExecutePaperTradeRequest,
+        config: &PaperExecutionConfig,
+        latency_ms: u64,
+    ) -> PaperFill {
+        let reference_price = drift_price(request.price, latency_ms, config.drift_bps_per_sec);
+
+        if config.use_live_quotes {
+            if let Some((mint, decimals)) = resolve_mint(request) {
+                match quote_fill_price(
+                    request.side,
+                    &mint,
+                    decimals,
+                    request.quantity,
+                    reference_price,
+                    config.slippage_tolerance_bps,
+                )
+                .await
+                {
+                    Ok((price, route_fee_rate)) => {
+                        return PaperFill {
+                            price,
+                            model_slippage: 0.0,
+                            route_fee_rate: Some(route_fee_rate),
+                            source: FILL_SOURCE_JUPITER,
+                        }
+                    }
+                    Err(e) => eprintln!(
+                        "Live quote unavailable for paper {} fill, using slippage model: {e}",
+                        request.symbol
+                    ),
+                }
+            }
+        }
+
+        let slippage = self.calculate_slippage(request.quantity * reference_price);
+        PaperFill {
+            price: self.execution_price(reference_price, slippage, request.side),
+            model_slippage: slippage,
+            route_fee_rate: None,
+            source: FILL_SOURCE_SIMULATED,
+        }
+    }
+
     pub async fn execute_trade(
         &self,
         request: ExecutePaperTradeRequest,
+    ) -> Result<PaperTradeResult, String> {
+        self.execute_fill(request, None).await
+    }
+
+    async fn execute_fill(
+        &self,
+        request: ExecutePaperTradeRequest,
+        order_id: Option<String>,
     ) -> Result<PaperTradeResult, String> {
         self.validate_request(&request)?;
 
+        let config = self.execution_config().await;
+        let latency_ms = simulate_latency(&config).await;
+        let fill = self.price_fill(&request, &config, latency_ms).await;
+
+        // Limit orders never fill worse than their limit, however far the
+        // market moved while the order was in flight.
+        let execution_price = match (request.order_type, request.limit_price) {
+            (OrderType::Limit | OrderType::TakeProfit, Some(limit)) => match request.side {
+                OrderSide::Buy => fill.price.min(limit),
+                OrderSide::Sell => fill.price.max(limit),
+            },
+            _ => fill.price,
+        };
+        let slippage = adverse_slippage(request.side, request.price, execution_price);
+        let tolerance = f64::from(config.slippage_tolerance_bps) / 10_000.0;
+        if matches!(request.order_type, OrderType::Market | OrderType::StopLoss)
+            && slippage > tolerance
+        {
+            return Err(format!(
+                "Fill slipped {:.2}%, beyond the {} bps tolerance",
+                slippage * 100.0,
+                config.slippage_tolerance_bps
+            ));
+        }
+
         let db_read = self.db.read().await;
         let mut account = db_read
             .get_or_create_account(DEFAULT_INITIAL_BALANCE)
             .await
             .map_err(|e| format!("Failed to load paper account: {e}"))?;
 
-        let order_value = request.quantity * request.price;
-        let slippage = self.calculate_slippage(order_value);
-        let execution_price = self.execution_price(request.price, slippage, request.side);
         let executed_value = request.quantity * execution_price;
 
-        let trading_fee = self.calculate_trading_fee(executed_value);
+        let trading_fee = match fill.route_fee_rate {
+            Some(rate) => executed_value * rate,
+            None => self.calculate_trading_fee(executed_value),
+        };
         let network_fee = self.fee_config.network_fee;
-        let price_impact_fee = self.calculate_price_impact_fee(executed_value, slippage);
+        // Quoted prices already carry the route's price impact.
+        let price_impact_fee = if fill.route_fee_rate.is_some() {
+            0.0
+        } else {
+            self.calculate_price_impact_fee(executed_value, fill.model_slippage)
+        };
         let total_fee = trading_fee + network_fee + price_impact_fee;
 
         let total_cost = match request.side {
@@ -837,6 +1298,9 @@ impl PaperTradingManager {
             slippage,
             total_cost,
             timestamp: Utc::now(),
+            fill_source: fill.source.to_string(),
+            latency_ms: latency_ms as i64,
+            order_id,
         };
 
         db_read
@@ -1014,33 +1478,395 @@ impl PaperTradingManager {
             .map_err(|e| format!("Failed to load paper performance: {e}"))
     }
 
-    pub async fn update_position_prices(&self, symbol: &str, price: f64) -> Result<(), String> {
-        self.current_prices
-            .write()
+    /// Places an order that fills immediately if the market already crosses
+    /// it, and otherwise rests until a price update does.
+    pub async fn place_order(
+        &self,
+        request: ExecutePaperTradeRequest,
+    ) -> Result<PaperOrderResult, String> {
+        validate_order_shape(&request)?;
+        let _guard = self.order_lock.lock().await;
+
+        let mut order = {
+            let db_read = self.db.read().await;
+            let account = db_read
+                .get_or_create_account(DEFAULT_INITIAL_BALANCE)
+                .await
+                .map_err(|e| format!("Failed to load paper account: {e}"))?;
+
+            if request.side == OrderSide::Sell {
+                self.ensure_position_exists(&db_read, &account.id, &request)
+                    .await?;
+            }
+
+            let now = Utc::now();
+            let order = PaperOrder {
+                id: Uuid::new_v4().to_string(),
+                account_id: account.id,
+                symbol: request.symbol.clone(),
+                mint: request.mint.clone(),
+                decimals: request.decimals,
+                side: request.side,
+                order_type: request.order_type,
+                quantity: request.quantity,
+                limit_price: request.limit_price,
+                stop_price: request.stop_price,
+                status: OrderStatus::Pending,
+                trade_id: None,
+                status_reason: None,
+                created_at: now,
+                updated_at: now,
+            };
+            db_read
+                .create_order(&order)
+                .await
+                .map_err(|e| format!("Failed to store paper order: {e}"))?;
+            order
+        };
+
+        if !order_triggered(
+            order.side,
+            order.order_type,
+            request.price,
+            order.limit_price,
+            order.stop_price,
+        ) {
+            return Ok(PaperOrderResult { order, fill: None });
+        }
+
+        let fill = self.fill_order(&mut order, request.price).await?;
+        Ok(PaperOrderResult {
+            order,
+            fill: Some(fill),
+        })
+    }
+
+    /// Fills a resting order at `market_price`, recording the outcome on the
+    /// order either way.
+    async fn fill_order(
+        &self,
+        order: &mut PaperOrder,
+        market_price: f64,
+    ) -> Result<PaperTradeResult, String> {
+        let result = self
+            .execute_fill(order.to_request(market_price), Some(order.id.clone()))
+            .await;
+
+        match &result {
+            Ok(fill) => {
+                order.status = OrderStatus::Filled;
+                order.trade_id = Some(fill.trade.id.clone());
+                order.status_reason = None;
+            }
+            Err(e) => {
+                order.status = OrderStatus::Failed;
+                order.status_reason = Some(e.clone());
+            }
+        }
+        order.updated_at = Utc::now();
+
+        self.db
+            .read()
             .await
-            .insert(symbol.to_string(), price);
+            .update_order(order)
+            .await
+            .map_err(|e| format!("Failed to update paper order: {e}"))?;
 
+        result
+    }
+
+    pub async fn cancel_order(&self, order_id: &str) -> Result<PaperOrder, String> {
+        let _guard = self.order_lock.lock().await;
+        let db_read = self.db.read().await;
+
+        let mut order = db_read
+            .get_order(order_id)
+            .await
+            .map_err(|e| format!("Failed to load paper order: {e}"))?
+            .ok_or_else(|| "Paper order not found".to_string())?;
+        if order.status != OrderStatus::Pending {
+            return Err(format!("Paper order is already {}", order.status));
+        }
+
+        order.status = OrderStatus::Cancelled;
+        order.updated_at = Utc::now();
+        db_read
+            .update_order(&order)
+            .await
+            .map_err(|e| format!("Failed to cancel paper order: {e}"))?;
+
+        Ok(order)
+    }
+
+    pub async fn get_open_orders(&self) -> Result<Vec<PaperOrder>, String> {
         let db_read = self.db.read().await;
         let account = db_read
             .get_or_create_account(DEFAULT_INITIAL_BALANCE)
             .await
             .map_err(|e| format!("Failed to load paper account: {e}"))?;
 
-        if let Some(mut position) = db_read
-            .get_position(&account.id, symbol)
+        db_read
+            .get_open_orders(&account.id, None)
             .await
-            .map_err(|e| format!("Failed to load paper position: {e}"))?
-        {
-            position.current_price = price;
-            position.unrealized_pnl = (price - position.entry_price) * position.quantity;
+            .map_err(|e| format!("Failed to load paper orders: {e}"))
+    }
 
-            db_read
-                .update_position_price(&position.id, price, position.unrealized_pnl)
+    /// Marks positions to `price` and fills any resting orders on `symbol`
+    /// the new price crosses. Returns the orders that were triggered.
+    pub async fn update_position_prices(
+        &self,
+        symbol: &str,
+        price: f64,
+    ) -> Result<Vec<PaperOrder>, String> {
+        self.current_prices
+            .write()
+            .await
+            .insert(symbol.to_string(), price);
+
+        let account_id = {
+            let db_read = self.db.read().await;
+            let account = db_read
+                .get_or_create_account(DEFAULT_INITIAL_BALANCE)
+                .await
+                .map_err(|e| format!("Failed to load paper account: {e}"))?;
+
+            if let Some(mut position) = db_read
+                .get_position(&account.id, symbol)
                 .await
-                .map_err(|e| format!("Failed to update paper position price: {e}"))?;
+                .map_err(|e| format!("Failed to load paper position: {e}"))?
+            {
+                position.current_price = price;
+                position.unrealized_pnl = (price - position.entry_price) * position.quantity;
+
+                db_read
+                    .update_position_price(&position.id, price, position.unrealized_pnl)
+                    .await
+                    .map_err(|e| format!("Failed to update paper position price: {e}"))?;
+            }
+
+            account.id
+        };
+
+        let _guard = self.order_lock.lock().await;
+        let open_orders = self
+            .db
+            .read()
+            .await
+            .get_open_orders(&account_id, Some(symbol))
+            .await
+            .map_err(|e| format!("Failed to load paper orders: {e}"))?;
+
+        let mut triggered = Vec::new();
+        for mut order in open_orders {
+            if !order_triggered(
+                order.side,
+                order.order_type,
+                price,
+                order.limit_price,
+                order.stop_price,
+            ) {
+                continue;
+            }
+            if let Err(e) = self.fill_order(&mut order, price).await {
+                eprintln!("Paper order {} failed to fill: {e}", order.id);
+            }
+            triggered.push(order);
         }
 
-        Ok(())
+        Ok(triggered)
+    }
+}
+
+fn validate_order_shape(request: &ExecutePaperTradeRequest) -> Result<(), String> {
+    if request.quantity <= 0.0 {
+        return Err("Quantity must be greater than zero".to_string());
+    }
+    if request.price <= 0.0 {
+        return Err("Price must be greater than zero".to_string());
+    }
+
+    match request.order_type {
+        OrderType::Market => Ok(()),
+        OrderType::Limit | OrderType::TakeProfit => match request.limit_price {
+            Some(limit) if limit > 0.0 => Ok(()),
+            Some(_) => Err("Limit price must be greater than zero".to_string()),
+            None => Err("Limit price required for limit orders".to_string()),
+        },
+        OrderType::StopLoss => match request.stop_price {
+            Some(stop) if stop > 0.0 => Ok(()),
+            Some(_) => Err("Stop price must be greater than zero".to_string()),
+            None => Err("Stop price required for stop orders".to_string()),
+        },
+        _ => Err("Unsupported order type for paper trading".to_string()),
+    }
+}
+
+/// Whether an order would execute at `market_price`. Limit and take-profit
+/// orders fill at their price or better; stops fire once the market trades
+/// through them.
+fn order_triggered(
+    side: OrderSide,
+    order_type: OrderType,
+    market_price: f64,
+    limit_price: Option<f64>,
+    stop_price: Option<f64>,
+) -> bool {
+    match order_type {
+        OrderType::Market => true,
+        OrderType::Limit | OrderType::TakeProfit => limit_price.is_some_and(|limit| match side {
+            OrderSide::Buy => market_price <= limit,
+            OrderSide::Sell => market_price >= limit,
+        }),
+        OrderType::StopLoss => stop_price.is_some_and(|stop| match side {
+            OrderSide::Buy => market_price >= stop,
+            OrderSide::Sell => market_price <= stop,
+        }),
+        _ => false,
+    }
+}
+
+/// Fraction by which the fill is worse than the reference price; negative
+/// when the market moved in the trader's favour.
+fn adverse_slippage(side: OrderSide, reference_price: f64, execution_price: f64) -> f64 {
+    match side {
+        OrderSide::Buy => (execution_price - reference_price) / reference_price,
+        OrderSide::Sell => (reference_price - execution_price) / reference_price,
+    }
+}
+
+async fn simulate_latency(config: &PaperExecutionConfig) -> u64 {
+    let latency_ms = if config.max_latency_ms > config.min_latency_ms {
+        rand::random_range(config.min_latency_ms..=config.max_latency_ms)
+    } else {
+        config.min_latency_ms
+    };
+    if latency_ms > 0 {
+        tokio::time::sleep(Duration::from_millis(latency_ms)).await;
+    }
+    latency_ms
+}
+
+fn drift_price(price: f64, latency_ms: u64, drift_bps_per_sec: f64) -> f64 {
+    let max_drift = drift_bps_per_sec.max(0.0) / 10_000.0 * latency_ms as f64 / 1_000.0;
+    if max_drift <= 0.0 {
+        return price;
+    }
+    price * (1.0 + rand::random_range(-max_drift..max_drift))
+}
+
+fn resolve_mint(request: &ExecutePaperTradeRequest) -> Option<(String, u8)> {
+    match (request.mint.as_deref(), request.decimals) {
+        (Some(USDC_MINT), _) => None,
+        (Some(mint), Some(decimals)) => Some((mint.to_string(), decimals)),
+        (Some(SOL_MINT), None) => Some((SOL_MINT.to_string(), SOL_DECIMALS)),
+        (None, _) if request.symbol.eq_ignore_ascii_case("SOL") => {
+            Some((SOL_MINT.to_string(), SOL_DECIMALS))
+        }
+        _ => None,
+    }
+}
+
+/// Quotes the order against USDC and returns the fill price net of route
+/// fees, together with the fee rate so it can be charged separately.
+async fn quote_fill_price(
+    side: OrderSide,
+    mint: &str,
+    decimals: u8,
+    quantity: f64,
+    reference_price: f64,
+    slippage_bps: u16,
+) -> Result<(f64, f64), String> {
+    let to_base_units = |amount: f64, decimals: u8| -> Result<u64, String> {
+        let units = (amount * 10f64.powi(i32::from(decimals))).round();
+        if units < 1.0 || units > u64::MAX as f64 {
+            return Err("Order size cannot be quoted".to_string());
+        }
+        Ok(units as u64)
+    };
+
+    let (input_mint, output_mint, amount, output_decimals) = match side {
+        OrderSide::Buy => (
+            USDC_MINT,
+            mint,
+            to_base_units(quantity * reference_price, USDC_DECIMALS)?,
+            decimals,
+        ),
+        OrderSide::Sell => (
+            mint,
+            USDC_MINT,
+            to_base_units(quantity, decimals)?,
+            USDC_DECIMALS,
+        ),
+    };
+
+    let quote = fetch_quote(&QuoteCommandInput {
+        input_mint: input_mint.to_string(),
+        output_mint: output_mint.to_string(),
+        amount,
+        slippage_bps: Some(slippage_bps),
+        swap_mode: Some(SwapMode::ExactIn),
+        platform_fee_bps: None,
+        only_direct_routes: None,
+        referral_account: None,
+        as_legacy_transaction: None,
+        priority_fee_config: None,
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let output = quote
+        .quote
+        .output_amount
+        .parse::<f64>()
+        .map_err(|e| format!("Invalid quote output: {e}"))?
+        / 10f64.powi(i32::from(output_decimals));
+    if output <= 0.0 {
+        return Err("Quote returned no output".to_string());
+    }
+
+    let fee_rate = (quote.route.total_fee_bps as f64 / 10_000.0).min(0.5);
+    let price = match side {
+        OrderSide::Buy => (quantity * reference_price) / output * (1.0 - fee_rate),
+        OrderSide::Sell => output / quantity / (1.0 - fee_rate),
+    };
+
+    Ok((price, fee_rate))
+}
+
+fn parse_side(value: &str) -> Result<OrderSide, sqlx::Error> {
+    match value {
+        "buy" => Ok(OrderSide::Buy),
+        "sell" => Ok(OrderSide::Sell),
+        other => Err(sqlx::Error::Decode(
+            format!("unknown order side: {other}").into(),
+        )),
+    }
+}
+
+fn parse_order_type(value: &str) -> Result<OrderType, sqlx::Error> {
+    match value {
+        "market" => Ok(OrderType::Market),
+        "limit" => Ok(OrderType::Limit),
+        "stop_loss" => Ok(OrderType::StopLoss),
+        "take_profit" => Ok(OrderType::TakeProfit),
+        "trailing_stop" => Ok(OrderType::TrailingStop),
+        other => Err(sqlx::Error::Decode(
+            format!("unknown order type: {other}").into(),
+        )),
+    }
+}
+
+fn parse_order_status(value: &str) -> Result<OrderStatus, sqlx::Error> {
+    match value {
+        "pending" => Ok(OrderStatus::Pending),
+        "partially_filled" => Ok(OrderStatus::PartiallyFilled),
+        "filled" => Ok(OrderStatus::Filled),
+        "cancelled" => Ok(OrderStatus::Cancelled),
+        "expired" => Ok(OrderStatus::Expired),
+        "failed" => Ok(OrderStatus::Failed),
+        other => Err(sqlx::Error::Decode(
+            format!("unknown order status: {other}").into(),
+        )),
     }
 }
 
@@ -1059,7 +1885,7 @@ pub async fn init_paper_trading(app_handle: &AppHandle) -> Result<(), String> {
 
     let app_dir = app_handle
         .path()
-        .app_data_dir()
+        .profile_data_dir()
         .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
 
     std::fs::create_dir_all(&app_dir)
@@ -1074,6 +1900,9 @@ pub async fn init_paper_trading(app_handle: &AppHandle) -> Result<(), String> {
 
     let shared_db = Arc::new(RwLock::new(db));
     let manager = Arc::new(PaperTradingManager::new(shared_db));
+    if let Err(e) = manager.load_execution_config().await {
+        eprintln!("{e}; using default paper execution config");
+    }
 
     PAPER_TRADING_STATE
         .set(manager)
@@ -1093,52 +1922,118 @@ fn require_state() -> Result<&'static SharedPaperTradingManager, String> {
 // ============================================================================
 
 #[tauri::command]
-pub async fn paper_trading_init(handle: AppHandle) -> Result<(), String> {
-    init_paper_trading(&handle).await
+pub async fn paper_trading_init(handle: AppHandle) -> Result<(), AppError> {
+    init_paper_trading(&handle)
+        .await
+        .logged("paper_trading_init")
 }
 
 #[tauri::command]
-pub async fn get_paper_account() -> Result<PaperAccount, String> {
-    let manager = require_state()?;
-    manager.get_account().await
+pub async fn get_paper_account() -> Result<PaperAccount, AppError> {
+    let manager = require_state().logged("get_paper_account")?;
+    manager.get_account().await.logged("get_paper_account")
 }
 
 #[tauri::command]
-pub async fn reset_paper_account(initial_balance: Option<f64>) -> Result<PaperAccount, String> {
-    let manager = require_state()?;
-    manager.reset_account(initial_balance).await
+pub async fn reset_paper_account(initial_balance: Option<f64>) -> Result<PaperAccount, AppError> {
+    let manager = require_state().logged("reset_paper_account")?;
+    manager
+        .reset_account(initial_balance)
+        .await
+        .logged("reset_paper_account")
 }
 
 #[tauri::command]
 pub async fn execute_paper_trade(
     request: ExecutePaperTradeRequest,
-) -> Result<PaperTradeResult, String> {
-    let manager = require_state()?;
-    manager.execute_trade(request).await
+) -> Result<PaperTradeResult, AppError> {
+    let manager = require_state().logged("execute_paper_trade")?;
+    manager
+        .execute_trade(request)
+        .await
+        .logged("execute_paper_trade")
+}
+
+#[tauri::command]
+pub async fn get_paper_positions() -> Result<Vec<PaperPosition>, AppError> {
+    let manager = require_state().logged("get_paper_positions")?;
+    manager.get_positions().await.logged("get_paper_positions")
+}
+
+#[tauri::command]
+pub async fn get_paper_trade_history() -> Result<Vec<PaperTrade>, AppError> {
+    let manager = require_state().logged("get_paper_trade_history")?;
+    manager
+        .get_trade_history()
+        .await
+        .logged("get_paper_trade_history")
+}
+
+#[tauri::command]
+pub async fn get_paper_performance() -> Result<PaperPerformance, AppError> {
+    let manager = require_state().logged("get_paper_performance")?;
+    manager
+        .get_performance()
+        .await
+        .logged("get_paper_performance")
+}
+
+#[tauri::command]
+pub async fn update_paper_position_prices(
+    symbol: String,
+    price: f64,
+) -> Result<Vec<PaperOrder>, AppError> {
+    let manager = require_state().logged("update_paper_position_prices")?;
+    manager
+        .update_position_prices(&symbol, price)
+        .await
+        .logged("update_paper_position_prices")
+}
+
+#[tauri::command]
+pub async fn place_paper_order(
+    request: ExecutePaperTradeRequest,
+) -> Result<PaperOrderResult, AppError> {
+    let manager = require_state().logged("place_paper_order")?;
+    manager
+        .place_order(request)
+        .await
+        .logged("place_paper_order")
 }
 
 #[tauri::command]
-pub async fn get_paper_positions() -> Result<Vec<PaperPosition>, String> {
-    let manager = require_state()?;
-    manager.get_positions().await
+pub async fn cancel_paper_order(order_id: String) -> Result<PaperOrder, AppError> {
+    let manager = require_state().logged("cancel_paper_order")?;
+    manager
+        .cancel_order(&order_id)
+        .await
+        .logged("cancel_paper_order")
 }
 
 #[tauri::command]
-pub async fn get_paper_trade_history() -> Result<Vec<PaperTrade>, String> {
-    let manager = require_state()?;
-    manager.get_trade_history().await
+pub async fn get_paper_open_orders() -> Result<Vec<PaperOrder>, AppError> {
+    let manager = require_state().logged("get_paper_open_orders")?;
+    manager
+        .get_open_orders()
+        .await
+        .logged("get_paper_open_orders")
 }
 
 #[tauri::command]
-pub async fn get_paper_performance() -> Result<PaperPerformance, String> {
-    let manager = require_state()?;
-    manager.get_performance().await
+pub async fn get_paper_execution_config() -> Result<PaperExecutionConfig, AppError> {
+    let manager = require_state().logged("get_paper_execution_config")?;
+    Ok(manager.execution_config().await)
 }
 
 #[tauri::command]
-pub async fn update_paper_position_prices(symbol: String, price: f64) -> Result<(), String> {
-    let manager = require_state()?;
-    manager.update_position_prices(&symbol, price).await
+pub async fn update_paper_execution_config(
+    config: PaperExecutionConfig,
+) -> Result<PaperExecutionConfig, AppError> {
+    let manager = require_state().logged("update_paper_execution_config")?;
+    manager
+        .update_execution_config(config)
+        .await
+        .logged("update_paper_execution_config")
 }
 
 pub fn register_paper_trading_state(app: &AppHandle) {
@@ -1173,11 +2068,34 @@ mod tests {
         let database = PaperTradingDatabase::new(db_path)
             .await
             .expect("failed to create paper trading database");
-        PaperTradingManager::with_config(
+        let manager = PaperTradingManager::with_config(
             Arc::new(RwLock::new(database)),
             slippage_config,
             fee_config,
-        )
+        );
+        // Keep tests offline and instant.
+        *manager.execution_config.write().await = PaperExecutionConfig {
+            use_live_quotes: false,
+            min_latency_ms: 0,
+            max_latency_ms: 0,
+            drift_bps_per_sec: 0.0,
+            ..Default::default()
+        };
+        manager
+    }
+
+    fn limit_request(side: OrderSide, price: f64, limit_price: f64) -> ExecutePaperTradeRequest {
+        ExecutePaperTradeRequest {
+            symbol: "SOL".to_string(),
+            side,
+            order_type: OrderType::Limit,
+            quantity: 1.0,
+            price,
+            limit_price: Some(limit_price),
+            stop_price: None,
+            mint: None,
+            decimals: None,
+        }
     }
 
     fn deterministic_slippage_config() -> SlippageConfig {
@@ -1205,6 +2123,8 @@ mod tests {
             price: 100.0,
             limit_price: None,
             stop_price: None,
+            mint: None,
+            decimals: None,
         };
 
         let result = manager
@@ -1248,6 +2168,8 @@ mod tests {
             price: 100.0,
             limit_price: None,
             stop_price: None,
+            mint: None,
+            decimals: None,
         };
 
         let result = manager
@@ -1279,6 +2201,8 @@ mod tests {
             price: 100.0,
             limit_price: None,
             stop_price: None,
+            mint: None,
+            decimals: None,
         };
         manager
             .execute_trade(buy_request)
@@ -1293,6 +2217,8 @@ mod tests {
             price: 110.0,
             limit_price: None,
             stop_price: None,
+            mint: None,
+            decimals: None,
         };
         let sell_result = manager
             .execute_trade(sell_request)
@@ -1321,6 +2247,8 @@ mod tests {
             price: 50.0,
             limit_price: None,
             stop_price: None,
+            mint: None,
+            decimals: None,
         };
         manager
             .execute_trade(buy_request)
@@ -1335,6 +2263,8 @@ mod tests {
             price: 60.0,
             limit_price: None,
             stop_price: None,
+            mint: None,
+            decimals: None,
         };
         manager
             .execute_trade(sell_request)
@@ -1346,4 +2276,101 @@ mod tests {
         assert_eq!(performance.total_trades, 2);
         assert!(performance.total_pnl > 0.0);
     }
+
+    #[tokio::test]
+    async fn test_resting_limit_order_fills_when_crossed() {
+        let manager =
+            create_manager_with_configs(deterministic_slippage_config(), FeeConfig::default())
+                .await;
+
+        let placed = manager
+            .place_order(limit_request(OrderSide::Buy, 105.0, 100.0))
+            .await
+            .expect("order placement");
+        assert!(placed.fill.is_none());
+        assert_eq!(placed.order.status, OrderStatus::Pending);
+
+        let untouched = manager
+            .update_position_prices("SOL", 101.0)
+            .await
+            .expect("price update");
+        assert!(untouched.is_empty());
+
+        let triggered = manager
+            .update_position_prices("SOL", 99.0)
+            .await
+            .expect("price update");
+        assert_eq!(triggered.len(), 1);
+        assert_eq!(triggered[0].status, OrderStatus::Filled);
+        assert!(manager.get_open_orders().await.expect("orders").is_empty());
+
+        let trades = manager.get_trade_history().await.expect("history");
+        assert_eq!(trades.len(), 1);
+        assert!(trades[0].price <= 100.0);
+        assert_eq!(
+            trades[0].order_id.as_deref(),
+            Some(placed.order.id.as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_order_does_not_fill() {
+        let manager =
+            create_manager_with_configs(deterministic_slippage_config(), FeeConfig::default())
+                .await;
+
+        let placed = manager
+            .place_order(limit_request(OrderSide::Buy, 105.0, 100.0))
+            .await
+            .expect("order placement");
+        let cancelled = manager
+            .cancel_order(&placed.order.id)
+            .await
+            .expect("cancel");
+        assert_eq!(cancelled.status, OrderStatus::Cancelled);
+        assert!(manager.cancel_order(&placed.order.id).await.is_err());
+
+        let triggered = manager
+            .update_position_prices("SOL", 90.0)
+            .await
+            .expect("price update");
+        assert!(triggered.is_empty());
+        assert!(manager
+            .get_trade_history()
+            .await
+            .expect("history")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_order_trigger_rules() {
+        assert!(order_triggered(
+            OrderSide::Sell,
+            OrderType::StopLoss,
+            94.0,
+            None,
+            Some(95.0)
+        ));
+        assert!(!order_triggered(
+            OrderSide::Sell,
+            OrderType::StopLoss,
+            96.0,
+            None,
+            Some(95.0)
+        ));
+        assert!(order_triggered(
+            OrderSide::Sell,
+            OrderType::TakeProfit,
+            120.0,
+            Some(110.0),
+            None
+        ));
+        assert!(!order_triggered(
+            OrderSide::Buy,
+            OrderType::Limit,
+            101.0,
+            Some(100.0),
+            None
+        ));
+    }
 }
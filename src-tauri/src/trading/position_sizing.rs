@@ -0,0 +1,402 @@
+use crate::api_config::stored_birdeye_key;
+use crate::errors::{AppError, CommandResultExt};
+use crate::market::birdeye_history::hourly_price_history;
+use crate::security::keystore::Keystore;
+use crate::trading::safety::SharedSafetyEngine;
+use crate::wallet::operations::WalletOperationsManager;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+/// Hard ceiling on any single position unless the caller asks for less.
+const DEFAULT_MAX_POSITION_PERCENT: f64 = 25.0;
+/// Hourly prices used for realized volatility.
+const VOLATILITY_LOOKBACK_SECS: i64 = 7 * 24 * 3600;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "camelCase")]
+pub enum SizingMethod {
+    /// Kelly criterion from the strategy's historical edge. `fraction`
+    /// scales the full Kelly bet down (0.5 is half-Kelly).
+    #[serde(rename_all = "camelCase")]
+    Kelly {
+        win_rate: f64,
+        win_loss_ratio: f64,
+        fraction: Option<f64>,
+    },
+    /// Risks a fixed share of the balance between entry and the stop.
+    #[serde(rename_all = "camelCase")]
+    FixedFractional {
+        risk_percent: f64,
+        stop_loss_percent: f64,
+    },
+    /// Like fixed-fractional, but the stop distance is a multiple of the
+    /// token's recent daily volatility, so choppier tokens get smaller size.
+    #[serde(rename_all = "camelCase")]
+    VolatilityAdjusted {
+        risk_percent: f64,
+        volatility_multiplier: Option<f64>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizingPreset {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub method: SizingMethod,
+    pub max_position_percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionSizeRequest {
+    pub wallet_address: String,
+    pub token_address: String,
+    pub entry_price: f64,
+    pub method: SizingMethod,
+    /// Falls back to the wallet's cached token balances when omitted.
+    pub account_balance_usd: Option<f64>,
+    pub max_position_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SizingLimits {
+    pub max_position_percent: f64,
+    /// From the safety policy, when it is enabled.
+    pub max_trade_amount_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionSizeResult {
+    pub account_balance_usd: f64,
+    /// Size the method asked for before any caps.
+    pub suggested_size_usd: f64,
+    pub size_usd: f64,
+    /// Amount to pass to `create_order`.
+    pub size_tokens: f64,
+    pub percent_of_balance: f64,
+    /// Loss if the stop is hit, when the method defines a stop.
+    pub risk_usd: Option<f64>,
+    pub stop_loss_price: Option<f64>,
+    pub daily_volatility_percent: Option<f64>,
+    pub capped_by: Vec<String>,
+}
+
+/// Daily volatility in percent from hourly prices: the standard deviation
+/// of hourly log returns scaled by sqrt(24).
+pub fn realized_daily_volatility(hourly_prices: &[f64]) -> Option<f64> {
+    let returns: Vec<f64> = hourly_prices
+        .windows(2)
+        .filter(|w| w[0] > 0.0 && w[1] > 0.0)
+        .map(|w| (w[1] / w[0]).ln())
+        .collect();
+    if returns.len() < 2 {
+        return None;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance =
+        returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    Some(variance.sqrt() * 24f64.sqrt() * 100.0)
+}
+
+pub fn size_position(
+    method: &SizingMethod,
+    balance_usd: f64,
+    entry_price: f64,
+    daily_volatility_percent: Option<f64>,
+    limits: &SizingLimits,
+) -> Result<PositionSizeResult, String> {
+    if balance_usd <= 0.0 {
+        return Err("Account balance must be positive".to_string());
+    }
+    if entry_price <= 0.0 {
+        return Err("Entry price must be positive".to_string());
+    }
+    if let SizingMethod::FixedFractional { risk_percent, .. }
+    | SizingMethod::VolatilityAdjusted { risk_percent, .. } = method
+    {
+        if !(*risk_percent > 0.0 && *risk_percent <= 100.0) {
+            return Err("Risk must be greater than 0% and at most 100%".to_string());
+        }
+    }
+
+    let (suggested_size_usd, stop_loss_percent) = match method {
+        SizingMethod::Kelly {
+            win_rate,
+            win_loss_ratio,
+            fraction,
+        } => {
+            if !(0.0..=1.0).contains(win_rate) || *win_loss_ratio <= 0.0 {
+                return Err(
+                    "Kelly needs a win rate in [0, 1] and a positive win/loss ratio".to_string(),
+                );
+            }
+            let kelly = win_rate - (1.0 - win_rate) / win_loss_ratio;
+            let fraction = fraction.unwrap_or(0.5).clamp(0.0, 1.0);
+            (balance_usd * (kelly * fraction).max(0.0), None)
+        }
+        SizingMethod::FixedFractional {
+            risk_percent,
+            stop_loss_percent,
+        } => {
+            if *stop_loss_percent <= 0.0 {
+                return Err("Stop loss must be positive".to_string());
+            }
+            (
+                balance_usd * risk_percent / stop_loss_percent,
+                Some(*stop_loss_percent),
+            )
+        }
+        SizingMethod::VolatilityAdjusted {
+            risk_percent,
+            volatility_multiplier,
+        } => {
+            let volatility = daily_volatility_percent
+                .filter(|v| *v > 0.0)
+                .ok_or_else(|| "No recent volatility data for this token".to_string())?;
+            let multiplier = volatility_multiplier.unwrap_or(2.0);
+            if multiplier <= 0.0 {
+                return Err("Volatility multiplier must be positive".to_string());
+            }
+            let stop = volatility * multiplier;
+            (balance_usd * risk_percent / stop, Some(stop))
+        }
+    };
+    if let Some(stop) = stop_loss_percent {
+        if stop >= 100.0 {
+            return Err(format!(
+                "A {:.1}% stop would put the stop price at or below zero",
+                stop
+            ));
+        }
+    }
+
+    let mut size_usd = suggested_size_usd;
+    let mut capped_by = Vec::new();
+    let position_cap = balance_usd * limits.max_position_percent / 100.0;
+    if size_usd > position_cap {
+        size_usd = position_cap;
+        capped_by.push(format!(
+            "Max position of {:.0}% of balance",
+            limits.max_position_percent
+        ));
+    }
+    if let Some(max_trade) = limits.max_trade_amount_usd {
+        if size_usd > max_trade {
+            size_usd = max_trade;
+            capped_by.push(format!("Safety policy max trade of ${max_trade:.2}"));
+        }
+    }
+
+    Ok(PositionSizeResult {
+        account_balance_usd: balance_usd,
+        suggested_size_usd,
+        size_usd,
+        size_tokens: size_usd / entry_price,
+        percent_of_balance: size_usd / balance_usd * 100.0,
+        risk_usd: stop_loss_percent.map(|stop| size_usd * stop / 100.0),
+        stop_loss_price: stop_loss_percent.map(|stop| entry_price * (1.0 - stop / 100.0)),
+        daily_volatility_percent,
+        capped_by,
+    })
+}
+
+async fn recent_volatility(keystore: &Keystore, mint: &str) -> Result<f64, String> {
+    let api_key = stored_birdeye_key(keystore)
+        .ok_or_else(|| "Volatility sizing needs a Birdeye API key for price history".to_string())?;
+    let to = chrono::Utc::now().timestamp();
+    let prices: Vec<f64> = hourly_price_history(
+        &reqwest::Client::new(),
+        &api_key,
+        mint,
+        to - VOLATILITY_LOOKBACK_SECS,
+        to,
+    )
+    .await?
+    .into_iter()
+    .map(|(_, price)| price)
+    .collect();
+    realized_daily_volatility(&prices)
+        .ok_or_else(|| format!("Not enough price history for {}", mint))
+}
+
+#[tauri::command]
+pub async fn calculate_position_size(
+    request: PositionSizeRequest,
+    safety_engine: State<'_, SharedSafetyEngine>,
+    operations: State<'_, WalletOperationsManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<PositionSizeResult, AppError> {
+    let balance_usd = request
+        .account_balance_usd
+        .or_else(|| operations.cached_balance_usd(&request.wallet_address))
+        .ok_or_else(|| {
+            AppError::Validation(
+                "Wallet balance unknown; refresh token balances or pass accountBalanceUsd"
+                    .to_string(),
+            )
+        })
+        .logged("calculate_position_size")?;
+
+    let max_trade_amount_usd = {
+        let engine = safety_engine.read().await;
+        let policy = engine.get_policy();
+        policy.max_trade_amount_usd.filter(|_| policy.enabled)
+    };
+    let limits = SizingLimits {
+        max_position_percent: request
+            .max_position_percent
+            .unwrap_or(DEFAULT_MAX_POSITION_PERCENT)
+            .clamp(0.0, 100.0),
+        max_trade_amount_usd,
+    };
+
+    // Only volatility sizing needs price history; the other methods must
+    // not fail just because no price source is configured.
+    let volatility = match request.method {
+        SizingMethod::VolatilityAdjusted { .. } => Some(
+            recent_volatility(&keystore, &request.token_address)
+                .await
+                .logged("calculate_position_size")?,
+        ),
+        _ => None,
+    };
+
+    size_position(
+        &request.method,
+        balance_usd,
+        request.entry_price,
+        volatility,
+        &limits,
+    )
+    .map_err(AppError::Validation)
+    .logged("calculate_position_size")
+}
+
+#[tauri::command]
+pub async fn get_sizing_presets() -> Result<Vec<SizingPreset>, AppError> {
+    Ok(vec![
+        SizingPreset {
+            id: "conservative".to_string(),
+            name: "Conservative".to_string(),
+            description: "Risk 0.5% of the balance with an 8% stop".to_string(),
+            method: SizingMethod::FixedFractional {
+                risk_percent: 0.5,
+                stop_loss_percent: 8.0,
+            },
+            max_position_percent: 10.0,
+        },
+        SizingPreset {
+            id: "standard".to_string(),
+            name: "Standard".to_string(),
+            description: "Risk 1% of the balance with a 5% stop".to_string(),
+            method: SizingMethod::FixedFractional {
+                risk_percent: 1.0,
+                stop_loss_percent: 5.0,
+            },
+            max_position_percent: 20.0,
+        },
+        SizingPreset {
+            id: "volatility".to_string(),
+            name: "Volatility-adjusted".to_string(),
+            description: "Risk 1% with a stop two daily moves away".to_string(),
+            method: SizingMethod::VolatilityAdjusted {
+                risk_percent: 1.0,
+                volatility_multiplier: Some(2.0),
+            },
+            max_position_percent: 20.0,
+        },
+        SizingPreset {
+            id: "quarter-kelly".to_string(),
+            name: "Quarter Kelly".to_string(),
+            description: "A quarter of the Kelly bet for a 55% win rate at 1.5:1".to_string(),
+            method: SizingMethod::Kelly {
+                win_rate: 0.55,
+                win_loss_ratio: 1.5,
+                fraction: Some(0.25),
+            },
+            max_position_percent: 25.0,
+        },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> SizingLimits {
+        SizingLimits {
+            max_position_percent: 25.0,
+            max_trade_amount_usd: None,
+        }
+    }
+
+    #[test]
+    fn fixed_fractional_risks_the_requested_share() {
+        let method = SizingMethod::FixedFractional {
+            risk_percent: 1.0,
+            stop_loss_percent: 5.0,
+        };
+        let result = size_position(&method, 10_000.0, 2.0, None, &limits()).unwrap();
+        assert!((result.size_usd - 2_000.0).abs() < 1e-9);
+        assert!((result.size_tokens - 1_000.0).abs() < 1e-9);
+        assert!((result.risk_usd.unwrap() - 100.0).abs() < 1e-9);
+        assert!((result.stop_loss_price.unwrap() - 1.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn kelly_is_capped_by_position_and_safety_limits() {
+        let method = SizingMethod::Kelly {
+            win_rate: 0.6,
+            win_loss_ratio: 2.0,
+            fraction: Some(1.0),
+        };
+        // Full Kelly is 40% of the balance.
+        let result = size_position(&method, 10_000.0, 1.0, None, &limits()).unwrap();
+        assert!((result.suggested_size_usd - 4_000.0).abs() < 1e-9);
+        assert_eq!(result.size_usd, 2_500.0);
+
+        let strict = SizingLimits {
+            max_trade_amount_usd: Some(1_000.0),
+            ..limits()
+        };
+        let result = size_position(&method, 10_000.0, 1.0, None, &strict).unwrap();
+        assert_eq!(result.size_usd, 1_000.0);
+        assert_eq!(result.capped_by.len(), 2);
+
+        let no_edge = SizingMethod::Kelly {
+            win_rate: 0.3,
+            win_loss_ratio: 1.0,
+            fraction: None,
+        };
+        let result = size_position(&no_edge, 10_000.0, 1.0, None, &limits()).unwrap();
+        assert_eq!(result.size_usd, 0.0);
+    }
+
+    #[test]
+    fn rejects_out_of_range_risk_and_stops_below_zero() {
+        for risk_percent in [0.0, -1.0, 150.0] {
+            let method = SizingMethod::FixedFractional {
+                risk_percent,
+                stop_loss_percent: 5.0,
+            };
+            assert!(size_position(&method, 10_000.0, 2.0, None, &limits()).is_err());
+        }
+
+        let through_zero = SizingMethod::FixedFractional {
+            risk_percent: 1.0,
+            stop_loss_percent: 100.0,
+        };
+        assert!(size_position(&through_zero, 10_000.0, 2.0, None, &limits()).is_err());
+
+        // Three 40% daily moves puts the stop below zero.
+        let wide = SizingMethod::VolatilityAdjusted {
+            risk_percent: 1.0,
+            volatility_multiplier: Some(3.0),
+        };
+        assert!(size_position(&wide, 10_000.0, 2.0, Some(40.0), &limits()).is_err());
+        assert!(size_position(&wide, 10_000.0, 2.0, Some(10.0), &limits()).is_ok());
+    }
+}
@@ -1,5 +1,9 @@
+use crate::core::WebSocketManager;
+use crate::errors::{AppError, CommandResultExt};
+use crate::websocket::types::{StreamEvent, StreamProvider};
 use serde::{Deserialize, Serialize};
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceUpdate {
@@ -10,15 +14,52 @@ pub struct PriceUpdate {
     pub ts: i64,
 }
 
-pub async fn start_price_listener(_app_handle: AppHandle) {
-    println!("Price listener initialized - will receive updates from WebSocket manager");
+/// Feeds Birdeye stream prices into the order manager so triggers and
+/// trailing stops move without the frontend calling `update_order_prices`.
+pub async fn start_price_listener(app_handle: AppHandle) {
+    use crate::trading::limit_orders::require_state;
+
+    let state = match require_state() {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("Price listener disabled: {}", e);
+            return;
+        }
+    };
+    let manager = state.manager.clone();
+    if let Err(e) = manager.watch_active_trailing_stops().await {
+        eprintln!("Failed to resume trailing stop price feeds: {}", e);
+    }
+
+    let Some(ws_manager) = app_handle.try_state::<WebSocketManager>() else {
+        eprintln!("Price listener disabled: WebSocket manager not available");
+        return;
+    };
+    let Some(mut events) = ws_manager.event_receiver(StreamProvider::Birdeye).await else {
+        eprintln!("Price listener disabled: no Birdeye stream");
+        return;
+    };
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(StreamEvent::PriceUpdate(delta)) => {
+                    if let Some(price) = delta.price {
+                        manager.update_price(&delta.symbol, price).await;
+                    }
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
 }
 
 #[tauri::command]
-pub async fn update_order_prices(symbol: String, price: f64) -> Result<(), String> {
+pub async fn update_order_prices(symbol: String, price: f64) -> Result<(), AppError> {
     use crate::trading::limit_orders::require_state;
 
-    let state = require_state()?;
+    let state = require_state().logged("update_order_prices")?;
     state.manager.update_price(&symbol, price).await;
     Ok(())
 }
@@ -2,18 +2,23 @@ pub mod cooldown;
 pub mod insurance;
 pub mod policy;
 pub mod simulator;
+pub mod trace;
 
 use cooldown::CooldownManager;
 use insurance::InsuranceCoordinator;
 use policy::PolicyEngine;
 use simulator::TransactionSimulator;
+use trace::DecisionTraceLog;
 
 pub use cooldown::CooldownStatus;
 pub use insurance::{InsuranceProvider, InsuranceQuote, InsuranceSelection};
 pub use policy::{PolicyCheckResult, PolicyViolation, SafetyPolicy, ViolationSeverity};
 pub use simulator::{ImpactPreview, MevRiskLevel, RouteHop, TransactionSimulation};
+pub use trace::{DecisionTrace, RuleEvaluation, RuleOutcome};
 
+use crate::token_extensions::{ExtensionRiskSeverity, TokenExtensionReport};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -29,6 +34,11 @@ pub struct SafetyCheckRequest {
     pub slippage_bps: u64,
     pub price_impact_percent: f64,
     pub security_score: Option<f64>,
+    /// Token-2022 extension data for each side of the swap, when known.
+    #[serde(default)]
+    pub input_extensions: Option<TokenExtensionReport>,
+    #[serde(default)]
+    pub output_extensions: Option<TokenExtensionReport>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +51,9 @@ pub struct SafetyCheckResult {
     pub insurance_required: bool,
     pub insurance_recommendation: Option<InsuranceQuote>,
     pub mev_suggestions: Vec<String>,
+    /// Every rule evaluated for this decision; also kept in the engine's
+    /// trace log for later review.
+    pub decision_trace: DecisionTrace,
 }
 
 pub struct SafetyEngine {
@@ -49,6 +62,7 @@ pub struct SafetyEngine {
     simulator: TransactionSimulator,
     insurance_coordinator: InsuranceCoordinator,
     emergency_halt: bool,
+    decision_traces: DecisionTraceLog,
 }
 
 impl SafetyEngine {
@@ -59,6 +73,7 @@ impl SafetyEngine {
             simulator: TransactionSimulator::default(),
             insurance_coordinator: InsuranceCoordinator::default(),
             emergency_halt: false,
+            decision_traces: DecisionTraceLog::default(),
         }
     }
 
@@ -79,12 +94,17 @@ impl SafetyEngine {
         request: SafetyCheckRequest,
     ) -> Result<SafetyCheckResult, String> {
         if self.emergency_halt {
-            let policy_result = PolicyCheckResult::new_blocked(vec![PolicyViolation {
+            let violation = PolicyViolation {
                 rule: "emergency_halt".to_string(),
                 message: "Emergency trading halt is active".to_string(),
                 severity: ViolationSeverity::Critical,
                 can_override: false,
-            }]);
+            };
+            let rules = vec![RuleEvaluation::violated(
+                &violation,
+                json!({ "emergency_halt": true }),
+            )];
+            let policy_result = PolicyCheckResult::new_blocked(vec![violation]);
 
             return Ok(SafetyCheckResult {
                 allowed: false,
@@ -95,20 +115,28 @@ impl SafetyEngine {
                 insurance_required: false,
                 insurance_recommendation: None,
                 mev_suggestions: Vec::new(),
+                decision_trace: self.record_decision(&request, false, rules),
             });
         }
+        let mut rules = vec![RuleEvaluation::passed(
+            "emergency_halt",
+            json!({ "emergency_halt": false }),
+        )];
 
         // Check policy violations
-        let policy_result = self.policy_engine.check_trade_policy(
+        let (mut policy_result, policy_rules) = self.policy_engine.evaluate_trade_policy(
             &request.wallet_address,
             request.amount_usd,
             request.price_impact_percent,
             request.slippage_bps as f64 / 100.0,
             request.security_score,
         );
+        rules.extend(policy_rules);
+        apply_extension_checks(&mut policy_result, &mut rules, &request);
 
         // Check cooldown
-        let cooldown_status = if self.get_policy().cooldown_enabled {
+        let cooldown_enabled = self.get_policy().cooldown_enabled;
+        let cooldown_status = if cooldown_enabled {
             self.cooldown_manager
                 .get_remaining_cooldown(&request.wallet_address)
         } else {
@@ -116,6 +144,24 @@ impl SafetyEngine {
         };
 
         let on_cooldown = cooldown_status.is_some();
+        rules.push(match &cooldown_status {
+            _ if !cooldown_enabled => {
+                RuleEvaluation::skipped("cooldown", json!({}), "Trade cooldown is disabled")
+            }
+            Some(status) => RuleEvaluation::failed(
+                "cooldown",
+                json!({
+                    "cooldown_seconds": status.cooldown_seconds,
+                    "remaining_seconds": status.remaining_seconds,
+                    "last_trade_timestamp": status.last_trade_timestamp,
+                }),
+                format!(
+                    "Wallet is cooling down for another {}s",
+                    status.remaining_seconds
+                ),
+            ),
+            None => RuleEvaluation::passed("cooldown", json!({ "remaining_seconds": 0 })),
+        });
 
         // Run transaction simulation if required
         let simulation = if self.get_policy().require_simulation {
@@ -171,6 +217,7 @@ impl SafetyEngine {
         let mev_suggestions = self.simulator.suggest_mev_protection(request.amount_usd);
 
         let allowed = policy_result.allowed && !on_cooldown;
+        let decision_trace = self.record_decision(&request, allowed, rules);
 
         Ok(SafetyCheckResult {
             allowed,
@@ -181,9 +228,42 @@ impl SafetyEngine {
             insurance_required,
             insurance_recommendation,
             mev_suggestions,
+            decision_trace,
         })
     }
 
+    fn record_decision(
+        &mut self,
+        request: &SafetyCheckRequest,
+        allowed: bool,
+        rules: Vec<RuleEvaluation>,
+    ) -> DecisionTrace {
+        let trace = DecisionTrace::new(
+            &request.wallet_address,
+            &request.input_symbol,
+            &request.output_symbol,
+            request.amount_usd,
+            allowed,
+            rules,
+        );
+        self.decision_traces.record(trace.clone());
+        trace
+    }
+
+    pub fn recent_decision_traces(
+        &self,
+        wallet_address: Option<&str>,
+        blocked_only: bool,
+        limit: usize,
+    ) -> Vec<DecisionTrace> {
+        self.decision_traces
+            .recent(wallet_address, blocked_only, limit)
+    }
+
+    pub fn get_decision_trace(&self, id: &str) -> Option<DecisionTrace> {
+        self.decision_traces.get(id)
+    }
+
     pub fn approve_trade(&mut self, wallet_address: &str) {
         if self.get_policy().cooldown_enabled {
             self.cooldown_manager.record_trade(wallet_address);
@@ -247,6 +327,78 @@ impl SafetyEngine {
     }
 }
 
+/// Folds Token-2022 extension risks into the policy result: non-transferable
+/// outputs block the trade, hooks and permanent delegates surface as
+/// overridable violations, everything else as warnings.
+fn apply_extension_checks(
+    policy_result: &mut PolicyCheckResult,
+    rules: &mut Vec<RuleEvaluation>,
+    request: &SafetyCheckRequest,
+) {
+    let sides = [
+        (
+            &request.input_extensions,
+            &request.input_symbol,
+            &request.input_mint,
+        ),
+        (
+            &request.output_extensions,
+            &request.output_symbol,
+            &request.output_mint,
+        ),
+    ];
+    for (report, symbol, mint) in sides {
+        let Some(report) = report else {
+            rules.push(RuleEvaluation::skipped(
+                "token_extensions",
+                json!({ "symbol": symbol, "mint": mint }),
+                format!("No extension data for {}", symbol),
+            ));
+            continue;
+        };
+        if report.risks.is_empty() {
+            rules.push(RuleEvaluation::passed(
+                "token_extensions",
+                json!({ "symbol": symbol, "mint": mint }),
+            ));
+        }
+        for risk in &report.risks {
+            let message = format!("{}: {}", symbol, risk.description);
+            let inputs = json!({
+                "symbol": symbol,
+                "mint": mint,
+                "extension": risk.extension,
+                "severity": risk.severity,
+            });
+            let violation = match risk.severity {
+                ExtensionRiskSeverity::Critical => PolicyViolation {
+                    rule: format!("token_extension_{}", risk.extension),
+                    message,
+                    severity: ViolationSeverity::Critical,
+                    can_override: false,
+                },
+                ExtensionRiskSeverity::High => PolicyViolation {
+                    rule: format!("token_extension_{}", risk.extension),
+                    message,
+                    severity: ViolationSeverity::Warning,
+                    can_override: true,
+                },
+                _ => {
+                    rules.push(RuleEvaluation::warning(
+                        format!("token_extension_{}", risk.extension),
+                        inputs,
+                        &message,
+                    ));
+                    policy_result.add_warning(message);
+                    continue;
+                }
+            };
+            rules.push(RuleEvaluation::violated(&violation, inputs));
+            policy_result.add_violation(violation);
+        }
+    }
+}
+
 pub type SharedSafetyEngine = Arc<RwLock<SafetyEngine>>;
 
 #[cfg(test)]
@@ -269,6 +421,8 @@ mod tests {
             slippage_bps: 50,
             price_impact_percent: 1.5,
             security_score: Some(85.0),
+            input_extensions: None,
+            output_extensions: None,
         };
 
         let result = engine.check_trade_safety(request).await;
@@ -293,6 +447,8 @@ mod tests {
             slippage_bps: 50,
             price_impact_percent: 1.5,
             security_score: Some(85.0),
+            input_extensions: None,
+            output_extensions: None,
         };
 
         // First trade allowed
@@ -322,6 +478,8 @@ mod tests {
             slippage_bps: 50,
             price_impact_percent: 1.5,
             security_score: Some(30.0),
+            input_extensions: None,
+            output_extensions: None,
         };
 
         let result = engine.check_trade_safety(request).await;
@@ -329,5 +487,21 @@ mod tests {
         let check = result.unwrap();
         assert!(!check.allowed);
         assert!(!check.policy_result.violations.is_empty());
+
+        let trace = &check.decision_trace;
+        assert!(!trace.allowed);
+        assert!(trace.summary.contains("high_risk_token"));
+        let failed: Vec<_> = trace
+            .rules
+            .iter()
+            .filter(|r| r.outcome == RuleOutcome::Failed)
+            .map(|r| r.rule.as_str())
+            .collect();
+        assert_eq!(failed, vec!["high_risk_token"]);
+        assert!(trace.rules.iter().any(|r| r.rule == "cooldown"));
+        assert_eq!(
+            engine.get_decision_trace(&trace.id).map(|t| t.allowed),
+            Some(false)
+        );
     }
 }
@@ -1,4 +1,6 @@
+use super::trace::RuleEvaluation;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -174,16 +176,42 @@ impl PolicyEngine {
         slippage: f64,
         risk_score: Option<f64>,
     ) -> PolicyCheckResult {
-        if !self.policy.enabled {
-            return PolicyCheckResult::new_allowed();
-        }
+        self.evaluate_trade_policy(
+            wallet_address,
+            amount_usd,
+            price_impact,
+            slippage,
+            risk_score,
+        )
+        .0
+    }
 
+    /// Runs the same checks as `check_trade_policy` and also returns how
+    /// each rule was evaluated, including the ones that passed.
+    pub fn evaluate_trade_policy(
+        &mut self,
+        wallet_address: &str,
+        amount_usd: f64,
+        price_impact: f64,
+        slippage: f64,
+        risk_score: Option<f64>,
+    ) -> (PolicyCheckResult, Vec<RuleEvaluation>) {
         let mut result = PolicyCheckResult::new_allowed();
+        let mut rules = Vec::new();
+
+        if !self.policy.enabled {
+            rules.push(RuleEvaluation::skipped(
+                "safety_policy",
+                json!({ "enabled": false }),
+                "Safety policy is disabled",
+            ));
+            return (result, rules);
+        }
 
         // Check trade amount limit
-        if let Some(max_amount) = self.policy.max_trade_amount_usd {
-            if amount_usd > max_amount {
-                result.add_violation(PolicyViolation {
+        match self.policy.max_trade_amount_usd {
+            Some(max_amount) => {
+                let violation = (amount_usd > max_amount).then(|| PolicyViolation {
                     rule: "max_trade_amount".to_string(),
                     message: format!(
                         "Trade amount ${:.2} exceeds maximum allowed ${:.2}",
@@ -192,18 +220,30 @@ impl PolicyEngine {
                     severity: ViolationSeverity::Error,
                     can_override: true,
                 });
+                record_rule(
+                    &mut result,
+                    &mut rules,
+                    "max_trade_amount",
+                    json!({ "amount_usd": amount_usd, "max_trade_amount_usd": max_amount }),
+                    violation,
+                );
             }
+            None => rules.push(RuleEvaluation::skipped(
+                "max_trade_amount",
+                json!({ "amount_usd": amount_usd }),
+                "No trade amount limit configured",
+            )),
         }
 
         // Check daily trade limit
-        if let Some(max_daily) = self.policy.max_daily_trades {
-            let count = self
-                .daily_trade_counts
-                .get(wallet_address)
-                .copied()
-                .unwrap_or(0);
-            if count >= max_daily {
-                result.add_violation(PolicyViolation {
+        let count = self
+            .daily_trade_counts
+            .get(wallet_address)
+            .copied()
+            .unwrap_or(0);
+        match self.policy.max_daily_trades {
+            Some(max_daily) => {
+                let violation = (count >= max_daily).then(|| PolicyViolation {
                     rule: "max_daily_trades".to_string(),
                     message: format!(
                         "Daily trade limit reached: {} of {} trades",
@@ -212,66 +252,118 @@ impl PolicyEngine {
                     severity: ViolationSeverity::Error,
                     can_override: false,
                 });
+                record_rule(
+                    &mut result,
+                    &mut rules,
+                    "max_daily_trades",
+                    json!({ "trades_today": count, "max_daily_trades": max_daily }),
+                    violation,
+                );
             }
+            None => rules.push(RuleEvaluation::skipped(
+                "max_daily_trades",
+                json!({ "trades_today": count }),
+                "No daily trade limit configured",
+            )),
         }
 
         // Check price impact
-        if price_impact > self.policy.max_price_impact_percent {
-            result.add_violation(PolicyViolation {
-                rule: "max_price_impact".to_string(),
-                message: format!(
-                    "Price impact {:.2}% exceeds maximum allowed {:.2}%",
-                    price_impact, self.policy.max_price_impact_percent
-                ),
-                severity: if price_impact > self.policy.max_price_impact_percent * 1.5 {
-                    ViolationSeverity::Critical
-                } else {
-                    ViolationSeverity::Warning
-                },
-                can_override: price_impact <= self.policy.max_price_impact_percent * 2.0,
-            });
-        }
+        let max_impact = self.policy.max_price_impact_percent;
+        let violation = (price_impact > max_impact).then(|| PolicyViolation {
+            rule: "max_price_impact".to_string(),
+            message: format!(
+                "Price impact {:.2}% exceeds maximum allowed {:.2}%",
+                price_impact, max_impact
+            ),
+            severity: if price_impact > max_impact * 1.5 {
+                ViolationSeverity::Critical
+            } else {
+                ViolationSeverity::Warning
+            },
+            can_override: price_impact <= max_impact * 2.0,
+        });
+        record_rule(
+            &mut result,
+            &mut rules,
+            "max_price_impact",
+            json!({
+                "price_impact_percent": price_impact,
+                "max_price_impact_percent": max_impact,
+            }),
+            violation,
+        );
 
         // Check slippage
-        if slippage > self.policy.max_slippage_percent {
-            result.add_violation(PolicyViolation {
-                rule: "max_slippage".to_string(),
-                message: format!(
-                    "Slippage {:.2}% exceeds maximum allowed {:.2}%",
-                    slippage, self.policy.max_slippage_percent
-                ),
-                severity: ViolationSeverity::Warning,
-                can_override: true,
-            });
-        }
+        let max_slippage = self.policy.max_slippage_percent;
+        let violation = (slippage > max_slippage).then(|| PolicyViolation {
+            rule: "max_slippage".to_string(),
+            message: format!(
+                "Slippage {:.2}% exceeds maximum allowed {:.2}%",
+                slippage, max_slippage
+            ),
+            severity: ViolationSeverity::Warning,
+            can_override: true,
+        });
+        record_rule(
+            &mut result,
+            &mut rules,
+            "max_slippage",
+            json!({ "slippage_percent": slippage, "max_slippage_percent": max_slippage }),
+            violation,
+        );
 
         // Check risk score
-        if let Some(score) = risk_score {
-            if self.policy.block_high_risk && score < self.policy.high_risk_threshold {
-                result.add_violation(PolicyViolation {
+        let threshold = self.policy.high_risk_threshold;
+        match risk_score {
+            Some(score) if self.policy.block_high_risk => {
+                let violation = (score < threshold).then(|| PolicyViolation {
                     rule: "high_risk_token".to_string(),
                     message: format!(
                         "Token security score {} is below threshold {}",
-                        score, self.policy.high_risk_threshold
+                        score, threshold
                     ),
                     severity: ViolationSeverity::Critical,
                     can_override: true,
                 });
+                record_rule(
+                    &mut result,
+                    &mut rules,
+                    "high_risk_token",
+                    json!({ "security_score": score, "high_risk_threshold": threshold }),
+                    violation,
+                );
             }
+            Some(score) => rules.push(RuleEvaluation::skipped(
+                "high_risk_token",
+                json!({ "security_score": score, "high_risk_threshold": threshold }),
+                "High-risk blocking is disabled",
+            )),
+            None => rules.push(RuleEvaluation::skipped(
+                "high_risk_token",
+                json!({ "security_score": null, "high_risk_threshold": threshold }),
+                "No security score available for this token",
+            )),
         }
 
         // Check if insurance is required
         if let Some(threshold) = self.policy.require_insurance_above_usd {
+            let inputs =
+                json!({ "amount_usd": amount_usd, "require_insurance_above_usd": threshold });
             if amount_usd > threshold {
+                let message = format!("Insurance recommended for trades above ${:.2}", threshold);
                 result.requires_insurance = true;
-                result.add_warning(format!(
-                    "Insurance recommended for trades above ${:.2}",
-                    threshold
+                rules.push(RuleEvaluation::warning(
+                    "insurance_required",
+                    inputs,
+                    &message,
                 ));
+                result.add_warning(message);
+            } else {
+                rules.push(RuleEvaluation::passed("insurance_required", inputs));
             }
         }
 
-        result
+        (result, rules)
     }
 
     pub fn increment_daily_trade_count(&mut self, wallet_address: &str) {
@@ -294,9 +386,27 @@ impl PolicyEngine {
     }
 }
 
+/// Applies a rule's violation, if any, and records the evaluation.
+fn record_rule(
+    result: &mut PolicyCheckResult,
+    rules: &mut Vec<RuleEvaluation>,
+    rule: &str,
+    inputs: serde_json::Value,
+    violation: Option<PolicyViolation>,
+) {
+    match violation {
+        Some(violation) => {
+            rules.push(RuleEvaluation::violated(&violation, inputs));
+            result.add_violation(violation);
+        }
+        None => rules.push(RuleEvaluation::passed(rule, inputs)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::trading::safety::trace::RuleOutcome;
 
     #[test]
     fn test_default_policy() {
@@ -354,4 +464,19 @@ mod tests {
         assert!(result.requires_insurance);
         assert!(!result.warnings.is_empty());
     }
+
+    #[test]
+    fn test_evaluation_lists_every_rule() {
+        let mut engine = PolicyEngine::new(SafetyPolicy::default());
+        let (result, rules) = engine.evaluate_trade_policy("wallet1", 15000.0, 1.0, 0.5, None);
+        assert!(!result.allowed);
+
+        let outcome = |name: &str| rules.iter().find(|r| r.rule == name).map(|r| r.outcome);
+        assert_eq!(outcome("max_trade_amount"), Some(RuleOutcome::Failed));
+        assert_eq!(outcome("max_daily_trades"), Some(RuleOutcome::Passed));
+        assert_eq!(outcome("max_price_impact"), Some(RuleOutcome::Passed));
+        assert_eq!(outcome("high_risk_token"), Some(RuleOutcome::Skipped));
+        let amount = rules.iter().find(|r| r.rule == "max_trade_amount").unwrap();
+        assert_eq!(amount.inputs["amount_usd"], 15000.0);
+    }
 }
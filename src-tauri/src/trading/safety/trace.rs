@@ -0,0 +1,242 @@
+use super::policy::{PolicyViolation, ViolationSeverity};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+
+/// How many decisions are kept for review before the oldest are dropped.
+const DEFAULT_TRACE_CAPACITY: usize = 500;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleOutcome {
+    Passed,
+    /// The rule flagged the trade without blocking it.
+    Warning,
+    Failed,
+    /// The rule is disabled or had nothing to evaluate.
+    Skipped,
+}
+
+/// One rule evaluated during a safety check, with the values it looked at.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleEvaluation {
+    pub rule: String,
+    pub inputs: Value,
+    pub outcome: RuleOutcome,
+    pub severity: Option<ViolationSeverity>,
+    pub message: Option<String>,
+}
+
+impl RuleEvaluation {
+    pub fn passed(rule: impl Into<String>, inputs: Value) -> Self {
+        Self {
+            rule: rule.into(),
+            inputs,
+            outcome: RuleOutcome::Passed,
+            severity: None,
+            message: None,
+        }
+    }
+
+    pub fn warning(rule: impl Into<String>, inputs: Value, message: impl Into<String>) -> Self {
+        Self {
+            rule: rule.into(),
+            inputs,
+            outcome: RuleOutcome::Warning,
+            severity: Some(ViolationSeverity::Warning),
+            message: Some(message.into()),
+        }
+    }
+
+    pub fn skipped(rule: impl Into<String>, inputs: Value, reason: impl Into<String>) -> Self {
+        Self {
+            rule: rule.into(),
+            inputs,
+            outcome: RuleOutcome::Skipped,
+            severity: None,
+            message: Some(reason.into()),
+        }
+    }
+
+    /// Records a violation; warning-level violations do not block the trade,
+    /// matching `PolicyCheckResult::add_violation`.
+    pub fn violated(violation: &PolicyViolation, inputs: Value) -> Self {
+        let outcome = match violation.severity {
+            ViolationSeverity::Warning => RuleOutcome::Warning,
+            ViolationSeverity::Error | ViolationSeverity::Critical => RuleOutcome::Failed,
+        };
+        Self {
+            rule: violation.rule.clone(),
+            inputs,
+            outcome,
+            severity: Some(violation.severity),
+            message: Some(violation.message.clone()),
+        }
+    }
+
+    /// A blocking rule that is not a policy violation, such as an active
+    /// cooldown.
+    pub fn failed(rule: impl Into<String>, inputs: Value, message: impl Into<String>) -> Self {
+        Self {
+            rule: rule.into(),
+            inputs,
+            outcome: RuleOutcome::Failed,
+            severity: Some(ViolationSeverity::Error),
+            message: Some(message.into()),
+        }
+    }
+}
+
+/// The full reasoning behind one `check_trade_safety` decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionTrace {
+    pub id: String,
+    pub evaluated_at: DateTime<Utc>,
+    pub wallet_address: String,
+    pub input_symbol: String,
+    pub output_symbol: String,
+    pub amount_usd: f64,
+    pub allowed: bool,
+    pub rules: Vec<RuleEvaluation>,
+    /// Human-readable explanation naming the rules that decided the outcome.
+    pub summary: String,
+}
+
+impl DecisionTrace {
+    pub fn new(
+        wallet_address: &str,
+        input_symbol: &str,
+        output_symbol: &str,
+        amount_usd: f64,
+        allowed: bool,
+        rules: Vec<RuleEvaluation>,
+    ) -> Self {
+        let summary = summarize(allowed, &rules);
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            evaluated_at: Utc::now(),
+            wallet_address: wallet_address.to_string(),
+            input_symbol: input_symbol.to_string(),
+            output_symbol: output_symbol.to_string(),
+            amount_usd,
+            allowed,
+            rules,
+            summary,
+        }
+    }
+}
+
+fn summarize(allowed: bool, rules: &[RuleEvaluation]) -> String {
+    let count = |outcome: RuleOutcome| rules.iter().filter(|r| r.outcome == outcome).count();
+    if allowed {
+        let warnings = count(RuleOutcome::Warning);
+        return match warnings {
+            0 => format!("Allowed: {} rules passed", count(RuleOutcome::Passed)),
+            n => format!(
+                "Allowed with {} warning{}: {} rules passed",
+                n,
+                if n == 1 { "" } else { "s" },
+                count(RuleOutcome::Passed)
+            ),
+        };
+    }
+
+    let reasons: Vec<String> = rules
+        .iter()
+        .filter(|r| r.outcome == RuleOutcome::Failed)
+        .map(|r| match &r.message {
+            Some(message) => format!("{} ({})", r.rule, message),
+            None => r.rule.clone(),
+        })
+        .collect();
+    format!("Blocked by {}", reasons.join("; "))
+}
+
+/// Bounded in-memory history of recent decisions, newest last.
+#[derive(Debug)]
+pub struct DecisionTraceLog {
+    traces: VecDeque<DecisionTrace>,
+    capacity: usize,
+}
+
+impl Default for DecisionTraceLog {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_TRACE_CAPACITY)
+    }
+}
+
+impl DecisionTraceLog {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            traces: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn record(&mut self, trace: DecisionTrace) {
+        if self.traces.len() >= self.capacity {
+            self.traces.pop_front();
+        }
+        self.traces.push_back(trace);
+    }
+
+    /// Newest first, optionally only for one wallet or only blocked trades.
+    pub fn recent(
+        &self,
+        wallet_address: Option<&str>,
+        blocked_only: bool,
+        limit: usize,
+    ) -> Vec<DecisionTrace> {
+        self.traces
+            .iter()
+            .rev()
+            .filter(|t| wallet_address.map_or(true, |wallet| t.wallet_address == wallet))
+            .filter(|t| !blocked_only || !t.allowed)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    pub fn get(&self, id: &str) -> Option<DecisionTrace> {
+        self.traces.iter().find(|t| t.id == id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_trace_log_is_bounded_and_newest_first() {
+        let mut log = DecisionTraceLog::with_capacity(2);
+        for (wallet, allowed) in [("a", true), ("b", false), ("a", false)] {
+            log.record(DecisionTrace::new(
+                wallet,
+                "SOL",
+                "USDC",
+                10.0,
+                allowed,
+                vec![],
+            ));
+        }
+
+        let all = log.recent(None, false, 10);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].wallet_address, "a");
+        assert_eq!(all[1].wallet_address, "b");
+        assert_eq!(log.recent(Some("a"), true, 10).len(), 1);
+        assert!(log.get(&all[1].id).is_some());
+    }
+
+    #[test]
+    fn test_summary_names_blocking_rules() {
+        let rules = vec![
+            RuleEvaluation::passed("max_slippage", json!({})),
+            RuleEvaluation::failed("cooldown", json!({}), "12s remaining"),
+        ];
+        let trace = DecisionTrace::new("a", "SOL", "USDC", 10.0, false, rules);
+        assert_eq!(trace.summary, "Blocked by cooldown (12s remaining)");
+    }
+}
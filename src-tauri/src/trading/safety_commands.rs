@@ -1,6 +1,8 @@
+use crate::errors::{AppError, CommandResultExt};
+use crate::token_extensions::{SharedTokenExtensionService, TokenExtensionReport};
 use crate::trading::safety::policy::SafetyPolicy;
 use crate::trading::safety::{
-    InsuranceProvider, SafetyCheckRequest, SafetyCheckResult, SharedSafetyEngine,
+    DecisionTrace, InsuranceProvider, SafetyCheckRequest, SafetyCheckResult, SharedSafetyEngine,
 };
 use serde::{Deserialize, Serialize};
 use tauri::State;
@@ -12,18 +14,66 @@ pub struct SafetyConfigUpdate {
 
 #[tauri::command]
 pub async fn check_trade_safety(
-    request: SafetyCheckRequest,
+    mut request: SafetyCheckRequest,
     safety_engine: State<'_, SharedSafetyEngine>,
-) -> Result<SafetyCheckResult, String> {
+    extensions: State<'_, SharedTokenExtensionService>,
+) -> Result<SafetyCheckResult, AppError> {
+    {
+        let mut extensions = extensions.write().await;
+        let epoch = extensions.current_epoch().await;
+        if request.input_extensions.is_none() {
+            request.input_extensions = extensions
+                .try_get_extensions(&request.input_mint)
+                .await
+                .map(|ext| TokenExtensionReport::new(ext, epoch));
+        }
+        if request.output_extensions.is_none() {
+            request.output_extensions = extensions
+                .try_get_extensions(&request.output_mint)
+                .await
+                .map(|ext| TokenExtensionReport::new(ext, epoch));
+        }
+    }
+
     let mut engine = safety_engine.write().await;
-    engine.check_trade_safety(request).await
+    engine
+        .check_trade_safety(request)
+        .await
+        .logged("check_trade_safety")
+}
+
+#[tauri::command]
+pub async fn get_safety_decision_traces(
+    wallet_address: Option<String>,
+    blocked_only: Option<bool>,
+    limit: Option<usize>,
+    safety_engine: State<'_, SharedSafetyEngine>,
+) -> Result<Vec<DecisionTrace>, AppError> {
+    let engine = safety_engine.read().await;
+    Ok(engine.recent_decision_traces(
+        wallet_address.as_deref(),
+        blocked_only.unwrap_or(false),
+        limit.unwrap_or(50),
+    ))
+}
+
+#[tauri::command]
+pub async fn get_safety_decision_trace(
+    trace_id: String,
+    safety_engine: State<'_, SharedSafetyEngine>,
+) -> Result<DecisionTrace, AppError> {
+    let engine = safety_engine.read().await;
+    engine
+        .get_decision_trace(&trace_id)
+        .ok_or_else(|| AppError::NotFound(format!("Decision trace {} not found", trace_id)))
+        .logged("get_safety_decision_trace")
 }
 
 #[tauri::command]
 pub async fn approve_trade(
     wallet_address: String,
     safety_engine: State<'_, SharedSafetyEngine>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let mut engine = safety_engine.write().await;
     engine.approve_trade(&wallet_address);
     Ok(())
@@ -32,7 +82,7 @@ pub async fn approve_trade(
 #[tauri::command]
 pub async fn get_safety_policy(
     safety_engine: State<'_, SharedSafetyEngine>,
-) -> Result<SafetyPolicy, String> {
+) -> Result<SafetyPolicy, AppError> {
     let engine = safety_engine.read().await;
     Ok(engine.get_policy().clone())
 }
@@ -41,7 +91,7 @@ pub async fn get_safety_policy(
 pub async fn update_safety_policy(
     policy: SafetyPolicy,
     safety_engine: State<'_, SharedSafetyEngine>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let mut engine = safety_engine.write().await;
     engine.update_policy(policy);
     Ok(())
@@ -51,7 +101,7 @@ pub async fn update_safety_policy(
 pub async fn get_cooldown_status(
     wallet_address: String,
     safety_engine: State<'_, SharedSafetyEngine>,
-) -> Result<Option<crate::trading::safety::cooldown::CooldownStatus>, String> {
+) -> Result<Option<crate::trading::safety::cooldown::CooldownStatus>, AppError> {
     let engine = safety_engine.read().await;
     Ok(engine.get_cooldown_status(&wallet_address))
 }
@@ -59,7 +109,7 @@ pub async fn get_cooldown_status(
 #[tauri::command]
 pub async fn reset_daily_limits(
     safety_engine: State<'_, SharedSafetyEngine>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let mut engine = safety_engine.write().await;
     engine.reset_daily_limits();
     Ok(())
@@ -72,14 +122,16 @@ pub async fn get_insurance_quote(
     price_impact_percent: f64,
     mev_risk_level: f64,
     safety_engine: State<'_, SharedSafetyEngine>,
-) -> Result<crate::trading::safety::insurance::InsuranceQuote, String> {
+) -> Result<crate::trading::safety::insurance::InsuranceQuote, AppError> {
     let mut engine = safety_engine.write().await;
-    engine.get_insurance_quote(
-        &provider_id,
-        trade_amount_usd,
-        price_impact_percent,
-        mev_risk_level,
-    )
+    engine
+        .get_insurance_quote(
+            &provider_id,
+            trade_amount_usd,
+            price_impact_percent,
+            mev_risk_level,
+        )
+        .logged("get_insurance_quote")
 }
 
 #[tauri::command]
@@ -89,20 +141,22 @@ pub async fn select_insurance(
     price_impact_percent: f64,
     mev_risk_level: f64,
     safety_engine: State<'_, SharedSafetyEngine>,
-) -> Result<crate::trading::safety::insurance::InsuranceSelection, String> {
+) -> Result<crate::trading::safety::insurance::InsuranceSelection, AppError> {
     let mut engine = safety_engine.write().await;
-    engine.select_insurance(
-        &provider_id,
-        trade_amount_usd,
-        price_impact_percent,
-        mev_risk_level,
-    )
+    engine
+        .select_insurance(
+            &provider_id,
+            trade_amount_usd,
+            price_impact_percent,
+            mev_risk_level,
+        )
+        .logged("select_insurance")
 }
 
 #[tauri::command]
 pub async fn list_insurance_providers(
     safety_engine: State<'_, SharedSafetyEngine>,
-) -> Result<Vec<InsuranceProvider>, String> {
+) -> Result<Vec<InsuranceProvider>, AppError> {
     let engine = safety_engine.read().await;
     Ok(engine.list_insurance_providers())
 }
@@ -110,7 +164,7 @@ pub async fn list_insurance_providers(
 #[tauri::command]
 pub async fn get_emergency_halt(
     safety_engine: State<'_, SharedSafetyEngine>,
-) -> Result<bool, String> {
+) -> Result<bool, AppError> {
     let engine = safety_engine.read().await;
     Ok(engine.is_emergency_halt())
 }
@@ -119,7 +173,7 @@ pub async fn get_emergency_halt(
 pub async fn set_emergency_halt(
     enabled: bool,
     safety_engine: State<'_, SharedSafetyEngine>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let mut engine = safety_engine.write().await;
     engine.set_emergency_halt(enabled);
     Ok(())
@@ -0,0 +1,513 @@
+//! Intraday session metrics for the heads-up display and tray tooltip.
+//!
+//! Fills from the order manager open and close session positions with
+//! average-cost accounting, and price ticks mark them to market. Every
+//! change is published as one snapshot on `SESSION_HUD_EVENT`; the tray
+//! listens to the same event for its tooltip. A session runs for the UTC
+//! day unless it is reset by hand.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Listener, Manager, State};
+use tokio::sync::RwLock;
+
+use crate::errors::{AppError, CommandResultExt};
+use crate::profiles::ProfilePaths;
+use crate::trading::types::{Order, OrderSide};
+use crate::tray::SharedTrayManager;
+
+const SESSION_FILE: &str = "session_hud.json";
+pub const SESSION_HUD_EVENT: &str = "session_hud_update";
+/// Price ticks publish at most this often; fills always publish.
+const TICK_EMIT_INTERVAL: Duration = Duration::from_secs(1);
+const STABLE_SYMBOLS: &[&str] = &["USDC", "USDT", "USD", "PYUSD", "USDH"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPosition {
+    pub symbol: String,
+    /// Signed: negative when more was sold than bought this session.
+    pub quantity: f64,
+    pub avg_price: f64,
+    pub last_price: f64,
+    pub realized_pnl: f64,
+}
+
+impl SessionPosition {
+    pub fn unrealized_pnl(&self) -> f64 {
+        self.quantity * (self.last_price - self.avg_price)
+    }
+
+    /// Applies a fill and returns the P&L it realized, if it closed any of
+    /// the position.
+    fn apply_fill(&mut self, signed_quantity: f64, price: f64) -> Option<f64> {
+        self.last_price = price;
+        if signed_quantity == 0.0 {
+            return None;
+        }
+        let opening = self.quantity == 0.0 || self.quantity.signum() == signed_quantity.signum();
+        if opening {
+            let held = self.quantity.abs();
+            let added = signed_quantity.abs();
+            self.avg_price = (held * self.avg_price + added * price) / (held + added);
+            self.quantity += signed_quantity;
+            return None;
+        }
+
+        let closed = signed_quantity.abs().min(self.quantity.abs());
+        let realized = closed * (price - self.avg_price) * self.quantity.signum();
+        let flips = signed_quantity.abs() > self.quantity.abs();
+        self.quantity += signed_quantity;
+        self.realized_pnl += realized;
+        if flips {
+            self.avg_price = price;
+        } else if self.quantity.abs() < f64::EPSILON {
+            self.quantity = 0.0;
+            self.avg_price = 0.0;
+        }
+        Some(realized)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionHudSettings {
+    /// Session loss the user is prepared to take; drawdown is reported as a
+    /// share of it.
+    pub risk_budget_usd: f64,
+}
+
+impl Default for SessionHudSettings {
+    fn default() -> Self {
+        Self {
+            risk_budget_usd: 1_000.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionHudSnapshot {
+    pub session_started_at: DateTime<Utc>,
+    pub session_pnl: f64,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    pub gross_exposure: f64,
+    pub net_exposure: f64,
+    pub trade_count: u32,
+    /// Share of closing trades that realized a gain; `None` before the first
+    /// close.
+    pub win_rate: Option<f64>,
+    pub risk_budget_usd: f64,
+    pub risk_budget_used_pct: f64,
+    pub positions: Vec<SessionPosition>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SessionHudSnapshot {
+    pub fn tooltip_line(&self) -> String {
+        let win_rate = self
+            .win_rate
+            .map(|rate| format!("{:.0}%", rate))
+            .unwrap_or_else(|| "-".to_string());
+        format!(
+            "Session {:+.2} USD | {} trades | win {} | risk {:.0}%",
+            self.session_pnl, self.trade_count, win_rate, self.risk_budget_used_pct
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionState {
+    started_at: DateTime<Utc>,
+    day: NaiveDate,
+    positions: HashMap<String, SessionPosition>,
+    trade_count: u32,
+    closing_trades: u32,
+    winning_trades: u32,
+}
+
+impl SessionState {
+    fn starting(now: DateTime<Utc>) -> Self {
+        Self {
+            started_at: now,
+            day: now.date_naive(),
+            positions: HashMap::new(),
+            trade_count: 0,
+            closing_trades: 0,
+            winning_trades: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredSession {
+    #[serde(default)]
+    settings: SessionHudSettings,
+    session: SessionState,
+}
+
+pub struct SessionHud {
+    settings: SessionHudSettings,
+    session: SessionState,
+    /// Latest USD price per symbol, also used to value the quote side of
+    /// buys.
+    prices: HashMap<String, f64>,
+    last_emit: Option<Instant>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedSessionHud = Arc<RwLock<SessionHud>>;
+
+impl SessionHud {
+    pub fn new(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(SESSION_FILE));
+        let stored: Option<StoredSession> = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok());
+        let (settings, session) = match stored {
+            Some(stored) => (stored.settings, stored.session),
+            None => (
+                SessionHudSettings::default(),
+                SessionState::starting(Utc::now()),
+            ),
+        };
+        Self {
+            settings,
+            session,
+            prices: HashMap::new(),
+            last_emit: None,
+            path,
+        }
+    }
+
+    fn roll_over(&mut self, now: DateTime<Utc>) {
+        if self.session.day != now.date_naive() {
+            self.session = SessionState::starting(now);
+        }
+    }
+
+    pub fn reset(&mut self, now: DateTime<Utc>) {
+        self.session = SessionState::starting(now);
+        self.save();
+    }
+
+    pub fn set_risk_budget(&mut self, risk_budget_usd: f64) {
+        self.settings.risk_budget_usd = risk_budget_usd;
+        self.save();
+    }
+
+    fn usd_price(&self, symbol: &str) -> Option<f64> {
+        if STABLE_SYMBOLS.contains(&symbol.to_uppercase().as_str()) {
+            return Some(1.0);
+        }
+        self.prices.get(symbol).copied()
+    }
+
+    /// Records a filled order at `fill_price`, the USD price of the traded
+    /// asset. Buys spend `amount` of the input token, so their size is
+    /// converted through the input token's price; sells spend the asset
+    /// itself.
+    pub fn record_fill(
+        &mut self,
+        order: &Order,
+        fill_price: f64,
+        now: DateTime<Utc>,
+    ) -> Result<(), String> {
+        if fill_price <= 0.0 {
+            return Err("Fill price must be positive".into());
+        }
+        self.roll_over(now);
+        let (symbol, signed_quantity) = match order.side {
+            OrderSide::Buy => {
+                let quote_usd = self.usd_price(&order.input_symbol).ok_or_else(|| {
+                    format!("No price for {} to size the fill", order.input_symbol)
+                })?;
+                (
+                    order.output_symbol.clone(),
+                    order.filled_amount * quote_usd / fill_price,
+                )
+            }
+            OrderSide::Sell => (order.input_symbol.clone(), -order.filled_amount),
+        };
+        self.prices.insert(symbol.clone(), fill_price);
+
+        let position = self
+            .session
+            .positions
+            .entry(symbol.clone())
+            .or_insert_with(|| SessionPosition {
+                symbol,
+                ..SessionPosition::default()
+            });
+        let realized = position.apply_fill(signed_quantity, fill_price);
+        self.session.trade_count += 1;
+        if let Some(realized) = realized {
+            self.session.closing_trades += 1;
+            if realized > 0.0 {
+                self.session.winning_trades += 1;
+            }
+        }
+        self.save();
+        Ok(())
+    }
+
+    /// Marks positions in `symbol` to `price`. Returns whether a snapshot
+    /// is due.
+    pub fn record_price(&mut self, symbol: &str, price: f64, now: DateTime<Utc>) -> bool {
+        self.prices.insert(symbol.to_string(), price);
+        let Some(position) = self.session.positions.get_mut(symbol) else {
+            return false;
+        };
+        position.last_price = price;
+        self.roll_over(now);
+        self.last_emit
+            .map_or(true, |last| last.elapsed() >= TICK_EMIT_INTERVAL)
+    }
+
+    pub fn snapshot(&self, now: DateTime<Utc>) -> SessionHudSnapshot {
+        let mut positions: Vec<SessionPosition> =
+            self.session.positions.values().cloned().collect();
+        positions.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        let realized_pnl: f64 = positions.iter().map(|p| p.realized_pnl).sum();
+        let unrealized_pnl: f64 = positions.iter().map(SessionPosition::unrealized_pnl).sum();
+        let session_pnl = realized_pnl + unrealized_pnl;
+        let risk_budget_used_pct = if self.settings.risk_budget_usd > 0.0 {
+            (-session_pnl).max(0.0) / self.settings.risk_budget_usd * 100.0
+        } else {
+            0.0
+        };
+        SessionHudSnapshot {
+            session_started_at: self.session.started_at,
+            session_pnl,
+            realized_pnl,
+            unrealized_pnl,
+            gross_exposure: positions
+                .iter()
+                .map(|p| p.quantity.abs() * p.last_price)
+                .sum(),
+            net_exposure: positions.iter().map(|p| p.quantity * p.last_price).sum(),
+            trade_count: self.session.trade_count,
+            win_rate: (self.session.closing_trades > 0).then(|| {
+                self.session.winning_trades as f64 / self.session.closing_trades as f64 * 100.0
+            }),
+            risk_budget_usd: self.settings.risk_budget_usd,
+            risk_budget_used_pct,
+            positions,
+            updated_at: now,
+        }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let stored = StoredSession {
+            settings: self.settings.clone(),
+            session: self.session.clone(),
+        };
+        match serde_json::to_string_pretty(&stored) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(path, contents) {
+                    eprintln!("Failed to save session HUD: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize session HUD: {}", e),
+        }
+    }
+}
+
+async fn publish(app: &AppHandle, hud: &SharedSessionHud) {
+    let snapshot = {
+        let mut hud = hud.write().await;
+        hud.last_emit = Some(Instant::now());
+        hud.snapshot(Utc::now())
+    };
+    let _ = app.emit(SESSION_HUD_EVENT, &snapshot);
+}
+
+/// Called by the order manager once an order has filled.
+pub async fn session_record_fill(app: &AppHandle, order: &Order, fill_price: f64) {
+    let Some(hud) = app
+        .try_state::<SharedSessionHud>()
+        .map(|state| state.inner().clone())
+    else {
+        return;
+    };
+    let recorded = hud.write().await.record_fill(order, fill_price, Utc::now());
+    match recorded {
+        Ok(()) => publish(app, &hud).await,
+        Err(e) => eprintln!("Session HUD skipped fill {}: {}", order.id, e),
+    }
+}
+
+/// Called for every price the order manager receives.
+pub async fn session_record_price(app: &AppHandle, symbol: &str, price: f64) {
+    let Some(hud) = app
+        .try_state::<SharedSessionHud>()
+        .map(|state| state.inner().clone())
+    else {
+        return;
+    };
+    let due = hud.write().await.record_price(symbol, price, Utc::now());
+    if due {
+        publish(app, &hud).await;
+    }
+}
+
+pub fn register_session_hud_state(app: &tauri::App) {
+    let hud: SharedSessionHud = Arc::new(RwLock::new(SessionHud::new(app.handle())));
+    app.manage(hud);
+
+    let handle = app.handle().clone();
+    app.listen(SESSION_HUD_EVENT, move |event| {
+        let Ok(snapshot) = serde_json::from_str::<SessionHudSnapshot>(event.payload()) else {
+            return;
+        };
+        if let Some(tray) = handle.try_state::<SharedTrayManager>() {
+            if let Err(e) = tray.set_session_summary(&handle, Some(snapshot.tooltip_line())) {
+                eprintln!("Failed to update tray session summary: {}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn session_hud_get(
+    hud: State<'_, SharedSessionHud>,
+) -> Result<SessionHudSnapshot, AppError> {
+    let mut hud = hud.write().await;
+    hud.roll_over(Utc::now());
+    Ok(hud.snapshot(Utc::now()))
+}
+
+#[tauri::command]
+pub async fn session_hud_reset(
+    app: AppHandle,
+    hud: State<'_, SharedSessionHud>,
+) -> Result<SessionHudSnapshot, AppError> {
+    hud.write().await.reset(Utc::now());
+    publish(&app, hud.inner()).await;
+    Ok(hud.read().await.snapshot(Utc::now()))
+}
+
+#[tauri::command]
+pub async fn session_hud_set_risk_budget(
+    app: AppHandle,
+    risk_budget_usd: f64,
+    hud: State<'_, SharedSessionHud>,
+) -> Result<SessionHudSnapshot, AppError> {
+    if !risk_budget_usd.is_finite() || risk_budget_usd < 0.0 {
+        return Err(
+            AppError::Validation("Risk budget cannot be negative".to_string())
+                .logged("session_hud_set_risk_budget"),
+        );
+    }
+    hud.write().await.set_risk_budget(risk_budget_usd);
+    publish(&app, hud.inner()).await;
+    Ok(hud.read().await.snapshot(Utc::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::types::{OrderStatus, OrderType};
+
+    fn hud() -> SessionHud {
+        SessionHud {
+            settings: SessionHudSettings::default(),
+            session: SessionState::starting(Utc::now()),
+            prices: HashMap::new(),
+            last_emit: None,
+            path: None,
+        }
+    }
+
+    fn filled(side: OrderSide, amount: f64) -> Order {
+        let (input, output) = match side {
+            OrderSide::Buy => ("USDC", "SOL"),
+            OrderSide::Sell => ("SOL", "USDC"),
+        };
+        Order {
+            id: "order".into(),
+            order_type: OrderType::Market,
+            side,
+            status: OrderStatus::Filled,
+            input_mint: input.into(),
+            output_mint: output.into(),
+            input_symbol: input.into(),
+            output_symbol: output.into(),
+            amount,
+            filled_amount: amount,
+            limit_price: None,
+            stop_price: None,
+            trailing_percent: None,
+            trailing_amount: None,
+            highest_price: None,
+            lowest_price: None,
+            linked_order_id: None,
+            slippage_bps: 50,
+            priority_fee_micro_lamports: 0,
+            wallet_address: "wallet".into(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            triggered_at: None,
+            tx_signature: None,
+            error_message: None,
+        }
+    }
+
+    #[test]
+    fn fills_and_ticks_drive_pnl_exposure_and_win_rate() {
+        let mut hud = hud();
+        let now = Utc::now();
+
+        // Spend 1000 USDC on SOL at 100, then sell half at 110.
+        hud.record_fill(&filled(OrderSide::Buy, 1_000.0), 100.0, now)
+            .unwrap();
+        hud.record_fill(&filled(OrderSide::Sell, 5.0), 110.0, now)
+            .unwrap();
+        hud.record_price("SOL", 90.0, now);
+
+        let snapshot = hud.snapshot(now);
+        assert_eq!(snapshot.trade_count, 2);
+        assert!((snapshot.realized_pnl - 50.0).abs() < 1e-9);
+        assert!((snapshot.unrealized_pnl + 50.0).abs() < 1e-9);
+        assert!((snapshot.gross_exposure - 450.0).abs() < 1e-9);
+        assert_eq!(snapshot.win_rate, Some(100.0));
+        assert_eq!(snapshot.risk_budget_used_pct, 0.0);
+    }
+
+    #[test]
+    fn losses_consume_the_risk_budget_and_sessions_roll_daily() {
+        let mut hud = hud();
+        hud.set_risk_budget(200.0);
+        let now = Utc::now();
+
+        hud.record_fill(&filled(OrderSide::Buy, 1_000.0), 100.0, now)
+            .unwrap();
+        hud.record_fill(&filled(OrderSide::Sell, 10.0), 95.0, now)
+            .unwrap();
+
+        let snapshot = hud.snapshot(now);
+        assert!((snapshot.session_pnl + 50.0).abs() < 1e-9);
+        assert!((snapshot.risk_budget_used_pct - 25.0).abs() < 1e-9);
+        assert_eq!(snapshot.win_rate, Some(0.0));
+        assert_eq!(snapshot.net_exposure, 0.0);
+
+        hud.roll_over(now + chrono::Duration::days(1));
+        assert_eq!(hud.snapshot(now).trade_count, 0);
+    }
+}
@@ -0,0 +1,791 @@
+//! User-written strategy scripts, run in a sandboxed Rhai engine.
+//!
+//! A script defines `fn on_bar(ctx)` and returns `"buy"`, `"sell"`,
+//! `"close"` or `"hold"` (or a map `#{ action: "buy", reason: "..." }`).
+//! `ctx` carries the current bar, recent `closes`/`volumes` and the position
+//! and cash of the account the script trades. Values that should survive
+//! between bars are kept on `this`, e.g. `this.last_cross = ctx.time;`.
+//!
+//! Indicator helpers `sma`, `ema`, `rsi`, `stddev`, `highest` and `lowest`
+//! take an array and a period and return NaN until enough data exists.
+
+use super::backtesting::{
+    generate_mock_historical_data, BacktestConfig, BacktestEngine, BacktestResult, HistoricalData,
+};
+use super::SharedAutoTradingEngine;
+use crate::data::historical::SharedHistoricalReplayManager;
+use crate::errors::{AppError, CommandResultExt};
+use crate::portfolio::SharedPortfolioData;
+use chrono::{DateTime, Utc};
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Array, CallFnOptions, Dynamic, Engine, Map, Scope, AST};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+/// Bars of history exposed to the script through `ctx.closes`/`ctx.volumes`.
+const LOOKBACK_BARS: usize = 500;
+const MAX_SCRIPT_BYTES: usize = 64 * 1024;
+const MAX_LOG_LINES: usize = 200;
+const VALIDATION_BARS: i64 = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptLimits {
+    /// Rhai operations allowed per `on_bar` call.
+    pub max_operations: u64,
+    pub max_call_depth: usize,
+    pub max_string_size: usize,
+    pub max_array_size: usize,
+    pub max_map_size: usize,
+    /// Wall-clock budget for a whole run (all bars).
+    pub timeout_ms: u64,
+    pub max_bars: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            max_operations: 250_000,
+            max_call_depth: 32,
+            max_string_size: 4_096,
+            max_array_size: 10_000,
+            max_map_size: 1_000,
+            timeout_ms: 10_000,
+            max_bars: 20_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptAction {
+    Buy,
+    Sell,
+    Close,
+    Hold,
+}
+
+impl ScriptAction {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "buy" | "long" => Ok(ScriptAction::Buy),
+            "sell" | "short" => Ok(ScriptAction::Sell),
+            "close" | "exit" => Ok(ScriptAction::Close),
+            "hold" | "" => Ok(ScriptAction::Hold),
+            other => Err(format!("on_bar returned unknown action '{}'", other)),
+        }
+    }
+
+    /// Signal value fed to the auto-trading engine.
+    pub fn signal_value(&self) -> f64 {
+        match self {
+            ScriptAction::Buy => 1.0,
+            ScriptAction::Sell | ScriptAction::Close => -1.0,
+            ScriptAction::Hold => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptDecision {
+    pub action: ScriptAction,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptValidation {
+    pub valid: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub functions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptBacktestResult {
+    pub result: BacktestResult,
+    /// "historical" when stored candles were used, "simulated" otherwise.
+    pub data_source: String,
+    pub bars_evaluated: usize,
+    pub logs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptEvaluation {
+    pub symbol: String,
+    pub decision: ScriptDecision,
+    pub bars_used: usize,
+    /// Set when the decision was forwarded to an auto-trading strategy:
+    /// whether the strategy's combined signal fired.
+    pub strategy_triggered: Option<bool>,
+    pub logs: Vec<String>,
+}
+
+/// Account state handed to the script alongside the bar.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScriptAccount {
+    pub position: f64,
+    pub entry_price: Option<f64>,
+    pub cash: f64,
+    pub equity: f64,
+}
+
+fn window(values: &[f64], period: i64) -> Option<&[f64]> {
+    let period = usize::try_from(period).ok().filter(|p| *p > 0)?;
+    (values.len() >= period).then(|| &values[values.len() - period..])
+}
+
+pub fn sma(values: &[f64], period: i64) -> f64 {
+    window(values, period)
+        .map(|w| w.iter().sum::<f64>() / w.len() as f64)
+        .unwrap_or(f64::NAN)
+}
+
+/// EMA seeded with the SMA of the first `period` values.
+pub fn ema(values: &[f64], period: i64) -> f64 {
+    let Some(n) = usize::try_from(period).ok().filter(|p| *p > 0) else {
+        return f64::NAN;
+    };
+    if values.len() < n {
+        return f64::NAN;
+    }
+    let k = 2.0 / (n as f64 + 1.0);
+    let mut value = values[..n].iter().sum::<f64>() / n as f64;
+    for price in &values[n..] {
+        value = price * k + value * (1.0 - k);
+    }
+    value
+}
+
+/// Wilder's RSI over the whole series.
+pub fn rsi(values: &[f64], period: i64) -> f64 {
+    let Some(n) = usize::try_from(period).ok().filter(|p| *p > 0) else {
+        return f64::NAN;
+    };
+    if values.len() <= n {
+        return f64::NAN;
+    }
+
+    let changes: Vec<f64> = values.windows(2).map(|w| w[1] - w[0]).collect();
+    let mut gain = changes[..n].iter().filter(|c| **c > 0.0).sum::<f64>() / n as f64;
+    let mut loss = -changes[..n].iter().filter(|c| **c < 0.0).sum::<f64>() / n as f64;
+    for change in &changes[n..] {
+        gain = (gain * (n - 1) as f64 + change.max(0.0)) / n as f64;
+        loss = (loss * (n - 1) as f64 + (-change).max(0.0)) / n as f64;
+    }
+
+    if loss == 0.0 {
+        return 100.0;
+    }
+    100.0 - 100.0 / (1.0 + gain / loss)
+}
+
+pub fn stddev(values: &[f64], period: i64) -> f64 {
+    window(values, period)
+        .map(|w| {
+            let mean = w.iter().sum::<f64>() / w.len() as f64;
+            (w.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / w.len() as f64).sqrt()
+        })
+        .unwrap_or(f64::NAN)
+}
+
+fn to_floats(values: &Array) -> Vec<f64> {
+    values
+        .iter()
+        .filter_map(|v| {
+            v.as_float()
+                .ok()
+                .or_else(|| v.as_int().ok().map(|i| i as f64))
+        })
+        .collect()
+}
+
+fn floats_to_array(values: &[f64]) -> Array {
+    values.iter().map(|v| Dynamic::from_float(*v)).collect()
+}
+
+/// A compiled script plus the sandboxed engine and per-run state.
+pub struct ScriptRunner {
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    state: Dynamic,
+    logs: Arc<Mutex<Vec<String>>>,
+}
+
+impl ScriptRunner {
+    pub fn new(script: &str, limits: &ScriptLimits) -> Result<Self, String> {
+        if script.len() > MAX_SCRIPT_BYTES {
+            return Err(format!("script exceeds {} bytes", MAX_SCRIPT_BYTES));
+        }
+
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let engine = Self::sandboxed_engine(limits, logs.clone());
+        let ast = engine.compile(script).map_err(|e| e.to_string())?;
+
+        if !ast
+            .iter_functions()
+            .any(|f| f.name == "on_bar" && f.params.len() == 1)
+        {
+            return Err("script must define fn on_bar(ctx)".to_string());
+        }
+
+        let mut scope = Scope::new();
+        engine
+            .run_ast_with_scope(&mut scope, &ast)
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            engine,
+            ast,
+            scope,
+            state: Dynamic::from_map(Map::new()),
+            logs,
+        })
+    }
+
+    fn sandboxed_engine(limits: &ScriptLimits, logs: Arc<Mutex<Vec<String>>>) -> Engine {
+        let mut engine = Engine::new();
+        engine.set_module_resolver(DummyModuleResolver::new());
+        engine.disable_symbol("eval");
+        engine.set_max_operations(limits.max_operations);
+        engine.set_max_call_levels(limits.max_call_depth);
+        engine.set_max_expr_depths(64, 32);
+        engine.set_max_string_size(limits.max_string_size);
+        engine.set_max_array_size(limits.max_array_size);
+        engine.set_max_map_size(limits.max_map_size);
+
+        let started = Instant::now();
+        let timeout = Duration::from_millis(limits.timeout_ms);
+        engine.on_progress(move |_| {
+            (started.elapsed() > timeout).then(|| Dynamic::from("script timed out"))
+        });
+
+        let print_logs = logs.clone();
+        engine.on_print(move |line| push_log(&print_logs, line.to_string()));
+        engine.on_debug(move |line, _, pos| push_log(&logs, format!("{} @ {}", line, pos)));
+
+        engine.register_fn("sma", |values: Array, period: i64| {
+            sma(&to_floats(&values), period)
+        });
+        engine.register_fn("ema", |values: Array, period: i64| {
+            ema(&to_floats(&values), period)
+        });
+        engine.register_fn("rsi", |values: Array, period: i64| {
+            rsi(&to_floats(&values), period)
+        });
+        engine.register_fn("stddev", |values: Array, period: i64| {
+            stddev(&to_floats(&values), period)
+        });
+        engine.register_fn("highest", |values: Array, period: i64| {
+            window(&to_floats(&values), period)
+                .map(|w| w.iter().cloned().fold(f64::MIN, f64::max))
+                .unwrap_or(f64::NAN)
+        });
+        engine.register_fn("lowest", |values: Array, period: i64| {
+            window(&to_floats(&values), period)
+                .map(|w| w.iter().cloned().fold(f64::MAX, f64::min))
+                .unwrap_or(f64::NAN)
+        });
+
+        engine
+    }
+
+    /// Runs `on_bar` for the last bar in `bars`.
+    pub fn on_bar(
+        &mut self,
+        symbol: &str,
+        bars: &[HistoricalData],
+        account: ScriptAccount,
+    ) -> Result<ScriptDecision, String> {
+        let bar = bars.last().ok_or("no bars to evaluate")?;
+        let history = &bars[bars.len().saturating_sub(LOOKBACK_BARS)..];
+        let closes: Vec<f64> = history.iter().map(|b| b.close).collect();
+        let volumes: Vec<f64> = history.iter().map(|b| b.volume).collect();
+
+        let mut ctx = Map::new();
+        ctx.insert("symbol".into(), Dynamic::from(symbol.to_string()));
+        ctx.insert("time".into(), Dynamic::from_int(bar.timestamp.timestamp()));
+        ctx.insert("bar_index".into(), Dynamic::from_int(bars.len() as i64 - 1));
+        ctx.insert("open".into(), Dynamic::from_float(bar.open));
+        ctx.insert("high".into(), Dynamic::from_float(bar.high));
+        ctx.insert("low".into(), Dynamic::from_float(bar.low));
+        ctx.insert("close".into(), Dynamic::from_float(bar.close));
+        ctx.insert("volume".into(), Dynamic::from_float(bar.volume));
+        ctx.insert(
+            "closes".into(),
+            Dynamic::from_array(floats_to_array(&closes)),
+        );
+        ctx.insert(
+            "volumes".into(),
+            Dynamic::from_array(floats_to_array(&volumes)),
+        );
+        ctx.insert("position".into(), Dynamic::from_float(account.position));
+        ctx.insert(
+            "entry_price".into(),
+            account
+                .entry_price
+                .map(Dynamic::from_float)
+                .unwrap_or(Dynamic::UNIT),
+        );
+        ctx.insert("cash".into(), Dynamic::from_float(account.cash));
+        ctx.insert("equity".into(), Dynamic::from_float(account.equity));
+
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.state);
+        let output = self
+            .engine
+            .call_fn_with_options::<Dynamic>(options, &mut self.scope, &self.ast, "on_bar", (ctx,))
+            .map_err(|e| e.to_string())?;
+
+        decision_from_dynamic(output)
+    }
+
+    pub fn take_logs(&self) -> Vec<String> {
+        match self.logs.lock() {
+            Ok(mut logs) => std::mem::take(&mut *logs),
+            Err(poisoned) => std::mem::take(&mut *poisoned.into_inner()),
+        }
+    }
+}
+
+fn push_log(logs: &Arc<Mutex<Vec<String>>>, line: String) {
+    if let Ok(mut logs) = logs.lock() {
+        if logs.len() < MAX_LOG_LINES {
+            logs.push(line);
+        }
+    }
+}
+
+fn decision_from_dynamic(output: Dynamic) -> Result<ScriptDecision, String> {
+    if output.is_unit() {
+        return Ok(ScriptDecision {
+            action: ScriptAction::Hold,
+            reason: None,
+        });
+    }
+    if output.is_string() {
+        let action = output.into_string().map_err(|e| e.to_string())?;
+        return Ok(ScriptDecision {
+            action: ScriptAction::parse(&action)?,
+            reason: None,
+        });
+    }
+    if output.is_map() {
+        let map = output.cast::<Map>();
+        let action = map
+            .get("action")
+            .and_then(|a| a.clone().into_string().ok())
+            .ok_or("on_bar map must contain an 'action' string")?;
+        let reason = map.get("reason").and_then(|r| r.clone().into_string().ok());
+        return Ok(ScriptDecision {
+            action: ScriptAction::parse(&action)?,
+            reason,
+        });
+    }
+
+    Err(format!(
+        "on_bar must return a string or map, got {}",
+        output.type_name()
+    ))
+}
+
+/// Compiles the script and dry-runs it over simulated bars.
+pub fn validate_script(script: &str, limits: &ScriptLimits) -> ScriptValidation {
+    let mut validation = ScriptValidation {
+        valid: false,
+        errors: Vec::new(),
+        warnings: Vec::new(),
+        functions: Vec::new(),
+    };
+
+    let mut runner = match ScriptRunner::new(script, limits) {
+        Ok(runner) => runner,
+        Err(e) => {
+            validation.errors.push(e);
+            return validation;
+        }
+    };
+    validation.functions = runner
+        .ast
+        .iter_functions()
+        .map(|f| format!("{}({})", f.name, f.params.join(", ")))
+        .collect();
+
+    let end = Utc::now();
+    let bars = generate_mock_historical_data(
+        end - chrono::Duration::hours(VALIDATION_BARS - 1),
+        end,
+        60,
+        100.0,
+    );
+    let mut saw_trade = false;
+    for i in 1..=bars.len() {
+        let account = ScriptAccount {
+            cash: 10_000.0,
+            equity: 10_000.0,
+            ..ScriptAccount::default()
+        };
+        match runner.on_bar("TEST", &bars[..i], account) {
+            Ok(decision) => saw_trade |= decision.action != ScriptAction::Hold,
+            Err(e) => {
+                validation.errors.push(format!("bar {}: {}", i - 1, e));
+                return validation;
+            }
+        }
+    }
+
+    if !saw_trade {
+        validation
+            .warnings
+            .push("script held on every simulated bar".to_string());
+    }
+    validation.valid = true;
+    validation
+}
+
+/// Runs the script bar by bar through the long-only backtest engine.
+pub fn run_script_backtest(
+    script: &str,
+    config: BacktestConfig,
+    bars: &[HistoricalData],
+    limits: &ScriptLimits,
+) -> Result<(BacktestResult, Vec<String>), String> {
+    if bars.len() > limits.max_bars {
+        return Err(format!(
+            "{} bars exceeds the limit of {}",
+            bars.len(),
+            limits.max_bars
+        ));
+    }
+
+    let mut runner = ScriptRunner::new(script, limits)?;
+    let symbol = config.symbol.clone();
+    let mut engine = BacktestEngine::new(config);
+
+    for (i, bar) in bars.iter().enumerate() {
+        engine.set_market_context(bar.volume, None);
+        if engine.has_pending_exit() {
+            engine.continue_pending_exit(bar.timestamp, bar.close);
+        }
+
+        let account = ScriptAccount {
+            position: engine.position_quantity(),
+            entry_price: engine.position_entry_price(),
+            cash: engine.cash(),
+            equity: engine.cash() + engine.position_quantity() * bar.close,
+        };
+        let decision = runner
+            .on_bar(&symbol, &bars[..=i], account)
+            .map_err(|e| format!("bar {}: {}", i, e))?;
+
+        let signal = decision.reason.or_else(|| Some("SCRIPT".to_string()));
+        match decision.action {
+            ScriptAction::Buy => engine.execute_buy(bar.timestamp, bar.close, signal),
+            ScriptAction::Sell | ScriptAction::Close => {
+                engine.execute_sell(bar.timestamp, bar.close, signal)
+            }
+            ScriptAction::Hold => {}
+        }
+        engine.update_equity_curve(bar.timestamp, bar.close);
+    }
+
+    if engine.position_quantity() > 0.0 {
+        if let Some(last) = bars.last() {
+            engine.close_position(
+                last.timestamp,
+                last.close,
+                Some("END_OF_PERIOD".to_string()),
+            );
+        }
+    }
+
+    Ok((engine.finalize(), runner.take_logs()))
+}
+
+fn interval_seconds(interval: &str) -> i64 {
+    match interval {
+        "1m" => 60,
+        "5m" => 300,
+        "15m" => 900,
+        "4h" => 14_400,
+        "1d" => 86_400,
+        _ => 3_600,
+    }
+}
+
+async fn load_bars(
+    app: &AppHandle,
+    symbol: &str,
+    interval: &str,
+    start: i64,
+    end: i64,
+) -> Vec<HistoricalData> {
+    let Some(historical) = app.try_state::<SharedHistoricalReplayManager>() else {
+        return Vec::new();
+    };
+    let manager = historical.read().await;
+    match manager.price_history(symbol, interval, start, end).await {
+        Ok(points) => points
+            .into_iter()
+            .filter_map(|p| {
+                Some(HistoricalData {
+                    timestamp: DateTime::from_timestamp(p.timestamp, 0)?,
+                    open: p.open,
+                    high: p.high,
+                    low: p.low,
+                    close: p.close,
+                    volume: p.volume,
+                })
+            })
+            .collect(),
+        Err(e) => {
+            eprintln!("Script price history failed for {}: {}", symbol, e);
+            Vec::new()
+        }
+    }
+}
+
+fn portfolio_account(app: &AppHandle, symbol: &str) -> ScriptAccount {
+    let Some(portfolio) = app.try_state::<SharedPortfolioData>() else {
+        return ScriptAccount::default();
+    };
+    let Ok(data) = portfolio.lock() else {
+        return ScriptAccount::default();
+    };
+
+    let positions = data.positions();
+    let mut account = ScriptAccount {
+        equity: positions.iter().map(|p| p.total_value).sum(),
+        cash: positions
+            .iter()
+            .filter(|p| matches!(p.symbol.as_str(), "USDC" | "USDT"))
+            .map(|p| p.total_value)
+            .sum(),
+        ..ScriptAccount::default()
+    };
+    if let Some(position) = positions
+        .iter()
+        .find(|p| p.symbol.eq_ignore_ascii_case(symbol))
+    {
+        account.position = position.amount;
+        account.entry_price = Some(position.avg_entry_price);
+    }
+    account
+}
+
+#[tauri::command]
+pub async fn strategy_script_validate(
+    script: String,
+    limits: Option<ScriptLimits>,
+) -> Result<ScriptValidation, AppError> {
+    let limits = limits.unwrap_or_default();
+    tokio::task::spawn_blocking(move || validate_script(&script, &limits))
+        .await
+        .logged("strategy_script_validate")
+}
+
+#[tauri::command]
+pub async fn strategy_script_backtest(
+    app: AppHandle,
+    script: String,
+    config: BacktestConfig,
+    limits: Option<ScriptLimits>,
+) -> Result<ScriptBacktestResult, AppError> {
+    let limits = limits.unwrap_or_default();
+    let mut bars = load_bars(
+        &app,
+        &config.symbol,
+        &config.data_interval,
+        config.start_date.timestamp(),
+        config.end_date.timestamp(),
+    )
+    .await;
+
+    let data_source = if bars.is_empty() {
+        bars = generate_mock_historical_data(
+            config.start_date,
+            config.end_date,
+            interval_seconds(&config.data_interval) / 60,
+            100.0,
+        );
+        "simulated"
+    } else {
+        "historical"
+    };
+
+    let bars_evaluated = bars.len();
+    let (result, logs) =
+        tokio::task::spawn_blocking(move || run_script_backtest(&script, config, &bars, &limits))
+            .await
+            .logged("strategy_script_backtest")?
+            .logged("strategy_script_backtest")?;
+
+    Ok(ScriptBacktestResult {
+        result,
+        data_source: data_source.to_string(),
+        bars_evaluated,
+        logs,
+    })
+}
+
+/// Evaluates the script once against stored candles and the live portfolio.
+/// `this` state starts empty on every call. When `strategy_id` and
+/// `source_id` are given, the decision is recorded as that signal source.
+#[tauri::command]
+pub async fn strategy_script_evaluate(
+    app: AppHandle,
+    script: String,
+    symbol: String,
+    interval: Option<String>,
+    strategy_id: Option<String>,
+    source_id: Option<String>,
+    limits: Option<ScriptLimits>,
+) -> Result<ScriptEvaluation, AppError> {
+    let limits = limits.unwrap_or_default();
+    let interval = interval.unwrap_or_else(|| "1h".to_string());
+    let end = Utc::now().timestamp();
+    let start = end - interval_seconds(&interval) * LOOKBACK_BARS as i64;
+
+    let bars = load_bars(&app, &symbol, &interval, start, end).await;
+    if bars.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "No {} price history stored for {}",
+            interval, symbol
+        ))
+        .logged("strategy_script_evaluate"));
+    }
+    let account = portfolio_account(&app, &symbol);
+
+    let bars_used = bars.len();
+    let script_symbol = symbol.clone();
+    let (decision, logs) = tokio::task::spawn_blocking(move || {
+        let mut runner = ScriptRunner::new(&script, &limits)?;
+        let decision = runner.on_bar(&script_symbol, &bars, account)?;
+        Ok::<_, String>((decision, runner.take_logs()))
+    })
+    .await
+    .logged("strategy_script_evaluate")?
+    .logged("strategy_script_evaluate")?;
+
+    let strategy_triggered = match (strategy_id, source_id) {
+        (Some(strategy_id), Some(source_id)) => {
+            let engine = app
+                .try_state::<SharedAutoTradingEngine>()
+                .ok_or("Auto trading engine unavailable")
+                .logged("strategy_script_evaluate")?;
+            let mut engine = engine.lock().logged("strategy_script_evaluate")?;
+            Some(
+                engine
+                    .record_external_signal(
+                        &strategy_id,
+                        &source_id,
+                        decision.action.signal_value(),
+                    )
+                    .logged("strategy_script_evaluate")?,
+            )
+        }
+        _ => None,
+    };
+
+    Ok(ScriptEvaluation {
+        symbol,
+        decision,
+        bars_used,
+        strategy_triggered,
+        logs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn rising_bars(count: usize) -> Vec<HistoricalData> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        (0..count)
+            .map(|i| {
+                let price = 100.0 + i as f64;
+                HistoricalData {
+                    timestamp: start + chrono::Duration::hours(i as i64),
+                    open: price,
+                    high: price + 0.5,
+                    low: price - 0.5,
+                    close: price,
+                    volume: 1_000.0,
+                }
+            })
+            .collect()
+    }
+
+    fn config() -> BacktestConfig {
+        BacktestConfig {
+            strategy_id: "script".to_string(),
+            symbol: "SOL".to_string(),
+            start_date: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            end_date: Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap(),
+            initial_capital: 10_000.0,
+            commission_rate: 0.0,
+            slippage_rate: 0.0,
+            data_interval: "1h".to_string(),
+            execution: None,
+        }
+    }
+
+    #[test]
+    fn indicators_match_hand_computed_values() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(sma(&values, 5), 3.0);
+        assert!(sma(&values, 6).is_nan());
+        assert_eq!(rsi(&values, 3), 100.0);
+        assert!((stddev(&values, 5) - 2.0_f64.sqrt()).abs() < 1e-9);
+        assert_eq!(ema(&values, 3), 4.0);
+    }
+
+    #[test]
+    fn script_without_on_bar_is_rejected() {
+        let validation = validate_script("let x = 1;", &ScriptLimits::default());
+        assert!(!validation.valid);
+    }
+
+    #[test]
+    fn runaway_script_hits_operation_limit() {
+        let limits = ScriptLimits {
+            max_operations: 1_000,
+            ..ScriptLimits::default()
+        };
+        let mut runner = ScriptRunner::new("fn on_bar(ctx) { loop { } }", &limits).unwrap();
+        let bars = rising_bars(1);
+        assert!(runner
+            .on_bar("SOL", &bars, ScriptAccount::default())
+            .is_err());
+    }
+
+    #[test]
+    fn backtest_follows_script_decisions_and_keeps_state() {
+        let script = r#"
+            fn on_bar(ctx) {
+                if this.bars == () { this.bars = 0; }
+                this.bars += 1;
+                if ctx.position == 0.0 && this.bars == 3 { return "buy"; }
+                if ctx.position > 0.0 && this.bars == 20 { return "sell"; }
+                "hold"
+            }
+        "#;
+        let (result, _) =
+            run_script_backtest(script, config(), &rising_bars(24), &ScriptLimits::default())
+                .unwrap();
+
+        assert_eq!(result.trades.len(), 2);
+        assert_eq!(result.trades[0].price, 102.0);
+        assert_eq!(result.trades[1].price, 119.0);
+        assert!(result.metrics.total_return > 0.0);
+    }
+}
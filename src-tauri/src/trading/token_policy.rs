@@ -0,0 +1,429 @@
+//! User-managed token whitelist and blacklist.
+//!
+//! Every path that can buy a token calls [`check_token_policy`] before it
+//! trades, and `jupiter_swap` calls it again as a backstop, so the lists
+//! apply the same way to manual orders, bots, copy trading and rebalancing.
+//! Only the token being acquired is checked: selling out of a listed token
+//! is always allowed, otherwise a blacklist could trap a position.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::{OnceCell, RwLock};
+
+use crate::errors::{AppError, CommandResultExt};
+use crate::profiles::ProfilePaths;
+use crate::security::reputation::SharedReputationEngine;
+
+const TOKEN_POLICY_FILE: &str = "token_policy.json";
+pub const TOKEN_POLICY_BLOCKED_EVENT: &str = "token_policy_blocked";
+
+/// SOL, USDC and USDT. Exits settle into these, so whitelist mode lets
+/// them through unless `allow_base_assets` is turned off.
+const BASE_ASSET_MINTS: &[&str] = &[
+    "So11111111111111111111111111111111111111112",
+    "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+    "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenListKind {
+    Whitelist,
+    Blacklist,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenListSource {
+    User,
+    /// Imported from the reputation system's token blacklist.
+    Reputation,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionPath {
+    ManualOrder,
+    Swap,
+    Bot,
+    CopyTrading,
+    Rebalance,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenListEntry {
+    pub mint: String,
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+    pub source: TokenListSource,
+    pub added_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TokenPolicy {
+    /// When on, only whitelisted tokens can be bought.
+    pub whitelist_enabled: bool,
+    pub allow_base_assets: bool,
+    pub whitelist: Vec<TokenListEntry>,
+    pub blacklist: Vec<TokenListEntry>,
+}
+
+impl Default for TokenPolicy {
+    fn default() -> Self {
+        Self {
+            whitelist_enabled: false,
+            allow_base_assets: true,
+            whitelist: Vec::new(),
+            blacklist: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPolicyDecision {
+    pub allowed: bool,
+    pub mint: String,
+    pub path: ExecutionPath,
+    pub reason: Option<String>,
+}
+
+impl TokenPolicy {
+    /// The one place the lists are interpreted. `mint` is the token the
+    /// trade would acquire.
+    pub fn evaluate(&self, path: ExecutionPath, mint: &str) -> TokenPolicyDecision {
+        let label = |entry: Option<&TokenListEntry>| {
+            entry
+                .and_then(|entry| entry.symbol.clone())
+                .unwrap_or_else(|| mint.to_string())
+        };
+
+        let reason = if let Some(entry) = self.entry(TokenListKind::Blacklist, mint) {
+            Some(match &entry.reason {
+                Some(reason) => format!("{} is blacklisted: {}", label(Some(entry)), reason),
+                None => format!("{} is blacklisted", label(Some(entry))),
+            })
+        } else if self.whitelist_enabled
+            && self.entry(TokenListKind::Whitelist, mint).is_none()
+            && !(self.allow_base_assets && BASE_ASSET_MINTS.contains(&mint))
+        {
+            Some(format!("{} is not on the token whitelist", label(None)))
+        } else {
+            None
+        };
+
+        TokenPolicyDecision {
+            allowed: reason.is_none(),
+            mint: mint.to_string(),
+            path,
+            reason,
+        }
+    }
+
+    pub fn entry(&self, kind: TokenListKind, mint: &str) -> Option<&TokenListEntry> {
+        self.list(kind).iter().find(|entry| entry.mint == mint)
+    }
+
+    fn list(&self, kind: TokenListKind) -> &[TokenListEntry] {
+        match kind {
+            TokenListKind::Whitelist => &self.whitelist,
+            TokenListKind::Blacklist => &self.blacklist,
+        }
+    }
+
+    fn list_mut(&mut self, kind: TokenListKind) -> &mut Vec<TokenListEntry> {
+        match kind {
+            TokenListKind::Whitelist => &mut self.whitelist,
+            TokenListKind::Blacklist => &mut self.blacklist,
+        }
+    }
+
+    /// Adds or replaces an entry. A token is on at most one list, so it is
+    /// taken off the other.
+    pub fn add(&mut self, kind: TokenListKind, entry: TokenListEntry) {
+        let other = match kind {
+            TokenListKind::Whitelist => TokenListKind::Blacklist,
+            TokenListKind::Blacklist => TokenListKind::Whitelist,
+        };
+        self.list_mut(other).retain(|e| e.mint != entry.mint);
+        let list = self.list_mut(kind);
+        list.retain(|e| e.mint != entry.mint);
+        list.push(entry);
+    }
+
+    pub fn remove(&mut self, kind: TokenListKind, mint: &str) -> bool {
+        let list = self.list_mut(kind);
+        let before = list.len();
+        list.retain(|entry| entry.mint != mint);
+        list.len() != before
+    }
+}
+
+pub struct TokenPolicyManager {
+    policy: TokenPolicy,
+    path: Option<PathBuf>,
+    app_handle: AppHandle,
+}
+
+pub type SharedTokenPolicyManager = Arc<RwLock<TokenPolicyManager>>;
+
+static TOKEN_POLICY: OnceCell<SharedTokenPolicyManager> = OnceCell::const_new();
+
+impl TokenPolicyManager {
+    pub fn new(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(TOKEN_POLICY_FILE));
+        let policy = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            policy,
+            path,
+            app_handle: app.clone(),
+        }
+    }
+
+    pub fn policy(&self) -> &TokenPolicy {
+        &self.policy
+    }
+
+    fn update(&mut self, change: impl FnOnce(&mut TokenPolicy)) -> TokenPolicy {
+        change(&mut self.policy);
+        self.save();
+        self.policy.clone()
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        match serde_json::to_string_pretty(&self.policy) {
+            Ok(contents) => {
+                if let Err(e) = fs::write(path, contents) {
+                    eprintln!("Failed to save token policy: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize token policy: {}", e),
+        }
+    }
+}
+
+pub fn register_token_policy_state(app: &tauri::App) {
+    let manager: SharedTokenPolicyManager =
+        Arc::new(RwLock::new(TokenPolicyManager::new(app.handle())));
+    let _ = TOKEN_POLICY.set(manager.clone());
+    app.manage(manager);
+}
+
+/// Refuses a trade that would acquire a blocked token. Fails closed when
+/// the policy has not been loaded, so nothing trades around it at startup.
+pub async fn check_token_policy(path: ExecutionPath, mint: &str) -> Result<(), String> {
+    let manager = TOKEN_POLICY
+        .get()
+        .ok_or_else(|| "Token policy is not initialized".to_string())?;
+    let manager = manager.read().await;
+    let decision = manager.policy.evaluate(path, mint);
+    if decision.allowed {
+        return Ok(());
+    }
+    let _ = manager
+        .app_handle
+        .emit(TOKEN_POLICY_BLOCKED_EVENT, &decision);
+    Err(decision
+        .reason
+        .unwrap_or_else(|| format!("{mint} is blocked by the token policy")))
+}
+
+#[tauri::command]
+pub async fn get_token_policy(
+    policy: State<'_, SharedTokenPolicyManager>,
+) -> Result<TokenPolicy, AppError> {
+    Ok(policy.read().await.policy().clone())
+}
+
+#[tauri::command]
+pub async fn token_policy_add(
+    kind: TokenListKind,
+    mint: String,
+    symbol: Option<String>,
+    reason: Option<String>,
+    policy: State<'_, SharedTokenPolicyManager>,
+) -> Result<TokenPolicy, AppError> {
+    if mint.trim().is_empty() {
+        return Err(
+            AppError::Validation("Token mint is required".to_string()).logged("token_policy_add")
+        );
+    }
+    let entry = TokenListEntry {
+        mint: mint.trim().to_string(),
+        symbol,
+        reason,
+        source: TokenListSource::User,
+        added_at: Utc::now().timestamp(),
+    };
+    Ok(policy
+        .write()
+        .await
+        .update(|policy| policy.add(kind, entry)))
+}
+
+#[tauri::command]
+pub async fn token_policy_remove(
+    kind: TokenListKind,
+    mint: String,
+    policy: State<'_, SharedTokenPolicyManager>,
+) -> Result<TokenPolicy, AppError> {
+    let mut manager = policy.write().await;
+    if manager.policy().entry(kind, &mint).is_none() {
+        return Err(
+            AppError::NotFound(format!("{mint} is not on the list")).logged("token_policy_remove")
+        );
+    }
+    Ok(manager.update(|policy| {
+        policy.remove(kind, &mint);
+    }))
+}
+
+#[tauri::command]
+pub async fn token_policy_set_whitelist_mode(
+    enabled: bool,
+    allow_base_assets: Option<bool>,
+    policy: State<'_, SharedTokenPolicyManager>,
+) -> Result<TokenPolicy, AppError> {
+    Ok(policy.write().await.update(|policy| {
+        policy.whitelist_enabled = enabled;
+        if let Some(allow) = allow_base_assets {
+            policy.allow_base_assets = allow;
+        }
+    }))
+}
+
+/// Copies the reputation system's active token blacklist in. Tokens the
+/// user has whitelisted are left alone. Returns the number of new entries.
+#[tauri::command]
+pub async fn token_policy_import_reputation(
+    reputation: State<'_, SharedReputationEngine>,
+    policy: State<'_, SharedTokenPolicyManager>,
+) -> Result<usize, AppError> {
+    let entries = reputation
+        .read()
+        .await
+        .get_blacklist(Some("token".to_string()))
+        .await
+        .logged("token_policy_import_reputation")?;
+
+    let mut manager = policy.write().await;
+    let mut imported = 0;
+    manager.update(|policy| {
+        for entry in entries {
+            if policy
+                .entry(TokenListKind::Whitelist, &entry.address)
+                .is_some()
+                || policy
+                    .entry(TokenListKind::Blacklist, &entry.address)
+                    .is_some()
+            {
+                continue;
+            }
+            policy.add(
+                TokenListKind::Blacklist,
+                TokenListEntry {
+                    mint: entry.address,
+                    symbol: None,
+                    reason: Some(entry.reason),
+                    source: TokenListSource::Reputation,
+                    added_at: Utc::now().timestamp(),
+                },
+            );
+            imported += 1;
+        }
+    });
+    Ok(imported)
+}
+
+#[tauri::command]
+pub async fn token_policy_check(
+    path: ExecutionPath,
+    mint: String,
+    policy: State<'_, SharedTokenPolicyManager>,
+) -> Result<TokenPolicyDecision, AppError> {
+    Ok(policy.read().await.policy().evaluate(path, &mint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    fn entry(mint: &str, reason: Option<&str>) -> TokenListEntry {
+        TokenListEntry {
+            mint: mint.to_string(),
+            symbol: Some("BONK".into()),
+            reason: reason.map(str::to_string),
+            source: TokenListSource::User,
+            added_at: 0,
+        }
+    }
+
+    #[test]
+    fn blacklist_blocks_buys_on_every_path() {
+        let mut policy = TokenPolicy::default();
+        policy.add(TokenListKind::Blacklist, entry(BONK, Some("rugged")));
+
+        for path in [
+            ExecutionPath::ManualOrder,
+            ExecutionPath::Bot,
+            ExecutionPath::CopyTrading,
+            ExecutionPath::Rebalance,
+        ] {
+            let decision = policy.evaluate(path, BONK);
+            assert!(!decision.allowed);
+            assert_eq!(
+                decision.reason.as_deref(),
+                Some("BONK is blacklisted: rugged")
+            );
+        }
+        assert!(policy.evaluate(ExecutionPath::Swap, USDC).allowed);
+    }
+
+    #[test]
+    fn whitelist_mode_allows_listed_and_base_assets_only() {
+        let mut policy = TokenPolicy {
+            whitelist_enabled: true,
+            ..Default::default()
+        };
+        assert!(!policy.evaluate(ExecutionPath::Bot, BONK).allowed);
+        assert!(policy.evaluate(ExecutionPath::Bot, USDC).allowed);
+
+        policy.add(TokenListKind::Whitelist, entry(BONK, None));
+        assert!(policy.evaluate(ExecutionPath::Bot, BONK).allowed);
+
+        policy.allow_base_assets = false;
+        assert!(!policy.evaluate(ExecutionPath::Bot, USDC).allowed);
+    }
+
+    #[test]
+    fn a_token_sits_on_one_list_at_a_time() {
+        let mut policy = TokenPolicy::default();
+        policy.add(TokenListKind::Whitelist, entry(BONK, None));
+        policy.add(TokenListKind::Blacklist, entry(BONK, None));
+
+        assert!(policy.entry(TokenListKind::Whitelist, BONK).is_none());
+        assert!(policy.entry(TokenListKind::Blacklist, BONK).is_some());
+    }
+}
@@ -86,6 +86,10 @@ pub struct Order {
     pub stop_price: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub trailing_percent: Option<f64>,
+    /// Absolute trail distance in quote units, the alternative to
+    /// `trailing_percent`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trailing_amount: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub highest_price: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -123,6 +127,7 @@ impl<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> for Order {
             limit_price: row.try_get("limit_price")?,
             stop_price: row.try_get("stop_price")?,
             trailing_percent: row.try_get("trailing_percent")?,
+            trailing_amount: row.try_get("trailing_amount")?,
             highest_price: row.try_get("highest_price")?,
             lowest_price: row.try_get("lowest_price")?,
             linked_order_id: row.try_get("linked_order_id")?,
@@ -153,6 +158,8 @@ pub struct CreateOrderRequest {
     pub stop_price: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub trailing_percent: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trailing_amount: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub linked_order_id: Option<String>,
     pub slippage_bps: i32,
@@ -160,6 +167,29 @@ pub struct CreateOrderRequest {
     pub wallet_address: String,
 }
 
+/// A take-profit and stop-loss submitted together as one-cancels-other:
+/// when either leg fills, the other is cancelled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateOrderGroupRequest {
+    pub take_profit: CreateOrderRequest,
+    pub stop_loss: CreateOrderRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderGroup {
+    pub group_id: String,
+    pub orders: Vec<Order>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderGroupCancelledEvent {
+    pub group_id: String,
+    /// The leg that filled or was cancelled by the user.
+    pub resolved_order_id: String,
+    pub cancelled_order_ids: Vec<String>,
+    pub reason: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderFill {
     pub order_id: String,
@@ -1,3 +1,6 @@
+use crate::profiles::ProfilePaths;
+use crate::wallet::flows::{flow_adjusted_pnl, SharedFlowLedger};
+use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::cmp::min;
@@ -70,6 +73,10 @@ pub struct TrayStats {
     pub pnl_value: f64,
     pub alert_count: u32,
     pub recent_alerts: Vec<TrayAlertPreview>,
+    /// Start of the period `pnl_value` covers. When set, deposits and
+    /// withdrawals since then are taken out of the P&L shown in the tray.
+    #[serde(default)]
+    pub pnl_since: Option<DateTime<Utc>>,
 }
 
 impl Default for TrayStats {
@@ -80,6 +87,7 @@ impl Default for TrayStats {
             pnl_value: 0.0,
             alert_count: 0,
             recent_alerts: Vec::new(),
+            pnl_since: None,
         }
     }
 }
@@ -90,6 +98,8 @@ pub struct TrayManager {
     shortcut: RwLock<Option<String>>,
     settings_path: RwLock<Option<PathBuf>>,
     tray_handle: RwLock<Option<TrayIcon>>,
+    /// One-line trading session summary appended to the tooltip.
+    session_summary: RwLock<Option<String>>,
 }
 
 impl TrayManager {
@@ -100,6 +110,7 @@ impl TrayManager {
             shortcut: RwLock::new(None),
             settings_path: RwLock::new(None),
             tray_handle: RwLock::new(None),
+            session_summary: RwLock::new(None),
         }
     }
 
@@ -173,7 +184,7 @@ impl TrayManager {
     }
 
     pub fn initialize(&self, app_handle: &AppHandle) {
-        match app_handle.path().app_data_dir() {
+        match app_handle.path().profile_data_dir() {
             Ok(mut data_dir) => {
                 if let Err(err) = fs::create_dir_all(&data_dir) {
                     eprintln!("Failed to ensure tray settings directory: {err}");
@@ -287,7 +298,11 @@ impl TrayManager {
         if let Some(tray) = tray_guard.as_ref() {
             tray.set_title(Some(&title))
                 .map_err(|e| format!("Failed to set tray title: {e}"))?;
-            tray.set_tooltip(Some(title))
+            let tooltip = match self.session_summary.read().as_ref() {
+                Some(summary) if settings.show_stats => format!("{}\n{}", title, summary),
+                _ => title,
+            };
+            tray.set_tooltip(Some(tooltip))
                 .map_err(|e| format!("Failed to set tray tooltip: {e}"))?;
         }
 
@@ -418,6 +433,15 @@ impl TrayManager {
         Ok(())
     }
 
+    pub fn set_session_summary(
+        &self,
+        app_handle: &AppHandle,
+        summary: Option<String>,
+    ) -> Result<(), String> {
+        *self.session_summary.write() = summary;
+        self.apply_icon_style(app_handle)
+    }
+
     pub fn update_badge(&self, app_handle: &AppHandle, count: u32) -> Result<(), String> {
         {
             let mut stats = self.stats.write();
@@ -444,7 +468,9 @@ impl TrayManager {
     }
 
     pub fn notify_minimized(&self, app_handle: &AppHandle) {
-        if !self.should_show_notifications() {
+        if !self.should_show_notifications()
+            || crate::auth::app_lock::notifications_muted(app_handle)
+        {
             return;
         }
 
@@ -517,10 +543,20 @@ pub fn update_tray_settings(
 
 #[tauri::command]
 pub fn update_tray_stats(
-    stats: TrayStats,
+    mut stats: TrayStats,
     tray_manager: tauri::State<'_, SharedTrayManager>,
     app: AppHandle,
 ) -> Result<(), String> {
+    if let (Some(since), Some(ledger)) = (stats.pnl_since, app.try_state::<SharedFlowLedger>()) {
+        let net_flow = ledger
+            .read()
+            .summary(None, Some(since))
+            .net_external_flow_usd;
+        let start_value = stats.portfolio_value - stats.pnl_value;
+        let adjusted = flow_adjusted_pnl(start_value, stats.portfolio_value, net_flow);
+        stats.pnl_value = adjusted.pnl;
+        stats.pnl_percentage = adjusted.pnl_percentage;
+    }
     tray_manager.update_stats(&app, stats)
 }
 
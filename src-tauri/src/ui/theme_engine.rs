@@ -1,4 +1,5 @@
 use chrono::Utc;
+use crate::profiles::ProfilePaths;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -138,7 +139,7 @@ impl ThemeEngine {
     fn resolve_storage_path(app: &AppHandle) -> Result<PathBuf, String> {
         let mut dir = app
             .path()
-            .app_data_dir()
+            .profile_data_dir()
             .map_err(|_| "Unable to resolve app data directory".to_string())?;
 
         if !dir.exists() {
@@ -1,3 +1,7 @@
+use crate::auth::command_policy::{CommandPolicyManager, GuardedCommand};
+use crate::auth::two_factor::TwoFactorManager;
+use crate::profiles::ProfilePaths;
+use crate::security::keystore::Keystore;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
@@ -58,7 +62,7 @@ impl UpdaterState {
     pub fn new(app_handle: &AppHandle) -> Result<Self, String> {
         let app_data_dir = app_handle
             .path()
-            .app_data_dir()
+            .profile_data_dir()
             .map_err(|e| format!("Unable to resolve app data directory: {}", e))?;
 
         let backup_path = app_data_dir.join("backups");
@@ -85,7 +89,7 @@ impl UpdaterState {
     pub async fn save_settings(&self, app_handle: &AppHandle) -> Result<(), String> {
         let app_data_dir = app_handle
             .path()
-            .app_data_dir()
+            .profile_data_dir()
             .map_err(|e| format!("Unable to resolve app data directory: {}", e))?;
 
         let settings_path = app_data_dir.join("updater_settings.json");
@@ -180,7 +184,21 @@ pub async fn rollback_update<R: Runtime>(
     app_handle: AppHandle<R>,
     state: State<'_, SharedUpdaterState>,
     window: Window<R>,
+    two_factor_code: Option<String>,
+    policy: State<'_, CommandPolicyManager>,
+    two_factor: State<'_, TwoFactorManager>,
+    keystore: State<'_, Keystore>,
 ) -> Result<(), String> {
+    policy
+        .authorize(
+            GuardedCommand::RollbackUpdate,
+            two_factor_code.as_deref(),
+            two_factor.inner(),
+            keystore.inner(),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
     let backup_dir = &state.backup_path;
     if !backup_dir.exists() {
         return Err("No backup available for rollback".to_string());
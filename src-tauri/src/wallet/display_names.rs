@@ -0,0 +1,432 @@
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::RwLock;
+
+use crate::errors::AppError;
+use crate::wallet::multi_wallet::MultiWalletManager;
+use crate::wallet::operations::WalletOperationsManager;
+
+pub const DISPLAY_NAMES_CHANGED_EVENT: &str = "display_names_changed";
+
+const SNS_FAVORITE_DOMAIN_URL: &str = "https://sns-sdk-proxy.bonfida.workers.dev/favorite-domain";
+const SNS_TTL: Duration = Duration::from_secs(60 * 60);
+/// Addresses without a domain are re-checked sooner, since most never
+/// register one but some do after being seen.
+const SNS_MISS_TTL: Duration = Duration::from_secs(10 * 60);
+const SNS_CONCURRENCY: usize = 8;
+const MAX_BATCH: usize = 500;
+
+/// Where a name came from, highest precedence first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DisplayNameSource {
+    OwnWallet,
+    AddressBook,
+    KnownEntity,
+    Sns,
+    /// No name is known; the display name is the shortened address.
+    Address,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NameCandidate {
+    pub source: DisplayNameSource,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayName {
+    pub address: String,
+    pub display_name: String,
+    pub source: DisplayNameSource,
+    /// Every name found for the address, in precedence order.
+    pub candidates: Vec<NameCandidate>,
+}
+
+impl DisplayName {
+    fn from_candidates(address: &str, mut candidates: Vec<NameCandidate>) -> Self {
+        candidates.sort_by_key(|c| c.source);
+        let (display_name, source) = match candidates.first() {
+            Some(best) => (best.name.clone(), best.source),
+            None => (short_address(address), DisplayNameSource::Address),
+        };
+        Self {
+            address: address.to_string(),
+            display_name,
+            source,
+            candidates,
+        }
+    }
+}
+
+/// Names from local sources, read once per batch so a batch sees one
+/// consistent view of the address book and wallet registry.
+struct LocalNames {
+    own_wallets: HashMap<String, String>,
+    contacts: HashMap<String, String>,
+}
+
+impl LocalNames {
+    fn load(app: &AppHandle) -> Self {
+        let own_wallets = app
+            .try_state::<MultiWalletManager>()
+            .and_then(|manager| manager.list_wallets().ok())
+            .map(|wallets| {
+                wallets
+                    .into_iter()
+                    .filter(|w| !w.label.trim().is_empty())
+                    .map(|w| (w.public_key, w.label))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let contacts = app
+            .try_state::<WalletOperationsManager>()
+            .map(|operations| operations.contact_names())
+            .unwrap_or_default();
+        Self {
+            own_wallets,
+            contacts,
+        }
+    }
+
+    fn candidates(&self, address: &str) -> Vec<NameCandidate> {
+        let mut candidates = Vec::new();
+        if let Some(name) = self.own_wallets.get(address) {
+            candidates.push(NameCandidate {
+                source: DisplayNameSource::OwnWallet,
+                name: name.clone(),
+            });
+        }
+        if let Some(name) = self.contacts.get(address) {
+            candidates.push(NameCandidate {
+                source: DisplayNameSource::AddressBook,
+                name: name.clone(),
+            });
+        }
+        if let Some(name) = known_entity(address) {
+            candidates.push(NameCandidate {
+                source: DisplayNameSource::KnownEntity,
+                name: name.to_string(),
+            });
+        }
+        candidates
+    }
+}
+
+/// Programs and accounts common enough to appear as counterparties in
+/// wallet history.
+pub fn known_entity(address: &str) -> Option<&'static str> {
+    let name = match address {
+        "11111111111111111111111111111111" => "System Program",
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" => "Token Program",
+        "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb" => "Token-2022 Program",
+        "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL" => "Associated Token Program",
+        "ComputeBudget111111111111111111111111111111" => "Compute Budget Program",
+        "Stake11111111111111111111111111111111111111" => "Stake Program",
+        "Vote111111111111111111111111111111111111111" => "Vote Program",
+        "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr" => "Memo Program",
+        "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s" => "Metaplex Token Metadata",
+        "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4" => "Jupiter Aggregator v6",
+        "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc" => "Orca Whirlpools",
+        "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8" => "Raydium AMM v4",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// `7xKX…9fQm` style abbreviation used when no name is known.
+pub fn short_address(address: &str) -> String {
+    let chars: Vec<char> = address.chars().collect();
+    if chars.len() <= 10 {
+        return address.to_string();
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{head}…{tail}")
+}
+
+/// Best name from local sources only, for modules that label addresses
+/// synchronously and cannot wait on an SNS lookup.
+pub fn local_display_name(app: &AppHandle, address: &str) -> Option<String> {
+    LocalNames::load(app)
+        .candidates(address)
+        .into_iter()
+        .min_by_key(|c| c.source)
+        .map(|c| c.name)
+}
+
+/// Tells the frontend to re-resolve names after a label changes.
+pub fn notify_display_names_changed(app: &AppHandle, addresses: Vec<String>) {
+    let _ = app.emit(DISPLAY_NAMES_CHANGED_EVENT, addresses);
+}
+
+fn looks_like_solana_address(address: &str) -> bool {
+    (32..=44).contains(&address.len())
+        && address
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l'))
+}
+
+struct CachedDomain {
+    domain: Option<String>,
+    fetched_at: Instant,
+}
+
+impl CachedDomain {
+    fn is_fresh(&self) -> bool {
+        let ttl = if self.domain.is_some() {
+            SNS_TTL
+        } else {
+            SNS_MISS_TTL
+        };
+        self.fetched_at.elapsed() < ttl
+    }
+}
+
+pub type SharedDisplayNameResolver = Arc<DisplayNameResolver>;
+
+/// Merges every naming source into one display name per address. Local
+/// sources are read on each call so renames show up immediately; SNS
+/// reverse lookups are cached.
+pub struct DisplayNameResolver {
+    client: reqwest::Client,
+    sns_cache: RwLock<HashMap<String, CachedDomain>>,
+}
+
+impl Default for DisplayNameResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DisplayNameResolver {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+            sns_cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub async fn resolve(
+        &self,
+        app: &AppHandle,
+        addresses: &[String],
+        include_sns: bool,
+    ) -> Vec<DisplayName> {
+        let local = LocalNames::load(app);
+        let mut unique: Vec<&String> = Vec::new();
+        for address in addresses {
+            if !unique.contains(&address) {
+                unique.push(address);
+            }
+        }
+
+        let domains = if include_sns {
+            let lookups: Vec<String> = unique
+                .iter()
+                .filter(|a| looks_like_solana_address(a))
+                .map(|a| a.to_string())
+                .collect();
+            self.sns_domains(&lookups).await
+        } else {
+            HashMap::new()
+        };
+
+        let resolved: HashMap<&String, DisplayName> = unique
+            .into_iter()
+            .map(|address| {
+                let mut candidates = local.candidates(address);
+                if let Some(Some(domain)) = domains.get(address) {
+                    candidates.push(NameCandidate {
+                        source: DisplayNameSource::Sns,
+                        name: domain.clone(),
+                    });
+                }
+                (address, DisplayName::from_candidates(address, candidates))
+            })
+            .collect();
+
+        addresses
+            .iter()
+            .filter_map(|address| resolved.get(address).cloned())
+            .collect()
+    }
+
+    /// Drops cached SNS results for the given addresses, or all of them.
+    pub async fn invalidate(&self, addresses: Option<&[String]>) {
+        let mut cache = self.sns_cache.write().await;
+        match addresses {
+            Some(addresses) => {
+                for address in addresses {
+                    cache.remove(address);
+                }
+            }
+            None => cache.clear(),
+        }
+    }
+
+    async fn sns_domains(&self, addresses: &[String]) -> HashMap<String, Option<String>> {
+        let mut domains = HashMap::new();
+        let mut missing = Vec::new();
+        {
+            let cache = self.sns_cache.read().await;
+            for address in addresses {
+                match cache.get(address).filter(|cached| cached.is_fresh()) {
+                    Some(cached) => {
+                        domains.insert(address.clone(), cached.domain.clone());
+                    }
+                    None => missing.push(address.clone()),
+                }
+            }
+        }
+        if missing.is_empty() {
+            return domains;
+        }
+
+        let fetched: Vec<(String, Result<Option<String>, String>)> = stream::iter(missing)
+            .map(|address| async move {
+                let domain = self.fetch_favorite_domain(&address).await;
+                (address, domain)
+            })
+            .buffer_unordered(SNS_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut cache = self.sns_cache.write().await;
+        for (address, result) in fetched {
+            match result {
+                Ok(domain) => {
+                    cache.insert(
+                        address.clone(),
+                        CachedDomain {
+                            domain: domain.clone(),
+                            fetched_at: Instant::now(),
+                        },
+                    );
+                    domains.insert(address, domain);
+                }
+                // Network failures are not cached so the next call retries.
+                Err(e) => tracing::debug!("SNS lookup for {} failed: {}", address, e),
+            }
+        }
+        domains
+    }
+
+    async fn fetch_favorite_domain(&self, address: &str) -> Result<Option<String>, String> {
+        let response = self
+            .client
+            .get(format!("{SNS_FAVORITE_DOMAIN_URL}/{address}"))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("SNS proxy returned {}", response.status()));
+        }
+        let body: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+        Ok(parse_favorite_domain(&body))
+    }
+}
+
+/// The proxy answers `{"s":"ok","result":{"reverse":"name",...}}`, or
+/// `{"s":"error",...}` when the owner has no favorite domain.
+fn parse_favorite_domain(body: &serde_json::Value) -> Option<String> {
+    if body["s"].as_str() != Some("ok") {
+        return None;
+    }
+    let name = body["result"]["reverse"].as_str()?.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some(if name.ends_with(".sol") {
+        name.to_string()
+    } else {
+        format!("{name}.sol")
+    })
+}
+
+#[tauri::command]
+pub async fn resolve_display_names(
+    app: AppHandle,
+    addresses: Vec<String>,
+    include_sns: Option<bool>,
+    resolver: State<'_, SharedDisplayNameResolver>,
+) -> Result<Vec<DisplayName>, AppError> {
+    if addresses.len() > MAX_BATCH {
+        return Err(AppError::Validation(format!(
+            "At most {MAX_BATCH} addresses can be resolved at once"
+        ))
+        .logged("resolve_display_names"));
+    }
+    Ok(resolver
+        .resolve(&app, &addresses, include_sns.unwrap_or(true))
+        .await)
+}
+
+/// Forgets cached SNS lookups, e.g. after the user registers a domain.
+#[tauri::command]
+pub async fn invalidate_display_names(
+    app: AppHandle,
+    addresses: Option<Vec<String>>,
+    resolver: State<'_, SharedDisplayNameResolver>,
+) -> Result<(), AppError> {
+    resolver.invalidate(addresses.as_deref()).await;
+    notify_display_names_changed(&app, addresses.unwrap_or_default());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_candidates_pick_highest_precedence() {
+        let address = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+        let resolved = DisplayName::from_candidates(
+            address,
+            vec![
+                NameCandidate {
+                    source: DisplayNameSource::KnownEntity,
+                    name: "Jupiter Aggregator v6".to_string(),
+                },
+                NameCandidate {
+                    source: DisplayNameSource::AddressBook,
+                    name: "Jup".to_string(),
+                },
+            ],
+        );
+        assert_eq!(resolved.display_name, "Jup");
+        assert_eq!(resolved.source, DisplayNameSource::AddressBook);
+        assert_eq!(resolved.candidates.len(), 2);
+
+        let unnamed = DisplayName::from_candidates(address, vec![]);
+        assert_eq!(unnamed.display_name, "JUP6…TaV4");
+        assert_eq!(unnamed.source, DisplayNameSource::Address);
+    }
+
+    #[test]
+    fn test_parse_favorite_domain() {
+        let found = json!({ "s": "ok", "result": { "domain": "x", "reverse": "bonfida" } });
+        assert_eq!(
+            parse_favorite_domain(&found),
+            Some("bonfida.sol".to_string())
+        );
+        let missing = json!({ "s": "error", "result": "Invalid domain input" });
+        assert_eq!(parse_favorite_domain(&missing), None);
+        assert!(!looks_like_solana_address(
+            "0x52908400098527886E0F7030069857D2E4169EE7"
+        ));
+        assert!(looks_like_solana_address(
+            "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"
+        ));
+    }
+}
@@ -0,0 +1,591 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::{AccountMeta, Instruction},
+    message::{Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::State;
+
+use crate::chains::{RoutingHint, RpcPool, SharedRpcPool};
+use crate::errors::{AppError, CommandResultExt};
+use crate::security::keystore::{Keystore, KeystoreError};
+
+const KEYSTORE_FEE_RELAYER_KEY: &str = "wallet.fee_relayer";
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+const SUBMIT_TIMEOUT: Duration = Duration::from_secs(30);
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+const TRANSFER_CHECKED_TAG: u8 = 12;
+/// Offset of `decimals` in an SPL mint account.
+const MINT_DECIMALS_OFFSET: usize = 44;
+
+/// External service that pays network fees on behalf of the user, e.g. an
+/// Octane deployment that takes its fee in the SPL token being sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeRelayerConfig {
+    pub enabled: bool,
+    pub endpoint: String,
+    /// Fee payer public key advertised by the relayer. Refreshed by the
+    /// health check; transactions are built with this account as payer.
+    pub fee_payer: Option<String>,
+    pub api_key: Option<String>,
+    /// Token mints the relayer accepts. Empty means any SPL token.
+    #[serde(default)]
+    pub allowed_mints: Vec<String>,
+    pub max_fee_per_transaction_sol: f64,
+    pub daily_cap_sol: f64,
+    pub max_transactions_per_day: u32,
+}
+
+impl Default for FeeRelayerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: String::new(),
+            fee_payer: None,
+            api_key: None,
+            allowed_mints: Vec::new(),
+            max_fee_per_transaction_sol: 0.0001,
+            daily_cap_sol: 0.01,
+            max_transactions_per_day: 50,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayerSpending {
+    pub day: NaiveDate,
+    pub sponsored_fees_sol: f64,
+    pub transactions: u32,
+}
+
+impl Default for RelayerSpending {
+    fn default() -> Self {
+        Self {
+            day: Utc::now().date_naive(),
+            sponsored_fees_sol: 0.0,
+            transactions: 0,
+        }
+    }
+}
+
+impl RelayerSpending {
+    fn roll_over(&mut self, today: NaiveDate) {
+        if self.day != today {
+            *self = Self {
+                day: today,
+                ..Self::default()
+            };
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayerHealth {
+    pub healthy: bool,
+    pub latency_ms: Option<u64>,
+    pub fee_payer: Option<String>,
+    pub error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayerStatus {
+    pub config: FeeRelayerConfig,
+    pub spending: RelayerSpending,
+    pub remaining_daily_sol: f64,
+    pub remaining_transactions: u32,
+    pub last_health: Option<RelayerHealth>,
+}
+
+/// Unsigned transfer with the relayer as fee payer, for the wallet to sign.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayedTransferDraft {
+    pub transaction_base64: String,
+    pub last_valid_block_height: u64,
+    pub fee_payer: String,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct PersistedRelayerState {
+    config: FeeRelayerConfig,
+    spending: RelayerSpending,
+}
+
+pub struct FeeRelayerManager {
+    state: Mutex<PersistedRelayerState>,
+    last_health: Mutex<Option<RelayerHealth>>,
+    client: reqwest::Client,
+}
+
+impl FeeRelayerManager {
+    pub fn initialize(keystore: &Keystore) -> Result<Self, KeystoreError> {
+        let state = match keystore.retrieve_secret(KEYSTORE_FEE_RELAYER_KEY) {
+            Ok(raw) => serde_json::from_slice(&raw).unwrap_or_default(),
+            Err(KeystoreError::NotFound) => PersistedRelayerState::default(),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Self {
+            state: Mutex::new(state),
+            last_health: Mutex::new(None),
+            client: reqwest::Client::new(),
+        })
+    }
+
+    fn persist(&self, keystore: &Keystore) -> Result<(), KeystoreError> {
+        let guard = self.state.lock().map_err(|_| KeystoreError::LockError)?;
+        let data = serde_json::to_vec(&*guard).map_err(|_| KeystoreError::SerializationError)?;
+        keystore.store_secret(KEYSTORE_FEE_RELAYER_KEY, &data)
+    }
+
+    pub fn config(&self) -> Result<FeeRelayerConfig, String> {
+        let guard = self.state.lock().map_err(|e| e.to_string())?;
+        Ok(guard.config.clone())
+    }
+
+    pub fn status(&self) -> Result<RelayerStatus, String> {
+        let mut guard = self.state.lock().map_err(|e| e.to_string())?;
+        guard.spending.roll_over(Utc::now().date_naive());
+        let last_health = self.last_health.lock().map_err(|e| e.to_string())?.clone();
+
+        Ok(RelayerStatus {
+            remaining_daily_sol: (guard.config.daily_cap_sol - guard.spending.sponsored_fees_sol)
+                .max(0.0),
+            remaining_transactions: guard
+                .config
+                .max_transactions_per_day
+                .saturating_sub(guard.spending.transactions),
+            config: guard.config.clone(),
+            spending: guard.spending.clone(),
+            last_health,
+        })
+    }
+
+    pub fn update_config(
+        &self,
+        config: FeeRelayerConfig,
+        keystore: &Keystore,
+    ) -> Result<FeeRelayerConfig, String> {
+        if config.enabled {
+            let url = reqwest::Url::parse(&config.endpoint)
+                .map_err(|e| format!("Invalid relayer endpoint: {e}"))?;
+            if url.scheme() != "https" && url.host_str() != Some("localhost") {
+                return Err("Relayer endpoint must use https".to_string());
+            }
+        }
+        if config.max_fee_per_transaction_sol < 0.0 || config.daily_cap_sol < 0.0 {
+            return Err("Relayer spending caps must not be negative".to_string());
+        }
+
+        {
+            let mut guard = self.state.lock().map_err(|e| e.to_string())?;
+            guard.config = config.clone();
+        }
+        self.persist(keystore).map_err(|e| e.to_string())?;
+        Ok(config)
+    }
+
+    /// Pings the relayer's config endpoint and records the advertised fee
+    /// payer.
+    pub async fn health_check(&self, keystore: &Keystore) -> Result<RelayerHealth, String> {
+        let config = self.config()?;
+        if config.endpoint.is_empty() {
+            return Err("No fee relayer endpoint configured".to_string());
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RelayerInfo {
+            fee_payer: Option<String>,
+        }
+
+        let started = Instant::now();
+        let mut request = self
+            .client
+            .get(format!("{}/api", config.endpoint.trim_end_matches('/')))
+            .timeout(HEALTH_CHECK_TIMEOUT);
+        if let Some(api_key) = &config.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let result = match request.send().await {
+            Ok(response) if response.status().is_success() => response
+                .json::<RelayerInfo>()
+                .await
+                .map_err(|e| format!("Failed to parse relayer response: {e}")),
+            Ok(response) => Err(format!("Relayer returned {}", response.status())),
+            Err(e) => Err(format!("Relayer request failed: {e}")),
+        };
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        let health = match result {
+            Ok(info) => RelayerHealth {
+                healthy: info.fee_payer.is_some(),
+                latency_ms: Some(latency_ms),
+                error: info
+                    .fee_payer
+                    .is_none()
+                    .then(|| "Relayer did not advertise a fee payer".to_string()),
+                fee_payer: info.fee_payer,
+                checked_at: Utc::now(),
+            },
+            Err(error) => RelayerHealth {
+                healthy: false,
+                latency_ms: None,
+                fee_payer: None,
+                error: Some(error),
+                checked_at: Utc::now(),
+            },
+        };
+
+        if let Some(fee_payer) = &health.fee_payer {
+            let changed = {
+                let mut guard = self.state.lock().map_err(|e| e.to_string())?;
+                let changed = guard.config.fee_payer.as_ref() != Some(fee_payer);
+                guard.config.fee_payer = Some(fee_payer.clone());
+                changed
+            };
+            if changed {
+                self.persist(keystore).map_err(|e| e.to_string())?;
+            }
+        }
+
+        *self.last_health.lock().map_err(|e| e.to_string())? = Some(health.clone());
+        Ok(health)
+    }
+
+    /// Checks that a transfer may be sponsored and returns the fee payer to
+    /// build it with. Does not consume any of the daily budget.
+    pub fn authorize(&self, token_mint: Option<&str>, fee_sol: f64) -> Result<String, String> {
+        let mut guard = self.state.lock().map_err(|e| e.to_string())?;
+        guard.spending.roll_over(Utc::now().date_naive());
+        check_sponsorship(&guard.config, &guard.spending, token_mint, fee_sol)
+    }
+
+    pub fn record_sponsored(&self, fee_sol: f64, keystore: &Keystore) -> Result<(), String> {
+        {
+            let mut guard = self.state.lock().map_err(|e| e.to_string())?;
+            guard.spending.roll_over(Utc::now().date_naive());
+            guard.spending.sponsored_fees_sol += fee_sol;
+            guard.spending.transactions += 1;
+        }
+        self.persist(keystore).map_err(|e| e.to_string())
+    }
+
+    /// Hands a user-signed transaction (base64, fee payer signature missing)
+    /// to the relayer, which co-signs and broadcasts it.
+    pub async fn submit(&self, transaction_base64: &str) -> Result<String, String> {
+        let config = self.config()?;
+
+        #[derive(Deserialize)]
+        struct SubmitResponse {
+            signature: String,
+        }
+
+        let mut request = self
+            .client
+            .post(format!(
+                "{}/api/transfer",
+                config.endpoint.trim_end_matches('/')
+            ))
+            .timeout(SUBMIT_TIMEOUT)
+            .json(&serde_json::json!({ "transaction": transaction_base64 }));
+        if let Some(api_key) = &config.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Relayer request failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Relayer rejected transaction: {}",
+                response.status()
+            ));
+        }
+
+        response
+            .json::<SubmitResponse>()
+            .await
+            .map(|body| body.signature)
+            .map_err(|e| format!("Failed to parse relayer response: {e}"))
+    }
+}
+
+/// Builds an SPL `TransferChecked` between the sender's and recipient's
+/// associated token accounts with the relayer as fee payer. The wallet adds
+/// the owner signature; the relayer adds its own when it broadcasts.
+pub async fn build_relayed_token_transfer(
+    pool: &SharedRpcPool,
+    fee_payer: &str,
+    owner: &str,
+    recipient: &str,
+    mint: &str,
+    amount: f64,
+    memo: Option<&str>,
+) -> Result<RelayedTransferDraft, String> {
+    if !amount.is_finite() || amount <= 0.0 {
+        return Err("Transfer amount must be positive".to_string());
+    }
+    let fee_payer_key = parse_pubkey("fee payer", fee_payer)?;
+    let owner_key = parse_pubkey("sender", owner)?;
+    let recipient_key = parse_pubkey("recipient", recipient)?;
+    let mint_key = parse_pubkey("token mint", mint)?;
+
+    let mint_account = RpcPool::call(pool, RoutingHint::Read, move |client| {
+        client.get_account(&mint_key)
+    })
+    .await?;
+    let token_program = mint_account.owner;
+    let token_program_id = token_program.to_string();
+    if token_program_id != TOKEN_PROGRAM_ID && token_program_id != TOKEN_2022_PROGRAM_ID {
+        return Err(format!("{} is not an SPL token mint", mint));
+    }
+    let decimals = *mint_account
+        .data
+        .get(MINT_DECIMALS_OFFSET)
+        .ok_or_else(|| "Mint account data is too short".to_string())?;
+
+    let source = associated_token_address(&owner_key, &mint_key, &token_program)?;
+    let destination = associated_token_address(&recipient_key, &mint_key, &token_program)?;
+    let destination_exists = RpcPool::call(pool, RoutingHint::Read, move |client| {
+        client
+            .get_account_with_commitment(&destination, CommitmentConfig::confirmed())
+            .map(|response| response.value.is_some())
+    })
+    .await?;
+    if !destination_exists {
+        return Err(
+            "Recipient has no token account for this mint; relayed transfers cannot create one"
+                .to_string(),
+        );
+    }
+
+    let raw_amount = (amount * 10f64.powi(decimals as i32)).round();
+    if raw_amount < 1.0 || raw_amount >= u64::MAX as f64 {
+        return Err("Transfer amount is out of range for this token".to_string());
+    }
+    let mut data = vec![TRANSFER_CHECKED_TAG];
+    data.extend_from_slice(&(raw_amount as u64).to_le_bytes());
+    data.push(decimals);
+
+    let mut instructions = vec![Instruction {
+        program_id: token_program,
+        accounts: vec![
+            AccountMeta::new(source, false),
+            AccountMeta::new_readonly(mint_key, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(owner_key, true),
+        ],
+        data,
+    }];
+    if let Some(memo) = memo.map(str::trim).filter(|m| !m.is_empty()) {
+        instructions.push(Instruction {
+            program_id: parse_pubkey("memo program", MEMO_PROGRAM_ID)?,
+            accounts: vec![AccountMeta::new_readonly(owner_key, true)],
+            data: memo.as_bytes().to_vec(),
+        });
+    }
+
+    let (blockhash, last_valid_block_height) = RpcPool::call(pool, RoutingHint::Read, |client| {
+        client.get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())
+    })
+    .await?;
+    let message = Message::new_with_blockhash(&instructions, Some(&fee_payer_key), &blockhash);
+    let transaction = VersionedTransaction {
+        signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+        message: VersionedMessage::Legacy(message),
+    };
+    let bytes = bincode::serialize(&transaction).map_err(|e| e.to_string())?;
+
+    Ok(RelayedTransferDraft {
+        transaction_base64: STANDARD.encode(bytes),
+        last_valid_block_height,
+        fee_payer: fee_payer.to_string(),
+    })
+}
+
+/// Rejects a wallet-signed transaction whose fee payer is not the relayer,
+/// before it is sent anywhere or counted against the caps.
+pub fn ensure_fee_payer(transaction_base64: &str, fee_payer: &str) -> Result<(), String> {
+    let bytes = STANDARD
+        .decode(transaction_base64.trim())
+        .map_err(|e| format!("Invalid transaction encoding: {e}"))?;
+    let transaction: VersionedTransaction =
+        bincode::deserialize(&bytes).map_err(|e| format!("Invalid transaction: {e}"))?;
+    let payer = transaction
+        .message
+        .static_account_keys()
+        .first()
+        .ok_or_else(|| "Transaction has no fee payer".to_string())?;
+    if payer.to_string() != fee_payer {
+        return Err(format!(
+            "Transaction fee payer {} is not the relayer's {}",
+            payer, fee_payer
+        ));
+    }
+    Ok(())
+}
+
+fn parse_pubkey(label: &str, value: &str) -> Result<Pubkey, String> {
+    Pubkey::from_str(value.trim()).map_err(|e| format!("Invalid {label}: {e}"))
+}
+
+fn associated_token_address(
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Pubkey, String> {
+    let program = parse_pubkey("associated token program", ASSOCIATED_TOKEN_PROGRAM_ID)?;
+    let (address, _) = Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &program,
+    );
+    Ok(address)
+}
+
+fn check_sponsorship(
+    config: &FeeRelayerConfig,
+    spending: &RelayerSpending,
+    token_mint: Option<&str>,
+    fee_sol: f64,
+) -> Result<String, String> {
+    if !config.enabled {
+        return Err("Fee relayer is not enabled".to_string());
+    }
+    let fee_payer = config
+        .fee_payer
+        .clone()
+        .ok_or_else(|| "Fee relayer has no fee payer; run a health check first".to_string())?;
+
+    let mint =
+        token_mint.ok_or_else(|| "Fee relayer only sponsors SPL token transfers".to_string())?;
+    if !config.allowed_mints.is_empty() && !config.allowed_mints.iter().any(|m| m == mint) {
+        return Err(format!("Fee relayer does not accept token {}", mint));
+    }
+    if fee_sol > config.max_fee_per_transaction_sol {
+        return Err(format!(
+            "Fee of {:.6} SOL exceeds the per-transaction relayer cap of {:.6} SOL",
+            fee_sol, config.max_fee_per_transaction_sol
+        ));
+    }
+    if spending.transactions >= config.max_transactions_per_day {
+        return Err("Daily relayer transaction limit reached".to_string());
+    }
+    if spending.sponsored_fees_sol + fee_sol > config.daily_cap_sol {
+        return Err("Daily relayer spending cap reached".to_string());
+    }
+
+    Ok(fee_payer)
+}
+
+#[tauri::command]
+pub async fn fee_relayer_get_status(
+    relayer: State<'_, FeeRelayerManager>,
+) -> Result<RelayerStatus, AppError> {
+    relayer.status().logged("fee_relayer_get_status")
+}
+
+#[tauri::command]
+pub async fn fee_relayer_update_config(
+    config: FeeRelayerConfig,
+    relayer: State<'_, FeeRelayerManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<FeeRelayerConfig, AppError> {
+    relayer
+        .update_config(config, &keystore)
+        .logged("fee_relayer_update_config")
+}
+
+#[tauri::command]
+pub async fn fee_relayer_health_check(
+    relayer: State<'_, FeeRelayerManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<RelayerHealth, AppError> {
+    relayer
+        .health_check(&keystore)
+        .await
+        .logged("fee_relayer_health_check")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    fn enabled_config() -> FeeRelayerConfig {
+        FeeRelayerConfig {
+            enabled: true,
+            endpoint: "https://relayer.example.com".to_string(),
+            fee_payer: Some("FeePayer1111111111111111111111111111111111".to_string()),
+            allowed_mints: vec![USDC.to_string()],
+            ..FeeRelayerConfig::default()
+        }
+    }
+
+    #[test]
+    fn sponsors_allowed_spl_transfers_within_caps() {
+        let config = enabled_config();
+        let spending = RelayerSpending::default();
+        assert!(check_sponsorship(&config, &spending, Some(USDC), 0.00001).is_ok());
+        assert!(check_sponsorship(&config, &spending, None, 0.00001).is_err());
+        assert!(check_sponsorship(&config, &spending, Some("OtherMint"), 0.00001).is_err());
+        assert!(check_sponsorship(&config, &spending, Some(USDC), 0.5).is_err());
+    }
+
+    #[test]
+    fn only_transactions_paid_by_the_relayer_are_accepted() {
+        let relayer = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let instruction = Instruction {
+            program_id: Pubkey::from_str(TOKEN_PROGRAM_ID).unwrap(),
+            accounts: vec![AccountMeta::new_readonly(owner, true)],
+            data: vec![TRANSFER_CHECKED_TAG],
+        };
+        let encode = |payer: &Pubkey| {
+            let message = Message::new(&[instruction.clone()], Some(payer));
+            let transaction = VersionedTransaction {
+                signatures: vec![Signature::default(); 2],
+                message: VersionedMessage::Legacy(message),
+            };
+            STANDARD.encode(bincode::serialize(&transaction).unwrap())
+        };
+
+        assert!(ensure_fee_payer(&encode(&relayer), &relayer.to_string()).is_ok());
+        assert!(ensure_fee_payer(&encode(&owner), &relayer.to_string()).is_err());
+        assert!(ensure_fee_payer("not base64!", &relayer.to_string()).is_err());
+    }
+
+    #[test]
+    fn daily_budget_resets_on_a_new_day() {
+        let config = enabled_config();
+        let mut spending = RelayerSpending {
+            day: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            sponsored_fees_sol: config.daily_cap_sol,
+            transactions: 3,
+        };
+        assert!(check_sponsorship(&config, &spending, Some(USDC), 0.00001).is_err());
+
+        spending.roll_over(NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+        assert_eq!(spending.transactions, 0);
+        assert!(check_sponsorship(&config, &spending, Some(USDC), 0.00001).is_ok());
+    }
+}
@@ -0,0 +1,353 @@
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+
+use crate::errors::{AppError, CommandResultExt};
+use crate::profiles::ProfilePaths;
+use crate::wallet::display_names::{DisplayNameSource, SharedDisplayNameResolver};
+use crate::wallet::multi_wallet::MultiWalletManager;
+
+const FLOWS_FILE: &str = "wallet_flows.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FlowKind {
+    /// Funds arriving from outside the user's registered wallets.
+    Deposit,
+    /// Funds leaving to an address the user does not own.
+    Withdrawal,
+    /// A move between two of the user's own wallets.
+    InternalTransfer,
+    /// One leg of a swap.
+    Trade,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FlowDirection {
+    In,
+    Out,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletFlow {
+    pub id: String,
+    pub wallet_address: String,
+    pub signature: String,
+    pub timestamp: DateTime<Utc>,
+    pub kind: FlowKind,
+    pub direction: FlowDirection,
+    pub mint: String,
+    pub symbol: String,
+    pub amount: f64,
+    pub usd_value: Option<f64>,
+    pub counterparty: Option<String>,
+    /// Resolved when flows are listed so renames apply to existing history.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counterparty_name: Option<String>,
+    /// Set when the user overrode the automatic classification.
+    #[serde(default)]
+    pub retagged: bool,
+}
+
+impl WalletFlow {
+    pub fn flow_id(
+        wallet_address: &str,
+        signature: &str,
+        mint: &str,
+        direction: FlowDirection,
+    ) -> String {
+        let direction = match direction {
+            FlowDirection::In => "in",
+            FlowDirection::Out => "out",
+        };
+        format!("{wallet_address}:{signature}:{mint}:{direction}")
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowSummary {
+    pub deposits_usd: f64,
+    pub withdrawals_usd: f64,
+    pub internal_transfers_usd: f64,
+    /// Deposits minus withdrawals; internal transfers cancel out.
+    pub net_external_flow_usd: f64,
+    pub unpriced_flows: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowAdjustedPnl {
+    pub raw_pnl: f64,
+    pub net_external_flow: f64,
+    pub pnl: f64,
+    pub pnl_percentage: f64,
+}
+
+/// Tags a transfer by whether any address on the other side of it is one
+/// of the user's own wallets.
+pub fn tag_transfer(
+    direction: FlowDirection,
+    counterparties: &[String],
+    own_wallets: &HashSet<String>,
+) -> (FlowKind, Option<String>) {
+    if let Some(own) = counterparties.iter().find(|c| own_wallets.contains(*c)) {
+        return (FlowKind::InternalTransfer, Some(own.clone()));
+    }
+    let kind = match direction {
+        FlowDirection::In => FlowKind::Deposit,
+        FlowDirection::Out => FlowKind::Withdrawal,
+    };
+    (kind, counterparties.first().cloned())
+}
+
+/// Change in value over a period with deposits and withdrawals taken out,
+/// so funding a wallet is not reported as profit. The percentage is
+/// measured against the starting value plus any net new money.
+pub fn flow_adjusted_pnl(
+    start_value: f64,
+    end_value: f64,
+    net_external_flow: f64,
+) -> FlowAdjustedPnl {
+    let raw_pnl = end_value - start_value;
+    let pnl = raw_pnl - net_external_flow;
+    let invested = start_value + net_external_flow.max(0.0);
+    FlowAdjustedPnl {
+        raw_pnl,
+        net_external_flow,
+        pnl,
+        pnl_percentage: if invested > 0.0 {
+            pnl / invested * 100.0
+        } else {
+            0.0
+        },
+    }
+}
+
+pub struct FlowLedger {
+    flows: HashMap<String, WalletFlow>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedFlowLedger = Arc<RwLock<FlowLedger>>;
+
+impl FlowLedger {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        let path = app_handle
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(FLOWS_FILE));
+        let flows = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self { flows, path }
+    }
+
+    /// Adds flows not seen before. Existing entries, including any the
+    /// user retagged, are left alone.
+    pub fn record(&mut self, flows: Vec<WalletFlow>) {
+        for flow in flows {
+            self.flows.entry(flow.id.clone()).or_insert(flow);
+        }
+    }
+
+    pub fn list(
+        &self,
+        wallet_address: Option<&str>,
+        since: Option<DateTime<Utc>>,
+    ) -> Vec<WalletFlow> {
+        let mut flows: Vec<WalletFlow> = self
+            .flows
+            .values()
+            .filter(|f| wallet_address.map_or(true, |w| f.wallet_address == w))
+            .filter(|f| since.map_or(true, |since| f.timestamp >= since))
+            .cloned()
+            .collect();
+        flows.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        flows
+    }
+
+    pub fn summary(
+        &self,
+        wallet_address: Option<&str>,
+        since: Option<DateTime<Utc>>,
+    ) -> FlowSummary {
+        let mut summary = FlowSummary::default();
+        for flow in self.list(wallet_address, since) {
+            let Some(value) = flow.usd_value else {
+                if flow.kind != FlowKind::Trade {
+                    summary.unpriced_flows += 1;
+                }
+                continue;
+            };
+            match flow.kind {
+                FlowKind::Deposit => summary.deposits_usd += value,
+                FlowKind::Withdrawal => summary.withdrawals_usd += value,
+                // Both legs are recorded when both wallets are tracked;
+                // count the outgoing one only.
+                FlowKind::InternalTransfer if flow.direction == FlowDirection::Out => {
+                    summary.internal_transfers_usd += value
+                }
+                _ => {}
+            }
+        }
+        summary.net_external_flow_usd = summary.deposits_usd - summary.withdrawals_usd;
+        summary
+    }
+
+    pub fn retag(&mut self, id: &str, kind: FlowKind) -> Result<WalletFlow, String> {
+        let flow = self
+            .flows
+            .get_mut(id)
+            .ok_or_else(|| format!("Flow {id} not found"))?;
+        let valid = match (flow.direction, kind) {
+            (_, FlowKind::Trade | FlowKind::InternalTransfer) => true,
+            (FlowDirection::In, FlowKind::Deposit) | (FlowDirection::Out, FlowKind::Withdrawal) => {
+                true
+            }
+            _ => false,
+        };
+        if !valid {
+            return Err("A deposit must be incoming and a withdrawal outgoing".to_string());
+        }
+        flow.kind = kind;
+        flow.retagged = true;
+        Ok(flow.clone())
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let contents = serde_json::to_string(&self.flows).map_err(|e| e.to_string())?;
+        fs::write(path, contents).map_err(|e| e.to_string())
+    }
+}
+
+/// Public keys of every wallet in the multi-wallet registry.
+pub fn own_wallet_addresses(app: &AppHandle) -> HashSet<String> {
+    app.try_state::<MultiWalletManager>()
+        .and_then(|manager| manager.list_wallets().ok())
+        .map(|wallets| wallets.into_iter().map(|w| w.public_key).collect())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub async fn wallet_flows_list(
+    app: AppHandle,
+    wallet_address: Option<String>,
+    since: Option<DateTime<Utc>>,
+    ledger: State<'_, SharedFlowLedger>,
+    resolver: State<'_, SharedDisplayNameResolver>,
+) -> Result<Vec<WalletFlow>, AppError> {
+    let mut flows = ledger.read().list(wallet_address.as_deref(), since);
+    let counterparties: Vec<String> = flows
+        .iter()
+        .filter_map(|f| f.counterparty.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    if counterparties.is_empty() {
+        return Ok(flows);
+    }
+    let names: HashMap<String, String> = resolver
+        .resolve(&app, &counterparties, true)
+        .await
+        .into_iter()
+        .filter(|n| n.source != DisplayNameSource::Address)
+        .map(|n| (n.address, n.display_name))
+        .collect();
+    for flow in &mut flows {
+        flow.counterparty_name = flow
+            .counterparty
+            .as_ref()
+            .and_then(|c| names.get(c).cloned());
+    }
+    Ok(flows)
+}
+
+#[tauri::command]
+pub async fn wallet_flows_summary(
+    wallet_address: Option<String>,
+    since: Option<DateTime<Utc>>,
+    ledger: State<'_, SharedFlowLedger>,
+) -> Result<FlowSummary, AppError> {
+    Ok(ledger.read().summary(wallet_address.as_deref(), since))
+}
+
+#[tauri::command]
+pub async fn wallet_flow_retag(
+    id: String,
+    kind: FlowKind,
+    ledger: State<'_, SharedFlowLedger>,
+) -> Result<WalletFlow, AppError> {
+    let mut ledger = ledger.write();
+    let flow = ledger
+        .retag(&id, kind)
+        .map_err(AppError::Validation)
+        .logged("wallet_flow_retag")?;
+    ledger.save().logged("wallet_flow_retag")?;
+    Ok(flow)
+}
+
+#[tauri::command]
+pub async fn wallet_flows_adjusted_pnl(
+    start_value: f64,
+    end_value: f64,
+    since: DateTime<Utc>,
+    wallet_address: Option<String>,
+    ledger: State<'_, SharedFlowLedger>,
+) -> Result<FlowAdjustedPnl, AppError> {
+    let summary = ledger
+        .read()
+        .summary(wallet_address.as_deref(), Some(since));
+    Ok(flow_adjusted_pnl(
+        start_value,
+        end_value,
+        summary.net_external_flow_usd,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfers_to_own_wallets_are_internal() {
+        let own = HashSet::from(["WalletB".to_string()]);
+        let (kind, counterparty) = tag_transfer(
+            FlowDirection::Out,
+            &["Stranger".to_string(), "WalletB".to_string()],
+            &own,
+        );
+        assert_eq!(kind, FlowKind::InternalTransfer);
+        assert_eq!(counterparty.as_deref(), Some("WalletB"));
+
+        let (kind, _) = tag_transfer(FlowDirection::In, &["Exchange".to_string()], &own);
+        assert_eq!(kind, FlowKind::Deposit);
+        let (kind, _) = tag_transfer(FlowDirection::Out, &[], &own);
+        assert_eq!(kind, FlowKind::Withdrawal);
+    }
+
+    #[test]
+    fn deposits_are_not_profit() {
+        // Started with $1,000, deposited $500, ended at $1,650.
+        let pnl = flow_adjusted_pnl(1_000.0, 1_650.0, 500.0);
+        assert_eq!(pnl.raw_pnl, 650.0);
+        assert_eq!(pnl.pnl, 150.0);
+        assert!((pnl.pnl_percentage - 10.0).abs() < 1e-9);
+
+        // A withdrawal is not a loss.
+        assert_eq!(flow_adjusted_pnl(1_000.0, 700.0, -300.0).pnl, 0.0);
+    }
+}
@@ -1,3 +1,5 @@
+use super::ledger::LedgerState;
+use super::trezor::{is_trezor_device_id, TrezorBackend};
 use base64::engine::general_purpose::STANDARD as BASE64_ENGINE;
 use base64::Engine;
 use serde::{Deserialize, Serialize};
@@ -27,6 +29,8 @@ pub enum HardwareWalletError {
     FirmwareOutdated(String),
     #[error("Wrong app opened on device")]
     WrongApp,
+    #[error("Unsupported operation: {0}")]
+    UnsupportedOperation(String),
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -111,10 +115,19 @@ pub struct GetAddressResponse {
     pub public_key: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignHardwareMessageRequest {
+    pub device_id: String,
+    pub message: String,
+    pub derivation_path: Option<String>,
+}
+
 #[derive(Default)]
 pub struct HardwareWalletState {
     devices: Mutex<Vec<HardwareWalletDevice>>,
     simulated: Mutex<bool>,
+    trezor: TrezorBackend,
 }
 
 impl HardwareWalletState {
@@ -122,10 +135,59 @@ impl HardwareWalletState {
         Self {
             devices: Mutex::new(Vec::new()),
             simulated: Mutex::new(true),
+            trezor: TrezorBackend::new(),
+        }
+    }
+
+    /// Looks up a connected device, cloned so the lock is not held while
+    /// waiting on the device.
+    async fn connected_device(
+        &self,
+        device_id: &str,
+    ) -> Result<HardwareWalletDevice, HardwareWalletError> {
+        let devices_guard = self.devices.lock().await;
+        let device = devices_guard
+            .iter()
+            .find(|d| d.device_id == device_id)
+            .ok_or(HardwareWalletError::DeviceNotFound)?;
+
+        if !device.connected {
+            return Err(HardwareWalletError::Disconnected);
         }
+
+        Ok(device.clone())
     }
 }
 
+/// Which code path talks to a device. Ledger signing happens in the
+/// frontend over WebHID, so the backend can only report on Ledger devices.
+enum Backend {
+    Simulated,
+    Trezor,
+    Ledger,
+}
+
+async fn backend_for(
+    state: &HardwareWalletState,
+    device: &HardwareWalletDevice,
+) -> Result<Backend, HardwareWalletError> {
+    if *state.simulated.lock().await {
+        return Ok(Backend::Simulated);
+    }
+    match device.device_type {
+        DeviceType::Trezor if is_trezor_device_id(&device.device_id) => Ok(Backend::Trezor),
+        DeviceType::Trezor => Err(HardwareWalletError::DeviceNotFound),
+        DeviceType::Ledger => Ok(Backend::Ledger),
+    }
+}
+
+fn ledger_unsupported(operation: &str) -> HardwareWalletError {
+    HardwareWalletError::UnsupportedOperation(format!(
+        "{} on Ledger runs through the WebHID flow (ledger_* commands)",
+        operation
+    ))
+}
+
 fn parse_derivation_path(path: &str) -> Result<Vec<u32>, HardwareWalletError> {
     let path = path.trim();
     if !path.starts_with("m/") && !path.starts_with("M/") {
@@ -207,6 +269,7 @@ fn generate_deterministic_signature(device_id: &str, transaction: &str) -> Strin
 #[tauri::command]
 pub async fn list_hardware_wallets(
     state: State<'_, HardwareWalletState>,
+    ledger: State<'_, LedgerState>,
 ) -> Result<Vec<HardwareWalletDevice>, HardwareWalletError> {
     let is_simulated = *state.simulated.lock().await;
 
@@ -217,7 +280,38 @@ pub async fn list_hardware_wallets(
         return Ok(devices);
     }
 
-    Ok(Vec::new())
+    let mut devices = ledger.hardware_devices();
+    let mut trezors = state.trezor.enumerate().await;
+
+    // Enumeration knows nothing about app-level connection state, so carry
+    // it over from the previous listing.
+    let mut devices_guard = state.devices.lock().await;
+    for trezor in &mut trezors {
+        if let Some(previous) = devices_guard
+            .iter()
+            .find(|d| d.device_id == trezor.device_id)
+        {
+            trezor.connected = previous.connected;
+            trezor.address = previous.address.clone();
+            if trezor.firmware_version.is_none() {
+                trezor.firmware_version = previous.firmware_version.clone();
+            }
+        }
+    }
+    devices.extend(trezors);
+    *devices_guard = devices.clone();
+
+    Ok(devices)
+}
+
+#[tauri::command]
+pub async fn set_hardware_wallet_simulation(
+    enabled: bool,
+    state: State<'_, HardwareWalletState>,
+) -> Result<(), HardwareWalletError> {
+    *state.simulated.lock().await = enabled;
+    state.devices.lock().await.clear();
+    Ok(())
 }
 
 #[tauri::command]
@@ -225,10 +319,21 @@ pub async fn connect_hardware_wallet(
     device_id: String,
     state: State<'_, HardwareWalletState>,
 ) -> Result<HardwareWalletDevice, HardwareWalletError> {
+    // Talk to a real Trezor before marking it connected, so a locked or
+    // busy device fails here rather than at signing time.
+    let firmware_version = if !*state.simulated.lock().await && is_trezor_device_id(&device_id) {
+        Some(state.trezor.features(&device_id).await?.firmware_string())
+    } else {
+        None
+    };
+
     let mut devices_guard = state.devices.lock().await;
 
     if let Some(device) = devices_guard.iter_mut().find(|d| d.device_id == device_id) {
         device.connected = true;
+        if firmware_version.is_some() {
+            device.firmware_version = firmware_version;
+        }
         return Ok(device.clone());
     }
 
@@ -255,29 +360,42 @@ pub async fn get_hardware_wallet_address(
     request: GetAddressRequest,
     state: State<'_, HardwareWalletState>,
 ) -> Result<GetAddressResponse, HardwareWalletError> {
-    let devices_guard = state.devices.lock().await;
+    let device = state.connected_device(&request.device_id).await?;
+    let path = parse_derivation_path(&request.derivation_path)?;
+
+    let response = match backend_for(&state, &device).await? {
+        Backend::Simulated => {
+            let address =
+                generate_deterministic_address(&request.device_id, &request.derivation_path);
+            let public_key = generate_deterministic_address(
+                &format!("{}-pubkey", request.device_id),
+                &request.derivation_path,
+            );
+            GetAddressResponse {
+                address,
+                public_key,
+            }
+        }
+        Backend::Trezor => {
+            state
+                .trezor
+                .get_address(&request.device_id, &path, request.display)
+                .await?
+        }
+        Backend::Ledger => return Err(ledger_unsupported("Address derivation")),
+    };
 
-    let device = devices_guard
-        .iter()
+    if let Some(device) = state
+        .devices
+        .lock()
+        .await
+        .iter_mut()
         .find(|d| d.device_id == request.device_id)
-        .ok_or(HardwareWalletError::DeviceNotFound)?;
-
-    if !device.connected {
-        return Err(HardwareWalletError::Disconnected);
+    {
+        device.address = Some(response.address.clone());
     }
 
-    parse_derivation_path(&request.derivation_path)?;
-
-    let address = generate_deterministic_address(&request.device_id, &request.derivation_path);
-    let public_key = generate_deterministic_address(
-        &format!("{}-pubkey", request.device_id),
-        &request.derivation_path,
-    );
-
-    Ok(GetAddressResponse {
-        address,
-        public_key,
-    })
+    Ok(response)
 }
 
 #[tauri::command]
@@ -285,42 +403,72 @@ pub async fn sign_with_hardware_wallet(
     request: SignTransactionRequest,
     state: State<'_, HardwareWalletState>,
 ) -> Result<SignTransactionResponse, HardwareWalletError> {
-    let device_id = {
-        let devices_guard = state.devices.lock().await;
-
-        let device = devices_guard
-            .iter()
-            .find(|d| d.device_id == request.device_id)
-            .ok_or(HardwareWalletError::DeviceNotFound)?;
-
-        if !device.connected {
-            return Err(HardwareWalletError::Disconnected);
-        }
-
-        device.device_id.clone()
-    };
+    let device = state.connected_device(&request.device_id).await?;
 
     let default_path = "m/44'/501'/0'/0'".to_string();
     let derivation_path = request.derivation_path.as_ref().unwrap_or(&default_path);
-    parse_derivation_path(derivation_path)?;
+    let path = parse_derivation_path(derivation_path)?;
 
-    let _transaction_bytes = BASE64_ENGINE
+    let transaction_bytes = BASE64_ENGINE
         .decode(request.transaction.as_bytes())
         .map_err(|e| {
             HardwareWalletError::Internal(format!("Invalid transaction encoding: {}", e))
         })?;
 
-    if _transaction_bytes.len() > 65535 {
+    if transaction_bytes.len() > 65535 {
         return Err(HardwareWalletError::TransactionTooLarge);
     }
 
-    tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
-
-    let signature = generate_deterministic_signature(&device_id, &request.transaction);
+    let signature = match backend_for(&state, &device).await? {
+        Backend::Simulated => {
+            tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
+            generate_deterministic_signature(&device.device_id, &request.transaction)
+        }
+        // Trezor signs the serialized transaction message, not the full
+        // wire transaction with its signature slots.
+        Backend::Trezor => {
+            state
+                .trezor
+                .sign_transaction(&device.device_id, &path, &transaction_bytes)
+                .await?
+        }
+        Backend::Ledger => return Err(ledger_unsupported("Transaction signing")),
+    };
 
     Ok(SignTransactionResponse { signature })
 }
 
+#[tauri::command]
+pub async fn sign_message_with_hardware_wallet(
+    request: SignHardwareMessageRequest,
+    state: State<'_, HardwareWalletState>,
+) -> Result<SignTransactionResponse, HardwareWalletError> {
+    let device = state.connected_device(&request.device_id).await?;
+
+    let default_path = "m/44'/501'/0'/0'".to_string();
+    let derivation_path = request.derivation_path.as_ref().unwrap_or(&default_path);
+    parse_derivation_path(derivation_path)?;
+
+    if request.message.is_empty() {
+        return Err(HardwareWalletError::Internal(
+            "Message is empty".to_string(),
+        ));
+    }
+
+    match backend_for(&state, &device).await? {
+        Backend::Simulated => Ok(SignTransactionResponse {
+            signature: generate_deterministic_signature(
+                &device.device_id,
+                &format!("message:{}", request.message),
+            ),
+        }),
+        Backend::Trezor => Err(HardwareWalletError::UnsupportedOperation(
+            "Trezor firmware cannot sign Solana off-chain messages".to_string(),
+        )),
+        Backend::Ledger => Err(ledger_unsupported("Message signing")),
+    }
+}
+
 #[tauri::command]
 pub async fn get_firmware_version(
     device_id: String,
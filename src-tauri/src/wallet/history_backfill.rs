@@ -0,0 +1,995 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::RwLock;
+
+use crate::api_config::stored_birdeye_key;
+use crate::chains::{RoutingHint, RpcPool, SharedRpcPool};
+use crate::errors::{AppError, CommandResultExt};
+use crate::market::birdeye_history::hourly_price_history;
+use crate::portfolio::{SharedTaxLotsState, TaxLot};
+use crate::profiles::ProfilePaths;
+use crate::security::keystore::Keystore;
+use crate::wallet::flows::{
+    own_wallet_addresses, tag_transfer, FlowDirection, FlowKind, SharedFlowLedger, WalletFlow,
+};
+use crate::wallet::performance::{RecordTradeRequest, SharedPerformanceDatabase};
+
+const BACKFILL_FILE: &str = "wallet_backfill.json";
+const SIGNATURE_PAGE_LIMIT: usize = 1000;
+const CHECKPOINT_INTERVAL: u64 = 25;
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+const DUST: f64 = 1e-9;
+/// Creating an associated token account costs ~0.00204 SOL in rent. SOL
+/// movements this small next to a token leg are account setup, not the
+/// other side of a trade.
+const RENT_NOISE_SOL: f64 = 0.0025;
+
+pub(crate) const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const USDT_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
+
+pub const BACKFILL_PROGRESS_EVENT: &str = "wallet_backfill_progress";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackfillStatus {
+    Scanning,
+    Processing,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl BackfillStatus {
+    fn is_running(&self) -> bool {
+        matches!(self, BackfillStatus::Scanning | BackfillStatus::Processing)
+    }
+}
+
+/// Progress of reconstructing a wallet's history. Persisted per wallet so
+/// later runs only pick up transactions newer than
+/// `newest_processed_signature`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillProgress {
+    pub wallet_address: String,
+    pub status: BackfillStatus,
+    pub signatures_found: u64,
+    pub transactions_processed: u64,
+    pub trades_recorded: u64,
+    pub transfers_recorded: u64,
+    /// Transfers to or from another of the user's wallets. These move no
+    /// cost basis and are excluded from `transfers_recorded`.
+    #[serde(default)]
+    pub internal_transfers: u64,
+    /// Trades and transfers for which no historical price was available.
+    /// These are booked at zero cost basis.
+    pub unpriced: u64,
+    pub percent_complete: f64,
+    pub newest_processed_signature: Option<String>,
+    pub oldest_timestamp: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BackfillProgress {
+    fn new(wallet_address: String, previous: Option<&BackfillProgress>) -> Self {
+        let now = Utc::now();
+        Self {
+            wallet_address,
+            status: BackfillStatus::Scanning,
+            signatures_found: 0,
+            transactions_processed: 0,
+            trades_recorded: 0,
+            transfers_recorded: 0,
+            internal_transfers: 0,
+            unpriced: 0,
+            percent_complete: 0.0,
+            newest_processed_signature: previous.and_then(|p| p.newest_processed_signature.clone()),
+            oldest_timestamp: previous.and_then(|p| p.oldest_timestamp),
+            error: None,
+            started_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// A balance change for the backfilled wallet. Native SOL and wrapped SOL
+/// are merged under the wSOL mint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenDelta {
+    pub mint: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HistoricalActivity {
+    Swap {
+        sold: TokenDelta,
+        bought: TokenDelta,
+    },
+    TransferIn(TokenDelta),
+    TransferOut(TokenDelta),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedTransaction {
+    pub signature: String,
+    pub timestamp: DateTime<Utc>,
+    pub fee_sol: f64,
+    pub activities: Vec<HistoricalActivity>,
+    /// Other owners whose balance of a mint moved opposite to the wallet's,
+    /// keyed by mint. Used to tell transfers between the user's own
+    /// wallets apart from deposits and withdrawals.
+    pub counterparties: HashMap<String, Vec<String>>,
+}
+
+/// Reads a `getTransaction` response (`jsonParsed` encoding) into the
+/// wallet's net balance changes. Failed transactions yield `None`.
+pub fn parse_transaction(owner: &str, signature: &str, tx: &Value) -> Option<ParsedTransaction> {
+    let meta = tx.get("meta")?;
+    if meta.get("err").is_some_and(|err| !err.is_null()) {
+        return None;
+    }
+    let timestamp = DateTime::from_timestamp(tx.get("blockTime")?.as_i64()?, 0)?;
+
+    // Net change per (holder, mint) for every party in the transaction.
+    let mut changes: HashMap<(String, String), f64> = HashMap::new();
+    for (sign, field) in [(-1.0, "preTokenBalances"), (1.0, "postTokenBalances")] {
+        for balance in meta[field].as_array().into_iter().flatten() {
+            let (Some(holder), Some(mint), Some(amount)) = (
+                balance["owner"].as_str(),
+                balance["mint"].as_str(),
+                ui_amount(balance),
+            ) else {
+                continue;
+            };
+            *changes
+                .entry((holder.to_string(), mint.to_string()))
+                .or_default() += sign * amount;
+        }
+    }
+
+    let fee = meta["fee"].as_u64().unwrap_or(0);
+    let mut fee_sol = 0.0;
+    for (index, key) in account_keys(tx).into_iter().enumerate() {
+        let pre = meta["preBalances"][index].as_u64().unwrap_or(0) as i128;
+        let mut post = meta["postBalances"][index].as_u64().unwrap_or(0) as i128;
+        // The fee payer is always the first account; its fee is a cost of
+        // the transaction, not part of the transfer.
+        if index == 0 {
+            post += fee as i128;
+            if key == owner {
+                fee_sol = fee as f64 / LAMPORTS_PER_SOL;
+            }
+        }
+        *changes.entry((key, WSOL_MINT.to_string())).or_default() +=
+            (post - pre) as f64 / LAMPORTS_PER_SOL;
+    }
+
+    let mut deltas: Vec<TokenDelta> = changes
+        .iter()
+        .filter(|((holder, _), amount)| holder == owner && amount.abs() > DUST)
+        .map(|((_, mint), amount)| TokenDelta {
+            mint: mint.clone(),
+            amount: *amount,
+        })
+        .collect();
+    deltas.sort_by(|a, b| a.mint.cmp(&b.mint));
+
+    let mut counterparties: HashMap<String, Vec<String>> = HashMap::new();
+    for delta in &deltas {
+        // SOL changes this small on other accounts are rent for token
+        // accounts created along the way.
+        let noise = if delta.mint == WSOL_MINT {
+            RENT_NOISE_SOL
+        } else {
+            DUST
+        };
+        let mut others: Vec<String> = changes
+            .iter()
+            .filter(|((holder, mint), amount)| {
+                holder != owner
+                    && *mint == delta.mint
+                    && amount.abs() > noise
+                    && amount.signum() != delta.amount.signum()
+            })
+            .map(|((holder, _), _)| holder.clone())
+            .collect();
+        if !others.is_empty() {
+            others.sort();
+            counterparties.insert(delta.mint.clone(), others);
+        }
+    }
+
+    Some(ParsedTransaction {
+        signature: signature.to_string(),
+        timestamp,
+        fee_sol,
+        activities: classify(deltas),
+        counterparties,
+    })
+}
+
+/// Pairs one outgoing and one incoming leg into a swap and books every
+/// other movement as a transfer.
+pub fn classify(deltas: Vec<TokenDelta>) -> Vec<HistoricalActivity> {
+    let (sol, tokens): (Vec<_>, Vec<_>) = deltas.into_iter().partition(|d| d.mint == WSOL_MINT);
+    let sol = sol.first().map(|d| d.amount).unwrap_or(0.0);
+    let (mut ins, mut outs): (Vec<_>, Vec<_>) = tokens.into_iter().partition(|d| d.amount > 0.0);
+    let has_tokens = !ins.is_empty() || !outs.is_empty();
+    let sol_leg = |amount: f64| TokenDelta {
+        mint: WSOL_MINT.to_string(),
+        amount,
+    };
+
+    let mut activities = Vec::new();
+    let mut sol_used = false;
+    let swap = if !outs.is_empty() && !ins.is_empty() {
+        Some((outs.remove(0), ins.remove(0)))
+    } else if !ins.is_empty() && sol < -RENT_NOISE_SOL {
+        sol_used = true;
+        Some((sol_leg(sol), ins.remove(0)))
+    } else if !outs.is_empty() && sol > RENT_NOISE_SOL {
+        sol_used = true;
+        Some((outs.remove(0), sol_leg(sol)))
+    } else {
+        None
+    };
+    if let Some((sold, bought)) = swap {
+        activities.push(HistoricalActivity::Swap {
+            sold: TokenDelta {
+                amount: sold.amount.abs(),
+                ..sold
+            },
+            bought,
+        });
+    }
+
+    for delta in ins {
+        activities.push(HistoricalActivity::TransferIn(delta));
+    }
+    for delta in outs {
+        activities.push(HistoricalActivity::TransferOut(TokenDelta {
+            amount: delta.amount.abs(),
+            ..delta
+        }));
+    }
+    if !sol_used && sol.abs() > DUST && (!has_tokens || sol.abs() > RENT_NOISE_SOL) {
+        if sol > 0.0 {
+            activities.push(HistoricalActivity::TransferIn(sol_leg(sol)));
+        } else {
+            activities.push(HistoricalActivity::TransferOut(sol_leg(-sol)));
+        }
+    }
+    activities
+}
+
+fn account_keys(tx: &Value) -> Vec<String> {
+    tx["transaction"]["message"]["accountKeys"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|key| {
+            key.as_str()
+                .or_else(|| key["pubkey"].as_str())
+                .map(|key| key.to_string())
+        })
+        .collect()
+}
+
+fn ui_amount(balance: &Value) -> Option<f64> {
+    let ui = &balance["uiTokenAmount"];
+    ui["uiAmountString"]
+        .as_str()
+        .and_then(|amount| amount.parse().ok())
+        .or_else(|| {
+            let raw: f64 = ui["amount"].as_str()?.parse().ok()?;
+            Some(raw / 10f64.powi(ui["decimals"].as_u64()? as i32))
+        })
+}
+
+fn is_stable(mint: &str) -> bool {
+    mint == USDC_MINT || mint == USDT_MINT
+}
+
+pub(crate) fn symbol_for(mint: &str) -> String {
+    match mint {
+        WSOL_MINT => "SOL".to_string(),
+        USDC_MINT => "USDC".to_string(),
+        USDT_MINT => "USDT".to_string(),
+        other => other.chars().take(6).collect(),
+    }
+}
+
+/// Historical USD prices from Birdeye, bucketed by hour.
+pub(crate) struct PriceOracle {
+    client: reqwest::Client,
+    api_key: Option<String>,
+    cache: HashMap<(String, i64), Option<f64>>,
+}
+
+impl PriceOracle {
+    pub(crate) fn new(api_key: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key,
+            cache: HashMap::new(),
+        }
+    }
+
+    pub(crate) async fn price_at(&mut self, mint: &str, at: DateTime<Utc>) -> Option<f64> {
+        if is_stable(mint) {
+            return Some(1.0);
+        }
+        let api_key = self.api_key.clone()?;
+        let bucket = at.timestamp() / 3600;
+        if let Some(price) = self.cache.get(&(mint.to_string(), bucket)) {
+            return *price;
+        }
+
+        let price = hourly_price_history(
+            &self.client,
+            &api_key,
+            mint,
+            bucket * 3600,
+            (bucket + 1) * 3600,
+        )
+        .await
+        .ok()
+        .and_then(|items| items.first().map(|(_, price)| *price));
+        self.cache.insert((mint.to_string(), bucket), price);
+        price
+    }
+
+    /// USD value of a swap, taken from the leg that is easiest to price:
+    /// stablecoins first, then SOL, then either token.
+    async fn swap_value(
+        &mut self,
+        sold: &TokenDelta,
+        bought: &TokenDelta,
+        at: DateTime<Utc>,
+    ) -> Option<f64> {
+        let mut legs = [sold, bought];
+        legs.sort_by_key(|leg| match leg.mint.as_str() {
+            mint if is_stable(mint) => 0,
+            WSOL_MINT => 1,
+            _ => 2,
+        });
+        for leg in legs {
+            if let Some(price) = self.price_at(&leg.mint, at).await {
+                return Some(price * leg.amount);
+            }
+        }
+        None
+    }
+}
+
+#[derive(Default)]
+struct ApplyOutcome {
+    trades: u64,
+    transfers: u64,
+    internal_transfers: u64,
+    unpriced: u64,
+}
+
+pub struct WalletBackfillService {
+    progress: RwLock<HashMap<String, BackfillProgress>>,
+    cancelled: RwLock<HashSet<String>>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedWalletBackfill = Arc<WalletBackfillService>;
+
+impl WalletBackfillService {
+    pub fn new(app_handle: &AppHandle) -> Self {
+        let path = app_handle
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(BACKFILL_FILE));
+        let mut progress: HashMap<String, BackfillProgress> = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        // Runs interrupted by a shutdown resume from their checkpoint when
+        // restarted.
+        for entry in progress.values_mut() {
+            if entry.status.is_running() {
+                entry.status = BackfillStatus::Cancelled;
+            }
+        }
+
+        Self {
+            progress: RwLock::new(progress),
+            cancelled: RwLock::new(HashSet::new()),
+            path,
+        }
+    }
+
+    pub async fn status(&self, wallet_address: &str) -> Option<BackfillProgress> {
+        self.progress.read().await.get(wallet_address).cloned()
+    }
+
+    pub async fn list(&self) -> Vec<BackfillProgress> {
+        let mut entries: Vec<_> = self.progress.read().await.values().cloned().collect();
+        entries.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        entries
+    }
+
+    /// Starts reconstructing `wallet_address`'s history in the background.
+    /// Already-processed transactions are skipped, so this also serves to
+    /// resume an interrupted run or catch up after time offline.
+    pub async fn start(
+        self: &Arc<Self>,
+        app: &AppHandle,
+        wallet_address: &str,
+    ) -> Result<BackfillProgress, String> {
+        Pubkey::from_str(wallet_address).map_err(|e| format!("Invalid wallet address: {e}"))?;
+
+        let progress = {
+            let mut guard = self.progress.write().await;
+            let previous = guard.get(wallet_address);
+            if previous.is_some_and(|p| p.status.is_running()) {
+                return Err(format!("Backfill already running for {wallet_address}"));
+            }
+            let progress = BackfillProgress::new(wallet_address.to_string(), previous);
+            guard.insert(wallet_address.to_string(), progress.clone());
+            progress
+        };
+        self.cancelled.write().await.remove(wallet_address);
+        let _ = app.emit(BACKFILL_PROGRESS_EVENT, &progress);
+
+        let service = self.clone();
+        let app = app.clone();
+        let wallet_address = wallet_address.to_string();
+        tauri::async_runtime::spawn(async move {
+            if let Err(error) = service.run(&app, &wallet_address).await {
+                service
+                    .update(&app, &wallet_address, |progress| {
+                        progress.status = BackfillStatus::Failed;
+                        progress.error = Some(error.clone());
+                    })
+                    .await;
+            }
+            if let Err(error) = service.save().await {
+                eprintln!("Failed to persist backfill progress: {error}");
+            }
+        });
+
+        Ok(progress)
+    }
+
+    pub async fn cancel(&self, wallet_address: &str) {
+        self.cancelled
+            .write()
+            .await
+            .insert(wallet_address.to_string());
+    }
+
+    async fn is_cancelled(&self, wallet_address: &str) -> bool {
+        self.cancelled.read().await.contains(wallet_address)
+    }
+
+    async fn run(&self, app: &AppHandle, wallet_address: &str) -> Result<(), String> {
+        let pool = app.state::<SharedRpcPool>().inner().clone();
+        let performance = app.state::<SharedPerformanceDatabase>().inner().clone();
+        let flows = app.state::<SharedFlowLedger>().inner().clone();
+        let own_wallets = own_wallet_addresses(app);
+        let mut oracle = PriceOracle::new(stored_birdeye_key(&app.state::<Keystore>()));
+
+        let until = self
+            .status(wallet_address)
+            .await
+            .and_then(|p| p.newest_processed_signature);
+        let mut signatures = Vec::new();
+        let mut before: Option<String> = None;
+        loop {
+            if self.is_cancelled(wallet_address).await {
+                return self.mark_cancelled(app, wallet_address).await;
+            }
+            let page =
+                fetch_signature_page(&pool, wallet_address, before.clone(), until.clone()).await?;
+            let page_len = page.len();
+            before = page.last().map(|(signature, _)| signature.clone());
+            signatures.extend(
+                page.into_iter()
+                    .filter(|(_, succeeded)| *succeeded)
+                    .map(|(signature, _)| signature),
+            );
+            let found = signatures.len() as u64;
+            self.update(app, wallet_address, |progress| {
+                progress.signatures_found = found
+            })
+            .await;
+            if page_len < SIGNATURE_PAGE_LIMIT {
+                break;
+            }
+        }
+
+        // Replay oldest first so sells are matched against earlier buys.
+        signatures.reverse();
+        let total = signatures.len() as u64;
+        self.update(app, wallet_address, |progress| {
+            progress.status = BackfillStatus::Processing;
+        })
+        .await;
+
+        for (index, signature) in signatures.into_iter().enumerate() {
+            if self.is_cancelled(wallet_address).await {
+                return self.mark_cancelled(app, wallet_address).await;
+            }
+
+            let tx = fetch_transaction(&pool, &signature).await?;
+            let mut outcome = ApplyOutcome::default();
+            let mut timestamp = None;
+            if let Some(parsed) = tx
+                .as_ref()
+                .and_then(|tx| parse_transaction(wallet_address, &signature, tx))
+            {
+                timestamp = Some(parsed.timestamp);
+                outcome = apply(
+                    app,
+                    &performance,
+                    &flows,
+                    &own_wallets,
+                    &mut oracle,
+                    wallet_address,
+                    &parsed,
+                )
+                .await?;
+            }
+
+            let processed = index as u64 + 1;
+            self.update(app, wallet_address, |progress| {
+                progress.transactions_processed = processed;
+                progress.trades_recorded += outcome.trades;
+                progress.transfers_recorded += outcome.transfers;
+                progress.internal_transfers += outcome.internal_transfers;
+                progress.unpriced += outcome.unpriced;
+                progress.percent_complete = processed as f64 / total as f64 * 100.0;
+                progress.newest_processed_signature = Some(signature.clone());
+                if progress.oldest_timestamp.is_none() {
+                    progress.oldest_timestamp = timestamp;
+                }
+            })
+            .await;
+            if processed % CHECKPOINT_INTERVAL == 0 {
+                self.save().await?;
+                flows.read().save()?;
+            }
+        }
+        flows.read().save()?;
+
+        if let Err(e) = performance
+            .read()
+            .await
+            .calculate_performance_score(wallet_address)
+            .await
+        {
+            eprintln!("Failed to rescore {wallet_address} after backfill: {e}");
+        }
+        self.update(app, wallet_address, |progress| {
+            progress.status = BackfillStatus::Completed;
+            progress.percent_complete = 100.0;
+        })
+        .await;
+        Ok(())
+    }
+
+    async fn mark_cancelled(&self, app: &AppHandle, wallet_address: &str) -> Result<(), String> {
+        self.update(app, wallet_address, |progress| {
+            progress.status = BackfillStatus::Cancelled;
+        })
+        .await;
+        Ok(())
+    }
+
+    async fn update<F>(&self, app: &AppHandle, wallet_address: &str, change: F)
+    where
+        F: FnOnce(&mut BackfillProgress),
+    {
+        let snapshot = {
+            let mut guard = self.progress.write().await;
+            let Some(progress) = guard.get_mut(wallet_address) else {
+                return;
+            };
+            change(progress);
+            progress.updated_at = Utc::now();
+            progress.clone()
+        };
+        let _ = app.emit(BACKFILL_PROGRESS_EVENT, &snapshot);
+    }
+
+    async fn save(&self) -> Result<(), String> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create backfill directory: {e}"))?;
+            }
+            let contents = serde_json::to_string_pretty(&*self.progress.read().await)
+                .map_err(|e| format!("Failed to serialize backfill progress: {e}"))?;
+            fs::write(path, contents)
+                .map_err(|e| format!("Failed to persist backfill progress: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// One page of signatures, newest first, with whether each transaction
+/// succeeded.
+async fn fetch_signature_page(
+    pool: &SharedRpcPool,
+    wallet_address: &str,
+    before: Option<String>,
+    until: Option<String>,
+) -> Result<Vec<(String, bool)>, String> {
+    let params = json!([
+        wallet_address,
+        {
+            "limit": SIGNATURE_PAGE_LIMIT,
+            "before": before,
+            "until": until,
+            "commitment": "finalized",
+        }
+    ]);
+    let page: Vec<Value> = RpcPool::call(pool, RoutingHint::Read, move |client| {
+        client.send(RpcRequest::GetSignaturesForAddress, params.clone())
+    })
+    .await
+    .map_err(|e| format!("Failed to fetch signatures: {e}"))?;
+
+    Ok(page
+        .into_iter()
+        .filter_map(|entry| {
+            let signature = entry["signature"].as_str()?.to_string();
+            Some((signature, entry["err"].is_null()))
+        })
+        .collect())
+}
+
+pub(crate) async fn fetch_transaction(
+    pool: &SharedRpcPool,
+    signature: &str,
+) -> Result<Option<Value>, String> {
+    let params = json!([
+        signature,
+        {
+            "encoding": "jsonParsed",
+            "commitment": "finalized",
+            "maxSupportedTransactionVersion": 0,
+        }
+    ]);
+    let tx: Value = RpcPool::call(pool, RoutingHint::Read, move |client| {
+        client.send(RpcRequest::GetTransaction, params.clone())
+    })
+    .await
+    .map_err(|e| format!("Failed to fetch transaction {signature}: {e}"))?;
+    Ok((!tx.is_null()).then_some(tx))
+}
+
+/// Books a parsed transaction into the performance database (swaps) and
+/// tax lots (swaps and transfers). Stablecoin legs carry no gain and are
+/// left out of both. Transactions the performance database already holds,
+/// such as trades recorded live after import, are not recorded twice.
+/// Every leg is also tagged in the flow ledger; transfers between the
+/// user's own wallets leave tax lots untouched since lots are pooled.
+async fn apply(
+    app: &AppHandle,
+    performance: &SharedPerformanceDatabase,
+    flows: &SharedFlowLedger,
+    own_wallets: &HashSet<String>,
+    oracle: &mut PriceOracle,
+    wallet_address: &str,
+    parsed: &ParsedTransaction,
+) -> Result<ApplyOutcome, String> {
+    let db = performance.read().await;
+    let known = db
+        .has_trade_signature(wallet_address, &parsed.signature)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut outcome = ApplyOutcome::default();
+    let mut fee = parsed.fee_sol;
+    let mut tagged = Vec::new();
+    let mut flow = |direction: FlowDirection,
+                    kind: FlowKind,
+                    counterparty: Option<String>,
+                    leg: &TokenDelta,
+                    usd_value: Option<f64>| {
+        tagged.push(WalletFlow {
+            id: WalletFlow::flow_id(wallet_address, &parsed.signature, &leg.mint, direction),
+            wallet_address: wallet_address.to_string(),
+            signature: parsed.signature.clone(),
+            timestamp: parsed.timestamp,
+            kind,
+            direction,
+            mint: leg.mint.clone(),
+            symbol: symbol_for(&leg.mint),
+            amount: leg.amount,
+            usd_value,
+            counterparty,
+            counterparty_name: None,
+            retagged: false,
+        });
+    };
+
+    for activity in &parsed.activities {
+        let (sold, bought, value) = match activity {
+            HistoricalActivity::Swap { sold, bought } => {
+                let value = oracle.swap_value(sold, bought, parsed.timestamp).await;
+                outcome.trades += 1;
+                flow(FlowDirection::Out, FlowKind::Trade, None, sold, value);
+                flow(FlowDirection::In, FlowKind::Trade, None, bought, value);
+                (Some(sold), Some(bought), value)
+            }
+            HistoricalActivity::TransferIn(delta) | HistoricalActivity::TransferOut(delta) => {
+                let direction = match activity {
+                    HistoricalActivity::TransferIn(_) => FlowDirection::In,
+                    _ => FlowDirection::Out,
+                };
+                let counterparties = parsed
+                    .counterparties
+                    .get(&delta.mint)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let (kind, counterparty) = tag_transfer(direction, counterparties, own_wallets);
+                let usd_value = oracle
+                    .price_at(&delta.mint, parsed.timestamp)
+                    .await
+                    .map(|price| price * delta.amount);
+                flow(direction, kind, counterparty, delta, usd_value);
+                if kind == FlowKind::InternalTransfer {
+                    outcome.internal_transfers += 1;
+                    continue;
+                }
+                outcome.transfers += 1;
+                match direction {
+                    FlowDirection::In => (None, Some(delta), usd_value),
+                    FlowDirection::Out => (Some(delta), None, None),
+                }
+            }
+        };
+        let is_swap = sold.is_some() && bought.is_some();
+        if value.is_none() && bought.is_some() {
+            outcome.unpriced += 1;
+        }
+
+        if is_swap && !known {
+            if let Some(value) = value {
+                for (side, leg) in [("sell", sold), ("buy", bought)] {
+                    let Some(leg) = leg.filter(|leg| !is_stable(&leg.mint)) else {
+                        continue;
+                    };
+                    db.record_trade_at(
+                        RecordTradeRequest {
+                            wallet_address: wallet_address.to_string(),
+                            token_mint: leg.mint.clone(),
+                            token_symbol: symbol_for(&leg.mint),
+                            side: side.to_string(),
+                            amount: leg.amount,
+                            price: value / leg.amount,
+                            fee: std::mem::take(&mut fee),
+                            tx_signature: parsed.signature.clone(),
+                        },
+                        parsed.timestamp,
+                    )
+                    .await
+                    .map_err(|e| format!("Failed to record trade: {e}"))?;
+                }
+            }
+        }
+
+        let tax_lots = app.state::<SharedTaxLotsState>();
+        let mut lots = tax_lots
+            .lock()
+            .map_err(|_| "Tax lots unavailable".to_string())?;
+        if let Some(leg) = sold.filter(|leg| !is_stable(&leg.mint)) {
+            // Only swaps with a known value realize a gain; transfers out
+            // and unpriced swaps just take the amount off the books.
+            let disposal = value
+                .filter(|_| is_swap)
+                .map(|value| (value / leg.amount, parsed.timestamp));
+            lots.relieve(&leg.mint, leg.amount, disposal);
+        }
+        if let Some(leg) = bought.filter(|leg| !is_stable(&leg.mint)) {
+            let lot_id = format!("backfill-{}-{}", parsed.signature, leg.mint);
+            if !lots.has_lot(&lot_id) {
+                let cost_basis = value.unwrap_or(0.0);
+                lots.add_lot(TaxLot {
+                    id: lot_id,
+                    symbol: symbol_for(&leg.mint),
+                    mint: leg.mint.clone(),
+                    amount: leg.amount,
+                    cost_basis,
+                    price_per_unit: cost_basis / leg.amount,
+                    acquired_at: parsed.timestamp.to_rfc3339(),
+                    disposed_amount: None,
+                    disposed_at: None,
+                    realized_gain: None,
+                });
+            }
+        }
+    }
+
+    drop(flow);
+    flows.write().record(tagged);
+    Ok(outcome)
+}
+
+#[tauri::command]
+pub async fn wallet_backfill_start(
+    app: AppHandle,
+    wallet_address: String,
+    backfill: State<'_, SharedWalletBackfill>,
+) -> Result<BackfillProgress, AppError> {
+    backfill
+        .start(&app, &wallet_address)
+        .await
+        .logged("wallet_backfill_start")
+}
+
+#[tauri::command]
+pub async fn wallet_backfill_status(
+    wallet_address: String,
+    backfill: State<'_, SharedWalletBackfill>,
+) -> Result<Option<BackfillProgress>, AppError> {
+    Ok(backfill.status(&wallet_address).await)
+}
+
+#[tauri::command]
+pub async fn wallet_backfill_list(
+    backfill: State<'_, SharedWalletBackfill>,
+) -> Result<Vec<BackfillProgress>, AppError> {
+    Ok(backfill.list().await)
+}
+
+#[tauri::command]
+pub async fn wallet_backfill_cancel(
+    wallet_address: String,
+    backfill: State<'_, SharedWalletBackfill>,
+) -> Result<(), AppError> {
+    backfill.cancel(&wallet_address).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER: &str = "Owner11111111111111111111111111111111111111";
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    fn delta(mint: &str, amount: f64) -> TokenDelta {
+        TokenDelta {
+            mint: mint.to_string(),
+            amount,
+        }
+    }
+
+    #[test]
+    fn parses_sol_for_token_swap_net_of_fee() {
+        let tx = json!({
+            "blockTime": 1_700_000_000,
+            "meta": {
+                "err": null,
+                "fee": 5000,
+                "preBalances": [2_000_000_000u64, 0],
+                "postBalances": [999_995_000u64, 0],
+                "preTokenBalances": [],
+                "postTokenBalances": [{
+                    "accountIndex": 1,
+                    "mint": BONK,
+                    "owner": OWNER,
+                    "uiTokenAmount": { "amount": "150000", "decimals": 5, "uiAmountString": "1.5" }
+                }]
+            },
+            "transaction": { "message": { "accountKeys": [
+                { "pubkey": OWNER, "signer": true },
+                { "pubkey": "TokenAccount1111111111111111111111111111111", "signer": false }
+            ]}}
+        });
+
+        let parsed = parse_transaction(OWNER, "sig", &tx).unwrap();
+        assert!((parsed.fee_sol - 0.000005).abs() < 1e-12);
+        assert_eq!(
+            parsed.activities,
+            vec![HistoricalActivity::Swap {
+                sold: delta(WSOL_MINT, 1.0),
+                bought: delta(BONK, 1.5),
+            }]
+        );
+
+        let mut failed = tx.clone();
+        failed["meta"]["err"] = json!({ "InstructionError": [0, "Custom"] });
+        assert!(parse_transaction(OWNER, "sig", &failed).is_none());
+    }
+
+    #[test]
+    fn records_who_took_the_other_side_of_a_transfer() {
+        let recipient = "Recipient111111111111111111111111111111111";
+        let tx = json!({
+            "blockTime": 1_700_000_000,
+            "meta": {
+                "err": null,
+                "fee": 5000,
+                "preBalances": [1_000_000_000u64, 0, 0, 0],
+                "postBalances": [497_955_000u64, 500_000_000u64, 2_040_000u64, 0],
+                "preTokenBalances": [{
+                    "accountIndex": 3,
+                    "mint": BONK,
+                    "owner": OWNER,
+                    "uiTokenAmount": { "amount": "1000", "decimals": 0, "uiAmountString": "1000" }
+                }],
+                "postTokenBalances": [{
+                    "accountIndex": 3,
+                    "mint": BONK,
+                    "owner": OWNER,
+                    "uiTokenAmount": { "amount": "400", "decimals": 0, "uiAmountString": "400" }
+                }, {
+                    "accountIndex": 2,
+                    "mint": BONK,
+                    "owner": recipient,
+                    "uiTokenAmount": { "amount": "600", "decimals": 0, "uiAmountString": "600" }
+                }]
+            },
+            "transaction": { "message": { "accountKeys": [
+                OWNER,
+                recipient,
+                "RecipientAta11111111111111111111111111111111",
+                "OwnerAta1111111111111111111111111111111111"
+            ]}}
+        });
+
+        let parsed = parse_transaction(OWNER, "sig", &tx).unwrap();
+        assert_eq!(
+            parsed.counterparties.get(BONK),
+            Some(&vec![recipient.to_string()])
+        );
+        // The new token account's rent is not a counterparty.
+        assert_eq!(
+            parsed.counterparties.get(WSOL_MINT),
+            Some(&vec![recipient.to_string()])
+        );
+    }
+
+    #[test]
+    fn account_rent_is_not_treated_as_a_purchase() {
+        let activities = classify(vec![delta(WSOL_MINT, -0.00203928), delta(BONK, 10.0)]);
+        assert_eq!(
+            activities,
+            vec![HistoricalActivity::TransferIn(delta(BONK, 10.0))]
+        );
+    }
+
+    #[test]
+    fn pairs_token_legs_and_books_the_rest_as_transfers() {
+        let activities = classify(vec![
+            delta(BONK, -100.0),
+            delta(USDC_MINT, 25.0),
+            delta(USDT_MINT, 3.0),
+            delta(WSOL_MINT, -0.5),
+        ]);
+        assert_eq!(
+            activities,
+            vec![
+                HistoricalActivity::Swap {
+                    sold: delta(BONK, 100.0),
+                    bought: delta(USDC_MINT, 25.0),
+                },
+                HistoricalActivity::TransferIn(delta(USDT_MINT, 3.0)),
+                HistoricalActivity::TransferOut(delta(WSOL_MINT, 0.5)),
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use super::hardware_wallet::DeviceType;
+use super::hardware_wallet::{DeviceType, HardwareWalletDevice};
 use base64::engine::general_purpose::STANDARD as BASE64_ENGINE;
 use base64::Engine;
 use serde::{Deserialize, Serialize};
@@ -105,6 +105,26 @@ impl LedgerState {
             active_device_id: Mutex::new(None),
         }
     }
+
+    /// Registered devices in the vendor-neutral shape used by
+    /// `list_hardware_wallets`.
+    pub fn hardware_devices(&self) -> Vec<HardwareWalletDevice> {
+        let Ok(devices) = self.devices.lock() else {
+            return Vec::new();
+        };
+        devices
+            .iter()
+            .map(|device| HardwareWalletDevice {
+                device_id: device.device_id.clone(),
+                device_type: DeviceType::Ledger,
+                product_name: device.product_name.clone(),
+                manufacturer: device.manufacturer.clone(),
+                connected: device.connected,
+                firmware_version: device.firmware_version.clone(),
+                address: device.address.clone(),
+            })
+            .collect()
+    }
 }
 
 fn validate_derivation_path(path: &str) -> Result<(), LedgerError> {
@@ -1,7 +1,16 @@
+pub mod display_names;
+pub mod fee_relayer;
+pub mod flows;
 pub mod hardware_wallet;
+pub mod history_backfill;
 pub mod ledger;
 pub mod multi_wallet;
 pub mod multisig;
 pub mod operations;
 pub mod performance;
 pub mod phantom;
+pub mod receipts;
+pub mod trezor;
+pub mod tx_builder;
+pub mod tx_lifecycle;
+pub mod walletconnect;
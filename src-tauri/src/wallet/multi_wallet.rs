@@ -2,10 +2,13 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Mutex, MutexGuard};
-use tauri::State;
+use tauri::{AppHandle, State};
 use uuid::Uuid;
 
+use crate::errors::{AppError, CommandResultExt};
 use crate::security::keystore::{Keystore, KeystoreError};
+use crate::wallet::display_names::notify_display_names_changed;
+use crate::wallet::history_backfill::SharedWalletBackfill;
 
 const KEYSTORE_STATE_KEY: &str = "wallet.multi_state";
 
@@ -204,6 +207,22 @@ pub enum MultiWalletError {
     Internal,
 }
 
+impl From<MultiWalletError> for AppError {
+    fn from(err: MultiWalletError) -> Self {
+        match err {
+            MultiWalletError::Serialization(e) => AppError::Serialization(e),
+            err @ (MultiWalletError::WalletNotFound(_) | MultiWalletError::GroupNotFound(_)) => {
+                AppError::NotFound(err.to_string())
+            }
+            err @ MultiWalletError::WalletExists(_) => AppError::Conflict(err.to_string()),
+            err @ MultiWalletError::InvalidInput(_) => AppError::Validation(err.to_string()),
+            err @ (MultiWalletError::Keystore(_) | MultiWalletError::Internal) => {
+                AppError::Generic(err.to_string())
+            }
+        }
+    }
+}
+
 pub struct MultiWalletManager {
     state: Mutex<MultiWalletState>,
 }
@@ -668,24 +687,42 @@ impl MultiWalletManager {
 
 #[tauri::command]
 pub async fn multi_wallet_add(
+    app: AppHandle,
     request: AddWalletRequest,
     manager: State<'_, MultiWalletManager>,
     keystore: State<'_, Keystore>,
-) -> Result<WalletInfo, String> {
-    manager
+    backfill: State<'_, SharedWalletBackfill>,
+) -> Result<WalletInfo, AppError> {
+    let wallet = manager
         .add_wallet(request, &keystore)
-        .map_err(|e| e.to_string())
+        .logged("multi_wallet_add")?;
+
+    // Existing wallets arrive with history; reconstruct it so performance
+    // and tax views cover more than post-import activity.
+    if wallet.chain_id == "solana" {
+        if let Err(e) = backfill.start(&app, &wallet.public_key).await {
+            eprintln!(
+                "Failed to start history backfill for {}: {}",
+                wallet.public_key, e
+            );
+        }
+    }
+    notify_display_names_changed(&app, vec![wallet.public_key.clone()]);
+    Ok(wallet)
 }
 
 #[tauri::command]
 pub async fn multi_wallet_update(
+    app: AppHandle,
     request: UpdateWalletRequest,
     manager: State<'_, MultiWalletManager>,
     keystore: State<'_, Keystore>,
-) -> Result<WalletInfo, String> {
-    manager
+) -> Result<WalletInfo, AppError> {
+    let wallet = manager
         .update_wallet(request, &keystore)
-        .map_err(|e| e.to_string())
+        .logged("multi_wallet_update")?;
+    notify_display_names_changed(&app, vec![wallet.public_key.clone()]);
+    Ok(wallet)
 }
 
 #[tauri::command]
@@ -693,10 +730,10 @@ pub async fn multi_wallet_remove(
     wallet_id: String,
     manager: State<'_, MultiWalletManager>,
     keystore: State<'_, Keystore>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     manager
         .remove_wallet(&wallet_id, &keystore)
-        .map_err(|e| e.to_string())
+        .logged("multi_wallet_remove")
 }
 
 #[tauri::command]
@@ -704,24 +741,26 @@ pub async fn multi_wallet_set_active(
     wallet_id: String,
     manager: State<'_, MultiWalletManager>,
     keystore: State<'_, Keystore>,
-) -> Result<WalletInfo, String> {
+) -> Result<WalletInfo, AppError> {
     manager
         .set_active_wallet(&wallet_id, &keystore)
-        .map_err(|e| e.to_string())
+        .logged("multi_wallet_set_active")
 }
 
 #[tauri::command]
 pub async fn multi_wallet_get_active(
     manager: State<'_, MultiWalletManager>,
-) -> Result<Option<WalletInfo>, String> {
-    manager.get_active_wallet().map_err(|e| e.to_string())
+) -> Result<Option<WalletInfo>, AppError> {
+    manager
+        .get_active_wallet()
+        .logged("multi_wallet_get_active")
 }
 
 #[tauri::command]
 pub async fn multi_wallet_list(
     manager: State<'_, MultiWalletManager>,
-) -> Result<Vec<WalletInfo>, String> {
-    manager.list_wallets().map_err(|e| e.to_string())
+) -> Result<Vec<WalletInfo>, AppError> {
+    manager.list_wallets().logged("multi_wallet_list")
 }
 
 #[tauri::command]
@@ -730,10 +769,10 @@ pub async fn multi_wallet_update_balance(
     balance: f64,
     manager: State<'_, MultiWalletManager>,
     keystore: State<'_, Keystore>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     manager
         .update_wallet_balance(&wallet_id, balance, &keystore)
-        .map_err(|e| e.to_string())
+        .logged("multi_wallet_update_balance")
 }
 
 #[tauri::command]
@@ -742,10 +781,10 @@ pub async fn multi_wallet_update_performance(
     metrics: PerformanceMetrics,
     manager: State<'_, MultiWalletManager>,
     keystore: State<'_, Keystore>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     manager
         .update_performance_metrics(&wallet_id, metrics, &keystore)
-        .map_err(|e| e.to_string())
+        .logged("multi_wallet_update_performance")
 }
 
 #[tauri::command]
@@ -753,10 +792,10 @@ pub async fn multi_wallet_create_group(
     request: CreateGroupRequest,
     manager: State<'_, MultiWalletManager>,
     keystore: State<'_, Keystore>,
-) -> Result<WalletGroup, String> {
+) -> Result<WalletGroup, AppError> {
     manager
         .create_group(request, &keystore)
-        .map_err(|e| e.to_string())
+        .logged("multi_wallet_create_group")
 }
 
 #[tauri::command]
@@ -764,10 +803,10 @@ pub async fn multi_wallet_update_group(
     request: UpdateGroupRequest,
     manager: State<'_, MultiWalletManager>,
     keystore: State<'_, Keystore>,
-) -> Result<WalletGroup, String> {
+) -> Result<WalletGroup, AppError> {
     manager
         .update_group(request, &keystore)
-        .map_err(|e| e.to_string())
+        .logged("multi_wallet_update_group")
 }
 
 #[tauri::command]
@@ -775,24 +814,24 @@ pub async fn multi_wallet_delete_group(
     group_id: String,
     manager: State<'_, MultiWalletManager>,
     keystore: State<'_, Keystore>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     manager
         .delete_group(&group_id, &keystore)
-        .map_err(|e| e.to_string())
+        .logged("multi_wallet_delete_group")
 }
 
 #[tauri::command]
 pub async fn multi_wallet_list_groups(
     manager: State<'_, MultiWalletManager>,
-) -> Result<Vec<WalletGroup>, String> {
-    manager.list_groups().map_err(|e| e.to_string())
+) -> Result<Vec<WalletGroup>, AppError> {
+    manager.list_groups().logged("multi_wallet_list_groups")
 }
 
 #[tauri::command]
 pub async fn multi_wallet_get_aggregated(
     manager: State<'_, MultiWalletManager>,
-) -> Result<AggregatedPortfolio, String> {
+) -> Result<AggregatedPortfolio, AppError> {
     manager
         .get_aggregated_portfolio()
-        .map_err(|e| e.to_string())
+        .logged("multi_wallet_get_aggregated")
 }
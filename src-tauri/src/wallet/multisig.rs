@@ -16,6 +16,9 @@ use tauri::State;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+use super::tx_builder::{BatchBuildRequest, BatchBuildResult, SharedTransactionBatchBuilder};
+use crate::errors::{AppError, CommandResultExt};
+
 // Squads Protocol Program ID (mainnet-beta)
 pub const SQUADS_PROGRAM_ID: &str = "SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf";
 
@@ -110,6 +113,23 @@ pub struct CreateProposalRequest {
     pub created_by: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBatchProposalRequest {
+    pub wallet_id: String,
+    pub created_by: String,
+    pub description: Option<String>,
+    /// Batch to compile. The payer defaults to the multisig address.
+    pub batch: BatchBuildRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProposal {
+    pub proposal: MultisigProposal,
+    pub build: BatchBuildResult,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SignProposalRequest {
@@ -585,69 +605,133 @@ pub type SharedMultisigDatabase = Arc<RwLock<MultisigDatabase>>;
 pub async fn create_multisig_wallet(
     request: CreateMultisigRequest,
     db: State<'_, SharedMultisigDatabase>,
-) -> Result<MultisigWallet, String> {
+) -> Result<MultisigWallet, AppError> {
     // Validate request
     if request.members.is_empty() {
-        return Err("At least one member is required".to_string());
+        return Err(
+            AppError::Validation("At least one member is required".to_string())
+                .logged("create_multisig_wallet"),
+        );
     }
 
     if request.threshold == 0 || request.threshold > request.members.len() as u32 {
-        return Err(format!(
+        return Err(AppError::Validation(format!(
             "Threshold must be between 1 and {}",
             request.members.len()
-        ));
+        ))
+        .logged("create_multisig_wallet"));
     }
 
     let db_guard = db.read().await;
     db_guard
         .create_wallet(request)
         .await
-        .map_err(|e| e.to_string())
+        .logged("create_multisig_wallet")
 }
 
 #[tauri::command]
 pub async fn list_multisig_wallets(
     db: State<'_, SharedMultisigDatabase>,
-) -> Result<Vec<MultisigWallet>, String> {
+) -> Result<Vec<MultisigWallet>, AppError> {
     let db_guard = db.read().await;
-    db_guard.list_wallets().await.map_err(|e| e.to_string())
+    db_guard
+        .list_wallets()
+        .await
+        .logged("list_multisig_wallets")
 }
 
 #[tauri::command]
 pub async fn get_multisig_wallet(
     wallet_id: String,
     db: State<'_, SharedMultisigDatabase>,
-) -> Result<Option<MultisigWallet>, String> {
+) -> Result<Option<MultisigWallet>, AppError> {
     let db_guard = db.read().await;
     db_guard
         .get_wallet(&wallet_id)
         .await
-        .map_err(|e| e.to_string())
+        .logged("get_multisig_wallet")
 }
 
 #[tauri::command]
 pub async fn create_proposal(
     request: CreateProposalRequest,
     db: State<'_, SharedMultisigDatabase>,
-) -> Result<MultisigProposal, String> {
+) -> Result<MultisigProposal, AppError> {
     let db_guard = db.read().await;
 
     // Verify wallet exists
     let wallet = db_guard
         .get_wallet(&request.wallet_id)
         .await
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Wallet not found".to_string())?;
+        .logged("create_proposal")?
+        .ok_or_else(|| AppError::NotFound("Wallet not found".to_string()))
+        .logged("create_proposal")?;
 
     // Verify creator is a member
     if !wallet.members.contains(&request.created_by) {
-        return Err("Only wallet members can create proposals".to_string());
+        return Err(
+            AppError::Forbidden("Only wallet members can create proposals".to_string())
+                .logged("create_proposal"),
+        );
     }
 
     db_guard
         .create_proposal(request)
         .await
-        .map_err(|e| e.to_string())
+        .logged("create_proposal")
+}
+
+/// Compiles a batch through the transaction builder (v0 with lookup
+/// tables when that is cheaper) and stores the result as a proposal.
+#[tauri::command]
+pub async fn create_batch_proposal(
+    mut request: CreateBatchProposalRequest,
+    db: State<'_, SharedMultisigDatabase>,
+    builder: State<'_, SharedTransactionBatchBuilder>,
+) -> Result<BatchProposal, AppError> {
+    let db_guard = db.read().await;
+
+    let wallet = db_guard
+        .get_wallet(&request.wallet_id)
+        .await
+        .logged("create_batch_proposal")?
+        .ok_or_else(|| AppError::NotFound("Wallet not found".to_string()))
+        .logged("create_batch_proposal")?;
+
+    if !wallet.members.contains(&request.created_by) {
+        return Err(
+            AppError::Forbidden("Only wallet members can create proposals".to_string())
+                .logged("create_batch_proposal"),
+        );
+    }
+
+    if request.batch.payer.is_empty() {
+        request.batch.payer = wallet.address.clone();
+    }
+    let build = builder
+        .write()
+        .await
+        .build(&request.batch)
+        .logged("create_batch_proposal")?;
+
+    let description = request.description.or_else(|| {
+        Some(format!(
+            "Batch of {} instruction(s) as {:?} transaction",
+            request.batch.instructions.len(),
+            build.format
+        ))
+    });
+    let proposal = db_guard
+        .create_proposal(CreateProposalRequest {
+            wallet_id: request.wallet_id,
+            transaction_data: build.transaction.clone(),
+            description,
+            created_by: request.created_by,
+        })
+        .await
+        .logged("create_batch_proposal")?;
+
+    Ok(BatchProposal { proposal, build })
 }
 
 #[tauri::command]
@@ -655,43 +739,51 @@ pub async fn list_proposals(
     wallet_id: String,
     status_filter: Option<String>,
     db: State<'_, SharedMultisigDatabase>,
-) -> Result<Vec<MultisigProposal>, String> {
+) -> Result<Vec<MultisigProposal>, AppError> {
     let db_guard = db.read().await;
     db_guard
         .list_proposals(&wallet_id, status_filter)
         .await
-        .map_err(|e| e.to_string())
+        .logged("list_proposals")
 }
 
 #[tauri::command]
 pub async fn sign_proposal(
     request: SignProposalRequest,
     db: State<'_, SharedMultisigDatabase>,
-) -> Result<ProposalSignature, String> {
+) -> Result<ProposalSignature, AppError> {
     let db_guard = db.read().await;
 
     // Verify proposal exists
     let proposal = db_guard
         .get_proposal(&request.proposal_id)
         .await
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Proposal not found".to_string())?;
+        .logged("sign_proposal")?
+        .ok_or_else(|| AppError::NotFound("Proposal not found".to_string()))
+        .logged("sign_proposal")?;
 
     // Verify proposal is pending or approved
     if proposal.status != ProposalStatus::Pending && proposal.status != ProposalStatus::Approved {
-        return Err("Proposal is not in a signable state".to_string());
+        return Err(
+            AppError::Conflict("Proposal is not in a signable state".to_string())
+                .logged("sign_proposal"),
+        );
     }
 
     // Verify wallet exists
     let wallet = db_guard
         .get_wallet(&proposal.wallet_id)
         .await
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Wallet not found".to_string())?;
+        .logged("sign_proposal")?
+        .ok_or_else(|| AppError::NotFound("Wallet not found".to_string()))
+        .logged("sign_proposal")?;
 
     // Verify signer is a member
     if !wallet.members.contains(&request.signer) {
-        return Err("Only wallet members can sign proposals".to_string());
+        return Err(
+            AppError::Forbidden("Only wallet members can sign proposals".to_string())
+                .logged("sign_proposal"),
+        );
     }
 
     // Check if already signed
@@ -700,46 +792,55 @@ pub async fn sign_proposal(
         .iter()
         .any(|sig| sig.signer == request.signer)
     {
-        return Err("You have already signed this proposal".to_string());
+        return Err(
+            AppError::Conflict("You have already signed this proposal".to_string())
+                .logged("sign_proposal"),
+        );
     }
 
     db_guard
         .add_signature(request)
         .await
-        .map_err(|e| e.to_string())
+        .logged("sign_proposal")
 }
 
 #[tauri::command]
 pub async fn execute_proposal(
     proposal_id: String,
     db: State<'_, SharedMultisigDatabase>,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let db_guard = db.read().await;
 
     // Verify proposal exists and is approved
     let proposal = db_guard
         .get_proposal(&proposal_id)
         .await
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Proposal not found".to_string())?;
+        .logged("execute_proposal")?
+        .ok_or_else(|| AppError::NotFound("Proposal not found".to_string()))
+        .logged("execute_proposal")?;
 
     if proposal.status != ProposalStatus::Approved {
-        return Err("Proposal is not approved for execution".to_string());
+        return Err(
+            AppError::Conflict("Proposal is not approved for execution".to_string())
+                .logged("execute_proposal"),
+        );
     }
 
     // Verify threshold is met
     let wallet = db_guard
         .get_wallet(&proposal.wallet_id)
         .await
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Wallet not found".to_string())?;
+        .logged("execute_proposal")?
+        .ok_or_else(|| AppError::NotFound("Wallet not found".to_string()))
+        .logged("execute_proposal")?;
 
     if proposal.signatures.len() < wallet.threshold as usize {
-        return Err(format!(
+        return Err(AppError::Conflict(format!(
             "Insufficient signatures: {} of {} required",
             proposal.signatures.len(),
             wallet.threshold
-        ));
+        ))
+        .logged("execute_proposal"));
     }
 
     // Simulate transaction execution
@@ -749,7 +850,7 @@ pub async fn execute_proposal(
     db_guard
         .execute_proposal(&proposal_id, tx_signature.clone())
         .await
-        .map_err(|e| e.to_string())?;
+        .logged("execute_proposal")?;
 
     Ok(tx_signature)
 }
@@ -759,28 +860,35 @@ pub async fn cancel_proposal(
     proposal_id: String,
     user_address: String,
     db: State<'_, SharedMultisigDatabase>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let db_guard = db.read().await;
 
     // Verify proposal exists
     let proposal = db_guard
         .get_proposal(&proposal_id)
         .await
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Proposal not found".to_string())?;
+        .logged("cancel_proposal")?
+        .ok_or_else(|| AppError::NotFound("Proposal not found".to_string()))
+        .logged("cancel_proposal")?;
 
     // Only creator can cancel
     if proposal.created_by != user_address {
-        return Err("Only the proposal creator can cancel it".to_string());
+        return Err(
+            AppError::Forbidden("Only the proposal creator can cancel it".to_string())
+                .logged("cancel_proposal"),
+        );
     }
 
     // Can only cancel pending proposals
     if proposal.status != ProposalStatus::Pending {
-        return Err("Can only cancel pending proposals".to_string());
+        return Err(
+            AppError::Conflict("Can only cancel pending proposals".to_string())
+                .logged("cancel_proposal"),
+        );
     }
 
     db_guard
         .cancel_proposal(&proposal_id)
         .await
-        .map_err(|e| e.to_string())
+        .logged("cancel_proposal")
 }
@@ -2,14 +2,30 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Mutex;
-use tauri::State;
+use tauri::{AppHandle, State};
 use uuid::Uuid;
 
+use super::display_names::notify_display_names_changed;
+use super::fee_relayer::{
+    build_relayed_token_transfer, ensure_fee_payer, FeeRelayerManager, RelayedTransferDraft,
+};
+use super::tx_lifecycle::{SharedTransactionLifecycle, SubmitTransactionRequest};
+use crate::auth::command_policy::{CommandPolicyManager, GuardedCommand, VerificationOutcome};
+use crate::auth::passkey::{PasskeyManager, SensitiveCategory};
+use crate::auth::two_factor::TwoFactorManager;
+use crate::chains::{
+    eth_to_wei, evm_client, send_native_transfer, validate_address, ChainId, Eip1559Fees,
+    SharedChainManager, SharedRpcPool, WEI_PER_ETH,
+};
+use crate::errors::{AppError, CommandResultExt};
+use crate::market::fetch_birdeye_price;
 use crate::security::keystore::{Keystore, KeystoreError};
+use crate::token_extensions::{SharedTokenExtensionService, TokenExtensionReport};
 
 const KEYSTORE_TOKEN_CACHE_KEY: &str = "wallet.token_cache";
 const KEYSTORE_ADDRESS_BOOK_KEY: &str = "wallet.address_book";
 const KEYSTORE_SWAP_HISTORY_KEY: &str = "wallet.swap_history";
+const WRAPPED_SOL_MINT: &str = "So11111111111111111111111111111111111111112";
 
 // Token Balance Types
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +40,9 @@ pub struct TokenBalance {
     pub change_24h: f64,
     pub logo_uri: Option<String>,
     pub last_updated: DateTime<Utc>,
+    /// Token-2022 extension data; absent for legacy SPL mints.
+    #[serde(default)]
+    pub extensions: Option<TokenExtensionReport>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +70,19 @@ pub struct SendTransactionInput {
     pub amount: f64,
     pub token_mint: Option<String>,
     pub memo: Option<String>,
+    /// Opt in to having the configured fee relayer pay network fees.
+    #[serde(default)]
+    pub use_fee_relayer: bool,
+    /// Wallet-signed transfer (base64). When present it is broadcast and
+    /// tracked by the transaction lifecycle service.
+    #[serde(default)]
+    pub signed_transaction: Option<String>,
+    #[serde(default)]
+    pub last_valid_block_height: Option<u64>,
+    /// Chain to send on; Solana when absent. On EVM chains
+    /// `signed_transaction` is a hex raw transaction.
+    #[serde(default)]
+    pub chain_id: Option<ChainId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +92,8 @@ pub struct TransactionFeeEstimate {
     pub priority_fee: f64,
     pub total_fee: f64,
     pub estimated_units: u64,
+    #[serde(default)]
+    pub eip1559: Option<Eip1559Fees>,
 }
 
 // Address Book Types
@@ -76,6 +110,34 @@ pub struct AddressBookContact {
     pub last_used: Option<DateTime<Utc>>,
     pub transaction_count: u64,
     pub tags: Vec<String>,
+    /// Addresses on other chains, each validated for that chain's format.
+    /// `address` is the contact's Solana address.
+    #[serde(default)]
+    pub chain_addresses: HashMap<ChainId, String>,
+}
+
+impl AddressBookContact {
+    pub fn address_for(&self, chain: &ChainId) -> Option<&str> {
+        match chain {
+            ChainId::Solana => Some(self.address.as_str()),
+            other => self.chain_addresses.get(other).map(String::as_str),
+        }
+    }
+}
+
+/// Validates every entry and drops Solana, which lives in `address`.
+fn validate_chain_addresses(
+    addresses: HashMap<ChainId, String>,
+) -> Result<HashMap<ChainId, String>, String> {
+    addresses
+        .into_iter()
+        .filter(|(chain, _)| *chain != ChainId::Solana)
+        .map(|(chain, address)| {
+            let normalized = validate_address(&chain, &address)
+                .map_err(|e| format!("{} address: {}", chain.as_str(), e))?;
+            Ok((chain, normalized))
+        })
+        .collect()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -101,6 +163,8 @@ pub struct AddContactRequest {
     pub nickname: Option<String>,
     pub notes: Option<String>,
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub chain_addresses: HashMap<ChainId, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -111,6 +175,8 @@ pub struct UpdateContactRequest {
     pub nickname: Option<Option<String>>,
     pub notes: Option<Option<String>>,
     pub tags: Option<Vec<String>>,
+    /// Replaces all non-Solana addresses when present.
+    pub chain_addresses: Option<HashMap<ChainId, String>>,
 }
 
 // Swap History Types
@@ -231,6 +297,39 @@ impl WalletOperationsManager {
         })
     }
 
+    /// USD value of the token balances last fetched for `address`, if any.
+    pub fn cached_balance_usd(&self, address: &str) -> Option<f64> {
+        let cache = self.token_cache.lock().ok()?;
+        cache
+            .balances
+            .get(address)
+            .map(|balances| balances.iter().map(|b| b.usd_value).sum())
+    }
+
+    /// Display name per address: the nickname if set, otherwise the label.
+    /// Each contact's other-chain addresses map to the same name.
+    pub fn contact_names(&self) -> HashMap<String, String> {
+        let Ok(book) = self.address_book.lock() else {
+            return HashMap::new();
+        };
+        let mut names = HashMap::new();
+        for contact in book.contacts.values() {
+            let name = contact
+                .nickname
+                .as_deref()
+                .filter(|n| !n.trim().is_empty())
+                .unwrap_or(&contact.label);
+            if name.trim().is_empty() {
+                continue;
+            }
+            names.insert(contact.address.clone(), name.to_string());
+            for address in contact.chain_addresses.values() {
+                names.insert(address.clone(), name.to_string());
+            }
+        }
+        names
+    }
+
     pub fn persist_token_cache(&self, keystore: &Keystore) -> Result<(), KeystoreError> {
         let guard = self
             .token_cache
@@ -266,63 +365,79 @@ pub async fn wallet_get_token_balances(
     force_refresh: bool,
     operations: State<'_, WalletOperationsManager>,
     keystore: State<'_, Keystore>,
-) -> Result<Vec<TokenBalance>, String> {
-    let mut cache = operations.token_cache.lock().map_err(|e| e.to_string())?;
-
+    extensions: State<'_, SharedTokenExtensionService>,
+) -> Result<Vec<TokenBalance>, AppError> {
     let now = Utc::now();
-    let should_refresh = force_refresh
-        || !cache.balances.contains_key(&address)
-        || (now.timestamp() - cache.last_updated.timestamp()) > cache.ttl_seconds as i64;
-
-    if should_refresh {
-        // In a real implementation, this would fetch from blockchain
-        // For now, we'll return mock data
-        let mock_balances = vec![
-            TokenBalance {
-                mint: "So11111111111111111111111111111111111111112".to_string(),
-                symbol: "SOL".to_string(),
-                name: "Solana".to_string(),
-                balance: 1.5,
-                decimals: 9,
-                usd_value: 150.0,
-                change_24h: 2.5,
-                logo_uri: Some("https://raw.githubusercontent.com/solana-labs/token-list/main/assets/mainnet/So11111111111111111111111111111111111111112/logo.png".to_string()),
-                last_updated: now,
-            },
-            TokenBalance {
-                mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
-                symbol: "USDC".to_string(),
-                name: "USD Coin".to_string(),
-                balance: 100.0,
-                decimals: 6,
-                usd_value: 100.0,
-                change_24h: 0.0,
-                logo_uri: Some("https://raw.githubusercontent.com/solana-labs/token-list/main/assets/mainnet/EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v/logo.png".to_string()),
-                last_updated: now,
-            },
-        ];
+    {
+        let cache = operations
+            .token_cache
+            .lock()
+            .logged("wallet_get_token_balances")?;
+        let should_refresh = force_refresh
+            || !cache.balances.contains_key(&address)
+            || (now.timestamp() - cache.last_updated.timestamp()) > cache.ttl_seconds as i64;
+        if !should_refresh {
+            return Ok(cache.balances.get(&address).cloned().unwrap_or_default());
+        }
+    }
 
-        cache
-            .balances
-            .insert(address.clone(), mock_balances.clone());
-        cache.last_updated = now;
+    // In a real implementation, this would fetch from blockchain
+    // For now, we'll return mock data
+    let mut balances = vec![
+        TokenBalance {
+            mint: "So11111111111111111111111111111111111111112".to_string(),
+            symbol: "SOL".to_string(),
+            name: "Solana".to_string(),
+            balance: 1.5,
+            decimals: 9,
+            usd_value: 150.0,
+            change_24h: 2.5,
+            logo_uri: Some("https://raw.githubusercontent.com/solana-labs/token-list/main/assets/mainnet/So11111111111111111111111111111111111111112/logo.png".to_string()),
+            last_updated: now,
+            extensions: None,
+        },
+        TokenBalance {
+            mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+            symbol: "USDC".to_string(),
+            name: "USD Coin".to_string(),
+            balance: 100.0,
+            decimals: 6,
+            usd_value: 100.0,
+            change_24h: 0.0,
+            logo_uri: Some("https://raw.githubusercontent.com/solana-labs/token-list/main/assets/mainnet/EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v/logo.png".to_string()),
+            last_updated: now,
+            extensions: None,
+        },
+    ];
+
+    {
+        let mut extensions = extensions.write().await;
+        let epoch = extensions.current_epoch().await;
+        for balance in balances.iter_mut() {
+            balance.extensions = extensions
+                .try_get_extensions(&balance.mint)
+                .await
+                .filter(|ext| ext.is_token_2022())
+                .map(|ext| TokenExtensionReport::new(ext, epoch));
+        }
+    }
 
-        operations
-            .persist_token_cache(&keystore)
-            .map_err(|e| e.to_string())?;
+    let mut cache = operations
+        .token_cache
+        .lock()
+        .logged("wallet_get_token_balances")?;
+    cache.balances.insert(address.clone(), balances.clone());
+    cache.last_updated = now;
+    drop(cache);
 
-        Ok(mock_balances)
-    } else {
-        Ok(cache.balances.get(&address).cloned().unwrap_or_default())
-    }
+    operations
+        .persist_token_cache(&keystore)
+        .logged("wallet_get_token_balances")?;
+
+    Ok(balances)
 }
 
-#[tauri::command]
-pub async fn wallet_estimate_fee(
-    recipient: String,
-    amount: f64,
-    token_mint: Option<String>,
-) -> Result<TransactionFeeEstimate, String> {
+fn solana_fee_estimate(token_mint: Option<&str>) -> TransactionFeeEstimate {
     // Mock implementation - in production, this would calculate actual fees
     let base_fee = if token_mint.is_some() {
         0.00001
@@ -331,29 +446,266 @@ pub async fn wallet_estimate_fee(
     };
     let priority_fee = 0.000001;
 
-    Ok(TransactionFeeEstimate {
+    TransactionFeeEstimate {
         base_fee,
         priority_fee,
         total_fee: base_fee + priority_fee,
         estimated_units: 200000,
+        eip1559: None,
+    }
+}
+
+/// Transfer amount in the native asset, the unit of the 2FA large-transfer
+/// threshold. Token amounts are converted through Birdeye spot prices;
+/// `None` when the token cannot be priced.
+async fn native_transfer_value(
+    amount: f64,
+    token_mint: Option<&str>,
+    keystore: &Keystore,
+) -> Option<f64> {
+    let mint = match token_mint {
+        Some(mint) if mint != WRAPPED_SOL_MINT => mint,
+        _ => return Some(amount),
+    };
+    let api_key = crate::api_config::stored_birdeye_key(keystore)?;
+    let (token, sol) = tokio::join!(
+        fetch_birdeye_price(mint, &api_key),
+        fetch_birdeye_price(WRAPPED_SOL_MINT, &api_key)
+    );
+    let (token, sol) = (token.ok()?.price, sol.ok()?.price);
+    (token.is_finite() && sol.is_finite() && sol > 0.0).then(|| amount * token / sol)
+}
+
+/// Gas for the transfer priced at the standard EIP-1559 tier, in the
+/// chain's native token.
+async fn evm_fee_estimate(
+    chain_manager: &SharedChainManager,
+    chain: &ChainId,
+    from: Option<&str>,
+    recipient: &str,
+    amount: f64,
+) -> Result<TransactionFeeEstimate, String> {
+    let client = evm_client(chain_manager, chain).await?;
+    let gas = client
+        .estimate_gas(from, recipient, eth_to_wei(amount)?, &[])
+        .await?;
+    let fees = client.fee_estimate().await?;
+    let base_fee = fees.base_fee_per_gas as f64 * gas as f64 / WEI_PER_ETH;
+    let total_fee = fees.expected_cost(gas);
+
+    Ok(TransactionFeeEstimate {
+        base_fee,
+        priority_fee: (total_fee - base_fee).max(0.0),
+        total_fee,
+        estimated_units: gas,
+        eip1559: Some(fees),
     })
 }
 
+#[tauri::command]
+pub async fn wallet_estimate_fee(
+    recipient: String,
+    amount: f64,
+    token_mint: Option<String>,
+    chain_id: Option<ChainId>,
+    wallet_address: Option<String>,
+    chain_manager: State<'_, SharedChainManager>,
+) -> Result<TransactionFeeEstimate, AppError> {
+    match chain_id {
+        Some(chain) if chain != ChainId::Solana => {
+            if token_mint.is_some() {
+                return Err(AppError::Validation(
+                    "Token transfers are only supported on Solana".to_string(),
+                )
+                .logged("wallet_estimate_fee"));
+            }
+            let recipient = validate_address(&chain, &recipient)
+                .map_err(|e| AppError::Validation(format!("Invalid recipient: {}", e)))
+                .logged("wallet_estimate_fee")?;
+            evm_fee_estimate(
+                &chain_manager,
+                &chain,
+                wallet_address.as_deref(),
+                &recipient,
+                amount,
+            )
+            .await
+            .logged("wallet_estimate_fee")
+        }
+        _ => Ok(solana_fee_estimate(token_mint.as_deref())),
+    }
+}
+
 #[tauri::command]
 pub async fn wallet_send_transaction(
+    app: AppHandle,
+    input: SendTransactionInput,
+    wallet_address: String,
+    relayer: State<'_, FeeRelayerManager>,
+    keystore: State<'_, Keystore>,
+    lifecycle: State<'_, SharedTransactionLifecycle>,
+    chain_manager: State<'_, SharedChainManager>,
+    passkeys: State<'_, PasskeyManager>,
+    policy: State<'_, CommandPolicyManager>,
+    two_factor: State<'_, TwoFactorManager>,
+    two_factor_code: Option<String>,
+) -> Result<String, AppError> {
+    passkeys
+        .require_presence(SensitiveCategory::Transfers)
+        .logged("wallet_send_transaction")?;
+    let outcome = policy
+        .authorize(
+            GuardedCommand::WalletSendTransaction,
+            two_factor_code.as_deref(),
+            two_factor.inner(),
+            keystore.inner(),
+        )
+        .await
+        .logged("wallet_send_transaction")?;
+    // Token transfers that cannot be priced count as large.
+    let value =
+        native_transfer_value(input.amount, input.token_mint.as_deref(), keystore.inner()).await;
+    let large_transfer = match value {
+        Some(value) => two_factor
+            .is_large_transfer(value)
+            .logged("wallet_send_transaction")?,
+        None => true,
+    };
+    if large_transfer && outcome != VerificationOutcome::TwoFactor {
+        two_factor
+            .require_code(two_factor_code.as_deref(), keystore.inner())
+            .logged("wallet_send_transaction")?;
+    }
+
+    if let Some(chain) = input.chain_id.clone().filter(|c| *c != ChainId::Solana) {
+        if input.use_fee_relayer || input.token_mint.is_some() {
+            return Err(AppError::Validation(format!(
+                "Fee relaying and token transfers are not available on {}",
+                chain.as_str()
+            ))
+            .logged("wallet_send_transaction"));
+        }
+        let from = validate_address(&chain, &wallet_address)
+            .map_err(|e| AppError::Validation(format!("Invalid sender: {}", e)))
+            .logged("wallet_send_transaction")?;
+        let to = validate_address(&chain, &input.recipient)
+            .map_err(|e| AppError::Validation(format!("Invalid recipient: {}", e)))
+            .logged("wallet_send_transaction")?;
+        return send_native_transfer(
+            &chain_manager,
+            &keystore,
+            &chain,
+            &from,
+            &to,
+            input.amount,
+            input.signed_transaction.as_deref(),
+        )
+        .await
+        .logged("wallet_send_transaction");
+    }
+
+    validate_address(&ChainId::Solana, &input.recipient)
+        .map_err(|e| AppError::Validation(format!("Invalid recipient: {}", e)))
+        .logged("wallet_send_transaction")?;
+
+    if input.use_fee_relayer {
+        crate::environment::require_mainnet("Fee relayer").logged("wallet_send_transaction")?;
+        let fee = solana_fee_estimate(input.token_mint.as_deref());
+        let fee_payer = relayer
+            .authorize(input.token_mint.as_deref(), fee.total_fee)
+            .map_err(AppError::Forbidden)
+            .logged("wallet_send_transaction")?;
+
+        let transaction = input
+            .signed_transaction
+            .ok_or_else(|| {
+                AppError::Validation(
+                    "Relayed transfers must be signed by the wallet; build one with \
+                     wallet_build_relayed_transfer"
+                        .to_string(),
+                )
+            })
+            .logged("wallet_send_transaction")?;
+        ensure_fee_payer(&transaction, &fee_payer)
+            .map_err(AppError::Validation)
+            .logged("wallet_send_transaction")?;
+
+        // Only count the sponsorship once the relayer has actually broadcast.
+        let signature = relayer
+            .submit(&transaction)
+            .await
+            .logged("wallet_send_transaction")?;
+        relayer
+            .record_sponsored(fee.total_fee, &keystore)
+            .logged("wallet_send_transaction")?;
+        return Ok(signature);
+    }
+
+    let transaction_base64 = input
+        .signed_transaction
+        .ok_or_else(|| {
+            AppError::Validation(
+                "Solana transfers must be signed by the wallet before they are sent".to_string(),
+            )
+        })
+        .logged("wallet_send_transaction")?;
+    let tracked = lifecycle
+        .submit(
+            app,
+            SubmitTransactionRequest {
+                transaction_base64,
+                last_valid_block_height: input.last_valid_block_height,
+                max_attempts: None,
+                fee_schedule: None,
+                label: Some(format!("Transfer from {wallet_address}")),
+            },
+        )
+        .await
+        .logged("wallet_send_transaction")?;
+    Ok(tracked.signature)
+}
+
+/// Builds the unsigned transfer for a relayed send. The wallet signs it and
+/// passes it back as `signed_transaction` with `use_fee_relayer` set.
+#[tauri::command]
+pub async fn wallet_build_relayed_transfer(
     input: SendTransactionInput,
     wallet_address: String,
-) -> Result<String, String> {
-    // Mock implementation - in production, this would sign and send transaction
-    // Returns transaction signature
-    Ok(format!("mock_tx_signature_{}", Uuid::new_v4()))
+    relayer: State<'_, FeeRelayerManager>,
+    rpc_pool: State<'_, SharedRpcPool>,
+) -> Result<RelayedTransferDraft, AppError> {
+    crate::environment::require_mainnet("Fee relayer").logged("wallet_build_relayed_transfer")?;
+    let mint = input
+        .token_mint
+        .as_deref()
+        .ok_or_else(|| {
+            AppError::Validation("Fee relayer only sponsors SPL token transfers".to_string())
+        })
+        .logged("wallet_build_relayed_transfer")?;
+    let fee = solana_fee_estimate(Some(mint));
+    let fee_payer = relayer
+        .authorize(Some(mint), fee.total_fee)
+        .map_err(AppError::Forbidden)
+        .logged("wallet_build_relayed_transfer")?;
+
+    build_relayed_token_transfer(
+        rpc_pool.inner(),
+        &fee_payer,
+        &wallet_address,
+        &input.recipient,
+        mint,
+        input.amount,
+        input.memo.as_deref(),
+    )
+    .await
+    .logged("wallet_build_relayed_transfer")
 }
 
 #[tauri::command]
-pub async fn wallet_generate_qr(data: QRCodeData) -> Result<String, String> {
+pub async fn wallet_generate_qr(data: QRCodeData) -> Result<String, AppError> {
     // Generate basic QR code data URI
     // In production, use qrcode crate
-    let json_data = serde_json::to_string(&data).map_err(|e| e.to_string())?;
+    let json_data = serde_json::to_string(&data).logged("wallet_generate_qr")?;
     Ok(format!(
         "data:image/png;base64,mock_qr_code_for_{}",
         json_data
@@ -369,7 +721,7 @@ pub async fn wallet_generate_solana_pay_qr(
     label: Option<String>,
     message: Option<String>,
     memo: Option<String>,
-) -> Result<SolanaPayQR, String> {
+) -> Result<SolanaPayQR, AppError> {
     let mut url = format!("solana:{}", recipient);
     let mut params = vec![];
 
@@ -413,58 +765,89 @@ pub async fn wallet_generate_solana_pay_qr(
 // Address Book Commands
 #[tauri::command]
 pub async fn address_book_add_contact(
+    app: AppHandle,
     request: AddContactRequest,
     operations: State<'_, WalletOperationsManager>,
     keystore: State<'_, Keystore>,
-) -> Result<AddressBookContact, String> {
-    let mut book = operations.address_book.lock().map_err(|e| e.to_string())?;
+) -> Result<AddressBookContact, AppError> {
+    let address = validate_address(&ChainId::Solana, &request.address)
+        .map_err(AppError::Validation)
+        .logged("address_book_add_contact")?;
+    let chain_addresses = validate_chain_addresses(request.chain_addresses)
+        .map_err(AppError::Validation)
+        .logged("address_book_add_contact")?;
+
+    let contact = {
+        let mut book = operations
+            .address_book
+            .lock()
+            .logged("address_book_add_contact")?;
+
+        // Check if address already exists
+        if book.contacts.values().any(|c| c.address == address) {
+            return Err(
+                AppError::Conflict("Contact with this address already exists".to_string())
+                    .logged("address_book_add_contact"),
+            );
+        }
 
-    // Check if address already exists
-    if book.contacts.values().any(|c| c.address == request.address) {
-        return Err("Contact with this address already exists".to_string());
-    }
+        let contact_id = format!("contact_{}", Uuid::new_v4());
+        let now = Utc::now();
 
-    let contact_id = format!("contact_{}", Uuid::new_v4());
-    let now = Utc::now();
+        let contact = AddressBookContact {
+            id: contact_id.clone(),
+            address,
+            label: request.label,
+            nickname: request.nickname,
+            notes: request.notes,
+            created_at: now,
+            updated_at: now,
+            last_used: None,
+            transaction_count: 0,
+            tags: request.tags,
+            chain_addresses,
+        };
 
-    let contact = AddressBookContact {
-        id: contact_id.clone(),
-        address: request.address,
-        label: request.label,
-        nickname: request.nickname,
-        notes: request.notes,
-        created_at: now,
-        updated_at: now,
-        last_used: None,
-        transaction_count: 0,
-        tags: request.tags,
+        book.contacts.insert(contact_id, contact.clone());
+        book.last_updated = now;
+        contact
     };
 
-    book.contacts.insert(contact_id, contact.clone());
-    book.last_updated = now;
-
     operations
         .persist_address_book(&keystore)
-        .map_err(|e| e.to_string())?;
+        .logged("address_book_add_contact")?;
 
+    notify_display_names_changed(&app, contact_addresses(&contact));
     Ok(contact)
 }
 
 #[tauri::command]
 pub async fn address_book_update_contact(
+    app: AppHandle,
     request: UpdateContactRequest,
     operations: State<'_, WalletOperationsManager>,
     keystore: State<'_, Keystore>,
-) -> Result<AddressBookContact, String> {
+) -> Result<AddressBookContact, AppError> {
+    let chain_addresses = request
+        .chain_addresses
+        .map(validate_chain_addresses)
+        .transpose()
+        .map_err(AppError::Validation)
+        .logged("address_book_update_contact")?;
+
     let updated_contact = {
-        let mut book = operations.address_book.lock().map_err(|e| e.to_string())?;
+        let mut book = operations
+            .address_book
+            .lock()
+            .logged("address_book_update_contact")?;
 
         let now = Utc::now();
         let updated = {
             let contact = book
                 .contacts
                 .get_mut(&request.contact_id)
-                .ok_or_else(|| "Contact not found".to_string())?;
+                .ok_or_else(|| AppError::NotFound("Contact not found".to_string()))
+                .logged("address_book_update_contact")?;
 
             if let Some(label) = request.label {
                 contact.label = label;
@@ -478,6 +861,9 @@ pub async fn address_book_update_contact(
             if let Some(tags) = request.tags {
                 contact.tags = tags;
             }
+            if let Some(chain_addresses) = chain_addresses {
+                contact.chain_addresses = chain_addresses;
+            }
 
             contact.updated_at = now;
             contact.clone()
@@ -489,36 +875,56 @@ pub async fn address_book_update_contact(
 
     operations
         .persist_address_book(&keystore)
-        .map_err(|e| e.to_string())?;
+        .logged("address_book_update_contact")?;
 
+    notify_display_names_changed(&app, contact_addresses(&updated_contact));
     Ok(updated_contact)
 }
 
 #[tauri::command]
 pub async fn address_book_delete_contact(
+    app: AppHandle,
     contact_id: String,
     operations: State<'_, WalletOperationsManager>,
     keystore: State<'_, Keystore>,
-) -> Result<(), String> {
-    let mut book = operations.address_book.lock().map_err(|e| e.to_string())?;
+) -> Result<(), AppError> {
+    let removed = {
+        let mut book = operations
+            .address_book
+            .lock()
+            .logged("address_book_delete_contact")?;
 
-    book.contacts
-        .remove(&contact_id)
-        .ok_or_else(|| "Contact not found".to_string())?;
+        let removed = book
+            .contacts
+            .remove(&contact_id)
+            .ok_or_else(|| AppError::NotFound("Contact not found".to_string()))
+            .logged("address_book_delete_contact")?;
 
-    book.last_updated = Utc::now();
+        book.last_updated = Utc::now();
+        removed
+    };
     operations
         .persist_address_book(&keystore)
-        .map_err(|e| e.to_string())?;
+        .logged("address_book_delete_contact")?;
 
+    notify_display_names_changed(&app, contact_addresses(&removed));
     Ok(())
 }
 
+fn contact_addresses(contact: &AddressBookContact) -> Vec<String> {
+    std::iter::once(contact.address.clone())
+        .chain(contact.chain_addresses.values().cloned())
+        .collect()
+}
+
 #[tauri::command]
 pub async fn address_book_list_contacts(
     operations: State<'_, WalletOperationsManager>,
-) -> Result<Vec<AddressBookContact>, String> {
-    let book = operations.address_book.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<AddressBookContact>, AppError> {
+    let book = operations
+        .address_book
+        .lock()
+        .logged("address_book_list_contacts")?;
 
     let mut contacts: Vec<AddressBookContact> = book.contacts.values().cloned().collect();
     contacts.sort_by(|a, b| {
@@ -534,8 +940,11 @@ pub async fn address_book_list_contacts(
 pub async fn address_book_search_contacts(
     query: String,
     operations: State<'_, WalletOperationsManager>,
-) -> Result<Vec<AddressBookContact>, String> {
-    let book = operations.address_book.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<AddressBookContact>, AppError> {
+    let book = operations
+        .address_book
+        .lock()
+        .logged("address_book_search_contacts")?;
 
     let query_lower = query.to_lowercase();
     let contacts: Vec<AddressBookContact> = book
@@ -544,6 +953,9 @@ pub async fn address_book_search_contacts(
         .filter(|c| {
             c.label.to_lowercase().contains(&query_lower)
                 || c.address.to_lowercase().contains(&query_lower)
+                || c.chain_addresses
+                    .values()
+                    .any(|a| a.to_lowercase().contains(&query_lower))
                 || c.nickname
                     .as_ref()
                     .map(|n| n.to_lowercase().contains(&query_lower))
@@ -558,12 +970,137 @@ pub async fn address_book_search_contacts(
     Ok(contacts)
 }
 
+#[tauri::command]
+pub async fn address_book_set_chain_address(
+    contact_id: String,
+    chain_id: String,
+    address: String,
+    operations: State<'_, WalletOperationsManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<AddressBookContact, AppError> {
+    let chain = ChainId::from_str(&chain_id)
+        .ok_or_else(|| AppError::Validation(format!("Invalid chain ID: {}", chain_id)))
+        .logged("address_book_set_chain_address")?;
+    if chain == ChainId::Solana {
+        return Err(AppError::Validation(
+            "A contact's Solana address is its primary address".to_string(),
+        )
+        .logged("address_book_set_chain_address"));
+    }
+    let address = validate_address(&chain, &address)
+        .map_err(AppError::Validation)
+        .logged("address_book_set_chain_address")?;
+
+    let updated = {
+        let mut book = operations
+            .address_book
+            .lock()
+            .logged("address_book_set_chain_address")?;
+        let now = Utc::now();
+        let contact = book
+            .contacts
+            .get_mut(&contact_id)
+            .ok_or_else(|| AppError::NotFound("Contact not found".to_string()))
+            .logged("address_book_set_chain_address")?;
+        contact.chain_addresses.insert(chain, address);
+        contact.updated_at = now;
+        let updated = contact.clone();
+        book.last_updated = now;
+        updated
+    };
+
+    operations
+        .persist_address_book(&keystore)
+        .logged("address_book_set_chain_address")?;
+
+    Ok(updated)
+}
+
+#[tauri::command]
+pub async fn address_book_remove_chain_address(
+    contact_id: String,
+    chain_id: String,
+    operations: State<'_, WalletOperationsManager>,
+    keystore: State<'_, Keystore>,
+) -> Result<AddressBookContact, AppError> {
+    let chain = ChainId::from_str(&chain_id)
+        .ok_or_else(|| AppError::Validation(format!("Invalid chain ID: {}", chain_id)))
+        .logged("address_book_remove_chain_address")?;
+
+    let updated = {
+        let mut book = operations
+            .address_book
+            .lock()
+            .logged("address_book_remove_chain_address")?;
+        let now = Utc::now();
+        let contact = book
+            .contacts
+            .get_mut(&contact_id)
+            .ok_or_else(|| AppError::NotFound("Contact not found".to_string()))
+            .logged("address_book_remove_chain_address")?;
+        contact
+            .chain_addresses
+            .remove(&chain)
+            .ok_or_else(|| AppError::NotFound(format!("Contact has no {} address", chain.as_str())))
+            .logged("address_book_remove_chain_address")?;
+        contact.updated_at = now;
+        let updated = contact.clone();
+        book.last_updated = now;
+        updated
+    };
+
+    operations
+        .persist_address_book(&keystore)
+        .logged("address_book_remove_chain_address")?;
+
+    Ok(updated)
+}
+
+/// The address to send to for a contact on `chain_id`. Errors rather than
+/// falling back to another chain's address.
+#[tauri::command]
+pub async fn address_book_resolve_address(
+    contact_id: String,
+    chain_id: String,
+    operations: State<'_, WalletOperationsManager>,
+) -> Result<String, AppError> {
+    let chain = ChainId::from_str(&chain_id)
+        .ok_or_else(|| AppError::Validation(format!("Invalid chain ID: {}", chain_id)))
+        .logged("address_book_resolve_address")?;
+    let book = operations
+        .address_book
+        .lock()
+        .logged("address_book_resolve_address")?;
+    let contact = book
+        .contacts
+        .get(&contact_id)
+        .ok_or_else(|| AppError::NotFound("Contact not found".to_string()))
+        .logged("address_book_resolve_address")?;
+    let address = contact
+        .address_for(&chain)
+        .ok_or_else(|| {
+            AppError::NotFound(format!(
+                "{} has no {} address saved",
+                contact.label,
+                chain.as_str()
+            ))
+        })
+        .logged("address_book_resolve_address")?;
+    // Entries imported from older exports were never validated.
+    validate_address(&chain, address)
+        .map_err(AppError::Validation)
+        .logged("address_book_resolve_address")
+}
+
 #[tauri::command]
 pub async fn address_book_export(
     operations: State<'_, WalletOperationsManager>,
-) -> Result<String, String> {
-    let book = operations.address_book.lock().map_err(|e| e.to_string())?;
-    serde_json::to_string_pretty(&*book).map_err(|e| e.to_string())
+) -> Result<String, AppError> {
+    let book = operations
+        .address_book
+        .lock()
+        .logged("address_book_export")?;
+    serde_json::to_string_pretty(&*book).logged("address_book_export")
 }
 
 #[tauri::command]
@@ -571,10 +1108,13 @@ pub async fn address_book_import(
     data: String,
     operations: State<'_, WalletOperationsManager>,
     keystore: State<'_, Keystore>,
-) -> Result<usize, String> {
-    let imported_book: AddressBook = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+) -> Result<usize, AppError> {
+    let imported_book: AddressBook = serde_json::from_str(&data).logged("address_book_import")?;
 
-    let mut book = operations.address_book.lock().map_err(|e| e.to_string())?;
+    let mut book = operations
+        .address_book
+        .lock()
+        .logged("address_book_import")?;
     let imported_count = imported_book.contacts.len();
 
     for (id, contact) in imported_book.contacts {
@@ -586,7 +1126,7 @@ pub async fn address_book_import(
     book.last_updated = Utc::now();
     operations
         .persist_address_book(&keystore)
-        .map_err(|e| e.to_string())?;
+        .logged("address_book_import")?;
 
     Ok(imported_count)
 }
@@ -597,8 +1137,11 @@ pub async fn swap_history_add_entry(
     entry: SwapHistoryEntry,
     operations: State<'_, WalletOperationsManager>,
     keystore: State<'_, Keystore>,
-) -> Result<(), String> {
-    let mut history = operations.swap_history.lock().map_err(|e| e.to_string())?;
+) -> Result<(), AppError> {
+    let mut history = operations
+        .swap_history
+        .lock()
+        .logged("swap_history_add_entry")?;
 
     history.swaps.push(entry);
     history.swaps.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
@@ -611,7 +1154,7 @@ pub async fn swap_history_add_entry(
     history.last_updated = Utc::now();
     operations
         .persist_swap_history(&keystore)
-        .map_err(|e| e.to_string())?;
+        .logged("swap_history_add_entry")?;
 
     Ok(())
 }
@@ -620,8 +1163,11 @@ pub async fn swap_history_add_entry(
 pub async fn swap_history_get_recent(
     limit: usize,
     operations: State<'_, WalletOperationsManager>,
-) -> Result<Vec<SwapHistoryEntry>, String> {
-    let history = operations.swap_history.lock().map_err(|e| e.to_string())?;
+) -> Result<Vec<SwapHistoryEntry>, AppError> {
+    let history = operations
+        .swap_history
+        .lock()
+        .logged("swap_history_get_recent")?;
 
     let swaps: Vec<SwapHistoryEntry> = history.swaps.iter().take(limit).cloned().collect();
 
@@ -629,7 +1175,7 @@ pub async fn swap_history_get_recent(
 }
 
 #[tauri::command]
-pub async fn wallet_get_bridge_providers() -> Result<Vec<BridgeProvider>, String> {
+pub async fn wallet_get_bridge_providers() -> Result<Vec<BridgeProvider>, AppError> {
     // Mock bridge providers
     Ok(vec![
         BridgeProvider {
@@ -1,3 +1,4 @@
+use crate::errors::{AppError, CommandResultExt};
 use crate::utils::Rfc3339DateTime;
 use chrono::{DateTime, Utc};
 use chrono::{Datelike, Timelike};
@@ -303,8 +304,17 @@ impl PerformanceDatabase {
     }
 
     pub async fn record_trade(&self, request: RecordTradeRequest) -> Result<Trade, sqlx::Error> {
+        self.record_trade_at(request, Utc::now()).await
+    }
+
+    /// Records a trade at its original execution time. Used when replaying
+    /// history, which must happen oldest first so sells match earlier buys.
+    pub async fn record_trade_at(
+        &self,
+        request: RecordTradeRequest,
+        timestamp: DateTime<Utc>,
+    ) -> Result<Trade, sqlx::Error> {
         let id = format!("trade_{}", uuid::Uuid::new_v4());
-        let timestamp = Utc::now();
         let total_value = request.amount * request.price;
 
         // Calculate PnL for sell trades by finding matching buy
@@ -367,6 +377,54 @@ impl PerformanceDatabase {
         Ok(trade)
     }
 
+    pub async fn has_trade_signature(
+        &self,
+        wallet_address: &str,
+        tx_signature: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) AS count FROM trades WHERE wallet_address = ?1 AND tx_signature = ?2",
+        )
+        .bind(wallet_address)
+        .bind(tx_signature)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.try_get::<i64, _>("count")? > 0)
+    }
+
+    pub async fn trades_for_signature(
+        &self,
+        wallet_address: &str,
+        tx_signature: &str,
+    ) -> Result<Vec<Trade>, sqlx::Error> {
+        sqlx::query_as::<_, Trade>(
+            "SELECT * FROM trades WHERE wallet_address = ?1 AND tx_signature = ?2",
+        )
+        .bind(wallet_address)
+        .bind(tx_signature)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// All trades for a wallet up to `end` (inclusive), oldest first.
+    pub async fn trades_until(
+        &self,
+        wallet_address: &str,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Trade>, sqlx::Error> {
+        sqlx::query_as::<_, Trade>(
+            r#"
+            SELECT * FROM trades
+            WHERE wallet_address = ?1 AND (?2 IS NULL OR timestamp <= ?2)
+            ORDER BY timestamp ASC
+            "#,
+        )
+        .bind(wallet_address)
+        .bind(end.map(|end| end.to_rfc3339()))
+        .fetch_all(&self.pool)
+        .await
+    }
+
     async fn calculate_pnl(
         &self,
         wallet_address: &str,
@@ -988,31 +1046,31 @@ pub type SharedPerformanceDatabase = Arc<RwLock<PerformanceDatabase>>;
 pub async fn record_trade(
     request: RecordTradeRequest,
     db: State<'_, SharedPerformanceDatabase>,
-) -> Result<Trade, String> {
+) -> Result<Trade, AppError> {
     let db = db.read().await;
-    db.record_trade(request).await.map_err(|e| e.to_string())
+    db.record_trade(request).await.logged("record_trade")
 }
 
 #[tauri::command]
 pub async fn calculate_wallet_performance(
     wallet_address: String,
     db: State<'_, SharedPerformanceDatabase>,
-) -> Result<PerformanceScore, String> {
+) -> Result<PerformanceScore, AppError> {
     let db = db.read().await;
     db.calculate_performance_score(&wallet_address)
         .await
-        .map_err(|e| e.to_string())
+        .logged("calculate_wallet_performance")
 }
 
 #[tauri::command]
 pub async fn get_wallet_performance_data(
     wallet_address: String,
     db: State<'_, SharedPerformanceDatabase>,
-) -> Result<WalletPerformanceData, String> {
+) -> Result<WalletPerformanceData, AppError> {
     let db = db.read().await;
     db.get_wallet_performance(&wallet_address)
         .await
-        .map_err(|e| e.to_string())
+        .logged("get_wallet_performance_data")
 }
 
 #[tauri::command]
@@ -1020,33 +1078,33 @@ pub async fn get_performance_score_history(
     wallet_address: String,
     limit: i64,
     db: State<'_, SharedPerformanceDatabase>,
-) -> Result<Vec<PerformanceScore>, String> {
+) -> Result<Vec<PerformanceScore>, AppError> {
     let db = db.read().await;
     db.get_score_history(&wallet_address, limit)
         .await
-        .map_err(|e| e.to_string())
+        .logged("get_performance_score_history")
 }
 
 #[tauri::command]
 pub async fn get_token_performance_breakdown(
     wallet_address: String,
     db: State<'_, SharedPerformanceDatabase>,
-) -> Result<Vec<TokenPerformance>, String> {
+) -> Result<Vec<TokenPerformance>, AppError> {
     let db = db.read().await;
     db.get_token_performance(&wallet_address)
         .await
-        .map_err(|e| e.to_string())
+        .logged("get_token_performance_breakdown")
 }
 
 #[tauri::command]
 pub async fn get_timing_analysis_data(
     wallet_address: String,
     db: State<'_, SharedPerformanceDatabase>,
-) -> Result<Vec<TimingAnalysis>, String> {
+) -> Result<Vec<TimingAnalysis>, AppError> {
     let db = db.read().await;
     db.get_timing_analysis(&wallet_address)
         .await
-        .map_err(|e| e.to_string())
+        .logged("get_timing_analysis_data")
 }
 
 #[tauri::command]
@@ -1054,22 +1112,22 @@ pub async fn get_best_worst_trades_data(
     wallet_address: String,
     limit: i64,
     db: State<'_, SharedPerformanceDatabase>,
-) -> Result<BestWorstTrades, String> {
+) -> Result<BestWorstTrades, AppError> {
     let db = db.read().await;
     db.get_best_worst_trades(&wallet_address, limit)
         .await
-        .map_err(|e| e.to_string())
+        .logged("get_best_worst_trades_data")
 }
 
 #[tauri::command]
 pub async fn get_benchmark_comparison_data(
     wallet_address: String,
     db: State<'_, SharedPerformanceDatabase>,
-) -> Result<Option<BenchmarkComparison>, String> {
+) -> Result<Option<BenchmarkComparison>, AppError> {
     let db = db.read().await;
     db.get_benchmark_comparison(&wallet_address)
         .await
-        .map_err(|e| e.to_string())
+        .logged("get_benchmark_comparison_data")
 }
 
 #[tauri::command]
@@ -1077,9 +1135,9 @@ pub async fn get_performance_alerts(
     wallet_address: String,
     limit: i64,
     db: State<'_, SharedPerformanceDatabase>,
-) -> Result<Vec<ScoreAlert>, String> {
+) -> Result<Vec<ScoreAlert>, AppError> {
     let db = db.read().await;
     db.get_score_alerts(&wallet_address, limit)
         .await
-        .map_err(|e| e.to_string())
+        .logged("get_performance_alerts")
 }
@@ -1,4 +1,5 @@
 use crate::data::event_store::{Event as AuditEvent, SharedEventStore};
+use crate::profiles::ProfilePaths;
 use crate::security::activity_log::ActivityLogger;
 use base64::engine::general_purpose::STANDARD as BASE64_ENGINE;
 use base64::Engine;
@@ -172,7 +173,7 @@ pub fn hydrate_wallet_state(app: &AppHandle) -> Result<(), PhantomError> {
 }
 
 fn session_path(app: &AppHandle) -> Result<PathBuf, PhantomError> {
-    let mut path = app.path().app_data_dir().map_err(|err| {
+    let mut path = app.path().profile_data_dir().map_err(|err| {
         PhantomError::storage(format!("Unable to resolve app data directory: {err}"))
     })?;
     if !path.exists() {
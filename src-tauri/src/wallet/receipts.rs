@@ -0,0 +1,521 @@
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use std::collections::HashMap;
+use std::str::FromStr;
+use tauri::{AppHandle, Manager, State};
+
+use crate::chains::SharedRpcPool;
+use crate::environment::active_environment;
+use crate::errors::{AppError, CommandResultExt};
+use crate::security::keystore::Keystore;
+use crate::wallet::flows::{FlowDirection, SharedFlowLedger};
+use crate::wallet::history_backfill::{
+    fetch_transaction, parse_transaction, symbol_for, HistoricalActivity, TokenDelta, WSOL_MINT,
+};
+use crate::wallet::performance::SharedPerformanceDatabase;
+
+const RECEIPT_SIGNING_KEY: &str = "receipt-signing-key";
+const RECEIPT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReceiptKind {
+    Trade,
+    Transfer,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReceiptFormat {
+    Json,
+    Pdf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptRequest {
+    pub wallet_address: String,
+    pub signature: String,
+    /// Defaults to JSON.
+    pub format: Option<ReceiptFormat>,
+    /// Free text for the counterparty, such as an OTC deal reference.
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptLeg {
+    pub direction: FlowDirection,
+    pub mint: String,
+    pub symbol: String,
+    pub amount: f64,
+    pub price_usd: Option<f64>,
+    pub value_usd: Option<f64>,
+}
+
+/// The signed part of a receipt. Field order is fixed by this struct, so
+/// re-serializing a received body reproduces the signed bytes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptBody {
+    pub version: u32,
+    pub receipt_id: String,
+    pub wallet_address: String,
+    pub tx_signature: String,
+    pub cluster: String,
+    pub block_time: DateTime<Utc>,
+    pub kind: ReceiptKind,
+    pub legs: Vec<ReceiptLeg>,
+    pub fee_sol: f64,
+    pub fee_usd: Option<f64>,
+    pub counterparty: Option<String>,
+    pub note: Option<String>,
+    pub issued_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionReceipt {
+    pub body: ReceiptBody,
+    /// Hex SHA-256 of the serialized body.
+    pub body_sha256: String,
+    /// Base58 ed25519 public key of the issuing app.
+    pub signer: String,
+    /// Base58 ed25519 signature over the serialized body.
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptDocument {
+    pub format: ReceiptFormat,
+    pub file_name: String,
+    pub mime_type: String,
+    /// Pretty-printed JSON, or base64 for PDF.
+    pub content: String,
+    pub receipt: TransactionReceipt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptVerification {
+    pub valid: bool,
+    /// Whether this installation issued the receipt.
+    pub issued_here: bool,
+    pub reason: Option<String>,
+}
+
+fn body_bytes(body: &ReceiptBody) -> Result<Vec<u8>, String> {
+    serde_json::to_vec(body).map_err(|e| format!("Failed to serialize receipt: {e}"))
+}
+
+pub fn sign_receipt(body: ReceiptBody, keypair: &Keypair) -> Result<TransactionReceipt, String> {
+    let bytes = body_bytes(&body)?;
+    Ok(TransactionReceipt {
+        body_sha256: hex::encode(Sha256::digest(&bytes)),
+        signer: keypair.pubkey().to_string(),
+        signature: keypair.sign_message(&bytes).to_string(),
+        body,
+    })
+}
+
+pub fn verify_receipt(receipt: &TransactionReceipt) -> Result<(), String> {
+    let bytes = body_bytes(&receipt.body)?;
+    if hex::encode(Sha256::digest(&bytes)) != receipt.body_sha256 {
+        return Err("Receipt digest does not match its contents".to_string());
+    }
+    let signer = Pubkey::from_str(&receipt.signer).map_err(|_| "Invalid signer key".to_string())?;
+    let signature =
+        Signature::from_str(&receipt.signature).map_err(|_| "Invalid signature".to_string())?;
+    if !signature.verify(signer.as_ref(), &bytes) {
+        return Err("Signature does not match the receipt contents".to_string());
+    }
+    Ok(())
+}
+
+/// The app's receipt signing key, created on first use. Counterparties
+/// verify receipts against its public key.
+fn signing_keypair(keystore: &Keystore) -> Result<Keypair, String> {
+    if let Ok(secret) = keystore.retrieve_secret(RECEIPT_SIGNING_KEY) {
+        return Keypair::from_bytes(&secret).map_err(|e| format!("Invalid receipt key: {e}"));
+    }
+    let keypair = Keypair::new();
+    keystore
+        .store_secret(RECEIPT_SIGNING_KEY, &keypair.to_bytes())
+        .map_err(|e| format!("Failed to store receipt key: {e}"))?;
+    Ok(keypair)
+}
+
+/// Fills in leg prices. Known prices come first; on a trade both legs
+/// share one value, so a single priced leg prices the other.
+fn price_legs(legs: &mut [ReceiptLeg], kind: ReceiptKind, known: &HashMap<String, f64>) {
+    for leg in legs.iter_mut() {
+        leg.price_usd = known.get(&leg.mint).copied();
+        leg.value_usd = leg.price_usd.map(|price| price * leg.amount);
+    }
+    if kind != ReceiptKind::Trade {
+        return;
+    }
+    let Some(value) = legs.iter().find_map(|leg| leg.value_usd) else {
+        return;
+    };
+    for leg in legs.iter_mut().filter(|leg| leg.value_usd.is_none()) {
+        leg.value_usd = Some(value);
+        leg.price_usd = (leg.amount > 0.0).then(|| value / leg.amount);
+    }
+}
+
+fn leg(direction: FlowDirection, delta: &TokenDelta) -> ReceiptLeg {
+    ReceiptLeg {
+        direction,
+        mint: delta.mint.clone(),
+        symbol: symbol_for(&delta.mint),
+        amount: delta.amount.abs(),
+        price_usd: None,
+        value_usd: None,
+    }
+}
+
+fn pdf_escape(line: &str) -> String {
+    line.chars()
+        .map(|c| {
+            if c.is_ascii() && !c.is_ascii_control() {
+                c
+            } else {
+                '?'
+            }
+        })
+        .collect::<String>()
+        .replace('\\', "\\\\")
+        .replace('(', "\\(")
+        .replace(')', "\\)")
+}
+
+fn receipt_lines(receipt: &TransactionReceipt) -> Vec<String> {
+    let body = &receipt.body;
+    let usd = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("${v:.2}"));
+    let mut lines = vec![
+        format!("Transaction Receipt {}", body.receipt_id),
+        String::new(),
+        format!("Type:          {:?}", body.kind),
+        format!("Wallet:        {}", body.wallet_address),
+        format!("Cluster:       {}", body.cluster),
+        format!("Executed:      {}", body.block_time.to_rfc3339()),
+        format!("Transaction:   {}", body.tx_signature),
+        String::new(),
+    ];
+    for leg in &body.legs {
+        let direction = match leg.direction {
+            FlowDirection::In => "Received",
+            FlowDirection::Out => "Sent",
+        };
+        lines.push(format!(
+            "{direction:<10} {} {} @ {} = {}",
+            leg.amount,
+            leg.symbol,
+            usd(leg.price_usd),
+            usd(leg.value_usd)
+        ));
+        lines.push(format!("           mint {}", leg.mint));
+    }
+    lines.push(String::new());
+    lines.push(format!(
+        "Network fee:   {} SOL ({})",
+        body.fee_sol,
+        usd(body.fee_usd)
+    ));
+    if let Some(counterparty) = &body.counterparty {
+        lines.push(format!("Counterparty:  {counterparty}"));
+    }
+    if let Some(note) = &body.note {
+        lines.push(format!("Note:          {note}"));
+    }
+    lines.push(format!("Issued:        {}", body.issued_at.to_rfc3339()));
+    lines.push(String::new());
+    lines.push("Verification (ed25519 over the JSON receipt body)".to_string());
+    lines.push(format!("Signer:        {}", receipt.signer));
+    lines.push(format!("SHA-256:       {}", receipt.body_sha256));
+    lines.push("Signature:".to_string());
+    lines.push(format!("  {}", receipt.signature));
+    lines
+}
+
+/// Renders a single-page A4 PDF using the built-in Courier font.
+pub fn render_pdf(receipt: &TransactionReceipt) -> Vec<u8> {
+    let mut content = String::from("BT /F1 9 Tf 12 TL 40 800 Td\n");
+    for line in receipt_lines(receipt) {
+        content.push_str(&format!("({}) Tj T*\n", pdf_escape(&line)));
+    }
+    content.push_str("ET");
+
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 595 842] \
+         /Resources << /Font << /F1 4 0 R >> >> /Contents 5 0 R >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Courier >>".to_string(),
+        format!(
+            "<< /Length {} >>\nstream\n{content}\nendstream",
+            content.len()
+        ),
+    ];
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{object}\nendobj\n", index + 1));
+    }
+    let xref = pdf.len();
+    pdf.push_str(&format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    ));
+    for offset in offsets {
+        pdf.push_str(&format!("{offset:010} 00000 n \n"));
+    }
+    pdf.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        objects.len() + 1
+    ));
+    pdf.into_bytes()
+}
+
+/// Builds a signed receipt for a confirmed transaction. Amounts and fees
+/// come from the chain; prices come from recorded trades and the flow
+/// ledger where available.
+pub async fn build_receipt(
+    app: &AppHandle,
+    request: ReceiptRequest,
+) -> Result<ReceiptDocument, String> {
+    let pool = app.state::<SharedRpcPool>();
+    let performance = app.state::<SharedPerformanceDatabase>();
+    let flows = app.state::<SharedFlowLedger>();
+    let keystore = app.state::<Keystore>();
+    let ReceiptRequest {
+        wallet_address,
+        signature,
+        format,
+        note,
+    } = request;
+    let tx = fetch_transaction(&pool, &signature)
+        .await?
+        .ok_or_else(|| format!("Transaction {signature} not found"))?;
+    let parsed = parse_transaction(&wallet_address, &signature, &tx)
+        .ok_or_else(|| "Transaction failed or has no block time".to_string())?;
+    if parsed.activities.is_empty() {
+        return Err(format!("Transaction moved no funds for {wallet_address}"));
+    }
+
+    let mut kind = ReceiptKind::Transfer;
+    let mut legs = Vec::new();
+    for activity in &parsed.activities {
+        match activity {
+            HistoricalActivity::Swap { sold, bought } => {
+                kind = ReceiptKind::Trade;
+                legs.push(leg(FlowDirection::Out, sold));
+                legs.push(leg(FlowDirection::In, bought));
+            }
+            HistoricalActivity::TransferIn(delta) => legs.push(leg(FlowDirection::In, delta)),
+            HistoricalActivity::TransferOut(delta) => legs.push(leg(FlowDirection::Out, delta)),
+        }
+    }
+
+    let recorded = flows.read().list(Some(&wallet_address), None);
+    let recorded: Vec<_> = recorded
+        .into_iter()
+        .filter(|flow| flow.signature == signature)
+        .collect();
+    let mut known: HashMap<String, f64> = recorded
+        .iter()
+        .filter(|flow| flow.amount > 0.0)
+        .filter_map(|flow| Some((flow.mint.clone(), flow.usd_value? / flow.amount)))
+        .collect();
+    let trades = performance
+        .read()
+        .await
+        .trades_for_signature(&wallet_address, &signature)
+        .await
+        .map_err(|e| e.to_string())?;
+    for trade in trades {
+        known.insert(trade.token_mint, trade.price);
+    }
+    price_legs(&mut legs, kind, &known);
+
+    let sol_price = legs
+        .iter()
+        .find(|leg| leg.mint == WSOL_MINT)
+        .and_then(|leg| leg.price_usd)
+        .or_else(|| known.get(WSOL_MINT).copied());
+    let counterparty = recorded
+        .iter()
+        .find_map(|flow| flow.counterparty.clone())
+        .or_else(|| match kind {
+            ReceiptKind::Transfer => legs
+                .iter()
+                .find_map(|leg| parsed.counterparties.get(&leg.mint)?.first().cloned()),
+            ReceiptKind::Trade => None,
+        });
+
+    let digest = Sha256::digest(format!("{wallet_address}:{signature}").as_bytes());
+    let body = ReceiptBody {
+        version: RECEIPT_VERSION,
+        receipt_id: format!("rcpt-{}", &hex::encode(digest)[..16]),
+        wallet_address,
+        tx_signature: signature,
+        cluster: active_environment().environment.as_str().to_string(),
+        block_time: parsed.timestamp,
+        kind,
+        legs,
+        fee_sol: parsed.fee_sol,
+        fee_usd: sol_price.map(|price| price * parsed.fee_sol),
+        counterparty,
+        note: note.filter(|note| !note.trim().is_empty()),
+        issued_at: Utc::now(),
+    };
+    let receipt = sign_receipt(body, &signing_keypair(&keystore)?)?;
+
+    let format = format.unwrap_or(ReceiptFormat::Json);
+    let (extension, mime_type, content) = match format {
+        ReceiptFormat::Json => (
+            "json",
+            "application/json",
+            serde_json::to_string_pretty(&receipt).map_err(|e| e.to_string())?,
+        ),
+        ReceiptFormat::Pdf => (
+            "pdf",
+            "application/pdf",
+            general_purpose::STANDARD.encode(render_pdf(&receipt)),
+        ),
+    };
+    Ok(ReceiptDocument {
+        format,
+        file_name: format!("{}.{extension}", receipt.body.receipt_id),
+        mime_type: mime_type.to_string(),
+        content,
+        receipt,
+    })
+}
+
+#[tauri::command]
+pub async fn generate_transaction_receipt(
+    app: AppHandle,
+    request: ReceiptRequest,
+) -> Result<ReceiptDocument, AppError> {
+    build_receipt(&app, request)
+        .await
+        .logged("generate_transaction_receipt")
+}
+
+#[tauri::command]
+pub async fn verify_transaction_receipt(
+    receipt: TransactionReceipt,
+    keystore: State<'_, Keystore>,
+) -> Result<ReceiptVerification, AppError> {
+    let issued_here = signing_keypair(&keystore)
+        .logged("verify_transaction_receipt")?
+        .pubkey()
+        .to_string()
+        == receipt.signer;
+    Ok(match verify_receipt(&receipt) {
+        Ok(()) => ReceiptVerification {
+            valid: true,
+            issued_here,
+            reason: None,
+        },
+        Err(reason) => ReceiptVerification {
+            valid: false,
+            issued_here,
+            reason: Some(reason),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body() -> ReceiptBody {
+        let mut legs = vec![
+            leg(
+                FlowDirection::Out,
+                &TokenDelta {
+                    mint: WSOL_MINT.to_string(),
+                    amount: -2.0,
+                },
+            ),
+            leg(
+                FlowDirection::In,
+                &TokenDelta {
+                    mint: "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string(),
+                    amount: 1_000_000.0,
+                },
+            ),
+        ];
+        price_legs(
+            &mut legs,
+            ReceiptKind::Trade,
+            &HashMap::from([(WSOL_MINT.to_string(), 150.0)]),
+        );
+        ReceiptBody {
+            version: RECEIPT_VERSION,
+            receipt_id: "rcpt-test".to_string(),
+            wallet_address: "Owner11111111111111111111111111111111111111".to_string(),
+            tx_signature: "sig".to_string(),
+            cluster: "mainnet".to_string(),
+            block_time: Utc::now(),
+            kind: ReceiptKind::Trade,
+            legs,
+            fee_sol: 0.000005,
+            fee_usd: Some(0.00075),
+            counterparty: None,
+            note: Some("OTC (desk)".to_string()),
+            issued_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn signed_receipts_survive_a_round_trip_and_detect_tampering() {
+        let body = body();
+        assert_eq!(body.legs[1].value_usd, Some(300.0));
+
+        let receipt = sign_receipt(body, &Keypair::new()).unwrap();
+        let json = serde_json::to_string(&receipt).unwrap();
+        let received: TransactionReceipt = serde_json::from_str(&json).unwrap();
+        assert!(verify_receipt(&received).is_ok());
+
+        let mut tampered = received.clone();
+        tampered.body.legs[0].amount = 20.0;
+        assert!(verify_receipt(&tampered).is_err());
+
+        let mut resigned = received;
+        resigned.body.legs[0].amount = 20.0;
+        resigned.body_sha256 = hex::encode(Sha256::digest(body_bytes(&resigned.body).unwrap()));
+        assert!(verify_receipt(&resigned).is_err());
+    }
+
+    #[test]
+    fn pdf_cross_reference_points_at_objects() {
+        let receipt = sign_receipt(body(), &Keypair::new()).unwrap();
+        let pdf = String::from_utf8(render_pdf(&receipt)).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.contains("OTC \\(desk\\)"));
+
+        let startxref: usize = pdf
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .and_then(|offset| offset.parse().ok())
+            .unwrap();
+        assert!(pdf[startxref..].starts_with("xref"));
+        let first_object: usize = pdf[startxref..].lines().nth(3).unwrap()[..10]
+            .parse()
+            .unwrap();
+        assert!(pdf[first_object..].starts_with("1 0 obj"));
+    }
+}
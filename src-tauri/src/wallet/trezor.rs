@@ -0,0 +1,521 @@
+//! Trezor devices reached through Trezor Bridge (`trezord`), the local
+//! service Trezor Suite installs. The bridge handles USB; this module speaks
+//! the Trezor wire protocol over its HTTP API.
+
+use super::hardware_wallet::{
+    DeviceType, GetAddressResponse, HardwareWalletDevice, HardwareWalletError,
+};
+use serde::Deserialize;
+use std::time::Duration;
+
+const BRIDGE_URL: &str = "http://127.0.0.1:21325";
+/// The bridge only answers origins on its allow-list; local development
+/// origins are on it.
+const BRIDGE_ORIGIN: &str = "http://localhost:8000";
+const DEVICE_ID_PREFIX: &str = "trezor:";
+/// Solana support landed in Trezor firmware 2.7.1.
+const MIN_SOLANA_FIRMWARE: (u32, u32, u32) = (2, 7, 1);
+
+// Message type ids from the firmware's messages.proto.
+const MSG_INITIALIZE: u16 = 0;
+const MSG_FAILURE: u16 = 3;
+const MSG_FEATURES: u16 = 17;
+const MSG_PIN_MATRIX_REQUEST: u16 = 18;
+const MSG_CANCEL: u16 = 20;
+const MSG_BUTTON_REQUEST: u16 = 26;
+const MSG_BUTTON_ACK: u16 = 27;
+const MSG_PASSPHRASE_REQUEST: u16 = 41;
+const MSG_PASSPHRASE_ACK: u16 = 42;
+const MSG_SOLANA_GET_ADDRESS: u16 = 902;
+const MSG_SOLANA_ADDRESS: u16 = 903;
+const MSG_SOLANA_SIGN_TX: u16 = 904;
+const MSG_SOLANA_TX_SIGNATURE: u16 = 905;
+
+const FAILURE_ACTION_CANCELLED: u64 = 4;
+const FAILURE_PIN_CANCELLED: u64 = 6;
+
+#[derive(Debug, Deserialize)]
+struct BridgeDevice {
+    path: String,
+    session: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BridgeSession {
+    session: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrezorFeatures {
+    pub vendor: Option<String>,
+    pub version: (u32, u32, u32),
+    pub label: Option<String>,
+    pub model: Option<String>,
+}
+
+impl TrezorFeatures {
+    pub fn firmware_string(&self) -> String {
+        format!("{}.{}.{}", self.version.0, self.version.1, self.version.2)
+    }
+}
+
+enum Field {
+    Varint(u64),
+    Bytes(Vec<u8>),
+}
+
+fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn put_varint_field(buf: &mut Vec<u8>, field: u32, value: u64) {
+    put_varint(buf, u64::from(field) << 3);
+    put_varint(buf, value);
+}
+
+fn put_bytes_field(buf: &mut Vec<u8>, field: u32, value: &[u8]) {
+    put_varint(buf, (u64::from(field) << 3) | 2);
+    put_varint(buf, value.len() as u64);
+    buf.extend_from_slice(value);
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64, HardwareWalletError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes
+            .get(*pos)
+            .ok_or_else(|| HardwareWalletError::InvalidResponse("Truncated varint".to_string()))?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(HardwareWalletError::InvalidResponse(
+        "Varint too long".to_string(),
+    ))
+}
+
+/// Minimal protobuf reader: varint and length-delimited fields are kept,
+/// fixed-width fields are skipped since no message used here has any.
+fn decode_fields(bytes: &[u8]) -> Result<Vec<(u32, Field)>, HardwareWalletError> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let key = read_varint(bytes, &mut pos)?;
+        let number = (key >> 3) as u32;
+        match key & 7 {
+            0 => fields.push((number, Field::Varint(read_varint(bytes, &mut pos)?))),
+            2 => {
+                let len = read_varint(bytes, &mut pos)? as usize;
+                let end = pos
+                    .checked_add(len)
+                    .filter(|end| *end <= bytes.len())
+                    .ok_or_else(|| {
+                        HardwareWalletError::InvalidResponse("Truncated field".to_string())
+                    })?;
+                fields.push((number, Field::Bytes(bytes[pos..end].to_vec())));
+                pos = end;
+            }
+            1 => pos += 8,
+            5 => pos += 4,
+            other => {
+                return Err(HardwareWalletError::InvalidResponse(format!(
+                    "Unsupported wire type {}",
+                    other
+                )))
+            }
+        }
+    }
+    Ok(fields)
+}
+
+fn string_field(fields: &[(u32, Field)], number: u32) -> Option<String> {
+    fields.iter().find_map(|(n, f)| match f {
+        Field::Bytes(b) if *n == number => String::from_utf8(b.clone()).ok(),
+        _ => None,
+    })
+}
+
+fn bytes_field(fields: &[(u32, Field)], number: u32) -> Option<Vec<u8>> {
+    fields.iter().find_map(|(n, f)| match f {
+        Field::Bytes(b) if *n == number => Some(b.clone()),
+        _ => None,
+    })
+}
+
+fn varint_field(fields: &[(u32, Field)], number: u32) -> Option<u64> {
+    fields.iter().find_map(|(n, f)| match f {
+        Field::Varint(v) if *n == number => Some(*v),
+        _ => None,
+    })
+}
+
+/// Bridge framing: 2-byte message type and 4-byte length, big-endian,
+/// followed by the protobuf body, all hex encoded.
+fn encode_frame(message_type: u16, payload: &[u8]) -> String {
+    let mut frame = Vec::with_capacity(6 + payload.len());
+    frame.extend_from_slice(&message_type.to_be_bytes());
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(payload);
+    hex::encode(frame)
+}
+
+fn decode_frame(body: &str) -> Result<(u16, Vec<u8>), HardwareWalletError> {
+    let bytes = hex::decode(body.trim())
+        .map_err(|e| HardwareWalletError::InvalidResponse(format!("Bad bridge frame: {}", e)))?;
+    if bytes.len() < 6 {
+        return Err(HardwareWalletError::InvalidResponse(
+            "Bridge frame too short".to_string(),
+        ));
+    }
+    let message_type = u16::from_be_bytes([bytes[0], bytes[1]]);
+    let len = u32::from_be_bytes([bytes[2], bytes[3], bytes[4], bytes[5]]) as usize;
+    let payload = bytes.get(6..6 + len).ok_or_else(|| {
+        HardwareWalletError::InvalidResponse("Bridge frame shorter than its length".to_string())
+    })?;
+    Ok((message_type, payload.to_vec()))
+}
+
+fn parse_features(payload: &[u8]) -> Result<TrezorFeatures, HardwareWalletError> {
+    let fields = decode_fields(payload)?;
+    let version = |n| varint_field(&fields, n).unwrap_or(0) as u32;
+    Ok(TrezorFeatures {
+        vendor: string_field(&fields, 1),
+        version: (version(2), version(3), version(4)),
+        label: string_field(&fields, 10),
+        model: string_field(&fields, 21),
+    })
+}
+
+fn path_message(derivation_path: &[u32]) -> Vec<u8> {
+    let mut payload = Vec::new();
+    for index in derivation_path {
+        put_varint_field(&mut payload, 1, u64::from(*index));
+    }
+    payload
+}
+
+pub fn is_trezor_device_id(device_id: &str) -> bool {
+    device_id.starts_with(DEVICE_ID_PREFIX)
+}
+
+fn bridge_path(device_id: &str) -> Result<&str, HardwareWalletError> {
+    device_id
+        .strip_prefix(DEVICE_ID_PREFIX)
+        .ok_or(HardwareWalletError::DeviceNotFound)
+}
+
+/// Backend for Trezor devices attached to this machine.
+pub struct TrezorBackend {
+    client: reqwest::Client,
+}
+
+impl Default for TrezorBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrezorBackend {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                // Calls block until the user confirms on the device.
+                .timeout(Duration::from_secs(120))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn post(&self, endpoint: &str, body: String) -> Result<String, HardwareWalletError> {
+        let response = self
+            .client
+            .post(format!("{}{}", BRIDGE_URL, endpoint))
+            .header("Origin", BRIDGE_ORIGIN)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| {
+                HardwareWalletError::Communication(format!("Trezor Bridge unreachable: {}", e))
+            })?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| HardwareWalletError::Communication(e.to_string()))?;
+        if !status.is_success() {
+            return Err(HardwareWalletError::Communication(format!(
+                "Trezor Bridge returned {}: {}",
+                status,
+                text.trim()
+            )));
+        }
+        Ok(text)
+    }
+
+    /// Devices the bridge sees. An absent bridge means no Trezor, not an
+    /// error, since most users only have a Ledger or nothing at all.
+    pub async fn enumerate(&self) -> Vec<HardwareWalletDevice> {
+        let devices: Vec<BridgeDevice> = match self.post("/enumerate", String::new()).await {
+            Ok(body) => serde_json::from_str(&body).unwrap_or_default(),
+            Err(_) => return Vec::new(),
+        };
+
+        let mut found = Vec::new();
+        for device in devices {
+            let mut entry = HardwareWalletDevice {
+                device_id: format!("{}{}", DEVICE_ID_PREFIX, device.path),
+                device_type: DeviceType::Trezor,
+                product_name: "Trezor".to_string(),
+                manufacturer: "SatoshiLabs".to_string(),
+                connected: false,
+                firmware_version: None,
+                address: None,
+            };
+            // A device held by another app (e.g. Trezor Suite) cannot be
+            // queried without stealing its session.
+            if device.session.is_none() {
+                if let Ok(features) = self.features(&entry.device_id).await {
+                    entry.product_name = match (&features.model, &features.label) {
+                        (Some(model), Some(label)) => format!("Trezor Model {} ({})", model, label),
+                        (Some(model), None) => format!("Trezor Model {}", model),
+                        (None, Some(label)) => format!("Trezor ({})", label),
+                        (None, None) => "Trezor".to_string(),
+                    };
+                    entry.firmware_version = Some(features.firmware_string());
+                }
+            }
+            found.push(entry);
+        }
+        found
+    }
+
+    async fn acquire(&self, path: &str) -> Result<String, HardwareWalletError> {
+        let body = self
+            .post(&format!("/acquire/{}/null", path), String::new())
+            .await?;
+        let session: BridgeSession = serde_json::from_str(&body)
+            .map_err(|e| HardwareWalletError::InvalidResponse(e.to_string()))?;
+        Ok(session.session)
+    }
+
+    async fn release(&self, session: &str) {
+        let _ = self
+            .post(&format!("/release/{}", session), String::new())
+            .await;
+    }
+
+    /// Sends one message and follows the device through button and
+    /// passphrase prompts until it answers with `expected`.
+    async fn exchange(
+        &self,
+        session: &str,
+        message_type: u16,
+        payload: Vec<u8>,
+        expected: u16,
+    ) -> Result<Vec<u8>, HardwareWalletError> {
+        let mut outgoing = (message_type, payload);
+        loop {
+            let body = self
+                .post(
+                    &format!("/call/{}", session),
+                    encode_frame(outgoing.0, &outgoing.1),
+                )
+                .await?;
+            let (response_type, response) = decode_frame(&body)?;
+            outgoing = match response_type {
+                t if t == expected => return Ok(response),
+                MSG_BUTTON_REQUEST => (MSG_BUTTON_ACK, Vec::new()),
+                MSG_PASSPHRASE_REQUEST => {
+                    // Passphrases are entered on the device, never in the app.
+                    let mut ack = Vec::new();
+                    put_varint_field(&mut ack, 3, 1);
+                    (MSG_PASSPHRASE_ACK, ack)
+                }
+                MSG_PIN_MATRIX_REQUEST => {
+                    let _ = self
+                        .post(&format!("/call/{}", session), encode_frame(MSG_CANCEL, &[]))
+                        .await;
+                    return Err(HardwareWalletError::Communication(
+                        "Unlock the Trezor with its PIN in Trezor Suite, then retry".to_string(),
+                    ));
+                }
+                MSG_FAILURE => {
+                    let fields = decode_fields(&response)?;
+                    let code = varint_field(&fields, 1);
+                    if matches!(code, Some(FAILURE_ACTION_CANCELLED | FAILURE_PIN_CANCELLED)) {
+                        return Err(HardwareWalletError::UserRejected);
+                    }
+                    return Err(HardwareWalletError::Communication(
+                        string_field(&fields, 2).unwrap_or_else(|| "Device failure".to_string()),
+                    ));
+                }
+                other => {
+                    return Err(HardwareWalletError::InvalidResponse(format!(
+                        "Unexpected message type {}",
+                        other
+                    )))
+                }
+            };
+        }
+    }
+
+    /// Acquires a session, initializes it and runs `op` with the device's
+    /// features, always releasing the session so Trezor Suite can use the
+    /// device afterwards.
+    async fn with_session<T, F, Fut>(
+        &self,
+        device_id: &str,
+        op: F,
+    ) -> Result<T, HardwareWalletError>
+    where
+        F: FnOnce(String, TrezorFeatures) -> Fut,
+        Fut: std::future::Future<Output = Result<T, HardwareWalletError>>,
+    {
+        let session = self.acquire(bridge_path(device_id)?).await?;
+        let result = async {
+            let features = parse_features(
+                &self
+                    .exchange(&session, MSG_INITIALIZE, Vec::new(), MSG_FEATURES)
+                    .await?,
+            )?;
+            op(session.clone(), features).await
+        }
+        .await;
+        self.release(&session).await;
+        result
+    }
+
+    pub async fn features(&self, device_id: &str) -> Result<TrezorFeatures, HardwareWalletError> {
+        self.with_session(device_id, |_, features| async move { Ok(features) })
+            .await
+    }
+
+    fn require_solana(features: &TrezorFeatures) -> Result<(), HardwareWalletError> {
+        if features.version < MIN_SOLANA_FIRMWARE {
+            return Err(HardwareWalletError::FirmwareOutdated(format!(
+                "Solana needs firmware {}.{}.{} or newer, device has {}",
+                MIN_SOLANA_FIRMWARE.0,
+                MIN_SOLANA_FIRMWARE.1,
+                MIN_SOLANA_FIRMWARE.2,
+                features.firmware_string()
+            )));
+        }
+        Ok(())
+    }
+
+    pub async fn get_address(
+        &self,
+        device_id: &str,
+        derivation_path: &[u32],
+        display: bool,
+    ) -> Result<GetAddressResponse, HardwareWalletError> {
+        let mut payload = path_message(derivation_path);
+        put_varint_field(&mut payload, 2, u64::from(display));
+
+        let response = self
+            .with_session(device_id, |session, features| async move {
+                Self::require_solana(&features)?;
+                self.exchange(
+                    &session,
+                    MSG_SOLANA_GET_ADDRESS,
+                    payload,
+                    MSG_SOLANA_ADDRESS,
+                )
+                .await
+            })
+            .await?;
+
+        let address = string_field(&decode_fields(&response)?, 1).ok_or_else(|| {
+            HardwareWalletError::InvalidResponse("SolanaAddress without address".to_string())
+        })?;
+        // A Solana address is the base58 public key itself.
+        Ok(GetAddressResponse {
+            public_key: address.clone(),
+            address,
+        })
+    }
+
+    /// Signs a serialized transaction message; returns the base58 signature.
+    pub async fn sign_transaction(
+        &self,
+        device_id: &str,
+        derivation_path: &[u32],
+        serialized_tx: &[u8],
+    ) -> Result<String, HardwareWalletError> {
+        let mut payload = path_message(derivation_path);
+        put_bytes_field(&mut payload, 2, serialized_tx);
+
+        let response = self
+            .with_session(device_id, |session, features| async move {
+                Self::require_solana(&features)?;
+                self.exchange(
+                    &session,
+                    MSG_SOLANA_SIGN_TX,
+                    payload,
+                    MSG_SOLANA_TX_SIGNATURE,
+                )
+                .await
+            })
+            .await?;
+
+        let signature = bytes_field(&decode_fields(&response)?, 1)
+            .filter(|s| s.len() == 64)
+            .ok_or_else(|| {
+                HardwareWalletError::InvalidResponse("Malformed Solana signature".to_string())
+            })?;
+        Ok(bs58::encode(signature).into_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_round_trip() {
+        let mut payload = path_message(&[0x8000_002c, 0x8000_01f5]);
+        put_varint_field(&mut payload, 2, 1);
+        let frame = encode_frame(MSG_SOLANA_GET_ADDRESS, &payload);
+        assert!(frame.starts_with("0386"));
+
+        let (message_type, decoded) = decode_frame(&frame).unwrap();
+        assert_eq!(message_type, MSG_SOLANA_GET_ADDRESS);
+        let fields = decode_fields(&decoded).unwrap();
+        let path: Vec<u64> = fields
+            .iter()
+            .filter_map(|(n, f)| match f {
+                Field::Varint(v) if *n == 1 => Some(*v),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(path, vec![0x8000_002c, 0x8000_01f5]);
+        assert_eq!(varint_field(&fields, 2), Some(1));
+    }
+
+    #[test]
+    fn test_parse_features() {
+        let mut payload = Vec::new();
+        put_bytes_field(&mut payload, 1, b"trezor.io");
+        put_varint_field(&mut payload, 2, 2);
+        put_varint_field(&mut payload, 3, 8);
+        put_varint_field(&mut payload, 4, 1);
+        put_bytes_field(&mut payload, 10, b"Vault");
+        put_bytes_field(&mut payload, 21, b"T");
+
+        let features = parse_features(&payload).unwrap();
+        assert_eq!(features.version, (2, 8, 1));
+        assert_eq!(features.model.as_deref(), Some("T"));
+        assert!(TrezorBackend::require_solana(&features).is_ok());
+        assert!(TrezorBackend::require_solana(&TrezorFeatures {
+            version: (2, 6, 4),
+            ..features
+        })
+        .is_err());
+    }
+}
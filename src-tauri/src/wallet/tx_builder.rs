@@ -0,0 +1,815 @@
+use crate::chains::{RoutingHint, RpcPool, SharedRpcPool};
+use crate::errors::{AppError, CommandResultExt};
+use crate::profiles::ProfilePaths;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    address_lookup_table::{
+        instruction::{create_lookup_table, extend_lookup_table},
+        state::AddressLookupTable,
+    },
+    address_lookup_table_account::AddressLookupTableAccount,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::{v0, Message, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::RwLock;
+
+const LOOKUP_TABLES_FILE: &str = "lookup_tables.json";
+
+/// Maximum serialized transaction size accepted by the network.
+pub const PACKET_DATA_SIZE: usize = 1232;
+const LOOKUP_TABLE_MAX_ADDRESSES: usize = 256;
+const LOOKUP_TABLE_META_SIZE: usize = 56;
+/// Addresses per extend instruction that still fit in a single transaction.
+const EXTEND_BATCH_SIZE: usize = 20;
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+/// Rent-exempt lamports per byte, including the 128-byte account overhead.
+const RENT_LAMPORTS_PER_BYTE: u64 = 6_960;
+const ACCOUNT_STORAGE_OVERHEAD: u64 = 128;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TxBuilderError {
+    #[error("invalid public key {0}")]
+    InvalidPubkey(String),
+    #[error("invalid instruction data: {0}")]
+    InvalidData(String),
+    #[error("batch contains no instructions")]
+    Empty,
+    #[error("failed to compile message: {0}")]
+    Compile(String),
+    #[error("rpc error: {0}")]
+    Rpc(String),
+    #[error("lookup table {0} is full")]
+    LookupTableFull(String),
+    #[error("{0}")]
+    Storage(String),
+}
+
+impl From<TxBuilderError> for AppError {
+    fn from(err: TxBuilderError) -> Self {
+        match err {
+            err @ (TxBuilderError::InvalidPubkey(_)
+            | TxBuilderError::InvalidData(_)
+            | TxBuilderError::Empty) => AppError::Validation(err.to_string()),
+            err @ TxBuilderError::LookupTableFull(_) => AppError::Conflict(err.to_string()),
+            TxBuilderError::Rpc(message) => AppError::SolanaClient(message),
+            err @ (TxBuilderError::Compile(_) | TxBuilderError::Storage(_)) => {
+                AppError::Generic(err.to_string())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuilderAccountMeta {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuilderInstruction {
+    pub program_id: String,
+    pub accounts: Vec<BuilderAccountMeta>,
+    /// Base64-encoded instruction data.
+    pub data: String,
+}
+
+impl BuilderInstruction {
+    pub fn to_instruction(&self) -> Result<Instruction, TxBuilderError> {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|meta| {
+                Ok(AccountMeta {
+                    pubkey: parse_pubkey(&meta.pubkey)?,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+            })
+            .collect::<Result<Vec<_>, TxBuilderError>>()?;
+        let data = STANDARD
+            .decode(&self.data)
+            .map_err(|e| TxBuilderError::InvalidData(e.to_string()))?;
+
+        Ok(Instruction {
+            program_id: parse_pubkey(&self.program_id)?,
+            accounts,
+            data,
+        })
+    }
+
+    pub fn from_instruction(instruction: &Instruction) -> Self {
+        Self {
+            program_id: instruction.program_id.to_string(),
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| BuilderAccountMeta {
+                    pubkey: meta.pubkey.to_string(),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: STANDARD.encode(&instruction.data),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionFormat {
+    Legacy,
+    V0,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LookupTableRecord {
+    pub address: String,
+    pub authority: String,
+    pub addresses: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl LookupTableRecord {
+    fn to_account(&self) -> Result<AddressLookupTableAccount, TxBuilderError> {
+        Ok(AddressLookupTableAccount {
+            key: parse_pubkey(&self.address)?,
+            addresses: self
+                .addresses
+                .iter()
+                .map(|address| parse_pubkey(address))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn overlap(&self, addresses: &HashSet<String>) -> usize {
+        self.addresses
+            .iter()
+            .filter(|address| addresses.contains(*address))
+            .count()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchBuildRequest {
+    #[serde(default)]
+    pub payer: String,
+    pub instructions: Vec<BuilderInstruction>,
+    /// Omitted when only estimating; a placeholder hash is used instead.
+    #[serde(default)]
+    pub recent_blockhash: Option<String>,
+    /// Forces a format. When omitted the cheaper option is chosen.
+    #[serde(default)]
+    pub format: Option<TransactionFormat>,
+    /// Lookup tables to use in addition to the ones picked automatically.
+    #[serde(default)]
+    pub lookup_tables: Vec<String>,
+    #[serde(default = "default_true")]
+    pub reuse_lookup_tables: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatEstimate {
+    pub format: TransactionFormat,
+    pub serialized_size: usize,
+    pub fits_single_transaction: bool,
+    /// Transactions needed to land the whole batch in this format.
+    pub transaction_count: usize,
+    pub signatures_per_transaction: usize,
+    pub fee_lamports: u64,
+    pub lookup_tables_used: Vec<String>,
+    pub accounts_via_lookup: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LookupTablePlan {
+    pub table_address: String,
+    pub creates_table: bool,
+    pub missing_addresses: Vec<String>,
+    /// Create and extend instructions, one transaction each.
+    pub setup_transactions: Vec<Vec<BuilderInstruction>>,
+    pub setup_fee_lamports: u64,
+    /// Rent deposited into the table; recoverable when it is closed.
+    pub rent_lamports: u64,
+    /// ALTs can only be used one slot after they are extended.
+    pub warmup_required: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchBuildResult {
+    pub format: TransactionFormat,
+    /// Base64 bincode of the unsigned transaction in the selected format.
+    pub transaction: String,
+    pub blockhash_placeholder: bool,
+    pub legacy: FormatEstimate,
+    pub v0: Option<FormatEstimate>,
+    pub size_savings_bytes: i64,
+    pub fee_savings_lamports: i64,
+}
+
+pub struct TransactionBatchBuilder {
+    rpc_pool: SharedRpcPool,
+    lookup_tables: Vec<LookupTableRecord>,
+    path: Option<PathBuf>,
+}
+
+pub type SharedTransactionBatchBuilder = Arc<RwLock<TransactionBatchBuilder>>;
+
+impl TransactionBatchBuilder {
+    pub fn new(app_handle: &AppHandle, rpc_pool: SharedRpcPool) -> Self {
+        let path = app_handle
+            .path()
+            .profile_data_dir()
+            .ok()
+            .map(|dir| dir.join(LOOKUP_TABLES_FILE));
+        let lookup_tables = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        Self {
+            rpc_pool,
+            lookup_tables,
+            path,
+        }
+    }
+
+    pub fn lookup_tables(&self) -> Vec<LookupTableRecord> {
+        self.lookup_tables.clone()
+    }
+
+    pub fn register_lookup_table(
+        &mut self,
+        record: LookupTableRecord,
+    ) -> Result<LookupTableRecord, TxBuilderError> {
+        parse_pubkey(&record.address)?;
+        self.lookup_tables.retain(|t| t.address != record.address);
+        self.lookup_tables.push(record.clone());
+        self.save()?;
+        Ok(record)
+    }
+
+    /// Reloads a table's addresses from chain and stores the result.
+    pub async fn refresh_lookup_table(
+        &mut self,
+        address: &str,
+        authority: Option<String>,
+    ) -> Result<LookupTableRecord, TxBuilderError> {
+        let key = parse_pubkey(address)?;
+        let account = RpcPool::call(&self.rpc_pool, RoutingHint::Read, move |client| {
+            client.get_account(&key)
+        })
+        .await
+        .map_err(TxBuilderError::Rpc)?;
+        let table = AddressLookupTable::deserialize(&account.data)
+            .map_err(|e| TxBuilderError::InvalidData(e.to_string()))?;
+
+        let existing = self.lookup_tables.iter().find(|t| t.address == address);
+        let record = LookupTableRecord {
+            address: address.to_string(),
+            authority: table
+                .meta
+                .authority
+                .map(|a| a.to_string())
+                .or(authority)
+                .or_else(|| existing.map(|t| t.authority.clone()))
+                .unwrap_or_default(),
+            addresses: table.addresses.iter().map(|a| a.to_string()).collect(),
+            created_at: existing.map(|t| t.created_at).unwrap_or_else(Utc::now),
+            last_used_at: existing.and_then(|t| t.last_used_at),
+        };
+        self.register_lookup_table(record)
+    }
+
+    /// Builds the batch as a single transaction, comparing legacy against
+    /// v0 with every reusable lookup table.
+    pub fn build(
+        &mut self,
+        request: &BatchBuildRequest,
+    ) -> Result<BatchBuildResult, TxBuilderError> {
+        if request.instructions.is_empty() {
+            return Err(TxBuilderError::Empty);
+        }
+        let payer = parse_pubkey(&request.payer)?;
+        let instructions = request
+            .instructions
+            .iter()
+            .map(BuilderInstruction::to_instruction)
+            .collect::<Result<Vec<_>, _>>()?;
+        let (blockhash, blockhash_placeholder) = match &request.recent_blockhash {
+            Some(hash) => (
+                Hash::from_str(hash).map_err(|e| TxBuilderError::InvalidData(e.to_string()))?,
+                false,
+            ),
+            None => (Hash::default(), true),
+        };
+
+        let legacy_tx = compile_legacy(&payer, &instructions, blockhash);
+        let legacy = FormatEstimate {
+            format: TransactionFormat::Legacy,
+            serialized_size: serialized_size(&legacy_tx),
+            fits_single_transaction: serialized_size(&legacy_tx) <= PACKET_DATA_SIZE,
+            transaction_count: legacy_transaction_count(&payer, &instructions, blockhash),
+            signatures_per_transaction: required_signatures(&legacy_tx),
+            fee_lamports: 0,
+            lookup_tables_used: Vec::new(),
+            accounts_via_lookup: 0,
+        }
+        .with_fee();
+
+        let tables = self.select_lookup_tables(request, &instructions)?;
+        let v0 = if request.format == Some(TransactionFormat::Legacy) {
+            None
+        } else {
+            let accounts = tables
+                .iter()
+                .map(LookupTableRecord::to_account)
+                .collect::<Result<Vec<_>, _>>()?;
+            let message = v0::Message::try_compile(&payer, &instructions, &accounts, blockhash)
+                .map_err(|e| TxBuilderError::Compile(e.to_string()))?;
+            let used: Vec<String> = message
+                .address_table_lookups
+                .iter()
+                .map(|lookup| lookup.account_key.to_string())
+                .collect();
+            let via_lookup = message
+                .address_table_lookups
+                .iter()
+                .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+                .sum();
+            let tx = unsigned(VersionedMessage::V0(message));
+            let size = serialized_size(&tx);
+            Some((
+                tx.clone(),
+                FormatEstimate {
+                    format: TransactionFormat::V0,
+                    serialized_size: size,
+                    fits_single_transaction: size <= PACKET_DATA_SIZE,
+                    transaction_count: 1,
+                    signatures_per_transaction: required_signatures(&tx),
+                    fee_lamports: 0,
+                    lookup_tables_used: used,
+                    accounts_via_lookup: via_lookup,
+                }
+                .with_fee(),
+            ))
+        };
+
+        let format = match (request.format, &v0) {
+            (Some(format), _) => format,
+            (None, Some((_, v0))) if prefer_v0(&legacy, v0) => TransactionFormat::V0,
+            _ => TransactionFormat::Legacy,
+        };
+
+        let (transaction, size_savings, fee_savings) = match (&v0, format) {
+            (Some((tx, estimate)), TransactionFormat::V0) => {
+                self.touch_lookup_tables(&estimate.lookup_tables_used)?;
+                (
+                    tx,
+                    diff(legacy.serialized_size, estimate.serialized_size),
+                    diff_u64(legacy.fee_lamports, estimate.fee_lamports),
+                )
+            }
+            _ => (&legacy_tx, 0, 0),
+        };
+        let encoded = bincode::serialize(transaction)
+            .map(|bytes| STANDARD.encode(bytes))
+            .map_err(|e| TxBuilderError::Compile(e.to_string()))?;
+
+        Ok(BatchBuildResult {
+            format,
+            transaction: encoded,
+            blockhash_placeholder,
+            legacy,
+            v0: v0.map(|(_, estimate)| estimate),
+            size_savings_bytes: size_savings,
+            fee_savings_lamports: fee_savings,
+        })
+    }
+
+    /// Plans the create/extend transactions needed so every account in
+    /// `addresses` can be loaded through a lookup table owned by
+    /// `authority`, reusing the best existing table when one has room.
+    pub async fn plan_lookup_table(
+        &self,
+        authority: &str,
+        payer: &str,
+        addresses: &[String],
+    ) -> Result<LookupTablePlan, TxBuilderError> {
+        let authority_key = parse_pubkey(authority)?;
+        let payer_key = parse_pubkey(payer)?;
+        let wanted: HashSet<String> = addresses.iter().cloned().collect();
+
+        let reusable = self
+            .lookup_tables
+            .iter()
+            .filter(|t| t.authority == authority)
+            .filter(|t| {
+                let missing = wanted.len() - t.overlap(&wanted);
+                t.addresses.len() + missing <= LOOKUP_TABLE_MAX_ADDRESSES
+            })
+            .max_by_key(|t| t.overlap(&wanted));
+
+        let mut setup_transactions = Vec::new();
+        let (table_key, creates_table, existing_len, missing) = match reusable {
+            Some(table) => {
+                let missing: Vec<String> = addresses
+                    .iter()
+                    .filter(|address| !table.addresses.contains(*address))
+                    .cloned()
+                    .collect();
+                (
+                    parse_pubkey(&table.address)?,
+                    false,
+                    table.addresses.len(),
+                    missing,
+                )
+            }
+            None => {
+                if addresses.len() > LOOKUP_TABLE_MAX_ADDRESSES {
+                    return Err(TxBuilderError::LookupTableFull(format!(
+                        "new table for {} addresses",
+                        addresses.len()
+                    )));
+                }
+                let slot = RpcPool::call(&self.rpc_pool, RoutingHint::Read, |client| {
+                    client.get_slot()
+                })
+                .await
+                .map_err(TxBuilderError::Rpc)?;
+                let (create, key) = create_lookup_table(authority_key, payer_key, slot);
+                setup_transactions.push(vec![BuilderInstruction::from_instruction(&create)]);
+                (key, true, 0, addresses.to_vec())
+            }
+        };
+
+        let missing_keys = missing
+            .iter()
+            .map(|address| parse_pubkey(address))
+            .collect::<Result<Vec<_>, _>>()?;
+        for chunk in missing_keys.chunks(EXTEND_BATCH_SIZE) {
+            let extend =
+                extend_lookup_table(table_key, authority_key, Some(payer_key), chunk.to_vec());
+            setup_transactions.push(vec![BuilderInstruction::from_instruction(&extend)]);
+        }
+
+        let rent_lamports = if creates_table {
+            rent_exempt_lamports(LOOKUP_TABLE_META_SIZE + 32 * missing.len())
+        } else {
+            rent_exempt_lamports(LOOKUP_TABLE_META_SIZE + 32 * (existing_len + missing.len()))
+                - rent_exempt_lamports(LOOKUP_TABLE_META_SIZE + 32 * existing_len)
+        };
+        let signers = if authority_key == payer_key { 1 } else { 2 };
+
+        Ok(LookupTablePlan {
+            table_address: table_key.to_string(),
+            creates_table,
+            warmup_required: !missing.is_empty(),
+            missing_addresses: missing,
+            setup_fee_lamports: setup_transactions.len() as u64 * signers * LAMPORTS_PER_SIGNATURE,
+            setup_transactions,
+            rent_lamports,
+        })
+    }
+
+    fn select_lookup_tables(
+        &self,
+        request: &BatchBuildRequest,
+        instructions: &[Instruction],
+    ) -> Result<Vec<LookupTableRecord>, TxBuilderError> {
+        let accounts: HashSet<String> = instructions
+            .iter()
+            .flat_map(|ix| {
+                ix.accounts
+                    .iter()
+                    .filter(|meta| !meta.is_signer)
+                    .map(|meta| meta.pubkey.to_string())
+            })
+            .collect();
+
+        let mut selected: Vec<LookupTableRecord> = Vec::new();
+        for address in &request.lookup_tables {
+            match self.lookup_tables.iter().find(|t| &t.address == address) {
+                Some(table) => selected.push(table.clone()),
+                None => {
+                    return Err(TxBuilderError::InvalidData(format!(
+                        "lookup table {} is not registered; refresh it first",
+                        address
+                    )))
+                }
+            }
+        }
+
+        if request.reuse_lookup_tables {
+            let mut covered: HashSet<String> = selected
+                .iter()
+                .flat_map(|t| t.addresses.iter().cloned())
+                .collect();
+            let mut candidates: Vec<&LookupTableRecord> = self
+                .lookup_tables
+                .iter()
+                .filter(|t| !selected.iter().any(|s| s.address == t.address))
+                .collect();
+            candidates.sort_by_key(|t| std::cmp::Reverse(t.overlap(&accounts)));
+
+            for table in candidates {
+                let gain = table
+                    .addresses
+                    .iter()
+                    .filter(|a| accounts.contains(*a) && !covered.contains(*a))
+                    .count();
+                // A lookup costs 34 bytes of overhead against 31 saved per
+                // account, so tables covering a single account do not pay off.
+                if gain < 2 {
+                    continue;
+                }
+                covered.extend(table.addresses.iter().cloned());
+                selected.push(table.clone());
+            }
+        }
+
+        Ok(selected)
+    }
+
+    fn touch_lookup_tables(&mut self, used: &[String]) -> Result<(), TxBuilderError> {
+        if used.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now();
+        for table in self
+            .lookup_tables
+            .iter_mut()
+            .filter(|t| used.contains(&t.address))
+        {
+            table.last_used_at = Some(now);
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), TxBuilderError> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| TxBuilderError::Storage(e.to_string()))?;
+            }
+            let contents = serde_json::to_string_pretty(&self.lookup_tables)
+                .map_err(|e| TxBuilderError::Storage(e.to_string()))?;
+            fs::write(path, contents).map_err(|e| TxBuilderError::Storage(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+impl FormatEstimate {
+    fn with_fee(mut self) -> Self {
+        self.fee_lamports = self.transaction_count as u64
+            * self.signatures_per_transaction as u64
+            * LAMPORTS_PER_SIGNATURE;
+        self
+    }
+}
+
+/// v0 wins when legacy cannot fit the batch in one transaction, or when it
+/// is strictly smaller at equal fees.
+fn prefer_v0(legacy: &FormatEstimate, v0: &FormatEstimate) -> bool {
+    if !v0.fits_single_transaction {
+        return false;
+    }
+    if !legacy.fits_single_transaction {
+        return true;
+    }
+    v0.fee_lamports < legacy.fee_lamports
+        || (v0.fee_lamports == legacy.fee_lamports
+            && !v0.lookup_tables_used.is_empty()
+            && v0.serialized_size < legacy.serialized_size)
+}
+
+fn compile_legacy(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    blockhash: Hash,
+) -> VersionedTransaction {
+    let message = Message::new_with_blockhash(instructions, Some(payer), &blockhash);
+    unsigned(VersionedMessage::Legacy(message))
+}
+
+/// Greedily packs instructions into legacy transactions under the packet
+/// limit to price what the batch costs without lookup tables.
+fn legacy_transaction_count(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    blockhash: Hash,
+) -> usize {
+    let mut count = 0;
+    let mut current: Vec<Instruction> = Vec::new();
+    for instruction in instructions {
+        current.push(instruction.clone());
+        let size = serialized_size(&compile_legacy(payer, &current, blockhash));
+        if size > PACKET_DATA_SIZE && current.len() > 1 {
+            count += 1;
+            current = vec![instruction.clone()];
+        }
+    }
+    if !current.is_empty() {
+        count += 1;
+    }
+    count
+}
+
+fn unsigned(message: VersionedMessage) -> VersionedTransaction {
+    let signatures = vec![Signature::default(); message.header().num_required_signatures as usize];
+    VersionedTransaction {
+        signatures,
+        message,
+    }
+}
+
+fn serialized_size(tx: &VersionedTransaction) -> usize {
+    bincode::serialized_size(tx)
+        .map(|size| size as usize)
+        .unwrap_or(usize::MAX)
+}
+
+fn required_signatures(tx: &VersionedTransaction) -> usize {
+    tx.message.header().num_required_signatures as usize
+}
+
+fn rent_exempt_lamports(data_len: usize) -> u64 {
+    (ACCOUNT_STORAGE_OVERHEAD + data_len as u64) * RENT_LAMPORTS_PER_BYTE
+}
+
+fn diff(a: usize, b: usize) -> i64 {
+    a as i64 - b as i64
+}
+
+fn diff_u64(a: u64, b: u64) -> i64 {
+    a as i64 - b as i64
+}
+
+fn parse_pubkey(value: &str) -> Result<Pubkey, TxBuilderError> {
+    Pubkey::from_str(value).map_err(|_| TxBuilderError::InvalidPubkey(value.to_string()))
+}
+
+#[tauri::command]
+pub async fn tx_builder_build_batch(
+    request: BatchBuildRequest,
+    builder: State<'_, SharedTransactionBatchBuilder>,
+) -> Result<BatchBuildResult, AppError> {
+    builder
+        .write()
+        .await
+        .build(&request)
+        .logged("tx_builder_build_batch")
+}
+
+#[tauri::command]
+pub async fn tx_builder_plan_lookup_table(
+    authority: String,
+    payer: String,
+    addresses: Vec<String>,
+    builder: State<'_, SharedTransactionBatchBuilder>,
+) -> Result<LookupTablePlan, AppError> {
+    builder
+        .read()
+        .await
+        .plan_lookup_table(&authority, &payer, &addresses)
+        .await
+        .logged("tx_builder_plan_lookup_table")
+}
+
+#[tauri::command]
+pub async fn tx_builder_register_lookup_table(
+    address: String,
+    authority: Option<String>,
+    builder: State<'_, SharedTransactionBatchBuilder>,
+) -> Result<LookupTableRecord, AppError> {
+    builder
+        .write()
+        .await
+        .refresh_lookup_table(&address, authority)
+        .await
+        .logged("tx_builder_register_lookup_table")
+}
+
+#[tauri::command]
+pub async fn tx_builder_list_lookup_tables(
+    builder: State<'_, SharedTransactionBatchBuilder>,
+) -> Result<Vec<LookupTableRecord>, AppError> {
+    Ok(builder.read().await.lookup_tables())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder(tables: Vec<LookupTableRecord>) -> TransactionBatchBuilder {
+        TransactionBatchBuilder {
+            rpc_pool: Arc::new(RwLock::new(RpcPool::default())),
+            lookup_tables: tables,
+            path: None,
+        }
+    }
+
+    fn transfer_batch(payer: &Pubkey, accounts: &[Pubkey]) -> Vec<BuilderInstruction> {
+        let program = Pubkey::new_unique();
+        accounts
+            .chunks(4)
+            .map(|chunk| {
+                let mut metas = vec![AccountMeta::new(*payer, true)];
+                metas.extend(chunk.iter().map(|key| AccountMeta::new(*key, false)));
+                BuilderInstruction::from_instruction(&Instruction {
+                    program_id: program,
+                    accounts: metas,
+                    data: vec![1, 2, 3, 4],
+                })
+            })
+            .collect()
+    }
+
+    fn request(payer: &Pubkey, instructions: Vec<BuilderInstruction>) -> BatchBuildRequest {
+        BatchBuildRequest {
+            payer: payer.to_string(),
+            instructions,
+            recent_blockhash: None,
+            format: None,
+            lookup_tables: Vec::new(),
+            reuse_lookup_tables: true,
+        }
+    }
+
+    #[test]
+    fn small_batches_stay_legacy() {
+        let payer = Pubkey::new_unique();
+        let accounts: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let result = builder(Vec::new())
+            .build(&request(&payer, transfer_batch(&payer, &accounts)))
+            .unwrap();
+
+        assert_eq!(result.format, TransactionFormat::Legacy);
+        assert!(result.legacy.fits_single_transaction);
+        assert!(result.blockhash_placeholder);
+        assert!(result.v0.unwrap().lookup_tables_used.is_empty());
+    }
+
+    #[test]
+    fn large_batches_reuse_lookup_tables() {
+        let payer = Pubkey::new_unique();
+        let accounts: Vec<Pubkey> = (0..48).map(|_| Pubkey::new_unique()).collect();
+        let table = LookupTableRecord {
+            address: Pubkey::new_unique().to_string(),
+            authority: payer.to_string(),
+            addresses: accounts.iter().map(|a| a.to_string()).collect(),
+            created_at: Utc::now(),
+            last_used_at: None,
+        };
+        let mut builder = builder(vec![table.clone()]);
+        let result = builder
+            .build(&request(&payer, transfer_batch(&payer, &accounts)))
+            .unwrap();
+
+        assert!(!result.legacy.fits_single_transaction);
+        assert!(result.legacy.transaction_count > 1);
+        assert_eq!(result.format, TransactionFormat::V0);
+        let v0 = result.v0.unwrap();
+        assert_eq!(v0.lookup_tables_used, vec![table.address.clone()]);
+        assert_eq!(v0.accounts_via_lookup, accounts.len());
+        assert!(result.size_savings_bytes > 0);
+        assert!(result.fee_savings_lamports > 0);
+        assert!(builder.lookup_tables()[0].last_used_at.is_some());
+    }
+
+    #[test]
+    fn forced_legacy_skips_v0() {
+        let payer = Pubkey::new_unique();
+        let accounts: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let mut req = request(&payer, transfer_batch(&payer, &accounts));
+        req.format = Some(TransactionFormat::Legacy);
+        let result = builder(Vec::new()).build(&req).unwrap();
+        assert!(result.v0.is_none());
+        assert_eq!(result.size_savings_bytes, 0);
+    }
+}
@@ -0,0 +1,1115 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Utc};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_sdk::{
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    compute_budget::{self, ComputeBudgetInstruction},
+    hash::Hash,
+    instruction::CompiledInstruction,
+    message::VersionedMessage,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::{oneshot, Mutex, RwLock};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use uuid::Uuid;
+
+use crate::api::trading_execution::get_priority_fee_estimates;
+use crate::chains::{RoutingHint, RpcPool, SharedRpcPool};
+use crate::environment::active_environment;
+use crate::errors::{AppError, CommandResultExt};
+use crate::notifications::router::SharedNotificationRouter;
+use crate::trading::OrderStatus;
+
+const BLOCKHASH_TTL: Duration = Duration::from_secs(15);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const RESIGN_TIMEOUT: Duration = Duration::from_secs(60);
+const FINALIZE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Consecutive polls that must miss a confirmed signature before it is
+/// treated as rolled back rather than a lagging RPC node.
+const ROLLBACK_MISSES: u32 = 3;
+const DEFAULT_MAX_ATTEMPTS: u32 = 4;
+/// `SetComputeUnitPrice` discriminant in the compute budget program.
+const SET_COMPUTE_UNIT_PRICE_TAG: u8 = 3;
+
+pub const LIFECYCLE_EVENT: &str = "transaction_lifecycle";
+pub const RESIGN_REQUIRED_EVENT: &str = "transaction_resign_required";
+pub const TRANSACTION_ALERT_EVENT: &str = "transaction_alert";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionLifecycleStatus {
+    Submitted,
+    AwaitingSignature,
+    Confirmed,
+    /// Was confirmed, then disappeared because its fork was abandoned. The
+    /// same signed transaction is rebroadcast while its blockhash is valid.
+    Reorged,
+    Finalized,
+    Expired,
+    Failed,
+}
+
+/// Priority fee used for each submission attempt. Every retry after a
+/// blockhash expiry multiplies the previous price, up to the cap.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityFeeSchedule {
+    pub initial_micro_lamports: u64,
+    pub multiplier: f64,
+    pub max_micro_lamports: u64,
+}
+
+impl Default for PriorityFeeSchedule {
+    fn default() -> Self {
+        Self {
+            initial_micro_lamports: 5_000,
+            multiplier: 1.5,
+            max_micro_lamports: 1_000_000,
+        }
+    }
+}
+
+impl PriorityFeeSchedule {
+    pub fn fee_for_attempt(&self, attempt: u32) -> u64 {
+        let fee =
+            self.initial_micro_lamports as f64 * self.multiplier.max(1.0).powi(attempt as i32);
+        (fee.round() as u64).min(self.max_micro_lamports)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedBlockhash {
+    pub blockhash: String,
+    pub last_valid_block_height: u64,
+    pub fetched_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitTransactionRequest {
+    /// Base64-encoded, fully signed transaction (legacy or v0).
+    pub transaction_base64: String,
+    #[serde(default)]
+    pub last_valid_block_height: Option<u64>,
+    #[serde(default)]
+    pub max_attempts: Option<u32>,
+    #[serde(default)]
+    pub fee_schedule: Option<PriorityFeeSchedule>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackedTransaction {
+    pub id: String,
+    pub label: Option<String>,
+    /// Signature of the current attempt.
+    pub signature: String,
+    /// Signatures of every attempt, oldest first.
+    pub signatures: Vec<String>,
+    pub status: TransactionLifecycleStatus,
+    pub attempt: u32,
+    pub max_attempts: u32,
+    pub priority_fee_micro_lamports: Option<u64>,
+    pub last_valid_block_height: Option<u64>,
+    pub slot: Option<u64>,
+    pub error: Option<String>,
+    /// Suggested next step after a reorg or a dropped transaction.
+    #[serde(default)]
+    pub remediation: Option<String>,
+    #[serde(default)]
+    pub reorg_count: u32,
+    pub submitted_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Emitted when a transaction expired and needs the wallet to sign a
+/// rebuilt copy with a fresh blockhash and higher priority fee.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResignRequest {
+    pub id: String,
+    pub attempt: u32,
+    pub transaction_base64: String,
+    pub priority_fee_micro_lamports: u64,
+    pub last_valid_block_height: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionAlertKind {
+    Reorged,
+    Dropped,
+}
+
+/// Emitted through [`TRANSACTION_ALERT_EVENT`] and the chat notification
+/// router when a transaction is rolled back or dropped.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionAlert {
+    pub id: String,
+    pub kind: TransactionAlertKind,
+    pub label: Option<String>,
+    pub signature: String,
+    pub message: String,
+    pub remediation: String,
+    /// Orders whose fill referenced one of the transaction's signatures.
+    pub affected_orders: Vec<String>,
+}
+
+enum ConfirmationOutcome {
+    Confirmed(Option<u64>),
+    Failed(String),
+    Expired,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum FinalityOutcome {
+    Finalized,
+    RolledBack,
+    TimedOut,
+}
+
+pub type SharedTransactionLifecycle = Arc<TransactionLifecycleService>;
+
+/// Owns submission of signed transactions: keeps a recent blockhash warm,
+/// rebroadcasts until the blockhash expires, asks for a re-signed copy
+/// with an escalated priority fee, and tracks each signature through to
+/// finalization. State is internally locked because monitoring runs in
+/// background tasks after the submitting command returns.
+pub struct TransactionLifecycleService {
+    rpc_pool: SharedRpcPool,
+    ws_url: Option<String>,
+    blockhash: RwLock<Option<(CachedBlockhash, Hash, std::time::Instant)>>,
+    tracked: RwLock<HashMap<String, TrackedTransaction>>,
+    resign_waiters: Mutex<HashMap<String, oneshot::Sender<VersionedTransaction>>>,
+}
+
+impl TransactionLifecycleService {
+    pub fn new(rpc_pool: SharedRpcPool) -> Self {
+        Self {
+            rpc_pool,
+            ws_url: active_environment()
+                .custom_ws_url
+                .clone()
+                .or_else(|| std::env::var("SOLANA_WS_URL").ok()),
+            blockhash: RwLock::new(None),
+            tracked: RwLock::new(HashMap::new()),
+            resign_waiters: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn recent_blockhash(&self) -> Result<CachedBlockhash, String> {
+        self.refresh_blockhash(false)
+            .await
+            .map(|(cached, _)| cached)
+    }
+
+    async fn refresh_blockhash(&self, force: bool) -> Result<(CachedBlockhash, Hash), String> {
+        if !force {
+            if let Some((cached, hash, fetched)) = self.blockhash.read().await.as_ref() {
+                if fetched.elapsed() < BLOCKHASH_TTL {
+                    return Ok((cached.clone(), *hash));
+                }
+            }
+        }
+
+        let (hash, last_valid_block_height) =
+            RpcPool::call(&self.rpc_pool, RoutingHint::Read, |client| {
+                client.get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())
+            })
+            .await
+            .map_err(|e| format!("Failed to fetch blockhash: {e}"))?;
+
+        let cached = CachedBlockhash {
+            blockhash: hash.to_string(),
+            last_valid_block_height,
+            fetched_at: Utc::now(),
+        };
+        *self.blockhash.write().await = Some((cached.clone(), hash, std::time::Instant::now()));
+        Ok((cached, hash))
+    }
+
+    pub async fn get(&self, id: &str) -> Option<TrackedTransaction> {
+        self.tracked.read().await.get(id).cloned()
+    }
+
+    pub async fn list(&self) -> Vec<TrackedTransaction> {
+        let mut tracked: Vec<_> = self.tracked.read().await.values().cloned().collect();
+        tracked.sort_by(|a, b| b.submitted_at.cmp(&a.submitted_at));
+        tracked
+    }
+
+    /// Broadcasts the transaction once with preflight checks and hands the
+    /// rest of its lifecycle to a background task. Returns as soon as the
+    /// first attempt is accepted by the RPC node.
+    pub async fn submit(
+        self: &Arc<Self>,
+        app: AppHandle,
+        request: SubmitTransactionRequest,
+    ) -> Result<TrackedTransaction, String> {
+        let transaction = decode_transaction(&request.transaction_base64)?;
+        let signature = self.send(&transaction, false).await?;
+
+        let schedule = match request.fee_schedule {
+            Some(schedule) => schedule,
+            None => default_fee_schedule().await,
+        };
+        let last_valid_block_height = match request.last_valid_block_height {
+            Some(height) => Some(height),
+            None => self.cached_last_valid_height(&transaction).await,
+        };
+        let now = Utc::now();
+        let tracked = TrackedTransaction {
+            id: Uuid::new_v4().to_string(),
+            label: request.label,
+            signature: signature.to_string(),
+            signatures: vec![signature.to_string()],
+            status: TransactionLifecycleStatus::Submitted,
+            attempt: 0,
+            max_attempts: request.max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS).max(1),
+            priority_fee_micro_lamports: compute_unit_price(
+                transaction.message.instructions(),
+                transaction.message.static_account_keys(),
+            ),
+            last_valid_block_height,
+            slot: None,
+            error: None,
+            remediation: None,
+            reorg_count: 0,
+            submitted_at: now,
+            updated_at: now,
+        };
+        self.tracked
+            .write()
+            .await
+            .insert(tracked.id.clone(), tracked.clone());
+        let _ = app.emit(LIFECYCLE_EVENT, &tracked);
+
+        let service = self.clone();
+        let id = tracked.id.clone();
+        tokio::spawn(async move {
+            service
+                .drive(app, id, transaction, last_valid_block_height, schedule)
+                .await;
+        });
+
+        Ok(tracked)
+    }
+
+    /// Delivers the wallet's signature for a rebuilt transaction that was
+    /// announced through [`RESIGN_REQUIRED_EVENT`].
+    pub async fn provide_signed(&self, id: &str, transaction_base64: &str) -> Result<(), String> {
+        let transaction = decode_transaction(transaction_base64)?;
+        let waiter = self
+            .resign_waiters
+            .lock()
+            .await
+            .remove(id)
+            .ok_or_else(|| format!("Transaction {id} is not waiting for a signature"))?;
+        waiter
+            .send(transaction)
+            .map_err(|_| "Re-sign request already timed out".to_string())
+    }
+
+    async fn drive(
+        self: Arc<Self>,
+        app: AppHandle,
+        id: String,
+        mut transaction: VersionedTransaction,
+        mut last_valid_block_height: Option<u64>,
+        schedule: PriorityFeeSchedule,
+    ) {
+        loop {
+            match self
+                .await_confirmation(&transaction, last_valid_block_height)
+                .await
+            {
+                ConfirmationOutcome::Confirmed(slot) => {
+                    self.update(&app, &id, |tx| {
+                        tx.status = TransactionLifecycleStatus::Confirmed;
+                        tx.slot = slot;
+                        tx.error = None;
+                        tx.remediation = None;
+                    })
+                    .await;
+                    if self.get(&id).await.is_some_and(|tx| tx.reorg_count > 0) {
+                        self.sync_orders(&id, None, None).await;
+                    }
+
+                    match self.await_finalized(transaction.signatures[0]).await {
+                        FinalityOutcome::Finalized => {
+                            self.update(&app, &id, |tx| {
+                                tx.status = TransactionLifecycleStatus::Finalized;
+                            })
+                            .await;
+                            return;
+                        }
+                        FinalityOutcome::TimedOut => {
+                            self.update(&app, &id, |tx| {
+                                tx.error = Some("Timed out waiting for finalization".to_string());
+                            })
+                            .await;
+                            return;
+                        }
+                        FinalityOutcome::RolledBack => {
+                            self.handle_reorg(&app, &id).await;
+                            // Same signature, so rebroadcasting cannot land it twice.
+                            continue;
+                        }
+                    }
+                }
+                ConfirmationOutcome::Failed(error) => {
+                    self.update(&app, &id, |tx| {
+                        tx.status = TransactionLifecycleStatus::Failed;
+                        tx.error = Some(error);
+                    })
+                    .await;
+                    return;
+                }
+                ConfirmationOutcome::Expired => {}
+            }
+
+            let Some(current) = self.get(&id).await else {
+                return;
+            };
+            let attempt = current.attempt + 1;
+            if attempt >= current.max_attempts {
+                self.update(&app, &id, |tx| {
+                    tx.status = TransactionLifecycleStatus::Expired;
+                    tx.error = Some("Blockhash expired on every attempt".to_string());
+                })
+                .await;
+                self.handle_dropped(&app, &id).await;
+                return;
+            }
+
+            match self
+                .resign(&app, &id, &transaction, attempt, &schedule)
+                .await
+            {
+                Ok((resigned, height, fee)) => {
+                    let signature = resigned.signatures[0].to_string();
+                    self.update(&app, &id, |tx| {
+                        tx.status = TransactionLifecycleStatus::Submitted;
+                        tx.attempt = attempt;
+                        tx.signature = signature.clone();
+                        tx.signatures.push(signature);
+                        tx.priority_fee_micro_lamports = fee;
+                        tx.last_valid_block_height = Some(height);
+                    })
+                    .await;
+                    transaction = resigned;
+                    last_valid_block_height = Some(height);
+                }
+                Err(error) => {
+                    self.update(&app, &id, |tx| {
+                        tx.status = TransactionLifecycleStatus::Expired;
+                        tx.error = Some(error);
+                    })
+                    .await;
+                    self.handle_dropped(&app, &id).await;
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn handle_reorg(&self, app: &AppHandle, id: &str) {
+        self.update(app, id, |tx| {
+            tx.error = Some(match tx.slot {
+                Some(slot) => format!("Confirmed in slot {slot}, then rolled back by a fork"),
+                None => "Confirmed, then rolled back by a fork".to_string(),
+            });
+            tx.status = TransactionLifecycleStatus::Reorged;
+            tx.reorg_count += 1;
+            tx.slot = None;
+            tx.remediation = Some(
+                "No action needed yet: the signed transaction is being rebroadcast while its \
+                 blockhash is valid. Do not resubmit it manually."
+                    .to_string(),
+            );
+        })
+        .await;
+        let affected = self
+            .sync_orders(
+                id,
+                None,
+                Some("Fill rolled back by a chain reorg; awaiting re-confirmation".to_string()),
+            )
+            .await;
+        self.alert(app, id, TransactionAlertKind::Reorged, affected)
+            .await;
+    }
+
+    /// The transaction can no longer land: every attempt's blockhash
+    /// expired, or the wallet did not re-sign. Orders filled by it are
+    /// marked failed so they are not counted as executed.
+    async fn handle_dropped(&self, app: &AppHandle, id: &str) {
+        self.update(app, id, |tx| {
+            tx.remediation = Some(if tx.reorg_count > 0 {
+                "The transaction was rolled back and expired before it landed again. Check \
+                 the wallet balance, then resubmit with a higher priority fee."
+                    .to_string()
+            } else {
+                "The transaction was dropped before landing and can no longer be processed. \
+                 Check the wallet balance, then resubmit with a higher priority fee."
+                    .to_string()
+            });
+        })
+        .await;
+        let affected = self
+            .sync_orders(
+                id,
+                Some(OrderStatus::Failed),
+                Some("Transaction was dropped before it finalized".to_string()),
+            )
+            .await;
+        self.alert(app, id, TransactionAlertKind::Dropped, affected)
+            .await;
+    }
+
+    /// Updates orders whose `tx_signature` is one of this transaction's
+    /// attempts. `status` of `None` keeps each order's current status.
+    /// Returns the ids of the updated orders.
+    async fn sync_orders(
+        &self,
+        id: &str,
+        status: Option<OrderStatus>,
+        note: Option<String>,
+    ) -> Vec<String> {
+        let Some(tracked) = self.get(id).await else {
+            return Vec::new();
+        };
+        let Ok(trading) = crate::trading::limit_orders::require_state() else {
+            return Vec::new();
+        };
+        let db = trading.db.read().await;
+
+        let mut affected = Vec::new();
+        for signature in &tracked.signatures {
+            let orders = match db.get_orders_by_tx_signature(signature).await {
+                Ok(orders) => orders,
+                Err(e) => {
+                    tracing::warn!("order lookup for {} failed: {}", signature, e);
+                    continue;
+                }
+            };
+            for order in orders {
+                let status = status.unwrap_or(order.status);
+                match db
+                    .update_order_status(&order.id, status, note.clone())
+                    .await
+                {
+                    Ok(()) => affected.push(order.id),
+                    Err(e) => tracing::warn!("failed to update order {}: {}", order.id, e),
+                }
+            }
+        }
+        affected
+    }
+
+    async fn alert(
+        &self,
+        app: &AppHandle,
+        id: &str,
+        kind: TransactionAlertKind,
+        affected_orders: Vec<String>,
+    ) {
+        let Some(tracked) = self.get(id).await else {
+            return;
+        };
+        let name = tracked
+            .label
+            .clone()
+            .unwrap_or_else(|| "Transaction".to_string());
+        let (title, message) = match kind {
+            TransactionAlertKind::Reorged => (
+                "Transaction rolled back",
+                format!(
+                    "{name} ({}) was confirmed, then rolled back by a chain reorg.",
+                    tracked.signature
+                ),
+            ),
+            TransactionAlertKind::Dropped => (
+                "Transaction dropped",
+                format!(
+                    "{name} ({}) was dropped and will not land.",
+                    tracked.signature
+                ),
+            ),
+        };
+        let alert = TransactionAlert {
+            id: tracked.id.clone(),
+            kind,
+            label: tracked.label.clone(),
+            signature: tracked.signature.clone(),
+            message,
+            remediation: tracked.remediation.clone().unwrap_or_default(),
+            affected_orders,
+        };
+        let _ = app.emit(TRANSACTION_ALERT_EVENT, &alert);
+
+        if let Some(router) = app.try_state::<SharedNotificationRouter>() {
+            let text = format!("{} {}", alert.message, alert.remediation);
+            if let Err(e) = router
+                .read()
+                .await
+                .send_text_notification(&alert.signature, title, &text)
+                .await
+            {
+                tracing::warn!("failed to send transaction alert: {}", e);
+            }
+        }
+    }
+
+    /// Rebroadcasts the transaction until a confirmation arrives over the
+    /// signature subscription or polling, or its blockhash expires.
+    async fn await_confirmation(
+        &self,
+        transaction: &VersionedTransaction,
+        last_valid_block_height: Option<u64>,
+    ) -> ConfirmationOutcome {
+        let signature = transaction.signatures[0];
+        let (notify_tx, mut notify_rx) = oneshot::channel();
+        let ws_url = match &self.ws_url {
+            Some(url) => url.clone(),
+            None => self
+                .rpc_pool
+                .read()
+                .await
+                .select(RoutingHint::Read)
+                .map(|url| websocket_url(&url))
+                .unwrap_or_default(),
+        };
+        let subscription = tokio::spawn(async move {
+            let _ = notify_tx.send(subscribe_signature(&ws_url, &signature.to_string()).await);
+        });
+
+        let mut ws_active = true;
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        let outcome = loop {
+            tokio::select! {
+                notification = &mut notify_rx, if ws_active => match notification {
+                    Ok(Ok((slot, None))) => break ConfirmationOutcome::Confirmed(Some(slot)),
+                    Ok(Ok((_, Some(error)))) => break ConfirmationOutcome::Failed(error),
+                    Ok(Err(error)) => {
+                        tracing::debug!("signature subscription for {} failed: {}", signature, error);
+                        ws_active = false;
+                    }
+                    Err(_) => ws_active = false,
+                },
+                _ = interval.tick() => {
+                    if let Some(outcome) = self.poll_status(signature).await {
+                        break outcome;
+                    }
+                    if self.is_expired(transaction, last_valid_block_height).await {
+                        break ConfirmationOutcome::Expired;
+                    }
+                    if let Err(error) = self.send(transaction, true).await {
+                        tracing::debug!("rebroadcast of {} failed: {}", signature, error);
+                    }
+                }
+            }
+        };
+        subscription.abort();
+        outcome
+    }
+
+    async fn poll_status(&self, signature: Signature) -> Option<ConfirmationOutcome> {
+        let status = RpcPool::call(&self.rpc_pool, RoutingHint::Read, move |client| {
+            client.get_signature_statuses(&[signature])
+        })
+        .await
+        .ok()?
+        .value
+        .into_iter()
+        .next()
+        .flatten()?;
+
+        if let Some(error) = &status.err {
+            return Some(ConfirmationOutcome::Failed(error.to_string()));
+        }
+        status
+            .satisfies_commitment(CommitmentConfig::confirmed())
+            .then_some(ConfirmationOutcome::Confirmed(Some(status.slot)))
+    }
+
+    /// Polls a confirmed signature until it finalizes. A signature that the
+    /// node stops reporting was on a fork the cluster abandoned.
+    async fn await_finalized(&self, signature: Signature) -> FinalityOutcome {
+        let deadline = tokio::time::Instant::now() + FINALIZE_TIMEOUT;
+        let mut misses = 0;
+        while tokio::time::Instant::now() < deadline {
+            let response = RpcPool::call(&self.rpc_pool, RoutingHint::Read, move |client| {
+                client.get_signature_statuses(&[signature])
+            })
+            .await;
+            // RPC errors say nothing about the signature, so they neither
+            // count as a miss nor reset the streak.
+            if let Ok(response) = response {
+                let finalized = response
+                    .value
+                    .into_iter()
+                    .next()
+                    .flatten()
+                    .map(|status| status.satisfies_commitment(CommitmentConfig::finalized()));
+                if let Some(outcome) = finality_step(finalized, &mut misses) {
+                    return outcome;
+                }
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        FinalityOutcome::TimedOut
+    }
+
+    async fn is_expired(
+        &self,
+        transaction: &VersionedTransaction,
+        last_valid_block_height: Option<u64>,
+    ) -> bool {
+        match last_valid_block_height {
+            Some(height) => RpcPool::call(&self.rpc_pool, RoutingHint::Read, |client| {
+                client.get_block_height_with_commitment(CommitmentConfig::confirmed())
+            })
+            .await
+            .ok()
+            .map(|current| blockhash_expired(current, height))
+            .unwrap_or(false),
+            None => {
+                let hash = *transaction.message.recent_blockhash();
+                RpcPool::call(&self.rpc_pool, RoutingHint::Read, move |client| {
+                    client.is_blockhash_valid(&hash, CommitmentConfig::confirmed())
+                })
+                .await
+                .ok()
+                .map(|valid| !valid)
+                .unwrap_or(false)
+            }
+        }
+    }
+
+    /// Rebuilds the expired transaction against a fresh blockhash with the
+    /// next priority fee in the schedule and waits for the wallet to sign
+    /// it, then submits the signed copy.
+    async fn resign(
+        &self,
+        app: &AppHandle,
+        id: &str,
+        expired: &VersionedTransaction,
+        attempt: u32,
+        schedule: &PriorityFeeSchedule,
+    ) -> Result<(VersionedTransaction, u64, Option<u64>), String> {
+        let (cached, hash) = self.refresh_blockhash(true).await?;
+        let fee = schedule.fee_for_attempt(attempt);
+        let mut message = expired.message.clone();
+        let repriced = reprice_message(&mut message, hash, fee);
+        let unsigned = VersionedTransaction {
+            signatures: vec![
+                Signature::default();
+                message.header().num_required_signatures as usize
+            ],
+            message,
+        };
+        let bytes = bincode::serialize(&unsigned).map_err(|e| e.to_string())?;
+
+        let (waiter, signed) = oneshot::channel();
+        self.resign_waiters
+            .lock()
+            .await
+            .insert(id.to_string(), waiter);
+        self.update(app, id, |tx| {
+            tx.status = TransactionLifecycleStatus::AwaitingSignature;
+        })
+        .await;
+        let _ = app.emit(
+            RESIGN_REQUIRED_EVENT,
+            ResignRequest {
+                id: id.to_string(),
+                attempt,
+                transaction_base64: STANDARD.encode(bytes),
+                priority_fee_micro_lamports: fee,
+                last_valid_block_height: cached.last_valid_block_height,
+            },
+        );
+
+        let signed = match tokio::time::timeout(RESIGN_TIMEOUT, signed).await {
+            Ok(Ok(signed)) => signed,
+            _ => {
+                self.resign_waiters.lock().await.remove(id);
+                return Err("Wallet did not re-sign the expired transaction".to_string());
+            }
+        };
+        if *signed.message.recent_blockhash() != hash {
+            return Err("Re-signed transaction does not use the refreshed blockhash".to_string());
+        }
+
+        self.send(&signed, false).await?;
+        Ok((
+            signed,
+            cached.last_valid_block_height,
+            repriced.then_some(fee),
+        ))
+    }
+
+    async fn send(
+        &self,
+        transaction: &VersionedTransaction,
+        skip_preflight: bool,
+    ) -> Result<Signature, String> {
+        let transaction = transaction.clone();
+        RpcPool::call(&self.rpc_pool, RoutingHint::Send, move |client| {
+            client.send_transaction_with_config(
+                &transaction,
+                RpcSendTransactionConfig {
+                    skip_preflight,
+                    preflight_commitment: Some(CommitmentLevel::Confirmed),
+                    // Rebroadcasting is handled here rather than by the RPC node.
+                    max_retries: Some(0),
+                    ..Default::default()
+                },
+            )
+        })
+        .await
+        .map_err(|e| format!("Failed to send transaction: {e}"))
+    }
+
+    async fn cached_last_valid_height(&self, transaction: &VersionedTransaction) -> Option<u64> {
+        let guard = self.blockhash.read().await;
+        let (cached, hash, _) = guard.as_ref()?;
+        (hash == transaction.message.recent_blockhash()).then_some(cached.last_valid_block_height)
+    }
+
+    async fn update<F>(&self, app: &AppHandle, id: &str, apply: F)
+    where
+        F: FnOnce(&mut TrackedTransaction),
+    {
+        let snapshot = {
+            let mut tracked = self.tracked.write().await;
+            let Some(tx) = tracked.get_mut(id) else {
+                return;
+            };
+            apply(tx);
+            tx.updated_at = Utc::now();
+            tx.clone()
+        };
+        let _ = app.emit(LIFECYCLE_EVENT, &snapshot);
+    }
+}
+
+/// Advances the finality check by one status poll: `Some(finalized)` when
+/// the node reports the signature, `None` when it no longer knows it.
+fn finality_step(status: Option<bool>, misses: &mut u32) -> Option<FinalityOutcome> {
+    match status {
+        Some(true) => Some(FinalityOutcome::Finalized),
+        Some(false) => {
+            *misses = 0;
+            None
+        }
+        None => {
+            *misses += 1;
+            (*misses >= ROLLBACK_MISSES).then_some(FinalityOutcome::RolledBack)
+        }
+    }
+}
+
+/// Solana rejects a transaction once the chain is past the last block
+/// height at which its blockhash is valid.
+pub fn blockhash_expired(current_block_height: u64, last_valid_block_height: u64) -> bool {
+    current_block_height > last_valid_block_height
+}
+
+/// Points the message at `blockhash` and sets its compute unit price,
+/// rewriting an existing `SetComputeUnitPrice` instruction or prepending
+/// one. Returns whether the price was applied.
+pub fn reprice_message(
+    message: &mut VersionedMessage,
+    blockhash: Hash,
+    micro_lamports: u64,
+) -> bool {
+    message.set_recent_blockhash(blockhash);
+    let price_data = ComputeBudgetInstruction::set_compute_unit_price(micro_lamports).data;
+
+    let (header, account_keys, instructions) = match message {
+        VersionedMessage::Legacy(m) => (&mut m.header, &mut m.account_keys, &mut m.instructions),
+        VersionedMessage::V0(m) => (&mut m.header, &mut m.account_keys, &mut m.instructions),
+    };
+
+    let program_index = match account_keys
+        .iter()
+        .position(|key| *key == compute_budget::id())
+    {
+        Some(index) => index,
+        None => {
+            // Append the program as a read-only, unsigned key. Lookup table
+            // indices come after static keys, so they shift by one.
+            let appended = account_keys.len();
+            if appended >= u8::MAX as usize {
+                return false;
+            }
+            for instruction in instructions.iter_mut() {
+                if instruction.program_id_index as usize >= appended {
+                    instruction.program_id_index += 1;
+                }
+                for account in instruction.accounts.iter_mut() {
+                    if *account as usize >= appended {
+                        *account += 1;
+                    }
+                }
+            }
+            account_keys.push(compute_budget::id());
+            header.num_readonly_unsigned_accounts += 1;
+            appended
+        }
+    };
+
+    match instructions.iter_mut().find(|instruction| {
+        instruction.program_id_index as usize == program_index
+            && instruction.data.first() == Some(&SET_COMPUTE_UNIT_PRICE_TAG)
+    }) {
+        Some(instruction) => instruction.data = price_data,
+        None => instructions.insert(
+            0,
+            CompiledInstruction {
+                program_id_index: program_index as u8,
+                accounts: Vec::new(),
+                data: price_data,
+            },
+        ),
+    }
+    true
+}
+
+fn compute_unit_price(
+    instructions: &[CompiledInstruction],
+    account_keys: &[solana_sdk::pubkey::Pubkey],
+) -> Option<u64> {
+    instructions.iter().find_map(|instruction| {
+        let program = account_keys.get(instruction.program_id_index as usize)?;
+        if *program != compute_budget::id()
+            || instruction.data.first() != Some(&SET_COMPUTE_UNIT_PRICE_TAG)
+        {
+            return None;
+        }
+        let bytes: [u8; 8] = instruction.data.get(1..9)?.try_into().ok()?;
+        Some(u64::from_le_bytes(bytes))
+    })
+}
+
+async fn default_fee_schedule() -> PriorityFeeSchedule {
+    let initial = get_priority_fee_estimates()
+        .await
+        .ok()
+        .and_then(|estimates| {
+            estimates
+                .into_iter()
+                .find(|estimate| estimate.preset == "normal")
+        })
+        .map(|estimate| estimate.micro_lamports);
+    match initial {
+        Some(initial_micro_lamports) => PriorityFeeSchedule {
+            initial_micro_lamports,
+            ..PriorityFeeSchedule::default()
+        },
+        None => PriorityFeeSchedule::default(),
+    }
+}
+
+fn decode_transaction(encoded: &str) -> Result<VersionedTransaction, String> {
+    let bytes = STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| format!("Invalid base64 transaction: {e}"))?;
+    let transaction: VersionedTransaction =
+        bincode::deserialize(&bytes).map_err(|e| format!("Invalid transaction bytes: {e}"))?;
+    if transaction.signatures.is_empty() {
+        return Err("Transaction is not signed".to_string());
+    }
+    Ok(transaction)
+}
+
+fn websocket_url(rpc_url: &str) -> String {
+    if let Some(rest) = rpc_url.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        rpc_url.to_string()
+    }
+}
+
+/// Waits for the `signatureSubscribe` notification at confirmed
+/// commitment. Returns the slot and the transaction error, if any.
+async fn subscribe_signature(
+    ws_url: &str,
+    signature: &str,
+) -> Result<(u64, Option<String>), String> {
+    let (mut stream, _) = connect_async(ws_url)
+        .await
+        .map_err(|e| format!("WebSocket connect failed: {e}"))?;
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "signatureSubscribe",
+        "params": [signature, { "commitment": "confirmed" }]
+    });
+    stream
+        .send(Message::Text(request.to_string()))
+        .await
+        .map_err(|e| format!("WebSocket send failed: {e}"))?;
+
+    while let Some(message) = stream.next().await {
+        let text = match message.map_err(|e| e.to_string())? {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&text) else {
+            continue;
+        };
+        if value.get("method").and_then(|m| m.as_str()) != Some("signatureNotification") {
+            continue;
+        }
+        let result = &value["params"]["result"];
+        let slot = result["context"]["slot"].as_u64().unwrap_or_default();
+        let error = match &result["value"]["err"] {
+            serde_json::Value::Null => None,
+            err => Some(err.to_string()),
+        };
+        return Ok((slot, error));
+    }
+    Err("WebSocket closed before confirmation".to_string())
+}
+
+#[tauri::command]
+pub async fn tx_lifecycle_submit(
+    app: AppHandle,
+    request: SubmitTransactionRequest,
+    lifecycle: State<'_, SharedTransactionLifecycle>,
+) -> Result<TrackedTransaction, AppError> {
+    lifecycle
+        .submit(app, request)
+        .await
+        .logged("tx_lifecycle_submit")
+}
+
+#[tauri::command]
+pub async fn tx_lifecycle_provide_signature(
+    id: String,
+    signed_transaction: String,
+    lifecycle: State<'_, SharedTransactionLifecycle>,
+) -> Result<(), AppError> {
+    lifecycle
+        .provide_signed(&id, &signed_transaction)
+        .await
+        .logged("tx_lifecycle_provide_signature")
+}
+
+#[tauri::command]
+pub async fn tx_lifecycle_get(
+    id: String,
+    lifecycle: State<'_, SharedTransactionLifecycle>,
+) -> Result<Option<TrackedTransaction>, AppError> {
+    Ok(lifecycle.get(&id).await)
+}
+
+#[tauri::command]
+pub async fn tx_lifecycle_list(
+    lifecycle: State<'_, SharedTransactionLifecycle>,
+) -> Result<Vec<TrackedTransaction>, AppError> {
+    Ok(lifecycle.list().await)
+}
+
+#[tauri::command]
+pub async fn tx_lifecycle_recent_blockhash(
+    lifecycle: State<'_, SharedTransactionLifecycle>,
+) -> Result<CachedBlockhash, AppError> {
+    lifecycle
+        .recent_blockhash()
+        .await
+        .logged("tx_lifecycle_recent_blockhash")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{message::Message, pubkey::Pubkey, system_instruction};
+
+    #[test]
+    fn fee_schedule_escalates_up_to_cap() {
+        let schedule = PriorityFeeSchedule {
+            initial_micro_lamports: 10_000,
+            multiplier: 2.0,
+            max_micro_lamports: 50_000,
+        };
+        assert_eq!(schedule.fee_for_attempt(0), 10_000);
+        assert_eq!(schedule.fee_for_attempt(1), 20_000);
+        assert_eq!(schedule.fee_for_attempt(2), 40_000);
+        assert_eq!(schedule.fee_for_attempt(3), 50_000);
+    }
+
+    #[test]
+    fn blockhash_expires_past_last_valid_height() {
+        assert!(!blockhash_expired(100, 100));
+        assert!(blockhash_expired(101, 100));
+    }
+
+    #[test]
+    fn finality_rolls_back_after_consecutive_misses() {
+        let mut misses = 0;
+        assert_eq!(finality_step(None, &mut misses), None);
+        assert_eq!(finality_step(Some(false), &mut misses), None);
+        assert_eq!(misses, 0);
+        for _ in 1..ROLLBACK_MISSES {
+            assert_eq!(finality_step(None, &mut misses), None);
+        }
+        assert_eq!(
+            finality_step(None, &mut misses),
+            Some(FinalityOutcome::RolledBack)
+        );
+        assert_eq!(
+            finality_step(Some(true), &mut 0),
+            Some(FinalityOutcome::Finalized)
+        );
+    }
+
+    #[test]
+    fn reprice_adds_then_rewrites_compute_unit_price() {
+        let payer = Pubkey::new_unique();
+        let transfer = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+        let mut message = VersionedMessage::Legacy(Message::new_with_blockhash(
+            &[transfer],
+            Some(&payer),
+            &Hash::default(),
+        ));
+        let readonly_before = message.header().num_readonly_unsigned_accounts;
+
+        let blockhash = Hash::new_unique();
+        assert!(reprice_message(&mut message, blockhash, 7_500));
+        assert_eq!(*message.recent_blockhash(), blockhash);
+        assert_eq!(
+            message.header().num_readonly_unsigned_accounts,
+            readonly_before + 1
+        );
+        assert_eq!(
+            compute_unit_price(message.instructions(), message.static_account_keys()),
+            Some(7_500)
+        );
+
+        assert!(reprice_message(&mut message, blockhash, 15_000));
+        assert_eq!(message.instructions().len(), 2);
+        assert_eq!(
+            compute_unit_price(message.instructions(), message.static_account_keys()),
+            Some(15_000)
+        );
+    }
+}
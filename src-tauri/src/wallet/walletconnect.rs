@@ -0,0 +1,1069 @@
+use crate::profiles::ProfilePaths;
+use crate::security::activity_log::ActivityLogger;
+use crate::security::key_agreement::{hkdf_sha256, x25519_public, x25519_shared};
+use crate::security::keystore::Keystore;
+use base64::engine::general_purpose::{STANDARD as BASE64_ENGINE, URL_SAFE_NO_PAD};
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, NewAead};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use chrono::Utc;
+use futures_util::{SinkExt, StreamExt};
+use parking_lot::Mutex as SyncMutex;
+use qrcodegen::{QrCode, QrCodeEcc};
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+const RELAY_URL: &str = "wss://relay.walletconnect.org";
+const PROJECT_ID_ENV: &str = "WALLETCONNECT_PROJECT_ID";
+const SESSIONS_FILE: &str = "walletconnect_sessions.json";
+const CLIENT_KEY: &str = "walletconnect_client_key";
+const SESSION_KEY_PREFIX: &str = "walletconnect_session_";
+const SOLANA_MAINNET: &str = "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp";
+const SOLANA_METHODS: [&str; 2] = ["solana_signTransaction", "solana_signMessage"];
+const PROPOSAL_TTL_SECS: i64 = 300;
+const REQUEST_TTL_SECS: u64 = 300;
+const RELAY_ACK_TIMEOUT: Duration = Duration::from_secs(10);
+const ENVELOPE_TYPE_0: u8 = 0;
+const IV_LENGTH: usize = 12;
+
+// Relay tags from the WalletConnect v2 sign protocol; a response is always
+// tagged one above its request.
+const TAG_SESSION_PROPOSE: u32 = 1100;
+const TAG_SESSION_REQUEST: u32 = 1108;
+const TAG_SESSION_DELETE: u32 = 1112;
+
+#[derive(Debug, Error)]
+pub enum WalletConnectError {
+    #[error("WalletConnect project id is not configured (set {PROJECT_ID_ENV})")]
+    MissingProjectId,
+    #[error("Relay error: {0}")]
+    Relay(String),
+    #[error("Encryption error: {0}")]
+    Crypto(String),
+    #[error("Session not found: {0}")]
+    SessionNotFound(String),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("Wallet rejected the request: {0}")]
+    Rejected(String),
+    #[error("Timed out waiting for the wallet")]
+    Timeout,
+    #[error("Storage error: {0}")]
+    Storage(String),
+}
+
+impl Serialize for WalletConnectError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerMetadata {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub icons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletConnectPairing {
+    pub topic: String,
+    /// `wc:` URI to show as a QR code or hand to a wallet deep link.
+    pub uri: String,
+    pub qr_code: String,
+    pub expires_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletConnectSession {
+    pub topic: String,
+    pub pairing_topic: String,
+    pub peer: PeerMetadata,
+    /// Base58 addresses the wallet approved.
+    pub accounts: Vec<String>,
+    /// CAIP-2 chain ids, e.g. `solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp`.
+    pub chains: Vec<String>,
+    pub methods: Vec<String>,
+    pub expiry: i64,
+    pub connected_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WcSignTransactionRequest {
+    pub topic: String,
+    /// Base64 bincode-serialized `VersionedTransaction`.
+    pub transaction: String,
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WcSignTransactionResponse {
+    pub signature: String,
+    /// The request transaction with the wallet's signature filled in.
+    pub transaction: String,
+}
+
+struct Proposal {
+    pairing_topic: String,
+}
+
+#[derive(Default)]
+struct Inner {
+    loaded: AtomicBool,
+    /// Symmetric keys by topic, for pairings and sessions alike.
+    keys: SyncMutex<HashMap<String, [u8; 32]>>,
+    /// Proposals the wallet answered but has not settled yet, by session topic.
+    proposals: SyncMutex<HashMap<String, Proposal>>,
+    sessions: SyncMutex<HashMap<String, WalletConnectSession>>,
+    /// Our WalletConnect requests awaiting the wallet's response.
+    pending: SyncMutex<HashMap<u64, oneshot::Sender<Result<Value, WalletConnectError>>>>,
+    /// Relay RPC calls awaiting the relay's acknowledgement.
+    relay_acks: SyncMutex<HashMap<u64, oneshot::Sender<Result<Value, String>>>>,
+    relay: Mutex<Option<mpsc::UnboundedSender<String>>>,
+}
+
+/// A WalletConnect v2 client acting as the dApp side: it proposes sessions
+/// to mobile wallets over the public relay and forwards signing requests.
+#[derive(Clone, Default)]
+pub struct WalletConnectState {
+    inner: Arc<Inner>,
+}
+
+impl WalletConnectState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restores persisted sessions and their keys the first time a command
+    /// runs; expired sessions are dropped.
+    fn load(&self, app: &AppHandle) {
+        if self.inner.loaded.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let Some(keystore) = app.try_state::<Keystore>() else {
+            return;
+        };
+        let now = Utc::now().timestamp();
+        let mut keys = self.inner.keys.lock();
+        let mut sessions = self.inner.sessions.lock();
+        for session in read_sessions(app) {
+            if session.expiry <= now {
+                let _ = keystore.remove_secret(&session_key_name(&session.topic));
+                continue;
+            }
+            let Ok(secret) = keystore.retrieve_secret(&session_key_name(&session.topic)) else {
+                continue;
+            };
+            let Ok(key) = <[u8; 32]>::try_from(secret.as_slice()) else {
+                continue;
+            };
+            keys.insert(session.topic.clone(), key);
+            sessions.insert(session.topic.clone(), session);
+        }
+    }
+
+    fn persist(&self, app: &AppHandle) {
+        let sessions: Vec<WalletConnectSession> =
+            self.inner.sessions.lock().values().cloned().collect();
+        if let Err(e) = write_sessions(app, &sessions) {
+            eprintln!("Failed to persist WalletConnect sessions: {}", e);
+        }
+    }
+
+    fn key_for(&self, topic: &str) -> Result<[u8; 32], WalletConnectError> {
+        self.inner
+            .keys
+            .lock()
+            .get(topic)
+            .copied()
+            .ok_or_else(|| WalletConnectError::SessionNotFound(topic.to_string()))
+    }
+
+    /// The relay socket, connecting (and resubscribing to every known topic)
+    /// when there is none or the previous one dropped.
+    async fn relay(
+        &self,
+        app: &AppHandle,
+    ) -> Result<mpsc::UnboundedSender<String>, WalletConnectError> {
+        let mut relay = self.inner.relay.lock().await;
+        if let Some(sender) = relay.as_ref().filter(|sender| !sender.is_closed()) {
+            return Ok(sender.clone());
+        }
+
+        let project_id = std::env::var(PROJECT_ID_ENV)
+            .ok()
+            .filter(|id| !id.trim().is_empty())
+            .ok_or(WalletConnectError::MissingProjectId)?;
+        let keypair = client_keypair(app)?;
+        let url = format!(
+            "{RELAY_URL}/?auth={}&projectId={}",
+            relay_auth_token(&keypair),
+            project_id.trim()
+        );
+        let (stream, _) = connect_async(url)
+            .await
+            .map_err(|e| WalletConnectError::Relay(format!("Failed to connect: {e}")))?;
+        let (mut write, mut read) = stream.split();
+        let (sender, mut outgoing) = mpsc::unbounded_channel::<String>();
+
+        tokio::spawn(async move {
+            while let Some(text) = outgoing.recv().await {
+                if write.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let client = self.clone();
+        let reader_app = app.clone();
+        let reader_sender = sender.clone();
+        tokio::spawn(async move {
+            while let Some(Ok(message)) = read.next().await {
+                if let Message::Text(text) = message {
+                    client.handle_relay_message(&reader_app, &reader_sender, &text);
+                }
+            }
+            // Fail everything still waiting so callers reconnect.
+            client.inner.relay_acks.lock().clear();
+            client.inner.pending.lock().clear();
+        });
+
+        *relay = Some(sender.clone());
+        drop(relay);
+
+        let topics: Vec<String> = self.inner.keys.lock().keys().cloned().collect();
+        for topic in topics {
+            self.relay_call(&sender, "irn_subscribe", json!({ "topic": topic }))
+                .await?;
+        }
+        Ok(sender)
+    }
+
+    fn relay_request(
+        &self,
+        sender: &mpsc::UnboundedSender<String>,
+        method: &str,
+        params: Value,
+    ) -> Result<oneshot::Receiver<Result<Value, String>>, WalletConnectError> {
+        let id = payload_id();
+        let (tx, rx) = oneshot::channel();
+        self.inner.relay_acks.lock().insert(id, tx);
+        let request = json!({ "id": id, "jsonrpc": "2.0", "method": method, "params": params });
+        sender.send(request.to_string()).map_err(|_| {
+            self.inner.relay_acks.lock().remove(&id);
+            WalletConnectError::Relay("Relay connection closed".to_string())
+        })?;
+        Ok(rx)
+    }
+
+    async fn relay_call(
+        &self,
+        sender: &mpsc::UnboundedSender<String>,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, WalletConnectError> {
+        let rx = self.relay_request(sender, method, params)?;
+        match tokio::time::timeout(RELAY_ACK_TIMEOUT, rx).await {
+            Ok(Ok(result)) => result.map_err(WalletConnectError::Relay),
+            Ok(Err(_)) => Err(WalletConnectError::Relay(
+                "Relay connection closed".to_string(),
+            )),
+            Err(_) => Err(WalletConnectError::Relay(format!(
+                "{method} was not acknowledged"
+            ))),
+        }
+    }
+
+    async fn subscribe(&self, app: &AppHandle, topic: &str) -> Result<(), WalletConnectError> {
+        let sender = self.relay(app).await?;
+        self.relay_call(&sender, "irn_subscribe", json!({ "topic": topic }))
+            .await
+            .map(|_| ())
+    }
+
+    fn publish_params(
+        &self,
+        topic: &str,
+        payload: &Value,
+        tag: u32,
+        prompt: bool,
+    ) -> Result<Value, WalletConnectError> {
+        let message = seal(&self.key_for(topic)?, &payload.to_string())?;
+        Ok(json!({
+            "topic": topic,
+            "message": message,
+            "ttl": REQUEST_TTL_SECS,
+            "tag": tag,
+            "prompt": prompt,
+        }))
+    }
+
+    /// Sends a WalletConnect request on `topic` and waits for the wallet's
+    /// response, which may take as long as the user needs to approve it.
+    async fn wc_request(
+        &self,
+        app: &AppHandle,
+        topic: &str,
+        method: &str,
+        params: Value,
+        tag: u32,
+    ) -> Result<Value, WalletConnectError> {
+        let id = payload_id();
+        let payload = json!({ "id": id, "jsonrpc": "2.0", "method": method, "params": params });
+        let publish = self.publish_params(topic, &payload, tag, true)?;
+        let (tx, rx) = oneshot::channel();
+        self.inner.pending.lock().insert(id, tx);
+
+        let sender = self.relay(app).await?;
+        if let Err(e) = self.relay_call(&sender, "irn_publish", publish).await {
+            self.inner.pending.lock().remove(&id);
+            return Err(e);
+        }
+
+        match tokio::time::timeout(Duration::from_secs(REQUEST_TTL_SECS), rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(WalletConnectError::Relay(
+                "Relay connection closed".to_string(),
+            )),
+            Err(_) => {
+                self.inner.pending.lock().remove(&id);
+                Err(WalletConnectError::Timeout)
+            }
+        }
+    }
+
+    /// Replies to a wallet request. Runs on the relay reader, so it must
+    /// not wait for the relay's acknowledgement.
+    fn respond(&self, sender: &mpsc::UnboundedSender<String>, topic: &str, id: &Value, tag: u32) {
+        let payload = json!({ "id": id, "jsonrpc": "2.0", "result": true });
+        let sent = self
+            .publish_params(topic, &payload, tag, false)
+            .and_then(|params| self.relay_request(sender, "irn_publish", params));
+        if let Err(e) = sent {
+            eprintln!("Failed to answer WalletConnect request: {}", e);
+        }
+    }
+
+    fn handle_relay_message(
+        &self,
+        app: &AppHandle,
+        sender: &mpsc::UnboundedSender<String>,
+        text: &str,
+    ) {
+        let Ok(value) = serde_json::from_str::<Value>(text) else {
+            return;
+        };
+
+        if value.get("method").and_then(Value::as_str) == Some("irn_subscription") {
+            let ack = json!({ "id": value["id"], "jsonrpc": "2.0", "result": true });
+            let _ = sender.send(ack.to_string());
+            let data = &value["params"]["data"];
+            if let (Some(topic), Some(message)) = (data["topic"].as_str(), data["message"].as_str())
+            {
+                self.handle_envelope(app, sender, topic, message);
+            }
+            return;
+        }
+
+        let Some(id) = value.get("id").and_then(Value::as_u64) else {
+            return;
+        };
+        if let Some(tx) = self.inner.relay_acks.lock().remove(&id) {
+            let result = match value.get("error") {
+                Some(error) => Err(error["message"]
+                    .as_str()
+                    .unwrap_or("Relay request failed")
+                    .to_string()),
+                None => Ok(value["result"].clone()),
+            };
+            let _ = tx.send(result);
+        }
+    }
+
+    fn handle_envelope(
+        &self,
+        app: &AppHandle,
+        sender: &mpsc::UnboundedSender<String>,
+        topic: &str,
+        message: &str,
+    ) {
+        let Ok(key) = self.key_for(topic) else {
+            return;
+        };
+        let Some(payload) = open(&key, message)
+            .ok()
+            .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+        else {
+            return;
+        };
+
+        let Some(method) = payload.get("method").and_then(Value::as_str) else {
+            if let Some(tx) = payload["id"]
+                .as_u64()
+                .and_then(|id| self.inner.pending.lock().remove(&id))
+            {
+                let result = match payload.get("error") {
+                    Some(error) => Err(WalletConnectError::Rejected(
+                        error["message"]
+                            .as_str()
+                            .unwrap_or("Request rejected")
+                            .to_string(),
+                    )),
+                    None => Ok(payload["result"].clone()),
+                };
+                let _ = tx.send(result);
+            }
+            return;
+        };
+
+        let id = &payload["id"];
+        let params = &payload["params"];
+        match method {
+            "wc_sessionSettle" => {
+                let Some(proposal) = self.inner.proposals.lock().remove(topic) else {
+                    return;
+                };
+                let session = settled_session(topic, &proposal.pairing_topic, params);
+                if let Some(keystore) = app.try_state::<Keystore>() {
+                    if let Err(e) = keystore.store_secret(&session_key_name(topic), &key) {
+                        eprintln!("Failed to store WalletConnect session key: {}", e);
+                    }
+                }
+                self.inner
+                    .sessions
+                    .lock()
+                    .insert(topic.to_string(), session.clone());
+                self.persist(app);
+                self.respond(sender, topic, id, 1103);
+                let _ = app.emit("walletconnect_session_settled", &session);
+            }
+            "wc_sessionUpdate" => {
+                if let Some(session) = self.inner.sessions.lock().get_mut(topic) {
+                    let (accounts, chains) = namespace_accounts(&params["namespaces"]);
+                    session.accounts = accounts;
+                    session.chains = chains;
+                }
+                self.persist(app);
+                self.respond(sender, topic, id, 1105);
+            }
+            "wc_sessionExtend" => {
+                if let Some(session) = self.inner.sessions.lock().get_mut(topic) {
+                    session.expiry = params["expiry"].as_i64().unwrap_or(session.expiry);
+                }
+                self.persist(app);
+                self.respond(sender, topic, id, 1107);
+            }
+            "wc_sessionEvent" => {
+                self.respond(sender, topic, id, 1111);
+                let _ = app.emit(
+                    "walletconnect_session_event",
+                    json!({ "topic": topic, "event": params["event"] }),
+                );
+            }
+            "wc_sessionDelete" => {
+                self.respond(sender, topic, id, TAG_SESSION_DELETE + 1);
+                self.forget_session(app, topic);
+                let _ = app.emit("walletconnect_session_deleted", json!({ "topic": topic }));
+            }
+            "wc_sessionPing" => self.respond(sender, topic, id, 1115),
+            "wc_pairingPing" => self.respond(sender, topic, id, 1003),
+            "wc_pairingDelete" => {
+                self.respond(sender, topic, id, 1001);
+                self.inner.keys.lock().remove(topic);
+            }
+            _ => {}
+        }
+    }
+
+    fn forget_session(&self, app: &AppHandle, topic: &str) {
+        self.inner.sessions.lock().remove(topic);
+        self.inner.keys.lock().remove(topic);
+        if let Some(keystore) = app.try_state::<Keystore>() {
+            let _ = keystore.remove_secret(&session_key_name(topic));
+        }
+        self.persist(app);
+    }
+
+    /// Sends the session proposal over a fresh pairing and, once the wallet
+    /// answers, subscribes to the session topic it settles on.
+    async fn propose(
+        &self,
+        app: &AppHandle,
+        pairing_topic: &str,
+        chains: Vec<String>,
+        expires_at: i64,
+    ) -> Result<(), WalletConnectError> {
+        let secret = random_bytes();
+        let params = json!({
+            "relays": [{ "protocol": "irn" }],
+            "proposer": {
+                "publicKey": hex::encode(x25519_public(&secret)),
+                "metadata": app_metadata(),
+            },
+            "requiredNamespaces": {
+                "solana": { "chains": chains, "methods": SOLANA_METHODS, "events": [] },
+            },
+            "expiryTimestamp": expires_at,
+        });
+        let result = self
+            .wc_request(
+                app,
+                pairing_topic,
+                "wc_sessionPropose",
+                params,
+                TAG_SESSION_PROPOSE,
+            )
+            .await?;
+
+        let responder = result["responderPublicKey"]
+            .as_str()
+            .and_then(|key| hex::decode(key).ok())
+            .and_then(|key| <[u8; 32]>::try_from(key).ok())
+            .ok_or_else(|| {
+                WalletConnectError::Crypto("Wallet sent an invalid public key".to_string())
+            })?;
+        let session_key = derive_sym_key(&secret, &responder);
+        let session_topic = topic_for(&session_key);
+        self.inner
+            .keys
+            .lock()
+            .insert(session_topic.clone(), session_key);
+        self.inner.proposals.lock().insert(
+            session_topic.clone(),
+            Proposal {
+                pairing_topic: pairing_topic.to_string(),
+            },
+        );
+        self.subscribe(app, &session_topic).await
+    }
+}
+
+fn session_key_name(topic: &str) -> String {
+    format!("{SESSION_KEY_PREFIX}{topic}")
+}
+
+fn read_sessions(app: &AppHandle) -> Vec<WalletConnectSession> {
+    let Ok(dir) = app.path().profile_data_dir() else {
+        return Vec::new();
+    };
+    std::fs::read_to_string(dir.join(SESSIONS_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_sessions(
+    app: &AppHandle,
+    sessions: &[WalletConnectSession],
+) -> Result<(), WalletConnectError> {
+    let dir = app
+        .path()
+        .profile_data_dir()
+        .map_err(|e| WalletConnectError::Storage(format!("Unable to resolve data dir: {e}")))?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| WalletConnectError::Storage(format!("Failed to create data dir: {e}")))?;
+    let content = serde_json::to_string_pretty(sessions)
+        .map_err(|e| WalletConnectError::Storage(format!("Failed to serialize sessions: {e}")))?;
+    std::fs::write(dir.join(SESSIONS_FILE), content)
+        .map_err(|e| WalletConnectError::Storage(format!("Failed to write sessions: {e}")))
+}
+
+/// The ed25519 key identifying this app to the relay, created on first use.
+fn client_keypair(app: &AppHandle) -> Result<Keypair, WalletConnectError> {
+    let keystore = app
+        .try_state::<Keystore>()
+        .ok_or_else(|| WalletConnectError::Storage("Keystore is not available".to_string()))?;
+    if let Ok(secret) = keystore.retrieve_secret(CLIENT_KEY) {
+        return Keypair::from_bytes(&secret)
+            .map_err(|e| WalletConnectError::Storage(format!("Invalid client key: {e}")));
+    }
+    let keypair = Keypair::new();
+    keystore
+        .store_secret(CLIENT_KEY, &keypair.to_bytes())
+        .map_err(|e| WalletConnectError::Storage(format!("Failed to store client key: {e}")))?;
+    Ok(keypair)
+}
+
+fn app_metadata() -> Value {
+    json!({
+        "name": "Eclipse Market Pro",
+        "description": "Solana trading desktop",
+        "url": "https://eclipse.market",
+        "icons": [],
+    })
+}
+
+fn random_bytes() -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Millisecond timestamp with three random digits appended, the id scheme
+/// WalletConnect clients use for JSON-RPC payloads.
+fn payload_id() -> u64 {
+    Utc::now().timestamp_millis() as u64 * 1000 + u64::from(OsRng.next_u32() % 1000)
+}
+
+/// Session key: HKDF-SHA256 over the X25519 shared secret, no salt or info.
+fn derive_sym_key(secret: &[u8; 32], peer_public: &[u8; 32]) -> [u8; 32] {
+    hkdf_sha256(None, &x25519_shared(secret, peer_public), &[])
+}
+
+fn topic_for(key: &[u8; 32]) -> String {
+    hex::encode(Sha256::digest(key))
+}
+
+/// Type 0 envelope: `base64(0x00 || iv || ChaCha20-Poly1305 ciphertext)`.
+fn seal(key: &[u8; 32], plaintext: &str) -> Result<String, WalletConnectError> {
+    let mut iv = [0u8; IV_LENGTH];
+    OsRng.fill_bytes(&mut iv);
+    let sealed = ChaCha20Poly1305::new(Key::from_slice(key))
+        .encrypt(Nonce::from_slice(&iv), plaintext.as_bytes())
+        .map_err(|e| WalletConnectError::Crypto(format!("Encryption failed: {e}")))?;
+    let mut envelope = Vec::with_capacity(1 + IV_LENGTH + sealed.len());
+    envelope.push(ENVELOPE_TYPE_0);
+    envelope.extend_from_slice(&iv);
+    envelope.extend_from_slice(&sealed);
+    Ok(BASE64_ENGINE.encode(envelope))
+}
+
+fn open(key: &[u8; 32], message: &str) -> Result<String, WalletConnectError> {
+    let envelope = BASE64_ENGINE
+        .decode(message)
+        .map_err(|e| WalletConnectError::Crypto(format!("Invalid envelope: {e}")))?;
+    if envelope.len() <= 1 + IV_LENGTH || envelope[0] != ENVELOPE_TYPE_0 {
+        return Err(WalletConnectError::Crypto(
+            "Unsupported envelope".to_string(),
+        ));
+    }
+    let (iv, sealed) = envelope[1..].split_at(IV_LENGTH);
+    let plaintext = ChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(Nonce::from_slice(iv), sealed)
+        .map_err(|e| WalletConnectError::Crypto(format!("Decryption failed: {e}")))?;
+    String::from_utf8(plaintext).map_err(|e| WalletConnectError::Crypto(e.to_string()))
+}
+
+/// JWT the relay requires: the client's ed25519 key as a `did:key` issuer,
+/// signed with EdDSA.
+fn relay_auth_token(keypair: &Keypair) -> String {
+    let mut multicodec = vec![0xed, 0x01];
+    multicodec.extend_from_slice(keypair.pubkey().as_ref());
+    let issuer = format!("did:key:z{}", bs58::encode(multicodec).into_string());
+    let issued_at = Utc::now().timestamp();
+    let header = URL_SAFE_NO_PAD.encode(json!({ "alg": "EdDSA", "typ": "JWT" }).to_string());
+    let claims = URL_SAFE_NO_PAD.encode(
+        json!({
+            "iss": issuer,
+            "sub": hex::encode(random_bytes()),
+            "aud": RELAY_URL,
+            "iat": issued_at,
+            "exp": issued_at + 86_400,
+        })
+        .to_string(),
+    );
+    let signing_input = format!("{header}.{claims}");
+    let signature = keypair.sign_message(signing_input.as_bytes());
+    format!(
+        "{signing_input}.{}",
+        URL_SAFE_NO_PAD.encode(signature.as_ref())
+    )
+}
+
+/// Addresses and chains from settled namespaces, whose accounts are CAIP-10
+/// strings like `solana:<chain ref>:<address>`.
+fn namespace_accounts(namespaces: &Value) -> (Vec<String>, Vec<String>) {
+    let mut addresses = Vec::new();
+    let mut chains = Vec::new();
+    let Some(namespaces) = namespaces.as_object() else {
+        return (addresses, chains);
+    };
+    for account in namespaces
+        .values()
+        .filter_map(|ns| ns["accounts"].as_array())
+        .flatten()
+        .filter_map(Value::as_str)
+    {
+        let Some((chain, address)) = account.rsplit_once(':') else {
+            continue;
+        };
+        if !addresses.iter().any(|a| a == address) {
+            addresses.push(address.to_string());
+        }
+        if !chains.iter().any(|c| c == chain) {
+            chains.push(chain.to_string());
+        }
+    }
+    (addresses, chains)
+}
+
+fn settled_session(topic: &str, pairing_topic: &str, params: &Value) -> WalletConnectSession {
+    let (accounts, chains) = namespace_accounts(&params["namespaces"]);
+    let methods = params["namespaces"]
+        .as_object()
+        .map(|namespaces| {
+            namespaces
+                .values()
+                .filter_map(|ns| ns["methods"].as_array())
+                .flatten()
+                .filter_map(|m| m.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    WalletConnectSession {
+        topic: topic.to_string(),
+        pairing_topic: pairing_topic.to_string(),
+        peer: serde_json::from_value(params["controller"]["metadata"].clone()).unwrap_or_default(),
+        accounts,
+        chains,
+        methods,
+        expiry: params["expiry"].as_i64().unwrap_or_default(),
+        connected_at: Utc::now().to_rfc3339(),
+    }
+}
+
+fn qr_svg(text: &str) -> Result<String, WalletConnectError> {
+    let qr = QrCode::encode_text(text, QrCodeEcc::Medium)
+        .map_err(|_| WalletConnectError::InvalidInput("URI too long for a QR code".to_string()))?;
+    let border = 4;
+    let total = qr.size() + border * 2;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" viewBox=\"0 0 {total} {total}\" stroke=\"none\"><rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/><path d=\""
+    );
+    for y in 0..qr.size() {
+        for x in 0..qr.size() {
+            if qr.get_module(x, y) {
+                svg.push_str(&format!("M{},{}h1v1h-1z", x + border, y + border));
+            }
+        }
+    }
+    svg.push_str("\" fill=\"#000000\"/></svg>");
+    Ok(svg)
+}
+
+/// Starts a pairing and returns the `wc:` URI and its QR code. The session
+/// proposal is sent in the background; `walletconnect_session_settled` fires
+/// once the wallet approves.
+#[tauri::command]
+pub async fn wc_pair(
+    chains: Option<Vec<String>>,
+    state: State<'_, WalletConnectState>,
+    app: AppHandle,
+) -> Result<WalletConnectPairing, WalletConnectError> {
+    state.load(&app);
+    let chains = chains
+        .filter(|chains| !chains.is_empty())
+        .unwrap_or_else(|| vec![SOLANA_MAINNET.to_string()]);
+    if let Some(chain) = chains.iter().find(|chain| !chain.starts_with("solana:")) {
+        return Err(WalletConnectError::InvalidInput(format!(
+            "Unsupported chain: {chain}"
+        )));
+    }
+
+    let sym_key = random_bytes();
+    let topic = hex::encode(random_bytes());
+    let expires_at = Utc::now().timestamp() + PROPOSAL_TTL_SECS;
+    let uri = format!(
+        "wc:{topic}@2?relay-protocol=irn&symKey={}&expiryTimestamp={expires_at}",
+        hex::encode(sym_key)
+    );
+    state.inner.keys.lock().insert(topic.clone(), sym_key);
+    if let Err(e) = state.subscribe(&app, &topic).await {
+        state.inner.keys.lock().remove(&topic);
+        return Err(e);
+    }
+
+    let client = state.inner().clone();
+    let pairing_topic = topic.clone();
+    tokio::spawn(async move {
+        let result = client
+            .propose(&app, &pairing_topic, chains, expires_at)
+            .await;
+        if let Err(e) = result {
+            client.inner.keys.lock().remove(&pairing_topic);
+            let _ = app.emit(
+                "walletconnect_pairing_failed",
+                json!({ "topic": pairing_topic, "error": e.to_string() }),
+            );
+        }
+    });
+
+    Ok(WalletConnectPairing {
+        qr_code: qr_svg(&uri)?,
+        topic,
+        uri,
+        expires_at,
+    })
+}
+
+#[tauri::command]
+pub async fn wc_list_sessions(
+    state: State<'_, WalletConnectState>,
+    app: AppHandle,
+) -> Result<Vec<WalletConnectSession>, WalletConnectError> {
+    state.load(&app);
+    let now = Utc::now().timestamp();
+    let expired: Vec<String> = state
+        .inner
+        .sessions
+        .lock()
+        .values()
+        .filter(|session| session.expiry <= now)
+        .map(|session| session.topic.clone())
+        .collect();
+    for topic in &expired {
+        state.forget_session(&app, topic);
+    }
+
+    let mut sessions: Vec<WalletConnectSession> =
+        state.inner.sessions.lock().values().cloned().collect();
+    sessions.sort_by(|a, b| b.connected_at.cmp(&a.connected_at));
+    Ok(sessions)
+}
+
+/// Asks the wallet behind `topic` to sign a transaction and returns it with
+/// the wallet's signature in place. The wallet only signs; broadcasting is
+/// left to the caller.
+#[tauri::command]
+pub async fn wc_sign_transaction(
+    request: WcSignTransactionRequest,
+    state: State<'_, WalletConnectState>,
+    app: AppHandle,
+) -> Result<WcSignTransactionResponse, WalletConnectError> {
+    state.load(&app);
+    let session = state
+        .inner
+        .sessions
+        .lock()
+        .get(&request.topic)
+        .cloned()
+        .ok_or_else(|| WalletConnectError::SessionNotFound(request.topic.clone()))?;
+
+    let address = match request.address {
+        Some(address) if session.accounts.contains(&address) => address,
+        Some(address) => {
+            return Err(WalletConnectError::InvalidInput(format!(
+                "{address} is not part of this session"
+            )))
+        }
+        None => session.accounts.first().cloned().ok_or_else(|| {
+            WalletConnectError::InvalidInput("Session has no accounts".to_string())
+        })?,
+    };
+    let signer = Pubkey::from_str(&address)
+        .map_err(|e| WalletConnectError::InvalidInput(format!("Invalid address: {e}")))?;
+
+    let bytes = BASE64_ENGINE
+        .decode(request.transaction.as_bytes())
+        .map_err(|e| {
+            WalletConnectError::InvalidInput(format!("Invalid transaction encoding: {e}"))
+        })?;
+    let mut transaction: VersionedTransaction = bincode::deserialize(&bytes).map_err(|e| {
+        WalletConnectError::InvalidInput(format!("Failed to decode transaction: {e}"))
+    })?;
+    let required = usize::from(transaction.message.header().num_required_signatures);
+    let signer_index = transaction
+        .message
+        .static_account_keys()
+        .iter()
+        .take(required)
+        .position(|key| *key == signer)
+        .ok_or_else(|| {
+            WalletConnectError::InvalidInput(format!(
+                "Transaction does not need a signature from {address}"
+            ))
+        })?;
+
+    let chain_id = session
+        .chains
+        .first()
+        .cloned()
+        .unwrap_or_else(|| SOLANA_MAINNET.to_string());
+    let params = json!({
+        "request": {
+            "method": "solana_signTransaction",
+            "params": { "transaction": request.transaction, "pubkey": address },
+        },
+        "chainId": chain_id,
+    });
+    let result = state
+        .wc_request(
+            &app,
+            &session.topic,
+            "wc_sessionRequest",
+            params,
+            TAG_SESSION_REQUEST,
+        )
+        .await?;
+
+    // Newer wallets return the signed transaction, older ones just the
+    // signature.
+    let returned = result["transaction"]
+        .as_str()
+        .and_then(|tx| BASE64_ENGINE.decode(tx).ok())
+        .and_then(|tx| bincode::deserialize::<VersionedTransaction>(&tx).ok())
+        .and_then(|tx| tx.signatures.get(signer_index).copied());
+    let signature = match returned {
+        Some(signature) => signature,
+        None => result["signature"]
+            .as_str()
+            .and_then(|s| Signature::from_str(s).ok())
+            .ok_or_else(|| {
+                WalletConnectError::Rejected("Wallet did not return a signature".to_string())
+            })?,
+    };
+    if !signature.verify(signer.as_ref(), &transaction.message.serialize()) {
+        return Err(WalletConnectError::Crypto(
+            "Wallet signature does not match the transaction".to_string(),
+        ));
+    }
+    if transaction.signatures.len() < required {
+        transaction
+            .signatures
+            .resize(required, Signature::default());
+    }
+    transaction.signatures[signer_index] = signature;
+    let signed = bincode::serialize(&transaction).map_err(|e| {
+        WalletConnectError::InvalidInput(format!("Failed to encode transaction: {e}"))
+    })?;
+
+    let logger = app.state::<ActivityLogger>();
+    let _ = logger
+        .log_sign(
+            &address,
+            json!({ "source": "walletconnect", "peer": session.peer.name }),
+            true,
+            None,
+        )
+        .await;
+
+    Ok(WcSignTransactionResponse {
+        signature: signature.to_string(),
+        transaction: BASE64_ENGINE.encode(signed),
+    })
+}
+
+#[tauri::command]
+pub async fn wc_disconnect(
+    topic: String,
+    state: State<'_, WalletConnectState>,
+    app: AppHandle,
+) -> Result<(), WalletConnectError> {
+    state.load(&app);
+    if !state.inner.sessions.lock().contains_key(&topic) {
+        return Err(WalletConnectError::SessionNotFound(topic));
+    }
+
+    // Tell the wallet, but drop the session locally even if the relay is down.
+    let payload = json!({
+        "id": payload_id(),
+        "jsonrpc": "2.0",
+        "method": "wc_sessionDelete",
+        "params": { "code": 6000, "message": "User disconnected." },
+    });
+    if let Ok(sender) = state.relay(&app).await {
+        if let Ok(params) = state.publish_params(&topic, &payload, TAG_SESSION_DELETE, false) {
+            let _ = state.relay_call(&sender, "irn_publish", params).await;
+        }
+    }
+    state.forget_session(&app, &topic);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envelopes_round_trip_and_reject_other_keys() {
+        let key = random_bytes();
+        let message = seal(&key, r#"{"id":1,"jsonrpc":"2.0","result":true}"#).unwrap();
+        let raw = BASE64_ENGINE.decode(&message).unwrap();
+        assert_eq!(raw[0], ENVELOPE_TYPE_0);
+        assert_eq!(
+            open(&key, &message).unwrap(),
+            r#"{"id":1,"jsonrpc":"2.0","result":true}"#
+        );
+        assert!(open(&random_bytes(), &message).is_err());
+    }
+
+    #[test]
+    fn both_sides_derive_the_same_session_topic() {
+        let ours = random_bytes();
+        let theirs = random_bytes();
+        let key = derive_sym_key(&ours, &x25519_public(&theirs));
+        assert_eq!(key, derive_sym_key(&theirs, &x25519_public(&ours)));
+        assert_eq!(topic_for(&key).len(), 64);
+    }
+
+    #[test]
+    fn settled_namespaces_yield_accounts_and_chains() {
+        let params = json!({
+            "controller": { "publicKey": "00", "metadata": { "name": "Solflare", "url": "https://solflare.com" } },
+            "namespaces": {
+                "solana": {
+                    "accounts": [
+                        "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp:9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+                        "solana:EtWTRABZaYq6iMfeYKouRu166VU2xqa1:9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+                    ],
+                    "methods": ["solana_signTransaction"],
+                    "events": [],
+                },
+            },
+            "expiry": 1_900_000_000,
+        });
+        let session = settled_session("topic", "pairing", &params);
+        assert_eq!(session.peer.name, "Solflare");
+        assert_eq!(
+            session.accounts,
+            vec!["9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM"]
+        );
+        assert_eq!(session.chains.len(), 2);
+        assert_eq!(session.chains[0], SOLANA_MAINNET);
+        assert_eq!(session.methods, vec!["solana_signTransaction"]);
+        assert_eq!(session.expiry, 1_900_000_000);
+    }
+
+    #[test]
+    fn relay_token_is_signed_by_the_client_key() {
+        let keypair = Keypair::new();
+        let token = relay_auth_token(&keypair);
+        let parts: Vec<&str> = token.split('.').collect();
+        assert_eq!(parts.len(), 3);
+        let claims: Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(parts[1]).unwrap()).unwrap();
+        assert!(claims["iss"].as_str().unwrap().starts_with("did:key:z6Mk"));
+        let signature = Signature::try_from(URL_SAFE_NO_PAD.decode(parts[2]).unwrap()).unwrap();
+        assert!(signature.verify(
+            keypair.pubkey().as_ref(),
+            format!("{}.{}", parts[0], parts[1]).as_bytes()
+        ));
+    }
+}
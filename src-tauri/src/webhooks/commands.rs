@@ -1,9 +1,14 @@
+use super::inbound::{SharedInboundWebhookServer, DEFAULT_INBOUND_PORT};
 use super::manager::WebhookManager;
-use super::types::{WebhookConfig, WebhookDeliveryLog, WebhookError, WebhookTestResult};
+use super::types::{
+    InboundServerStatus, InboundWebhookEndpoint, InboundWebhookEvent, InboundWebhookInput,
+    WebhookConfig, WebhookDeliveryLog, WebhookError, WebhookTestResult,
+};
+use crate::security::keystore::Keystore;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, State};
 use tokio::sync::RwLock;
 
 pub type SharedWebhookManager = Arc<RwLock<WebhookManager>>;
@@ -90,3 +95,99 @@ pub async fn list_webhook_delivery_logs(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+pub async fn list_inbound_webhooks(
+    manager: State<'_, SharedWebhookManager>,
+) -> Result<Vec<InboundWebhookEndpoint>, String> {
+    let mgr = manager.read().await;
+    mgr.list_inbound_endpoints()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_inbound_webhook(
+    manager: State<'_, SharedWebhookManager>,
+    keystore: State<'_, Keystore>,
+    input: InboundWebhookInput,
+) -> Result<InboundWebhookEndpoint, String> {
+    let mgr = manager.read().await;
+    mgr.create_inbound_endpoint(input, &keystore)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_inbound_webhook(
+    manager: State<'_, SharedWebhookManager>,
+    id: String,
+    input: InboundWebhookInput,
+) -> Result<InboundWebhookEndpoint, String> {
+    let mgr = manager.read().await;
+    mgr.update_inbound_endpoint(&id, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_inbound_webhook(
+    manager: State<'_, SharedWebhookManager>,
+    keystore: State<'_, Keystore>,
+    id: String,
+) -> Result<(), String> {
+    let mgr = manager.read().await;
+    mgr.delete_inbound_endpoint(&id, &keystore)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rotate_inbound_webhook_secret(
+    manager: State<'_, SharedWebhookManager>,
+    keystore: State<'_, Keystore>,
+    id: String,
+) -> Result<InboundWebhookEndpoint, String> {
+    let mgr = manager.read().await;
+    mgr.rotate_inbound_secret(&id, &keystore)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn start_inbound_webhook_server(
+    app: AppHandle,
+    server: State<'_, SharedInboundWebhookServer>,
+    port: Option<u16>,
+) -> Result<InboundServerStatus, String> {
+    let mut server = server.write().await;
+    server
+        .start(app, port.unwrap_or(DEFAULT_INBOUND_PORT))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn stop_inbound_webhook_server(
+    server: State<'_, SharedInboundWebhookServer>,
+) -> Result<InboundServerStatus, String> {
+    let mut server = server.write().await;
+    Ok(server.stop())
+}
+
+#[tauri::command]
+pub async fn get_inbound_webhook_server_status(
+    server: State<'_, SharedInboundWebhookServer>,
+) -> Result<InboundServerStatus, String> {
+    let server = server.read().await;
+    Ok(server.status())
+}
+
+#[tauri::command]
+pub async fn list_inbound_webhook_events(
+    server: State<'_, SharedInboundWebhookServer>,
+    limit: Option<usize>,
+) -> Result<Vec<InboundWebhookEvent>, String> {
+    let server = server.read().await;
+    Ok(server.recent_events(limit.unwrap_or(50)))
+}
@@ -0,0 +1,774 @@
+//! Localhost listener for inbound webhooks such as TradingView alerts.
+//!
+//! Each configured endpoint is served on `POST /hooks/<path>`. Requests carry
+//! an `X-Timestamp` (unix seconds), a unique `X-Delivery-Id`, and an
+//! `X-Signature` with the hex HMAC-SHA256 of `<timestamp>.<delivery id>.<raw
+//! body>`, keyed with the endpoint secret. Requests older than five minutes
+//! and delivery ids already seen are rejected, so a captured request cannot
+//! be replayed into a trade. TradingView cannot sign requests itself, so the
+//! expected setup is a tunnel or relay that forwards to this port and adds
+//! the headers.
+
+use super::commands::SharedWebhookManager;
+use super::manager::load_inbound_secret;
+use super::types::{
+    InboundMapping, InboundRoute, InboundServerStatus, InboundWebhookEndpoint, InboundWebhookEvent,
+    WebhookError,
+};
+use crate::alerts::SharedAlertManager;
+use crate::security::keystore::Keystore;
+use crate::trading::SharedAutoTradingEngine;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, RwLock};
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const DEFAULT_INBOUND_PORT: u16 = 8787;
+const MAX_HEADER_BYTES: usize = 8 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_STORED_EVENTS: usize = 200;
+/// How far a request timestamp may be from the local clock.
+const SIGNATURE_WINDOW_SECS: i64 = 300;
+
+pub type SharedInboundWebhookServer = Arc<RwLock<InboundWebhookServer>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignalAction {
+    Buy,
+    Sell,
+    Close,
+}
+
+impl SignalAction {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "buy" | "long" => Some(SignalAction::Buy),
+            "sell" | "short" => Some(SignalAction::Sell),
+            "close" | "exit" | "flat" => Some(SignalAction::Close),
+            _ => None,
+        }
+    }
+
+    /// Signal strength handed to the auto-trading engine.
+    pub fn signal_value(&self) -> f64 {
+        match self {
+            SignalAction::Buy => 1.0,
+            SignalAction::Sell => -1.0,
+            SignalAction::Close => 0.0,
+        }
+    }
+}
+
+/// An inbound payload after applying the endpoint's field mapping.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradingViewAlert {
+    pub symbol: String,
+    pub price: Option<f64>,
+    pub action: Option<SignalAction>,
+}
+
+/// Delivery ids accepted inside the signature window. Anything older is
+/// rejected on its timestamp alone, so entries are pruned past the window.
+#[derive(Debug, Default)]
+struct ReplayGuard {
+    seen: HashMap<String, i64>,
+}
+
+impl ReplayGuard {
+    /// Records the delivery and returns false if it was already seen.
+    fn record(&mut self, endpoint_id: &str, delivery_id: &str, timestamp: i64, now: i64) -> bool {
+        self.seen
+            .retain(|_, seen_at| *seen_at >= now - SIGNATURE_WINDOW_SECS);
+        self.seen
+            .insert(format!("{}:{}", endpoint_id, delivery_id), timestamp)
+            .is_none()
+    }
+}
+
+#[derive(Debug)]
+struct RequestHead {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+}
+
+pub struct InboundWebhookServer {
+    shutdown: Option<oneshot::Sender<()>>,
+    port: Option<u16>,
+    started_at: Option<DateTime<Utc>>,
+    requests_received: Arc<AtomicU64>,
+    events: Arc<Mutex<VecDeque<InboundWebhookEvent>>>,
+    replays: Arc<Mutex<ReplayGuard>>,
+}
+
+impl Default for InboundWebhookServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InboundWebhookServer {
+    pub fn new() -> Self {
+        Self {
+            shutdown: None,
+            port: None,
+            started_at: None,
+            requests_received: Arc::new(AtomicU64::new(0)),
+            events: Arc::new(Mutex::new(VecDeque::new())),
+            replays: Arc::new(Mutex::new(ReplayGuard::default())),
+        }
+    }
+
+    pub fn status(&self) -> InboundServerStatus {
+        InboundServerStatus {
+            running: self.shutdown.is_some(),
+            port: self.port,
+            started_at: self.started_at,
+            requests_received: self.requests_received.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Binds `127.0.0.1:<port>` and serves inbound webhooks until stopped.
+    pub async fn start(
+        &mut self,
+        app: AppHandle,
+        port: u16,
+    ) -> Result<InboundServerStatus, WebhookError> {
+        if self.shutdown.is_some() {
+            return Err(WebhookError::Internal(
+                "inbound webhook server already running".to_string(),
+            ));
+        }
+
+        let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+        let counter = self.requests_received.clone();
+        let events = self.events.clone();
+        let replays = self.replays.clone();
+
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    accepted = listener.accept() => {
+                        let (stream, _) = match accepted {
+                            Ok(conn) => conn,
+                            Err(e) => {
+                                eprintln!("Inbound webhook accept failed: {}", e);
+                                continue;
+                            }
+                        };
+                        counter.fetch_add(1, Ordering::Relaxed);
+                        let app = app.clone();
+                        let events = events.clone();
+                        let replays = replays.clone();
+                        tauri::async_runtime::spawn(async move {
+                            handle_connection(app, stream, events, replays).await;
+                        });
+                    }
+                }
+            }
+        });
+
+        self.shutdown = Some(shutdown_tx);
+        self.port = Some(port);
+        self.started_at = Some(Utc::now());
+        Ok(self.status())
+    }
+
+    pub fn stop(&mut self) -> InboundServerStatus {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        self.port = None;
+        self.started_at = None;
+        self.status()
+    }
+
+    pub fn recent_events(&self, limit: usize) -> Vec<InboundWebhookEvent> {
+        let events = match self.events.lock() {
+            Ok(events) => events,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        events.iter().rev().take(limit).cloned().collect()
+    }
+}
+
+/// Checks a hex HMAC-SHA256, with or without a `sha256=` prefix, over
+/// `<timestamp>.<delivery id>.<raw body>`.
+pub fn verify_inbound_signature(
+    secret: &[u8],
+    timestamp: &str,
+    delivery_id: &str,
+    body: &[u8],
+    signature: &str,
+) -> bool {
+    let signature = signature.trim();
+    let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+    let Ok(expected) = hex::decode(signature) else {
+        return false;
+    };
+    let Ok(mut mac) = HmacSha256::new_from_slice(secret) else {
+        return false;
+    };
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(delivery_id.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+fn lookup_field<'a>(payload: &'a Value, field: &str) -> Option<&'a Value> {
+    field
+        .split('.')
+        .try_fold(payload, |value, key| value.get(key))
+}
+
+fn value_as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Applies an endpoint mapping to a JSON payload. Exchange prefixes such as
+/// `BINANCE:SOLUSDT` are dropped before aliases are looked up.
+pub fn extract_alert(
+    mapping: &InboundMapping,
+    payload: &Value,
+) -> Result<TradingViewAlert, String> {
+    let raw_symbol = lookup_field(payload, &mapping.symbol_field)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("missing symbol field '{}'", mapping.symbol_field))?;
+    let ticker = raw_symbol
+        .rsplit(':')
+        .next()
+        .unwrap_or(raw_symbol)
+        .trim()
+        .to_uppercase();
+    if ticker.is_empty() {
+        return Err("empty symbol".to_string());
+    }
+
+    let symbol = mapping
+        .symbol_aliases
+        .iter()
+        .find(|(from, _)| from.eq_ignore_ascii_case(&ticker))
+        .map(|(_, to)| to.to_uppercase())
+        .unwrap_or(ticker);
+
+    if !mapping.allowed_symbols.is_empty()
+        && !mapping
+            .allowed_symbols
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(&symbol))
+    {
+        return Err(format!("symbol {} is not allowed on this endpoint", symbol));
+    }
+
+    let price = lookup_field(payload, &mapping.price_field).and_then(value_as_f64);
+    let action = match lookup_field(payload, &mapping.action_field).and_then(Value::as_str) {
+        Some(raw) => {
+            Some(SignalAction::parse(raw).ok_or_else(|| format!("unrecognised action '{}'", raw))?)
+        }
+        None => None,
+    };
+
+    Ok(TradingViewAlert {
+        symbol,
+        price,
+        action,
+    })
+}
+
+fn parse_request_head(head: &str) -> Result<RequestHead, String> {
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().ok_or("empty request")?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or("missing method")?.to_uppercase();
+    let target = parts.next().ok_or("missing path")?;
+    let path = target.split('?').next().unwrap_or(target).to_string();
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+
+    Ok(RequestHead {
+        method,
+        path,
+        headers,
+    })
+}
+
+fn find_header_end(buf: &[u8]) -> Option<usize> {
+    buf.windows(4).position(|window| window == b"\r\n\r\n")
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<(RequestHead, Vec<u8>), (u16, String)> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+
+    let header_end = loop {
+        if let Some(end) = find_header_end(&buf) {
+            break end;
+        }
+        if buf.len() > MAX_HEADER_BYTES {
+            return Err((431, "request headers too large".to_string()));
+        }
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| (400, e.to_string()))?;
+        if read == 0 {
+            return Err((400, "connection closed before headers".to_string()));
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = std::str::from_utf8(&buf[..header_end])
+        .map_err(|_| (400, "request headers are not UTF-8".to_string()))
+        .and_then(|head| parse_request_head(head).map_err(|e| (400, e)))?;
+
+    let content_length = head
+        .headers
+        .get("content-length")
+        .and_then(|len| len.parse::<usize>().ok())
+        .unwrap_or(0);
+    if content_length > MAX_BODY_BYTES {
+        return Err((413, "payload too large".to_string()));
+    }
+
+    let mut body = buf[header_end + 4..].to_vec();
+    if body.len() < content_length {
+        let mut rest = vec![0u8; content_length - body.len()];
+        stream
+            .read_exact(&mut rest)
+            .await
+            .map_err(|e| (400, e.to_string()))?;
+        body.extend_from_slice(&rest);
+    }
+    body.truncate(content_length);
+
+    Ok((head, body))
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+async fn write_response(stream: &mut TcpStream, status: u16, message: &str) {
+    let body = json!({ "ok": status == 200, "message": message }).to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason_phrase(status),
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+struct Outcome {
+    status: u16,
+    message: String,
+    endpoint: Option<InboundWebhookEndpoint>,
+    symbol: Option<String>,
+}
+
+impl Outcome {
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            endpoint: None,
+            symbol: None,
+        }
+    }
+}
+
+async fn handle_connection(
+    app: AppHandle,
+    mut stream: TcpStream,
+    events: Arc<Mutex<VecDeque<InboundWebhookEvent>>>,
+    replays: Arc<Mutex<ReplayGuard>>,
+) {
+    let outcome = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Err(_) => Outcome::error(408, "timed out reading request"),
+        Ok(Err((status, message))) => Outcome::error(status, message),
+        Ok(Ok((head, body))) => process_request(&app, &replays, head, body).await,
+    };
+
+    write_response(&mut stream, outcome.status, &outcome.message).await;
+
+    let event = InboundWebhookEvent {
+        id: Uuid::new_v4().to_string(),
+        endpoint_id: outcome.endpoint.as_ref().map(|e| e.id.clone()),
+        endpoint_name: outcome.endpoint.as_ref().map(|e| e.name.clone()),
+        status_code: outcome.status,
+        message: outcome.message,
+        symbol: outcome.symbol,
+        received_at: Utc::now(),
+    };
+    let _ = app.emit("inbound_webhook_received", &event);
+
+    let mut events = match events.lock() {
+        Ok(events) => events,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    events.push_back(event);
+    while events.len() > MAX_STORED_EVENTS {
+        events.pop_front();
+    }
+}
+
+async fn process_request(
+    app: &AppHandle,
+    replays: &Mutex<ReplayGuard>,
+    head: RequestHead,
+    body: Vec<u8>,
+) -> Outcome {
+    if head.method != "POST" {
+        return Outcome::error(405, "only POST is supported");
+    }
+    let Some(path) = head.path.strip_prefix("/hooks/") else {
+        return Outcome::error(404, "unknown endpoint");
+    };
+
+    let Some(manager) = app.try_state::<SharedWebhookManager>() else {
+        return Outcome::error(503, "webhook manager unavailable");
+    };
+    let endpoint = {
+        let mgr = manager.read().await;
+        match mgr
+            .find_inbound_endpoint_by_path(path.trim_end_matches('/'))
+            .await
+        {
+            Ok(Some(endpoint)) => endpoint,
+            Ok(None) => return Outcome::error(404, "unknown endpoint"),
+            Err(e) => return Outcome::error(500, e.to_string()),
+        }
+    };
+
+    let mut outcome = route_request(app, replays, &endpoint, &head, &body).await;
+    outcome.endpoint = Some(endpoint);
+    outcome
+}
+
+async fn route_request(
+    app: &AppHandle,
+    replays: &Mutex<ReplayGuard>,
+    endpoint: &InboundWebhookEndpoint,
+    head: &RequestHead,
+    body: &[u8],
+) -> Outcome {
+    if !endpoint.enabled {
+        return Outcome::error(403, "endpoint disabled");
+    }
+
+    if let Err((status, message)) = authenticate(app, replays, endpoint, head, body) {
+        return Outcome::error(status, message);
+    }
+
+    let payload: Value = match serde_json::from_slice(body) {
+        Ok(payload) => payload,
+        Err(e) => return Outcome::error(400, format!("payload is not JSON: {}", e)),
+    };
+    let alert = match extract_alert(&endpoint.mapping, &payload) {
+        Ok(alert) => alert,
+        Err(e) => return Outcome::error(400, e),
+    };
+
+    let result = match &endpoint.route {
+        InboundRoute::AutoTrading {
+            strategy_id,
+            source_id,
+        } => route_to_auto_trading(app, strategy_id, source_id, &alert),
+        InboundRoute::PriceAlerts => route_to_price_alerts(app, &alert).await,
+    };
+
+    match result {
+        Ok(message) => Outcome {
+            status: 200,
+            message,
+            endpoint: None,
+            symbol: Some(alert.symbol),
+        },
+        Err((status, message)) => Outcome {
+            status,
+            message,
+            endpoint: None,
+            symbol: Some(alert.symbol),
+        },
+    }
+}
+
+/// Verifies the signature, then the timestamp window, then that the
+/// delivery id is new. The signature goes first so unsigned requests never
+/// reach the replay guard.
+fn authenticate(
+    app: &AppHandle,
+    replays: &Mutex<ReplayGuard>,
+    endpoint: &InboundWebhookEndpoint,
+    head: &RequestHead,
+    body: &[u8],
+) -> Result<(), (u16, String)> {
+    let (Some(timestamp), Some(delivery_id), Some(signature)) = (
+        head.headers.get("x-timestamp"),
+        head.headers.get("x-delivery-id"),
+        head.headers.get("x-signature"),
+    ) else {
+        return Err((
+            401,
+            "X-Timestamp, X-Delivery-Id and X-Signature are required".to_string(),
+        ));
+    };
+
+    let keystore = app
+        .try_state::<Keystore>()
+        .ok_or_else(|| (503, "keystore unavailable".to_string()))?;
+    let secret = load_inbound_secret(&keystore, &endpoint.id).map_err(|e| (500, e.to_string()))?;
+    if !verify_inbound_signature(&secret, timestamp, delivery_id, body, signature) {
+        return Err((401, "invalid signature".to_string()));
+    }
+
+    let now = Utc::now().timestamp();
+    let timestamp: i64 = timestamp
+        .trim()
+        .parse()
+        .map_err(|_| (401, "X-Timestamp is not a unix timestamp".to_string()))?;
+    if (now - timestamp).abs() > SIGNATURE_WINDOW_SECS {
+        return Err((
+            401,
+            "request timestamp outside the allowed window".to_string(),
+        ));
+    }
+
+    let mut replays = match replays.lock() {
+        Ok(replays) => replays,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if !replays.record(&endpoint.id, delivery_id.trim(), timestamp, now) {
+        return Err((409, "delivery already processed".to_string()));
+    }
+    Ok(())
+}
+
+fn route_to_auto_trading(
+    app: &AppHandle,
+    strategy_id: &str,
+    source_id: &str,
+    alert: &TradingViewAlert,
+) -> Result<String, (u16, String)> {
+    let action = alert
+        .action
+        .ok_or_else(|| (400, "payload has no action".to_string()))?;
+    let engine = app
+        .try_state::<SharedAutoTradingEngine>()
+        .ok_or_else(|| (503, "auto trading engine unavailable".to_string()))?;
+
+    let fired = {
+        let mut engine = engine.lock().map_err(|e| (500, e.to_string()))?;
+        if let Some(strategy) = engine.get_strategy(strategy_id) {
+            if !strategy.allowed_symbols.is_empty()
+                && !strategy
+                    .allowed_symbols
+                    .iter()
+                    .any(|s| s.eq_ignore_ascii_case(&alert.symbol))
+            {
+                return Err((
+                    422,
+                    format!(
+                        "{} is not traded by strategy {}",
+                        alert.symbol, strategy.name
+                    ),
+                ));
+            }
+        }
+        engine
+            .record_external_signal(strategy_id, source_id, action.signal_value())
+            .map_err(|e| (422, e))?
+    };
+
+    if fired {
+        let _ = app.emit(
+            "auto_trading_webhook_signal",
+            json!({
+                "strategyId": strategy_id,
+                "sourceId": source_id,
+                "symbol": alert.symbol,
+                "action": action,
+                "price": alert.price,
+            }),
+        );
+        Ok(format!(
+            "signal recorded; strategy {} triggered",
+            strategy_id
+        ))
+    } else {
+        Ok("signal recorded".to_string())
+    }
+}
+
+async fn route_to_price_alerts(
+    app: &AppHandle,
+    alert: &TradingViewAlert,
+) -> Result<String, (u16, String)> {
+    let price = alert
+        .price
+        .ok_or_else(|| (400, "payload has no price".to_string()))?;
+    let alerts = app
+        .try_state::<SharedAlertManager>()
+        .ok_or_else(|| (503, "alert manager unavailable".to_string()))?;
+
+    let triggered = alerts
+        .read()
+        .await
+        .check_and_trigger_alerts(&alert.symbol, price, None, None)
+        .await
+        .map_err(|e| (500, e.to_string()))?;
+
+    Ok(format!("{} alert(s) triggered", triggered.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_accepts_prefixed_and_bare_hex() {
+        let body = br#"{"ticker":"SOLUSDT"}"#;
+        let mut mac = HmacSha256::new_from_slice(b"secret").unwrap();
+        mac.update(b"1700000000.delivery-1.");
+        mac.update(body);
+        let signature = hex::encode(mac.finalize().into_bytes());
+
+        assert!(verify_inbound_signature(
+            b"secret",
+            "1700000000",
+            "delivery-1",
+            body,
+            &signature
+        ));
+        assert!(verify_inbound_signature(
+            b"secret",
+            "1700000000",
+            "delivery-1",
+            body,
+            &format!("sha256={}", signature)
+        ));
+        assert!(!verify_inbound_signature(
+            b"other",
+            "1700000000",
+            "delivery-1",
+            body,
+            &signature
+        ));
+        // The timestamp and delivery id are covered by the signature.
+        assert!(!verify_inbound_signature(
+            b"secret",
+            "1700000300",
+            "delivery-1",
+            body,
+            &signature
+        ));
+        assert!(!verify_inbound_signature(
+            b"secret",
+            "1700000000",
+            "delivery-2",
+            body,
+            &signature
+        ));
+    }
+
+    #[test]
+    fn replay_guard_rejects_repeated_deliveries() {
+        let mut guard = ReplayGuard::default();
+        let now = 1_700_000_000;
+
+        assert!(guard.record("endpoint", "delivery-1", now, now));
+        assert!(!guard.record("endpoint", "delivery-1", now, now + 10));
+        assert!(guard.record("other-endpoint", "delivery-1", now, now + 10));
+
+        // Past the window the entry is pruned; the timestamp check rejects
+        // such a request before it gets here.
+        let later = now + SIGNATURE_WINDOW_SECS + 1;
+        assert!(guard.record("endpoint", "delivery-1", now, later));
+        assert_eq!(guard.seen.len(), 1);
+    }
+
+    #[test]
+    fn extract_alert_applies_mapping() {
+        let mut mapping = InboundMapping::default();
+        mapping
+            .symbol_aliases
+            .insert("solusdt".to_string(), "SOL".to_string());
+        mapping.price_field = "bar.close".to_string();
+
+        let payload = json!({
+            "ticker": "BINANCE:SOLUSDT",
+            "bar": { "close": "142.5" },
+            "action": "long"
+        });
+        let alert = extract_alert(&mapping, &payload).unwrap();
+
+        assert_eq!(alert.symbol, "SOL");
+        assert_eq!(alert.price, Some(142.5));
+        assert_eq!(alert.action, Some(SignalAction::Buy));
+    }
+
+    #[test]
+    fn extract_alert_enforces_allowed_symbols() {
+        let mapping = InboundMapping {
+            allowed_symbols: vec!["SOL".to_string()],
+            ..InboundMapping::default()
+        };
+        let payload = json!({ "ticker": "BONK", "close": 0.00002 });
+
+        assert!(extract_alert(&mapping, &payload).is_err());
+    }
+
+    #[test]
+    fn parses_request_head() {
+        let head = parse_request_head(
+            "POST /hooks/abc123?x=1 HTTP/1.1\r\nHost: localhost\r\nX-Signature: deadbeef",
+        )
+        .unwrap();
+
+        assert_eq!(head.method, "POST");
+        assert_eq!(head.path, "/hooks/abc123");
+        assert_eq!(head.headers.get("x-signature").unwrap(), "deadbeef");
+    }
+}
@@ -1,8 +1,10 @@
+use crate::profiles::ProfilePaths;
+use crate::security::keystore::{Keystore, KeystoreError};
 use super::retry::RetryExecutor;
 use super::template::TemplateEngine;
 use super::types::{
-    DeliveryStatus, RetryPolicy, WebhookConfig, WebhookDeliveryLog, WebhookError, WebhookMethod,
-    WebhookTestResult,
+    DeliveryStatus, InboundWebhookEndpoint, InboundWebhookInput, RetryPolicy, WebhookConfig,
+    WebhookDeliveryLog, WebhookError, WebhookMethod, WebhookTestResult,
 };
 use chrono::{DateTime, Utc};
 use reqwest::Client;
@@ -13,8 +15,10 @@ use std::time::Instant;
 use tauri::{AppHandle, Manager};
 use tokio::sync::Mutex;
 use uuid::Uuid;
+use zeroize::Zeroizing;
 
 const WEBHOOKS_DB_FILE: &str = "webhooks.db";
+const INBOUND_SECRET_KEY_PREFIX: &str = "inbound-webhook-secret-";
 
 pub struct WebhookManager {
     pool: Pool<Sqlite>,
@@ -24,7 +28,7 @@ pub struct WebhookManager {
 }
 
 impl WebhookManager {
-    pub async fn new(app: &AppHandle) -> Result<Self, WebhookError> {
+    pub async fn new(app: &AppHandle, keystore: &Keystore) -> Result<Self, WebhookError> {
         let db_path = Self::webhooks_db_path(app)?;
         let db_url = format!("sqlite:{}?mode=rwc", db_path.display());
         
@@ -46,11 +50,12 @@ impl WebhookManager {
         };
 
         manager.initialize().await?;
+        manager.migrate_inbound_secrets(keystore).await?;
         Ok(manager)
     }
 
     fn webhooks_db_path(app: &AppHandle) -> Result<std::path::PathBuf, WebhookError> {
-        let mut path = app.path().app_data_dir().map_err(|err| {
+        let mut path = app.path().profile_data_dir().map_err(|err| {
             WebhookError::Internal(format!("Unable to resolve app data directory: {err}"))
         })?;
 
@@ -102,6 +107,25 @@ impl WebhookManager {
         .execute(&self.pool)
         .await?;
 
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS inbound_webhooks (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                path TEXT NOT NULL UNIQUE,
+                -- Left empty; secrets are kept in the keystore.
+                secret TEXT NOT NULL,
+                enabled INTEGER NOT NULL,
+                route_json TEXT NOT NULL,
+                mapping_json TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
         Ok(())
     }
 
@@ -171,6 +195,199 @@ impl WebhookManager {
         Ok(())
     }
 
+    pub async fn list_inbound_endpoints(
+        &self,
+    ) -> Result<Vec<InboundWebhookEndpoint>, WebhookError> {
+        let rows = sqlx::query("SELECT * FROM inbound_webhooks ORDER BY created_at DESC")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut endpoints = Vec::new();
+        for row in rows {
+            endpoints.push(self.row_to_inbound(row)?);
+        }
+
+        Ok(endpoints)
+    }
+
+    pub async fn get_inbound_endpoint(
+        &self,
+        id: &str,
+    ) -> Result<InboundWebhookEndpoint, WebhookError> {
+        let row = sqlx::query("SELECT * FROM inbound_webhooks WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| WebhookError::NotFound(id.to_string()))?;
+
+        self.row_to_inbound(row)
+    }
+
+    pub async fn find_inbound_endpoint_by_path(
+        &self,
+        path: &str,
+    ) -> Result<Option<InboundWebhookEndpoint>, WebhookError> {
+        let row = sqlx::query("SELECT * FROM inbound_webhooks WHERE path = ?1")
+            .bind(path)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(|row| self.row_to_inbound(row)).transpose()
+    }
+
+    pub async fn create_inbound_endpoint(
+        &self,
+        input: InboundWebhookInput,
+        keystore: &Keystore,
+    ) -> Result<InboundWebhookEndpoint, WebhookError> {
+        let now = Utc::now();
+        let id = Uuid::new_v4().to_string();
+        let secret = Self::generate_inbound_secret();
+        keystore.store_secret(&inbound_secret_key(&id), secret.as_bytes())?;
+        let endpoint = InboundWebhookEndpoint {
+            id,
+            name: input.name,
+            path: Uuid::new_v4().simple().to_string(),
+            secret: Some(secret),
+            enabled: input.enabled,
+            route: input.route,
+            mapping: input.mapping,
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.save_inbound_endpoint(&endpoint).await?;
+        Ok(endpoint)
+    }
+
+    /// Updates the name, routing and mapping of an endpoint. Its path and
+    /// secret are kept so existing TradingView alerts keep working.
+    pub async fn update_inbound_endpoint(
+        &self,
+        id: &str,
+        input: InboundWebhookInput,
+    ) -> Result<InboundWebhookEndpoint, WebhookError> {
+        let mut endpoint = self.get_inbound_endpoint(id).await?;
+        endpoint.name = input.name;
+        endpoint.enabled = input.enabled;
+        endpoint.route = input.route;
+        endpoint.mapping = input.mapping;
+        endpoint.updated_at = Utc::now();
+
+        self.save_inbound_endpoint(&endpoint).await?;
+        Ok(endpoint)
+    }
+
+    pub async fn rotate_inbound_secret(
+        &self,
+        id: &str,
+        keystore: &Keystore,
+    ) -> Result<InboundWebhookEndpoint, WebhookError> {
+        let mut endpoint = self.get_inbound_endpoint(id).await?;
+        let secret = Self::generate_inbound_secret();
+        keystore.store_secret(&inbound_secret_key(id), secret.as_bytes())?;
+        endpoint.secret = Some(secret);
+        endpoint.updated_at = Utc::now();
+
+        self.save_inbound_endpoint(&endpoint).await?;
+        Ok(endpoint)
+    }
+
+    pub async fn delete_inbound_endpoint(
+        &self,
+        id: &str,
+        keystore: &Keystore,
+    ) -> Result<(), WebhookError> {
+        sqlx::query("DELETE FROM inbound_webhooks WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        match keystore.remove_secret(&inbound_secret_key(id)) {
+            Ok(()) | Err(KeystoreError::NotFound) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Moves secrets that earlier versions kept in the database into the
+    /// keystore and blanks the column.
+    async fn migrate_inbound_secrets(&self, keystore: &Keystore) -> Result<(), WebhookError> {
+        let rows = sqlx::query("SELECT id, secret FROM inbound_webhooks WHERE secret != ''")
+            .fetch_all(&self.pool)
+            .await?;
+
+        for row in rows {
+            let id: String = row.try_get("id")?;
+            let secret: String = row.try_get("secret")?;
+            keystore.store_secret(&inbound_secret_key(&id), secret.as_bytes())?;
+            sqlx::query("UPDATE inbound_webhooks SET secret = '' WHERE id = ?1")
+                .bind(&id)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    fn generate_inbound_secret() -> String {
+        hex::encode(rand::random::<[u8; 32]>())
+    }
+
+    async fn save_inbound_endpoint(
+        &self,
+        endpoint: &InboundWebhookEndpoint,
+    ) -> Result<(), WebhookError> {
+        sqlx::query(
+            r#"
+            INSERT INTO inbound_webhooks (
+                id, name, path, secret, enabled, route_json, mapping_json, created_at, updated_at
+            ) VALUES (?1, ?2, ?3, '', ?4, ?5, ?6, ?7, ?8)
+            ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                enabled = excluded.enabled,
+                route_json = excluded.route_json,
+                mapping_json = excluded.mapping_json,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&endpoint.id)
+        .bind(&endpoint.name)
+        .bind(&endpoint.path)
+        .bind(if endpoint.enabled { 1 } else { 0 })
+        .bind(serde_json::to_string(&endpoint.route)?)
+        .bind(serde_json::to_string(&endpoint.mapping)?)
+        .bind(endpoint.created_at.to_rfc3339())
+        .bind(endpoint.updated_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    fn row_to_inbound(
+        &self,
+        row: sqlx::sqlite::SqliteRow,
+    ) -> Result<InboundWebhookEndpoint, WebhookError> {
+        let route_json: String = row.try_get("route_json")?;
+        let mapping_json: String = row.try_get("mapping_json")?;
+
+        Ok(InboundWebhookEndpoint {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            path: row.try_get("path")?,
+            secret: None,
+            enabled: row.try_get::<i64, _>("enabled")? == 1,
+            route: serde_json::from_str(&route_json)?,
+            mapping: serde_json::from_str(&mapping_json)?,
+            created_at: DateTime::parse_from_rfc3339(&row.try_get::<String, _>("created_at")?)
+                .map_err(|e| WebhookError::Internal(format!("Invalid timestamp: {}", e)))?
+                .with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&row.try_get::<String, _>("updated_at")?)
+                .map_err(|e| WebhookError::Internal(format!("Invalid timestamp: {}", e)))?
+                .with_timezone(&Utc),
+        })
+    }
+
     async fn insert_or_update(&self, config: &WebhookConfig) -> Result<(), WebhookError> {
         sqlx::query(
             r#"
@@ -569,3 +786,15 @@ impl WebhookManager {
         self.row_to_log(row)
     }
 }
+
+fn inbound_secret_key(endpoint_id: &str) -> String {
+    format!("{}{}", INBOUND_SECRET_KEY_PREFIX, endpoint_id)
+}
+
+/// Signing secret of an inbound endpoint, read from the keystore.
+pub fn load_inbound_secret(
+    keystore: &Keystore,
+    endpoint_id: &str,
+) -> Result<Zeroizing<Vec<u8>>, WebhookError> {
+    Ok(keystore.retrieve_secret(&inbound_secret_key(endpoint_id))?)
+}
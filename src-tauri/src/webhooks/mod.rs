@@ -1,9 +1,11 @@
 pub mod commands;
+pub mod inbound;
 pub mod manager;
 pub mod retry;
 pub mod template;
 pub mod types;
 
 pub use commands::*;
+pub use inbound::{InboundWebhookServer, SharedInboundWebhookServer};
 pub use manager::WebhookManager;
 pub use types::*;
@@ -1,3 +1,4 @@
+use crate::security::keystore::KeystoreError;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -90,6 +91,113 @@ pub struct WebhookTestResult {
     pub latency_ms: Option<u64>,
 }
 
+/// Where an inbound webhook's payload is delivered.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "target", rename_all = "camelCase")]
+pub enum InboundRoute {
+    /// Feed the alert's action into a running auto-trading strategy as a
+    /// signal from `source_id` (buy = 1.0, sell = -1.0, close = 0.0).
+    #[serde(rename_all = "camelCase")]
+    AutoTrading {
+        strategy_id: String,
+        source_id: String,
+    },
+    /// Evaluate the user's price alerts for the symbol at the payload price.
+    PriceAlerts,
+}
+
+fn default_symbol_field() -> String {
+    "ticker".to_string()
+}
+
+fn default_price_field() -> String {
+    "close".to_string()
+}
+
+fn default_action_field() -> String {
+    "action".to_string()
+}
+
+/// Which payload fields carry the symbol, price and action, so endpoints can
+/// accept whatever JSON shape the TradingView alert message was written in.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundMapping {
+    #[serde(default = "default_symbol_field")]
+    pub symbol_field: String,
+    #[serde(default = "default_price_field")]
+    pub price_field: String,
+    #[serde(default = "default_action_field")]
+    pub action_field: String,
+    /// Rewrites exchange tickers to app symbols, e.g. `SOLUSDT` -> `SOL`.
+    #[serde(default)]
+    pub symbol_aliases: HashMap<String, String>,
+    /// When non-empty, payloads for other symbols are rejected.
+    #[serde(default)]
+    pub allowed_symbols: Vec<String>,
+}
+
+impl Default for InboundMapping {
+    fn default() -> Self {
+        Self {
+            symbol_field: default_symbol_field(),
+            price_field: default_price_field(),
+            action_field: default_action_field(),
+            symbol_aliases: HashMap::new(),
+            allowed_symbols: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundWebhookEndpoint {
+    pub id: String,
+    pub name: String,
+    /// Path segment the endpoint is served on: `/hooks/<path>`.
+    pub path: String,
+    /// The signing secret lives in the keystore and is only returned when
+    /// the endpoint is created or its secret rotated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub enabled: bool,
+    pub route: InboundRoute,
+    pub mapping: InboundMapping,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundWebhookInput {
+    pub name: String,
+    pub enabled: bool,
+    pub route: InboundRoute,
+    #[serde(default)]
+    pub mapping: InboundMapping,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundWebhookEvent {
+    pub id: String,
+    pub endpoint_id: Option<String>,
+    pub endpoint_name: Option<String>,
+    pub status_code: u16,
+    pub message: String,
+    pub symbol: Option<String>,
+    pub received_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundServerStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub requests_received: u64,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum WebhookError {
     #[error("database error: {0}")]
@@ -100,6 +208,8 @@ pub enum WebhookError {
     Serialization(#[from] serde_json::Error),
     #[error("http error: {0}")]
     Http(#[from] reqwest::Error),
+    #[error("keystore error: {0}")]
+    Keystore(#[from] KeystoreError),
     #[error("invalid template: {0}")]
     InvalidTemplate(String),
     #[error("webhook not found: {0}")]
@@ -300,6 +300,7 @@ impl BirdeyeStream {
         let mut stats = self.connection.statistics.write().await;
         stats.messages_received += 1;
         stats.bytes_received += bytes as u64;
+        crate::monitor::record_network_usage("birdeye_ws", 0, bytes as u64);
     }
 
     async fn emit_status(&self) {}
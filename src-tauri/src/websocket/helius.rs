@@ -1,4 +1,5 @@
 use crate::core::websocket_manager::{ConnectionStateInternal, StreamConnection};
+use crate::insiders::compressed_nft::{collect_program_ids, parse_compressed_nft_events};
 use crate::websocket::types::*;
 use futures_util::{SinkExt, StreamExt};
 use serde_json::json;
@@ -219,6 +220,8 @@ impl HeliusStream {
                 .get("to")
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string()),
+            program_ids: collect_program_ids(params),
+            compressed_nft_events: parse_compressed_nft_events(params),
         })
     }
 
@@ -231,6 +234,7 @@ impl HeliusStream {
         let mut stats = self.connection.statistics.write().await;
         stats.messages_received += 1;
         stats.bytes_received += bytes as u64;
+        crate::monitor::record_network_usage("helius_ws", 0, bytes as u64);
     }
 
     pub async fn subscribe(
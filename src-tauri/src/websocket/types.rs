@@ -1,3 +1,4 @@
+use crate::insiders::compressed_nft::CompressedNftEvent;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant};
@@ -96,6 +97,10 @@ pub struct TransactionUpdate {
     pub symbol: Option<String>,
     pub from: Option<String>,
     pub to: Option<String>,
+    #[serde(default)]
+    pub program_ids: Vec<String>,
+    #[serde(default)]
+    pub compressed_nft_events: Vec<CompressedNftEvent>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
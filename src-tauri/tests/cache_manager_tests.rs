@@ -439,3 +439,40 @@ async fn test_cache_api_call_reduction() {
     let stats_after = manager_rehydrated.get_statistics().await;
     assert_eq!(stats_after.disk_hits, 1, "Disk cache should register a hit");
 }
+
+#[tokio::test]
+async fn test_most_queried_tokens_survive_restart_and_decay() {
+    let epoch = UNIX_EPOCH + Duration::from_secs(1_000_000);
+    let time_provider = Arc::new(FakeTimeProvider::new(epoch));
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config_path = temp_dir.path().join("cache_ttl.json");
+
+    let manager = CacheManager::with_time_provider_and_path(
+        100,
+        1000,
+        config_path.clone(),
+        time_provider.clone(),
+    );
+
+    for _ in 0..4 {
+        manager.get("token_price_BONK", CacheType::TokenPrice).await;
+    }
+    manager.get("token_info_JUP", CacheType::TokenInfo).await;
+    manager.get("market_overview", CacheType::MarketData).await;
+
+    assert_eq!(
+        manager.most_queried_tokens(10).await,
+        vec![("BONK".to_string(), 4), ("JUP".to_string(), 1)]
+    );
+
+    // Decay halves counts, drops tokens that reach zero, and persists the rest
+    manager.decay_query_counts().await.unwrap();
+    drop(manager);
+
+    let manager_rehydrated =
+        CacheManager::with_time_provider_and_path(100, 1000, config_path, time_provider);
+    assert_eq!(
+        manager_rehydrated.most_queried_tokens(10).await,
+        vec![("BONK".to_string(), 2)]
+    );
+}